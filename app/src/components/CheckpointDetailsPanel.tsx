@@ -528,6 +528,14 @@ export default function CheckpointDetailsPanel({
               <dd style={{ margin: 0 }}>
                 {`${checkpointDetails.usageTokens} tokens (prompt ${checkpointDetails.promptTokens} · completion ${checkpointDetails.completionTokens})`}
               </dd>
+              {checkpointDetails.usdCost != null && (
+                <>
+                  <dt>Cost</dt>
+                  <dd style={{ margin: 0 }}>
+                    {`$${checkpointDetails.usdCost.toFixed(4)} · ${(checkpointDetails.natureCost ?? 0).toFixed(2)} nature cost`}
+                  </dd>
+                </>
+              )}
             </dl>
             {checkpointDetails.incident && (
               <div
@@ -602,6 +610,14 @@ export default function CheckpointDetailsPanel({
                   label: "Semantic Digest",
                   value: checkpointDetails.semanticDigest ?? null,
                 },
+                {
+                  label: "Provider Request ID",
+                  value: checkpointDetails.providerRequestId ?? null,
+                },
+                {
+                  label: "Response Headers SHA-256",
+                  value: checkpointDetails.responseHeadersSha256 ?? null,
+                },
               ]}
               viewMode={outputViewMode}
               onChangeMode={setOutputViewMode}
@@ -239,7 +239,10 @@ export default function CheckpointEditor({
 
   // Typed step fields (new system)
   const [sourceStep, setSourceStep] = React.useState<number | null>(null);
-  const [useOutputFrom, setUseOutputFrom] = React.useState<number | null>(null);
+  // Step index in this run, or checkpoint id from an earlier run
+  const [useOutputFrom, setUseOutputFrom] = React.useState<number | string | null>(null);
+  // Render the prompt as a template instead of appending the source output
+  const [promptTemplate, setPromptTemplate] = React.useState(false);
   const [summaryType, setSummaryType] = React.useState("brief");
   const [customInstructions, setCustomInstructions] = React.useState("");
 
@@ -305,9 +308,19 @@ export default function CheckpointEditor({
         multiple: false,
         directory: false,
         filters: [
-          { name: 'Documents', extensions: ['pdf', 'tex', 'latex', 'docx', 'txt'] },
+          {
+            name: 'Documents',
+            extensions: [
+              'pdf', 'tex', 'latex', 'docx', 'txt', 'html', 'htm', 'epub', 'md', 'markdown',
+              'ipynb',
+            ],
+          },
           { name: 'PDF', extensions: ['pdf'] },
           { name: 'LaTeX', extensions: ['tex', 'latex'] },
+          { name: 'Web pages', extensions: ['html', 'htm'] },
+          { name: 'EPUB', extensions: ['epub'] },
+          { name: 'Markdown', extensions: ['md', 'markdown'] },
+          { name: 'Jupyter notebooks', extensions: ['ipynb'] },
           { name: 'All Files', extensions: ['*'] },
         ],
       });
@@ -456,6 +469,7 @@ export default function CheckpointEditor({
         model: cleanedModel,
         prompt: cleanedPrompt,
         useOutputFrom: useOutputFrom === null ? undefined : useOutputFrom,
+        template: promptTemplate || undefined,
         tokenBudget: parsedBudget,
         proofMode: proofMode,
         epsilon: proofMode === "concordant" ? epsilon : undefined,
@@ -608,6 +622,10 @@ export default function CheckpointEditor({
               <option key="latex" value="latex">LaTeX</option>
               <option key="txt" value="txt">TXT</option>
               <option key="docx" value="docx">DOCX</option>
+              <option key="html" value="html">HTML</option>
+              <option key="epub" value="epub">EPUB</option>
+              <option key="markdown" value="markdown">Markdown</option>
+              <option key="ipynb" value="ipynb">Jupyter notebook</option>
             </select>
           </label>
           <label style={{ display: "flex", flexDirection: "column", gap: "4px" }}>
@@ -783,7 +801,7 @@ export default function CheckpointEditor({
             <label style={{ display: "flex", flexDirection: "column", gap: "4px" }}>
               Use Output From (optional)
               <select
-                value={useOutputFrom ?? ""}
+                value={typeof useOutputFrom === "number" ? useOutputFrom : ""}
                 onChange={(event) => setUseOutputFrom(event.target.value ? parseInt(event.target.value) : null)}
               >
                 <option value="">None (standalone prompt)</option>
@@ -795,6 +813,27 @@ export default function CheckpointEditor({
               </select>
             </label>
           )}
+          {stepType === "prompt" && (
+            <label style={{ display: "flex", flexDirection: "column", gap: "4px" }}>
+              Or checkpoint ID from an earlier run (optional)
+              <input
+                type="text"
+                value={typeof useOutputFrom === "string" ? useOutputFrom : ""}
+                onChange={(event) => setUseOutputFrom(event.target.value.trim() || null)}
+                placeholder="Checkpoint ID"
+              />
+            </label>
+          )}
+          {stepType === "prompt" && (
+            <label style={{ display: "flex", alignItems: "center", gap: "8px" }}>
+              <input
+                type="checkbox"
+                checked={promptTemplate}
+                onChange={(event) => setPromptTemplate(event.target.checked)}
+              />
+              Render as template ({"{{source.output}}"}, {"{{#each}}"}, {"{{> partial}}"}, {"{{truncate}}"})
+            </label>
+          )}
           <label style={{ display: "flex", flexDirection: "column", gap: "4px" }}>
             Model
             <select value={model} onChange={(event) => setModel(event.target.value)}>
@@ -28,6 +28,11 @@ function truncatePrompt(value: string, length = 160): string {
   return `${normalized.slice(0, length)}…`;
 }
 
+/** Prompt context is a step of this run or a checkpoint id from an earlier run */
+function outputSourceLabel(source: number | string): string {
+  return typeof source === "string" ? `checkpoint ${source.slice(0, 8)}` : `Step ${source + 1}`;
+}
+
 export default function CheckpointListItem({
   config,
   onEdit,
@@ -63,7 +68,7 @@ export default function CheckpointListItem({
     try {
       const promptConfig = JSON.parse(config.configJson);
       const contextLabel = promptConfig.useOutputFrom !== undefined && promptConfig.useOutputFrom !== null
-        ? ` (with context from Step ${promptConfig.useOutputFrom + 1})`
+        ? ` (with context from ${outputSourceLabel(promptConfig.useOutputFrom)})`
         : '';
       promptPreview = truncatePrompt(promptConfig.prompt) + contextLabel;
     } catch {
@@ -182,7 +187,7 @@ export default function CheckpointListItem({
                     </span>
                     {promptConfig.useOutputFrom !== undefined && promptConfig.useOutputFrom !== null && (
                       <span>
-                        <strong>Uses:</strong> Step {promptConfig.useOutputFrom + 1}
+                        <strong>Uses:</strong> {outputSourceLabel(promptConfig.useOutputFrom)}
                       </span>
                     )}
                   </>
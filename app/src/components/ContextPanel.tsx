@@ -10,6 +10,7 @@ import {
   estimateRunCost,
   type RunCostEstimates,
   exportProject,
+  exportPolicyHistory,
   importProject,
   importCar,
   getCheckpointDetails,
@@ -157,6 +158,26 @@ export default function ContextPanel({
         );
       }
     }
+    // Window budgets: what the run would bring the window to, and what it has left
+    for (const window of costEstimates.windows ?? []) {
+      const { budget } = window;
+      const label = `Per-${window.window}`;
+      if (budget.tokens != null && window.estimatedTokens > budget.tokens) {
+        messages.push(
+          `${label} tokens: ${window.estimatedTokens.toLocaleString()} / ${budget.tokens.toLocaleString()} (${(window.remainingTokens ?? 0).toLocaleString()} left)`,
+        );
+      }
+      if (budget.usd != null && window.estimatedUsd > budget.usd) {
+        messages.push(
+          `${label} USD: ${window.estimatedUsd.toFixed(2)} / ${budget.usd.toFixed(2)} (${(window.remainingUsd ?? 0).toFixed(2)} left)`,
+        );
+      }
+      if (budget.natureCost != null && window.estimatedNatureCost > budget.natureCost) {
+        messages.push(
+          `${label} Nature Cost: ${window.estimatedNatureCost.toFixed(2)} / ${budget.natureCost.toFixed(2)} (${(window.remainingNatureCost ?? 0).toFixed(2)} left)`,
+        );
+      }
+    }
     return messages;
   }, [costEstimates]);
 
@@ -347,6 +368,31 @@ export default function ContextPanel({
     }
   }, [projectId]);
 
+  const handleExportPolicyHistory = React.useCallback(async () => {
+    const { save } = await import('@tauri-apps/plugin-dialog');
+    const savePath = await save({
+      defaultPath: `${projectId}-policy-history.json`,
+      filters: [{ name: 'Policy History', extensions: ['json'] }],
+    });
+    if (!savePath) {
+      return;
+    }
+
+    setExportError(null);
+    setExportStatus(null);
+    setExportingProject(true);
+    try {
+      const path = await exportPolicyHistory(projectId, savePath);
+      setExportStatus(`Signed policy history saved to ${path}`);
+    } catch (err) {
+      setExportError(
+        `Failed to export policy history: ${err instanceof Error ? err.message : String(err)}`,
+      );
+    } finally {
+      setExportingProject(false);
+    }
+  }, [projectId]);
+
   const handleImportProjectArchive = React.useCallback(() => {
     setProjectImportError(null);
     setProjectImportStatus(null);
@@ -853,6 +899,14 @@ export default function ContextPanel({
           >
             {exportingProject ? "Exporting…" : "Export Project"}
           </button>
+          <button
+            type="button"
+            onClick={handleExportPolicyHistory}
+            disabled={exportingProject}
+            style={combineButtonStyles(buttonSecondary, exportingProject && buttonDisabled)}
+          >
+            Export Signed Policy History
+          </button>
           <button
             type="button"
             onClick={handleImportProjectArchive}
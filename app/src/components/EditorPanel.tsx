@@ -20,6 +20,7 @@ import {
   renameRun,
   deleteRun,
   startRun,
+  waitForJob,
   cloneRun,
   estimateRunCost,
   openInteractiveCheckpointSession,
@@ -738,6 +739,26 @@ export default function EditorPanel({
         );
       }
     }
+    // Window budgets: what the run would bring the window to, and what it has left
+    for (const window of costEstimates.windows ?? []) {
+      const { budget } = window;
+      const label = `Per-${window.window}`;
+      if (budget.tokens != null && window.estimatedTokens > budget.tokens) {
+        messages.push(
+          `${label} tokens: ${window.estimatedTokens.toLocaleString()} / ${budget.tokens.toLocaleString()} (${(window.remainingTokens ?? 0).toLocaleString()} left)`,
+        );
+      }
+      if (budget.usd != null && window.estimatedUsd > budget.usd) {
+        messages.push(
+          `${label} USD: ${window.estimatedUsd.toFixed(2)} / ${budget.usd.toFixed(2)} (${(window.remainingUsd ?? 0).toFixed(2)} left)`,
+        );
+      }
+      if (budget.natureCost != null && window.estimatedNatureCost > budget.natureCost) {
+        messages.push(
+          `${label} Nature Cost: ${window.estimatedNatureCost.toFixed(2)} / ${budget.natureCost.toFixed(2)} (${(window.remainingNatureCost ?? 0).toFixed(2)} left)`,
+        );
+      }
+    }
     return messages;
   }, [costEstimates]);
 
@@ -1347,7 +1368,9 @@ export default function EditorPanel({
     setErrorMessage(null);
     setExecutingRun(true);
     try {
-      const execution = await startRun(selectedRunId);
+      const jobId = await startRun(selectedRunId);
+      setStatusMessage('Run queued.');
+      const execution = await waitForJob<RunExecutionSummary>(jobId);
       const executedAt = execution.createdAt
         ? new Date(execution.createdAt).toLocaleString()
         : null;
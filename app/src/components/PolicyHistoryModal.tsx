@@ -1,5 +1,5 @@
 import React from 'react';
-import { PolicyVersion } from '../lib/api';
+import { Policy, PolicyVersion, SubBudget } from '../lib/api';
 import { buttonSecondary, combineButtonStyles } from '../styles/common';
 
 interface PolicyHistoryModalProps {
@@ -8,6 +8,24 @@ interface PolicyHistoryModalProps {
   onClose: () => void;
 }
 
+function capLabel(name: string, budget: SubBudget): string {
+  const caps = [
+    budget.tokens != null ? `${budget.tokens.toLocaleString()} tokens` : null,
+    budget.usd != null ? `$${budget.usd.toFixed(2)}` : null,
+    budget.natureCost != null ? `${budget.natureCost.toFixed(1)} nature` : null,
+  ].filter(Boolean);
+  return `${name} ≤ ${caps.join(' / ')}`;
+}
+
+// Model, provider and window caps of a policy version, e.g. "anthropic ≤ $5.00"
+function subBudgetLabels(policy: Policy): string[] {
+  return [
+    ...Object.entries(policy.modelBudgets ?? {}),
+    ...Object.entries(policy.providerBudgets ?? {}),
+    ...(policy.windowBudgets ?? []).map((budget) => [`per ${budget.window}`, budget] as const),
+  ].map(([name, budget]) => capLabel(name, budget));
+}
+
 export default function PolicyHistoryModal({
   versions,
   currentVersion,
@@ -146,6 +164,12 @@ export default function PolicyHistoryModal({
                         {version.policy.allowNetwork ? 'Allowed' : 'Denied'}
                       </span>
                     </div>
+                    {subBudgetLabels(version.policy).length > 0 && (
+                      <div style={{ gridColumn: '1 / -1' }}>
+                        <span style={{ color: '#999' }}>Sub-budgets:</span>{' '}
+                        <span style={{ color: '#fff' }}>{subBudgetLabels(version.policy).join(', ')}</span>
+                      </div>
+                    )}
                   </div>
                 </div>
               );
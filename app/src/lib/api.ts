@@ -1,5 +1,6 @@
 // In app/src/lib/api.ts
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { interactiveFeatureEnabled } from './featureFlags.js';
 
 export interface Project {
@@ -7,6 +8,7 @@ export interface Project {
   name: string;
   created_at: string; // Dates are serialized as strings
   pubkey: string;
+  archived_at?: string | null; // set while the project is archived
 }
 
 export interface ExecutionStepProofSummary {
@@ -21,6 +23,28 @@ export interface RunExecutionSummary {
   id: string;
   createdAt: string;
   stepProofs: ExecutionStepProofSummary[];
+  preemptions?: Preemption[];
+  environment?: RunEnvironment;
+}
+
+/** Where an execution ran; also embedded in its CAR as `run_environment` */
+export interface RunEnvironment {
+  os: string;
+  os_version?: string;
+  arch: string;
+  app_version: string;
+  model_catalog_version: string;
+  ollama_version?: string;
+  machine_fingerprint: string;
+}
+
+export type RunPriority = 'low' | 'normal' | 'high';
+
+export interface Preemption {
+  beforeStep: number;
+  preemptedBy: string;
+  pausedAt: string;
+  resumedAt: string;
 }
 
 export interface RunSummary {
@@ -30,6 +54,7 @@ export interface RunSummary {
   kind: string;
   epsilon?: number | null;
   hasPersistedCheckpoint: boolean;
+  priority: RunPriority;
   executions: RunExecutionSummary[];
   stepProofs: ExecutionStepProofSummary[];
 }
@@ -75,6 +100,11 @@ export interface CheckpointDetails {
   promptPayload?: string | null;
   outputPayload?: string | null;
   message?: CheckpointMessage | null;
+  providerRequestId?: string | null;
+  responseHeadersSha256?: string | null;
+  usdCost?: number | null;
+  natureCost?: number | null;
+  gCo2e?: number | null;
 }
 
 export interface IncidentSummary {
@@ -124,6 +154,8 @@ export interface CheckpointReplayReport {
   configuredEpsilon?: number | null;
   similarityScore?: number | null;
   grade?: ReplayGrade | null;
+  originalOutputRef?: string | null;
+  replayOutputRef?: string | null;
 }
 
 export interface ReplayReport {
@@ -139,6 +171,54 @@ export interface ReplayReport {
   checkpointReports: CheckpointReplayReport[];
   similarityScore?: number | null;
   grade?: ReplayGrade | null;
+  replayCarId?: string | null;
+  replayCarPath?: string | null;
+}
+
+export interface ReplayOptions {
+  storeOutputs?: boolean;
+  emitReplayCar?: boolean;
+}
+
+export interface VerificationChallenge {
+  id: string;
+  runId: string;
+  orderIndex: number;
+  nonce: string;
+  issuedAt: string;
+  expiresAt: string;
+}
+
+export interface ChallengedStep {
+  orderIndex: number;
+  stepType: string;
+  model?: string | null;
+  proofMode: RunProofMode;
+  epsilon?: number | null;
+  inputsSha256: string;
+}
+
+export interface ChallengeProof {
+  id: string;
+  challenge: VerificationChallenge;
+  step: ChallengedStep;
+  startedAt: string;
+  completedAt: string;
+  output: string;
+  outputsSha256: string;
+  semanticDigest: string;
+  commitment: string;
+  originalOutputsSha256?: string | null;
+  originalSemanticDigest?: string | null;
+  signerPublicKey: string;
+  signatures: string[];
+}
+
+export interface ChallengeVerification {
+  valid: boolean;
+  matchesOriginal: boolean;
+  semanticDistance?: number | null;
+  errors: string[];
 }
 
 export interface CarSampler {
@@ -253,11 +333,69 @@ export interface FileImportPayload {
   [key: string]: unknown;
 }
 
+export type ContentAction = 'off' | 'warn' | 'block';
+
+export type ContentPatternClass = 'secrets' | 'pii' | 'prompt_injection';
+
+export interface ContentRules {
+  secrets: ContentAction;
+  pii: ContentAction;
+  promptInjection: ContentAction;
+  customPatterns?: { class: ContentPatternClass; name: string; pattern: string }[];
+}
+
 export interface Policy {
   allowNetwork: boolean;
   budgetTokens: number;
   budgetUsd: number;
   budgetNatureCost: number;
+  // Programs fine-tune steps may run, by name on PATH or by path; none refuses fine-tune steps
+  allowedTrainingPrograms?: string[];
+  contentRules?: ContentRules;
+  responseCache?: boolean;
+  executionWindow?: ExecutionWindow | null;
+  incidentActions?: Record<string, IncidentAction>;
+  // Caps by model id and by catalog provider, within the project budgets
+  modelBudgets?: Record<string, SubBudget>;
+  providerBudgets?: Record<string, SubBudget>;
+  // Caps per UTC day, week or month, across policy versions
+  windowBudgets?: WindowBudget[];
+  // Data residency; steps whose model falls outside it are refused
+  providerAllowlist?: ProviderAllowlist;
+  // Percentages of each budget at which a budget alert fires, e.g. [80, 95]
+  budgetAlertThresholds?: number[];
+  // Changes must be proposed and approved by someone else instead of applied
+  requireChangeApproval?: boolean;
+}
+
+// Regions come from the model catalog: 'local' for on-device inference, else e.g. 'us' or 'eu'.
+// Empty lists allow any provider or region.
+export interface ProviderAllowlist {
+  providers?: string[];
+  regions?: string[];
+  deniedRegions?: string[];
+}
+
+export type BudgetWindow = 'day' | 'week' | 'month';
+
+export interface WindowBudget extends SubBudget {
+  window: BudgetWindow;
+}
+
+// An absent cap is unlimited
+export interface SubBudget {
+  tokens?: number | null;
+  usd?: number | null;
+  natureCost?: number | null;
+}
+
+export type IncidentAction = 'block' | 'warn_continue' | 'pause_for_approval' | 'notify';
+
+export interface ExecutionWindow {
+  start?: string | null;
+  end?: string | null;
+  requireAcPower?: boolean;
+  maxTemperatureCelsius?: number | null;
 }
 
 export interface PolicyVersion {
@@ -268,6 +406,11 @@ export interface PolicyVersion {
   createdAt: string;
   createdBy?: string | null;
   changeNotes?: string | null;
+  // Set on versions created from an approved proposal
+  approvedBy?: string | null;
+  approvedAt?: string | null;
+  // The default policy this version resolves against
+  defaultPolicy?: Policy | null;
 }
 
 export interface ProjectLedgerTotals {
@@ -307,11 +450,61 @@ export interface CostProjection {
   exceedsTokens: boolean;
   exceedsUsd: boolean;
   exceedsNatureCost: boolean;
+  subBudgetOverruns?: SubBudgetOverrun[];
+}
+
+export interface SubBudgetOverrun {
+  scope: 'model' | 'provider';
+  name: string;
+  budget: 'tokens' | 'usd' | 'natureCost';
+  estimated: number;
+  limit: number;
+}
+
+// A window budget against the window's usage so far plus the run's projection
+export interface WindowProjection {
+  window: BudgetWindow;
+  windowStart: string;
+  budget: SubBudget;
+  estimatedTokens: number;
+  estimatedUsd: number;
+  estimatedNatureCost: number;
+  // Left in the window before the run; absent where the budget sets no cap
+  remainingTokens?: number | null;
+  remainingUsd?: number | null;
+  remainingNatureCost?: number | null;
+  exceeds: boolean;
 }
 
 export interface RunCostEstimates {
   perRun: CostProjection;
   cumulative: CostProjection;
+  windows?: WindowProjection[];
+}
+
+export type DryRunSeverity = 'error' | 'warning';
+
+export interface DryRunIssue {
+  severity: DryRunSeverity;
+  orderIndex?: number | null;
+  message: string;
+}
+
+export interface DryRunStep {
+  orderIndex: number;
+  stepType: string;
+  model?: string | null;
+  provider?: string | null;
+  sourcePaths?: string[];
+  dependsOn?: number[];
+}
+
+export interface DryRunReport {
+  runId: string;
+  valid: boolean;
+  steps: DryRunStep[];
+  issues: DryRunIssue[];
+  costEstimates: RunCostEstimates;
 }
 
 export interface RunStepConfig {
@@ -351,7 +544,7 @@ export type FinalizeInteractiveCheckpoint = (
 
 export interface DocumentIngestionConfig {
   sourcePath: string;
-  format: string; // "pdf", "latex", "docx", "txt"
+  format: string; // "pdf", "latex", "docx", "txt", "html", "epub", "markdown", "ipynb"
   privacyStatus: string;
   outputStorage?: string;
 }
@@ -414,10 +607,51 @@ export async function renameProject(projectId: string, name: string): Promise<Pr
   return await invoke<Project>('rename_project', { projectId, name });
 }
 
+/** Archives the project; it is purged once its retention period has passed */
 export async function deleteProject(projectId: string): Promise<void> {
   await invoke('delete_project', { projectId });
 }
 
+export async function restoreProject(projectId: string): Promise<Project> {
+  return await invoke<Project>('restore_project', { projectId });
+}
+
+export async function listArchivedProjects(): Promise<Project[]> {
+  return await invoke<Project[]>('list_archived_projects');
+}
+
+/** Deletes an archived project and its signing key for good */
+export async function purgeProject(projectId: string): Promise<void> {
+  await invoke('purge_project', { projectId });
+}
+
+/** Days archived projects are kept before they are purged; null keeps them */
+export async function getProjectRetentionDays(): Promise<number | null> {
+  return await invoke<number | null>('get_project_retention_days');
+}
+
+export async function setProjectRetentionDays(
+  retentionDays: number | null,
+): Promise<number | null> {
+  return await invoke<number | null>('set_project_retention_days', { retentionDays });
+}
+
+export type ProjectArchiveAction = 'archived' | 'restored' | 'purged';
+
+export interface ProjectArchiveEvent {
+  id: string;
+  projectId: string;
+  projectName: string;
+  action: ProjectArchiveAction;
+  at: string;
+}
+
+export async function listProjectArchiveEvents(projectId?: string): Promise<ProjectArchiveEvent[]> {
+  return await invoke<ProjectArchiveEvent[]>('list_project_archive_events', {
+    projectId: projectId ?? null,
+  });
+}
+
 export async function createRun(params: CreateRunParams): Promise<string> {
   return await invoke<string>('create_run', {
     projectId: params.projectId,
@@ -438,6 +672,10 @@ export async function renameRun(runId: string, name: string): Promise<void> {
   await invoke('rename_run', { runId, name });
 }
 
+export async function setRunPriority(runId: string, priority: RunPriority): Promise<void> {
+  await invoke('set_run_priority', { runId, priority });
+}
+
 export async function deleteRun(runId: string): Promise<void> {
   await invoke('delete_run', { runId });
 }
@@ -508,8 +746,27 @@ export async function reorderRunSteps(
   });
 }
 
-export async function startRun(runId: string): Promise<RunExecutionSummary> {
-  return await invoke<RunExecutionSummary>('start_run', { runId });
+/**
+ * Queue a run execution and return its job id; `waitForJob` gives the
+ * `RunExecutionSummary` once it has run. `inputs` fill `{{inputs.name}}`
+ * placeholders in the run's prompts.
+ */
+export async function startRun(
+  runId: string,
+  inputs?: Record<string, unknown>,
+): Promise<string> {
+  return await invoke<string>('start_run', { runId, inputs: inputs ?? null });
+}
+
+/**
+ * Validate a run without executing it. `inputs` are rendered into step
+ * configs the same way `startRun` would, so source paths are checked as read.
+ */
+export async function startRunDry(
+  runId: string,
+  inputs?: Record<string, unknown>,
+): Promise<DryRunReport> {
+  return await invoke<DryRunReport>('start_run_dry', { runId, inputs: inputs ?? null });
 }
 
 export async function cloneRun(runId: string): Promise<string> {
@@ -520,6 +777,55 @@ export async function estimateRunCost(runId: string): Promise<RunCostEstimates>
   return await invoke<RunCostEstimates>('estimate_run_cost', { runId });
 }
 
+export interface TokenRange {
+  low: number;
+  expected: number;
+  high: number;
+}
+
+export interface CostRange {
+  low: number;
+  expected: number;
+  high: number;
+}
+
+export type EstimateBasis = 'history' | 'projection' | 'tokenBudget';
+
+export interface StepCostEstimate {
+  stepId: string;
+  orderIndex: number;
+  stepType: string;
+  model?: string | null;
+  basis: EstimateBasis;
+  historySamples: number;
+  inputTokens: TokenRange;
+  outputTokens: TokenRange;
+  /** What the step is charged: its input and the output its budget allows */
+  usageTokens: TokenRange;
+  usd: CostRange;
+  natureCost: CostRange;
+  tokenBudget: number;
+  contextWindow?: number | null;
+  exceedsTokenBudget: boolean;
+  exceedsContextWindow: boolean;
+  notes?: string[];
+}
+
+export interface RunStepCostEstimates {
+  runId: string;
+  steps: StepCostEstimate[];
+  usageTokens: TokenRange;
+  usd: CostRange;
+  natureCost: CostRange;
+  /** What estimateRunCost projects: the steps' token budgets */
+  staticBudgetTokens: number;
+}
+
+/** Per-step usage ranges that follow the context chained steps inherit */
+export async function estimateRunStepCosts(runId: string): Promise<RunStepCostEstimates> {
+  return await invoke<RunStepCostEstimates>('estimate_run_step_costs', { runId });
+}
+
 export const openInteractiveCheckpointSession: OpenInteractiveCheckpointSession | undefined =
   interactiveFeatureEnabled
     ? async (runId: string, checkpointId: string) =>
@@ -550,6 +856,16 @@ export async function getPolicy(projectId: string): Promise<Policy> {
   return await invoke<Policy>('get_policy', { projectId });
 }
 
+// App-level default that projects inherit and may only tighten for the
+// budgets, network access and concurrency it caps
+export async function getDefaultPolicy(): Promise<Policy | null> {
+  return await invoke<Policy | null>('get_default_policy');
+}
+
+export async function setDefaultPolicy(policy: Policy): Promise<void> {
+  await invoke('set_default_policy', { policy });
+}
+
 export async function getProjectUsageLedger(
   projectId: string,
 ): Promise<ProjectUsageLedgerSnapshot> {
@@ -558,6 +874,440 @@ export async function getProjectUsageLedger(
   });
 }
 
+export interface LedgerDrift {
+  policyVersion: number;
+  recorded: ProjectLedgerTotals;
+  // From the project's checkpoints and replays
+  recomputed: ProjectLedgerTotals;
+  drifted: boolean;
+}
+
+export interface LedgerReconciliation {
+  projectId: string;
+  policyVersions: LedgerDrift[];
+  drifted: boolean;
+  repaired: boolean;
+}
+
+// With repair, drifted ledgers are overwritten with the recomputed totals
+export async function reconcileLedger(
+  projectId: string,
+  repair = false,
+): Promise<LedgerReconciliation> {
+  return await invoke<LedgerReconciliation>('reconcile_ledger', { projectId, repair });
+}
+
+export type DashboardExecutionStatus = 'running' | 'completed' | 'incident';
+
+export interface DashboardExecution {
+  executionId: string;
+  runId: string;
+  runName: string;
+  createdAt: string;
+  status: DashboardExecutionStatus;
+  checkpointCount: number;
+  usageTokens: number;
+}
+
+export interface BurnDownPoint {
+  date: string;
+  tokens: number;
+  usd: number;
+  remainingTokens: number;
+  remainingUsd: number;
+}
+
+export interface ModelCost {
+  model: string;
+  tokens: number;
+  usd: number;
+  checkpoints: number;
+}
+
+export interface PendingReceipt {
+  executionId: string;
+  runId: string;
+  runName: string;
+  createdAt: string;
+}
+
+export interface ProjectDashboard {
+  projectId: string;
+  recentExecutions: DashboardExecution[];
+  budget: {
+    ledger: ProjectUsageLedgerSnapshot;
+    daily: BurnDownPoint[];
+  };
+  incidentsBySeverity: Record<string, number>;
+  topModels: ModelCost[];
+  receiptsPending: PendingReceipt[];
+}
+
+export async function getProjectDashboard(projectId: string): Promise<ProjectDashboard> {
+  return await invoke<ProjectDashboard>('get_project_dashboard', { projectId });
+}
+
+export type UsageGranularity = 'day' | 'week';
+
+export interface UsageBucket {
+  periodStart: string;
+  model: string | null;
+  checkpoints: number;
+  tokens: number;
+  usd: number;
+  natureCost: number;
+}
+
+export interface UsageTimeseries {
+  projectId: string;
+  granularity: UsageGranularity;
+  buckets: UsageBucket[];
+}
+
+export async function getUsageTimeseries(
+  projectId: string,
+  granularity: UsageGranularity,
+): Promise<UsageTimeseries> {
+  return await invoke<UsageTimeseries>('get_usage_timeseries', { projectId, granularity });
+}
+
+export type ArtifactMatchRole = 'output' | 'attachment' | 'input' | 'run_inputs';
+
+export interface ArtifactCheckpointMatch {
+  projectId: string;
+  projectName: string;
+  runId: string;
+  runName: string;
+  runExecutionId: string;
+  checkpointId: string;
+  kind: string;
+  timestamp: string;
+  roles: ArtifactMatchRole[];
+}
+
+export interface ArtifactExecutionInputMatch {
+  projectId: string;
+  projectName: string;
+  runId: string;
+  runName: string;
+  runExecutionId: string;
+  createdAt: string;
+}
+
+export interface ArtifactDocumentMatch {
+  projectId: string;
+  projectName: string;
+  dataset: string;
+  split: string;
+}
+
+export interface ArtifactReceiptMatch {
+  carId: string;
+  runId: string;
+  runExecutionId?: string | null;
+  filePath: string;
+  createdAt: string;
+}
+
+export interface ArtifactExecutionVerification {
+  runExecutionId: string;
+  checkpointsVerified: number;
+  error?: string | null;
+}
+
+export interface WorkspaceSearch {
+  sha256: string;
+  checkpoints: ArtifactCheckpointMatch[];
+  executionInputs: ArtifactExecutionInputMatch[];
+  documents: ArtifactDocumentMatch[];
+  receipts: ArtifactReceiptMatch[];
+  verifications: ArtifactExecutionVerification[];
+}
+
+// Where an output, prompt or document digest appears across all projects
+export async function searchWorkspace(sha256: string): Promise<WorkspaceSearch> {
+  return await invoke<WorkspaceSearch>('search_workspace', { sha256 });
+}
+
+export interface SigningKeyEvent {
+  id: string;
+  projectId: string;
+  event: 'regenerated';
+  previousPubkey?: string;
+  pubkey: string;
+  reason: string;
+  runId?: string;
+  createdAt: string;
+}
+
+export async function listSigningKeyEvents(projectId: string): Promise<SigningKeyEvent[]> {
+  return await invoke<SigningKeyEvent[]>('list_signing_key_events', { projectId });
+}
+
+export type KeyProtectionChoice =
+  | { kind: 'keychain' }
+  | { kind: 'passphrase' }
+  // The helper gets the challenge as hex (a `{challenge}` argument, else
+  // stdin) and prints the hardware token's response as hex
+  | { kind: 'hardware'; program: string; args: string[] };
+
+export interface KeyProtectionStatus {
+  kind: KeyProtectionChoice['kind'];
+  locked: boolean;
+}
+
+export async function getKeyProtection(projectId: string): Promise<KeyProtectionStatus> {
+  return await invoke<KeyProtectionStatus>('get_key_protection', { projectId });
+}
+
+export async function setKeyProtection(
+  projectId: string,
+  protection: KeyProtectionChoice,
+  passphrase?: string
+): Promise<KeyProtectionStatus> {
+  return await invoke<KeyProtectionStatus>('set_key_protection', {
+    projectId,
+    protection,
+    passphrase,
+  });
+}
+
+export async function unlockSigningKey(projectId: string, passphrase?: string): Promise<void> {
+  await invoke('unlock_signing_key', { projectId, passphrase });
+}
+
+export async function lockSigningKey(projectId: string): Promise<void> {
+  await invoke('lock_signing_key', { projectId });
+}
+
+export type SignerRole = 'reviewer' | 'organization';
+
+export interface Cosignature {
+  role: SignerRole;
+  public_key: string;
+  signature: string;
+}
+
+export async function countersignCar(
+  carPath: string,
+  role: SignerRole,
+  signerId: string
+): Promise<Cosignature> {
+  return await invoke<Cosignature>('countersign_car', { carPath, role, signerId });
+}
+
+/** Verify a CAR file and save the outcome as a self-contained HTML report;
+ * resolves to whether the CAR verified */
+export async function exportVerificationReport(
+  carPath: string,
+  outputPath: string
+): Promise<boolean> {
+  return await invoke<boolean>('export_verification_report', { carPath, outputPath });
+}
+
+// Writes a detached signature next to the file and returns its path
+export async function signArtifact(checkpointId: string, filePath: string): Promise<string> {
+  return await invoke<string>('sign_artifact', { checkpointId, filePath });
+}
+
+export interface GroupKey {
+  group_public_key: string;
+  min_signers: number;
+  verifying_shares: Record<string, string>;
+}
+
+export interface CosignerEndpoint {
+  identifier: number;
+  url: string;
+}
+
+export interface ThresholdConfig {
+  group: GroupKey;
+  local_identifier: number | null;
+  cosigners: CosignerEndpoint[];
+}
+
+export interface KeyShare {
+  identifier: number;
+  signing_share: string;
+  group_public_key: string;
+  min_signers: number;
+}
+
+export async function getThresholdSigning(projectId: string): Promise<ThresholdConfig | null> {
+  return await invoke<ThresholdConfig | null>('get_threshold_signing', { projectId });
+}
+
+export async function enableThresholdSigning(
+  projectId: string,
+  minSigners: number,
+  cosignerUrls: string[]
+): Promise<KeyShare[]> {
+  return await invoke<KeyShare[]>('enable_threshold_signing', {
+    projectId,
+    minSigners,
+    cosignerUrls,
+  });
+}
+
+export async function exportPolicyHistory(
+  projectId: string,
+  outputPath?: string
+): Promise<string> {
+  return await invoke<string>('export_policy_history', { projectId, outputPath });
+}
+
+export type JobState = 'queued' | 'running' | 'succeeded' | 'failed';
+
+export interface JobStatus {
+  id: string;
+  kind: string;
+  state: JobState;
+  submittedAt: string;
+  startedAt?: string;
+  finishedAt?: string;
+  error?: string;
+  /** What a succeeded job returned */
+  result?: unknown;
+}
+
+export async function getJobStatus(jobId: string): Promise<JobStatus> {
+  return await invoke<JobStatus>('get_job_status', { jobId });
+}
+
+/** Poll a job until it finishes; resolves with its result or throws its error */
+export async function waitForJob<T>(jobId: string, pollIntervalMs = 500): Promise<T> {
+  for (;;) {
+    const status = await getJobStatus(jobId);
+    if (status.state === 'succeeded') {
+      return status.result as T;
+    }
+    if (status.state === 'failed') {
+      throw new Error(status.error ?? `Job ${jobId} failed`);
+    }
+    await new Promise((resolve) => setTimeout(resolve, pollIntervalMs));
+  }
+}
+
+export async function listJobs(): Promise<JobStatus[]> {
+  return await invoke<JobStatus[]>('list_jobs');
+}
+
+export interface PromptPartial {
+  projectId: string;
+  name: string;
+  template: string;
+  updatedAt: string;
+}
+
+export async function listPromptPartials(projectId: string): Promise<PromptPartial[]> {
+  return await invoke<PromptPartial[]>('list_prompt_partials', { projectId });
+}
+
+export async function savePromptPartial(
+  projectId: string,
+  name: string,
+  template: string
+): Promise<PromptPartial> {
+  return await invoke<PromptPartial>('save_prompt_partial', { projectId, name, template });
+}
+
+export async function deletePromptPartial(projectId: string, name: string): Promise<void> {
+  await invoke('delete_prompt_partial', { projectId, name });
+}
+
+export interface IncidentResponse {
+  id: string;
+  projectId: string;
+  runId: string;
+  runExecutionId: string;
+  checkpointId: string;
+  incidentKind: string;
+  action: 'notify' | 'pause_for_approval';
+  status: 'pending' | 'acknowledged' | 'approved' | 'rejected';
+  details: string;
+  createdAt: string;
+  resolvedAt?: string;
+}
+
+export async function listIncidentResponses(
+  projectId: string,
+  pendingOnly = false
+): Promise<IncidentResponse[]> {
+  return await invoke<IncidentResponse[]>('list_incident_responses', { projectId, pendingOnly });
+}
+
+export async function acknowledgeIncidentNotification(responseId: string): Promise<IncidentResponse> {
+  return await invoke<IncidentResponse>('acknowledge_incident_notification', { responseId });
+}
+
+export async function decideIncidentApproval(
+  responseId: string,
+  approve: boolean
+): Promise<IncidentResponse> {
+  return await invoke<IncidentResponse>('decide_incident_approval', { responseId, approve });
+}
+
+/** Who acknowledged an incident, when and why, signed with the project key */
+export interface IncidentAcknowledgement {
+  checkpointId: string;
+  projectId: string;
+  checkpointCurrChain: string;
+  acknowledgedBy: string;
+  note?: string;
+  acknowledgedAt: string;
+  signerPublicKey: string;
+  signature: string;
+}
+
+export interface IncidentRecord {
+  checkpointId: string;
+  runId: string;
+  runName: string;
+  runExecutionId?: string;
+  timestamp: string;
+  kind: string;
+  severity: string;
+  details: string;
+  acknowledgement?: IncidentAcknowledgement;
+}
+
+/** UTC days (YYYY-MM-DD), both included */
+export interface DateRange {
+  from?: string;
+  to?: string;
+}
+
+export interface IncidentFilters {
+  severity?: string;
+  kind?: string;
+  dateRange?: DateRange;
+}
+
+export async function listIncidents(
+  projectId: string,
+  filters: IncidentFilters = {}
+): Promise<IncidentRecord[]> {
+  return await invoke<IncidentRecord[]>('list_incidents', {
+    projectId,
+    severity: filters.severity ?? null,
+    kind: filters.kind ?? null,
+    dateRange: filters.dateRange ?? null
+  });
+}
+
+export async function acknowledgeIncident(
+  checkpointId: string,
+  acknowledgedBy: string,
+  note?: string
+): Promise<IncidentAcknowledgement> {
+  return await invoke<IncidentAcknowledgement>('acknowledge_incident', {
+    checkpointId,
+    acknowledgedBy,
+    note: note ?? null
+  });
+}
+
 export async function updatePolicy(projectId: string, policy: Policy): Promise<void> {
   await invoke('update_policy', { projectId, policy });
 }
@@ -586,19 +1336,308 @@ export async function getCurrentPolicyVersionNumber(projectId: string): Promise<
   return await invoke<number>('get_current_policy_version_number', { projectId });
 }
 
+export type PolicyProposalStatus = 'pending' | 'approved' | 'rejected';
+
+export interface PolicyProposal {
+  id: string;
+  projectId: string;
+  /** Policy version when proposed; 0 for none */
+  baseVersion: number;
+  policy: Policy;
+  proposedBy: string;
+  changeNotes?: string | null;
+  proposedAt: string;
+  status: PolicyProposalStatus;
+  decidedBy?: string | null;
+  decidedAt?: string | null;
+  approvedVersion?: number | null;
+}
+
+export interface PolicyFieldChange {
+  /** e.g. `windowBudgets[0].tokens` */
+  path: string;
+  /** Current value; absent when the field is unset */
+  left?: unknown;
+  /** Proposed value */
+  right?: unknown;
+}
+
+export interface PolicyProposalReview {
+  proposal: PolicyProposal;
+  currentVersion: number;
+  /** Pending, but the policy changed since; it must be proposed again */
+  stale: boolean;
+  changes: PolicyFieldChange[];
+}
+
+export async function proposePolicyChange(
+  projectId: string,
+  policy: Policy,
+  proposedBy: string,
+  changeNotes?: string,
+): Promise<PolicyProposalReview> {
+  return await invoke<PolicyProposalReview>('propose_policy_change', {
+    projectId,
+    policy,
+    proposedBy,
+    changeNotes: changeNotes ?? null,
+  });
+}
+
+export async function approvePolicyChange(
+  proposalId: string,
+  approvedBy: string,
+): Promise<PolicyVersion> {
+  return await invoke<PolicyVersion>('approve_policy_change', { proposalId, approvedBy });
+}
+
+export async function rejectPolicyChange(
+  proposalId: string,
+  rejectedBy: string,
+): Promise<PolicyProposal> {
+  return await invoke<PolicyProposal>('reject_policy_change', { proposalId, rejectedBy });
+}
+
+export async function listPolicyProposals(projectId: string): Promise<PolicyProposalReview[]> {
+  return await invoke<PolicyProposalReview[]>('list_policy_proposals', { projectId });
+}
+
+export type SimulatedOutcome = 'blocked' | 'warned';
+
+export interface SimulatedIncident {
+  kind: string;
+  severity: string;
+  details: string;
+  /** The draft's action for the incident */
+  action: IncidentAction;
+}
+
+export interface SimulatedExecution {
+  executionId: string;
+  runId: string;
+  runName: string;
+  createdAt: string;
+  usageTokens: number;
+  usd: number;
+  natureCost: number;
+  outcome: SimulatedOutcome;
+  incidents: SimulatedIncident[];
+}
+
+export interface PolicySimulation {
+  projectId: string;
+  executionsReplayed: number;
+  blocked: number;
+  warned: number;
+  /** Executions the draft would have blocked or warned on, oldest first */
+  executions: SimulatedExecution[];
+}
+
+/** Replay the project's past executions against a draft policy before saving it */
+export async function simulatePolicyChange(
+  projectId: string,
+  draftPolicy: Policy
+): Promise<PolicySimulation> {
+  return await invoke<PolicySimulation>('simulate_policy_change', { projectId, draftPolicy });
+}
+
+
+/** Encoding of the CAR inside an emitted bundle */
+export type CarFormat = 'json' | 'dag-cbor';
+
+// Prompts and output attachments withheld behind salted hash commitments
+export interface RedactionRequest {
+  stepIds?: string[];
+  checkpointIds?: string[];
+}
+
+// Which executions a CAR covers; 'all' packages every execution of the run
+export type ExecutionSelection =
+  | { mode: 'latest' }
+  | { mode: 'execution'; runExecutionId: string }
+  | { mode: 'all' };
+
+// RFC 3161 timestamp authority and transparency log URLs to attest a CAR
+export interface Attestations {
+  timestampAuthority?: string;
+  transparencyLog?: string;
+}
+
+// age X25519 recipients (age1...) to encrypt a CAR bundle to
+export interface Encryption {
+  recipients: string[];
+}
+
+export async function emitCar(
+  runId: string,
+  outputPath?: string,
+  format?: CarFormat,
+  redaction?: RedactionRequest,
+  execution?: ExecutionSelection,
+  attestations?: Attestations,
+  encryption?: Encryption,
+): Promise<string> {
+  return await invoke<string>('emit_car', {
+    runId,
+    outputPath: outputPath ?? null,
+    format: format ?? null,
+    redaction: redaction ?? null,
+    execution: execution ?? null,
+    attestations: attestations ?? null,
+    encryption: encryption ?? null,
+  });
+}
+
+export async function replayRun(runId: string, options?: ReplayOptions): Promise<ReplayReport> {
+  return await invoke<ReplayReport>('replay_run', { runId, options: options ?? null });
+}
+
+export async function issueVerificationChallenge(
+  runId: string,
+  orderIndex: number,
+  ttlSeconds?: number,
+): Promise<VerificationChallenge> {
+  return await invoke<VerificationChallenge>('issue_verification_challenge', {
+    runId,
+    orderIndex,
+    ttlSeconds: ttlSeconds ?? null,
+  });
+}
+
+export async function respondToVerificationChallenge(
+  challenge: VerificationChallenge,
+): Promise<ChallengeProof> {
+  return await invoke<ChallengeProof>('respond_to_verification_challenge', { challenge });
+}
+
+export async function verifyChallengeProof(
+  proof: ChallengeProof,
+  challenge: VerificationChallenge,
+  expectedPublicKey?: string,
+): Promise<ChallengeVerification> {
+  return await invoke<ChallengeVerification>('verify_challenge_proof', {
+    proof,
+    challenge,
+    expectedPublicKey: expectedPublicKey ?? null,
+  });
+}
+
+// The user's grid region (null: the catalog's default region) and gCO2e per kWh by region
+export interface GridSettings {
+  gridRegion?: string | null;
+  defaultRegion: string;
+  carbonIntensity: Record<string, number>;
+}
+
+export async function getGridSettings(): Promise<GridSettings> {
+  return await invoke<GridSettings>('get_grid_settings');
+}
+
+export async function setGridRegion(gridRegion: string | null): Promise<GridSettings> {
+  return await invoke<GridSettings>('set_grid_region', { gridRegion });
+}
+
+export interface SelfVerificationSettings {
+  enabled: boolean;
+  hourUtc: number;
+  lookbackDays: number;
+  lastRunAt?: string;
+}
+
+export interface VerificationRecord {
+  id: string;
+  passId: string;
+  targetKind: 'car_file' | 'checkpoint_chain';
+  targetId: string;
+  projectId?: string;
+  runId?: string;
+  status: 'passed' | 'failed';
+  details?: string;
+  newFailure: boolean;
+  acknowledgedAt?: string;
+  verifiedAt: string;
+}
+
+export interface SelfVerificationReport {
+  passId: string;
+  startedAt: string;
+  finishedAt: string;
+  carFilesChecked: number;
+  chainsChecked: number;
+  failed: number;
+  newFailures: VerificationRecord[];
+}
+
+export async function getSelfVerificationSettings(): Promise<SelfVerificationSettings> {
+  return await invoke<SelfVerificationSettings>('get_self_verification_settings');
+}
+
+export async function setSelfVerificationSettings(
+  enabled: boolean,
+  hourUtc: number,
+  lookbackDays: number,
+): Promise<SelfVerificationSettings> {
+  return await invoke<SelfVerificationSettings>('set_self_verification_settings', {
+    enabled,
+    hourUtc,
+    lookbackDays,
+  });
+}
+
+export async function runSelfVerification(): Promise<SelfVerificationReport> {
+  return await invoke<SelfVerificationReport>('run_self_verification');
+}
+
+export async function listVerificationHistory(limit?: number): Promise<VerificationRecord[]> {
+  return await invoke<VerificationRecord[]>('list_verification_history', { limit: limit ?? null });
+}
 
-export async function emitCar(runId: string, outputPath?: string): Promise<string> {
-  return await invoke<string>('emit_car', { runId, outputPath: outputPath ?? null });
+export async function listSelfVerificationAlerts(
+  pendingOnly: boolean,
+): Promise<VerificationRecord[]> {
+  return await invoke<VerificationRecord[]>('list_self_verification_alerts', { pendingOnly });
 }
 
-export async function replayRun(runId: string): Promise<ReplayReport> {
-  return await invoke<ReplayReport>('replay_run', { runId });
+export async function acknowledgeSelfVerificationAlert(
+  alertId: string,
+): Promise<VerificationRecord> {
+  return await invoke<VerificationRecord>('acknowledge_self_verification_alert', { alertId });
 }
 
 export async function exportProject(projectId: string, outputPath?: string): Promise<string> {
   return await invoke<string>('export_project', { projectId, outputPath: outputPath ?? null });
 }
 
+export interface RunArchive {
+  runId: string;
+  archivePath: string;
+  archiveSha256: string;
+  signature: string;
+  archivedAt: string;
+  /** Set once payloads were restored; absent while the run is in cold storage */
+  rehydratedAt?: string;
+}
+
+export async function archiveRun(runId: string): Promise<RunArchive> {
+  return await invoke<RunArchive>('archive_run', { runId });
+}
+
+export async function rehydrateRun(runId: string): Promise<RunArchive> {
+  return await invoke<RunArchive>('rehydrate_run', { runId });
+}
+
+export async function getRunArchive(runId: string): Promise<RunArchive | null> {
+  return await invoke<RunArchive | null>('get_run_archive', { runId });
+}
+
+export async function getArchiveDir(): Promise<string> {
+  return await invoke<string>('get_archive_dir');
+}
+
+export async function setArchiveDir(archiveDir: string | null): Promise<void> {
+  await invoke('set_archive_dir', { archiveDir });
+}
+
 export async function importProject(payload: FileImportPayload): Promise<ProjectImportSummary> {
   return await invoke<ProjectImportSummary>('import_project', { args: payload });
 }
@@ -607,6 +1646,148 @@ export async function importCar(payload: FileImportPayload): Promise<CarImportRe
   return await invoke<CarImportResult>('import_car', { args: payload });
 }
 
+// ============================================================================
+// Telemetry (opt-in, aggregated locally)
+// ============================================================================
+
+export interface TelemetrySettings {
+  enabled: boolean;
+  enabledAt?: string | null;
+  lastExportedAt?: string | null;
+}
+
+export interface VerificationStats {
+  passed: number;
+  failed: number;
+  passRate?: number | null;
+}
+
+export interface TelemetryReport {
+  schemaVersion: number;
+  collectingSince?: string | null;
+  generatedOn: string;
+  appVersion: string;
+  runsCompleted: number;
+  runsFailed: number;
+  stepTypes: Record<string, number>;
+  verifications: Record<string, VerificationStats>;
+}
+
+export async function getTelemetrySettings(): Promise<TelemetrySettings> {
+  return await invoke<TelemetrySettings>('get_telemetry_settings');
+}
+
+export async function setTelemetryEnabled(enabled: boolean): Promise<TelemetrySettings> {
+  return await invoke<TelemetrySettings>('set_telemetry_enabled', { enabled });
+}
+
+export async function previewTelemetryReport(): Promise<TelemetryReport> {
+  return await invoke<TelemetryReport>('preview_telemetry_report');
+}
+
+export async function exportTelemetryReport(outputPath: string): Promise<TelemetryReport> {
+  return await invoke<TelemetryReport>('export_telemetry_report', { outputPath });
+}
+
+export async function resetTelemetry(): Promise<void> {
+  await invoke('reset_telemetry');
+}
+
+// ============================================================================
+// Budget alerts and notifications
+// ============================================================================
+
+export interface NotificationSettings {
+  webhookUrl?: string | null;
+  emailTo?: string | null;
+  emailFrom?: string | null;
+  smtpHost?: string | null;
+  smtpPort?: number | null;
+  smtpUsername?: string | null;
+}
+
+export interface BudgetAlert {
+  id: string;
+  projectId: string;
+  // 'policy' for the policy version's budgets, or 'day' | 'week' | 'month'
+  scope: string;
+  // Policy version, or the first day of the window
+  period: string;
+  budget: 'tokens' | 'usd' | 'natureCost';
+  threshold: number;
+  used: number;
+  limit: number;
+  firedAt: string;
+}
+
+export const BUDGET_ALERT_EVENT = 'budget-alert';
+
+export async function getNotificationSettings(): Promise<NotificationSettings> {
+  return await invoke<NotificationSettings>('get_notification_settings');
+}
+
+// An empty smtpPassword removes the stored one; omitting it keeps it
+export async function setNotificationSettings(
+  settings: NotificationSettings,
+  smtpPassword?: string,
+): Promise<NotificationSettings> {
+  return await invoke<NotificationSettings>('set_notification_settings', {
+    settings,
+    smtpPassword: smtpPassword ?? null,
+  });
+}
+
+export async function listBudgetAlerts(projectId: string): Promise<BudgetAlert[]> {
+  return await invoke<BudgetAlert[]>('list_budget_alerts', { projectId });
+}
+
+export async function onBudgetAlert(
+  handler: (alert: BudgetAlert) => void,
+): Promise<UnlistenFn> {
+  return await listen<BudgetAlert>(BUDGET_ALERT_EVENT, (event) => handler(event.payload));
+}
+
+// ============================================================================
+// Plugins (WASM step types)
+// ============================================================================
+
+export type PluginCapability = 'read_step_output' | 'read_source_file' | 'log';
+
+export interface PluginStepType {
+  name: string;
+  description?: string;
+}
+
+export interface PluginManifest {
+  id: string;
+  name: string;
+  version: string;
+  module: string;
+  moduleSha256: string;
+  stepTypes: PluginStepType[];
+  capabilities: PluginCapability[];
+  maxFuel: number;
+  maxMemoryBytes: number;
+}
+
+export interface PluginLoadError {
+  directory: string;
+  message: string;
+}
+
+export interface PluginListing {
+  plugins: PluginManifest[];
+  errors: PluginLoadError[];
+}
+
+export async function listPlugins(): Promise<PluginListing> {
+  return await invoke<PluginListing>('list_plugins');
+}
+
+export async function reloadPlugins(): Promise<PluginListing> {
+  return await invoke<PluginListing>('reload_plugins');
+}
+
 // ============================================================================
 // API Key Management
 // ============================================================================
@@ -670,3 +1851,81 @@ export async function listAllAvailableModels(): Promise<CatalogModel[]> {
 export async function estimateModelCost(modelId: string, tokens: number): Promise<ModelCostEstimate> {
   return await invoke<ModelCostEstimate>('estimate_model_cost', { modelId, tokens });
 }
+
+// ============================================================================
+// Model Weights
+// ============================================================================
+
+export interface ModelWeights {
+  id: string;
+  modelId: string;
+  weightsSha256: string;
+  weightsPath?: string;
+  trainingCarId?: string;
+  registeredAt: string;
+}
+
+export async function registerModelWeights(
+  modelId: string,
+  weightsPath: string,
+  trainingCarId?: string | null,
+): Promise<ModelWeights> {
+  return await invoke<ModelWeights>('register_model_weights', {
+    modelId,
+    weightsPath,
+    trainingCarId: trainingCarId ?? null,
+  });
+}
+
+export async function listModelWeights(): Promise<ModelWeights[]> {
+  return await invoke<ModelWeights[]>('list_model_weights');
+}
+
+export async function unregisterModelWeights(modelId: string): Promise<void> {
+  await invoke('unregister_model_weights', { modelId });
+}
+
+// ============================================================================
+// Dataset Splits
+// ============================================================================
+
+export type DatasetSplitKind = 'train' | 'validation' | 'test';
+
+export interface DatasetSplit {
+  projectId: string;
+  dataset: string;
+  split: DatasetSplitKind;
+  documentHashes: string[];
+}
+
+export async function setDatasetSplit(
+  projectId: string,
+  dataset: string,
+  split: DatasetSplitKind,
+  documentHashes: string[],
+  documentPaths?: string[] | null,
+): Promise<DatasetSplit> {
+  return await invoke<DatasetSplit>('set_dataset_split', {
+    projectId,
+    dataset,
+    split,
+    documentHashes,
+    documentPaths: documentPaths ?? null,
+  });
+}
+
+export async function listDatasetSplits(projectId: string): Promise<DatasetSplit[]> {
+  return await invoke<DatasetSplit[]>('list_dataset_splits', { projectId });
+}
+
+export async function deleteDataset(projectId: string, dataset: string): Promise<void> {
+  await invoke('delete_dataset', { projectId, dataset });
+}
+
+// ============================================================================
+// Response Cache
+// ============================================================================
+
+export async function clearResponseCache(): Promise<number> {
+  return await invoke<number>('clear_response_cache');
+}
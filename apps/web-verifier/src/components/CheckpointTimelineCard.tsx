@@ -0,0 +1,68 @@
+import { CheckCircle2, XCircle } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import type { CheckpointRecord } from '../types/verifier';
+
+interface CheckpointTimelineCardProps {
+  checkpoints: CheckpointRecord[];
+}
+
+const shortHash = (hash?: string) => (hash ? `${hash.slice(0, 12)}…` : '—');
+
+const Check = ({ valid, label }: { valid: boolean; label: string }) => (
+  <span className={`flex items-center gap-1 ${valid ? 'text-emerald-700' : 'text-rose-700'}`}>
+    {valid ? <CheckCircle2 className="h-3 w-3" /> : <XCircle className="h-3 w-3" />}
+    {label}
+  </span>
+);
+
+// Every checkpoint of the run in order, each with its own chain and
+// signature outcome; expand one to inspect its hashes
+const CheckpointTimelineCard = ({ checkpoints }: CheckpointTimelineCardProps) => {
+  const { t } = useTranslation();
+
+  return (
+    <section className="rounded-xl border border-slate-200 bg-white shadow-sm overflow-hidden">
+      <div className="bg-slate-50 border-b border-slate-200 px-4 py-3">
+        <h3 className="text-sm font-semibold text-slate-700">{t('timeline_title')}</h3>
+      </div>
+      <ol className="p-4 space-y-2">
+        {checkpoints.map((checkpoint) => (
+          <li key={checkpoint.index} className="rounded-lg border border-slate-100 bg-slate-50 px-3 py-2">
+            <details>
+              <summary className="cursor-pointer text-xs text-slate-700">
+                <span className="font-mono text-slate-400">#{checkpoint.index + 1}</span>{' '}
+                <span className="font-semibold">{checkpoint.kind}</span>{' '}
+                <span className="text-slate-500">{checkpoint.timestamp}</span>
+              </summary>
+              <dl className="mt-2 grid grid-cols-[auto,1fr] gap-x-3 gap-y-1 text-[10px] text-slate-600">
+                <dt>{t('timeline_id')}</dt>
+                <dd className="font-mono break-all">{checkpoint.id}</dd>
+                <dt>{t('timeline_inputs')}</dt>
+                <dd className="font-mono">{shortHash(checkpoint.inputs_sha256)}</dd>
+                <dt>{t('timeline_outputs')}</dt>
+                <dd className="font-mono">{shortHash(checkpoint.outputs_sha256)}</dd>
+                <dt>{t('timeline_chain')}</dt>
+                <dd className="font-mono">{shortHash(checkpoint.curr_chain)}</dd>
+                <dt>{t('timeline_tokens')}</dt>
+                <dd>
+                  {t('timeline_token_usage', {
+                    prompt: checkpoint.prompt_tokens,
+                    completion: checkpoint.completion_tokens,
+                    total: checkpoint.usage_tokens
+                  })}
+                  {checkpoint.cache_hit && ` · ${t('timeline_cache_hit')}`}
+                </dd>
+              </dl>
+            </details>
+            <div className="mt-1 flex gap-3 text-[10px]">
+              <Check valid={checkpoint.chain_valid} label={t('timeline_chain_check')} />
+              <Check valid={checkpoint.signature_valid} label={t('timeline_signature_check')} />
+            </div>
+          </li>
+        ))}
+      </ol>
+    </section>
+  );
+};
+
+export default CheckpointTimelineCard;
@@ -0,0 +1,81 @@
+import { useState, type ChangeEvent } from 'react';
+import { CheckCircle2, FolderOpen, HardDrive, Loader2, XCircle } from 'lucide-react';
+import { useTranslation } from 'react-i18next';
+import type { ExternalAttachment, ExternalFileCheck } from '../types/verifier';
+import { checkExternalFile } from '../wasm/loader';
+import { formatFileSize } from '../utils/zipParser';
+
+interface ExternalAttachmentsCardProps {
+  attachments: ExternalAttachment[];
+}
+
+// Name a local copy may go by: the last path segment of the URI
+const fileNameOf = (uri: string) => uri.split(/[?#]/)[0].split('/').pop() ?? uri;
+
+const ExternalAttachmentsCard = ({ attachments }: ExternalAttachmentsCardProps) => {
+  const { t } = useTranslation();
+  const [checks, setChecks] = useState<Record<string, ExternalFileCheck>>({});
+  const [checking, setChecking] = useState(false);
+
+  const onSelect = async (event: ChangeEvent<HTMLInputElement>) => {
+    const files = Array.from(event.target.files ?? []);
+    if (!files.length) return;
+    setChecking(true);
+    try {
+      // Copies are matched by content hash or by the file name of the URI
+      for (const attachment of attachments) {
+        const copy = files.find(
+          (file) => file.name === attachment.sha256 || file.name === fileNameOf(attachment.uri)
+        );
+        if (!copy) continue;
+        const check = await checkExternalFile(copy, attachment);
+        setChecks((previous) => ({ ...previous, [attachment.uri]: check }));
+      }
+    } finally {
+      setChecking(false);
+    }
+  };
+
+  return (
+    <section className="rounded-xl border border-slate-200 bg-white shadow-sm overflow-hidden">
+      <div className="bg-slate-50 border-b border-slate-200 px-4 py-3">
+        <h3 className="text-sm font-semibold text-slate-700">{t('external_attachments_title')}</h3>
+      </div>
+      <div className="p-4 space-y-3">
+        <p className="text-xs text-slate-500">{t('external_attachments_body')}</p>
+        <ul className="space-y-2">
+          {attachments.map((attachment) => {
+            const check = checks[attachment.uri];
+            return (
+              <li key={attachment.uri} className="rounded-lg border border-slate-100 bg-slate-50 px-3 py-2">
+                <div className="flex items-center gap-2">
+                  {!check && <HardDrive className="h-4 w-4 shrink-0 text-slate-400" />}
+                  {check?.verified && <CheckCircle2 className="h-4 w-4 shrink-0 text-emerald-600" />}
+                  {check && !check.verified && <XCircle className="h-4 w-4 shrink-0 text-rose-600" />}
+                  <span className="text-xs font-mono text-slate-700 break-all">
+                    {fileNameOf(attachment.uri)}
+                  </span>
+                </div>
+                <p className="mt-1 text-[10px] text-slate-500">
+                  {formatFileSize(attachment.size)} ·{' '}
+                  {check
+                    ? check.verified
+                      ? t('external_attachments_verified')
+                      : check.error
+                    : t('external_attachments_unchecked')}
+                </p>
+              </li>
+            );
+          })}
+        </ul>
+        <label className="flex cursor-pointer items-center justify-center gap-2 rounded-lg border border-dashed border-slate-300 px-3 py-2 text-xs font-medium text-slate-600 hover:border-emerald-400 hover:text-emerald-700">
+          {checking ? <Loader2 className="h-4 w-4 animate-spin" /> : <FolderOpen className="h-4 w-4" />}
+          {checking ? t('external_attachments_checking') : t('external_attachments_select')}
+          <input type="file" multiple className="hidden" disabled={checking} onChange={onSelect} />
+        </label>
+      </div>
+    </section>
+  );
+};
+
+export default ExternalAttachmentsCard;
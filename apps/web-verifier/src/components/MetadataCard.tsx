@@ -3,6 +3,7 @@ import {
   CalendarClock,
   FileBadge2,
   Fingerprint,
+  Monitor,
   ShieldCheck,
   Workflow,
   Copy
@@ -44,7 +45,10 @@ const MetadataCard = ({ report }: MetadataCardProps) => {
 
   const statusStyles = {
     verified: {
-      label: t('metadata_status_verified'),
+      label:
+        report.summary.redactions > 0
+          ? t('metadata_status_verified_redacted', { count: report.summary.redactions })
+          : t('metadata_status_verified'),
       className: 'border-emerald-200 bg-emerald-100 text-emerald-700'
     },
     failed: {
@@ -63,6 +67,18 @@ const MetadataCard = ({ report }: MetadataCardProps) => {
   const modelVersion = report.model.version ? truncateHash(report.model.version, 8, 8) : '';
   const modelLabel = [modelName, modelVersion].filter(Boolean).join(' · ') || 'Unknown model';
 
+  const environment = report.run_environment;
+  const environmentLabel = environment
+    ? [
+        environment.os_version ?? environment.os,
+        environment.arch,
+        `Intelexta ${environment.app_version}`,
+        environment.ollama_version && `Ollama ${environment.ollama_version}`
+      ]
+        .filter(Boolean)
+        .join(' · ')
+    : '';
+
   const signerKey = report.signer?.public_key ?? '';
   const signerLabel = signerKey ? truncateHash(signerKey, 16, 8) : t('metadata_signer_unsigned');
 
@@ -160,6 +176,23 @@ const MetadataCard = ({ report }: MetadataCardProps) => {
           </div>
         </div>
 
+        {/* Run environment */}
+        {environment && (
+          <div className="flex items-start gap-3 rounded-lg border border-slate-100 bg-slate-50 px-4 py-3">
+            <Monitor className="mt-0.5 h-4 w-4 flex-shrink-0 text-emerald-600" aria-hidden />
+            <div className="flex-1 min-w-0">
+              <dt className="text-[10px] uppercase font-bold tracking-wide text-slate-400">{t('metadata_environment')}</dt>
+              <dd className="text-sm font-medium text-slate-700 truncate">{environmentLabel}</dd>
+              <dd className="text-xs text-slate-500 truncate">
+                {t('metadata_environment_catalog', { version: environment.model_catalog_version })}
+              </dd>
+              <dd className="text-xs text-slate-500 font-mono truncate" title={environment.machine_fingerprint}>
+                {t('metadata_environment_machine', { fingerprint: truncateHash(environment.machine_fingerprint, 16, 8) })}
+              </dd>
+            </div>
+          </div>
+        )}
+
         {/* Timestamp */}
         <div className="flex items-start gap-3 rounded-lg border border-slate-100 bg-slate-50 px-4 py-3">
           <CalendarClock className="mt-0.5 h-4 w-4 flex-shrink-0 text-emerald-600" aria-hidden />
@@ -1,13 +1,20 @@
-import { useCallback, useEffect, useState } from 'react';
+import { type ChangeEvent, useCallback, useEffect, useState } from 'react';
 import { type FileRejection, useDropzone } from 'react-dropzone';
 import clsx from 'clsx';
 import { useTranslation } from 'react-i18next';
-import { AlertCircle, CheckCircle2, Loader2, UploadCloud, FileJson, Package } from 'lucide-react';
-import { initVerifier, verifyCarBytes, verifyCarJson } from '../wasm/loader';
-import type { VerificationReport } from '../types/verifier';
+import { AlertCircle, CheckCircle2, Loader2, UploadCloud, FileJson, FolderOpen, Package } from 'lucide-react';
+import {
+  initVerifier,
+  verifyCarFileInWorker,
+  verifyCarJson,
+  verifyCarWithAttachments
+} from '../wasm/loader';
+import type { VerificationProgress, VerificationReport } from '../types/verifier';
 import type { Car, AttachmentPreview } from '../types/car';
 import WorkflowViewer from './WorkflowViewer';
 import MetadataCard from './MetadataCard';
+import ExternalAttachmentsCard from './ExternalAttachmentsCard';
+import CheckpointTimelineCard from './CheckpointTimelineCard';
 import ContentView from './ContentView';
 import { parseCarZip } from '../utils/zipParser';
 import { Layout } from './Layout';
@@ -15,18 +22,30 @@ import {
   PROOF_FILE_ACCEPT_MESSAGE,
   buildProofDropzoneAccept,
   proofFileValidator,
+  splitProofDirectory,
   validateProofFileName
 } from '../utils/proofFiles';
 
 type Status = 'idle' | 'loading' | 'success' | 'error';
 type ViewMode = 'verify' | 'content';
 
-const LoadingSkeleton = () => {
+const LoadingSkeleton = ({ progress }: { progress: VerificationProgress | null }) => {
   const { t } = useTranslation();
+  const fraction = progress?.total ? Math.min(progress.completed / progress.total, 1) : null;
   return (
     <div className="flex flex-col items-center justify-center py-20 gap-4 text-slate-500">
       <Loader2 className="w-10 h-10 animate-spin text-emerald-600" />
-      <p className="font-medium animate-pulse">{t('loading_verifying')}</p>
+      <p className="font-medium animate-pulse">
+        {progress ? t(`progress_${progress.stage}`) : t('loading_verifying')}
+      </p>
+      {fraction !== null && (
+        <div className="w-64 h-2 rounded-full bg-slate-200 overflow-hidden">
+          <div
+            className="h-full bg-emerald-600 transition-all"
+            style={{ width: `${Math.round(fraction * 100)}%` }}
+          />
+        </div>
+      )}
     </div>
   );
 };
@@ -42,6 +61,7 @@ const Verifier = () => {
   const [viewMode, setViewMode] = useState<ViewMode>('verify');
   const [parsedCar, setParsedCar] = useState<Car | null>(null);
   const [attachments, setAttachments] = useState<AttachmentPreview[]>([]);
+  const [progress, setProgress] = useState<VerificationProgress | null>(null);
 
   useEffect(() => {
     initVerifier().catch((err) => console.warn('Verifier init warning', err));
@@ -66,6 +86,7 @@ const Verifier = () => {
     setParsedCar(null);
     setAttachments([]);
     setFileKind(null);
+    setProgress(null);
 
     const validation = validateProofFileName(file.name);
     if (!validation.valid) {
@@ -100,9 +121,8 @@ const Verifier = () => {
           console.warn('Failed to parse ZIP:', parseErr);
         }
 
-        const buffer = await file.arrayBuffer();
-        const bytes = new Uint8Array(buffer);
-        const verification = await verifyCarBytes(bytes);
+        // Verified on a worker so large bundles do not freeze the page
+        const verification = await verifyCarFileInWorker(file, undefined, setProgress);
         setResult(verification);
         setRawJson(JSON.stringify(verification, null, 2));
         setStatus(verification.status === 'verified' ? 'success' : 'error');
@@ -115,6 +135,50 @@ const Verifier = () => {
     }
   }, []);
 
+  // A bare car.json with its attachments/ directory, picked as one folder
+  const onPickFolder = useCallback(async (event: ChangeEvent<HTMLInputElement>) => {
+    const files = Array.from(event.target.files ?? []);
+    event.target.value = '';
+    if (!files.length) return;
+
+    const { car, attachments: attachmentFiles } = splitProofDirectory(files);
+    setDroppedFileName(car ? car.webkitRelativePath : null);
+    setStatus('loading');
+    setError(null);
+    setResult(null);
+    setRawJson('');
+    setParsedCar(null);
+    setAttachments([]);
+    setFileKind(null);
+    setProgress(null);
+
+    if (!car) {
+      setStatus('error');
+      setError(t('folder_missing_car'));
+      return;
+    }
+
+    setFileKind('json');
+
+    try {
+      const json = await car.text();
+      try {
+        setParsedCar(JSON.parse(json) as Car);
+      } catch (parseErr) {
+        console.warn('Failed to parse CAR JSON:', parseErr);
+      }
+      const verification = await verifyCarWithAttachments(json, attachmentFiles);
+      setResult(verification);
+      setRawJson(JSON.stringify(verification, null, 2));
+      setStatus(verification.status === 'verified' ? 'success' : 'error');
+      if (verification.status !== 'verified') setError(verification.error || 'Verification failed');
+    } catch (err) {
+      console.error(err);
+      setStatus('error');
+      setError(err instanceof Error ? err.message : 'Unknown error');
+    }
+  }, [t]);
+
   const onDropRejected = useCallback((fileRejections: FileRejection[]) => {
     if (!fileRejections.length) return;
     setStatus('error');
@@ -173,6 +237,12 @@ const Verifier = () => {
             </div>
           </div>
 
+          <label className="mt-4 flex items-center justify-center gap-2 text-sm text-slate-500 hover:text-emerald-600 cursor-pointer">
+            <FolderOpen size={16} />
+            {t('folder_pick')}
+            <input type="file" className="hidden" onChange={onPickFolder} {...{ webkitdirectory: '' }} />
+          </label>
+
           {error && (
             <div className="mt-6 rounded-lg border border-rose-200 bg-rose-50 p-4 text-sm text-rose-700 flex items-start gap-3 shadow-sm">
               <AlertCircle className="h-5 w-5 shrink-0 text-rose-500" />
@@ -186,7 +256,7 @@ const Verifier = () => {
       )}
 
       {/* 2. LOADING STATE */}
-      {status === 'loading' && <LoadingSkeleton />}
+      {status === 'loading' && <LoadingSkeleton progress={progress} />}
 
       {/* 3. RESULT STATE */}
       {result && (
@@ -216,6 +286,12 @@ const Verifier = () => {
               </div>
               <aside className="space-y-6">
                 <MetadataCard report={result} />
+                {result.external_attachments && (
+                  <ExternalAttachmentsCard attachments={result.external_attachments} />
+                )}
+                {result.checkpoints && result.checkpoints.length > 0 && (
+                  <CheckpointTimelineCard checkpoints={result.checkpoints} />
+                )}
                 <div className="rounded-xl border border-slate-200 bg-white shadow-sm overflow-hidden">
                   <div className="bg-slate-50 border-b border-slate-200 px-4 py-3">
                     <h3 className="text-sm font-semibold text-slate-700">{t('raw_json_output')}</h3>
@@ -1,4 +1,4 @@
-import { AlertCircle, CheckCircle2, Circle, FileText, Paperclip } from 'lucide-react';
+import { AlertCircle, AlertTriangle, CheckCircle2, Circle, FileText, Paperclip } from 'lucide-react';
 import { useTranslation } from 'react-i18next';
 import type { VerificationReport, WorkflowStep } from '../types/verifier';
 
@@ -15,6 +15,10 @@ const statusAppearance = {
     badge: 'border-rose-200 bg-rose-50 text-rose-700',
     icon: <AlertCircle className="h-5 w-5 text-rose-500" aria-hidden />
   },
+  warning: {
+    badge: 'border-amber-200 bg-amber-50 text-amber-700',
+    icon: <AlertTriangle className="h-5 w-5 text-amber-500" aria-hidden />
+  },
   skipped: {
     badge: 'border-slate-200 bg-slate-100 text-slate-500',
     icon: <Circle className="h-4 w-4 text-slate-300" aria-hidden />
@@ -81,6 +85,46 @@ const WorkflowViewer = ({ report }: WorkflowViewerProps) => {
                   </div>
                 )}
 
+                {step.explanation && (
+                  <details className="rounded-lg border border-rose-200 bg-white p-3 text-xs text-slate-700">
+                    <summary className="cursor-pointer font-bold text-rose-700">
+                      {t('workflow_diagnostics')}
+                    </summary>
+                    <div className="mt-3 space-y-3">
+                      <p>{step.explanation.summary}</p>
+                      {step.explanation.checkpoint && (
+                        <p className="font-mono text-[11px] text-slate-500">
+                          {t('workflow_diagnostics_checkpoint', step.explanation.checkpoint)}
+                        </p>
+                      )}
+                      {step.explanation.subject && (
+                        <p>
+                          <span className="font-bold text-slate-500">{t('workflow_diagnostics_subject')}: </span>
+                          <span className="font-mono break-all">{step.explanation.subject}</span>
+                        </p>
+                      )}
+                      {(step.explanation.computed || step.explanation.expected) && (
+                        <dl className="grid gap-1 font-mono text-[11px]">
+                          <dt className="font-sans font-bold text-slate-500">{t('workflow_diagnostics_computed')}</dt>
+                          <dd className="break-all">{step.explanation.computed ?? '—'}</dd>
+                          <dt className="font-sans font-bold text-slate-500">{t('workflow_diagnostics_expected')}</dt>
+                          <dd className="break-all">{step.explanation.expected ?? '—'}</dd>
+                        </dl>
+                      )}
+                      {step.explanation.remediation.length > 0 && (
+                        <div>
+                          <p className="font-bold text-slate-500">{t('workflow_diagnostics_remediation')}</p>
+                          <ul className="mt-1 list-disc space-y-1 pl-4">
+                            {step.explanation.remediation.map((hint, i) => (
+                              <li key={i}>{hint}</li>
+                            ))}
+                          </ul>
+                        </div>
+                      )}
+                    </div>
+                  </details>
+                )}
+
                 <div className="grid gap-4 md:grid-cols-2">
                   {/* Content Section */}
                   <section className="space-y-2">
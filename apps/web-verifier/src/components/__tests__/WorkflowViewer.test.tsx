@@ -16,8 +16,12 @@ const baseReport: VerificationReport = {
     checkpoints_total: 3,
     provenance_verified: 2,
     provenance_total: 2,
+    provenance_unvalidated: 0,
     attachments_verified: 2,
     attachments_total: 2,
+    redactions: 0,
+    semantic_digests_recomputed: 0,
+    transcript_messages: 0,
     hash_chain_valid: true,
     signatures_valid: true,
     content_integrity_valid: true
@@ -62,6 +66,32 @@ describe('WorkflowViewer', () => {
     expect(screen.getAllByText('No attachments.')).toHaveLength(1);
   });
 
+  it('renders diagnostics of explained failures', () => {
+    const explainedReport: VerificationReport = {
+      ...baseReport,
+      workflow: {
+        steps: [
+          {
+            ...baseReport.workflow.steps[1],
+            explanation: {
+              summary: 'An attachment was modified or replaced inside the archive.',
+              subject: 'attachments/abc.txt',
+              computed: 'ff00',
+              expected: 'abc',
+              remediation: ['Obtain the original archive.']
+            }
+          }
+        ]
+      }
+    };
+    render(<WorkflowViewer report={explainedReport} />);
+
+    expect(screen.getByText('Diagnostics')).toBeInTheDocument();
+    expect(screen.getByText('attachments/abc.txt')).toBeInTheDocument();
+    expect(screen.getByText('ff00')).toBeInTheDocument();
+    expect(screen.getByText('Obtain the original archive.')).toBeInTheDocument();
+  });
+
   it('renders an empty state when no steps are present', () => {
     const emptyReport: VerificationReport = { ...baseReport, workflow: { steps: [] } };
     render(<WorkflowViewer report={emptyReport} />);
@@ -14,10 +14,21 @@ export interface Car {
   provenance: ProvenanceClaim[];
   checkpoints: string[];
   sgrade: SGrade;
+  run_environment?: RunEnvironment;
   signer_public_key: string;
   signatures: string[];
 }
 
+export interface RunEnvironment {
+  os: string;
+  os_version?: string;
+  arch: string;
+  app_version: string;
+  model_catalog_version: string;
+  ollama_version?: string;
+  machine_fingerprint: string;
+}
+
 export interface RunInfo {
   kind: string; // 'exact' | 'concordant' | 'interactive'
   name: string;
@@ -76,6 +87,10 @@ export interface ProcessCheckpointProof {
   usage_tokens: number;
   prompt_tokens: number;
   completion_tokens: number;
+  // SimHash of the output text, outside the hashed body
+  semantic_digest?: string;
+  // The message of an interactive turn; no body when redacted
+  message?: { role: string; body?: string }; // role: 'human' | 'ai'
 }
 
 export interface PolicyRef {
@@ -84,6 +99,11 @@ export interface PolicyRef {
   estimator: string;
   model_catalog_hash?: string;
   model_catalog_version?: string;
+  limits?: Budgets;
+  // Default and project policies the policy was resolved from
+  layers?: { default_hash: string; project_hash?: string };
+  // Providers and regions steps' models were confined to
+  provider_allowlist?: { providers?: string[]; regions?: string[]; deniedRegions?: string[] };
 }
 
 export interface Budgets {
@@ -100,6 +120,8 @@ export interface ProvenanceClaim {
 export interface SGrade {
   score: number;
   components: SGradeComponents;
+  // Emissions of the run's checkpoints (gCO2e) and the grid region local models were priced against
+  inputs?: { g_co2e: number; grid_region: string };
 }
 
 export interface SGradeComponents {
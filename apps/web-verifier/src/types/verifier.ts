@@ -1,11 +1,21 @@
-export type VerificationStatus = 'verified' | 'failed';
+import type { RunEnvironment } from './car';
 
-export type StepStatus = 'passed' | 'failed' | 'skipped';
+export type VerificationStatus = 'verified' | 'failed' | 'limit_exceeded' | 'unsupported_version';
+
+export type StepStatus = 'passed' | 'failed' | 'skipped' | 'warning';
 
 export interface SignerSummary {
   public_key: string;
 }
 
+export type SignerRole = 'author' | 'reviewer' | 'organization';
+
+export interface SignerCheck {
+  role: SignerRole;
+  public_key: string;
+  valid: boolean;
+}
+
 export interface ModelSummary {
   name: string;
   version: string;
@@ -17,8 +27,14 @@ export interface SummaryMetrics {
   checkpoints_total: number;
   provenance_verified: number;
   provenance_total: number;
+  provenance_unvalidated: number;
   attachments_verified: number;
   attachments_total: number;
+  redactions: number;
+  // Semantic digests recomputed from the outputs the bundle carries
+  semantic_digests_recomputed: number;
+  // Chat messages of interactive checkpoints checked against the chain
+  transcript_messages: number;
   hash_chain_valid: boolean;
   signatures_valid: boolean;
   content_integrity_valid: boolean;
@@ -29,12 +45,100 @@ export interface StepDetail {
   value: string;
 }
 
+export interface CheckpointLocation {
+  index: number;
+  id: string;
+  kind: string;
+  timestamp: string;
+}
+
+// Diagnostics the explain mode attaches to failed steps
+export interface StepExplanation {
+  summary: string;
+  computed?: string;
+  expected?: string;
+  checkpoint?: CheckpointLocation;
+  subject?: string;
+  remediation: string[];
+}
+
 export interface WorkflowStep {
   key: string;
   label: string;
   status: StepStatus;
   details?: StepDetail[];
   error?: string;
+  explanation?: StepExplanation;
+}
+
+export interface VerificationLimits {
+  maxCheckpoints?: number;
+  maxAttachmentBytes?: number;
+  timeBudgetMs?: number;
+  // Run every step even after one fails instead of skipping the rest
+  continueOnError?: boolean;
+}
+
+// Reported by a VerifierSession as each verification stage starts
+export type VerificationStage =
+  | 'receiving'
+  | 'decoding'
+  | 'attachments'
+  | 'hash_chain'
+  | 'signatures'
+  | 'provenance'
+  | 'complete';
+
+export interface VerificationProgress {
+  stage: VerificationStage;
+  completed: number;
+  total?: number;
+}
+
+export interface LimitExceeded {
+  limit: string;
+  message: string;
+}
+
+// The CAR declares a format version this verifier does not know
+export interface UnsupportedFormat {
+  found: number;
+  supported: number[];
+}
+
+// A file the CAR binds by hash without bundling it, checked against a local copy
+export interface ExternalAttachment {
+  uri: string;
+  sha256: string;
+  size: number;
+}
+
+export interface ExternalFileCheck {
+  uri: string;
+  verified: boolean;
+  computed_sha256: string;
+  size: number;
+  error?: string;
+}
+
+// One checkpoint of the run, listed in detail mode
+export interface CheckpointRecord {
+  index: number;
+  id: string;
+  kind: string;
+  timestamp: string;
+  parent_checkpoint_id?: string;
+  turn_index?: number;
+  inputs_sha256?: string;
+  outputs_sha256?: string;
+  prev_chain: string;
+  curr_chain: string;
+  chain_valid: boolean;
+  signature_valid: boolean;
+  usage_tokens: number;
+  prompt_tokens: number;
+  completion_tokens: number;
+  cache_hit: boolean;
 }
 
 export interface WasmVerificationReport {
@@ -42,11 +146,18 @@ export interface WasmVerificationReport {
   car_id: string;
   run_id: string;
   created_at: string;
+  format_version?: number;
   signer?: SignerSummary;
+  signers?: SignerCheck[];
   model: ModelSummary;
+  run_environment?: RunEnvironment;
+  external_attachments?: ExternalAttachment[];
   steps: WorkflowStep[];
   summary: SummaryMetrics;
   error?: string;
+  limit_exceeded?: LimitExceeded;
+  unsupported_format?: UnsupportedFormat;
+  checkpoints?: CheckpointRecord[];
 }
 
 export interface VerificationWorkflow {
@@ -5,6 +5,7 @@ import {
   getProofFileKind,
   normalizeFileName,
   proofFileValidator,
+  splitProofDirectory,
   validateProofFileName
 } from './proofFiles';
 
@@ -16,6 +17,7 @@ describe('proof file utilities', () => {
   it('extracts known extensions', () => {
     expect(getProofFileExtension('report.car.json')).toBe('.car.json');
     expect(getProofFileExtension('report.car.zip')).toBe('.car.zip');
+    expect(getProofFileExtension('report.car.cbor')).toBe('.car.cbor');
   });
 
   it('returns null for unsupported extensions', () => {
@@ -25,6 +27,7 @@ describe('proof file utilities', () => {
   it('detects the proof file kind', () => {
     expect(getProofFileKind('report.car.json')).toBe('json');
     expect(getProofFileKind('archive.car.zip')).toBe('car');
+    expect(getProofFileKind('report.car.cbor')).toBe('car');
   });
 
   it('validates supported file names', () => {
@@ -53,4 +56,15 @@ describe('proof file utilities', () => {
     expect(rejection).not.toBeNull();
     expect(rejection?.code).toBe('file-invalid-type');
   });
+
+  it('splits a picked folder into the CAR and its attachments', () => {
+    const file = (path: string) => ({ name: path.split('/').pop() ?? path, webkitRelativePath: path });
+    const { car, attachments } = splitProofDirectory([
+      file('receipt/car.json'),
+      file('receipt/attachments/ab12.txt'),
+      file('receipt/notes/attachments.txt')
+    ]);
+    expect(car?.webkitRelativePath).toBe('receipt/car.json');
+    expect(attachments.map((attachment) => attachment.name)).toEqual(['ab12.txt']);
+  });
 });
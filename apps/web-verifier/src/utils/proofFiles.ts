@@ -1,12 +1,12 @@
 import type { FileError } from 'react-dropzone';
 
-export const ACCEPTED_PROOF_EXTENSIONS = ['.car.json', '.car.zip'] as const;
+export const ACCEPTED_PROOF_EXTENSIONS = ['.car.json', '.car.cbor', '.car.zip'] as const;
 
 export type ProofFileExtension = (typeof ACCEPTED_PROOF_EXTENSIONS)[number];
 export type ProofFileKind = 'json' | 'car';
 
 export const PROOF_FILE_ACCEPT_MESSAGE =
-  'Upload a .car.json transcript or a .car.zip archive exported from Intelexta.';
+  'Upload a .car.json or .car.cbor transcript or a .car.zip archive exported from Intelexta.';
 
 export function normalizeFileName(fileName: string): string {
   return fileName.trim().toLowerCase();
@@ -64,9 +64,10 @@ export function validateProofFileName(fileName: string):
 export function buildProofDropzoneAccept(): Record<string, string[]> {
   return {
     'application/json': ['.car.json', '.json'],
+    'application/cbor': ['.car.cbor'],
     'application/zip': ['.car.zip'],
     'application/x-zip-compressed': ['.car.zip'],
-    'application/octet-stream': ['.car.zip']
+    'application/octet-stream': ['.car.zip', '.car.cbor']
   };
 }
 
@@ -91,3 +92,17 @@ export function proofFileValidator(file: FileLike): ProofFileValidationError | n
   }
   return null;
 }
+
+export type DirectoryFile = { name: string; webkitRelativePath: string };
+
+// Split the files of a picked folder into its CAR transcript and the files
+// inside its attachments/ directory
+export function splitProofDirectory<T extends DirectoryFile>(
+  files: T[]
+): { car: T | null; attachments: T[] } {
+  const car = files.find((file) => getProofFileExtension(file.name) === '.car.json') ?? null;
+  const attachments = files.filter((file) =>
+    file.webkitRelativePath.split('/').slice(0, -1).includes('attachments')
+  );
+  return { car, attachments };
+}
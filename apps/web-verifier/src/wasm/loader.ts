@@ -1,9 +1,19 @@
 import type {
+  CheckpointLocation,
+  CheckpointRecord,
+  ExternalAttachment,
+  ExternalFileCheck,
+  LimitExceeded,
   ModelSummary,
+  SignerCheck,
   SignerSummary,
   StepDetail,
+  StepExplanation,
   StepStatus,
   SummaryMetrics,
+  UnsupportedFormat,
+  VerificationLimits,
+  VerificationProgress,
   VerificationReport,
   VerificationStatus,
   VerificationWorkflow,
@@ -17,8 +27,36 @@ type VerifierModule = {
   init_verifier?: () => Promise<void>;
   verify_car_bytes?: (bytes: Uint8Array) => Promise<unknown>;
   verify_car_json?: (json: string) => Promise<unknown>;
+  verify_car_bytes_with_limits?: (bytes: Uint8Array, limits?: VerificationLimits) => Promise<unknown>;
+  verify_car_bytes_async?: (bytes: Uint8Array, limits?: VerificationLimits) => Promise<unknown>;
+  verify_car_with_attachments?: (
+    json: string,
+    attachments: { name: string; bytes: Uint8Array }[],
+    limits?: VerificationLimits
+  ) => unknown;
+  explain_car_bytes_async?: (bytes: Uint8Array, limits?: VerificationLimits) => Promise<unknown>;
+  inspect_car_bytes_async?: (bytes: Uint8Array, limits?: VerificationLimits) => Promise<unknown>;
+  initThreadPool?: (threads: number) => Promise<unknown>;
+  ExternalFileHasher?: new () => {
+    update: (chunk: Uint8Array) => void;
+    finish: (expected: ExternalAttachment) => unknown;
+  };
+  VerifierSession?: new (
+    limits: VerificationLimits | undefined,
+    explain: boolean,
+    onProgress?: (progress: VerificationProgress) => void
+  ) => {
+    detail?: boolean;
+    push_chunk: (chunk: Uint8Array) => void;
+    finish: () => Promise<unknown>;
+  };
 };
 
+// Local copies of external attachments are read in slices of this size
+const EXTERNAL_FILE_SLICE = 8 * 1024 * 1024;
+// CAR files verified through a session are pushed in slices of this size
+const CAR_FILE_SLICE = 8 * 1024 * 1024;
+
 let modulePromise: Promise<VerifierModule> | null = null;
 
 async function loadModule(): Promise<VerifierModule> {
@@ -26,7 +64,7 @@ async function loadModule(): Promise<VerifierModule> {
     // IMPORTANT: load via URL string, not a static import
     // Add cache buster in development
     const cacheBuster = import.meta.env.DEV ? `?t=${Date.now()}` : '';
-    const jsUrl = new URL(`/pkg/intelexta_wasm_verify.js${cacheBuster}`, window.location.origin).toString();
+    const jsUrl = new URL(`/pkg/intelexta_wasm_verify.js${cacheBuster}`, self.location.origin).toString();
     modulePromise = import(/* @vite-ignore */ jsUrl) as Promise<VerifierModule>;
 
     const mod = await modulePromise;
@@ -34,9 +72,13 @@ async function loadModule(): Promise<VerifierModule> {
     // Let wasm-bindgen locate the .wasm relative to the JS file
     // If your glue expects a URL explicitly, pass the correct filename:
     if (typeof mod.default === 'function') {
-      await mod.default(new URL(`/pkg/intelexta_wasm_verify_bg.wasm${cacheBuster}`, window.location.origin));
+      await mod.default(new URL(`/pkg/intelexta_wasm_verify_bg.wasm${cacheBuster}`, self.location.origin));
       // or simply: await mod.default();  // works for most wasm-pack builds
     }
+    // Builds with the `parallel` feature hash attachments on web workers
+    if (typeof mod.initThreadPool === 'function') {
+      await mod.initThreadPool(navigator.hardwareConcurrency || 4);
+    }
     return mod;
   }
   return modulePromise;
@@ -47,8 +89,12 @@ const defaultSummary: SummaryMetrics = {
   checkpoints_total: 0,
   provenance_verified: 0,
   provenance_total: 0,
+  provenance_unvalidated: 0,
   attachments_verified: 0,
   attachments_total: 0,
+  redactions: 0,
+  semantic_digests_recomputed: 0,
+  transcript_messages: 0,
   hash_chain_valid: false,
   signatures_valid: false,
   content_integrity_valid: false
@@ -69,8 +115,12 @@ function isRecord(value: unknown): value is Record<string, unknown> {
 function coerceStatus(value: unknown): VerificationStatus {
   if (typeof value === 'string') {
     const normalized = value.toLowerCase();
-    if (normalized === 'verified') {
-      return 'verified';
+    if (
+      normalized === 'verified' ||
+      normalized === 'limit_exceeded' ||
+      normalized === 'unsupported_version'
+    ) {
+      return normalized;
     }
   }
   return 'failed';
@@ -79,7 +129,12 @@ function coerceStatus(value: unknown): VerificationStatus {
 function coerceStepStatus(value: unknown): StepStatus {
   if (typeof value === 'string') {
     const normalized = value.toLowerCase();
-    if (normalized === 'passed' || normalized === 'failed' || normalized === 'skipped') {
+    if (
+      normalized === 'passed' ||
+      normalized === 'failed' ||
+      normalized === 'skipped' ||
+      normalized === 'warning'
+    ) {
       return normalized;
     }
   }
@@ -94,6 +149,32 @@ function sanitizeDetail(detail: unknown): StepDetail | null {
   return { label, value };
 }
 
+function sanitizeCheckpointLocation(value: unknown): CheckpointLocation | undefined {
+  if (!isRecord(value)) return undefined;
+  if (typeof value.index !== 'number' || typeof value.id !== 'string') return undefined;
+  return {
+    index: value.index,
+    id: value.id,
+    kind: typeof value.kind === 'string' ? value.kind : '',
+    timestamp: typeof value.timestamp === 'string' ? value.timestamp : ''
+  };
+}
+
+function sanitizeExplanation(value: unknown): StepExplanation | undefined {
+  if (!isRecord(value) || typeof value.summary !== 'string') return undefined;
+  const optionalString = (field: unknown) => (typeof field === 'string' ? field : undefined);
+  return {
+    summary: value.summary,
+    computed: optionalString(value.computed),
+    expected: optionalString(value.expected),
+    checkpoint: sanitizeCheckpointLocation(value.checkpoint),
+    subject: optionalString(value.subject),
+    remediation: Array.isArray(value.remediation)
+      ? value.remediation.filter((hint): hint is string => typeof hint === 'string')
+      : []
+  };
+}
+
 function sanitizeStep(step: unknown, index: number): WorkflowStep {
   if (!isRecord(step)) {
     return {
@@ -113,7 +194,8 @@ function sanitizeStep(step: unknown, index: number): WorkflowStep {
     label: typeof step.label === 'string' ? step.label : `Step ${index + 1}`,
     status: coerceStepStatus(step.status),
     details: details.length ? details : undefined,
-    error: typeof step.error === 'string' ? step.error : undefined
+    error: typeof step.error === 'string' ? step.error : undefined,
+    explanation: sanitizeExplanation(step.explanation)
   };
 }
 
@@ -129,8 +211,12 @@ function sanitizeSummary(summary: unknown): SummaryMetrics {
     checkpoints_total: numberOrZero(summary.checkpoints_total),
     provenance_verified: numberOrZero(summary.provenance_verified),
     provenance_total: numberOrZero(summary.provenance_total),
+    provenance_unvalidated: numberOrZero(summary.provenance_unvalidated),
     attachments_verified: numberOrZero(summary.attachments_verified),
     attachments_total: numberOrZero(summary.attachments_total),
+    redactions: numberOrZero(summary.redactions),
+    semantic_digests_recomputed: numberOrZero(summary.semantic_digests_recomputed),
+    transcript_messages: numberOrZero(summary.transcript_messages),
     hash_chain_valid: Boolean(summary.hash_chain_valid),
     signatures_valid: Boolean(summary.signatures_valid),
     content_integrity_valid: Boolean(summary.content_integrity_valid)
@@ -154,6 +240,57 @@ function sanitizeSigner(signer: unknown): SignerSummary | undefined {
   return { public_key: signer.public_key };
 }
 
+function sanitizeSigners(value: unknown): SignerCheck[] | undefined {
+  if (!Array.isArray(value)) return undefined;
+  const signers = value.filter(
+    (entry): entry is SignerCheck =>
+      isRecord(entry) &&
+      (entry.role === 'author' || entry.role === 'reviewer' || entry.role === 'organization') &&
+      typeof entry.public_key === 'string' &&
+      typeof entry.valid === 'boolean'
+  );
+  return signers.length ? signers : undefined;
+}
+
+function sanitizeLimitExceeded(value: unknown): LimitExceeded | undefined {
+  if (!isRecord(value)) return undefined;
+  if (typeof value.limit !== 'string' || typeof value.message !== 'string') return undefined;
+  return { limit: value.limit, message: value.message };
+}
+
+function sanitizeUnsupportedFormat(value: unknown): UnsupportedFormat | undefined {
+  if (!isRecord(value)) return undefined;
+  if (typeof value.found !== 'number' || !Array.isArray(value.supported)) return undefined;
+  const supported = value.supported.filter((version): version is number => typeof version === 'number');
+  return { found: value.found, supported };
+}
+
+function sanitizeExternalAttachments(value: unknown): ExternalAttachment[] | undefined {
+  if (!Array.isArray(value)) return undefined;
+  const attachments = value.filter(
+    (entry): entry is ExternalAttachment =>
+      isRecord(entry) &&
+      typeof entry.uri === 'string' &&
+      typeof entry.sha256 === 'string' &&
+      typeof entry.size === 'number'
+  );
+  return attachments.length ? attachments : undefined;
+}
+
+function sanitizeCheckpoints(value: unknown): CheckpointRecord[] | undefined {
+  if (!Array.isArray(value)) return undefined;
+  return value.filter(
+    (entry): entry is CheckpointRecord =>
+      isRecord(entry) &&
+      typeof entry.index === 'number' &&
+      typeof entry.id === 'string' &&
+      typeof entry.kind === 'string' &&
+      typeof entry.timestamp === 'string' &&
+      typeof entry.chain_valid === 'boolean' &&
+      typeof entry.signature_valid === 'boolean'
+  );
+}
+
 function fromPartialReport(value: unknown): VerificationReport {
   if (!isRecord(value)) {
     return {
@@ -185,11 +322,17 @@ function fromPartialReport(value: unknown): VerificationReport {
     car_id: typeof partial.car_id === 'string' ? partial.car_id : '',
     run_id: typeof partial.run_id === 'string' ? partial.run_id : '',
     created_at: typeof partial.created_at === 'string' ? partial.created_at : '',
+    format_version: typeof partial.format_version === 'number' ? partial.format_version : undefined,
     signer: sanitizeSigner(partial.signer),
+    signers: sanitizeSigners(partial.signers),
     model: sanitizeModel(partial.model),
+    external_attachments: sanitizeExternalAttachments(partial.external_attachments),
     summary: sanitizeSummary(partial.summary),
     workflow,
-    error: typeof partial.error === 'string' ? partial.error : undefined
+    error: typeof partial.error === 'string' ? partial.error : undefined,
+    limit_exceeded: sanitizeLimitExceeded(partial.limit_exceeded),
+    unsupported_format: sanitizeUnsupportedFormat(partial.unsupported_format),
+    checkpoints: sanitizeCheckpoints(partial.checkpoints)
   };
 }
 
@@ -213,16 +356,122 @@ export async function initVerifier(): Promise<void> {
   }
 }
 
-export async function verifyCarBytes(bytes: Uint8Array): Promise<VerificationReport> {
+export async function verifyCarBytes(bytes: Uint8Array, limits?: VerificationLimits): Promise<VerificationReport> {
   const mod = await loadModule();
+  if (mod.inspect_car_bytes_async) {
+    // Explain mode plus the per-checkpoint records of the run timeline
+    return normalizeResult(await mod.inspect_car_bytes_async(bytes, limits));
+  }
+  if (mod.explain_car_bytes_async) {
+    // Same checks as verify_car_bytes_async, with diagnostics on failed steps
+    return normalizeResult(await mod.explain_car_bytes_async(bytes, limits));
+  }
+  if (mod.verify_car_bytes_async) {
+    // Yields to the event loop while hashing attachments so the UI stays responsive
+    return normalizeResult(await mod.verify_car_bytes_async(bytes, limits));
+  }
+  if (limits) {
+    if (!mod.verify_car_bytes_with_limits) {
+      throw new Error('verify_car_bytes_with_limits is not exported by the WASM bundle');
+    }
+    return normalizeResult(await mod.verify_car_bytes_with_limits(bytes, limits));
+  }
   if (!mod.verify_car_bytes) throw new Error('verify_car_bytes is not exported by the WASM bundle');
   const result = await mod.verify_car_bytes(bytes);
   return normalizeResult(result);
 }
 
+// Verify a CAR file through a VerifierSession, pushing it in slices and
+// reporting each stage. Falls back to verifyCarBytes for bundles built
+// before sessions existed.
+export async function verifyCarFile(
+  file: Blob,
+  limits?: VerificationLimits,
+  onProgress?: (progress: VerificationProgress) => void
+): Promise<VerificationReport> {
+  const mod = await loadModule();
+  if (!mod.VerifierSession) {
+    return verifyCarBytes(new Uint8Array(await file.arrayBuffer()), limits);
+  }
+  const session = new mod.VerifierSession(limits, true, (progress) => {
+    // The session counts bytes received; only the caller knows the file size
+    onProgress?.(progress.stage === 'receiving' ? { ...progress, total: file.size } : progress);
+  });
+  session.detail = true;
+  for (let offset = 0; offset < file.size; offset += CAR_FILE_SLICE) {
+    const slice = file.slice(offset, offset + CAR_FILE_SLICE);
+    session.push_chunk(new Uint8Array(await slice.arrayBuffer()));
+  }
+  return normalizeResult(await session.finish());
+}
+
+type WorkerMessage =
+  | { type: 'progress'; progress: VerificationProgress }
+  | { type: 'report'; report: VerificationReport }
+  | { type: 'error'; message: string };
+
+// Run verifyCarFile on a web worker so large CARs never block the page
+export function verifyCarFileInWorker(
+  file: Blob,
+  limits?: VerificationLimits,
+  onProgress?: (progress: VerificationProgress) => void
+): Promise<VerificationReport> {
+  if (typeof Worker === 'undefined') return verifyCarFile(file, limits, onProgress);
+  const worker = new Worker(new URL('./verifier.worker.ts', import.meta.url), { type: 'module' });
+  return new Promise<VerificationReport>((resolve, reject) => {
+    worker.onmessage = (event: MessageEvent<WorkerMessage>) => {
+      const message = event.data;
+      if (message.type === 'progress') onProgress?.(message.progress);
+      else if (message.type === 'report') resolve(message.report);
+      else reject(new Error(message.message));
+    };
+    worker.onerror = (event) => reject(new Error(event.message || 'Verifier worker failed'));
+    worker.postMessage({ file, limits });
+  }).finally(() => worker.terminate());
+}
+
+// Verify a bare car.json against the files of its attachments/ directory
+export async function verifyCarWithAttachments(
+  json: string,
+  files: File[],
+  limits?: VerificationLimits
+): Promise<VerificationReport> {
+  const mod = await loadModule();
+  if (!mod.verify_car_with_attachments) {
+    throw new Error('verify_car_with_attachments is not exported by the WASM bundle');
+  }
+  const attachments = await Promise.all(
+    files.map(async (file) => ({
+      name: file.webkitRelativePath || file.name,
+      bytes: new Uint8Array(await file.arrayBuffer())
+    }))
+  );
+  return normalizeResult(mod.verify_car_with_attachments(json, attachments, limits));
+}
+
 export async function verifyCarJson(json: string): Promise<VerificationReport> {
   const mod = await loadModule();
   if (!mod.verify_car_json) throw new Error('verify_car_json is not exported by the WASM bundle');
   const result = await mod.verify_car_json(json);
   return normalizeResult(result);
 }
+
+// Hash a local copy of an external attachment in slices, so corpora of
+// several gigabytes never sit in memory at once, and compare it with the CAR
+export async function checkExternalFile(
+  file: File,
+  expected: ExternalAttachment
+): Promise<ExternalFileCheck> {
+  const mod = await loadModule();
+  if (!mod.ExternalFileHasher) throw new Error('ExternalFileHasher is not exported by the WASM bundle');
+  const hasher = new mod.ExternalFileHasher();
+  for (let offset = 0; offset < file.size; offset += EXTERNAL_FILE_SLICE) {
+    const slice = file.slice(offset, offset + EXTERNAL_FILE_SLICE);
+    hasher.update(new Uint8Array(await slice.arrayBuffer()));
+  }
+  const check = hasher.finish(expected);
+  if (!isRecord(check) || typeof check.verified !== 'boolean') {
+    throw new Error('Unexpected external file check from the WASM bundle');
+  }
+  return check as unknown as ExternalFileCheck;
+}
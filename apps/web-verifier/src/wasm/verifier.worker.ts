@@ -0,0 +1,17 @@
+// Verifies a CAR off the main thread; see verifyCarFileInWorker in loader.ts
+import type { VerificationLimits } from '../types/verifier';
+import { verifyCarFile } from './loader';
+
+type VerifyRequest = { file: Blob; limits?: VerificationLimits };
+
+self.onmessage = async (event: MessageEvent<VerifyRequest>) => {
+  const { file, limits } = event.data;
+  try {
+    const report = await verifyCarFile(file, limits, (progress) => {
+      self.postMessage({ type: 'progress', progress });
+    });
+    self.postMessage({ type: 'report', report });
+  } catch (err) {
+    self.postMessage({ type: 'error', message: err instanceof Error ? err.message : 'Unknown error' });
+  }
+};
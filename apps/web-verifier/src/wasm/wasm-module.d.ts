@@ -5,4 +5,29 @@ declare module '/pkg/*.js' {
   export function init_verifier(): void;
   export function verify_car_bytes(bytes: Uint8Array): Promise<any>;
   export function verify_car_json(json: string): Promise<any>;
+  export function verify_car_bytes_with_limits(bytes: Uint8Array, limits?: unknown): Promise<any>;
+  export function verify_car_bytes_async(bytes: Uint8Array, limits?: unknown): Promise<any>;
+  export function explain_car_bytes_async(bytes: Uint8Array, limits?: unknown): Promise<any>;
+  export function inspect_car_bytes_async(bytes: Uint8Array, limits?: unknown): Promise<any>;
+  export function verify_car_with_attachments(json: string, attachments: unknown[], limits?: unknown): any;
+  export function initThreadPool(threads: number): Promise<any>;
+  export class ExternalFileHasher {
+    constructor();
+    update(chunk: Uint8Array): void;
+    finish(expected: unknown): any;
+  }
+  export class VerifierSession {
+    constructor(limits: unknown, explain: boolean, onProgress?: (progress: any) => void);
+    detail: boolean;
+    push_chunk(chunk: Uint8Array): void;
+    finish(): Promise<any>;
+  }
+  export class TrustStore {
+    constructor();
+    static from_json(json: string): TrustStore;
+    pin(project: string, publicKey: string): void;
+    unpin(publicKey: string): void;
+    to_json(): string;
+    check(publicKey: string): any;
+  }
 }
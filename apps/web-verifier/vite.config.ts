@@ -7,6 +7,10 @@ export default defineConfig({
     outDir: 'dist',
     sourcemap: true
   },
+  // The verifier worker loads the wasm glue with a dynamic import
+  worker: {
+    format: 'es'
+  },
   test: {
     environment: 'jsdom',
     globals: true,
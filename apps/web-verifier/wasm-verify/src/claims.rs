@@ -0,0 +1,467 @@
+//! Provenance claim types known to the web verifier
+//!
+//! Mirrors the claim registry in the desktop app's `car` module: registered
+//! claim types are validated, claims of any other type are reported as
+//! unvalidated extensions rather than skipped.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::model::{Car, ProvenanceClaim};
+
+/// Checks a claim against the CAR that carries it; `Err` explains the mismatch
+pub type ClaimValidator = fn(&ProvenanceClaim, &Car) -> Result<(), String>;
+
+#[derive(Clone)]
+pub struct ClaimType {
+    pub name: String,
+    /// Schema the claim's `data` must satisfy (`type`, `required`,
+    /// `properties` and `items` are checked); `None` for digest-only claims
+    pub schema: Option<Value>,
+    pub validate: ClaimValidator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimStatus {
+    Verified,
+    Failed(String),
+    UnvalidatedExtension,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClaimCheck {
+    pub index: usize,
+    pub claim_type: String,
+    pub status: ClaimStatus,
+}
+
+pub struct ClaimRegistry {
+    types: BTreeMap<String, ClaimType>,
+}
+
+impl Default for ClaimRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ClaimRegistry {
+    pub fn builtin() -> Self {
+        let mut registry = Self {
+            types: BTreeMap::new(),
+        };
+        registry.register(ClaimType {
+            name: "config".to_string(),
+            schema: None,
+            validate: validate_config_claim,
+        });
+        for name in ["input", "output"] {
+            registry.register(ClaimType {
+                name: name.to_string(),
+                schema: None,
+                validate: validate_checkpoint_digest_claim,
+            });
+        }
+        registry.register(ClaimType {
+            name: "output_schema".to_string(),
+            schema: None,
+            validate: validate_output_schema_claim,
+        });
+        registry.register(ClaimType {
+            name: "model_weights".to_string(),
+            schema: Some(json!({
+                "type": "object",
+                "required": ["model", "checkpointId"],
+                "properties": {
+                    "model": {"type": "string"},
+                    "checkpointId": {"type": "string"},
+                    "trainingCarId": {"type": "string"}
+                }
+            })),
+            validate: validate_model_weights_claim,
+        });
+        registry
+    }
+
+    pub fn register(&mut self, claim_type: ClaimType) {
+        self.types.insert(claim_type.name.clone(), claim_type);
+    }
+
+    pub fn check_claim(&self, claim: &ProvenanceClaim, car: &Car) -> ClaimStatus {
+        let Some(digest) = claim.sha256.strip_prefix("sha256:") else {
+            return ClaimStatus::Failed("hash must start with 'sha256:'".to_string());
+        };
+        if digest.len() != 64 || !digest.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return ClaimStatus::Failed("hash is not a hex sha256 digest".to_string());
+        }
+        let Some(claim_type) = self.types.get(&claim.claim_type) else {
+            return ClaimStatus::UnvalidatedExtension;
+        };
+
+        if let Some(schema) = &claim_type.schema {
+            let Some(data) = &claim.data else {
+                return ClaimStatus::Failed("claim data is missing".to_string());
+            };
+            if let Err(reason) = check_data(schema, data, "") {
+                return ClaimStatus::Failed(format!("claim data violates schema: {reason}"));
+            }
+        }
+
+        match (claim_type.validate)(claim, car) {
+            Ok(()) => ClaimStatus::Verified,
+            Err(reason) => ClaimStatus::Failed(reason),
+        }
+    }
+
+    pub fn verify(&self, car: &Car) -> Vec<ClaimCheck> {
+        car.provenance
+            .iter()
+            .enumerate()
+            .map(|(index, claim)| ClaimCheck {
+                index,
+                claim_type: claim.claim_type.clone(),
+                status: self.check_claim(claim, car),
+            })
+            .collect()
+    }
+}
+
+/// Distinct claim types no validator was registered for
+pub fn unvalidated_types(checks: &[ClaimCheck]) -> Vec<String> {
+    let types: BTreeSet<&str> = checks
+        .iter()
+        .filter(|check| check.status == ClaimStatus::UnvalidatedExtension)
+        .map(|check| check.claim_type.as_str())
+        .collect();
+    types.into_iter().map(str::to_string).collect()
+}
+
+/// Step config fields that carry prompt text
+const REDACTABLE_CONFIG_FIELDS: [&str; 3] = ["prompt", "promptTemplate", "customInstructions"];
+
+fn is_redaction_marker(value: &str) -> bool {
+    value
+        .strip_prefix("redacted:sha256:")
+        .is_some_and(|digest| digest.len() == 64 && digest.chars().all(|ch| ch.is_ascii_hexdigit()))
+}
+
+/// Every redacted field must carry the marker of a listed redaction and every
+/// listed step redaction must be in place; returns the number of redactions
+pub fn check_redactions(car: &Car) -> Result<usize, String> {
+    for redaction in &car.redactions {
+        if !is_redaction_marker(&redaction.marker()) {
+            return Err(format!(
+                "redaction of {} has no sha256 commitment",
+                redaction.target
+            ));
+        }
+    }
+
+    let mut in_place = 0;
+    for step in &car.run.steps {
+        let mut fields = Vec::new();
+        if let Some(prompt) = &step.prompt {
+            fields.push((format!("steps/{}/prompt", step.id), prompt.clone()));
+        }
+        let config = step
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Value>(json).ok());
+        if let Some(config) = config {
+            for field in REDACTABLE_CONFIG_FIELDS {
+                if let Some(text) = config[field].as_str() {
+                    fields.push((format!("steps/{}/config/{field}", step.id), text.to_string()));
+                }
+            }
+        }
+
+        for (target, value) in fields {
+            if !is_redaction_marker(&value) {
+                continue;
+            }
+            let listed = car
+                .redactions
+                .iter()
+                .any(|redaction| redaction.target == target && redaction.marker() == value);
+            if !listed {
+                return Err(format!("{target} is redacted without a listed commitment"));
+            }
+            in_place += 1;
+        }
+    }
+    let listed_in_steps = car
+        .redactions
+        .iter()
+        .filter(|redaction| redaction.target.starts_with("steps/"))
+        .count();
+    if listed_in_steps != in_place {
+        return Err(format!(
+            "{listed_in_steps} step redactions are listed but {in_place} fields are redacted"
+        ));
+    }
+    Ok(car.redactions.len())
+}
+
+fn json_type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn check_data(schema: &Value, data: &Value, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        if !json_type_matches(expected, data) {
+            return Err(format!("{at}: expected {expected}"));
+        }
+    }
+    if let (Some(required), Some(object)) = (
+        schema.get("required").and_then(Value::as_array),
+        data.as_object(),
+    ) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                return Err(format!("{at}: missing required property '{name}'"));
+            }
+        }
+    }
+    if let (Some(properties), Some(object)) = (
+        schema.get("properties").and_then(Value::as_object),
+        data.as_object(),
+    ) {
+        for (name, sub_schema) in properties {
+            if let Some(value) = object.get(name) {
+                check_data(sub_schema, value, &format!("{path}/{name}"))?;
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), data.as_array()) {
+        for (index, value) in array.iter().enumerate() {
+            check_data(items, value, &format!("{path}/{index}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn claim_digest(claim: &ProvenanceClaim) -> &str {
+    claim
+        .sha256
+        .strip_prefix("sha256:")
+        .unwrap_or(&claim.sha256)
+}
+
+fn canonical_sha256(value: &Value) -> Result<String, String> {
+    let canonical = intelexta_canon::to_vec(value).map_err(|err| err.to_string())?;
+    Ok(hex::encode(Sha256::digest(&canonical)))
+}
+
+fn validate_config_claim(claim: &ProvenanceClaim, car: &Car) -> Result<(), String> {
+    let spec_json = serde_json::to_value(&car.run.steps).map_err(|err| err.to_string())?;
+    let computed = canonical_sha256(&spec_json)?;
+    if computed == claim_digest(claim) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Config hash mismatch\nExpected: {}\nComputed: {}",
+            claim_digest(claim),
+            computed
+        ))
+    }
+}
+
+fn validate_checkpoint_digest_claim(claim: &ProvenanceClaim, car: &Car) -> Result<(), String> {
+    let digest = Some(claim_digest(claim));
+    let found = car.proof.process.as_ref().is_some_and(|process| {
+        process.sequential_checkpoints.iter().any(|checkpoint| {
+            checkpoint.inputs_sha256.as_deref() == digest
+                || checkpoint.outputs_sha256.as_deref() == digest
+        })
+    });
+    if found {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} hash not found in checkpoints",
+            claim.claim_type
+        ))
+    }
+}
+
+fn validate_output_schema_claim(claim: &ProvenanceClaim, car: &Car) -> Result<(), String> {
+    let found = car.run.steps.iter().any(|step| {
+        step.config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Value>(json).ok())
+            .and_then(|config| config.get("outputSchema").map(canonical_sha256))
+            .and_then(Result::ok)
+            .is_some_and(|digest| digest == claim_digest(claim))
+    });
+    if found {
+        Ok(())
+    } else {
+        Err("output schema hash does not match any step's schema".to_string())
+    }
+}
+
+fn validate_model_weights_claim(claim: &ProvenanceClaim, car: &Car) -> Result<(), String> {
+    let data = claim.data.as_ref().ok_or("claim data is missing")?;
+    let model = data
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let checkpoint_id = data
+        .get("checkpointId")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    let has_checkpoint = car.proof.process.as_ref().is_some_and(|process| {
+        process
+            .sequential_checkpoints
+            .iter()
+            .any(|checkpoint| checkpoint.id == checkpoint_id && checkpoint.kind == "Step")
+    });
+    if !has_checkpoint {
+        return Err(format!(
+            "checkpoint {checkpoint_id} not found in process proof"
+        ));
+    }
+    if !car
+        .run
+        .steps
+        .iter()
+        .any(|step| step.model.as_deref() == Some(model))
+    {
+        return Err(format!("no step of the run uses model {model}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_JSON: &[u8] = include_bytes!("../tests/fixtures/sample.car.json");
+
+    fn sample_car() -> Car {
+        serde_json::from_slice(SAMPLE_JSON).expect("sample CAR parses")
+    }
+
+    fn claim(claim_type: &str, data: Option<Value>) -> ProvenanceClaim {
+        ProvenanceClaim {
+            claim_type: claim_type.to_string(),
+            sha256: format!("sha256:{}", "ab".repeat(32)),
+            data,
+        }
+    }
+
+    #[test]
+    fn unknown_claim_types_are_unvalidated_extensions() {
+        let mut car = sample_car();
+        car.provenance = vec![claim("dataset", None), claim("dataset", None)];
+        let checks = ClaimRegistry::builtin().verify(&car);
+        assert!(checks
+            .iter()
+            .all(|check| check.status == ClaimStatus::UnvalidatedExtension));
+        assert_eq!(unvalidated_types(&checks), vec!["dataset".to_string()]);
+    }
+
+    #[test]
+    fn registered_extensions_are_validated() {
+        let car = sample_car();
+        let mut registry = ClaimRegistry::builtin();
+        registry.register(ClaimType {
+            name: "dataset".to_string(),
+            schema: Some(json!({
+                "type": "object",
+                "required": ["name", "rows"],
+                "properties": {"rows": {"type": "integer"}}
+            })),
+            validate: |_, _| Ok(()),
+        });
+
+        let valid = claim("dataset", Some(json!({"name": "squad", "rows": 10})));
+        assert_eq!(registry.check_claim(&valid, &car), ClaimStatus::Verified);
+        let wrong_type = claim("dataset", Some(json!({"name": "squad", "rows": "ten"})));
+        assert!(matches!(
+            registry.check_claim(&wrong_type, &car),
+            ClaimStatus::Failed(reason) if reason.contains("/rows")
+        ));
+        assert!(matches!(
+            registry.check_claim(&claim("dataset", None), &car),
+            ClaimStatus::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn config_claims_must_match_the_run_steps() {
+        let car = sample_car();
+        let spec = serde_json::to_value(&car.run.steps).unwrap();
+        let config = ProvenanceClaim {
+            claim_type: "config".to_string(),
+            sha256: format!("sha256:{}", canonical_sha256(&spec).unwrap()),
+            data: None,
+        };
+        let registry = ClaimRegistry::builtin();
+        assert_eq!(registry.check_claim(&config, &car), ClaimStatus::Verified);
+        assert!(matches!(
+            registry.check_claim(&claim("config", None), &car),
+            ClaimStatus::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn model_weights_claims_reference_a_step_checkpoint_on_the_model() {
+        let car = sample_car();
+        let registry = ClaimRegistry::builtin();
+        let weights = |model: &str, checkpoint: &str| {
+            claim(
+                "model_weights",
+                Some(
+                    json!({"model": model, "checkpointId": checkpoint, "trainingCarId": "car:train"}),
+                ),
+            )
+        };
+
+        assert_eq!(
+            registry.check_claim(&weights("demo-model", "ckpt-1"), &car),
+            ClaimStatus::Verified
+        );
+        assert!(matches!(
+            registry.check_claim(&weights("demo-model", "ckpt-missing"), &car),
+            ClaimStatus::Failed(_)
+        ));
+        assert!(matches!(
+            registry.check_claim(&weights("other-model", "ckpt-1"), &car),
+            ClaimStatus::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn redacted_fields_need_a_listed_commitment() {
+        let mut car = sample_car();
+        assert_eq!(check_redactions(&car), Ok(0));
+
+        let redaction = crate::model::Redaction {
+            target: format!("steps/{}/prompt", car.run.steps[0].id),
+            commitment: format!("sha256:{}", "cd".repeat(32)),
+        };
+        car.run.steps[0].prompt = Some(redaction.marker());
+        assert!(check_redactions(&car).unwrap_err().contains("without a listed commitment"));
+
+        car.redactions.push(redaction);
+        car.redactions.push(crate::model::Redaction {
+            target: format!("attachments/{}", "ef".repeat(32)),
+            commitment: format!("sha256:{}", "01".repeat(32)),
+        });
+        assert_eq!(check_redactions(&car), Ok(2));
+    }
+}
@@ -0,0 +1,246 @@
+//! Explanations for failed verification steps
+//!
+//! In explain mode every failed step carries a `StepExplanation`: what was
+//! computed against what the CAR records, where the check broke and what the
+//! holder of the receipt can do about it. Checks that fail on a specific value
+//! return a `Mismatch`, whose message is the step's usual terse error, so the
+//! explanation can be built without parsing that message.
+
+use serde::Serialize;
+
+use crate::model::{Car, ProcessCheckpointProof};
+
+/// Desktop release whose export format this verifier follows; receipts
+/// exported from it or later carry everything checked here
+pub const EXPORTER_VERSION: &str = "0.1.0";
+
+/// A check that failed on a specific value
+#[derive(Debug, Clone, Default)]
+pub struct Mismatch {
+    pub message: String,
+    /// Position of the checkpoint in `sequential_checkpoints`
+    pub checkpoint: Option<usize>,
+    /// Attachment file or provenance claim the check was run on
+    pub subject: Option<String>,
+    pub computed: Option<String>,
+    pub expected: Option<String>,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+impl From<intelexta_verify_core::Error> for Mismatch {
+    fn from(err: intelexta_verify_core::Error) -> Self {
+        use intelexta_verify_core::Error;
+
+        let message = err.to_string();
+        match err {
+            Error::ChainBroken {
+                index,
+                expected,
+                found,
+                ..
+            } => Mismatch {
+                message,
+                checkpoint: Some(index),
+                computed: Some(expected),
+                expected: Some(found),
+                ..Mismatch::default()
+            },
+            Error::MalformedSignature { index } | Error::SignatureMismatch { index } => Mismatch {
+                message,
+                checkpoint: index,
+                ..Mismatch::default()
+            },
+            Error::AttachmentMismatch { expected, computed } => Mismatch {
+                message,
+                computed: Some(computed),
+                expected: Some(expected),
+                ..Mismatch::default()
+            },
+            Error::InvalidKey(_) | Error::Canonicalization(_) => Mismatch {
+                message,
+                ..Mismatch::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepExplanation {
+    /// What went wrong, in one or two sentences
+    pub summary: String,
+    /// Value the verifier computed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed: Option<String>,
+    /// Value the CAR records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Checkpoint the check broke at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<CheckpointLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    pub remediation: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointLocation {
+    pub index: usize,
+    pub id: String,
+    pub kind: String,
+    pub timestamp: String,
+}
+
+impl StepExplanation {
+    fn new(summary: impl Into<String>, remediation: &[&str]) -> Self {
+        Self {
+            summary: summary.into(),
+            computed: None,
+            expected: None,
+            checkpoint: None,
+            subject: None,
+            remediation: remediation.iter().map(|hint| hint.to_string()).collect(),
+        }
+    }
+
+    /// Take the computed and expected values and the location of a mismatch
+    fn with_mismatch(
+        mut self,
+        err: &anyhow::Error,
+        checkpoints: &[ProcessCheckpointProof],
+    ) -> Self {
+        if let Some(mismatch) = err.downcast_ref::<Mismatch>() {
+            self.computed = mismatch.computed.clone();
+            self.expected = mismatch.expected.clone();
+            self.subject = mismatch.subject.clone();
+            self.checkpoint = mismatch
+                .checkpoint
+                .and_then(|index| locate(checkpoints, index));
+        }
+        self
+    }
+}
+
+fn locate(checkpoints: &[ProcessCheckpointProof], index: usize) -> Option<CheckpointLocation> {
+    checkpoints.get(index).map(|checkpoint| CheckpointLocation {
+        index,
+        id: checkpoint.id.clone(),
+        kind: checkpoint.kind.clone(),
+        timestamp: checkpoint.timestamp.clone(),
+    })
+}
+
+fn reexport_hint() -> String {
+    format!("Re-export the CAR from Intelexta {EXPORTER_VERSION} or later.")
+}
+
+/// The CAR has no process proof, or one without checkpoints
+pub fn missing_process(car: &Car) -> StepExplanation {
+    if car.proof.process.is_some() {
+        return StepExplanation::new(
+            "The process proof lists no checkpoints, so there is no hash chain to check.",
+            &["Export the CAR from an execution that recorded at least one checkpoint."],
+        );
+    }
+    let mut explanation = StepExplanation::new(
+        format!(
+            "The CAR has no process proof (match kind '{}'); it was exported before receipts carried signed checkpoints.",
+            car.proof.match_kind
+        ),
+        &[],
+    );
+    explanation.remediation.push(reexport_hint());
+    explanation
+}
+
+pub fn hash_chain(err: &anyhow::Error, checkpoints: &[ProcessCheckpointProof]) -> StepExplanation {
+    let mut explanation = StepExplanation::new(
+        "A checkpoint's recorded chain hash does not match the hash of its contents and the previous link, so the checkpoint was changed after it was signed.",
+        &[
+            "If the CAR was not edited, update the verifier: the checkpoint may carry fields this version does not hash yet.",
+            "Otherwise the receipt was altered after export; obtain the original CAR or export it again from the desktop app.",
+        ],
+    )
+    .with_mismatch(err, checkpoints);
+
+    // A link that does not point at its predecessor means checkpoints were
+    // dropped or reordered rather than edited
+    if let Some(location) = &explanation.checkpoint {
+        let relinked = location.index > 0
+            && checkpoints[location.index].prev_chain != checkpoints[location.index - 1].curr_chain;
+        if relinked {
+            explanation.summary = format!(
+                "Checkpoint #{} does not link to checkpoint #{}: checkpoints were removed or reordered.",
+                location.index,
+                location.index - 1
+            );
+        }
+    }
+    explanation
+}
+
+pub fn body_signature(err: &anyhow::Error) -> StepExplanation {
+    if err.downcast_ref::<Mismatch>().is_none() {
+        let mut explanation = StepExplanation::new(
+            format!("The CAR body signature could not be checked: {err}."),
+            &[],
+        );
+        explanation.remediation.push(reexport_hint());
+        return explanation;
+    }
+    StepExplanation::new(
+        "The signature over the CAR body does not verify: a field outside `signatures` changed after signing. Reformatting the JSON does not affect it, changing any value does.",
+        &[
+            "Obtain the original CAR or export it again from the desktop app.",
+            "Do not edit car.json by hand; annotations belong next to the receipt, not in it.",
+        ],
+    )
+}
+
+pub fn checkpoint_signatures(
+    err: &anyhow::Error,
+    checkpoints: &[ProcessCheckpointProof],
+    signer_public_key: &str,
+) -> StepExplanation {
+    let mut explanation = StepExplanation::new(
+        format!("A checkpoint signature does not verify against the signer key {signer_public_key}."),
+        &[
+            "Check that the CAR was exported by the project that ran it; checkpoints signed with another project's key do not verify under this one.",
+            "If the project's signing key was regenerated, export the CAR again so it names the key the checkpoints were signed with.",
+        ],
+    )
+    .with_mismatch(err, checkpoints);
+    if explanation.checkpoint.is_none() {
+        explanation.summary = format!("The signatures could not be checked: {err}.");
+    }
+    explanation
+}
+
+pub fn provenance(err: &anyhow::Error) -> StepExplanation {
+    let mut explanation = StepExplanation::new(
+        "A provenance claim no longer matches the run configuration or checkpoint it commits to.",
+        &[],
+    )
+    .with_mismatch(err, &[]);
+    // Claims are derived from the run at export time, so older exports may
+    // follow a claim layout this verifier no longer accepts
+    explanation.remediation.push(reexport_hint());
+    explanation
+}
+
+pub fn attachments(err: &anyhow::Error) -> StepExplanation {
+    StepExplanation::new(
+        "An attachment's contents do not hash to the digest in its file name, so the file was modified or replaced inside the archive.",
+        &[
+            "Obtain the original archive or export the CAR again from the desktop app.",
+            "Repackaging tools that re-encode text (line endings, BOMs) change the hash; copy the archive byte for byte.",
+        ],
+    )
+    .with_mismatch(err, &[])
+}
@@ -1,17 +1,61 @@
-use std::io::{Cursor, Read};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
 
 use anyhow::{anyhow, Context, Result};
-use base64::{engine::general_purpose::STANDARD, Engine as _};
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
-use serde::Serialize;
+use intelexta_archive::{ArchiveError, ArchiveLimits, SafeArchive};
+use intelexta_verify_core::budget::{self, BudgetViolation, CheckpointUsage, Totals};
+use intelexta_verify_core::clock::{self, ClockAnomaly, ClockReading};
+use intelexta_verify_core::merkle;
+use intelexta_verify_core::semantic::{
+    self, SemanticCheckpoint, SemanticIssue, SemanticProof, SemanticStep,
+};
+use intelexta_verify_core::transcript::{self, TranscriptEntry};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
 
 const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
 
+/// Envelope of DAG-CBOR encoded CARs (see `car_cbor` in the app crate)
+const CAR_CBOR_FORMAT: &str = "intelexta-car+dag-cbor";
+const CAR_CBOR_VERSION: u64 = 1;
+const CAR_CBOR_ENTRY: &str = "car.cbor";
+
+/// Attachments hashed between yields to the browser event loop
+const ATTACHMENT_CHUNK: usize = 16;
+
+/// The checks after the CAR is decoded, in the order they run; a check that
+/// stops verification skips the ones after it
+const STEPS: [(&str, &str); 8] = [
+    ("hash_chain", "Hash chain integrity"),
+    ("clock", "Checkpoint clock"),
+    ("policy", "Policy budgets"),
+    ("signatures", "Signature validation"),
+    ("provenance", "Provenance verification"),
+    ("attachments", "Attachment integrity"),
+    ("semantic", "Semantic digests"),
+    ("transcript", "Chat transcript"),
+];
+
+/// Start the rayon worker pool (`await initThreadPool(navigator.hardwareConcurrency)`)
+/// before verifying when built with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+mod claims;
+mod diagnostics;
 mod model;
-use model::{Car, ProcessCheckpointProof};
+mod timeline;
+mod trust;
+use claims::{ClaimRegistry, ClaimStatus};
+use diagnostics::{Mismatch, StepExplanation};
+use model::{
+    Car, Cosignature, ExternalAttachment, FormatSpec, ProcessCheckpointProof, RunEnvironment,
+    SignatureKind, SignerRole, UnsupportedFormatVersion,
+};
+pub use timeline::CheckpointRecord;
+pub use trust::{SignerTrust, TrustStore};
 
 #[wasm_bindgen]
 pub fn verify_car_bytes(bytes: &[u8]) -> Result<JsValue, JsError> {
@@ -27,18 +71,512 @@ pub fn verify_car_json(json: &str) -> Result<JsValue, JsError> {
     serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
 }
 
+/// Verify a CAR (JSON or ZIP) under caller-supplied resource limits.
+/// `limits` may be `undefined`/`null` or an object such as
+/// `{ maxCheckpoints, maxAttachmentBytes, timeBudgetMs }`.
+#[wasm_bindgen]
+pub fn verify_car_bytes_with_limits(bytes: &[u8], limits: JsValue) -> Result<JsValue, JsError> {
+    let budget = Budget::new(parse_limits(limits)?);
+    let decoded = decode_car_with_limits(bytes, &budget).map_err(to_js_error)?;
+    let report = verify_car_with_budget(decoded, &budget, false).map_err(to_js_error)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Verify a bare `car.json` together with a directory of its attachments,
+/// such as the files of a `webkitdirectory` picker. `attachments` is an
+/// array of `{ name, bytes }` with `bytes` a `Uint8Array`; only the file
+/// name part of `name` is used, so paths relative to any folder work. Like a
+/// ZIP bundle, the directory must hold every attachment the manifest lists.
+#[wasm_bindgen]
+pub fn verify_car_with_attachments(
+    json: &str,
+    attachments: js_sys::Array,
+    limits: JsValue,
+) -> Result<JsValue, JsError> {
+    let budget = Budget::new(parse_limits(limits)?);
+    let files = attachment_files(&attachments)?;
+    let decoded =
+        load_car_with_attachments(json.as_bytes(), files, &budget).map_err(to_js_error)?;
+    let report = verify_car_with_budget(decoded, &budget, false).map_err(to_js_error)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn attachment_files(attachments: &js_sys::Array) -> Result<Vec<(String, Vec<u8>)>, JsError> {
+    let mut files = Vec::new();
+    for (index, file) in attachments.iter().enumerate() {
+        let name = js_sys::Reflect::get(&file, &JsValue::from_str("name"))
+            .ok()
+            .and_then(|name| name.as_string())
+            .ok_or_else(|| JsError::new(&format!("Attachment {index} has no name")))?;
+        let bytes = js_sys::Reflect::get(&file, &JsValue::from_str("bytes"))
+            .ok()
+            .and_then(|bytes| bytes.dyn_into::<js_sys::Uint8Array>().ok())
+            .ok_or_else(|| JsError::new(&format!("Attachment {name} has no bytes")))?;
+        files.push((name, bytes.to_vec()));
+    }
+    Ok(files)
+}
+
+/// Async variant of `verify_car_bytes_with_limits` for large receipts.
+/// Attachments are hashed in chunks, yielding to the event loop between
+/// chunks so the page stays responsive while hundreds of files are checked.
+#[wasm_bindgen]
+pub async fn verify_car_bytes_async(bytes: Vec<u8>, limits: JsValue) -> Result<JsValue, JsError> {
+    verify_in_chunks(bytes, limits, false).await
+}
+
+/// Explain mode of `verify_car_bytes_async`: every failed step carries an
+/// `explanation` with the computed and expected values, the checkpoint the
+/// check broke at and remediation hints.
+#[wasm_bindgen]
+pub async fn explain_car_bytes_async(bytes: Vec<u8>, limits: JsValue) -> Result<JsValue, JsError> {
+    verify_in_chunks(bytes, limits, true).await
+}
+
+/// Detail mode of `explain_car_bytes_async`: the report also carries a
+/// `checkpoints` record per checkpoint (id, kind, timestamp, hashes, chain
+/// and signature status, token usage) for rendering a timeline of the run.
+#[wasm_bindgen]
+pub async fn inspect_car_bytes_async(bytes: Vec<u8>, limits: JsValue) -> Result<JsValue, JsError> {
+    let budget = Budget::new(parse_limits(limits)?);
+    let mode = ReportMode {
+        explain: true,
+        detail: true,
+    };
+    let report = verify_staged(&bytes, &budget, mode, &mut |_| {})
+        .await
+        .map_err(to_js_error)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+async fn verify_in_chunks(
+    bytes: Vec<u8>,
+    limits: JsValue,
+    explain: bool,
+) -> Result<JsValue, JsError> {
+    let budget = Budget::new(parse_limits(limits)?);
+    let mode = ReportMode {
+        explain,
+        detail: false,
+    };
+    let report = verify_staged(&bytes, &budget, mode, &mut |_| {})
+        .await
+        .map_err(to_js_error)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// What a report carries beyond the verdict
+#[derive(Debug, Clone, Copy)]
+struct ReportMode {
+    /// Failed steps carry a `StepExplanation`
+    explain: bool,
+    /// The report lists a `CheckpointRecord` per checkpoint
+    detail: bool,
+}
+
+/// Decode and verify a CAR, hashing attachments in chunks between yields to
+/// the event loop and reporting each stage to `progress`
+async fn verify_staged(
+    bytes: &[u8],
+    budget: &Budget,
+    mode: ReportMode,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<VerificationReport> {
+    progress(Progress::started("decoding"));
+    let decoded = decode_car_with_limits(bytes, budget)?;
+    let records = if mode.detail {
+        checkpoint_records(&decoded.car, budget)
+    } else {
+        None
+    };
+
+    let total = decoded.attachments.len() as u64;
+    let mut hashed = 0;
+    let mut attachment_outcome = Ok(0);
+    for chunk in decoded.attachments.chunks(ATTACHMENT_CHUNK) {
+        progress(Progress::of("attachments", hashed, Some(total)));
+        match verify_all_attachments(chunk, budget) {
+            Ok(verified) => {
+                attachment_outcome = attachment_outcome.map(|total| total + verified);
+            }
+            Err(err) => {
+                attachment_outcome = Err(err);
+                break;
+            }
+        }
+        hashed += chunk.len() as u64;
+        yield_to_event_loop().await;
+    }
+
+    let mut report =
+        assemble_report(decoded, budget, attachment_outcome, mode.explain, progress)?;
+    report.checkpoints = records;
+    progress(Progress::of("complete", 1, Some(1)));
+    Ok(report)
+}
+
+/// Records of the CAR's checkpoints, unless the CAR is in an unknown format
+/// or exceeds the checkpoint limit, neither of which verification gets past
+fn checkpoint_records(car: &Car, budget: &Budget) -> Option<Vec<CheckpointRecord>> {
+    model::negotiate_format(car.format_version).ok()?;
+    let checkpoints = &car.proof.process.as_ref()?.sequential_checkpoints;
+    budget.check_checkpoints(checkpoints.len()).ok()?;
+    Some(timeline::checkpoint_records(checkpoints, &car.signer_public_key))
+}
+
+/// Incremental verification for CARs too large to hand over in one piece.
+/// Push the file in slices as it is read, then `finish`; `onProgress`, when
+/// given, is called with a `Progress` as each stage starts, so a web worker
+/// can drive a progress bar while the main thread stays free.
+#[wasm_bindgen]
+pub struct VerifierSession {
+    limits: VerificationLimits,
+    mode: ReportMode,
+    buffer: Vec<u8>,
+    on_progress: Option<js_sys::Function>,
+}
+
+#[wasm_bindgen]
+impl VerifierSession {
+    /// `limits` as for `verify_car_bytes_with_limits`; `explain` attaches
+    /// diagnostics to failed steps as in `explain_car_bytes_async`
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        limits: JsValue,
+        explain: bool,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<VerifierSession, JsError> {
+        Ok(Self::with_limits(parse_limits(limits)?, explain, on_progress))
+    }
+
+    /// Detail mode, as in `inspect_car_bytes_async`: `session.detail = true`
+    #[wasm_bindgen(setter)]
+    pub fn set_detail(&mut self, detail: bool) {
+        self.mode.detail = detail;
+    }
+
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> Result<(), JsError> {
+        self.append(chunk)
+            .map_err(|err| JsError::new(&err.to_string()))?;
+        notify(
+            self.on_progress.as_ref(),
+            Progress::of("receiving", self.buffer.len() as u64, None),
+        );
+        Ok(())
+    }
+
+    /// Verify everything pushed so far and resolve to the report
+    pub async fn finish(self) -> Result<JsValue, JsError> {
+        let Self {
+            limits,
+            mode,
+            buffer,
+            on_progress,
+        } = self;
+        let budget = Budget::new(limits);
+        let mut progress = |progress| notify(on_progress.as_ref(), progress);
+        let report = verify_staged(&buffer, &budget, mode, &mut progress)
+            .await
+            .map_err(to_js_error)?;
+        serde_wasm_bindgen::to_value(&report).map_err(|err| JsError::new(&err.to_string()))
+    }
+}
+
+impl VerifierSession {
+    fn with_limits(
+        limits: VerificationLimits,
+        explain: bool,
+        on_progress: Option<js_sys::Function>,
+    ) -> Self {
+        Self {
+            limits,
+            mode: ReportMode {
+                explain,
+                detail: false,
+            },
+            buffer: Vec::new(),
+            on_progress,
+        }
+    }
+
+    /// Buffer a slice; the time budget only starts in `finish`, so slow
+    /// reads do not count against it
+    fn append(&mut self, chunk: &[u8]) -> Result<(), LimitExceeded> {
+        // No CAR beyond the archive size limit can be read anyway
+        let max = ArchiveLimits::default().max_total_bytes;
+        if (self.buffer.len() + chunk.len()) as u64 > max {
+            return Err(LimitExceeded {
+                limit: "archive",
+                message: format!("CAR exceeds the {max} byte size limit"),
+            });
+        }
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
+}
+
+/// Progress of a `VerifierSession`: the stage (`receiving`, `decoding`,
+/// `attachments`, `hash_chain`, `signatures`, `provenance`, `complete`) and,
+/// where it is counted, how far it got. `receiving` counts bytes pushed;
+/// `attachments` counts files hashed.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub stage: &'static str,
+    pub completed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    fn started(stage: &'static str) -> Self {
+        Self::of(stage, 0, None)
+    }
+
+    fn of(stage: &'static str, completed: u64, total: Option<u64>) -> Self {
+        Self {
+            stage,
+            completed,
+            total,
+        }
+    }
+}
+
+/// Progress callbacks are advisory; a throwing callback does not stop
+/// verification
+fn notify(callback: Option<&js_sys::Function>, progress: Progress) {
+    let Some(callback) = callback else {
+        return;
+    };
+    if let Ok(value) = serde_wasm_bindgen::to_value(&progress) {
+        let _ = callback.call1(&JsValue::NULL, &value);
+    }
+}
+
+/// Streams a local copy of an external attachment through sha256, so files
+/// of several gigabytes are checked without holding them in memory. Feed it
+/// the file in slices, then compare with the CAR's entry.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct ExternalFileHasher {
+    hasher: Sha256,
+    size: u64,
+}
+
+#[wasm_bindgen]
+impl ExternalFileHasher {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+        self.size += chunk.len() as u64;
+    }
+
+    /// Check the streamed file against `expected`, one entry of the report's
+    /// `external_attachments`
+    pub fn finish(self, expected: JsValue) -> Result<JsValue, JsError> {
+        let expected: ExternalAttachment = serde_wasm_bindgen::from_value(expected)
+            .map_err(|err| JsError::new(&format!("Invalid external attachment: {err}")))?;
+        serde_wasm_bindgen::to_value(&self.check(&expected))
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+
+    fn check(self, expected: &ExternalAttachment) -> ExternalFileCheck {
+        let computed_sha256 = hex::encode(self.hasher.finalize());
+        let error = if self.size != expected.size {
+            Some(format!(
+                "File is {} bytes, the CAR records {}",
+                self.size, expected.size
+            ))
+        } else if computed_sha256 != expected.sha256 {
+            Some(format!(
+                "Content mismatch\nExpected sha256: {}\nComputed sha256: {computed_sha256}",
+                expected.sha256
+            ))
+        } else {
+            None
+        };
+        ExternalFileCheck {
+            uri: expected.uri.clone(),
+            verified: error.is_none(),
+            computed_sha256,
+            size: self.size,
+            error,
+        }
+    }
+}
+
+/// Outcome of checking a local copy of an external attachment
+#[derive(Serialize, Debug)]
+pub struct ExternalFileCheck {
+    pub uri: String,
+    pub verified: bool,
+    pub computed_sha256: String,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Resolve on the next macrotask so the browser can render and handle input
+async fn yield_to_event_loop() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|value| value.dyn_into::<js_sys::Function>().ok());
+        let scheduled = set_timeout
+            .map(|set_timeout| set_timeout.call2(&global, &resolve, &JsValue::from(0)).is_ok())
+            .unwrap_or(false);
+        if !scheduled {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+/// Resource limits an embedding application can impose on untrusted CARs
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationLimits {
+    pub max_checkpoints: Option<usize>,
+    pub max_attachment_bytes: Option<u64>,
+    pub time_budget_ms: Option<u64>,
+    /// Run every check even after one fails, so the report shows each
+    /// failed step instead of skipping the rest
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// Verification stopped at a caller-imposed limit. This says nothing about
+/// whether the CAR has been tampered with.
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitExceeded {
+    pub limit: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Verification limit exceeded ({}): {}", self.limit, self.message)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+struct Budget {
+    limits: VerificationLimits,
+    started_ms: i64,
+}
+
+impl Budget {
+    fn new(limits: VerificationLimits) -> Self {
+        Self {
+            limits,
+            // chrono reads the JS clock on wasm32, where std::time::Instant panics
+            started_ms: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    fn check_time(&self) -> Result<(), LimitExceeded> {
+        let Some(budget_ms) = self.limits.time_budget_ms else {
+            return Ok(());
+        };
+        let elapsed = chrono::Utc::now().timestamp_millis() - self.started_ms;
+        if elapsed > budget_ms as i64 {
+            return Err(LimitExceeded {
+                limit: "time_budget_ms",
+                message: format!("verification exceeded the {budget_ms} ms time budget"),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_checkpoints(&self, count: usize) -> Result<(), LimitExceeded> {
+        match self.limits.max_checkpoints {
+            Some(max) if count > max => Err(LimitExceeded {
+                limit: "max_checkpoints",
+                message: format!("CAR has {count} checkpoints, exceeding the limit of {max}"),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_attachment_bytes(&self, total: u64) -> Result<(), LimitExceeded> {
+        match self.limits.max_attachment_bytes {
+            Some(max) if total > max => Err(LimitExceeded {
+                limit: "max_attachment_bytes",
+                message: format!("attachments exceed the limit of {max} bytes"),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_limits(limits: JsValue) -> Result<VerificationLimits, JsError> {
+    if limits.is_undefined() || limits.is_null() {
+        return Ok(VerificationLimits::default());
+    }
+    serde_wasm_bindgen::from_value(limits)
+        .map_err(|err| JsError::new(&format!("Invalid verification limits: {err}")))
+}
+
 fn to_js_error(err: anyhow::Error) -> JsError {
     JsError::new(&err.to_string())
 }
 
 fn decode_car(bytes: &[u8]) -> Result<DecodedCar> {
+    decode_car_with_limits(bytes, &Budget::new(VerificationLimits::default()))
+}
+
+fn decode_car_with_limits(bytes: &[u8], budget: &Budget) -> Result<DecodedCar> {
     if bytes.len() >= ZIP_MAGIC.len() && &bytes[..ZIP_MAGIC.len()] == ZIP_MAGIC {
-        load_car_from_zip(bytes)
+        load_car_from_zip(bytes, budget)
+    } else if bytes.first().is_some_and(|byte| byte >> 5 == 5) {
+        // A CBOR map head; JSON text never starts with one
+        load_car_from_cbor(bytes)
     } else {
         load_car_from_json(bytes)
     }
 }
 
+fn load_car_from_cbor(bytes: &[u8]) -> Result<DecodedCar> {
+    let raw_json = decode_car_cbor(bytes)?;
+    let car: Car = serde_json::from_str(&raw_json).context("Failed to parse CAR CBOR")?;
+    Ok(DecodedCar {
+        car,
+        raw_json,
+        attachments: Vec::new(),
+        bundled: false,
+        limit_exceeded: None,
+    })
+}
+
+/// Decode a DAG-CBOR envelope to the CAR JSON its body signature covers
+fn decode_car_cbor(bytes: &[u8]) -> Result<String> {
+    let envelope: Value =
+        ciborium::de::from_reader(bytes).context("CAR is not valid CBOR")?;
+    let Value::Object(mut fields) = envelope else {
+        return Err(anyhow!("CBOR document is not an encoded CAR"));
+    };
+    match fields.get("format").and_then(Value::as_str) {
+        Some(CAR_CBOR_FORMAT) => {}
+        Some(other) => return Err(anyhow!("Unsupported CAR encoding '{other}'")),
+        None => return Err(anyhow!("CBOR document is not an encoded CAR")),
+    }
+    match fields.get("version").and_then(Value::as_u64) {
+        Some(CAR_CBOR_VERSION) => {}
+        Some(other) => return Err(anyhow!("Unsupported {CAR_CBOR_FORMAT} version {other}")),
+        None => return Err(anyhow!("Encoded CAR has no version")),
+    }
+    let car = fields
+        .remove("car")
+        .filter(Value::is_object)
+        .ok_or_else(|| anyhow!("Encoded CAR has no car body"))?;
+    Ok(serde_json::to_string(&car)?)
+}
+
 fn load_car_from_json(bytes: &[u8]) -> Result<DecodedCar> {
     let car: Car = serde_json::from_slice(bytes).context("Failed to parse CAR JSON")?;
     let raw_json = String::from_utf8(bytes.to_vec()).context("Invalid UTF-8 in CAR JSON")?;
@@ -46,46 +584,161 @@ fn load_car_from_json(bytes: &[u8]) -> Result<DecodedCar> {
         car,
         raw_json,
         attachments: Vec::new(),
+        bundled: false,
+        limit_exceeded: None,
     })
 }
 
-fn load_car_from_zip(bytes: &[u8]) -> Result<DecodedCar> {
-    let reader = Cursor::new(bytes);
-    let mut archive = zip::ZipArchive::new(reader).context("Failed to read CAR ZIP archive")?;
+/// A JSON CAR with attachments supplied as `(path, bytes)` files. Hidden
+/// files, such as a `.DS_Store` a folder picker includes, are skipped.
+fn load_car_with_attachments(
+    json: &[u8],
+    files: Vec<(String, Vec<u8>)>,
+    budget: &Budget,
+) -> Result<DecodedCar> {
+    let mut decoded = load_car_from_json(json)?;
+    decoded.bundled = true;
+
+    let mut attachment_bytes = 0u64;
+    for (path, data) in files {
+        let file_name = path.rsplit('/').next().unwrap_or_default();
+        if file_name.is_empty() || file_name.starts_with('.') {
+            continue;
+        }
+        let name = format!("attachments/{file_name}");
+        let data = decompress_attachment(&name, data)?;
+        attachment_bytes += data.len() as u64;
+        if let Err(exceeded) = budget.check_attachment_bytes(attachment_bytes) {
+            decoded.limit_exceeded = Some(exceeded);
+            break;
+        }
+        decoded.attachments.push(Attachment { name, data });
+    }
+    // In archive order, as a ZIP would be, whatever order the picker used
+    decoded.attachments.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(decoded)
+}
 
-    let mut car_json = None;
-    let mut attachments = Vec::new();
+/// Compressed attachments are hashed, and counted against the budget,
+/// decompressed
+fn decompress_attachment(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
+    match model::AttachmentCompression::of_entry(name) {
+        Some(compression) => compression
+            .decompress(&data, ArchiveLimits::default().max_entry_bytes)
+            .map_err(|reason| anyhow!("{name}: {reason}")),
+        None => Ok(data),
+    }
+}
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let name = file.name().to_string();
+fn load_car_from_zip(bytes: &[u8], budget: &Budget) -> Result<DecodedCar> {
+    let reader = Cursor::new(bytes);
+    let mut archive = match SafeArchive::new(reader, ArchiveLimits::default()) {
+        Ok(archive) => archive,
+        Err(err) if err.is_limit() => {
+            return Err(LimitExceeded {
+                limit: "archive",
+                message: err.to_string(),
+            }
+            .into())
+        }
+        Err(err) => return Err(anyhow!(err).context("Failed to read CAR ZIP archive")),
+    };
 
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+    let (car, raw_json) = if archive.names().iter().any(|name| name == CAR_CBOR_ENTRY) {
+        let car_data = archive
+            .read_by_name(CAR_CBOR_ENTRY)
+            .map_err(|err| anyhow!(err).context("Failed to read car.cbor from ZIP"))?;
+        let raw_json = decode_car_cbor(&car_data).context("Failed to decode car.cbor from ZIP")?;
+        let car: Car =
+            serde_json::from_str(&raw_json).context("Failed to parse car.cbor from ZIP")?;
+        (car, raw_json)
+    } else {
+        let car_data = match archive.read_by_name("car.json") {
+            Ok(data) => data,
+            Err(ArchiveError::Missing(_)) => return Err(anyhow!("CAR ZIP is missing car.json")),
+            Err(err) => return Err(anyhow!(err).context("Failed to read car.json from ZIP")),
+        };
+        let car: Car =
+            serde_json::from_slice(&car_data).context("Failed to parse car.json from ZIP")?;
+        let raw_json = String::from_utf8(car_data).context("Invalid UTF-8 in car.json")?;
+        (car, raw_json)
+    };
 
-        if name == "car.json" {
-            car_json = Some(buffer);
-        } else if name.starts_with("attachments/") && !name.ends_with('/') {
-            attachments.push(Attachment { name, data: buffer });
+    // Attachments are read only while they fit the caller's byte budget; the
+    // chain and signatures are still verified when they do not.
+    let mut attachments = Vec::new();
+    let mut attachment_bytes = 0u64;
+    let mut limit_exceeded = None;
+    for index in 0..archive.len() {
+        let name = archive.names()[index].clone();
+        if !name.starts_with("attachments/") || name.ends_with('/') {
+            continue;
+        }
+        if let Err(exceeded) =
+            budget.check_attachment_bytes(attachment_bytes + archive.declared_size(index))
+        {
+            limit_exceeded = Some(exceeded);
+            break;
+        }
+        let Some(data) = archive.read_index(index)? else {
+            continue;
+        };
+        let data = decompress_attachment(&name, data)?;
+        attachment_bytes += data.len() as u64;
+        if let Err(exceeded) = budget.check_attachment_bytes(attachment_bytes) {
+            limit_exceeded = Some(exceeded);
+            break;
         }
+        attachments.push(Attachment { name, data });
     }
 
-    let car_data = car_json.ok_or_else(|| anyhow!("CAR ZIP is missing car.json"))?;
-    let car: Car =
-        serde_json::from_slice(&car_data).context("Failed to parse car.json from ZIP")?;
-    let raw_json = String::from_utf8(car_data).context("Invalid UTF-8 in car.json")?;
-
-    Ok(DecodedCar { car, raw_json, attachments })
+    Ok(DecodedCar {
+        car,
+        raw_json,
+        attachments,
+        bundled: true,
+        limit_exceeded,
+    })
 }
 
 fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
-    let DecodedCar { car, raw_json, attachments } = decoded;
+    verify_car_with_budget(decoded, &Budget::new(VerificationLimits::default()), false)
+}
+
+fn verify_car_with_budget(
+    decoded: DecodedCar,
+    budget: &Budget,
+    explain: bool,
+) -> Result<VerificationReport> {
+    let attachment_outcome = verify_all_attachments(&decoded.attachments, budget);
+    assemble_report(decoded, budget, attachment_outcome, explain, &mut |_| {})
+}
+
+/// Run the chain, signature and provenance checks and combine them with the
+/// outcome of attachment hashing, which callers may run ahead of time. With
+/// `explain`, failed steps carry a `StepExplanation`; `progress` hears of
+/// each check as it starts.
+fn assemble_report(
+    decoded: DecodedCar,
+    budget: &Budget,
+    attachment_outcome: Result<usize>,
+    explain: bool,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<VerificationReport> {
+    let DecodedCar {
+        car,
+        raw_json,
+        attachments,
+        bundled,
+        limit_exceeded,
+    } = decoded;
 
     let mut summary = SummaryMetrics {
         checkpoints_verified: 0,
         checkpoints_total: 0,
         provenance_verified: 0,
         provenance_total: car.provenance.len(),
+        provenance_unvalidated: 0,
         attachments_verified: 0,
         attachments_total: attachments
             .iter()
@@ -93,6 +746,9 @@ fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
                 attachment.name.starts_with("attachments/") && !attachment.name.ends_with('/')
             })
             .count(),
+        redactions: 0,
+        semantic_digests_recomputed: 0,
+        transcript_messages: 0,
         hash_chain_valid: false,
         signatures_valid: false,
         content_integrity_valid: false,
@@ -100,25 +756,28 @@ fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
 
     let mut steps = Vec::new();
     let mut overall_error = None;
+    // With `continueOnError` a failed check does not skip the ones after it
+    let keep_going = budget.limits.continue_on_error;
+    let mut content_intact = true;
+
+    // The declared format version decides which signatures are required
+    let format = match model::negotiate_format(car.format_version) {
+        Ok(format) => format,
+        Err(unsupported) => {
+            steps.extend(skipped_steps("hash_chain", &unsupported.to_string()));
+            return Ok(unsupported_report(car, summary, steps, unsupported));
+        }
+    };
 
     let process = match &car.proof.process {
         Some(process) if !process.sequential_checkpoints.is_empty() => process,
         Some(_) => {
             let message = "CAR has no checkpoints to verify".to_string();
-            steps.push(WorkflowStep::failure(
-                "hash_chain",
-                "Hash chain integrity",
-                &message,
-            ));
-            steps.extend(skipped_steps(
-                ["signatures", "provenance", "attachments"],
-                [
-                    "Signature validation",
-                    "Provenance verification",
-                    "Attachment integrity",
-                ],
-                &message,
-            ));
+            steps.push(
+                WorkflowStep::failure("hash_chain", "Hash chain integrity", &message)
+                    .explained(explain.then(|| diagnostics::missing_process(&car))),
+            );
+            steps.extend(skipped_steps("clock", &message));
             return Ok(build_report(car, summary, steps, Some(message)));
         }
         None => {
@@ -126,27 +785,24 @@ fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
                 "CAR has no process proof (match_kind: {}). This CAR was likely exported with an older version of Intelexta. Please re-export the CAR to include cryptographic signatures for verification.",
                 car.proof.match_kind
             );
-            steps.push(WorkflowStep::failure(
-                "hash_chain",
-                "Hash chain integrity",
-                &message,
-            ));
-            steps.extend(skipped_steps(
-                ["signatures", "provenance", "attachments"],
-                [
-                    "Signature validation",
-                    "Provenance verification",
-                    "Attachment integrity",
-                ],
-                &message,
-            ));
+            steps.push(
+                WorkflowStep::failure("hash_chain", "Hash chain integrity", &message)
+                    .explained(explain.then(|| diagnostics::missing_process(&car))),
+            );
+            steps.extend(skipped_steps("clock", &message));
             return Ok(build_report(car, summary, steps, Some(message)));
         }
     };
 
     summary.checkpoints_total = process.sequential_checkpoints.len();
 
-    match verify_hash_chain(&process.sequential_checkpoints) {
+    if let Err(exceeded) = budget.check_checkpoints(summary.checkpoints_total) {
+        steps.extend(skipped_steps("hash_chain", &exceeded.to_string()));
+        return Ok(limit_report(car, summary, steps, exceeded));
+    }
+
+    progress(Progress::of("hash_chain", 0, Some(summary.checkpoints_total as u64)));
+    match verify_hash_chain(&process.sequential_checkpoints, budget) {
         Ok(count) => {
             summary.hash_chain_valid = true;
             summary.checkpoints_verified = count;
@@ -160,107 +816,192 @@ fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
             ));
         }
         Err(err) => {
+            if let Some(exceeded) = err.downcast_ref::<LimitExceeded>() {
+                steps.extend(skipped_steps("hash_chain", &exceeded.to_string()));
+                return Ok(limit_report(car, summary, steps, exceeded.clone()));
+            }
             let message = format!("Hash chain verification failed: {err}");
-            steps.push(WorkflowStep::failure(
-                "hash_chain",
-                "Hash chain integrity",
-                &message,
-            ));
-            steps.extend(skipped_steps(
-                ["signatures", "provenance", "attachments"],
-                [
-                    "Signature validation",
-                    "Provenance verification",
-                    "Attachment integrity",
-                ],
-                &message,
-            ));
+            steps.push(
+                WorkflowStep::failure("hash_chain", "Hash chain integrity", &message).explained(
+                    explain.then(|| diagnostics::hash_chain(&err, &process.sequential_checkpoints)),
+                ),
+            );
+            if !keep_going {
+                steps.extend(skipped_steps("clock", &message));
+                return Ok(build_report(car, summary, steps, Some(message)));
+            }
             overall_error = Some(message);
-            return Ok(build_report(car, summary, steps, overall_error));
         }
     }
 
+    // Timestamps that run backwards or into the future only warn
+    let anomalies = clock_anomalies(&car, &process.sequential_checkpoints);
+    if anomalies.is_empty() {
+        steps.push(WorkflowStep::success(
+            "clock",
+            "Checkpoint clock",
+            vec![StepDetail::new(
+                "Checkpoint timestamps",
+                "In order and within the CAR's lifetime",
+            )],
+        ));
+    } else {
+        let details = anomalies
+            .iter()
+            .map(|anomaly| StepDetail::new("Clock anomaly", anomaly.to_string()))
+            .collect();
+        steps.push(WorkflowStep::warning("clock", "Checkpoint clock", details));
+    }
+
+    // Usage over the policy's budgets, or misreported, only warns too
+    let violations = budget_violations(&car, &process.sequential_checkpoints);
+    if violations.is_empty() {
+        let checked = if car.policy_ref.limits.is_some() {
+            "Recorded usage matches the checkpoints and stays within the policy"
+        } else {
+            "Recorded usage matches the checkpoints; the CAR embeds no policy budgets"
+        };
+        steps.push(WorkflowStep::success(
+            "policy",
+            "Policy budgets",
+            vec![StepDetail::new("Budgets", checked)],
+        ));
+    } else {
+        let details = violations
+            .iter()
+            .map(|violation| StepDetail::new("Budget violation", violation.to_string()))
+            .collect();
+        steps.push(WorkflowStep::warning("policy", "Policy budgets", details));
+    }
+
+    progress(Progress::started("signatures"));
     // Verify top-level body signature (if present)
-    match verify_top_level_signature(&car, &raw_json) {
-        Ok(_) => {
-            // Top-level signature verified or not present (legacy format)
-        }
-        Err(err) => {
-            let message = format!("Top-level body signature verification failed: {err}");
-            steps.push(WorkflowStep::failure(
-                "signatures",
-                "Signature validation",
-                &message,
-            ));
-            steps.extend(skipped_steps(
-                ["provenance", "attachments"],
-                ["Provenance verification", "Attachment integrity"],
-                &message,
-            ));
-            overall_error = Some(message);
-            return Ok(build_report(car, summary, steps, overall_error));
+    let body_signature = verify_top_level_signature(&car, &raw_json, &format);
+    if let Err(err) = &body_signature {
+        let message = format!("Top-level body signature verification failed: {err}");
+        steps.push(
+            WorkflowStep::failure("signatures", "Signature validation", &message)
+                .explained(explain.then(|| diagnostics::body_signature(err))),
+        );
+        if !keep_going {
+            steps.extend(skipped_steps("provenance", &message));
+            return Ok(build_report(car, summary, steps, Some(message)));
         }
+        overall_error.get_or_insert(message);
     }
 
-    match verify_signatures(&car.signer_public_key, &process.sequential_checkpoints) {
-        Ok(_) => {
+    let signatures = body_signature.is_ok().then(|| {
+        verify_signatures(&car.signer_public_key, &process.sequential_checkpoints, budget)
+            .and_then(|_| verify_execution_sections(&car, budget))
+            .and_then(|executions| Ok((executions, verify_cosignatures(&car)?)))
+    });
+    match signatures {
+        // The signatures step already failed on the body signature
+        None => {}
+        Some(Ok((executions, cosignatures))) => {
             summary.signatures_valid = true;
+            let mut details = vec![StepDetail::new(
+                "Checkpoint signatures",
+                format!("{} verified", summary.checkpoints_total),
+            )];
+            if executions > 0 {
+                details.push(StepDetail::new(
+                    "Execution proofs",
+                    format!("{executions} executions verified"),
+                ));
+            }
+            if cosignatures > 0 {
+                details.push(StepDetail::new(
+                    "Co-signatures",
+                    format!("{cosignatures} verified"),
+                ));
+            }
             steps.push(WorkflowStep::success(
                 "signatures",
                 "Signature validation",
-                vec![StepDetail::new(
-                    "Checkpoint signatures",
-                    format!("{} verified", summary.checkpoints_total),
-                )],
+                details,
             ));
         }
-        Err(err) => {
+        Some(Err(err)) => {
+            if let Some(exceeded) = err.downcast_ref::<LimitExceeded>() {
+                steps.extend(skipped_steps("signatures", &exceeded.to_string()));
+                return Ok(stopped_report(car, summary, steps, exceeded.clone(), overall_error));
+            }
             let message = format!("Signature verification failed: {err}");
-            steps.push(WorkflowStep::failure(
-                "signatures",
-                "Signature validation",
-                &message,
-            ));
-            steps.extend(skipped_steps(
-                ["provenance", "attachments"],
-                ["Provenance verification", "Attachment integrity"],
-                &message,
-            ));
-            overall_error = Some(message);
-            return Ok(build_report(car, summary, steps, overall_error));
+            steps.push(
+                WorkflowStep::failure("signatures", "Signature validation", &message).explained(
+                    explain.then(|| {
+                        diagnostics::checkpoint_signatures(
+                            &err,
+                            &process.sequential_checkpoints,
+                            &car.signer_public_key,
+                        )
+                    }),
+                ),
+            );
+            if !keep_going {
+                steps.extend(skipped_steps("provenance", &message));
+                return Ok(build_report(car, summary, steps, Some(message)));
+            }
+            overall_error.get_or_insert(message);
         }
     }
 
-    match verify_provenance(&car, &process.sequential_checkpoints) {
-        Ok(verified) => {
+    progress(Progress::started("provenance"));
+    let provenance = verify_provenance(&car)
+        .and_then(|(verified, unvalidated)| Ok((verified, unvalidated, verify_redactions(&car)?)));
+    match provenance {
+        Ok((verified, unvalidated, redactions)) => {
             summary.provenance_verified = verified;
+            summary.provenance_unvalidated = summary.provenance_total - verified;
+            summary.redactions = redactions;
+            let mut details = vec![StepDetail::new(
+                "Provenance claims",
+                format!("{verified}/{} verified", summary.provenance_total),
+            )];
+            if !unvalidated.is_empty() {
+                details.push(StepDetail::new(
+                    "Unvalidated extensions",
+                    unvalidated.join(", "),
+                ));
+            }
+            if redactions > 0 {
+                details.push(StepDetail::new(
+                    "Redactions",
+                    format!("{redactions} withheld behind commitments"),
+                ));
+            }
             steps.push(WorkflowStep::success(
                 "provenance",
                 "Provenance verification",
-                vec![StepDetail::new(
-                    "Provenance claims",
-                    format!("{verified}/{} verified", summary.provenance_total),
-                )],
+                details,
             ));
         }
         Err(err) => {
             let message = format!("Content integrity verification failed: {err}");
-            steps.push(WorkflowStep::failure(
-                "provenance",
-                "Provenance verification",
-                &message,
-            ));
-            steps.push(WorkflowStep::skipped(
-                "attachments",
-                "Attachment integrity",
-                &message,
-            ));
-            overall_error = Some(message);
-            return Ok(build_report(car, summary, steps, overall_error));
+            steps.push(
+                WorkflowStep::failure("provenance", "Provenance verification", &message)
+                    .explained(explain.then(|| diagnostics::provenance(&err))),
+            );
+            if !keep_going {
+                steps.extend(skipped_steps("attachments", &message));
+                return Ok(build_report(car, summary, steps, Some(message)));
+            }
+            overall_error.get_or_insert(message);
+            content_intact = false;
         }
     }
 
-    match verify_all_attachments(&attachments) {
+    if let Some(exceeded) = limit_exceeded {
+        steps.extend(skipped_steps("attachments", &exceeded.to_string()));
+        return Ok(stopped_report(car, summary, steps, exceeded, overall_error));
+    }
+
+    let attachment_outcome = attachment_outcome.and_then(|verified| {
+        verify_attachment_manifest(&car, &attachments, bundled)?;
+        Ok(verified)
+    });
+    match attachment_outcome {
         Ok(verified) => {
             summary.attachments_verified = verified;
             steps.push(WorkflowStep::success(
@@ -273,18 +1014,78 @@ fn verify_car(decoded: DecodedCar) -> Result<VerificationReport> {
             ));
         }
         Err(err) => {
+            if let Some(exceeded) = err.downcast_ref::<LimitExceeded>() {
+                steps.extend(skipped_steps("attachments", &exceeded.to_string()));
+                return Ok(stopped_report(car, summary, steps, exceeded.clone(), overall_error));
+            }
             let message = format!("Attachment verification failed: {err}");
-            steps.push(WorkflowStep::failure(
-                "attachments",
-                "Attachment integrity",
-                &message,
-            ));
-            overall_error = Some(message);
-            return Ok(build_report(car, summary, steps, overall_error));
+            steps.push(
+                WorkflowStep::failure("attachments", "Attachment integrity", &message)
+                    .explained(explain.then(|| diagnostics::attachments(&err))),
+            );
+            overall_error.get_or_insert(message);
+            content_intact = false;
         }
     }
 
-    summary.content_integrity_valid = true;
+    // Concordant steps need a usable epsilon, and recorded semantic digests
+    // must match the outputs the bundle carries, once those hashed correctly
+    let outputs = if content_intact {
+        output_texts(&attachments)
+    } else {
+        HashMap::new()
+    };
+    let (issues, recomputed) = semantic_issues(&car, &outputs);
+    summary.semantic_digests_recomputed = recomputed;
+    if issues.is_empty() {
+        let checked = if recomputed > 0 {
+            format!("{recomputed} recomputed from bundled outputs")
+        } else {
+            "Well formed; no bundled output to recompute them from".to_string()
+        };
+        steps.push(WorkflowStep::success(
+            "semantic",
+            "Semantic digests",
+            vec![StepDetail::new("Semantic digests", checked)],
+        ));
+    } else {
+        let message = format!("Semantic digest check failed: {}", issues[0]);
+        let mut step = WorkflowStep::failure("semantic", "Semantic digests", &message);
+        step.details = issues
+            .iter()
+            .map(|issue| StepDetail::new("Semantic issue", issue.to_string()))
+            .collect();
+        steps.push(step);
+        overall_error.get_or_insert(message);
+        content_intact = false;
+    }
+
+    // Each chat message must hash to its checkpoint, and each AI reply
+    // answer the human message before it
+    match transcript::check_transcript(&transcript_entries(&car)) {
+        Ok(checked) => {
+            summary.transcript_messages = checked.messages;
+            let mut details = vec![StepDetail::new(
+                "Messages",
+                format!("{} checked, {} withheld", checked.messages, checked.withheld),
+            )];
+            if checked.unbound > 0 {
+                details.push(StepDetail::new(
+                    "Unbound human messages",
+                    format!("{} covered by the body signature only", checked.unbound),
+                ));
+            }
+            steps.push(WorkflowStep::success("transcript", "Chat transcript", details));
+        }
+        Err(err) => {
+            let message = format!("Transcript verification failed: {err}");
+            steps.push(WorkflowStep::failure("transcript", "Chat transcript", &message));
+            overall_error.get_or_insert(message);
+            content_intact = false;
+        }
+    }
+
+    summary.content_integrity_valid = content_intact;
 
     Ok(build_report(car, summary, steps, overall_error))
 }
@@ -315,269 +1116,475 @@ fn build_report(
             public_key: car.signer_public_key.clone(),
         })
     };
+    // Co-signers are only listed once the author's signatures hold
+    let signers = if summary.signatures_valid {
+        let author = SignerCheck {
+            role: SignerRole::Author,
+            public_key: car.signer_public_key.clone(),
+            valid: true,
+        };
+        std::iter::once(author)
+            .chain(check_cosignatures(&car).unwrap_or_default())
+            .collect()
+    } else {
+        Vec::new()
+    };
 
     VerificationReport {
         status,
         car_id: car.id.clone(),
         run_id: car.run_id.clone(),
         created_at: car.created_at.to_rfc3339(),
+        format_version: car.format_version,
         signer,
+        signers,
         model: ModelSummary {
             name: car.run.model.clone(),
             version: car.run.version.clone(),
             kind: car.run.kind.clone(),
         },
+        run_environment: car.run_environment.clone(),
+        external_attachments: car.external_attachments.clone(),
         steps,
         summary,
         error,
+        limit_exceeded: None,
+        unsupported_format: None,
+        checkpoints: None,
     }
 }
 
-fn verify_hash_chain(checkpoints: &[ProcessCheckpointProof]) -> Result<usize> {
-    let mut verified = 0;
-
-    for (index, checkpoint) in checkpoints.iter().enumerate() {
-        let expected = compute_checkpoint_hash(checkpoint)?;
-        if expected != checkpoint.curr_chain {
-            return Err(anyhow!(
-                "Hash chain broken at checkpoint #{index} (id: {})\nExpected: {expected}\nFound: {}",
-                checkpoint.id,
-                checkpoint.curr_chain
-            ));
-        }
-        verified += 1;
-    }
-
-    Ok(verified)
+/// Report for a CAR in a format version this verifier does not know; no
+/// check ran, so it says nothing about tampering
+fn unsupported_report(
+    car: Car,
+    summary: SummaryMetrics,
+    steps: Vec<WorkflowStep>,
+    unsupported: UnsupportedFormatVersion,
+) -> VerificationReport {
+    let mut report = build_report(car, summary, steps, Some(unsupported.to_string()));
+    report.status = VerificationStatus::UnsupportedVersion;
+    report.unsupported_format = Some(unsupported);
+    report
 }
 
-fn compute_checkpoint_hash(checkpoint: &ProcessCheckpointProof) -> Result<String> {
-    #[derive(Serialize)]
-    struct CheckpointBody<'a> {
-        run_id: &'a str,
-        kind: &'a str,
-        timestamp: &'a str,
-        inputs_sha256: &'a Option<String>,
-        outputs_sha256: &'a Option<String>,
-        incident: Option<Value>,
-        usage_tokens: u64,
-        prompt_tokens: u64,
-        completion_tokens: u64,
+/// Report for a limit hit after `continueOnError` let verification run past
+/// a failure, which the limit must not hide
+fn stopped_report(
+    car: Car,
+    summary: SummaryMetrics,
+    steps: Vec<WorkflowStep>,
+    exceeded: LimitExceeded,
+    failure: Option<String>,
+) -> VerificationReport {
+    match failure {
+        Some(error) => build_report(car, summary, steps, Some(error)),
+        None => limit_report(car, summary, steps, exceeded),
     }
+}
 
-    let body = CheckpointBody {
-        run_id: &checkpoint.run_id,
-        kind: &checkpoint.kind,
-        timestamp: &checkpoint.timestamp,
-        inputs_sha256: &checkpoint.inputs_sha256,
-        outputs_sha256: &checkpoint.outputs_sha256,
-        incident: None,
-        usage_tokens: checkpoint.usage_tokens,
-        prompt_tokens: checkpoint.prompt_tokens,
-        completion_tokens: checkpoint.completion_tokens,
-    };
-
-    let body_json = serde_json::to_value(&body)?;
-    let canonical = canonical_json(&body_json)?;
-
-    let mut hasher = Sha256::new();
-    hasher.update(checkpoint.prev_chain.as_bytes());
-    hasher.update(&canonical);
-    Ok(hex::encode(hasher.finalize()))
+/// Report for a verification cut short by a caller limit, kept distinct from
+/// a failed (possibly tampered) CAR
+fn limit_report(
+    car: Car,
+    summary: SummaryMetrics,
+    steps: Vec<WorkflowStep>,
+    exceeded: LimitExceeded,
+) -> VerificationReport {
+    let mut report = build_report(car, summary, steps, Some(exceeded.to_string()));
+    report.status = VerificationStatus::LimitExceeded;
+    report.limit_exceeded = Some(exceeded);
+    report
 }
 
-fn canonical_json(value: &Value) -> Result<Vec<u8>> {
-    serde_jcs::to_vec(value).map_err(|err| anyhow!("Failed to canonicalize JSON: {err}"))
+fn verify_hash_chain(checkpoints: &[ProcessCheckpointProof], budget: &Budget) -> Result<usize> {
+    for (index, checkpoint) in checkpoints.iter().enumerate() {
+        budget.check_time()?;
+        intelexta_verify_core::check_chain_link(index, checkpoint).map_err(Mismatch::from)?;
+    }
+    Ok(checkpoints.len())
 }
 
-fn verify_top_level_signature(car: &Car, raw_json: &str) -> Result<()> {
-    // Check if we have the new signature format (ed25519-body:...)
+fn verify_top_level_signature(car: &Car, raw_json: &str, format: &FormatSpec) -> Result<()> {
     if car.signatures.is_empty() {
         return Err(anyhow!("No signatures found in CAR"));
     }
+    format.check_signatures(&car.signatures).map_err(|reason| anyhow!(reason))?;
 
-    let first_sig = &car.signatures[0];
+    let body_signature = car
+        .signatures
+        .iter()
+        .find_map(|entry| match model::parse_signature(entry) {
+            Some((SignatureKind::Body, encoded)) => Some(encoded),
+            _ => None,
+        });
 
-    // If it's the new format, verify top-level body signature
-    if first_sig.starts_with("ed25519-body:") {
+    // Required from format version 2 on; legacy CARs may lack it
+    if let Some(sig_b64) = body_signature {
         if car.signer_public_key.is_empty() {
             return Err(anyhow!("Top-level signature present but signer_public_key is empty"));
         }
-
-        // Extract signature
-        let sig_b64 = first_sig.strip_prefix("ed25519-body:").unwrap();
-
-        // Parse raw JSON as Value and remove signatures field
-        let mut car_json: Value = serde_json::from_str(raw_json)
-            .context("Failed to parse raw JSON")?;
-
-        // Remove signatures field
-        if let Some(obj) = car_json.as_object_mut() {
-            obj.remove("signatures");
-        }
-
-        // Canonicalize the body (without re-serializing through Rust structs)
-        let canonical = canonical_json(&car_json)?;
-
-        // Verify signature
-        let public_key_bytes = STANDARD
-            .decode(&car.signer_public_key)
-            .context("Invalid signer public key base64")?;
-
-        let verifying_key = VerifyingKey::from_bytes(
-            &public_key_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Public key must be 32 bytes"))?,
-        )
-        .context("Invalid Ed25519 public key")?;
-
-        let signature_bytes = STANDARD
-            .decode(sig_b64)
-            .context("Invalid top-level signature base64")?;
-
-        let signature = Signature::from_bytes(
-            &signature_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Signature must be 64 bytes"))?,
-        );
-
-        verifying_key
-            .verify(&canonical, &signature)
-            .context("Top-level body signature verification failed")?;
+        let car_json: Value = serde_json::from_str(raw_json).context("Failed to parse raw JSON")?;
+        let verifying_key =
+            trust::decode_key(&car.signer_public_key).context("Invalid signer public key")?;
+        intelexta_verify_core::check_body_signature(&car_json, &verifying_key, sig_b64)
+            .map_err(Mismatch::from)?;
     }
-    // else: legacy format (no ed25519-body prefix), skip top-level verification
+    // else: a legacy CAR without a body signature, which its format allows
 
     Ok(())
 }
 
-fn verify_signatures(public_key_b64: &str, checkpoints: &[ProcessCheckpointProof]) -> Result<()> {
-    let public_key_bytes = STANDARD
-        .decode(public_key_b64)
-        .context("Invalid signer public key base64")?;
-
-    let verifying_key = VerifyingKey::from_bytes(
-        &public_key_bytes
-            .try_into()
-            .map_err(|_| anyhow!("Public key must be 32 bytes"))?,
-    )
-    .context("Invalid Ed25519 public key")?;
+/// Co-signatures of the CAR, each with whether it verifies under the key it
+/// names; fails on a malformed entry
+fn check_cosignatures(car: &Car) -> Result<Vec<SignerCheck>> {
+    let mut signers = Vec::new();
+    for entry in &car.signatures {
+        let Some(cosignature) = Cosignature::parse(entry) else {
+            continue;
+        };
+        let cosignature = cosignature.map_err(|reason| anyhow!(reason))?;
+        let message = Cosignature::message(&car.id, cosignature.role);
+        let valid = trust::decode_key(&cosignature.public_key).is_ok_and(|key| {
+            intelexta_verify_core::signature_valid(&key, &message, &cosignature.signature)
+        });
+        signers.push(SignerCheck {
+            role: cosignature.role,
+            public_key: cosignature.public_key,
+            valid,
+        });
+    }
+    Ok(signers)
+}
 
-    for (index, checkpoint) in checkpoints.iter().enumerate() {
-        let signature_bytes = STANDARD
-            .decode(&checkpoint.signature)
-            .with_context(|| format!("Invalid signature base64 at checkpoint #{index}"))?;
-
-        let signature = Signature::from_bytes(
-            &signature_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Signature must be 64 bytes at checkpoint #{index}"))?,
-        );
+/// Fails on the first co-signature that does not verify; returns how many did
+fn verify_cosignatures(car: &Car) -> Result<usize> {
+    let cosigners = check_cosignatures(car)?;
+    if let Some(invalid) = cosigners.iter().find(|signer| !signer.valid) {
+        return Err(anyhow!(
+            "{} co-signature by {} does not verify",
+            invalid.role.as_str(),
+            invalid.public_key
+        ));
+    }
+    Ok(cosigners.len())
+}
 
-        verifying_key
-            .verify(checkpoint.curr_chain.as_bytes(), &signature)
-            .with_context(|| format!("Signature verification failed at checkpoint #{index}"))?;
+/// Chains and signatures of each execution section of a multi-execution CAR;
+/// returns how many verified
+fn verify_execution_sections(car: &Car, budget: &Budget) -> Result<usize> {
+    for execution in &car.proof.executions {
+        let checkpoints = &execution.process.sequential_checkpoints;
+        verify_hash_chain(checkpoints, budget)
+            .and_then(|_| verify_signatures(&car.signer_public_key, checkpoints, budget))
+            .with_context(|| format!("Execution {}", execution.run_execution_id))?;
     }
+    Ok(car.proof.executions.len())
+}
 
+fn verify_signatures(
+    public_key_b64: &str,
+    checkpoints: &[ProcessCheckpointProof],
+    budget: &Budget,
+) -> Result<()> {
+    let verifying_key = trust::decode_key(public_key_b64).context("Invalid signer public key")?;
+    for (index, checkpoint) in checkpoints.iter().enumerate() {
+        budget.check_time()?;
+        intelexta_verify_core::check_checkpoint_signature(index, checkpoint, &verifying_key)
+            .map_err(Mismatch::from)?;
+    }
     Ok(())
 }
 
-fn verify_provenance(car: &Car, checkpoints: &[ProcessCheckpointProof]) -> Result<usize> {
+/// Check every provenance claim; returns the number verified and the claim
+/// types that have no validator here
+fn verify_provenance(car: &Car) -> Result<(usize, Vec<String>)> {
+    let checks = ClaimRegistry::builtin().verify(car);
     let mut verified = 0;
-
-    for (index, claim) in car.provenance.iter().enumerate() {
-        let expected_hash = claim.sha256.strip_prefix("sha256:").ok_or_else(|| {
-            anyhow!(
-                "Invalid provenance claim #{}: hash must start with 'sha256:'",
-                index
-            )
-        })?;
-
-        match claim.claim_type.as_str() {
-            "config" => {
-                let spec_json = serde_json::to_value(&car.run.steps)?;
-                let canonical = canonical_json(&spec_json)?;
-                let computed = hex::encode(Sha256::digest(&canonical));
-
-                if computed != expected_hash {
-                    return Err(anyhow!(
-                        "Config hash mismatch at provenance claim #{}\nExpected: {}\nComputed: {}",
-                        index,
-                        expected_hash,
-                        computed
-                    ));
+    for check in &checks {
+        match &check.status {
+            ClaimStatus::Verified => verified += 1,
+            ClaimStatus::Failed(reason) => {
+                return Err(Mismatch {
+                    message: format!(
+                        "{} claim failed at provenance claim #{}: {}",
+                        check.claim_type, check.index, reason
+                    ),
+                    subject: Some(format!("{} claim #{}", check.claim_type, check.index)),
+                    ..Mismatch::default()
                 }
-                verified += 1;
+                .into());
             }
-            "input" | "output" => {
-                let exists = checkpoints.iter().any(|checkpoint| {
-                    checkpoint
-                        .inputs_sha256
-                        .as_deref()
-                        .map(|hash| hash == expected_hash)
-                        .unwrap_or(false)
-                        || checkpoint
-                            .outputs_sha256
-                            .as_deref()
-                            .map(|hash| hash == expected_hash)
-                            .unwrap_or(false)
-                });
-
-                if !exists {
-                    return Err(anyhow!(
-                        "{} hash not found in checkpoints at provenance claim #{}",
-                        claim.claim_type,
-                        index
-                    ));
-                }
-                verified += 1;
-            }
-            _ => {}
+            ClaimStatus::UnvalidatedExtension => {}
         }
     }
-
-    Ok(verified)
+    Ok((verified, claims::unvalidated_types(&checks)))
 }
 
-fn verify_all_attachments(attachments: &[Attachment]) -> Result<usize> {
-    let mut verified = 0;
+/// Redacted fields must carry the commitments the signed body lists; returns
+/// the number of redactions
+fn verify_redactions(car: &Car) -> Result<usize> {
+    claims::check_redactions(car).map_err(|reason| {
+        Mismatch {
+            message: format!("Redaction check failed: {reason}"),
+            subject: Some("redactions".to_string()),
+            ..Mismatch::default()
+        }
+        .into()
+    })
+}
 
-    for attachment in attachments
+fn verify_all_attachments(attachments: &[Attachment], budget: &Budget) -> Result<usize> {
+    let files: Vec<&Attachment> = attachments
         .iter()
         .filter(|att| att.name.starts_with("attachments/") && !att.name.ends_with('/'))
-    {
-        let expected = attachment
+        .collect();
+
+    let check = |attachment: &&Attachment| -> Result<()> {
+        budget.check_time()?;
+        verify_attachment(attachment)
+    };
+
+    // Hashing dominates for large receipts; outcomes are collected in archive
+    // order so the reported mismatch does not depend on thread scheduling
+    #[cfg(feature = "parallel")]
+    let outcomes: Vec<Result<()>> = {
+        use rayon::prelude::*;
+        files.par_iter().map(check).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let outcomes: Vec<Result<()>> = files.iter().map(check).collect();
+
+    outcomes.into_iter().collect::<Result<Vec<()>>>()?;
+    Ok(files.len())
+}
+
+/// The attachment files must be exactly the set the signed manifest lists:
+/// each one proves into the Merkle root, and in a bundle no listed attachment
+/// is missing unless it was redacted. CARs without a manifest pass unchecked.
+fn verify_attachment_manifest(car: &Car, attachments: &[Attachment], bundled: bool) -> Result<()> {
+    let Some(manifest) = &car.attachments else {
+        return Ok(());
+    };
+    let mismatch = |message: String, subject: &str| -> anyhow::Error {
+        Mismatch {
+            message,
+            subject: Some(subject.to_string()),
+            ..Mismatch::default()
+        }
+        .into()
+    };
+
+    let root = manifest.merkle_root.strip_prefix("sha256:").unwrap_or_default();
+    let computed = merkle::root(&manifest.hashes);
+    if merkle::leaves(&manifest.hashes) != manifest.hashes || computed != root {
+        return Err(Mismatch {
+            message: "Attachment Merkle root mismatch".to_string(),
+            subject: Some("attachments".to_string()),
+            expected: Some(manifest.merkle_root.clone()),
+            computed: Some(format!("sha256:{computed}")),
+            ..Mismatch::default()
+        }
+        .into());
+    }
+
+    let mut found = Vec::new();
+    for attachment in attachments {
+        let Some(hash) = attachment
             .name
             .strip_prefix("attachments/")
-            .ok_or_else(|| anyhow!("Invalid attachment path: {}", attachment.name))?;
+            .and_then(|name| name.split_once('.'))
+            .map(|(hash, _extension)| hash)
+        else {
+            continue;
+        };
+        if !merkle::proves_into(&manifest.hashes, root, hash) {
+            return Err(mismatch(
+                format!(
+                    "Attachment {} is not in the CAR's attachment manifest",
+                    attachment.name
+                ),
+                &attachment.name,
+            ));
+        }
+        if model::AttachmentCompression::of_entry(&attachment.name) != manifest.compression {
+            return Err(mismatch(
+                format!(
+                    "Attachment {} is not encoded as the CAR's attachment manifest records",
+                    attachment.name
+                ),
+                &attachment.name,
+            ));
+        }
+        found.push(hash);
+    }
+
+    if bundled {
+        for hash in &manifest.hashes {
+            let target = format!("attachments/{hash}");
+            let redacted = car.redactions.iter().any(|redaction| redaction.target == target);
+            if !found.contains(&hash.as_str()) && !redacted {
+                return Err(mismatch(
+                    format!(
+                        "Attachment {hash} is listed in the CAR's manifest \
+                         but missing from the bundle"
+                    ),
+                    &target,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
 
-        let (hash, _extension) = expected
-            .split_once('.')
-            .ok_or_else(|| anyhow!("Invalid attachment filename format: {}", attachment.name))?;
+fn verify_attachment(attachment: &Attachment) -> Result<()> {
+    let expected = attachment
+        .name
+        .strip_prefix("attachments/")
+        .ok_or_else(|| anyhow!("Invalid attachment path: {}", attachment.name))?;
 
-        let computed = hex::encode(Sha256::digest(&attachment.data));
+    let (hash, _extension) = expected
+        .split_once('.')
+        .ok_or_else(|| anyhow!("Invalid attachment filename format: {}", attachment.name))?;
 
-        if computed != hash {
-            return Err(anyhow!(
+    if let Err(intelexta_verify_core::Error::AttachmentMismatch { expected, computed }) =
+        intelexta_verify_core::check_attachment(hash, &attachment.data)
+    {
+        return Err(Mismatch {
+            message: format!(
                 "Attachment content mismatch\nFile: {}\nExpected hash: {}\nComputed hash: {}",
-                attachment.name,
-                hash,
-                computed
-            ));
+                attachment.name, expected, computed
+            ),
+            subject: Some(attachment.name.clone()),
+            expected: Some(expected),
+            computed: Some(computed),
+            ..Mismatch::default()
         }
-
-        verified += 1;
+        .into());
     }
 
-    Ok(verified)
+    Ok(())
+}
+
+/// Bundled attachments as text, by the hash they are named by; one named
+/// by a checkpoint's `outputs_sha256` is the output its digest was taken from
+fn output_texts(attachments: &[Attachment]) -> HashMap<&str, &str> {
+    attachments
+        .iter()
+        .filter_map(|attachment| {
+            let file = attachment.name.strip_prefix("attachments/")?;
+            let (hash, _extension) = file.split_once('.')?;
+            Some((hash, std::str::from_utf8(&attachment.data).ok()?))
+        })
+        .collect()
+}
+
+/// Every checkpoint of the CAR, its execution sections included, once each
+fn all_checkpoints(car: &Car) -> Vec<&ProcessCheckpointProof> {
+    let mut seen = HashSet::new();
+    car.proof
+        .process
+        .iter()
+        .chain(car.proof.executions.iter().map(|execution| &execution.process))
+        .flat_map(|process| &process.sequential_checkpoints)
+        .filter(|checkpoint| seen.insert(checkpoint.id.as_str()))
+        .collect()
+}
+
+/// The CAR's checkpoints as the transcript check reads them
+fn transcript_entries(car: &Car) -> Vec<TranscriptEntry<'_>> {
+    all_checkpoints(car)
+        .into_iter()
+        .map(|checkpoint| TranscriptEntry {
+            id: &checkpoint.id,
+            parent_id: checkpoint.parent_checkpoint_id.as_deref(),
+            turn_index: checkpoint.turn_index,
+            inputs_sha256: checkpoint.inputs_sha256.as_deref(),
+            outputs_sha256: checkpoint.outputs_sha256.as_deref(),
+            role: checkpoint.message.as_ref().map(|message| message.role.as_str()),
+            body: checkpoint.message.as_ref().and_then(|message| message.body.as_deref()),
+        })
+        .collect()
 }
 
-fn skipped_steps<const N: usize>(
-    keys: [&'static str; N],
-    labels: [&'static str; N],
-    reason: &str,
-) -> Vec<WorkflowStep> {
-    keys.into_iter()
-        .zip(labels)
+/// Semantic digests and epsilons checked against the CAR's steps, its proof
+/// and the bundled outputs; returns the issues and how many digests were
+/// recomputed
+fn semantic_issues(car: &Car, outputs: &HashMap<&str, &str>) -> (Vec<SemanticIssue>, usize) {
+    let steps: Vec<SemanticStep> = car
+        .run
+        .steps
+        .iter()
+        .map(|step| SemanticStep {
+            id: &step.id,
+            concordant: matches!(step.proof_mode, model::RunProofMode::Concordant)
+                && !step.checkpoint_type.eq_ignore_ascii_case("InteractiveChat"),
+            epsilon: step.epsilon,
+        })
+        .collect();
+    let checkpoints: Vec<SemanticCheckpoint> = all_checkpoints(car)
+        .into_iter()
+        .map(|checkpoint| SemanticCheckpoint {
+            id: &checkpoint.id,
+            semantic_digest: checkpoint.semantic_digest.as_deref(),
+            output: checkpoint
+                .outputs_sha256
+                .as_deref()
+                .and_then(|hash| outputs.get(hash).copied()),
+        })
+        .collect();
+    let recomputed = checkpoints
+        .iter()
+        .filter(|checkpoint| checkpoint.semantic_digest.is_some() && checkpoint.output.is_some())
+        .count();
+    let proof = SemanticProof {
+        epsilon: car.proof.epsilon,
+        original_digest: car.proof.original_semantic_digest.as_deref(),
+        replay_digest: car.proof.replay_semantic_digest.as_deref(),
+    };
+    (semantic::semantic_issues(&steps, &checkpoints, &proof), recomputed)
+}
+
+fn clock_anomalies(car: &Car, checkpoints: &[ProcessCheckpointProof]) -> Vec<ClockAnomaly> {
+    let readings: Vec<ClockReading> = checkpoints
+        .iter()
+        .map(|checkpoint| ClockReading {
+            id: &checkpoint.id,
+            at_ms: chrono::DateTime::parse_from_rfc3339(&checkpoint.timestamp)
+                .ok()
+                .map(|at| at.timestamp_millis()),
+        })
+        .collect();
+    clock::clock_anomalies(
+        &readings,
+        car.created_at.timestamp_millis(),
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+fn budget_violations(car: &Car, checkpoints: &[ProcessCheckpointProof]) -> Vec<BudgetViolation> {
+    let usage: Vec<CheckpointUsage> = checkpoints
+        .iter()
+        .map(|checkpoint| CheckpointUsage {
+            usage_tokens: checkpoint.usage_tokens,
+            usd_cost: checkpoint.usd_cost,
+            nature_cost: checkpoint.nature_cost,
+        })
+        .collect();
+    let totals = |budgets: &model::Budgets| Totals {
+        tokens: budgets.tokens,
+        usd: budgets.usd,
+        nature_cost: budgets.nature_cost,
+    };
+    let limits = car.policy_ref.limits.as_ref().map(totals);
+    budget::budget_violations(&totals(&car.budgets), &usage, limits.as_ref())
+}
+
+/// `first` and the steps after it, skipped for `reason`
+fn skipped_steps(first: &str, reason: &str) -> Vec<WorkflowStep> {
+    let start = STEPS.iter().position(|(key, _)| *key == first).unwrap_or(0);
+    STEPS[start..]
+        .iter()
         .map(|(key, label)| WorkflowStep::skipped(key, label, reason))
         .collect()
 }
@@ -586,6 +1593,10 @@ struct DecodedCar {
     car: Car,
     raw_json: String,
     attachments: Vec<Attachment>,
+    /// Read from a ZIP bundle or an attachment directory, which must carry
+    /// every listed attachment
+    bundled: bool,
+    limit_exceeded: Option<LimitExceeded>,
 }
 
 struct Attachment {
@@ -598,6 +1609,8 @@ struct Attachment {
 pub enum VerificationStatus {
     Verified,
     Failed,
+    LimitExceeded,
+    UnsupportedVersion,
 }
 
 #[derive(Serialize)]
@@ -606,12 +1619,30 @@ pub struct VerificationReport {
     pub car_id: String,
     pub run_id: String,
     pub created_at: String,
+    pub format_version: u32,
     pub signer: Option<SignerSummary>,
+    /// The author, then each reviewer or organization that countersigned;
+    /// empty unless the signatures verified
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signers: Vec<SignerCheck>,
     pub model: ModelSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_environment: Option<RunEnvironment>,
+    /// Files the CAR binds without bundling them; the report does not check
+    /// them, `ExternalFileHasher` checks copies the user supplies
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_attachments: Vec<ExternalAttachment>,
     pub steps: Vec<WorkflowStep>,
     pub summary: SummaryMetrics,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_exceeded: Option<LimitExceeded>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsupported_format: Option<UnsupportedFormatVersion>,
+    /// Per-checkpoint records, in detail mode only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoints: Option<Vec<CheckpointRecord>>,
 }
 
 #[derive(Serialize)]
@@ -619,6 +1650,14 @@ pub struct SignerSummary {
     pub public_key: String,
 }
 
+/// A signer of the CAR and whether their signature holds
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SignerCheck {
+    pub role: SignerRole,
+    pub public_key: String,
+    pub valid: bool,
+}
+
 #[derive(Serialize)]
 pub struct ModelSummary {
     pub name: String,
@@ -632,8 +1671,17 @@ pub struct SummaryMetrics {
     pub checkpoints_total: usize,
     pub provenance_verified: usize,
     pub provenance_total: usize,
+    /// Claims of types this verifier has no validator for
+    pub provenance_unvalidated: usize,
     pub attachments_verified: usize,
     pub attachments_total: usize,
+    /// Fields and attachments withheld behind commitments; the CAR still
+    /// verifies "with N redactions"
+    pub redactions: usize,
+    /// Semantic digests recomputed from the outputs the bundle carries
+    pub semantic_digests_recomputed: usize,
+    /// Chat messages of interactive checkpoints checked against the chain
+    pub transcript_messages: usize,
     pub hash_chain_valid: bool,
     pub signatures_valid: bool,
     pub content_integrity_valid: bool,
@@ -648,6 +1696,9 @@ pub struct WorkflowStep {
     pub details: Vec<StepDetail>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Diagnostics of a failed step, in explain mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<StepExplanation>,
 }
 
 impl WorkflowStep {
@@ -658,6 +1709,7 @@ impl WorkflowStep {
             status: StepStatus::Passed,
             details,
             error: None,
+            explanation: None,
         }
     }
 
@@ -668,6 +1720,18 @@ impl WorkflowStep {
             status: StepStatus::Failed,
             details: Vec::new(),
             error: Some(message.to_string()),
+            explanation: None,
+        }
+    }
+
+    fn warning(key: &'static str, label: &'static str, details: Vec<StepDetail>) -> Self {
+        Self {
+            key,
+            label,
+            status: StepStatus::Warning,
+            details,
+            error: None,
+            explanation: None,
         }
     }
 
@@ -678,8 +1742,14 @@ impl WorkflowStep {
             status: StepStatus::Skipped,
             details: Vec::new(),
             error: Some(reason.to_string()),
+            explanation: None,
         }
     }
+
+    fn explained(mut self, explanation: Option<StepExplanation>) -> Self {
+        self.explanation = explanation;
+        self
+    }
 }
 
 #[derive(Serialize)]
@@ -703,6 +1773,8 @@ pub enum StepStatus {
     Passed,
     Failed,
     Skipped,
+    /// Suspicious but not proof of tampering; does not fail the report
+    Warning,
 }
 
 #[cfg(test)]
@@ -710,6 +1782,7 @@ mod tests {
     use super::*;
 
     use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use model::TranscriptMessage;
 
     const SAMPLE_JSON: &[u8] = include_bytes!("../tests/fixtures/sample.car.json");
     const SAMPLE_ZIP_BASE64: &str = concat!(
@@ -730,6 +1803,16 @@ mod tests {
         "OWVkNzJiNDc4N2YzYzI4YjI5ZWFhMTAzOTcwNzg2NzU1Yzk3MTFjYmIxOWJlNjMxYy50eHRQSwUGAAAAAAIAAgC0AAAAwQQAAAAA",
     );
 
+    #[test]
+    fn canonical_json_matches_the_shared_vectors() {
+        for vector in intelexta_canon::vectors() {
+            let input: Value = serde_json::from_str(&vector.input).unwrap();
+            let canonical = intelexta_verify_core::canonical_json(&input).unwrap();
+            let canonical = String::from_utf8(canonical).unwrap();
+            assert_eq!(canonical, vector.canonical, "{}", vector.name);
+        }
+    }
+
     fn sample_zip_bytes() -> Vec<u8> {
         STANDARD
             .decode(SAMPLE_ZIP_BASE64.as_bytes())
@@ -761,4 +1844,427 @@ mod tests {
             report.summary.attachments_total
         );
     }
+
+    #[test]
+    fn checkpoint_limit_is_reported_separately() {
+        let budget = Budget::new(VerificationLimits {
+            max_checkpoints: Some(0),
+            ..VerificationLimits::default()
+        });
+        let decoded = decode_car_with_limits(SAMPLE_JSON, &budget).expect("decode json");
+        let report = verify_car_with_budget(decoded, &budget, false).expect("verify json");
+        assert!(matches!(report.status, VerificationStatus::LimitExceeded));
+        assert_eq!(report.limit_exceeded.map(|limit| limit.limit), Some("max_checkpoints"));
+        assert!(!report.summary.hash_chain_valid);
+    }
+
+    #[test]
+    fn attachment_byte_limit_stops_before_reading() {
+        use std::io::Write;
+        use zip::write::FileOptions;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("car.json", FileOptions::default()).unwrap();
+        zip.write_all(SAMPLE_JSON).unwrap();
+        zip.start_file("attachments/abc.txt", FileOptions::default()).unwrap();
+        zip.write_all(b"attachment body").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let budget = Budget::new(VerificationLimits {
+            max_attachment_bytes: Some(4),
+            ..VerificationLimits::default()
+        });
+        let decoded = decode_car_with_limits(&bytes, &budget).expect("decode zip");
+        assert!(decoded.attachments.is_empty());
+        assert_eq!(
+            decoded.limit_exceeded.as_ref().map(|limit| limit.limit),
+            Some("max_attachment_bytes")
+        );
+    }
+
+    #[test]
+    fn explain_mode_locates_the_broken_checkpoint() {
+        let mut car: Value = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let recorded = car["proof"]["process"]["sequential_checkpoints"][0]["curr_chain"].clone();
+        car["proof"]["process"]["sequential_checkpoints"][0]["outputs_sha256"] =
+            Value::from("00".repeat(32));
+        let tampered = serde_json::to_vec(&car).unwrap();
+        let budget = Budget::new(VerificationLimits::default());
+
+        let terse = verify_car_with_budget(decode_car(&tampered).unwrap(), &budget, false).unwrap();
+        assert!(terse.steps.iter().all(|step| step.explanation.is_none()));
+
+        let report = verify_car_with_budget(decode_car(&tampered).unwrap(), &budget, true).unwrap();
+        assert!(matches!(report.steps[0].status, StepStatus::Failed));
+        let explanation = report.steps[0].explanation.as_ref().expect("explained failure");
+        assert_eq!(
+            explanation.checkpoint.as_ref().map(|checkpoint| checkpoint.id.as_str()),
+            Some("ckpt-1")
+        );
+        assert_eq!(explanation.expected.as_deref(), recorded.as_str());
+        assert!(explanation.computed.is_some() && explanation.computed != explanation.expected);
+        assert!(!explanation.remediation.is_empty());
+        // Skipped steps are not explained
+        assert!(report.steps[1..].iter().all(|step| step.explanation.is_none()));
+    }
+
+    #[test]
+    fn continue_on_error_runs_every_step_after_a_failure() {
+        let mut car: Value = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        car["proof"]["process"]["sequential_checkpoints"][0]["usage_tokens"] = Value::from(1);
+        let tampered = serde_json::to_vec(&car).unwrap();
+        let statuses = |report: &VerificationReport| -> Vec<(&str, bool)> {
+            let steps = report.steps.iter();
+            steps.map(|step| (step.key, matches!(step.status, StepStatus::Skipped))).collect()
+        };
+
+        let budget = Budget::new(VerificationLimits::default());
+        let stopped = verify_car_with_budget(decode_car(&tampered).unwrap(), &budget, false);
+        let stopped = stopped.unwrap();
+        assert!(statuses(&stopped)[1..].iter().all(|(_, skipped)| *skipped));
+
+        let budget = Budget::new(VerificationLimits {
+            continue_on_error: true,
+            ..VerificationLimits::default()
+        });
+        let report = verify_car_with_budget(decode_car(&tampered).unwrap(), &budget, false);
+        let report = report.unwrap();
+        assert!(matches!(report.status, VerificationStatus::Failed));
+        assert_eq!(report.error, stopped.error);
+        assert!(statuses(&report).iter().all(|(_, skipped)| !*skipped));
+        assert!(matches!(report.steps[0].status, StepStatus::Failed));
+        assert!(report.summary.signatures_valid);
+    }
+
+    #[test]
+    fn checkpoint_clock_anomalies_only_warn() {
+        let report = verify_car(decode_car(SAMPLE_JSON).unwrap()).unwrap();
+        let clock = report.steps.iter().find(|step| step.key == "clock").unwrap();
+        assert!(matches!(clock.status, StepStatus::Passed));
+
+        let mut car: Car = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let checkpoints = &mut car.proof.process.as_mut().unwrap().sequential_checkpoints;
+        checkpoints[0].timestamp = "2999-01-01T00:00:00Z".to_string();
+        let checkpoints = checkpoints.clone();
+        let anomalies = clock_anomalies(&car, &checkpoints);
+        assert!(anomalies.contains(&ClockAnomaly::InFuture {
+            index: 0,
+            id: checkpoints[0].id.clone(),
+        }));
+    }
+
+    #[test]
+    fn usage_is_recomputed_and_held_to_the_policy_budgets() {
+        // The fixture records 100 tokens for a checkpoint that used 10
+        let report = verify_car(decode_car(SAMPLE_JSON).unwrap()).unwrap();
+        let policy = report.steps.iter().find(|step| step.key == "policy").unwrap();
+        assert!(matches!(policy.status, StepStatus::Warning));
+        assert_eq!(policy.details.len(), 1);
+
+        let mut car: Car = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let checkpoints = car.proof.process.clone().unwrap().sequential_checkpoints;
+        car.budgets.tokens = 10;
+        assert!(budget_violations(&car, &checkpoints).is_empty());
+
+        car.policy_ref.limits = Some(model::Budgets {
+            usd: 1.0,
+            tokens: 5,
+            nature_cost: 1.0,
+        });
+        let violations = budget_violations(&car, &checkpoints);
+        assert!(violations.iter().any(|violation| matches!(
+            violation,
+            BudgetViolation::Exceeded {
+                budget: budget::Budget::Tokens,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn semantic_digests_are_recomputed_from_bundled_outputs() {
+        let mut car: Car = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let output = "The replayed summary, close enough to the original";
+        let hash = hex::encode(Sha256::digest(output.as_bytes()));
+        let checkpoint = &mut car.proof.process.as_mut().unwrap().sequential_checkpoints[0];
+        checkpoint.outputs_sha256 = Some(hash.clone());
+        checkpoint.semantic_digest = Some("0000000000000000".to_string());
+        let outputs = HashMap::from([(hash.as_str(), output)]);
+
+        let (issues, recomputed) = semantic_issues(&car, &outputs);
+        assert_eq!(recomputed, 1);
+        assert!(matches!(issues.as_slice(), [SemanticIssue::DigestMismatch { .. }]));
+
+        let checkpoint = &mut car.proof.process.as_mut().unwrap().sequential_checkpoints[0];
+        checkpoint.semantic_digest = Some(semantic::semantic_digest(output));
+        assert!(semantic_issues(&car, &outputs).0.is_empty());
+        // Without the output the digest is only checked for form
+        assert_eq!(semantic_issues(&car, &HashMap::new()), (Vec::new(), 0));
+    }
+
+    #[test]
+    fn chat_messages_are_checked_against_their_checkpoints() {
+        let mut car: Car = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let reply = "Four.";
+        let process = car.proof.process.as_mut().unwrap();
+        let mut human = process.sequential_checkpoints[0].clone();
+        human.id = "turn-0".to_string();
+        human.turn_index = Some(0);
+        human.inputs_sha256 = None;
+        human.message = Some(TranscriptMessage {
+            role: "human".to_string(),
+            body: Some("What is 2 + 2?".to_string()),
+        });
+        let mut ai = human.clone();
+        ai.id = "turn-1".to_string();
+        ai.parent_checkpoint_id = Some(human.id.clone());
+        ai.turn_index = Some(1);
+        ai.outputs_sha256 = Some(hex::encode(Sha256::digest(reply.as_bytes())));
+        ai.message = Some(TranscriptMessage {
+            role: "ai".to_string(),
+            body: Some(reply.to_string()),
+        });
+        process.sequential_checkpoints.extend([human, ai]);
+
+        let checked = transcript::check_transcript(&transcript_entries(&car)).unwrap();
+        assert_eq!((checked.messages, checked.unbound), (2, 1));
+
+        let process = car.proof.process.as_mut().unwrap();
+        let ai = process.sequential_checkpoints.last_mut().unwrap();
+        ai.message.as_mut().unwrap().body = Some("Five.".to_string());
+        assert!(transcript::check_transcript(&transcript_entries(&car)).is_err());
+    }
+
+    #[test]
+    fn schema_validated_checkpoints_hash_their_schema() {
+        let car: Value = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let mut checkpoint = car["proof"]["process"]["sequential_checkpoints"][0].clone();
+        let schema_hash = hex::encode(Sha256::digest(br#"{"type":"object"}"#));
+        // The body the app hashes when it records a schema-validated step
+        let body = serde_json::json!({
+            "run_id": checkpoint["run_id"],
+            "kind": checkpoint["kind"],
+            "timestamp": checkpoint["timestamp"],
+            "inputs_sha256": checkpoint["inputs_sha256"],
+            "outputs_sha256": checkpoint["outputs_sha256"],
+            "incident": null,
+            "usage_tokens": checkpoint["usage_tokens"],
+            "prompt_tokens": checkpoint["prompt_tokens"],
+            "completion_tokens": checkpoint["completion_tokens"],
+            "output_schema_sha256": schema_hash,
+        });
+        let canonical = intelexta_verify_core::canonical_json(&body).unwrap();
+        let prev_chain = checkpoint["prev_chain"].as_str().unwrap().to_string();
+        let chained = [prev_chain.as_bytes(), &canonical].concat();
+        checkpoint["curr_chain"] = Value::from(hex::encode(Sha256::digest(chained)));
+        checkpoint["output_schema_sha256"] = Value::from(schema_hash);
+
+        let proof: ProcessCheckpointProof = serde_json::from_value(checkpoint).unwrap();
+        assert!(intelexta_verify_core::check_chain_link(0, &proof).is_ok());
+
+        let mut unschematized = proof.clone();
+        unschematized.output_schema_sha256 = None;
+        assert!(intelexta_verify_core::check_chain_link(0, &unschematized).is_err());
+    }
+
+    #[test]
+    fn cbor_cars_decode_to_the_json_they_were_encoded_from() {
+        let car: Value = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let mut encoded = Vec::new();
+        ciborium::ser::into_writer(
+            &serde_json::json!({"format": CAR_CBOR_FORMAT, "version": 1, "car": car}),
+            &mut encoded,
+        )
+        .unwrap();
+
+        let decoded = decode_car(&encoded).expect("decode cbor");
+        assert_eq!(serde_json::from_str::<Value>(&decoded.raw_json).unwrap(), car);
+        assert_eq!(Value::from(decoded.car.id), car["id"]);
+
+        let mut other_version = Vec::new();
+        ciborium::ser::into_writer(
+            &serde_json::json!({"format": CAR_CBOR_FORMAT, "version": 2, "car": car}),
+            &mut other_version,
+        )
+        .unwrap();
+        assert!(decode_car(&other_version).is_err());
+    }
+
+    #[test]
+    fn explained_attachment_mismatch_names_the_file_and_hashes() {
+        let expected = hex::encode(Sha256::digest(b"original"));
+        let attachment = Attachment {
+            name: format!("attachments/{expected}.txt"),
+            data: b"replaced".to_vec(),
+        };
+        let err = verify_all_attachments(
+            std::slice::from_ref(&attachment),
+            &Budget::new(VerificationLimits::default()),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("Attachment content mismatch"));
+
+        let explanation = diagnostics::attachments(&err);
+        assert_eq!(explanation.subject.as_deref(), Some(attachment.name.as_str()));
+        assert_eq!(explanation.expected.as_deref(), Some(expected.as_str()));
+        assert_eq!(
+            explanation.computed,
+            Some(hex::encode(Sha256::digest(b"replaced")))
+        );
+    }
+
+    #[test]
+    fn attachments_must_match_the_signed_manifest() {
+        let attachment = |text: &str| Attachment {
+            name: format!("attachments/{}.txt", hex::encode(Sha256::digest(text))),
+            data: text.as_bytes().to_vec(),
+        };
+        let hash = |attachment: &Attachment| attachment.name[12..76].to_string();
+        let (first, second) = (attachment("first"), attachment("second"));
+        let hashes = merkle::leaves(&[hash(&first), hash(&second)]);
+        let mut car = decode_car(SAMPLE_JSON).expect("decode json").car;
+        car.attachments = Some(model::AttachmentManifest {
+            merkle_root: format!("sha256:{}", merkle::root(&hashes)),
+            hashes,
+            compression: None,
+        });
+
+        let both = [first, second];
+        assert!(verify_attachment_manifest(&car, &both, true).is_ok());
+        // Only a bundle has to carry every listed attachment
+        assert!(verify_attachment_manifest(&car, &[], false).is_ok());
+        let removed = verify_attachment_manifest(&car, &both[..1], true).unwrap_err();
+        assert!(removed.to_string().contains("missing from the bundle"));
+
+        let added = [attachment("slipped in")];
+        let err = verify_attachment_manifest(&car, &added, false).unwrap_err();
+        assert!(err.to_string().contains("not in the CAR's attachment manifest"));
+
+        let compressed = Attachment {
+            name: format!("{}.zst", both[0].name),
+            data: both[0].data.clone(),
+        };
+        let err = verify_attachment_manifest(&car, &[compressed], false).unwrap_err();
+        assert!(err.to_string().contains("not encoded as"));
+
+        car.attachments.as_mut().unwrap().hashes.pop();
+        let err = verify_attachment_manifest(&car, &[], false).unwrap_err();
+        assert!(err.to_string().contains("Merkle root mismatch"));
+    }
+
+    #[test]
+    fn attachment_directories_are_read_by_file_name() {
+        let budget = Budget::new(VerificationLimits::default());
+        let file = |text: &str| format!("{}.txt", hex::encode(Sha256::digest(text)));
+        let files = vec![
+            (format!("receipt/attachments/{}", file("second")), b"second".to_vec()),
+            (file("first"), b"first".to_vec()),
+            ("receipt/.DS_Store".to_string(), Vec::new()),
+        ];
+        let decoded = load_car_with_attachments(SAMPLE_JSON, files, &budget).unwrap();
+        assert!(decoded.bundled);
+        let names: Vec<&str> = decoded.attachments.iter().map(|a| a.name.as_str()).collect();
+        let mut expected = [
+            format!("attachments/{}", file("first")),
+            format!("attachments/{}", file("second")),
+        ];
+        expected.sort();
+        assert_eq!(names, expected);
+        assert_eq!(verify_all_attachments(&decoded.attachments, &budget).unwrap(), 2);
+
+        let swapped = vec![(file("first"), b"second".to_vec())];
+        let decoded = load_car_with_attachments(SAMPLE_JSON, swapped, &budget).unwrap();
+        let err = verify_all_attachments(&decoded.attachments, &budget).unwrap_err();
+        assert!(err.to_string().starts_with("Attachment content mismatch"));
+    }
+
+    #[test]
+    fn external_files_are_hashed_in_slices_and_checked_against_the_car() {
+        let content = b"a corpus too large to bundle";
+        let expected = ExternalAttachment {
+            uri: "file:///data/corpus.txt".to_string(),
+            sha256: hex::encode(Sha256::digest(content)),
+            size: content.len() as u64,
+        };
+        let hash = |slices: &[&[u8]]| {
+            let mut hasher = ExternalFileHasher::new();
+            slices.iter().for_each(|slice| hasher.update(slice));
+            hasher.check(&expected)
+        };
+
+        let check = hash(&[&content[..8], &content[8..]]);
+        assert!(check.verified, "{:?}", check.error);
+        assert_eq!(check.computed_sha256, expected.sha256);
+
+        let altered = hash(&[b"a corpus too large to bundlE"]);
+        assert!(altered.error.unwrap().contains("Content mismatch"));
+        let truncated = hash(&[&content[..8]]);
+        assert!(!truncated.verified);
+        assert!(truncated.error.unwrap().contains("8 bytes"));
+    }
+
+    #[test]
+    fn unknown_format_versions_are_reported_not_failed() {
+        let mut car: Value = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        car["format_version"] = serde_json::json!(99);
+        let report = verify_car(decode_car(&serde_json::to_vec(&car).unwrap()).unwrap()).unwrap();
+
+        assert!(matches!(report.status, VerificationStatus::UnsupportedVersion));
+        assert_eq!(report.format_version, 99);
+        let unsupported = report.unsupported_format.expect("unsupported format");
+        assert_eq!(unsupported.supported, vec![1, 2, 3]);
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| matches!(step.status, StepStatus::Skipped)));
+    }
+
+    #[test]
+    fn sessions_buffer_slices_and_report_each_stage() {
+        use std::future::Future;
+        use std::task::{Context, Poll, Waker};
+
+        let mut session = VerifierSession::with_limits(VerificationLimits::default(), false, None);
+        for slice in SAMPLE_JSON.chunks(100) {
+            session.append(slice).unwrap();
+        }
+        assert_eq!(session.buffer, SAMPLE_JSON);
+
+        let budget = Budget::new(session.limits.clone());
+        let mut stages = Vec::new();
+        let mut record = |progress| stages.push(progress);
+        // Without attachments there is nothing to yield between, so the
+        // first poll completes
+        let polled = std::pin::pin!(verify_staged(&session.buffer, &budget, session.mode, &mut record))
+            .poll(&mut Context::from_waker(Waker::noop()));
+        let Poll::Ready(report) = polled else {
+            panic!("verification of a JSON CAR yielded");
+        };
+        let report = report.unwrap();
+
+        let checkpoints = report.summary.checkpoints_total as u64;
+        assert_eq!(stages[0], Progress::started("decoding"));
+        assert_eq!(stages[1], Progress::of("hash_chain", 0, Some(checkpoints)));
+        assert_eq!(stages.last(), Some(&Progress::of("complete", 1, Some(1))));
+    }
+
+    #[test]
+    fn execution_sections_are_verified_one_by_one() {
+        let budget = Budget::new(VerificationLimits::default());
+        let mut car = decode_car(SAMPLE_JSON).expect("decode json").car;
+        let process = car.proof.process.clone().expect("process proof");
+        let section = |id: &str| model::ExecutionProof {
+            run_execution_id: id.to_string(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            budgets: car.budgets.clone(),
+            run_environment: None,
+            process: process.clone(),
+        };
+        car.proof.executions = vec![section("e1"), section("e2")];
+        assert_eq!(verify_execution_sections(&car, &budget).unwrap(), 2);
+
+        car.proof.executions[1].process.sequential_checkpoints[0].usage_tokens += 1;
+        let err = verify_execution_sections(&car, &budget).unwrap_err();
+        assert!(err.to_string().starts_with("Execution e2"));
+    }
 }
@@ -1,9 +1,177 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Format version of CARs written before `format_version` existed
+pub const LEGACY_FORMAT_VERSION: u32 = 1;
+
+/// What a CAR of one format version must carry to verify; mirrors
+/// `FORMAT_VERSIONS` in the desktop app's `car` module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub version: u32,
+    pub body_signature_required: bool,
+    pub checkpoint_signature_required: bool,
+}
+
+/// Format versions this verifier accepts
+pub const FORMAT_VERSIONS: &[FormatSpec] = &[
+    FormatSpec {
+        version: LEGACY_FORMAT_VERSION,
+        body_signature_required: false,
+        checkpoint_signature_required: false,
+    },
+    FormatSpec {
+        version: 2,
+        body_signature_required: true,
+        checkpoint_signature_required: true,
+    },
+    FormatSpec {
+        version: 3,
+        body_signature_required: true,
+        checkpoint_signature_required: true,
+    },
+];
+
+/// A CAR written in a format version this verifier does not know
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFormatVersion {
+    pub found: u32,
+    pub supported: Vec<u32>,
+}
+
+impl std::fmt::Display for UnsupportedFormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let supported: Vec<String> = self.supported.iter().map(u32::to_string).collect();
+        write!(
+            f,
+            "CAR format version {} is not supported (this verifier supports {})",
+            self.found,
+            supported.join(", ")
+        )
+    }
+}
+
+pub fn negotiate_format(version: u32) -> Result<FormatSpec, UnsupportedFormatVersion> {
+    FORMAT_VERSIONS
+        .iter()
+        .find(|spec| spec.version == version)
+        .copied()
+        .ok_or_else(|| UnsupportedFormatVersion {
+            found: version,
+            supported: FORMAT_VERSIONS.iter().map(|spec| spec.version).collect(),
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    /// `ed25519-body:`, the canonical body without `signatures`
+    Body,
+    /// `ed25519-checkpoint:`, or bare `ed25519:` in legacy CARs: the CAR ID
+    Checkpoint,
+}
+
+/// Kind and base64 signature of a `signatures` entry
+pub fn parse_signature(entry: &str) -> Option<(SignatureKind, &str)> {
+    match entry.split_once(':')? {
+        ("ed25519-body", encoded) => Some((SignatureKind::Body, encoded)),
+        ("ed25519-checkpoint" | "ed25519", encoded) => Some((SignatureKind::Checkpoint, encoded)),
+        _ => None,
+    }
+}
+
+impl FormatSpec {
+    /// `Err` names the first signature the format requires that `signatures` lacks
+    pub fn check_signatures(&self, signatures: &[String]) -> Result<(), String> {
+        let has = |kind| {
+            signatures
+                .iter()
+                .any(|entry| parse_signature(entry).is_some_and(|(found, _)| found == kind))
+        };
+        if self.body_signature_required && !has(SignatureKind::Body) {
+            return Err(format!(
+                "format version {} CARs must carry an ed25519-body signature",
+                self.version
+            ));
+        }
+        if self.checkpoint_signature_required && !has(SignatureKind::Checkpoint) {
+            return Err(format!(
+                "format version {} CARs must carry an ed25519-checkpoint signature",
+                self.version
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Prefix of the countersignatures reviewers add to an emitted CAR:
+/// `ed25519-cosign:<role>:<base64 public key>:<base64 signature>`
+pub const COSIGNATURE_PREFIX: &str = "ed25519-cosign:";
+
+/// Who a CAR signature speaks for
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRole {
+    Author,
+    Reviewer,
+    Organization,
+}
+
+impl SignerRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignerRole::Author => "author",
+            SignerRole::Reviewer => "reviewer",
+            SignerRole::Organization => "organization",
+        }
+    }
+}
+
+/// A countersignature of an emitted CAR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cosignature {
+    pub role: SignerRole,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl Cosignature {
+    /// `None` for entries that are not co-signatures, `Some(Err)` for
+    /// malformed ones
+    pub fn parse(entry: &str) -> Option<Result<Self, String>> {
+        let fields = entry.strip_prefix(COSIGNATURE_PREFIX)?;
+        let mut parts = fields.splitn(3, ':');
+        let (Some(role), Some(public_key), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Some(Err(format!("malformed co-signature '{entry}'")));
+        };
+        let role = match role {
+            "reviewer" => SignerRole::Reviewer,
+            "organization" => SignerRole::Organization,
+            other => return Some(Err(format!("co-signature has unknown role '{other}'"))),
+        };
+        Some(Ok(Self {
+            role,
+            public_key: public_key.to_string(),
+            signature: signature.to_string(),
+        }))
+    }
+
+    /// What the co-signer signs: the CAR id bound to their role
+    pub fn message(car_id: &str, role: SignerRole) -> Vec<u8> {
+        format!("{COSIGNATURE_PREFIX}{}:{car_id}", role.as_str()).into_bytes()
+    }
+}
+
+fn legacy_format_version() -> u32 {
+    LEGACY_FORMAT_VERSION
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Car {
     pub id: String,
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
     pub run_id: String,
     pub created_at: DateTime<Utc>,
     pub run: RunInfo,
@@ -12,11 +180,99 @@ pub struct Car {
     pub budgets: Budgets,
     pub provenance: Vec<ProvenanceClaim>,
     pub checkpoints: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<AttachmentManifest>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_attachments: Vec<ExternalAttachment>,
     pub sgrade: SGrade,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_environment: Option<RunEnvironment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
     pub signer_public_key: String,
     pub signatures: Vec<String>,
 }
 
+/// Attachments of the CAR's bundle, bound to the body by their Merkle root
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttachmentManifest {
+    pub hashes: Vec<String>,
+    pub merkle_root: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<AttachmentCompression>,
+}
+
+/// How the bundle stores attachment content; hashes are always over the
+/// decompressed bytes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentCompression {
+    /// One zstd frame per attachment, in `attachments/<hash>.txt.zst`
+    Zstd,
+}
+
+impl AttachmentCompression {
+    /// Encoding of a bundle entry, from its name
+    pub fn of_entry(name: &str) -> Option<Self> {
+        name.ends_with(".zst").then_some(Self::Zstd)
+    }
+
+    /// `Err` for corrupt input or content larger than `max_bytes`
+    pub fn decompress(self, bytes: &[u8], max_bytes: u64) -> Result<Vec<u8>, String> {
+        use std::io::Read;
+        let decoder = match self {
+            Self::Zstd => ruzstd::decoding::StreamingDecoder::new(bytes)
+                .map_err(|err| format!("invalid zstd attachment: {err}"))?,
+        };
+        let mut content = Vec::new();
+        decoder
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(&mut content)
+            .map_err(|err| format!("invalid zstd attachment: {err}"))?;
+        if content.len() as u64 > max_bytes {
+            return Err(format!("attachment decompresses to more than {max_bytes} bytes"));
+        }
+        Ok(content)
+    }
+}
+
+/// A file the CAR binds by content hash without bundling it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExternalAttachment {
+    pub uri: String,
+    /// Hex sha256 of the file content
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// A field or attachment withheld behind a salted sha256 commitment
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Redaction {
+    pub target: String,
+    pub commitment: String,
+}
+
+impl Redaction {
+    /// Value left in place of a redacted field
+    pub fn marker(&self) -> String {
+        format!("redacted:{}", self.commitment)
+    }
+}
+
+/// Where the run executed, as recorded when the execution started
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunEnvironment {
+    pub os: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub app_version: String,
+    pub model_catalog_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ollama_version: Option<String>,
+    pub machine_fingerprint: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RunInfo {
@@ -90,6 +346,9 @@ pub struct Proof {
     pub replay_semantic_digest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub process: Option<ProcessProof>,
+    /// One section per execution in multi-execution CARs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executions: Vec<ExecutionProof>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -97,6 +356,16 @@ pub struct ProcessProof {
     pub sequential_checkpoints: Vec<ProcessCheckpointProof>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionProof {
+    pub run_execution_id: String,
+    pub created_at: String,
+    pub budgets: Budgets,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_environment: Option<RunEnvironment>,
+    pub process: ProcessProof,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProcessCheckpointProof {
     pub id: String,
@@ -117,6 +386,74 @@ pub struct ProcessCheckpointProof {
     pub usage_tokens: u64,
     pub prompt_tokens: u64,
     pub completion_tokens: u64,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+    /// Provider, finish reason, tool calls and request ID of a model response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_metadata: Option<serde_json::Value>,
+    /// Canonical hash of the run inputs, carried by the first checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_inputs_sha256: Option<String>,
+    /// Hash of the JSON Schema the step's output was validated against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema_sha256: Option<String>,
+    /// Cost priced from the model catalog when the checkpoint was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nature_cost: Option<f64>,
+    /// SimHash of the output text, outside the hashed body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_digest: Option<String>,
+    /// The human or AI message of an interactive turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<TranscriptMessage>,
+}
+
+/// A chat message; its body is absent when redacted
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TranscriptMessage {
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl intelexta_verify_core::ChainedCheckpoint for ProcessCheckpointProof {
+    type Metadata = serde_json::Value;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn prev_chain(&self) -> &str {
+        &self.prev_chain
+    }
+
+    fn curr_chain(&self) -> &str {
+        &self.curr_chain
+    }
+
+    fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    fn body(&self) -> intelexta_verify_core::CheckpointBody<'_> {
+        intelexta_verify_core::CheckpointBody {
+            run_id: &self.run_id,
+            kind: &self.kind,
+            timestamp: self.timestamp.clone(),
+            inputs_sha256: self.inputs_sha256.as_deref(),
+            outputs_sha256: self.outputs_sha256.as_deref(),
+            incident: None,
+            usage_tokens: self.usage_tokens,
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            cache_hit: self.cache_hit,
+            response_metadata: self.response_metadata.as_ref(),
+            run_inputs_sha256: self.run_inputs_sha256.as_deref(),
+            output_schema_sha256: self.output_schema_sha256.as_deref(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -128,6 +465,32 @@ pub struct PolicyRef {
     pub model_catalog_hash: String,
     #[serde(default = "default_catalog_version")]
     pub model_catalog_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<Budgets>,
+    /// Default and project policy hashes the policy was resolved from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layers: Option<PolicyLayers>,
+    /// Providers and regions the policy confined steps' models to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_allowlist: Option<ProviderAllowlist>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PolicyLayers {
+    pub default_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAllowlist {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_regions: Vec<String>,
 }
 
 fn default_catalog_hash() -> String {
@@ -149,12 +512,23 @@ pub struct Budgets {
 pub struct ProvenanceClaim {
     pub claim_type: String,
     pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SGrade {
     pub score: u8,
     pub components: SGradeComponents,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<SGradeInputs>,
+}
+
+/// Emissions of the run's checkpoints and the grid region local models were priced against
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SGradeInputs {
+    pub g_co2e: f64,
+    pub grid_region: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -0,0 +1,95 @@
+//! Per-checkpoint records for the detail mode
+//!
+//! The report's summary only counts verified checkpoints. In detail mode it
+//! also lists every checkpoint of the run with its hashes, token usage and
+//! the outcome of its own chain and signature checks, so the page can render
+//! an inspectable timeline. A record is checked on its own: one broken link
+//! does not mark the checkpoints after it.
+
+use intelexta_verify_core::{check_chain_link, check_checkpoint_signature, decode_verifying_key};
+use serde::Serialize;
+
+use crate::model::ProcessCheckpointProof;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CheckpointRecord {
+    pub index: usize,
+    pub id: String,
+    pub kind: String,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_checkpoint_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs_sha256: Option<String>,
+    pub prev_chain: String,
+    pub curr_chain: String,
+    /// The checkpoint hashes to `curr_chain`; incidents are not re-hashed
+    pub chain_valid: bool,
+    /// The CAR's signer signed `curr_chain`
+    pub signature_valid: bool,
+    pub usage_tokens: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cache_hit: bool,
+}
+
+pub fn checkpoint_records(
+    checkpoints: &[ProcessCheckpointProof],
+    signer_public_key: &str,
+) -> Vec<CheckpointRecord> {
+    let key = decode_verifying_key(signer_public_key).ok();
+    checkpoints
+        .iter()
+        .enumerate()
+        .map(|(index, checkpoint)| CheckpointRecord {
+            index,
+            id: checkpoint.id.clone(),
+            kind: checkpoint.kind.clone(),
+            timestamp: checkpoint.timestamp.clone(),
+            parent_checkpoint_id: checkpoint.parent_checkpoint_id.clone(),
+            turn_index: checkpoint.turn_index,
+            inputs_sha256: checkpoint.inputs_sha256.clone(),
+            outputs_sha256: checkpoint.outputs_sha256.clone(),
+            prev_chain: checkpoint.prev_chain.clone(),
+            curr_chain: checkpoint.curr_chain.clone(),
+            chain_valid: check_chain_link(index, checkpoint).is_ok(),
+            signature_valid: key
+                .as_ref()
+                .is_some_and(|key| check_checkpoint_signature(index, checkpoint, key).is_ok()),
+            usage_tokens: checkpoint.usage_tokens,
+            prompt_tokens: checkpoint.prompt_tokens,
+            completion_tokens: checkpoint.completion_tokens,
+            cache_hit: checkpoint.cache_hit,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_JSON: &[u8] = include_bytes!("../tests/fixtures/sample.car.json");
+
+    #[test]
+    fn a_tampered_checkpoint_is_marked_alone() {
+        let car: crate::model::Car = serde_json::from_slice(SAMPLE_JSON).unwrap();
+        let mut checkpoints = car.proof.process.expect("process proof").sequential_checkpoints;
+        let honest = checkpoint_records(&checkpoints, &car.signer_public_key);
+        assert_eq!(honest.len(), checkpoints.len());
+        assert_eq!(honest[0].id, checkpoints[0].id);
+
+        checkpoints[0].usage_tokens += 1;
+        let records = checkpoint_records(&checkpoints, &car.signer_public_key);
+        assert!(!records[0].chain_valid);
+        assert_eq!(records[0].usage_tokens, checkpoints[0].usage_tokens);
+        for (record, before) in records.iter().zip(&honest).skip(1) {
+            assert_eq!(record.chain_valid, before.chain_valid);
+        }
+        // The signature covers curr_chain, which did not change
+        assert_eq!(records[0].signature_valid, honest[0].signature_valid);
+    }
+}
@@ -0,0 +1,163 @@
+//! Signer key pinning
+//!
+//! Mirrors `verify::TrustedKeys` in the desktop app: a trust store lists the
+//! ed25519 keys of known projects, in the format of the CLI's
+//! `--trusted-keys` file, and a verified CAR's signer is either one of them
+//! or unknown. The page keeps the store (e.g. in local storage) and checks
+//! `report.signer.public_key` against it after verification.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+struct TrustedKeys {
+    keys: Vec<TrustedKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct TrustedKey {
+    project: String,
+    public_key: String,
+}
+
+/// Whether a CAR's signer is one the trust store knows
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SignerTrust {
+    /// Verified by a key pinned for `project`
+    Trusted { project: String },
+    /// Self-consistent, but signed by a key the trust store does not list
+    Unknown { public_key: String },
+}
+
+/// Signer keys the user trusts
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct TrustStore {
+    keys: TrustedKeys,
+}
+
+#[wasm_bindgen]
+impl TrustStore {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a trust store in the CLI's `--trusted-keys` format
+    pub fn from_json(json: &str) -> Result<TrustStore, JsError> {
+        Self::parse(json).map_err(|err| JsError::new(&format!("{err:#}")))
+    }
+
+    /// Pin `public_key` (base64 ed25519) as a signer key of `project`
+    pub fn pin(&mut self, project: &str, public_key: &str) -> Result<(), JsError> {
+        self.add(project, public_key)
+            .map_err(|err| JsError::new(&format!("{err:#}")))
+    }
+
+    /// Forget a pinned key
+    pub fn unpin(&mut self, public_key: &str) {
+        let key = decode_key(public_key).ok();
+        self.keys.keys.retain(|pinned| {
+            pinned.public_key != public_key && decode_key(&pinned.public_key).ok() != key
+        });
+    }
+
+    /// The store in the `--trusted-keys` format, for saving or for the CLI
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.keys).unwrap_or_default()
+    }
+
+    /// Whether the store lists `public_key`, the signer key of a verified
+    /// report. Says nothing about whether the CAR's signatures hold.
+    pub fn check(&self, public_key: &str) -> Result<JsValue, JsError> {
+        serde_wasm_bindgen::to_value(&self.lookup(public_key))
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+}
+
+impl TrustStore {
+    fn parse(json: &str) -> Result<Self> {
+        let keys: TrustedKeys =
+            serde_json::from_str(json).context("trusted keys are not valid JSON")?;
+        for key in &keys.keys {
+            decode_key(&key.public_key)
+                .with_context(|| format!("trusted key of project {}", key.project))?;
+        }
+        Ok(Self { keys })
+    }
+
+    fn add(&mut self, project: &str, public_key: &str) -> Result<()> {
+        decode_key(public_key).with_context(|| format!("trusted key of project {project}"))?;
+        self.keys.keys.push(TrustedKey {
+            project: project.to_string(),
+            public_key: public_key.to_string(),
+        });
+        Ok(())
+    }
+
+    fn lookup(&self, public_key: &str) -> SignerTrust {
+        let signer = decode_key(public_key).ok();
+        let pinned = self
+            .keys
+            .keys
+            .iter()
+            .find(|key| signer.is_some() && decode_key(&key.public_key).ok() == signer);
+        match pinned {
+            Some(key) => SignerTrust::Trusted {
+                project: key.project.clone(),
+            },
+            None => SignerTrust::Unknown {
+                public_key: public_key.to_string(),
+            },
+        }
+    }
+}
+
+pub(crate) use intelexta_verify_core::decode_verifying_key as decode_key;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use ed25519_dalek::SigningKey;
+
+    fn key(seed: u8) -> String {
+        STANDARD.encode(
+            SigningKey::from_bytes(&[seed; 32])
+                .verifying_key()
+                .as_bytes(),
+        )
+    }
+
+    #[test]
+    fn pinned_keys_are_trusted_and_others_unknown() {
+        let mut store = TrustStore::parse(&format!(
+            r#"{{"keys": [{{"project": "nightly", "public_key": "{}"}}]}}"#,
+            key(1)
+        ))
+        .unwrap();
+        assert_eq!(
+            store.lookup(&key(1)),
+            SignerTrust::Trusted {
+                project: "nightly".to_string()
+            }
+        );
+        assert_eq!(
+            store.lookup(&key(2)),
+            SignerTrust::Unknown { public_key: key(2) }
+        );
+
+        store.add("rotated", &key(2)).unwrap();
+        assert!(matches!(store.lookup(&key(2)), SignerTrust::Trusted { .. }));
+        store.unpin(&key(1));
+        assert!(matches!(store.lookup(&key(1)), SignerTrust::Unknown { .. }));
+        assert_eq!(
+            TrustStore::parse(&store.to_json()).unwrap().keys,
+            store.keys
+        );
+
+        assert!(store.add("bad", "AAAA").is_err());
+    }
+}
@@ -8,5 +8,6 @@ fn main() {
 
     println!("cargo:rerun-if-changed=../schemas/car-v0.2.schema.json");
 
-    tauri_build::build()
+    #[cfg(feature = "app")]
+    tauri_build::build();
 }
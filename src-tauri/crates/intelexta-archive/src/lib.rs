@@ -0,0 +1,450 @@
+// src-tauri/crates/intelexta-archive/src/lib.rs
+//!
+//! Bounded ZIP reading for CAR bundles and project archives
+//!
+//! Archives handed to the importer and the verifiers are untrusted input.
+//! `SafeArchive` wraps `zip::ZipArchive` and refuses to hand out data unless:
+//!
+//! - every entry name is a plain relative path (no `..`, absolute paths,
+//!   drive letters, backslashes or NUL bytes)
+//! - the archive holds at most `max_entries` entries
+//! - no entry decompresses to more than `max_entry_bytes`
+//! - all entries together decompress to at most `max_total_bytes`
+//! - large entries do not expand beyond `max_compression_ratio`
+//!
+//! Size limits are checked against the sizes declared in the central
+//! directory when the archive is opened and again against the bytes actually
+//! produced while reading, so a forged header cannot bypass them.
+
+use std::io::{Read, Seek, Write};
+
+/// Entries smaller than this are exempt from the compression ratio check;
+/// short text compresses extremely well and is harmless at this size.
+pub const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    pub max_entry_bytes: u64,
+    pub max_total_bytes: u64,
+    pub max_compression_ratio: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_entry_bytes: 256 * 1024 * 1024,
+            max_total_bytes: 1024 * 1024 * 1024,
+            max_compression_ratio: 250,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("unsafe entry path '{0}'")]
+    UnsafePath(String),
+    #[error("archive has {count} entries, exceeding the limit of {limit}")]
+    TooManyEntries { count: usize, limit: usize },
+    #[error("entry '{name}' exceeds the {limit} byte size limit")]
+    EntryTooLarge { name: String, limit: u64 },
+    #[error("archive contents exceed the {limit} byte total size limit")]
+    TotalTooLarge { limit: u64 },
+    #[error("entry '{name}' exceeds the {limit}:1 compression ratio limit")]
+    CompressionRatio { name: String, limit: u64 },
+    #[error("entry '{0}' not found in archive")]
+    Missing(String),
+    #[error("invalid archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("failed to read archive: {0}")]
+    Io(std::io::Error),
+}
+
+/// Reading an `EntryReader` fails with an I/O error carrying the limit it
+/// hit; converting that error back recovers the `ArchiveError`
+impl From<std::io::Error> for ArchiveError {
+    fn from(err: std::io::Error) -> Self {
+        err.downcast::<ArchiveError>().unwrap_or_else(ArchiveError::Io)
+    }
+}
+
+impl ArchiveError {
+    /// Whether the archive was refused for exceeding a resource limit rather
+    /// than for being malformed
+    pub fn is_limit(&self) -> bool {
+        matches!(
+            self,
+            ArchiveError::TooManyEntries { .. }
+                | ArchiveError::EntryTooLarge { .. }
+                | ArchiveError::TotalTooLarge { .. }
+                | ArchiveError::CompressionRatio { .. }
+        )
+    }
+}
+
+/// Validate an entry name and return it unchanged if it is a safe relative path
+pub fn sanitize_entry_path(name: &str) -> Result<&str, ArchiveError> {
+    let unsafe_path = || ArchiveError::UnsafePath(name.to_string());
+
+    if name.is_empty() || name.contains('\0') || name.contains('\\') || name.starts_with('/') {
+        return Err(unsafe_path());
+    }
+    // Windows drive prefixes such as `C:` are absolute on that platform
+    let bytes = name.as_bytes();
+    if bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
+        return Err(unsafe_path());
+    }
+    if name
+        .trim_end_matches('/')
+        .split('/')
+        .any(|component| component.is_empty() || component == "..")
+    {
+        return Err(unsafe_path());
+    }
+
+    Ok(name)
+}
+
+/// A ZIP archive whose entries are only readable within `ArchiveLimits`
+pub struct SafeArchive<R: Read + Seek> {
+    archive: zip::ZipArchive<R>,
+    limits: ArchiveLimits,
+    names: Vec<String>,
+    sizes: Vec<u64>,
+    bytes_read: u64,
+}
+
+impl<R: Read + Seek> SafeArchive<R> {
+    pub fn new(reader: R, limits: ArchiveLimits) -> Result<Self, ArchiveError> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        if archive.len() > limits.max_entries {
+            return Err(ArchiveError::TooManyEntries {
+                count: archive.len(),
+                limit: limits.max_entries,
+            });
+        }
+
+        let mut names = Vec::with_capacity(archive.len());
+        let mut sizes = Vec::with_capacity(archive.len());
+        let mut declared_total: u64 = 0;
+        for index in 0..archive.len() {
+            let file = archive.by_index_raw(index)?;
+            let name = sanitize_entry_path(file.name())?.to_string();
+            check_entry_size(&name, file.size(), file.compressed_size(), &limits)?;
+            declared_total = declared_total.saturating_add(file.size());
+            if declared_total > limits.max_total_bytes {
+                return Err(ArchiveError::TotalTooLarge {
+                    limit: limits.max_total_bytes,
+                });
+            }
+            names.push(name);
+            sizes.push(file.size());
+        }
+
+        Ok(Self {
+            archive,
+            limits,
+            names,
+            sizes,
+            bytes_read: 0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Sanitized entry names in archive order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Uncompressed size declared by the central directory. Reads are still
+    /// bounded by the limits, so callers may use this to budget before reading.
+    pub fn declared_size(&self, index: usize) -> u64 {
+        self.sizes[index]
+    }
+
+    pub fn read_by_name(&mut self, name: &str) -> Result<Vec<u8>, ArchiveError> {
+        let index = self
+            .names
+            .iter()
+            .position(|entry| entry == name)
+            .ok_or_else(|| ArchiveError::Missing(name.to_string()))?;
+        self.read_index(index)?
+            .ok_or_else(|| ArchiveError::Missing(name.to_string()))
+    }
+
+    /// Read one entry; directories yield `None`
+    pub fn read_index(&mut self, index: usize) -> Result<Option<Vec<u8>>, ArchiveError> {
+        let Some(mut entry) = self.open_index(index)? else {
+            return Ok(None);
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        Ok(Some(data))
+    }
+
+    /// Stream one entry, for entries too large to hold in memory;
+    /// directories yield `None`. The reader fails as soon as the entry
+    /// yields more than the limits allow.
+    pub fn open_index(&mut self, index: usize) -> Result<Option<EntryReader<'_>>, ArchiveError> {
+        let limits = self.limits;
+        let remaining_total = limits.max_total_bytes.saturating_sub(self.bytes_read);
+
+        let file = self.archive.by_index(index)?;
+        if file.is_dir() {
+            return Ok(None);
+        }
+        Ok(Some(EntryReader {
+            name: self.names[index].clone(),
+            compressed_size: file.compressed_size(),
+            file,
+            limits,
+            remaining_total,
+            read: 0,
+            bytes_read: &mut self.bytes_read,
+        }))
+    }
+
+    /// Copy one entry into `writer` as it is stored, without decompressing
+    /// it; its declared sizes were checked when the archive was opened
+    pub fn raw_copy_into<W: Write + Seek>(
+        &mut self,
+        index: usize,
+        writer: &mut zip::ZipWriter<W>,
+    ) -> Result<(), ArchiveError> {
+        writer.raw_copy_file(self.archive.by_index_raw(index)?)?;
+        Ok(())
+    }
+
+    /// Read every file entry, skipping directories
+    pub fn read_all(&mut self) -> Result<Vec<(String, Vec<u8>)>, ArchiveError> {
+        let mut entries = Vec::new();
+        for index in 0..self.names.len() {
+            if let Some(data) = self.read_index(index)? {
+                entries.push((self.names[index].clone(), data));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// An entry of a `SafeArchive` being read. Never trusts the declared size:
+/// the sizes and compression ratio are checked against the bytes actually
+/// produced, after every read.
+pub struct EntryReader<'a> {
+    file: zip::read::ZipFile<'a>,
+    name: String,
+    compressed_size: u64,
+    limits: ArchiveLimits,
+    /// What the archive-wide limit leaves for this entry
+    remaining_total: u64,
+    read: u64,
+    bytes_read: &'a mut u64,
+}
+
+impl EntryReader<'_> {
+    fn check(&self) -> Result<(), ArchiveError> {
+        if self.read > self.limits.max_entry_bytes {
+            return Err(ArchiveError::EntryTooLarge {
+                name: self.name.clone(),
+                limit: self.limits.max_entry_bytes,
+            });
+        }
+        if self.read > self.remaining_total {
+            return Err(ArchiveError::TotalTooLarge {
+                limit: self.limits.max_total_bytes,
+            });
+        }
+        check_entry_size(&self.name, self.read, self.compressed_size, &self.limits)
+    }
+}
+
+impl Read for EntryReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.file.read(buf)?;
+        self.read += count as u64;
+        *self.bytes_read += count as u64;
+        self.check().map_err(std::io::Error::other)?;
+        Ok(count)
+    }
+}
+
+fn check_entry_size(
+    name: &str,
+    size: u64,
+    compressed_size: u64,
+    limits: &ArchiveLimits,
+) -> Result<(), ArchiveError> {
+    if size > limits.max_entry_bytes {
+        return Err(ArchiveError::EntryTooLarge {
+            name: name.to_string(),
+            limit: limits.max_entry_bytes,
+        });
+    }
+    if size >= RATIO_CHECK_MIN_BYTES
+        && size / compressed_size.max(1) > limits.max_compression_ratio
+    {
+        return Err(ArchiveError::CompressionRatio {
+            name: name.to_string(),
+            limit: limits.max_compression_ratio,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+
+    fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, data) in entries {
+            if name.ends_with('/') {
+                zip.add_directory(*name, options).unwrap();
+            } else {
+                zip.start_file(*name, options).unwrap();
+                zip.write_all(data).unwrap();
+            }
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn sanitize_rejects_traversal_and_absolute_paths() {
+        assert!(sanitize_entry_path("attachments/abc.txt").is_ok());
+        assert!(sanitize_entry_path("attachments/").is_ok());
+        for name in [
+            "../car.json",
+            "attachments/../../etc/passwd",
+            "/etc/passwd",
+            "C:/Windows/system.ini",
+            "attachments\\..\\car.json",
+            "a//b",
+            "",
+        ] {
+            assert!(
+                matches!(sanitize_entry_path(name), Err(ArchiveError::UnsafePath(_))),
+                "{name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn reads_regular_bundle() {
+        let bytes = build_zip(&[
+            ("car.json", b"{}"),
+            ("attachments/", b""),
+            ("attachments/abc.txt", b"hello"),
+        ]);
+        let mut archive = SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default()).unwrap();
+        assert_eq!(archive.len(), 3);
+        assert_eq!(archive.declared_size(2), 5);
+        assert_eq!(archive.read_by_name("car.json").unwrap(), b"{}");
+        let entries = archive.read_all().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1], ("attachments/abc.txt".to_string(), b"hello".to_vec()));
+        assert!(matches!(
+            archive.read_by_name("missing.json"),
+            Err(ArchiveError::Missing(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_traversal_entry_on_open() {
+        let bytes = build_zip(&[("car.json", b"{}"), ("../evil.txt", b"x")]);
+        assert!(matches!(
+            SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default()),
+            Err(ArchiveError::UnsafePath(_))
+        ));
+    }
+
+    #[test]
+    fn enforces_entry_count_and_size_limits() {
+        let bytes = build_zip(&[("a.txt", b"12345"), ("b.txt", b"12345")]);
+
+        let limits = ArchiveLimits {
+            max_entries: 1,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            SafeArchive::new(Cursor::new(bytes.clone()), limits),
+            Err(ArchiveError::TooManyEntries { count: 2, limit: 1 })
+        ));
+
+        let limits = ArchiveLimits {
+            max_entry_bytes: 4,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            SafeArchive::new(Cursor::new(bytes.clone()), limits),
+            Err(ArchiveError::EntryTooLarge { .. })
+        ));
+
+        let limits = ArchiveLimits {
+            max_total_bytes: 8,
+            ..ArchiveLimits::default()
+        };
+        assert!(matches!(
+            SafeArchive::new(Cursor::new(bytes), limits),
+            Err(ArchiveError::TotalTooLarge { limit: 8 })
+        ));
+    }
+
+    #[test]
+    fn streamed_entries_stop_at_the_limits() {
+        let bytes = build_zip(&[("a.txt", b"12345"), ("b.txt", b"67890")]);
+        let mut archive = SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default()).unwrap();
+        let mut streamed = Vec::new();
+        std::io::copy(&mut archive.open_index(1).unwrap().unwrap(), &mut streamed).unwrap();
+        assert_eq!(streamed, b"67890");
+
+        // Limits are checked against the bytes read, as a forged size would
+        // slip past the check on open
+        let mut archive = SafeArchive::new(
+            Cursor::new(build_zip(&[("a.txt", b"12345")])),
+            ArchiveLimits::default(),
+        )
+        .unwrap();
+        archive.limits.max_entry_bytes = 4;
+        let err = std::io::copy(&mut archive.open_index(0).unwrap().unwrap(), &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(
+            ArchiveError::from(err),
+            ArchiveError::EntryTooLarge { limit: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn raw_copies_keep_entries_as_stored() {
+        let bytes = build_zip(&[("a.txt", b"12345"), ("b.txt", b"67890")]);
+        let mut archive = SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default()).unwrap();
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive.raw_copy_into(1, &mut zip).unwrap();
+        let copied = zip.finish().unwrap().into_inner();
+
+        let mut copy = SafeArchive::new(Cursor::new(copied), ArchiveLimits::default()).unwrap();
+        assert_eq!(copy.names(), ["b.txt"]);
+        assert_eq!(copy.read_by_name("b.txt").unwrap(), b"67890");
+    }
+
+    #[test]
+    fn rejects_highly_compressed_entries() {
+        let zeros = vec![0u8; 4 * 1024 * 1024];
+        let bytes = build_zip(&[("bomb.txt", &zeros)]);
+        assert!(matches!(
+            SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default()),
+            Err(ArchiveError::CompressionRatio { .. })
+        ));
+    }
+}
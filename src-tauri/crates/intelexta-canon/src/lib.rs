@@ -0,0 +1,93 @@
+// src-tauri/crates/intelexta-canon/src/lib.rs
+//!
+//! Canonical JSON, the bytes checkpoint chains, CAR IDs and signatures cover
+//!
+//! The orchestrator, the verifier CLI and the WASM verifier must produce the
+//! very same bytes for the same document, or receipts signed by one fail to
+//! verify in another. All three canonicalize through this crate.
+//!
+//! The scheme is the JSON Canonicalization Scheme (RFC 8785) as `serde_jcs`
+//! implements it, with two deviations that every receipt signed so far
+//! depends on, and which therefore stay:
+//!
+//! - object keys sort by the bytes of their escaped UTF-8 form rather than by
+//!   UTF-16 code units, so `"\r"` sorts after `"1"` and astral characters
+//!   sort after the rest of the BMP
+//! - integers beyond 2^53 are written exactly instead of rounded to a double
+//!
+//! `vectors/canonical.json` pins the output on tricky floats, unicode keys,
+//! escapes and nested maps. Each vector gives a JSON `input` and the
+//! `canonical` text it must produce; an implementation in another language
+//! should pass them all before it verifies receipts.
+
+use serde::{Deserialize, Serialize};
+
+pub use serde_json::Error;
+
+/// The published test vectors, see the crate documentation
+pub const VECTORS: &str = include_str!("../vectors/canonical.json");
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Vector {
+    pub name: String,
+    pub description: String,
+    /// JSON text, parsed before it is canonicalized
+    pub input: String,
+    pub canonical: String,
+}
+
+pub fn vectors() -> Vec<Vector> {
+    serde_json::from_str(VECTORS).expect("canonicalization vectors are valid JSON")
+}
+
+pub fn to_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    serde_jcs::to_vec(value)
+}
+
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    serde_jcs::to_string(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn every_vector_canonicalizes_as_published() {
+        let vectors = vectors();
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let input: Value = serde_json::from_str(&vector.input).unwrap();
+            assert_eq!(to_string(&input).unwrap(), vector.canonical, "{}", vector.name);
+
+            // Canonical text is a fixed point
+            let reparsed: Value = serde_json::from_str(&vector.canonical).unwrap();
+            assert_eq!(to_string(&reparsed).unwrap(), vector.canonical, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn structs_canonicalize_like_their_json_values() {
+        #[derive(Serialize)]
+        struct Body {
+            usd_cost: f64,
+            kind: &'static str,
+            usage_tokens: u64,
+            #[serde(rename = "\u{20ac}")]
+            euro: Option<u8>,
+        }
+        let body = Body {
+            usd_cost: 0.0042,
+            kind: "Step",
+            usage_tokens: 1200,
+            euro: None,
+        };
+        let value = serde_json::to_value(&body).unwrap();
+        assert_eq!(to_vec(&body).unwrap(), to_vec(&value).unwrap());
+        assert_eq!(
+            to_string(&body).unwrap(),
+            "{\"kind\":\"Step\",\"usage_tokens\":1200,\"usd_cost\":0.0042,\"\u{20ac}\":null}"
+        );
+    }
+}
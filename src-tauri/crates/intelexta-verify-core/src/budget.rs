@@ -0,0 +1,240 @@
+// src-tauri/crates/intelexta-verify-core/src/budget.rs
+//!
+//! Whether a run kept to the budgets of the policy it ran under
+//!
+//! A CAR's `budgets` record what its run used and `policy_ref.limits` the
+//! policy's budgets. Both are signed, but only the checkpoints' own usage is
+//! chained, so verifiers recompute the totals from the checkpoints, compare
+//! them with `budgets` and check them against the limits. A run the governor
+//! stopped mid-generation can legitimately end over budget, so verifiers
+//! report violations as warnings.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Token, USD and nature-cost amounts, used for both usage and limits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Totals {
+    pub tokens: u64,
+    pub usd: f64,
+    pub nature_cost: f64,
+}
+
+/// What one checkpoint used, as chained into the CAR
+#[derive(Debug, Clone, Copy)]
+pub struct CheckpointUsage {
+    pub usage_tokens: u64,
+    /// Costs priced from the model catalog when the checkpoint was recorded
+    pub usd_cost: Option<f64>,
+    pub nature_cost: Option<f64>,
+}
+
+/// Totals recomputed from the checkpoints; a cost is `None` when neither the
+/// checkpoints nor the policy say how to price it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recomputed {
+    pub tokens: u64,
+    pub usd: Option<f64>,
+    pub nature_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Tokens,
+    Usd,
+    NatureCost,
+}
+
+impl fmt::Display for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Budget::Tokens => "Token",
+            Budget::Usd => "USD",
+            Budget::NatureCost => "Nature cost",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetViolation {
+    /// The CAR's `budgets` disagree with what its checkpoints add up to
+    Misreported {
+        budget: Budget,
+        recorded: f64,
+        recomputed: f64,
+    },
+    /// The run used more than its policy allowed
+    Exceeded {
+        budget: Budget,
+        used: f64,
+        limit: f64,
+    },
+}
+
+impl fmt::Display for BudgetViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Misreported {
+                budget,
+                recorded,
+                recomputed,
+            } => write!(
+                f,
+                "{budget} usage is recorded as {recorded}, but the checkpoints add up to \
+                 {recomputed}"
+            ),
+            Self::Exceeded {
+                budget,
+                used,
+                limit,
+            } => write!(f, "{budget} usage {used} exceeds the policy budget of {limit}"),
+        }
+    }
+}
+
+/// Recompute a run's totals the way the exporter does: from the costs priced
+/// at execution when any checkpoint has one, otherwise at the policy's
+/// per-token rates
+pub fn recompute(checkpoints: &[CheckpointUsage], limits: Option<&Totals>) -> Recomputed {
+    let tokens = checkpoints.iter().map(|ck| ck.usage_tokens).sum();
+    if checkpoints.iter().any(|ck| ck.usd_cost.is_some()) {
+        return Recomputed {
+            tokens,
+            usd: Some(checkpoints.iter().filter_map(|ck| ck.usd_cost).sum()),
+            nature_cost: Some(checkpoints.iter().filter_map(|ck| ck.nature_cost).sum()),
+        };
+    }
+    let per_token = |budget: f64| match limits {
+        Some(limits) if limits.tokens > 0 => Some(budget / limits.tokens as f64 * tokens as f64),
+        Some(_) => Some(0.0),
+        None => None,
+    };
+    Recomputed {
+        tokens,
+        usd: limits.and_then(|limits| per_token(limits.usd)),
+        nature_cost: limits.and_then(|limits| per_token(limits.nature_cost)),
+    }
+}
+
+/// Check a run's recorded totals against its checkpoints and, when the CAR
+/// embeds them, its policy's limits
+pub fn budget_violations(
+    recorded: &Totals,
+    checkpoints: &[CheckpointUsage],
+    limits: Option<&Totals>,
+) -> Vec<BudgetViolation> {
+    let recomputed = recompute(checkpoints, limits);
+    let mut violations = Vec::new();
+    let amounts = [
+        (
+            Budget::Tokens,
+            recorded.tokens as f64,
+            Some(recomputed.tokens as f64),
+            limits.map(|limits| limits.tokens as f64),
+        ),
+        (Budget::Usd, recorded.usd, recomputed.usd, limits.map(|limits| limits.usd)),
+        (
+            Budget::NatureCost,
+            recorded.nature_cost,
+            recomputed.nature_cost,
+            limits.map(|limits| limits.nature_cost),
+        ),
+    ];
+    for (budget, recorded, recomputed, limit) in amounts {
+        if let Some(recomputed) = recomputed.filter(|&value| !approx_eq(value, recorded)) {
+            violations.push(BudgetViolation::Misreported {
+                budget,
+                recorded,
+                recomputed,
+            });
+        }
+        // Checkpoints are chained, so what they add up to is what was used
+        let used = recomputed.unwrap_or(recorded);
+        if let Some(limit) = limit.filter(|&limit| used > limit && !approx_eq(used, limit)) {
+            violations.push(BudgetViolation::Exceeded {
+                budget,
+                used,
+                limit,
+            });
+        }
+    }
+    violations
+}
+
+/// Costs are sums of floats, which the exporter and a verifier may round
+/// differently
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(usage_tokens: u64, usd_cost: Option<f64>) -> CheckpointUsage {
+        CheckpointUsage {
+            usage_tokens,
+            usd_cost,
+            nature_cost: usd_cost.map(|usd| usd * 2.0),
+        }
+    }
+
+    #[test]
+    fn totals_are_recomputed_and_held_to_the_policy() {
+        let limits = Totals {
+            tokens: 1_000,
+            usd: 1.0,
+            nature_cost: 10.0,
+        };
+        let priced = [usage(300, Some(0.1)), usage(200, Some(0.2))];
+        let recorded = Totals {
+            tokens: 500,
+            usd: 0.1 + 0.2,
+            nature_cost: 0.2 + 0.4,
+        };
+        assert!(budget_violations(&recorded, &priced, Some(&limits)).is_empty());
+
+        // Checkpoints without prices are costed at the policy's rates
+        let unpriced = [usage(1_500, None)];
+        let recorded = Totals {
+            tokens: 1_400,
+            usd: 1.5,
+            nature_cost: 15.0,
+        };
+        assert_eq!(
+            budget_violations(&recorded, &unpriced, Some(&limits)),
+            [
+                BudgetViolation::Misreported {
+                    budget: Budget::Tokens,
+                    recorded: 1_400.0,
+                    recomputed: 1_500.0,
+                },
+                BudgetViolation::Exceeded {
+                    budget: Budget::Tokens,
+                    used: 1_500.0,
+                    limit: 1_000.0,
+                },
+                BudgetViolation::Exceeded {
+                    budget: Budget::Usd,
+                    used: 1.5,
+                    limit: 1.0,
+                },
+                BudgetViolation::Exceeded {
+                    budget: Budget::NatureCost,
+                    used: 15.0,
+                    limit: 10.0,
+                },
+            ]
+        );
+
+        // Without limits only the token total can be recomputed
+        assert_eq!(
+            budget_violations(&recorded, &unpriced, None),
+            [BudgetViolation::Misreported {
+                budget: Budget::Tokens,
+                recorded: 1_400.0,
+                recomputed: 1_500.0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// src-tauri/crates/intelexta-verify-core/src/clock.rs
+//!
+//! Sanity checks on the clock a CAR's checkpoints were stamped with
+//!
+//! Checkpoint timestamps are covered by the chain hash and signatures, so
+//! they cannot be changed after signing; but a signer can stamp whatever
+//! times it likes. Times that run backwards, postdate the CAR or lie in the
+//! verifier's future are cheap hints of a forged or badly replayed run. They
+//! prove nothing on their own, so verifiers report them as warnings.
+//!
+//! The crate does no date parsing: callers read each timestamp into Unix
+//! milliseconds, with `None` for one that does not parse.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// How far ahead of the verifier's clock a time may be before it counts as
+/// in the future, allowing for skew between the signer's and verifier's clocks
+pub const CLOCK_SKEW_MS: i64 = 5 * 60 * 1000;
+
+/// One checkpoint's time as read by the caller
+#[derive(Debug, Clone, Copy)]
+pub struct ClockReading<'a> {
+    pub id: &'a str,
+    /// Unix milliseconds, or `None` if the timestamp does not parse
+    pub at_ms: Option<i64>,
+}
+
+/// A time that does not fit the run's clock; checkpoint indexes count from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClockAnomaly {
+    Unreadable { index: usize, id: String },
+    /// Earlier than the checkpoint before it
+    Backwards { index: usize, id: String },
+    /// Later than the CAR's `created_at`
+    AfterCreation { index: usize, id: String },
+    /// Later than the verifier's clock, beyond `CLOCK_SKEW_MS`
+    InFuture { index: usize, id: String },
+    /// The CAR's own `created_at` is in the future
+    CreatedInFuture,
+}
+
+impl fmt::Display for ClockAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable { index, id } => {
+                write!(f, "Checkpoint #{index} ({id}) has an unreadable timestamp")
+            }
+            Self::Backwards { index, id } => write!(
+                f,
+                "Checkpoint #{index} ({id}) is timestamped before the checkpoint preceding it"
+            ),
+            Self::AfterCreation { index, id } => write!(
+                f,
+                "Checkpoint #{index} ({id}) is timestamped after the CAR was created"
+            ),
+            Self::InFuture { index, id } => {
+                write!(f, "Checkpoint #{index} ({id}) is timestamped in the future")
+            }
+            Self::CreatedInFuture => write!(f, "The CAR's created_at is in the future"),
+        }
+    }
+}
+
+/// Check that checkpoint times never decrease, do not postdate the CAR's
+/// `created_at` and, like `created_at`, are not in the future at `now_ms`
+pub fn clock_anomalies(
+    checkpoints: &[ClockReading<'_>],
+    created_at_ms: i64,
+    now_ms: i64,
+) -> Vec<ClockAnomaly> {
+    let horizon = now_ms.saturating_add(CLOCK_SKEW_MS);
+    let mut anomalies = Vec::new();
+    if created_at_ms > horizon {
+        anomalies.push(ClockAnomaly::CreatedInFuture);
+    }
+    let mut previous: Option<i64> = None;
+    for (index, reading) in checkpoints.iter().enumerate() {
+        let id = String::from(reading.id);
+        let Some(at) = reading.at_ms else {
+            anomalies.push(ClockAnomaly::Unreadable { index, id });
+            continue;
+        };
+        if previous.is_some_and(|previous| at < previous) {
+            anomalies.push(ClockAnomaly::Backwards {
+                index,
+                id: id.clone(),
+            });
+        }
+        if at > horizon {
+            anomalies.push(ClockAnomaly::InFuture { index, id });
+        } else if at > created_at_ms {
+            anomalies.push(ClockAnomaly::AfterCreation { index, id });
+        }
+        previous = Some(at);
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_order_and_late_times_are_flagged() {
+        let reading = |id, at_ms| ClockReading { id, at_ms };
+        let (created, now) = (10_000, 20_000);
+        let ordered = [reading("a", Some(1_000)), reading("b", Some(1_000))];
+        assert!(clock_anomalies(&ordered, created, now).is_empty());
+
+        let checkpoints = [
+            reading("a", Some(5_000)),
+            reading("b", Some(4_000)),
+            reading("c", None),
+            reading("d", Some(12_000)),
+            reading("e", Some(now + CLOCK_SKEW_MS + 1)),
+        ];
+        let anomalies = clock_anomalies(&checkpoints, created, now);
+        assert_eq!(
+            anomalies,
+            [
+                ClockAnomaly::Backwards { index: 1, id: "b".into() },
+                ClockAnomaly::Unreadable { index: 2, id: "c".into() },
+                ClockAnomaly::AfterCreation { index: 3, id: "d".into() },
+                ClockAnomaly::InFuture { index: 4, id: "e".into() },
+            ]
+        );
+
+        let future = clock_anomalies(&[], now + CLOCK_SKEW_MS + 1, now);
+        assert_eq!(future, [ClockAnomaly::CreatedInFuture]);
+    }
+}
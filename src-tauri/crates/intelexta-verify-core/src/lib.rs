@@ -0,0 +1,386 @@
+// src-tauri/crates/intelexta-verify-core/src/lib.rs
+//!
+//! The checks every CAR verifier runs, shared by the desktop app, the
+//! `intelexta-verify` CLI and the web verifier
+//!
+//! Each of them used to carry its own copy of the checkpoint body, the chain
+//! and signature loops and the attachment hashing, and the copies drifted.
+//! They now call into this crate and keep only what differs between them:
+//! how a CAR is read, time and size budgets, and how failures are reported.
+//!
+//! The crate is `no_std` with `alloc` and does no I/O, so it builds for any
+//! target the verifiers run on. Canonical JSON comes from `intelexta-canon`,
+//! the one dependency that still pulls in `std`.
+//!
+//! - `CheckpointBody::chain_hash` recomputes a checkpoint's `curr_chain`
+//! - `check_chain_link` and `check_checkpoint_signature` check one entry of
+//!   a checkpoint chain, any type implementing `ChainedCheckpoint`
+//! - `body_signature_message` gives the bytes an `ed25519-body:` signature
+//!   covers, from the CAR JSON as written
+//! - `check_attachment` checks content against the hash it is named by;
+//!   `AttachmentHasher` does so chunk by chunk
+//! - `merkle` proves attachments into the manifest's root
+//! - `clock` flags checkpoint times that run backwards or into the future
+//! - `budget` recomputes a run's usage and holds it to its policy's budgets
+//! - `semantic` computes semantic digests and checks concordant steps'
+//!   digests and epsilons
+//! - `transcript` checks an interactive run's chat messages against their
+//!   checkpoints
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod budget;
+pub mod clock;
+pub mod merkle;
+pub mod semantic;
+pub mod transcript;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use core::fmt;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Why a check failed; checkpoint indexes count from 0 within one chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    ChainBroken {
+        index: usize,
+        id: String,
+        expected: String,
+        found: String,
+    },
+    InvalidKey(&'static str),
+    /// Not base64, or not 64 bytes; `index` is `None` for the body signature
+    MalformedSignature {
+        index: Option<usize>,
+    },
+    /// `index` is `None` for the body signature
+    SignatureMismatch {
+        index: Option<usize>,
+    },
+    AttachmentMismatch {
+        expected: String,
+        computed: String,
+    },
+    Canonicalization(String),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ChainBroken {
+                index,
+                id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Hash chain broken at checkpoint #{index} (id: {id})\n\
+                 Expected: {expected}\nFound: {found}"
+            ),
+            Error::InvalidKey(reason) => write!(f, "invalid verifying key: {reason}"),
+            Error::MalformedSignature { index: Some(index) } => {
+                write!(
+                    f,
+                    "Signature must be 64 bytes of base64 at checkpoint #{index}"
+                )
+            }
+            Error::MalformedSignature { index: None } => {
+                f.write_str("Top-level signature must be 64 bytes of base64")
+            }
+            Error::SignatureMismatch { index: Some(index) } => {
+                write!(f, "Signature verification failed at checkpoint #{index}")
+            }
+            Error::SignatureMismatch { index: None } => {
+                f.write_str("Top-level body signature verification failed")
+            }
+            Error::AttachmentMismatch { expected, computed } => write!(
+                f,
+                "Attachment content mismatch\nExpected hash: {expected}\n\
+                 Computed hash: {computed}"
+            ),
+            Error::Canonicalization(reason) => {
+                write!(f, "Failed to canonicalize JSON: {reason}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+pub fn canonical_json<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    intelexta_canon::to_vec(value).map_err(|err| Error::Canonicalization(err.to_string()))
+}
+
+/// The part of a checkpoint its chain hash covers. `M` is the type the
+/// caller reads response metadata into; any type serializing to the same
+/// JSON hashes the same.
+#[derive(Serialize)]
+pub struct CheckpointBody<'a, M = Value> {
+    pub run_id: &'a str,
+    pub kind: &'a str, // "Step" or "Incident"
+    pub timestamp: String,
+    pub inputs_sha256: Option<&'a str>,
+    pub outputs_sha256: Option<&'a str>,
+    pub incident: Option<&'a Value>,
+    pub usage_tokens: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Omitted when false so bodies of fresh generations hash as before
+    #[serde(skip_serializing_if = "core::ops::Not::not")]
+    pub cache_hit: bool,
+    /// Normalized provider response details; absent for non-model checkpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_metadata: Option<&'a M>,
+    /// Canonical hash of the run inputs; only on the first checkpoint of an execution
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_inputs_sha256: Option<&'a str>,
+    /// Hash of the JSON Schema the step's output was validated against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema_sha256: Option<&'a str>,
+}
+
+impl<M: Serialize> CheckpointBody<'_, M> {
+    /// sha256(prev_chain || canonical body), the `curr_chain` the checkpoint signs
+    pub fn chain_hash(&self, prev_chain: &str) -> Result<String> {
+        let canonical = canonical_json(self)?;
+        Ok(sha256_hex(&[prev_chain.as_bytes(), &canonical].concat()))
+    }
+}
+
+/// A checkpoint as a CAR's process proof carries it
+pub trait ChainedCheckpoint {
+    type Metadata: Serialize;
+
+    fn id(&self) -> &str;
+    fn prev_chain(&self) -> &str;
+    fn curr_chain(&self) -> &str;
+    /// Base64, with or without an `ed25519:` prefix
+    fn signature(&self) -> &str;
+    /// Proof checkpoints leave out incident details, so the body has none
+    fn body(&self) -> CheckpointBody<'_, Self::Metadata>;
+}
+
+/// `checkpoint`, number `index` in its chain, must hash to its `curr_chain`.
+/// Incident details are not part of the CAR, so only step checkpoints can be
+/// re-hashed; `check_checkpoint_signature` covers both kinds.
+pub fn check_chain_link<C: ChainedCheckpoint>(index: usize, checkpoint: &C) -> Result<()> {
+    let body = checkpoint.body();
+    if body.kind == "Incident" {
+        return Ok(());
+    }
+    let expected = body.chain_hash(checkpoint.prev_chain())?;
+    if expected != checkpoint.curr_chain() {
+        return Err(Error::ChainBroken {
+            index,
+            id: checkpoint.id().into(),
+            expected,
+            found: checkpoint.curr_chain().into(),
+        });
+    }
+    Ok(())
+}
+
+/// `checkpoint`, number `index` in its chain, must sign its `curr_chain`
+pub fn check_checkpoint_signature<C: ChainedCheckpoint>(
+    index: usize,
+    checkpoint: &C,
+    key: &VerifyingKey,
+) -> Result<()> {
+    let encoded = checkpoint.signature();
+    let encoded = encoded.strip_prefix("ed25519:").unwrap_or(encoded);
+    let signature =
+        decode_signature(encoded).ok_or(Error::MalformedSignature { index: Some(index) })?;
+    key.verify(checkpoint.curr_chain().as_bytes(), &signature)
+        .map_err(|_| Error::SignatureMismatch { index: Some(index) })
+}
+
+pub fn decode_verifying_key(pubkey_b64: &str) -> Result<VerifyingKey> {
+    let bytes = STANDARD
+        .decode(pubkey_b64)
+        .map_err(|_| Error::InvalidKey("not base64"))?;
+    let array: [u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = bytes
+        .try_into()
+        .map_err(|_| Error::InvalidKey("not 32 bytes"))?;
+    VerifyingKey::from_bytes(&array).map_err(|_| Error::InvalidKey("not an ed25519 point"))
+}
+
+fn decode_signature(signature_b64: &str) -> Option<Signature> {
+    let bytes = STANDARD.decode(signature_b64).ok()?;
+    Some(Signature::from_bytes(&<[u8; 64]>::try_from(bytes).ok()?))
+}
+
+pub fn signature_valid(key: &VerifyingKey, message: &[u8], signature_b64: &str) -> bool {
+    decode_signature(signature_b64).is_some_and(|signature| key.verify(message, &signature).is_ok())
+}
+
+/// The bytes an `ed25519-body:` signature covers: the canonical CAR JSON
+/// without its `signatures`. It is taken from the JSON as written, not as
+/// re-serialized, so fields a verifier does not model stay covered.
+pub fn body_signature_message(car: &Value) -> Result<Vec<u8>> {
+    let mut body = car.clone();
+    if let Value::Object(fields) = &mut body {
+        fields.remove("signatures");
+    }
+    canonical_json(&body)
+}
+
+/// The body signature `signature_b64` of the CAR JSON `car` must verify
+pub fn check_body_signature(car: &Value, key: &VerifyingKey, signature_b64: &str) -> Result<()> {
+    let message = body_signature_message(car)?;
+    let signature =
+        decode_signature(signature_b64).ok_or(Error::MalformedSignature { index: None })?;
+    key.verify(&message, &signature)
+        .map_err(|_| Error::SignatureMismatch { index: None })
+}
+
+/// Attachment `content` must hash to the hex `expected_hash` it is named by
+pub fn check_attachment(expected_hash: &str, content: &[u8]) -> Result<()> {
+    let mut hasher = AttachmentHasher::new(expected_hash);
+    hasher.update(content);
+    hasher.finish()
+}
+
+/// `check_attachment` for content that arrives in chunks, such as an
+/// attachment too large to hold in memory
+pub struct AttachmentHasher {
+    expected: String,
+    hasher: Sha256,
+}
+
+impl AttachmentHasher {
+    pub fn new(expected_hash: &str) -> Self {
+        Self {
+            expected: expected_hash.into(),
+            hasher: Sha256::new(),
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    pub fn finish(self) -> Result<()> {
+        let computed = hex::encode(self.hasher.finalize());
+        if computed != self.expected {
+            return Err(Error::AttachmentMismatch {
+                expected: self.expected,
+                computed,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    struct Checkpoint {
+        prev_chain: String,
+        curr_chain: String,
+        signature: String,
+    }
+
+    impl ChainedCheckpoint for Checkpoint {
+        type Metadata = Value;
+
+        fn id(&self) -> &str {
+            "ckpt-1"
+        }
+        fn prev_chain(&self) -> &str {
+            &self.prev_chain
+        }
+        fn curr_chain(&self) -> &str {
+            &self.curr_chain
+        }
+        fn signature(&self) -> &str {
+            &self.signature
+        }
+        fn body(&self) -> CheckpointBody<'_> {
+            CheckpointBody {
+                run_id: "run-1",
+                kind: "Step",
+                timestamp: "2025-01-01T00:00:00+00:00".into(),
+                inputs_sha256: None,
+                outputs_sha256: Some("ab"),
+                incident: None,
+                usage_tokens: 3,
+                prompt_tokens: 1,
+                completion_tokens: 2,
+                cache_hit: false,
+                response_metadata: None,
+                run_inputs_sha256: None,
+                output_schema_sha256: None,
+            }
+        }
+    }
+
+    #[test]
+    fn chain_links_and_signatures_are_checked_per_checkpoint() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut checkpoint = Checkpoint {
+            prev_chain: String::new(),
+            curr_chain: String::new(),
+            signature: String::new(),
+        };
+        checkpoint.curr_chain = checkpoint.body().chain_hash("").unwrap();
+        let signature = key.sign(checkpoint.curr_chain.as_bytes()).to_bytes();
+        checkpoint.signature = alloc::format!("ed25519:{}", STANDARD.encode(signature));
+        check_chain_link(0, &checkpoint).unwrap();
+        check_checkpoint_signature(0, &checkpoint, &key.verifying_key()).unwrap();
+
+        checkpoint.prev_chain = "00".into();
+        assert!(matches!(
+            check_chain_link(4, &checkpoint),
+            Err(Error::ChainBroken { index: 4, .. })
+        ));
+        let other = SigningKey::from_bytes(&[8; 32]).verifying_key();
+        assert_eq!(
+            check_checkpoint_signature(4, &checkpoint, &other),
+            Err(Error::SignatureMismatch { index: Some(4) })
+        );
+    }
+
+    #[test]
+    fn body_signatures_cover_the_json_as_written_without_signatures() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut car = serde_json::json!({"id": "car:1", "unmodeled": [1, 2]});
+        let signature =
+            STANDARD.encode(key.sign(&body_signature_message(&car).unwrap()).to_bytes());
+        car["signatures"] = serde_json::json!([alloc::format!("ed25519-body:{signature}")]);
+        check_body_signature(&car, &key.verifying_key(), &signature).unwrap();
+
+        car["unmodeled"] = serde_json::json!([1]);
+        assert_eq!(
+            check_body_signature(&car, &key.verifying_key(), &signature),
+            Err(Error::SignatureMismatch { index: None })
+        );
+    }
+
+    #[test]
+    fn attachments_must_hash_to_their_name() {
+        check_attachment(&sha256_hex(b"content"), b"content").unwrap();
+        let mut chunked = AttachmentHasher::new(&sha256_hex(b"content"));
+        chunked.update(b"con");
+        chunked.update(b"tent");
+        chunked.finish().unwrap();
+        assert!(matches!(
+            check_attachment(&sha256_hex(b"content"), b"tampered"),
+            Err(Error::AttachmentMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+// src-tauri/crates/intelexta-verify-core/src/merkle.rs
+//!
+//! Merkle tree over a CAR's attachments
+//!
+//! Attachments are named by the sha256 of their content, which makes each
+//! one self-verifying but leaves the set open: a file could be dropped from
+//! or slipped into a bundle without touching the signed body. The CAR body
+//! therefore carries the sorted attachment hashes and the Merkle root over
+//! them, and verifiers prove every attachment they find into that root.
+//!
+//! Tree layout, with all digests sha256:
+//!
+//! - leaves are the attachment hashes, sorted and deduplicated;
+//!   a leaf node is `H(0x00 || hex hash as ASCII)`
+//! - an inner node is `H(0x01 || left || right)` over the raw child digests
+//! - a node without a sibling moves up a level unchanged
+//! - the root of no attachments is `H("")`
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Which side of the path the sibling sits on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProofStep {
+    /// Hex digest of the sibling node
+    pub sibling: String,
+    pub side: Side,
+}
+
+/// Sorted, deduplicated leaves of a set of attachment hashes
+pub fn leaves<I, S>(hashes: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut leaves: Vec<String> = hashes.into_iter().map(Into::into).collect();
+    leaves.sort();
+    leaves.dedup();
+    leaves
+}
+
+fn leaf_node(hash: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(hash.as_bytes());
+    hasher.finalize().into()
+}
+
+fn inner_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => inner_node(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks(2) yields one or two nodes"),
+        })
+        .collect()
+}
+
+/// Hex Merkle root over `leaves`, which must already be sorted (see `leaves`)
+pub fn root(leaves: &[String]) -> String {
+    if leaves.is_empty() {
+        return hex::encode(Sha256::digest(b""));
+    }
+    let mut level: Vec<[u8; 32]> = leaves.iter().map(|leaf| leaf_node(leaf)).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    hex::encode(level[0])
+}
+
+/// Path from `leaf` to the root, or `None` when `leaf` is not in the tree
+pub fn inclusion_proof(leaves: &[String], leaf: &str) -> Option<Vec<ProofStep>> {
+    let mut index = leaves.iter().position(|candidate| candidate == leaf)?;
+    let mut level: Vec<[u8; 32]> = leaves.iter().map(|leaf| leaf_node(leaf)).collect();
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if let Some(node) = level.get(sibling) {
+            proof.push(ProofStep {
+                sibling: hex::encode(node),
+                side: if sibling < index {
+                    Side::Left
+                } else {
+                    Side::Right
+                },
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Whether `proof` leads from `leaf` to the hex `root`
+pub fn verify_inclusion(root: &str, leaf: &str, proof: &[ProofStep]) -> bool {
+    let mut node = leaf_node(leaf);
+    for step in proof {
+        let Some(sibling) = hex::decode(&step.sibling)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        else {
+            return false;
+        };
+        node = match step.side {
+            Side::Left => inner_node(&sibling, &node),
+            Side::Right => inner_node(&node, &sibling),
+        };
+    }
+    hex::encode(node) == root
+}
+
+/// Whether `leaf` is in the tree over `leaves` and proves into the hex `root`
+pub fn proves_into(leaves: &[String], root: &str, leaf: &str) -> bool {
+    inclusion_proof(leaves, leaf).is_some_and(|proof| verify_inclusion(root, leaf, &proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn hashes(count: usize) -> Vec<String> {
+        leaves((0..count).map(|n| hex::encode(Sha256::digest(n.to_string()))))
+    }
+
+    #[test]
+    fn every_leaf_proves_into_the_root() {
+        for count in 1..=9 {
+            let leaves = hashes(count);
+            let root = root(&leaves);
+            for leaf in &leaves {
+                let proof = inclusion_proof(&leaves, leaf).unwrap();
+                assert!(verify_inclusion(&root, leaf, &proof), "{count} leaves");
+            }
+        }
+    }
+
+    #[test]
+    fn added_or_removed_leaves_change_the_root() {
+        let leaves = hashes(5);
+        let root = root(&leaves);
+        assert_ne!(super::root(&leaves[..4]), root);
+        assert_ne!(super::root(&hashes(6)), root);
+
+        let outsider = hex::encode(Sha256::digest(b"outsider"));
+        assert!(inclusion_proof(&leaves, &outsider).is_none());
+        let proof = inclusion_proof(&leaves, &leaves[0]).unwrap();
+        assert!(!verify_inclusion(&root, &outsider, &proof));
+    }
+
+    #[test]
+    fn leaves_are_sorted_and_the_empty_root_is_fixed() {
+        assert_eq!(
+            leaves(["b", "a", "b"]),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        assert_eq!(
+            root(&[]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        // Pinned so bundles emitted before the tree moved here still verify
+        let fixture = leaves(["cc".repeat(32), "aa".repeat(32), "bb".repeat(32)]);
+        let fixture_root = root(&fixture);
+        assert_eq!(
+            fixture_root,
+            "3f4d56bc1f51d654d942888094dbd258f42d158c6b0f67add2825cc4b2a560be"
+        );
+        assert!(fixture
+            .iter()
+            .all(|leaf| proves_into(&fixture, &fixture_root, leaf)));
+        assert!(!proves_into(&fixture, &fixture_root, &"dd".repeat(32)));
+        assert!(!proves_into(&fixture[..2], &fixture_root, &fixture[0]));
+    }
+}
@@ -0,0 +1,338 @@
+// src-tauri/crates/intelexta-verify-core/src/semantic.rs
+//!
+//! Semantic digests, and the checks concordant proofs rest on
+//!
+//! A concordant step passes replay when its new output lies within
+//! `epsilon` of the original, measured as the Hamming distance between the
+//! two outputs' semantic digests over `DIGEST_BITS`. A semantic digest is a
+//! 64-bit SimHash of the lowercased text's character trigrams, written as 16
+//! hex digits. Features are hashed with SipHash-1-3 under zero keys, the
+//! hasher the app has always used through `std`'s `DefaultHasher`, spelled
+//! out here so every verifier computes the same digests on every target.
+//!
+//! `semantic_issues` checks what a CAR records: that digests are well
+//! formed, that concordant steps carry an epsilon within `[0, 1]`, that a
+//! digest recomputed from an output the bundle carries matches the recorded
+//! one and that a recorded replay lies within its epsilon.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Width of a semantic digest; distances are normalized by it
+pub const DIGEST_BITS: u32 = 64;
+
+/// Semantic digest of `text`
+pub fn semantic_digest(text: &str) -> String {
+    if text.trim().is_empty() {
+        return format!("{:016x}", 0_u64);
+    }
+
+    let normalized = text.to_lowercase();
+    let chars: Vec<char> = normalized.chars().collect();
+    let mut features: Vec<String> = chars.windows(3).map(|gram| gram.iter().collect()).collect();
+    if features.is_empty() {
+        features = normalized.split_whitespace().map(String::from).collect();
+    }
+    if features.is_empty() {
+        features.push(normalized);
+    }
+
+    let mut weights = [0_i64; DIGEST_BITS as usize];
+    for feature in &features {
+        let hash = sip13_str(feature);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if (hash >> bit) & 1 == 1 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+
+    let mut digest = 0_u64;
+    for (bit, weight) in weights.iter().enumerate() {
+        if *weight >= 0 {
+            digest |= 1 << bit;
+        }
+    }
+    format!("{digest:016x}")
+}
+
+/// A digest as 16 lowercase hex digits, or `None` if it is not one
+pub fn parse_digest(digest: &str) -> Option<u64> {
+    let well_formed = digest.len() == 16
+        && digest.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    well_formed.then(|| u64::from_str_radix(digest, 16).ok()).flatten()
+}
+
+/// Hamming distance between two digests, in bits
+pub fn semantic_distance(a: &str, b: &str) -> Option<u32> {
+    Some((parse_digest(a)? ^ parse_digest(b)?).count_ones())
+}
+
+/// Distance as a fraction of `DIGEST_BITS`, the scale epsilon is given in
+pub fn normalized_distance(a: &str, b: &str) -> Option<f64> {
+    semantic_distance(a, b).map(|distance| f64::from(distance) / f64::from(DIGEST_BITS))
+}
+
+/// Whether `epsilon` is a usable bound on a normalized distance
+pub fn epsilon_in_bounds(epsilon: f64) -> bool {
+    (0.0..=1.0).contains(&epsilon)
+}
+
+/// A step as its semantic checks need it
+#[derive(Debug, Clone, Copy)]
+pub struct SemanticStep<'a> {
+    pub id: &'a str,
+    pub concordant: bool,
+    pub epsilon: Option<f64>,
+}
+
+/// A checkpoint's recorded digest and, when the bundle carries it, the
+/// output that digest was computed from
+#[derive(Debug, Clone, Copy)]
+pub struct SemanticCheckpoint<'a> {
+    pub id: &'a str,
+    pub semantic_digest: Option<&'a str>,
+    pub output: Option<&'a str>,
+}
+
+/// The proof-level record of a concordant replay
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SemanticProof<'a> {
+    pub epsilon: Option<f64>,
+    pub original_digest: Option<&'a str>,
+    pub replay_digest: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SemanticIssue {
+    /// `location` names the checkpoint, or the proof field
+    MalformedDigest { location: String },
+    MissingEpsilon { step: String },
+    /// `step` is `None` for the proof's own epsilon
+    EpsilonOutOfBounds { step: Option<String>, epsilon: f64 },
+    /// The digest recomputed from the checkpoint's output differs
+    DigestMismatch {
+        checkpoint: String,
+        recorded: String,
+        recomputed: String,
+    },
+    /// The recorded replay is further from the original than epsilon allows
+    ReplayOutsideEpsilon { distance: f64, epsilon: f64 },
+}
+
+impl fmt::Display for SemanticIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedDigest { location } => {
+                write!(f, "{location} is not a 16-digit hex semantic digest")
+            }
+            Self::MissingEpsilon { step } => {
+                write!(f, "Concordant step {step} records no epsilon")
+            }
+            Self::EpsilonOutOfBounds {
+                step: Some(step),
+                epsilon,
+            } => write!(f, "Step {step} has epsilon {epsilon}, outside [0, 1]"),
+            Self::EpsilonOutOfBounds { step: None, epsilon } => {
+                write!(f, "The proof's epsilon {epsilon} is outside [0, 1]")
+            }
+            Self::DigestMismatch {
+                checkpoint,
+                recorded,
+                recomputed,
+            } => write!(
+                f,
+                "Checkpoint {checkpoint} records semantic digest {recorded}, but its output \
+                 digests to {recomputed}"
+            ),
+            Self::ReplayOutsideEpsilon { distance, epsilon } => write!(
+                f,
+                "The replay lies at semantic distance {distance:.4}, beyond epsilon {epsilon}"
+            ),
+        }
+    }
+}
+
+/// Check the semantic digests and epsilons a CAR records
+pub fn semantic_issues(
+    steps: &[SemanticStep<'_>],
+    checkpoints: &[SemanticCheckpoint<'_>],
+    proof: &SemanticProof<'_>,
+) -> Vec<SemanticIssue> {
+    let mut issues = Vec::new();
+    for step in steps.iter().filter(|step| step.concordant) {
+        match step.epsilon {
+            None => issues.push(SemanticIssue::MissingEpsilon {
+                step: String::from(step.id),
+            }),
+            Some(epsilon) if !epsilon_in_bounds(epsilon) => {
+                issues.push(SemanticIssue::EpsilonOutOfBounds {
+                    step: Some(String::from(step.id)),
+                    epsilon,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for checkpoint in checkpoints {
+        let Some(recorded) = checkpoint.semantic_digest else {
+            continue;
+        };
+        if parse_digest(recorded).is_none() {
+            issues.push(SemanticIssue::MalformedDigest {
+                location: format!("Checkpoint {}", checkpoint.id),
+            });
+            continue;
+        }
+        if let Some(output) = checkpoint.output {
+            let recomputed = semantic_digest(output);
+            if recomputed != recorded {
+                issues.push(SemanticIssue::DigestMismatch {
+                    checkpoint: String::from(checkpoint.id),
+                    recorded: String::from(recorded),
+                    recomputed,
+                });
+            }
+        }
+    }
+
+    let mut digests_valid = true;
+    for (field, digest) in [
+        ("original_semantic_digest", proof.original_digest),
+        ("replay_semantic_digest", proof.replay_digest),
+    ] {
+        if digest.is_some_and(|digest| parse_digest(digest).is_none()) {
+            digests_valid = false;
+            issues.push(SemanticIssue::MalformedDigest {
+                location: format!("proof.{field}"),
+            });
+        }
+    }
+    if let Some(epsilon) = proof.epsilon {
+        if !epsilon_in_bounds(epsilon) {
+            issues.push(SemanticIssue::EpsilonOutOfBounds {
+                step: None,
+                epsilon,
+            });
+        } else if let (true, Some(original), Some(replay)) =
+            (digests_valid, proof.original_digest, proof.replay_digest)
+        {
+            let distance = normalized_distance(original, replay).unwrap_or(1.0);
+            if distance > epsilon {
+                issues.push(SemanticIssue::ReplayOutsideEpsilon { distance, epsilon });
+            }
+        }
+    }
+    issues
+}
+
+/// SipHash-1-3 under zero keys of `text` as `Hash for str` feeds it: its
+/// bytes, then 0xff
+fn sip13_str(text: &str) -> u64 {
+    let mut state = [
+        0x736f_6d65_7073_6575_u64,
+        0x646f_7261_6e64_6f6d,
+        0x6c79_6765_6e65_7261,
+        0x7465_6462_7974_6573,
+    ];
+    let mut message: Vec<u8> = Vec::with_capacity(text.len() + 1);
+    message.extend_from_slice(text.as_bytes());
+    message.push(0xff);
+
+    let mut words = message.chunks_exact(8);
+    for word in &mut words {
+        let m = u64::from_le_bytes(word.try_into().expect("chunks are 8 bytes"));
+        state[3] ^= m;
+        sip_round(&mut state);
+        state[0] ^= m;
+    }
+    let mut last = (message.len() as u64 & 0xff) << 56;
+    for (i, byte) in words.remainder().iter().enumerate() {
+        last |= u64::from(*byte) << (8 * i);
+    }
+    state[3] ^= last;
+    sip_round(&mut state);
+    state[0] ^= last;
+    state[2] ^= 0xff;
+    for _ in 0..3 {
+        sip_round(&mut state);
+    }
+    state[0] ^ state[1] ^ state[2] ^ state[3]
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    #[test]
+    fn features_hash_as_std_default_hasher_does() {
+        for text in ["", "a", "abc", "exactly8", "a longer feature than eight bytes", "é€😀"] {
+            let mut hasher = DefaultHasher::new();
+            text.hash(&mut hasher);
+            assert_eq!(sip13_str(text), hasher.finish(), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn recorded_digests_and_epsilons_are_checked() {
+        let output = "The quick brown fox jumps over the lazy dog";
+        let digest = semantic_digest(output);
+        assert_eq!(semantic_distance(&digest, &digest), Some(0));
+        assert_eq!(parse_digest("ABCDEF0123456789"), None);
+        assert_eq!(semantic_distance(&digest, "xyz"), None);
+
+        let steps = [
+            SemanticStep { id: "s1", concordant: true, epsilon: Some(0.2) },
+            SemanticStep { id: "s2", concordant: true, epsilon: Some(1.5) },
+            SemanticStep { id: "s3", concordant: true, epsilon: None },
+            SemanticStep { id: "s4", concordant: false, epsilon: None },
+        ];
+        let other = semantic_digest("Something else entirely, at some length");
+        let checkpoints = [
+            SemanticCheckpoint { id: "c1", semantic_digest: Some(&digest), output: Some(output) },
+            SemanticCheckpoint { id: "c2", semantic_digest: Some(&other), output: Some(output) },
+            SemanticCheckpoint { id: "c3", semantic_digest: Some("zz"), output: None },
+            SemanticCheckpoint { id: "c4", semantic_digest: None, output: Some(output) },
+        ];
+        let proof = SemanticProof {
+            epsilon: Some(0.0),
+            original_digest: Some(&digest),
+            replay_digest: Some(&other),
+        };
+        let issues = semantic_issues(&steps, &checkpoints, &proof);
+        assert_eq!(issues.len(), 5, "{issues:?}");
+        assert_eq!(
+            issues[0],
+            SemanticIssue::EpsilonOutOfBounds { step: Some("s2".into()), epsilon: 1.5 }
+        );
+        assert_eq!(issues[1], SemanticIssue::MissingEpsilon { step: "s3".into() });
+        assert!(matches!(&issues[2], SemanticIssue::DigestMismatch { checkpoint, .. }
+            if checkpoint == "c2"));
+        assert_eq!(issues[3], SemanticIssue::MalformedDigest { location: "Checkpoint c3".into() });
+        assert!(matches!(issues[4], SemanticIssue::ReplayOutsideEpsilon { .. }));
+
+        let within = SemanticProof { epsilon: Some(1.0), ..proof };
+        assert!(semantic_issues(&[], &[], &within).is_empty());
+    }
+}
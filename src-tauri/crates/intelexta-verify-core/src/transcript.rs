@@ -0,0 +1,240 @@
+// src-tauri/crates/intelexta-verify-core/src/transcript.rs
+//!
+//! Integrity of the chat transcript an interactive run's CAR carries
+//!
+//! Each turn of an interactive checkpoint is a pair of chained checkpoints:
+//! the human message, then the AI reply whose parent it is. CARs carry each
+//! message next to its checkpoint. The chain binds an AI reply through its
+//! `outputs_sha256` and, for checkpoints recorded since transcripts were
+//! exported, a human message through its `inputs_sha256`; older human
+//! messages are covered only by the CAR's body signature and are counted as
+//! unbound. A message withheld by redaction has no body and is only counted.
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::sha256_hex;
+
+pub const HUMAN: &str = "human";
+pub const AI: &str = "ai";
+
+/// A checkpoint as the transcript check needs it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TranscriptEntry<'a> {
+    pub id: &'a str,
+    pub parent_id: Option<&'a str>,
+    pub turn_index: Option<u32>,
+    pub inputs_sha256: Option<&'a str>,
+    pub outputs_sha256: Option<&'a str>,
+    /// `None` for a checkpoint that carries no message
+    pub role: Option<&'a str>,
+    /// `None` when the message was withheld
+    pub body: Option<&'a str>,
+}
+
+/// What a transcript that holds is made of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TranscriptSummary {
+    pub messages: usize,
+    pub withheld: usize,
+    /// Human messages from before the chain bound them
+    pub unbound: usize,
+}
+
+/// Why a transcript does not hold; checkpoint indexes count from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    UnknownRole {
+        index: usize,
+        id: String,
+        role: String,
+    },
+    /// A message on a checkpoint that is not a turn
+    NotATurn { index: usize, id: String },
+    /// The message does not hash to what its checkpoint recorded
+    MessageMismatch {
+        index: usize,
+        id: String,
+        expected: String,
+        computed: String,
+    },
+    /// An AI reply the chain does not bind
+    UnboundReply { index: usize, id: String },
+    /// An AI reply that does not follow the human message it answers
+    OutOfTurn { index: usize, id: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRole { index, id, role } => write!(
+                f,
+                "Checkpoint #{index} ({id}) carries a message of unknown role {role:?}"
+            ),
+            Self::NotATurn { index, id } => write!(
+                f,
+                "Checkpoint #{index} ({id}) carries a message but is not a chat turn"
+            ),
+            Self::MessageMismatch {
+                index,
+                id,
+                expected,
+                computed,
+            } => write!(
+                f,
+                "Message of checkpoint #{index} ({id}) does not match its checkpoint\n\
+                 Expected: {expected}\nComputed: {computed}"
+            ),
+            Self::UnboundReply { index, id } => write!(
+                f,
+                "AI message of checkpoint #{index} ({id}) has no outputs_sha256 binding it"
+            ),
+            Self::OutOfTurn { index, id } => write!(
+                f,
+                "AI message of checkpoint #{index} ({id}) does not answer the human \
+                 message before it"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for TranscriptError {}
+
+/// Check every message against its checkpoint, and every AI reply against
+/// the human message it answers
+pub fn check_transcript(
+    entries: &[TranscriptEntry<'_>],
+) -> Result<TranscriptSummary, TranscriptError> {
+    let mut summary = TranscriptSummary::default();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(role) = entry.role else {
+            continue;
+        };
+        let id = || String::from(entry.id);
+        let Some(turn_index) = entry.turn_index else {
+            return Err(TranscriptError::NotATurn { index, id: id() });
+        };
+
+        let bound_to = match role {
+            HUMAN => entry.inputs_sha256,
+            AI => {
+                let answers = entry
+                    .parent_id
+                    .and_then(|parent| entries.iter().find(|other| other.id == parent));
+                let in_turn = answers.is_some_and(|parent| {
+                    parent.role == Some(HUMAN)
+                        && parent.turn_index.map(|turn| turn + 1) == Some(turn_index)
+                });
+                if !in_turn {
+                    return Err(TranscriptError::OutOfTurn { index, id: id() });
+                }
+                if entry.outputs_sha256.is_none() {
+                    return Err(TranscriptError::UnboundReply { index, id: id() });
+                }
+                entry.outputs_sha256
+            }
+            other => {
+                return Err(TranscriptError::UnknownRole {
+                    index,
+                    id: id(),
+                    role: String::from(other),
+                })
+            }
+        };
+
+        summary.messages += 1;
+        let Some(body) = entry.body else {
+            summary.withheld += 1;
+            continue;
+        };
+        match bound_to {
+            Some(expected) => {
+                let computed = sha256_hex(body.as_bytes());
+                if computed != expected {
+                    return Err(TranscriptError::MessageMismatch {
+                        index,
+                        id: id(),
+                        expected: String::from(expected),
+                        computed,
+                    });
+                }
+            }
+            None => summary.unbound += 1,
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn messages_must_hash_to_their_checkpoints_and_replies_follow_turns() {
+        let question = sha256_hex(b"What is 2 + 2?");
+        let answer = sha256_hex(b"4");
+        let human = TranscriptEntry {
+            id: "h0",
+            turn_index: Some(0),
+            inputs_sha256: Some(&question),
+            role: Some(HUMAN),
+            body: Some("What is 2 + 2?"),
+            ..TranscriptEntry::default()
+        };
+        let ai = TranscriptEntry {
+            id: "a1",
+            parent_id: Some("h0"),
+            turn_index: Some(1),
+            outputs_sha256: Some(&answer),
+            role: Some(AI),
+            body: Some("4"),
+            ..TranscriptEntry::default()
+        };
+        let step = TranscriptEntry {
+            id: "s",
+            ..TranscriptEntry::default()
+        };
+        let summary = check_transcript(&[step, human, ai]).unwrap();
+        assert_eq!(
+            summary,
+            TranscriptSummary {
+                messages: 2,
+                withheld: 0,
+                unbound: 0
+            }
+        );
+
+        let legacy = TranscriptEntry {
+            inputs_sha256: None,
+            ..human
+        };
+        let withheld = TranscriptEntry { body: None, ..ai };
+        let summary = check_transcript(&[legacy, withheld]).unwrap();
+        assert_eq!((summary.unbound, summary.withheld), (1, 1));
+
+        let tampered = TranscriptEntry {
+            body: Some("5"),
+            ..ai
+        };
+        assert!(matches!(
+            check_transcript(&[human, tampered]),
+            Err(TranscriptError::MessageMismatch { index: 1, .. })
+        ));
+        assert_eq!(
+            check_transcript(&[ai]),
+            Err(TranscriptError::OutOfTurn {
+                index: 0,
+                id: "a1".to_string()
+            })
+        );
+        let system = TranscriptEntry {
+            role: Some("system"),
+            ..human
+        };
+        assert!(matches!(
+            check_transcript(&[system]),
+            Err(TranscriptError::UnknownRole { .. })
+        ));
+    }
+}
@@ -0,0 +1,1253 @@
+//! Verification engine behind the `intelexta-verify` CLI and the
+//! `intelexta-verifyd` HTTP service
+//!
+//! Loads a CAR from JSON, DAG-CBOR or a ZIP bundle and checks its hash chain,
+//! signatures, content integrity, semantic digests, chat transcripts,
+//! co-signatures, timestamp and transparency log entry within caller-imposed
+//! limits, producing a `VerificationReport`. Output formatting is left to the
+//! callers.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+use rayon::prelude::*;
+
+use intelexta::car::{
+    check_redactions, negotiate_format, parse_attachment_entry, parse_signature,
+    AttachmentCompression, Budgets, Car, ClaimRegistry, ClaimStatus, FormatSpec,
+    ProcessCheckpointProof, SignatureKind, SignerRole, UnsupportedFormatVersion,
+};
+use intelexta::car_cbor;
+use intelexta::verify::{
+    check_cosignatures, verify_external_attachments, ExternalAttachments, SignerCheck,
+    SignerTrust,
+};
+use intelexta::timestamp::{self, TimestampInfo};
+use intelexta::transparency::{self, LogEntryProof};
+use intelexta_archive::{ArchiveError, ArchiveLimits, SafeArchive};
+use intelexta_verify_core::budget::{self, CheckpointUsage, Totals};
+use intelexta_verify_core::clock::{self, ClockReading};
+use intelexta_verify_core::semantic::{
+    self, SemanticCheckpoint, SemanticProof, SemanticStep,
+};
+use intelexta_verify_core::transcript::{self, TranscriptEntry};
+use intelexta_verify_core::AttachmentHasher;
+
+/// Attachments read from the archive before hashing them in parallel
+const HASH_BATCH: usize = 64;
+
+/// Prefix of the failures the semantic digest stage reports
+pub const SEMANTIC_FAILURE: &str = "Semantic digest check failed";
+
+/// Prefix of the failures the chat transcript stage reports
+pub const TRANSCRIPT_FAILURE: &str = "Transcript verification failed";
+
+/// Resource limits for verifying untrusted CARs
+#[derive(Debug, Clone, Default)]
+pub struct VerificationLimits {
+    pub max_checkpoints: Option<usize>,
+    pub max_attachment_bytes: Option<u64>,
+    pub time_budget: Option<Duration>,
+    /// Stream attachments through a fixed buffer rather than reading them
+    /// into memory, see `--stream`
+    pub stream: bool,
+    /// Run every check after a failed one, see `--continue-on-error`
+    pub continue_on_error: bool,
+}
+
+impl VerificationLimits {
+    /// Limits for opening bundles. Streaming never holds an entry in memory,
+    /// so only the entry count and compression ratio stay bounded.
+    pub fn archive_limits(&self) -> ArchiveLimits {
+        if self.stream {
+            ArchiveLimits {
+                max_entry_bytes: u64::MAX,
+                max_total_bytes: u64::MAX,
+                ..ArchiveLimits::default()
+            }
+        } else {
+            ArchiveLimits::default()
+        }
+    }
+}
+
+/// Verification stopped at a caller-imposed limit. This is reported separately
+/// from a failed verification: it says nothing about tampering.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LimitExceeded {
+    pub limit: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Verification limit exceeded ({}): {}", self.limit, self.message)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+pub struct Budget {
+    pub limits: VerificationLimits,
+    started: Instant,
+}
+
+impl Budget {
+    pub fn new(limits: VerificationLimits) -> Self {
+        Self {
+            limits,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn check_time(&self) -> Result<(), LimitExceeded> {
+        match self.limits.time_budget {
+            Some(budget) if self.started.elapsed() > budget => Err(LimitExceeded {
+                limit: "time_budget_ms",
+                message: format!(
+                    "verification exceeded the {} ms time budget",
+                    budget.as_millis()
+                ),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_checkpoints(&self, count: usize) -> Result<(), LimitExceeded> {
+        match self.limits.max_checkpoints {
+            Some(max) if count > max => Err(LimitExceeded {
+                limit: "max_checkpoints",
+                message: format!("CAR has {} checkpoints, exceeding the limit of {}", count, max),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn check_attachment_bytes(&self, total: u64) -> Result<(), LimitExceeded> {
+        match self.limits.max_attachment_bytes {
+            Some(max) if total > max => Err(LimitExceeded {
+                limit: "max_attachment_bytes",
+                message: format!("attachments exceed the limit of {} bytes", max),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct VerificationReport {
+    pub car_id: String,
+    /// Canonical UTC, as signed
+    pub created_at: String,
+    pub format_version: u32,
+    pub file_integrity: bool,
+    pub hash_chain_valid: bool,
+    pub signatures_valid: bool,
+    pub content_integrity_valid: bool,
+    pub checkpoints_verified: usize,
+    pub checkpoints_total: usize,
+    /// Checkpoints whose generation was served from the response cache
+    #[serde(skip_serializing_if = "is_zero")]
+    pub cached_checkpoints: usize,
+    /// Checkpoint times that run backwards, postdate the CAR or lie in the
+    /// future; suspicious, but they do not fail verification
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub clock_warnings: Vec<String>,
+    /// Recorded usage that disagrees with the checkpoints or exceeds the
+    /// policy budgets the CAR embeds; flagged, but not a failure
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub policy_violations: Vec<String>,
+    /// Checkpoints whose semantic digest was recomputed from the output the
+    /// bundle carries
+    #[serde(skip_serializing_if = "is_zero")]
+    pub semantic_digests_recomputed: usize,
+    /// Chat messages of interactive checkpoints checked against the chain
+    #[serde(skip_serializing_if = "is_zero")]
+    pub transcript_messages: usize,
+    /// Of those, messages whose body was withheld by redaction
+    #[serde(skip_serializing_if = "is_zero")]
+    pub transcript_messages_withheld: usize,
+    /// Of those, human messages recorded before the chain bound them
+    #[serde(skip_serializing_if = "is_zero")]
+    pub transcript_messages_unbound: usize,
+    pub provenance_claims_verified: usize,
+    pub provenance_claims_total: usize,
+    /// Claim types this verifier has no validator for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unvalidated_extensions: Vec<String>,
+    /// Fields and attachments withheld behind salted commitments
+    #[serde(skip_serializing_if = "is_zero")]
+    pub redactions: usize,
+    /// Execution sections of a multi-execution CAR whose chains and
+    /// signatures verified
+    #[serde(skip_serializing_if = "is_zero")]
+    pub executions_verified: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub executions_total: usize,
+    pub overall_result: bool,
+    /// The first failed check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Every failed check, in order; with --continue-on-error there may be
+    /// more than one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_exceeded: Option<LimitExceeded>,
+    /// Set when the CAR's format version is not one this verifier knows;
+    /// nothing else was checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsupported_format: Option<UnsupportedFormatVersion>,
+    /// The TSA's word on when the CAR existed; its stamped digest was
+    /// checked, its signature was not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<TimestampInfo>,
+    /// Where the bundle says the CAR was logged; the entry and its
+    /// inclusion proof were checked, the log's signed checkpoint was not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transparency_log: Option<TransparencyLogReport>,
+    /// Files the CAR binds by hash without bundling them
+    #[serde(skip_serializing_if = "is_zero")]
+    pub external_attachments_total: usize,
+    /// Outcome of checking them against --attachments-dir; absent when no
+    /// directory was given and they were not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_attachments: Option<ExternalAttachments>,
+    /// Whether --trusted-keys lists the signer; absent without a trust
+    /// store or when the signatures did not verify
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer_trust: Option<SignerTrust>,
+    /// The author and each reviewer or organization that countersigned;
+    /// absent when verification stopped before the co-signatures
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signers: Vec<SignerCheck>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TransparencyLogReport {
+    pub log_url: String,
+    pub uuid: String,
+    pub log_index: u64,
+    pub integrated_time: i64,
+    pub tree_size: u64,
+    pub root_hash: String,
+}
+
+/// Load CAR from a JSON, DAG-CBOR or ZIP file
+/// Returns the parsed CAR, the raw JSON string, and the path to use for attachment verification
+pub fn load_car_file(path: &PathBuf, limits: ArchiveLimits) -> Result<(Car, String, PathBuf)> {
+    let extension = path.extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+
+    let (car, raw_json) = match extension {
+        "zip" => load_car_from_zip(path, limits)?,
+        "json" => load_car_from_json(path)?,
+        "cbor" => load_car_from_cbor(path)?,
+        _ => {
+            // Try JSON first, then DAG-CBOR, then ZIP
+            load_car_from_json(path)
+                .or_else(|_| load_car_from_cbor(path))
+                .or_else(|_| load_car_from_zip(path, limits))
+                .with_context(|| format!("Could not parse CAR file: {}", path.display()))?
+        }
+    };
+
+    Ok((car, raw_json, path.clone()))
+}
+
+/// Load CAR from JSON file
+fn load_car_from_json(path: &PathBuf) -> Result<(Car, String)> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    let car = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse CAR JSON from: {}", path.display()))?;
+
+    Ok((car, contents))
+}
+
+/// Load CAR from a DAG-CBOR file, decoded to the JSON its signatures cover
+fn load_car_from_cbor(path: &PathBuf) -> Result<(Car, String)> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+    parse_cbor_car(&bytes)
+        .with_context(|| format!("Failed to parse CAR CBOR from: {}", path.display()))
+}
+
+fn parse_cbor_car(bytes: &[u8]) -> Result<(Car, String)> {
+    let value = car_cbor::decode(bytes)?;
+    let contents = serde_json::to_string(&value)?;
+    let car = serde_json::from_value(value)?;
+    Ok((car, contents))
+}
+
+/// Load CAR from ZIP file (extract car.json or car.cbor)
+fn load_car_from_zip(path: &PathBuf, limits: ArchiveLimits) -> Result<(Car, String)> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open ZIP file: {}", path.display()))?;
+
+    let mut archive = open_archive(file, limits)
+        .with_context(|| format!("Failed to read ZIP archive: {}", path.display()))?;
+
+    // DAG-CBOR bundles carry car.cbor in place of car.json
+    if archive.names().iter().any(|name| name == car_cbor::BUNDLE_ENTRY) {
+        let car_bytes = archive.read_by_name(car_cbor::BUNDLE_ENTRY)
+            .with_context(|| "Failed to read car.cbor from ZIP")?;
+        return parse_cbor_car(&car_bytes).context("Failed to parse car.cbor from ZIP");
+    }
+
+    // Find and read car.json
+    let car_bytes = archive.read_by_name("car.json")
+        .with_context(|| "CAR ZIP must contain a readable car.json")?;
+
+    let contents = String::from_utf8(car_bytes)
+        .context("Failed to read car.json from ZIP")?;
+
+    let car = serde_json::from_str(&contents)
+        .context("Failed to parse car.json from ZIP")?;
+
+    Ok((car, contents))
+}
+
+/// Open a CAR ZIP, reporting archive size refusals as a limit rather than tampering
+fn open_archive(file: fs::File, limits: ArchiveLimits) -> Result<SafeArchive<fs::File>> {
+    match SafeArchive::new(file, limits) {
+        Ok(archive) => Ok(archive),
+        Err(err) if err.is_limit() => Err(LimitExceeded {
+            limit: "archive",
+            message: err.to_string(),
+        }
+        .into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Main verification logic
+pub fn verify_car(
+    car: &Car,
+    raw_json: &str,
+    car_path: &PathBuf,
+    attachments_dir: Option<&Path>,
+    budget: &Budget,
+) -> Result<VerificationReport> {
+    let mut report = VerificationReport {
+        car_id: car.id.clone(),
+        created_at: car.created_at.to_rfc3339(),
+        format_version: car.format_version,
+        file_integrity: true,
+        hash_chain_valid: false,
+        signatures_valid: false,
+        content_integrity_valid: false,
+        checkpoints_verified: 0,
+        checkpoints_total: 0,
+        cached_checkpoints: 0,
+        clock_warnings: Vec::new(),
+        policy_violations: Vec::new(),
+        semantic_digests_recomputed: 0,
+        transcript_messages: 0,
+        transcript_messages_withheld: 0,
+        transcript_messages_unbound: 0,
+        provenance_claims_verified: 0,
+        provenance_claims_total: 0,
+        unvalidated_extensions: Vec::new(),
+        redactions: 0,
+        executions_verified: 0,
+        executions_total: car.proof.executions.len(),
+        overall_result: false,
+        error: None,
+        failures: Vec::new(),
+        limit_exceeded: None,
+        unsupported_format: None,
+        timestamp: None,
+        transparency_log: None,
+        external_attachments_total: car.external_attachments.len(),
+        external_attachments: None,
+        signer_trust: None,
+        signers: Vec::new(),
+    };
+
+    // Which signatures and checks apply is decided by the declared format
+    // version, never guessed from the signatures present
+    let format = match negotiate_format(car.format_version) {
+        Ok(format) => format,
+        Err(unsupported) => {
+            report.error = Some(unsupported.to_string());
+            report.unsupported_format = Some(unsupported);
+            return Ok(report);
+        }
+    };
+
+    // Get process proof checkpoints
+    let checkpoints = match &car.proof.process {
+        Some(process) => &process.sequential_checkpoints,
+        None => {
+            report.error = Some(format!(
+                "CAR has no process proof (match_kind: {}). This CAR was likely exported with an older version of Intelexta. \
+                 Please re-export the CAR to include cryptographic signatures for verification.",
+                car.proof.match_kind
+            ));
+            return Ok(report);
+        }
+    };
+
+    report.checkpoints_total = checkpoints.len();
+    report.cached_checkpoints = checkpoints.iter().filter(|ck| ck.cache_hit).count();
+
+    if checkpoints.is_empty() {
+        report.error = Some("CAR has no checkpoints to verify".to_string());
+        return Ok(report);
+    }
+
+    if let Err(exceeded) = budget.check_checkpoints(checkpoints.len()) {
+        return Ok(report.stopped_at_limit(exceeded));
+    }
+
+    // Verify hash chain
+    match verify_hash_chain(checkpoints, budget) {
+        Ok(verified_count) => {
+            report.hash_chain_valid = true;
+            report.checkpoints_verified = verified_count;
+        }
+        Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                return Ok(report.stopped_at_limit(exceeded.clone()));
+            }
+            if report.fail(format!("Hash chain verification failed: {}", e), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    report.clock_warnings = clock_warnings(car, checkpoints);
+    report.policy_violations = policy_violations(car, checkpoints);
+
+    // Verify top-level body signature (if present)
+    let body_signature = verify_top_level_signature(car, raw_json, &format);
+    if let Err(e) = &body_signature {
+        if report.fail(format!("Top-level body signature verification failed: {}", e), budget) {
+            return Ok(report);
+        }
+    }
+
+    // Verify signatures
+    match verify_signatures(&car.signer_public_key, checkpoints, budget) {
+        Ok(_) => {
+            report.signatures_valid = body_signature.is_ok();
+        }
+        Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                return Ok(report.stopped_at_limit(exceeded.clone()));
+            }
+            if report.fail(format!("Signature verification failed: {}", e), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // Multi-execution CARs: every execution section is a chain of its own
+    for execution in &car.proof.executions {
+        let checkpoints = &execution.process.sequential_checkpoints;
+        let outcome = verify_hash_chain(checkpoints, budget)
+            .and_then(|_| verify_signatures(&car.signer_public_key, checkpoints, budget));
+        if let Err(e) = outcome {
+            if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                return Ok(report.stopped_at_limit(exceeded.clone()));
+            }
+            let message = format!(
+                "Execution {} verification failed: {}",
+                execution.run_execution_id, e
+            );
+            if report.fail(message, budget) {
+                return Ok(report);
+            }
+            continue;
+        }
+        report.executions_verified += 1;
+    }
+
+    // Verify content integrity (provenance claims + attachments)
+    match verify_content_integrity(car, car_path, budget) {
+        Ok((verified_count, unvalidated)) => {
+            report.content_integrity_valid = true;
+            report.provenance_claims_verified = verified_count;
+            report.unvalidated_extensions = unvalidated;
+            report.provenance_claims_total = car.provenance.len();
+        }
+        Err(e) => {
+            report.provenance_claims_total = car.provenance.len();
+            if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                return Ok(report.stopped_at_limit(exceeded.clone()));
+            }
+            if report.fail(format!("Content integrity verification failed: {}", e), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // Concordant steps need a usable epsilon, and recorded semantic digests
+    // must match the outputs the bundle carries; only hash-checked outputs
+    // are read back
+    let outputs = if report.content_integrity_valid {
+        semantic_outputs(car, car_path, budget)
+    } else {
+        Ok(HashMap::new())
+    };
+    match outputs {
+        Ok(outputs) => {
+            let (issues, recomputed) = semantic_issues(car, &outputs);
+            report.semantic_digests_recomputed = recomputed;
+            for issue in issues {
+                if report.fail(format!("{SEMANTIC_FAILURE}: {issue}"), budget) {
+                    return Ok(report);
+                }
+            }
+        }
+        Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<LimitExceeded>() {
+                return Ok(report.stopped_at_limit(exceeded.clone()));
+            }
+            if report.fail(format!("{SEMANTIC_FAILURE}: {e}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // Each chat message must hash to its checkpoint, and each AI reply
+    // answer the human message before it
+    match transcript::check_transcript(&transcript_entries(car)) {
+        Ok(summary) => {
+            report.transcript_messages = summary.messages;
+            report.transcript_messages_withheld = summary.withheld;
+            report.transcript_messages_unbound = summary.unbound;
+        }
+        Err(e) => {
+            if report.fail(format!("{TRANSCRIPT_FAILURE}: {e}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // Redacted fields must carry the commitments the signed body lists
+    match check_redactions(car) {
+        Ok(count) => report.redactions = count,
+        Err(reason) => {
+            if report.fail(format!("Redaction check failed: {reason}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // A trusted timestamp must stamp this CAR's id
+    match timestamp::verify_car_timestamp(car) {
+        Ok(info) => report.timestamp = info,
+        Err(reason) => {
+            if report.fail(format!("Timestamp verification failed: {reason}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // Co-signatures must verify under the keys they name
+    match check_cosignatures(car) {
+        Ok(cosigners) => {
+            report.signers.push(SignerCheck {
+                role: SignerRole::Author,
+                public_key: car.signer_public_key.clone(),
+                valid: report.signatures_valid,
+            });
+            report.signers.extend(cosigners);
+            if let Some(invalid) = report.signers[1..].iter().find(|signer| !signer.valid) {
+                if report.fail(format!(
+                    "Co-signature verification failed: {} signature by {} does not verify",
+                    invalid.role.as_str(),
+                    invalid.public_key
+                ), budget) {
+                    return Ok(report);
+                }
+            }
+        }
+        Err(reason) => {
+            if report.fail(format!("Co-signature verification failed: {reason}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // A bundle's transparency log entry must log this CAR and prove into the
+    // logged tree
+    let log_entry = load_log_entry(car_path, budget.limits.archive_limits()).and_then(|entry| match entry {
+        Some(entry) => transparency::verify_entry(car, &entry)
+            .map(|_| Some(entry))
+            .map_err(|reason| anyhow!(reason)),
+        None => Ok(None),
+    });
+    match log_entry {
+        Ok(entry) => report.transparency_log = entry.map(TransparencyLogReport::from),
+        Err(e) => {
+            if report.fail(format!("Transparency log verification failed: {e}"), budget) {
+                return Ok(report);
+            }
+        }
+    }
+
+    // External attachments are checked against local copies when given
+    if let Some(dir) = attachments_dir {
+        match verify_external_attachments(car, dir) {
+            Ok(found) if !found.missing.is_empty() => {
+                let message = format!(
+                    "External attachments have no copy in {}: {}",
+                    dir.display(),
+                    found.missing.join(", ")
+                );
+                report.external_attachments = Some(found);
+                if report.fail(message, budget) {
+                    return Ok(report);
+                }
+            }
+            Ok(found) => report.external_attachments = Some(found),
+            Err(e) => {
+                if report.fail(format!("External attachment verification failed: {e}"), budget) {
+                    return Ok(report);
+                }
+            }
+        }
+    }
+
+    // Overall result
+    report.overall_result = report.file_integrity
+        && report.hash_chain_valid
+        && report.signatures_valid
+        && report.content_integrity_valid
+        && report.checkpoints_verified == report.checkpoints_total
+        && report.executions_verified == report.executions_total
+        && report.failures.is_empty();
+
+    Ok(report)
+}
+
+impl VerificationReport {
+    /// Record a failed check. Returns whether verification stops there, as
+    /// it does unless `--continue-on-error` asked for every check to run.
+    fn fail(&mut self, message: String, budget: &Budget) -> bool {
+        self.error.get_or_insert_with(|| message.clone());
+        self.failures.push(message);
+        !budget.limits.continue_on_error
+    }
+
+    fn stopped_at_limit(mut self, exceeded: LimitExceeded) -> Self {
+        // A failure --continue-on-error already found outweighs the limit
+        if !self.failures.is_empty() {
+            self.failures.push(exceeded.to_string());
+            return self;
+        }
+        self.error = Some(exceeded.to_string());
+        self.limit_exceeded = Some(exceeded);
+        self.overall_result = false;
+        self
+    }
+}
+
+impl From<LogEntryProof> for TransparencyLogReport {
+    fn from(entry: LogEntryProof) -> Self {
+        Self {
+            log_url: entry.log_url,
+            uuid: entry.uuid,
+            log_index: entry.log_index,
+            integrated_time: entry.integrated_time,
+            tree_size: entry.inclusion_proof.tree_size,
+            root_hash: entry.inclusion_proof.root_hash,
+        }
+    }
+}
+
+/// The transparency log entry a bundle carries next to its CAR, if any
+fn load_log_entry(car_path: &PathBuf, limits: ArchiveLimits) -> Result<Option<LogEntryProof>> {
+    if car_path.extension().and_then(|s| s.to_str()) != Some("zip") {
+        return Ok(None);
+    }
+    let file = fs::File::open(car_path)
+        .with_context(|| format!("Failed to open ZIP file: {}", car_path.display()))?;
+    let mut archive = open_archive(file, limits)?;
+    if !archive.names().iter().any(|name| name == transparency::BUNDLE_ENTRY) {
+        return Ok(None);
+    }
+    let bytes = archive.read_by_name(transparency::BUNDLE_ENTRY)?;
+    let entry = serde_json::from_slice(&bytes)
+        .with_context(|| format!("{} is not a log entry", transparency::BUNDLE_ENTRY))?;
+    Ok(Some(entry))
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Checkpoint times that do not fit the run's clock
+fn clock_warnings(car: &Car, checkpoints: &[ProcessCheckpointProof]) -> Vec<String> {
+    let readings: Vec<ClockReading> = checkpoints
+        .iter()
+        .map(|checkpoint| ClockReading {
+            id: &checkpoint.id,
+            at_ms: chrono::DateTime::parse_from_rfc3339(&checkpoint.timestamp)
+                .ok()
+                .map(|at| at.timestamp_millis()),
+        })
+        .collect();
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    clock::clock_anomalies(&readings, car.created_at.timestamp_millis(), now_ms)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Recorded usage checked against the checkpoints and the policy's budgets
+fn policy_violations(car: &Car, checkpoints: &[ProcessCheckpointProof]) -> Vec<String> {
+    let usage: Vec<CheckpointUsage> = checkpoints
+        .iter()
+        .map(|checkpoint| CheckpointUsage {
+            usage_tokens: checkpoint.usage_tokens,
+            usd_cost: checkpoint.usd_cost,
+            nature_cost: checkpoint.nature_cost,
+        })
+        .collect();
+    let totals = |budgets: &Budgets| Totals {
+        tokens: budgets.tokens,
+        usd: budgets.usd,
+        nature_cost: budgets.nature_cost,
+    };
+    let limits = car.policy_ref.limits.as_ref().map(totals);
+    budget::budget_violations(&totals(&car.budgets), &usage, limits.as_ref())
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Every checkpoint of the CAR, its execution sections included, once each
+fn all_checkpoints(car: &Car) -> Vec<&ProcessCheckpointProof> {
+    let mut seen = HashSet::new();
+    car.proof
+        .process
+        .iter()
+        .chain(car.proof.executions.iter().map(|execution| &execution.process))
+        .flat_map(|process| &process.sequential_checkpoints)
+        .filter(|checkpoint| seen.insert(checkpoint.id.as_str()))
+        .collect()
+}
+
+/// The CAR's checkpoints as the transcript check reads them
+fn transcript_entries(car: &Car) -> Vec<TranscriptEntry<'_>> {
+    all_checkpoints(car)
+        .into_iter()
+        .map(|checkpoint| TranscriptEntry {
+            id: &checkpoint.id,
+            parent_id: checkpoint.parent_checkpoint_id.as_deref(),
+            turn_index: checkpoint.turn_index,
+            inputs_sha256: checkpoint.inputs_sha256.as_deref(),
+            outputs_sha256: checkpoint.outputs_sha256.as_deref(),
+            role: checkpoint.message.as_ref().map(|message| message.role.as_str()),
+            body: checkpoint.message.as_ref().and_then(|message| message.body.as_deref()),
+        })
+        .collect()
+}
+
+/// Semantic digests and epsilons checked against the CAR's steps, its
+/// proof and `outputs`, the bundled outputs by hash. Returns the issues
+/// found and how many digests were recomputed.
+fn semantic_issues(car: &Car, outputs: &HashMap<String, String>) -> (Vec<String>, usize) {
+    let steps: Vec<SemanticStep> = car
+        .run
+        .steps
+        .iter()
+        .map(|step| SemanticStep {
+            id: &step.id,
+            concordant: step.proof_mode.is_concordant() && !step.is_interactive_chat(),
+            epsilon: step.epsilon,
+        })
+        .collect();
+    let checkpoints: Vec<SemanticCheckpoint> = all_checkpoints(car)
+        .into_iter()
+        .map(|checkpoint| SemanticCheckpoint {
+            id: &checkpoint.id,
+            semantic_digest: checkpoint.semantic_digest.as_deref(),
+            output: checkpoint
+                .outputs_sha256
+                .as_ref()
+                .and_then(|hash| outputs.get(hash))
+                .map(String::as_str),
+        })
+        .collect();
+    let recomputed = checkpoints
+        .iter()
+        .filter(|checkpoint| checkpoint.semantic_digest.is_some() && checkpoint.output.is_some())
+        .count();
+    let proof = SemanticProof {
+        epsilon: car.proof.epsilon,
+        original_digest: car.proof.original_semantic_digest.as_deref(),
+        replay_digest: car.proof.replay_semantic_digest.as_deref(),
+    };
+    let issues = semantic::semantic_issues(&steps, &checkpoints, &proof)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    (issues, recomputed)
+}
+
+/// The bundled outputs of checkpoints that record a semantic digest, keyed
+/// by hash. An attachment named by a checkpoint's `outputs_sha256` is the
+/// text the digest was taken from; other attachments are not read.
+fn semantic_outputs(
+    car: &Car,
+    car_path: &PathBuf,
+    budget: &Budget,
+) -> Result<HashMap<String, String>> {
+    let wanted: HashSet<&str> = all_checkpoints(car)
+        .into_iter()
+        .filter(|checkpoint| checkpoint.semantic_digest.is_some())
+        .filter_map(|checkpoint| checkpoint.outputs_sha256.as_deref())
+        .collect();
+    let mut outputs = HashMap::new();
+    if wanted.is_empty() {
+        return Ok(outputs);
+    }
+    if car_path.extension().and_then(|s| s.to_str()) == Some("zip") {
+        let file = fs::File::open(car_path)
+            .with_context(|| format!("Failed to open ZIP file: {}", car_path.display()))?;
+        let mut archive = open_archive(file, budget.limits.archive_limits())?;
+        let names = archive.names().to_vec();
+        for (i, name) in names.iter().enumerate() {
+            let Some((hash, compression)) = parse_attachment_entry(name) else {
+                continue;
+            };
+            if !wanted.contains(hash) {
+                continue;
+            }
+            let content = archive
+                .read_index(i)
+                .with_context(|| format!("Failed to read attachment file: {}", name))?
+                .unwrap_or_default();
+            budget.check_time()?;
+            let text = output_text(name, compression, content)?;
+            outputs.extend(text.map(|text| (hash.to_string(), text)));
+        }
+        return Ok(outputs);
+    }
+
+    let Some(dir) = car_path.parent().map(|parent| parent.join("attachments")) else {
+        return Ok(outputs);
+    };
+    for hash in wanted {
+        for (file_name, compression) in [
+            (format!("{hash}.txt"), None),
+            (format!("{hash}.txt.zst"), Some(AttachmentCompression::Zstd)),
+        ] {
+            let path = dir.join(&file_name);
+            if path.is_file() {
+                let content = fs::read(&path).with_context(|| {
+                    format!("Failed to read attachment file: {}", path.display())
+                })?;
+                budget.check_time()?;
+                let text = output_text(&file_name, compression, content)?;
+                outputs.extend(text.map(|text| (hash.to_string(), text)));
+            }
+        }
+    }
+    Ok(outputs)
+}
+
+/// An output attachment as text; `None` when it is not UTF-8, as no output
+/// that was digested can be
+fn output_text(
+    name: &str,
+    compression: Option<AttachmentCompression>,
+    content: Vec<u8>,
+) -> Result<Option<String>> {
+    let content = match compression {
+        Some(compression) => compression
+            .decompress(&content, ArchiveLimits::default().max_entry_bytes)
+            .map_err(|reason| anyhow!("{}: {}", name, reason))?,
+        None => content,
+    };
+    Ok(String::from_utf8(content).ok())
+}
+
+/// Verify the hash chain across all checkpoints
+fn verify_hash_chain(checkpoints: &[ProcessCheckpointProof], budget: &Budget) -> Result<usize> {
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        budget.check_time()?;
+        intelexta_verify_core::check_chain_link(i, checkpoint)?;
+    }
+    Ok(checkpoints.len())
+}
+
+/// Verify Ed25519 signatures on all checkpoints
+fn verify_signatures(
+    public_key_b64: &str,
+    checkpoints: &[ProcessCheckpointProof],
+    budget: &Budget,
+) -> Result<()> {
+    let public_key = intelexta_verify_core::decode_verifying_key(public_key_b64)
+        .context("Invalid signer public key")?;
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        budget.check_time()?;
+        intelexta_verify_core::check_checkpoint_signature(i, checkpoint, &public_key)?;
+    }
+    Ok(())
+}
+
+/// Verify top-level body signature (required from format version 2 on)
+///
+/// Current CARs carry dual signatures:
+/// - ed25519-body:<sig> - covers entire CAR body (prevents tampering with created_at, budgets, etc.)
+/// - ed25519-checkpoint:<sig> - covers checkpoint chain hash (verified by verify_signatures)
+fn verify_top_level_signature(car: &Car, raw_json: &str, format: &FormatSpec) -> Result<()> {
+    if car.signatures.is_empty() {
+        return Err(anyhow!("No signatures found in CAR"));
+    }
+    format.check_signatures(&car.signatures).map_err(|reason| anyhow!(reason))?;
+
+    let body_signature = car.signatures.iter().find_map(|entry| match parse_signature(entry) {
+        Some((SignatureKind::Body, encoded)) => Some(encoded),
+        _ => None,
+    });
+
+    if let Some(sig_b64) = body_signature {
+        if car.signer_public_key.is_empty() {
+            return Err(anyhow!("Top-level signature present but signer_public_key is empty"));
+        }
+        let car_json: serde_json::Value = serde_json::from_str(raw_json)
+            .context("Failed to parse raw JSON")?;
+        let public_key = intelexta_verify_core::decode_verifying_key(&car.signer_public_key)
+            .context("Invalid signer public key")?;
+        intelexta_verify_core::check_body_signature(&car_json, &public_key, sig_b64)?;
+    }
+    // else: a legacy CAR without a body signature, which its format allows
+
+    Ok(())
+}
+
+/// Verify content integrity by checking provenance claims and attachment files.
+/// Returns the number of verified claims and the claim types left unvalidated.
+fn verify_content_integrity(
+    car: &Car,
+    car_path: &PathBuf,
+    budget: &Budget,
+) -> Result<(usize, Vec<String>)> {
+    // Step 1: Verify provenance claims with the registered claim types
+    let claims = ClaimRegistry::builtin().verify(car);
+    if let Some(failure) = claims.failures().next() {
+        if let ClaimStatus::Failed { reason } = &failure.status {
+            return Err(anyhow!(
+                "{} claim failed at provenance claim #{}: {}",
+                failure.claim_type,
+                failure.index,
+                reason
+            ));
+        }
+    }
+
+    // Step 2: Verify all attachment files in the CAR
+    // Attachments are self-verifying: filename = hash of content
+    // We verify that every attachment file's content matches its filename hash
+    // and that the set of files is the one the signed manifest lists
+    verify_all_attachments(car, car_path, budget)?;
+
+    Ok((claims.verified(), claims.unvalidated_types()))
+}
+
+/// Verify all attachment files in the CAR
+/// Attachments are self-verifying: the filename is the hash of the content.
+/// When the CAR carries an attachment manifest, every file must prove into
+/// its Merkle root and, in a ZIP bundle, every listed, unredacted attachment
+/// must be present. A standalone JSON CAR is checked against the
+/// `attachments/` directory next to it, if there is one, where any subset of
+/// the listed attachments may be.
+fn verify_all_attachments(car: &Car, car_path: &PathBuf, budget: &Budget) -> Result<()> {
+    // CARs from before attachment manifests leave the set unchecked
+    if let Some(manifest) = &car.attachments {
+        manifest.check_root().map_err(|reason| anyhow!(reason))?;
+    }
+
+    // Determine if we're working with a ZIP or JSON file
+    let extension = car_path.extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+
+    if extension != "zip" {
+        return verify_sibling_attachments(car, car_path, budget);
+    }
+
+    let file = fs::File::open(car_path)
+        .with_context(|| format!("Failed to open ZIP file: {}", car_path.display()))?;
+
+    let mut archive = open_archive(file, budget.limits.archive_limits())
+        .with_context(|| format!("Failed to read ZIP archive: {}", car_path.display()))?;
+
+    // Only files in the attachments/ directory are attachments
+    let mut entries = Vec::new();
+    for (i, name) in archive.names().iter().enumerate() {
+        if let Some((hash, compression)) = check_attachment_entry(car, name)? {
+            entries.push((i, name.clone(), hash, compression));
+        }
+    }
+
+    let found = if budget.limits.stream {
+        let total = entries.iter().map(|(i, ..)| archive.declared_size(*i)).sum();
+        let progress = attachment_progress(total);
+        let mut attachment_bytes = 0u64;
+        for (i, name, hash, compression) in &entries {
+            budget.check_time()?;
+            let Some(entry) = archive.open_index(*i)
+                .with_context(|| format!("Failed to read attachment file: {}", name))?
+            else {
+                continue;
+            };
+            let entry = progress.wrap_read(entry);
+            stream_attachment(name, hash, *compression, entry, &mut attachment_bytes, budget)?;
+        }
+        entries.into_iter().map(|(_, _, hash, _)| hash).collect()
+    } else {
+        let mut batches = AttachmentBatches::default();
+        for (i, name, hash, compression) in entries {
+            budget.check_time()?;
+            budget.check_attachment_bytes(batches.bytes + archive.declared_size(i))?;
+
+            let content = archive.read_index(i)
+                .with_context(|| format!("Failed to read attachment file: {}", name))?
+                .unwrap_or_default();
+            batches.push(&name, hash, compression, content, budget)?;
+        }
+        batches.finish(budget)?
+    };
+
+    for hash in car.attachments.iter().flat_map(|manifest| &manifest.hashes) {
+        let target = format!("attachments/{hash}");
+        let redacted = car.redactions.iter().any(|redaction| redaction.target == target);
+        if !found.contains(hash) && !redacted {
+            return Err(anyhow!(
+                "Attachment {} is listed in the CAR's manifest but missing from the bundle",
+                hash
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Attachments in the `attachments/` directory next to a standalone JSON CAR
+fn verify_sibling_attachments(car: &Car, car_path: &Path, budget: &Budget) -> Result<()> {
+    let Some(dir) = car_path.parent().map(|parent| parent.join("attachments")) else {
+        return Ok(());
+    };
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut paths = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    paths.sort();
+
+    let mut entries = Vec::new();
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let name = format!("attachments/{file_name}");
+        if let Some((hash, compression)) = check_attachment_entry(car, &name)? {
+            let size = fs::metadata(&path)?.len();
+            entries.push((path, name, hash, compression, size));
+        }
+    }
+
+    if budget.limits.stream {
+        let progress = attachment_progress(entries.iter().map(|entry| entry.4).sum());
+        let mut attachment_bytes = 0u64;
+        for (path, name, hash, compression, _) in &entries {
+            budget.check_time()?;
+            let file = fs::File::open(path)
+                .with_context(|| format!("Failed to read attachment file: {}", path.display()))?;
+            let file = progress.wrap_read(file);
+            stream_attachment(name, hash, *compression, file, &mut attachment_bytes, budget)?;
+        }
+        return Ok(());
+    }
+
+    let mut batches = AttachmentBatches::default();
+    for (path, name, hash, compression, size) in entries {
+        budget.check_time()?;
+        budget.check_attachment_bytes(batches.bytes + size)?;
+
+        let content = fs::read(&path)
+            .with_context(|| format!("Failed to read attachment file: {}", path.display()))?;
+        batches.push(&name, hash, compression, content, budget)?;
+    }
+    batches.finish(budget)?;
+    Ok(())
+}
+
+/// The content hash and encoding of attachment entry `name`, after checking
+/// them against the CAR's manifest; `None` for entries that are not attachments
+fn check_attachment_entry(
+    car: &Car,
+    name: &str,
+) -> Result<Option<(String, Option<AttachmentCompression>)>> {
+    let Some((hash, compression)) = parse_attachment_entry(name) else {
+        return Ok(None);
+    };
+    if let Some(manifest) = &car.attachments {
+        manifest.check_inclusion(hash).map_err(|reason| anyhow!(reason))?;
+        manifest.check_encoding(hash, compression).map_err(|reason| anyhow!(reason))?;
+    }
+    Ok(Some((hash.to_string(), compression)))
+}
+
+/// Attachment contents awaiting their hash check. Reading is sequential, so
+/// entries are collected in batches and each batch is hashed across all
+/// cores before the next one is read.
+#[derive(Default)]
+struct AttachmentBatches {
+    /// Decompressed bytes read so far
+    bytes: u64,
+    found: HashSet<String>,
+    batch: Vec<(String, Vec<u8>)>,
+}
+
+impl AttachmentBatches {
+    /// Queue the content of entry `name`; compressed attachments are hashed,
+    /// and counted against the limit, decompressed
+    fn push(
+        &mut self,
+        name: &str,
+        hash: String,
+        compression: Option<AttachmentCompression>,
+        content: Vec<u8>,
+        budget: &Budget,
+    ) -> Result<()> {
+        let content = match compression {
+            Some(compression) => compression
+                .decompress(&content, ArchiveLimits::default().max_entry_bytes)
+                .map_err(|reason| anyhow!("{}: {}", name, reason))?,
+            None => content,
+        };
+        self.bytes += content.len() as u64;
+        budget.check_attachment_bytes(self.bytes)?;
+
+        self.found.insert(hash.clone());
+        self.batch.push((hash, content));
+        if self.batch.len() == HASH_BATCH {
+            verify_attachment_batch(&self.batch, budget)?;
+            self.batch.clear();
+        }
+        Ok(())
+    }
+
+    /// Hash what is left; returns the hashes of every attachment seen
+    fn finish(self, budget: &Budget) -> Result<HashSet<String>> {
+        verify_attachment_batch(&self.batch, budget)?;
+        Ok(self.found)
+    }
+}
+
+/// Hash one attachment as it is read, decompressing on the way, so memory
+/// stays flat however large it is. `attachment_bytes` counts the bytes of
+/// every attachment streamed so far against the budget.
+fn stream_attachment(
+    name: &str,
+    hash: &str,
+    compression: Option<AttachmentCompression>,
+    mut entry: impl Read,
+    attachment_bytes: &mut u64,
+    budget: &Budget,
+) -> Result<()> {
+    let mut sink = HashingSink {
+        hasher: AttachmentHasher::new(hash),
+        attachment_bytes,
+        budget,
+    };
+    let copied = match compression {
+        Some(compression) => compression
+            .decoder(entry)
+            .map_err(|reason| anyhow!("{}: {}", name, reason))
+            .map(|mut decoder| std::io::copy(&mut decoder, &mut sink))?,
+        None => std::io::copy(&mut entry, &mut sink),
+    };
+    copied.map_err(|err| streaming_error(err, name))?;
+    Ok(sink.hasher.finish()?)
+}
+
+/// A failed streamed read, reported as the limit it stopped at if it did
+fn streaming_error(err: std::io::Error, name: &str) -> anyhow::Error {
+    let err = match err.downcast::<LimitExceeded>() {
+        Ok(exceeded) => return exceeded.into(),
+        Err(err) => err,
+    };
+    match err.downcast::<ArchiveError>() {
+        Ok(err) if err.is_limit() => LimitExceeded {
+            limit: "archive",
+            message: err.to_string(),
+        }
+        .into(),
+        Ok(err) => anyhow::Error::from(err)
+            .context(format!("Failed to read attachment file: {}", name)),
+        Err(err) => anyhow::Error::from(err)
+            .context(format!("Failed to read attachment file: {}", name)),
+    }
+}
+
+/// Feeds streamed attachment content to its hasher, counting the bytes
+/// against the budget as they arrive
+struct HashingSink<'a> {
+    hasher: AttachmentHasher,
+    attachment_bytes: &'a mut u64,
+    budget: &'a Budget,
+}
+
+impl Write for HashingSink<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        *self.attachment_bytes += buf.len() as u64;
+        self.budget
+            .check_attachment_bytes(*self.attachment_bytes)
+            .and_then(|_| self.budget.check_time())
+            .map_err(std::io::Error::other)?;
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Progress over `total_bytes` of attachments on stderr; hidden when stderr
+/// is not a terminal and cleared once done
+fn attachment_progress(total_bytes: u64) -> ProgressBar {
+    let style = ProgressStyle::with_template(
+        "{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+    )
+    .expect("progress template is valid")
+    .progress_chars("=> ");
+    ProgressBar::new(total_bytes)
+        .with_style(style)
+        .with_message("Hashing attachments")
+        .with_finish(ProgressFinish::AndClear)
+}
+
+/// Hash a batch of attachments in parallel and report the first mismatch in
+/// archive order, so the result does not depend on thread scheduling
+fn verify_attachment_batch(batch: &[(String, Vec<u8>)], budget: &Budget) -> Result<()> {
+    let outcomes: Vec<Result<()>> = batch
+        .par_iter()
+        .map(|(hash, content)| {
+            budget.check_time()?;
+            Ok(intelexta_verify_core::check_attachment(hash, content)?)
+        })
+        .collect();
+    outcomes.into_iter().collect()
+}
+
@@ -1,530 +1,1055 @@
 use std::fs;
-use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
-use sha2::{Digest, Sha256};
-
-use intelexta::car::{Car, ProcessCheckpointProof};
+use indicatif::{ProgressBar, ProgressFinish};
+use rayon::prelude::*;
+
+use intelexta::car::Car;
+use intelexta::car_diff::{self, CarDiff};
+use intelexta::display_time::{self, TimeDisplay};
+use intelexta::policy_history;
+use intelexta::provenance::{self, ArtifactBinding, ArtifactSignature};
+use intelexta::timestamp;
+use intelexta::verification_html::{HtmlReport, ReportStep, StepOutcome};
+use intelexta::verify::{check_receipt_chain, SignerTrust, TrustedKeys};
+use intelexta_archive::ArchiveLimits;
+use intelexta_verify::{
+    load_car_file, verify_car, Budget, LimitExceeded, VerificationLimits, VerificationReport,
+    SEMANTIC_FAILURE, TRANSCRIPT_FAILURE,
+};
+
+mod replay;
 
 /// Standalone verification utility for Intelexta CAR (Content-Addressed Receipt) files.
 ///
 /// Verifies cryptographic integrity, hash chains, and digital signatures without requiring
-/// the full Intelexta application or database.
+/// the full Intelexta application or database. Signed policy history exports are
+/// recognized and verified as well.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    /// Path to the CAR file (.car.json or .car.zip)
-    car_file: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to the CAR file (.car.json, .car.cbor or .car.zip), a policy history export (.json),
+    /// or a project's receipts directory to verify its receipt chain
+    #[arg(required = true)]
+    car_file: Option<PathBuf>,
 
-    /// Output format (human or json)
+    /// Output format (human, json, sarif or html)
     #[arg(long, default_value = "human")]
     format: OutputFormat,
+
+    /// Stop (exit code 2) if the CAR has more checkpoints than this
+    #[arg(long)]
+    max_checkpoints: Option<usize>,
+
+    /// Stop (exit code 2) if attachments total more than this many bytes
+    #[arg(long)]
+    max_attachment_bytes: Option<u64>,
+
+    /// Stop (exit code 2) if verification takes longer than this many milliseconds
+    #[arg(long)]
+    time_budget_ms: Option<u64>,
+
+    /// Hash attachments one at a time as they stream out of the bundle, with
+    /// a progress bar, instead of in parallel batches held in memory. Lifts
+    /// the bundle size limits so multi-GB receipts verify in bounded memory;
+    /// --max-attachment-bytes still applies.
+    #[arg(long)]
+    stream: bool,
+
+    /// Run every check even after one fails and report all failures, e.g. a
+    /// broken hash chain and a tampered attachment, instead of stopping at
+    /// the first
+    #[arg(long)]
+    continue_on_error: bool,
+
+    /// Timezone for times in the human report, e.g. Europe/Berlin
+    /// (defaults to INTELEXTA_TZ or TZ, then UTC)
+    #[arg(long)]
+    timezone: Option<String>,
+
+    /// Locale for times in the human report, e.g. de_DE
+    /// (defaults to LC_ALL, LC_TIME or LANG)
+    #[arg(long)]
+    locale: Option<String>,
+
+    /// Write the CAR's RFC 3161 timestamp token (DER) to this file, for
+    /// checking the TSA's signature with `openssl ts -verify`
+    #[arg(long)]
+    export_timestamp: Option<PathBuf>,
+
+    /// Directory of local copies of the CAR's external attachments, named
+    /// by content hash or by the file name of their URI
+    #[arg(long)]
+    attachments_dir: Option<PathBuf>,
+
+    /// Trust store of known project keys; a CAR that verifies under a key
+    /// it does not list exits with code 4
+    #[arg(long)]
+    trusted_keys: Option<PathBuf>,
+
+    /// age identity file to decrypt an encrypted bundle (.car.zip.age) with
+    /// before verifying it
+    #[arg(long)]
+    identity: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two CARs structurally: run spec, models, policy, checkpoint
+    /// chain, token usage and provenance claims. Exits 1 if they differ.
+    /// Neither CAR is verified.
+    Diff {
+        /// The baseline CAR
+        baseline: PathBuf,
+        /// The CAR to compare with it, e.g. a re-run
+        rerun: PathBuf,
+        /// Output format (human or json)
+        #[arg(long, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Verify many CARs in parallel and print a summary table. Exits 0 if
+    /// every CAR verified, 3 if the only problems are unsupported format
+    /// versions, and 1 otherwise.
+    Batch {
+        /// Directory of CARs, or a glob pattern such as 'receipts/**/*.car.zip'
+        target: String,
+        /// Output format (human, json or sarif)
+        #[arg(long, default_value = "human")]
+        format: OutputFormat,
+        /// Stop starting new verifications once a CAR fails; the rest are
+        /// reported as skipped
+        #[arg(long)]
+        fail_fast: bool,
+        /// Also write each CAR's JSON report to this directory, named after
+        /// the CAR's path within the batch
+        #[arg(long)]
+        results_dir: Option<PathBuf>,
+    },
+    /// Verify a detached signature over a plain file, such as a report
+    /// rendered from a checkpoint's output. With `--car`, the CAR must also
+    /// verify, be signed by the same key and hold the checkpoint with the
+    /// signed output hash. Exits 1 if anything does not verify.
+    VerifyArtifact {
+        /// The signed file
+        file: PathBuf,
+        /// Its signature, `<file>.sig.json`
+        signature: PathBuf,
+        /// CAR of the run the checkpoint belongs to
+        #[arg(long)]
+        car: Option<PathBuf>,
+        /// Output format (human or json)
+        #[arg(long, default_value = "human")]
+        format: OutputFormat,
+    },
+    /// Re-run the CAR's exact-mode steps against a local Ollama and compare
+    /// output hashes with the recorded ones. Steps the CAR does not carry
+    /// enough to reproduce are skipped. Exits 1 unless at least one step was
+    /// replayed and every replayed step matched. The CAR is not verified.
+    Replay {
+        /// The CAR to replay
+        car: PathBuf,
+        /// Ollama to generate with, as host:port or a URL
+        #[arg(long, default_value = replay::DEFAULT_OLLAMA_HOST)]
+        ollama_host: String,
+        /// Output format (human or json)
+        #[arg(long, default_value = "human")]
+        format: OutputFormat,
+    },
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
 enum OutputFormat {
     Human,
     Json,
-}
-
-#[derive(Debug, serde::Serialize)]
-struct VerificationReport {
-    car_id: String,
-    file_integrity: bool,
-    hash_chain_valid: bool,
-    signatures_valid: bool,
-    content_integrity_valid: bool,
-    checkpoints_verified: usize,
-    checkpoints_total: usize,
-    provenance_claims_verified: usize,
-    provenance_claims_total: usize,
-    overall_result: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    error: Option<String>,
+    /// SARIF 2.1.0 log, for code-scanning dashboards and CI annotations
+    Sarif,
+    /// Self-contained HTML page, for attaching to audit documentation
+    Html,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Diff { baseline, rerun, format }) => {
+            return diff_cars(baseline, rerun, format);
+        }
+        Some(Command::Batch {
+            target,
+            format,
+            fail_fast,
+            results_dir,
+        }) => return verify_batch(target, format, *fail_fast, results_dir.as_deref()),
+        Some(Command::VerifyArtifact {
+            file,
+            signature,
+            car,
+            format,
+        }) => return verify_artifact(file, signature, car.as_ref(), format),
+        Some(Command::Replay {
+            car,
+            ollama_host,
+            format,
+        }) => return replay_car(car, ollama_host, format),
+        None => {}
+    }
+    let car_file = cli.car_file.clone().expect("clap requires a CAR file without a subcommand");
+    let mut time_display = TimeDisplay::from_env();
+    if let Some(timezone) = &cli.timezone {
+        time_display.timezone = display_time::parse_timezone(timezone)?;
+    }
+    if let Some(locale) = &cli.locale {
+        time_display.locale = display_time::parse_locale(locale)?;
+    }
+    let trusted_keys = match &cli.trusted_keys {
+        Some(path) => {
+            let json = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read trusted keys: {}", path.display()))?;
+            Some(TrustedKeys::from_json(&json)?)
+        }
+        None => None,
+    };
+    let budget = Budget::new(VerificationLimits {
+        max_checkpoints: cli.max_checkpoints,
+        max_attachment_bytes: cli.max_attachment_bytes,
+        time_budget: cli.time_budget_ms.map(Duration::from_millis),
+        stream: cli.stream,
+        continue_on_error: cli.continue_on_error,
+    });
+
+    if car_file.is_dir() {
+        return verify_project_chain(&car_file, &cli.format, &budget);
+    }
+
+    // An encrypted bundle is verified from a private decrypted copy, removed
+    // once the report is ready
+    let decrypted = if is_encrypted(&car_file)? {
+        let identity = cli.identity.as_deref().ok_or_else(|| {
+            anyhow!(
+                "{} is encrypted; pass --identity with an age identity file",
+                car_file.display()
+            )
+        })?;
+        Some(DecryptedCar::new(&car_file, identity)?)
+    } else {
+        None
+    };
+    let source = decrypted.as_ref().map_or_else(|| car_file.clone(), |copy| copy.path.clone());
+
+    // Policy history exports are JSON documents with their own format marker;
+    // bundles are never read whole
+    let is_json = source.extension().is_some_and(|extension| extension == "json");
+    if let Some(raw_json) = is_json.then(|| fs::read_to_string(&source).ok()).flatten() {
+        if policy_history::is_policy_history(&raw_json) {
+            return verify_policy_history(&raw_json, &cli.format);
+        }
+    }
 
     // Load and parse the CAR file
-    let (car, raw_json, car_path) = load_car_file(&cli.car_file)?;
+    let (car, raw_json, car_path) = match load_car_file(&source, budget.limits.archive_limits()) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            if let Some(exceeded) = err.downcast_ref::<LimitExceeded>() {
+                drop(decrypted);
+                eprintln!("{} {}", "⚠ LIMIT EXCEEDED:".yellow().bold(), exceeded);
+                std::process::exit(2);
+            }
+            return Err(err);
+        }
+    };
 
     // Run verification (pass the path for attachment verification and raw JSON for signature verification)
-    let report = verify_car(&car, &raw_json, &car_path)?;
+    let mut report =
+        verify_car(&car, &raw_json, &car_path, cli.attachments_dir.as_deref(), &budget)?;
+    if let Some(trusted_keys) = &trusted_keys {
+        if report.signatures_valid {
+            report.signer_trust = Some(trusted_keys.signer_trust(&car));
+        }
+    }
+
+    if let Some(path) = &cli.export_timestamp {
+        let encoded = timestamp::car_token(&car).context("CAR carries no timestamp token")?;
+        let token = STANDARD.decode(encoded).context("timestamp token is not base64")?;
+        fs::write(path, token)
+            .with_context(|| format!("Failed to write timestamp token: {}", path.display()))?;
+    }
+    drop(decrypted);
 
     // Output results
     match cli.format {
-        OutputFormat::Human => print_human_report(&report),
+        OutputFormat::Human => print_human_report(&report, &time_display),
         OutputFormat::Json => print_json_report(&report)?,
+        OutputFormat::Sarif => print_sarif_log(&[(car_file.as_path(), Ok(&report))])?,
+        OutputFormat::Html => print_html_report(&report, &car),
     }
 
     // Exit with appropriate code
-    if report.overall_result {
+    if report.overall_result && matches!(report.signer_trust, Some(SignerTrust::Unknown { .. })) {
+        std::process::exit(4);
+    } else if report.overall_result {
         Ok(())
+    } else if report.limit_exceeded.is_some() {
+        std::process::exit(2);
+    } else if report.unsupported_format.is_some() {
+        std::process::exit(3);
     } else {
         std::process::exit(1);
     }
 }
 
-/// Load CAR from either JSON or ZIP file
-/// Returns the parsed CAR, the raw JSON string, and the path to use for attachment verification
-fn load_car_file(path: &PathBuf) -> Result<(Car, String, PathBuf)> {
-    let extension = path.extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("");
-
-    let (car, raw_json) = match extension {
-        "zip" => load_car_from_zip(path)?,
-        "json" => load_car_from_json(path)?,
-        _ => {
-            // Try JSON first, then ZIP
-            load_car_from_json(path)
-                .or_else(|_| load_car_from_zip(path))
-                .with_context(|| format!("Could not parse CAR file: {}", path.display()))?
-        }
-    };
-
-    Ok((car, raw_json, path.clone()))
-}
-
-/// Load CAR from JSON file
-fn load_car_from_json(path: &PathBuf) -> Result<(Car, String)> {
-    let contents = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read file: {}", path.display()))?;
-
-    let car = serde_json::from_str(&contents)
-        .with_context(|| format!("Failed to parse CAR JSON from: {}", path.display()))?;
-
-    Ok((car, contents))
+/// Result of `verify-artifact`
+#[derive(Debug, serde::Serialize)]
+struct ArtifactReport {
+    file_name: String,
+    sha256: String,
+    checkpoint: ArtifactBinding,
+    public_key: String,
+    signature_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    car: Option<ArtifactCarCheck>,
+    overall_result: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
 }
 
-/// Load CAR from ZIP file (extract car.json)
-fn load_car_from_zip(path: &PathBuf) -> Result<(Car, String)> {
-    let file = fs::File::open(path)
-        .with_context(|| format!("Failed to open ZIP file: {}", path.display()))?;
-
-    let mut archive = zip::ZipArchive::new(file)
-        .with_context(|| format!("Failed to read ZIP archive: {}", path.display()))?;
-
-    // Find and read car.json
-    let mut car_file = archive.by_name("car.json")
-        .with_context(|| "CAR ZIP must contain car.json")?;
-
-    let mut contents = String::new();
-    car_file.read_to_string(&mut contents)
-        .context("Failed to read car.json from ZIP")?;
-
-    let car = serde_json::from_str(&contents)
-        .context("Failed to parse car.json from ZIP")?;
-
-    Ok((car, contents))
+/// How an artifact's checkpoint binding holds up against its run's CAR
+#[derive(Debug, serde::Serialize)]
+struct ArtifactCarCheck {
+    car_id: String,
+    car_verified: bool,
+    same_signer: bool,
+    checkpoint_found: bool,
 }
 
-/// Main verification logic
-fn verify_car(car: &Car, raw_json: &str, car_path: &PathBuf) -> Result<VerificationReport> {
-    let mut report = VerificationReport {
-        car_id: car.id.clone(),
-        file_integrity: true,
-        hash_chain_valid: false,
-        signatures_valid: false,
-        content_integrity_valid: false,
-        checkpoints_verified: 0,
-        checkpoints_total: 0,
-        provenance_claims_verified: 0,
-        provenance_claims_total: 0,
-        overall_result: false,
-        error: None,
+/// Verify a detached artifact signature, and its checkpoint against `car`
+fn verify_artifact(
+    file: &Path,
+    signature_path: &Path,
+    car_file: Option<&PathBuf>,
+    format: &OutputFormat,
+) -> Result<()> {
+    if matches!(format, OutputFormat::Sarif | OutputFormat::Html) {
+        return Err(anyhow!("verify-artifact has no SARIF or HTML output; use json"));
+    }
+    let json = fs::read_to_string(signature_path)
+        .with_context(|| format!("Failed to read signature: {}", signature_path.display()))?;
+    let signature: ArtifactSignature = serde_json::from_str(&json)
+        .with_context(|| format!("Not an artifact signature: {}", signature_path.display()))?;
+
+    let mut errors = Vec::new();
+    let signature_valid = match provenance::verify_file(file, &signature) {
+        Ok(()) => true,
+        Err(err) => {
+            errors.push(err.to_string());
+            false
+        }
     };
 
-    // Get process proof checkpoints
-    let checkpoints = match &car.proof.process {
-        Some(process) => &process.sequential_checkpoints,
-        None => {
-            report.error = Some(format!(
-                "CAR has no process proof (match_kind: {}). This CAR was likely exported with an older version of Intelexta. \
-                 Please re-export the CAR to include cryptographic signatures for verification.",
-                car.proof.match_kind
-            ));
-            return Ok(report);
+    let car = match car_file {
+        Some(path) => {
+            let (car, raw_json, car_path) = load_car_file(path, ArchiveLimits::default())?;
+            let report = verify_car(
+                &car,
+                &raw_json,
+                &car_path,
+                None,
+                &Budget::new(VerificationLimits::default()),
+            )?;
+            let binding = &signature.checkpoint;
+            let checkpoint_found = car.run_id == binding.run_id
+                && car
+                    .proof
+                    .process
+                    .iter()
+                    .chain(car.proof.executions.iter().map(|execution| &execution.process))
+                    .flat_map(|process| &process.sequential_checkpoints)
+                    .any(|checkpoint| {
+                        checkpoint.id == binding.checkpoint_id
+                            && checkpoint.outputs_sha256.as_deref()
+                                == Some(binding.outputs_sha256.as_str())
+                    });
+            let check = ArtifactCarCheck {
+                car_id: car.id.clone(),
+                car_verified: report.overall_result,
+                same_signer: car.signer_public_key == signature.public_key,
+                checkpoint_found,
+            };
+            if !check.car_verified {
+                errors.push(format!("CAR {} does not verify", check.car_id));
+            }
+            if !check.same_signer {
+                errors.push("the artifact and the CAR are signed by different keys".to_string());
+            }
+            if !check.checkpoint_found {
+                errors.push(format!(
+                    "CAR {} has no checkpoint {} with output sha256 {}",
+                    check.car_id, binding.checkpoint_id, binding.outputs_sha256
+                ));
+            }
+            Some(check)
         }
+        None => None,
     };
 
-    report.checkpoints_total = checkpoints.len();
-
-    if checkpoints.is_empty() {
-        report.error = Some("CAR has no checkpoints to verify".to_string());
-        return Ok(report);
-    }
-
-    // Verify hash chain
-    match verify_hash_chain(checkpoints) {
-        Ok(verified_count) => {
-            report.hash_chain_valid = true;
-            report.checkpoints_verified = verified_count;
-        }
-        Err(e) => {
-            report.error = Some(format!("Hash chain verification failed: {}", e));
-            return Ok(report);
+    let report = ArtifactReport {
+        overall_result: errors.is_empty(),
+        file_name: signature.file_name,
+        sha256: signature.sha256,
+        checkpoint: signature.checkpoint,
+        public_key: signature.public_key,
+        signature_valid,
+        car,
+        errors,
+    };
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Human | OutputFormat::Sarif | OutputFormat::Html => {
+            print_human_artifact_report(&report)
         }
     }
+    if !report.overall_result {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_human_artifact_report(report: &ArtifactReport) {
+    let mark = |ok: bool| if ok { "✓".green() } else { "✗".red() };
 
-    // Verify top-level body signature (if present)
-    if let Err(e) = verify_top_level_signature(car, raw_json) {
-        report.error = Some(format!("Top-level body signature verification failed: {}", e));
-        return Ok(report);
+    println!("\n{}", "Intelexta Artifact Verification".bold().cyan());
+    println!("{}", "=".repeat(50));
+    println!();
+    println!("File:       {}", report.file_name);
+    println!("SHA-256:    {}", report.sha256.bright_black());
+    println!("Run:        {}", report.checkpoint.run_id);
+    println!("Checkpoint: {}", report.checkpoint.checkpoint_id);
+    println!("Output:     {}", report.checkpoint.outputs_sha256.bright_black());
+    println!("Signer:     {}", report.public_key.bright_black());
+    println!();
+    println!("{} Signature", mark(report.signature_valid));
+    if let Some(car) = &report.car {
+        println!("{} CAR {} verifies", mark(car.car_verified), car.car_id);
+        println!("{} Signed by the CAR's key", mark(car.same_signer));
+        println!("{} Checkpoint is part of the CAR", mark(car.checkpoint_found));
+    }
+    for error in &report.errors {
+        println!("  {}", error.red());
     }
 
-    // Verify signatures
-    match verify_signatures(&car.signer_public_key, checkpoints) {
-        Ok(_) => {
-            report.signatures_valid = true;
-        }
-        Err(e) => {
-            report.error = Some(format!("Signature verification failed: {}", e));
-            return Ok(report);
-        }
+    println!();
+    if report.overall_result {
+        println!("{}", "✓ ARTIFACT VERIFIED".green().bold());
+    } else {
+        println!("{}", "✗ ARTIFACT VERIFICATION FAILED".red().bold());
     }
+}
 
-    // Verify content integrity (provenance claims + attachments)
-    match verify_content_integrity(car, car_path) {
-        Ok(verified_count) => {
-            report.content_integrity_valid = true;
-            report.provenance_claims_verified = verified_count;
-            report.provenance_claims_total = car.provenance.len();
-        }
-        Err(e) => {
-            report.error = Some(format!("Content integrity verification failed: {}", e));
-            report.provenance_claims_total = car.provenance.len();
-            return Ok(report);
+/// Compare two CARs and exit 1 if they differ
+fn diff_cars(baseline: &PathBuf, rerun: &PathBuf, format: &OutputFormat) -> Result<()> {
+    if matches!(format, OutputFormat::Sarif | OutputFormat::Html) {
+        return Err(anyhow!("diff has no SARIF or HTML output; use json"));
+    }
+    let (left, _, _) = load_car_file(baseline, ArchiveLimits::default())?;
+    let (right, _, _) = load_car_file(rerun, ArchiveLimits::default())?;
+    let diff = car_diff::diff(&left, &right);
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        OutputFormat::Human | OutputFormat::Sarif | OutputFormat::Html => {
+            print_human_diff(&diff)
         }
     }
-
-    // Overall result
-    report.overall_result = report.file_integrity
-        && report.hash_chain_valid
-        && report.signatures_valid
-        && report.content_integrity_valid
-        && report.checkpoints_verified == report.checkpoints_total;
-
-    Ok(report)
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
-/// Checkpoint body structure used for hash computation (must match orchestrator.rs)
-#[derive(serde::Serialize)]
-struct CheckpointBody<'a> {
-    run_id: &'a str,
-    kind: &'a str,
-    timestamp: &'a str,
-    inputs_sha256: &'a Option<String>,
-    outputs_sha256: &'a Option<String>,
-    incident: Option<serde_json::Value>,
-    usage_tokens: u64,
-    prompt_tokens: u64,
-    completion_tokens: u64,
+/// Replay a CAR's exact-mode steps and exit 1 unless they reproduce
+fn replay_car(car_file: &PathBuf, ollama_host: &str, format: &OutputFormat) -> Result<()> {
+    if matches!(format, OutputFormat::Sarif | OutputFormat::Html) {
+        return Err(anyhow!("replay has no SARIF or HTML output; use json"));
+    }
+    let (car, _, _) = load_car_file(car_file, ArchiveLimits::default())?;
+    let spinner = ProgressBar::new_spinner()
+        .with_message(format!("Replaying {} against Ollama at {ollama_host}", car.id))
+        .with_finish(ProgressFinish::AndClear);
+    spinner.enable_steady_tick(Duration::from_millis(120));
+    let report = replay::replay_car(&car, ollama_host);
+    spinner.finish();
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Human | OutputFormat::Sarif | OutputFormat::Html => {
+            print_human_replay(&report)
+        }
+    }
+    if !report.match_status {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
-/// Verify the hash chain across all checkpoints
-fn verify_hash_chain(checkpoints: &[ProcessCheckpointProof]) -> Result<usize> {
-    let mut verified_count = 0;
-
-    for (i, checkpoint) in checkpoints.iter().enumerate() {
-        // Compute expected curr_chain from prev_chain + canonical checkpoint body
-        let expected_curr = compute_checkpoint_hash(checkpoint)?;
-
-        if expected_curr != checkpoint.curr_chain {
-            return Err(anyhow!(
-                "Hash chain broken at checkpoint #{} (id: {})\nExpected: {}\nFound: {}",
-                i,
-                checkpoint.id,
-                expected_curr,
-                checkpoint.curr_chain
-            ));
+fn print_human_replay(report: &replay::ReplayReport) {
+    println!("\n{}", "Intelexta CAR Replay".bold().cyan());
+    println!("{}", "=".repeat(50));
+    println!();
+    println!("CAR ID: {}", report.car_id.bright_black());
+    println!("Run:    {}", report.run_id);
+    println!("Ollama: {}", report.ollama_host);
+    println!();
+    for entry in &report.checkpoint_reports {
+        let mark = match entry.status {
+            replay::ReplayStatus::Matched => "✓".green(),
+            replay::ReplayStatus::Mismatched | replay::ReplayStatus::Failed => "✗".red(),
+            replay::ReplayStatus::Skipped => "-".bright_black(),
+        };
+        println!("  {} Step {} ({})", mark, entry.order_index, entry.checkpoint_config_id);
+        if let Some(message) = &entry.error_message {
+            println!("      {}", message.bright_black());
+        }
+        if entry.status == replay::ReplayStatus::Mismatched {
+            println!("      recorded: {}", entry.original_digest.bright_black());
+            println!("      replayed: {}", entry.replay_digest.bright_black());
         }
-
-        verified_count += 1;
     }
 
-    Ok(verified_count)
+    println!();
+    println!("{}", "-".repeat(50));
+    if report.match_status {
+        println!(
+            "{} {}",
+            "✓ REPRODUCED:".green().bold(),
+            "Every replayed step produced its recorded output.".green()
+        );
+    } else {
+        println!("{}", "✗ NOT REPRODUCED".red().bold());
+        if let Some(message) = &report.error_message {
+            println!("{}", message.red());
+        }
+    }
+    println!();
 }
 
-/// Compute checkpoint hash: SHA256(prev_chain || canonical_json(checkpoint_body))
-fn compute_checkpoint_hash(checkpoint: &ProcessCheckpointProof) -> Result<String> {
-    // Reconstruct the checkpoint body exactly as it was signed
-    let body = CheckpointBody {
-        run_id: &checkpoint.run_id,
-        kind: &checkpoint.kind,
-        timestamp: &checkpoint.timestamp,
-        inputs_sha256: &checkpoint.inputs_sha256,
-        outputs_sha256: &checkpoint.outputs_sha256,
-        incident: None, // Incidents are not included in process checkpoints
-        usage_tokens: checkpoint.usage_tokens,
-        prompt_tokens: checkpoint.prompt_tokens,
-        completion_tokens: checkpoint.completion_tokens,
+fn print_human_diff(diff: &CarDiff) {
+    let show = |value: &Option<serde_json::Value>| match value {
+        Some(value) => value.to_string(),
+        None => "(absent)".to_string(),
     };
 
-    // Convert to JSON value and canonicalize
-    let body_json = serde_json::to_value(&body)?;
-    let canonical = canonical_json(&body_json)?;
-
-    // Compute SHA256(prev_chain || canonical_body)
-    let mut hasher = Sha256::new();
-    hasher.update(checkpoint.prev_chain.as_bytes());
-    hasher.update(&canonical);
-    Ok(hex::encode(hasher.finalize()))
-}
-
-/// Canonical JSON implementation (must match provenance::canonical_json)
-/// Uses JCS (JSON Canonicalization Scheme) for deterministic encoding
-fn canonical_json(value: &serde_json::Value) -> Result<Vec<u8>> {
-    serde_jcs::to_vec(value).map_err(|e| anyhow!("Failed to canonicalize JSON: {}", e))
-}
+    println!("\n{}", "Intelexta CAR Diff".bold().cyan());
+    println!("{}", "=".repeat(50));
+    println!();
+    println!("Baseline: {}", diff.left.bright_black());
+    println!("Re-run:   {}", diff.right.bright_black());
+
+    for section in &diff.sections {
+        println!();
+        println!("{}", section.section.bold());
+        for change in &section.changes {
+            println!(
+                "  {}: {} → {}",
+                change.path,
+                show(&change.left).red(),
+                show(&change.right).green()
+            );
+        }
+    }
 
-/// Verify Ed25519 signatures on all checkpoints
-fn verify_signatures(
-    public_key_b64: &str,
-    checkpoints: &[ProcessCheckpointProof],
-) -> Result<()> {
-    // Parse public key from base64
-    let public_key_bytes = STANDARD
-        .decode(public_key_b64)
-        .context("Invalid public key base64")?;
-
-    let public_key = VerifyingKey::from_bytes(
-        &public_key_bytes
-            .try_into()
-            .map_err(|_| anyhow!("Public key must be 32 bytes"))?,
-    )
-    .context("Invalid Ed25519 public key")?;
-
-    // Verify each checkpoint signature
-    for (i, checkpoint) in checkpoints.iter().enumerate() {
-        // Parse signature from base64
-        let sig_bytes = STANDARD
-            .decode(&checkpoint.signature)
-            .with_context(|| format!("Invalid signature base64 at checkpoint #{}", i))?;
-
-        let signature = Signature::from_bytes(
-            &sig_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Signature must be 64 bytes at checkpoint #{}", i))?,
+    println!();
+    println!("{}", "-".repeat(50));
+    if diff.is_empty() {
+        println!(
+            "{} {}",
+            "✓ IDENTICAL:".green().bold(),
+            "The receipts agree in every compared section.".green()
+        );
+    } else {
+        let changes: usize = diff.sections.iter().map(|section| section.changes.len()).sum();
+        println!(
+            "{} {} changes in {} sections",
+            "≠ DIFFERENT:".yellow().bold(),
+            changes,
+            diff.sections.len()
         );
-
-        // The message being signed is the curr_chain hash
-        let message = checkpoint.curr_chain.as_bytes();
-
-        // Verify signature
-        public_key
-            .verify(message, &signature)
-            .with_context(|| format!("Signature verification failed at checkpoint #{}", i))?;
     }
-
-    Ok(())
+    println!();
 }
 
-/// Verify top-level body signature (if present in new format)
-///
-/// New CAR format includes dual signatures:
-/// - ed25519-body:<sig> - covers entire CAR body (prevents tampering with created_at, budgets, etc.)
-/// - ed25519-checkpoint:<sig> - covers checkpoint chain hash (verified by verify_signatures)
-fn verify_top_level_signature(car: &Car, raw_json: &str) -> Result<()> {
-    if car.signatures.is_empty() {
-        return Err(anyhow!("No signatures found in CAR"));
+/// Verify a signed policy history export and exit 1 if it does not hold
+fn verify_policy_history(raw_json: &str, format: &OutputFormat) -> Result<()> {
+    if matches!(format, OutputFormat::Sarif | OutputFormat::Html) {
+        return Err(anyhow!("policy history exports have no SARIF or HTML output; use json"));
     }
-
-    let first_sig = &car.signatures[0];
-
-    // If it's the new format with top-level body signature, verify it
-    if first_sig.starts_with("ed25519-body:") {
-        if car.signer_public_key.is_empty() {
-            return Err(anyhow!("Top-level signature present but signer_public_key is empty"));
+    let outcome = policy_history::verify_bundle(raw_json);
+    match format {
+        OutputFormat::Json => {
+            let report = match &outcome {
+                Ok(verified) => serde_json::json!({ "overall_result": true, "policy_history": verified }),
+                Err(err) => serde_json::json!({ "overall_result": false, "error": err.to_string() }),
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
         }
-
-        let sig_b64 = first_sig.strip_prefix("ed25519-body:").unwrap();
-
-        // Parse raw JSON as Value and remove signatures field
-        let mut car_json: serde_json::Value = serde_json::from_str(raw_json)
-            .context("Failed to parse raw JSON")?;
-
-        // Remove signatures field
-        if let Some(obj) = car_json.as_object_mut() {
-            obj.remove("signatures");
+        OutputFormat::Human | OutputFormat::Sarif | OutputFormat::Html => {
+            println!("\n{}", "Intelexta Policy History Verification".bold().cyan());
+            println!("{}", "=".repeat(50));
+            println!();
+            match &outcome {
+                Ok(verified) => {
+                    println!("Project: {}", verified.project_id.bright_black());
+                    println!("Head hash: {}", verified.head_hash.bright_black());
+                    println!();
+                    print_check("Signature", true);
+                    print_check(
+                        &format!(
+                            "Version Chain ({} versions, current v{})",
+                            verified.versions_verified, verified.current_version
+                        ),
+                        true,
+                    );
+                    println!();
+                    println!("{}", "-".repeat(50));
+                    println!(
+                        "{} {}",
+                        "✓ VERIFIED:".green().bold(),
+                        "The policy history is complete and has not been tampered with.".green()
+                    );
+                }
+                Err(err) => {
+                    println!("{} {}", "✗ FAILED:".red().bold(), err.to_string().red());
+                }
+            }
+            println!();
         }
-
-        // Canonicalize the body (without re-serializing through Rust structs)
-        let canonical = canonical_json(&car_json)?;
-
-        // Parse public key
-        let public_key_bytes = STANDARD
-            .decode(&car.signer_public_key)
-            .context("Invalid signer public key base64")?;
-
-        let public_key = VerifyingKey::from_bytes(
-            &public_key_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Public key must be 32 bytes"))?,
-        )
-        .context("Invalid Ed25519 public key")?;
-
-        // Parse signature
-        let signature_bytes = STANDARD
-            .decode(sig_b64)
-            .context("Invalid top-level signature base64")?;
-
-        let signature = Signature::from_bytes(
-            &signature_bytes
-                .try_into()
-                .map_err(|_| anyhow!("Signature must be 64 bytes"))?,
-        );
-
-        // Verify signature
-        public_key
-            .verify(&canonical, &signature)
-            .context("Top-level body signature verification failed")?;
     }
-    // else: legacy format without top-level signature, skip this check
-
+    if outcome.is_err() {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
-/// Verify content integrity by checking provenance claims and attachment files
-fn verify_content_integrity(car: &Car, car_path: &PathBuf) -> Result<usize> {
-    let mut verified_count = 0;
-
-    // Step 1: Verify provenance claims (config hash)
-    for (i, claim) in car.provenance.iter().enumerate() {
-        // Extract the hash from the claim (format: "sha256:...")
-        let expected_hash = claim
-            .sha256
-            .strip_prefix("sha256:")
-            .ok_or_else(|| anyhow!("Invalid provenance claim #{}: hash must start with 'sha256:'", i))?;
-
-        match claim.claim_type.as_str() {
-            "config" => {
-                // Verify run specification hash
-                let spec_json = serde_json::to_value(&car.run.steps)?;
-                let canonical = canonical_json(&spec_json)?;
-                let computed_hash = hex::encode(Sha256::digest(&canonical));
-
-                if computed_hash != expected_hash {
-                    return Err(anyhow!(
-                        "Config hash mismatch at provenance claim #{}\nExpected: {}\nComputed: {}",
-                        i,
-                        expected_hash,
-                        computed_hash
-                    ));
-                }
-                verified_count += 1;
+/// Verify every CAR in a project's receipts directory, then the chain their
+/// `previous_car_id` links form; exit 1 if either does not hold
+fn verify_project_chain(dir: &Path, format: &OutputFormat, budget: &Budget) -> Result<()> {
+    if matches!(format, OutputFormat::Sarif | OutputFormat::Html) {
+        return Err(anyhow!("receipt chains have no SARIF or HTML output; use json, or batch for SARIF"));
+    }
+    let paths = car_files_in(dir)?;
+
+    let mut cars = Vec::new();
+    let mut failures = Vec::new();
+    for path in &paths {
+        let outcome = load_car_file(path, budget.limits.archive_limits()).and_then(
+            |(car, raw_json, car_path)| {
+                verify_car(&car, &raw_json, &car_path, None, budget).map(|report| (car, report))
+            },
+        );
+        match outcome {
+            Ok((car, report)) if report.overall_result => cars.push(car),
+            Ok((_, report)) => failures.push(format!(
+                "{}: {}",
+                path.display(),
+                report.error.unwrap_or_else(|| "verification failed".to_string())
+            )),
+            Err(err) => failures.push(format!("{}: {err}", path.display())),
+        }
+    }
+    let chain = check_receipt_chain(&cars);
+    let passed = failures.is_empty() && chain.is_ok();
+
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "overall_result": passed,
+                "receipts_verified": cars.len(),
+                "receipts_total": paths.len(),
+                "receipt_chain": chain.as_ref().ok(),
+                "failures": failures,
+                "error": chain.as_ref().err().map(|err| err.to_string()),
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Human | OutputFormat::Sarif | OutputFormat::Html => {
+            println!("\n{}", "Intelexta Project Receipt Chain Verification".bold().cyan());
+            println!("{}", "=".repeat(50));
+            println!();
+            println!("Directory: {}", dir.display().to_string().bright_black());
+            println!();
+            print_check(
+                &format!("Receipts ({}/{} verified)", cars.len(), paths.len()),
+                failures.is_empty(),
+            );
+            for failure in &failures {
+                println!("  {} {}", "✗".red(), failure);
             }
-            "input" | "output" => {
-                // For inputs/outputs, verify the hash appears in checkpoints
-                // Actual content verification happens in Step 2
-                let hash_exists = car
-                    .proof
-                    .process
-                    .as_ref()
-                    .map(|p| {
-                        p.sequential_checkpoints.iter().any(|ck| {
-                            ck.inputs_sha256.as_deref() == Some(expected_hash)
-                                || ck.outputs_sha256.as_deref() == Some(expected_hash)
-                        })
-                    })
-                    .unwrap_or(false);
-
-                if !hash_exists {
-                    return Err(anyhow!(
-                        "{} hash not found in checkpoints at provenance claim #{}",
-                        claim.claim_type,
-                        i
-                    ));
+            match &chain {
+                Ok(chain) => {
+                    print_check(
+                        &format!("Receipt Chain ({} linked receipts)", chain.receipts.len()),
+                        true,
+                    );
+                    if !chain.unchained.is_empty() {
+                        println!(
+                            "  {} {} receipt(s) predate the chain",
+                            "ℹ".bright_blue(),
+                            chain.unchained.len()
+                        );
+                    }
+                }
+                Err(err) => {
+                    print_check("Receipt Chain", false);
+                    println!("  {} {}", "✗".red(), err);
                 }
-                verified_count += 1;
             }
-            _ => {
-                // Unknown claim type - skip for forward compatibility
-                continue;
+            println!();
+            println!("{}", "-".repeat(50));
+            if passed {
+                println!(
+                    "{} {}",
+                    "✓ VERIFIED:".green().bold(),
+                    "Every receipt is valid and none was removed from or replaced in the chain."
+                        .green()
+                );
+            } else {
+                println!("{} {}", "✗ FAILED:".red().bold(), "Verification failed.".red());
             }
+            println!();
         }
     }
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    // Step 2: Verify all attachment files in the CAR
-    // Attachments are self-verifying: filename = hash of content
-    // We verify that every attachment file's content matches its filename hash
-    verify_all_attachments(car_path)?;
+/// The CAR files of a directory, sorted by name
+fn car_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            [".car.zip", ".car.json", ".car.cbor"]
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
 
-    Ok(verified_count)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BatchStatus {
+    Verified,
+    Failed,
+    UnsupportedFormat,
+    /// Not started because an earlier CAR failed under --fail-fast
+    Skipped,
 }
 
-/// Verify all attachment files in the CAR
-/// Attachments are self-verifying: the filename is the hash of the content
-fn verify_all_attachments(car_path: &PathBuf) -> Result<()> {
-    // Determine if we're working with a ZIP or JSON file
-    let extension = car_path.extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("");
+/// One CAR of a batch
+#[derive(Debug, serde::Serialize)]
+struct BatchResult {
+    path: PathBuf,
+    status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Absent when the CAR could not be loaded or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<VerificationReport>,
+}
 
-    if extension != "zip" {
-        // For standalone JSON, skip attachment verification
-        // (attachments would need to be in a sibling directory)
-        return Ok(());
+/// Directory a batch was taken from: the target directory, or the deepest
+/// directory every CAR a glob matched is under
+fn batch_root(target: &str, paths: &[PathBuf]) -> PathBuf {
+    if Path::new(target).is_dir() {
+        return PathBuf::from(target);
+    }
+    let mut root = paths
+        .first()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for path in paths {
+        while !path.starts_with(&root) && root.pop() {}
     }
+    root
+}
 
-    let file = fs::File::open(car_path)
-        .with_context(|| format!("Failed to open ZIP file: {}", car_path.display()))?;
+/// Write each result to `<path within root>.verify.json` under `dir`,
+/// mirroring subdirectories so CARs that share a file name do not overwrite
+/// each other's results
+fn write_batch_results(dir: &Path, root: &Path, results: &[BatchResult]) -> Result<()> {
+    for result in results {
+        let relative = result
+            .path
+            .strip_prefix(root)
+            .unwrap_or_else(|_| Path::new(result.path.file_name().unwrap_or_default()));
+        let mut name = relative.as_os_str().to_owned();
+        name.push(".verify.json");
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(result)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
 
-    let mut archive = zip::ZipArchive::new(file)
-        .with_context(|| format!("Failed to read ZIP archive: {}", car_path.display()))?;
+/// Verify every CAR `target` names, a directory or a glob pattern, in
+/// parallel; exit codes are those `Command::Batch` documents
+fn verify_batch(
+    target: &str,
+    format: &OutputFormat,
+    fail_fast: bool,
+    results_dir: Option<&Path>,
+) -> Result<()> {
+    if matches!(format, OutputFormat::Html) {
+        return Err(anyhow!("batch has no HTML output; use json or sarif"));
+    }
+    let paths = if Path::new(target).is_dir() {
+        car_files_in(Path::new(target))?
+    } else {
+        let mut paths = glob::glob(target)
+            .with_context(|| format!("Invalid glob pattern: {target}"))?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    };
+    if paths.is_empty() {
+        return Err(anyhow!("No CAR files found at {target}"));
+    }
+    if let Some(dir) = results_dir {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create results directory: {}", dir.display()))?;
+    }
 
-    // Find all files in the attachments/ directory
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let name = file.name().to_string();
+    // Each CAR gets its own budget; batches impose no limits
+    let stop = AtomicBool::new(false);
+    let results: Vec<BatchResult> = paths
+        .par_iter()
+        .map(|path| {
+            if stop.load(Ordering::Relaxed) {
+                return BatchResult {
+                    path: path.clone(),
+                    status: BatchStatus::Skipped,
+                    error: None,
+                    report: None,
+                };
+            }
+            let budget = Budget::new(VerificationLimits::default());
+            let outcome = load_car_file(path, budget.limits.archive_limits()).and_then(
+                |(car, raw_json, car_path)| verify_car(&car, &raw_json, &car_path, None, &budget),
+            );
+            let result = match outcome {
+                Ok(report) => BatchResult {
+                    path: path.clone(),
+                    status: if report.overall_result {
+                        BatchStatus::Verified
+                    } else if report.unsupported_format.is_some() {
+                        BatchStatus::UnsupportedFormat
+                    } else {
+                        BatchStatus::Failed
+                    },
+                    error: report.error.clone(),
+                    report: Some(report),
+                },
+                Err(err) => BatchResult {
+                    path: path.clone(),
+                    status: BatchStatus::Failed,
+                    error: Some(err.to_string()),
+                    report: None,
+                },
+            };
+            if fail_fast && result.status != BatchStatus::Verified {
+                stop.store(true, Ordering::Relaxed);
+            }
+            result
+        })
+        .collect();
 
-        // Only process files in attachments/ directory
-        if !name.starts_with("attachments/") || !name.ends_with(".txt") {
-            continue;
+    if let Some(dir) = results_dir {
+        write_batch_results(dir, &batch_root(target, &paths), &results)?;
+    }
+
+    let count = |status: BatchStatus| results.iter().filter(|r| r.status == status).count();
+    let verified = count(BatchStatus::Verified);
+    let failed = count(BatchStatus::Failed);
+    let unsupported = count(BatchStatus::UnsupportedFormat);
+    let skipped = count(BatchStatus::Skipped);
+
+    match format {
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "overall_result": verified == results.len(),
+                "total": results.len(),
+                "verified": verified,
+                "failed": failed,
+                "unsupported_format": unsupported,
+                "skipped": skipped,
+                "results": results,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Sarif => {
+            let outcomes: Vec<_> = results
+                .iter()
+                .filter(|result| result.status != BatchStatus::Skipped)
+                .map(|result| {
+                    let outcome = match (&result.report, &result.error) {
+                        (Some(report), _) => Ok(report),
+                        (None, error) => Err(error.as_deref().unwrap_or("CAR could not be read")),
+                    };
+                    (result.path.as_path(), outcome)
+                })
+                .collect();
+            print_sarif_log(&outcomes)?;
         }
+        OutputFormat::Human | OutputFormat::Html => {
+            println!("\n{}", "Intelexta Batch Verification".bold().cyan());
+            println!("{}", "=".repeat(50));
+            println!();
+            for result in &results {
+                let status = match result.status {
+                    BatchStatus::Verified => "✓ VERIFIED   ".green(),
+                    BatchStatus::Failed => "✗ FAILED     ".red(),
+                    BatchStatus::UnsupportedFormat => "⚠ UNSUPPORTED".yellow(),
+                    BatchStatus::Skipped => "- SKIPPED    ".bright_black(),
+                };
+                let checkpoints = result
+                    .report
+                    .as_ref()
+                    .map(|report| {
+                        format!(
+                            "{}/{} checkpoints",
+                            report.checkpoints_verified, report.checkpoints_total
+                        )
+                    })
+                    .unwrap_or_default();
+                let path = result.path.display();
+                println!("  {} {}  {}", status, path, checkpoints.bright_black());
+                if let Some(error) = &result.error {
+                    println!("      {}", error.lines().next().unwrap_or_default());
+                }
+            }
+            println!();
+            println!("{}", "-".repeat(50));
+            println!(
+                "{} verified, {} failed, {} unsupported, {} skipped ({} total)",
+                verified,
+                failed,
+                unsupported,
+                skipped,
+                results.len()
+            );
+            println!();
+        }
+    }
 
-        // Extract the expected hash from the filename
-        // Format: attachments/{hash}.txt
-        let expected_hash = name
-            .strip_prefix("attachments/")
-            .and_then(|s| s.strip_suffix(".txt"))
-            .ok_or_else(|| anyhow!("Invalid attachment filename format: {}", name))?;
+    if verified == results.len() {
+        Ok(())
+    } else if failed == 0 && skipped == 0 {
+        std::process::exit(3);
+    } else {
+        std::process::exit(1);
+    }
+}
 
-        // Read the file content
-        let mut content = Vec::new();
-        file.read_to_end(&mut content)
-            .with_context(|| format!("Failed to read attachment file: {}", name))?;
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1\n";
+
+/// Whether `path` is an age-encrypted bundle, by extension or header
+fn is_encrypted(path: &Path) -> Result<bool> {
+    use std::io::Read;
+
+    if path.extension().and_then(|s| s.to_str()) == Some("age") {
+        return Ok(true);
+    }
+    let Ok(file) = fs::File::open(path) else {
+        return Ok(false);
+    };
+    let mut header = Vec::new();
+    file.take(AGE_HEADER.len() as u64).read_to_end(&mut header)?;
+    Ok(header == AGE_HEADER)
+}
 
-        // Compute SHA256 hash of the content
-        let computed_hash = hex::encode(Sha256::digest(&content));
+/// Decrypted copy of an encrypted bundle in the temp directory, readable only
+/// by its owner and removed on drop
+struct DecryptedCar {
+    path: PathBuf,
+}
 
-        // Verify the hash matches the filename
-        if computed_hash != expected_hash {
-            return Err(anyhow!(
-                "Attachment content mismatch\nFile: {}\nExpected hash (from filename): {}\nComputed hash (from content): {}\n\nThis indicates the attachment file has been tampered with!",
-                name,
-                expected_hash,
-                computed_hash
-            ));
+impl DecryptedCar {
+    fn new(encrypted: &Path, identity_file: &Path) -> Result<Self> {
+        let identities = fs::File::open(identity_file)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                Ok(age::IdentityFile::from_buffer(std::io::BufReader::new(file))?
+                    .into_identities()?)
+            })
+            .with_context(|| format!("Failed to read identity file: {}", identity_file.display()))?;
+        let input = fs::File::open(encrypted)
+            .with_context(|| format!("Failed to open {}", encrypted.display()))?;
+        let mut reader = age::Decryptor::new(std::io::BufReader::new(input))
+            .and_then(|decryptor| {
+                decryptor.decrypt(identities.iter().map(|identity| identity.as_ref()))
+            })
+            .with_context(|| format!("Failed to decrypt {}", encrypted.display()))?;
+
+        // Keep the inner extension (.car.zip) so the copy loads like the original
+        let name = encrypted.file_name().and_then(|s| s.to_str()).unwrap_or("car");
+        let name = name.strip_suffix(".age").unwrap_or(name);
+        let path = std::env::temp_dir()
+            .join(format!("intelexta-verify-{}-{name}", std::process::id()));
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
         }
+        let mut output = options
+            .open(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let copy = Self { path };
+        std::io::copy(&mut reader, &mut output)
+            .with_context(|| format!("Failed to decrypt {}", encrypted.display()))?;
+        Ok(copy)
     }
+}
 
-    Ok(())
+impl Drop for DecryptedCar {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
 }
 
 /// Print human-readable report
-fn print_human_report(report: &VerificationReport) {
+fn print_human_report(report: &VerificationReport, time_display: &TimeDisplay) {
     println!("\n{}", "Intelexta CAR Verification".bold().cyan());
     println!("{}", "=".repeat(50));
     println!();
 
     println!("CAR ID: {}", report.car_id.bright_black());
+    println!(
+        "Created: {}",
+        time_display.format_rfc3339(&report.created_at).bright_black()
+    );
+    println!(
+        "Format version: {}",
+        report.format_version.to_string().bright_black()
+    );
     println!();
 
     // File integrity
@@ -539,12 +1064,36 @@ fn print_human_report(report: &VerificationReport) {
         report.hash_chain_valid,
     );
 
+    if report.cached_checkpoints > 0 {
+        println!(
+            "  {} {} checkpoint(s) served from the response cache",
+            "ℹ".bright_blue(),
+            report.cached_checkpoints
+        );
+    }
+    for warning in &report.clock_warnings {
+        println!("  {} {}", "!".yellow(), warning);
+    }
+    for violation in &report.policy_violations {
+        println!("  {} {}", "!".yellow(), violation);
+    }
+
     // Signatures
     print_check(
         &format!("Signatures ({} checkpoints)", report.checkpoints_total),
         report.signatures_valid,
     );
 
+    if report.executions_total > 0 {
+        print_check(
+            &format!(
+                "Execution Proofs ({}/{} executions)",
+                report.executions_verified, report.executions_total
+            ),
+            report.executions_verified == report.executions_total,
+        );
+    }
+
     // Content integrity
     print_check(
         &format!(
@@ -553,20 +1102,164 @@ fn print_human_report(report: &VerificationReport) {
         ),
         report.content_integrity_valid,
     );
+    if !report.unvalidated_extensions.is_empty() {
+        println!(
+            "  {} Unvalidated extension claims: {}",
+            "!".yellow(),
+            report.unvalidated_extensions.join(", ")
+        );
+    }
+
+    let semantic_failures = report
+        .failures
+        .iter()
+        .filter(|message| message.starts_with(SEMANTIC_FAILURE))
+        .count();
+    if report.semantic_digests_recomputed > 0 || semantic_failures > 0 {
+        print_check(
+            &format!(
+                "Semantic Digests ({} recomputed from bundled outputs)",
+                report.semantic_digests_recomputed
+            ),
+            semantic_failures == 0,
+        );
+    }
+
+    let transcript_failed = report
+        .failures
+        .iter()
+        .any(|message| message.starts_with(TRANSCRIPT_FAILURE));
+    if report.transcript_messages > 0 || transcript_failed {
+        print_check(
+            &format!("Chat Transcript ({} messages)", report.transcript_messages),
+            !transcript_failed,
+        );
+        if report.transcript_messages_withheld > 0 {
+            println!(
+                "  {} {} withheld by redaction",
+                "ℹ".bright_blue(),
+                report.transcript_messages_withheld
+            );
+        }
+        if report.transcript_messages_unbound > 0 {
+            println!(
+                "  {} {} human messages predate the chain binding them; covered by the \
+                 body signature only",
+                "!".yellow(),
+                report.transcript_messages_unbound
+            );
+        }
+    }
+
+    if let Some(timestamp) = &report.timestamp {
+        let stamped_at = time_display.format(&timestamp.gen_time);
+        print_check(&format!("Trusted Timestamp ({stamped_at})"), true);
+        println!(
+            "  {} TSA signature not checked; see --export-timestamp",
+            "ℹ".bright_blue()
+        );
+    }
+
+    if report.signers.len() > 1 {
+        let valid = report.signers.iter().filter(|signer| signer.valid).count();
+        print_check(
+            &format!("Co-signatures ({}/{} signers)", valid, report.signers.len()),
+            valid == report.signers.len(),
+        );
+        for signer in &report.signers {
+            let mark = if signer.valid { "✓".green() } else { "✗".red() };
+            println!("    {} {}: {}", mark, signer.role.as_str(), signer.public_key);
+        }
+    }
+
+    if let Some(log) = &report.transparency_log {
+        print_check(
+            &format!("Transparency Log (entry {} at {})", log.log_index, log.log_url),
+            true,
+        );
+        println!(
+            "  {} Compare root {} (tree size {}) with the log's signed checkpoint",
+            "ℹ".bright_blue(),
+            log.root_hash,
+            log.tree_size
+        );
+    }
+
+    if report.external_attachments_total > 0 {
+        match &report.external_attachments {
+            Some(found) => print_check(
+                &format!(
+                    "External Attachments ({}/{} files)",
+                    found.verified.len(),
+                    report.external_attachments_total
+                ),
+                found.verified.len() == report.external_attachments_total,
+            ),
+            None => println!(
+                "{} {} external attachments not checked; see --attachments-dir",
+                "ℹ".bright_blue(),
+                report.external_attachments_total
+            ),
+        }
+    }
 
     println!();
     println!("{}", "-".repeat(50));
 
     // Overall result
-    if report.overall_result {
+    if report.overall_result && report.redactions > 0 {
+        println!(
+            "{} {}",
+            format!("✓ VERIFIED with {} redactions:", report.redactions).green().bold(),
+            "Everything disclosed is cryptographically valid; withheld content is committed to by hash.".green()
+        );
+    } else if report.overall_result {
         println!(
             "{} {}",
             "✓ VERIFIED:".green().bold(),
             "This CAR is cryptographically valid and has not been tampered with.".green()
         );
+    }
+    if report.overall_result {
+        match &report.signer_trust {
+            Some(SignerTrust::Trusted { project }) => println!(
+                "{} {}",
+                "✓ TRUSTED SIGNER:".green().bold(),
+                format!("Signed with a key pinned for {project}.").green()
+            ),
+            Some(SignerTrust::Unknown { public_key }) => {
+                println!(
+                    "{} {}",
+                    "⚠ UNKNOWN SIGNER:".yellow().bold(),
+                    "The CAR is self-consistent, but the trust store does not list its signer."
+                        .yellow()
+                );
+                println!("{} {}", "Signer key:".yellow(), public_key);
+            }
+            None => {}
+        }
+    } else if let Some(unsupported) = &report.unsupported_format {
+        println!(
+            "{} {}",
+            "⚠ UNSUPPORTED FORMAT:".yellow().bold(),
+            "This CAR was written in a format this verifier does not know; nothing was checked.".yellow()
+        );
+        println!("{} {}", "Format:".yellow(), unsupported);
+        println!("{}", "Upgrade intelexta-verify to verify this CAR.".yellow());
+    } else if let Some(exceeded) = &report.limit_exceeded {
+        println!(
+            "{} {}",
+            "⚠ LIMIT EXCEEDED:".yellow().bold(),
+            "Verification stopped before completion; no tampering was detected in the checks that ran.".yellow()
+        );
+        println!("{} {}", "Limit:".yellow(), exceeded.message);
     } else {
         println!("{} {}", "✗ FAILED:".red().bold(), "Verification failed.".red());
-        if let Some(error) = &report.error {
+        if report.failures.len() > 1 {
+            for failure in &report.failures {
+                println!("{} {}", "Error:".red(), failure);
+            }
+        } else if let Some(error) = &report.error {
             println!("{} {}", "Error:".red(), error);
         }
     }
@@ -581,6 +1274,308 @@ fn print_json_report(report: &VerificationReport) -> Result<()> {
     Ok(())
 }
 
+/// Print a self-contained HTML report, for attaching to audit documentation
+fn print_html_report(report: &VerificationReport, car: &Car) {
+    // A check that neither passed nor failed was not reached
+    let outcome = |passed: bool, failure: &str| {
+        if passed {
+            StepOutcome::Passed
+        } else if report.failures.iter().any(|message| message.contains(failure)) {
+            StepOutcome::Failed
+        } else {
+            StepOutcome::Skipped
+        }
+    };
+    let mut steps = vec![
+        ReportStep::new(
+            "File integrity",
+            outcome(report.file_integrity, ""),
+            "CAR parsed",
+        ),
+        ReportStep::new(
+            "Hash chain",
+            outcome(report.hash_chain_valid, "Hash chain verification failed"),
+            format!(
+                "{}/{} checkpoints verified",
+                report.checkpoints_verified, report.checkpoints_total
+            ),
+        ),
+    ];
+    if !report.clock_warnings.is_empty() {
+        let detail = report.clock_warnings.join("; ");
+        steps.push(ReportStep::new("Checkpoint clock", StepOutcome::Warning, detail));
+    }
+    if !report.policy_violations.is_empty() {
+        let detail = report.policy_violations.join("; ");
+        steps.push(ReportStep::new("Policy budgets", StepOutcome::Warning, detail));
+    }
+    steps.push(ReportStep::new(
+        "Signatures",
+        outcome(report.signatures_valid, "ignature verification failed"),
+        format!("{} checkpoints", report.checkpoints_total),
+    ));
+    if report.executions_total > 0 {
+        steps.push(ReportStep::new(
+            "Execution proofs",
+            outcome(report.executions_verified == report.executions_total, "Execution "),
+            format!(
+                "{}/{} executions verified",
+                report.executions_verified, report.executions_total
+            ),
+        ));
+    }
+    let mut content = format!(
+        "{}/{} provenance claims verified",
+        report.provenance_claims_verified, report.provenance_claims_total
+    );
+    if !report.unvalidated_extensions.is_empty() {
+        content.push_str(&format!(
+            "; no validator for {}",
+            report.unvalidated_extensions.join(", ")
+        ));
+    }
+    steps.push(ReportStep::new(
+        "Content integrity",
+        outcome(report.content_integrity_valid, "Content integrity verification failed"),
+        content,
+    ));
+    let semantic_failed = report
+        .failures
+        .iter()
+        .any(|message| message.starts_with(SEMANTIC_FAILURE));
+    if report.semantic_digests_recomputed > 0 || semantic_failed {
+        steps.push(ReportStep::new(
+            "Semantic digests",
+            if semantic_failed { StepOutcome::Failed } else { StepOutcome::Passed },
+            format!(
+                "{} recomputed from bundled outputs",
+                report.semantic_digests_recomputed
+            ),
+        ));
+    }
+    let transcript_failed = report
+        .failures
+        .iter()
+        .any(|message| message.starts_with(TRANSCRIPT_FAILURE));
+    if report.transcript_messages > 0 || transcript_failed {
+        steps.push(ReportStep::new(
+            "Chat transcript",
+            if transcript_failed {
+                StepOutcome::Failed
+            } else if report.transcript_messages_unbound > 0 {
+                StepOutcome::Warning
+            } else {
+                StepOutcome::Passed
+            },
+            format!(
+                "{} messages, {} withheld, {} bound by the body signature only",
+                report.transcript_messages,
+                report.transcript_messages_withheld,
+                report.transcript_messages_unbound
+            ),
+        ));
+    }
+    if report.redactions > 0 {
+        steps.push(ReportStep::new(
+            "Redactions",
+            StepOutcome::Passed,
+            format!("{} redactions match their commitments", report.redactions),
+        ));
+    }
+    if let Some(timestamp) = &report.timestamp {
+        steps.push(ReportStep::new(
+            "Trusted timestamp",
+            StepOutcome::Passed,
+            format!("Stamped {}; TSA signature not checked", timestamp.gen_time.to_rfc3339()),
+        ));
+    }
+    if report.signers.len() > 1 {
+        let valid = report.signers.iter().filter(|signer| signer.valid).count();
+        steps.push(ReportStep::new(
+            "Co-signatures",
+            outcome(valid == report.signers.len(), "Co-signature verification failed"),
+            format!("{}/{} signers", valid, report.signers.len()),
+        ));
+    }
+    if let Some(log) = &report.transparency_log {
+        steps.push(ReportStep::new(
+            "Transparency log",
+            StepOutcome::Passed,
+            format!("Entry {} at {}", log.log_index, log.log_url),
+        ));
+    }
+    if report.external_attachments_total > 0 {
+        steps.push(match &report.external_attachments {
+            Some(found) => ReportStep::new(
+                "External attachments",
+                outcome(
+                    found.verified.len() == report.external_attachments_total,
+                    "External attachment",
+                ),
+                format!(
+                    "{}/{} files verified",
+                    found.verified.len(),
+                    report.external_attachments_total
+                ),
+            ),
+            None => ReportStep::new(
+                "External attachments",
+                StepOutcome::Skipped,
+                "No local copies given; see --attachments-dir",
+            ),
+        });
+    }
+    match &report.signer_trust {
+        Some(SignerTrust::Trusted { project }) => steps.push(ReportStep::new(
+            "Trusted signer",
+            StepOutcome::Passed,
+            format!("Key pinned for {project}"),
+        )),
+        Some(SignerTrust::Unknown { .. }) => steps.push(ReportStep::new(
+            "Trusted signer",
+            StepOutcome::Warning,
+            "The trust store does not list the signer",
+        )),
+        None => {}
+    }
+
+    let html = HtmlReport {
+        car,
+        verified: report.overall_result,
+        error: match report.failures.len() {
+            0 | 1 => report.error.clone(),
+            _ => Some(report.failures.join("; ")),
+        },
+        steps,
+        signers: report.signers.clone(),
+        generator: format!("intelexta-verify {}", env!("CARGO_PKG_VERSION")),
+    };
+    print!("{}", html.render());
+}
+
+/// SARIF rules, one per way a CAR can fail: id, short description, level
+const SARIF_RULES: [(&str, &str, &str); 10] = [
+    ("unreadable-car", "The CAR file could not be read or parsed", "error"),
+    ("unsupported-format", "The CAR's format version is unknown to this verifier", "warning"),
+    ("verification-limit", "Verification stopped at a caller-imposed limit", "warning"),
+    ("hash-chain", "The checkpoint hash chain does not hold", "error"),
+    ("signatures", "A signature over the CAR or its checkpoints does not verify", "error"),
+    ("content-integrity", "Provenance claims or attachments do not match their hashes", "error"),
+    (
+        "semantic-digests",
+        "A semantic digest does not match its output, or a concordant epsilon is unusable",
+        "error",
+    ),
+    (
+        "chat-transcript",
+        "A chat message does not match its checkpoint or answers out of turn",
+        "error",
+    ),
+    (
+        "attestations",
+        "A redaction, timestamp, log entry or external attachment does not hold",
+        "error",
+    ),
+    ("unknown-signer", "The CAR verifies under a key the trust store does not list", "warning"),
+];
+
+/// The SARIF rule a failed report falls under, following the order of the
+/// checks in `verify_car`
+fn sarif_rule(report: &VerificationReport) -> &'static str {
+    if report.unsupported_format.is_some() {
+        "unsupported-format"
+    } else if report.limit_exceeded.is_some() {
+        "verification-limit"
+    } else if !report.hash_chain_valid {
+        "hash-chain"
+    } else if !report.signatures_valid
+        || report.executions_verified < report.executions_total
+        || report.signers.iter().any(|signer| !signer.valid)
+    {
+        "signatures"
+    } else if !report.content_integrity_valid {
+        "content-integrity"
+    } else if report.error.as_deref().is_some_and(|error| error.starts_with(SEMANTIC_FAILURE)) {
+        "semantic-digests"
+    } else if report.error.as_deref().is_some_and(|error| error.starts_with(TRANSCRIPT_FAILURE)) {
+        "chat-transcript"
+    } else {
+        "attestations"
+    }
+}
+
+/// Print a SARIF 2.1.0 log with one result per CAR that did not verify or
+/// whose signer the trust store does not list; other CARs produce no result
+fn print_sarif_log(outcomes: &[(&Path, Result<&VerificationReport, &str>)]) -> Result<()> {
+    let rules: Vec<_> = SARIF_RULES
+        .iter()
+        .map(|(id, description, level)| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level },
+            })
+        })
+        .collect();
+    let results: Vec<_> = outcomes
+        .iter()
+        .filter_map(|(path, outcome)| {
+            let (rule, message, car_id) = match outcome {
+                Ok(report) if report.overall_result => match &report.signer_trust {
+                    Some(SignerTrust::Unknown { public_key }) => (
+                        "unknown-signer",
+                        format!("CAR is signed by untrusted key {public_key}"),
+                        Some(report.car_id.as_str()),
+                    ),
+                    _ => return None,
+                },
+                Ok(report) => (
+                    sarif_rule(report),
+                    report.error.clone().unwrap_or_else(|| "Verification failed".to_string()),
+                    Some(report.car_id.as_str()),
+                ),
+                Err(error) => ("unreadable-car", error.to_string(), None),
+            };
+            let level = SARIF_RULES
+                .iter()
+                .find(|(id, _, _)| *id == rule)
+                .map_or("error", |(_, _, level)| *level);
+            let mut result = serde_json::json!({
+                "ruleId": rule,
+                "level": level,
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": path.to_string_lossy().replace('\\', "/"),
+                        },
+                    },
+                }],
+            });
+            if let Some(car_id) = car_id {
+                result["properties"] = serde_json::json!({ "carId": car_id });
+            }
+            Some(result)
+        })
+        .collect();
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "intelexta-verify",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    println!("{}", serde_json::to_string_pretty(&log)?);
+    Ok(())
+}
+
 /// Helper to print a check result
 fn print_check(label: &str, passed: bool) {
     if passed {
@@ -589,3 +1584,38 @@ fn print_check(label: &str, passed: bool) {
         println!("  {} {}", "✗".red(), label);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_results_of_cars_sharing_a_file_name_are_kept_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let scratch = dir.path();
+        let paths = vec![
+            scratch.join("cars/alpha/run.car.zip"),
+            scratch.join("cars/beta/run.car.zip"),
+        ];
+        let root = batch_root(&format!("{}/cars/*/*.car.zip", scratch.display()), &paths);
+        assert_eq!(root, scratch.join("cars"));
+
+        let results: Vec<BatchResult> = paths
+            .iter()
+            .map(|path| BatchResult {
+                path: path.clone(),
+                status: BatchStatus::Failed,
+                error: Some(path.display().to_string()),
+                report: None,
+            })
+            .collect();
+        let results_dir = scratch.join("results");
+        write_batch_results(&results_dir, &root, &results).unwrap();
+
+        for (dir, path) in ["alpha", "beta"].iter().zip(&paths) {
+            let written =
+                fs::read_to_string(results_dir.join(dir).join("run.car.zip.verify.json")).unwrap();
+            assert!(written.contains(&path.display().to_string()));
+        }
+    }
+}
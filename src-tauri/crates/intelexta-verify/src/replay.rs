@@ -0,0 +1,318 @@
+//! `intelexta-verify replay`: re-run a CAR's exact-mode steps against a local
+//! Ollama and compare output hashes
+//!
+//! Exact-mode steps pin the run seed and greedy decoding, so the same model
+//! given the same prompt reproduces the recorded output byte for byte. The
+//! CAR carries each step's model and prompt and each checkpoint's input and
+//! output hashes, which is all a replay needs: no app, database or API keys.
+//! A prompt rendered from a template or an earlier step's output hashes
+//! differently from the step's prompt and is skipped, as are concordant and
+//! non-LLM steps. The app replays those from its database.
+//!
+//! The CAR itself is not verified; run `intelexta-verify` on it first.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use intelexta::car::{Car, ProcessCheckpointProof};
+use intelexta::run_spec::{RunProofMode, RunStep};
+use intelexta_verify_core::sha256_hex;
+use serde::{Deserialize, Serialize};
+
+/// Where `ollama serve` listens unless told otherwise
+pub const DEFAULT_OLLAMA_HOST: &str = "127.0.0.1:11434";
+
+/// Local generations can be slow on modest hardware
+const GENERATION_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Outcome of replaying a CAR, shaped like the app's replay report
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub run_id: String,
+    pub car_id: String,
+    pub ollama_host: String,
+    /// At least one step was replayed and every replayed step reproduced
+    /// its recorded output
+    pub match_status: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    pub checkpoint_reports: Vec<CheckpointReplayReport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayStatus {
+    Matched,
+    Mismatched,
+    /// The model could not be run, see `errorMessage`
+    Failed,
+    /// Not replayable from the CAR alone, see `errorMessage`
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointReplayReport {
+    pub checkpoint_config_id: String,
+    pub checkpoint_id: String,
+    pub order_index: i64,
+    pub proof_mode: RunProofMode,
+    pub status: ReplayStatus,
+    pub match_status: bool,
+    pub original_digest: String,
+    pub replay_digest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_tokens: Option<u64>,
+}
+
+impl CheckpointReplayReport {
+    fn new(step: &RunStep, checkpoint: &ProcessCheckpointProof) -> Self {
+        Self {
+            checkpoint_config_id: step.id.clone(),
+            checkpoint_id: checkpoint.id.clone(),
+            order_index: step.order_index,
+            proof_mode: step.proof_mode,
+            status: ReplayStatus::Skipped,
+            match_status: false,
+            original_digest: checkpoint.outputs_sha256.clone().unwrap_or_default(),
+            replay_digest: String::new(),
+            error_message: None,
+            usage_tokens: None,
+        }
+    }
+}
+
+/// Replay every exact-mode step the CAR carries enough to reproduce
+pub fn replay_car(car: &Car, ollama_host: &str) -> ReplayReport {
+    let mut report = ReplayReport {
+        run_id: car.run_id.clone(),
+        car_id: car.id.clone(),
+        ollama_host: ollama_host.to_string(),
+        match_status: false,
+        error_message: None,
+        checkpoint_reports: Vec::new(),
+    };
+    let pairs = match step_checkpoints(car) {
+        Ok(pairs) => pairs,
+        Err(reason) => {
+            report.error_message = Some(reason);
+            return report;
+        }
+    };
+
+    for (step, checkpoint) in pairs {
+        let mut entry = CheckpointReplayReport::new(step, checkpoint);
+        match replayable(step, checkpoint) {
+            Ok((model, prompt)) => match generate(ollama_host, model, prompt, car.run.seed) {
+                Ok(generation) => {
+                    entry.usage_tokens = Some(generation.usage_tokens());
+                    entry.replay_digest = sha256_hex(generation.response.as_bytes());
+                    entry.match_status = entry.replay_digest == entry.original_digest;
+                    if entry.match_status {
+                        entry.status = ReplayStatus::Matched;
+                    } else {
+                        entry.status = ReplayStatus::Mismatched;
+                        entry.error_message = Some("outputs digest mismatch".to_string());
+                    }
+                }
+                Err(err) => {
+                    entry.status = ReplayStatus::Failed;
+                    entry.error_message = Some(format!("{err:#}"));
+                }
+            },
+            Err(reason) => entry.error_message = Some(reason),
+        }
+        report.checkpoint_reports.push(entry);
+    }
+
+    let replayed: Vec<_> = report
+        .checkpoint_reports
+        .iter()
+        .filter(|entry| entry.status != ReplayStatus::Skipped)
+        .collect();
+    report.match_status = !replayed.is_empty() && replayed.iter().all(|entry| entry.match_status);
+    report.error_message = if replayed.is_empty() {
+        Some("no step of this CAR can be replayed from the CAR alone".to_string())
+    } else {
+        replayed
+            .iter()
+            .find(|entry| !entry.match_status)
+            .and_then(|entry| entry.error_message.clone())
+    };
+    report
+}
+
+/// Pair the run's steps, in order, with the checkpoints they recorded.
+/// Interactive chats and the items of fan-out steps have checkpoints of
+/// their own and are left out, as are incidents.
+fn step_checkpoints(car: &Car) -> Result<Vec<(&RunStep, &ProcessCheckpointProof)>, String> {
+    let mut steps: Vec<&RunStep> = car
+        .run
+        .steps
+        .iter()
+        .filter(|step| !step.is_interactive_chat())
+        .collect();
+    steps.sort_by_key(|step| step.order_index);
+    let checkpoints: Vec<&ProcessCheckpointProof> = car
+        .proof
+        .process
+        .iter()
+        .flat_map(|process| &process.sequential_checkpoints)
+        .filter(|checkpoint| {
+            checkpoint.kind == "Step"
+                && checkpoint.parent_checkpoint_id.is_none()
+                && checkpoint.turn_index.is_none()
+        })
+        .collect();
+    if steps.len() != checkpoints.len() {
+        return Err(format!(
+            "CAR has {} step checkpoints for {} steps; they cannot be paired",
+            checkpoints.len(),
+            steps.len()
+        ));
+    }
+    Ok(steps.into_iter().zip(checkpoints).collect())
+}
+
+/// The model and prompt to replay a step with, or why it cannot be replayed
+fn replayable<'a>(
+    step: &'a RunStep,
+    checkpoint: &ProcessCheckpointProof,
+) -> Result<(&'a str, &'a str), String> {
+    if step.proof_mode == RunProofMode::Concordant {
+        return Err("concordant steps are graded by similarity; replay them in the app".into());
+    }
+    if !step.is_llm_step() {
+        return Err(format!("{} steps are not replayed from the CAR", step.step_type));
+    }
+    let model = step.model.as_deref().ok_or("step records no model")?;
+    if model == "stub-model" {
+        return Err("stub model steps are simulated; replay them in the app".into());
+    }
+    if let Some(metadata) = &checkpoint.response_metadata {
+        if metadata.provider != "ollama" {
+            return Err(format!("served by {}, not a local Ollama model", metadata.provider));
+        }
+    }
+    let prompt = step.prompt.as_deref().ok_or("step records no prompt")?;
+    if checkpoint.outputs_sha256.is_none() {
+        return Err("no outputs digest recorded for checkpoint".into());
+    }
+    if checkpoint
+        .inputs_sha256
+        .as_deref()
+        .is_some_and(|sent| sent != sha256_hex(prompt.as_bytes()))
+    {
+        return Err("the prompt sent was rendered from a template or earlier outputs, which \
+                    the CAR does not carry"
+            .into());
+    }
+    Ok((model, prompt))
+}
+
+#[derive(Debug, Deserialize)]
+struct Generation {
+    response: String,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+}
+
+impl Generation {
+    fn usage_tokens(&self) -> u64 {
+        self.prompt_eval_count + self.eval_count
+    }
+}
+
+/// Generate with the seed and greedy decoding exact-mode steps ran with
+fn generate(host: &str, model: &str, prompt: &str, seed: u64) -> Result<Generation> {
+    let url = format!("{}/api/generate", ollama_base_url(host));
+    let body = serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "stream": false,
+        "options": { "seed": seed, "temperature": 0.0 },
+    });
+    let response = ureq::post(&url)
+        .timeout(GENERATION_TIMEOUT)
+        .send_json(body)
+        .map_err(|err| match err {
+            ureq::Error::Status(status, response) => anyhow!(
+                "Ollama answered {status}: {}",
+                response.into_string().unwrap_or_default().trim()
+            ),
+            ureq::Error::Transport(transport) => anyhow!("could not reach Ollama: {transport}"),
+        })?;
+    response
+        .into_json()
+        .with_context(|| format!("unexpected response from {url}"))
+}
+
+/// Accept `host:port` as well as a URL, like `OLLAMA_HOST`
+fn ollama_base_url(host: &str) -> String {
+    let host = host.trim_end_matches('/');
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("http://{host}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(value: serde_json::Value) -> RunStep {
+        let mut step = serde_json::json!({
+            "id": "cfg-1",
+            "runId": "run-1",
+            "orderIndex": 0,
+            "checkpointType": "Step",
+            "model": "llama3",
+            "prompt": "Summarize",
+        });
+        step.as_object_mut().unwrap().extend(value.as_object().unwrap().clone());
+        serde_json::from_value(step).unwrap()
+    }
+
+    fn checkpoint(inputs: &str) -> ProcessCheckpointProof {
+        serde_json::from_value(serde_json::json!({
+            "id": "ck-1",
+            "prev_chain": "",
+            "curr_chain": "",
+            "signature": "",
+            "run_id": "run-1",
+            "kind": "Step",
+            "timestamp": "2024-01-02T03:04:05Z",
+            "inputs_sha256": sha256_hex(inputs.as_bytes()),
+            "outputs_sha256": "ab",
+            "usage_tokens": 0,
+            "prompt_tokens": 0,
+            "completion_tokens": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_exact_llm_steps_with_their_sent_prompt_are_replayed() {
+        let exact = step(serde_json::json!({}));
+        assert_eq!(replayable(&exact, &checkpoint("Summarize")), Ok(("llama3", "Summarize")));
+        // A template rendered into something else
+        assert!(replayable(&exact, &checkpoint("Summarize: the report")).is_err());
+
+        let concordant = step(serde_json::json!({ "proofMode": "concordant" }));
+        assert!(replayable(&concordant, &checkpoint("Summarize")).is_err());
+        let ingest = step(serde_json::json!({ "stepType": "ingest" }));
+        assert!(replayable(&ingest, &checkpoint("Summarize")).is_err());
+    }
+
+    #[test]
+    fn hosts_may_be_given_with_or_without_a_scheme() {
+        assert_eq!(ollama_base_url(DEFAULT_OLLAMA_HOST), "http://127.0.0.1:11434");
+        assert_eq!(ollama_base_url("https://gpu.local:443/"), "https://gpu.local:443");
+    }
+}
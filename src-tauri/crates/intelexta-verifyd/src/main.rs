@@ -0,0 +1,233 @@
+//! `intelexta-verifyd`: an HTTP endpoint for verifying CARs
+//!
+//! `POST /verify` takes a CAR as the request body, as JSON, DAG-CBOR or a ZIP
+//! bundle, and answers with the JSON verification report `intelexta-verify
+//! --format json` prints. The checks are the CLI's own, run under the limits
+//! the service was started with. A CAR that fails verification still gets a
+//! 200 with `overall_result: false`; only uploads that cannot be read as a
+//! CAR are rejected.
+//!
+//! Uploads are written to a private temporary directory, verified and
+//! removed. External attachments are not checked: the service has no local
+//! copies of them.
+
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Parser;
+
+use intelexta::verify::TrustedKeys;
+use intelexta_verify::{load_car_file, verify_car, Budget, LimitExceeded, VerificationLimits};
+
+/// HTTP verification service for Intelexta CAR (Content-Addressed Receipt) files.
+#[derive(Parser, Debug)]
+#[command(name = "intelexta-verifyd")]
+#[command(version)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    listen: SocketAddr,
+
+    /// Reject uploads larger than this many bytes (413)
+    #[arg(long, default_value_t = 256 * 1024 * 1024)]
+    max_upload_bytes: usize,
+
+    /// Stop verifying CARs with more checkpoints than this
+    #[arg(long)]
+    max_checkpoints: Option<usize>,
+
+    /// Stop verifying CARs whose attachments total more than this many bytes
+    #[arg(long)]
+    max_attachment_bytes: Option<u64>,
+
+    /// Stop verifying a CAR after this many milliseconds
+    #[arg(long)]
+    time_budget_ms: Option<u64>,
+
+    /// Trust store of known project keys; reports say whether it lists the
+    /// signer of each CAR that verifies
+    #[arg(long)]
+    trusted_keys: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+struct AppState {
+    limits: VerificationLimits,
+    trusted_keys: Option<Arc<TrustedKeys>>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let trusted_keys = match &cli.trusted_keys {
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read trusted keys: {}", path.display()))?;
+            Some(Arc::new(TrustedKeys::from_json(&json)?))
+        }
+        None => None,
+    };
+    let state = AppState {
+        limits: VerificationLimits {
+            max_checkpoints: cli.max_checkpoints,
+            max_attachment_bytes: cli.max_attachment_bytes,
+            time_budget: cli.time_budget_ms.map(Duration::from_millis),
+            ..VerificationLimits::default()
+        },
+        trusted_keys,
+    };
+
+    let listener = tokio::net::TcpListener::bind(cli.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", cli.listen))?;
+    eprintln!("intelexta-verifyd listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app(state, cli.max_upload_bytes))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+fn app(state: AppState, max_upload_bytes: usize) -> Router {
+    Router::new()
+        .route("/verify", post(verify))
+        .route("/health", get(|| async { "ok" }))
+        .layer(DefaultBodyLimit::max(max_upload_bytes))
+        .with_state(state)
+}
+
+/// Why an upload produced no report
+enum Rejection {
+    /// Not a CAR in any format the verifier reads
+    Unreadable(anyhow::Error),
+    /// The bundle is larger than the archive limits allow
+    Limit(LimitExceeded),
+    Internal(anyhow::Error),
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        let (status, body) = match self {
+            Rejection::Unreadable(err) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                serde_json::json!({ "error": format!("{err:#}") }),
+            ),
+            Rejection::Limit(exceeded) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                serde_json::json!({ "error": exceeded.to_string(), "limit_exceeded": exceeded }),
+            ),
+            Rejection::Internal(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                serde_json::json!({ "error": format!("{err:#}") }),
+            ),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+async fn verify(State(state): State<AppState>, body: Bytes) -> Response {
+    // Verification reads files and hashes on rayon's pool; keep it off the
+    // async workers
+    match tokio::task::spawn_blocking(move || verify_upload(&body, &state)).await {
+        Ok(Ok(report)) => Json(report).into_response(),
+        Ok(Err(rejection)) => rejection.into_response(),
+        Err(err) => Rejection::Internal(err.into()).into_response(),
+    }
+}
+
+fn verify_upload(
+    body: &[u8],
+    state: &AppState,
+) -> Result<intelexta_verify::VerificationReport, Rejection> {
+    // A directory of its own, readable only by the service, so no other
+    // upload's attachments/ directory sits next to the CAR
+    let dir = tempfile::Builder::new()
+        .prefix("intelexta-verifyd-")
+        .tempdir()
+        .context("Failed to create a temporary directory")
+        .map_err(Rejection::Internal)?;
+    let path = dir.path().join(format!("upload.car.{}", upload_extension(body)));
+    std::fs::File::create(&path)
+        .and_then(|mut file| file.write_all(body))
+        .with_context(|| format!("Failed to write {}", path.display()))
+        .map_err(Rejection::Internal)?;
+
+    let budget = Budget::new(state.limits.clone());
+    let (car, raw_json, car_path) = load_car_file(&path, budget.limits.archive_limits())
+        .map_err(|err| match err.downcast::<LimitExceeded>() {
+            Ok(exceeded) => Rejection::Limit(exceeded),
+            Err(err) => Rejection::Unreadable(err),
+        })?;
+    let mut report =
+        verify_car(&car, &raw_json, &car_path, None, &budget).map_err(Rejection::Internal)?;
+    if let Some(trusted_keys) = &state.trusted_keys {
+        if report.signatures_valid {
+            report.signer_trust = Some(trusted_keys.signer_trust(&car));
+        }
+    }
+    Ok(report)
+}
+
+/// The extension the loader needs to read an upload, by its first bytes:
+/// bundles are checked for attachments only when they end in `.zip`
+fn upload_extension(body: &[u8]) -> &'static str {
+    if body.starts_with(b"PK\x03\x04") {
+        "zip"
+    } else if body.trim_ascii_start().starts_with(b"{") {
+        "json"
+    } else {
+        "cbor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_app(max_upload_bytes: usize) -> Router {
+        let state = AppState {
+            limits: VerificationLimits::default(),
+            trusted_keys: None,
+        };
+        app(state, max_upload_bytes)
+    }
+
+    async fn post_verify(app: Router, body: &'static [u8]) -> (StatusCode, serde_json::Value) {
+        let request = Request::post("/verify").body(Body::from(body)).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[test]
+    fn uploads_are_stored_under_the_extension_their_format_needs() {
+        assert_eq!(upload_extension(b"PK\x03\x04rest"), "zip");
+        assert_eq!(upload_extension(b"\n  {\"id\": \"car:1\"}"), "json");
+        assert_eq!(upload_extension(&[0xa1, 0x62, 0x69, 0x64]), "cbor");
+    }
+
+    #[tokio::test]
+    async fn uploads_that_are_not_cars_are_rejected() {
+        let (status, body) = post_verify(test_app(1024), b"{\"not\": \"a car\"}").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body["error"].as_str().is_some_and(|error| !error.is_empty()));
+
+        let (status, _) = post_verify(test_app(4), b"{\"too\": \"large\"}").await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,492 @@
+// src-tauri/src/agent.rs
+//!
+//! Tool-calling protocol for `agent` steps
+//!
+//! Each turn the model answers with one JSON object: either a tool call
+//! `{"tool": "<name>", "input": {...}}` or a final answer `{"final": "..."}`.
+//! A response that is not such an object is taken as the final answer. Tools
+//! run inside the orchestrator without a shell:
+//!
+//! - `calculator`: evaluates an arithmetic expression
+//! - `retrieval`: returns the passages of the source step output that best
+//!   match a query
+//! - `read_file`: reads a text file under the step's declared file roots
+//!
+//! Every model turn and every tool invocation is persisted as its own signed
+//! checkpoint, so the trajectory can be audited call by call.
+
+use crate::output_schema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Turns an agent may take when the step does not set `max_turns`
+pub const DEFAULT_MAX_TURNS: u32 = 8;
+
+/// Tool results longer than this are truncated before they reach the model
+const MAX_TOOL_OUTPUT_CHARS: usize = 16_384;
+
+/// Passages a retrieval call returns
+const RETRIEVAL_TOP_K: usize = 3;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentTool {
+    Calculator,
+    Retrieval,
+    ReadFile,
+}
+
+impl AgentTool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AgentTool::Calculator => "calculator",
+            AgentTool::Retrieval => "retrieval",
+            AgentTool::ReadFile => "read_file",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "calculator" => Some(AgentTool::Calculator),
+            "retrieval" => Some(AgentTool::Retrieval),
+            "read_file" => Some(AgentTool::ReadFile),
+            _ => None,
+        }
+    }
+
+    fn usage(&self) -> &'static str {
+        match self {
+            AgentTool::Calculator => {
+                r#"calculator {"expression": "(3 + 4) * 2"} - evaluates arithmetic (+ - * / % ^, parentheses)"#
+            }
+            AgentTool::Retrieval => {
+                r#"retrieval {"query": "..."} - returns the most relevant passages of the source document"#
+            }
+            AgentTool::ReadFile => {
+                r#"read_file {"path": "..."} - returns the text of a local file"#
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentAction {
+    ToolCall { tool: String, input: Value },
+    Final { answer: String },
+}
+
+/// Interpret a model turn. Anything other than a tool-call object is final.
+pub fn parse_action(response: &str) -> AgentAction {
+    let Ok(Value::Object(object)) = output_schema::parse_structured_output(response) else {
+        return AgentAction::Final {
+            answer: response.trim().to_string(),
+        };
+    };
+    if let Some(tool) = object.get("tool").and_then(Value::as_str) {
+        return AgentAction::ToolCall {
+            tool: tool.to_string(),
+            input: object.get("input").cloned().unwrap_or(Value::Null),
+        };
+    }
+    match object.get("final") {
+        Some(Value::String(answer)) => AgentAction::Final {
+            answer: answer.clone(),
+        },
+        Some(other) => AgentAction::Final {
+            answer: other.to_string(),
+        },
+        None => AgentAction::Final {
+            answer: response.trim().to_string(),
+        },
+    }
+}
+
+/// Reasoning the model gave alongside its action, if any
+pub fn parse_thought(response: &str) -> Option<String> {
+    let Ok(Value::Object(object)) = output_schema::parse_structured_output(response) else {
+        return None;
+    };
+    object
+        .get("thought")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|thought| !thought.is_empty())
+        .map(str::to_string)
+}
+
+/// One completed tool invocation, fed back to the model on the next turn
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolExchange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought: Option<String>,
+    pub tool: String,
+    pub input: Value,
+    pub result: String,
+}
+
+/// Prompt for the next turn: protocol, available tools, task and trajectory
+pub fn build_agent_prompt(task: &str, tools: &[AgentTool], transcript: &[ToolExchange]) -> String {
+    let mut prompt = String::from(
+        "You are an agent completing a task. Reply with exactly one JSON object per turn:\n\
+         {\"thought\": \"<reasoning>\", \"tool\": \"<name>\", \"input\": {...}} to call a tool, or\n\
+         {\"thought\": \"<reasoning>\", \"final\": \"<answer>\"} once you can answer.\n",
+    );
+    if tools.is_empty() {
+        prompt.push_str("\nNo tools are available; answer directly.\n");
+    } else {
+        prompt.push_str("\nAvailable tools:\n");
+        for tool in tools {
+            prompt.push_str("- ");
+            prompt.push_str(tool.usage());
+            prompt.push('\n');
+        }
+    }
+    prompt.push_str("\nTask:\n");
+    prompt.push_str(task);
+    prompt.push('\n');
+    for exchange in transcript {
+        if let Some(thought) = &exchange.thought {
+            prompt.push_str(&format!("\nThought: {thought}"));
+        }
+        prompt.push_str(&format!(
+            "\nTool call: {} {}\nTool result:\n{}\n",
+            exchange.tool, exchange.input, exchange.result
+        ));
+    }
+    prompt
+}
+
+/// What tools may touch while a step runs
+pub struct ToolContext<'a> {
+    /// Source step output searched by `retrieval`
+    pub corpus: Option<&'a str>,
+    /// Directories `read_file` may read from
+    pub file_roots: &'a [String],
+}
+
+/// Why a tool call produced no result
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolError {
+    /// Reported back to the model, which may try again
+    Recoverable(String),
+    /// The call asked for a file outside the permitted roots
+    PathDenied(PathBuf),
+}
+
+pub fn run_tool(
+    allowed: &[AgentTool],
+    tool: &str,
+    input: &Value,
+    context: &ToolContext<'_>,
+) -> Result<String, ToolError> {
+    let recoverable = |message: String| ToolError::Recoverable(message);
+    let Some(tool) = AgentTool::from_name(tool).filter(|tool| allowed.contains(tool)) else {
+        return Err(recoverable(format!("unknown or disabled tool '{tool}'")));
+    };
+    let argument = |name: &str| {
+        input
+            .get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| recoverable(format!("{} requires a string '{}'", tool.name(), name)))
+    };
+
+    let output = match tool {
+        AgentTool::Calculator => {
+            let value = evaluate_expression(argument("expression")?).map_err(recoverable)?;
+            format_number(value)
+        }
+        AgentTool::Retrieval => {
+            let corpus = context.corpus.ok_or_else(|| {
+                recoverable("retrieval has no source document for this step".to_string())
+            })?;
+            let passages = retrieve(corpus, argument("query")?, RETRIEVAL_TOP_K);
+            if passages.is_empty() {
+                "No matching passages.".to_string()
+            } else {
+                passages.join("\n---\n")
+            }
+        }
+        AgentTool::ReadFile => {
+            let path = resolve_readable_path(argument("path")?, context.file_roots)?;
+            std::fs::read_to_string(&path)
+                .map_err(|err| recoverable(format!("cannot read {}: {}", path.display(), err)))?
+        }
+    };
+    Ok(truncate(output))
+}
+
+fn truncate(mut output: String) -> String {
+    if let Some((index, _)) = output.char_indices().nth(MAX_TOOL_OUTPUT_CHARS) {
+        output.truncate(index);
+        output.push_str("\n…[truncated]");
+    }
+    output
+}
+
+/// The file must resolve inside one of the declared roots; `..` and symlinks
+/// are resolved before the check
+fn resolve_readable_path(path: &str, roots: &[String]) -> Result<PathBuf, ToolError> {
+    let requested = Path::new(path);
+    let resolved = std::fs::canonicalize(requested)
+        .map_err(|err| ToolError::Recoverable(format!("cannot read {path}: {err}")))?;
+    let permitted = roots
+        .iter()
+        .filter_map(|root| std::fs::canonicalize(root).ok())
+        .any(|root| resolved.starts_with(root));
+    if permitted {
+        Ok(resolved)
+    } else {
+        Err(ToolError::PathDenied(resolved))
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Rank blank-line separated passages by how many query terms they contain
+fn retrieve(corpus: &str, query: &str, top_k: usize) -> Vec<String> {
+    let terms: std::collections::BTreeSet<String> = tokenize(query).into_iter().collect();
+    let mut scored: Vec<(usize, usize, &str)> = corpus
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|passage| !passage.is_empty())
+        .enumerate()
+        .map(|(index, passage)| {
+            let words: std::collections::BTreeSet<String> = tokenize(passage).into_iter().collect();
+            (terms.intersection(&words).count(), index, passage)
+        })
+        .filter(|(score, _, _)| *score > 0)
+        .collect();
+    // Highest score first; ties keep document order
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .take(top_k)
+        .map(|(_, _, passage)| passage.to_string())
+        .collect()
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value}")
+    }
+}
+
+/// Evaluate `+ - * / % ^` over numbers with parentheses and unary minus
+pub fn evaluate_expression(expression: &str) -> Result<f64, String> {
+    let mut parser = ExpressionParser {
+        chars: expression
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .collect(),
+        position: 0,
+    };
+    let value = parser.sum()?;
+    if parser.position != parser.chars.len() {
+        return Err(format!(
+            "unexpected '{}' in expression",
+            parser.chars[parser.position]
+        ));
+    }
+    if !value.is_finite() {
+        return Err("expression does not evaluate to a finite number".to_string());
+    }
+    Ok(value)
+}
+
+struct ExpressionParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl ExpressionParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.position += 1;
+            let rhs = self.product()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.power()?;
+        while let Some(op @ ('*' | '/' | '%')) = self.peek() {
+            self.position += 1;
+            let rhs = self.power()?;
+            if op != '*' && rhs == 0.0 {
+                return Err("division by zero".to_string());
+            }
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.unary()?;
+        if self.peek() == Some('^') {
+            self.position += 1;
+            // Right-associative: 2^3^2 = 2^9
+            let exponent = self.power()?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.peek() == Some('-') {
+            self.position += 1;
+            return Ok(-self.unary()?);
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let value = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err("missing closing parenthesis".to_string());
+                }
+                self.position += 1;
+                Ok(value)
+            }
+            Some(ch) if ch.is_ascii_digit() || ch == '.' => {
+                let start = self.position;
+                while self
+                    .peek()
+                    .is_some_and(|ch| ch.is_ascii_digit() || ch == '.')
+                {
+                    self.position += 1;
+                }
+                let literal: String = self.chars[start..self.position].iter().collect();
+                literal
+                    .parse()
+                    .map_err(|_| format!("invalid number '{literal}'"))
+            }
+            Some(ch) => Err(format!("unexpected '{ch}' in expression")),
+            None => Err("expression ended unexpectedly".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_tool_calls_and_final_answers() {
+        assert_eq!(
+            parse_action(r#"{"tool": "calculator", "input": {"expression": "1+1"}}"#),
+            AgentAction::ToolCall {
+                tool: "calculator".to_string(),
+                input: json!({"expression": "1+1"}),
+            }
+        );
+        assert_eq!(
+            parse_action("```json\n{\"final\": \"42\"}\n```"),
+            AgentAction::Final {
+                answer: "42".to_string()
+            }
+        );
+        assert_eq!(
+            parse_action("The answer is 42."),
+            AgentAction::Final {
+                answer: "The answer is 42.".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn thoughts_are_parsed_and_replayed_in_the_transcript() {
+        let reply = r#"{"thought": "Need the product first.", "tool": "calculator", "input": {"expression": "6*7"}}"#;
+        assert_eq!(
+            parse_thought(reply).as_deref(),
+            Some("Need the product first.")
+        );
+        assert_eq!(parse_thought("The answer is 42."), None);
+
+        let transcript = vec![ToolExchange {
+            thought: parse_thought(reply),
+            tool: "calculator".to_string(),
+            input: json!({"expression": "6*7"}),
+            result: "42".to_string(),
+        }];
+        let prompt = build_agent_prompt("Multiply", &[AgentTool::Calculator], &transcript);
+        assert!(prompt.contains("Thought: Need the product first.\nTool call: calculator"));
+    }
+
+    #[test]
+    fn calculator_respects_precedence() {
+        assert_eq!(evaluate_expression("(3 + 4) * 2").unwrap(), 14.0);
+        assert_eq!(evaluate_expression("2 + 3 * 4 ^ 2").unwrap(), 50.0);
+        assert_eq!(evaluate_expression("-2 ^ 2").unwrap(), 4.0);
+        assert_eq!(evaluate_expression("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert!(evaluate_expression("1 / 0").is_err());
+        assert!(evaluate_expression("(1 + 2").is_err());
+        assert!(evaluate_expression("rm -rf /").is_err());
+    }
+
+    #[test]
+    fn retrieval_ranks_passages_by_query_terms() {
+        let corpus = "Solar panels convert light.\n\nWind turbines convert wind into power.\n\nTurbines and solar both need grid power.";
+        let passages = retrieve(corpus, "wind turbines power", 2);
+        assert_eq!(
+            passages,
+            vec![
+                "Wind turbines convert wind into power.".to_string(),
+                "Turbines and solar both need grid power.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn tools_must_be_enabled_and_files_inside_roots() {
+        let dir = std::env::temp_dir().join(format!("agent-tools-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        std::fs::write(&file, "hello").unwrap();
+        let roots = vec![dir.display().to_string()];
+        let context = ToolContext {
+            corpus: None,
+            file_roots: &roots,
+        };
+        let input = json!({"path": file.display().to_string()});
+
+        assert_eq!(
+            run_tool(&[AgentTool::ReadFile], "read_file", &input, &context),
+            Ok("hello".to_string())
+        );
+        assert!(matches!(
+            run_tool(&[AgentTool::Calculator], "read_file", &input, &context),
+            Err(ToolError::Recoverable(_))
+        ));
+        let outside = ToolContext {
+            corpus: None,
+            file_roots: &[],
+        };
+        assert!(matches!(
+            run_tool(&[AgentTool::ReadFile], "read_file", &input, &outside),
+            Err(ToolError::PathDenied(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,17 @@
 // In src-tauri/src/api.rs
 use crate::{
-    api_keys, car, ledger, orchestrator, portability, provenance, replay,
+    api_keys, car, challenge, cold_storage, content_policy, cost_estimation, dashboard,
+    execution_window, governance, incident_log, incident_response, key_protection, ledger,
+    notifications, orchestrator, plugins, policy_approval, policy_history, policy_simulation,
+    portability, project_archival, prompt_template, provenance, replay, run_environment, run_queue,
+    runtime, self_verification,
     store::{self, policies::Policy},
-    DbPool, Error, Project,
+    telemetry, templates, threshold, triggers, workspace_search, DbPool, Error, Project,
 };
+use intelexta_verify_core::semantic;
 use rusqlite::{params, types::Type, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 #[cfg(feature = "interactive")]
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -47,19 +53,61 @@ pub fn rename_project(
     Ok(project)
 }
 
+/// Deleting a project archives it; it is purged once its retention period
+/// has passed, or by `purge_project`
 #[tauri::command]
 pub fn delete_project(project_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
     let mut conn = pool.get()?;
-    store::projects::delete(&mut conn, &project_id)?;
-    if let Err(err) = provenance::delete_secret_key(&project_id) {
-        eprintln!(
-            "[intelexta] WARNING: Failed to delete provenance key for project {}: {}",
-            project_id, err
-        );
-    }
+    project_archival::archive_project(&mut conn, &project_id)?;
     Ok(())
 }
 
+#[tauri::command]
+pub fn restore_project(project_id: String, pool: State<'_, DbPool>) -> Result<Project, Error> {
+    let mut conn = pool.get()?;
+    project_archival::restore_project(&mut conn, &project_id)
+}
+
+#[tauri::command]
+pub fn list_archived_projects(pool: State<'_, DbPool>) -> Result<Vec<Project>, Error> {
+    let conn = pool.get()?;
+    store::projects::list_archived(&conn)
+}
+
+/// Delete an archived project and its signing key for good
+#[tauri::command]
+pub fn purge_project(project_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
+    let mut conn = pool.get()?;
+    project_archival::purge_project(&mut conn, &project_id)
+}
+
+/// Days archived projects are kept before they are purged; none keeps them
+#[tauri::command]
+pub fn get_project_retention_days(pool: State<'_, DbPool>) -> Result<Option<u32>, Error> {
+    let conn = pool.get()?;
+    store::project_retention_settings::get_retention_days(&conn)
+}
+
+#[tauri::command]
+pub fn set_project_retention_days(
+    retention_days: Option<u32>,
+    pool: State<'_, DbPool>,
+) -> Result<Option<u32>, Error> {
+    let conn = pool.get()?;
+    project_archival::set_retention_days(&conn, retention_days)?;
+    store::project_retention_settings::get_retention_days(&conn)
+}
+
+/// Projects archived, restored and purged, newest first
+#[tauri::command]
+pub fn list_project_archive_events(
+    project_id: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::project_archive_events::ProjectArchiveEvent>, Error> {
+    let conn = pool.get()?;
+    store::project_archive_events::list(&conn, project_id.as_deref())
+}
+
 pub(crate) fn create_project_with_pool(name: String, pool: &DbPool) -> Result<Project, Error> {
     let project_id = Uuid::new_v4().to_string();
     let kp = provenance::generate_keypair();
@@ -123,6 +171,16 @@ pub fn rename_run(run_id: String, name: String, pool: State<'_, DbPool>) -> Resu
         .map_err(|err| Error::Api(err.to_string()))
 }
 
+#[tauri::command]
+pub fn set_run_priority(
+    run_id: String,
+    priority: run_queue::RunPriority,
+    pool: State<'_, DbPool>,
+) -> Result<(), Error> {
+    orchestrator::set_run_priority(pool.inner(), &run_id, priority)
+        .map_err(|err| Error::Api(err.to_string()))
+}
+
 #[tauri::command]
 pub fn delete_run(run_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
     orchestrator::delete_run(pool.inner(), &run_id).map_err(|err| Error::Api(err.to_string()))
@@ -217,6 +275,10 @@ pub struct RunExecutionSummary {
     pub created_at: String,
     #[serde(default)]
     pub step_proofs: Vec<ExecutionStepProofSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preemptions: Vec<run_queue::Preemption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<run_environment::RunEnvironment>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -230,6 +292,8 @@ pub struct RunSummary {
     pub epsilon: Option<f64>,
     pub has_persisted_checkpoint: bool,
     #[serde(default)]
+    pub priority: run_queue::RunPriority,
+    #[serde(default)]
     pub executions: Vec<RunExecutionSummary>,
     #[serde(default)]
     pub step_proofs: Vec<ExecutionStepProofSummary>,
@@ -254,6 +318,8 @@ fn hydrate_run_summary(row: &rusqlite::Row<'_>) -> rusqlite::Result<RunSummary>
         epsilon: None,
         // has_persisted_checkpoint is the new name for the column at index 3.
         has_persisted_checkpoint: row.get(3)?,
+        priority: run_queue::RunPriority::try_from(row.get::<_, String>(4)?.as_str())
+            .unwrap_or_default(),
         executions: Vec::new(),
         step_proofs: Vec::new(),
     })
@@ -295,7 +361,7 @@ pub fn list_runs(project_id: String, pool: State<'_, DbPool>) -> Result<Vec<RunS
     let conn = pool.get()?;
     // This SQL query is now simpler and no longer selects the obsolete spec_json.
     let mut stmt = conn.prepare(
-        "SELECT r.id, r.name, r.created_at, EXISTS (SELECT 1 FROM run_executions e WHERE e.run_id = r.id) AS has_persisted_checkpoint FROM runs r WHERE r.project_id = ?1 ORDER BY r.created_at DESC",
+        "SELECT r.id, r.name, r.created_at, EXISTS (SELECT 1 FROM run_executions e WHERE e.run_id = r.id) AS has_persisted_checkpoint, r.priority FROM runs r WHERE r.project_id = ?1 ORDER BY r.created_at DESC",
     )?;
 
     let runs_iter = stmt.query_map(params![project_id], hydrate_run_summary)?;
@@ -327,6 +393,8 @@ pub fn list_runs(project_id: String, pool: State<'_, DbPool>) -> Result<Vec<RunS
                 id: record.id,
                 created_at: record.created_at,
                 step_proofs: step_proofs.clone(),
+                preemptions: record.preemptions,
+                environment: record.environment,
             })
             .collect();
 
@@ -338,7 +406,7 @@ pub fn list_runs(project_id: String, pool: State<'_, DbPool>) -> Result<Vec<RunS
 fn load_run_summary(conn: &Connection, run_id: &str) -> Result<RunSummary, Error> {
     let summary = conn
         .query_row(
-            "SELECT r.id, r.name, r.created_at, r.spec_json, EXISTS (SELECT 1 FROM run_executions e WHERE e.run_id = r.id) AS has_persisted_checkpoint FROM runs r WHERE r.id = ?1",
+            "SELECT r.id, r.name, r.created_at, EXISTS (SELECT 1 FROM run_executions e WHERE e.run_id = r.id) AS has_persisted_checkpoint, r.priority FROM runs r WHERE r.id = ?1",
             params![run_id],
             hydrate_run_summary,
         )
@@ -356,6 +424,8 @@ fn load_run_summary(conn: &Connection, run_id: &str) -> Result<RunSummary, Error
             id: record.id,
             created_at: record.created_at,
             step_proofs: step_proofs.clone(),
+            preemptions: record.preemptions,
+            environment: record.environment,
         })
         .collect();
     if !summary.executions.is_empty() {
@@ -406,6 +476,15 @@ pub struct CheckpointDetails {
     pub prompt_payload: Option<String>,
     pub output_payload: Option<String>,
     pub message: Option<CheckpointMessageSummary>,
+    /// Request ID the model provider assigned to the call
+    pub provider_request_id: Option<String>,
+    /// sha256 of the provider's response headers (see `model_adapters::DIGESTED_RESPONSE_HEADERS`)
+    pub response_headers_sha256: Option<String>,
+    /// USD and Nature Cost priced when the checkpoint was recorded
+    pub usd_cost: Option<f64>,
+    pub nature_cost: Option<f64>,
+    /// Emissions computed when the checkpoint was recorded
+    pub g_co2e: Option<f64>,
 }
 
 #[cfg(feature = "interactive")]
@@ -662,7 +741,7 @@ pub(crate) fn get_checkpoint_details_with_pool(
 ) -> Result<CheckpointDetails, Error> {
     let conn = pool.get()?;
     let mut stmt = conn.prepare(
-        "SELECT c.id, c.run_id, c.run_execution_id, c.timestamp, c.kind, c.incident_json, c.inputs_sha256, c.outputs_sha256, c.semantic_digest, c.usage_tokens, c.prompt_tokens, c.completion_tokens, c.parent_checkpoint_id, c.turn_index, c.checkpoint_config_id, p.prompt_payload, p.output_payload, m.role, m.body, m.created_at, m.updated_at
+        "SELECT c.id, c.run_id, c.run_execution_id, c.timestamp, c.kind, c.incident_json, c.inputs_sha256, c.outputs_sha256, c.semantic_digest, c.usage_tokens, c.prompt_tokens, c.completion_tokens, c.parent_checkpoint_id, c.turn_index, c.checkpoint_config_id, p.prompt_payload, p.output_payload, m.role, m.body, m.created_at, m.updated_at, c.response_metadata_json, c.usd_cost, c.nature_cost, c.g_co2e
          FROM checkpoints c
          LEFT JOIN checkpoint_payloads p ON p.checkpoint_id = c.id
          LEFT JOIN checkpoint_messages m ON m.checkpoint_id = c.id
@@ -708,6 +787,13 @@ pub(crate) fn get_checkpoint_details_with_pool(
             }),
             _ => None,
         };
+        let response_metadata = row
+            .get::<_, Option<String>>(21)?
+            .map(|json| serde_json::from_str::<crate::model_adapters::ResponseMetadata>(&json))
+            .transpose()
+            .map_err(|err| {
+                rusqlite::Error::FromSqlConversionFailure(21, Type::Text, Box::new(err))
+            })?;
 
         Ok(CheckpointDetails {
             id: row.get(0)?,
@@ -737,6 +823,14 @@ pub(crate) fn get_checkpoint_details_with_pool(
             prompt_payload,
             output_payload,
             message,
+            provider_request_id: response_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.provider_request_id.clone()),
+            response_headers_sha256: response_metadata
+                .and_then(|metadata| metadata.response_headers_sha256),
+            usd_cost: row.get(22)?,
+            nature_cost: row.get(23)?,
+            g_co2e: row.get(24)?,
         })
     });
 
@@ -751,19 +845,23 @@ pub(crate) fn get_checkpoint_details_with_pool(
 
 #[cfg(feature = "interactive")]
 #[tauri::command]
-pub fn submit_interactive_checkpoint_turn(
+pub async fn submit_interactive_checkpoint_turn(
     run_id: String,
     checkpoint_id: String,
     prompt_text: String,
     pool: State<'_, DbPool>,
 ) -> Result<orchestrator::SubmitTurnOutcome, Error> {
-    orchestrator::submit_interactive_checkpoint_turn(
-        pool.inner(),
-        &run_id,
-        &checkpoint_id,
-        &prompt_text,
-    )
-    .map_err(|err| Error::Api(err.to_string()))
+    let pool = pool.inner().clone();
+    run_job("submit_interactive_checkpoint_turn", move || {
+        orchestrator::submit_interactive_checkpoint_turn(
+            &pool,
+            &run_id,
+            &checkpoint_id,
+            &prompt_text,
+        )
+        .map_err(|err| Error::Api(err.to_string()))
+    })
+    .await
 }
 
 #[cfg(feature = "interactive")]
@@ -840,22 +938,225 @@ pub fn get_policy(project_id: String, pool: State<'_, DbPool>) -> Result<Policy,
     store::policies::get(&conn, &project_id)
 }
 
+/// The app-level default policy projects inherit; none until one is set
+#[tauri::command]
+pub fn get_default_policy(pool: State<'_, DbPool>) -> Result<Option<Policy>, Error> {
+    let conn = pool.get()?;
+    store::policies::get_default(&conn)
+}
+
+/// Set the default policy; projects' own policies are tightened to the
+/// fields it caps from then on
+#[tauri::command]
+pub fn set_default_policy(policy: Policy, pool: State<'_, DbPool>) -> Result<(), Error> {
+    content_policy::validate_rules(&policy.content_rules).map_err(Error::Api)?;
+    if let Some(window) = &policy.execution_window {
+        execution_window::validate_window(window).map_err(Error::Api)?;
+    }
+    notifications::validate_thresholds(&policy.budget_alert_thresholds).map_err(Error::Api)?;
+    let conn = pool.get()?;
+    store::policies::set_default(&conn, &policy)
+}
+
 #[tauri::command]
 pub async fn replay_run(
     run_id: String,
+    options: Option<replay::ReplayOptions>,
     pool: State<'_, DbPool>,
+    app_handle: AppHandle,
 ) -> Result<replay::ReplayReport, Error> {
+    let options = options.unwrap_or_default();
+    let base_dir = if options.emit_replay_car {
+        Some(
+            app_handle
+                .path()
+                .app_local_data_dir()
+                .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?,
+        )
+    } else {
+        None
+    };
+    let pool = pool.inner().clone();
+    run_job("replay_run", move || {
+        replay_run_with_options(run_id, &pool, options, base_dir.as_deref())
+    })
+    .await
+}
+
+/// Auditor side: challenge the prover to re-execute one step of a run
+#[tauri::command]
+pub fn issue_verification_challenge(
+    run_id: String,
+    order_index: i64,
+    ttl_seconds: Option<i64>,
+) -> Result<challenge::Challenge, Error> {
+    Ok(challenge::issue_challenge(&run_id, order_index, ttl_seconds))
+}
+
+/// Prover side: re-execute the challenged step and return the signed proof
+#[tauri::command]
+pub async fn respond_to_verification_challenge(
+    challenge: challenge::Challenge,
+    pool: State<'_, DbPool>,
+) -> Result<challenge::ChallengeProof, Error> {
+    let pool = pool.inner().clone();
+    run_job("respond_to_verification_challenge", move || {
+        let client = orchestrator::DispatchingLlmClient::new();
+        challenge::respond_to_challenge(&pool, &challenge, &client)
+            .map_err(|err| Error::Api(err.to_string()))
+    })
+    .await
+}
+
+/// Auditor side: check a proof against the challenge that was issued
+#[tauri::command]
+pub fn verify_challenge_proof(
+    proof: challenge::ChallengeProof,
+    challenge: challenge::Challenge,
+    expected_public_key: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<challenge::ChallengeVerification, Error> {
+    let verification =
+        challenge::verify_challenge_proof(&proof, &challenge, expected_public_key.as_deref());
+    telemetry::record_verification_with_pool(
+        pool.inner(),
+        telemetry::VerificationKind::Challenge,
+        verification.valid,
+    );
+    Ok(verification)
+}
+
+/// The user's grid region and the regions the model catalog knows
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSettings {
+    /// `None` prices on-device inference against the catalog's default region
+    pub grid_region: Option<String>,
+    pub default_region: String,
+    /// gCO2e per kWh by grid region
+    pub carbon_intensity: BTreeMap<String, f64>,
+}
+
+fn grid_settings(grid_region: Option<String>) -> GridSettings {
+    let carbon_intensity = crate::model_catalog::try_get_global_catalog()
+        .map(|catalog| catalog.raw.grid_carbon_intensity.clone().into_iter().collect())
+        .unwrap_or_default();
+    GridSettings {
+        grid_region,
+        default_region: crate::model_catalog::DEFAULT_GRID_REGION.to_string(),
+        carbon_intensity,
+    }
+}
+
+#[tauri::command]
+pub fn get_grid_settings(pool: State<'_, DbPool>) -> Result<GridSettings, Error> {
+    let conn = pool.get()?;
+    Ok(grid_settings(store::carbon_settings::get_grid_region(&conn)?))
+}
+
+/// Set the grid region on-device inference is priced against; costs of
+/// checkpoints already recorded keep the region they were priced with
+#[tauri::command]
+pub fn set_grid_region(
+    grid_region: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<GridSettings, Error> {
+    let grid_region = grid_region
+        .map(|region| region.trim().to_lowercase())
+        .filter(|region| !region.is_empty());
+    let settings = grid_settings(grid_region.clone());
+    if let Some(region) = &grid_region {
+        let known = &settings.carbon_intensity;
+        if !known.is_empty() && !known.contains_key(region) {
+            return Err(Error::Api(format!(
+                "unknown grid region '{region}'; the model catalog lists {}",
+                known.keys().cloned().collect::<Vec<_>>().join(", ")
+            )));
+        }
+    }
+    let conn = pool.get()?;
+    store::carbon_settings::set_grid_region(&conn, grid_region.as_deref())?;
+    crate::model_catalog::set_grid_region(grid_region);
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_self_verification_settings(
+    pool: State<'_, DbPool>,
+) -> Result<store::self_verification::SelfVerificationSettings, Error> {
+    let conn = pool.get()?;
+    store::self_verification::get_settings(&conn)
+}
+
+#[tauri::command]
+pub fn set_self_verification_settings(
+    enabled: bool,
+    hour_utc: u32,
+    lookback_days: u32,
+    pool: State<'_, DbPool>,
+) -> Result<store::self_verification::SelfVerificationSettings, Error> {
+    let conn = pool.get()?;
+    store::self_verification::update_settings(&conn, enabled, hour_utc, lookback_days)
+}
+
+/// Re-verify recent receipts and checkpoint chains now instead of waiting
+/// for the nightly pass
+#[tauri::command]
+pub async fn run_self_verification(
+    pool: State<'_, DbPool>,
+) -> Result<self_verification::SelfVerificationReport, Error> {
     let pool = pool.inner().clone();
-    let handle = tauri::async_runtime::spawn_blocking(move || replay_run_with_pool(run_id, &pool));
-    let result = handle
-        .await
-        .map_err(|err| Error::Api(format!("replay run task failed: {err}")))?;
-    result
+    run_job("self_verification", move || {
+        let lookback_days = store::self_verification::get_settings(&*pool.get()?)?.lookback_days;
+        self_verification::run_pass(&pool, lookback_days)
+    })
+    .await
+}
+
+/// Self-verification results, newest first
+#[tauri::command]
+pub fn list_verification_history(
+    limit: Option<u32>,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::self_verification::VerificationRecord>, Error> {
+    let conn = pool.get()?;
+    store::self_verification::list(&conn, limit.unwrap_or(200))
+}
+
+/// Receipts and chains that started failing self-verification
+#[tauri::command]
+pub fn list_self_verification_alerts(
+    pending_only: bool,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::self_verification::VerificationRecord>, Error> {
+    let conn = pool.get()?;
+    store::self_verification::list_alerts(&conn, pending_only)
+}
+
+#[tauri::command]
+pub fn acknowledge_self_verification_alert(
+    alert_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::self_verification::VerificationRecord, Error> {
+    let conn = pool.get()?;
+    store::self_verification::acknowledge(&conn, &alert_id, &chrono::Utc::now().to_rfc3339())
 }
 
+#[cfg(test)]
 pub(crate) fn replay_run_with_pool(
     run_id: String,
     pool: &DbPool,
+) -> Result<replay::ReplayReport, Error> {
+    replay_run_with_options(run_id, pool, replay::ReplayOptions::default(), None)
+}
+
+/// Replay a run, optionally keeping its outputs and emitting a replay CAR
+/// under `base_dir/{project_id}/receipts`
+pub(crate) fn replay_run_with_options(
+    run_id: String,
+    pool: &DbPool,
+    options: replay::ReplayOptions,
+    base_dir: Option<&Path>,
 ) -> Result<replay::ReplayReport, Error> {
     let conn = pool.get()?;
     let stored_run = match orchestrator::load_stored_run(&conn, &run_id) {
@@ -881,6 +1182,8 @@ pub(crate) fn replay_run_with_pool(
         ));
     }
 
+    cold_storage::ensure_hot(&conn, &run_id)?;
+
     // Get policy and current ledger to check if replay is allowed
     let policy = store::policies::get_for_policy_version(
         &conn,
@@ -1010,6 +1313,9 @@ pub(crate) fn replay_run_with_pool(
                         usage_tokens: None,
                         usage_usd: None,
                         usage_nature_cost: None,
+                        original_output_ref: None,
+                        replay_output_ref: None,
+                        replay_output: None,
                     });
                 checkpoint_reports.push(report);
             }
@@ -1061,21 +1367,48 @@ pub(crate) fn replay_run_with_pool(
 
     // Only update ledger if there was actual usage
     if total_usage_tokens > 0 {
-        store::project_usage_ledgers::increment(
+        store::project_usage_ledgers::record_replay(
             &conn,
             &stored_run.project_id,
+            &run_id,
             stored_run.policy_version,
             total_usage_tokens,
             total_usage_usd,
             total_usage_nature_cost,
         )?;
+        notifications::check_budget_alerts(
+            &conn,
+            &stored_run.project_id,
+            stored_run.policy_version,
+        );
     }
 
-    Ok(replay::ReplayReport::from_checkpoint_reports(
-        run_id,
-        checkpoint_reports,
-        None,
-    ))
+    if options.store_outputs || options.emit_replay_car {
+        replay::store_replay_outputs(&conn, &run_id, &mut checkpoint_reports)
+            .map_err(|err| Error::Api(format!("failed to store replay outputs: {err}")))?;
+    }
+
+    let mut report = replay::ReplayReport::from_checkpoint_reports(run_id, checkpoint_reports, None);
+    telemetry::record_verification(&conn, telemetry::VerificationKind::Replay, report.match_status);
+
+    if options.emit_replay_car {
+        let base_dir = base_dir
+            .ok_or_else(|| Error::Api("no output directory for the replay CAR".to_string()))?;
+        let replay_car =
+            replay::build_replay_car(&conn, &report).map_err(|err| Error::Api(err.to_string()))?;
+
+        let receipts_dir = base_dir.join(&stored_run.project_id).join("receipts");
+        std::fs::create_dir_all(&receipts_dir)
+            .map_err(|err| Error::Api(format!("failed to create receipts dir: {err}")))?;
+        let file_path = receipts_dir.join(format!("{}.replay.zip", replay_car.id.replace(':', "_")));
+        replay::write_replay_car_bundle(&replay_car, &file_path)
+            .map_err(|err| Error::Api(format!("failed to build replay CAR bundle: {err}")))?;
+
+        report.replay_car_id = Some(replay_car.id);
+        report.replay_car_path = Some(file_path.to_string_lossy().to_string());
+    }
+
+    Ok(report)
 }
 
 #[tauri::command]
@@ -1167,6 +1500,13 @@ pub fn update_run_step(
                 orchestrator::StepConfig::Ingest { .. } => "ingest",
                 orchestrator::StepConfig::Summarize { .. } => "summarize",
                 orchestrator::StepConfig::Prompt { .. } => "prompt",
+                orchestrator::StepConfig::Map { .. } => "map",
+                orchestrator::StepConfig::Branch { .. } => "branch",
+                orchestrator::StepConfig::Plugin { .. } => "plugin",
+                orchestrator::StepConfig::Agent { .. } => "agent",
+                orchestrator::StepConfig::FineTune { .. } => "finetune",
+                orchestrator::StepConfig::Embed { .. } => "embed",
+                orchestrator::StepConfig::Evaluate { .. } => "evaluate",
             };
 
             if config.step_type != expected_type {
@@ -1175,6 +1515,8 @@ pub fn update_run_step(
                     config.step_type, expected_type
                 )));
             }
+
+            step_config.validate().map_err(Error::Api)?;
         }
         config.config_json = Some(config_json);
     }
@@ -1182,9 +1524,9 @@ pub fn update_run_step(
         let value = config
             .epsilon
             .ok_or_else(|| Error::Api("concordant steps require an epsilon".to_string()))?;
-        if !value.is_finite() || value < 0.0 {
+        if !semantic::epsilon_in_bounds(value) {
             return Err(Error::Api(
-                "epsilon must be a finite, non-negative value".to_string(),
+                "epsilon must be between 0 and 1".to_string(),
             ));
         }
         config.epsilon = Some(value);
@@ -1320,31 +1662,88 @@ pub(crate) fn reorder_run_steps_with_pool(
     list_run_steps_with_pool(run_id, pool)
 }
 
+/// Queue a run execution and return its job id right away. The job waits
+/// for the run queue and the execution window before it takes a worker; its
+/// status carries the `RunExecutionSummary` once it succeeded.
+#[tauri::command]
+pub fn start_run(
+    run_id: String,
+    inputs: Option<serde_json::Map<String, serde_json::Value>>,
+    pool: State<'_, DbPool>,
+) -> Result<String, Error> {
+    let admit_pool = pool.inner().clone();
+    let pool = pool.inner().clone();
+    let job = runtime::workers().submit_admitted(
+        "start_run",
+        move || {
+            orchestrator::admit_run_with_inputs(&admit_pool, &run_id, inputs.unwrap_or_default())
+                .map_err(|err| Error::Api(err.to_string()))
+        },
+        move |admitted| {
+            let client = orchestrator::DispatchingLlmClient::new();
+            let record = orchestrator::execute_admitted_run(&pool, admitted, &client)
+                .map_err(|err| Error::Api(err.to_string()))?;
+
+            let conn = pool.get()?;
+            let step_proofs = load_step_proof_summaries(&conn, &record.run_id)?;
+
+            Ok(RunExecutionSummary {
+                id: record.id,
+                created_at: record.created_at,
+                step_proofs,
+                preemptions: record.preemptions,
+                environment: record.environment,
+            })
+        },
+    );
+    Ok(job.id)
+}
+
+/// Validate a run without calling models or writing checkpoints
 #[tauri::command]
-pub async fn start_run(
+pub async fn start_run_dry(
     run_id: String,
+    inputs: Option<serde_json::Map<String, serde_json::Value>>,
     pool: State<'_, DbPool>,
-) -> Result<RunExecutionSummary, Error> {
+) -> Result<orchestrator::DryRunReport, Error> {
     let pool = pool.inner().clone();
-    let handle = tauri::async_runtime::spawn_blocking(move || -> Result<_, Error> {
-        let record =
-            orchestrator::start_run(&pool, &run_id).map_err(|err| Error::Api(err.to_string()))?;
+    run_job("start_run_dry", move || {
+        orchestrator::start_run_dry(&pool, &run_id, inputs.unwrap_or_default())
+            .map_err(|err| Error::Api(err.to_string()))
+    })
+    .await
+}
 
-        let conn = pool.get()?;
-        let step_proofs = load_step_proof_summaries(&conn, &run_id)?;
+/// Executions waiting in the run queue or currently running
+#[tauri::command]
+pub fn list_active_executions(
+    project_id: Option<String>,
+) -> Result<Vec<run_queue::ActiveExecution>, Error> {
+    Ok(run_queue::global().active(project_id.as_deref()))
+}
 
-        Ok((record, step_proofs))
-    });
-    let result = handle
-        .await
-        .map_err(|err| Error::Api(format!("start run task failed: {err}")))?;
-    let (record, step_proofs) = result?;
-
-    Ok(RunExecutionSummary {
-        id: record.id,
-        created_at: record.created_at,
-        step_proofs,
-    })
+/// Run blocking model, database or file work on the worker pool and wait for
+/// its result. Commands whose work can wait long to be admitted, like
+/// `start_run`, return the job id instead.
+async fn run_job<T, F>(kind: &str, work: F) -> Result<T, Error>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    runtime::workers().submit(kind, work).join().await
+}
+
+#[tauri::command]
+pub fn get_job_status(job_id: String) -> Result<runtime::JobStatus, Error> {
+    runtime::workers()
+        .status(&job_id)
+        .ok_or_else(|| Error::Api(format!("job {job_id} not found")))
+}
+
+/// Jobs queued or running on the worker pool and the most recently finished
+#[tauri::command]
+pub fn list_jobs() -> Result<Vec<runtime::JobStatus>, Error> {
+    Ok(runtime::workers().list())
 }
 
 #[tauri::command]
@@ -1362,77 +1761,595 @@ pub fn estimate_run_cost(
         .map_err(|err| Error::Api(err.to_string()))
 }
 
+/// Low, expected and high usage per step, following the context chained
+/// steps inherit from ingested documents and earlier outputs
 #[tauri::command]
-pub fn update_policy(
-    project_id: String,
-    policy: Policy,
+pub fn estimate_run_step_costs(
+    run_id: String,
     pool: State<'_, DbPool>,
-) -> Result<(), Error> {
+) -> Result<cost_estimation::RunStepCostEstimates, Error> {
     let conn = pool.get()?;
-    store::policies::upsert(&conn, &project_id, &policy)
+    cost_estimation::estimate_step_costs(&conn, &run_id)
 }
 
 #[tauri::command]
-pub fn update_policy_with_notes(
-    project_id: String,
-    policy: Policy,
-    change_notes: Option<String>,
+pub fn create_run_trigger(
+    run_id: String,
+    watch_path: String,
     pool: State<'_, DbPool>,
-) -> Result<(), Error> {
+) -> Result<store::run_triggers::RunTrigger, Error> {
+    let trimmed = watch_path.trim();
+    if trimmed.is_empty() {
+        return Err(Error::Api("Trigger watch path cannot be empty".into()));
+    }
+    let watch_dir = Path::new(trimmed);
+    if !watch_dir.is_absolute() {
+        return Err(Error::Api("Trigger watch path must be absolute".into()));
+    }
+
     let conn = pool.get()?;
-    store::policies::upsert_with_notes(
+    let policy = load_run_policy(&conn, &run_id)?;
+    if let Err(incident) = governance::enforce_path_policy(&policy, watch_dir) {
+        return Err(Error::Api(incident.details));
+    }
+
+    fs::create_dir_all(watch_dir).map_err(|err| {
+        Error::Api(format!(
+            "failed to create trigger directory {}: {err}",
+            watch_dir.display()
+        ))
+    })?;
+
+    store::run_triggers::create(
         &conn,
-        &project_id,
-        &policy,
-        Some("user"), // TODO: Get actual user if authentication is added
-        change_notes.as_deref(),
+        &run_id,
+        store::run_triggers::KIND_FILE_DROP,
+        trimmed,
     )
 }
 
+/// Policy in effect for a run (the policy version it was created under)
+fn load_run_policy(conn: &Connection, run_id: &str) -> Result<Policy, Error> {
+    let (project_id, policy_version): (String, Option<i64>) = conn
+        .query_row(
+            "SELECT project_id, policy_version FROM runs WHERE id = ?1",
+            params![run_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|err| match err {
+            rusqlite::Error::QueryReturnedNoRows => Error::Api(format!("run {run_id} not found")),
+            other => Error::from(other),
+        })?;
+    store::policies::get_for_policy_version(conn, &project_id, policy_version)
+}
+
 #[tauri::command]
-pub fn get_policy_versions(
-    project_id: String,
+pub fn list_run_triggers(
+    run_id: String,
     pool: State<'_, DbPool>,
-) -> Result<Vec<store::policies::PolicyVersion>, Error> {
+) -> Result<Vec<store::run_triggers::RunTrigger>, Error> {
     let conn = pool.get()?;
-    store::policies::get_versions(&conn, &project_id)
+    store::run_triggers::list_for_run(&conn, &run_id)
 }
 
 #[tauri::command]
-pub fn get_policy_version(
-    project_id: String,
-    version: i64,
+pub fn set_run_trigger_enabled(
+    trigger_id: String,
+    enabled: bool,
     pool: State<'_, DbPool>,
-) -> Result<Option<store::policies::PolicyVersion>, Error> {
+) -> Result<store::run_triggers::RunTrigger, Error> {
     let conn = pool.get()?;
-    store::policies::get_version(&conn, &project_id, version)
+    store::run_triggers::set_enabled(&conn, &trigger_id, enabled)
 }
 
 #[tauri::command]
-pub fn get_current_policy_version_number(
-    project_id: String,
+pub fn delete_run_trigger(trigger_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
+    let mut conn = pool.get()?;
+    store::run_triggers::delete(&mut conn, &trigger_id)
+}
+
+#[tauri::command]
+pub fn list_run_trigger_events(
+    trigger_id: String,
     pool: State<'_, DbPool>,
-) -> Result<i64, Error> {
+) -> Result<Vec<store::run_triggers::RunTriggerEvent>, Error> {
     let conn = pool.get()?;
-    store::policies::get_current_version(&conn, &project_id)
+    store::run_triggers::list_events(&conn, &trigger_id)
 }
 
 #[tauri::command]
-pub fn get_project_usage_ledger(
+pub async fn poll_run_triggers(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::run_triggers::RunTriggerEvent>, Error> {
+    let pool = pool.inner().clone();
+    run_job("poll_run_triggers", move || {
+        triggers::poll_file_drop_triggers(&pool).map_err(|err| Error::Api(err.to_string()))
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn save_run_template(
+    run_id: String,
+    name: String,
+    description: Option<String>,
+    variables: Vec<String>,
+    pool: State<'_, DbPool>,
+) -> Result<store::run_templates::RunTemplate, Error> {
+    templates::save_run_template(
+        pool.inner(),
+        &run_id,
+        &name,
+        description.as_deref(),
+        &variables,
+    )
+    .map_err(|err| Error::Api(err.to_string()))
+}
+
+#[tauri::command]
+pub fn list_run_templates(
     project_id: String,
     pool: State<'_, DbPool>,
-) -> Result<ledger::ProjectLedgerSnapshot, Error> {
+) -> Result<Vec<store::run_templates::RunTemplate>, Error> {
     let conn = pool.get()?;
-    ledger::get_project_ledger_snapshot(&conn, &project_id)
+    store::run_templates::list_for_project(&conn, &project_id)
 }
 
-// --- MERGED AND FIXED emit_car FUNCTIONALITY ---
-pub(crate) fn emit_car_to_base_dir(
-    run_id: &str,
-    run_execution_id: Option<&str>,
-    pool: &DbPool,
-    base_dir: &Path,
-) -> Result<PathBuf, Error> {
+#[tauri::command]
+pub fn instantiate_run_template(
+    template_id: String,
+    name: Option<String>,
+    values: serde_json::Map<String, serde_json::Value>,
+    pool: State<'_, DbPool>,
+) -> Result<String, Error> {
+    templates::instantiate_run_template(pool.inner(), &template_id, name.as_deref(), &values)
+        .map_err(|err| Error::Api(err.to_string()))
+}
+
+#[tauri::command]
+pub fn delete_run_template(template_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
+    let conn = pool.get()?;
+    store::run_templates::delete(&conn, &template_id)
+}
+
+#[tauri::command]
+pub fn update_policy(
+    project_id: String,
+    policy: Policy,
+    pool: State<'_, DbPool>,
+) -> Result<(), Error> {
+    content_policy::validate_rules(&policy.content_rules).map_err(Error::Api)?;
+    if let Some(window) = &policy.execution_window {
+        execution_window::validate_window(window).map_err(Error::Api)?;
+    }
+    notifications::validate_thresholds(&policy.budget_alert_thresholds).map_err(Error::Api)?;
+    let conn = pool.get()?;
+    policy_approval::ensure_direct_update_allowed(&conn, &project_id)?;
+    store::policies::upsert(&conn, &project_id, &policy)
+}
+
+#[tauri::command]
+pub fn update_policy_with_notes(
+    project_id: String,
+    policy: Policy,
+    change_notes: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<(), Error> {
+    content_policy::validate_rules(&policy.content_rules).map_err(Error::Api)?;
+    if let Some(window) = &policy.execution_window {
+        execution_window::validate_window(window).map_err(Error::Api)?;
+    }
+    notifications::validate_thresholds(&policy.budget_alert_thresholds).map_err(Error::Api)?;
+    let conn = pool.get()?;
+    policy_approval::ensure_direct_update_allowed(&conn, &project_id)?;
+    store::policies::upsert_with_notes(
+        &conn,
+        &project_id,
+        &policy,
+        Some("user"), // TODO: Get actual user if authentication is added
+        change_notes.as_deref(),
+    )
+}
+
+/// Store a policy change for someone else to approve; it does not apply yet
+#[tauri::command]
+pub fn propose_policy_change(
+    project_id: String,
+    policy: Policy,
+    proposed_by: String,
+    change_notes: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<policy_approval::ProposalReview, Error> {
+    content_policy::validate_rules(&policy.content_rules).map_err(Error::Api)?;
+    if let Some(window) = &policy.execution_window {
+        execution_window::validate_window(window).map_err(Error::Api)?;
+    }
+    notifications::validate_thresholds(&policy.budget_alert_thresholds).map_err(Error::Api)?;
+    let conn = pool.get()?;
+    policy_approval::propose_policy_change(
+        &conn,
+        &project_id,
+        &policy,
+        &proposed_by,
+        change_notes.as_deref(),
+    )
+}
+
+/// Apply a pending policy proposal as the project's next policy version
+#[tauri::command]
+pub fn approve_policy_change(
+    proposal_id: String,
+    approved_by: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::policies::PolicyVersion, Error> {
+    let mut conn = pool.get()?;
+    policy_approval::approve_policy_change(&mut conn, &proposal_id, &approved_by)
+}
+
+#[tauri::command]
+pub fn reject_policy_change(
+    proposal_id: String,
+    rejected_by: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::policy_proposals::PolicyProposal, Error> {
+    let conn = pool.get()?;
+    policy_approval::reject_policy_change(&conn, &proposal_id, &rejected_by)
+}
+
+#[tauri::command]
+pub fn list_policy_proposals(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<policy_approval::ProposalReview>, Error> {
+    let conn = pool.get()?;
+    policy_approval::list_policy_proposals(&conn, &project_id)
+}
+
+#[tauri::command]
+pub fn get_policy_versions(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::policies::PolicyVersion>, Error> {
+    let conn = pool.get()?;
+    store::policies::get_versions(&conn, &project_id)
+}
+
+#[tauri::command]
+pub fn get_policy_version(
+    project_id: String,
+    version: i64,
+    pool: State<'_, DbPool>,
+) -> Result<Option<store::policies::PolicyVersion>, Error> {
+    let conn = pool.get()?;
+    store::policies::get_version(&conn, &project_id, version)
+}
+
+#[tauri::command]
+pub fn get_current_policy_version_number(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<i64, Error> {
+    let conn = pool.get()?;
+    store::policies::get_current_version(&conn, &project_id)
+}
+
+/// Past executions a draft policy would have blocked or warned on, so budgets
+/// can be tuned before the draft is saved as a new version
+#[tauri::command]
+pub fn simulate_policy_change(
+    project_id: String,
+    draft_policy: Policy,
+    pool: State<'_, DbPool>,
+) -> Result<policy_simulation::PolicySimulation, Error> {
+    let conn = pool.get()?;
+    policy_simulation::simulate_policy_change(&conn, &project_id, &draft_policy)
+}
+
+/// Recent executions, budget burn-down, incidents, top models and pending receipts
+#[tauri::command]
+pub fn get_project_dashboard(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<dashboard::ProjectDashboard, Error> {
+    let conn = pool.get()?;
+    dashboard::get_project_dashboard(&conn, &project_id)
+}
+
+/// Tokens, USD and Nature Cost per day or week and model, for usage charts
+#[tauri::command]
+pub fn get_usage_timeseries(
+    project_id: String,
+    granularity: dashboard::UsageGranularity,
+    pool: State<'_, DbPool>,
+) -> Result<dashboard::UsageTimeseries, Error> {
+    let conn = pool.get()?;
+    dashboard::get_usage_timeseries(&conn, &project_id, granularity)
+}
+
+/// Runs, checkpoints, documents and receipts in any project that carry a digest
+#[tauri::command]
+pub fn search_workspace(
+    sha256: String,
+    pool: State<'_, DbPool>,
+) -> Result<workspace_search::WorkspaceSearch, Error> {
+    let conn = pool.get()?;
+    workspace_search::search_workspace(&conn, &sha256)
+}
+
+/// Signing key changes of a project, oldest first
+#[tauri::command]
+pub fn list_signing_key_events(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::signing_key_events::SigningKeyEvent>, Error> {
+    let conn = pool.get()?;
+    store::signing_key_events::list(&conn, &project_id)
+}
+
+/// How the project's signing key is protected and whether it is unlocked
+#[tauri::command]
+pub fn get_key_protection(
+    project_id: String,
+) -> Result<key_protection::KeyProtectionStatus, Error> {
+    key_protection::status(&project_id).map_err(|err| Error::Api(err.to_string()))
+}
+
+/// Move the project's signing key to the system keychain, a passphrase-sealed
+/// file or a hardware-sealed file. The key, and so the project's public key,
+/// stays the same.
+#[tauri::command]
+pub fn set_key_protection(
+    project_id: String,
+    protection: key_protection::KeyProtectionChoice,
+    passphrase: Option<String>,
+) -> Result<key_protection::KeyProtectionStatus, Error> {
+    key_protection::set_protection(&project_id, &protection, passphrase.as_deref())
+        .and_then(|_| key_protection::status(&project_id))
+        .map_err(|err| Error::Api(format!("Failed to protect signing key: {err:#}")))
+}
+
+#[tauri::command]
+pub fn unlock_signing_key(project_id: String, passphrase: Option<String>) -> Result<(), Error> {
+    key_protection::unlock(&project_id, passphrase.as_deref())
+        .map_err(|err| Error::Api(format!("Failed to unlock signing key: {err:#}")))
+}
+
+#[tauri::command]
+pub fn lock_signing_key(project_id: String) -> Result<(), Error> {
+    key_protection::lock(&project_id);
+    Ok(())
+}
+
+/// Countersign an emitted CAR, a bundle or a `car.json` file, as a reviewer
+/// or organization. `signer_id` names the identity whose key signs; its key
+/// is generated the first time it countersigns.
+#[tauri::command]
+pub fn countersign_car(
+    car_path: String,
+    role: car::SignerRole,
+    signer_id: String,
+) -> Result<car::Cosignature, Error> {
+    let signing_key = provenance::load_or_create_cosigner_key(&signer_id)
+        .map_err(|err| Error::Api(format!("Failed to load co-signer key: {err:#}")))?;
+    car::countersign_car_file(Path::new(&car_path), role, &signing_key)
+        .map_err(|err| Error::Api(format!("Failed to countersign CAR: {err:#}")))
+}
+
+/// Verify a CAR file and save the outcome as a self-contained HTML report,
+/// for attaching to audit documentation. Returns whether the CAR verified;
+/// a failed verification is still exported.
+#[tauri::command]
+pub fn export_verification_report(car_path: String, output_path: String) -> Result<bool, Error> {
+    self_verification::export_verification_report(Path::new(&car_path), Path::new(&output_path))
+        .map_err(|err| Error::Api(format!("Failed to export verification report: {err:#}")))
+}
+
+/// Sign a file produced from a checkpoint's output, such as a report
+/// rendered from it, with the project key. The signature is written next to
+/// the file as `<file>.sig.json`, whose path is returned.
+#[tauri::command]
+pub fn sign_artifact(
+    checkpoint_id: String,
+    file_path: String,
+    pool: State<'_, DbPool>,
+) -> Result<String, Error> {
+    let conn = pool.get()?;
+    let (run_id, project_id, outputs_sha256): (String, String, Option<String>) = conn
+        .query_row(
+            "SELECT c.run_id, r.project_id, c.outputs_sha256 FROM checkpoints c
+             JOIN runs r ON r.id = c.run_id WHERE c.id = ?1",
+            params![&checkpoint_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| Error::Api(format!("checkpoint {checkpoint_id} not found")))?;
+    let outputs_sha256 = outputs_sha256
+        .ok_or_else(|| Error::Api(format!("checkpoint {checkpoint_id} has no output")))?;
+
+    let signer = provenance::ProjectSigner::load(&project_id)
+        .map_err(|err| Error::Api(format!("failed to load signing key: {err}")))?;
+    let path = Path::new(&file_path);
+    let binding = provenance::ArtifactBinding {
+        run_id,
+        checkpoint_id,
+        outputs_sha256,
+    };
+    let signature = provenance::sign_file(path, binding, &signer)
+        .map_err(|err| Error::Api(format!("Failed to sign artifact: {err:#}")))?;
+    let signature_path = provenance::artifact_signature_path(path);
+    let json = serde_json::to_string_pretty(&signature)
+        .map_err(|err| Error::Api(format!("failed to serialize signature: {err}")))?;
+    std::fs::write(&signature_path, json).map_err(|err| {
+        Error::Api(format!("failed to write {}: {err}", signature_path.display()))
+    })?;
+    Ok(signature_path.to_string_lossy().to_string())
+}
+
+/// The project's threshold signing setup, if its key is split among cosigners
+#[tauri::command]
+pub fn get_threshold_signing(
+    project_id: String,
+) -> Result<Option<threshold::ThresholdConfig>, Error> {
+    threshold::load_config(&project_id).map_err(|err| Error::Api(err.to_string()))
+}
+
+/// Split the project's signing key so that any `min_signers` of this device
+/// and the cosigners at `cosigner_urls` sign its checkpoints and CARs. The
+/// returned shares, in the order of `cosigner_urls`, must be handed to the
+/// cosigners' operators; the whole key is gone afterwards.
+#[tauri::command]
+pub fn enable_threshold_signing(
+    project_id: String,
+    min_signers: u16,
+    cosigner_urls: Vec<String>,
+) -> Result<Vec<threshold::KeyShare>, Error> {
+    threshold::enable(&project_id, min_signers, &cosigner_urls)
+        .map_err(|err| Error::Api(format!("Failed to enable threshold signing: {err:#}")))
+}
+
+#[tauri::command]
+pub fn list_prompt_partials(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::prompt_partials::PromptPartial>, Error> {
+    let conn = pool.get()?;
+    store::prompt_partials::list(&conn, &project_id)
+}
+
+/// Create or replace a partial that template prompts include as `{{> name}}`
+#[tauri::command]
+pub fn save_prompt_partial(
+    project_id: String,
+    name: String,
+    template: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::prompt_partials::PromptPartial, Error> {
+    prompt_template::validate_partial_name(&name).map_err(Error::Api)?;
+    prompt_template::validate(&template)
+        .map_err(|err| Error::Api(format!("invalid partial template: {err}")))?;
+    let partial = store::prompt_partials::PromptPartial {
+        project_id,
+        name,
+        template,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let conn = pool.get()?;
+    store::prompt_partials::upsert(&conn, &partial)?;
+    Ok(partial)
+}
+
+#[tauri::command]
+pub fn delete_prompt_partial(
+    project_id: String,
+    name: String,
+    pool: State<'_, DbPool>,
+) -> Result<(), Error> {
+    let conn = pool.get()?;
+    if !store::prompt_partials::delete(&conn, &project_id, &name)? {
+        return Err(Error::Api(format!("prompt partial '{name}' not found")));
+    }
+    Ok(())
+}
+
+/// Incident notifications and approval requests of a project, newest first
+#[tauri::command]
+pub fn list_incident_responses(
+    project_id: String,
+    pending_only: Option<bool>,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::incident_responses::IncidentResponse>, Error> {
+    let conn = pool.get()?;
+    store::incident_responses::list(&conn, &project_id, pending_only.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn acknowledge_incident_notification(
+    response_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::incident_responses::IncidentResponse, Error> {
+    let conn = pool.get()?;
+    incident_response::acknowledge(&conn, &response_id)
+}
+
+/// Approve (resume) or reject (stop) an execution paused at an incident
+#[tauri::command]
+pub fn decide_incident_approval(
+    response_id: String,
+    approve: bool,
+    pool: State<'_, DbPool>,
+) -> Result<store::incident_responses::IncidentResponse, Error> {
+    let conn = pool.get()?;
+    incident_response::decide(&conn, &response_id, approve)
+}
+
+/// Incident checkpoints across the project's runs, newest first, with their
+/// acknowledgements
+#[tauri::command]
+pub fn list_incidents(
+    project_id: String,
+    severity: Option<String>,
+    kind: Option<String>,
+    date_range: Option<incident_log::DateRange>,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<incident_log::IncidentRecord>, Error> {
+    let conn = pool.get()?;
+    incident_log::list_incidents(
+        &conn,
+        &project_id,
+        severity.as_deref(),
+        kind.as_deref(),
+        date_range.as_ref(),
+    )
+}
+
+/// Record a signed acknowledgement of an incident checkpoint
+#[tauri::command]
+pub fn acknowledge_incident(
+    checkpoint_id: String,
+    acknowledged_by: String,
+    note: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<store::incident_acknowledgements::IncidentAcknowledgement, Error> {
+    let conn = pool.get()?;
+    incident_log::acknowledge_incident(&conn, &checkpoint_id, &acknowledged_by, note.as_deref())
+}
+
+#[tauri::command]
+pub fn get_project_usage_ledger(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<ledger::ProjectLedgerSnapshot, Error> {
+    let conn = pool.get()?;
+    ledger::get_project_ledger_snapshot(&conn, &project_id)
+}
+
+/// Recompute the project's usage from its checkpoints and replays and report
+/// where the ledger drifted from it; with `repair`, overwrite drifted ledgers
+#[tauri::command]
+pub fn reconcile_ledger(
+    project_id: String,
+    repair: Option<bool>,
+    pool: State<'_, DbPool>,
+) -> Result<ledger::LedgerReconciliation, Error> {
+    let mut conn = pool.get()?;
+    ledger::reconcile_ledger(&mut conn, &project_id, repair.unwrap_or(false))
+}
+
+// --- MERGED AND FIXED emit_car FUNCTIONALITY ---
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn emit_car_to_base_dir(
+    run_id: &str,
+    selection: &car::ExecutionSelection,
+    pool: &DbPool,
+    base_dir: &Path,
+    format: car::CarFormat,
+    redaction: &car::RedactionRequest,
+    attestations: &car::Attestations,
+    encryption: &car::Encryption,
+) -> Result<PathBuf, Error> {
+    let recipients = encryption
+        .parse_recipients()
+        .map_err(|err| Error::Api(err.to_string()))?;
     let conn = pool.get()?;
     let project_id: String = conn
         .query_row(
@@ -1446,23 +2363,40 @@ pub(crate) fn emit_car_to_base_dir(
         })?;
 
     // First build the CAR to get its ID and metadata
-    let car = car::build_car(&conn, run_id, run_execution_id)
+    let car = car::build_selected_car(&conn, run_id, selection, redaction)
         .map_err(|err| Error::Api(err.to_string()))?;
+    let execution_id = receipt_execution_id(&conn, run_id, selection)?;
 
     let receipts_dir = base_dir.join(&project_id).join("receipts");
     std::fs::create_dir_all(&receipts_dir)
         .map_err(|err| Error::Api(format!("failed to create receipts dir: {err}")))?;
 
     // Create zip bundle instead of just JSON
-    let file_path = receipts_dir.join(format!("{}.car.zip", car.id.replace(':', "_")));
-    car::build_car_bundle(&conn, run_id, run_execution_id, &file_path)
-        .map_err(|err| Error::Api(format!("failed to build CAR bundle: {err}")))?;
+    let bundle_path = receipts_dir.join(format!("{}.car.zip", car.id.replace(':', "_")));
+    car::build_car_bundle(
+        &conn,
+        run_id,
+        selection,
+        &bundle_path,
+        format,
+        redaction,
+        attestations,
+    )
+    .map_err(|err| Error::Api(format!("failed to build CAR bundle: {err}")))?;
+    let file_path = if encryption.is_enabled() {
+        let encrypted_path = car::encrypted_path(&bundle_path);
+        car::encrypt_car_file(&bundle_path, &encrypted_path, &recipients)
+            .map_err(|err| Error::Api(format!("failed to encrypt CAR bundle: {err:#}")))?;
+        encrypted_path
+    } else {
+        bundle_path
+    };
 
     let created_at = car.created_at.to_rfc3339();
     let file_path_str = file_path.to_string_lossy().to_string();
 
     conn.execute(
-        "INSERT OR REPLACE INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT OR REPLACE INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade, run_execution_id, previous_car_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             &car.id,
             run_id,
@@ -1471,172 +2405,390 @@ pub(crate) fn emit_car_to_base_dir(
             &car.proof.match_kind,
             car.proof.epsilon,
             i64::from(car.sgrade.score),
+            &execution_id,
+            &car.previous_car_id,
         ],
     )?;
 
     Ok(file_path)
 }
 
+/// Execution a receipt is emitted for; `None` for a receipt covering all of them
+fn receipt_execution_id(
+    conn: &Connection,
+    run_id: &str,
+    selection: &car::ExecutionSelection,
+) -> Result<Option<String>, Error> {
+    match selection {
+        car::ExecutionSelection::Execution { run_execution_id } => {
+            Ok(Some(run_execution_id.clone()))
+        }
+        car::ExecutionSelection::Latest => orchestrator::load_latest_run_execution(conn, run_id)
+            .map(|record| record.map(|record| record.id))
+            .map_err(|err| Error::Api(err.to_string())),
+        car::ExecutionSelection::All => Ok(None),
+    }
+}
+
 #[tauri::command]
-pub fn emit_car(
+#[allow(clippy::too_many_arguments)]
+pub async fn emit_car(
     run_id: String,
     output_path: Option<String>,
+    format: Option<car::CarFormat>,
+    redaction: Option<car::RedactionRequest>,
+    execution: Option<car::ExecutionSelection>,
+    attestations: Option<car::Attestations>,
+    encryption: Option<car::Encryption>,
     pool: State<'_, DbPool>,
     app_handle: AppHandle,
 ) -> Result<String, Error> {
-    if let Some(custom_path) = output_path {
-        // User specified a custom path - save bundle there
-        let conn = pool.get()?;
-        let car =
-            car::build_car(&conn, &run_id, None).map_err(|err| Error::Api(err.to_string()))?;
-
-        let custom_path_buf = PathBuf::from(&custom_path);
-        car::build_car_bundle(&conn, &run_id, None, &custom_path_buf)
+    let pool = pool.inner().clone();
+    let format = format.unwrap_or_default();
+    let redaction = redaction.unwrap_or_default();
+    let selection = execution.unwrap_or_default();
+    let attestations = attestations.unwrap_or_default();
+    let encryption = encryption.unwrap_or_default();
+    run_job("emit_car", move || {
+        if let Some(custom_path) = output_path {
+            // User specified a custom path - save bundle there
+            let recipients = encryption
+                .parse_recipients()
+                .map_err(|err| Error::Api(err.to_string()))?;
+            let conn = pool.get()?;
+            let car = car::build_selected_car(&conn, &run_id, &selection, &redaction)
+                .map_err(|err| Error::Api(err.to_string()))?;
+
+            // An encrypted bundle is built next to its destination first
+            let custom_path_buf = PathBuf::from(&custom_path);
+            let bundle_path = if encryption.is_enabled() {
+                custom_path_buf.with_extension("plain.tmp")
+            } else {
+                custom_path_buf.clone()
+            };
+            car::build_car_bundle(
+                &conn,
+                &run_id,
+                &selection,
+                &bundle_path,
+                format,
+                &redaction,
+                &attestations,
+            )
             .map_err(|err| Error::Api(format!("failed to build CAR bundle: {err}")))?;
+            if encryption.is_enabled() {
+                car::encrypt_car_file(&bundle_path, &custom_path_buf, &recipients)
+                    .map_err(|err| Error::Api(format!("failed to encrypt CAR bundle: {err:#}")))?;
+            }
 
-        // Still record in database
-        let created_at = car.created_at.to_rfc3339();
-        conn.execute(
-            "INSERT OR REPLACE INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                &car.id,
+            // Still record in database
+            let created_at = car.created_at.to_rfc3339();
+            let execution_id = receipt_execution_id(&conn, &run_id, &selection)?;
+            conn.execute(
+                "INSERT OR REPLACE INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade, run_execution_id, previous_car_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    &car.id,
+                    &run_id,
+                    &created_at,
+                    &custom_path,
+                    &car.proof.match_kind,
+                    car.proof.epsilon,
+                    i64::from(car.sgrade.score),
+                    &execution_id,
+                    &car.previous_car_id,
+                ],
+            )?;
+
+            Ok(custom_path)
+        } else {
+            // Use default location in app data
+            let base_dir = app_handle
+                .path()
+                .app_local_data_dir()
+                .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+            let path = emit_car_to_base_dir(
                 &run_id,
-                &created_at,
-                &custom_path,
-                &car.proof.match_kind,
-                car.proof.epsilon,
-                i64::from(car.sgrade.score),
-            ],
-        )?;
+                &selection,
+                &pool,
+                &base_dir,
+                format,
+                &redaction,
+                &attestations,
+                &encryption,
+            )?;
+            Ok(path.to_string_lossy().to_string())
+        }
+    })
+    .await
+}
 
-        Ok(custom_path)
-    } else {
-        // Use default location in app data
-        let base_dir = app_handle
-            .path()
-            .app_local_data_dir()
-            .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
-        let path = emit_car_to_base_dir(&run_id, None, pool.inner(), &base_dir)?;
-        Ok(path.to_string_lossy().to_string())
-    }
+#[tauri::command]
+pub async fn export_project(
+    project_id: String,
+    output_path: Option<String>,
+    pool: State<'_, DbPool>,
+    app_handle: AppHandle,
+) -> Result<String, Error> {
+    let pool = pool.inner().clone();
+    run_job("export_project", move || {
+        if let Some(custom_path) = output_path {
+            // User specified exact output path - export directly there
+            let custom_path_buf = PathBuf::from(&custom_path);
+            let conn = pool.get()?;
+            let project = portability::load_project(&conn, &project_id)?;
+            let policy = store::policies::get(&conn, &project_id)?;
+            let policy_versions =
+                crate::portability::load_policy_versions_for_export(&conn, &project_id)?;
+            let project_usage_ledgers =
+                crate::portability::load_project_usage_ledgers_for_export(&conn, &project_id)?;
+            let replay_usage =
+                store::project_usage_ledgers::list_replays(&conn, &project_id)?;
+            let incidents =
+                incident_log::list_incidents(&conn, &project_id, None, None, None)?;
+            let (runs, attachments) = portability::load_runs_for_export(&conn, &project_id)?;
+
+            portability::write_project_archive_to_path(
+                &custom_path_buf,
+                &project,
+                &policy,
+                &policy_versions,
+                &project_usage_ledgers,
+                &replay_usage,
+                &incidents,
+                &runs,
+                &attachments,
+            )?;
+
+            Ok(custom_path)
+        } else {
+            // Use default location in app data with nested structure
+            let base_dir = app_handle
+                .path()
+                .app_local_data_dir()
+                .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+            let path = portability::export_project_archive(&pool, &project_id, &base_dir)?;
+            Ok(path.to_string_lossy().to_string())
+        }
+    })
+    .await
 }
 
+/// Write the project's signed policy version history, verifiable on its own
+/// with `intelexta-verify`
 #[tauri::command]
-pub fn export_project(
+pub async fn export_policy_history(
     project_id: String,
     output_path: Option<String>,
     pool: State<'_, DbPool>,
     app_handle: AppHandle,
 ) -> Result<String, Error> {
-    if let Some(custom_path) = output_path {
-        // User specified exact output path - export directly there
-        let custom_path_buf = PathBuf::from(&custom_path);
-        let conn = pool.get()?;
-        let project = portability::load_project(&conn, &project_id)?;
-        let policy = store::policies::get(&conn, &project_id)?;
-        let policy_versions =
-            crate::portability::load_policy_versions_for_export(&conn, &project_id)?;
-        let project_usage_ledgers =
-            crate::portability::load_project_usage_ledgers_for_export(&conn, &project_id)?;
-        let (runs, attachments) = portability::load_runs_for_export(&conn, &project_id)?;
-
-        portability::write_project_archive_to_path(
-            &custom_path_buf,
-            &project,
-            &policy,
-            &policy_versions,
-            &project_usage_ledgers,
-            &runs,
-            &attachments,
-        )?;
+    let pool = pool.inner().clone();
+    run_job("export_policy_history", move || {
+        let path = match output_path {
+            Some(custom_path) => PathBuf::from(custom_path),
+            None => {
+                let base_dir = app_handle
+                    .path()
+                    .app_local_data_dir()
+                    .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+                let exports_dir = base_dir.join(&project_id).join("exports");
+                std::fs::create_dir_all(&exports_dir).map_err(|err| {
+                    Error::Api(format!(
+                        "failed to create export dir {}: {err}",
+                        exports_dir.display()
+                    ))
+                })?;
+                let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+                exports_dir.join(format!("policy-history-{timestamp}.json"))
+            }
+        };
 
-        Ok(custom_path)
-    } else {
-        // Use default location in app data with nested structure
-        let base_dir = app_handle
-            .path()
-            .app_local_data_dir()
-            .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
-        let path = portability::export_project_archive(pool.inner(), &project_id, &base_dir)?;
+        let signing_key = provenance::load_secret_key(&project_id)
+            .map_err(|err| Error::Api(format!("failed to load signing key: {err}")))?;
+        let conn = pool.get()?;
+        let bundle = policy_history::build_bundle(&conn, &project_id, &signing_key)
+            .map_err(|err| Error::Api(err.to_string()))?;
+        let json = serde_json::to_string_pretty(&bundle)
+            .map_err(|err| Error::Api(format!("failed to serialize policy history: {err}")))?;
+        std::fs::write(&path, json)
+            .map_err(|err| Error::Api(format!("failed to write {}: {err}", path.display())))?;
         Ok(path.to_string_lossy().to_string())
-    }
+    })
+    .await
 }
 
+/// Move a completed run's payloads and attachments into a signed archive
+/// in the configured cold storage directory
 #[tauri::command]
-pub fn import_project(
-    args: ImportProjectArgs,
+pub async fn archive_run(
+    run_id: String,
     pool: State<'_, DbPool>,
     app_handle: AppHandle,
-) -> Result<portability::ProjectImportSummary, Error> {
-    let ImportProjectArgs {
-        archive_path,
-        file_name,
-        bytes,
-    } = args;
+) -> Result<store::run_archives::RunArchive, Error> {
+    let base_dir = app_handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+    let pool = pool.inner().clone();
+    run_job("archive_run", move || {
+        let archive_dir = {
+            let conn = pool.get()?;
+            cold_storage::resolve_archive_dir(&conn, &base_dir)?
+        };
+        cold_storage::archive_run(
+            &pool,
+            crate::attachments::get_global_attachment_store(),
+            &run_id,
+            &archive_dir,
+        )
+    })
+    .await
+}
 
+/// Restore an archived run's payloads and attachments for replay or export
+#[tauri::command]
+pub async fn rehydrate_run(
+    run_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<store::run_archives::RunArchive, Error> {
+    let pool = pool.inner().clone();
+    run_job("rehydrate_run", move || {
+        cold_storage::rehydrate_run(
+            &pool,
+            crate::attachments::get_global_attachment_store(),
+            &run_id,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_run_archive(
+    run_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Option<store::run_archives::RunArchive>, Error> {
+    let conn = pool.get()?;
+    store::run_archives::get(&conn, &run_id)
+}
+
+/// Directory new run archives are written to
+#[tauri::command]
+pub fn get_archive_dir(pool: State<'_, DbPool>, app_handle: AppHandle) -> Result<String, Error> {
     let base_dir = app_handle
         .path()
         .app_local_data_dir()
         .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+    let conn = pool.get()?;
+    let archive_dir = cold_storage::resolve_archive_dir(&conn, &base_dir)?;
+    Ok(archive_dir.to_string_lossy().to_string())
+}
 
-    if let Some(path) = archive_path {
-        let path = PathBuf::from(path);
-        return portability::import_project_archive(pool.inner(), &path, &base_dir);
+/// Set the archive directory; `None` restores the default under the app data dir
+#[tauri::command]
+pub fn set_archive_dir(archive_dir: Option<String>, pool: State<'_, DbPool>) -> Result<(), Error> {
+    let archive_dir = archive_dir
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty());
+    if let Some(dir) = &archive_dir {
+        if !Path::new(dir).is_absolute() {
+            return Err(Error::Api("archive directory must be an absolute path".to_string()));
+        }
     }
+    let conn = pool.get()?;
+    store::run_archives::set_archive_dir(&conn, archive_dir.as_deref())
+}
+
+#[tauri::command]
+pub async fn import_project(
+    args: ImportProjectArgs,
+    pool: State<'_, DbPool>,
+    app_handle: AppHandle,
+) -> Result<portability::ProjectImportSummary, Error> {
+    let base_dir = app_handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+    let pool = pool.inner().clone();
+    run_job("import_project", move || {
+        let ImportProjectArgs {
+            archive_path,
+            file_name,
+            bytes,
+        } = args;
+
+        if let Some(path) = archive_path {
+            let path = PathBuf::from(path);
+            return portability::import_project_archive(&pool, &path, &base_dir);
+        }
 
-    let bytes = bytes.ok_or_else(|| Error::Api("No project archive provided.".into()))?;
-    let temp_path =
-        persist_uploaded_bytes(&base_dir, "imports", file_name.as_deref(), &bytes, "ixp")?;
+        let bytes = bytes.ok_or_else(|| Error::Api("No project archive provided.".into()))?;
+        let temp_path =
+            persist_uploaded_bytes(&base_dir, "imports", file_name.as_deref(), &bytes, "ixp")?;
 
-    let result = portability::import_project_archive(pool.inner(), &temp_path, &base_dir);
-    if let Err(err) = fs::remove_file(&temp_path) {
-        eprintln!(
-            "failed to remove temporary project archive {}: {err}",
-            temp_path.display()
-        );
-    }
-    result
+        let result = portability::import_project_archive(&pool, &temp_path, &base_dir);
+        if let Err(err) = fs::remove_file(&temp_path) {
+            eprintln!(
+                "failed to remove temporary project archive {}: {err}",
+                temp_path.display()
+            );
+        }
+        result
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn import_car(
+pub async fn import_car(
     args: ImportCarArgs,
     pool: State<'_, DbPool>,
     app_handle: AppHandle,
 ) -> Result<portability::CarImportResult, Error> {
-    let ImportCarArgs {
-        car_path,
-        file_name,
-        bytes,
-    } = args;
-
     let base_dir = app_handle
         .path()
         .app_local_data_dir()
         .map_err(|err| Error::Api(format!("failed to resolve app data dir: {err}")))?;
+    let pool = pool.inner().clone();
+    run_job("import_car", move || {
+        let ImportCarArgs {
+            car_path,
+            file_name,
+            bytes,
+        } = args;
+
+        if let Some(path) = car_path {
+            let path = PathBuf::from(path);
+            let result = portability::import_car_file(&pool, &path, &base_dir);
+            record_car_import(&pool, &result);
+            return result;
+        }
 
-    if let Some(path) = car_path {
-        let path = PathBuf::from(path);
-        return portability::import_car_file(pool.inner(), &path, &base_dir);
-    }
+        let bytes = bytes.ok_or_else(|| Error::Api("No CAR data provided.".into()))?;
+        let temp_path = persist_uploaded_bytes(
+            &base_dir,
+            "imports",
+            file_name.as_deref(),
+            &bytes,
+            "car.json",
+        )?;
 
-    let bytes = bytes.ok_or_else(|| Error::Api("No CAR data provided.".into()))?;
-    let temp_path = persist_uploaded_bytes(
-        &base_dir,
-        "imports",
-        file_name.as_deref(),
-        &bytes,
-        "car.json",
-    )?;
+        let result = portability::import_car_file(&pool, &temp_path, &base_dir);
+        if let Err(err) = fs::remove_file(&temp_path) {
+            eprintln!(
+                "failed to remove temporary CAR file {}: {err}",
+                temp_path.display()
+            );
+        }
+        record_car_import(&pool, &result);
+        result
+    })
+    .await
+}
 
-    let result = portability::import_car_file(pool.inner(), &temp_path, &base_dir);
-    if let Err(err) = fs::remove_file(&temp_path) {
-        eprintln!(
-            "failed to remove temporary CAR file {}: {err}",
-            temp_path.display()
-        );
-    }
-    result
+fn record_car_import(pool: &DbPool, result: &Result<portability::CarImportResult, Error>) {
+    let passed = result
+        .as_ref()
+        .is_ok_and(|imported| imported.replay_report.match_status);
+    telemetry::record_verification_with_pool(pool, telemetry::VerificationKind::CarImport, passed);
 }
 
 fn persist_uploaded_bytes(
@@ -1715,10 +2867,94 @@ fn fallback_file_name(fallback_ext: &str) -> String {
     }
 }
 
+#[tauri::command]
+pub fn list_plugins() -> Result<plugins::PluginListing, Error> {
+    Ok(plugins::list_plugins())
+}
+
+/// Re-read the plugin directory after plugins were installed or removed
+#[tauri::command]
+pub fn reload_plugins() -> Result<plugins::PluginListing, Error> {
+    plugins::reload_global_registry().map_err(|err| Error::Api(err.to_string()))
+}
+
 // ============================================================================
 // API Key Management Commands
 // ============================================================================
 
+#[tauri::command]
+pub fn get_telemetry_settings(
+    pool: State<'_, DbPool>,
+) -> Result<store::telemetry::TelemetrySettings, Error> {
+    let conn = pool.get()?;
+    store::telemetry::get_settings(&conn)
+}
+
+/// Opt in to or out of telemetry. Opting out deletes the collected counts.
+#[tauri::command]
+pub fn set_telemetry_enabled(
+    enabled: bool,
+    pool: State<'_, DbPool>,
+) -> Result<store::telemetry::TelemetrySettings, Error> {
+    let conn = pool.get()?;
+    store::telemetry::set_enabled(&conn, enabled)
+}
+
+/// The report exactly as it would be exported, for review in the UI
+#[tauri::command]
+pub fn preview_telemetry_report(
+    pool: State<'_, DbPool>,
+) -> Result<telemetry::TelemetryReport, Error> {
+    let conn = pool.get()?;
+    telemetry::build_report(&conn).map_err(|err| Error::Api(err.to_string()))
+}
+
+#[tauri::command]
+pub fn export_telemetry_report(
+    output_path: String,
+    pool: State<'_, DbPool>,
+) -> Result<telemetry::TelemetryReport, Error> {
+    let conn = pool.get()?;
+    telemetry::export_report(&conn, Path::new(&output_path))
+        .map_err(|err| Error::Api(err.to_string()))
+}
+
+#[tauri::command]
+pub fn reset_telemetry(pool: State<'_, DbPool>) -> Result<(), Error> {
+    let conn = pool.get()?;
+    store::telemetry::reset_counters(&conn)
+}
+
+#[tauri::command]
+pub fn get_notification_settings(
+    pool: State<'_, DbPool>,
+) -> Result<store::notifications::NotificationSettings, Error> {
+    let conn = pool.get()?;
+    store::notifications::get_settings(&conn)
+}
+
+/// Set where budget alerts are delivered besides the app. The SMTP password
+/// goes to the keychain; an empty one removes it, none keeps the stored one.
+#[tauri::command]
+pub fn set_notification_settings(
+    settings: store::notifications::NotificationSettings,
+    smtp_password: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<store::notifications::NotificationSettings, Error> {
+    let conn = pool.get()?;
+    notifications::save_settings(&conn, &settings, smtp_password.as_deref())
+}
+
+#[tauri::command]
+pub fn list_budget_alerts(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::notifications::BudgetAlert>, Error> {
+    let conn = pool.get()?;
+    dashboard::ensure_project(&conn, &project_id)?;
+    store::notifications::list(&conn, &project_id)
+}
+
 #[tauri::command]
 pub fn list_api_keys_status() -> Result<Vec<api_keys::ApiKeyStatus>, Error> {
     Ok(api_keys::get_all_api_key_status())
@@ -1904,3 +3140,134 @@ pub fn list_all_available_models() -> Result<Vec<CatalogModel>, Error> {
 
     Ok(models)
 }
+
+// ============================================================================
+// Model Weights Commands
+// ============================================================================
+
+/// Register the weights file of a local fine-tuned model. Every later
+/// checkpoint on the model carries a `model_weights` claim with its hash.
+#[tauri::command]
+pub fn register_model_weights(
+    model_id: String,
+    weights_path: String,
+    training_car_id: Option<String>,
+    pool: State<'_, DbPool>,
+) -> Result<store::model_weights::ModelWeights, Error> {
+    let model_id = model_id.trim();
+    if model_id.is_empty() {
+        return Err(Error::Api("model id is required".to_string()));
+    }
+    let training_car_id = training_car_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+    if let Some(id) = &training_car_id {
+        if !id.starts_with("car:") {
+            return Err(Error::Api(format!(
+                "training run reference must be a CAR id (car:...), got {id}"
+            )));
+        }
+    }
+
+    let weights_sha256 = provenance::sha256_file(Path::new(&weights_path)).map_err(|err| {
+        Error::Api(format!("failed to hash weights file {weights_path}: {err}"))
+    })?;
+    let conn = pool.get()?;
+    store::model_weights::register(
+        &conn,
+        model_id,
+        &weights_sha256,
+        Some(&weights_path),
+        training_car_id.as_deref(),
+    )
+}
+
+#[tauri::command]
+pub fn list_model_weights(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::model_weights::ModelWeights>, Error> {
+    let conn = pool.get()?;
+    store::model_weights::list_current(&conn)
+}
+
+#[tauri::command]
+pub fn unregister_model_weights(model_id: String, pool: State<'_, DbPool>) -> Result<(), Error> {
+    let conn = pool.get()?;
+    store::model_weights::unregister(&conn, &model_id)
+}
+
+// ============================================================================
+// Dataset Split Commands
+// ============================================================================
+
+/// Assign documents to a train/validation/test split. Documents are named by
+/// sha256 hashes, by file paths (hashed here), or both. Steps that send a
+/// validation or test document to a model are stopped with a
+/// `dataset_leakage` incident.
+#[tauri::command]
+pub fn set_dataset_split(
+    project_id: String,
+    dataset: String,
+    split: String,
+    document_hashes: Vec<String>,
+    document_paths: Option<Vec<String>>,
+    pool: State<'_, DbPool>,
+) -> Result<store::dataset_splits::DatasetSplit, Error> {
+    let dataset = dataset.trim();
+    if dataset.is_empty() {
+        return Err(Error::Api("dataset name is required".to_string()));
+    }
+    let split = store::dataset_splits::SplitKind::parse(split.trim()).ok_or_else(|| {
+        Error::Api(format!(
+            "unknown split {split}; expected train, validation or test"
+        ))
+    })?;
+
+    let mut hashes = document_hashes;
+    for path in document_paths.unwrap_or_default() {
+        let hash = provenance::sha256_file(Path::new(&path))
+            .map_err(|err| Error::Api(format!("failed to hash document {path}: {err}")))?;
+        hashes.push(hash);
+    }
+    if let Some(invalid) = hashes.iter().find(|hash| {
+        let hash = hash.trim();
+        hash.len() != 64 || !hash.chars().all(|ch| ch.is_ascii_hexdigit())
+    }) {
+        return Err(Error::Api(format!(
+            "document hash {invalid} is not a hex sha256 digest"
+        )));
+    }
+
+    let mut conn = pool.get()?;
+    store::dataset_splits::set_split(&mut conn, &project_id, dataset, split, &hashes)
+}
+
+#[tauri::command]
+pub fn list_dataset_splits(
+    project_id: String,
+    pool: State<'_, DbPool>,
+) -> Result<Vec<store::dataset_splits::DatasetSplit>, Error> {
+    let conn = pool.get()?;
+    store::dataset_splits::list_for_project(&conn, &project_id)
+}
+
+#[tauri::command]
+pub fn delete_dataset(
+    project_id: String,
+    dataset: String,
+    pool: State<'_, DbPool>,
+) -> Result<(), Error> {
+    let conn = pool.get()?;
+    store::dataset_splits::delete_dataset(&conn, &project_id, &dataset)
+}
+
+// ============================================================================
+// Response Cache Commands
+// ============================================================================
+
+/// Drop every cached model generation; later cache-enabled runs call providers again
+#[tauri::command]
+pub fn clear_response_cache(pool: State<'_, DbPool>) -> Result<usize, Error> {
+    let conn = pool.get()?;
+    store::response_cache::clear(&conn)
+}
@@ -110,6 +110,50 @@ impl AttachmentStore {
         Ok(())
     }
 
+    /// Save the output a step handed to later steps, keyed by the
+    /// `outputs_sha256` its checkpoint records
+    pub fn save_step_output(&self, outputs_sha256: &str, content: &str) -> Result<()> {
+        let file_path = self.step_output_path(outputs_sha256)?;
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        if !file_path.exists() {
+            fs::write(&file_path, content).with_context(|| {
+                format!("Failed to write step output to {:?}", file_path)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Load a step output by the `outputs_sha256` of its checkpoint
+    pub fn load_step_output(&self, outputs_sha256: &str) -> Result<Option<String>> {
+        let file_path = self.step_output_path(outputs_sha256)?;
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        fs::read_to_string(&file_path)
+            .map(Some)
+            .with_context(|| format!("Failed to read step output from {:?}", file_path))
+    }
+
+    /// Step outputs live under `steps/`, apart from content-addressed outputs
+    fn step_output_path(&self, outputs_sha256: &str) -> Result<PathBuf> {
+        if outputs_sha256.len() != 64 || !outputs_sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("Invalid outputs hash: {}", outputs_sha256));
+        }
+        Ok(self
+            .base_path
+            .join("steps")
+            .join(&outputs_sha256[0..2])
+            .join(format!("{}.txt", outputs_sha256)))
+    }
+
     /// Check if an attachment exists for the given hash
     pub fn exists(&self, hash: &str) -> bool {
         self.hash_to_path(hash).exists()
@@ -179,6 +223,18 @@ impl AttachmentStore {
         Ok(())
     }
 
+    /// Delete the step output stored for a checkpoint's `outputs_sha256`
+    pub fn delete_step_output(&self, outputs_sha256: &str) -> Result<()> {
+        let file_path = self.step_output_path(outputs_sha256)?;
+
+        if file_path.exists() {
+            fs::remove_file(&file_path)
+                .with_context(|| format!("Failed to delete step output {:?}", file_path))?;
+        }
+
+        Ok(())
+    }
+
     /// Get the base path of the attachment store
     pub fn base_path(&self) -> &Path {
         &self.base_path
@@ -281,6 +337,23 @@ mod tests {
         assert!(!store.exists("nonexistent_hash"));
     }
 
+    #[test]
+    fn test_step_outputs_keyed_by_outputs_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = AttachmentStore::new(temp_dir.path().to_path_buf()).unwrap();
+
+        // The key is the checkpoint's outputs hash, not the hash of the stored text
+        let outputs_sha256 = "ab".repeat(32);
+        store.save_step_output(&outputs_sha256, "{\"summary\": \"ok\"}").unwrap();
+
+        assert_eq!(
+            store.load_step_output(&outputs_sha256).unwrap().as_deref(),
+            Some("{\"summary\": \"ok\"}")
+        );
+        assert_eq!(store.load_step_output(&"cd".repeat(32)).unwrap(), None);
+        assert!(store.save_step_output("../escape", "x").is_err());
+    }
+
     #[test]
     fn test_delete() {
         let temp_dir = TempDir::new().unwrap();
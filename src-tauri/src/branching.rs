@@ -0,0 +1,304 @@
+// src-tauri/src/branching.rs
+//!
+//! Conditional branching for typed step pipelines
+//!
+//! A `branch` step evaluates a predicate against a prior step's output and
+//! selects which group of subsequent steps executes. Predicates:
+//!
+//! - `regex`: outcome is `"true"` when the pattern matches the output
+//! - `jsonField`: outcome is `"true"` when the field at a JSON pointer compares
+//!   successfully against a value
+//! - `llmClassification`: a model picks one of a fixed set of labels; the
+//!   outcome is the chosen label
+//!
+//! The resulting `BranchDecision` is stored as the checkpoint output so replay
+//! can re-derive the outcome from the recorded source output and verify the
+//! branch that was taken.
+
+use crate::provenance;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum BranchPredicate {
+    #[serde(rename = "regex")]
+    Regex { pattern: String },
+
+    #[serde(rename = "jsonField", rename_all = "camelCase")]
+    JsonField {
+        /// RFC 6901 JSON pointer into the source output, e.g. `/verdict/score`
+        pointer: String,
+        op: ComparisonOp,
+        #[serde(default)]
+        value: Value,
+    },
+
+    #[serde(rename = "llmClassification", rename_all = "camelCase")]
+    LlmClassification {
+        model: String,
+        labels: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        instructions: Option<String>,
+    },
+}
+
+impl BranchPredicate {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BranchPredicate::Regex { .. } => "regex",
+            BranchPredicate::JsonField { .. } => "jsonField",
+            BranchPredicate::LlmClassification { .. } => "llmClassification",
+        }
+    }
+
+    /// Whether the outcome can be re-derived from the source output alone
+    pub fn is_deterministic(&self) -> bool {
+        !matches!(self, BranchPredicate::LlmClassification { .. })
+    }
+
+    /// Reject predicates that can never evaluate before they are stored on a step
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            BranchPredicate::Regex { pattern } => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|err| format!("invalid pattern '{}': {}", pattern, err)),
+            BranchPredicate::JsonField { pointer, .. } => {
+                if pointer.is_empty() || pointer.starts_with('/') {
+                    Ok(())
+                } else {
+                    Err(format!("JSON pointer '{}' must start with '/'", pointer))
+                }
+            }
+            BranchPredicate::LlmClassification { labels, .. } => {
+                if labels.is_empty() {
+                    Err("classification requires at least one label".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ComparisonOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    Exists,
+}
+
+/// Decision recorded as the output of a branch checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDecision {
+    pub predicate: BranchPredicate,
+    pub source_step: usize,
+    pub source_outputs_sha256: String,
+    pub outcome: String,
+    pub selected_steps: Vec<usize>,
+    pub skipped_steps: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classifier_response: Option<String>,
+}
+
+impl BranchDecision {
+    /// Digest committed to by the checkpoint's outputs_sha256
+    pub fn digest(&self) -> String {
+        provenance::sha256_hex(&provenance::canonical_json(self))
+    }
+}
+
+pub fn evaluate_regex(pattern: &str, text: &str) -> Result<bool, String> {
+    Regex::new(pattern)
+        .map(|re| re.is_match(text))
+        .map_err(|err| format!("invalid pattern '{}': {}", pattern, err))
+}
+
+pub fn evaluate_json_field(
+    pointer: &str,
+    op: ComparisonOp,
+    expected: &Value,
+    text: &str,
+) -> Result<bool, String> {
+    let document = crate::output_schema::parse_structured_output(text)?;
+    let actual = document.pointer(pointer);
+
+    let Some(actual) = actual else {
+        return match op {
+            ComparisonOp::Exists => Ok(false),
+            ComparisonOp::Ne => Ok(true),
+            _ => Err(format!("field '{}' not found in source output", pointer)),
+        };
+    };
+
+    Ok(match op {
+        ComparisonOp::Exists => true,
+        ComparisonOp::Eq => values_equal(actual, expected),
+        ComparisonOp::Ne => !values_equal(actual, expected),
+        ComparisonOp::Contains => match actual {
+            Value::String(haystack) => expected.as_str().is_some_and(|needle| haystack.contains(needle)),
+            Value::Array(items) => items.iter().any(|item| values_equal(item, expected)),
+            Value::Object(map) => expected.as_str().is_some_and(|key| map.contains_key(key)),
+            _ => false,
+        },
+        ComparisonOp::Gt | ComparisonOp::Gte | ComparisonOp::Lt | ComparisonOp::Lte => {
+            let (Some(lhs), Some(rhs)) = (actual.as_f64(), expected.as_f64()) else {
+                return Err(format!(
+                    "field '{}' cannot be compared numerically with {}",
+                    pointer, expected
+                ));
+            };
+            match op {
+                ComparisonOp::Gt => lhs > rhs,
+                ComparisonOp::Gte => lhs >= rhs,
+                ComparisonOp::Lt => lhs < rhs,
+                _ => lhs <= rhs,
+            }
+        }
+    })
+}
+
+fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual.as_f64(), expected.as_f64()) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => actual == expected,
+    }
+}
+
+pub fn build_classification_prompt(
+    labels: &[String],
+    instructions: Option<&str>,
+    source_text: &str,
+) -> String {
+    let mut prompt = String::new();
+    if let Some(instructions) = instructions {
+        prompt.push_str(instructions.trim());
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("Classify the following content into exactly one of these labels: ");
+    prompt.push_str(&labels.join(", "));
+    prompt.push_str(".\nRespond with the label only.\n\n---\n\n");
+    prompt.push_str(source_text);
+    prompt
+}
+
+/// Map a classifier response onto one of the allowed labels.
+/// An exact (case-insensitive) answer wins; otherwise the first label that
+/// appears in the response is used.
+pub fn match_label(labels: &[String], response: &str) -> Option<String> {
+    let answer = response.trim().trim_matches(|c: char| c == '"' || c == '.' || c == '`');
+    if let Some(label) = labels.iter().find(|label| label.eq_ignore_ascii_case(answer)) {
+        return Some(label.clone());
+    }
+
+    let lowered = response.to_lowercase();
+    labels
+        .iter()
+        .filter_map(|label| lowered.find(&label.to_lowercase()).map(|pos| (pos, label)))
+        .min_by_key(|(pos, _)| *pos)
+        .map(|(_, label)| label.clone())
+}
+
+/// Outcome of a deterministic predicate, as stored in the decision
+pub fn evaluate_deterministic(predicate: &BranchPredicate, text: &str) -> Result<String, String> {
+    match predicate {
+        BranchPredicate::Regex { pattern } => evaluate_regex(pattern, text).map(|hit| hit.to_string()),
+        BranchPredicate::JsonField { pointer, op, value } => {
+            evaluate_json_field(pointer, *op, value, text).map(|hit| hit.to_string())
+        }
+        BranchPredicate::LlmClassification { .. } => {
+            Err("classification outcomes require a model call".to_string())
+        }
+    }
+}
+
+/// Split the branch's step groups into the selected group and every step
+/// that belongs only to other groups.
+pub fn resolve_groups(
+    branches: &BTreeMap<String, Vec<usize>>,
+    outcome: &str,
+) -> (Vec<usize>, Vec<usize>) {
+    let selected: Vec<usize> = branches.get(outcome).cloned().unwrap_or_default();
+    let mut skipped: Vec<usize> = branches
+        .iter()
+        .filter(|(label, _)| label.as_str() != outcome)
+        .flat_map(|(_, steps)| steps.iter().copied())
+        .filter(|step| !selected.contains(step))
+        .collect();
+    skipped.sort_unstable();
+    skipped.dedup();
+    (selected, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn predicates_deserialize_from_step_config_json() {
+        let predicate: BranchPredicate = serde_json::from_value(json!({
+            "kind": "jsonField",
+            "pointer": "/score",
+            "op": "gte",
+            "value": 0.8
+        }))
+        .unwrap();
+        assert_eq!(predicate.kind(), "jsonField");
+        assert!(predicate.is_deterministic());
+        assert!(predicate.check().is_ok());
+    }
+
+    #[test]
+    fn json_field_comparisons() {
+        let output = "```json\n{\"score\": 0.9, \"tags\": [\"a\"], \"verdict\": \"accept\"}\n```";
+        assert!(evaluate_json_field("/score", ComparisonOp::Gte, &json!(0.8), output).unwrap());
+        assert!(!evaluate_json_field("/verdict", ComparisonOp::Eq, &json!("reject"), output).unwrap());
+        assert!(evaluate_json_field("/tags", ComparisonOp::Contains, &json!("a"), output).unwrap());
+        assert!(!evaluate_json_field("/missing", ComparisonOp::Exists, &Value::Null, output).unwrap());
+        assert!(evaluate_json_field("/missing", ComparisonOp::Eq, &json!(1), output).is_err());
+    }
+
+    #[test]
+    fn regex_outcome_is_boolean_string() {
+        let predicate = BranchPredicate::Regex {
+            pattern: "(?i)approved".to_string(),
+        };
+        assert_eq!(evaluate_deterministic(&predicate, "Status: APPROVED").unwrap(), "true");
+        assert_eq!(evaluate_deterministic(&predicate, "pending").unwrap(), "false");
+    }
+
+    #[test]
+    fn label_matching_prefers_exact_answers() {
+        let labels = vec!["positive".to_string(), "negative".to_string()];
+        assert_eq!(match_label(&labels, " Negative.\n"), Some("negative".to_string()));
+        assert_eq!(
+            match_label(&labels, "I would say positive, not negative"),
+            Some("positive".to_string())
+        );
+        assert_eq!(match_label(&labels, "unsure"), None);
+    }
+
+    #[test]
+    fn resolve_groups_skips_other_branches() {
+        let mut branches = BTreeMap::new();
+        branches.insert("true".to_string(), vec![2, 3]);
+        branches.insert("false".to_string(), vec![4, 3]);
+        let (selected, skipped) = resolve_groups(&branches, "true");
+        assert_eq!(selected, vec![2, 3]);
+        assert_eq!(skipped, vec![4]);
+
+        let (selected, skipped) = resolve_groups(&branches, "other");
+        assert!(selected.is_empty());
+        assert_eq!(skipped, vec![2, 3, 4]);
+    }
+}
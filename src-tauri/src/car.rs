@@ -4,13 +4,21 @@
 //! that serve as the ultimate proof of a run's integrity. It also calculates
 //! the S-Grade, a score reflecting the run's adherence to best practices.
 
+#[cfg(feature = "app")]
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
+#[cfg(feature = "app")]
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{orchestrator, provenance, store};
+use crate::response_metadata::ResponseMetadata;
+use crate::provider_policy::ProviderAllowlist;
+use crate::run_environment::RunEnvironment;
+use crate::run_spec::RunStep;
+use crate::{merkle, output_schema, provenance};
+#[cfg(feature = "app")]
+use crate::{orchestrator, store};
 // TODO: You will need a robust canonical JSON crate. `serde_json_canon` is a good choice.
 // use serde_json_canon;
 
@@ -18,18 +26,267 @@ use crate::{orchestrator, provenance, store};
 // These structs define the precise layout of the .car.json file, updated to support
 // multiple replay modes (Exact, Concordant, Interactive).
 
+/// Format version of CARs written before `format_version` existed
+pub const LEGACY_FORMAT_VERSION: u32 = 1;
+
+/// Format version this build writes
+pub const CAR_FORMAT_VERSION: u32 = 3;
+
+/// What a CAR of one format version must carry to verify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub version: u32,
+    /// `ed25519-body:` over the canonical body without `signatures`
+    pub body_signature_required: bool,
+    /// `ed25519-checkpoint:` over the CAR ID
+    pub checkpoint_signature_required: bool,
+}
+
+/// Format versions verifiers of this build accept
+pub const FORMAT_VERSIONS: &[FormatSpec] = &[
+    // Signed with whichever signatures the exporting build produced
+    FormatSpec {
+        version: LEGACY_FORMAT_VERSION,
+        body_signature_required: false,
+        checkpoint_signature_required: false,
+    },
+    FormatSpec {
+        version: 2,
+        body_signature_required: true,
+        checkpoint_signature_required: true,
+    },
+    // Bundle attachments may be compressed as the attachment manifest records
+    FormatSpec {
+        version: 3,
+        body_signature_required: true,
+        checkpoint_signature_required: true,
+    },
+];
+
+/// A CAR written in a format version this verifier does not know
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFormatVersion {
+    pub found: u32,
+    pub supported: Vec<u32>,
+}
+
+impl std::fmt::Display for UnsupportedFormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let supported: Vec<String> = self.supported.iter().map(u32::to_string).collect();
+        write!(
+            f,
+            "CAR format version {} is not supported (this verifier supports {})",
+            self.found,
+            supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedFormatVersion {}
+
+/// The spec of `version`, or which versions are supported instead
+pub fn negotiate_format(
+    version: u32,
+) -> std::result::Result<FormatSpec, UnsupportedFormatVersion> {
+    FORMAT_VERSIONS
+        .iter()
+        .find(|spec| spec.version == version)
+        .copied()
+        .ok_or_else(|| UnsupportedFormatVersion {
+            found: version,
+            supported: FORMAT_VERSIONS.iter().map(|spec| spec.version).collect(),
+        })
+}
+
+/// What a CAR signature covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    /// `ed25519-body:`, the canonical body without `signatures`
+    Body,
+    /// `ed25519-checkpoint:`, or bare `ed25519:` in legacy CARs: the CAR ID
+    Checkpoint,
+}
+
+/// Kind and base64 signature of a `signatures` entry; `None` for schemes
+/// this build does not know
+pub fn parse_signature(entry: &str) -> Option<(SignatureKind, &str)> {
+    match entry.split_once(':')? {
+        ("ed25519-body", encoded) => Some((SignatureKind::Body, encoded)),
+        ("ed25519-checkpoint" | "ed25519", encoded) => Some((SignatureKind::Checkpoint, encoded)),
+        _ => None,
+    }
+}
+
+impl FormatSpec {
+    /// `Err` names the first signature the format requires that `signatures` lacks
+    pub fn check_signatures(&self, signatures: &[String]) -> std::result::Result<(), String> {
+        let has = |kind| {
+            signatures
+                .iter()
+                .any(|entry| parse_signature(entry).is_some_and(|(found, _)| found == kind))
+        };
+        if self.body_signature_required && !has(SignatureKind::Body) {
+            return Err(format!(
+                "format version {} CARs must carry an ed25519-body signature",
+                self.version
+            ));
+        }
+        if self.checkpoint_signature_required && !has(SignatureKind::Checkpoint) {
+            return Err(format!(
+                "format version {} CARs must carry an ed25519-checkpoint signature",
+                self.version
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Prefix of the `signatures` entries reviewers add to an emitted CAR:
+/// `ed25519-cosign:<role>:<base64 public key>:<base64 signature>`. Like
+/// timestamp tokens they sit outside the CAR id and the body signature, so
+/// adding one leaves the author's signatures intact.
+pub const COSIGNATURE_PREFIX: &str = "ed25519-cosign:";
+
+/// Who a CAR signature speaks for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRole {
+    /// The project key that emitted the CAR, `signer_public_key`
+    Author,
+    /// Someone who reviewed the run after emission
+    Reviewer,
+    /// A key vouching for the CAR on behalf of an organization
+    Organization,
+}
+
+impl SignerRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignerRole::Author => "author",
+            SignerRole::Reviewer => "reviewer",
+            SignerRole::Organization => "organization",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "author" => Some(SignerRole::Author),
+            "reviewer" => Some(SignerRole::Reviewer),
+            "organization" => Some(SignerRole::Organization),
+            _ => None,
+        }
+    }
+}
+
+/// A countersignature of an emitted CAR
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Cosignature {
+    pub role: SignerRole,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl Cosignature {
+    /// `None` for entries that are not co-signatures, `Some(Err)` for
+    /// malformed ones
+    pub fn parse(entry: &str) -> Option<std::result::Result<Self, String>> {
+        let fields = entry.strip_prefix(COSIGNATURE_PREFIX)?;
+        let mut parts = fields.splitn(3, ':');
+        let (Some(role), Some(public_key), Some(signature)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Some(Err(format!("malformed co-signature '{entry}'")));
+        };
+        let Some(role) = SignerRole::parse(role).filter(|role| *role != SignerRole::Author) else {
+            return Some(Err(format!("co-signature has unknown role '{role}'")));
+        };
+        Some(Ok(Self {
+            role,
+            public_key: public_key.to_string(),
+            signature: signature.to_string(),
+        }))
+    }
+
+    pub fn entry(&self) -> String {
+        format!(
+            "{COSIGNATURE_PREFIX}{}:{}:{}",
+            self.role.as_str(),
+            self.public_key,
+            self.signature
+        )
+    }
+
+    /// What the co-signer signs: the CAR id, which commits to the body, bound
+    /// to their role so a reviewer signature cannot pass as an organization's
+    pub fn message(car_id: &str, role: SignerRole) -> Vec<u8> {
+        format!("{COSIGNATURE_PREFIX}{}:{car_id}", role.as_str()).into_bytes()
+    }
+}
+
+/// Countersign an emitted CAR as `role`. The author signs at emission, so
+/// `SignerRole::Author` is refused, as is a second signature by the same key
+/// in the same role.
+pub fn cosign(
+    car: &mut Car,
+    role: SignerRole,
+    signing_key: &ed25519_dalek::SigningKey,
+) -> std::result::Result<Cosignature, String> {
+    if role == SignerRole::Author {
+        return Err("the author signs a CAR when it is emitted".to_string());
+    }
+    let public_key = provenance::public_key_from_secret(signing_key);
+    let already = car.signatures.iter().any(|entry| {
+        matches!(Cosignature::parse(entry), Some(Ok(existing))
+            if existing.role == role && existing.public_key == public_key)
+    });
+    if already {
+        return Err(format!("{public_key} already co-signed as {}", role.as_str()));
+    }
+    let cosignature = Cosignature {
+        role,
+        signature: provenance::sign_bytes(signing_key, &Cosignature::message(&car.id, role)),
+        public_key,
+    };
+    car.signatures.push(cosignature.entry());
+    Ok(cosignature)
+}
+
+fn legacy_format_version() -> u32 {
+    LEGACY_FORMAT_VERSION
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Car {
     pub id: String, // "car:..." - sha256 of the canonical body
+    /// See `FORMAT_VERSIONS`; absent in CARs that predate versioning
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
     pub run_id: String,
     pub created_at: DateTime<Utc>,
+    /// ID of the receipt the project emitted before this one, chaining the
+    /// project's CARs into a ledger; absent in a project's first CAR and in
+    /// CARs that predate the chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_car_id: Option<String>,
     pub run: RunInfo, // Formerly 'runtime'
     pub proof: Proof,
     pub policy_ref: PolicyRef,
     pub budgets: Budgets,
     pub provenance: Vec<ProvenanceClaim>,
     pub checkpoints: Vec<String>, // List of checkpoint IDs
+    /// Attachments of the CAR's bundle, bound to the body by their Merkle root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<AttachmentManifest>,
+    /// Files bound by hash but left out of the bundle, such as ingested corpora
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_attachments: Vec<ExternalAttachment>,
     pub sgrade: SGrade,
+    /// OS, app and model catalog versions and machine fingerprint of the execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_environment: Option<RunEnvironment>,
+    /// Fields and attachments withheld behind salted commitments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
     pub signer_public_key: String,
     pub signatures: Vec<String>, // e.g., ["ed25519:..."]
 }
@@ -41,7 +298,7 @@ pub struct RunInfo {
     pub model: String,
     pub version: String,
     pub seed: u64,
-    pub steps: Vec<orchestrator::RunStep>,
+    pub steps: Vec<RunStep>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sampler: Option<Sampler>, // Details for stochastic runs
 }
@@ -66,6 +323,10 @@ pub struct Proof {
     pub replay_semantic_digest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub process: Option<ProcessProof>,
+    /// One section per execution when the CAR packages every execution of
+    /// the run; `process` then repeats the latest one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executions: Vec<ExecutionProof>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -73,6 +334,17 @@ pub struct ProcessProof {
     pub sequential_checkpoints: Vec<ProcessCheckpointProof>,
 }
 
+/// Proof section of one execution in a multi-execution CAR
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionProof {
+    pub run_execution_id: String,
+    pub created_at: String,
+    pub budgets: Budgets,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_environment: Option<RunEnvironment>,
+    pub process: ProcessProof,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProcessCheckpointProof {
     pub id: String,
@@ -94,6 +366,41 @@ pub struct ProcessCheckpointProof {
     pub usage_tokens: u64,
     pub prompt_tokens: u64,
     pub completion_tokens: u64,
+    /// Generation served from the response cache; part of the hashed body when true
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+    /// Provider, finish reason, tool calls and request ID of a model response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_metadata: Option<ResponseMetadata>,
+    /// Canonical hash of the run inputs, carried by the first checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_inputs_sha256: Option<String>,
+    /// Hash of the JSON Schema the step's output was validated against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema_sha256: Option<String>,
+    /// Cost priced from the model catalog when the checkpoint was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nature_cost: Option<f64>,
+    /// SimHash of the output text that concordant replay compares against;
+    /// not part of the hashed body, and withheld with a redacted output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_digest: Option<String>,
+    /// The human or AI message of an interactive turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<TranscriptMessage>,
+}
+
+/// A chat message; an AI reply hashes to its checkpoint's `outputs_sha256`
+/// and a human message, since transcripts were exported, to `inputs_sha256`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TranscriptMessage {
+    /// `human` or `ai`
+    pub role: String,
+    /// Absent when the checkpoint's output is redacted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -105,6 +412,27 @@ pub struct PolicyRef {
     pub model_catalog_hash: String, // SHA256 hash of the model catalog for pricing verification
     #[serde(default = "default_catalog_version")]
     pub model_catalog_version: String, // Version of the model catalog used
+    /// The policy's budgets, which verifiers hold the recorded `budgets` to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<Budgets>,
+    /// The default and project policies `hash` was resolved from; absent
+    /// when no default policy is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layers: Option<PolicyLayers>,
+    /// Providers and regions the policy confined steps' models to; absent
+    /// when it did not restrict them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_allowlist: Option<ProviderAllowlist>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PolicyLayers {
+    /// Hash of the app-level default policy
+    pub default_hash: String,
+    /// Hash of the project's own policy; absent when the project inherits
+    /// the default as it is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_hash: Option<String>,
 }
 
 fn default_catalog_hash() -> String {
@@ -122,10 +450,209 @@ pub struct Budgets {
     pub nature_cost: f64,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttachmentManifest {
+    /// Content hashes of the attachments, sorted
+    pub hashes: Vec<String>,
+    /// `sha256:` Merkle root over `hashes`, see `merkle`
+    pub merkle_root: String,
+    /// Encoding of the bundle's attachment entries; hashes always cover the
+    /// decompressed content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<AttachmentCompression>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentCompression {
+    /// One zstd frame per attachment, in `attachments/<hash>.txt.zst`
+    Zstd,
+}
+
+impl AttachmentCompression {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Zstd => ".zst",
+        }
+    }
+
+    pub fn compress(self, content: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Zstd => ruzstd::encoding::compress_to_vec(
+                content,
+                ruzstd::encoding::CompressionLevel::Fastest,
+            ),
+        }
+    }
+
+    /// `Err` for corrupt input or content larger than `max_bytes`
+    pub fn decompress(self, bytes: &[u8], max_bytes: u64) -> std::result::Result<Vec<u8>, String> {
+        use std::io::Read;
+        let decoder = self.decoder(bytes)?;
+        let mut content = Vec::new();
+        decoder
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(&mut content)
+            .map_err(|err| format!("invalid zstd attachment: {err}"))?;
+        if content.len() as u64 > max_bytes {
+            return Err(format!("attachment decompresses to more than {max_bytes} bytes"));
+        }
+        Ok(content)
+    }
+
+    /// Decompress `compressed` as it is read, for attachments too large to
+    /// hold in memory; `Err` when the frame header is corrupt
+    pub fn decoder<R: std::io::Read>(
+        self,
+        compressed: R,
+    ) -> std::result::Result<impl std::io::Read, String> {
+        match self {
+            Self::Zstd => ruzstd::decoding::StreamingDecoder::new(compressed)
+                .map_err(|err| format!("invalid zstd attachment: {err}")),
+        }
+    }
+}
+
+/// Hash and encoding of a bundle entry under `attachments/`; `None` for
+/// other entries
+pub fn parse_attachment_entry(name: &str) -> Option<(&str, Option<AttachmentCompression>)> {
+    let file = name.strip_prefix("attachments/")?;
+    if let Some(hash) = file.strip_suffix(".txt.zst") {
+        return Some((hash, Some(AttachmentCompression::Zstd)));
+    }
+    file.strip_suffix(".txt").map(|hash| (hash, None))
+}
+
+impl AttachmentManifest {
+    pub fn new<I, S>(hashes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let hashes = merkle::leaves(hashes);
+        let merkle_root = format!("sha256:{}", merkle::root(&hashes));
+        Self {
+            hashes,
+            merkle_root,
+            compression: None,
+        }
+    }
+
+    /// Bundle entry of the attachment with content hash `hash`
+    pub fn entry_name(&self, hash: &str) -> String {
+        let extension = self.compression.map(AttachmentCompression::extension);
+        format!("attachments/{hash}.txt{}", extension.unwrap_or_default())
+    }
+
+    /// `Err` when a bundle entry is not encoded as the manifest records
+    pub fn check_encoding(
+        &self,
+        hash: &str,
+        compression: Option<AttachmentCompression>,
+    ) -> std::result::Result<(), String> {
+        if compression == self.compression {
+            Ok(())
+        } else {
+            Err(format!(
+                "attachment {hash} is not encoded as the attachment manifest records"
+            ))
+        }
+    }
+
+    /// `Err` when `merkle_root` is not the root over `hashes`
+    pub fn check_root(&self) -> std::result::Result<(), String> {
+        let computed = merkle::root(&self.hashes);
+        if merkle::leaves(self.hashes.iter().cloned()) != self.hashes {
+            return Err("attachment hashes are not sorted and unique".to_string());
+        }
+        match self.merkle_root.strip_prefix("sha256:") {
+            Some(root) if root == computed => Ok(()),
+            _ => Err(format!(
+                "attachment Merkle root mismatch\nExpected: {}\nComputed: sha256:{computed}",
+                self.merkle_root
+            )),
+        }
+    }
+
+    /// `Err` when `hash` does not prove into the manifest's root
+    pub fn check_inclusion(&self, hash: &str) -> std::result::Result<(), String> {
+        let root = self.merkle_root.strip_prefix("sha256:").unwrap_or_default();
+        match merkle::inclusion_proof(&self.hashes, hash) {
+            Some(proof) if merkle::verify_inclusion(root, hash, &proof) => Ok(()),
+            _ => Err(format!("attachment {hash} is not in the CAR's attachment manifest")),
+        }
+    }
+}
+
+/// A file the CAR binds by content hash without carrying it. Verifiers
+/// check a local copy when one is supplied; the CAR alone says nothing
+/// about whether the file at `uri` still matches.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExternalAttachment {
+    /// Where the file was read from, e.g. `file:///data/corpus.pdf`
+    pub uri: String,
+    /// Hex sha256 of the file content
+    pub sha256: String,
+    /// Size of the file in bytes
+    pub size: u64,
+}
+
+impl ExternalAttachment {
+    /// Describe the file at `path`, hashing it without reading it into memory
+    pub fn of_file(uri: impl Into<String>, path: &std::path::Path) -> std::io::Result<Self> {
+        let (sha256, size) = hash_file(path)?;
+        Ok(Self {
+            uri: uri.into(),
+            sha256,
+            size,
+        })
+    }
+
+    /// Last path segment of `uri`, the name a local copy may go by
+    pub fn file_name(&self) -> Option<&str> {
+        let path = self.uri.split(['?', '#']).next().unwrap_or_default();
+        path.rsplit('/').next().filter(|name| !name.is_empty())
+    }
+
+    /// `Err` when the file at `path` is not this attachment
+    pub fn check_file(&self, path: &std::path::Path) -> std::result::Result<(), String> {
+        let size = std::fs::metadata(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?
+            .len();
+        if size != self.size {
+            return Err(format!(
+                "external attachment {} is {size} bytes, the CAR records {}",
+                self.uri, self.size
+            ));
+        }
+        let (computed, _) =
+            hash_file(path).map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+        if computed != self.sha256 {
+            return Err(format!(
+                "external attachment {} content mismatch\nExpected sha256: {}\nComputed sha256: {computed}",
+                self.uri, self.sha256
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hex sha256 and size of the file at `path`, read in fixed-size chunks
+fn hash_file(path: &std::path::Path) -> std::io::Result<(String, u64)> {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProvenanceClaim {
-    pub claim_type: String, // "input", "output", "config"
+    pub claim_type: String, // "input", "output", "config", "output_schema", "model_weights", "checkpoint_reference", or an extension type
     pub sha256: String,
+    /// Structured content of extension claims (e.g. a dataset descriptor),
+    /// checked against the claim type's schema when the type is registered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
 }
 
 // NOTE: The Replay struct is now replaced by the more detailed `Proof` struct.
@@ -134,6 +661,19 @@ pub struct ProvenanceClaim {
 pub struct SGrade {
     pub score: u8, // 0-100
     pub components: SGradeComponents,
+    /// Emissions the energy component rests on; absent for runs recorded
+    /// before checkpoint emissions were stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<SGradeInputs>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SGradeInputs {
+    /// Sum of the checkpoints' gCO2e: each model's energy per token from the
+    /// catalog times the carbon intensity of the grid it ran on
+    pub g_co2e: f64,
+    /// The user's grid region, which on-device inference was priced against
+    pub grid_region: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -186,142 +726,835 @@ pub fn calculate_s_grade(
     SGrade {
         score: final_score.round() as u8,
         components,
+        inputs: None,
     }
 }
 
-// --- CAR Building Logic ---
+// --- Provenance Claim Types ---
+// Verifiers check every claim whose type is registered. Claims of unknown types
+// are reported as unvalidated extensions instead of being skipped silently, so
+// CARs from newer versions or third-party tools remain readable.
 
-struct CheckpointRow {
-    id: String,
-    kind: String,
-    timestamp: DateTime<Utc>,
-    inputs_sha256: Option<String>,
-    outputs_sha256: Option<String>,
-    usage_tokens: u64,
-    prompt_tokens: u64,
-    completion_tokens: u64,
-    parent_checkpoint_id: Option<String>,
-    turn_index: Option<u32>,
-    prev_chain: String,
-    curr_chain: String,
-    signature: String,
+/// Checks a claim against the CAR that carries it; `Err` explains the mismatch
+pub type ClaimValidator = fn(&ProvenanceClaim, &Car) -> std::result::Result<(), String>;
+
+#[derive(Clone)]
+pub struct ClaimType {
+    pub name: String,
+    /// JSON Schema the claim's `data` must satisfy; `None` for digest-only claims
+    pub schema: Option<Value>,
+    pub validate: ClaimValidator,
 }
 
-pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>) -> Result<Car> {
-    let (project_id, run_created_at): (String, String) = conn
-        .query_row(
-            "SELECT project_id, created_at FROM runs WHERE id = ?1",
-            params![run_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|err| anyhow!("failed to load run {run_id}: {err}"))?;
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ClaimStatus {
+    Verified,
+    Failed { reason: String },
+    UnvalidatedExtension,
+}
 
-    let created_at = DateTime::parse_from_rfc3339(&run_created_at)
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|err| anyhow!("invalid run created_at timestamp: {err}"))?;
-    let stored_run = orchestrator::load_stored_run(conn, run_id)
-        .map_err(|err| anyhow!("failed to load stored run: {err}"))?;
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClaimCheck {
+    pub index: usize,
+    pub claim_type: String,
+    #[serde(flatten)]
+    pub status: ClaimStatus,
+}
 
-    let execution_id = if let Some(exec_id) = run_execution_id {
-        let owner: Option<String> = conn
-            .query_row(
-                "SELECT run_id FROM run_executions WHERE id = ?1",
-                params![exec_id],
-                |row| row.get(0),
-            )
-            .optional()?;
-        let owner =
-            owner.ok_or_else(|| anyhow!("run execution {exec_id} not found for run {run_id}"))?;
-        if owner != run_id {
-            return Err(anyhow!(
-                "run execution {exec_id} does not belong to run {run_id}"
-            ));
-        }
-        exec_id.to_string()
-    } else {
-        let latest_execution = orchestrator::load_latest_run_execution(conn, run_id)
-            .map_err(|err| {
-                anyhow!("failed to resolve latest run execution for run {run_id}: {err}")
-            })?;
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ClaimsReport {
+    pub checks: Vec<ClaimCheck>,
+}
 
-        let Some(record) = latest_execution else {
-            return Err(anyhow!(
-                "failed to resolve latest run execution for run {run_id}: not found"
-            ));
-        };
+impl ClaimsReport {
+    pub fn verified(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == ClaimStatus::Verified)
+            .count()
+    }
 
-        record.id
-    };
+    pub fn failures(&self) -> impl Iterator<Item = &ClaimCheck> {
+        self.checks
+            .iter()
+            .filter(|check| matches!(check.status, ClaimStatus::Failed { .. }))
+    }
 
-    let project_pubkey: String = conn
-        .query_row(
-            "SELECT pubkey FROM projects WHERE id = ?1",
-            params![&project_id],
-            |row| row.get(0),
-        )
-        .map_err(|err| anyhow!("failed to load project {project_id}: {err}"))?;
+    /// Distinct claim types no validator was registered for
+    pub fn unvalidated_types(&self) -> Vec<String> {
+        let types: std::collections::BTreeSet<&str> = self
+            .checks
+            .iter()
+            .filter(|check| check.status == ClaimStatus::UnvalidatedExtension)
+            .map(|check| check.claim_type.as_str())
+            .collect();
+        types.into_iter().map(str::to_string).collect()
+    }
+}
 
-    let run_steps = stored_run.steps.clone();
+pub struct ClaimRegistry {
+    types: std::collections::BTreeMap<String, ClaimType>,
+}
 
-    let mut stmt = conn.prepare(
-        "SELECT id, kind, timestamp, inputs_sha256, outputs_sha256, usage_tokens, prompt_tokens, completion_tokens, parent_checkpoint_id, turn_index, prev_chain, curr_chain, signature
-         FROM checkpoints WHERE run_id = ?1 AND run_execution_id = ?2 ORDER BY timestamp ASC",
-    )?;
-    let rows = stmt.query_map(params![run_id, &execution_id], |row| {
-        let ts: String = row.get(2)?;
-        let parsed_ts = DateTime::parse_from_rfc3339(&ts)
-            .map(|dt| dt.with_timezone(&Utc))
-            .map_err(|err| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    2,
-                    rusqlite::types::Type::Text,
-                    Box::new(err),
-                )
-            })?;
-        let usage: i64 = row.get(5)?;
-        let prompt: i64 = row.get(6)?;
-        let completion: i64 = row.get(7)?;
-        Ok(CheckpointRow {
-            id: row.get(0)?,
-            kind: row.get(1)?,
-            timestamp: parsed_ts,
-            inputs_sha256: row.get(3)?,
-            outputs_sha256: row.get(4)?,
-            usage_tokens: usage.max(0) as u64,
-            prompt_tokens: prompt.max(0) as u64,
-            completion_tokens: completion.max(0) as u64,
-            parent_checkpoint_id: row.get(8)?,
-            turn_index: row
-                .get::<_, Option<i64>>(9)?
-                .map(|value| value.max(0) as u32),
-            prev_chain: row.get(10)?,
-            curr_chain: row.get(11)?,
-            signature: row.get(12)?,
-        })
-    })?;
+impl Default for ClaimRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
 
-    let mut checkpoints: Vec<CheckpointRow> = Vec::new();
-    for row in rows {
-        checkpoints.push(row?);
+impl ClaimRegistry {
+    /// Registry with the claim types Intelexta itself emits
+    pub fn builtin() -> Self {
+        let mut registry = Self {
+            types: std::collections::BTreeMap::new(),
+        };
+        registry.register(ClaimType {
+            name: "config".to_string(),
+            schema: None,
+            validate: validate_config_claim,
+        });
+        for name in ["input", "output"] {
+            registry.register(ClaimType {
+                name: name.to_string(),
+                schema: None,
+                validate: validate_checkpoint_digest_claim,
+            });
+        }
+        registry.register(ClaimType {
+            name: "output_schema".to_string(),
+            schema: None,
+            validate: validate_output_schema_claim,
+        });
+        registry.register(ClaimType {
+            name: "model_weights".to_string(),
+            schema: Some(model_weights_schema()),
+            validate: validate_model_weights_claim,
+        });
+        registry.register(ClaimType {
+            name: "checkpoint_reference".to_string(),
+            schema: Some(checkpoint_reference_schema()),
+            validate: validate_checkpoint_reference_claim,
+        });
+        registry
     }
 
-    let policy = store::policies::get(conn, &project_id)?;
-    let policy_canon = provenance::canonical_json(&policy);
-    let policy_hash = provenance::sha256_hex(&policy_canon);
+    /// Add a claim type, replacing any earlier registration of the same name
+    pub fn register(&mut self, claim_type: ClaimType) {
+        self.types.insert(claim_type.name.clone(), claim_type);
+    }
 
-    let total_usage_tokens: u64 = checkpoints.iter().map(|ck| ck.usage_tokens).sum();
-    let usd_per_token = if policy.budget_tokens > 0 {
-        policy.budget_usd / policy.budget_tokens as f64
-    } else {
-        0.0
-    };
-    let nature_cost_per_token = if policy.budget_tokens > 0 {
-        policy.budget_nature_cost / policy.budget_tokens as f64
-    } else {
-        0.0
-    };
-    let estimated_usd = usd_per_token * total_usage_tokens as f64;
-    let estimated_nature_cost = nature_cost_per_token * total_usage_tokens as f64;
+    pub fn get(&self, name: &str) -> Option<&ClaimType> {
+        self.types.get(name)
+    }
+
+    pub fn check_claim(&self, claim: &ProvenanceClaim, car: &Car) -> ClaimStatus {
+        let failed = |reason: String| ClaimStatus::Failed { reason };
+        let Some(digest) = claim.sha256.strip_prefix("sha256:") else {
+            return failed("hash must start with 'sha256:'".to_string());
+        };
+        if digest.len() != 64 || !digest.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return failed("hash is not a hex sha256 digest".to_string());
+        }
+        let Some(claim_type) = self.types.get(&claim.claim_type) else {
+            return ClaimStatus::UnvalidatedExtension;
+        };
+
+        if let Some(schema) = &claim_type.schema {
+            let Some(data) = &claim.data else {
+                return failed("claim data is missing".to_string());
+            };
+            let violations = output_schema::validate(schema, data);
+            if !violations.is_empty() {
+                let issues: Vec<String> = violations.iter().map(ToString::to_string).collect();
+                return failed(format!("claim data violates schema: {}", issues.join("; ")));
+            }
+        }
+
+        match (claim_type.validate)(claim, car) {
+            Ok(()) => ClaimStatus::Verified,
+            Err(reason) => failed(reason),
+        }
+    }
+
+    pub fn verify(&self, car: &Car) -> ClaimsReport {
+        ClaimsReport {
+            checks: car
+                .provenance
+                .iter()
+                .enumerate()
+                .map(|(index, claim)| ClaimCheck {
+                    index,
+                    claim_type: claim.claim_type.clone(),
+                    status: self.check_claim(claim, car),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Step configs are read as plain JSON, so CARs with step types this build
+/// does not know still verify
+fn step_config(step: &RunStep) -> Option<Value> {
+    step.config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+}
+
+/// `outputSchema` of a prompt step
+fn step_output_schema(step: &RunStep) -> Option<Value> {
+    let config = step_config(step)?;
+    if config["stepType"] != "prompt" {
+        return None;
+    }
+    config.get("outputSchema").filter(|schema| !schema.is_null()).cloned()
+}
+
+/// Checkpoint of an earlier run a prompt step (`useOutputFrom`) or an
+/// evaluate step (`referenceCheckpointId`) consumes
+fn step_referenced_checkpoint(step: &RunStep) -> Option<String> {
+    let config = step_config(step)?;
+    let field = match config["stepType"].as_str()? {
+        "prompt" => "useOutputFrom",
+        "evaluate" => "referenceCheckpointId",
+        _ => return None,
+    };
+    // `useOutputFrom` is a step index when it refers to the same run
+    config[field].as_str().map(str::to_string)
+}
+
+fn claim_digest(claim: &ProvenanceClaim) -> &str {
+    claim.sha256.strip_prefix("sha256:").unwrap_or(&claim.sha256)
+}
+
+fn validate_config_claim(claim: &ProvenanceClaim, car: &Car) -> std::result::Result<(), String> {
+    let computed = provenance::sha256_hex(&provenance::canonical_json(&car.run.steps));
+    if computed == claim_digest(claim) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Config hash mismatch\nExpected: {}\nComputed: {}",
+            claim_digest(claim),
+            computed
+        ))
+    }
+}
+
+/// Input and output claims must name a digest recorded by some checkpoint
+fn validate_checkpoint_digest_claim(
+    claim: &ProvenanceClaim,
+    car: &Car,
+) -> std::result::Result<(), String> {
+    let digest = Some(claim_digest(claim));
+    let found = car.proof.process.as_ref().is_some_and(|process| {
+        process.sequential_checkpoints.iter().any(|checkpoint| {
+            checkpoint.inputs_sha256.as_deref() == digest
+                || checkpoint.outputs_sha256.as_deref() == digest
+        })
+    });
+    if found {
+        Ok(())
+    } else {
+        Err(format!("{} hash not found in checkpoints", claim.claim_type))
+    }
+}
+
+/// Output schema claims must match a schema declared by one of the run's steps
+fn validate_output_schema_claim(
+    claim: &ProvenanceClaim,
+    car: &Car,
+) -> std::result::Result<(), String> {
+    let found = car.run.steps.iter().any(|step| {
+        step_output_schema(step)
+            .is_some_and(|schema| output_schema::schema_sha256(&schema) == claim_digest(claim))
+    });
+    if found {
+        Ok(())
+    } else {
+        Err("output schema hash does not match any step's schema".to_string())
+    }
+}
+
+/// Weights claims name the model and the checkpoint that ran on those weights
+fn model_weights_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["model", "checkpointId"],
+        "properties": {
+            "model": {"type": "string"},
+            "checkpointId": {"type": "string"},
+            "trainingCarId": {"type": "string"}
+        }
+    })
+}
+
+/// References name the earlier checkpoint whose output a step consumed, the
+/// run that recorded it and that checkpoint's signature
+fn checkpoint_reference_schema() -> Value {
+    serde_json::json!({
+        "type": "object",
+        "required": ["checkpointId", "runId", "signature"],
+        "properties": {
+            "checkpointId": {"type": "string"},
+            "runId": {"type": "string"},
+            "signature": {"type": "string"}
+        }
+    })
+}
+
+/// Checkpoint references must name a checkpoint one of the run's steps consumes
+fn validate_checkpoint_reference_claim(
+    claim: &ProvenanceClaim,
+    car: &Car,
+) -> std::result::Result<(), String> {
+    let checkpoint_id = claim
+        .data
+        .as_ref()
+        .and_then(|data| data["checkpointId"].as_str())
+        .unwrap_or_default();
+    let referenced = car
+        .run
+        .steps
+        .iter()
+        .any(|step| step_referenced_checkpoint(step).as_deref() == Some(checkpoint_id));
+    if referenced {
+        Ok(())
+    } else {
+        Err(format!("no step references checkpoint {checkpoint_id}"))
+    }
+}
+
+// --- Redaction ---
+// Selective disclosure: prompt text and output attachments can be withheld
+// from a CAR. Each withheld value is replaced by a salted sha256 commitment
+// before the CAR is hashed and signed, so the chain and claims still verify
+// and whoever holds the salt can later open a commitment to a single reader.
+
+/// Prefix of a field value withheld behind a commitment
+pub const REDACTED_PREFIX: &str = "redacted:";
+
+/// Step config fields that carry prompt text
+const REDACTABLE_CONFIG_FIELDS: [&str; 3] = ["prompt", "promptTemplate", "customInstructions"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Redaction {
+    /// `steps/<step id>/prompt`, `steps/<step id>/config/<field>` or `attachments/<hash>`
+    pub target: String,
+    /// `sha256:` digest of the salt followed by the withheld value
+    pub commitment: String,
+}
+
+impl Redaction {
+    /// Value left in place of a redacted field
+    pub fn marker(&self) -> String {
+        format!("{REDACTED_PREFIX}{}", self.commitment)
+    }
+
+    /// Whether `value`, salted with `salt`, is what was withheld
+    pub fn opens(&self, salt: &str, value: &[u8]) -> bool {
+        redaction_commitment(salt, value) == self.commitment
+    }
+}
+
+pub fn redaction_commitment(salt: &str, value: &[u8]) -> String {
+    format!(
+        "sha256:{}",
+        provenance::sha256_hex(&[salt.as_bytes(), value].concat())
+    )
+}
+
+/// Executions a CAR is built from
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "mode")]
+pub enum ExecutionSelection {
+    /// The run's most recent execution
+    #[default]
+    Latest,
+    #[serde(rename_all = "camelCase")]
+    Execution { run_execution_id: String },
+    /// Every execution, each in its own proof section
+    All,
+}
+
+impl ExecutionSelection {
+    /// The single execution selected, `None` for the latest or all
+    pub fn run_execution_id(&self) -> Option<&str> {
+        match self {
+            Self::Execution { run_execution_id } => Some(run_execution_id),
+            Self::Latest | Self::All => None,
+        }
+    }
+}
+
+/// Fields and attachments to withhold when building a CAR
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRequest {
+    /// Steps whose prompt text is withheld
+    #[serde(default)]
+    pub step_ids: Vec<String>,
+    /// Checkpoints whose output attachment is left out of bundles and whose
+    /// chat message is withheld
+    #[serde(default)]
+    pub checkpoint_ids: Vec<String>,
+}
+
+/// Third parties asked to attest to a CAR when its bundle is built
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestations {
+    /// URL of an RFC 3161 timestamp authority to stamp the CAR, see `timestamp`
+    #[serde(default)]
+    pub timestamp_authority: Option<String>,
+    /// Base URL of a transparency log to publish the CAR to, see `transparency`
+    #[serde(default)]
+    pub transparency_log: Option<String>,
+}
+
+/// Recipients an emitted bundle is encrypted to, so a CAR carrying
+/// sensitive payloads can be sent to a specific auditor
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Encryption {
+    /// age X25519 recipients (`age1...`); none leaves the bundle in the clear
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Extension added to the file name of an encrypted bundle
+pub const ENCRYPTED_EXTENSION: &str = "age";
+
+#[cfg(feature = "app")]
+impl Encryption {
+    pub fn is_enabled(&self) -> bool {
+        !self.recipients.is_empty()
+    }
+
+    /// Parse the recipients, so a typo fails before anything is built
+    pub fn parse_recipients(&self) -> Result<Vec<age::x25519::Recipient>> {
+        self.recipients
+            .iter()
+            .map(|recipient| {
+                recipient
+                    .trim()
+                    .parse::<age::x25519::Recipient>()
+                    .map_err(|err| anyhow!("invalid age recipient '{recipient}': {err}"))
+            })
+            .collect()
+    }
+}
+
+fn is_redaction_marker(value: &str) -> bool {
+    value
+        .strip_prefix(REDACTED_PREFIX)
+        .and_then(|commitment| commitment.strip_prefix("sha256:"))
+        .is_some_and(|digest| digest.len() == 64 && digest.chars().all(|ch| ch.is_ascii_hexdigit()))
+}
+
+/// Prompt text of a step, keyed by the target a redaction names it by
+fn redactable_fields(step: &RunStep) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    if let Some(prompt) = &step.prompt {
+        fields.push((format!("steps/{}/prompt", step.id), prompt.clone()));
+    }
+    if let Some(config) = step_config(step) {
+        for field in REDACTABLE_CONFIG_FIELDS {
+            if let Some(text) = config[field].as_str() {
+                fields.push((format!("steps/{}/config/{field}", step.id), text.to_string()));
+            }
+        }
+    }
+    fields
+}
+
+/// Every redacted field must carry the marker of a listed redaction and every
+/// listed step redaction must be in place; returns the number of redactions
+pub fn check_redactions(car: &Car) -> std::result::Result<usize, String> {
+    for redaction in &car.redactions {
+        if !is_redaction_marker(&redaction.marker()) {
+            return Err(format!(
+                "redaction of {} has no sha256 commitment",
+                redaction.target
+            ));
+        }
+    }
+
+    let mut in_place = 0;
+    for step in &car.run.steps {
+        for (target, value) in redactable_fields(step) {
+            if !is_redaction_marker(&value) {
+                continue;
+            }
+            let listed = car
+                .redactions
+                .iter()
+                .any(|redaction| redaction.target == target && redaction.marker() == value);
+            if !listed {
+                return Err(format!("{target} is redacted without a listed commitment"));
+            }
+            in_place += 1;
+        }
+    }
+    let listed_in_steps = car
+        .redactions
+        .iter()
+        .filter(|redaction| redaction.target.starts_with("steps/"))
+        .count();
+    if listed_in_steps != in_place {
+        return Err(format!(
+            "{listed_in_steps} step redactions are listed but {in_place} fields are redacted"
+        ));
+    }
+    Ok(car.redactions.len())
+}
+
+/// Salt of a redaction, derived from the signing key so the signer can reopen
+/// any commitment without keeping salts around
+#[cfg(feature = "app")]
+pub fn redaction_salt(
+    signing_key: &ed25519_dalek::SigningKey,
+    run_id: &str,
+    target: &str,
+) -> String {
+    provenance::sha256_hex(
+        &[
+            b"intelexta-redaction\0".as_slice(),
+            signing_key.to_bytes().as_slice(),
+            run_id.as_bytes(),
+            b"\0",
+            target.as_bytes(),
+        ]
+        .concat(),
+    )
+}
+
+/// Replace a step's prompt text with commitment markers
+#[cfg(feature = "app")]
+fn redact_step(
+    step: &mut RunStep,
+    signing_key: &ed25519_dalek::SigningKey,
+    run_id: &str,
+) -> Vec<Redaction> {
+    let mut redactions = Vec::new();
+    let mut redact = |target: String, value: &str| {
+        let salt = redaction_salt(signing_key, run_id, &target);
+        let redaction = Redaction {
+            commitment: redaction_commitment(&salt, value.as_bytes()),
+            target,
+        };
+        let marker = redaction.marker();
+        redactions.push(redaction);
+        marker
+    };
+
+    if let Some(prompt) = &step.prompt {
+        step.prompt = Some(redact(format!("steps/{}/prompt", step.id), prompt));
+    }
+    if let Some(mut config) = step_config(step) {
+        let mut changed = false;
+        for field in REDACTABLE_CONFIG_FIELDS {
+            if let Some(text) = config[field].as_str() {
+                let marker = redact(format!("steps/{}/config/{field}", step.id), text);
+                config[field] = Value::String(marker);
+                changed = true;
+            }
+        }
+        if changed {
+            step.config_json = Some(config.to_string());
+        }
+    }
+    redactions
+}
+
+#[cfg(feature = "app")]
+fn model_weights_claim(weights: &store::model_weights::ModelWeights, checkpoint_id: &str) -> ProvenanceClaim {
+    let mut data = serde_json::json!({
+        "model": &weights.model_id,
+        "checkpointId": checkpoint_id,
+    });
+    if let Some(training_car_id) = &weights.training_car_id {
+        data["trainingCarId"] = Value::from(training_car_id.as_str());
+    }
+    ProvenanceClaim {
+        claim_type: "model_weights".to_string(),
+        sha256: format!("sha256:{}", weights.weights_sha256),
+        data: Some(data),
+    }
+}
+
+/// Weights claims must point at a step checkpoint of this CAR whose step used the model
+fn validate_model_weights_claim(
+    claim: &ProvenanceClaim,
+    car: &Car,
+) -> std::result::Result<(), String> {
+    let data = claim.data.as_ref().ok_or("claim data is missing")?;
+    let model = data.get("model").and_then(Value::as_str).unwrap_or_default();
+    let checkpoint_id = data.get("checkpointId").and_then(Value::as_str).unwrap_or_default();
+
+    let has_checkpoint = car.proof.process.as_ref().is_some_and(|process| {
+        process
+            .sequential_checkpoints
+            .iter()
+            .any(|checkpoint| checkpoint.id == checkpoint_id && checkpoint.kind == "Step")
+    });
+    if !has_checkpoint {
+        return Err(format!("checkpoint {checkpoint_id} not found in process proof"));
+    }
+    if !car.run.steps.iter().any(|step| step.model.as_deref() == Some(model)) {
+        return Err(format!("no step of the run uses model {model}"));
+    }
+    Ok(())
+}
+
+// --- CAR Building Logic ---
+
+#[cfg(feature = "app")]
+struct CheckpointRow {
+    id: String,
+    kind: String,
+    timestamp: DateTime<Utc>,
+    inputs_sha256: Option<String>,
+    outputs_sha256: Option<String>,
+    usage_tokens: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    parent_checkpoint_id: Option<String>,
+    turn_index: Option<u32>,
+    prev_chain: String,
+    curr_chain: String,
+    signature: String,
+    checkpoint_config_id: Option<String>,
+    cache_hit: bool,
+    response_metadata: Option<ResponseMetadata>,
+    run_inputs_sha256: Option<String>,
+    output_schema_sha256: Option<String>,
+    usd_cost: Option<f64>,
+    nature_cost: Option<f64>,
+    g_co2e: Option<f64>,
+    semantic_digest: Option<String>,
+    /// Role and body of an interactive turn's message
+    message: Option<(String, String)>,
+}
+
+#[cfg(feature = "app")]
+pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>) -> Result<Car> {
+    build_redacted_car(conn, run_id, run_execution_id, &RedactionRequest::default())
+}
+
+/// Build a CAR with the requested prompts and output attachments withheld
+/// behind commitments
+#[cfg(feature = "app")]
+pub fn build_redacted_car(
+    conn: &Connection,
+    run_id: &str,
+    run_execution_id: Option<&str>,
+    redaction: &RedactionRequest,
+) -> Result<Car> {
+    let (mut car, signer) = build_unsigned_car(conn, run_id, run_execution_id, redaction)?;
+    sign_car(&mut car, &signer)?;
+    Ok(car)
+}
+
+/// Build a CAR of the executions `selection` names
+#[cfg(feature = "app")]
+pub fn build_selected_car(
+    conn: &Connection,
+    run_id: &str,
+    selection: &ExecutionSelection,
+    redaction: &RedactionRequest,
+) -> Result<Car> {
+    match selection {
+        ExecutionSelection::All => build_combined_car(conn, run_id, redaction),
+        _ => build_redacted_car(conn, run_id, selection.run_execution_id(), redaction),
+    }
+}
+
+/// Build one CAR over every execution of a run, so comparisons across
+/// executions travel together. The top-level proof, budgets and claims are
+/// those of the latest execution, as in a single-execution CAR, and redacted
+/// checkpoints must belong to it; `proof.executions` holds a section per
+/// execution, oldest first, and the attachment manifest covers them all.
+#[cfg(feature = "app")]
+pub fn build_combined_car(
+    conn: &Connection,
+    run_id: &str,
+    redaction: &RedactionRequest,
+) -> Result<Car> {
+    let (mut car, signer) = build_unsigned_car(conn, run_id, None, redaction)?;
+    let project_id: String = conn.query_row(
+        "SELECT project_id FROM runs WHERE id = ?1",
+        params![run_id],
+        |row| row.get(0),
+    )?;
+    let policy = store::policies::get(conn, &project_id)?;
+
+    let mut executions = orchestrator::list_run_executions(conn, run_id)?;
+    executions.reverse();
+    let mut attachment_hashes = car
+        .attachments
+        .take()
+        .map(|manifest| manifest.hashes)
+        .unwrap_or_default();
+    for execution in executions {
+        let checkpoints = load_checkpoint_rows(conn, run_id, &execution.id)?;
+        let checkpoint_ids: Vec<String> = checkpoints.iter().map(|ck| ck.id.clone()).collect();
+        attachment_hashes.extend(full_output_hashes(conn, &checkpoint_ids)?);
+        car.proof.executions.push(ExecutionProof {
+            budgets: execution_budgets(&checkpoints, &policy),
+            process: process_proof(run_id, &checkpoints, redaction).unwrap_or(ProcessProof {
+                sequential_checkpoints: Vec::new(),
+            }),
+            run_environment: load_run_environment(conn, &execution.id)?,
+            run_execution_id: execution.id,
+            created_at: execution.created_at,
+        });
+    }
+    car.attachments = Some(AttachmentManifest {
+        compression: Some(AttachmentCompression::Zstd),
+        ..AttachmentManifest::new(attachment_hashes)
+    });
+
+    sign_car(&mut car, &signer)?;
+    Ok(car)
+}
+
+/// Everything of a single-execution CAR but its ID and signatures
+#[cfg(feature = "app")]
+fn build_unsigned_car(
+    conn: &Connection,
+    run_id: &str,
+    run_execution_id: Option<&str>,
+    redaction: &RedactionRequest,
+) -> Result<(Car, provenance::ProjectSigner)> {
+    let (project_id, run_created_at): (String, String) = conn
+        .query_row(
+            "SELECT project_id, created_at FROM runs WHERE id = ?1",
+            params![run_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|err| anyhow!("failed to load run {run_id}: {err}"))?;
+
+    let created_at = DateTime::parse_from_rfc3339(&run_created_at)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|err| anyhow!("invalid run created_at timestamp: {err}"))?;
+    let stored_run = orchestrator::load_stored_run(conn, run_id)
+        .map_err(|err| anyhow!("failed to load stored run: {err}"))?;
+
+    let execution_id = if let Some(exec_id) = run_execution_id {
+        let owner: Option<String> = conn
+            .query_row(
+                "SELECT run_id FROM run_executions WHERE id = ?1",
+                params![exec_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let owner =
+            owner.ok_or_else(|| anyhow!("run execution {exec_id} not found for run {run_id}"))?;
+        if owner != run_id {
+            return Err(anyhow!(
+                "run execution {exec_id} does not belong to run {run_id}"
+            ));
+        }
+        exec_id.to_string()
+    } else {
+        let latest_execution = orchestrator::load_latest_run_execution(conn, run_id)
+            .map_err(|err| {
+                anyhow!("failed to resolve latest run execution for run {run_id}: {err}")
+            })?;
+
+        let Some(record) = latest_execution else {
+            return Err(anyhow!(
+                "failed to resolve latest run execution for run {run_id}: not found"
+            ));
+        };
+
+        record.id
+    };
+
+    let run_environment = load_run_environment(conn, &execution_id)?;
+
+    let project_pubkey: String = conn
+        .query_row(
+            "SELECT pubkey FROM projects WHERE id = ?1",
+            params![&project_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| anyhow!("failed to load project {project_id}: {err}"))?;
+
+    let signer = provenance::ProjectSigner::load(&project_id)
+        .with_context(|| format!("failed to load signing key for project {project_id}"))?;
+    // Only asked for when something is redacted: a threshold project derives
+    // it from this device's share
+    let redaction_key = || {
+        signer
+            .redaction_key()
+            .with_context(|| format!("failed to load redaction key for project {project_id}"))
+    };
+
+    // Redacted prompts are replaced before anything hashes the steps
+    let mut run_steps = stored_run.steps.clone();
+    let mut redactions = Vec::new();
+    for step_id in &redaction.step_ids {
+        let step = run_steps
+            .iter_mut()
+            .find(|step| &step.id == step_id)
+            .ok_or_else(|| anyhow!("step {step_id} is not part of run {run_id}"))?;
+        redactions.extend(redact_step(step, &redaction_key()?, run_id));
+    }
+
+    let checkpoints = load_checkpoint_rows(conn, run_id, &execution_id)?;
+
+    for checkpoint_id in &redaction.checkpoint_ids {
+        if !checkpoints.iter().any(|ck| &ck.id == checkpoint_id) {
+            return Err(anyhow!(
+                "checkpoint {checkpoint_id} is not part of run execution {execution_id}"
+            ));
+        }
+        let hash: Option<String> = conn
+            .query_row(
+                "SELECT full_output_hash FROM checkpoint_payloads WHERE checkpoint_id = ?1",
+                params![checkpoint_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        // Outputs kept inline never leave the database, so there is nothing to withhold
+        let Some(hash) = hash else {
+            continue;
+        };
+        let content = crate::attachments::get_global_attachment_store().load_full_output(&hash)?;
+        let target = format!("attachments/{hash}");
+        let salt = redaction_salt(&redaction_key()?, run_id, &target);
+        redactions.push(Redaction {
+            commitment: redaction_commitment(&salt, content.as_bytes()),
+            target,
+        });
+    }
+
+    let policy = store::policies::get(conn, &project_id)?;
+    let policy_canon = provenance::canonical_json(&policy);
+    let policy_hash = provenance::sha256_hex(&policy_canon);
+    let layer_hash = |layer: &store::policies::Policy| {
+        format!("sha256:{}", provenance::sha256_hex(&provenance::canonical_json(layer)))
+    };
+    let policy_layers = match store::policies::get_default(conn)? {
+        Some(default) => Some(PolicyLayers {
+            default_hash: layer_hash(&default),
+            project_hash: store::policies::get_project_layer(conn, &project_id)?
+                .as_ref()
+                .map(layer_hash),
+        }),
+        None => None,
+    };
+
+    let budgets = execution_budgets(&checkpoints, &policy);
+    // The estimator records which of the two pricings the budgets used
+    let priced_at_execution = checkpoints.iter().any(|ck| ck.usd_cost.is_some());
+    let nature_cost_per_token = if policy.budget_tokens > 0 {
+        policy.budget_nature_cost / policy.budget_tokens as f64
+    } else {
+        0.0
+    };
 
     let mut provenance_claims = Vec::new();
     let spec_canon = provenance::canonical_json(&run_steps);
@@ -329,23 +1562,118 @@ pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>
     provenance_claims.push(ProvenanceClaim {
         claim_type: "config".to_string(),
         sha256: format!("sha256:{spec_hash}"),
+        data: None,
     });
 
+    // External input (e.g. a trigger payload) that parameterized this execution
+    let execution_input_sha: Option<String> = conn
+        .query_row(
+            "SELECT input_sha256 FROM run_executions WHERE id = ?1",
+            params![&execution_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    if let Some(input_sha) = execution_input_sha {
+        provenance_claims.push(ProvenanceClaim {
+            claim_type: "input".to_string(),
+            sha256: format!("sha256:{input_sha}"),
+            data: None,
+        });
+    }
+
+    // Inputs the run was started with
+    if let Some(inputs_sha) = checkpoints.iter().find_map(|ck| ck.run_inputs_sha256.as_ref()) {
+        provenance_claims.push(ProvenanceClaim {
+            claim_type: "run_inputs".to_string(),
+            sha256: format!("sha256:{inputs_sha}"),
+            data: None,
+        });
+    }
+
+    // Output schemas constrain what a step may emit, so they are claimed separately
+    for step in &run_steps {
+        let schema = step
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok())
+            .and_then(|step_config| step_config.output_schema().cloned());
+        if let Some(schema) = schema {
+            provenance_claims.push(ProvenanceClaim {
+                claim_type: "output_schema".to_string(),
+                sha256: format!("sha256:{}", output_schema::schema_sha256(&schema)),
+                data: None,
+            });
+        }
+    }
+
+    // Outputs of earlier runs that steps consumed, pinned to the digest and
+    // signature of the checkpoint that recorded them
+    for step in &run_steps {
+        let referenced = step
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok())
+            .and_then(|step_config| step_config.referenced_checkpoint().map(str::to_string));
+        let Some(checkpoint_id) = referenced else {
+            continue;
+        };
+        let (source_run_id, outputs_sha256, signature): (String, Option<String>, String) = conn
+            .query_row(
+                "SELECT run_id, outputs_sha256, signature FROM checkpoints WHERE id = ?1",
+                params![&checkpoint_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow!("referenced checkpoint {checkpoint_id} not found"))?;
+        let outputs_sha256 = outputs_sha256
+            .ok_or_else(|| anyhow!("referenced checkpoint {checkpoint_id} has no output digest"))?;
+        provenance_claims.push(ProvenanceClaim {
+            claim_type: "checkpoint_reference".to_string(),
+            sha256: format!("sha256:{outputs_sha256}"),
+            data: Some(serde_json::json!({
+                "checkpointId": checkpoint_id,
+                "runId": source_run_id,
+                "signature": signature,
+            })),
+        });
+    }
+
     for ck in &checkpoints {
         if let Some(ref input_sha) = ck.inputs_sha256 {
             provenance_claims.push(ProvenanceClaim {
                 claim_type: "input".to_string(),
                 sha256: format!("sha256:{input_sha}"),
+                data: None,
             });
         }
         if let Some(ref output_sha) = ck.outputs_sha256 {
             provenance_claims.push(ProvenanceClaim {
                 claim_type: "output".to_string(),
                 sha256: format!("sha256:{output_sha}"),
+                data: None,
             });
         }
     }
 
+    // Fine-tuned weights registered for the model behind each step checkpoint,
+    // linking inference evidence back to the training run's CAR
+    for ck in checkpoints.iter().filter(|ck| ck.kind == "Step") {
+        let model = ck.checkpoint_config_id.as_deref().and_then(|config_id| {
+            run_steps
+                .iter()
+                .find(|step| step.id == config_id)
+                .and_then(|step| step.model.as_deref())
+        });
+        let Some(model) = model else {
+            continue;
+        };
+        let weights = store::model_weights::effective_at(conn, model, &ck.timestamp.to_rfc3339())?;
+        if let Some(weights) = weights {
+            provenance_claims.push(model_weights_claim(&weights, &ck.id));
+        }
+    }
+
     let model_identifier = format!("workflow:{}", stored_run.name);
     let checkpoints_canon = provenance::canonical_json(&run_steps);
     let version_digest = provenance::sha256_hex(&checkpoints_canon);
@@ -363,33 +1691,7 @@ pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>
 
     // Always include process proof with signatures for verification
     // (Previously this was only included for interactive workflows)
-    let process_proof = if !checkpoints.is_empty() {
-        let sequential = checkpoints
-            .iter()
-            .map(|ck| ProcessCheckpointProof {
-                id: ck.id.clone(),
-                parent_checkpoint_id: ck.parent_checkpoint_id.clone(),
-                turn_index: ck.turn_index,
-                prev_chain: ck.prev_chain.clone(),
-                curr_chain: ck.curr_chain.clone(),
-                signature: ck.signature.clone(),
-                // Checkpoint body fields for hash verification
-                run_id: run_id.to_string(),
-                kind: ck.kind.clone(),
-                timestamp: ck.timestamp.to_rfc3339(),
-                inputs_sha256: ck.inputs_sha256.clone(),
-                outputs_sha256: ck.outputs_sha256.clone(),
-                usage_tokens: ck.usage_tokens,
-                prompt_tokens: ck.prompt_tokens,
-                completion_tokens: ck.completion_tokens,
-            })
-            .collect();
-        Some(ProcessProof {
-            sequential_checkpoints: sequential,
-        })
-    } else {
-        None
-    };
+    let process_proof = process_proof(run_id, &checkpoints, redaction);
 
     let default_mode = stored_run.proof_mode.unwrap_or_default();
     let has_concordant_checkpoint = run_steps
@@ -412,10 +1714,17 @@ pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>
 
     let checkpoint_ids: Vec<String> = checkpoints.iter().map(|ck| ck.id.clone()).collect();
 
-    let mut car = Car {
+    // Full outputs the bundle carries as attachments
+    let attachment_hashes = full_output_hashes(conn, &checkpoint_ids)?;
+    let previous_car_id = latest_project_receipt(conn, &project_id)?;
+    let external_attachments = ingested_sources(&stored_run.steps)?;
+
+    let car = Car {
         id: String::new(),
+        format_version: CAR_FORMAT_VERSION,
         run_id: run_id.to_string(),
         created_at: car_created_at,
+        previous_car_id,
         run: RunInfo {
             kind: run_kind,
             name: stored_run.name.clone(),
@@ -432,27 +1741,52 @@ pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>
             original_semantic_digest: None,
             replay_semantic_digest: None,
             process: process_proof,
+            executions: Vec::new(),
         },
         policy_ref: PolicyRef {
             hash: format!("sha256:{policy_hash}"),
             egress: policy.allow_network,
-            estimator: format!("usage_tokens * {:.6} nature_cost/token", nature_cost_per_token),
+            estimator: if priced_at_execution {
+                "checkpoint costs priced from the model catalog at execution time".to_string()
+            } else {
+                format!("usage_tokens * {:.6} nature_cost/token", nature_cost_per_token)
+            },
             model_catalog_hash: format!("sha256:{}", crate::model_catalog::get_global_catalog().hash()),
             model_catalog_version: crate::model_catalog::get_global_catalog().version().to_string(),
+            limits: Some(Budgets {
+                usd: policy.budget_usd,
+                tokens: policy.budget_tokens,
+                nature_cost: policy.budget_nature_cost,
+            }),
+            layers: policy_layers,
+            provider_allowlist: (!policy.provider_allowlist.is_unrestricted())
+                .then(|| policy.provider_allowlist.clone()),
         },
-        budgets: Budgets {
-            usd: estimated_usd,
-            tokens: total_usage_tokens,
-            nature_cost: estimated_nature_cost,
-        },
+        budgets,
         provenance: provenance_claims,
         checkpoints: checkpoint_ids,
-        sgrade: calculate_s_grade(true, had_incident, true),
+        attachments: Some(AttachmentManifest {
+            compression: Some(AttachmentCompression::Zstd),
+            ..AttachmentManifest::new(attachment_hashes)
+        }),
+        external_attachments,
+        sgrade: SGrade {
+            inputs: sgrade_inputs(&checkpoints, store::carbon_settings::get_grid_region(conn)?),
+            ..calculate_s_grade(true, had_incident, true)
+        },
+        run_environment,
+        redactions,
         signer_public_key: project_pubkey,
         signatures: Vec::new(),
     };
 
-    let mut body_value = serde_json::to_value(&car)?;
+    Ok((car, signer))
+}
+
+/// Set the CAR ID and the body and checkpoint signatures
+#[cfg(feature = "app")]
+fn sign_car(car: &mut Car, signer: &provenance::ProjectSigner) -> Result<()> {
+    let mut body_value = serde_json::to_value(&*car)?;
     if let Value::Object(ref mut obj) = body_value {
         obj.remove("id");
         obj.remove("signatures");
@@ -461,83 +1795,778 @@ pub fn build_car(conn: &Connection, run_id: &str, run_execution_id: Option<&str>
     let car_id = provenance::sha256_hex(&canonical);
     car.id = format!("car:{car_id}");
 
-    let signing_key = provenance::load_secret_key(&project_id)
-        .with_context(|| format!("failed to load signing key for project {project_id}"))?;
-
     // Generate checkpoint signature (signs the CAR ID)
-    let checkpoint_signature = provenance::sign_bytes(&signing_key, car.id.as_bytes());
+    let checkpoint_signature = signer.sign(car.id.as_bytes())?;
 
     // Generate body signature: serialize CAR to JSON (as it will be written to file),
     // parse back as Value, remove signatures, canonicalize, and sign
-    let car_json_string = serde_json::to_string(&car)?;
+    let car_json_string = serde_json::to_string(&*car)?;
     let mut car_json: serde_json::Value = serde_json::from_str(&car_json_string)?;
     if let Some(obj) = car_json.as_object_mut() {
         obj.remove("signatures");
     }
     let body_canonical = provenance::canonical_json(&car_json);
-    let body_signature = provenance::sign_bytes(&signing_key, &body_canonical);
+    let body_signature = signer.sign(&body_canonical)?;
 
     // Store dual signatures
     car.signatures.push(format!("ed25519-body:{body_signature}"));
     car.signatures.push(format!("ed25519-checkpoint:{checkpoint_signature}"));
 
-    Ok(car)
+    Ok(())
 }
 
-/// Build a complete CAR bundle with attachments as a zip file
+#[cfg(feature = "app")]
+fn load_checkpoint_rows(
+    conn: &Connection,
+    run_id: &str,
+    execution_id: &str,
+) -> Result<Vec<CheckpointRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.kind, c.timestamp, c.inputs_sha256, c.outputs_sha256, c.usage_tokens, c.prompt_tokens, c.completion_tokens, c.parent_checkpoint_id, c.turn_index, c.prev_chain, c.curr_chain, c.signature, c.checkpoint_config_id, c.cache_hit, c.response_metadata_json, c.run_inputs_sha256, c.usd_cost, c.nature_cost, c.semantic_digest, m.role, m.body, c.g_co2e, c.output_schema_sha256
+         FROM checkpoints c LEFT JOIN checkpoint_messages m ON m.checkpoint_id = c.id
+         WHERE c.run_id = ?1 AND c.run_execution_id = ?2 ORDER BY c.timestamp ASC",
+    )?;
+    let rows = stmt.query_map(params![run_id, execution_id], |row| {
+        let ts: String = row.get(2)?;
+        let parsed_ts = DateTime::parse_from_rfc3339(&ts)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|err| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    2,
+                    rusqlite::types::Type::Text,
+                    Box::new(err),
+                )
+            })?;
+        let usage: i64 = row.get(5)?;
+        let prompt: i64 = row.get(6)?;
+        let completion: i64 = row.get(7)?;
+        Ok(CheckpointRow {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            timestamp: parsed_ts,
+            inputs_sha256: row.get(3)?,
+            outputs_sha256: row.get(4)?,
+            usage_tokens: usage.max(0) as u64,
+            prompt_tokens: prompt.max(0) as u64,
+            completion_tokens: completion.max(0) as u64,
+            parent_checkpoint_id: row.get(8)?,
+            turn_index: row
+                .get::<_, Option<i64>>(9)?
+                .map(|value| value.max(0) as u32),
+            prev_chain: row.get(10)?,
+            curr_chain: row.get(11)?,
+            signature: row.get(12)?,
+            checkpoint_config_id: row.get(13)?,
+            cache_hit: row.get(14)?,
+            response_metadata: row
+                .get::<_, Option<String>>(15)?
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .map_err(|err| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        15,
+                        rusqlite::types::Type::Text,
+                        Box::new(err),
+                    )
+                })?,
+            run_inputs_sha256: row.get(16)?,
+            output_schema_sha256: row.get(23)?,
+            usd_cost: row.get(17)?,
+            nature_cost: row.get(18)?,
+            g_co2e: row.get(22)?,
+            semantic_digest: row.get(19)?,
+            message: match (row.get::<_, Option<String>>(20)?, row.get(21)?) {
+                (Some(role), Some(body)) => Some((role, body)),
+                _ => None,
+            },
+        })
+    })?;
+
+    let mut checkpoints: Vec<CheckpointRow> = Vec::new();
+    for row in rows {
+        checkpoints.push(row?);
+    }
+    Ok(checkpoints)
+}
+
+#[cfg(feature = "app")]
+fn load_run_environment(conn: &Connection, execution_id: &str) -> Result<Option<RunEnvironment>> {
+    conn.query_row(
+        "SELECT environment_json FROM run_executions WHERE id = ?1",
+        params![execution_id],
+        |row| row.get::<_, Option<String>>(0),
+    )?
+    .map(|json| serde_json::from_str::<RunEnvironment>(&json))
+    .transpose()
+    .context("invalid run environment")
+}
+
+/// Budgets of one execution's checkpoints
+/// Head of the project's receipt chain: the receipt recorded last
+#[cfg(feature = "app")]
+fn latest_project_receipt(conn: &Connection, project_id: &str) -> Result<Option<String>> {
+    // `INSERT OR REPLACE` gives a re-recorded receipt a new rowid, so the
+    // highest rowid is the receipt emitted last
+    conn.query_row(
+        "SELECT r.id FROM receipts r JOIN runs ON runs.id = r.run_id
+         WHERE runs.project_id = ?1
+         ORDER BY r.rowid DESC LIMIT 1",
+        params![project_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .context("failed to load the project's latest receipt")
+}
+
+#[cfg(feature = "app")]
+fn execution_budgets(checkpoints: &[CheckpointRow], policy: &store::policies::Policy) -> Budgets {
+    let total_usage_tokens: u64 = checkpoints.iter().map(|ck| ck.usage_tokens).sum();
+    let usd_per_token = if policy.budget_tokens > 0 {
+        policy.budget_usd / policy.budget_tokens as f64
+    } else {
+        0.0
+    };
+    let nature_cost_per_token = if policy.budget_tokens > 0 {
+        policy.budget_nature_cost / policy.budget_tokens as f64
+    } else {
+        0.0
+    };
+    // Costs priced at execution time are authoritative; executions recorded
+    // before they were stored fall back to the policy's per-token rates
+    let priced_at_execution = checkpoints.iter().any(|ck| ck.usd_cost.is_some());
+    let (estimated_usd, estimated_nature_cost) = if priced_at_execution {
+        (
+            checkpoints.iter().filter_map(|ck| ck.usd_cost).sum(),
+            checkpoints.iter().filter_map(|ck| ck.nature_cost).sum(),
+        )
+    } else {
+        (
+            usd_per_token * total_usage_tokens as f64,
+            nature_cost_per_token * total_usage_tokens as f64,
+        )
+    };
+    Budgets {
+        usd: estimated_usd,
+        tokens: total_usage_tokens,
+        nature_cost: estimated_nature_cost,
+    }
+}
+
+/// Emissions recorded with the execution's checkpoints; `None` when none were
+#[cfg(feature = "app")]
+fn sgrade_inputs(
+    checkpoints: &[CheckpointRow],
+    grid_region: Option<String>,
+) -> Option<SGradeInputs> {
+    let emissions: Vec<f64> = checkpoints.iter().filter_map(|ck| ck.g_co2e).collect();
+    if emissions.is_empty() {
+        return None;
+    }
+    Some(SGradeInputs {
+        g_co2e: emissions.iter().sum(),
+        grid_region: grid_region
+            .unwrap_or_else(|| crate::model_catalog::DEFAULT_GRID_REGION.to_string()),
+    })
+}
+
+#[cfg(feature = "app")]
+fn process_proof(
+    run_id: &str,
+    checkpoints: &[CheckpointRow],
+    redaction: &RedactionRequest,
+) -> Option<ProcessProof> {
+    if checkpoints.is_empty() {
+        return None;
+    }
+    let sequential = checkpoints
+        .iter()
+        .map(|ck| ProcessCheckpointProof {
+            id: ck.id.clone(),
+            parent_checkpoint_id: ck.parent_checkpoint_id.clone(),
+            turn_index: ck.turn_index,
+            prev_chain: ck.prev_chain.clone(),
+            curr_chain: ck.curr_chain.clone(),
+            signature: ck.signature.clone(),
+            // Checkpoint body fields for hash verification
+            run_id: run_id.to_string(),
+            kind: ck.kind.clone(),
+            timestamp: ck.timestamp.to_rfc3339(),
+            inputs_sha256: ck.inputs_sha256.clone(),
+            outputs_sha256: ck.outputs_sha256.clone(),
+            usage_tokens: ck.usage_tokens,
+            prompt_tokens: ck.prompt_tokens,
+            completion_tokens: ck.completion_tokens,
+            cache_hit: ck.cache_hit,
+            response_metadata: ck.response_metadata.clone(),
+            run_inputs_sha256: ck.run_inputs_sha256.clone(),
+            output_schema_sha256: ck.output_schema_sha256.clone(),
+            usd_cost: ck.usd_cost,
+            nature_cost: ck.nature_cost,
+            // A digest of a withheld output would still say what it is close to
+            semantic_digest: ck
+                .semantic_digest
+                .clone()
+                .filter(|_| !redaction.checkpoint_ids.contains(&ck.id)),
+            message: ck.message.as_ref().map(|(role, body)| TranscriptMessage {
+                role: role.clone(),
+                body: Some(body.clone()).filter(|_| !redaction.checkpoint_ids.contains(&ck.id)),
+            }),
+        })
+        .collect();
+    Some(ProcessProof {
+        sequential_checkpoints: sequential,
+    })
+}
+
+/// Attachment store hashes of the checkpoints' full outputs
+#[cfg(feature = "app")]
+fn full_output_hashes(conn: &Connection, checkpoint_ids: &[String]) -> Result<Vec<String>> {
+    let mut attachment_hashes = Vec::new();
+    for checkpoint_id in checkpoint_ids {
+        let hash: Option<String> = conn
+            .query_row(
+                "SELECT full_output_hash FROM checkpoint_payloads WHERE checkpoint_id = ?1",
+                params![checkpoint_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        attachment_hashes.extend(hash);
+    }
+    Ok(attachment_hashes)
+}
+
+/// Source files of the run's ingestion steps. Corpora can run to gigabytes,
+/// so the CAR binds them by hash instead of bundling them.
+#[cfg(feature = "app")]
+fn ingested_sources(steps: &[RunStep]) -> Result<Vec<ExternalAttachment>> {
+    let mut sources: Vec<ExternalAttachment> = Vec::new();
+    for step in steps.iter().filter(|step| step.is_document_ingestion()) {
+        let Some(config) = step_config(step) else {
+            continue;
+        };
+        let Some(source_path) = config["sourcePath"].as_str() else {
+            continue;
+        };
+        let path = source_path.replace('\\', "/");
+        let uri = if path.starts_with('/') {
+            format!("file://{path}")
+        } else {
+            format!("file:///{path}")
+        };
+        if sources.iter().any(|source| source.uri == uri) {
+            continue;
+        }
+        let source = ExternalAttachment::of_file(uri, std::path::Path::new(source_path))
+            .with_context(|| format!("failed to hash ingested source {source_path}"))?;
+        sources.push(source);
+    }
+    Ok(sources)
+}
+
+// --- C2PA export ---
+// Maps a CAR onto a C2PA manifest definition so content-provenance tooling
+// can read it. Intelexta-specific data travels in `org.intelexta.*`
+// assertions; the manifest is a view of the CAR and carries no signature of
+// its own, the CAR's ed25519 signatures remain the proof.
+
+/// Media type of a CAR, used as the C2PA manifest's `format`
+pub const CAR_MEDIA_TYPE: &str = "application/vnd.intelexta.car+json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct C2paManifest {
+    pub claim_generator: String,
+    pub title: String,
+    pub format: String,
+    /// The CAR's id
+    pub instance_id: String,
+    pub assertions: Vec<C2paAssertion>,
+    pub signature_info: C2paSignatureInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct C2paAssertion {
+    pub label: String,
+    pub data: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct C2paSignatureInfo {
+    pub alg: String,
+    /// Base64 ed25519 public key of the CAR's signer
+    pub public_key: String,
+    pub signatures: Vec<String>,
+}
+
+/// C2PA manifest definition of `car`: a `c2pa.actions` assertion for the
+/// run, its provenance claims and the hash chain of every checkpoint
+pub fn export_c2pa_manifest(car: &Car) -> C2paManifest {
+    let generator = format!("Intelexta/{}", env!("CARGO_PKG_VERSION"));
+    let actions = serde_json::json!({
+        "actions": [{
+            "action": "c2pa.created",
+            "when": car.created_at.to_rfc3339(),
+            "softwareAgent": generator,
+            "parameters": {
+                "run_id": car.run_id,
+                "kind": car.run.kind,
+                "model": car.run.model,
+            },
+        }],
+    });
+    let claims: Vec<Value> = car
+        .provenance
+        .iter()
+        .map(|claim| {
+            let (alg, hash) = claim.sha256.split_once(':').unwrap_or(("sha256", &claim.sha256));
+            serde_json::json!({"claim_type": claim.claim_type, "alg": alg, "hash": hash})
+        })
+        .collect();
+
+    // A multi-execution CAR's `process` repeats its latest execution
+    let sections: Vec<(Option<&str>, &ProcessProof)> = if car.proof.executions.is_empty() {
+        car.proof.process.iter().map(|process| (None, process)).collect()
+    } else {
+        car.proof
+            .executions
+            .iter()
+            .map(|execution| (Some(execution.run_execution_id.as_str()), &execution.process))
+            .collect()
+    };
+    let checkpoints: Vec<Value> = sections
+        .into_iter()
+        .flat_map(|(execution, process)| {
+            process.sequential_checkpoints.iter().map(move |checkpoint| {
+                serde_json::json!({
+                    "id": checkpoint.id,
+                    "run_execution_id": execution,
+                    "kind": checkpoint.kind,
+                    "timestamp": checkpoint.timestamp,
+                    "inputs_sha256": checkpoint.inputs_sha256,
+                    "outputs_sha256": checkpoint.outputs_sha256,
+                    "prev_chain": checkpoint.prev_chain,
+                    "curr_chain": checkpoint.curr_chain,
+                    "signature": checkpoint.signature,
+                })
+            })
+        })
+        .collect();
+
+    let mut assertions = vec![
+        C2paAssertion {
+            label: "c2pa.actions".to_string(),
+            data: actions,
+        },
+        C2paAssertion {
+            label: "org.intelexta.car".to_string(),
+            data: serde_json::json!({
+                "car_id": car.id,
+                "format_version": car.format_version,
+                "policy_hash": car.policy_ref.hash,
+            }),
+        },
+        C2paAssertion {
+            label: "org.intelexta.provenance".to_string(),
+            data: serde_json::json!({"claims": claims}),
+        },
+        C2paAssertion {
+            label: "org.intelexta.checkpoints".to_string(),
+            data: serde_json::json!({"alg": "sha256", "checkpoints": checkpoints}),
+        },
+    ];
+    if let Some(attachments) = &car.attachments {
+        assertions.push(C2paAssertion {
+            label: "org.intelexta.attachments".to_string(),
+            data: serde_json::to_value(attachments).unwrap_or(Value::Null),
+        });
+    }
+
+    C2paManifest {
+        claim_generator: generator,
+        title: car.run.name.clone(),
+        format: CAR_MEDIA_TYPE.to_string(),
+        instance_id: car.id.clone(),
+        assertions,
+        signature_info: C2paSignatureInfo {
+            alg: "Ed25519".to_string(),
+            public_key: car.signer_public_key.clone(),
+            signatures: car.signatures.clone(),
+        },
+    }
+}
+
+/// Encoding of the CAR inside a bundle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CarFormat {
+    /// `car.json`
+    #[default]
+    Json,
+    /// `car.cbor`, see `car_cbor`
+    DagCbor,
+}
+
+/// Build a complete CAR bundle with attachments as a zip file, with the
+/// timestamp and transparency log entry `attestations` asks for
+#[cfg(feature = "app")]
 pub fn build_car_bundle(
     conn: &Connection,
     run_id: &str,
-    run_execution_id: Option<&str>,
+    selection: &ExecutionSelection,
     output_path: &std::path::Path,
+    format: CarFormat,
+    redaction: &RedactionRequest,
+    attestations: &Attestations,
 ) -> Result<()> {
     use std::fs::File;
     use std::io::Write;
     use zip::write::FileOptions;
-    use zip::ZipWriter;
+    use zip::{CompressionMethod, ZipWriter};
 
-    // Build the CAR JSON
-    let car = build_car(conn, run_id, run_execution_id)?;
-    let car_json = serde_json::to_string_pretty(&car)?;
+    // Bundles carry full outputs, which archived runs keep in cold storage
+    crate::cold_storage::ensure_hot(conn, run_id)?;
+
+    // Build the CAR and encode it in the requested format
+    let mut car = build_selected_car(conn, run_id, selection, redaction)?;
+    if let Some(tsa_url) = &attestations.timestamp_authority {
+        crate::timestamp::stamp_car(&mut car, tsa_url)?;
+    }
+    let log_entry = match &attestations.transparency_log {
+        Some(log_url) => Some(crate::transparency::publish(&car, log_url)?),
+        None => None,
+    };
+    let (entry_name, car_bytes) = match format {
+        CarFormat::Json => ("car.json", serde_json::to_vec_pretty(&car)?),
+        CarFormat::DagCbor => (
+            crate::car_cbor::BUNDLE_ENTRY,
+            crate::car_cbor::encode(&serde_json::to_value(&car)?),
+        ),
+    };
 
     // Create zip file
     let file = File::create(output_path)
         .with_context(|| format!("Failed to create zip file at {:?}", output_path))?;
     let mut zip = ZipWriter::new(file);
 
-    // Add car.json to zip
-    zip.start_file("car.json", FileOptions::default())?;
-    zip.write_all(car_json.as_bytes())?;
-
-    // Collect all attachment hashes from checkpoint payloads
-    let mut attachment_hashes = Vec::new();
-    for checkpoint_id in &car.checkpoints {
-        let hash: Option<String> = conn
-            .query_row(
-                "SELECT full_output_hash FROM checkpoint_payloads WHERE checkpoint_id = ?1",
-                params![checkpoint_id],
-                |row| row.get(0),
-            )
-            .optional()?;
+    // Add the CAR to zip
+    zip.start_file(entry_name, FileOptions::default())?;
+    zip.write_all(&car_bytes)?;
 
-        if let Some(h) = hash {
-            attachment_hashes.push(h);
-        }
+    if let Some(log_entry) = &log_entry {
+        zip.start_file(crate::transparency::BUNDLE_ENTRY, FileOptions::default())?;
+        zip.write_all(&serde_json::to_vec_pretty(log_entry)?)?;
     }
 
-    // Add all attachments to zip
+    // Add every attachment of the manifest once, however many checkpoints
+    // share it; redacted outputs stay out of the bundle
     let attachment_store = crate::attachments::get_global_attachment_store();
-    for hash in attachment_hashes {
-        if attachment_store.exists(&hash) {
-            let content = attachment_store.load_full_output(&hash)?;
+    let Some(manifest) = &car.attachments else {
+        zip.finish()?;
+        return Ok(());
+    };
+    for hash in &manifest.hashes {
+        let target = format!("attachments/{hash}");
+        if car.redactions.iter().any(|redaction| redaction.target == target) {
+            continue;
+        }
+        // A bundle missing a listed attachment would fail verification
+        if !attachment_store.exists(hash) {
+            return Err(anyhow!("attachment {hash} is missing from the attachment store"));
+        }
+        let content = attachment_store.load_full_output(hash)?;
 
-            // Store as attachments/{hash}.txt
-            let filename = format!("attachments/{}.txt", hash);
-            zip.start_file(&filename, FileOptions::default())?;
-            zip.write_all(content.as_bytes())?;
+        // Compressed entries are stored as they are; deflating them again gains nothing
+        match manifest.compression {
+            Some(compression) => {
+                let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+                zip.start_file(manifest.entry_name(hash), options)?;
+                zip.write_all(&compression.compress(content.as_bytes()))?;
+            }
+            None => {
+                zip.start_file(manifest.entry_name(hash), FileOptions::default())?;
+                zip.write_all(content.as_bytes())?;
+            }
         }
     }
 
     zip.finish()?;
     Ok(())
 }
+
+/// `path` with the encrypted bundle extension appended, `x.car.zip.age`
+#[cfg(feature = "app")]
+pub fn encrypted_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ENCRYPTED_EXTENSION);
+    name.into()
+}
+
+/// Encrypt the bundle at `plain_path` to `recipients` as `encrypted_path`,
+/// then remove the plaintext bundle
+#[cfg(feature = "app")]
+pub fn encrypt_car_file(
+    plain_path: &std::path::Path,
+    encrypted_path: &std::path::Path,
+    recipients: &[age::x25519::Recipient],
+) -> Result<()> {
+    use std::io::Write;
+
+    let encrypt = || -> Result<()> {
+        let encryptor = age::Encryptor::with_recipients(
+            recipients.iter().map(|recipient| recipient as &dyn age::Recipient),
+        )
+        .map_err(|err| anyhow!("failed to encrypt CAR bundle: {err}"))?;
+        let mut plain = std::fs::File::open(plain_path)
+            .with_context(|| format!("failed to open {}", plain_path.display()))?;
+        let output = std::fs::File::create(encrypted_path)
+            .with_context(|| format!("failed to create {}", encrypted_path.display()))?;
+        let mut writer = encryptor.wrap_output(std::io::BufWriter::new(output))?;
+        std::io::copy(&mut plain, &mut writer)?;
+        writer.finish()?.flush()?;
+        Ok(())
+    };
+    let result = encrypt();
+    if result.is_err() {
+        let _ = std::fs::remove_file(encrypted_path);
+    }
+    std::fs::remove_file(plain_path)
+        .with_context(|| format!("failed to remove {}", plain_path.display()))?;
+    result
+}
+
+/// Countersign the CAR of a bundle or of a `car.json` file in place. Only the
+/// CAR's `signatures` change: the rest of its JSON is kept as written, since
+/// the author's body signature covers it, and the other entries of a bundle
+/// are copied without recompressing them.
+#[cfg(feature = "app")]
+pub fn countersign_car_file(
+    path: &std::path::Path,
+    role: SignerRole,
+    signing_key: &ed25519_dalek::SigningKey,
+) -> Result<Cosignature> {
+    use intelexta_archive::{ArchiveLimits, SafeArchive};
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+
+    let cosign_json = |json: &[u8]| -> Result<(Value, Cosignature)> {
+        let mut value: Value = serde_json::from_slice(json).context("CAR is not valid JSON")?;
+        let mut car: Car = serde_json::from_value(value.clone()).context("file is not a CAR")?;
+        let cosignature = cosign(&mut car, role, signing_key).map_err(|err| anyhow!(err))?;
+        value["signatures"] = serde_json::json!(car.signatures);
+        Ok((value, cosignature))
+    };
+
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    if !bytes.starts_with(b"PK") {
+        let (value, cosignature) = cosign_json(&bytes)?;
+        std::fs::write(path, serde_json::to_vec_pretty(&value)?)?;
+        return Ok(cosignature);
+    }
+
+    let mut archive = SafeArchive::new(Cursor::new(bytes.as_slice()), ArchiveLimits::default())
+        .map_err(|err| anyhow!("failed to read CAR bundle {:?}: {err}", path))?;
+    let entry = if archive.names().iter().any(|name| name == crate::car_cbor::BUNDLE_ENTRY) {
+        crate::car_cbor::BUNDLE_ENTRY
+    } else {
+        "car.json"
+    };
+    let encoded = archive
+        .read_by_name(entry)
+        .map_err(|err| anyhow!("failed to read {entry} from {:?}: {err}", path))?;
+    let (car_bytes, cosignature) = if entry == crate::car_cbor::BUNDLE_ENTRY {
+        let (value, cosignature) = cosign_json(&crate::car_cbor::decode_to_json(&encoded)?)?;
+        (crate::car_cbor::encode(&value), cosignature)
+    } else {
+        let (value, cosignature) = cosign_json(&encoded)?;
+        (serde_json::to_vec_pretty(&value)?, cosignature)
+    };
+
+    // Write the new bundle next to the old one and swap it in once complete
+    let tmp_path = path.with_extension("cosign.tmp");
+    let written = (|| -> Result<()> {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&tmp_path)?);
+        zip.start_file(entry, FileOptions::default())?;
+        zip.write_all(&car_bytes)?;
+        for index in 0..archive.len() {
+            if archive.names()[index] != entry {
+                archive.raw_copy_into(index, &mut zip)?;
+            }
+        }
+        zip.finish()?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    written.with_context(|| format!("Failed to rewrite CAR bundle {:?}", path))?;
+    Ok(cosignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn car_with_claims(provenance: Value) -> Car {
+        serde_json::from_value(json!({
+            "id": "car:test",
+            "run_id": "run-1",
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {"kind": "exact", "name": "test", "model": "stub-model", "version": "1", "seed": 1, "steps": []},
+            "proof": {"match_kind": "process", "process": {"sequential_checkpoints": [{
+                "id": "ck-1", "prev_chain": "", "curr_chain": "c", "signature": "s",
+                "run_id": "run-1", "kind": "Step", "timestamp": "2025-01-01T00:00:00Z",
+                "outputs_sha256": "ab".repeat(32),
+                "usage_tokens": 0, "prompt_tokens": 0, "completion_tokens": 0
+            }]}},
+            "policy_ref": {"hash": "h", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 0, "nature_cost": 0.0},
+            "provenance": provenance,
+            "checkpoints": ["ck-1"],
+            "sgrade": {"score": 0, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": "",
+            "signatures": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn extension_claims_are_validated_only_when_registered() {
+        let digest = format!("sha256:{}", "ab".repeat(32));
+        let car = car_with_claims(json!([
+            {"claim_type": "output", "sha256": digest},
+            {"claim_type": "dataset", "sha256": digest, "data": {"rows": "many"}},
+        ]));
+
+        let report = ClaimRegistry::builtin().verify(&car);
+        assert_eq!(report.verified(), 1);
+        assert_eq!(report.unvalidated_types(), vec!["dataset".to_string()]);
+
+        let mut registry = ClaimRegistry::builtin();
+        registry.register(ClaimType {
+            name: "dataset".to_string(),
+            schema: Some(json!({"type": "object", "properties": {"rows": {"type": "integer"}}})),
+            validate: |_, _| Ok(()),
+        });
+        let report = registry.verify(&car);
+        assert_eq!(report.failures().count(), 1);
+        assert!(report.unvalidated_types().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "app")]
+    fn model_weights_claims_must_reference_a_step_checkpoint_using_the_model() {
+        let weights = store::model_weights::ModelWeights {
+            id: "w-1".to_string(),
+            model_id: "llama-ft".to_string(),
+            weights_sha256: "cd".repeat(32),
+            weights_path: None,
+            training_car_id: Some("car:training".to_string()),
+            registered_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        let claims = vec![
+            model_weights_claim(&weights, "ck-1"),
+            model_weights_claim(&weights, "ck-missing"),
+        ];
+        assert_eq!(claims[0].data.as_ref().unwrap()["trainingCarId"], "car:training");
+
+        let mut car = car_with_claims(serde_json::to_value(&claims).unwrap());
+        car.run.steps = serde_json::from_value(json!([{
+            "id": "step-1", "runId": "run-1", "orderIndex": 0,
+            "checkpointType": "Step", "model": "llama-ft"
+        }]))
+        .unwrap();
+
+        let report = ClaimRegistry::builtin().verify(&car);
+        assert_eq!(report.checks[0].status, ClaimStatus::Verified);
+        assert!(matches!(report.checks[1].status, ClaimStatus::Failed { .. }));
+
+        car.run.steps[0].model = Some("base-model".to_string());
+        let report = ClaimRegistry::builtin().verify(&car);
+        assert_eq!(report.failures().count(), 2);
+    }
+
+    #[test]
+    fn checkpoint_reference_claims_must_name_a_consumed_checkpoint() {
+        let digest = format!("sha256:{}", "ef".repeat(32));
+        let mut car = car_with_claims(json!([
+            {"claim_type": "checkpoint_reference", "sha256": digest,
+             "data": {"checkpointId": "ck-old", "runId": "run-0", "signature": "sig"}},
+            {"claim_type": "checkpoint_reference", "sha256": digest,
+             "data": {"checkpointId": "ck-other", "runId": "run-0", "signature": "sig"}},
+            {"claim_type": "checkpoint_reference", "sha256": digest,
+             "data": {"checkpointId": "ck-old"}},
+        ]));
+        let config = json!({"stepType": "prompt", "model": "stub-model", "prompt": "Q", "useOutputFrom": "ck-old"});
+        car.run.steps = serde_json::from_value(json!([{
+            "id": "step-1", "runId": "run-1", "orderIndex": 0,
+            "checkpointType": "Step", "configJson": config.to_string()
+        }]))
+        .unwrap();
+
+        let report = ClaimRegistry::builtin().verify(&car);
+        assert_eq!(report.checks[0].status, ClaimStatus::Verified);
+        assert!(matches!(report.checks[1].status, ClaimStatus::Failed { .. }));
+        assert!(matches!(report.checks[2].status, ClaimStatus::Failed { .. }));
+    }
+
+    #[test]
+    fn compressed_attachments_are_named_and_bounded_by_their_encoding() {
+        let content = "an ingested document ".repeat(100);
+        let hash = provenance::sha256_hex(content.as_bytes());
+        let manifest = AttachmentManifest {
+            compression: Some(AttachmentCompression::Zstd),
+            ..AttachmentManifest::new([hash.clone()])
+        };
+
+        let name = manifest.entry_name(&hash);
+        assert_eq!(name, format!("attachments/{hash}.txt.zst"));
+        let (parsed, compression) = parse_attachment_entry(&name).unwrap();
+        assert_eq!((parsed, compression), (hash.as_str(), manifest.compression));
+        assert!(manifest.check_encoding(&hash, None).is_err());
+
+        let compressed = AttachmentCompression::Zstd.compress(content.as_bytes());
+        assert!(compressed.len() < content.len());
+        let restored = AttachmentCompression::Zstd.decompress(&compressed, 1 << 20).unwrap();
+        assert_eq!(provenance::sha256_hex(&restored), hash);
+        assert!(AttachmentCompression::Zstd.decompress(&compressed, 100).is_err());
+        assert_eq!(parse_attachment_entry("car.json"), None);
+    }
+
+    #[test]
+    fn c2pa_manifests_carry_claims_signer_and_checkpoint_hashes() {
+        let digest = format!("sha256:{}", "ab".repeat(32));
+        let mut car = car_with_claims(json!([{"claim_type": "output", "sha256": digest}]));
+        car.signer_public_key = "cd".repeat(32);
+        car.signatures = vec!["ed25519-body:sig".to_string()];
+
+        let manifest = export_c2pa_manifest(&car);
+        assert_eq!(manifest.instance_id, "car:test");
+        assert_eq!(manifest.format, CAR_MEDIA_TYPE);
+        assert_eq!(manifest.signature_info.public_key, car.signer_public_key);
+        assert_eq!(manifest.signature_info.signatures, car.signatures);
+
+        let assertion = |label: &str| {
+            manifest
+                .assertions
+                .iter()
+                .find(|assertion| assertion.label == label)
+                .map(|assertion| assertion.data.clone())
+                .unwrap()
+        };
+        assert_eq!(assertion("c2pa.actions")["actions"][0]["action"], "c2pa.created");
+        assert_eq!(
+            assertion("org.intelexta.provenance")["claims"][0],
+            json!({"claim_type": "output", "alg": "sha256", "hash": "ab".repeat(32)})
+        );
+        let checkpoints = assertion("org.intelexta.checkpoints");
+        assert_eq!(checkpoints["checkpoints"][0]["id"], "ck-1");
+        assert_eq!(checkpoints["checkpoints"][0]["curr_chain"], "c");
+        assert!(!manifest
+            .assertions
+            .iter()
+            .any(|assertion| assertion.label == "org.intelexta.attachments"));
+    }
+}
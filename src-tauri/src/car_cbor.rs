@@ -0,0 +1,177 @@
+// src-tauri/src/car_cbor.rs
+//!
+//! DAG-CBOR encoding of CARs
+//!
+//! Large runs make `car.json` slow to write and parse, so a CAR can also be
+//! exported as DAG-CBOR. The document is an envelope naming its format and
+//! version around the CAR's JSON value:
+//!
+//! ```text
+//! { "format": "intelexta-car+dag-cbor", "version": 1, "car": { ... } }
+//! ```
+//!
+//! The body signature covers the canonical JSON of the CAR, so verifiers
+//! decode the envelope back into that JSON value and check it as usual.
+//! Encoding follows the DAG-CBOR rules (shortest integer heads, 64-bit
+//! floats, map keys sorted by length then bytes, no indefinite lengths), so
+//! a CAR has exactly one encoding.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+
+pub const FORMAT: &str = "intelexta-car+dag-cbor";
+pub const VERSION: u64 = 1;
+
+/// Name of the encoded CAR inside a bundle, in place of `car.json`
+pub const BUNDLE_ENTRY: &str = "car.cbor";
+
+/// Encode a CAR, given as the JSON value it is signed over
+pub fn encode(car: &Value) -> Vec<u8> {
+    let envelope = serde_json::json!({
+        "format": FORMAT,
+        "version": VERSION,
+        "car": car,
+    });
+    let mut out = Vec::new();
+    write_value(&mut out, &envelope);
+    out
+}
+
+/// Decode an envelope back into the CAR's JSON value
+pub fn decode(bytes: &[u8]) -> Result<Value> {
+    let envelope: Value =
+        ciborium::de::from_reader(bytes).context("CAR is not valid CBOR")?;
+    let Value::Object(mut fields) = envelope else {
+        bail!("CBOR document is not an encoded CAR");
+    };
+    match fields.get("format").and_then(Value::as_str) {
+        Some(FORMAT) => {}
+        Some(other) => bail!("unsupported CAR encoding '{other}'"),
+        None => bail!("CBOR document is not an encoded CAR"),
+    }
+    match fields.get("version").and_then(Value::as_u64) {
+        Some(VERSION) => {}
+        Some(other) => bail!("unsupported {FORMAT} version {other}"),
+        None => bail!("encoded CAR has no version"),
+    }
+    fields
+        .remove("car")
+        .filter(Value::is_object)
+        .ok_or_else(|| anyhow!("encoded CAR has no car body"))
+}
+
+/// Decode an envelope into `car.json` bytes for the JSON verifiers
+pub fn decode_to_json(bytes: &[u8]) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&decode(bytes)?)?)
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        out.push(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(n as u8);
+    } else if n <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(false) => out.push(0xf4),
+        Value::Bool(true) => out.push(0xf5),
+        Value::Number(number) => {
+            if let Some(n) = number.as_u64() {
+                write_head(out, 0, n);
+            } else if let Some(n) = number.as_i64() {
+                write_head(out, 1, (-1 - n) as u64);
+            } else {
+                // serde_json numbers are always finite
+                let n = number.as_f64().unwrap_or_default();
+                out.push(0xfb);
+                out.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+        Value::String(text) => {
+            write_head(out, 3, text.len() as u64);
+            out.extend_from_slice(text.as_bytes());
+        }
+        Value::Array(items) => {
+            write_head(out, 4, items.len() as u64);
+            for item in items {
+                write_value(out, item);
+            }
+        }
+        Value::Object(fields) => {
+            let mut entries: Vec<_> = fields.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| {
+                a.len().cmp(&b.len()).then_with(|| a.as_bytes().cmp(b.as_bytes()))
+            });
+            write_head(out, 5, entries.len() as u64);
+            for (key, item) in entries {
+                write_head(out, 3, key.len() as u64);
+                out.extend_from_slice(key.as_bytes());
+                write_value(out, item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cars_round_trip_through_the_envelope() {
+        let car = json!({
+            "id": "car:test",
+            "budgets": {"usd": 0.25, "tokens": 70000, "nature_cost": 0.0},
+            "sgrade": {"score": 87, "delta": -3},
+            "signatures": ["ed25519-body:abc"],
+            "provenance": [],
+            "sampler": null,
+            "replayable": true
+        });
+        let encoded = encode(&car);
+        assert_eq!(decode(&encoded).unwrap(), car);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&decode_to_json(&encoded).unwrap()).unwrap(),
+            car
+        );
+    }
+
+    #[test]
+    fn encoding_is_deterministic_dag_cbor() {
+        // Keys sort by length first, integers take the shortest head and
+        // floats are always 64-bit
+        let mut out = Vec::new();
+        write_value(&mut out, &json!({"bb": 1.5, "a": 500, "c": -1}));
+        let mut expected = vec![0xa3, 0x61, b'a', 0x19, 0x01, 0xf4, 0x61, b'c', 0x20];
+        expected.extend_from_slice(&[0x62, b'b', b'b', 0xfb]);
+        expected.extend_from_slice(&1.5f64.to_be_bytes());
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn other_documents_are_rejected() {
+        let mut out = Vec::new();
+        write_value(&mut out, &json!({"format": "something-else", "version": 1, "car": {}}));
+        assert!(decode(&out).unwrap_err().to_string().contains("unsupported"));
+
+        let mut out = Vec::new();
+        write_value(&mut out, &json!({"format": FORMAT, "version": 2, "car": {}}));
+        assert!(decode(&out).unwrap_err().to_string().contains("version 2"));
+
+        assert!(decode(b"{\"id\": 1}").is_err());
+    }
+}
@@ -0,0 +1,373 @@
+// src-tauri/src/car_diff.rs
+//!
+//! Structural comparison of two CARs
+//!
+//! Reviewing a re-run against its baseline means asking what changed between
+//! the two receipts. `diff` answers section by section: the run spec, the
+//! models, the policy, the checkpoint chain, token usage and the provenance
+//! claims. Values that differ by construction between any two runs (run and
+//! step IDs, checkpoint IDs, timestamps, chain hashes and signatures) are
+//! left out, so an unchanged re-run diffs empty. The receipts are compared
+//! as they are; neither is verified.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::car::{Car, ProcessCheckpointProof};
+
+/// What changed between two CARs, by section
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CarDiff {
+    pub left: String,
+    pub right: String,
+    /// Sections with at least one change, in the order `diff` checks them
+    pub sections: Vec<SectionDiff>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SectionDiff {
+    /// `run_spec`, `models`, `policy`, `checkpoints`, `token_usage` or `provenance`
+    pub section: &'static str,
+    pub changes: Vec<Change>,
+}
+
+/// One value that differs; `None` on a side that does not have it
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Location of the value within the section, e.g. `steps[1].config.prompt`
+    pub path: String,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+impl CarDiff {
+    /// Whether the two CARs agree in every compared section
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
+
+/// Compare `left`, usually the baseline, with `right`
+pub fn diff(left: &Car, right: &Car) -> CarDiff {
+    let sections = [
+        ("run_spec", run_spec(left), run_spec(right)),
+        ("models", models(left), models(right)),
+        (
+            "policy",
+            to_value(&left.policy_ref),
+            to_value(&right.policy_ref),
+        ),
+        ("checkpoints", checkpoints(left), checkpoints(right)),
+        ("token_usage", token_usage(left), token_usage(right)),
+        ("provenance", provenance(left), provenance(right)),
+    ];
+    CarDiff {
+        left: left.id.clone(),
+        right: right.id.clone(),
+        sections: sections
+            .into_iter()
+            .filter_map(|(section, left, right)| {
+                let mut changes = Vec::new();
+                diff_values("", &left, &right, &mut changes);
+                (!changes.is_empty()).then_some(SectionDiff { section, changes })
+            })
+            .collect(),
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Run name, kind, seed and steps; step configs are compared field by field
+fn run_spec(car: &Car) -> Value {
+    let steps: Vec<Value> = car
+        .run
+        .steps
+        .iter()
+        .map(|step| {
+            let mut step = to_value(step);
+            if let Value::Object(fields) = &mut step {
+                fields.remove("id");
+                fields.remove("runId");
+                if let Some(Value::String(json)) = fields.remove("configJson") {
+                    let config = serde_json::from_str(&json).unwrap_or(Value::String(json));
+                    fields.insert("config".to_string(), config);
+                }
+            }
+            step
+        })
+        .collect();
+    serde_json::json!({
+        "name": car.run.name,
+        "kind": car.run.kind,
+        "seed": car.run.seed,
+        "steps": steps,
+    })
+}
+
+/// The run's model label, sampler and the models its steps call, and the
+/// provider that answered each checkpoint
+fn models(car: &Car) -> Value {
+    let mut step_models: Vec<&str> = car
+        .run
+        .steps
+        .iter()
+        .filter_map(|step| step.model.as_deref())
+        .collect();
+    step_models.sort_unstable();
+    step_models.dedup();
+    let providers: Vec<Option<&str>> = latest_checkpoints(car)
+        .iter()
+        .map(|checkpoint| {
+            let metadata = checkpoint.response_metadata.as_ref();
+            metadata.map(|metadata| metadata.provider.as_str())
+        })
+        .collect();
+    serde_json::json!({
+        "run": car.run.model,
+        "sampler": car.run.sampler,
+        "steps": step_models,
+        "providers": providers,
+    })
+}
+
+/// Checkpoints of the latest execution: their kind and what they hashed
+fn checkpoints(car: &Car) -> Value {
+    let checkpoints: Vec<Value> = latest_checkpoints(car)
+        .iter()
+        .map(|checkpoint| {
+            serde_json::json!({
+                "kind": checkpoint.kind,
+                "inputs_sha256": checkpoint.inputs_sha256,
+                "outputs_sha256": checkpoint.outputs_sha256,
+                "cache_hit": checkpoint.cache_hit,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "count": checkpoints.len(),
+        "executions": car.proof.executions.len(),
+        "chain": checkpoints,
+    })
+}
+
+/// Budgets and the token counts of every checkpoint, with their totals
+fn token_usage(car: &Car) -> Value {
+    let checkpoints = latest_checkpoints(car);
+    let per_checkpoint: Vec<Value> = checkpoints
+        .iter()
+        .map(|checkpoint| {
+            serde_json::json!({
+                "usage": checkpoint.usage_tokens,
+                "prompt": checkpoint.prompt_tokens,
+                "completion": checkpoint.completion_tokens,
+            })
+        })
+        .collect();
+    let total = |count: fn(&ProcessCheckpointProof) -> u64| -> u64 {
+        checkpoints.iter().map(count).sum()
+    };
+    serde_json::json!({
+        "budgets": car.budgets,
+        "total_usage": total(|checkpoint| checkpoint.usage_tokens),
+        "total_prompt": total(|checkpoint| checkpoint.prompt_tokens),
+        "total_completion": total(|checkpoint| checkpoint.completion_tokens),
+        "checkpoints": per_checkpoint,
+    })
+}
+
+/// Claim hashes by claim type, sorted, so reordered claims do not show
+fn provenance(car: &Car) -> Value {
+    let mut claims = serde_json::Map::new();
+    for claim in &car.provenance {
+        let hashes = claims
+            .entry(claim.claim_type.clone())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(hashes) = hashes {
+            hashes.push(Value::String(claim.sha256.clone()));
+        }
+    }
+    for hashes in claims.values_mut() {
+        if let Value::Array(hashes) = hashes {
+            hashes.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+        }
+    }
+    Value::Object(claims)
+}
+
+fn latest_checkpoints(car: &Car) -> &[ProcessCheckpointProof] {
+    car.proof
+        .process
+        .as_ref()
+        .map(|process| process.sequential_checkpoints.as_slice())
+        .unwrap_or_default()
+}
+
+/// Collect the leaves where `left` and `right` differ. Objects are compared
+/// key by key and arrays index by index; anything else is compared whole.
+pub(crate) fn diff_values(
+    path: &str,
+    left: &Value,
+    right: &Value,
+    changes: &mut Vec<Change>,
+) {
+    let child = |key: &str| match path {
+        "" => key.to_string(),
+        _ => format!("{path}.{key}"),
+    };
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                match (left.get(key), right.get(key)) {
+                    (Some(l), Some(r)) => diff_values(&child(key), l, r, changes),
+                    (l, r) => push_change(child(key), l, r, changes),
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for index in 0..left.len().max(right.len()) {
+                let path = format!("{path}[{index}]");
+                match (left.get(index), right.get(index)) {
+                    (Some(l), Some(r)) => diff_values(&path, l, r, changes),
+                    (l, r) => push_change(path, l, r, changes),
+                }
+            }
+        }
+        _ if left != right => push_change(path.to_string(), Some(left), Some(right), changes),
+        _ => {}
+    }
+}
+
+fn push_change(
+    path: String,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    changes: &mut Vec<Change>,
+) {
+    // A null and a missing value say the same
+    let left = left.filter(|value| !value.is_null()).cloned();
+    let right = right.filter(|value| !value.is_null()).cloned();
+    if left != right {
+        changes.push(Change { path, left, right });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn car(id: &str) -> Car {
+        serde_json::from_value(json!({
+            "id": id,
+            "run_id": format!("run-{id}"),
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {
+                "kind": "exact", "name": "nightly", "model": "workflow:nightly",
+                "version": "1", "seed": 7,
+                "steps": [{
+                    "id": format!("step-{id}"), "runId": format!("run-{id}"), "orderIndex": 0,
+                    "checkpointType": "Step", "stepType": "prompt", "model": "stub-model",
+                    "tokenBudget": 100, "proofMode": "exact",
+                    "configJson": "{\"stepType\":\"prompt\",\"prompt\":\"Summarize\"}"
+                }]
+            },
+            "proof": {"match_kind": "process", "process": {"sequential_checkpoints": [{
+                "id": format!("ck-{id}"), "prev_chain": "", "curr_chain": format!("chain-{id}"),
+                "signature": format!("sig-{id}"), "run_id": format!("run-{id}"), "kind": "Step",
+                "timestamp": "2025-01-01T00:00:00Z", "outputs_sha256": "aa",
+                "usage_tokens": 10, "prompt_tokens": 6, "completion_tokens": 4
+            }]}},
+            "policy_ref": {"hash": "sha256:p", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 10, "nature_cost": 0.0},
+            "provenance": [
+                {"claim_type": "output", "sha256": "sha256:bb"},
+                {"claim_type": "config", "sha256": "sha256:cc"}
+            ],
+            "checkpoints": [format!("ck-{id}")],
+            "sgrade": {"score": 0, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": "",
+            "signatures": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn identical_reruns_diff_empty() {
+        let mut rerun = car("car:rerun");
+        rerun.provenance.reverse();
+        let diff = diff(&car("car:base"), &rerun);
+        assert!(diff.is_empty(), "{:?}", diff.sections);
+        assert_eq!(
+            (diff.left.as_str(), diff.right.as_str()),
+            ("car:base", "car:rerun")
+        );
+    }
+
+    #[test]
+    fn changes_are_reported_by_section_and_path() {
+        let base = car("car:base");
+        let mut rerun = car("car:rerun");
+        rerun.run.steps[0].config_json =
+            Some(r#"{"stepType":"prompt","prompt":"Summarize briefly"}"#.to_string());
+        rerun.run.steps[0].model = Some("other-model".to_string());
+        rerun.policy_ref.egress = true;
+        let checkpoint = &mut rerun.proof.process.as_mut().unwrap().sequential_checkpoints[0];
+        checkpoint.outputs_sha256 = Some("ab".to_string());
+        checkpoint.completion_tokens = 9;
+        rerun.provenance[0].sha256 = "sha256:bd".to_string();
+
+        let diff = diff(&base, &rerun);
+        let sections: Vec<&str> = diff
+            .sections
+            .iter()
+            .map(|section| section.section)
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                "run_spec",
+                "models",
+                "policy",
+                "checkpoints",
+                "token_usage",
+                "provenance"
+            ]
+        );
+        let paths = |section: usize| -> Vec<&str> {
+            diff.sections[section]
+                .changes
+                .iter()
+                .map(|change| change.path.as_str())
+                .collect()
+        };
+        assert_eq!(paths(0), ["steps[0].config.prompt", "steps[0].model"]);
+        assert_eq!(paths(1), ["steps[0]"]);
+        assert_eq!(paths(2), ["egress"]);
+        assert_eq!(paths(3), ["chain[0].outputs_sha256"]);
+        assert_eq!(paths(4), ["checkpoints[0].completion", "total_completion"]);
+        assert_eq!(paths(5), ["output[0]"]);
+        assert_eq!(
+            diff.sections[2].changes[0],
+            Change {
+                path: "egress".to_string(),
+                left: Some(json!(false)),
+                right: Some(json!(true)),
+            }
+        );
+
+        // A claim only one side has
+        rerun.provenance.pop();
+        let diff = super::diff(&base, &rerun);
+        let provenance = diff.sections.last().unwrap();
+        assert!(provenance.changes.contains(&Change {
+            path: "config".to_string(),
+            left: Some(json!(["sha256:cc"])),
+            right: None,
+        }));
+    }
+}
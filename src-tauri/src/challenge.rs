@@ -0,0 +1,424 @@
+// src-tauri/src/challenge.rs
+//!
+//! Verification Challenges: two-party proof of fresh re-execution
+//!
+//! A CAR shows that outputs were recorded, not that the prover can still
+//! produce them. A challenge session closes that gap:
+//!
+//! 1. The auditor issues a `Challenge` naming one step of a run, carrying a
+//!    random nonce and an expiry.
+//! 2. The prover re-executes that step and answers with a `ChallengeProof`, a
+//!    signed mini-CAR holding the fresh output and a commitment
+//!    `sha256(nonce || output)`.
+//! 3. The auditor checks the proof against the challenge it issued.
+//!
+//! The nonce cannot be known before the challenge is issued and the expiry
+//! bounds the time to answer, so a proof is hard to assemble from cached
+//! outputs. The fresh output is also compared with the run's recorded
+//! checkpoint: by digest for exact steps, by semantic distance otherwise.
+
+use crate::orchestrator::{self, LlmClient, RunProofMode};
+use crate::{provenance, store, DbPool};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rand::{rngs::OsRng, RngCore};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Time the prover has to answer when the auditor does not choose one
+pub const DEFAULT_CHALLENGE_TTL_SECS: i64 = 300;
+
+const NONCE_BYTES: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Challenge {
+    pub id: String,
+    pub run_id: String,
+    pub order_index: i64,
+    /// Hex-encoded random nonce
+    pub nonce: String,
+    pub issued_at: String,
+    pub expires_at: String,
+}
+
+/// The step that was re-executed, as configured in the prover's run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengedStep {
+    pub order_index: i64,
+    pub step_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub proof_mode: RunProofMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+    /// sha256 of the prompt, or of the config JSON for ingest steps
+    pub inputs_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeProof {
+    pub id: String, // "challenge-car:..." - sha256 of the canonical body
+    pub challenge: Challenge,
+    pub step: ChallengedStep,
+    pub started_at: String,
+    pub completed_at: String,
+    pub output: String,
+    pub outputs_sha256: String,
+    pub semantic_digest: String,
+    /// sha256(nonce bytes || output bytes)
+    pub commitment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_outputs_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_semantic_digest: Option<String>,
+    pub signer_public_key: String,
+    pub signatures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeVerification {
+    pub valid: bool,
+    /// Whether the fresh output agrees with the recorded checkpoint
+    pub matches_original: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantic_distance: Option<f64>,
+    pub errors: Vec<String>,
+}
+
+/// Create a challenge for one step of a run. The auditor keeps a copy to
+/// check the proof against.
+pub fn issue_challenge(run_id: &str, order_index: i64, ttl_secs: Option<i64>) -> Challenge {
+    let mut nonce = [0u8; NONCE_BYTES];
+    OsRng.fill_bytes(&mut nonce);
+    let issued_at = Utc::now();
+    let ttl = ttl_secs.unwrap_or(DEFAULT_CHALLENGE_TTL_SECS).max(1);
+    Challenge {
+        id: Uuid::new_v4().to_string(),
+        run_id: run_id.to_string(),
+        order_index,
+        nonce: hex::encode(nonce),
+        issued_at: issued_at.to_rfc3339(),
+        expires_at: (issued_at + Duration::seconds(ttl)).to_rfc3339(),
+    }
+}
+
+pub fn commitment(nonce_hex: &str, output: &str) -> Result<String> {
+    let mut bytes = hex::decode(nonce_hex).context("challenge nonce is not valid hex")?;
+    if bytes.len() != NONCE_BYTES {
+        return Err(anyhow!("challenge nonce must be {NONCE_BYTES} bytes"));
+    }
+    bytes.extend_from_slice(output.as_bytes());
+    Ok(provenance::sha256_hex(&bytes))
+}
+
+/// Re-execute the challenged step and sign the result with the project key
+pub fn respond_to_challenge(
+    pool: &DbPool,
+    challenge: &Challenge,
+    llm_client: &dyn LlmClient,
+) -> Result<ChallengeProof> {
+    let started_at = Utc::now();
+    if started_at > parse_time(&challenge.expires_at)? {
+        return Err(anyhow!("challenge {} has expired", challenge.id));
+    }
+    // Reject malformed nonces before spending tokens on the step
+    commitment(&challenge.nonce, "")?;
+
+    let conn = pool.get()?;
+    let run = orchestrator::load_stored_run(&conn, &challenge.run_id)?;
+    let step = run
+        .steps
+        .iter()
+        .find(|step| step.order_index == challenge.order_index)
+        .ok_or_else(|| {
+            anyhow!(
+                "run {} has no step at position {}",
+                run.id,
+                challenge.order_index
+            )
+        })?;
+    if step.is_interactive_chat() || !(step.is_llm_step() || step.is_document_ingestion()) {
+        return Err(anyhow!(
+            "only LLM and ingest steps can be challenged, step {} is '{}'",
+            step.order_index,
+            step.step_type
+        ));
+    }
+
+    let (original_outputs_sha256, original_semantic_digest) = conn
+        .query_row(
+            "SELECT outputs_sha256, semantic_digest FROM checkpoints
+             WHERE run_id = ?1 AND checkpoint_config_id = ?2 AND kind = 'Step' AND parent_checkpoint_id IS NULL
+             ORDER BY timestamp DESC, id DESC LIMIT 1",
+            params![&run.id, &step.id],
+            |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?)),
+        )
+        .optional()?
+        .unwrap_or_default();
+    let pubkey: String = conn.query_row(
+        "SELECT pubkey FROM projects WHERE id = ?1",
+        params![&run.project_id],
+        |row| row.get(0),
+    )?;
+    let policy =
+        store::policies::get_for_policy_version(&conn, &run.project_id, run.policy_version)?;
+    drop(conn);
+
+    let node = orchestrator::execute_checkpoint(step, run.seed, llm_client, &policy)?;
+    let completed_at = Utc::now();
+    let output = node.output_payload.unwrap_or_default();
+
+    let mut proof = ChallengeProof {
+        id: String::new(),
+        challenge: challenge.clone(),
+        step: ChallengedStep {
+            order_index: step.order_index,
+            step_type: step.step_type.clone(),
+            model: step.model.clone(),
+            proof_mode: step.proof_mode,
+            epsilon: step.epsilon.or(run.epsilon),
+            inputs_sha256: node.inputs_sha256.unwrap_or_default(),
+        },
+        started_at: started_at.to_rfc3339(),
+        completed_at: completed_at.to_rfc3339(),
+        commitment: commitment(&challenge.nonce, &output)?,
+        output,
+        outputs_sha256: node.outputs_sha256.unwrap_or_default(),
+        semantic_digest: node.semantic_digest.unwrap_or_default(),
+        original_outputs_sha256,
+        original_semantic_digest,
+        signer_public_key: pubkey,
+        signatures: Vec::new(),
+    };
+    proof.id = format!("challenge-car:{}", provenance::sha256_hex(&proof_body(&proof, true)?));
+
+    let signer = provenance::ProjectSigner::load(&run.project_id)
+        .with_context(|| format!("failed to load signing key for project {}", run.project_id))?;
+    let signature = signer.sign(&proof_body(&proof, false)?)?;
+    proof.signatures.push(format!("ed25519-body:{signature}"));
+
+    Ok(proof)
+}
+
+/// Check a proof against the challenge the auditor issued. When
+/// `expected_public_key` is given the proof must be signed by that key.
+pub fn verify_challenge_proof(
+    proof: &ChallengeProof,
+    challenge: &Challenge,
+    expected_public_key: Option<&str>,
+) -> ChallengeVerification {
+    let mut errors = Vec::new();
+
+    if &proof.challenge != challenge {
+        errors.push("proof does not answer the issued challenge".to_string());
+    }
+    if proof.step.order_index != challenge.order_index {
+        errors.push("proof re-executed a different step".to_string());
+    }
+    if let Err(err) = check_timing(proof, challenge) {
+        errors.push(err.to_string());
+    }
+    match commitment(&challenge.nonce, &proof.output) {
+        Ok(expected) if expected == proof.commitment => {}
+        Ok(_) => errors.push("commitment does not match nonce and output".to_string()),
+        Err(err) => errors.push(err.to_string()),
+    }
+    match proof_body(proof, true) {
+        Ok(body) => {
+            if proof.id != format!("challenge-car:{}", provenance::sha256_hex(&body)) {
+                errors.push("proof id does not match its contents".to_string());
+            }
+        }
+        Err(err) => errors.push(err.to_string()),
+    }
+    if let Some(expected) = expected_public_key {
+        if proof.signer_public_key != expected {
+            errors.push("proof was signed by an unexpected key".to_string());
+        }
+    }
+    if let Err(err) = check_signature(proof) {
+        errors.push(err.to_string());
+    }
+
+    let (matches_original, semantic_distance) = compare_with_original(proof);
+    ChallengeVerification {
+        valid: errors.is_empty(),
+        matches_original,
+        semantic_distance,
+        errors,
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(value)
+        .with_context(|| format!("invalid timestamp '{value}'"))?
+        .with_timezone(&Utc))
+}
+
+fn check_timing(proof: &ChallengeProof, challenge: &Challenge) -> Result<()> {
+    let issued_at = parse_time(&challenge.issued_at)?;
+    let expires_at = parse_time(&challenge.expires_at)?;
+    let started_at = parse_time(&proof.started_at)?;
+    let completed_at = parse_time(&proof.completed_at)?;
+    if started_at < issued_at || completed_at < started_at {
+        return Err(anyhow!("proof timestamps precede the challenge"));
+    }
+    if completed_at > expires_at {
+        return Err(anyhow!("proof was completed after the challenge expired"));
+    }
+    Ok(())
+}
+
+/// Canonical body that is hashed into the id (`without_id`) or signed
+fn proof_body(proof: &ChallengeProof, without_id: bool) -> Result<Vec<u8>> {
+    let mut body = serde_json::to_value(proof)?;
+    if let serde_json::Value::Object(ref mut obj) = body {
+        obj.remove("signatures");
+        if without_id {
+            obj.remove("id");
+        }
+    }
+    Ok(provenance::canonical_json(&body))
+}
+
+fn check_signature(proof: &ChallengeProof) -> Result<()> {
+    let key_bytes: [u8; 32] = STANDARD
+        .decode(&proof.signer_public_key)
+        .context("signer public key is not valid base64")?
+        .try_into()
+        .map_err(|_| anyhow!("signer public key has invalid length"))?;
+    let verifying_key = VerifyingKey::from_bytes(&key_bytes)
+        .map_err(|err| anyhow!("invalid signer public key: {err}"))?;
+
+    let signature = proof
+        .signatures
+        .iter()
+        .find_map(|entry| entry.strip_prefix("ed25519-body:"))
+        .ok_or_else(|| anyhow!("proof is not signed"))?;
+    let signature_bytes: [u8; ed25519_dalek::SIGNATURE_LENGTH] = STANDARD
+        .decode(signature)
+        .context("proof signature is not valid base64")?
+        .try_into()
+        .map_err(|_| anyhow!("proof signature has invalid length"))?;
+
+    verifying_key
+        .verify(&proof_body(proof, false)?, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| anyhow!("proof signature verification failed"))
+}
+
+fn compare_with_original(proof: &ChallengeProof) -> (bool, Option<f64>) {
+    let Some(original) = proof.original_outputs_sha256.as_deref() else {
+        return (false, None);
+    };
+    let distance = proof
+        .original_semantic_digest
+        .as_deref()
+        .and_then(|digest| provenance::semantic_distance(digest, &proof.semantic_digest))
+        .map(|distance| distance as f64 / 64.0);
+
+    let matches = match proof.step.proof_mode {
+        RunProofMode::Exact => original == proof.outputs_sha256,
+        RunProofMode::Concordant => {
+            original == proof.outputs_sha256
+                || matches!(
+                    (distance, proof.step.epsilon),
+                    (Some(distance), Some(epsilon)) if distance <= epsilon
+                )
+        }
+    };
+    (matches, distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_proof(challenge: &Challenge) -> ChallengeProof {
+        let output = "fresh output".to_string();
+        ChallengeProof {
+            id: String::new(),
+            challenge: challenge.clone(),
+            step: ChallengedStep {
+                order_index: challenge.order_index,
+                step_type: "llm".to_string(),
+                model: Some("stub-model".to_string()),
+                proof_mode: RunProofMode::Exact,
+                epsilon: None,
+                inputs_sha256: provenance::sha256_hex(b"prompt"),
+            },
+            started_at: challenge.issued_at.clone(),
+            completed_at: challenge.issued_at.clone(),
+            commitment: commitment(&challenge.nonce, &output).unwrap(),
+            outputs_sha256: provenance::sha256_hex(output.as_bytes()),
+            semantic_digest: provenance::semantic_digest(&output),
+            original_outputs_sha256: Some(provenance::sha256_hex(output.as_bytes())),
+            original_semantic_digest: None,
+            output,
+            signer_public_key: String::new(),
+            signatures: Vec::new(),
+        }
+    }
+
+    fn sign(mut proof: ChallengeProof) -> ChallengeProof {
+        let keypair = provenance::generate_keypair();
+        let secret: [u8; 32] = STANDARD.decode(keypair.secret_key_b64).unwrap().try_into().unwrap();
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&secret);
+        proof.signer_public_key = keypair.public_key_b64;
+        proof.id = format!(
+            "challenge-car:{}",
+            provenance::sha256_hex(&proof_body(&proof, true).unwrap())
+        );
+        let signature = provenance::sign_bytes(&signing_key, &proof_body(&proof, false).unwrap());
+        proof.signatures = vec![format!("ed25519-body:{signature}")];
+        proof
+    }
+
+    #[test]
+    fn challenges_carry_fresh_nonces() {
+        let first = issue_challenge("run-1", 0, None);
+        let second = issue_challenge("run-1", 0, None);
+        assert_eq!(first.nonce.len(), NONCE_BYTES * 2);
+        assert_ne!(first.nonce, second.nonce);
+        assert!(commitment(&first.nonce, "x").unwrap() != commitment(&second.nonce, "x").unwrap());
+        assert!(commitment("abcd", "x").is_err());
+    }
+
+    #[test]
+    fn signed_proof_verifies_against_its_challenge() {
+        let challenge = issue_challenge("run-1", 2, Some(60));
+        let proof = sign(sample_proof(&challenge));
+
+        let verification = verify_challenge_proof(&proof, &challenge, Some(&proof.signer_public_key));
+        assert!(verification.valid, "{:?}", verification.errors);
+        assert!(verification.matches_original);
+    }
+
+    #[test]
+    fn proofs_for_other_challenges_or_tampered_outputs_fail() {
+        let challenge = issue_challenge("run-1", 2, Some(60));
+        let proof = sign(sample_proof(&challenge));
+
+        let other = issue_challenge("run-1", 2, Some(60));
+        assert!(!verify_challenge_proof(&proof, &other, None).valid);
+
+        let mut tampered = proof.clone();
+        tampered.output = "cached output".to_string();
+        let verification = verify_challenge_proof(&tampered, &challenge, None);
+        assert!(!verification.valid);
+        assert!(verification.errors.iter().any(|err| err.contains("commitment")));
+
+        let late = sign(ChallengeProof {
+            completed_at: (parse_time(&challenge.expires_at).unwrap() + Duration::seconds(1))
+                .to_rfc3339(),
+            ..sample_proof(&challenge)
+        });
+        assert!(!verify_challenge_proof(&late, &challenge, None).valid);
+    }
+}
@@ -0,0 +1,523 @@
+// src-tauri/src/cold_storage.rs
+//!
+//! Run archival to cold storage
+//!
+//! Archiving a completed run moves its checkpoint payloads (prompts and output
+//! previews), full-output attachments and persisted step outputs into a single
+//! deflated ZIP in the configured archive directory. Checkpoints, hashes and
+//! signatures stay in the database, so CARs can still be emitted and the run
+//! still shows up everywhere; only the bulky content goes cold.
+//!
+//! Archive layout:
+//! ```
+//! manifest.json          payloads plus sha256 of every other entry
+//! manifest.sig           Ed25519 signature (base64) over the canonical manifest
+//! attachments/{hash}.txt full outputs, keyed by content hash
+//! steps/{hash}.txt       step outputs, keyed by checkpoint outputs_sha256
+//! ```
+//!
+//! `rehydrate_run` checks the archive against the digest recorded when it was
+//! written and the manifest signature against the project key before putting
+//! anything back. Replay, export and chaining from an archived run's
+//! checkpoints are refused until the run is rehydrated.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::Utc;
+use ed25519_dalek::{Signature, SigningKey, Verifier, VerifyingKey};
+use intelexta_archive::{ArchiveLimits, SafeArchive};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+
+use crate::attachments::AttachmentStore;
+use crate::store::run_archives::{self, RunArchive};
+use crate::{portability, provenance, run_queue, DbPool, Error};
+
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// Directory under the app data dir used when no archive dir is configured
+pub const DEFAULT_ARCHIVE_DIR: &str = "cold_storage";
+
+const MANIFEST_PATH: &str = "manifest.json";
+const SIGNATURE_PATH: &str = "manifest.sig";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedPayload {
+    pub checkpoint_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_payload: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_payload: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveManifest {
+    pub version: u32,
+    pub run_id: String,
+    pub project_id: String,
+    pub archived_at: String,
+    pub payloads: Vec<ArchivedPayload>,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+fn attachment_entry(hash: &str) -> String {
+    format!("attachments/{hash}.txt")
+}
+
+fn step_output_entry(outputs_sha256: &str) -> String {
+    format!("steps/{outputs_sha256}.txt")
+}
+
+/// Archive directory from the settings, or the default under `base_dir`
+pub fn resolve_archive_dir(conn: &Connection, base_dir: &Path) -> Result<PathBuf, Error> {
+    Ok(run_archives::archive_dir(conn)?
+        .map(PathBuf::from)
+        .unwrap_or_else(|| base_dir.join(DEFAULT_ARCHIVE_DIR)))
+}
+
+/// Refuse operations that need the full payloads of an archived run
+pub fn ensure_hot(conn: &Connection, run_id: &str) -> Result<(), Error> {
+    if run_archives::is_cold(conn, run_id)? {
+        return Err(Error::Api(format!(
+            "run {run_id} is archived in cold storage; rehydrate it first"
+        )));
+    }
+    Ok(())
+}
+
+/// Write the archive, signing the canonical manifest with the project key;
+/// returns the base64 signature
+pub fn write_archive(
+    path: &Path,
+    manifest: &ArchiveManifest,
+    files: &[(String, Vec<u8>)],
+    signing_key: &SigningKey,
+) -> Result<String, Error> {
+    let signature = provenance::sign_bytes(signing_key, &provenance::canonical_json(manifest));
+    let manifest_json = serde_json::to_vec_pretty(manifest)
+        .map_err(|err| Error::Api(format!("failed to serialize archive manifest: {err}")))?;
+
+    let file = fs::File::create(path).map_err(|err| {
+        Error::Api(format!(
+            "failed to create archive {}: {err}",
+            path.display()
+        ))
+    })?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let entries = files
+        .iter()
+        .map(|(name, bytes)| (name.as_str(), bytes.as_slice()))
+        .chain([
+            (MANIFEST_PATH, manifest_json.as_slice()),
+            (SIGNATURE_PATH, signature.as_bytes()),
+        ]);
+    for (name, bytes) in entries {
+        zip.start_file(name, options)
+            .map_err(|err| Error::Api(format!("failed to add archive entry: {err}")))?;
+        zip.write_all(bytes)
+            .map_err(|err| Error::Api(format!("failed to write archive entry: {err}")))?;
+    }
+    zip.finish()
+        .map_err(|err| Error::Api(format!("failed to finalize archive: {err}")))?;
+    Ok(signature)
+}
+
+/// Read an archive, checking the manifest signature and the digest of every entry
+pub fn read_archive(
+    bytes: &[u8],
+    verifying_key: &VerifyingKey,
+) -> Result<(ArchiveManifest, HashMap<String, Vec<u8>>), Error> {
+    let mut archive = SafeArchive::new(Cursor::new(bytes), ArchiveLimits::default())
+        .map_err(|err| Error::Api(format!("invalid run archive: {err}")))?;
+    let mut read = |name: &str| {
+        archive
+            .read_by_name(name)
+            .map_err(|err| Error::Api(format!("invalid run archive: {err}")))
+    };
+
+    let manifest_json = read(MANIFEST_PATH)?;
+    let signature_b64 = read(SIGNATURE_PATH)?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&manifest_json)
+        .map_err(|err| Error::Api(format!("invalid archive manifest: {err}")))?;
+    if manifest.version != ARCHIVE_FORMAT_VERSION {
+        return Err(Error::Api(format!(
+            "unsupported run archive version {}",
+            manifest.version
+        )));
+    }
+
+    let signature_bytes: [u8; ed25519_dalek::SIGNATURE_LENGTH] = STANDARD
+        .decode(signature_b64.trim_ascii())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| Error::Api("archive signature is malformed".to_string()))?;
+    verifying_key
+        .verify(
+            &provenance::canonical_json(&manifest),
+            &Signature::from_bytes(&signature_bytes),
+        )
+        .map_err(|_| Error::Api("archive manifest signature is invalid".to_string()))?;
+
+    let mut files = HashMap::new();
+    for entry in &manifest.entries {
+        let content = read(&entry.path)?;
+        if provenance::sha256_hex(&content) != entry.sha256 {
+            return Err(Error::Api(format!(
+                "archive entry {} does not match its manifest digest",
+                entry.path
+            )));
+        }
+        files.insert(entry.path.clone(), content);
+    }
+    Ok((manifest, files))
+}
+
+fn run_project_id(conn: &Connection, run_id: &str) -> Result<String, Error> {
+    conn.query_row(
+        "SELECT project_id FROM runs WHERE id = ?1",
+        params![run_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| Error::Api(format!("run {run_id} not found")))
+}
+
+/// Content still needed hot by another run that has not been archived
+fn shared_with_hot_run(
+    conn: &Connection,
+    sql: &str,
+    hash: &str,
+    run_id: &str,
+) -> Result<bool, Error> {
+    Ok(conn
+        .query_row(sql, params![hash, run_id], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Move a completed run's payloads and attachments into a signed archive
+/// under `archive_dir/{project_id}`
+pub fn archive_run(
+    pool: &DbPool,
+    attachments: &AttachmentStore,
+    run_id: &str,
+    archive_dir: &Path,
+) -> Result<RunArchive, Error> {
+    let mut conn = pool.get()?;
+    let project_id = run_project_id(&conn, run_id)?;
+    ensure_hot(&conn, run_id)?;
+    if run_queue::global()
+        .active(Some(&project_id))
+        .iter()
+        .any(|entry| entry.run_id == run_id)
+    {
+        return Err(Error::Api(format!(
+            "run {run_id} is queued or running and cannot be archived"
+        )));
+    }
+    let executions: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM run_executions WHERE run_id = ?1",
+        params![run_id],
+        |row| row.get(0),
+    )?;
+    if executions == 0 {
+        return Err(Error::Api(format!("run {run_id} has not been executed")));
+    }
+
+    let mut payloads = Vec::new();
+    let mut attachment_hashes = BTreeSet::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT p.checkpoint_id, p.prompt_payload, p.output_payload, p.full_output_hash
+             FROM checkpoint_payloads p
+             JOIN checkpoints c ON c.id = p.checkpoint_id
+             WHERE c.run_id = ?1
+             ORDER BY p.checkpoint_id",
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| {
+            Ok((
+                ArchivedPayload {
+                    checkpoint_id: row.get(0)?,
+                    prompt_payload: row.get(1)?,
+                    output_payload: row.get(2)?,
+                },
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (payload, full_output_hash) = row?;
+            payloads.push(payload);
+            attachment_hashes.extend(full_output_hash);
+        }
+    }
+    let step_output_hashes: BTreeSet<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT outputs_sha256 FROM checkpoints
+             WHERE run_id = ?1 AND kind = 'Step' AND outputs_sha256 IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![run_id], |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<_, _>>()?
+    };
+
+    let load_err = |err: anyhow::Error| Error::Api(err.to_string());
+    let mut files = Vec::new();
+    for hash in &attachment_hashes {
+        if attachments.exists(hash) {
+            let content = attachments.load_full_output(hash).map_err(load_err)?;
+            files.push((attachment_entry(hash), content.into_bytes()));
+        }
+    }
+    let mut archived_step_outputs = Vec::new();
+    for hash in &step_output_hashes {
+        if let Some(content) = attachments.load_step_output(hash).map_err(load_err)? {
+            files.push((step_output_entry(hash), content.into_bytes()));
+            archived_step_outputs.push(hash);
+        }
+    }
+
+    let archived_at = Utc::now();
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_FORMAT_VERSION,
+        run_id: run_id.to_string(),
+        project_id: project_id.clone(),
+        archived_at: archived_at.to_rfc3339(),
+        payloads,
+        entries: files
+            .iter()
+            .map(|(path, bytes)| ArchiveEntry {
+                path: path.clone(),
+                sha256: provenance::sha256_hex(bytes),
+            })
+            .collect(),
+    };
+
+    let project_dir = archive_dir.join(&project_id);
+    fs::create_dir_all(&project_dir).map_err(|err| {
+        Error::Api(format!(
+            "failed to create archive dir {}: {err}",
+            project_dir.display()
+        ))
+    })?;
+    let archive_path = project_dir.join(format!(
+        "run-{run_id}-{}.zip",
+        archived_at.format("%Y%m%dT%H%M%SZ")
+    ));
+    let signing_key = provenance::load_secret_key(&project_id)
+        .map_err(|err| Error::Api(format!("failed to load signing key: {err}")))?;
+    let signature = write_archive(&archive_path, &manifest, &files, &signing_key)?;
+    let archive_sha256 = provenance::sha256_file(&archive_path)
+        .map_err(|err| Error::Api(format!("failed to hash archive: {err}")))?;
+
+    let archive = RunArchive {
+        run_id: run_id.to_string(),
+        archive_path: archive_path.to_string_lossy().to_string(),
+        archive_sha256,
+        signature,
+        archived_at: manifest.archived_at.clone(),
+        rehydrated_at: None,
+    };
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE checkpoint_payloads
+         SET prompt_payload = NULL, output_payload = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE checkpoint_id IN (SELECT id FROM checkpoints WHERE run_id = ?1)",
+        params![run_id],
+    )?;
+    run_archives::record(&tx, &archive)?;
+    tx.commit()?;
+
+    // Content-addressed files may also back other runs; only drop what no
+    // other hot run still references
+    for hash in &attachment_hashes {
+        let shared = shared_with_hot_run(
+            &conn,
+            "SELECT 1 FROM checkpoint_payloads p
+             JOIN checkpoints c ON c.id = p.checkpoint_id
+             WHERE p.full_output_hash = ?1 AND c.run_id != ?2
+               AND c.run_id NOT IN (SELECT run_id FROM run_archives WHERE rehydrated_at IS NULL)
+             LIMIT 1",
+            hash,
+            run_id,
+        )?;
+        if !shared {
+            attachments.delete(hash).map_err(load_err)?;
+        }
+    }
+    for hash in archived_step_outputs {
+        let shared = shared_with_hot_run(
+            &conn,
+            "SELECT 1 FROM checkpoints
+             WHERE outputs_sha256 = ?1 AND run_id != ?2
+               AND run_id NOT IN (SELECT run_id FROM run_archives WHERE rehydrated_at IS NULL)
+             LIMIT 1",
+            hash,
+            run_id,
+        )?;
+        if !shared {
+            attachments.delete_step_output(hash).map_err(load_err)?;
+        }
+    }
+
+    Ok(archive)
+}
+
+/// Restore an archived run's payloads and attachments from cold storage.
+/// The archive itself is kept.
+pub fn rehydrate_run(
+    pool: &DbPool,
+    attachments: &AttachmentStore,
+    run_id: &str,
+) -> Result<RunArchive, Error> {
+    let mut conn = pool.get()?;
+    let archive = run_archives::get(&conn, run_id)?
+        .filter(RunArchive::is_cold)
+        .ok_or_else(|| Error::Api(format!("run {run_id} is not archived")))?;
+    let project_id = run_project_id(&conn, run_id)?;
+    let pubkey: String = conn.query_row(
+        "SELECT pubkey FROM projects WHERE id = ?1",
+        params![&project_id],
+        |row| row.get(0),
+    )?;
+
+    let bytes = fs::read(&archive.archive_path).map_err(|err| {
+        Error::Api(format!(
+            "failed to read archive {}: {err}",
+            archive.archive_path
+        ))
+    })?;
+    if provenance::sha256_hex(&bytes) != archive.archive_sha256 {
+        return Err(Error::Api(format!(
+            "archive {} was modified after it was written",
+            archive.archive_path
+        )));
+    }
+    let verifying_key = portability::decode_verifying_key(&pubkey)?;
+    let (manifest, files) = read_archive(&bytes, &verifying_key)?;
+    if manifest.run_id != run_id {
+        return Err(Error::Api(format!(
+            "archive belongs to run {}, not {run_id}",
+            manifest.run_id
+        )));
+    }
+
+    let store_err = |err: anyhow::Error| Error::Api(err.to_string());
+    for (path, content) in &files {
+        let content = std::str::from_utf8(content)
+            .map_err(|_| Error::Api(format!("archive entry {path} is not UTF-8")))?;
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        if path.starts_with("attachments/") {
+            attachments
+                .store_with_hash(name, content)
+                .map_err(store_err)?;
+        } else if path.starts_with("steps/") {
+            attachments
+                .save_step_output(name, content)
+                .map_err(store_err)?;
+        }
+    }
+
+    let tx = conn.transaction()?;
+    for payload in &manifest.payloads {
+        tx.execute(
+            "UPDATE checkpoint_payloads
+             SET prompt_payload = ?2, output_payload = ?3, updated_at = CURRENT_TIMESTAMP
+             WHERE checkpoint_id = ?1
+               AND checkpoint_id IN (SELECT id FROM checkpoints WHERE run_id = ?4)",
+            params![
+                &payload.checkpoint_id,
+                &payload.prompt_payload,
+                &payload.output_payload,
+                run_id,
+            ],
+        )?;
+    }
+    let rehydrated_at = Utc::now().to_rfc3339();
+    run_archives::mark_rehydrated(&tx, run_id, &rehydrated_at)?;
+    tx.commit()?;
+
+    Ok(RunArchive {
+        rehydrated_at: Some(rehydrated_at),
+        ..archive
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manifest(files: &[(String, Vec<u8>)]) -> ArchiveManifest {
+        ArchiveManifest {
+            version: ARCHIVE_FORMAT_VERSION,
+            run_id: "run-1".to_string(),
+            project_id: "project-1".to_string(),
+            archived_at: "2025-01-01T00:00:00Z".to_string(),
+            payloads: vec![ArchivedPayload {
+                checkpoint_id: "ck-1".to_string(),
+                prompt_payload: Some("prompt".to_string()),
+                output_payload: Some("output".to_string()),
+            }],
+            entries: files
+                .iter()
+                .map(|(path, bytes)| ArchiveEntry {
+                    path: path.clone(),
+                    sha256: provenance::sha256_hex(bytes),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn archives_round_trip_only_under_the_signing_key() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.zip");
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let files = vec![(attachment_entry(&"ab".repeat(32)), b"full output".to_vec())];
+        let manifest = manifest(&files);
+
+        write_archive(&path, &manifest, &files, &signing_key).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let (read_manifest, read_files) =
+            read_archive(&bytes, &signing_key.verifying_key()).unwrap();
+        assert_eq!(read_manifest, manifest);
+        assert_eq!(read_files[&files[0].0], b"full output".to_vec());
+
+        let other_key = SigningKey::from_bytes(&[8u8; 32]);
+        assert!(read_archive(&bytes, &other_key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn entries_must_match_their_manifest_digest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("run.zip");
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let files = vec![(step_output_entry(&"cd".repeat(32)), b"step output".to_vec())];
+        let manifest = manifest(&files);
+        let tampered = vec![(files[0].0.clone(), b"other output".to_vec())];
+
+        write_archive(&path, &manifest, &tampered, &signing_key).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let err = read_archive(&bytes, &signing_key.verifying_key()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("does not match its manifest digest"));
+    }
+}
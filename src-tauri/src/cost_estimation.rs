@@ -0,0 +1,417 @@
+// src-tauri/src/cost_estimation.rs
+//!
+//! Cost estimation: per-step usage ranges that follow chained context
+//!
+//! `estimate_run_cost` charges every step its static token budget, but a step
+//! that takes an earlier output as context sends that output along with its
+//! own prompt. This estimator walks the run's steps in order and carries each
+//! step's projected output into the steps that consume it:
+//!
+//! - ingest steps output their document, sized from the file on disk;
+//! - model steps output what their earlier executions did (fewest, mean and
+//!   most completion tokens), or else a share of the model's catalog output
+//!   limit;
+//! - a step's input is its own prompt plus the outputs it consumes, or the
+//!   recorded output of the earlier run's checkpoint it references.
+//!
+//! A step is charged its whole input plus as much output as its token budget
+//! leaves room for: the budget stops generation, not the prompt. Inputs that
+//! can outgrow the model's catalog context window are flagged, except for
+//! summaries, which are costed as the chunked summaries the orchestrator
+//! makes of them. Steps that send no prompt of their own (plugins, fine-tunes,
+//! predicate branches) are estimated from their history, or their budget.
+//! Token counts are estimated at four bytes per token, as the orchestrator
+//! estimates them.
+
+use crate::{governance, model_catalog, orchestrator, run_spec::RunStep, Error};
+use orchestrator::StepConfig;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Output limit assumed for models the catalog gives none
+const DEFAULT_OUTPUT_LIMIT_TOKENS: u64 = 1_024;
+
+const BYTES_PER_TOKEN: u64 = 4;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRange {
+    pub low: u64,
+    pub expected: u64,
+    pub high: u64,
+}
+
+impl TokenRange {
+    pub fn exact(tokens: u64) -> Self {
+        Self {
+            low: tokens,
+            expected: tokens,
+            high: tokens,
+        }
+    }
+
+    fn zip(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        Self {
+            low: f(self.low, other.low),
+            expected: f(self.expected, other.expected),
+            high: f(self.high, other.high),
+        }
+    }
+
+    fn plus(self, other: Self) -> Self {
+        self.zip(other, u64::saturating_add)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostRange {
+    pub low: f64,
+    pub expected: f64,
+    pub high: f64,
+}
+
+impl CostRange {
+    fn priced(tokens: TokenRange, price: impl Fn(u64) -> f64) -> Self {
+        Self {
+            low: price(tokens.low),
+            expected: price(tokens.expected),
+            high: price(tokens.high),
+        }
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Self {
+            low: self.low + other.low,
+            expected: self.expected + other.expected,
+            high: self.high + other.high,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EstimateBasis {
+    /// Input projected from prompts, documents and context; output from the
+    /// step's earlier executions
+    History,
+    /// Prompts, documents and context, and the model catalog
+    Projection,
+    /// The step's token budget, for a step with nothing to project from
+    TokenBudget,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepCostEstimate {
+    pub step_id: String,
+    pub order_index: i64,
+    pub step_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub basis: EstimateBasis,
+    /// Earlier executions of the step the estimate drew on
+    pub history_samples: u64,
+    pub input_tokens: TokenRange,
+    pub output_tokens: TokenRange,
+    /// What the step is charged: its input and the output its budget allows
+    pub usage_tokens: TokenRange,
+    pub usd: CostRange,
+    pub nature_cost: CostRange,
+    pub token_budget: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    /// Input and output can outrun the token budget, which stops the step early
+    pub exceeds_token_budget: bool,
+    /// The input can outgrow the model's context window
+    pub exceeds_context_window: bool,
+    /// What could not be sized, e.g. a missing document
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStepCostEstimates {
+    pub run_id: String,
+    /// In run order; interactive chat steps are not charged and left out
+    pub steps: Vec<StepCostEstimate>,
+    pub usage_tokens: TokenRange,
+    pub usd: CostRange,
+    pub nature_cost: CostRange,
+    /// What `estimate_run_cost` projects: the steps' token budgets
+    pub static_budget_tokens: u64,
+}
+
+/// Usage recorded by a step's earlier top-level checkpoints
+#[derive(Debug, Clone, Copy, Default)]
+struct StepHistory {
+    samples: u64,
+    output: TokenRange,
+    usage: TokenRange,
+}
+
+fn load_history(conn: &Connection, step_id: &str) -> Result<StepHistory, Error> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*),
+                MIN(completion_tokens), AVG(completion_tokens), MAX(completion_tokens),
+                MIN(usage_tokens), AVG(usage_tokens), MAX(usage_tokens)
+         FROM checkpoints
+         WHERE checkpoint_config_id = ?1 AND parent_checkpoint_id IS NULL",
+        params![step_id],
+        |row| {
+            let range = |at: usize| -> rusqlite::Result<TokenRange> {
+                Ok(TokenRange {
+                    low: row.get::<_, Option<i64>>(at)?.unwrap_or(0).max(0) as u64,
+                    expected: row
+                        .get::<_, Option<f64>>(at + 1)?
+                        .unwrap_or(0.0)
+                        .round()
+                        .max(0.0) as u64,
+                    high: row.get::<_, Option<i64>>(at + 2)?.unwrap_or(0).max(0) as u64,
+                })
+            };
+            Ok(StepHistory {
+                samples: row.get::<_, i64>(0)?.max(0) as u64,
+                output: range(1)?,
+                usage: range(4)?,
+            })
+        },
+    )?)
+}
+
+/// Tokens of an ingested document: text formats as they are on disk, while
+/// PDF, Word, HTML and notebook files hold somewhere between a tenth and all
+/// of their size as text, a quarter expected. EPUBs are compressed, so their
+/// text can be a few times their size on disk.
+fn document_output(source_path: &str, format: &str) -> Option<TokenRange> {
+    let bytes = std::fs::metadata(source_path).ok()?.len();
+    let tokens = bytes.div_ceil(BYTES_PER_TOKEN);
+    Some(match format.to_lowercase().as_str() {
+        "pdf" | "docx" | "doc" | "html" | "htm" | "ipynb" => TokenRange {
+            low: tokens / 10,
+            expected: tokens / 4,
+            high: tokens,
+        },
+        "epub" => TokenRange {
+            low: tokens / 4,
+            expected: tokens,
+            high: tokens * 3,
+        },
+        _ => TokenRange::exact(tokens),
+    })
+}
+
+/// Output of a model step without history: up to the model's output limit,
+/// a quarter of it expected
+fn projected_output(model: &str) -> TokenRange {
+    let limit = model_catalog::try_get_global_catalog()
+        .and_then(|catalog| catalog.get_model(model))
+        .and_then(|model_def| model_def.max_output_tokens)
+        .map_or(DEFAULT_OUTPUT_LIMIT_TOKENS, u64::from);
+    TokenRange {
+        low: limit / 16,
+        expected: limit / 4,
+        high: limit,
+    }
+}
+
+fn context_window(model: &str) -> Option<u64> {
+    model_catalog::try_get_global_catalog()?
+        .get_model(model)?
+        .context_window
+        .map(u64::from)
+}
+
+/// The text a step sends besides the outputs it consumes
+fn own_prompt(step: &RunStep, config: Option<&StepConfig>) -> String {
+    match config {
+        Some(StepConfig::Summarize {
+            summary_type,
+            custom_instructions,
+            ..
+        }) => orchestrator::summary_instructions(summary_type, custom_instructions.as_deref())
+            .to_string(),
+        Some(StepConfig::Prompt { prompt, .. } | StepConfig::Agent { prompt, .. }) => {
+            prompt.clone()
+        }
+        Some(StepConfig::Map {
+            prompt_template, ..
+        }) => prompt_template.clone(),
+        Some(StepConfig::Evaluate { judge_criteria, .. }) => {
+            judge_criteria.clone().unwrap_or_default()
+        }
+        Some(_) => String::new(),
+        None => step.prompt.clone().unwrap_or_default(),
+    }
+}
+
+/// Output tokens recorded by the earlier run's checkpoint a step references
+fn referenced_output(conn: &Connection, checkpoint_id: &str) -> Result<Option<u64>, Error> {
+    Ok(conn
+        .query_row(
+            "SELECT completion_tokens FROM checkpoints WHERE id = ?1",
+            params![checkpoint_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()?
+        .map(|tokens| tokens.max(0) as u64))
+}
+
+fn estimate_step(
+    conn: &Connection,
+    step: &RunStep,
+    outputs: &BTreeMap<i64, TokenRange>,
+) -> Result<StepCostEstimate, Error> {
+    let config = step
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<StepConfig>(json).ok());
+    let model = orchestrator::pricing_model(step.config_json.as_deref(), step.model.as_deref());
+    let history = load_history(conn, &step.id)?;
+    let mut notes = Vec::new();
+
+    let prompt = own_prompt(step, config.as_ref());
+    let mut input = TokenRange::exact(orchestrator::estimate_tokens(&prompt));
+    for source in orchestrator::step_sources(step) {
+        match outputs.get(&(source as i64)) {
+            Some(output) => input = input.plus(*output),
+            None => notes.push(format!("source step {source} is not in the run")),
+        }
+    }
+    if let Some(checkpoint_id) = config.as_ref().and_then(StepConfig::referenced_checkpoint) {
+        match referenced_output(conn, checkpoint_id)? {
+            Some(tokens) => input = input.plus(TokenRange::exact(tokens)),
+            None => notes.push(format!(
+                "referenced checkpoint {checkpoint_id} was not found"
+            )),
+        }
+    }
+
+    let mut basis = EstimateBasis::Projection;
+    let mut window = None;
+    let mut exceeds_context_window = false;
+    let (input, output, usage) = match (&config, &model) {
+        (
+            Some(StepConfig::Ingest {
+                source_path,
+                format,
+                ..
+            }),
+            _,
+        ) => {
+            let output = document_output(source_path, format).unwrap_or_else(|| {
+                notes.push(format!("document {source_path} could not be read"));
+                TokenRange::default()
+            });
+            (TokenRange::default(), output, TokenRange::default())
+        }
+        (_, Some(model)) => {
+            let mut output = match &config {
+                Some(StepConfig::Embed { .. }) => TokenRange::default(),
+                _ if history.samples > 0 => {
+                    basis = EstimateBasis::History;
+                    history.output
+                }
+                _ => projected_output(model),
+            };
+            window = context_window(model);
+            let mut input = input;
+            let prompt_limit = orchestrator::summary_prompt_limit(model).filter(|limit| *limit > 0);
+            match (&config, prompt_limit) {
+                // Each chunk is summarized, then the chunk summaries are combined
+                (Some(StepConfig::Summarize { .. }), Some(limit)) => {
+                    let chunks = input.zip(TokenRange::exact(limit), u64::div_ceil);
+                    let summaries = chunks.zip(output, |chunks, output| {
+                        if chunks > 1 {
+                            chunks.saturating_mul(output)
+                        } else {
+                            0
+                        }
+                    });
+                    input = input.plus(summaries);
+                    output = output.plus(summaries);
+                }
+                _ => exceeds_context_window = window.is_some_and(|window| input.high > window),
+            }
+            let usage = input.plus(output.zip(input, |output, input| {
+                if step.token_budget > 0 {
+                    output.min(step.token_budget.saturating_sub(input))
+                } else {
+                    output
+                }
+            }));
+            (input, output, usage)
+        }
+        _ if history.samples > 0 => {
+            basis = EstimateBasis::History;
+            (input, history.output, history.usage)
+        }
+        _ => {
+            basis = EstimateBasis::TokenBudget;
+            (
+                input,
+                TokenRange::default(),
+                TokenRange::exact(step.token_budget),
+            )
+        }
+    };
+
+    let model_id = model.as_deref();
+    Ok(StepCostEstimate {
+        step_id: step.id.clone(),
+        order_index: step.order_index,
+        step_type: step.step_type.clone(),
+        basis,
+        history_samples: history.samples,
+        usd: CostRange::priced(usage, |tokens| {
+            governance::estimate_usd_cost(tokens, model_id)
+        }),
+        nature_cost: CostRange::priced(usage, |tokens| {
+            governance::estimate_nature_cost(tokens, model_id)
+        }),
+        exceeds_token_budget: model.is_some()
+            && step.token_budget > 0
+            && input.high.saturating_add(output.high) > step.token_budget,
+        model,
+        input_tokens: input,
+        output_tokens: output,
+        usage_tokens: usage,
+        token_budget: step.token_budget,
+        context_window: window,
+        exceeds_context_window,
+        notes,
+    })
+}
+
+/// Low, expected and high usage of each of the run's steps, with the context
+/// each step inherits from the steps before it
+pub fn estimate_step_costs(conn: &Connection, run_id: &str) -> Result<RunStepCostEstimates, Error> {
+    let stored_run =
+        orchestrator::load_stored_run(conn, run_id).map_err(|err| Error::Api(err.to_string()))?;
+    let mut steps = stored_run.steps;
+    steps.sort_by_key(|step| step.order_index);
+
+    let mut outputs = BTreeMap::new();
+    let mut estimates = RunStepCostEstimates {
+        run_id: run_id.to_string(),
+        steps: Vec::new(),
+        usage_tokens: TokenRange::default(),
+        usd: CostRange::default(),
+        nature_cost: CostRange::default(),
+        static_budget_tokens: 0,
+    };
+    for step in steps.iter().filter(|step| !step.is_interactive_chat()) {
+        let estimate = estimate_step(conn, step, &outputs)?;
+        outputs.insert(step.order_index, estimate.output_tokens);
+        estimates.usage_tokens = estimates.usage_tokens.plus(estimate.usage_tokens);
+        estimates.usd = estimates.usd.plus(estimate.usd);
+        estimates.nature_cost = estimates.nature_cost.plus(estimate.nature_cost);
+        estimates.static_budget_tokens = estimates
+            .static_budget_tokens
+            .saturating_add(step.token_budget);
+        estimates.steps.push(estimate);
+    }
+    Ok(estimates)
+}
@@ -0,0 +1,491 @@
+// src-tauri/src/dashboard.rs
+//!
+//! Project dashboard summary
+//!
+//! Aggregates what the project overview shows into a single call: recent
+//! executions and their status, budget burn-down against the current policy,
+//! incident counts by severity, the models that cost the most, and executions
+//! that have no receipt yet. Usage over time, per day or week and per model,
+//! is aggregated in SQL by `get_usage_timeseries`.
+//!
+//! Usage is taken from top-level checkpoints only; map elements and agent
+//! turns are children whose usage is already included in their step.
+
+use crate::{
+    governance,
+    ledger::{self, ProjectLedgerSnapshot},
+    run_queue::{self, ExecutionState},
+    Error,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Number of executions listed under recent executions
+pub const RECENT_EXECUTIONS: usize = 10;
+/// Number of models listed under top models
+pub const TOP_MODELS: usize = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Running,
+    Completed,
+    /// Finished, but recorded at least one incident checkpoint
+    Incident,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardExecution {
+    pub execution_id: String,
+    pub run_id: String,
+    pub run_name: String,
+    pub created_at: String,
+    pub status: ExecutionStatus,
+    pub checkpoint_count: u64,
+    pub usage_tokens: u64,
+}
+
+/// Usage of one day under the current policy version, with running totals
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnDownPoint {
+    pub date: String,
+    pub tokens: u64,
+    pub usd: f64,
+    pub remaining_tokens: i64,
+    pub remaining_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetBurnDown {
+    pub ledger: ProjectLedgerSnapshot,
+    pub daily: Vec<BurnDownPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCost {
+    pub model: String,
+    pub tokens: u64,
+    pub usd: f64,
+    pub checkpoints: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingReceipt {
+    pub execution_id: String,
+    pub run_id: String,
+    pub run_name: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDashboard {
+    pub project_id: String,
+    pub recent_executions: Vec<DashboardExecution>,
+    pub budget: BudgetBurnDown,
+    pub incidents_by_severity: BTreeMap<String, u64>,
+    pub top_models: Vec<ModelCost>,
+    pub receipts_pending: Vec<PendingReceipt>,
+}
+
+/// Width of the buckets a usage time series is aggregated into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGranularity {
+    Day,
+    /// Weeks start on Monday
+    Week,
+}
+
+impl UsageGranularity {
+    /// SQL for the first UTC day of the bucket a checkpoint falls in
+    fn bucket_start_sql(self) -> &'static str {
+        match self {
+            Self::Day => "date(c.timestamp)",
+            Self::Week => "date(c.timestamp, 'weekday 0', '-6 days')",
+        }
+    }
+}
+
+/// Usage of one model over one day or week
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    /// First day of the bucket, YYYY-MM-DD
+    pub period_start: String,
+    /// None for steps that name no model
+    pub model: Option<String>,
+    pub checkpoints: u64,
+    pub tokens: u64,
+    pub usd: f64,
+    pub nature_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTimeseries {
+    pub project_id: String,
+    pub granularity: UsageGranularity,
+    /// Ordered by period, then model
+    pub buckets: Vec<UsageBucket>,
+}
+
+pub fn get_project_dashboard(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<ProjectDashboard, Error> {
+    ensure_project(conn, project_id)?;
+
+    let running: HashSet<String> = run_queue::global()
+        .active(Some(project_id))
+        .into_iter()
+        .filter(|entry| entry.state == ExecutionState::Running)
+        .filter_map(|entry| entry.execution_id)
+        .collect();
+
+    let ledger = ledger::get_project_ledger_snapshot(conn, project_id)?;
+    let usage = load_step_usage(conn, project_id)?;
+
+    Ok(ProjectDashboard {
+        project_id: project_id.to_string(),
+        recent_executions: recent_executions(conn, project_id, &running)?,
+        budget: BudgetBurnDown {
+            daily: burn_down(&ledger, &usage),
+            ledger,
+        },
+        incidents_by_severity: incidents_by_severity(conn, project_id)?,
+        top_models: top_models(&usage),
+        receipts_pending: receipts_pending(conn, project_id, &running)?,
+    })
+}
+
+/// Tokens, USD and Nature Cost of a project's top-level checkpoints per day
+/// or week and model. Checkpoints recorded before costs were stored are
+/// priced from the model catalog, as the ledger prices them.
+pub fn get_usage_timeseries(
+    conn: &Connection,
+    project_id: &str,
+    granularity: UsageGranularity,
+) -> Result<UsageTimeseries, Error> {
+    ensure_project(conn, project_id)?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {bucket} AS period_start, s.model, COUNT(*), SUM(c.usage_tokens),
+                SUM(c.usd_cost), SUM(CASE WHEN c.usd_cost IS NULL THEN c.usage_tokens ELSE 0 END),
+                SUM(c.nature_cost), SUM(CASE WHEN c.nature_cost IS NULL THEN c.usage_tokens ELSE 0 END)
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         LEFT JOIN run_steps s ON s.id = c.checkpoint_config_id
+         WHERE r.project_id = ?1 AND c.parent_checkpoint_id IS NULL AND c.usage_tokens > 0
+           AND {bucket} IS NOT NULL
+         GROUP BY period_start, s.model
+         ORDER BY period_start, s.model",
+        bucket = granularity.bucket_start_sql(),
+    ))?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        let model: Option<String> = row.get(1)?;
+        let unpriced_usd_tokens = row.get::<_, i64>(5)?.max(0) as u64;
+        let unpriced_nature_tokens = row.get::<_, i64>(7)?.max(0) as u64;
+        Ok(UsageBucket {
+            period_start: row.get(0)?,
+            checkpoints: row.get::<_, i64>(2)?.max(0) as u64,
+            tokens: row.get::<_, i64>(3)?.max(0) as u64,
+            usd: row.get::<_, Option<f64>>(4)?.unwrap_or(0.0)
+                + governance::estimate_usd_cost(unpriced_usd_tokens, model.as_deref()),
+            nature_cost: row.get::<_, Option<f64>>(6)?.unwrap_or(0.0)
+                + governance::estimate_nature_cost(unpriced_nature_tokens, model.as_deref()),
+            model,
+        })
+    })?;
+
+    Ok(UsageTimeseries {
+        project_id: project_id.to_string(),
+        granularity,
+        buckets: rows.collect::<Result<_, _>>()?,
+    })
+}
+
+pub(crate) fn ensure_project(conn: &Connection, project_id: &str) -> Result<(), Error> {
+    let exists: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(Error::Api(format!("project {project_id} not found"))),
+    }
+}
+
+fn recent_executions(
+    conn: &Connection,
+    project_id: &str,
+    running: &HashSet<String>,
+) -> Result<Vec<DashboardExecution>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.run_id, r.name, e.created_at,
+                (SELECT COUNT(*) FROM checkpoints c WHERE c.run_execution_id = e.id),
+                (SELECT COALESCE(SUM(c.usage_tokens), 0) FROM checkpoints c
+                  WHERE c.run_execution_id = e.id AND c.parent_checkpoint_id IS NULL),
+                EXISTS (SELECT 1 FROM checkpoints c WHERE c.run_execution_id = e.id AND c.kind = 'Incident')
+         FROM run_executions e
+         JOIN runs r ON r.id = e.run_id
+         WHERE r.project_id = ?1
+         ORDER BY datetime(e.created_at) DESC, e.id DESC
+         LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![project_id, RECENT_EXECUTIONS as i64], |row| {
+        let execution_id: String = row.get(0)?;
+        let had_incident: bool = row.get(6)?;
+        let status = if running.contains(&execution_id) {
+            ExecutionStatus::Running
+        } else if had_incident {
+            ExecutionStatus::Incident
+        } else {
+            ExecutionStatus::Completed
+        };
+        Ok(DashboardExecution {
+            execution_id,
+            run_id: row.get(1)?,
+            run_name: row.get(2)?,
+            created_at: row.get(3)?,
+            status,
+            checkpoint_count: row.get::<_, i64>(4)?.max(0) as u64,
+            usage_tokens: row.get::<_, i64>(5)?.max(0) as u64,
+        })
+    })?;
+    rows.collect::<Result<_, _>>().map_err(Into::into)
+}
+
+/// Usage of one top-level checkpoint
+struct StepUsage {
+    date: String,
+    policy_version: Option<i64>,
+    model: Option<String>,
+    tokens: u64,
+    /// Priced at execution time; None for checkpoints recorded before costs were stored
+    usd_cost: Option<f64>,
+}
+
+impl StepUsage {
+    fn usd(&self) -> f64 {
+        self.usd_cost
+            .unwrap_or_else(|| governance::estimate_usd_cost(self.tokens, self.model.as_deref()))
+    }
+}
+
+fn load_step_usage(conn: &Connection, project_id: &str) -> Result<Vec<StepUsage>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT c.timestamp, r.policy_version, s.model, c.usage_tokens, c.usd_cost
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         LEFT JOIN run_steps s ON s.id = c.checkpoint_config_id
+         WHERE r.project_id = ?1 AND c.parent_checkpoint_id IS NULL AND c.usage_tokens > 0",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        let timestamp: String = row.get(0)?;
+        Ok(StepUsage {
+            date: timestamp.chars().take(10).collect(),
+            policy_version: row.get(1)?,
+            model: row.get(2)?,
+            tokens: row.get::<_, i64>(3)?.max(0) as u64,
+            usd_cost: row.get(4)?,
+        })
+    })?;
+    rows.collect::<Result<_, _>>().map_err(Into::into)
+}
+
+/// Daily usage under the ledger's policy version, with what the budgets have left
+fn burn_down(ledger: &ProjectLedgerSnapshot, usage: &[StepUsage]) -> Vec<BurnDownPoint> {
+    let mut days: BTreeMap<&str, (u64, f64)> = BTreeMap::new();
+    for step in usage
+        .iter()
+        .filter(|step| step.policy_version.unwrap_or(0) == ledger.policy_version)
+    {
+        let day = days.entry(step.date.as_str()).or_default();
+        day.0 += step.tokens;
+        day.1 += step.usd();
+    }
+
+    let mut remaining_tokens = ledger.budgets.tokens as i64;
+    let mut remaining_usd = ledger.budgets.usd;
+    days.into_iter()
+        .map(|(date, (tokens, usd))| {
+            remaining_tokens = remaining_tokens.saturating_sub(tokens as i64);
+            remaining_usd -= usd;
+            BurnDownPoint {
+                date: date.to_string(),
+                tokens,
+                usd,
+                remaining_tokens,
+                remaining_usd,
+            }
+        })
+        .collect()
+}
+
+fn top_models(usage: &[StepUsage]) -> Vec<ModelCost> {
+    let mut by_model: HashMap<&str, ModelCost> = HashMap::new();
+    for step in usage {
+        let Some(model) = step.model.as_deref() else {
+            continue;
+        };
+        let entry = by_model.entry(model).or_insert_with(|| ModelCost {
+            model: model.to_string(),
+            tokens: 0,
+            usd: 0.0,
+            checkpoints: 0,
+        });
+        entry.tokens += step.tokens;
+        entry.usd += step.usd();
+        entry.checkpoints += 1;
+    }
+    let mut models: Vec<ModelCost> = by_model.into_values().collect();
+    models.sort_by(|a, b| {
+        b.usd
+            .total_cmp(&a.usd)
+            .then_with(|| b.tokens.cmp(&a.tokens))
+            .then_with(|| a.model.cmp(&b.model))
+    });
+    models.truncate(TOP_MODELS);
+    models
+}
+
+fn incidents_by_severity(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<BTreeMap<String, u64>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT c.incident_json FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         WHERE r.project_id = ?1 AND c.kind = 'Incident'",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| row.get::<_, Option<String>>(0))?;
+
+    let mut counts = BTreeMap::new();
+    for incident_json in rows {
+        let severity = incident_json?
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .and_then(|incident| incident["severity"].as_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string());
+        *counts.entry(severity).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Finished executions without a receipt. Receipts recorded before they
+/// were linked to an execution count for every execution of their run.
+fn receipts_pending(
+    conn: &Connection,
+    project_id: &str,
+    running: &HashSet<String>,
+) -> Result<Vec<PendingReceipt>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.run_id, r.name, e.created_at
+         FROM run_executions e
+         JOIN runs r ON r.id = e.run_id
+         WHERE r.project_id = ?1
+           AND EXISTS (SELECT 1 FROM checkpoints c WHERE c.run_execution_id = e.id)
+           AND NOT EXISTS (
+             SELECT 1 FROM receipts rc
+             WHERE rc.run_execution_id = e.id
+                OR (rc.run_execution_id IS NULL AND rc.run_id = e.run_id)
+           )
+         ORDER BY datetime(e.created_at) DESC, e.id DESC",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok(PendingReceipt {
+            execution_id: row.get(0)?,
+            run_id: row.get(1)?,
+            run_name: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    })?;
+
+    let mut pending = Vec::new();
+    for receipt in rows {
+        let receipt = receipt?;
+        if !running.contains(&receipt.execution_id) {
+            pending.push(receipt);
+        }
+    }
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{LedgerBudgets, LedgerRemaining, LedgerTotals};
+
+    fn usage(date: &str, policy_version: Option<i64>, model: &str, tokens: u64) -> StepUsage {
+        StepUsage {
+            date: date.to_string(),
+            policy_version,
+            model: Some(model.to_string()),
+            tokens,
+            usd_cost: None,
+        }
+    }
+
+    #[test]
+    fn burn_down_counts_only_the_current_policy_version() {
+        let ledger = ProjectLedgerSnapshot {
+            project_id: "p".into(),
+            policy_version: 2,
+            totals: LedgerTotals {
+                tokens: 0,
+                usd: 0.0,
+                nature_cost: 0.0,
+            },
+            budgets: LedgerBudgets {
+                tokens: 1_000,
+                usd: 10.0,
+                nature_cost: 0.0,
+            },
+            remaining: LedgerRemaining {
+                tokens: 1_000,
+                usd: 10.0,
+                nature_cost: 0.0,
+            },
+            last_updated: None,
+        };
+        let steps = vec![
+            usage("2026-03-02", Some(2), "stub-model", 300),
+            usage("2026-03-01", Some(2), "stub-model", 100),
+            usage("2026-03-01", Some(1), "stub-model", 5_000),
+            usage("2026-03-02", Some(2), "stub-model", 50),
+        ];
+
+        let daily = burn_down(&ledger, &steps);
+        let points: Vec<(&str, u64, i64)> = daily
+            .iter()
+            .map(|point| (point.date.as_str(), point.tokens, point.remaining_tokens))
+            .collect();
+        assert_eq!(
+            points,
+            vec![("2026-03-01", 100, 900), ("2026-03-02", 350, 550)]
+        );
+        assert!(daily[1].remaining_usd <= daily[0].remaining_usd);
+    }
+
+    #[test]
+    fn top_models_rank_by_cost_and_group_checkpoints() {
+        let steps = vec![
+            usage("2026-03-01", None, "small", 10),
+            usage("2026-03-01", None, "large", 5_000),
+            usage("2026-03-02", None, "small", 20),
+        ];
+        let models = top_models(&steps);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].model, "large");
+        assert_eq!((models[1].tokens, models[1].checkpoints), (30, 2));
+    }
+}
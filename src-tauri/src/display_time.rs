@@ -0,0 +1,172 @@
+// src-tauri/src/display_time.rs
+//!
+//! Timestamps for people to read
+//!
+//! Signed data (checkpoint bodies, CARs, receipts, policy history) always
+//! carries canonical UTC RFC 3339 strings. This module renders those strings
+//! in a chosen timezone and locale for human-facing output such as the CLI
+//! verifier's human report. It only ever reads timestamps: nothing it
+//! returns may be written back into signed data.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Locale, Utc};
+use chrono_tz::Tz;
+
+/// Date, time and zone abbreviation in the locale's own formats
+const DISPLAY_FORMAT: &str = "%x %X %Z";
+
+/// Timezone and locale human-facing timestamps are shown in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeDisplay {
+    pub timezone: Tz,
+    pub locale: Locale,
+}
+
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            locale: Locale::POSIX,
+        }
+    }
+}
+
+impl TimeDisplay {
+    /// `timezone` is an IANA name ("Europe/Berlin"); `locale` a POSIX or
+    /// BCP 47 tag ("de_DE", "pt-BR", "fr_FR.UTF-8")
+    pub fn new(timezone: &str, locale: &str) -> Result<Self> {
+        Ok(Self {
+            timezone: parse_timezone(timezone)?,
+            locale: parse_locale(locale)?,
+        })
+    }
+
+    /// Settings from `INTELEXTA_TZ`/`TZ` and `LC_ALL`/`LC_TIME`/`LANG`;
+    /// anything missing or unrecognized falls back to UTC and POSIX
+    pub fn from_env() -> Self {
+        let var = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+        };
+        let defaults = Self::default();
+        Self {
+            timezone: var(&["INTELEXTA_TZ", "TZ"])
+                .and_then(|value| parse_timezone(&value).ok())
+                .unwrap_or(defaults.timezone),
+            locale: var(&["LC_ALL", "LC_TIME", "LANG"])
+                .and_then(|value| parse_locale(&value).ok())
+                .unwrap_or(defaults.locale),
+        }
+    }
+
+    pub fn format(&self, timestamp: &DateTime<Utc>) -> String {
+        timestamp
+            .with_timezone(&self.timezone)
+            .format_localized(DISPLAY_FORMAT, self.locale)
+            .to_string()
+    }
+
+    /// Format a stored RFC 3339 timestamp; strings that do not parse are
+    /// shown as they are
+    pub fn format_rfc3339(&self, timestamp: &str) -> String {
+        DateTime::parse_from_rfc3339(timestamp)
+            .map(|parsed| self.format(&parsed.with_timezone(&Utc)))
+            .unwrap_or_else(|_| timestamp.to_string())
+    }
+}
+
+/// IANA timezone name; POSIX `TZ` values may carry a leading colon
+pub fn parse_timezone(value: &str) -> Result<Tz> {
+    let name = value.trim().trim_start_matches(':');
+    name.parse::<Tz>().map_err(|_| anyhow!("unknown timezone '{name}'"))
+}
+
+/// POSIX locale name or BCP 47 tag
+pub fn parse_locale(value: &str) -> Result<Locale> {
+    // Drop the codeset and modifier ("de_DE.UTF-8@euro") and accept BCP 47 tags
+    let tag = value
+        .trim()
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('-', "_");
+    match tag.as_str() {
+        "C" | "POSIX" => Ok(Locale::POSIX),
+        _ => Locale::try_from(tag.as_str()).map_err(|_| anyhow!("unknown locale '{value}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::CheckpointBody;
+
+    const SIGNED: [&str; 3] = [
+        "2025-01-01T00:00:00+00:00",
+        "2025-03-30T01:30:00.123456+00:00",
+        "2025-10-26T23:59:59.999999999+00:00",
+    ];
+
+    #[test]
+    fn timestamps_render_in_the_chosen_zone_and_locale() {
+        let display = TimeDisplay::new("Europe/Berlin", "de-DE").unwrap();
+        assert_eq!(
+            display.format_rfc3339("2025-07-01T12:00:00+00:00"),
+            "01.07.2025 14:00:00 CEST"
+        );
+        let display = TimeDisplay::new("America/New_York", "en_US.UTF-8").unwrap();
+        assert_eq!(
+            display.format_rfc3339("2025-01-15T17:05:00Z"),
+            "01/15/2025 12:05:00 PM EST"
+        );
+        assert_eq!(
+            TimeDisplay::default().format_rfc3339("2025-01-15T17:05:00Z"),
+            "01/15/25 17:05:00 UTC"
+        );
+        assert_eq!(TimeDisplay::default().format_rfc3339("yesterday"), "yesterday");
+    }
+
+    #[test]
+    fn unknown_settings_are_rejected() {
+        assert!(TimeDisplay::new("Mars/Olympus_Mons", "en_US").is_err());
+        assert!(TimeDisplay::new("UTC", "xx_YY").is_err());
+        assert_eq!(
+            TimeDisplay::new(":Asia/Tokyo", "C").unwrap().timezone,
+            Tz::Asia__Tokyo
+        );
+    }
+
+    #[test]
+    fn displaying_timestamps_never_changes_signing_inputs() {
+        for signed in SIGNED {
+            let body = |timestamp: &str| CheckpointBody {
+                run_id: "r1",
+                kind: "Step",
+                timestamp: timestamp.to_string(),
+                inputs_sha256: None,
+                outputs_sha256: Some("aa"),
+                incident: None,
+                usage_tokens: 5,
+                prompt_tokens: 3,
+                completion_tokens: 2,
+                cache_hit: false,
+                response_metadata: None,
+                run_inputs_sha256: None,
+                output_schema_sha256: None,
+            };
+            let before = body(signed).chain_hash("").unwrap();
+
+            let parsed = DateTime::parse_from_rfc3339(signed).unwrap().with_timezone(&Utc);
+            for (timezone, locale) in [("Asia/Tokyo", "ja_JP"), ("America/Sao_Paulo", "pt-BR")] {
+                let display = TimeDisplay::new(timezone, locale).unwrap();
+                assert!(!display.format(&parsed).is_empty());
+                // The zoned time converts back to the identical canonical string
+                let zoned = parsed.with_timezone(&display.timezone);
+                assert_eq!(zoned.with_timezone(&Utc).to_rfc3339(), signed);
+            }
+            assert_eq!(parsed.to_rfc3339(), signed);
+            assert_eq!(body(&parsed.to_rfc3339()).chain_hash("").unwrap(), before);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+// EPUB extractor
+// An EPUB is a ZIP archive of XHTML chapters; the package (OPF) file lists
+// them in reading order and carries the Dublin Core metadata
+use super::html::{attribute, element_text, html_to_text, tokenize, Token};
+use crate::document_processing::schemas::{DocumentMetadata, PdfIntermediate};
+use anyhow::{anyhow, Context, Result};
+use intelexta_archive::{ArchiveError, ArchiveLimits, SafeArchive};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+const CONTAINER_PATH: &str = "META-INF/container.xml";
+
+/// Books are XHTML plus images; an EPUB beyond these is refused unread
+const EPUB_LIMITS: ArchiveLimits = ArchiveLimits {
+    max_entries: 10_000,
+    max_entry_bytes: 64 * 1024 * 1024,
+    max_total_bytes: 512 * 1024 * 1024,
+    max_compression_ratio: 100,
+};
+
+fn read_entry<R: Read + Seek>(archive: &mut SafeArchive<R>, name: &str) -> Result<String> {
+    let bytes = archive.read_by_name(name).map_err(|err| match err {
+        ArchiveError::Missing(_) => anyhow!("EPUB is missing {}", name),
+        err => anyhow!("Failed to read {} from EPUB: {}", name, err),
+    })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Tag name without its namespace prefix: `dc:title` -> `title`
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Path of an archive entry referenced from the entry at `base`
+fn resolve_href(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href);
+    let mut parts: Vec<&str> = base.split('/').collect();
+    parts.pop();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    let path = parts.join("/");
+    percent_decode(&path)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Path of the package file, from META-INF/container.xml
+fn package_path(container: &str) -> Result<String> {
+    tokenize(container)
+        .iter()
+        .find_map(|token| match token {
+            Token::Start { name, attrs, .. } if local_name(name) == "rootfile" => {
+                attribute(attrs, "full-path")
+            }
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("EPUB container does not name a package file"))
+}
+
+/// Dublin Core metadata from the package file
+fn extract_metadata(tokens: &[Token<'_>]) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata::default();
+    for (index, token) in tokens.iter().enumerate() {
+        let Token::Start { name, .. } = token else {
+            continue;
+        };
+        if !name.starts_with("dc:") {
+            continue;
+        }
+        let Some(value) = element_text(&tokens[index..], name) else {
+            continue;
+        };
+        match local_name(name) {
+            "title" => {
+                metadata.title.get_or_insert(value);
+            }
+            "creator" => metadata.authors.push(value),
+            "date" => {
+                metadata.date_published.get_or_insert(value);
+            }
+            "publisher" => {
+                metadata.publisher.get_or_insert(value);
+            }
+            "description" => {
+                metadata.abstract_text.get_or_insert(html_to_text(&value));
+            }
+            "subject" => metadata.keywords_from_source.push(value),
+            _ => {}
+        }
+    }
+    metadata
+}
+
+/// Archive paths of the chapters in reading order, leaving out the spine
+/// items marked `linear="no"` (notes, answer keys and the like)
+fn spine_paths(tokens: &[Token<'_>], package_path: &str) -> Vec<String> {
+    let mut manifest = HashMap::new();
+    let mut spine = Vec::new();
+    for token in tokens {
+        let Token::Start { name, attrs, .. } = token else {
+            continue;
+        };
+        match local_name(name) {
+            "item" => {
+                if let (Some(id), Some(href)) = (attribute(attrs, "id"), attribute(attrs, "href")) {
+                    manifest.insert(id, href);
+                }
+            }
+            "itemref" => {
+                let linear = attribute(attrs, "linear").unwrap_or_default();
+                if !linear.eq_ignore_ascii_case("no") {
+                    spine.extend(attribute(attrs, "idref"));
+                }
+            }
+            _ => {}
+        }
+    }
+    spine
+        .iter()
+        .filter_map(|idref| manifest.get(idref))
+        .map(|href| resolve_href(package_path, href))
+        .collect()
+}
+
+pub struct EpubExtractor;
+
+impl EpubExtractor {
+    /// Extract the text and metadata of an EPUB book
+    ///
+    /// Chapters are read in spine order and run through the HTML extractor,
+    /// so headings and list items come out the same way they do for pages.
+    pub fn extract(epub_path: impl AsRef<Path>) -> Result<PdfIntermediate> {
+        let epub_path = epub_path.as_ref();
+
+        let file = File::open(epub_path)
+            .with_context(|| format!("Failed to open EPUB file: {}", epub_path.display()))?;
+        let mut archive = SafeArchive::new(file, EPUB_LIMITS)
+            .with_context(|| format!("Failed to read EPUB as ZIP: {}", epub_path.display()))?;
+
+        let package_path = package_path(&read_entry(&mut archive, CONTAINER_PATH)?)?;
+        let package = read_entry(&mut archive, &package_path)?;
+        let package_tokens = tokenize(&package);
+
+        let mut chapters = Vec::new();
+        for chapter_path in spine_paths(&package_tokens, &package_path) {
+            match read_entry(&mut archive, &chapter_path) {
+                Ok(chapter) => {
+                    let text = html_to_text(&chapter);
+                    if !text.is_empty() {
+                        chapters.push(text);
+                    }
+                }
+                Err(err) => eprintln!("⚠️  Warning: Skipping EPUB chapter: {}", err),
+            }
+        }
+        if chapters.is_empty() {
+            return Err(anyhow!(
+                "EPUB has no readable chapters: {}",
+                epub_path.display()
+            ));
+        }
+
+        let mut metadata = extract_metadata(&package_tokens);
+        if metadata.title.is_none() {
+            metadata.title = epub_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string);
+        }
+
+        let relative_path = epub_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown.epub")
+            .to_string();
+
+        Ok(PdfIntermediate {
+            source_file_relative_path: relative_path,
+            category_path_tags: vec![],
+            extracted_metadata_guess: metadata,
+            auto_cleaned_text: chapters.join("\n\n"),
+            status: "auto_extracted".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use zip::write::{FileOptions, ZipWriter};
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+    const PACKAGE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>On Computable Numbers</dc:title>
+    <dc:creator>Alan Turing</dc:creator>
+    <dc:date>1936</dc:date>
+    <dc:subject>Computability</dc:subject>
+  </metadata>
+  <manifest>
+    <item id="notes" href="text/notes.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/chapter%202.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine>
+    <itemref idref="ch1"/>
+    <itemref idref="ch2"/>
+    <itemref idref="notes" linear="no"/>
+  </spine>
+</package>"#;
+
+    fn build_epub(entries: &[(&str, &str)]) -> Result<NamedTempFile> {
+        let mut temp_file = NamedTempFile::new()?;
+        {
+            let mut zip = ZipWriter::new(temp_file.as_file_mut());
+            for (name, content) in entries {
+                zip.start_file(*name, FileOptions::default())?;
+                zip.write_all(content.as_bytes())?;
+            }
+            zip.finish()?;
+        }
+        Ok(temp_file)
+    }
+
+    #[test]
+    fn test_extracts_chapters_in_spine_order() -> Result<()> {
+        let epub = build_epub(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", PACKAGE),
+            (
+                "OEBPS/text/ch1.xhtml",
+                "<html><body><h1>Introduction</h1><p>First chapter.</p></body></html>",
+            ),
+            (
+                "OEBPS/text/chapter 2.xhtml",
+                "<html><body><h1>Machines</h1><p>Second chapter.</p></body></html>",
+            ),
+            (
+                "OEBPS/text/notes.xhtml",
+                "<html><body><p>Notes.</p></body></html>",
+            ),
+        ])?;
+
+        let result = EpubExtractor::extract(epub.path())?;
+        assert_eq!(
+            result.auto_cleaned_text,
+            "# Introduction\n\nFirst chapter.\n\n# Machines\n\nSecond chapter."
+        );
+        let metadata = result.extracted_metadata_guess;
+        assert_eq!(metadata.title.as_deref(), Some("On Computable Numbers"));
+        assert_eq!(metadata.authors, vec!["Alan Turing"]);
+        assert_eq!(metadata.date_published.as_deref(), Some("1936"));
+        assert_eq!(metadata.keywords_from_source, vec!["Computability"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_archive_without_container() -> Result<()> {
+        let epub = build_epub(&[("OEBPS/content.opf", PACKAGE)])?;
+        assert!(EpubExtractor::extract(epub.path()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_highly_compressed_chapters() -> Result<()> {
+        let bomb = " ".repeat(4 * 1024 * 1024);
+        let epub = build_epub(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", PACKAGE),
+            ("OEBPS/text/ch1.xhtml", bomb.as_str()),
+        ])?;
+        let err = EpubExtractor::extract(epub.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::CompressionRatio { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolves_relative_hrefs() {
+        assert_eq!(
+            resolve_href("OEBPS/content.opf", "text/a.xhtml#top"),
+            "OEBPS/text/a.xhtml"
+        );
+        assert_eq!(
+            resolve_href("OEBPS/pkg/content.opf", "../b%20c.xhtml"),
+            "OEBPS/b c.xhtml"
+        );
+        assert_eq!(resolve_href("content.opf", "./ch.xhtml"), "ch.xhtml");
+    }
+}
@@ -0,0 +1,584 @@
+// HTML extractor
+// Readability-style: keeps the main content and drops navigation, headers,
+// footers, sidebars and link lists around it
+use crate::document_processing::schemas::{DocumentMetadata, PdfIntermediate};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Elements whose content is never document text
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "form",
+    "button", "select", "textarea", "head",
+];
+
+/// Page furniture around the main content
+const BOILERPLATE_ELEMENTS: &[&str] = &["nav", "header", "footer", "aside", "menu"];
+
+/// Class, id and role fragments that mark page furniture
+const BOILERPLATE_MARKERS: &[&str] = &[
+    "nav",
+    "menu",
+    "footer",
+    "header",
+    "sidebar",
+    "breadcrumb",
+    "cookie",
+    "banner",
+    "advert",
+    "share",
+    "social",
+    "comment",
+    "related",
+    "newsletter",
+    "complementary",
+    "contentinfo",
+];
+
+/// Elements that never have content or an end tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements that start a new block of text
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "li",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "br",
+    "hr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "pre",
+    "table",
+    "tr",
+    "figure",
+    "figcaption",
+    "caption",
+    "body",
+];
+
+/// Blocks with more than this share of their text in links are link lists
+const MAX_LINK_DENSITY: f64 = 0.5;
+
+/// A piece of markup, as the tokenizer sees it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    /// Lowercased tag name and the raw attributes
+    Start {
+        name: String,
+        attrs: &'a str,
+        self_closing: bool,
+    },
+    End {
+        name: String,
+    },
+}
+
+impl Token<'_> {
+    fn is_start_of(&self, tag: &str) -> bool {
+        matches!(self, Token::Start { name, .. } if name == tag)
+    }
+}
+
+/// Split markup into text and tags, dropping comments, doctypes, processing
+/// instructions and CDATA markers
+pub(crate) fn tokenize(markup: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = markup;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if open > 0 {
+            tokens.push(Token::Text(&rest[..open]));
+        }
+        rest = &rest[open..];
+
+        let skip_past = |marker: &str| {
+            rest.find(marker)
+                .map_or("", |at| &rest[at + marker.len()..])
+        };
+        if rest.starts_with("<!--") {
+            rest = skip_past("-->");
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            tokens.push(Token::Text(&cdata[..end]));
+            rest = cdata.get(end + 3..).unwrap_or("");
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = skip_past(">");
+            continue;
+        }
+
+        let Some(close) = rest.find('>') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::End {
+                name: name.trim().to_ascii_lowercase(),
+            });
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // A stray `<` in text
+            tokens.push(Token::Text("<"));
+            tokens.push(Token::Text(tag));
+            tokens.push(Token::Text(">"));
+            continue;
+        }
+        tokens.push(Token::Start {
+            name,
+            attrs: &tag[name_end..],
+            self_closing,
+        });
+    }
+    tokens
+}
+
+/// Value of an attribute in a tag's raw attributes, entities decoded
+pub(crate) fn attribute(attrs: &str, wanted: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = after[1..].find(quote).map_or(after.len(), |at| at + 1);
+                        rest = after.get(end + 1..).unwrap_or("");
+                        &after[1..end]
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        rest = &after[end..];
+                        &after[..end]
+                    }
+                }
+            }
+            None => "",
+        };
+        if name.eq_ignore_ascii_case(wanted) {
+            return Some(decode_entities(value));
+        }
+    }
+}
+
+/// Decode character references and the common named entities
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            "copy" => Some('©'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Index just past the end tag closing the element started at `start`, or
+/// the end of the tokens when it is never closed
+pub(crate) fn element_end(tokens: &[Token<'_>], start: usize) -> usize {
+    let Some(Token::Start { name, .. }) = tokens.get(start) else {
+        return start + 1;
+    };
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Start {
+                name: other,
+                self_closing: false,
+                ..
+            } if other == name => depth += 1,
+            Token::End { name: other } if other == name => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Text inside the first element with the given tag
+pub(crate) fn element_text(tokens: &[Token<'_>], tag: &str) -> Option<String> {
+    let start = tokens.iter().position(|token| token.is_start_of(tag))?;
+    let text = tokens[start..element_end(tokens, start)]
+        .iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(decode_entities(text)),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = collapse_whitespace(&text);
+    (!text.is_empty()).then_some(text)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_boilerplate(name: &str, attrs: &str) -> bool {
+    if BOILERPLATE_ELEMENTS.contains(&name) {
+        return true;
+    }
+    ["class", "id", "role"]
+        .iter()
+        .filter_map(|attr| attribute(attrs, attr))
+        .any(|value| {
+            let value = value.to_ascii_lowercase();
+            value
+                .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+                .any(|word| BOILERPLATE_MARKERS.contains(&word))
+        })
+}
+
+/// Where the main content is: the first `article` or `main` element, or
+/// failing those the body
+fn content_range(tokens: &[Token<'_>]) -> (usize, usize) {
+    let main = tokens.iter().position(|token| match token {
+        Token::Start { name, attrs, .. } => {
+            name == "article"
+                || name == "main"
+                || attribute(attrs, "role").is_some_and(|role| role.eq_ignore_ascii_case("main"))
+        }
+        _ => false,
+    });
+    match main.or_else(|| tokens.iter().position(|token| token.is_start_of("body"))) {
+        Some(start) => (start, element_end(tokens, start)),
+        None => (0, tokens.len()),
+    }
+}
+
+/// Text being gathered into a block
+#[derive(Default)]
+struct Block {
+    prefix: String,
+    text: String,
+    link_chars: usize,
+}
+
+impl Block {
+    /// Add the block to `blocks` unless it is empty or mostly links
+    fn flush(&mut self, blocks: &mut Vec<String>) {
+        let block = std::mem::take(self);
+        let text = collapse_whitespace(&block.text);
+        if text.is_empty() {
+            return;
+        }
+        let link_density = block.link_chars as f64 / block.text.trim().chars().count() as f64;
+        if block.prefix.starts_with('#') || link_density <= MAX_LINK_DENSITY {
+            blocks.push(format!("{}{}", block.prefix, text));
+        }
+    }
+}
+
+/// The main content of an HTML page as text, with headings and list items
+/// marked up as Markdown and blocks separated by blank lines
+pub(crate) fn html_to_text(html: &str) -> String {
+    let tokens = tokenize(html);
+    let (start, end) = content_range(&tokens);
+
+    let mut blocks = Vec::new();
+    let mut block = Block::default();
+    let mut link_depth = 0usize;
+    let mut index = start;
+    while index < end {
+        match &tokens[index] {
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if link_depth > 0 {
+                    block.link_chars += text.trim().chars().count();
+                }
+                block.text.push_str(&text);
+            }
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => {
+                if !self_closing
+                    && !VOID_ELEMENTS.contains(&name.as_str())
+                    && (SKIPPED_ELEMENTS.contains(&name.as_str())
+                        || (index > start && is_boilerplate(name, attrs)))
+                {
+                    index = element_end(&tokens, index);
+                    continue;
+                }
+                if name == "a" && !self_closing {
+                    link_depth += 1;
+                }
+                if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                    block.flush(&mut blocks);
+                    match name.as_str() {
+                        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                            let level = usize::from(name.as_bytes()[1] - b'0');
+                            block.prefix = format!("{} ", "#".repeat(level));
+                        }
+                        "li" => block.prefix = "- ".to_string(),
+                        _ => {}
+                    }
+                } else {
+                    block.text.push(' ');
+                }
+            }
+            Token::End { name } => {
+                if name == "a" {
+                    link_depth = link_depth.saturating_sub(1);
+                }
+                if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                    block.flush(&mut blocks);
+                } else {
+                    block.text.push(' ');
+                }
+            }
+        }
+        index += 1;
+    }
+    block.flush(&mut blocks);
+    blocks.join("\n\n")
+}
+
+/// Metadata from the page's `<title>` and `<meta>` tags, including the
+/// `citation_*` tags scholarly publishers add
+fn extract_metadata(tokens: &[Token<'_>]) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata::default();
+    for token in tokens {
+        let Token::Start { name, attrs, .. } = token else {
+            continue;
+        };
+        if name != "meta" {
+            continue;
+        }
+        let (Some(key), Some(content)) = (
+            attribute(attrs, "name").or_else(|| attribute(attrs, "property")),
+            attribute(attrs, "content"),
+        ) else {
+            continue;
+        };
+        let content = collapse_whitespace(&content);
+        if content.is_empty() {
+            continue;
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "citation_title" | "og:title" => {
+                metadata.title.get_or_insert(content);
+            }
+            "citation_author" | "author" => metadata.authors.push(content),
+            "description" | "og:description" | "citation_abstract" => {
+                metadata.abstract_text.get_or_insert(content);
+            }
+            "keywords" | "citation_keywords" => metadata.keywords_from_source.extend(
+                content
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(str::to_string),
+            ),
+            "citation_publication_date" | "citation_date" => {
+                metadata.date_published.get_or_insert(content);
+            }
+            "citation_doi" => {
+                metadata.doi.get_or_insert(content);
+            }
+            "citation_journal_title" => {
+                metadata.journal_ref.get_or_insert(content);
+            }
+            "citation_publisher" | "og:site_name" => {
+                metadata.publisher.get_or_insert(content);
+            }
+            _ => {}
+        }
+    }
+    if metadata.title.is_none() {
+        metadata.title = element_text(tokens, "title").or_else(|| element_text(tokens, "h1"));
+    }
+    metadata
+}
+
+pub struct HtmlExtractor;
+
+impl HtmlExtractor {
+    /// Extract the main content and metadata of an HTML page
+    ///
+    /// Returns a PdfIntermediate structure, like the other text formats
+    pub fn extract(html_path: impl AsRef<Path>) -> Result<PdfIntermediate> {
+        let html_path = html_path.as_ref();
+
+        let bytes = fs::read(html_path)
+            .with_context(|| format!("Failed to read HTML file: {}", html_path.display()))?;
+        let html = String::from_utf8_lossy(&bytes);
+
+        let mut metadata = extract_metadata(&tokenize(&html));
+        if metadata.title.is_none() {
+            metadata.title = html_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string);
+        }
+
+        let relative_path = html_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown.html")
+            .to_string();
+
+        Ok(PdfIntermediate {
+            source_file_relative_path: relative_path,
+            category_path_tags: vec![],
+            extracted_metadata_guess: metadata,
+            auto_cleaned_text: html_to_text(&html),
+            status: "auto_extracted".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Fallback title</title>
+  <meta name="citation_title" content="Sparse Attention &amp; Memory">
+  <meta name="citation_author" content="Ada Lovelace">
+  <meta name="citation_author" content="Alan Turing">
+  <meta name="citation_doi" content="10.1234/example">
+  <meta name="keywords" content="attention, memory">
+  <script>var tracking = "<p>not text</p>";</script>
+  <style>p { color: red; }</style>
+</head>
+<body>
+  <header><a href="/">Home</a> <a href="/about">About</a></header>
+  <nav><ul><li><a href="/a">Section A</a></li><li><a href="/b">Section B</a></li></ul></nav>
+  <div class="cookie-banner">We use cookies.</div>
+  <main>
+    <h1>Sparse Attention</h1>
+    <p>Attention is <em>all</em> you need &mdash; mostly.</p>
+    <!-- a comment <p>hidden</p> -->
+    <ul><li>First point</li><li>Second point</li></ul>
+    <div class="share-links"><a href="/x">Share</a></div>
+    <p><a href="/1">Read</a> <a href="/2">more</a> <a href="/3">links</a></p>
+  </main>
+  <footer>Copyright 2024</footer>
+</body>
+</html>"#;
+
+    #[test]
+    fn test_keeps_main_content_and_drops_boilerplate() {
+        let text = html_to_text(PAGE);
+        assert_eq!(
+            text,
+            "# Sparse Attention\n\nAttention is all you need — mostly.\n\n- First point\n\n- Second point"
+        );
+    }
+
+    #[test]
+    fn test_uses_body_without_main_element() {
+        let text =
+            html_to_text("<body><nav><a href='/'>Home</a></nav><p>Body text, 5 &lt; 6</p></body>");
+        assert_eq!(text, "Body text, 5 < 6");
+    }
+
+    #[test]
+    fn test_extracts_metadata() -> Result<()> {
+        let mut temp_file = NamedTempFile::new()?;
+        write!(temp_file, "{}", PAGE)?;
+
+        let result = HtmlExtractor::extract(temp_file.path())?;
+        let metadata = result.extracted_metadata_guess;
+        assert_eq!(metadata.title.as_deref(), Some("Sparse Attention & Memory"));
+        assert_eq!(metadata.authors, vec!["Ada Lovelace", "Alan Turing"]);
+        assert_eq!(metadata.doi.as_deref(), Some("10.1234/example"));
+        assert_eq!(metadata.keywords_from_source, vec!["attention", "memory"]);
+        assert!(result.auto_cleaned_text.starts_with("# Sparse Attention"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decodes_entities() {
+        assert_eq!(
+            decode_entities("a &amp; b &#233; &#x41; &bogus; &"),
+            "a & b é A &bogus; &"
+        );
+    }
+}
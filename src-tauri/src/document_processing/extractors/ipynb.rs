@@ -0,0 +1,282 @@
+// Jupyter notebook extractor
+// Markdown cells are kept as Markdown, code cells become fenced code blocks
+// in the notebook's language and their text outputs follow in `output` fences
+use super::markdown::{first_heading, normalize_markdown};
+use crate::document_processing::schemas::{DocumentMetadata, PdfIntermediate};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Longest output kept per code cell; long logs and tables are cut here
+const MAX_OUTPUT_CHARS: usize = 4_000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Notebook {
+    cells: Vec<Cell>,
+    metadata: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cell {
+    cell_type: String,
+    #[serde(default)]
+    source: Value,
+    #[serde(default)]
+    outputs: Vec<Value>,
+}
+
+/// Notebook text fields are either a string or a list of lines
+fn multiline(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Remove ANSI colour codes, which tracebacks are full of
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        stripped.push(c);
+    }
+    stripped
+}
+
+/// A fenced code block whose fence is longer than any backtick run inside it
+fn fenced(info: &str, body: &str) -> String {
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{info}\n{}\n{fence}", body.trim_end_matches('\n'))
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((at, _)) => format!("{}\n[output truncated]", &text[..at]),
+        None => text.to_string(),
+    }
+}
+
+/// Text of one output: streams and plain-text results as they are, errors as
+/// their traceback, and rich outputs without a text form as a placeholder
+fn output_text(output: &Value) -> Option<String> {
+    match output.get("output_type")?.as_str()? {
+        "stream" => Some(multiline(output.get("text")?)),
+        "execute_result" | "display_data" => {
+            let data = output.get("data")?.as_object()?;
+            if let Some(text) = data.get("text/plain") {
+                return Some(multiline(text));
+            }
+            data.keys().next().map(|mime| format!("[{mime} output]"))
+        }
+        "error" => {
+            let traceback = output
+                .get("traceback")
+                .and_then(Value::as_array)
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .filter(|traceback| !traceback.is_empty());
+            let text = traceback.unwrap_or_else(|| {
+                let field = |name| output.get(name).and_then(Value::as_str).unwrap_or("");
+                format!("{}: {}", field("ename"), field("evalue"))
+            });
+            Some(strip_ansi(&text))
+        }
+        _ => None,
+    }
+}
+
+/// Language of the notebook's code cells, for the code fences
+fn notebook_language(metadata: &Value) -> String {
+    metadata
+        .pointer("/language_info/name")
+        .or_else(|| metadata.pointer("/kernelspec/language"))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// The notebook as Markdown, cell by cell
+fn notebook_to_markdown(notebook: &Notebook) -> String {
+    let language = notebook_language(&notebook.metadata);
+    let mut blocks = Vec::new();
+    for cell in &notebook.cells {
+        let source = multiline(&cell.source);
+        match cell.cell_type.as_str() {
+            "markdown" => blocks.push(normalize_markdown(&source)),
+            "code" => {
+                if !source.trim().is_empty() {
+                    blocks.push(fenced(&language, &source));
+                }
+                let outputs: Vec<String> = cell
+                    .outputs
+                    .iter()
+                    .filter_map(output_text)
+                    .filter(|text| !text.trim().is_empty())
+                    .map(|text| text.trim_end_matches('\n').to_string())
+                    .collect();
+                if !outputs.is_empty() {
+                    let outputs = outputs.join("\n");
+                    blocks.push(fenced("output", &truncate(&outputs)));
+                }
+            }
+            // Raw cells hold text for other tools, such as LaTeX for nbconvert
+            _ => blocks.push(source.trim().to_string()),
+        }
+    }
+    blocks.retain(|block| !block.trim().is_empty());
+    blocks.join("\n\n")
+}
+
+fn extract_metadata(notebook: &Notebook, text: &str) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata::default();
+    let field = |name: &str| notebook.metadata.get(name);
+    metadata.title = field("title")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| first_heading(text));
+    if let Some(authors) = field("authors").and_then(Value::as_array) {
+        metadata.authors = authors
+            .iter()
+            .filter_map(|author| author.get("name").or(Some(author)))
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect();
+    }
+    metadata
+}
+
+pub struct IpynbExtractor;
+
+impl IpynbExtractor {
+    /// Extract text and metadata from a Jupyter notebook (nbformat 4)
+    ///
+    /// The title comes from the notebook metadata, then the first `#`
+    /// heading, then the file name.
+    pub fn extract(ipynb_path: impl AsRef<Path>) -> Result<PdfIntermediate> {
+        let ipynb_path = ipynb_path.as_ref();
+
+        let content = fs::read_to_string(ipynb_path)
+            .with_context(|| format!("Failed to read notebook: {}", ipynb_path.display()))?;
+        let notebook: Notebook = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse notebook JSON: {}", ipynb_path.display()))?;
+
+        let text = notebook_to_markdown(&notebook);
+        let mut metadata = extract_metadata(&notebook, &text);
+        if metadata.title.is_none() {
+            metadata.title = ipynb_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string);
+        }
+
+        let relative_path = ipynb_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown.ipynb")
+            .to_string();
+
+        Ok(PdfIntermediate {
+            source_file_relative_path: relative_path,
+            category_path_tags: vec![],
+            extracted_metadata_guess: metadata,
+            auto_cleaned_text: text,
+            status: "auto_extracted".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const NOTEBOOK: &str = r##"{
+  "nbformat": 4,
+  "nbformat_minor": 5,
+  "metadata": {
+    "authors": [{"name": "Ada Lovelace"}],
+    "kernelspec": {"name": "python3", "display_name": "Python 3", "language": "python"}
+  },
+  "cells": [
+    {"cell_type": "markdown", "metadata": {}, "source": ["Results\n", "=======\n", "\n", "Fit the model."]},
+    {
+      "cell_type": "code",
+      "execution_count": 1,
+      "metadata": {},
+      "source": "print('loss')\nloss",
+      "outputs": [
+        {"output_type": "stream", "name": "stdout", "text": ["loss\n"]},
+        {"output_type": "execute_result", "execution_count": 1, "data": {"text/plain": ["0.25"]}, "metadata": {}},
+        {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo="}, "metadata": {}}
+      ]
+    },
+    {
+      "cell_type": "code",
+      "execution_count": 2,
+      "metadata": {},
+      "source": ["1 / 0"],
+      "outputs": [
+        {"output_type": "error", "ename": "ZeroDivisionError", "evalue": "division by zero", "traceback": ["\u001b[0;31mZeroDivisionError\u001b[0m: division by zero"]}
+      ]
+    },
+    {"cell_type": "code", "execution_count": null, "metadata": {}, "source": [], "outputs": []}
+  ]
+}"##;
+
+    #[test]
+    fn test_extracts_cells_and_outputs() -> Result<()> {
+        let mut temp_file = NamedTempFile::new()?;
+        write!(temp_file, "{}", NOTEBOOK)?;
+
+        let result = IpynbExtractor::extract(temp_file.path())?;
+        assert_eq!(
+            result.auto_cleaned_text,
+            "# Results\n\nFit the model.\n\n\
+             ```python\nprint('loss')\nloss\n```\n\n\
+             ```output\nloss\n0.25\n[image/png output]\n```\n\n\
+             ```python\n1 / 0\n```\n\n\
+             ```output\nZeroDivisionError: division by zero\n```"
+        );
+        let metadata = result.extracted_metadata_guess;
+        assert_eq!(metadata.title.as_deref(), Some("Results"));
+        assert_eq!(metadata.authors, vec!["Ada Lovelace"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fence_outgrows_backticks_in_cell() {
+        assert_eq!(
+            fenced("markdown", "```\ncode\n```"),
+            "````markdown\n```\ncode\n```\n````"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_json() -> Result<()> {
+        let mut temp_file = NamedTempFile::new()?;
+        write!(temp_file, "not a notebook")?;
+        assert!(IpynbExtractor::extract(temp_file.path()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+// Markdown extractor
+// Markdown is already the canonical text format: headings are kept as they
+// are written, setext headings are rewritten as ATX ones, and YAML front
+// matter is lifted into the metadata
+use crate::document_processing::schemas::{DocumentMetadata, PdfIntermediate};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Whether a line opens or closes a fenced code block, and with what fence
+fn fence_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    ["```", "~~~"]
+        .into_iter()
+        .find(|fence| trimmed.starts_with(fence))
+        .map(|fence| {
+            let marker = fence.chars().next().unwrap_or('`');
+            let len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+            &trimmed[..len]
+        })
+}
+
+/// Level of a setext underline: `===` for 1, `---` for 2
+fn setext_level(line: &str) -> Option<usize> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// An ATX heading's level and text, trailing `#`s dropped
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let line = line.trim_start();
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text))
+}
+
+/// Split off YAML front matter delimited by `---` lines at the very start
+fn split_front_matter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return (None, text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// A front matter value as a list: `[a, b]`, `a, b` or the `- item` lines
+/// that followed the key
+fn list_value(inline: &str, items: &[String]) -> Vec<String> {
+    let inline = inline.trim();
+    let inline = inline
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(inline);
+    inline
+        .split(',')
+        .map(unquote)
+        .chain(items.iter().cloned())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Metadata from the flat keys of YAML front matter
+///
+/// Only `key: value` pairs and `- item` lists are read, which covers the
+/// front matter written by static site generators and paper templates.
+fn parse_front_matter(front_matter: &str, metadata: &mut DocumentMetadata) {
+    let mut entries: Vec<(String, String, Vec<String>)> = Vec::new();
+    for line in front_matter.lines() {
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            if let Some((_, _, items)) = entries.last_mut() {
+                // `- name: Ada` lists of author records
+                let item = item.trim_start_matches("name:");
+                items.push(unquote(item));
+            }
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            entries.push((
+                key.trim().to_lowercase(),
+                value.trim().to_string(),
+                Vec::new(),
+            ));
+        }
+    }
+
+    for (key, value, items) in entries {
+        match key.as_str() {
+            "title" => metadata.title = Some(unquote(&value)).filter(|v| !v.is_empty()),
+            "author" | "authors" => metadata.authors.extend(list_value(&value, &items)),
+            "date" => metadata.date_published = Some(unquote(&value)).filter(|v| !v.is_empty()),
+            "tags" | "keywords" => metadata
+                .keywords_from_source
+                .extend(list_value(&value, &items)),
+            "abstract" | "description" | "summary" => {
+                metadata.abstract_text = Some(unquote(&value)).filter(|v| !v.is_empty())
+            }
+            "doi" => metadata.doi = Some(unquote(&value)).filter(|v| !v.is_empty()),
+            _ => {}
+        }
+    }
+}
+
+/// Normalize Markdown for the canonical text: setext headings become ATX
+/// headings, runs of blank lines collapse to one and trailing whitespace
+/// goes. Fenced code blocks are left exactly as written.
+pub(crate) fn normalize_markdown(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut open_fence: Option<&str> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+
+        if let Some(fence) = open_fence {
+            if fence_marker(line).is_some_and(|marker| marker.starts_with(fence)) {
+                open_fence = None;
+            }
+            out.push(line.to_string());
+            continue;
+        }
+        if let Some(marker) = fence_marker(line) {
+            open_fence = Some(marker);
+            out.push(line.trim_end().to_string());
+            continue;
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            if out.last().is_some_and(|last| !last.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+        if let Some((level, heading)) = atx_heading(line) {
+            out.push(format!("{} {}", "#".repeat(level), heading));
+            continue;
+        }
+        let underline = lines.get(index).and_then(|next| setext_level(next));
+        let paragraph_start = out.last().is_none_or(|last| last.is_empty());
+        if let (Some(level), true) = (underline, paragraph_start) {
+            out.push(format!("{} {}", "#".repeat(level), line.trim()));
+            index += 1;
+            continue;
+        }
+        out.push(line.to_string());
+    }
+    while out.last().is_some_and(|last| last.is_empty()) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+/// Text of the first top-level heading outside code blocks
+pub(crate) fn first_heading(markdown: &str) -> Option<String> {
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if fence_marker(line).is_some() {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some((1, heading)) = atx_heading(line) {
+            if !heading.is_empty() {
+                return Some(heading.to_string());
+            }
+        }
+    }
+    None
+}
+
+pub struct MarkdownExtractor;
+
+impl MarkdownExtractor {
+    /// Extract text and metadata from a Markdown file
+    ///
+    /// The title comes from the front matter, then the first `#` heading,
+    /// then the file name.
+    pub fn extract(md_path: impl AsRef<Path>) -> Result<PdfIntermediate> {
+        let md_path = md_path.as_ref();
+
+        let content = fs::read_to_string(md_path)
+            .with_context(|| format!("Failed to read Markdown file: {}", md_path.display()))?;
+        let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+
+        let mut metadata = DocumentMetadata::default();
+        let (front_matter, body) = split_front_matter(content);
+        if let Some(front_matter) = front_matter {
+            parse_front_matter(front_matter, &mut metadata);
+        }
+
+        let text = normalize_markdown(body);
+        if metadata.title.is_none() {
+            metadata.title = first_heading(&text).or_else(|| {
+                md_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(str::to_string)
+            });
+        }
+
+        let relative_path = md_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown.md")
+            .to_string();
+
+        Ok(PdfIntermediate {
+            source_file_relative_path: relative_path,
+            category_path_tags: vec![],
+            extracted_metadata_guess: metadata,
+            auto_cleaned_text: text,
+            status: "auto_extracted".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_normalizes_headings_outside_code() {
+        let markdown = "Overview\n========\n\nIntro text.   \n\n\n\nDetails\n-------\n\
+                        ## Method ##\n```python\n# not a heading\n\n\nx = 1\n```\n";
+        assert_eq!(
+            normalize_markdown(markdown),
+            "# Overview\n\nIntro text.\n\n## Details\n## Method\n\
+             ```python\n# not a heading\n\n\nx = 1\n```"
+        );
+    }
+
+    #[test]
+    fn test_underline_after_paragraph_text_is_not_a_heading() {
+        assert_eq!(
+            normalize_markdown("one line\nsecond line\n---\n"),
+            "one line\nsecond line\n---"
+        );
+        assert_eq!(normalize_markdown("#hashtag"), "#hashtag");
+    }
+
+    #[test]
+    fn test_extracts_front_matter() -> Result<()> {
+        let mut temp_file = NamedTempFile::new()?;
+        write!(
+            temp_file,
+            "---\ntitle: \"Scaling Laws\"\nauthors:\n  - name: Ada Lovelace\n  - Alan Turing\n\
+             date: 2024-03-01\ntags: [ml, scaling]\n---\n# Introduction\n\nBody.\n"
+        )?;
+
+        let result = MarkdownExtractor::extract(temp_file.path())?;
+        let metadata = result.extracted_metadata_guess;
+        assert_eq!(metadata.title.as_deref(), Some("Scaling Laws"));
+        assert_eq!(metadata.authors, vec!["Ada Lovelace", "Alan Turing"]);
+        assert_eq!(metadata.date_published.as_deref(), Some("2024-03-01"));
+        assert_eq!(metadata.keywords_from_source, vec!["ml", "scaling"]);
+        assert_eq!(result.auto_cleaned_text, "# Introduction\n\nBody.");
+        Ok(())
+    }
+
+    #[test]
+    fn test_title_falls_back_to_first_heading() -> Result<()> {
+        let mut temp_file = NamedTempFile::new()?;
+        write!(
+            temp_file,
+            "```\n# comment\n```\n\n## Setup\n\n# Project README\n"
+        )?;
+
+        let result = MarkdownExtractor::extract(temp_file.path())?;
+        assert_eq!(
+            result.extracted_metadata_guess.title.as_deref(),
+            Some("Project README")
+        );
+        Ok(())
+    }
+}
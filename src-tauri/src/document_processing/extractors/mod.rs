@@ -4,8 +4,16 @@ pub mod pdf;
 pub mod latex;
 pub mod txt;
 pub mod docx;
+pub mod html;
+pub mod epub;
+pub mod markdown;
+pub mod ipynb;
 
 pub use pdf::PdfExtractor;
 pub use latex::LatexExtractor;
 pub use txt::TxtExtractor;
 pub use docx::DocxExtractor;
+pub use html::HtmlExtractor;
+pub use epub::EpubExtractor;
+pub use markdown::MarkdownExtractor;
+pub use ipynb::IpynbExtractor;
@@ -31,12 +31,24 @@ pub use schemas::{
     LatexIntermediate,
 };
 
-pub use extractors::{PdfExtractor, LatexExtractor, TxtExtractor, DocxExtractor};
+pub use extractors::{
+    PdfExtractor, LatexExtractor, TxtExtractor, DocxExtractor, HtmlExtractor, EpubExtractor,
+    MarkdownExtractor, IpynbExtractor,
+};
 pub use processors::CanonicalProcessor;
 pub use utils::{find_files_by_extension, get_relative_path, ensure_dir_exists};
 
 use std::path::Path;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+
+use crate::governance;
+use crate::store::policies::Policy;
+
+/// Refuse to read `path` unless it resolves, symlinks included, inside the
+/// project's allowed filesystem roots
+pub fn ensure_sandboxed(policy: &Policy, path: &Path) -> Result<()> {
+    governance::enforce_path_policy(policy, path).map_err(|incident| anyhow!(incident.details))
+}
 
 /// High-level API for processing PDFs to canonical format
 pub fn process_pdf_to_canonical(
@@ -118,18 +130,100 @@ pub fn process_docx_to_canonical(
     Ok(canonical)
 }
 
+/// High-level API for processing HTML to canonical format
+pub fn process_html_to_canonical(
+    html_path: impl AsRef<Path>,
+    privacy_status: Option<String>,
+) -> Result<CanonicalDocument> {
+    let html_path = html_path.as_ref();
+
+    // Extract the main content (returns PdfIntermediate format)
+    let intermediate = HtmlExtractor::extract(html_path)?;
+
+    let canonical = CanonicalProcessor::process_pdf_intermediate(
+        intermediate,
+        html_path,
+        privacy_status,
+    )?;
+
+    Ok(canonical)
+}
+
+/// High-level API for processing EPUB to canonical format
+pub fn process_epub_to_canonical(
+    epub_path: impl AsRef<Path>,
+    privacy_status: Option<String>,
+) -> Result<CanonicalDocument> {
+    let epub_path = epub_path.as_ref();
+
+    // Extract the chapters in reading order (returns PdfIntermediate format)
+    let intermediate = EpubExtractor::extract(epub_path)?;
+
+    let canonical = CanonicalProcessor::process_pdf_intermediate(
+        intermediate,
+        epub_path,
+        privacy_status,
+    )?;
+
+    Ok(canonical)
+}
+
+/// High-level API for processing Markdown to canonical format
+pub fn process_markdown_to_canonical(
+    md_path: impl AsRef<Path>,
+    privacy_status: Option<String>,
+) -> Result<CanonicalDocument> {
+    let md_path = md_path.as_ref();
+
+    // Extract with the heading structure kept (returns PdfIntermediate format)
+    let intermediate = MarkdownExtractor::extract(md_path)?;
+
+    let canonical = CanonicalProcessor::process_pdf_intermediate(
+        intermediate,
+        md_path,
+        privacy_status,
+    )?;
+
+    Ok(canonical)
+}
+
+/// High-level API for processing Jupyter notebooks to canonical format
+pub fn process_ipynb_to_canonical(
+    ipynb_path: impl AsRef<Path>,
+    privacy_status: Option<String>,
+) -> Result<CanonicalDocument> {
+    let ipynb_path = ipynb_path.as_ref();
+
+    // Render cells and outputs as Markdown (returns PdfIntermediate format)
+    let intermediate = IpynbExtractor::extract(ipynb_path)?;
+
+    let canonical = CanonicalProcessor::process_pdf_intermediate(
+        intermediate,
+        ipynb_path,
+        privacy_status,
+    )?;
+
+    Ok(canonical)
+}
+
 /// Process a directory of documents to canonical JSONL
 pub fn process_directory_to_jsonl(
     input_dir: impl AsRef<Path>,
     output_jsonl: impl AsRef<Path>,
     file_extension: &str, // "pdf" or "tex"
     overwrite: bool,
+    policy: &Policy,
 ) -> Result<usize> {
     let input_dir = input_dir.as_ref();
     let output_jsonl = output_jsonl.as_ref();
 
-    // Find all files with the given extension
+    // Find all files with the given extension. The walk follows symlinks, so
+    // every file found is checked against the sandbox, not just the directory
+    ensure_sandboxed(policy, input_dir)?;
     let files = find_files_by_extension(input_dir, file_extension)?;
+    for file_path in &files {
+        ensure_sandboxed(policy, file_path)?;
+    }
 
     let mut documents = Vec::new();
 
@@ -163,4 +257,40 @@ mod tests {
         let _doc: Option<CanonicalDocument> = None;
         let _metadata: Option<DocumentMetadata> = None;
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_directory_symlinks_cannot_escape_the_sandbox() {
+        let allowed = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let secret = outside.path().join("secret.tex");
+        std::fs::write(&secret, "\\section{Secret}\nOutside the sandbox.").unwrap();
+        std::fs::write(
+            allowed.path().join("paper.tex"),
+            "\\section{Paper}\nInside.",
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&secret, allowed.path().join("linked.tex")).unwrap();
+
+        let policy = Policy {
+            allowed_path_roots: vec![allowed.path().to_string_lossy().into_owned()],
+            ..Policy::default()
+        };
+        let output = outside.path().join("out.jsonl");
+
+        let err =
+            process_directory_to_jsonl(allowed.path(), &output, "tex", true, &policy).unwrap_err();
+        assert!(err.to_string().contains("secret.tex"), "{err}");
+        assert!(!output.exists());
+
+        // The link itself is fine once its target is inside an allowed root
+        let policy = Policy {
+            allowed_path_roots: vec![
+                allowed.path().to_string_lossy().into_owned(),
+                outside.path().to_string_lossy().into_owned(),
+            ],
+            ..Policy::default()
+        };
+        assert!(process_directory_to_jsonl(allowed.path(), &output, "tex", true, &policy).is_ok());
+    }
 }
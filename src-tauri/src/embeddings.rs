@@ -0,0 +1,156 @@
+// src-tauri/src/embeddings.rs
+//!
+//! Embeddings for `embed` steps
+//!
+//! The step splits a prior output into chunks (see `chunk`), embeds each chunk
+//! with the configured model and stores the vectors in the `embeddings` table
+//! for downstream retrieval. Provenance records a manifest rather than the
+//! vectors: the model, the dimensions and, per chunk, the sha256 of its text
+//! and of its vector (little-endian f32 bytes).
+
+use crate::provenance;
+use serde::{Deserialize, Serialize};
+
+/// One embedded chunk of the source output
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddedChunk {
+    pub index: usize,
+    pub text: String,
+    pub text_sha256: String,
+    pub vector: Vec<f32>,
+    pub vector_sha256: String,
+}
+
+impl EmbeddedChunk {
+    pub fn new(index: usize, text: String, vector: Vec<f32>) -> Self {
+        Self {
+            index,
+            text_sha256: provenance::sha256_hex(text.as_bytes()),
+            vector_sha256: vector_sha256(&vector),
+            text,
+            vector,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkDigest {
+    pub index: usize,
+    pub text_sha256: String,
+    pub vector_sha256: String,
+}
+
+/// Step output recorded in provenance in place of the vectors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingManifest {
+    pub model: String,
+    pub dimensions: usize,
+    pub chunks: Vec<ChunkDigest>,
+}
+
+impl EmbeddingManifest {
+    pub fn new(model: &str, chunks: &[EmbeddedChunk]) -> Self {
+        Self {
+            model: model.to_string(),
+            dimensions: chunks.first().map_or(0, |chunk| chunk.vector.len()),
+            chunks: chunks
+                .iter()
+                .map(|chunk| ChunkDigest {
+                    index: chunk.index,
+                    text_sha256: chunk.text_sha256.clone(),
+                    vector_sha256: chunk.vector_sha256.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Little-endian f32 bytes, the form vectors are hashed and stored in
+pub fn vector_bytes(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub fn vector_from_bytes(bytes: &[u8]) -> Option<Vec<f32>> {
+    if bytes.len() % 4 != 0 {
+        return None;
+    }
+    Some(
+        bytes
+            .chunks_exact(4)
+            .map(|raw| f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+            .collect(),
+    )
+}
+
+pub fn vector_sha256(vector: &[f32]) -> String {
+    provenance::sha256_hex(&vector_bytes(vector))
+}
+
+/// Cosine similarity; 0 when either vector is zero or the lengths differ
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Indices of the `k` candidates most similar to `query`, best first
+pub fn nearest(query: &[f32], candidates: &[Vec<f32>], k: usize) -> Vec<usize> {
+    let mut scored: Vec<(usize, f32)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| (index, cosine_similarity(query, candidate)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored.into_iter().take(k).map(|(index, _)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_round_trip_through_bytes_and_hash_stably() {
+        let vector = vec![0.25_f32, -1.5, 3.0];
+        let bytes = vector_bytes(&vector);
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(vector_from_bytes(&bytes), Some(vector.clone()));
+        assert_eq!(vector_from_bytes(&bytes[..5]), None);
+
+        let chunk = EmbeddedChunk::new(0, "alpha".to_string(), vector.clone());
+        assert_eq!(chunk.vector_sha256, provenance::sha256_hex(&bytes));
+        let manifest = EmbeddingManifest::new("nomic-embed-text", &[chunk]);
+        assert_eq!(manifest.dimensions, 3);
+        assert_eq!(
+            manifest.chunks[0].text_sha256,
+            provenance::sha256_hex(b"alpha")
+        );
+    }
+
+    #[test]
+    fn nearest_ranks_by_cosine_similarity() {
+        let candidates = vec![
+            vec![0.0, 1.0],
+            vec![1.0, 0.1],
+            vec![-1.0, 0.0],
+            vec![0.0, 0.0],
+        ];
+        assert_eq!(nearest(&[1.0, 0.0], &candidates, 2), vec![1, 0]);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -0,0 +1,204 @@
+// src-tauri/src/evaluation.rs
+//!
+//! Metrics for `evaluate` steps
+//!
+//! An evaluate step scores a candidate output against a reference output,
+//! taken from another step of the same run or from a checkpoint of an earlier
+//! run. Exact match and ROUGE are always computed; embedding cosine and an
+//! LLM judge are computed when the step names the respective model. The
+//! report commits to the sha256 of both texts, so the scores are tied to the
+//! outputs they were computed on.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Scores of one candidate against one reference
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationScores {
+    pub exact_match: bool,
+    pub rouge_1: f64,
+    pub rouge_2: f64,
+    pub rouge_l: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_cosine: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge: Option<JudgeVerdict>,
+}
+
+/// Score an LLM judge gave the candidate, between 0 and 1
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JudgeVerdict {
+    pub model: String,
+    pub score: f64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub rationale: String,
+}
+
+/// Step output of an evaluate step
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationReport {
+    pub candidate_sha256: String,
+    pub reference_sha256: String,
+    /// Set when the reference came from a checkpoint rather than a step of the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_checkpoint_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    pub scores: EvaluationScores,
+}
+
+/// Whether the texts are equal once surrounding whitespace is ignored
+pub fn exact_match(candidate: &str, reference: &str) -> bool {
+    candidate.trim() == reference.trim()
+}
+
+/// Lowercased alphanumeric words
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn f1(overlap: usize, candidate_len: usize, reference_len: usize) -> f64 {
+    if candidate_len == 0 && reference_len == 0 {
+        return 1.0;
+    }
+    if overlap == 0 {
+        return 0.0;
+    }
+    let precision = overlap as f64 / candidate_len as f64;
+    let recall = overlap as f64 / reference_len as f64;
+    2.0 * precision * recall / (precision + recall)
+}
+
+fn ngram_counts(words: &[String], n: usize) -> HashMap<&[String], usize> {
+    let mut counts = HashMap::new();
+    for ngram in words.windows(n) {
+        *counts.entry(ngram).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// ROUGE-N F1 over word n-grams
+pub fn rouge_n(candidate: &str, reference: &str, n: usize) -> f64 {
+    let candidate = tokens(candidate);
+    let reference = tokens(reference);
+    let candidate_counts = ngram_counts(&candidate, n);
+    let reference_counts = ngram_counts(&reference, n);
+    let overlap = candidate_counts
+        .iter()
+        .map(|(ngram, count)| (*count).min(reference_counts.get(ngram).copied().unwrap_or(0)))
+        .sum();
+    f1(
+        overlap,
+        candidate_counts.values().sum(),
+        reference_counts.values().sum(),
+    )
+}
+
+/// ROUGE-L F1 over the longest common word subsequence
+pub fn rouge_l(candidate: &str, reference: &str) -> f64 {
+    let candidate = tokens(candidate);
+    let reference = tokens(reference);
+    let mut previous = vec![0usize; reference.len() + 1];
+    for word in &candidate {
+        let mut current = vec![0usize; reference.len() + 1];
+        for (j, other) in reference.iter().enumerate() {
+            current[j + 1] = if word == other {
+                previous[j] + 1
+            } else {
+                current[j].max(previous[j + 1])
+            };
+        }
+        previous = current;
+    }
+    f1(previous[reference.len()], candidate.len(), reference.len())
+}
+
+/// Exact match and ROUGE scores; the model-based scores are filled in by the caller
+pub fn lexical_scores(candidate: &str, reference: &str) -> EvaluationScores {
+    EvaluationScores {
+        exact_match: exact_match(candidate, reference),
+        rouge_1: rouge_n(candidate, reference, 1),
+        rouge_2: rouge_n(candidate, reference, 2),
+        rouge_l: rouge_l(candidate, reference),
+        embedding_cosine: None,
+        judge: None,
+    }
+}
+
+/// Prompt asking a judge model to grade the candidate against the reference
+pub fn build_judge_prompt(candidate: &str, reference: &str, criteria: Option<&str>) -> String {
+    let criteria = criteria
+        .map(str::trim)
+        .filter(|criteria| !criteria.is_empty())
+        .unwrap_or("How well does the candidate convey the same content as the reference?");
+    format!(
+        "You are grading a candidate answer against a reference answer.\n\
+         Criteria: {criteria}\n\n\
+         Reference:\n{reference}\n\n\
+         Candidate:\n{candidate}\n\n\
+         Respond with only a JSON object: {{\"score\": <number between 0 and 1>, \"rationale\": \"<one sentence>\"}}"
+    )
+}
+
+/// Score and rationale from a judge reply; the first JSON object in the reply
+/// must carry a `score` between 0 and 1
+pub fn parse_judge_reply(reply: &str) -> Result<(f64, String), String> {
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let object = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err("judge reply contains no JSON object".to_string()),
+    };
+    let value: serde_json::Value = serde_json::from_str(object)
+        .map_err(|err| format!("judge reply is not valid JSON: {err}"))?;
+    let score = value["score"]
+        .as_f64()
+        .ok_or_else(|| "judge reply has no numeric score".to_string())?;
+    if !(0.0..=1.0).contains(&score) {
+        return Err(format!("judge score {score} is outside 0..1"));
+    }
+    let rationale = value["rationale"].as_str().unwrap_or_default().to_string();
+    Ok((score, rationale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lexical_scores_compare_words_not_formatting() {
+        let scores = lexical_scores("The cat sat on the mat.", "the cat sat on the mat");
+        assert!(!scores.exact_match);
+        assert_eq!(scores.rouge_1, 1.0);
+        assert_eq!(scores.rouge_l, 1.0);
+
+        let partial = lexical_scores("the cat sat", "the cat sat on the mat");
+        assert_eq!(partial.rouge_1, 2.0 * 0.5 / 1.5);
+        assert_eq!(partial.rouge_2, 2.0 * 0.4 / 1.4);
+        assert_eq!(
+            rouge_l("mat the on", "the cat sat on the mat"),
+            2.0 * (2.0 / 3.0) * (2.0 / 6.0) / (2.0 / 3.0 + 2.0 / 6.0)
+        );
+        assert_eq!(rouge_n("", "", 1), 1.0);
+        assert_eq!(rouge_n("words", "", 1), 0.0);
+        assert!(exact_match(" same\n", "same"));
+    }
+
+    #[test]
+    fn judge_replies_must_carry_a_score_in_range() {
+        let reply = "Sure.\n{\"score\": 0.75, \"rationale\": \"Mostly right.\"}";
+        assert_eq!(
+            parse_judge_reply(reply).unwrap(),
+            (0.75, "Mostly right.".to_string())
+        );
+        assert!(parse_judge_reply("{\"score\": 7}").is_err());
+        assert!(parse_judge_reply("eight out of ten").is_err());
+        assert!(build_judge_prompt("a", "b", None).contains("Reference:\nb"));
+    }
+}
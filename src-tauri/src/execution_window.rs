@@ -0,0 +1,317 @@
+// src-tauri/src/execution_window.rs
+//!
+//! Execution windows for runs that use local models
+//!
+//! A project policy can confine runs that call local models (or fine-tune
+//! locally) to a daily time window, to AC power and to a maximum system
+//! temperature. Outside the window such runs wait in the run queue as
+//! `deferred` and re-check every `RECHECK_INTERVAL`; runs that only call
+//! hosted providers are never deferred.
+//!
+//! Power and temperature are read from sysfs on Linux and from `pmset` on
+//! macOS. Where a status cannot be read the condition is taken as met, so an
+//! unsupported platform never blocks runs indefinitely.
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How often a deferred run checks whether its window has opened
+pub const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWindow {
+    /// Local time the window opens, as "HH:MM"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// Local time the window closes, as "HH:MM"; earlier than `start` for
+    /// windows that span midnight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// Defer while the machine runs on battery
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_ac_power: bool,
+    /// Defer while the hottest thermal zone is above this temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_temperature_celsius: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+/// Host conditions a window is checked against
+#[derive(Debug, Clone, Copy)]
+pub struct HostStatus {
+    pub local_time: NaiveTime,
+    pub power: PowerSource,
+    pub temperature_celsius: Option<f64>,
+}
+
+impl HostStatus {
+    pub fn current() -> Self {
+        Self {
+            local_time: Local::now().time(),
+            power: power_source(),
+            temperature_celsius: max_temperature_celsius(),
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("invalid window time '{value}', expected HH:MM"))
+}
+
+/// Check window times and limits before the policy is stored
+pub fn validate_window(window: &ExecutionWindow) -> Result<(), String> {
+    match (&window.start, &window.end) {
+        (Some(start), Some(end)) => {
+            parse_time(start)?;
+            parse_time(end)?;
+        }
+        (None, None) => {}
+        _ => return Err("execution windows need both a start and an end time".to_string()),
+    }
+    if let Some(limit) = window.max_temperature_celsius {
+        if !limit.is_finite() || limit <= 0.0 {
+            return Err("maximum temperature must be a positive number".to_string());
+        }
+    }
+    Ok(())
+}
+
+impl ExecutionWindow {
+    /// Whether the window's times admit `time`; windows without valid times
+    /// always do
+    fn opens_at(&self, time: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (&self.start, &self.end) else {
+            return true;
+        };
+        let (Ok(open), Ok(close)) = (parse_time(start), parse_time(end)) else {
+            return true;
+        };
+        if open <= close {
+            open == close || (open <= time && time < close)
+        } else {
+            time >= open || time < close
+        }
+    }
+
+    /// This window narrowed to `default`: times that open outside the
+    /// default's window fall back to it, and both windows' power and
+    /// temperature limits apply
+    pub fn within(&self, default: &ExecutionWindow) -> ExecutionWindow {
+        let inside_default = (0..24 * 60)
+            .filter_map(|minute| NaiveTime::from_hms_opt(minute / 60, minute % 60, 0))
+            .all(|time| !self.opens_at(time) || default.opens_at(time));
+        let times = if inside_default { self } else { default };
+        ExecutionWindow {
+            start: times.start.clone(),
+            end: times.end.clone(),
+            require_ac_power: self.require_ac_power || default.require_ac_power,
+            max_temperature_celsius: match (
+                self.max_temperature_celsius,
+                default.max_temperature_celsius,
+            ) {
+                (Some(own), Some(cap)) => Some(own.min(cap)),
+                (own, cap) => own.or(cap),
+            },
+        }
+    }
+
+    /// Why runs must wait under the given conditions, or None if they may start
+    pub fn deferral_reason(&self, status: &HostStatus) -> Option<String> {
+        if let (Some(start), Some(end)) = (&self.start, &self.end) {
+            if !self.opens_at(status.local_time) {
+                return Some(format!("outside execution window {start}-{end}"));
+            }
+        }
+        if self.require_ac_power && status.power == PowerSource::Battery {
+            return Some("running on battery power".to_string());
+        }
+        if let (Some(limit), Some(temperature)) =
+            (self.max_temperature_celsius, status.temperature_celsius)
+        {
+            if temperature > limit {
+                return Some(format!(
+                    "system temperature {temperature:.0}°C is above {limit:.0}°C"
+                ));
+            }
+        }
+        None
+    }
+}
+
+/// `pmset -g batt` reports the source on its first line
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> PowerSource {
+    if output.contains("'AC Power'") {
+        PowerSource::Ac
+    } else if output.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn power_source() -> PowerSource {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Unknown;
+    };
+    let read = |path: &std::path::Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_str() {
+            "Mains" | "USB" if read(&path, "online") == "1" => return PowerSource::Ac,
+            "Battery" if read(&path, "status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    // Machines without a battery run on mains power
+    if discharging {
+        PowerSource::Battery
+    } else {
+        PowerSource::Ac
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn power_source() -> PowerSource {
+    std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .map(|output| parse_pmset(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(PowerSource::Unknown)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn power_source() -> PowerSource {
+    PowerSource::Unknown
+}
+
+/// Hottest thermal zone in °C, where the platform exposes one
+#[cfg(target_os = "linux")]
+pub fn max_temperature_celsius() -> Option<f64> {
+    std::fs::read_dir("/sys/class/thermal")
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("thermal_zone")
+        })
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("temp")).ok())
+        .filter_map(|value| value.trim().parse::<f64>().ok())
+        .map(|millidegrees| millidegrees / 1000.0)
+        .reduce(f64::max)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn max_temperature_celsius() -> Option<f64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(time: &str, power: PowerSource, temperature: Option<f64>) -> HostStatus {
+        HostStatus {
+            local_time: parse_time(time).unwrap(),
+            power,
+            temperature_celsius: temperature,
+        }
+    }
+
+    #[test]
+    fn overnight_windows_span_midnight() {
+        let window = ExecutionWindow {
+            start: Some("22:00".into()),
+            end: Some("06:30".into()),
+            ..Default::default()
+        };
+        assert!(window
+            .deferral_reason(&status("23:15", PowerSource::Ac, None))
+            .is_none());
+        assert!(window
+            .deferral_reason(&status("05:59", PowerSource::Ac, None))
+            .is_none());
+        assert_eq!(
+            window.deferral_reason(&status("12:00", PowerSource::Ac, None)),
+            Some("outside execution window 22:00-06:30".to_string())
+        );
+    }
+
+    #[test]
+    fn power_and_temperature_limits_defer_runs() {
+        let window = ExecutionWindow {
+            require_ac_power: true,
+            max_temperature_celsius: Some(80.0),
+            ..Default::default()
+        };
+        assert!(window
+            .deferral_reason(&status("12:00", PowerSource::Battery, None))
+            .is_some());
+        assert!(window
+            .deferral_reason(&status("12:00", PowerSource::Ac, Some(91.0)))
+            .is_some());
+        // Unreadable status does not block runs
+        assert!(window
+            .deferral_reason(&status("12:00", PowerSource::Unknown, None))
+            .is_none());
+
+        assert_eq!(
+            parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0"),
+            PowerSource::Battery
+        );
+        assert!(validate_window(&ExecutionWindow {
+            start: Some("22:00".into()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn windows_narrow_to_the_default() {
+        let nights = ExecutionWindow {
+            start: Some("22:00".into()),
+            end: Some("06:00".into()),
+            max_temperature_celsius: Some(80.0),
+            ..Default::default()
+        };
+        let late_nights = ExecutionWindow {
+            start: Some("01:00".into()),
+            end: Some("05:00".into()),
+            require_ac_power: true,
+            ..Default::default()
+        };
+        let narrowed = late_nights.within(&nights);
+        assert_eq!(narrowed.start.as_deref(), Some("01:00"));
+        assert!(narrowed.require_ac_power);
+        assert_eq!(narrowed.max_temperature_celsius, Some(80.0));
+
+        // Hours the default does not open fall back to its window
+        let evenings = ExecutionWindow {
+            start: Some("18:00".into()),
+            end: Some("23:00".into()),
+            ..Default::default()
+        };
+        assert_eq!(evenings.within(&nights).start.as_deref(), Some("22:00"));
+        assert_eq!(
+            ExecutionWindow::default().within(&nights).end.as_deref(),
+            Some("06:00")
+        );
+    }
+}
@@ -0,0 +1,478 @@
+// src-tauri/src/finetune.rs
+//!
+//! Local fine-tuning jobs for `finetune` steps
+//!
+//! The step names a training program (e.g. llama.cpp `finetune` or axolotl)
+//! and its arguments; the program is run directly, never through a shell, and
+//! only when the project policy lists it in `allowed_training_programs`.
+//! `{{name}}` placeholders in the arguments are filled from the step's
+//! hyperparameters, which are also passed as `INTELEXTA_HP_<NAME>` environment
+//! variables. The job's evidence is recorded as separate checkpoints:
+//!
+//! - hyperparameters as configured
+//! - dataset files with their sha256 at training time
+//! - the loss curve parsed from the job output (stored as an attachment)
+//! - the sha256 of the weights file the job produced
+//!
+//! Replay does not retrain; it re-hashes the datasets and weights and checks
+//! them against the recorded manifest.
+
+use crate::governance::{self, Incident};
+use crate::store::policies::Policy;
+use crate::{orchestrator, provenance};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Wall-clock limit when the step does not set `timeout_secs`
+pub const DEFAULT_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+/// Bytes of stderr quoted when a job fails
+const MAX_LOG_TAIL_BYTES: usize = 8_192;
+
+/// Training job as configured on a `finetune` step
+#[derive(Debug, Clone)]
+pub struct FineTuneJob<'a> {
+    pub base_model: &'a str,
+    pub command: &'a [String],
+    pub hyperparameters: &'a serde_json::Map<String, Value>,
+    pub datasets: &'a [String],
+    pub output_weights: &'a str,
+    pub loss_log: Option<&'a str>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetVersion {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct LossPoint {
+    pub step: u64,
+    pub loss: f64,
+}
+
+/// Everything a fine-tune checkpoint attests, stored as the step output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FineTuneManifest {
+    pub base_model: String,
+    pub command: Vec<String>,
+    pub hyperparameters: serde_json::Map<String, Value>,
+    pub datasets: Vec<DatasetVersion>,
+    pub loss_curve_sha256: String,
+    pub loss_points: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_loss: Option<f64>,
+    pub weights_path: String,
+    pub weights_sha256: String,
+}
+
+/// Result of a completed job; the loss curve is kept out of the manifest so it
+/// can be stored as its own attachment
+pub struct FineTuneOutcome {
+    pub manifest: FineTuneManifest,
+    pub loss_curve: Vec<LossPoint>,
+}
+
+pub fn hash_datasets(datasets: &[String]) -> anyhow::Result<Vec<DatasetVersion>> {
+    datasets
+        .iter()
+        .map(|path| {
+            let sha256 = provenance::sha256_file(Path::new(path))
+                .with_context(|| format!("failed to hash dataset {path}"))?;
+            Ok(DatasetVersion {
+                path: path.clone(),
+                sha256,
+            })
+        })
+        .collect()
+}
+
+/// Command line with hyperparameter placeholders filled in
+pub fn render_command(
+    command: &[String],
+    hyperparameters: &serde_json::Map<String, Value>,
+) -> Vec<String> {
+    command
+        .iter()
+        .map(|arg| orchestrator::apply_run_parameters(arg, hyperparameters))
+        .collect()
+}
+
+/// The file `program` runs: itself when it is a path, else the first match on
+/// `PATH`, with symlinks resolved
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    let found = if path.components().count() > 1 {
+        path.is_file().then(|| path.to_path_buf())
+    } else {
+        let paths = std::env::var_os("PATH")?;
+        std::env::split_paths(&paths).find_map(|dir| {
+            let candidate = dir.join(program);
+            [candidate.clone(), candidate.with_extension("exe")]
+                .into_iter()
+                .find(|candidate| candidate.is_file())
+        })
+    }?;
+    Some(found.canonicalize().unwrap_or(found))
+}
+
+/// The file `program` runs, if the policy lists it among the training
+/// programs steps may run and it lies inside the filesystem sandbox
+pub fn authorize_program(policy: &Policy, program: &str) -> Result<PathBuf, Incident> {
+    let denied = |details: String| Incident {
+        kind: "training_program_denied".into(),
+        severity: "error".into(),
+        details,
+    };
+    let resolved = resolve_program(program)
+        .ok_or_else(|| denied(format!("Training program '{program}' was not found")))?;
+    let allowed = policy
+        .allowed_training_programs
+        .iter()
+        .any(|allowed| resolve_program(allowed).as_ref() == Some(&resolved));
+    if !allowed {
+        return Err(denied(format!(
+            "Training program {} is not allowed by project policy (allowed: {})",
+            resolved.display(),
+            policy.allowed_training_programs.join(", ")
+        )));
+    }
+    governance::enforce_path_policy(policy, &resolved)?;
+    Ok(resolved)
+}
+
+/// The program `job` may run under `policy`; the files the job writes and
+/// reads back must lie inside the filesystem sandbox too
+pub fn authorize(policy: &Policy, job: &FineTuneJob<'_>) -> Result<PathBuf, Incident> {
+    let command = render_command(job.command, job.hyperparameters);
+    let program = command.first().ok_or_else(|| Incident {
+        kind: "training_program_denied".into(),
+        severity: "error".into(),
+        details: "Fine-tune step has an empty command".into(),
+    })?;
+    let program = authorize_program(policy, program)?;
+    for path in std::iter::once(job.output_weights).chain(job.loss_log) {
+        governance::enforce_path_policy(policy, Path::new(path))?;
+    }
+    Ok(program)
+}
+
+fn hyperparameter_env_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("INTELEXTA_HP_{sanitized}")
+}
+
+/// Extract `(step, loss)` pairs from training logs. Lines mentioning `loss`
+/// followed by a number are taken in order; an explicit `step`/`iter`/`epoch`
+/// number on the same line is used as the step, otherwise the line's position.
+pub fn parse_loss_curve(log: &str) -> Vec<LossPoint> {
+    let mut points = Vec::new();
+    for line in log.lines() {
+        let lower = line.to_ascii_lowercase();
+        let Some(loss) = number_after(&lower, "loss") else {
+            continue;
+        };
+        let step = ["step", "iter", "epoch"]
+            .iter()
+            .find_map(|key| number_after(&lower, key))
+            .filter(|step| step.fract() == 0.0 && *step >= 0.0)
+            .map(|step| step as u64)
+            .unwrap_or(points.len() as u64);
+        points.push(LossPoint { step, loss });
+    }
+    points
+}
+
+/// First number following `key` (skipping `=`, `:`, quotes and spaces)
+fn number_after(line: &str, key: &str) -> Option<f64> {
+    let start = line.find(key)? + key.len();
+    let rest = line[start..].trim_start_matches(|ch: char| {
+        ch.is_ascii_whitespace() || matches!(ch, '=' | ':' | '"' | '\'')
+    });
+    let end = rest
+        .find(|ch: char| !(ch.is_ascii_digit() || matches!(ch, '.' | '-' | 'e' | '+')))
+        .unwrap_or(rest.len());
+    rest[..end]
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+}
+
+fn tail(bytes: &[u8]) -> String {
+    let start = bytes.len().saturating_sub(MAX_LOG_TAIL_BYTES);
+    String::from_utf8_lossy(&bytes[start..]).into_owned()
+}
+
+/// Run the training job to completion and collect its evidence. `program` is
+/// the job's training program as `authorize` resolved it.
+pub fn run_job(job: &FineTuneJob<'_>, program: &Path) -> anyhow::Result<FineTuneOutcome> {
+    let datasets = hash_datasets(job.datasets)?;
+    let command = render_command(job.command, job.hyperparameters);
+    let args = command
+        .get(1..)
+        .ok_or_else(|| anyhow!("fine-tune step has an empty command"))?;
+
+    let mut process = Command::new(program);
+    process
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (name, value) in job.hyperparameters {
+        let value = match value {
+            Value::String(inner) => inner.clone(),
+            other => other.to_string(),
+        };
+        process.env(hyperparameter_env_name(name), value);
+    }
+    let mut child = process
+        .spawn()
+        .with_context(|| format!("failed to start training program {}", program.display()))?;
+
+    // Drain both pipes on threads so a chatty job cannot block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stdout.read_to_end(&mut buffer).map(|_| buffer)
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        stderr.read_to_end(&mut buffer).map(|_| buffer)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > job.timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!(
+                "training job exceeded its {}s time limit",
+                job.timeout.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    };
+    let stdout = stdout_reader
+        .join()
+        .map_err(|_| anyhow!("failed to read training output"))??;
+    let stderr = stderr_reader
+        .join()
+        .map_err(|_| anyhow!("failed to read training output"))??;
+    if !status.success() {
+        return Err(anyhow!(
+            "training job failed ({status}): {}",
+            tail(&stderr).trim()
+        ));
+    }
+
+    let log = match job.loss_log {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read loss log {path}"))?,
+        None => format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&stdout),
+            String::from_utf8_lossy(&stderr)
+        ),
+    };
+    let loss_curve = parse_loss_curve(&log);
+
+    let weights_sha256 = provenance::sha256_file(Path::new(job.output_weights))
+        .with_context(|| format!("training job produced no weights at {}", job.output_weights))?;
+
+    Ok(FineTuneOutcome {
+        manifest: FineTuneManifest {
+            base_model: job.base_model.to_string(),
+            command,
+            hyperparameters: job.hyperparameters.clone(),
+            datasets,
+            loss_curve_sha256: provenance::sha256_hex(&provenance::canonical_json(&loss_curve)),
+            loss_points: loss_curve.len(),
+            final_loss: loss_curve.last().map(|point| point.loss),
+            weights_path: job.output_weights.to_string(),
+            weights_sha256,
+        },
+        loss_curve,
+    })
+}
+
+/// Re-hash the recorded datasets and weights; returns the mismatches found
+pub fn check_artifacts(manifest: &FineTuneManifest) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    for dataset in &manifest.datasets {
+        match provenance::sha256_file(Path::new(&dataset.path)) {
+            Ok(sha256) if sha256 == dataset.sha256 => {}
+            Ok(_) => mismatches.push(format!("dataset {} changed since training", dataset.path)),
+            Err(err) => mismatches.push(format!("dataset {} unreadable: {}", dataset.path, err)),
+        }
+    }
+    match provenance::sha256_file(Path::new(&manifest.weights_path)) {
+        Ok(sha256) if sha256 == manifest.weights_sha256 => {}
+        Ok(_) => mismatches.push(format!(
+            "weights {} changed since training",
+            manifest.weights_path
+        )),
+        Err(err) => mismatches.push(format!(
+            "weights {} unreadable: {}",
+            manifest.weights_path, err
+        )),
+    }
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_loss_from_common_log_formats() {
+        let log = "loading model\n\
+                   iter 10: loss = 2.50\n\
+                   {'loss': 1.75, 'epoch': 1}\n\
+                   step=30 train_loss: 1.2e0\n\
+                   loss 0.9";
+        let curve = parse_loss_curve(log);
+        assert_eq!(
+            curve,
+            vec![
+                LossPoint {
+                    step: 10,
+                    loss: 2.5
+                },
+                LossPoint {
+                    step: 1,
+                    loss: 1.75
+                },
+                LossPoint {
+                    step: 30,
+                    loss: 1.2
+                },
+                LossPoint { step: 3, loss: 0.9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn hyperparameters_fill_command_placeholders() {
+        let hyperparameters = json!({"lr": 0.0001, "epochs": 3, "data": "train.jsonl"});
+        let command = vec![
+            "finetune".to_string(),
+            "--lr={{lr}}".to_string(),
+            "--epochs".to_string(),
+            "{{epochs}}".to_string(),
+            "{{data}}".to_string(),
+        ];
+        assert_eq!(
+            render_command(&command, hyperparameters.as_object().unwrap()),
+            vec!["finetune", "--lr=0.0001", "--epochs", "3", "train.jsonl"]
+        );
+        assert_eq!(
+            hyperparameter_env_name("warmup-steps"),
+            "INTELEXTA_HP_WARMUP_STEPS"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_job_and_records_artifacts() {
+        let dir = std::env::temp_dir().join(format!("finetune-job-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dataset = dir.join("train.jsonl");
+        std::fs::write(&dataset, "{\"text\": \"hi\"}\n").unwrap();
+        let weights = dir.join("adapter.bin");
+        let script = format!(
+            "echo 'step 1 loss 2.0'; echo 'step 2 loss 1.0'; printf weights > {}",
+            weights.display()
+        );
+        let command = vec!["sh".to_string(), "-c".to_string(), script];
+        let hyperparameters = serde_json::Map::new();
+        let datasets = vec![dataset.display().to_string()];
+        let weights_path = weights.display().to_string();
+        let job = FineTuneJob {
+            base_model: "llama3",
+            command: &command,
+            hyperparameters: &hyperparameters,
+            datasets: &datasets,
+            output_weights: &weights_path,
+            loss_log: None,
+            timeout: Duration::from_secs(30),
+        };
+
+        let policy = Policy {
+            allowed_training_programs: vec!["sh".to_string()],
+            ..Policy::default()
+        };
+        let program = authorize(&policy, &job).unwrap();
+        let outcome = run_job(&job, &program).unwrap();
+        assert_eq!(outcome.loss_curve.len(), 2);
+        assert_eq!(outcome.manifest.final_loss, Some(1.0));
+        assert_eq!(
+            outcome.manifest.weights_sha256,
+            provenance::sha256_hex(b"weights")
+        );
+        assert!(check_artifacts(&outcome.manifest).is_empty());
+
+        std::fs::write(&weights, "tampered").unwrap();
+        assert_eq!(check_artifacts(&outcome.manifest).len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_training_programs_the_policy_does_not_list() {
+        let command = vec![
+            "{{trainer}}".to_string(),
+            "-c".to_string(),
+            "true".to_string(),
+        ];
+        let hyperparameters = json!({"trainer": "sh"});
+        let job = FineTuneJob {
+            base_model: "llama3",
+            command: &command,
+            hyperparameters: hyperparameters.as_object().unwrap(),
+            datasets: &[],
+            output_weights: "adapter.bin",
+            loss_log: None,
+            timeout: Duration::from_secs(30),
+        };
+
+        let refused = authorize(&Policy::default(), &job).unwrap_err();
+        assert_eq!(refused.kind, "training_program_denied");
+        let other_program = Policy {
+            allowed_training_programs: vec!["true".to_string()],
+            ..Policy::default()
+        };
+        let refused = authorize(&other_program, &job).unwrap_err();
+        assert_eq!(refused.kind, "training_program_denied");
+
+        let sandboxed = Policy {
+            allowed_training_programs: vec!["sh".to_string()],
+            allowed_path_roots: vec!["/nonexistent-training-root".to_string()],
+            ..Policy::default()
+        };
+        let refused = authorize(&sandboxed, &job).unwrap_err();
+        assert_eq!(refused.kind, "filesystem_access_denied");
+    }
+}
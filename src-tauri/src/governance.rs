@@ -1,7 +1,12 @@
 // src-tauri/src/governance.rs
 use crate::model_catalog;
-use crate::store::policies::Policy;
+use crate::provider_policy::LOCAL_REGION;
+use crate::store::dataset_splits::HeldOutAssignment;
+use crate::store::policies::{Policy, SubBudget};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Budgets {
@@ -30,6 +35,22 @@ pub fn enforce_budget(budget_tokens: u64, usage_tokens: u64) -> Result<(), Incid
     }
 }
 
+/// Incident for a generation stopped mid-stream by the step's token budget
+pub fn stream_budget_incident(
+    step_label: &str,
+    budget_tokens: u64,
+    usage_tokens: u64,
+    partial_output_sha256: &str,
+) -> Incident {
+    Incident {
+        kind: "budget_exceeded".into(),
+        severity: "error".into(),
+        details: format!(
+            "{step_label} stopped mid-generation: usage={usage_tokens} > budget={budget_tokens}; partial output sha256={partial_output_sha256}"
+        ),
+    }
+}
+
 /// Comprehensive policy enforcement for regular run execution
 /// Returns the first BLOCKING violation encountered, or Ok if all checks pass
 /// Note: Nature Cost violations return warnings but don't block execution
@@ -91,6 +112,322 @@ pub fn enforce_network_policy(policy: &Policy) -> Result<(), Incident> {
     }
 }
 
+/// Data residency: the model must come from a provider, and a region, the
+/// policy's allowlist allows
+pub fn enforce_provider_policy(policy: &Policy, model_id: &str) -> Result<(), Incident> {
+    let provider = model_provider(model_id);
+    let region = model_region(model_id);
+    match policy.provider_allowlist.violation(&provider, &region) {
+        Some(reason) => Err(Incident {
+            kind: "provider_policy_violation".into(),
+            severity: "error".into(),
+            details: format!("Model {model_id} refused by project policy: {reason}"),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Filesystem sandbox enforcement: the path must resolve inside one of the
+/// policy's allowed roots. Symlinks and `..` components are resolved first so
+/// a path cannot escape a root. An empty root list leaves access unrestricted.
+pub fn enforce_path_policy(policy: &Policy, path: &Path) -> Result<(), Incident> {
+    if policy.allowed_path_roots.is_empty() {
+        return Ok(());
+    }
+
+    let resolved = resolve_path(path);
+    let allowed = policy
+        .allowed_path_roots
+        .iter()
+        .map(|root| resolve_path(Path::new(root)))
+        .any(|root| resolved.starts_with(&root));
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(Incident {
+            kind: "filesystem_access_denied".into(),
+            severity: "error".into(),
+            details: format!(
+                "Access to {} denied by project policy (allowed roots: {})",
+                resolved.display(),
+                policy.allowed_path_roots.join(", ")
+            ),
+        })
+    }
+}
+
+/// Held-out split enforcement: a step that sends documents to a model must not
+/// consume any document assigned to a validation or test split.
+pub fn enforce_split_isolation<'a>(
+    held_out: &HashMap<String, HeldOutAssignment>,
+    consumed: impl IntoIterator<Item = &'a String>,
+) -> Result<(), Incident> {
+    let mut leaked: Vec<String> = consumed
+        .into_iter()
+        .filter_map(|hash| {
+            held_out.get(hash).map(|assignment| {
+                format!(
+                    "{} ({} split of {})",
+                    hash,
+                    assignment.split.as_str(),
+                    assignment.dataset
+                )
+            })
+        })
+        .collect();
+    if leaked.is_empty() {
+        return Ok(());
+    }
+    leaked.sort();
+    Err(Incident {
+        kind: "dataset_leakage".into(),
+        severity: "error".into(),
+        details: format!("Step consumes held-out documents: {}", leaked.join(", ")),
+    })
+}
+
+/// Privacy status of ingested documents that any model may receive
+pub const PUBLIC_PRIVACY_STATUS: &str = "public";
+
+/// Privacy enforcement: content derived from documents ingested with a
+/// privacy status other than public must not reach a model served over the
+/// network. The incident blocks unless the policy's incident actions let the
+/// step wait for approval or continue.
+pub fn enforce_privacy_policy<'a>(
+    model_id: &str,
+    requires_network: bool,
+    statuses: impl IntoIterator<Item = &'a String>,
+) -> Result<(), Incident> {
+    let mut restricted: Vec<&str> = statuses
+        .into_iter()
+        .map(String::as_str)
+        .filter(|status| *status != PUBLIC_PRIVACY_STATUS)
+        .collect();
+    if !requires_network || restricted.is_empty() {
+        return Ok(());
+    }
+    restricted.sort_unstable();
+    restricted.dedup();
+    Err(Incident {
+        kind: "restricted_content_egress".into(),
+        severity: "error".into(),
+        details: format!(
+            "Step would send content derived from {} documents to network model {model_id}",
+            restricted.join(", ")
+        ),
+    })
+}
+
+/// Resolve a path for sandbox checks: normalize it lexically against the
+/// current directory, then canonicalize its longest existing ancestor.
+fn resolve_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+
+    for ancestor in normalized.ancestors().skip(1) {
+        if let Ok(canonical) = std::fs::canonicalize(ancestor) {
+            if let Ok(rest) = normalized.strip_prefix(ancestor) {
+                return canonical.join(rest);
+            }
+        }
+    }
+    normalized
+}
+
+/// Cost of a checkpoint, priced from the catalog when it is recorded
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointCost {
+    pub usd: f64,
+    pub nature_cost: f64,
+    /// Emissions of the energy the tokens took; absent for models the catalog
+    /// has no energy figure for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub g_co2e: Option<f64>,
+}
+
+impl CheckpointCost {
+    pub fn priced(tokens: u64, model_id: Option<&str>) -> Self {
+        Self {
+            usd: estimate_usd_cost(tokens, model_id),
+            nature_cost: estimate_nature_cost(tokens, model_id),
+            g_co2e: model_id.and_then(|model| estimate_gco2e(tokens, model)),
+        }
+    }
+}
+
+/// Provider a model's usage counts against: the catalog's, or Ollama for
+/// models the catalog does not list, which the dispatcher sends there
+pub fn model_provider(model_id: &str) -> String {
+    model_catalog::try_get_global_catalog()
+        .and_then(|catalog| catalog.get_model(model_id))
+        .map(|model| model.provider.clone())
+        .unwrap_or_else(|| "ollama".to_string())
+}
+
+/// Region a model's provider processes data in, from the catalog. Ollama
+/// and internal models run on-device; a provider the catalog gives no region
+/// is "unknown", which only a policy without allowed regions lets through
+pub fn model_region(model_id: &str) -> String {
+    let provider = model_provider(model_id);
+    model_catalog::try_get_global_catalog()
+        .and_then(|catalog| catalog.get_provider(&provider))
+        .and_then(|info| info.region.clone())
+        .unwrap_or_else(|| match provider.as_str() {
+            "ollama" | "internal" => LOCAL_REGION.to_string(),
+            _ => "unknown".to_string(),
+        })
+}
+
+/// Tokens and costs attributed to one model
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ModelUsage {
+    pub tokens: u64,
+    pub usd: f64,
+    pub nature_cost: f64,
+}
+
+impl ModelUsage {
+    pub fn priced(tokens: u64, model_id: Option<&str>) -> Self {
+        let cost = CheckpointCost::priced(tokens, model_id);
+        Self {
+            tokens,
+            usd: cost.usd,
+            nature_cost: cost.nature_cost,
+        }
+    }
+
+    pub fn add(&mut self, other: ModelUsage) {
+        self.tokens = self.tokens.saturating_add(other.tokens);
+        self.usd += other.usd;
+        self.nature_cost += other.nature_cost;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SubBudgetScope {
+    Model,
+    Provider,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SubBudgetKind {
+    Tokens,
+    Usd,
+    NatureCost,
+}
+
+/// A model or provider cap that usage exceeds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubBudgetOverrun {
+    pub scope: SubBudgetScope,
+    /// The model id or provider
+    pub name: String,
+    pub budget: SubBudgetKind,
+    pub estimated: f64,
+    pub limit: f64,
+}
+
+impl fmt::Display for SubBudgetOverrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            SubBudgetScope::Model => "model",
+            SubBudgetScope::Provider => "provider",
+        };
+        match self.budget {
+            SubBudgetKind::Tokens => write!(
+                f,
+                "{scope} {} tokens {:.0} > {:.0}",
+                self.name, self.estimated, self.limit
+            ),
+            SubBudgetKind::Usd => write!(
+                f,
+                "{scope} {} USD {:.2} > {:.2}",
+                self.name, self.estimated, self.limit
+            ),
+            SubBudgetKind::NatureCost => write!(
+                f,
+                "{scope} {} Nature Cost {:.2} > {:.2}",
+                self.name, self.estimated, self.limit
+            ),
+        }
+    }
+}
+
+/// The policy's model and provider caps that `usage`, keyed by the model it
+/// was priced with, exceeds
+pub fn sub_budget_overruns(
+    policy: &Policy,
+    usage: &BTreeMap<String, ModelUsage>,
+) -> Vec<SubBudgetOverrun> {
+    let mut by_provider: BTreeMap<String, ModelUsage> = BTreeMap::new();
+    if !policy.provider_budgets.is_empty() {
+        for (model, used) in usage {
+            by_provider.entry(model_provider(model)).or_default().add(*used);
+        }
+    }
+
+    let scopes = [
+        (SubBudgetScope::Model, &policy.model_budgets, usage),
+        (SubBudgetScope::Provider, &policy.provider_budgets, &by_provider),
+    ];
+    let mut overruns = Vec::new();
+    for (scope, budgets, usage) in scopes {
+        for (name, budget) in budgets {
+            let used = usage.get(name).copied().unwrap_or_default();
+            overruns.extend(
+                sub_budget_checks(budget, &used)
+                    .into_iter()
+                    .filter(|(_, estimated, limit)| estimated > limit)
+                    .map(|(kind, estimated, limit)| SubBudgetOverrun {
+                        scope,
+                        name: name.clone(),
+                        budget: kind,
+                        estimated,
+                        limit,
+                    }),
+            );
+        }
+    }
+    overruns
+}
+
+/// Each cap the sub-budget sets, with the usage it applies to
+fn sub_budget_checks(budget: &SubBudget, used: &ModelUsage) -> Vec<(SubBudgetKind, f64, f64)> {
+    [
+        (SubBudgetKind::Tokens, used.tokens as f64, budget.tokens.map(|tokens| tokens as f64)),
+        (SubBudgetKind::Usd, used.usd, budget.usd),
+        (SubBudgetKind::NatureCost, used.nature_cost, budget.nature_cost),
+    ]
+    .into_iter()
+    .filter_map(|(kind, estimated, limit)| Some((kind, estimated, limit?)))
+    .collect()
+}
+
 /// Estimate USD cost based on token count and model
 /// Uses the model catalog for accurate per-model pricing
 pub fn estimate_usd_cost(tokens: u64, model_id: Option<&str>) -> f64 {
@@ -118,7 +455,8 @@ pub fn estimate_usd_cost_legacy(tokens: u64) -> f64 {
 pub fn estimate_nature_cost(tokens: u64, model_id: Option<&str>) -> f64 {
     if let Some(catalog) = model_catalog::try_get_global_catalog() {
         if let Some(model) = model_id {
-            return catalog.calculate_nature_cost(model, tokens);
+            let grid_region = model_catalog::grid_region();
+            return catalog.calculate_nature_cost_in(model, tokens, grid_region.as_deref());
         }
         // If catalog is available but no model specified, use catalog's fallback
         return (tokens as f64 / 1_000_000.0) * catalog.raw.defaults.fallback_nature_cost_per_million_tokens;
@@ -135,6 +473,17 @@ pub fn estimate_nature_cost_legacy(tokens: u64) -> f64 {
     estimate_nature_cost(tokens, None)
 }
 
+/// Estimate gCO2e for a model's tokens on the grid it runs on; the user's
+/// grid region applies to on-device models
+pub fn estimate_gco2e(tokens: u64, model_id: &str) -> Option<f64> {
+    let grid_region = model_catalog::grid_region();
+    model_catalog::try_get_global_catalog()?.calculate_gco2e(
+        model_id,
+        tokens,
+        grid_region.as_deref(),
+    )
+}
+
 /// Estimate energy consumption in kWh for a given model and token count
 pub fn estimate_energy_kwh(tokens: u64, model_id: Option<&str>) -> f64 {
     if let Some(catalog) = model_catalog::try_get_global_catalog() {
@@ -146,3 +495,137 @@ pub fn estimate_energy_kwh(tokens: u64, model_id: Option<&str>) -> f64 {
     // Fallback: assume minimal energy for unknown models
     0.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandboxed_policy(root: &Path) -> Policy {
+        Policy {
+            allowed_path_roots: vec![root.to_string_lossy().to_string()],
+            ..Policy::default()
+        }
+    }
+
+    #[test]
+    fn sub_budgets_cap_models_and_providers() {
+        let mut policy = Policy::default();
+        policy.model_budgets.insert(
+            "claude-3-5-sonnet".to_string(),
+            SubBudget {
+                usd: Some(5.0),
+                ..SubBudget::default()
+            },
+        );
+        policy.provider_budgets.insert(
+            "ollama".to_string(),
+            SubBudget {
+                tokens: Some(1_000),
+                ..SubBudget::default()
+            },
+        );
+        let usage = |tokens, usd| ModelUsage {
+            tokens,
+            usd,
+            nature_cost: 0.0,
+        };
+        let within: BTreeMap<String, ModelUsage> = [
+            ("claude-3-5-sonnet".to_string(), usage(10, 4.5)),
+            ("llama3.2:1b".to_string(), usage(600, 0.0)),
+        ]
+        .into();
+        assert!(sub_budget_overruns(&policy, &within).is_empty());
+
+        let mut over = within.clone();
+        over.get_mut("claude-3-5-sonnet").unwrap().usd = 5.5;
+        over.insert("gemma:2b".to_string(), usage(500, 0.0));
+        let overruns = sub_budget_overruns(&policy, &over);
+        let found: Vec<(SubBudgetScope, &str, SubBudgetKind)> = overruns
+            .iter()
+            .map(|overrun| (overrun.scope, overrun.name.as_str(), overrun.budget))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (SubBudgetScope::Model, "claude-3-5-sonnet", SubBudgetKind::Usd),
+                (SubBudgetScope::Provider, "ollama", SubBudgetKind::Tokens),
+            ]
+        );
+        assert_eq!(overruns[1].to_string(), "provider ollama tokens 1100 > 1000");
+    }
+
+    #[test]
+    fn path_policy_is_unrestricted_without_roots() {
+        assert!(enforce_path_policy(&Policy::default(), Path::new("/etc/passwd")).is_ok());
+    }
+
+    #[test]
+    fn path_policy_allows_paths_inside_roots() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("paper.pdf");
+        std::fs::write(&file, b"%PDF").unwrap();
+        let policy = sandboxed_policy(root.path());
+
+        assert!(enforce_path_policy(&policy, &file).is_ok());
+        assert!(enforce_path_policy(&policy, &root.path().join("missing/notes.txt")).is_ok());
+    }
+
+    #[test]
+    fn path_policy_rejects_escapes() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let policy = sandboxed_policy(root.path());
+
+        let incident = enforce_path_policy(&policy, outside.path()).unwrap_err();
+        assert_eq!(incident.kind, "filesystem_access_denied");
+        assert!(enforce_path_policy(&policy, &root.path().join("../escape.txt")).is_err());
+    }
+
+    #[test]
+    fn split_isolation_flags_held_out_documents() {
+        use crate::store::dataset_splits::SplitKind;
+
+        let held_out = HashMap::from([(
+            "abc123".to_string(),
+            HeldOutAssignment {
+                dataset: "qa-bench".to_string(),
+                split: SplitKind::Test,
+            },
+        )]);
+        let train_doc = "def456".to_string();
+        let test_doc = "abc123".to_string();
+
+        assert!(enforce_split_isolation(&held_out, [&train_doc]).is_ok());
+        let incident = enforce_split_isolation(&held_out, [&train_doc, &test_doc]).unwrap_err();
+        assert_eq!(incident.kind, "dataset_leakage");
+        assert!(incident.details.contains("test split of qa-bench"));
+    }
+
+    #[test]
+    fn restricted_documents_stay_off_network_models() {
+        let anonymized = "consent_obtained_anonymized".to_string();
+        let public = PUBLIC_PRIVACY_STATUS.to_string();
+        assert!(enforce_privacy_policy("claude-haiku", true, [&public]).is_ok());
+        assert!(enforce_privacy_policy("llama3.2:1b", false, [&anonymized]).is_ok());
+
+        let incident =
+            enforce_privacy_policy("claude-haiku", true, [&public, &anonymized]).unwrap_err();
+        assert_eq!(incident.kind, "restricted_content_egress");
+        assert!(incident.details.contains("consent_obtained_anonymized documents"));
+    }
+
+    #[test]
+    fn provider_policy_refuses_models_outside_the_allowlist() {
+        let mut policy = Policy::default();
+        assert!(enforce_provider_policy(&policy, "llama3.2:1b").is_ok());
+
+        // Models the catalog does not list run on local Ollama
+        policy.provider_allowlist.regions = vec![LOCAL_REGION.to_string()];
+        assert!(enforce_provider_policy(&policy, "llama3.2:1b").is_ok());
+
+        policy.provider_allowlist.providers = vec!["anthropic".to_string()];
+        let incident = enforce_provider_policy(&policy, "llama3.2:1b").unwrap_err();
+        assert_eq!(incident.kind, "provider_policy_violation");
+        assert!(incident.details.contains("provider ollama"));
+    }
+}
@@ -0,0 +1,208 @@
+// src-tauri/src/incident_log.rs
+//!
+//! Incident log: a project's incidents, browsable and acknowledged
+//!
+//! Incidents are recorded as `Incident` checkpoints inside their execution's
+//! chain. The log lists them across the project's runs, newest first, and
+//! filters them by severity, kind and UTC day. Acknowledging an incident
+//! records who acknowledged it, when and with what note; the project key
+//! signs the canonical acknowledgement, which commits to the incident
+//! checkpoint's chain hash, so the acknowledgement cannot be moved to another
+//! incident or altered. Acknowledging an incident also acknowledges the
+//! notifications its policy action raised.
+//!
+//! Project archives carry the log, acknowledgements included, as
+//! `incidents.json`; importing an archive restores acknowledgements whose
+//! signatures verify against the project key.
+
+use crate::{
+    dashboard, governance, provenance,
+    store::{
+        incident_acknowledgements::{self, IncidentAcknowledgement},
+        incident_responses,
+    },
+    Error,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{NaiveDate, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// UTC days, both included; an absent bound leaves that side open
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentRecord {
+    pub checkpoint_id: String,
+    pub run_id: String,
+    pub run_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_execution_id: Option<String>,
+    pub timestamp: String,
+    pub kind: String,
+    pub severity: String,
+    pub details: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgement: Option<IncidentAcknowledgement>,
+}
+
+/// The project's incidents, newest first, narrowed by whichever filters are given
+pub fn list_incidents(
+    conn: &Connection,
+    project_id: &str,
+    severity: Option<&str>,
+    kind: Option<&str>,
+    date_range: Option<&DateRange>,
+) -> Result<Vec<IncidentRecord>, Error> {
+    dashboard::ensure_project(conn, project_id)?;
+    let range = date_range.cloned().unwrap_or_default();
+    let mut acknowledgements: HashMap<String, IncidentAcknowledgement> =
+        incident_acknowledgements::list(conn, project_id)?
+            .into_iter()
+            .map(|acknowledgement| (acknowledgement.checkpoint_id.clone(), acknowledgement))
+            .collect();
+
+    let mut stmt = conn.prepare(
+        "SELECT c.id, c.run_id, r.name, c.run_execution_id, c.timestamp, c.incident_json
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         WHERE r.project_id = ?1 AND c.kind = 'Incident' AND c.incident_json IS NOT NULL
+           AND (?2 IS NULL OR date(c.timestamp) >= ?2)
+           AND (?3 IS NULL OR date(c.timestamp) <= ?3)
+         ORDER BY datetime(c.timestamp) DESC, c.id",
+    )?;
+    let rows = stmt.query_map(
+        params![
+            project_id,
+            range.from.map(|day| day.to_string()),
+            range.to.map(|day| day.to_string()),
+        ],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        },
+    )?;
+
+    let mut incidents = Vec::new();
+    for row in rows {
+        let (checkpoint_id, run_id, run_name, run_execution_id, timestamp, incident_json) = row?;
+        let incident: governance::Incident = serde_json::from_str(&incident_json).map_err(|err| {
+            Error::Api(format!("incident of checkpoint {checkpoint_id} is invalid: {err}"))
+        })?;
+        if severity.is_some_and(|severity| severity != incident.severity)
+            || kind.is_some_and(|kind| kind != incident.kind)
+        {
+            continue;
+        }
+        incidents.push(IncidentRecord {
+            acknowledgement: acknowledgements.remove(&checkpoint_id),
+            checkpoint_id,
+            run_id,
+            run_name,
+            run_execution_id,
+            timestamp,
+            kind: incident.kind,
+            severity: incident.severity,
+            details: incident.details,
+        });
+    }
+    Ok(incidents)
+}
+
+/// Acknowledge an incident checkpoint, signing the acknowledgement with the
+/// project key; an incident is acknowledged once
+pub fn acknowledge_incident(
+    conn: &Connection,
+    checkpoint_id: &str,
+    acknowledged_by: &str,
+    note: Option<&str>,
+) -> Result<IncidentAcknowledgement, Error> {
+    let acknowledged_by = acknowledged_by.trim();
+    if acknowledged_by.is_empty() {
+        return Err(Error::Api("an acknowledgement needs who acknowledged it".into()));
+    }
+    let (project_id, kind, curr_chain): (String, String, String) = conn
+        .query_row(
+            "SELECT r.project_id, c.kind, c.curr_chain FROM checkpoints c
+             JOIN runs r ON r.id = c.run_id WHERE c.id = ?1",
+            params![checkpoint_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| Error::Api(format!("checkpoint {checkpoint_id} not found")))?;
+    if kind != "Incident" {
+        return Err(Error::Api(format!("checkpoint {checkpoint_id} is not an incident")));
+    }
+    if let Some(existing) = incident_acknowledgements::get(conn, checkpoint_id)? {
+        return Err(Error::Api(format!(
+            "incident {checkpoint_id} was already acknowledged by {} at {}",
+            existing.acknowledged_by, existing.acknowledged_at
+        )));
+    }
+
+    let signer = provenance::ProjectSigner::load(&project_id)
+        .map_err(|err| Error::Api(format!("failed to load signing key: {err}")))?;
+    let mut acknowledgement = IncidentAcknowledgement {
+        checkpoint_id: checkpoint_id.to_string(),
+        project_id,
+        checkpoint_curr_chain: curr_chain,
+        acknowledged_by: acknowledged_by.to_string(),
+        note: note.map(str::trim).filter(|note| !note.is_empty()).map(str::to_string),
+        acknowledged_at: Utc::now().to_rfc3339(),
+        signer_public_key: signer.public_key(),
+        signature: String::new(),
+    };
+    acknowledgement.signature = signer
+        .sign(&signed_body(&acknowledgement)?)
+        .map_err(|err| Error::Api(format!("failed to sign acknowledgement: {err}")))?;
+
+    incident_acknowledgements::insert(conn, &acknowledgement)?;
+    incident_responses::acknowledge_notifications_for_checkpoint(
+        conn,
+        checkpoint_id,
+        &acknowledgement.acknowledged_at,
+    )?;
+    Ok(acknowledgement)
+}
+
+/// Whether the acknowledgement is signed by `verifying_key` over its contents
+pub fn acknowledgement_signed_by(
+    acknowledgement: &IncidentAcknowledgement,
+    verifying_key: &VerifyingKey,
+) -> bool {
+    let Ok(body) = signed_body(acknowledgement) else {
+        return false;
+    };
+    let signature = STANDARD
+        .decode(&acknowledgement.signature)
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes));
+    signature.is_some_and(|signature| verifying_key.verify(&body, &signature).is_ok())
+}
+
+fn signed_body(acknowledgement: &IncidentAcknowledgement) -> Result<Vec<u8>, Error> {
+    let mut value = serde_json::to_value(acknowledgement)
+        .map_err(|err| Error::Api(format!("failed to serialize acknowledgement: {err}")))?;
+    if let Value::Object(ref mut fields) = value {
+        fields.remove("signature");
+    }
+    Ok(provenance::canonical_json(&value))
+}
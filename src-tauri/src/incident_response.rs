@@ -0,0 +1,322 @@
+// src-tauri/src/incident_response.rs
+//!
+//! Incident responses: what an execution does once it recorded an incident
+//!
+//! A project policy maps incident kinds to an `IncidentAction`. Kinds it does
+//! not list keep the built-in behavior: errors block the execution and
+//! warnings let it continue. `notify` continues and leaves a notification to
+//! acknowledge; `pause_for_approval` holds the execution, with its run queue
+//! slot, until the pending request is approved (continue) or rejected (block).
+//!
+//! Responses are kept in the `incident_responses` table next to the incident
+//! checkpoint they belong to, so the decision itself is part of the record.
+
+use crate::governance::Incident;
+use crate::store::incident_responses::{self, IncidentResponse};
+use crate::Error;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often a paused execution re-reads its approval request, in case it
+/// was decided from another process
+pub const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wakes paused executions when an approval request is decided
+static DECISIONS: Lazy<(Mutex<()>, Condvar)> = Lazy::new(|| (Mutex::new(()), Condvar::new()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentAction {
+    /// Stop the execution at the incident
+    Block,
+    /// Record the incident and carry on
+    WarnContinue,
+    /// Hold the execution until the incident is approved or rejected
+    PauseForApproval,
+    /// Carry on and leave a notification to acknowledge
+    Notify,
+}
+
+impl IncidentAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncidentAction::Block => "block",
+            IncidentAction::WarnContinue => "warn_continue",
+            IncidentAction::PauseForApproval => "pause_for_approval",
+            IncidentAction::Notify => "notify",
+        }
+    }
+
+    /// Rank by how much of the execution the action holds back
+    fn strictness(&self) -> u8 {
+        match self {
+            IncidentAction::WarnContinue => 0,
+            IncidentAction::Notify => 1,
+            IncidentAction::PauseForApproval => 2,
+            IncidentAction::Block => 3,
+        }
+    }
+
+    /// Behavior for kinds the policy does not configure
+    pub fn default_for(incident: &Incident) -> Self {
+        if incident.severity == "error" {
+            IncidentAction::Block
+        } else {
+            IncidentAction::WarnContinue
+        }
+    }
+}
+
+/// The configured action for the incident's kind, or its default
+pub fn action_for(
+    actions: &BTreeMap<String, IncidentAction>,
+    incident: &Incident,
+) -> IncidentAction {
+    actions
+        .get(&incident.kind)
+        .copied()
+        .unwrap_or_else(|| IncidentAction::default_for(incident))
+}
+
+/// `actions` with every kind `default` configures at least as strict as the
+/// default's action for it
+pub fn actions_within(
+    actions: &BTreeMap<String, IncidentAction>,
+    default: &BTreeMap<String, IncidentAction>,
+) -> BTreeMap<String, IncidentAction> {
+    let mut narrowed = actions.clone();
+    for (kind, cap) in default {
+        narrowed
+            .entry(kind.clone())
+            .and_modify(|action| {
+                if action.strictness() < cap.strictness() {
+                    *action = *cap;
+                }
+            })
+            .or_insert(*cap);
+    }
+    narrowed
+}
+
+/// Where an incident was recorded
+#[derive(Debug, Clone, Copy)]
+pub struct IncidentSite<'a> {
+    pub project_id: &'a str,
+    pub run_id: &'a str,
+    pub run_execution_id: &'a str,
+    pub checkpoint_id: &'a str,
+}
+
+/// Apply the policy's action to a recorded incident; returns whether the
+/// execution continues past it. Blocks while approval is pending.
+pub fn respond(
+    conn: &Connection,
+    actions: &BTreeMap<String, IncidentAction>,
+    site: IncidentSite<'_>,
+    incident: &Incident,
+) -> Result<bool, Error> {
+    match action_for(actions, incident) {
+        IncidentAction::Block => Ok(false),
+        IncidentAction::WarnContinue => Ok(true),
+        IncidentAction::Notify => {
+            raise(conn, site, incident, IncidentAction::Notify)?;
+            Ok(true)
+        }
+        IncidentAction::PauseForApproval => {
+            let request = raise(conn, site, incident, IncidentAction::PauseForApproval)?;
+            wait_for_decision(conn, &request.id)
+        }
+    }
+}
+
+fn raise(
+    conn: &Connection,
+    site: IncidentSite<'_>,
+    incident: &Incident,
+    action: IncidentAction,
+) -> Result<IncidentResponse, Error> {
+    let response = IncidentResponse {
+        id: Uuid::new_v4().to_string(),
+        project_id: site.project_id.to_string(),
+        run_id: site.run_id.to_string(),
+        run_execution_id: site.run_execution_id.to_string(),
+        checkpoint_id: site.checkpoint_id.to_string(),
+        incident_kind: incident.kind.clone(),
+        action: action.as_str().to_string(),
+        status: "pending".to_string(),
+        details: incident.details.clone(),
+        created_at: Utc::now().to_rfc3339(),
+        resolved_at: None,
+    };
+    incident_responses::insert(conn, &response)?;
+    Ok(response)
+}
+
+/// Wait until the approval request is decided; true if it was approved
+pub fn wait_for_decision(conn: &Connection, request_id: &str) -> Result<bool, Error> {
+    let (lock, decided) = &*DECISIONS;
+    let mut guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    loop {
+        let status = incident_responses::get(conn, request_id)?.map(|request| request.status);
+        match status.as_deref() {
+            Some("pending") => {}
+            Some("approved") => return Ok(true),
+            _ => return Ok(false),
+        }
+        guard = decided
+            .wait_timeout(guard, RECHECK_INTERVAL)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0;
+    }
+}
+
+/// Approve or reject a pending approval request and resume its execution
+pub fn decide(
+    conn: &Connection,
+    request_id: &str,
+    approve: bool,
+) -> Result<IncidentResponse, Error> {
+    let status = if approve { "approved" } else { "rejected" };
+    let response = resolve(conn, request_id, IncidentAction::PauseForApproval, status)?;
+    let (lock, decided) = &*DECISIONS;
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    decided.notify_all();
+    Ok(response)
+}
+
+/// Mark a pending notification as seen
+pub fn acknowledge(conn: &Connection, notification_id: &str) -> Result<IncidentResponse, Error> {
+    resolve(
+        conn,
+        notification_id,
+        IncidentAction::Notify,
+        "acknowledged",
+    )
+}
+
+fn resolve(
+    conn: &Connection,
+    id: &str,
+    action: IncidentAction,
+    status: &str,
+) -> Result<IncidentResponse, Error> {
+    let response = incident_responses::get(conn, id)?
+        .filter(|response| response.action == action.as_str())
+        .ok_or_else(|| Error::Api(format!("no {} incident response {id}", action.as_str())))?;
+    if !incident_responses::resolve(conn, id, status, &Utc::now().to_rfc3339())? {
+        return Err(Error::Api(format!(
+            "incident response {id} was already {}",
+            response.status
+        )));
+    }
+    incident_responses::get(conn, id)?
+        .ok_or_else(|| Error::Api(format!("incident response {id} not found")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::store::migrations::runner()
+            .to_latest(&mut conn)
+            .unwrap();
+        conn
+    }
+
+    fn incident(kind: &str, severity: &str) -> Incident {
+        Incident {
+            kind: kind.to_string(),
+            severity: severity.to_string(),
+            details: format!("{kind} happened"),
+        }
+    }
+
+    const SITE: IncidentSite<'static> = IncidentSite {
+        project_id: "project-1",
+        run_id: "run-1",
+        run_execution_id: "execution-1",
+        checkpoint_id: "ck-1",
+    };
+
+    #[test]
+    fn unconfigured_kinds_block_on_errors_and_continue_on_warnings() {
+        let mut actions = BTreeMap::new();
+        assert_eq!(
+            action_for(&actions, &incident("network_denied", "error")),
+            IncidentAction::Block
+        );
+        assert_eq!(
+            action_for(&actions, &incident("nature_cost_warning", "warn")),
+            IncidentAction::WarnContinue
+        );
+
+        actions.insert("nature_cost_warning".to_string(), IncidentAction::Block);
+        actions.insert(
+            "network_denied".to_string(),
+            IncidentAction::PauseForApproval,
+        );
+        assert_eq!(
+            action_for(&actions, &incident("nature_cost_warning", "warn")),
+            IncidentAction::Block
+        );
+        assert_eq!(
+            action_for(&actions, &incident("network_denied", "error")),
+            IncidentAction::PauseForApproval
+        );
+
+        let json = serde_json::to_value(&actions).unwrap();
+        assert_eq!(json["network_denied"], "pause_for_approval");
+        assert_eq!(
+            serde_json::from_value::<BTreeMap<String, IncidentAction>>(json).unwrap(),
+            actions
+        );
+    }
+
+    #[test]
+    fn notifications_continue_and_are_acknowledged_once() {
+        let conn = test_conn();
+        let actions = BTreeMap::from([("nature_cost_warning".to_string(), IncidentAction::Notify)]);
+        assert!(respond(
+            &conn,
+            &actions,
+            SITE,
+            &incident("nature_cost_warning", "warn")
+        )
+        .unwrap());
+
+        let pending = incident_responses::list(&conn, "project-1", true).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].action, "notify");
+        assert!(decide(&conn, &pending[0].id, true).is_err());
+
+        let acknowledged = acknowledge(&conn, &pending[0].id).unwrap();
+        assert_eq!(acknowledged.status, "acknowledged");
+        assert!(acknowledge(&conn, &pending[0].id).is_err());
+        assert!(incident_responses::list(&conn, "project-1", true)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn decided_approval_requests_resume_or_stop_the_execution() {
+        let conn = test_conn();
+        let network = incident("network_denied", "error");
+        let approved = raise(&conn, SITE, &network, IncidentAction::PauseForApproval).unwrap();
+        let rejected = raise(&conn, SITE, &network, IncidentAction::PauseForApproval).unwrap();
+
+        decide(&conn, &approved.id, true).unwrap();
+        decide(&conn, &rejected.id, false).unwrap();
+        assert!(decide(&conn, &approved.id, false).is_err());
+
+        assert!(wait_for_decision(&conn, &approved.id).unwrap());
+        assert!(!wait_for_decision(&conn, &rejected.id).unwrap());
+    }
+}
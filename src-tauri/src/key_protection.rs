@@ -0,0 +1,466 @@
+// src-tauri/src/key_protection.rs
+//!
+//! Signing keys sealed at rest
+//!
+//! By default a project's ed25519 signing key lives in the system keychain,
+//! or in plaintext in the fallback key directory when there is none. A
+//! project can instead seal its key: the seed is encrypted with
+//! XChaCha20-Poly1305 under a key-encryption key that is never stored, and
+//! only the sealed file (`<project>.sealed.json` in the key directory) is
+//! kept. The key-encryption key comes from
+//!
+//! - a passphrase, stretched with Argon2id, or
+//! - a hardware token answering a stored challenge: FIDO2 `hmac-secret`, a
+//!   YubiKey HMAC challenge-response slot, or an HMAC key bound to a TPM or
+//!   Secure Enclave. The token is reached through a helper program, so any
+//!   authenticator with a command-line tool works.
+//!
+//! CARs are signed with ed25519, which secure enclaves and TPMs do not
+//! sign with, so the unsealed key exists in memory while the project is
+//! unlocked, never on disk.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{anyhow, bail, Context};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ed25519_dalek::SigningKey;
+use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::provenance;
+
+/// Format version of sealed key files
+const SEALED_KEY_VERSION: u32 = 1;
+
+/// Argon2id costs for new passphrase-sealed keys (OWASP's 19 MiB profile)
+const ARGON2_M_COST: u32 = 19 * 1024;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+
+/// Placeholder a hardware helper's arguments may carry for the challenge
+const CHALLENGE_PLACEHOLDER: &str = "{challenge}";
+
+/// Keys unsealed this session, by project
+static UNLOCKED: Lazy<Mutex<HashMap<String, SigningKey>>> = Lazy::new(Default::default);
+
+/// How a project's signing key is protected, as the project settings
+/// select it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum KeyProtectionChoice {
+    /// System keychain, or the plaintext fallback directory
+    Keychain,
+    Passphrase,
+    /// `program` is run with `args`; it gets the challenge as hex, in place
+    /// of a `{challenge}` argument or else on stdin, and prints the token's
+    /// response as hex
+    Hardware {
+        program: String,
+        args: Vec<String>,
+    },
+}
+
+/// What unseals a sealed key, stored in its file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum KeyProtection {
+    #[serde(rename_all = "camelCase")]
+    Passphrase {
+        /// Hex Argon2id salt
+        salt: String,
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    },
+    Hardware {
+        program: String,
+        args: Vec<String>,
+        /// Hex challenge the token answers with the key-encryption key
+        challenge: String,
+    },
+}
+
+/// A signing key encrypted at rest
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SealedKey {
+    pub version: u32,
+    /// Base64 public key; authenticated with the ciphertext
+    pub public_key: String,
+    pub protection: KeyProtection,
+    /// Hex XChaCha20-Poly1305 nonce
+    pub nonce: String,
+    /// Base64 encrypted seed
+    pub ciphertext: String,
+}
+
+/// A sealed key that has not been unlocked this session. Distinct from a
+/// missing key, so callers never regenerate a key that is only locked.
+#[derive(Debug, thiserror::Error)]
+#[error("the signing key of project {project_id} is locked; unlock it with its passphrase")]
+pub struct KeyLocked {
+    pub project_id: String,
+}
+
+/// Protection of a project's key as the UI shows it
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyProtectionStatus {
+    /// `keychain`, `passphrase` or `hardware`
+    pub kind: &'static str,
+    /// Whether signing needs an unlock first
+    pub locked: bool,
+}
+
+impl KeyProtection {
+    fn new(choice: &KeyProtectionChoice) -> anyhow::Result<Self> {
+        let mut random = [0u8; 32];
+        OsRng.fill_bytes(&mut random);
+        match choice {
+            KeyProtectionChoice::Keychain => bail!("keychain keys are not sealed"),
+            KeyProtectionChoice::Passphrase => Ok(Self::Passphrase {
+                salt: hex::encode(&random[..16]),
+                m_cost: ARGON2_M_COST,
+                t_cost: ARGON2_T_COST,
+                p_cost: ARGON2_P_COST,
+            }),
+            KeyProtectionChoice::Hardware { program, args } => Ok(Self::Hardware {
+                program: program.clone(),
+                args: args.clone(),
+                challenge: hex::encode(random),
+            }),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Passphrase { .. } => "passphrase",
+            Self::Hardware { .. } => "hardware",
+        }
+    }
+
+    /// Derive the key-encryption key; passphrase protection needs `passphrase`
+    fn key_encryption_key(&self, passphrase: Option<&str>) -> anyhow::Result<[u8; 32]> {
+        match self {
+            Self::Passphrase {
+                salt,
+                m_cost,
+                t_cost,
+                p_cost,
+            } => {
+                let passphrase = passphrase
+                    .filter(|passphrase| !passphrase.is_empty())
+                    .ok_or_else(|| anyhow!("a passphrase is required"))?;
+                let salt = hex::decode(salt).context("sealed key salt is not hex")?;
+                let params = Params::new(*m_cost, *t_cost, *p_cost, Some(32))
+                    .map_err(|err| anyhow!("invalid Argon2 parameters: {err}"))?;
+                let mut key = [0u8; 32];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                    .map_err(|err| anyhow!("Argon2 failed: {err}"))?;
+                Ok(key)
+            }
+            Self::Hardware {
+                program,
+                args,
+                challenge,
+            } => {
+                let response = challenge_token(program, args, challenge)?;
+                Ok(Sha256::new()
+                    .chain_update(b"intelexta-sealed-key-v1")
+                    .chain_update(response)
+                    .finalize()
+                    .into())
+            }
+        }
+    }
+}
+
+/// Ask a hardware token, through its helper program, to answer `challenge`
+fn challenge_token(program: &str, args: &[String], challenge: &str) -> anyhow::Result<Vec<u8>> {
+    let in_args = args.iter().any(|arg| arg.contains(CHALLENGE_PLACEHOLDER));
+    let mut child = Command::new(program)
+        .args(
+            args.iter()
+                .map(|arg| arg.replace(CHALLENGE_PLACEHOLDER, challenge)),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to start hardware key helper {program}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if !in_args {
+            writeln!(stdin, "{challenge}").context("failed to send the challenge")?;
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("hardware key helper {program} failed"))?;
+    if !output.status.success() {
+        bail!(
+            "hardware key helper {program} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let response = hex::decode(String::from_utf8_lossy(&output.stdout).trim())
+        .with_context(|| format!("hardware key helper {program} did not print a hex response"))?;
+    if response.len() < 16 {
+        bail!("hardware key helper {program} answered with fewer than 16 bytes");
+    }
+    Ok(response)
+}
+
+impl SealedKey {
+    /// Seal `signing_key` under a new protection of the chosen kind
+    pub fn seal(
+        signing_key: &SigningKey,
+        choice: &KeyProtectionChoice,
+        passphrase: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let protection = KeyProtection::new(choice)?;
+        let key = protection.key_encryption_key(passphrase)?;
+        let public_key = provenance::public_key_from_secret(signing_key);
+        let mut nonce = [0u8; 24];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(&key.into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &signing_key.to_bytes(),
+                    aad: public_key.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("failed to seal the signing key"))?;
+        Ok(Self {
+            version: SEALED_KEY_VERSION,
+            public_key,
+            protection,
+            nonce: hex::encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn unseal(&self, passphrase: Option<&str>) -> anyhow::Result<SigningKey> {
+        if self.version != SEALED_KEY_VERSION {
+            bail!("unsupported sealed key version {}", self.version);
+        }
+        let key = self.protection.key_encryption_key(passphrase)?;
+        let nonce = hex::decode(&self.nonce).context("sealed key nonce is not hex")?;
+        if nonce.len() != 24 {
+            bail!("sealed key nonce has invalid length");
+        }
+        let ciphertext = STANDARD
+            .decode(&self.ciphertext)
+            .context("sealed key ciphertext is not base64")?;
+        let seed = XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: self.public_key.as_bytes(),
+                },
+            )
+            .map_err(|_| match self.protection {
+                KeyProtection::Passphrase { .. } => anyhow!("wrong passphrase"),
+                KeyProtection::Hardware { .. } => {
+                    anyhow!("the hardware token's response does not unseal the key")
+                }
+            })?;
+        let seed: [u8; 32] = seed
+            .try_into()
+            .map_err(|_| anyhow!("sealed key has invalid length"))?;
+        let signing_key = SigningKey::from_bytes(&seed);
+        if provenance::public_key_from_secret(&signing_key) != self.public_key {
+            bail!("sealed key does not match its public key");
+        }
+        Ok(signing_key)
+    }
+}
+
+fn read_sealed(project_id: &str) -> anyhow::Result<Option<SealedKey>> {
+    let path = crate::keychain::sealed_key_path(project_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)
+        .with_context(|| format!("unable to read sealed key file at {}", path.display()))?;
+    let sealed = serde_json::from_str(&json)
+        .with_context(|| format!("sealed key file at {} is corrupt", path.display()))?;
+    Ok(Some(sealed))
+}
+
+/// The unlocked signing key of a project with a sealed key. `None` when
+/// the project's key is not sealed; a locked passphrase key is a
+/// `KeyLocked` error, a hardware key is unlocked on first use.
+pub fn load_sealed(project_id: &str) -> anyhow::Result<Option<SigningKey>> {
+    let mut unlocked = UNLOCKED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(signing_key) = unlocked.get(project_id) {
+        return Ok(Some(signing_key.clone()));
+    }
+    let Some(sealed) = read_sealed(project_id)? else {
+        return Ok(None);
+    };
+    if matches!(sealed.protection, KeyProtection::Passphrase { .. }) {
+        return Err(KeyLocked {
+            project_id: project_id.to_string(),
+        }
+        .into());
+    }
+    let signing_key = sealed.unseal(None)?;
+    unlocked.insert(project_id.to_string(), signing_key.clone());
+    Ok(Some(signing_key))
+}
+
+/// Unseal a project's key for the rest of the session
+pub fn unlock(project_id: &str, passphrase: Option<&str>) -> anyhow::Result<()> {
+    let sealed = read_sealed(project_id)?
+        .ok_or_else(|| anyhow!("the signing key of project {project_id} is not sealed"))?;
+    let signing_key = sealed.unseal(passphrase)?;
+    UNLOCKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(project_id.to_string(), signing_key);
+    Ok(())
+}
+
+/// Forget an unsealed key; signing needs an unlock again
+pub fn lock(project_id: &str) {
+    UNLOCKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(project_id);
+}
+
+pub fn status(project_id: &str) -> anyhow::Result<KeyProtectionStatus> {
+    let unlocked = UNLOCKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .contains_key(project_id);
+    Ok(match read_sealed(project_id)? {
+        None => KeyProtectionStatus {
+            kind: "keychain",
+            locked: false,
+        },
+        Some(sealed) => KeyProtectionStatus {
+            kind: sealed.protection.kind(),
+            locked: !unlocked,
+        },
+    })
+}
+
+/// Move a project's signing key to the chosen protection. The key itself is
+/// unchanged, so receipts it signed keep verifying; a sealed key must be
+/// unlocked first. Sealing removes the key from the keychain and the
+/// fallback directory.
+pub fn set_protection(
+    project_id: &str,
+    choice: &KeyProtectionChoice,
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    let signing_key = match load_sealed(project_id)? {
+        Some(signing_key) => signing_key,
+        None => provenance::load_secret_key(project_id)?,
+    };
+    let path = crate::keychain::sealed_key_path(project_id)?;
+    match choice {
+        KeyProtectionChoice::Keychain => {
+            crate::keychain::store_secret(project_id, &STANDARD.encode(signing_key.to_bytes()))?;
+            if path.exists() {
+                std::fs::remove_file(&path).with_context(|| {
+                    format!("unable to remove sealed key file at {}", path.display())
+                })?;
+            }
+        }
+        choice => {
+            let sealed = SealedKey::seal(&signing_key, choice, passphrase)?;
+            // Check the seal opens before the plaintext copy is gone
+            sealed.unseal(passphrase)?;
+            crate::keychain::write_private_file(&path, &serde_json::to_string_pretty(&sealed)?)?;
+            crate::keychain::delete_secret(project_id)?;
+        }
+    }
+    UNLOCKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(project_id.to_string(), signing_key);
+    Ok(())
+}
+
+/// Delete a project's sealed key, if it has one
+pub fn delete(project_id: &str) -> anyhow::Result<()> {
+    lock(project_id);
+    let path = crate::keychain::sealed_key_path(project_id)?;
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .with_context(|| format!("unable to remove sealed key file at {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_sealed_keys_open_only_with_their_passphrase() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let sealed = SealedKey::seal(
+            &signing_key,
+            &KeyProtectionChoice::Passphrase,
+            Some("correct horse"),
+        )
+        .unwrap();
+
+        let unsealed = sealed.unseal(Some("correct horse")).unwrap();
+        assert_eq!(unsealed.to_bytes(), signing_key.to_bytes());
+        assert!(sealed
+            .unseal(Some("battery staple"))
+            .unwrap_err()
+            .to_string()
+            .contains("wrong passphrase"));
+        assert!(sealed.unseal(None).is_err());
+
+        // The public key is authenticated with the seed
+        let mut swapped = sealed.clone();
+        swapped.public_key =
+            provenance::public_key_from_secret(&SigningKey::from_bytes(&[8u8; 32]));
+        assert!(swapped.unseal(Some("correct horse")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hardware_sealed_keys_open_with_the_tokens_response() {
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        // A stand-in token that answers every challenge with sha256 of it
+        let token = KeyProtectionChoice::Hardware {
+            program: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "printf %s \"$1\" | sha256sum | cut -d' ' -f1".to_string(),
+                "token".to_string(),
+                CHALLENGE_PLACEHOLDER.to_string(),
+            ],
+        };
+        let sealed = SealedKey::seal(&signing_key, &token, None).unwrap();
+        assert_eq!(
+            sealed.unseal(None).unwrap().to_bytes(),
+            signing_key.to_bytes()
+        );
+
+        let mut other_token = sealed.clone();
+        if let KeyProtection::Hardware { args, .. } = &mut other_token.protection {
+            args[1] = "printf %s \"$1\" | sha512sum | cut -d' ' -f1".to_string();
+        }
+        assert!(other_token.unseal(None).is_err());
+    }
+}
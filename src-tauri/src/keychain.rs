@@ -142,19 +142,35 @@ pub fn delete_secret(project_id: &str) -> anyhow::Result<()> {
 
 fn persist_secret_to_fallback(project_id: &str, secret_b64: &str) -> anyhow::Result<()> {
     let path = get_fallback_path(project_id)?;
-    fs::write(&path, secret_b64).with_context(|| fallback_write_error(&path))?;
+    write_private_file(&path, secret_b64)
+}
+
+/// Write a file only the current user can read, as key files are kept
+pub(crate) fn write_private_file(path: &Path, contents: &str) -> anyhow::Result<()> {
+    fs::write(path, contents).with_context(|| fallback_write_error(path))?;
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let permissions = fs::Permissions::from_mode(0o600);
-        fs::set_permissions(&path, permissions)
-            .with_context(|| fallback_permissions_error(&path))?;
+        fs::set_permissions(path, permissions)
+            .with_context(|| fallback_permissions_error(path))?;
     }
 
     Ok(())
 }
 
+/// Where a project's sealed signing key is kept (see `key_protection`); the
+/// key directory is used whether or not the system keychain works
+pub(crate) fn sealed_key_path(project_id: &str) -> anyhow::Result<PathBuf> {
+    Ok(get_fallback_path(project_id)?.with_extension("sealed.json"))
+}
+
+/// Where a project's threshold signing setup is kept (see `threshold`)
+pub(crate) fn threshold_config_path(project_id: &str) -> anyhow::Result<PathBuf> {
+    Ok(get_fallback_path(project_id)?.with_extension("threshold.json"))
+}
+
 fn probe_system_keyring() -> keyring::Result<()> {
     let entry = keyring::Entry::new(KEYCHAIN_SERVICE_NAME, "__intelexta_probe__")?;
     let secret = "test_secret";
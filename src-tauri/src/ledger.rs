@@ -1,4 +1,5 @@
 use crate::{
+    dashboard, governance, orchestrator,
     store::{
         self,
         policies::Policy,
@@ -6,10 +7,11 @@ use crate::{
     },
     Error,
 };
-use rusqlite::Connection;
+use rusqlite::{params, Connection};
 use serde::Serialize;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct LedgerTotals {
     pub tokens: u64,
@@ -94,3 +96,160 @@ pub fn get_project_ledger_snapshot(
         last_updated: ledger.updated_at,
     })
 }
+
+/// Cost differences below this are float rounding, not drift
+const COST_TOLERANCE: f64 = 1e-6;
+
+/// One policy version's ledger next to the usage recomputed for it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerDrift {
+    pub policy_version: i64,
+    pub recorded: LedgerTotals,
+    pub recomputed: LedgerTotals,
+    pub drifted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerReconciliation {
+    pub project_id: String,
+    pub policy_versions: Vec<LedgerDrift>,
+    pub drifted: bool,
+    /// Whether drifted ledgers were overwritten with the recomputed totals
+    pub repaired: bool,
+}
+
+impl LedgerTotals {
+    fn add(&mut self, usage: governance::ModelUsage) {
+        self.tokens = self.tokens.saturating_add(usage.tokens);
+        self.usd += usage.usd;
+        self.nature_cost += usage.nature_cost;
+    }
+
+    fn differs_from(&self, other: &LedgerTotals) -> bool {
+        self.tokens != other.tokens
+            || (self.usd - other.usd).abs() > COST_TOLERANCE
+            || (self.nature_cost - other.nature_cost).abs() > COST_TOLERANCE
+    }
+}
+
+/// Usage per policy version recomputed from what the project recorded: the
+/// top-level checkpoints of its executions, priced from the model catalog
+/// where no cost was stored, plus the usage of its replays. Interactive
+/// turns are not counted, as they are not charged to the ledger.
+fn recompute_usage(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<BTreeMap<i64, LedgerTotals>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT MAX(COALESCE(r.policy_version, 0), 0) AS version, s.model, s.config_json,
+                SUM(c.usage_tokens),
+                SUM(c.usd_cost), SUM(CASE WHEN c.usd_cost IS NULL THEN c.usage_tokens ELSE 0 END),
+                SUM(c.nature_cost), SUM(CASE WHEN c.nature_cost IS NULL THEN c.usage_tokens ELSE 0 END)
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         LEFT JOIN run_steps s ON s.id = c.checkpoint_config_id
+         WHERE r.project_id = ?1 AND c.parent_checkpoint_id IS NULL AND c.usage_tokens > 0
+         GROUP BY version, c.checkpoint_config_id",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        let model: Option<String> = row.get(1)?;
+        let config_json: Option<String> = row.get(2)?;
+        let model = orchestrator::pricing_model(config_json.as_deref(), model.as_deref());
+        let unpriced_usd_tokens = row.get::<_, i64>(5)?.max(0) as u64;
+        let unpriced_nature_tokens = row.get::<_, i64>(7)?.max(0) as u64;
+        Ok((
+            row.get::<_, i64>(0)?,
+            governance::ModelUsage {
+                tokens: row.get::<_, i64>(3)?.max(0) as u64,
+                usd: row.get::<_, Option<f64>>(4)?.unwrap_or(0.0)
+                    + governance::estimate_usd_cost(unpriced_usd_tokens, model.as_deref()),
+                nature_cost: row.get::<_, Option<f64>>(6)?.unwrap_or(0.0)
+                    + governance::estimate_nature_cost(unpriced_nature_tokens, model.as_deref()),
+            },
+        ))
+    })?;
+
+    let mut usage: BTreeMap<i64, LedgerTotals> = BTreeMap::new();
+    for row in rows {
+        let (policy_version, used) = row?;
+        usage.entry(policy_version).or_default().add(used);
+    }
+    for replay in project_usage_ledgers::list_replays(conn, project_id)? {
+        usage
+            .entry(replay.policy_version)
+            .or_default()
+            .add(governance::ModelUsage {
+                tokens: replay.total_tokens,
+                usd: replay.total_usd,
+                nature_cost: replay.total_nature_cost,
+            });
+    }
+    Ok(usage)
+}
+
+/// Compare the project's ledgers against the usage recomputed from its
+/// checkpoints and replays and, with `repair`, overwrite drifted ledgers
+/// with the recomputed totals. Usage of deleted runs and steps is no longer
+/// recorded anywhere, so a repair after deletions gives it back to the
+/// budgets.
+pub fn reconcile_ledger(
+    conn: &mut Connection,
+    project_id: &str,
+    repair: bool,
+) -> Result<LedgerReconciliation, Error> {
+    dashboard::ensure_project(conn, project_id)?;
+    let mut recomputed = recompute_usage(conn, project_id)?;
+    let mut policy_versions = Vec::new();
+    for ledger in project_usage_ledgers::list(conn, project_id)? {
+        let recorded = LedgerTotals {
+            tokens: ledger.total_tokens,
+            usd: ledger.total_usd,
+            nature_cost: ledger.total_nature_cost,
+        };
+        let recomputed = recomputed
+            .remove(&ledger.policy_version)
+            .unwrap_or_default();
+        policy_versions.push(LedgerDrift {
+            policy_version: ledger.policy_version,
+            drifted: recorded.differs_from(&recomputed),
+            recorded,
+            recomputed,
+        });
+    }
+    // Usage recorded under versions the ledger has no row for
+    for (policy_version, recomputed) in recomputed {
+        policy_versions.push(LedgerDrift {
+            policy_version,
+            drifted: recomputed.differs_from(&LedgerTotals::default()),
+            recorded: LedgerTotals::default(),
+            recomputed,
+        });
+    }
+    policy_versions.sort_by_key(|drift| drift.policy_version);
+
+    let drifted = policy_versions.iter().any(|drift| drift.drifted);
+    let repaired = repair && drifted;
+    if repaired {
+        let tx = conn.transaction()?;
+        for drift in policy_versions.iter().filter(|drift| drift.drifted) {
+            project_usage_ledgers::set_totals(
+                &tx,
+                project_id,
+                Some(drift.policy_version),
+                drift.recomputed.tokens,
+                drift.recomputed.usd,
+                drift.recomputed.nature_cost,
+            )?;
+        }
+        tx.commit()?;
+    }
+
+    Ok(LedgerReconciliation {
+        project_id: project_id.to_string(),
+        policy_versions,
+        drifted,
+        repaired,
+    })
+}
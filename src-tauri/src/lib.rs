@@ -1,22 +1,29 @@
 // In src-tauri/src/lib.rs
 
 use chrono::{DateTime, Utc};
+#[cfg(feature = "app")]
 use r2d2::Pool;
+#[cfg(feature = "app")]
 use r2d2_sqlite::SqliteConnectionManager;
 use serde::{Deserialize, Serialize};
 
 // The shared database pool type
+#[cfg(feature = "app")]
 pub type DbPool = Pool<SqliteConnectionManager>;
 
 // Your main API error type
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[cfg(feature = "app")]
     #[error(transparent)]
     Db(#[from] rusqlite::Error),
+    #[cfg(feature = "app")]
     #[error(transparent)]
     Pool(#[from] r2d2::Error),
+    #[cfg(feature = "app")]
     #[error(transparent)]
     Keyring(#[from] keyring::Error),
+    #[cfg(feature = "app")]
     #[error(transparent)]
     Migration(#[from] rusqlite_migration::Error),
     #[error("API Error: {0}")]
@@ -33,25 +40,110 @@ impl serde::Serialize for Error {
 }
 
 // Re-export modules to be accessible from main.rs
+// Only the CAR model, verification and threshold signing build without the
+// `app` feature
+#[cfg(feature = "app")]
+pub mod agent;
+#[cfg(feature = "app")]
 pub mod api;
+#[cfg(feature = "app")]
 pub mod api_keys;
+#[cfg(feature = "app")]
 pub mod attachments;
+#[cfg(feature = "app")]
+pub mod branching;
 pub mod car;
+pub mod car_cbor;
+pub mod car_diff;
+#[cfg(feature = "app")]
+pub mod challenge;
+#[cfg(feature = "app")]
 pub mod chunk;
+#[cfg(feature = "app")]
+pub mod cold_storage;
+#[cfg(feature = "app")]
+pub mod content_policy;
+#[cfg(feature = "app")]
+pub mod cost_estimation;
+#[cfg(feature = "app")]
+pub mod dashboard;
+pub mod display_time;
+#[cfg(feature = "app")]
+pub mod embeddings;
+#[cfg(feature = "app")]
+pub mod evaluation;
+#[cfg(feature = "app")]
+pub mod execution_window;
+#[cfg(feature = "app")]
+pub mod finetune;
+#[cfg(feature = "app")]
 pub mod governance;
+#[cfg(feature = "app")]
+pub mod incident_log;
+#[cfg(feature = "app")]
+pub mod incident_response;
+#[cfg(feature = "app")]
 pub mod ingest;
+#[cfg(feature = "app")]
+pub mod key_protection;
+#[cfg(feature = "app")]
 pub mod keychain;
+#[cfg(feature = "app")]
 pub mod ledger;
+pub use intelexta_verify_core::merkle;
+#[cfg(feature = "app")]
 pub mod model_adapters;
+#[cfg(feature = "app")]
 pub mod model_catalog;
+#[cfg(feature = "app")]
+pub mod notifications;
+#[cfg(feature = "app")]
 pub mod orchestrator;
+pub mod output_schema;
+#[cfg(feature = "app")]
+pub mod plugins;
+#[cfg(feature = "app")]
+pub mod policy_approval;
+pub mod policy_history;
+#[cfg(feature = "app")]
+pub mod policy_simulation;
+#[cfg(feature = "app")]
 pub mod portability;
+#[cfg(feature = "app")]
+pub mod project_archival;
+#[cfg(feature = "app")]
+pub mod prompt_template;
 pub mod provenance;
+pub mod provider_policy;
+#[cfg(feature = "app")]
 pub mod replay;
+pub mod response_metadata;
+pub mod run_environment;
+#[cfg(feature = "app")]
+pub mod run_queue;
+pub mod run_spec;
+#[cfg(feature = "app")]
 pub mod runtime;
+#[cfg(feature = "app")]
+pub mod self_verification;
+#[cfg(feature = "app")]
 pub mod store;
+#[cfg(feature = "app")]
+pub mod telemetry;
+#[cfg(feature = "app")]
+pub mod templates;
+pub mod threshold;
+pub mod timestamp;
+pub mod transparency;
+#[cfg(feature = "app")]
+pub mod triggers;
+pub mod verification_html;
+pub mod verify;
+#[cfg(feature = "app")]
+pub mod workspace_search;
 
 // Document processing module (converted from sci-llm-data-prep)
+#[cfg(feature = "app")]
 pub mod document_processing;
 
 // === Core Data Structures for Sprint 0 ===
@@ -62,6 +154,9 @@ pub struct Project {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub pubkey: String, // base64 encoded
+    /// Set while the project is archived, hidden from the project list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -78,5 +173,5 @@ pub struct Incident {
     pub related_checkpoint_id: Option<String>,
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "app"))]
 mod tests;
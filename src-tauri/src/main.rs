@@ -36,9 +36,21 @@ fn main() {
                 eprintln!("⚠️  Warning: Failed to initialize attachment store: {}", err);
             });
 
+        // Load installed step-type plugins
+        intelexta::plugins::init_global_registry(&app_data_dir)
+            .unwrap_or_else(|err| {
+                eprintln!("⚠️  Warning: Failed to load plugins: {}", err);
+            });
+
         let db_path = app_data_dir.join("intelexta.sqlite");
 
-        let manager = r2d2_sqlite::SqliteConnectionManager::file(db_path);
+        // Concurrent run executions write from several pooled connections:
+        // WAL lets readers proceed during writes and the busy timeout makes
+        // writers wait for each other instead of failing
+        let manager = r2d2_sqlite::SqliteConnectionManager::file(db_path).with_init(|conn| {
+            conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+            conn.busy_timeout(std::time::Duration::from_secs(30))
+        });
         let pool = r2d2::Pool::new(manager).expect("failed to create db pool");
 
         // --- FIX IS HERE ---
@@ -48,6 +60,32 @@ fn main() {
         store::migrate_db(&mut conn)?;
         // --- END FIX ---
 
+        // On-device inference is priced against the user's grid region
+        intelexta::model_catalog::set_grid_region(
+            store::carbon_settings::get_grid_region(&conn).unwrap_or_else(|err| {
+                eprintln!("⚠️  Warning: Failed to load grid region: {}", err);
+                None
+            }),
+        );
+
+        // Fire file-drop run triggers in the background
+        intelexta::triggers::spawn_file_drop_watcher(pool.clone(), std::time::Duration::from_secs(5));
+
+        // Re-verify recent receipts and checkpoint chains once a night
+        intelexta::self_verification::spawn_nightly_scheduler(
+            pool.clone(),
+            intelexta::self_verification::SCHEDULE_CHECK_INTERVAL,
+        );
+
+        // Purge archived projects once their retention period has passed
+        intelexta::project_archival::spawn_retention_sweeper(
+            pool.clone(),
+            intelexta::project_archival::RETENTION_CHECK_INTERVAL,
+        );
+
+        // Budget alerts are also delivered to the app's windows
+        intelexta::notifications::init_event_emitter(app.handle().clone());
+
         app.manage(pool);
 
         Ok(())
@@ -58,6 +96,12 @@ fn main() {
         api::create_project,
         api::rename_project,
         api::delete_project,
+        api::restore_project,
+        api::list_archived_projects,
+        api::purge_project,
+        api::get_project_retention_days,
+        api::set_project_retention_days,
+        api::list_project_archive_events,
         api::list_projects,
         api::list_local_models,
         api::create_run,
@@ -77,26 +121,105 @@ fn main() {
         api::submit_interactive_checkpoint_turn,
         api::finalize_interactive_checkpoint,
         api::start_run,
+        api::start_run_dry,
+        api::list_active_executions,
+        api::get_job_status,
+        api::list_jobs,
         api::clone_run,
         api::estimate_run_cost,
+        api::estimate_run_step_costs,
+        api::create_run_trigger,
+        api::list_run_triggers,
+        api::set_run_trigger_enabled,
+        api::delete_run_trigger,
+        api::list_run_trigger_events,
+        api::poll_run_triggers,
+        api::save_run_template,
+        api::list_run_templates,
+        api::instantiate_run_template,
+        api::delete_run_template,
         api::get_project_usage_ledger,
+        api::reconcile_ledger,
         api::get_policy,
+        api::get_default_policy,
+        api::set_default_policy,
         api::update_policy,
         api::update_policy_with_notes,
+        api::propose_policy_change,
+        api::approve_policy_change,
+        api::reject_policy_change,
+        api::list_policy_proposals,
         api::get_policy_versions,
         api::get_policy_version,
         api::get_current_policy_version_number,
+        api::simulate_policy_change,
         api::replay_run,
+        api::issue_verification_challenge,
+        api::respond_to_verification_challenge,
+        api::verify_challenge_proof,
+        api::get_self_verification_settings,
+        api::set_self_verification_settings,
+        api::get_grid_settings,
+        api::set_grid_region,
+        api::run_self_verification,
+        api::list_verification_history,
+        api::list_self_verification_alerts,
+        api::acknowledge_self_verification_alert,
         api::emit_car,
         api::export_project,
         api::import_project,
         api::import_car,
+        api::get_telemetry_settings,
+        api::set_telemetry_enabled,
+        api::preview_telemetry_report,
+        api::export_telemetry_report,
+        api::reset_telemetry,
+        api::get_notification_settings,
+        api::set_notification_settings,
+        api::list_budget_alerts,
+        api::list_plugins,
+        api::reload_plugins,
         api::list_api_keys_status,
         api::set_api_key,
         api::delete_api_key,
         api::list_catalog_models,
         api::list_all_available_models,
-        api::estimate_model_cost
+        api::estimate_model_cost,
+        api::register_model_weights,
+        api::list_model_weights,
+        api::unregister_model_weights,
+        api::set_dataset_split,
+        api::list_dataset_splits,
+        api::delete_dataset,
+        api::clear_response_cache,
+        api::set_run_priority,
+        api::get_project_dashboard,
+        api::get_usage_timeseries,
+        api::archive_run,
+        api::rehydrate_run,
+        api::get_run_archive,
+        api::get_archive_dir,
+        api::set_archive_dir,
+        api::list_signing_key_events,
+        api::get_key_protection,
+        api::set_key_protection,
+        api::unlock_signing_key,
+        api::lock_signing_key,
+        api::countersign_car,
+        api::export_verification_report,
+        api::sign_artifact,
+        api::get_threshold_signing,
+        api::enable_threshold_signing,
+        api::list_incident_responses,
+        api::acknowledge_incident_notification,
+        api::decide_incident_approval,
+        api::list_incidents,
+        api::acknowledge_incident,
+        api::list_prompt_partials,
+        api::save_prompt_partial,
+        api::delete_prompt_partial,
+        api::export_policy_history,
+        api::search_workspace
     ]);
 
     #[cfg(not(feature = "interactive"))]
@@ -104,6 +227,12 @@ fn main() {
         api::create_project,
         api::rename_project,
         api::delete_project,
+        api::restore_project,
+        api::list_archived_projects,
+        api::purge_project,
+        api::get_project_retention_days,
+        api::set_project_retention_days,
+        api::list_project_archive_events,
         api::list_projects,
         api::list_local_models,
         api::create_run,
@@ -120,20 +249,99 @@ fn main() {
         api::delete_run_step,
         api::reorder_run_steps,
         api::start_run,
+        api::start_run_dry,
+        api::list_active_executions,
+        api::get_job_status,
+        api::list_jobs,
         api::clone_run,
         api::estimate_run_cost,
+        api::estimate_run_step_costs,
+        api::create_run_trigger,
+        api::list_run_triggers,
+        api::set_run_trigger_enabled,
+        api::delete_run_trigger,
+        api::list_run_trigger_events,
+        api::poll_run_triggers,
+        api::save_run_template,
+        api::list_run_templates,
+        api::instantiate_run_template,
+        api::delete_run_template,
         api::get_project_usage_ledger,
+        api::reconcile_ledger,
         api::get_policy,
+        api::get_default_policy,
+        api::set_default_policy,
         api::update_policy,
         api::update_policy_with_notes,
+        api::propose_policy_change,
+        api::approve_policy_change,
+        api::reject_policy_change,
+        api::list_policy_proposals,
         api::get_policy_versions,
         api::get_policy_version,
         api::get_current_policy_version_number,
+        api::simulate_policy_change,
         api::replay_run,
+        api::issue_verification_challenge,
+        api::respond_to_verification_challenge,
+        api::verify_challenge_proof,
+        api::get_self_verification_settings,
+        api::set_self_verification_settings,
+        api::get_grid_settings,
+        api::set_grid_region,
+        api::run_self_verification,
+        api::list_verification_history,
+        api::list_self_verification_alerts,
+        api::acknowledge_self_verification_alert,
         api::emit_car,
         api::export_project,
         api::import_project,
-        api::import_car
+        api::import_car,
+        api::get_telemetry_settings,
+        api::set_telemetry_enabled,
+        api::preview_telemetry_report,
+        api::export_telemetry_report,
+        api::reset_telemetry,
+        api::get_notification_settings,
+        api::set_notification_settings,
+        api::list_budget_alerts,
+        api::list_plugins,
+        api::reload_plugins,
+        api::register_model_weights,
+        api::list_model_weights,
+        api::unregister_model_weights,
+        api::set_dataset_split,
+        api::list_dataset_splits,
+        api::delete_dataset,
+        api::clear_response_cache,
+        api::set_run_priority,
+        api::get_project_dashboard,
+        api::get_usage_timeseries,
+        api::archive_run,
+        api::rehydrate_run,
+        api::get_run_archive,
+        api::get_archive_dir,
+        api::set_archive_dir,
+        api::list_signing_key_events,
+        api::get_key_protection,
+        api::set_key_protection,
+        api::unlock_signing_key,
+        api::lock_signing_key,
+        api::countersign_car,
+        api::export_verification_report,
+        api::sign_artifact,
+        api::get_threshold_signing,
+        api::enable_threshold_signing,
+        api::list_incident_responses,
+        api::acknowledge_incident_notification,
+        api::decide_incident_approval,
+        api::list_incidents,
+        api::acknowledge_incident,
+        api::list_prompt_partials,
+        api::save_prompt_partial,
+        api::delete_prompt_partial,
+        api::export_policy_history,
+        api::search_workspace
     ]);
 
     builder
@@ -9,10 +9,14 @@
 //! - ModelAdapter trait: Common interface for all providers
 //! - Provider-specific adapters: AnthropicAdapter, OpenAIAdapter, etc.
 //! - ModelDispatcher: Routes requests to appropriate adapter based on model ID
+//! - ProviderResponse: Provider-neutral result every adapter normalizes into
 
-use crate::{api_keys, model_catalog};
+use crate::{api_keys, model_catalog, provenance};
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use crate::response_metadata::{FinishReason, ResponseMetadata, ToolCall};
 
 /// Token usage statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,17 +31,391 @@ impl TokenUsage {
     }
 }
 
-/// LLM generation result
+impl FinishReason {
+    fn from_anthropic(reason: Option<&str>) -> Self {
+        match reason {
+            Some("end_turn") | Some("stop_sequence") => FinishReason::Stop,
+            Some("max_tokens") => FinishReason::Length,
+            Some("tool_use") => FinishReason::ToolCalls,
+            Some("refusal") => FinishReason::ContentFilter,
+            _ => FinishReason::Unknown,
+        }
+    }
+
+    fn from_openai(reason: Option<&str>) -> Self {
+        match reason {
+            Some("stop") => FinishReason::Stop,
+            Some("length") => FinishReason::Length,
+            Some("tool_calls") | Some("function_call") => FinishReason::ToolCalls,
+            Some("content_filter") => FinishReason::ContentFilter,
+            _ => FinishReason::Unknown,
+        }
+    }
+
+    fn from_gemini(reason: Option<&str>) -> Self {
+        match reason {
+            Some("STOP") => FinishReason::Stop,
+            Some("MAX_TOKENS") => FinishReason::Length,
+            Some("SAFETY") | Some("RECITATION") | Some("BLOCKLIST")
+            | Some("PROHIBITED_CONTENT") | Some("SPII") => FinishReason::ContentFilter,
+            _ => FinishReason::Unknown,
+        }
+    }
+
+    /// Ollama reports `done_reason` on the final stream chunk
+    pub fn from_ollama(reason: Option<&str>) -> Self {
+        match reason {
+            Some("stop") => FinishReason::Stop,
+            Some("length") => FinishReason::Length,
+            _ => FinishReason::Unknown,
+        }
+    }
+}
+
+/// Provider-neutral result of one generation
 #[derive(Debug, Clone)]
-pub struct LlmGeneration {
-    pub response: String,
+pub struct ProviderResponse {
+    pub provider: String,
+    pub text: String,
+    pub tool_calls: Vec<ToolCall>,
     pub usage: TokenUsage,
+    pub finish_reason: FinishReason,
+    /// Request ID the provider assigned, for cross-checking against its logs
+    pub provider_request_id: Option<String>,
+    /// sha256 of the response headers named in `DIGESTED_RESPONSE_HEADERS`
+    pub response_headers_sha256: Option<String>,
+}
+
+impl ProviderResponse {
+    pub fn metadata(&self) -> ResponseMetadata {
+        ResponseMetadata {
+            provider: self.provider.clone(),
+            finish_reason: self.finish_reason,
+            tool_calls: self.tool_calls.clone(),
+            provider_request_id: self.provider_request_id.clone(),
+            response_headers_sha256: self.response_headers_sha256.clone(),
+        }
+    }
+}
+
+/// Response headers a provider can be asked about when a call is disputed:
+/// request IDs, timing, and the account and model that served the call
+pub const DIGESTED_RESPONSE_HEADERS: &[&str] = &[
+    "anthropic-organization-id",
+    "date",
+    "openai-model",
+    "openai-organization",
+    "openai-processing-ms",
+    "openai-version",
+    "request-id",
+    "x-request-id",
+];
+
+/// sha256 over the digested headers that are present, one `name:value` line
+/// each in `DIGESTED_RESPONSE_HEADERS` order
+pub fn response_headers_digest<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+    let mut lines = String::new();
+    for name in DIGESTED_RESPONSE_HEADERS {
+        if let Some(value) = header(name) {
+            lines.push_str(&format!("{}:{}\n", name, value.trim()));
+        }
+    }
+    (!lines.is_empty()).then(|| provenance::sha256_hex(lines.as_bytes()))
+}
+
+fn json_u64(value: &Value) -> u64 {
+    value.as_u64().unwrap_or(0)
+}
+
+/// Request ID from the response header, falling back to the ID in the body
+fn request_id(header: Option<&str>, body: &Value, body_field: &str) -> Option<String> {
+    header
+        .filter(|id| !id.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| body[body_field].as_str().map(str::to_string))
+}
+
+/// Normalizes an Anthropic Messages API response
+pub fn normalize_anthropic(body: &Value, request_header: Option<&str>) -> Result<ProviderResponse> {
+    let blocks = body["content"]
+        .as_array()
+        .ok_or_else(|| anyhow!("No content in Anthropic response"))?;
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block["type"].as_str() {
+            Some("text") => text.push_str(block["text"].as_str().unwrap_or_default()),
+            Some("tool_use") => tool_calls.push(ToolCall {
+                id: block["id"].as_str().map(str::to_string),
+                name: block["name"].as_str().unwrap_or_default().to_string(),
+                arguments: block["input"].clone(),
+            }),
+            _ => {}
+        }
+    }
+    if text.is_empty() && tool_calls.is_empty() {
+        return Err(anyhow!("No text in Anthropic response"));
+    }
+
+    Ok(ProviderResponse {
+        provider: "anthropic".to_string(),
+        text,
+        tool_calls,
+        usage: TokenUsage {
+            prompt_tokens: json_u64(&body["usage"]["input_tokens"]),
+            completion_tokens: json_u64(&body["usage"]["output_tokens"]),
+        },
+        finish_reason: FinishReason::from_anthropic(body["stop_reason"].as_str()),
+        provider_request_id: request_id(request_header, body, "id"),
+        response_headers_sha256: None,
+    })
+}
+
+/// Normalizes an OpenAI-style Chat Completions response (OpenAI, Groq, xAI)
+pub fn normalize_openai(
+    provider: &str,
+    body: &Value,
+    request_header: Option<&str>,
+) -> Result<ProviderResponse> {
+    let choice = &body["choices"][0];
+    let message = &choice["message"];
+    let tool_calls: Vec<ToolCall> = message["tool_calls"]
+        .as_array()
+        .map(|calls| {
+            calls
+                .iter()
+                .map(|call| {
+                    // Arguments arrive as a JSON-encoded string
+                    let raw = &call["function"]["arguments"];
+                    let arguments = raw
+                        .as_str()
+                        .and_then(|encoded| serde_json::from_str(encoded).ok())
+                        .unwrap_or_else(|| raw.clone());
+                    ToolCall {
+                        id: call["id"].as_str().map(str::to_string),
+                        name: call["function"]["name"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        arguments,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let text = match message["content"].as_str() {
+        Some(content) => content.to_string(),
+        None if !tool_calls.is_empty() => String::new(),
+        None => return Err(anyhow!("No content in {} response", provider)),
+    };
+
+    Ok(ProviderResponse {
+        provider: provider.to_string(),
+        text,
+        tool_calls,
+        usage: TokenUsage {
+            prompt_tokens: json_u64(&body["usage"]["prompt_tokens"]),
+            completion_tokens: json_u64(&body["usage"]["completion_tokens"]),
+        },
+        finish_reason: FinishReason::from_openai(choice["finish_reason"].as_str()),
+        provider_request_id: request_id(request_header, body, "id"),
+        response_headers_sha256: None,
+    })
+}
+
+/// Normalizes a Gemini generateContent response
+pub fn normalize_gemini(body: &Value) -> Result<ProviderResponse> {
+    let candidate = &body["candidates"][0];
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+        if let Some(fragment) = part["text"].as_str() {
+            text.push_str(fragment);
+        }
+        if part["functionCall"].is_object() {
+            tool_calls.push(ToolCall {
+                id: None,
+                name: part["functionCall"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                arguments: part["functionCall"]["args"].clone(),
+            });
+        }
+    }
+    if text.is_empty() && tool_calls.is_empty() {
+        return Err(anyhow!("No text in Gemini response"));
+    }
+
+    Ok(ProviderResponse {
+        provider: "google".to_string(),
+        text,
+        tool_calls,
+        usage: TokenUsage {
+            prompt_tokens: json_u64(&body["usageMetadata"]["promptTokenCount"]),
+            completion_tokens: json_u64(&body["usageMetadata"]["candidatesTokenCount"]),
+        },
+        finish_reason: FinishReason::from_gemini(candidate["finishReason"].as_str()),
+        provider_request_id: request_id(None, body, "responseId"),
+        response_headers_sha256: None,
+    })
+}
+
+/// Vectors for a batch of inputs, in input order
+#[derive(Debug, Clone)]
+pub struct EmbeddingResponse {
+    pub provider: String,
+    pub vectors: Vec<Vec<f32>>,
+    pub prompt_tokens: u64,
+}
+
+fn json_vector(value: &Value) -> Option<Vec<f32>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|component| component.as_f64().map(|component| component as f32))
+        .collect()
+}
+
+/// Normalizes an Ollama `/api/embed` response
+pub fn normalize_ollama_embeddings(body: &Value, inputs: usize) -> Result<EmbeddingResponse> {
+    let vectors = body["embeddings"]
+        .as_array()
+        .and_then(|vectors| vectors.iter().map(json_vector).collect::<Option<Vec<_>>>())
+        .ok_or_else(|| anyhow!("No embeddings in Ollama response"))?;
+    if vectors.len() != inputs {
+        return Err(anyhow!(
+            "Ollama returned {} embeddings for {} inputs",
+            vectors.len(),
+            inputs
+        ));
+    }
+    Ok(EmbeddingResponse {
+        provider: "ollama".to_string(),
+        vectors,
+        prompt_tokens: json_u64(&body["prompt_eval_count"]),
+    })
+}
+
+/// Normalizes an OpenAI-style `/embeddings` response, ordering vectors by
+/// their `index`
+pub fn normalize_openai_embeddings(
+    provider: &str,
+    body: &Value,
+    inputs: usize,
+) -> Result<EmbeddingResponse> {
+    let mut indexed = body["data"]
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| Some((item["index"].as_u64()?, json_vector(&item["embedding"])?)))
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| anyhow!("No embeddings in {} response", provider))?;
+    if indexed.len() != inputs {
+        return Err(anyhow!(
+            "{} returned {} embeddings for {} inputs",
+            provider,
+            indexed.len(),
+            inputs
+        ));
+    }
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok(EmbeddingResponse {
+        provider: provider.to_string(),
+        vectors: indexed.into_iter().map(|(_, vector)| vector).collect(),
+        prompt_tokens: json_u64(&body["usage"]["prompt_tokens"]),
+    })
+}
+
+/// Sampling options that make a generation reproducible
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingOptions {
+    pub seed: u64,
+    pub temperature: f64,
+}
+
+impl SamplingOptions {
+    /// Greedy decoding with a fixed seed, used for exact-mode steps
+    pub fn deterministic(seed: u64) -> Self {
+        Self {
+            seed,
+            temperature: 0.0,
+        }
+    }
+}
+
+/// Completion tokens asked of a cloud model when its catalog entry lists no
+/// output limit
+const DEFAULT_MAX_OUTPUT_TOKENS: u64 = 4096;
+
+/// Completion tokens to ask a cloud model for: its catalog output limit,
+/// lowered to what the step's remaining token limit leaves once the prompt's
+/// estimated tokens are taken out of it
+fn max_output_tokens(
+    model_def: &model_catalog::ModelDef,
+    prompt: &str,
+    token_limit: Option<u64>,
+) -> u64 {
+    let catalog_max = model_def
+        .max_output_tokens
+        .map_or(DEFAULT_MAX_OUTPUT_TOKENS, u64::from)
+        .max(1);
+    token_limit.map_or(catalog_max, |limit| {
+        limit
+            .saturating_sub(crate::orchestrator::estimate_tokens(prompt))
+            .clamp(1, catalog_max)
+    })
 }
 
 /// Model adapter trait - common interface for all LLM providers
 pub trait ModelAdapter: Send + Sync {
     /// Generate text from a prompt
-    fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration>;
+    fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse>;
+
+    /// Whether `generate_with_sampling` honors the requested sampling options
+    fn supports_sampling(&self) -> bool {
+        false
+    }
+
+    /// Generate with fixed sampling options. Adapters that cannot pass them to
+    /// the provider keep the default, which ignores them.
+    fn generate_with_sampling(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        _sampling: &SamplingOptions,
+    ) -> Result<ProviderResponse> {
+        self.generate(model_id, prompt)
+    }
+
+    /// Generate within `token_limit`: streaming adapters abandon the stream
+    /// once prompt plus completion tokens exceed it, request/response
+    /// adapters ask the provider for at most that many completion tokens.
+    /// The default generates in full.
+    fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        _token_limit: u64,
+    ) -> Result<ProviderResponse> {
+        match sampling {
+            Some(sampling) => self.generate_with_sampling(model_id, prompt, sampling),
+            None => self.generate(model_id, prompt),
+        }
+    }
+
+    /// Embed each input with an embedding model
+    fn embed(&self, model_id: &str, _inputs: &[String]) -> Result<EmbeddingResponse> {
+        Err(anyhow!(
+            "{} does not provide embeddings (model {})",
+            self.provider_name(),
+            model_id
+        ))
+    }
 
     /// Check if this adapter can handle the given model
     fn can_handle(&self, model_id: &str) -> bool;
@@ -66,21 +444,84 @@ impl OllamaAdapter {
     }
 }
 
-impl ModelAdapter for OllamaAdapter {
-    fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration> {
+impl OllamaAdapter {
+    fn stream(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: Option<u64>,
+    ) -> Result<ProviderResponse> {
         // Use existing perform_ollama_stream function
         // For Ollama, the internal `id` is the `apiName`
-        let orch_result = crate::orchestrator::perform_ollama_stream(model_id, prompt)?;
-
-        // Convert from orchestrator::LlmGeneration to model_adapters::LlmGeneration
-        Ok(LlmGeneration {
-            response: orch_result.response,
+        let orch_result =
+            crate::orchestrator::perform_ollama_stream(model_id, prompt, sampling, token_limit)?;
+        let finish_reason = orch_result
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.finish_reason)
+            .unwrap_or(FinishReason::Unknown);
+
+        // Convert from orchestrator::LlmGeneration to the normalized response
+        Ok(ProviderResponse {
+            provider: "ollama".to_string(),
+            text: orch_result.response,
+            tool_calls: Vec::new(),
             usage: TokenUsage {
                 prompt_tokens: orch_result.usage.prompt_tokens,
                 completion_tokens: orch_result.usage.completion_tokens,
             },
+            finish_reason,
+            provider_request_id: None,
+            response_headers_sha256: None,
         })
     }
+}
+
+impl ModelAdapter for OllamaAdapter {
+    fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse> {
+        self.stream(model_id, prompt, None, None)
+    }
+
+    fn supports_sampling(&self) -> bool {
+        true
+    }
+
+    fn generate_with_sampling(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> Result<ProviderResponse> {
+        self.stream(model_id, prompt, Some(sampling), None)
+    }
+
+    fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> Result<ProviderResponse> {
+        self.stream(model_id, prompt, sampling, Some(token_limit))
+    }
+
+    fn embed(&self, model_id: &str, inputs: &[String]) -> Result<EmbeddingResponse> {
+        let client = ureq::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build();
+        let response = client
+            .post(&format!("http://{}/api/embed", self.host))
+            .send_json(serde_json::json!({
+                "model": model_id,
+                "input": inputs,
+            }))
+            .map_err(|err| anyhow!("Ollama embeddings request failed: {}", err))?;
+        let response_json: serde_json::Value = response
+            .into_json()
+            .context("Failed to parse Ollama embeddings response")?;
+        normalize_ollama_embeddings(&response_json, inputs.len())
+    }
 
     fn can_handle(&self, model_id: &str) -> bool {
         // Check if model is from Ollama provider in catalog
@@ -124,10 +565,15 @@ impl AnthropicAdapter {
         api_keys::load_api_key(api_keys::ApiKeyProvider::Anthropic)
             .context("Anthropic API key not configured. Please add it in Settings → API Keys")
     }
-}
 
-impl ModelAdapter for AnthropicAdapter {
-    fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration> {
+    /// Generate, asking for at most `max_output_tokens(model, prompt, token_limit)`
+    /// completion tokens
+    fn complete(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        token_limit: Option<u64>,
+    ) -> Result<ProviderResponse> {
         let api_key = self.get_api_key()?;
 
         // --- FIX START ---
@@ -142,7 +588,7 @@ impl ModelAdapter for AnthropicAdapter {
         // Build request payload for Anthropic Messages API
         let payload = serde_json::json!({
             "model": api_model_name, // Use the correct name
-            "max_tokens": 4096,
+            "max_tokens": max_output_tokens(model_def, prompt, token_limit),
             "messages": [{
                 "role": "user",
                 "content": prompt
@@ -182,30 +628,31 @@ impl ModelAdapter for AnthropicAdapter {
         };
 
         // Parse response
+        let request_header = response.header("request-id").map(str::to_string);
+        let headers_sha256 = response_headers_digest(|name| response.header(name));
         let response_json: serde_json::Value = response
             .into_json()
             .context("Failed to parse Anthropic API response")?;
 
-        // Extract text from response
-        let text = response_json["content"][0]["text"]
-            .as_str()
-            .ok_or_else(|| anyhow!("No text in Anthropic response"))?
-            .to_string();
-
-        // Extract usage
-        let usage = TokenUsage {
-            prompt_tokens: response_json["usage"]["input_tokens"]
-                .as_u64()
-                .unwrap_or(0),
-            completion_tokens: response_json["usage"]["output_tokens"]
-                .as_u64()
-                .unwrap_or(0),
-        };
+        let mut normalized = normalize_anthropic(&response_json, request_header.as_deref())?;
+        normalized.response_headers_sha256 = headers_sha256;
+        Ok(normalized)
+    }
+}
 
-        Ok(LlmGeneration {
-            response: text,
-            usage,
-        })
+impl ModelAdapter for AnthropicAdapter {
+    fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, None)
+    }
+
+    fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        _sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, Some(token_limit))
     }
 
     fn can_handle(&self, model_id: &str) -> bool {
@@ -251,6 +698,15 @@ impl OpenAICompatibleAdapter {
         }
     }
 
+    /// Catalog provider of the models this adapter serves
+    fn provider_id(&self) -> &'static str {
+        match self.provider {
+            api_keys::ApiKeyProvider::Groq => "groq",
+            api_keys::ApiKeyProvider::XAI => "xai",
+            _ => "openai",
+        }
+    }
+
     fn get_api_key(&self) -> Result<String> {
         api_keys::load_api_key(self.provider).with_context(|| {
             format!(
@@ -259,10 +715,15 @@ impl OpenAICompatibleAdapter {
             )
         })
     }
-}
 
-impl ModelAdapter for OpenAICompatibleAdapter {
-    fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration> {
+    /// Generate, asking for at most `max_output_tokens(model, prompt, token_limit)`
+    /// completion tokens
+    fn complete(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        token_limit: Option<u64>,
+    ) -> Result<ProviderResponse> {
         let api_key = self.get_api_key()?;
 
         // Look up the correct apiName from the catalog
@@ -280,7 +741,7 @@ impl ModelAdapter for OpenAICompatibleAdapter {
                 "role": "user",
                 "content": prompt
             }],
-            "max_tokens": 4096,
+            "max_tokens": max_output_tokens(model_def, prompt, token_limit),
         });
 
         // Make HTTP request
@@ -317,30 +778,73 @@ impl ModelAdapter for OpenAICompatibleAdapter {
         };
 
         // Parse response
+        let request_header = response.header("x-request-id").map(str::to_string);
+        let headers_sha256 = response_headers_digest(|name| response.header(name));
         let response_json: serde_json::Value = response
             .into_json()
             .context(format!("Failed to parse {} API response", self.provider_name()))?;
 
-        // Extract text from response
-        let text = response_json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| anyhow!("No content in {} response", self.provider_name()))?
-            .to_string();
-
-        // Extract usage
-        let usage = TokenUsage {
-            prompt_tokens: response_json["usage"]["prompt_tokens"]
-                .as_u64()
-                .unwrap_or(0),
-            completion_tokens: response_json["usage"]["completion_tokens"]
-                .as_u64()
-                .unwrap_or(0),
-        };
+        let mut normalized = normalize_openai(
+            self.provider_id(),
+            &response_json,
+            request_header.as_deref(),
+        )?;
+        normalized.response_headers_sha256 = headers_sha256;
+        Ok(normalized)
+    }
+}
 
-        Ok(LlmGeneration {
-            response: text,
-            usage,
-        })
+impl ModelAdapter for OpenAICompatibleAdapter {
+    fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, None)
+    }
+
+    fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        _sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, Some(token_limit))
+    }
+
+    fn embed(&self, model_id: &str, inputs: &[String]) -> Result<EmbeddingResponse> {
+        let api_key = self.get_api_key()?;
+        let api_model_name = model_catalog::try_get_global_catalog()
+            .and_then(|catalog| catalog.get_model(model_id))
+            .map(|model_def| model_def.api_name.clone().unwrap_or_else(|| model_def.id.clone()))
+            .unwrap_or_else(|| model_id.to_string());
+
+        let client = ureq::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build();
+        let response = client
+            .post(&format!("{}/embeddings", self.api_base))
+            .set("Authorization", &format!("Bearer {}", api_key))
+            .set("Content-Type", "application/json")
+            .send_json(serde_json::json!({
+                "model": api_model_name,
+                "input": inputs,
+            }));
+        let response = match response {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(code, resp)) => {
+                let error_msg = resp
+                    .into_json::<serde_json::Value>()
+                    .ok()
+                    .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+                    .unwrap_or_else(|| format!("HTTP {} error", code));
+                return Err(anyhow!("{} embeddings error (HTTP {}): {}", self.provider_name(), code, error_msg));
+            }
+            Err(e) => {
+                return Err(anyhow!("Failed to connect to {} API: {}", self.provider_name(), e));
+            }
+        };
+        let response_json: serde_json::Value = response
+            .into_json()
+            .context(format!("Failed to parse {} embeddings response", self.provider_name()))?;
+        normalize_openai_embeddings(self.provider_id(), &response_json, inputs.len())
     }
 
     fn can_handle(&self, model_id: &str) -> bool {
@@ -380,10 +884,15 @@ impl GoogleAdapter {
         api_keys::load_api_key(api_keys::ApiKeyProvider::Google)
             .context("Google API key not configured. Please add it in Settings → API Keys")
     }
-}
 
-impl ModelAdapter for GoogleAdapter {
-    fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration> {
+    /// Generate, asking for at most `max_output_tokens(model, prompt, token_limit)`
+    /// completion tokens
+    fn complete(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        token_limit: Option<u64>,
+    ) -> Result<ProviderResponse> {
         let api_key = self.get_api_key()?;
 
         // Look up the correct apiName from the catalog
@@ -401,7 +910,7 @@ impl ModelAdapter for GoogleAdapter {
                 }]
             }],
             "generationConfig": {
-                "maxOutputTokens": 4096
+                "maxOutputTokens": max_output_tokens(model_def, prompt, token_limit)
             }
         });
 
@@ -441,30 +950,30 @@ impl ModelAdapter for GoogleAdapter {
         };
 
         // Parse response
+        let headers_sha256 = response_headers_digest(|name| response.header(name));
         let response_json: serde_json::Value = response
             .into_json()
             .context("Failed to parse Gemini API response")?;
 
-        // Extract text from response
-        let text = response_json["candidates"][0]["content"]["parts"][0]["text"]
-            .as_str()
-            .ok_or_else(|| anyhow!("No text in Gemini response"))?
-            .to_string();
-
-        // Extract usage (Gemini has different structure)
-        let usage = TokenUsage {
-            prompt_tokens: response_json["usageMetadata"]["promptTokenCount"]
-                .as_u64()
-                .unwrap_or(0),
-            completion_tokens: response_json["usageMetadata"]["candidatesTokenCount"]
-                .as_u64()
-                .unwrap_or(0),
-        };
+        let mut normalized = normalize_gemini(&response_json)?;
+        normalized.response_headers_sha256 = headers_sha256;
+        Ok(normalized)
+    }
+}
 
-        Ok(LlmGeneration {
-            response: text,
-            usage,
-        })
+impl ModelAdapter for GoogleAdapter {
+    fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, None)
+    }
+
+    fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        _sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> Result<ProviderResponse> {
+        self.complete(model_id, prompt, Some(token_limit))
     }
 
     fn can_handle(&self, model_id: &str) -> bool {
@@ -501,7 +1010,12 @@ impl ModelDispatcher {
         Self { adapters }
     }
 
-    pub fn generate(&self, model_id: &str, prompt: &str) -> Result<LlmGeneration> {
+    /// Whether any adapter would accept the model, without contacting it
+    pub fn has_adapter(&self, model_id: &str) -> bool {
+        self.adapters.iter().any(|adapter| adapter.can_handle(model_id))
+    }
+
+    pub fn generate(&self, model_id: &str, prompt: &str) -> Result<ProviderResponse> {
         // Find adapter that can handle this model
         for adapter in &self.adapters {
             if adapter.can_handle(model_id) {
@@ -517,6 +1031,89 @@ impl ModelDispatcher {
         ))
     }
 
+    /// Generate with fixed sampling options and report the options that were
+    /// applied, or `None` when the model's provider cannot honor them
+    pub fn generate_with_sampling(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> Result<(ProviderResponse, Option<SamplingOptions>)> {
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| adapter.can_handle(model_id))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No adapter found for model '{}'. Please check model catalog configuration.",
+                    model_id
+                )
+            })?;
+        let applied = adapter.supports_sampling().then_some(*sampling);
+        let generation = adapter
+            .generate_with_sampling(model_id, prompt, sampling)
+            .with_context(|| {
+                format!(
+                    "Failed to generate with {} for model {}",
+                    adapter.provider_name(),
+                    model_id
+                )
+            })?;
+        Ok((generation, applied))
+    }
+
+    /// Generate within a token limit, with sampling options if given, and
+    /// report the options that were applied
+    pub fn generate_bounded(
+        &self,
+        model_id: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> Result<(ProviderResponse, Option<SamplingOptions>)> {
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| adapter.can_handle(model_id))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No adapter found for model '{}'. Please check model catalog configuration.",
+                    model_id
+                )
+            })?;
+        let applied = sampling.filter(|_| adapter.supports_sampling()).copied();
+        let generation = adapter
+            .generate_bounded(model_id, prompt, sampling, token_limit)
+            .with_context(|| {
+                format!(
+                    "Failed to generate with {} for model {}",
+                    adapter.provider_name(),
+                    model_id
+                )
+            })?;
+        Ok((generation, applied))
+    }
+
+    pub fn embed(&self, model_id: &str, inputs: &[String]) -> Result<EmbeddingResponse> {
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| adapter.can_handle(model_id))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No adapter found for model '{}'. Please check model catalog configuration.",
+                    model_id
+                )
+            })?;
+        adapter.embed(model_id, inputs).with_context(|| {
+            format!(
+                "Failed to embed with {} for model {}",
+                adapter.provider_name(),
+                model_id
+            )
+        })
+    }
+
     /// Check if API key is required and configured for a model
     pub fn check_api_key_configured(&self, model_id: &str) -> Result<()> {
         // Check if model requires API key
@@ -583,6 +1180,169 @@ mod tests {
         assert!(!adapter.can_handle("gpt-4o"));
     }
 
+    #[test]
+    fn cloud_requests_ask_for_at_most_the_token_limit() {
+        let mut model: model_catalog::ModelDef = serde_json::from_value(serde_json::json!({
+            "id": "claude-test",
+            "provider": "anthropic",
+            "display_name": "Claude Test",
+            "description": "",
+            "cost_per_million_tokens": 3.0,
+            "nature_cost_per_million_tokens": 1.0,
+            "energy_kwh_per_million_tokens": 0.5
+        }))
+        .unwrap();
+        assert_eq!(
+            max_output_tokens(&model, "", None),
+            DEFAULT_MAX_OUTPUT_TOKENS
+        );
+        assert_eq!(max_output_tokens(&model, "", Some(500)), 500);
+
+        model.max_output_tokens = Some(8192);
+        assert_eq!(max_output_tokens(&model, "", None), 8192);
+        assert_eq!(max_output_tokens(&model, "", Some(100_000)), 8192);
+        assert_eq!(max_output_tokens(&model, "", Some(0)), 1);
+    }
+
+    #[test]
+    fn cloud_requests_leave_the_prompt_its_share_of_the_token_limit() {
+        let model: model_catalog::ModelDef = serde_json::from_value(serde_json::json!({
+            "id": "claude-test",
+            "provider": "anthropic",
+            "display_name": "Claude Test",
+            "description": "",
+            "cost_per_million_tokens": 3.0,
+            "nature_cost_per_million_tokens": 1.0,
+            "energy_kwh_per_million_tokens": 0.5,
+            "max_output_tokens": 8192
+        }))
+        .unwrap();
+        // About 1,000 prompt tokens
+        let prompt = "word ".repeat(800);
+
+        assert_eq!(max_output_tokens(&model, &prompt, Some(1_200)), 200);
+        // A prompt that alone spends the budget leaves the smallest completion
+        assert_eq!(max_output_tokens(&model, &prompt, Some(300)), 1);
+        // Without a limit the prompt does not count against the catalog maximum
+        assert_eq!(max_output_tokens(&model, &prompt, None), 8192);
+    }
+
+    #[test]
+    fn anthropic_and_openai_responses_normalize_alike() {
+        let anthropic = serde_json::json!({
+            "id": "msg_01",
+            "content": [
+                {"type": "text", "text": "Checking the weather."},
+                {"type": "tool_use", "id": "toolu_1", "name": "weather", "input": {"city": "Lima"}}
+            ],
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 12, "output_tokens": 30}
+        });
+        let openai = serde_json::json!({
+            "id": "chatcmpl-9",
+            "choices": [{
+                "message": {
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "weather", "arguments": "{\"city\":\"Lima\"}"}
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 30}
+        });
+
+        let from_anthropic = normalize_anthropic(&anthropic, Some("req_abc")).unwrap();
+        let from_openai = normalize_openai("groq", &openai, None).unwrap();
+
+        assert_eq!(from_anthropic.text, "Checking the weather.");
+        assert_eq!(from_anthropic.provider_request_id.as_deref(), Some("req_abc"));
+        assert_eq!(from_openai.text, "");
+        assert_eq!(from_openai.provider_request_id.as_deref(), Some("chatcmpl-9"));
+        for response in [&from_anthropic, &from_openai] {
+            assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+            assert_eq!(response.usage.total(), 42);
+            assert_eq!(response.tool_calls[0].name, "weather");
+            assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"city": "Lima"}));
+        }
+        assert_eq!(from_openai.metadata().provider, "groq");
+    }
+
+    #[test]
+    fn gemini_parts_are_joined_and_safety_stops_map_to_content_filter() {
+        let body = serde_json::json!({
+            "responseId": "resp-7",
+            "candidates": [{
+                "content": {"parts": [{"text": "Partial "}, {"text": "answer"}]},
+                "finishReason": "SAFETY"
+            }],
+            "usageMetadata": {"promptTokenCount": 5, "candidatesTokenCount": 2}
+        });
+        let response = normalize_gemini(&body).unwrap();
+        assert_eq!(response.text, "Partial answer");
+        assert_eq!(response.finish_reason, FinishReason::ContentFilter);
+        assert_eq!(response.provider_request_id.as_deref(), Some("resp-7"));
+
+        // Checkpoints record the metadata without empty tool calls
+        let recorded = serde_json::to_value(response.metadata()).unwrap();
+        assert_eq!(
+            recorded,
+            serde_json::json!({
+                "provider": "google",
+                "finish_reason": "content_filter",
+                "provider_request_id": "resp-7"
+            })
+        );
+
+        assert!(normalize_gemini(&serde_json::json!({"candidates": []})).is_err());
+    }
+
+    #[test]
+    fn response_headers_digest_covers_only_named_headers() {
+        let headers = [
+            ("x-request-id", "req_1 "),
+            ("date", "Tue, 01 Oct 2024 10:00:00 GMT"),
+            ("set-cookie", "a=b"),
+        ];
+        let lookup = |name: &str| {
+            headers
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| *value)
+        };
+        let digest = response_headers_digest(lookup).unwrap();
+        assert_eq!(
+            digest,
+            provenance::sha256_hex(b"date:Tue, 01 Oct 2024 10:00:00 GMT\nx-request-id:req_1\n")
+        );
+        assert!(response_headers_digest(|_| None).is_none());
+    }
+
+    #[test]
+    fn embedding_responses_normalize_in_input_order() {
+        let ollama = serde_json::json!({
+            "embeddings": [[0.5, 1.0], [0.0, -1.0]],
+            "prompt_eval_count": 9
+        });
+        let normalized = normalize_ollama_embeddings(&ollama, 2).unwrap();
+        assert_eq!(normalized.vectors, vec![vec![0.5, 1.0], vec![0.0, -1.0]]);
+        assert_eq!(normalized.prompt_tokens, 9);
+        assert!(normalize_ollama_embeddings(&ollama, 3).is_err());
+
+        let openai = serde_json::json!({
+            "data": [
+                {"index": 1, "embedding": [0.0, -1.0]},
+                {"index": 0, "embedding": [0.5, 1.0]}
+            ],
+            "usage": {"prompt_tokens": 9, "total_tokens": 9}
+        });
+        let normalized = normalize_openai_embeddings("openai", &openai, 2).unwrap();
+        assert_eq!(normalized.vectors, vec![vec![0.5, 1.0], vec![0.0, -1.0]]);
+        assert_eq!(normalized.provider, "openai");
+    }
+
     #[test]
     fn test_dispatcher_finds_adapter() {
         let dispatcher = ModelDispatcher::new();
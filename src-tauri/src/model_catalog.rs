@@ -106,6 +106,10 @@ pub struct ProviderInfo {
     pub requires_api_key: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_endpoint: Option<String>,
+    /// Where the provider processes requests: "local" for on-device
+    /// inference, otherwise a jurisdiction such as "us" or "eu"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
 }
 
 /// Signature block for catalog verification
@@ -124,10 +128,20 @@ pub struct RawModelCatalog {
     pub nature_cost_algorithms: HashMap<String, NatureCostAlgorithm>,
     pub models: Vec<ModelDef>,
     pub providers: HashMap<String, ProviderInfo>,
+    /// Carbon intensity of electricity, in gCO2e per kWh, by grid region
+    #[serde(default)]
+    pub grid_carbon_intensity: HashMap<String, f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<CatalogSignature>,
 }
 
+/// Grid region whose carbon intensity applies when the catalog lists none
+/// for a region
+pub const DEFAULT_GRID_REGION: &str = "world";
+
+/// Global average carbon intensity, for catalogs without a grid table
+const FALLBACK_GCO2E_PER_KWH: f64 = 475.0;
+
 /// Verified model catalog with computed hash
 #[derive(Debug, Clone)]
 pub struct ModelCatalog {
@@ -372,8 +386,27 @@ impl ModelCatalog {
         (tokens as f64 / 1_000_000.0) * cost_per_million
     }
 
-    /// Calculate nature cost for a given model and token count
+    /// Calculate nature cost for a given model and token count, for a local
+    /// grid in the default region
     pub fn calculate_nature_cost(&self, model_id: &str, tokens: u64) -> f64 {
+        self.calculate_nature_cost_in(model_id, tokens, None)
+    }
+
+    /// Calculate nature cost with the catalog's algorithm: with
+    /// `energy_based`, the gCO2e of the model's energy on the grid it runs
+    /// on (see `calculate_gco2e`); otherwise, or for models without an
+    /// energy figure, the model's flat nature cost per token
+    pub fn calculate_nature_cost_in(
+        &self,
+        model_id: &str,
+        tokens: u64,
+        grid_region: Option<&str>,
+    ) -> f64 {
+        if self.raw.defaults.nature_cost_algorithm == "energy_based" {
+            if let Some(g_co2e) = self.calculate_gco2e(model_id, tokens, grid_region) {
+                return g_co2e;
+            }
+        }
         let nature_cost_per_million = self
             .get_model(model_id)
             .and_then(|m| {
@@ -389,6 +422,47 @@ impl ModelCatalog {
         (tokens as f64 / 1_000_000.0) * nature_cost_per_million
     }
 
+    /// Grid region a model's energy is drawn from: its provider's region for
+    /// hosted models, the user's grid for models that run on-device
+    pub fn energy_region(&self, model_id: &str, grid_region: Option<&str>) -> String {
+        let hosted_region = self
+            .get_model(model_id)
+            .and_then(|model| self.get_provider(&model.provider))
+            .and_then(|provider| provider.region.as_deref())
+            .filter(|region| *region != crate::provider_policy::LOCAL_REGION);
+        hosted_region
+            .or(grid_region)
+            .unwrap_or(DEFAULT_GRID_REGION)
+            .to_string()
+    }
+
+    /// Carbon intensity of a grid region in gCO2e per kWh
+    pub fn carbon_intensity(&self, region: &str) -> f64 {
+        let table = &self.raw.grid_carbon_intensity;
+        table
+            .get(region)
+            .or_else(|| table.get(DEFAULT_GRID_REGION))
+            .copied()
+            .unwrap_or(FALLBACK_GCO2E_PER_KWH)
+    }
+
+    /// gCO2e of a model's tokens: energy per token from the catalog times the
+    /// carbon intensity of the grid it runs on; `None` for models without an
+    /// energy figure
+    pub fn calculate_gco2e(
+        &self,
+        model_id: &str,
+        tokens: u64,
+        grid_region: Option<&str>,
+    ) -> Option<f64> {
+        let energy_per_million = self
+            .get_model(model_id)
+            .map(|m| m.energy_kwh_per_million_tokens)
+            .filter(|value| value.is_finite() && *value > 0.0)?;
+        let kwh = (tokens as f64 / 1_000_000.0) * energy_per_million;
+        Some(kwh * self.carbon_intensity(&self.energy_region(model_id, grid_region)))
+    }
+
     /// Calculate energy consumption for a given model and token count
     pub fn calculate_energy_kwh(&self, model_id: &str, tokens: u64) -> f64 {
         let energy_per_million = self
@@ -479,6 +553,7 @@ impl ModelCatalog {
                 },
             ],
             providers: HashMap::new(),
+            grid_carbon_intensity: HashMap::new(),
             signature: None,
         };
 
@@ -529,6 +604,18 @@ pub fn try_get_global_catalog() -> Option<&'static ModelCatalog> {
     GLOBAL_CATALOG.get()
 }
 
+/// The user's grid region, which on-device inference is priced against
+static GRID_REGION: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+
+/// Set the user's grid region (loaded from the database at startup)
+pub fn set_grid_region(region: Option<String>) {
+    *GRID_REGION.write().unwrap_or_else(|err| err.into_inner()) = region;
+}
+
+pub fn grid_region() -> Option<String> {
+    GRID_REGION.read().unwrap_or_else(|err| err.into_inner()).clone()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -595,6 +682,71 @@ description = "Test provider"
         assert_eq!(nature_cost, 2.5);
     }
 
+    #[test]
+    fn test_energy_based_nature_cost_uses_the_grid_the_model_runs_on() {
+        let toml = r#"
+[metadata]
+version = "1.0.0"
+created_at = "2025-01-01T00:00:00Z"
+description = "Test catalog"
+
+[defaults]
+nature_cost_algorithm = "energy_based"
+fallback_cost_per_million_tokens = 5.0
+fallback_nature_cost_per_million_tokens = 2.5
+
+[nature_cost_algorithms.energy_based]
+formula = "test"
+description = "Test algorithm"
+
+[grid_carbon_intensity]
+world = 500.0
+us = 400.0
+fr = 50.0
+
+[[models]]
+id = "hosted"
+provider = "cloud"
+display_name = "Hosted"
+description = "Hosted model"
+cost_per_million_tokens = 1.0
+nature_cost_per_million_tokens = 0.5
+energy_kwh_per_million_tokens = 2.0
+enabled = true
+
+[[models]]
+id = "local"
+provider = "ollama"
+display_name = "Local"
+description = "On-device model"
+cost_per_million_tokens = 0.0
+nature_cost_per_million_tokens = 0.5
+energy_kwh_per_million_tokens = 1.0
+enabled = true
+
+[providers.cloud]
+name = "Cloud"
+description = "Hosted provider"
+region = "us"
+
+[providers.ollama]
+name = "Ollama"
+description = "Local provider"
+region = "local"
+"#;
+
+        let catalog = ModelCatalog::load_from_str(toml).unwrap();
+        // Hosted models run on their provider's grid wherever the user is
+        assert_eq!(catalog.calculate_gco2e("hosted", 1_000_000, Some("fr")), Some(800.0));
+        // On-device models run on the user's grid, or the default region's
+        assert_eq!(catalog.calculate_nature_cost_in("local", 1_000_000, Some("fr")), 50.0);
+        assert_eq!(catalog.calculate_nature_cost("local", 1_000_000), 500.0);
+        assert_eq!(catalog.carbon_intensity("unlisted"), 500.0);
+        // Models without an energy figure keep the flat nature cost
+        assert_eq!(catalog.calculate_gco2e("unknown", 1_000_000, None), None);
+        assert_eq!(catalog.calculate_nature_cost("unknown", 1_000_000), 2.5);
+    }
+
     #[test]
     fn test_load_from_str() {
         let toml = r#"
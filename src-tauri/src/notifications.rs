@@ -0,0 +1,341 @@
+// src-tauri/src/notifications.rs
+//!
+//! Notifications: budget alerts before budgets run out
+//!
+//! A policy's `budgetAlertThresholds` are percentages of each of its budgets,
+//! e.g. `[80, 95]`. Whenever usage is committed to the project's ledger, the
+//! policy version's token, USD and Nature Cost budgets and its window budgets
+//! are compared against them. A threshold fires once per budget and period
+//! (policy version, or calendar window) and is recorded in `budget_alerts`.
+//!
+//! Fired alerts are delivered as a `budget-alert` Tauri event and, when
+//! configured, POSTed as JSON to a webhook and emailed over SMTP. Deliveries
+//! run in the background and their failures are only logged: an alert must
+//! never fail the run that crossed it.
+
+use crate::store::{
+    self,
+    notifications::{self as alerts, BudgetAlert, NotificationSettings},
+    policies::Policy,
+};
+use crate::{keychain, Error};
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use once_cell::sync::OnceCell;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::Emitter;
+
+pub const BUDGET_ALERT_EVENT: &str = "budget-alert";
+
+/// Keychain entry holding the SMTP password
+pub const SMTP_PASSWORD_SECRET_ID: &str = "notifications_smtp_password";
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+static APP_HANDLE: OnceCell<tauri::AppHandle> = OnceCell::new();
+
+/// Deliver budget alerts to the app's windows from now on
+pub fn init_event_emitter(handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(handle);
+}
+
+pub fn validate_thresholds(thresholds: &[u32]) -> Result<(), String> {
+    match thresholds
+        .iter()
+        .find(|threshold| !(1..=100).contains(*threshold))
+    {
+        Some(threshold) => Err(format!(
+            "budget alert threshold {threshold}% must be between 1% and 100%"
+        )),
+        None => Ok(()),
+    }
+}
+
+pub fn validate_settings(settings: &NotificationSettings) -> Result<(), String> {
+    if let Some(url) = &settings.webhook_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!("webhook URL {url} must be an http(s) URL"));
+        }
+    }
+    for address in [&settings.email_to, &settings.email_from]
+        .into_iter()
+        .flatten()
+    {
+        address
+            .parse::<Mailbox>()
+            .map_err(|err| format!("invalid email address {address}: {err}"))?;
+    }
+    if settings.email_to.is_some() && settings.smtp_host.is_none() {
+        return Err("email alerts need an SMTP host".into());
+    }
+    Ok(())
+}
+
+/// Save where alerts are delivered. A given SMTP password replaces the stored
+/// one and an empty one removes it; without one the stored password is kept.
+pub fn save_settings(
+    conn: &Connection,
+    settings: &NotificationSettings,
+    smtp_password: Option<&str>,
+) -> Result<NotificationSettings, Error> {
+    validate_settings(settings).map_err(Error::Api)?;
+    match smtp_password {
+        Some("") => {
+            // Nothing to remove is fine
+            let _ = keychain::delete_secret(SMTP_PASSWORD_SECRET_ID);
+        }
+        Some(password) => keychain::store_secret(SMTP_PASSWORD_SECRET_ID, password)
+            .map_err(|err| Error::Api(format!("failed to store SMTP password: {err}")))?,
+        None => {}
+    }
+    alerts::set_settings(conn, settings)?;
+    alerts::get_settings(conn)
+}
+
+/// Fire the alert thresholds the project's usage under `policy_version` has
+/// newly crossed, returning the alerts that were delivered
+pub fn check_budget_alerts(
+    conn: &Connection,
+    project_id: &str,
+    policy_version: Option<i64>,
+) -> Vec<BudgetAlert> {
+    let fired = match record_crossed_thresholds(conn, project_id, policy_version) {
+        Ok(fired) => fired,
+        Err(err) => {
+            eprintln!("⚠️  Warning: Failed to check budget alerts: {}", err);
+            return Vec::new();
+        }
+    };
+    if !fired.is_empty() {
+        dispatch(conn, &fired);
+    }
+    fired
+}
+
+/// Budget usage the policy's thresholds are measured against
+struct BudgetUsage {
+    scope: String,
+    period: String,
+    budget: &'static str,
+    used: f64,
+    limit: f64,
+}
+
+fn budget_usages(
+    conn: &Connection,
+    project_id: &str,
+    policy_version: Option<i64>,
+    policy: &Policy,
+) -> Result<Vec<BudgetUsage>, Error> {
+    let ledger = store::project_usage_ledgers::get(conn, project_id, policy_version)?;
+    let period = ledger.policy_version.to_string();
+    let mut usages = vec![
+        BudgetUsage {
+            scope: "policy".into(),
+            period: period.clone(),
+            budget: "tokens",
+            used: ledger.total_tokens as f64,
+            limit: policy.budget_tokens as f64,
+        },
+        BudgetUsage {
+            scope: "policy".into(),
+            period: period.clone(),
+            budget: "usd",
+            used: ledger.total_usd,
+            limit: policy.budget_usd,
+        },
+        BudgetUsage {
+            scope: "policy".into(),
+            period,
+            budget: "natureCost",
+            used: ledger.total_nature_cost,
+            limit: policy.budget_nature_cost,
+        },
+    ];
+
+    let now = Utc::now();
+    for window_budget in &policy.window_budgets {
+        let usage =
+            store::project_usage_ledgers::get_window(conn, project_id, window_budget.window, now)?;
+        let caps = &window_budget.caps;
+        let window_usages = [
+            (
+                "tokens",
+                usage.total_tokens as f64,
+                caps.tokens.map(|cap| cap as f64),
+            ),
+            ("usd", usage.total_usd, caps.usd),
+            ("natureCost", usage.total_nature_cost, caps.nature_cost),
+        ];
+        for (budget, used, limit) in window_usages {
+            if let Some(limit) = limit {
+                usages.push(BudgetUsage {
+                    scope: window_budget.window.as_str().into(),
+                    period: usage.window_start.clone(),
+                    budget,
+                    used,
+                    limit,
+                });
+            }
+        }
+    }
+    Ok(usages)
+}
+
+/// Record every crossed threshold that has not fired yet; of those, only the
+/// highest per budget is returned for delivery
+fn record_crossed_thresholds(
+    conn: &Connection,
+    project_id: &str,
+    policy_version: Option<i64>,
+) -> Result<Vec<BudgetAlert>, Error> {
+    let policy = store::policies::get_for_policy_version(conn, project_id, policy_version)?;
+    if policy.budget_alert_thresholds.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut thresholds = policy.budget_alert_thresholds.clone();
+    thresholds.sort_unstable();
+    thresholds.dedup();
+
+    let fired_at = Utc::now().to_rfc3339();
+    let mut fired = BTreeMap::new();
+    for usage in budget_usages(conn, project_id, policy_version, &policy)? {
+        if usage.limit <= 0.0 {
+            continue;
+        }
+        for &threshold in &thresholds {
+            if usage.used < usage.limit * f64::from(threshold) / 100.0 {
+                break;
+            }
+            let alert = BudgetAlert {
+                id: uuid::Uuid::new_v4().to_string(),
+                project_id: project_id.to_string(),
+                scope: usage.scope.clone(),
+                period: usage.period.clone(),
+                budget: usage.budget.to_string(),
+                threshold,
+                used: usage.used,
+                limit: usage.limit,
+                fired_at: fired_at.clone(),
+            };
+            if alerts::insert_if_new(conn, &alert)? {
+                fired.insert((usage.scope.clone(), usage.budget), alert);
+            }
+        }
+    }
+    Ok(fired.into_values().collect())
+}
+
+fn dispatch(conn: &Connection, fired: &[BudgetAlert]) {
+    if let Some(handle) = APP_HANDLE.get() {
+        for alert in fired {
+            if let Err(err) = handle.emit(BUDGET_ALERT_EVENT, alert) {
+                eprintln!("⚠️  Warning: Failed to emit budget alert: {}", err);
+            }
+        }
+    }
+
+    let settings = match alerts::get_settings(conn) {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("⚠️  Warning: Failed to load notification settings: {}", err);
+            return;
+        }
+    };
+    if settings.webhook_url.is_none() && settings.email_to.is_none() {
+        return;
+    }
+    let smtp_password = settings
+        .smtp_username
+        .as_ref()
+        .and_then(|_| keychain::load_secret(SMTP_PASSWORD_SECRET_ID).ok());
+    let fired = fired.to_vec();
+    std::thread::spawn(move || {
+        for alert in &fired {
+            if let Some(url) = &settings.webhook_url {
+                if let Err(err) = post_webhook(url, alert) {
+                    eprintln!(
+                        "⚠️  Warning: Failed to deliver budget alert webhook: {}",
+                        err
+                    );
+                }
+            }
+            if settings.email_to.is_some() {
+                if let Err(err) = send_email(&settings, smtp_password.as_deref(), alert) {
+                    eprintln!("⚠️  Warning: Failed to email budget alert: {}", err);
+                }
+            }
+        }
+    });
+}
+
+fn describe(alert: &BudgetAlert) -> String {
+    let budget = match alert.budget.as_str() {
+        "tokens" => "token",
+        "usd" => "USD",
+        "natureCost" => "Nature Cost",
+        other => other,
+    };
+    let scope = match alert.scope.as_str() {
+        "policy" => format!("under policy version {}", alert.period),
+        window => format!("for the {window} starting {}", alert.period),
+    };
+    format!(
+        "Project {} has used {:.0}% of its {budget} budget {scope} ({} of {}), \
+         crossing the {}% alert threshold.",
+        alert.project_id,
+        alert.used / alert.limit * 100.0,
+        alert.used,
+        alert.limit,
+        alert.threshold
+    )
+}
+
+fn post_webhook(url: &str, alert: &BudgetAlert) -> anyhow::Result<()> {
+    ureq::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .post(url)
+        .send_json(serde_json::json!({
+            "event": BUDGET_ALERT_EVENT,
+            "message": describe(alert),
+            "alert": alert,
+        }))
+        .map_err(|err| anyhow!("POST {url} failed: {err}"))?;
+    Ok(())
+}
+
+fn send_email(
+    settings: &NotificationSettings,
+    smtp_password: Option<&str>,
+    alert: &BudgetAlert,
+) -> anyhow::Result<()> {
+    let to = settings.email_to.as_deref().context("no alert recipient")?;
+    let host = settings.smtp_host.as_deref().context("no SMTP host")?;
+    let from = settings.email_from.as_deref().unwrap_or(to);
+    let email = Message::builder()
+        .from(from.parse::<Mailbox>()?)
+        .to(to.parse::<Mailbox>()?)
+        .subject(format!(
+            "Intelexta budget alert: {}% of {} budget used",
+            alert.threshold, alert.budget
+        ))
+        .body(describe(alert))?;
+
+    // Submission ports upgrade with STARTTLS, anything else uses implicit TLS
+    let mut transport = match settings.smtp_port {
+        Some(port @ (25 | 587)) => SmtpTransport::starttls_relay(host)?.port(port),
+        Some(port) => SmtpTransport::relay(host)?.port(port),
+        None => SmtpTransport::relay(host)?,
+    };
+    if let (Some(username), Some(password)) = (&settings.smtp_username, smtp_password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.into()));
+    }
+    transport.build().send(&email)?;
+    Ok(())
+}
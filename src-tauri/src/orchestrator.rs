@@ -1,21 +1,31 @@
 // src-tauri/src/orchestrator.rs
 use crate::api::RunStepRequest;
-use crate::{governance, provenance, store, DbPool};
+use crate::{agent, branching, chunk, content_policy, embeddings, evaluation, execution_window, finetune, governance, incident_response, notifications, output_schema, prompt_template, plugins, provenance, run_queue, runtime, store, telemetry, DbPool};
+use crate::model_adapters::{EmbeddingResponse, ResponseMetadata, SamplingOptions};
+use crate::provenance::ProjectSigner;
+use crate::run_environment::RunEnvironment;
+use crate::run_spec::default_step_type;
+use crate::store::project_usage_ledgers::{BudgetWindow, WindowUsage};
+use crate::verify::CheckpointBody;
 use anyhow::{anyhow, Context};
+use intelexta_verify_core::semantic;
 use chrono::Utc;
-use ed25519_dalek::SigningKey;
 use keyring::Error as KeyringError;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
-use std::fmt;
 use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
 use std::net::TcpStream;
 use std::ops::Deref;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 use uuid::Uuid;
 
+pub use crate::run_spec::{RunProofMode, RunProofModeParseError, RunStep};
+
 const STUB_MODEL_ID: &str = "stub-model";
 
 // Debug logging flag - set to false for production
@@ -28,12 +38,15 @@ const MAX_PAYLOAD_PREVIEW_SIZE: usize = 65_536; // 64KB preview limit
 const CLAUDE_MODEL_PREFIX: &str = "claude-";
 const CLAUDE_API_PLACEHOLDER_KEY: &str = "sk-ant-REDACTED";
 
+// Correction prompts issued before a schema violation becomes an incident
+const DEFAULT_SCHEMA_RETRIES: u32 = 1;
+
 /// Configuration for document ingestion steps
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DocumentIngestionConfig {
     pub source_path: String,
-    pub format: String, // "pdf", "latex", "docx", "txt"
+    pub format: String, // "pdf", "latex", "docx", "txt", "html", "epub", "markdown", "ipynb"
     pub privacy_status: String, // "public", "consent_obtained_anonymized", etc.
     #[serde(default)]
     pub output_storage: String, // "database" or "file", defaults to "database"
@@ -48,7 +61,7 @@ pub enum StepConfig {
     #[serde(rename = "ingest", rename_all = "camelCase")]
     Ingest {
         source_path: String,
-        format: String,  // "pdf", "latex", "txt", "docx"
+        format: String,  // "pdf", "latex", "txt", "docx", "html", "epub", "markdown", "ipynb"
         privacy_status: String,
     },
 
@@ -80,9 +93,122 @@ pub enum StepConfig {
         model: String,
         prompt: String,
 
-        /// Optional: index of step to use as context
+        /// Optional: step of this run, or checkpoint of an earlier run, to use as context
+        #[serde(skip_serializing_if = "Option::is_none")]
+        use_output_from: Option<StepOutputRef>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+
+        /// Optional: JSON Schema the response must conform to
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output_schema: Option<Value>,
+
+        /// Optional: correction attempts when the response violates `output_schema`
+        /// (defaults to DEFAULT_SCHEMA_RETRIES)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        schema_retries: Option<u32>,
+
+        /// Render `prompt` as a template (see `prompt_template`) instead of
+        /// appending the source output as context
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        template: bool,
+    },
+
+    /// Apply a prompt template to each element of a prior step's JSON array output
+    #[serde(rename = "map", rename_all = "camelCase")]
+    Map {
+        /// Index of the step whose output is a JSON array
+        source_step: usize,
+
+        model: String,
+
+        /// Template rendered per element; `{{item}}` and `{{index}}` are substituted
+        prompt_template: String,
+
+        /// Token budget shared by all elements
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+    },
+
+    /// Evaluate a predicate on a prior step's output and select which step group runs
+    #[serde(rename = "branch", rename_all = "camelCase")]
+    Branch {
+        /// Index of the step whose output the predicate inspects
+        source_step: usize,
+
+        predicate: branching::BranchPredicate,
+
+        /// Outcome ("true"/"false", or a classification label) -> order indices of
+        /// the steps it enables; steps only listed under other outcomes are skipped
+        branches: std::collections::BTreeMap<String, Vec<usize>>,
+    },
+
+    /// Step type provided by an installed WASM plugin (see `plugins`)
+    #[serde(rename = "plugin", rename_all = "camelCase")]
+    Plugin {
+        /// Plugin id from the plugin's manifest
+        plugin: String,
+
+        /// One of the step types the plugin declares
+        step: String,
+
+        /// Passed to the plugin unchanged
+        #[serde(default)]
+        config: Value,
+
+        /// Optional: index of the step whose output the plugin receives
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_step: Option<usize>,
+
+        /// Optional: local file the plugin receives, subject to the path policy
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_path: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+    },
+
+    /// Multi-turn agent that may call tools before answering (see `agent`)
+    #[serde(rename = "agent", rename_all = "camelCase")]
+    Agent {
+        model: String,
+
+        /// Task the agent works on
+        prompt: String,
+
+        #[serde(default)]
+        tools: Vec<agent::AgentTool>,
+
+        /// Optional: index of the step whose output `retrieval` searches
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_step: Option<usize>,
+
+        /// Directories `read_file` may read from (within the policy's allowed roots)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        file_roots: Vec<String>,
+
+        /// Optional: model turns before the step fails (defaults to agent::DEFAULT_MAX_TURNS)
         #[serde(skip_serializing_if = "Option::is_none")]
-        use_output_from: Option<usize>,
+        max_turns: Option<u32>,
 
         #[serde(skip_serializing_if = "Option::is_none")]
         token_budget: Option<i32>,
@@ -93,6 +219,228 @@ pub enum StepConfig {
         #[serde(skip_serializing_if = "Option::is_none")]
         epsilon: Option<f64>,
     },
+
+    /// Local training job run as a subprocess (see `finetune`)
+    #[serde(rename = "finetune", rename_all = "camelCase")]
+    FineTune {
+        /// Model the job fine-tunes
+        base_model: String,
+
+        /// Training program and its arguments; `{{name}}` takes a hyperparameter
+        command: Vec<String>,
+
+        #[serde(default)]
+        hyperparameters: serde_json::Map<String, Value>,
+
+        /// Dataset files, hashed before training
+        #[serde(default)]
+        datasets: Vec<String>,
+
+        /// Weights file the job writes
+        output_weights: String,
+
+        /// Optional: file the job writes its loss log to (defaults to its output)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        loss_log: Option<String>,
+
+        /// Optional: model id to register the trained weights under
+        #[serde(skip_serializing_if = "Option::is_none")]
+        register_as: Option<String>,
+
+        /// Optional: wall-clock limit (defaults to finetune::DEFAULT_TIMEOUT_SECS)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_secs: Option<u64>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+    },
+
+    /// Embed chunks of a prior step's output for retrieval (see `embeddings`)
+    #[serde(rename = "embed", rename_all = "camelCase")]
+    Embed {
+        /// Index of the step whose output is chunked and embedded
+        source_step: usize,
+
+        /// Embedding model
+        model: String,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+    },
+
+    /// Score one output against a reference output (see `evaluation`)
+    #[serde(rename = "evaluate", rename_all = "camelCase")]
+    Evaluate {
+        /// Index of the step whose output is scored
+        candidate_step: usize,
+
+        /// Optional: index of the step whose output is the reference
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reference_step: Option<usize>,
+
+        /// Optional: checkpoint of an earlier run in the project whose output is the reference
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reference_checkpoint_id: Option<String>,
+
+        /// Optional: model whose embeddings give the cosine score
+        #[serde(skip_serializing_if = "Option::is_none")]
+        embedding_model: Option<String>,
+
+        /// Optional: model asked to grade the candidate
+        #[serde(skip_serializing_if = "Option::is_none")]
+        judge_model: Option<String>,
+
+        /// Optional: what the judge grades on
+        #[serde(skip_serializing_if = "Option::is_none")]
+        judge_criteria: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token_budget: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof_mode: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        epsilon: Option<f64>,
+    },
+}
+
+/// Output a step takes as context: a step index in the same run, or the id
+/// of a step checkpoint recorded by an earlier run of the project
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum StepOutputRef {
+    Step(usize),
+    Checkpoint(String),
+}
+
+impl StepOutputRef {
+    pub fn step_index(&self) -> Option<usize> {
+        match self {
+            StepOutputRef::Step(index) => Some(*index),
+            StepOutputRef::Checkpoint(_) => None,
+        }
+    }
+
+    pub fn checkpoint_id(&self) -> Option<&str> {
+        match self {
+            StepOutputRef::Step(_) => None,
+            StepOutputRef::Checkpoint(id) => Some(id),
+        }
+    }
+}
+
+impl StepConfig {
+    /// Checkpoint of an earlier run whose output this step consumes, if any
+    pub fn referenced_checkpoint(&self) -> Option<&str> {
+        match self {
+            StepConfig::Prompt {
+                use_output_from, ..
+            } => use_output_from.as_ref().and_then(StepOutputRef::checkpoint_id),
+            StepConfig::Evaluate {
+                reference_checkpoint_id,
+                ..
+            } => reference_checkpoint_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// JSON Schema the step output must satisfy, if any
+    pub fn output_schema(&self) -> Option<&Value> {
+        match self {
+            StepConfig::Prompt { output_schema, .. } => output_schema.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Check embedded schemas and predicates before the config is stored
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(schema) = self.output_schema() {
+            output_schema::check_schema(schema)
+                .map_err(|err| format!("invalid output schema: {}", err))?;
+        }
+        if let StepConfig::Prompt {
+            prompt,
+            template: true,
+            ..
+        } = self
+        {
+            prompt_template::validate(prompt)
+                .map_err(|err| format!("invalid prompt template: {}", err))?;
+        }
+        if let StepConfig::Branch { predicate, .. } = self {
+            predicate
+                .check()
+                .map_err(|err| format!("invalid branch predicate: {}", err))?;
+        }
+        if let StepConfig::Plugin { plugin, step, .. } = self {
+            if plugin.trim().is_empty() || step.trim().is_empty() {
+                return Err("plugin steps require a plugin id and step type".to_string());
+            }
+        }
+        if let StepConfig::Agent {
+            tools,
+            source_step,
+            file_roots,
+            max_turns,
+            ..
+        } = self
+        {
+            if max_turns == &Some(0) {
+                return Err("agent steps need at least one turn".to_string());
+            }
+            if tools.contains(&agent::AgentTool::Retrieval) && source_step.is_none() {
+                return Err("the retrieval tool requires a source step".to_string());
+            }
+            if tools.contains(&agent::AgentTool::ReadFile) && file_roots.is_empty() {
+                return Err("the read_file tool requires at least one file root".to_string());
+            }
+        }
+        if let StepConfig::FineTune {
+            command,
+            output_weights,
+            ..
+        } = self
+        {
+            if command.first().map_or(true, |program| program.trim().is_empty()) {
+                return Err("fine-tune steps require a training command".to_string());
+            }
+            if output_weights.trim().is_empty() {
+                return Err("fine-tune steps require an output weights path".to_string());
+            }
+        }
+        if let StepConfig::Embed { model, .. } = self {
+            if model.trim().is_empty() {
+                return Err("embed steps require an embedding model".to_string());
+            }
+        }
+        if let StepConfig::Evaluate {
+            reference_step,
+            reference_checkpoint_id,
+            ..
+        } = self
+        {
+            if reference_step.is_some() == reference_checkpoint_id.is_some() {
+                return Err(
+                    "evaluate steps require either a reference step or a reference checkpoint"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Output from a step execution (for chaining)
@@ -105,19 +453,6 @@ pub struct StepOutput {
     pub outputs_sha256: String,
 }
 
-#[derive(Serialize)]
-struct CheckpointBody<'a> {
-    run_id: &'a str,
-    kind: &'a str, // "Step" or "Incident"
-    timestamp: String,
-    inputs_sha256: Option<&'a str>,
-    outputs_sha256: Option<&'a str>,
-    incident: Option<&'a serde_json::Value>,
-    usage_tokens: u64,
-    prompt_tokens: u64,
-    completion_tokens: u64,
-}
-
 #[derive(Clone, Copy)]
 struct CheckpointMessageInput<'a> {
     role: &'a str,
@@ -142,6 +477,14 @@ struct CheckpointInsert<'a> {
     semantic_digest: Option<&'a str>,
     prompt_payload: Option<&'a str>,
     output_payload: Option<&'a str>,
+    sampling_json: Option<&'a str>,
+    cache_hit: bool,
+    response_metadata: Option<&'a ResponseMetadata>,
+    run_inputs_sha256: Option<&'a str>,
+    /// Hash of the JSON Schema the step's output was validated against
+    output_schema_sha256: Option<&'a str>,
+    /// Priced at execution time; None where no model usage is attributed
+    cost: Option<governance::CheckpointCost>,
     message: Option<CheckpointMessageInput<'a>>,
 }
 
@@ -150,74 +493,10 @@ struct PersistedCheckpoint {
     curr_chain: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum RunProofMode {
-    Exact,
-    Concordant,
-}
-
-#[derive(Debug, Clone)]
-pub struct RunProofModeParseError {
-    mode: String,
-}
-
-impl RunProofModeParseError {
-    fn new(mode: &str) -> Self {
-        Self {
-            mode: mode.to_string(),
-        }
-    }
-}
-
-impl fmt::Display for RunProofModeParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "unsupported run proof mode: {}", self.mode)
-    }
-}
-
-impl std::error::Error for RunProofModeParseError {}
-
-impl Default for RunProofMode {
-    fn default() -> Self {
-        RunProofMode::Exact
-    }
-}
-
-impl RunProofMode {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            RunProofMode::Exact => "exact",
-            RunProofMode::Concordant => "concordant",
-        }
-    }
-
-    pub fn is_concordant(&self) -> bool {
-        matches!(self, RunProofMode::Concordant)
-    }
-}
-
-impl TryFrom<&str> for RunProofMode {
-    type Error = RunProofModeParseError;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "exact" => Ok(RunProofMode::Exact),
-            "concordant" => Ok(RunProofMode::Concordant),
-            "interactive" => Ok(RunProofMode::Exact),
-            other => Err(RunProofModeParseError::new(other)),
-        }
-    }
-}
-
 fn default_checkpoint_type() -> String {
     "Step".to_string()
 }
 
-fn default_step_type() -> String {
-    "llm".to_string()
-}
-
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RunStepTemplate {
@@ -244,45 +523,6 @@ pub struct RunStepTemplate {
     pub checkpoint_type: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RunStep {
-    pub id: String,
-    pub run_id: String,
-    pub order_index: i64,
-    pub checkpoint_type: String,
-    #[serde(default = "default_step_type")]
-    pub step_type: String,
-    // LLM step fields (optional for document ingestion steps)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub model: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub prompt: Option<String>,
-    #[serde(default)]
-    pub token_budget: u64,
-    #[serde(default)]
-    pub proof_mode: RunProofMode,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub epsilon: Option<f64>,
-    // Document ingestion config (as JSON string)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub config_json: Option<String>,
-}
-
-impl RunStep {
-    pub fn is_interactive_chat(&self) -> bool {
-        self.checkpoint_type.eq_ignore_ascii_case("InteractiveChat")
-    }
-
-    pub fn is_llm_step(&self) -> bool {
-        self.step_type == "llm"
-    }
-
-    pub fn is_document_ingestion(&self) -> bool {
-        self.step_type == "ingest" || self.step_type == "document_ingestion"
-    }
-}
-
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoredRun {
@@ -298,6 +538,8 @@ pub struct StoredRun {
     pub proof_mode: Option<RunProofMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub epsilon: Option<f64>,
+    #[serde(default)]
+    pub priority: run_queue::RunPriority,
     pub steps: Vec<RunStep>,
 }
 
@@ -307,6 +549,12 @@ pub struct RunExecutionRecord {
     pub id: String,
     pub run_id: String,
     pub created_at: String,
+    /// Pauses between steps that let higher-priority runs go first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub preemptions: Vec<run_queue::Preemption>,
+    /// Where the execution ran, recorded when it started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<RunEnvironment>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -328,12 +576,20 @@ pub(crate) struct NodeExecution {
     pub(crate) usage: TokenUsage,
     pub(crate) prompt_payload: Option<String>,
     pub(crate) output_payload: Option<String>,
+    /// Sampling options the provider applied, recorded so exact replays reuse them
+    pub(crate) sampling: Option<SamplingOptions>,
+    /// Served from the response cache rather than generated by the provider
+    pub(crate) cached: bool,
+    /// Finish reason, tool calls and request ID of the provider response
+    pub(crate) response_metadata: Option<ResponseMetadata>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct LlmGeneration {
     pub response: String,
     pub usage: TokenUsage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ResponseMetadata>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -348,49 +604,311 @@ pub struct CostProjection {
     pub exceeds_tokens: bool,
     pub exceeds_usd: bool,
     pub exceeds_nature_cost: bool,
+    /// Model and provider budgets of the policy the projection exceeds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_budget_overruns: Vec<governance::SubBudgetOverrun>,
 }
 
 impl CostProjection {
     fn exceeds_any(&self) -> bool {
-        self.exceeds_tokens || self.exceeds_usd || self.exceeds_nature_cost
+        self.exceeds_tokens
+            || self.exceeds_usd
+            || self.exceeds_nature_cost
+            || !self.sub_budget_overruns.is_empty()
     }
 }
 
+/// A window budget of the policy against what the project recorded in the
+/// current window plus what the run is still projected to use
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct RunCostEstimates {
-    pub per_run: CostProjection,
-    pub cumulative: CostProjection,
+pub struct WindowProjection {
+    pub window: BudgetWindow,
+    /// First UTC day of the current window
+    pub window_start: String,
+    pub budget: store::policies::SubBudget,
+    pub estimated_tokens: u64,
+    pub estimated_usd: f64,
+    pub estimated_nature_cost: f64,
+    /// What the window has left before the run, e.g. tokens remaining today;
+    /// absent where the budget sets no cap
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining_nature_cost: Option<f64>,
+    pub exceeds: bool,
 }
 
-impl RunCostEstimates {
-    fn exceeds_any(&self) -> bool {
-        self.per_run.exceeds_any() || self.cumulative.exceeds_any()
+impl WindowProjection {
+    fn new(
+        budget: &store::policies::WindowBudget,
+        used: &WindowUsage,
+        projected: ProjectedUsage,
+    ) -> Self {
+        let caps = &budget.caps;
+        let mut projection = WindowProjection {
+            window: budget.window,
+            window_start: used.window_start.clone(),
+            budget: caps.clone(),
+            estimated_tokens: used.total_tokens.saturating_add(projected.tokens),
+            estimated_usd: used.total_usd + projected.usd,
+            estimated_nature_cost: used.total_nature_cost + projected.nature_cost,
+            remaining_tokens: caps.tokens.map(|cap| cap.saturating_sub(used.total_tokens)),
+            remaining_usd: caps.usd.map(|cap| (cap - used.total_usd).max(0.0)),
+            remaining_nature_cost: caps
+                .nature_cost
+                .map(|cap| (cap - used.total_nature_cost).max(0.0)),
+            exceeds: false,
+        };
+        projection.exceeds = !projection.overruns().is_empty();
+        projection
     }
-}
-
-fn sum_token_budgets(configs: &[RunStep]) -> u64 {
-    configs
-        .iter()
-        .filter(|cfg| !cfg.is_interactive_chat())
-        .fold(0u64, |acc, cfg| acc.saturating_add(cfg.token_budget))
-}
 
-fn estimate_costs_with_policy(
+    /// Each cap the projection exceeds, e.g. "day tokens 120000 > 100000"
+    fn overruns(&self) -> Vec<String> {
+        let window = self.window.as_str();
+        let mut overruns = Vec::new();
+        if let Some(cap) = self.budget.tokens.filter(|cap| self.estimated_tokens > *cap) {
+            overruns.push(format!("{window} tokens {} > {cap}", self.estimated_tokens));
+        }
+        if let Some(cap) = self.budget.usd.filter(|cap| self.estimated_usd > *cap) {
+            overruns.push(format!("{window} USD {:.2} > {cap:.2}", self.estimated_usd));
+        }
+        if let Some(cap) = self
+            .budget
+            .nature_cost
+            .filter(|cap| self.estimated_nature_cost > *cap)
+        {
+            overruns.push(format!(
+                "{window} Nature Cost {:.2} > {cap:.2}",
+                self.estimated_nature_cost
+            ));
+        }
+        overruns
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCostEstimates {
+    pub per_run: CostProjection,
+    pub cumulative: CostProjection,
+    /// The policy's day, week and month budgets, in its order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<WindowProjection>,
+}
+
+impl RunCostEstimates {
+    fn exceeds_any(&self) -> bool {
+        self.per_run.exceeds_any()
+            || self.cumulative.exceeds_any()
+            || self.windows.iter().any(|window| window.exceeds)
+    }
+
+    /// Each blocking overrun: tokens and USD, and every model, provider or
+    /// window cap, e.g. "per-run tokens 1200 > 1000"
+    pub(crate) fn blocking_overruns(&self) -> Vec<String> {
+        let (per_run, cumulative) = (&self.per_run, &self.cumulative);
+        let mut issues = Vec::new();
+        if per_run.exceeds_tokens {
+            issues.push(format!(
+                "per-run tokens {} > {}",
+                per_run.estimated_tokens, per_run.budget_tokens
+            ));
+        }
+        if per_run.exceeds_usd {
+            issues.push(format!(
+                "per-run USD {:.2} > {:.2}",
+                per_run.estimated_usd, per_run.budget_usd
+            ));
+        }
+        if cumulative.exceeds_tokens {
+            issues.push(format!(
+                "cumulative tokens {} > {}",
+                cumulative.estimated_tokens, cumulative.budget_tokens
+            ));
+        }
+        if cumulative.exceeds_usd {
+            issues.push(format!(
+                "cumulative USD {:.2} > {:.2}",
+                cumulative.estimated_usd, cumulative.budget_usd
+            ));
+        }
+        for overrun in &per_run.sub_budget_overruns {
+            issues.push(format!("per-run {overrun}"));
+        }
+        for overrun in &cumulative.sub_budget_overruns {
+            issues.push(format!("cumulative {overrun}"));
+        }
+        for window in &self.windows {
+            issues.extend(window.overruns());
+        }
+        issues
+    }
+
+    /// The projection over its Nature Cost budget, cumulative first; Nature
+    /// Cost only warns unless the policy says otherwise
+    pub(crate) fn nature_cost_overrun(&self) -> Option<(&'static str, &CostProjection)> {
+        if self.cumulative.exceeds_nature_cost {
+            Some(("cumulative", &self.cumulative))
+        } else if self.per_run.exceeds_nature_cost {
+            Some(("per-run", &self.per_run))
+        } else {
+            None
+        }
+    }
+}
+
+/// Token budgets of steps still to run, priced with each step's model
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ProjectedUsage {
+    pub(crate) tokens: u64,
+    pub(crate) usd: f64,
+    pub(crate) nature_cost: f64,
+}
+
+fn project_step_usage(configs: &[RunStep]) -> ProjectedUsage {
+    configs
+        .iter()
+        .filter(|cfg| !cfg.is_interactive_chat())
+        .fold(ProjectedUsage::default(), |acc, cfg| {
+            let cost = governance::CheckpointCost::priced(
+                cfg.token_budget,
+                step_pricing_model(cfg).as_deref(),
+            );
+            ProjectedUsage {
+                tokens: acc.tokens.saturating_add(cfg.token_budget),
+                usd: acc.usd + cost.usd,
+                nature_cost: acc.nature_cost + cost.nature_cost,
+            }
+        })
+}
+
+/// Usage per pricing model, for the policy's model and provider budgets
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UsageByModel {
+    /// This execution's, recorded and still to run
+    pub(crate) run: BTreeMap<String, governance::ModelUsage>,
+    /// The project's other executions under the run's policy version
+    pub(crate) project: BTreeMap<String, governance::ModelUsage>,
+}
+
+pub(crate) fn add_model_usage(
+    usage: &mut BTreeMap<String, governance::ModelUsage>,
+    model: Option<&str>,
+    used: governance::ModelUsage,
+) {
+    if let Some(model) = model {
+        usage.entry(model.to_string()).or_default().add(used);
+    }
+}
+
+/// Token budgets of steps still to run, per the model they are priced with
+fn project_step_usage_by_model(configs: &[RunStep]) -> BTreeMap<String, governance::ModelUsage> {
+    let mut usage = BTreeMap::new();
+    for cfg in configs.iter().filter(|cfg| !cfg.is_interactive_chat()) {
+        let model = step_pricing_model(cfg);
+        add_model_usage(
+            &mut usage,
+            model.as_deref(),
+            governance::ModelUsage::priced(cfg.token_budget, model.as_deref()),
+        );
+    }
+    usage
+}
+
+/// Usage of the project's top-level checkpoints under a policy version, per
+/// pricing model, leaving out one execution; empty unless the policy sets
+/// model or provider budgets
+fn load_project_usage_by_model(
+    conn: &Connection,
+    policy: &store::policies::Policy,
+    project_id: &str,
+    policy_version: Option<i64>,
+    excluding_execution: Option<&str>,
+) -> anyhow::Result<BTreeMap<String, governance::ModelUsage>> {
+    let mut usage = BTreeMap::new();
+    if !policy.has_sub_budgets() {
+        return Ok(usage);
+    }
+    let mut stmt = conn.prepare(
+        "SELECT s.model, s.config_json, SUM(c.usage_tokens),
+                SUM(c.usd_cost), SUM(CASE WHEN c.usd_cost IS NULL THEN c.usage_tokens ELSE 0 END),
+                SUM(c.nature_cost), SUM(CASE WHEN c.nature_cost IS NULL THEN c.usage_tokens ELSE 0 END)
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         JOIN run_steps s ON s.id = c.checkpoint_config_id
+         WHERE r.project_id = ?1 AND COALESCE(r.policy_version, 0) = ?2
+           AND c.parent_checkpoint_id IS NULL AND c.usage_tokens > 0
+           AND (?3 IS NULL OR c.run_execution_id IS NOT ?3)
+         GROUP BY c.checkpoint_config_id",
+    )?;
+    let rows = stmt.query_map(
+        params![project_id, policy_version.unwrap_or(0).max(0), excluding_execution],
+        |row| {
+            let model: Option<String> = row.get(0)?;
+            let config_json: Option<String> = row.get(1)?;
+            Ok((
+                pricing_model(config_json.as_deref(), model.as_deref()),
+                row.get::<_, i64>(2)?.max(0) as u64,
+                row.get::<_, Option<f64>>(3)?,
+                row.get::<_, i64>(4)?.max(0) as u64,
+                row.get::<_, Option<f64>>(5)?,
+                row.get::<_, i64>(6)?.max(0) as u64,
+            ))
+        },
+    )?;
+    for row in rows {
+        let (model, tokens, usd, unpriced_usd, nature_cost, unpriced_nature) = row?;
+        let model = model.as_deref();
+        add_model_usage(
+            &mut usage,
+            model,
+            governance::ModelUsage {
+                tokens,
+                // Checkpoints recorded before costs were stored are priced now
+                usd: usd.unwrap_or(0.0) + governance::estimate_usd_cost(unpriced_usd, model),
+                nature_cost: nature_cost.unwrap_or(0.0)
+                    + governance::estimate_nature_cost(unpriced_nature, model),
+            },
+        );
+    }
+    Ok(usage)
+}
+
+/// Model a step's token usage is priced with; typed steps name it in their config
+fn step_pricing_model(config: &RunStep) -> Option<String> {
+    pricing_model(config.config_json.as_deref(), config.model.as_deref())
+}
+
+pub(crate) fn pricing_model(config_json: Option<&str>, model: Option<&str>) -> Option<String> {
+    match config_json.map(serde_json::from_str::<StepConfig>) {
+        Some(Ok(step_config)) => step_config_references(&step_config).0,
+        _ => model.map(str::to_string),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn estimate_costs_with_policy(
     policy: &store::policies::Policy,
     tokens_consumed_so_far: u64,
-    projected_tokens_remaining: u64,
+    projected_remaining: ProjectedUsage,
     run_usage_usd: f64,
     run_usage_nature_cost: f64,
     ledger_tokens: u64,
     ledger_usd: f64,
     ledger_nature_cost: f64,
+    usage_by_model: &UsageByModel,
+    window_usage: &[WindowUsage],
 ) -> RunCostEstimates {
     fn build_cost_projection(
         policy: &store::policies::Policy,
         estimated_tokens: u64,
         estimated_usd: f64,
         estimated_nature_cost: f64,
+        usage_by_model: &BTreeMap<String, governance::ModelUsage>,
     ) -> CostProjection {
         CostProjection {
             estimated_tokens,
@@ -402,25 +920,28 @@ fn estimate_costs_with_policy(
             exceeds_tokens: estimated_tokens > policy.budget_tokens,
             exceeds_usd: estimated_usd > policy.budget_usd,
             exceeds_nature_cost: estimated_nature_cost > policy.budget_nature_cost,
+            sub_budget_overruns: governance::sub_budget_overruns(policy, usage_by_model),
         }
     }
 
-    let projected_run_tokens = tokens_consumed_so_far.saturating_add(projected_tokens_remaining);
-    let estimated_future_usd = governance::estimate_usd_cost(projected_tokens_remaining, None);
-    let estimated_future_nature = governance::estimate_nature_cost(projected_tokens_remaining, None);
-
-    let per_run_estimated_usd = run_usage_usd + estimated_future_usd;
-    let per_run_estimated_nature = run_usage_nature_cost + estimated_future_nature;
+    let projected_run_tokens = tokens_consumed_so_far.saturating_add(projected_remaining.tokens);
+    let per_run_estimated_usd = run_usage_usd + projected_remaining.usd;
+    let per_run_estimated_nature = run_usage_nature_cost + projected_remaining.nature_cost;
     let per_run_projection = build_cost_projection(
         policy,
         projected_run_tokens,
         per_run_estimated_usd,
         per_run_estimated_nature,
+        &usage_by_model.run,
     );
 
+    let mut cumulative_by_model = usage_by_model.project.clone();
+    for (model, used) in &usage_by_model.run {
+        add_model_usage(&mut cumulative_by_model, Some(model), *used);
+    }
     let cumulative_tokens = ledger_tokens
         .saturating_add(tokens_consumed_so_far)
-        .saturating_add(projected_tokens_remaining);
+        .saturating_add(projected_remaining.tokens);
     let cumulative_estimated_usd = ledger_usd + per_run_estimated_usd;
     let cumulative_estimated_nature = ledger_nature_cost + per_run_estimated_nature;
     let cumulative_projection = build_cost_projection(
@@ -428,14 +949,46 @@ fn estimate_costs_with_policy(
         cumulative_tokens,
         cumulative_estimated_usd,
         cumulative_estimated_nature,
+        &cumulative_by_model,
     );
 
+    // Window usage already holds what this execution recorded, so only what
+    // it is still projected to use is added to it
+    let windows = policy
+        .window_budgets
+        .iter()
+        .filter_map(|budget| {
+            let used = window_usage.iter().find(|used| used.window == budget.window)?;
+            Some(WindowProjection::new(budget, used, projected_remaining))
+        })
+        .collect();
+
     RunCostEstimates {
         per_run: per_run_projection,
         cumulative: cumulative_projection,
+        windows,
     }
 }
 
+/// The project's usage in the current window of each window budget
+fn load_window_usage(
+    conn: &Connection,
+    policy: &store::policies::Policy,
+    project_id: &str,
+) -> anyhow::Result<Vec<WindowUsage>> {
+    let now = Utc::now();
+    let mut windows: Vec<BudgetWindow> =
+        policy.window_budgets.iter().map(|budget| budget.window).collect();
+    windows.sort();
+    windows.dedup();
+    windows
+        .into_iter()
+        .map(|window| {
+            Ok(store::project_usage_ledgers::get_window(conn, project_id, window, now)?)
+        })
+        .collect()
+}
+
 #[cfg(feature = "interactive")]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -448,6 +1001,281 @@ pub struct SubmitTurnOutcome {
 
 pub trait LlmClient {
     fn stream_generate(&self, model: &str, prompt: &str) -> anyhow::Result<LlmGeneration>;
+
+    /// Generate with fixed sampling options, returning the options the
+    /// provider actually applied (`None` when it cannot honor them)
+    fn generate_with_sampling(
+        &self,
+        model: &str,
+        prompt: &str,
+        _sampling: &SamplingOptions,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        Ok((self.stream_generate(model, prompt)?, None))
+    }
+
+    /// Generate, stopping mid-stream once prompt plus completion tokens
+    /// exceed `token_limit`; a stopped generation reports
+    /// `FinishReason::Budget`. Clients that cannot stop early generate in full
+    /// and leave the budget to the check after generation.
+    fn generate_bounded(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        _token_limit: u64,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        match sampling {
+            Some(sampling) => self.generate_with_sampling(model, prompt, sampling),
+            None => Ok((self.stream_generate(model, prompt)?, None)),
+        }
+    }
+
+    /// Embed each input with an embedding model
+    fn embed(&self, model: &str, _inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+        Err(anyhow!("embeddings are not available for model {model}"))
+    }
+
+    /// Whether the most recent generation was served from a response cache
+    fn last_generation_cached(&self) -> bool {
+        false
+    }
+}
+
+/// Whether a generation was cut off by the token budget
+fn stopped_for_budget(generation: &LlmGeneration) -> bool {
+    generation.metadata.as_ref().is_some_and(|metadata| {
+        metadata.finish_reason == crate::model_adapters::FinishReason::Budget
+    })
+}
+
+/// A generation abandoned mid-stream because the step's budget ran out
+pub(crate) struct BudgetStop {
+    pub(crate) usage_tokens: u64,
+    pub(crate) partial_output_sha256: String,
+}
+
+/// Bounds every model call of one step by what remains of the step's token
+/// budget, so a runaway generation is stopped while it streams rather than
+/// reported after it completes
+pub(crate) struct StepBudgetClient<'a> {
+    inner: &'a dyn LlmClient,
+    budget: u64,
+    used: std::cell::Cell<u64>,
+    stop: std::cell::RefCell<Option<BudgetStop>>,
+}
+
+impl<'a> StepBudgetClient<'a> {
+    pub(crate) fn new(inner: &'a dyn LlmClient, budget: u64) -> Self {
+        Self {
+            inner,
+            budget,
+            used: std::cell::Cell::new(0),
+            stop: std::cell::RefCell::new(None),
+        }
+    }
+
+    /// The generation that ran out of budget, if one did
+    pub(crate) fn budget_stop(&self) -> Option<BudgetStop> {
+        self.stop.borrow_mut().take()
+    }
+
+    fn remaining(&self) -> u64 {
+        self.budget.saturating_sub(self.used.get())
+    }
+}
+
+impl LlmClient for StepBudgetClient<'_> {
+    fn stream_generate(&self, model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
+        Ok(self.generate_bounded(model, prompt, None, u64::MAX)?.0)
+    }
+
+    fn generate_with_sampling(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        self.generate_bounded(model, prompt, Some(sampling), u64::MAX)
+    }
+
+    fn generate_bounded(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        let limit = token_limit.min(self.remaining());
+        let (generation, applied) = self.inner.generate_bounded(model, prompt, sampling, limit)?;
+        let used = self.used.get().saturating_add(generation.usage.total());
+        self.used.set(used);
+        if stopped_for_budget(&generation) {
+            self.stop.borrow_mut().get_or_insert(BudgetStop {
+                usage_tokens: used,
+                partial_output_sha256: provenance::sha256_hex(generation.response.as_bytes()),
+            });
+        }
+        Ok((generation, applied))
+    }
+
+    fn embed(&self, model: &str, inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+        let embedded = self.inner.embed(model, inputs)?;
+        self.used
+            .set(self.used.get().saturating_add(embedded.prompt_tokens));
+        Ok(embedded)
+    }
+
+    fn last_generation_cached(&self) -> bool {
+        self.inner.last_generation_cached()
+    }
+}
+
+/// Step a cached generation was made for: responses are only shared within
+/// a project and between steps validated against the same output schema
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CacheScope {
+    pub(crate) project_id: String,
+    pub(crate) output_schema_sha256: Option<String>,
+}
+
+/// Cache key of a generation: model, sampling parameters, prompt, the token
+/// limit it was generated under and the step it was made for
+pub(crate) fn response_cache_key(
+    model: &str,
+    prompt: &str,
+    sampling: Option<&SamplingOptions>,
+    token_limit: Option<u64>,
+    scope: &CacheScope,
+) -> String {
+    let keyed = serde_json::json!({
+        "model": model,
+        "parameters": sampling,
+        "prompt": prompt,
+        "tokenLimit": token_limit,
+        "projectId": scope.project_id,
+        "outputSchemaSha256": scope.output_schema_sha256,
+    });
+    provenance::sha256_hex(&provenance::canonical_json(&keyed))
+}
+
+/// Serves repeated model+parameters+prompt requests of one step from the
+/// project's response cache. Hits report zero usage, since nothing was spent
+/// on them.
+pub(crate) struct CachingLlmClient<'a> {
+    conn: &'a Connection,
+    inner: &'a dyn LlmClient,
+    scope: CacheScope,
+    last_hit: std::cell::Cell<bool>,
+}
+
+impl<'a> CachingLlmClient<'a> {
+    pub(crate) fn new(conn: &'a Connection, inner: &'a dyn LlmClient, scope: CacheScope) -> Self {
+        Self {
+            conn,
+            inner,
+            scope,
+            last_hit: std::cell::Cell::new(false),
+        }
+    }
+
+    fn lookup(&self, key: &str) -> anyhow::Result<Option<LlmGeneration>> {
+        let cached = store::response_cache::get(self.conn, key)?;
+        self.last_hit.set(cached.is_some());
+        // A hit reports no provider response: nothing was requested from one
+        Ok(cached.map(|cached| LlmGeneration {
+            response: cached.response,
+            usage: TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+            },
+            metadata: None,
+        }))
+    }
+
+    fn remember(&self, key: &str, model: &str, generation: &LlmGeneration) -> anyhow::Result<()> {
+        store::response_cache::put(
+            self.conn,
+            key,
+            model,
+            &store::response_cache::CachedResponse {
+                response: generation.response.clone(),
+                prompt_tokens: generation.usage.prompt_tokens,
+                completion_tokens: generation.usage.completion_tokens,
+            },
+        )?;
+        Ok(())
+    }
+}
+
+impl LlmClient for CachingLlmClient<'_> {
+    fn stream_generate(&self, model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
+        let key = response_cache_key(model, prompt, None, None, &self.scope);
+        if let Some(generation) = self.lookup(&key)? {
+            return Ok(generation);
+        }
+        let generation = self.inner.stream_generate(model, prompt)?;
+        self.remember(&key, model, &generation)?;
+        Ok(generation)
+    }
+
+    fn generate_with_sampling(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        let key = response_cache_key(model, prompt, Some(sampling), None, &self.scope);
+        if let Some(generation) = self.lookup(&key)? {
+            return Ok((generation, Some(*sampling)));
+        }
+        let (generation, applied) = self.inner.generate_with_sampling(model, prompt, sampling)?;
+        // Only generations the provider actually seeded answer seeded requests
+        if applied.is_some() {
+            self.remember(&key, model, &generation)?;
+        }
+        Ok((generation, applied))
+    }
+
+    fn generate_bounded(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        let key = response_cache_key(model, prompt, sampling, Some(token_limit), &self.scope);
+        if let Some(generation) = self.lookup(&key)? {
+            return Ok((generation, sampling.copied()));
+        }
+        let (generation, applied) = self
+            .inner
+            .generate_bounded(model, prompt, sampling, token_limit)?;
+        // Partial generations and unseeded answers to seeded requests are not reused
+        if !stopped_for_budget(&generation) && (sampling.is_none() || applied.is_some()) {
+            self.remember(&key, model, &generation)?;
+        }
+        Ok((generation, applied))
+    }
+
+    fn embed(&self, model: &str, inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+        self.inner.embed(model, inputs)
+    }
+
+    fn last_generation_cached(&self) -> bool {
+        self.last_hit.get()
+    }
+}
+
+fn convert_adapter_generation(generation: crate::model_adapters::ProviderResponse) -> LlmGeneration {
+    let metadata = generation.metadata();
+    LlmGeneration {
+        response: generation.text,
+        usage: TokenUsage {
+            prompt_tokens: generation.usage.prompt_tokens,
+            completion_tokens: generation.usage.completion_tokens,
+        },
+        metadata: Some(metadata),
+    }
 }
 
 /// Modern LLM client using the model dispatcher (supports all providers)
@@ -471,14 +1299,40 @@ impl LlmClient for DispatchingLlmClient {
         // Dispatch to appropriate adapter
         let generation = self.dispatcher.generate(model, prompt)?;
 
-        // Convert from model_adapters::LlmGeneration to orchestrator::LlmGeneration
-        Ok(LlmGeneration {
-            response: generation.response,
-            usage: TokenUsage {
-                prompt_tokens: generation.usage.prompt_tokens,
-                completion_tokens: generation.usage.completion_tokens,
-            },
-        })
+        // Convert from the normalized provider response to orchestrator::LlmGeneration
+        Ok(convert_adapter_generation(generation))
+    }
+
+    fn generate_with_sampling(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        self.dispatcher.check_api_key_configured(model)?;
+        let (generation, applied) = self
+            .dispatcher
+            .generate_with_sampling(model, prompt, sampling)?;
+        Ok((convert_adapter_generation(generation), applied))
+    }
+
+    fn generate_bounded(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        self.dispatcher.check_api_key_configured(model)?;
+        let (generation, applied) = self
+            .dispatcher
+            .generate_bounded(model, prompt, sampling, token_limit)?;
+        Ok((convert_adapter_generation(generation), applied))
+    }
+
+    fn embed(&self, model: &str, inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+        self.dispatcher.check_api_key_configured(model)?;
+        self.dispatcher.embed(model, inputs)
     }
 }
 
@@ -520,7 +1374,36 @@ impl DefaultOllamaClient {
 
 impl LlmClient for DefaultOllamaClient {
     fn stream_generate(&self, model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
-        perform_ollama_stream(model, prompt)
+        perform_ollama_stream(model, prompt, None, None)
+    }
+
+    fn generate_with_sampling(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: &SamplingOptions,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        let generation = perform_ollama_stream(model, prompt, Some(sampling), None)?;
+        Ok((generation, Some(*sampling)))
+    }
+
+    fn generate_bounded(
+        &self,
+        model: &str,
+        prompt: &str,
+        sampling: Option<&SamplingOptions>,
+        token_limit: u64,
+    ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+        let generation = perform_ollama_stream(model, prompt, sampling, Some(token_limit))?;
+        Ok((generation, sampling.copied()))
+    }
+
+    fn embed(&self, model: &str, inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+        crate::model_adapters::ModelAdapter::embed(
+            &crate::model_adapters::OllamaAdapter::new(),
+            model,
+            inputs,
+        )
     }
 }
 
@@ -529,6 +1412,20 @@ pub fn replay_llm_generation(model: &str, prompt: &str) -> anyhow::Result<LlmGen
     client.stream_generate(model, prompt)
 }
 
+/// Replays a generation with the sampling options recorded on the original
+/// checkpoint, so seeded exact-mode outputs reproduce
+pub fn replay_llm_generation_with_sampling(
+    model: &str,
+    prompt: &str,
+    sampling: Option<&SamplingOptions>,
+) -> anyhow::Result<LlmGeneration> {
+    let client = DispatchingLlmClient::new();
+    match sampling {
+        Some(sampling) => Ok(client.generate_with_sampling(model, prompt, sampling)?.0),
+        None => client.stream_generate(model, prompt),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaTagsResponse {
     models: Vec<OllamaModelEntry>,
@@ -684,13 +1581,36 @@ pub fn fetch_ollama_models_list() -> anyhow::Result<Vec<String>> {
     fetch_ollama_models()
 }
 
-pub(crate) fn perform_ollama_stream(model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
-    let body = serde_json::json!({
+fn ollama_generate_body(model: &str, prompt: &str, sampling: Option<&SamplingOptions>) -> String {
+    let mut body = serde_json::json!({
         "model": model,
         "prompt": prompt,
         "stream": true,
-    })
-    .to_string();
+    });
+    if let Some(sampling) = sampling {
+        body["options"] = serde_json::json!({
+            "seed": sampling.seed,
+            "temperature": sampling.temperature,
+        });
+    }
+    body.to_string()
+}
+
+/// Rough token count of text not yet tokenized by the provider
+pub(crate) fn estimate_tokens(text: &str) -> u64 {
+    (text.len() as u64).div_ceil(4)
+}
+
+/// Streams a generation from Ollama. With a `token_limit`, every streamed
+/// chunk counts as one completion token and the stream is abandoned as soon
+/// as the estimated prompt plus streamed tokens exceed the limit.
+pub(crate) fn perform_ollama_stream(
+    model: &str,
+    prompt: &str,
+    sampling: Option<&SamplingOptions>,
+    token_limit: Option<u64>,
+) -> anyhow::Result<LlmGeneration> {
+    let body = ollama_generate_body(model, prompt, sampling);
 
     let request = format!(
         "POST /api/generate HTTP/1.1\r\nHost: {OLLAMA_HOST}\r\nContent-Type: application/json\r\nAccept: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
@@ -735,6 +1655,10 @@ pub(crate) fn perform_ollama_stream(model: &str, prompt: &str) -> anyhow::Result
     let mut response_text = String::new();
     let mut prompt_tokens = 0_u64;
     let mut completion_tokens = 0_u64;
+    let mut done_reason = None;
+    let prompt_estimate = estimate_tokens(prompt);
+    let mut streamed_tokens = 0_u64;
+    let mut stopped_for_budget = false;
 
     loop {
         let mut size_line = String::new();
@@ -754,20 +1678,47 @@ pub(crate) fn perform_ollama_stream(model: &str, prompt: &str) -> anyhow::Result
         let mut crlf = [0u8; 2];
         reader.read_exact(&mut crlf)?;
 
+        let streamed_before = response_text.len();
         process_stream_chunk(
             &chunk_data,
             &mut response_text,
             &mut prompt_tokens,
             &mut completion_tokens,
+            &mut done_reason,
         )?;
+        if response_text.len() > streamed_before {
+            streamed_tokens += 1;
+        }
+
+        if done_reason.is_none()
+            && token_limit.is_some_and(|limit| prompt_estimate + streamed_tokens > limit)
+        {
+            // Dropping the connection makes Ollama cancel the generation
+            stopped_for_budget = true;
+            prompt_tokens = prompt_estimate;
+            completion_tokens = streamed_tokens;
+            break;
+        }
     }
 
+    let finish_reason = if stopped_for_budget {
+        crate::model_adapters::FinishReason::Budget
+    } else {
+        crate::model_adapters::FinishReason::from_ollama(done_reason.as_deref())
+    };
     Ok(LlmGeneration {
         response: response_text,
         usage: TokenUsage {
             prompt_tokens,
             completion_tokens,
         },
+        metadata: Some(ResponseMetadata {
+            provider: "ollama".to_string(),
+            finish_reason,
+            tool_calls: Vec::new(),
+            provider_request_id: None,
+            response_headers_sha256: None,
+        }),
     })
 }
 
@@ -776,6 +1727,7 @@ fn process_stream_chunk(
     response_text: &mut String,
     prompt_tokens: &mut u64,
     completion_tokens: &mut u64,
+    done_reason: &mut Option<String>,
 ) -> anyhow::Result<()> {
     if bytes.is_empty() {
         return Ok(());
@@ -805,6 +1757,9 @@ fn process_stream_chunk(
         if let Some(count) = value.get("eval_count").and_then(|v| v.as_u64()) {
             *completion_tokens = count;
         }
+        if let Some(reason) = value.get("done_reason").and_then(|v| v.as_str()) {
+            *done_reason = Some(reason.to_string());
+        }
     }
 
     Ok(())
@@ -823,8 +1778,8 @@ pub fn create_run(
 ) -> anyhow::Result<String> {
     let run_epsilon = match (proof_mode.is_concordant(), epsilon) {
         (true, Some(value)) => {
-            if !value.is_finite() || value < 0.0 {
-                return Err(anyhow!("epsilon must be a finite, non-negative value"));
+            if !semantic::epsilon_in_bounds(value) {
+                return Err(anyhow!("epsilon must be between 0 and 1"));
             }
             Some(value)
         }
@@ -832,8 +1787,8 @@ pub fn create_run(
             return Err(anyhow!("concordant runs require an epsilon"));
         }
         (false, Some(value)) => {
-            if !value.is_finite() || value < 0.0 {
-                return Err(anyhow!("epsilon must be a finite, non-negative value"));
+            if !semantic::epsilon_in_bounds(value) {
+                return Err(anyhow!("epsilon must be between 0 and 1"));
             }
             Some(value)
         }
@@ -846,19 +1801,19 @@ pub fn create_run(
                 .epsilon
                 .or(run_epsilon)
                 .ok_or_else(|| anyhow!("concordant steps require an epsilon"))?;
-            if !epsilon.is_finite() || epsilon < 0.0 {
-                return Err(anyhow!("epsilon must be a finite, non-negative value"));
+            if !semantic::epsilon_in_bounds(epsilon) {
+                return Err(anyhow!("epsilon must be between 0 and 1"));
             }
             template.epsilon = Some(epsilon);
         } else if let Some(value) = template.epsilon {
-            if !value.is_finite() || value < 0.0 {
-                return Err(anyhow!("epsilon must be a finite, non-negative value"));
+            if !semantic::epsilon_in_bounds(value) {
+                return Err(anyhow!("epsilon must be between 0 and 1"));
             }
         }
     }
 
     let mut conn = pool.get()?;
-    ensure_project_signing_key(&conn, project_id)?;
+    ensure_project_signing_key(&conn, project_id, None)?;
 
     let run_id = Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
@@ -976,6 +1931,13 @@ pub fn delete_run(pool: &DbPool, run_id: &str) -> anyhow::Result<()> {
 
     tx.execute("DELETE FROM checkpoints WHERE run_id = ?1", params![run_id])?;
 
+    tx.execute(
+        "DELETE FROM run_trigger_events WHERE trigger_id IN (SELECT id FROM run_triggers WHERE run_id = ?1)",
+        params![run_id],
+    )?;
+
+    tx.execute("DELETE FROM run_triggers WHERE run_id = ?1", params![run_id])?;
+
     tx.execute(
         "DELETE FROM run_executions WHERE run_id = ?1",
         params![run_id],
@@ -994,7 +1956,7 @@ pub fn delete_run(pool: &DbPool, run_id: &str) -> anyhow::Result<()> {
 
 fn persist_checkpoint(
     conn: &Connection,
-    signing_key: &SigningKey,
+    signing_key: &ProjectSigner,
     params: &CheckpointInsert<'_>,
 ) -> anyhow::Result<PersistedCheckpoint> {
     let checkpoint_body = CheckpointBody {
@@ -1007,17 +1969,23 @@ fn persist_checkpoint(
         usage_tokens: params.usage_tokens,
         prompt_tokens: params.prompt_tokens,
         completion_tokens: params.completion_tokens,
+        cache_hit: params.cache_hit,
+        response_metadata: params.response_metadata,
+        run_inputs_sha256: params.run_inputs_sha256,
+        output_schema_sha256: params.output_schema_sha256,
     };
 
-    let body_json = serde_json::to_value(&checkpoint_body)?;
-    let canonical = provenance::canonical_json(&body_json);
-    let curr_chain = provenance::sha256_hex(&[params.prev_chain.as_bytes(), &canonical].concat());
-    let signature = provenance::sign_bytes(signing_key, curr_chain.as_bytes());
+    let curr_chain = checkpoint_body.chain_hash(params.prev_chain)?;
+    let signature = signing_key.sign(curr_chain.as_bytes())?;
     let checkpoint_id = Uuid::new_v4().to_string();
     let incident_json = params.incident.map(|value| value.to_string());
+    let response_metadata_json = params
+        .response_metadata
+        .map(serde_json::to_string)
+        .transpose()?;
 
     conn.execute(
-        "INSERT INTO checkpoints (id, run_id, run_execution_id, checkpoint_config_id, parent_checkpoint_id, turn_index, kind, incident_json, timestamp, inputs_sha256, outputs_sha256, prev_chain, curr_chain, signature, usage_tokens, semantic_digest, prompt_tokens, completion_tokens) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17,?18)",
+        "INSERT INTO checkpoints (id, run_id, run_execution_id, checkpoint_config_id, parent_checkpoint_id, turn_index, kind, incident_json, timestamp, inputs_sha256, outputs_sha256, prev_chain, curr_chain, signature, usage_tokens, semantic_digest, prompt_tokens, completion_tokens, sampling_json, cache_hit, response_metadata_json, run_inputs_sha256, usd_cost, nature_cost, g_co2e, output_schema_sha256) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17,?18,?19,?20,?21,?22,?23,?24,?25,?26)",
         params![
             &checkpoint_id,
             params.run_id,
@@ -1037,6 +2005,14 @@ fn persist_checkpoint(
             params.semantic_digest,
             (params.prompt_tokens as i64),
             (params.completion_tokens as i64),
+            params.sampling_json,
+            params.cache_hit,
+            response_metadata_json.as_deref(),
+            params.run_inputs_sha256,
+            params.cost.map(|cost| cost.usd),
+            params.cost.map(|cost| cost.nature_cost),
+            params.cost.and_then(|cost| cost.g_co2e),
+            params.output_schema_sha256,
         ],
     )?;
 
@@ -1083,6 +2059,52 @@ fn persist_checkpoint(
     })
 }
 
+/// Record `incident` as an Incident checkpoint of `execution` chained after
+/// `prev_chain`; the execution's first checkpoint also commits to its run inputs
+#[allow(clippy::too_many_arguments)]
+fn persist_incident_checkpoint(
+    conn: &Connection,
+    signing_key: &ProjectSigner,
+    execution: &RunExecutionRecord,
+    checkpoint_config_id: Option<&str>,
+    timestamp: &str,
+    prev_chain: &str,
+    run_inputs_sha256: Option<&str>,
+    incident: &governance::Incident,
+) -> anyhow::Result<PersistedCheckpoint> {
+    let incident_value = serde_json::to_value(incident)?;
+    persist_checkpoint(
+        conn,
+        signing_key,
+        &CheckpointInsert {
+            run_id: &execution.run_id,
+            run_execution_id: &execution.id,
+            checkpoint_config_id,
+            parent_checkpoint_id: None,
+            turn_index: None,
+            kind: "Incident",
+            timestamp,
+            incident: Some(&incident_value),
+            inputs_sha256: None,
+            outputs_sha256: None,
+            prev_chain,
+            usage_tokens: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            semantic_digest: None,
+            prompt_payload: None,
+            output_payload: None,
+            sampling_json: None,
+            cache_hit: false,
+            response_metadata: None,
+            run_inputs_sha256: run_inputs_sha256.filter(|_| prev_chain.is_empty()),
+            output_schema_sha256: None,
+            cost: None,
+            message: None,
+        },
+    )
+}
+
 #[cfg(feature = "interactive")]
 fn sum_checkpoint_token_usage(
     conn: &Connection,
@@ -1153,16 +2175,27 @@ pub fn estimate_run_cost(conn: &Connection, run_id: &str) -> anyhow::Result<RunC
         &stored_run.project_id,
         stored_run.policy_version,
     )?;
-    let projected_tokens_remaining = sum_token_budgets(&stored_run.steps);
+    let usage_by_model = UsageByModel {
+        run: project_step_usage_by_model(&stored_run.steps),
+        project: load_project_usage_by_model(
+            conn,
+            &policy,
+            &stored_run.project_id,
+            stored_run.policy_version,
+            None,
+        )?,
+    };
     Ok(estimate_costs_with_policy(
         &policy,
         0,
-        projected_tokens_remaining,
+        project_step_usage(&stored_run.steps),
         0.0,
         0.0,
         ledger_snapshot.total_tokens,
         ledger_snapshot.total_usd,
         ledger_snapshot.total_nature_cost,
+        &usage_by_model,
+        &load_window_usage(conn, &policy, &stored_run.project_id)?,
     ))
 }
 
@@ -1234,9 +2267,10 @@ pub fn load_stored_run(conn: &Connection, run_id: &str) -> anyhow::Result<Stored
         String,
         String,
         Option<i64>,
+        String,
     )> = conn
         .query_row(
-            "SELECT project_id, name, seed, epsilon, token_budget, default_model, proof_mode, policy_version FROM runs WHERE id = ?1",
+            "SELECT project_id, name, seed, epsilon, token_budget, default_model, proof_mode, policy_version, priority FROM runs WHERE id = ?1",
             params![run_id],
             |row| Ok((
                 row.get(0)?,
@@ -1247,6 +2281,7 @@ pub fn load_stored_run(conn: &Connection, run_id: &str) -> anyhow::Result<Stored
                 row.get(5)?,
                 row.get(6)?,
                 row.get(7)?,
+                row.get(8)?,
             )),
         )
         .optional()?;
@@ -1260,7 +2295,9 @@ pub fn load_stored_run(conn: &Connection, run_id: &str) -> anyhow::Result<Stored
         default_model,
         proof_mode_raw,
         policy_version,
+        priority_raw,
     ) = row.ok_or_else(|| anyhow!(format!("run {run_id} not found")))?;
+    let priority = run_queue::RunPriority::try_from(priority_raw.as_str()).map_err(|err| anyhow!(err))?;
     let seed = seed_raw.max(0) as u64;
     let token_budget = token_budget_raw.max(0) as u64;
     let steps = load_run_steps(conn, run_id)?;
@@ -1278,31 +2315,89 @@ pub fn load_stored_run(conn: &Connection, run_id: &str) -> anyhow::Result<Stored
         policy_version,
         proof_mode: Some(proof_mode),
         epsilon,
+        priority,
         steps,
     })
 }
 
-fn insert_run_execution(conn: &Connection, run_id: &str) -> anyhow::Result<RunExecutionRecord> {
+fn insert_run_execution(
+    conn: &Connection,
+    run_id: &str,
+    environment: RunEnvironment,
+) -> anyhow::Result<RunExecutionRecord> {
     let execution_id = Uuid::new_v4().to_string();
     let created_at = Utc::now().to_rfc3339();
     conn.execute(
-        "INSERT INTO run_executions (id, run_id, created_at) VALUES (?1, ?2, ?3)",
-        params![&execution_id, run_id, &created_at],
+        "INSERT INTO run_executions (id, run_id, created_at, environment_json) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            &execution_id,
+            run_id,
+            &created_at,
+            serde_json::to_string(&environment)?
+        ],
     )?;
 
     Ok(RunExecutionRecord {
         id: execution_id,
         run_id: run_id.to_string(),
         created_at,
+        preemptions: Vec::new(),
+        environment: Some(environment),
     })
 }
 
-pub fn list_run_executions(
+fn parse_environment(environment_json: Option<String>) -> Option<RunEnvironment> {
+    environment_json.and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn parse_preemptions(preemptions_json: Option<String>) -> Vec<run_queue::Preemption> {
+    preemptions_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Append a preemption to the execution record
+fn record_preemption(
+    conn: &Connection,
+    execution_id: &str,
+    preemption: run_queue::Preemption,
+) -> anyhow::Result<()> {
+    let existing: Option<String> = conn.query_row(
+        "SELECT preemptions_json FROM run_executions WHERE id = ?1",
+        params![execution_id],
+        |row| row.get(0),
+    )?;
+    let mut preemptions = parse_preemptions(existing);
+    preemptions.push(preemption);
+    conn.execute(
+        "UPDATE run_executions SET preemptions_json = ?1 WHERE id = ?2",
+        params![serde_json::to_string(&preemptions)?, execution_id],
+    )?;
+    Ok(())
+}
+
+pub fn set_run_priority(
+    pool: &DbPool,
+    run_id: &str,
+    priority: run_queue::RunPriority,
+) -> anyhow::Result<()> {
+    let conn = pool.get()?;
+    let affected = conn.execute(
+        "UPDATE runs SET priority = ?1 WHERE id = ?2",
+        params![priority.as_str(), run_id],
+    )?;
+    if affected == 0 {
+        return Err(anyhow!(format!("run {run_id} not found")));
+    }
+    Ok(())
+}
+
+pub fn list_run_executions(
     conn: &Connection,
     run_id: &str,
 ) -> anyhow::Result<Vec<RunExecutionRecord>> {
     let mut stmt = conn.prepare(
-        "SELECT id, run_id, created_at FROM run_executions WHERE run_id = ?1 ORDER BY datetime(created_at) DESC, id DESC",
+        "SELECT id, run_id, created_at, preemptions_json, environment_json FROM run_executions WHERE run_id = ?1 ORDER BY datetime(created_at) DESC, id DESC",
     )?;
 
     let rows = stmt.query_map(params![run_id], |row| {
@@ -1310,6 +2405,8 @@ pub fn list_run_executions(
             id: row.get(0)?,
             run_id: row.get(1)?,
             created_at: row.get(2)?,
+            preemptions: parse_preemptions(row.get(3)?),
+            environment: parse_environment(row.get(4)?),
         })
     })?;
 
@@ -1326,13 +2423,15 @@ pub fn load_latest_run_execution(
     run_id: &str,
 ) -> anyhow::Result<Option<RunExecutionRecord>> {
     conn.query_row(
-        "SELECT id, run_id, created_at FROM run_executions WHERE run_id = ?1 ORDER BY datetime(created_at) DESC, id DESC LIMIT 1",
+        "SELECT id, run_id, created_at, preemptions_json, environment_json FROM run_executions WHERE run_id = ?1 ORDER BY datetime(created_at) DESC, id DESC LIMIT 1",
         params![run_id],
         |row| {
             Ok(RunExecutionRecord {
                 id: row.get(0)?,
                 run_id: row.get(1)?,
                 created_at: row.get(2)?,
+                preemptions: parse_preemptions(row.get(3)?),
+                environment: parse_environment(row.get(4)?),
             })
         },
     )
@@ -1525,16 +2624,13 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
 
     let llm_prompt = build_interactive_prompt(config_prompt, &transcript, trimmed_prompt);
 
-    let signing_key = ensure_project_signing_key(&conn, &stored_run.project_id)?;
+    // A regeneration here is kept as a signing key event; the turn's
+    // checkpoints are chained per configuration, so no incident is inserted
+    let (signing_key, _) = ensure_project_signing_key(&conn, &stored_run.project_id, Some(run_id))?;
 
     // Enforce network policy for interactive checkpoints if model requires network
     let policy = store::policies::get(&conn, &stored_run.project_id)?;
-    let model_requires_network = crate::model_catalog::try_get_global_catalog()
-        .and_then(|catalog| catalog.get_model(config_model))
-        .map(|model_def| model_def.requires_network)
-        .unwrap_or(config_model != STUB_MODEL_ID); // Fallback: assume network needed unless stub
-
-    if model_requires_network {
+    if model_requires_network(config_model) {
         if let Err(network_incident) = governance::enforce_network_policy(&policy) {
             return Err(anyhow!(format!(
                 "Network access denied by project policy: {}",
@@ -1542,8 +2638,11 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
             )));
         }
     }
+    if let Err(provider_incident) = governance::enforce_provider_policy(&policy, config_model) {
+        return Err(anyhow!(provider_incident.details));
+    }
 
-    let LlmGeneration { response, usage } =
+    let LlmGeneration { response, usage, .. } =
         llm_client.stream_generate(config_model, &llm_prompt)?;
     let sanitized_llm_prompt = sanitize_payload(&llm_prompt);
     let sanitized_response = sanitize_payload(&response);
@@ -1598,6 +2697,8 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
     };
 
     let human_timestamp = Utc::now().to_rfc3339();
+    // Binds the message into the chain, so transcripts in CARs can be checked
+    let human_sha = provenance::sha256_hex(trimmed_prompt.as_bytes());
     let human_insert = CheckpointInsert {
         run_id,
         run_execution_id: run_execution_id.as_str(),
@@ -1607,7 +2708,7 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
         kind: "Step",
         timestamp: &human_timestamp,
         incident: None,
-        inputs_sha256: None,
+        inputs_sha256: Some(human_sha.as_str()),
         outputs_sha256: None,
         prev_chain: prev_chain_ref,
         usage_tokens: 0,
@@ -1616,6 +2717,12 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
         semantic_digest: None,
         prompt_payload: None,
         output_payload: None,
+        sampling_json: None,
+        cache_hit: false,
+        response_metadata: None,
+        run_inputs_sha256: None,
+        output_schema_sha256: None,
+        cost: None,
         message: Some(CheckpointMessageInput {
             role: "human",
             body: trimmed_prompt,
@@ -1654,6 +2761,15 @@ pub(crate) fn submit_interactive_checkpoint_turn_with_client(
         semantic_digest: None,
         prompt_payload: Some(sanitized_llm_prompt.as_str()),
         output_payload: Some(sanitized_response.as_str()),
+        sampling_json: None,
+        cache_hit: false,
+        response_metadata: None,
+        run_inputs_sha256: None,
+        output_schema_sha256: None,
+        cost: Some(governance::CheckpointCost::priced(
+            usage_tokens,
+            Some(config_model.as_str()),
+        )),
         message: Some(CheckpointMessageInput {
             role: "ai",
             body: &response,
@@ -1758,13 +2874,125 @@ pub fn start_run(pool: &DbPool, run_id: &str) -> anyhow::Result<RunExecutionReco
     start_run_with_client(pool, run_id, &client)
 }
 
+/// Admit a run with named inputs that prompts reference as `{{inputs.name}}`;
+/// see `admit_run`
+pub fn admit_run_with_inputs(
+    pool: &DbPool,
+    run_id: &str,
+    inputs: serde_json::Map<String, Value>,
+) -> anyhow::Result<AdmittedRun> {
+    let invocation = RunInvocation {
+        inputs,
+        ..RunInvocation::default()
+    };
+    admit_run(pool, run_id, invocation)
+}
+
+/// External inputs that parameterize a single execution (e.g. a trigger payload)
+#[derive(Debug, Clone, Default)]
+pub struct RunInvocation {
+    /// Values substituted into `{{name}}` placeholders of step prompts
+    pub parameters: serde_json::Map<String, Value>,
+    /// sha256 of the raw input that supplied the parameters, claimed in the CAR
+    pub input_sha256: Option<String>,
+    /// Run inputs supplied at start time, substituted into `{{inputs.name}}`
+    pub inputs: serde_json::Map<String, Value>,
+}
+
+impl RunInvocation {
+    /// Render parameters and run inputs into a step prompt or config
+    pub fn render(&self, text: &str) -> String {
+        let rendered = apply_run_parameters(text, &self.parameters);
+        if self.inputs.is_empty() {
+            return rendered;
+        }
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|(name, value)| (format!("{RUN_INPUT_PREFIX}{name}"), value.clone()))
+            .collect();
+        apply_run_parameters(&rendered, &inputs)
+    }
+
+    /// sha256 of the canonical JSON input map, when inputs were supplied
+    pub fn inputs_sha256(&self) -> Option<String> {
+        (!self.inputs.is_empty())
+            .then(|| provenance::sha256_hex(&provenance::canonical_json(&self.inputs)))
+    }
+}
+
+const RUN_INPUT_PREFIX: &str = "inputs.";
+
+/// Names of the run inputs a text references as `{{inputs.name}}`
+pub fn referenced_run_inputs(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        if let Some(name) = rest[..end].strip_prefix(RUN_INPUT_PREFIX) {
+            names.insert(name.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+/// Replace `{{name}}` placeholders with invocation parameters.
+/// Strings are inserted verbatim, other values as compact JSON.
+pub fn apply_run_parameters(text: &str, parameters: &serde_json::Map<String, Value>) -> String {
+    let mut rendered = text.to_string();
+    for (name, value) in parameters {
+        let replacement = match value {
+            Value::String(inner) => inner.clone(),
+            other => other.to_string(),
+        };
+        rendered = rendered.replace(&format!("{{{{{}}}}}", name), &replacement);
+    }
+    rendered
+}
+
 pub(crate) fn start_run_with_client(
     pool: &DbPool,
     run_id: &str,
     llm_client: &dyn LlmClient,
 ) -> anyhow::Result<RunExecutionRecord> {
-    let mut conn = pool.get()?;
-    let stored_run = load_stored_run(&conn, run_id)?;
+    start_run_with_invocation(pool, run_id, llm_client, &RunInvocation::default())
+}
+
+pub(crate) fn start_run_with_invocation(
+    pool: &DbPool,
+    run_id: &str,
+    llm_client: &dyn LlmClient,
+    invocation: &RunInvocation,
+) -> anyhow::Result<RunExecutionRecord> {
+    let admitted = admit_run(pool, run_id, invocation.clone())?;
+    execute_admitted_run(pool, admitted, llm_client)
+}
+
+/// A validated run holding its slot in the run queue
+pub struct AdmittedRun {
+    stored_run: StoredRun,
+    invocation: RunInvocation,
+    permit: run_queue::RunPermit<'static>,
+}
+
+/// Validate a run and block until the run queue admits it. Runs on local
+/// models first wait for the project's execution window.
+pub(crate) fn admit_run(
+    pool: &DbPool,
+    run_id: &str,
+    invocation: RunInvocation,
+) -> anyhow::Result<AdmittedRun> {
+    let (stored_run, max_concurrent_runs, window) = {
+        let conn = pool.get()?;
+        crate::cold_storage::ensure_hot(&conn, run_id)?;
+        let stored_run = load_stored_run(&conn, run_id)?;
+        let policy = store::policies::get(&conn, &stored_run.project_id)?;
+        (stored_run, policy.max_concurrent_runs, policy.execution_window)
+    };
 
     if stored_run.steps.is_empty() {
         return Err(anyhow!(format!(
@@ -1772,6 +3000,21 @@ pub(crate) fn start_run_with_client(
         )));
     }
 
+    let missing_inputs: BTreeSet<String> = stored_run
+        .steps
+        .iter()
+        .filter(|config| !config.is_interactive_chat())
+        .flat_map(|config| config.prompt.iter().chain(config.config_json.iter()))
+        .flat_map(|text| referenced_run_inputs(text))
+        .filter(|name| !invocation.inputs.contains_key(name))
+        .collect();
+    if !missing_inputs.is_empty() {
+        return Err(anyhow!(format!(
+            "run {run_id} needs inputs that were not supplied: {}",
+            missing_inputs.into_iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
     for config in stored_run
         .steps
         .iter()
@@ -1782,629 +3025,3202 @@ pub(crate) fn start_run_with_client(
             .epsilon
             .or(stored_run.epsilon)
             .ok_or_else(|| anyhow!("concordant steps require an epsilon"))?;
-        if !epsilon.is_finite() || epsilon < 0.0 {
+        if !semantic::epsilon_in_bounds(epsilon) {
             return Err(anyhow!(
-                "step epsilon must be a finite, non-negative value for concordant checkpoints"
+                "step epsilon must be between 0 and 1 for concordant checkpoints"
             ));
         }
     }
 
-    let tx = conn.transaction()?;
-    let execution_record = insert_run_execution(&tx, run_id)?;
-    let signing_key = ensure_project_signing_key(&tx, &stored_run.project_id)?;
-    let policy = store::policies::get_for_policy_version(
-        tx.deref(),
-        &stored_run.project_id,
-        stored_run.policy_version,
-    )?;
-    let ledger_snapshot = store::project_usage_ledgers::get(
-        tx.deref(),
-        &stored_run.project_id,
-        stored_run.policy_version,
-    )?;
-    let ledger_tokens = ledger_snapshot.total_tokens;
-    let ledger_usd = ledger_snapshot.total_usd;
-    let ledger_nature_cost = ledger_snapshot.total_nature_cost;
-    let mut prev_chain = String::new();
-    let mut cumulative_usage_tokens: u64 = 0;
-    let mut run_usage_usd: f64 = 0.0;
-    let mut run_usage_nature_cost: f64 = 0.0;
+    // Wait for a slot before taking a connection so queued runs cannot starve the pool
+    let permit = match window.filter(|_| run_uses_local_models(&stored_run)) {
+        Some(window) => run_queue::global().acquire_when(
+            &stored_run.project_id,
+            run_id,
+            stored_run.priority,
+            max_concurrent_runs,
+            execution_window::RECHECK_INTERVAL,
+            || window.deferral_reason(&execution_window::HostStatus::current()),
+        ),
+        None => run_queue::global().acquire(
+            &stored_run.project_id,
+            run_id,
+            stored_run.priority,
+            max_concurrent_runs,
+        ),
+    };
 
-    // Track step outputs for chaining
-    let mut prior_outputs: std::collections::HashMap<usize, StepOutput> = std::collections::HashMap::new();
+    Ok(AdmittedRun {
+        stored_run,
+        invocation,
+        permit,
+    })
+}
 
-    for (index, config) in stored_run.steps.iter().enumerate() {
-        if config.is_interactive_chat() {
-            continue;
-        }
+/// Execute an admitted run; its queue slot is released when it finishes
+pub fn execute_admitted_run(
+    pool: &DbPool,
+    admitted: AdmittedRun,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<RunExecutionRecord> {
+    let AdmittedRun {
+        stored_run,
+        invocation,
+        permit,
+    } = admitted;
+    let run_id = stored_run.id.as_str();
 
-        let timestamp = Utc::now().to_rfc3339();
+    // Captured before taking a connection; asking Ollama for its version can take a moment
+    let environment = RunEnvironment::capture(run_uses_local_models(&stored_run));
 
-        let projected_remaining_tokens = sum_token_budgets(&stored_run.steps[index..]);
-        let projected_costs = estimate_costs_with_policy(
-            &policy,
-            cumulative_usage_tokens,
-            projected_remaining_tokens,
-            run_usage_usd,
-            run_usage_nature_cost,
-            ledger_tokens,
-            ledger_usd,
-            ledger_nature_cost,
-        );
-        let per_run_projection = &projected_costs.per_run;
-        let cumulative_projection = &projected_costs.cumulative;
-
-        // Check blocking budget violations (tokens and USD)
-        let has_blocking_violation = per_run_projection.exceeds_tokens
-            || per_run_projection.exceeds_usd
-            || cumulative_projection.exceeds_tokens
-            || cumulative_projection.exceeds_usd;
-
-        if has_blocking_violation {
-            let mut issues = Vec::new();
-            if per_run_projection.exceeds_tokens {
-                issues.push(format!(
-                    "per-run tokens {} > {}",
-                    per_run_projection.estimated_tokens, per_run_projection.budget_tokens
-                ));
-            }
-            if per_run_projection.exceeds_usd {
-                issues.push(format!(
-                    "per-run USD {:.2} > {:.2}",
-                    per_run_projection.estimated_usd, per_run_projection.budget_usd
-                ));
-            }
-            if cumulative_projection.exceeds_tokens {
-                issues.push(format!(
-                    "cumulative tokens {} > {}",
-                    cumulative_projection.estimated_tokens, cumulative_projection.budget_tokens
-                ));
-            }
-            if cumulative_projection.exceeds_usd {
-                issues.push(format!(
-                    "cumulative USD {:.2} > {:.2}",
-                    cumulative_projection.estimated_usd, cumulative_projection.budget_usd
-                ));
-            }
+    let mut conn = pool.get()?;
+    let (signing_key, key_event) =
+        ensure_project_signing_key(&conn, &stored_run.project_id, Some(run_id))?;
 
-            let summary = issues.join(", ");
-            let incident = governance::Incident {
-                kind: "budget_projection_exceeded".into(),
-                severity: "error".into(),
-                details: format!(
-                    "Projected costs exceed policy budgets before executing checkpoint {} ({}): {}.",
-                    config.id, config.checkpoint_type, summary
-                ),
-            };
-            let incident_value = serde_json::to_value(&incident)?;
+    let tx = conn.transaction()?;
+    let execution_record = insert_run_execution(&tx, run_id, environment)?;
+    if let Some(ref input_sha256) = invocation.input_sha256 {
+        tx.execute(
+            "UPDATE run_executions SET input_sha256 = ?1 WHERE id = ?2",
+            params![input_sha256, &execution_record.id],
+        )?;
+    }
+    tx.commit()?;
+    permit.set_execution_id(&execution_record.id);
+
+    // Steps run outside a long-lived transaction so concurrent executions can
+    // interleave their checkpoint and ledger writes. A failed execution is
+    // discarded afterwards, leaving no partial record behind.
+    let mut committed = LedgerDelta::default();
+    if let Err(err) = execute_run_steps(
+        &conn,
+        &stored_run,
+        &execution_record,
+        &signing_key,
+        key_event.as_ref(),
+        llm_client,
+        &invocation,
+        &permit,
+        &mut committed,
+    ) {
+        if let Err(cleanup_err) =
+            discard_execution(&mut conn, &stored_run, &execution_record.id, &committed)
+        {
+            eprintln!(
+                "⚠️  Warning: failed to discard execution {}: {}",
+                execution_record.id, cleanup_err
+            );
+        }
+        telemetry::record_run(&conn, false, std::iter::empty());
+        return Err(err);
+    }
 
-            let checkpoint_insert = CheckpointInsert {
-                run_id,
-                run_execution_id: execution_record.id.as_str(),
-                checkpoint_config_id: Some(config.id.as_str()),
-                parent_checkpoint_id: None,
-                turn_index: None,
-                kind: "Incident",
-                timestamp: &timestamp,
-                incident: Some(&incident_value),
-                inputs_sha256: None,
-                outputs_sha256: None,
-                prev_chain: prev_chain.as_str(),
-                usage_tokens: 0,
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                semantic_digest: None,
-                prompt_payload: None,
-                output_payload: None,
-                message: None,
-            };
+    telemetry::record_run(
+        &conn,
+        true,
+        stored_run.steps.iter().map(|step| step.step_type.as_str()),
+    );
+    Ok(execution_record)
+}
 
-            persist_checkpoint(&tx, &signing_key, &checkpoint_insert)?;
-            break;
-        }
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DryRunSeverity {
+    /// The run would fail or be stopped by policy
+    Error,
+    /// The run may work, but something could not be confirmed up front
+    Warning,
+}
 
-        // Handle Nature Cost warning (non-blocking)
-        let nature_warning_projection: Option<(&str, &CostProjection)> = if cumulative_projection
-            .exceeds_nature_cost
-        {
-            Some(("cumulative", cumulative_projection))
-        } else if per_run_projection.exceeds_nature_cost {
-            Some(("per-run", per_run_projection))
-        } else {
-            None
-        };
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunIssue {
+    pub severity: DryRunSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_index: Option<i64>,
+    pub message: String,
+}
 
-        if let Some((scope, projection)) = nature_warning_projection {
-            let warning = governance::Incident {
-                kind: "nature_cost_warning".into(),
-                severity: "warn".into(),
-                details: format!(
-                    "{} Nature Cost {:.2} exceeds budget {:.2} for checkpoint {} (execution continues)",
-                    scope,
-                    projection.estimated_nature_cost,
-                    projection.budget_nature_cost,
-                    config.id
-                ),
-            };
-            let warning_value = serde_json::to_value(&warning)?;
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunStep {
+    pub order_index: i64,
+    pub step_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Provider from the model catalog, or "stub"/"unknown"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub source_paths: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<usize>,
+}
 
-            let warning_checkpoint = CheckpointInsert {
-                run_id,
-                run_execution_id: execution_record.id.as_str(),
-                checkpoint_config_id: Some(config.id.as_str()),
-                parent_checkpoint_id: None,
-                turn_index: None,
-                kind: "Incident",
-                timestamp: &timestamp,
-                incident: Some(&warning_value),
-                inputs_sha256: None,
-                outputs_sha256: None,
-                prev_chain: prev_chain.as_str(),
-                usage_tokens: 0,
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                semantic_digest: None,
-                prompt_payload: None,
-                output_payload: None,
-                message: None,
-            };
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunReport {
+    pub run_id: String,
+    /// No errors were found; warnings do not affect validity
+    pub valid: bool,
+    pub steps: Vec<DryRunStep>,
+    pub issues: Vec<DryRunIssue>,
+    pub cost_estimates: RunCostEstimates,
+}
 
-            let warning_persisted = persist_checkpoint(&tx, &signing_key, &warning_checkpoint)?;
-            prev_chain = warning_persisted.curr_chain;
-            // Continue execution despite warning
+/// Validate a run as `start_run` would execute it, without calling any model
+/// or writing checkpoints. Step configs are resolved, source paths and step
+/// references checked, and models resolved against the catalog and policy.
+/// Source paths are rendered with `inputs` as `start_run` would render them.
+pub fn start_run_dry(
+    pool: &DbPool,
+    run_id: &str,
+    inputs: serde_json::Map<String, Value>,
+) -> anyhow::Result<DryRunReport> {
+    let invocation = RunInvocation {
+        inputs,
+        ..RunInvocation::default()
+    };
+    let conn = pool.get()?;
+    let stored_run = load_stored_run(&conn, run_id)?;
+    let policy = store::policies::get_for_policy_version(
+        &conn,
+        &stored_run.project_id,
+        stored_run.policy_version,
+    )?;
+    let cost_estimates = estimate_run_cost(&conn, run_id)?;
+
+    let mut issues = Vec::new();
+    // Checkpoints of earlier runs that steps consume must still be readable
+    for step in &stored_run.steps {
+        let Some(config) = step
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<StepConfig>(json).ok())
+        else {
+            continue;
+        };
+        if let Some(checkpoint_id) = config.referenced_checkpoint() {
+            if let Err(err) =
+                load_referenced_checkpoint_output(&conn, &stored_run.project_id, checkpoint_id)
+            {
+                issues.push(dry_run_error(Some(step.order_index), err.to_string()));
+            }
+        }
+        if let StepConfig::Prompt {
+            prompt,
+            template: true,
+            ..
+        } = &config
+        {
+            let partials = store::prompt_partials::as_map(&conn, &stored_run.project_id)?;
+            if let Err(err) = prompt_template::check_partials(prompt, &partials) {
+                issues.push(dry_run_error(
+                    Some(step.order_index),
+                    format!("prompt template: {err}"),
+                ));
+            }
         }
+    }
+    drop(conn);
 
-        // Check network policy before executing checkpoints that require network
-        let model_requires_network = if let Some(ref model_id) = config.model {
-            crate::model_catalog::try_get_global_catalog()
-                .and_then(|catalog| catalog.get_model(model_id))
-                .map(|model_def| model_def.requires_network)
-                .unwrap_or(model_id != STUB_MODEL_ID) // Fallback: assume network needed unless stub
-        } else {
-            false
-        };
+    if stored_run.steps.is_empty() {
+        issues.push(dry_run_error(
+            None,
+            format!("run {run_id} has no configured checkpoints"),
+        ));
+    }
+    if cost_estimates.per_run.exceeds_tokens || cost_estimates.cumulative.exceeds_tokens {
+        issues.push(dry_run_error(
+            None,
+            "projected tokens exceed the policy budget",
+        ));
+    }
+    if cost_estimates.per_run.exceeds_usd || cost_estimates.cumulative.exceeds_usd {
+        issues.push(dry_run_error(
+            None,
+            "projected USD cost exceeds the policy budget",
+        ));
+    }
 
-        if model_requires_network {
-            if let Err(network_incident) = governance::enforce_network_policy(&policy) {
-                let incident_value = serde_json::to_value(&network_incident)?;
-                let checkpoint_insert = CheckpointInsert {
-                    run_id,
-                    run_execution_id: execution_record.id.as_str(),
-                    checkpoint_config_id: Some(config.id.as_str()),
-                    parent_checkpoint_id: None,
-                    turn_index: None,
-                    kind: "Incident",
-                    timestamp: &timestamp,
-                    incident: Some(&incident_value),
-                    inputs_sha256: None,
-                    outputs_sha256: None,
-                    prev_chain: prev_chain.as_str(),
-                    usage_tokens: 0,
-                    prompt_tokens: 0,
-                    completion_tokens: 0,
-                    semantic_digest: None,
-                    prompt_payload: None,
-                    output_payload: None,
-                    message: None,
-                };
-                persist_checkpoint(&tx, &signing_key, &checkpoint_insert)?;
-                break;
+    // Only non-interactive steps run (and produce outputs) during start_run
+    let executed: std::collections::HashSet<usize> = stored_run
+        .steps
+        .iter()
+        .filter(|step| !step.is_interactive_chat())
+        .map(|step| step.order_index as usize)
+        .collect();
+    let dispatcher = crate::model_adapters::ModelDispatcher::new();
+    let mut privacy_lineage: std::collections::HashMap<usize, BTreeSet<String>> =
+        std::collections::HashMap::new();
+
+    let mut steps = Vec::new();
+    for step in &stored_run.steps {
+        let mut dry_step = DryRunStep {
+            order_index: step.order_index,
+            step_type: step.step_type.clone(),
+            model: step.model.clone(),
+            provider: None,
+            source_paths: step_filesystem_paths(step, &invocation),
+            depends_on: Vec::new(),
+        };
+        if step.is_interactive_chat() {
+            steps.push(dry_step);
+            continue;
+        }
+        let at = Some(step.order_index);
+
+        if step.proof_mode.is_concordant() {
+            match step.epsilon.or(stored_run.epsilon) {
+                Some(epsilon) if semantic::epsilon_in_bounds(epsilon) => {}
+                Some(_) => issues.push(dry_run_error(
+                    at,
+                    "step epsilon must be between 0 and 1",
+                )),
+                None => issues.push(dry_run_error(at, "concordant steps require an epsilon")),
             }
         }
 
-        // Execute the checkpoint - handle typed steps with chaining
-        let execution = if let Some(ref config_json_str) = config.config_json {
-            // Try to parse as typed StepConfig
-            if DEBUG_STEP_EXECUTION {
-                eprintln!("🔍 Attempting to parse config_json: {}", config_json_str);
-            }
-            match serde_json::from_str::<StepConfig>(config_json_str) {
-                Ok(step_config) => {
-                    if DEBUG_STEP_EXECUTION {
-                        eprintln!("✅ Successfully parsed typed step: {:?}", step_config);
+        match step.config_json.as_deref() {
+            Some(config_json) => match serde_json::from_str::<StepConfig>(config_json) {
+                Ok(config) => {
+                    if let Err(err) = config.validate() {
+                        issues.push(dry_run_error(at, err));
                     }
-                    // Execute based on step type
-                    match step_config {
-                    StepConfig::Ingest { source_path, format, privacy_status } => {
-                        // Build DocumentIngestionConfig JSON for the ingestion function
-                        let ingestion_config = DocumentIngestionConfig {
-                            source_path,
-                            format,
-                            privacy_status,
-                            output_storage: "database".to_string(),
-                        };
-                        let ingestion_json = serde_json::to_string(&ingestion_config)?;
-                        execute_document_ingestion_checkpoint(&ingestion_json)?
-                    }
-                    StepConfig::Summarize {
-                        source_step,
-                        model,
-                        summary_type,
-                        custom_instructions,
-                        token_budget: _,
-                        proof_mode: _,
-                        epsilon: _,
-                    } => {
-                        // Resolve source step if specified
-                        if let Some(source_idx) = source_step {
-                            let source = prior_outputs.get(&source_idx).ok_or_else(|| {
-                                anyhow!(
-                                    "Step {} references non-existent source step {}",
-                                    config.order_index,
-                                    source_idx
-                                )
-                            })?;
-
-                            // Build summary prompt
-                            let prompt = build_summary_prompt(
-                                source,
-                                &summary_type,
-                                custom_instructions.as_deref(),
-                            )?;
-
-                            // Execute based on model type (stub, mock, or real LLM)
-                            if model == STUB_MODEL_ID {
-                                execute_stub_checkpoint(stored_run.seed, config.order_index, &prompt)
-                            } else if model.starts_with(CLAUDE_MODEL_PREFIX) {
-                                execute_claude_mock_checkpoint(&model, &prompt)?
-                            } else {
-                                execute_llm_checkpoint(&model, &prompt, llm_client)?
-                            }
-                        } else {
-                            return Err(anyhow!(
-                                "Summarize step {} requires a source_step",
-                                config.order_index
-                            ));
-                        }
+                    let (model, depends_on, targets) = step_config_references(&config);
+                    if let Some(model) = model {
+                        dry_step.model = Some(model);
                     }
-                    StepConfig::Prompt {
-                        model,
-                        prompt,
-                        use_output_from,
-                        token_budget: _,
-                        proof_mode: _,
-                        epsilon: _,
-                    } => {
-                        // Optionally use output from previous step
-                        let final_prompt = if let Some(source_idx) = use_output_from {
-                            let source = prior_outputs.get(&source_idx).ok_or_else(|| {
-                                anyhow!(
-                                    "Step {} references non-existent source step {}",
-                                    config.order_index,
-                                    source_idx
-                                )
-                            })?;
-                            if DEBUG_STEP_EXECUTION {
-                                eprintln!("🔗 Prompt step {} using output from step {}", config.order_index, source_idx);
-                                eprintln!("   Source output length: {} chars", source.output_text.len());
-                                eprintln!("   Source output preview: {}",
-                                    if source.output_text.len() > 200 {
-                                        format!("{}...", &source.output_text[..200])
-                                    } else {
-                                        source.output_text.clone()
-                                    });
-                            }
-                            let context_prompt = build_prompt_with_context(&prompt, source);
-                            if DEBUG_STEP_EXECUTION {
-                                eprintln!("   Final prompt length: {} chars", context_prompt.len());
-                            }
-                            context_prompt
-                        } else {
-                            if DEBUG_STEP_EXECUTION {
-                                eprintln!("🔗 Prompt step {} running standalone (no context)", config.order_index);
+                    dry_step.depends_on = depends_on;
+                    check_step_references(
+                        step,
+                        &dry_step.depends_on,
+                        &targets,
+                        &executed,
+                        &mut issues,
+                    );
+                    check_plugin_step(step.order_index, &config, &mut issues);
+                    if let StepConfig::FineTune {
+                        command,
+                        hyperparameters,
+                        ..
+                    } = &config
+                    {
+                        let command = finetune::render_command(command, hyperparameters);
+                        if let Some(program) = command.first() {
+                            if let Err(incident) = finetune::authorize_program(&policy, program) {
+                                issues.push(dry_run_error(at, incident.details));
                             }
-                            prompt.clone()
-                        };
-
-                        // Execute based on model type (stub, mock, or real LLM)
-                        if model == STUB_MODEL_ID {
-                            execute_stub_checkpoint(stored_run.seed, config.order_index, &final_prompt)
-                        } else if model.starts_with(CLAUDE_MODEL_PREFIX) {
-                            execute_claude_mock_checkpoint(&model, &final_prompt)?
-                        } else {
-                            execute_llm_checkpoint(&model, &final_prompt, llm_client)?
                         }
                     }
-                    }
                 }
-                Err(parse_err) => {
-                    if DEBUG_STEP_EXECUTION {
-                        eprintln!("❌ Failed to parse as typed step: {}", parse_err);
-                        eprintln!("   Falling back to legacy execution");
+                Err(err) if !step.is_document_ingestion() => {
+                    issues.push(dry_run_error(at, format!("invalid step config: {err}")));
+                }
+                // Legacy ingestion steps store a bare DocumentIngestionConfig
+                Err(_) => {
+                    if let Err(err) = serde_json::from_str::<DocumentIngestionConfig>(config_json) {
+                        issues.push(dry_run_error(
+                            at,
+                            format!("invalid ingestion config: {err}"),
+                        ));
                     }
-                    // Not a typed config, use legacy execution
-                    execute_checkpoint(config, stored_run.seed, llm_client)?
                 }
+            },
+            None if step.is_document_ingestion() => {
+                issues.push(dry_run_error(at, "document ingestion step missing config"));
             }
-        } else {
-            // No config_json, use legacy execution
-            execute_checkpoint(config, stored_run.seed, llm_client)?
+            None => {
+                if step.model.is_none() {
+                    issues.push(dry_run_error(at, "LLM step missing model"));
+                }
+                if step.prompt.is_none() {
+                    issues.push(dry_run_error(at, "LLM step missing prompt"));
+                }
+            }
+        }
+
+        for path in &dry_step.source_paths {
+            check_source_path(&policy, step.order_index, path, &mut issues);
+        }
+        if let Some(model) = dry_step.model.clone() {
+            dry_step.provider = Some(check_step_model(
+                &policy,
+                &dispatcher,
+                step.order_index,
+                &model,
+                &mut issues,
+            ));
+        }
+        let privacy_statuses = step_privacy_statuses(step, &privacy_lineage);
+        if let Some(model) = step_pricing_model(step) {
+            if let Err(incident) = governance::enforce_privacy_policy(
+                &model,
+                model_requires_network(&model),
+                &privacy_statuses,
+            ) {
+                // Blocks unless the policy lets the step wait for approval or continue
+                let action = incident_response::action_for(&policy.incident_actions, &incident);
+                issues.push(if action == incident_response::IncidentAction::Block {
+                    dry_run_error(at, incident.details)
+                } else {
+                    dry_run_warning(at, incident.details)
+                });
+            }
+        }
+        privacy_lineage.insert(step.order_index as usize, privacy_statuses);
+        steps.push(dry_step);
+    }
+
+    let valid = issues
+        .iter()
+        .all(|issue| issue.severity != DryRunSeverity::Error);
+    Ok(DryRunReport {
+        run_id: run_id.to_string(),
+        valid,
+        steps,
+        issues,
+        cost_estimates,
+    })
+}
+
+fn dry_run_error(order_index: Option<i64>, message: impl Into<String>) -> DryRunIssue {
+    DryRunIssue {
+        severity: DryRunSeverity::Error,
+        order_index,
+        message: message.into(),
+    }
+}
+
+fn dry_run_warning(order_index: Option<i64>, message: impl Into<String>) -> DryRunIssue {
+    DryRunIssue {
+        severity: DryRunSeverity::Warning,
+        order_index,
+        message: message.into(),
+    }
+}
+
+/// Plugin steps need the plugin installed with the step type and capabilities they use
+fn check_plugin_step(order_index: i64, config: &StepConfig, issues: &mut Vec<DryRunIssue>) {
+    let StepConfig::Plugin {
+        plugin,
+        step,
+        source_step,
+        source_path,
+        ..
+    } = config
+    else {
+        return;
+    };
+    let at = Some(order_index);
+    let Some(manifest) = plugins::plugin_manifest(plugin) else {
+        issues.push(dry_run_error(at, format!("plugin '{plugin}' is not installed")));
+        return;
+    };
+    if !manifest.provides(step) {
+        issues.push(dry_run_error(
+            at,
+            format!("plugin {plugin} does not provide step type '{step}'"),
+        ));
+    }
+    if source_step.is_some() && !manifest.has_capability(plugins::PluginCapability::ReadStepOutput)
+    {
+        issues.push(dry_run_error(
+            at,
+            format!("plugin {plugin} lacks the read_step_output capability"),
+        ));
+    }
+    if source_path.is_some() && !manifest.has_capability(plugins::PluginCapability::ReadSourceFile)
+    {
+        issues.push(dry_run_error(
+            at,
+            format!("plugin {plugin} lacks the read_source_file capability"),
+        ));
+    }
+}
+
+/// Model, source steps and (for branches) target steps named by a typed config
+/// Earlier steps of the run whose outputs the step consumes
+pub(crate) fn step_sources(config: &RunStep) -> Vec<usize> {
+    config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<StepConfig>(json).ok())
+        .map(|step_config| step_config_references(&step_config).1)
+        .unwrap_or_default()
+}
+
+fn step_config_references(config: &StepConfig) -> (Option<String>, Vec<usize>, Vec<usize>) {
+    match config {
+        StepConfig::Ingest { .. } => (None, Vec::new(), Vec::new()),
+        StepConfig::Summarize {
+            source_step, model, ..
+        } => (
+            Some(model.clone()),
+            source_step.iter().copied().collect(),
+            Vec::new(),
+        ),
+        StepConfig::Prompt {
+            model,
+            use_output_from,
+            ..
+        } => (
+            Some(model.clone()),
+            use_output_from.iter().filter_map(StepOutputRef::step_index).collect(),
+            Vec::new(),
+        ),
+        StepConfig::Map {
+            source_step, model, ..
+        } => (Some(model.clone()), vec![*source_step], Vec::new()),
+        StepConfig::Branch {
+            source_step,
+            predicate,
+            branches,
+        } => {
+            let model = match predicate {
+                branching::BranchPredicate::LlmClassification { model, .. } => Some(model.clone()),
+                _ => None,
+            };
+            let mut targets: Vec<usize> = branches.values().flatten().copied().collect();
+            targets.sort_unstable();
+            targets.dedup();
+            (model, vec![*source_step], targets)
+        }
+        StepConfig::Plugin { source_step, .. } => {
+            (None, source_step.iter().copied().collect(), Vec::new())
+        }
+        StepConfig::Agent {
+            model, source_step, ..
+        } => (
+            Some(model.clone()),
+            source_step.iter().copied().collect(),
+            Vec::new(),
+        ),
+        // The base model is trained locally, not called through a provider
+        StepConfig::FineTune { .. } => (None, Vec::new(), Vec::new()),
+        StepConfig::Embed {
+            source_step, model, ..
+        } => (Some(model.clone()), vec![*source_step], Vec::new()),
+        StepConfig::Evaluate {
+            candidate_step,
+            reference_step,
+            embedding_model,
+            judge_model,
+            ..
+        } => (
+            judge_model.clone().or_else(|| embedding_model.clone()),
+            std::iter::once(*candidate_step)
+                .chain(reference_step.iter().copied())
+                .collect(),
+            Vec::new(),
+        ),
+    }
+}
+
+fn check_step_references(
+    step: &RunStep,
+    sources: &[usize],
+    targets: &[usize],
+    executed: &std::collections::HashSet<usize>,
+    issues: &mut Vec<DryRunIssue>,
+) {
+    let at = Some(step.order_index);
+    let position = step.order_index as usize;
+    if matches!(
+        serde_json::from_str::<StepConfig>(step.config_json.as_deref().unwrap_or_default()),
+        Ok(StepConfig::Summarize {
+            source_step: None,
+            ..
+        })
+    ) {
+        issues.push(dry_run_error(at, "summarize step requires a source_step"));
+    }
+    for &source in sources {
+        if source >= position {
+            issues.push(dry_run_error(
+                at,
+                format!("references step {source}, which does not run before it"),
+            ));
+        } else if !executed.contains(&source) {
+            issues.push(dry_run_error(
+                at,
+                format!("references step {source}, which does not exist or produces no output"),
+            ));
+        }
+    }
+    for &target in targets {
+        if target <= position || !executed.contains(&target) {
+            issues.push(dry_run_error(
+                at,
+                format!("branch selects step {target}, which is not a later step of the run"),
+            ));
+        }
+    }
+}
+
+fn check_source_path(
+    policy: &store::policies::Policy,
+    order_index: i64,
+    path: &str,
+    issues: &mut Vec<DryRunIssue>,
+) {
+    let at = Some(order_index);
+    if path.contains("{{") {
+        issues.push(dry_run_warning(
+            at,
+            format!("source path '{path}' depends on run parameters and was not checked"),
+        ));
+        return;
+    }
+    let path_ref = std::path::Path::new(path);
+    if let Err(incident) = governance::enforce_path_policy(policy, path_ref) {
+        issues.push(dry_run_error(at, incident.details));
+    }
+    if !path_ref.exists() {
+        issues.push(dry_run_error(
+            at,
+            format!("source path '{path}' does not exist"),
+        ));
+    }
+}
+
+/// Resolve a model against the catalog and policy; returns its provider
+fn check_step_model(
+    policy: &store::policies::Policy,
+    dispatcher: &crate::model_adapters::ModelDispatcher,
+    order_index: i64,
+    model: &str,
+    issues: &mut Vec<DryRunIssue>,
+) -> String {
+    let at = Some(order_index);
+    if model == STUB_MODEL_ID {
+        return "stub".to_string();
+    }
+
+    let catalog_entry =
+        crate::model_catalog::try_get_global_catalog().and_then(|catalog| catalog.get_model(model));
+    // Claude models currently answer with a mock response and need no key
+    let is_mock = model.starts_with(CLAUDE_MODEL_PREFIX);
+    match catalog_entry {
+        Some(model_def) => {
+            if !model_def.enabled {
+                issues.push(dry_run_error(
+                    at,
+                    format!("model '{model}' is disabled in the catalog"),
+                ));
+            }
+            if !is_mock {
+                if let Err(err) = dispatcher.check_api_key_configured(model) {
+                    issues.push(dry_run_error(at, err.to_string()));
+                }
+            }
+        }
+        None if is_mock => {}
+        None if dispatcher.has_adapter(model) => {
+            issues.push(dry_run_warning(
+                at,
+                format!(
+                    "model '{model}' is not in the catalog; assumed to be a local Ollama model"
+                ),
+            ));
+        }
+        None => {
+            issues.push(dry_run_error(
+                at,
+                format!("no provider can run model '{model}'"),
+            ));
+            return "unknown".to_string();
+        }
+    }
+
+    // Same fallback as execution: assume network unless the catalog says otherwise
+    let requires_network = catalog_entry.is_none_or(|model_def| model_def.requires_network);
+    if requires_network {
+        if let Err(incident) = governance::enforce_network_policy(policy) {
+            issues.push(dry_run_error(at, incident.details));
+        }
+    }
+    if let Err(incident) = governance::enforce_provider_policy(policy, model) {
+        issues.push(dry_run_error(at, incident.details));
+    }
+
+    match catalog_entry {
+        Some(model_def) => model_def.provider.clone(),
+        None if is_mock => "anthropic".to_string(),
+        None => "ollama".to_string(),
+    }
+}
+
+/// Usage an execution has already added to the project ledger
+#[derive(Debug, Default, Clone, Copy)]
+struct LedgerDelta {
+    tokens: u64,
+    usd: f64,
+    nature_cost: f64,
+}
+
+fn execute_run_steps(
+    conn: &Connection,
+    stored_run: &StoredRun,
+    execution_record: &RunExecutionRecord,
+    signing_key: &ProjectSigner,
+    key_event: Option<&store::signing_key_events::SigningKeyEvent>,
+    llm_client: &dyn LlmClient,
+    invocation: &RunInvocation,
+    permit: &run_queue::RunPermit<'_>,
+    committed: &mut LedgerDelta,
+) -> anyhow::Result<()> {
+    let run_id = stored_run.id.as_str();
+    let policy = store::policies::get_for_policy_version(
+        conn,
+        &stored_run.project_id,
+        stored_run.policy_version,
+    )?;
+    let mut prev_chain = String::new();
+    let mut cumulative_usage_tokens: u64 = 0;
+    let mut run_usage_usd: f64 = 0.0;
+    let mut run_usage_nature_cost: f64 = 0.0;
+    let mut run_usage_by_model: BTreeMap<String, governance::ModelUsage> = BTreeMap::new();
+
+    // Track step outputs for chaining
+    let mut prior_outputs: std::collections::HashMap<usize, StepOutput> = std::collections::HashMap::new();
+
+    // The run inputs hash is committed to by the first checkpoint of the execution
+    let run_inputs_sha256 = invocation.inputs_sha256();
+    let first_checkpoint_inputs =
+        |prev_chain: &str| run_inputs_sha256.as_deref().filter(|_| prev_chain.is_empty());
+
+    // Whether the execution continues past a recorded incident, per the policy's
+    // incident actions; pauses here while approval is pending
+    let respond_to_incident = |checkpoint_id: &str, incident: &governance::Incident| {
+        incident_response::respond(
+            conn,
+            &policy.incident_actions,
+            incident_response::IncidentSite {
+                project_id: &stored_run.project_id,
+                run_id,
+                run_execution_id: &execution_record.id,
+                checkpoint_id,
+            },
+            incident,
+        )
+    };
+
+    // A key regenerated for this execution is recorded ahead of the steps it signs
+    if let Some(event) = key_event {
+        let incident = governance::Incident {
+            kind: "signing_key_regenerated".into(),
+            severity: "warn".into(),
+            details: format!(
+                "Signing key for project {} was missing and has been regenerated (new public key {}); checkpoints signed before {} verify only against the previous key.",
+                event.project_id, event.pubkey, event.created_at
+            ),
         };
+        let persisted = persist_incident_checkpoint(
+            conn,
+            signing_key,
+            execution_record,
+            None,
+            &Utc::now().to_rfc3339(),
+            &prev_chain,
+            run_inputs_sha256.as_deref(),
+            &incident,
+        )?;
+        if !respond_to_incident(&persisted.id, &incident)? {
+            return Ok(());
+        }
+        prev_chain = persisted.curr_chain;
+    }
 
-        let total_usage = execution.usage.total();
-        cumulative_usage_tokens = cumulative_usage_tokens.saturating_add(total_usage);
-        let step_model = config.model.as_deref();
-        let step_usd = governance::estimate_usd_cost(total_usage, step_model);
-        let step_nature_cost = governance::estimate_nature_cost(total_usage, step_model);
-        run_usage_usd += step_usd;
-        run_usage_nature_cost += step_nature_cost;
-        let prompt_tokens = execution.usage.prompt_tokens;
-        let completion_tokens = execution.usage.completion_tokens;
-        let mut incident_value: Option<serde_json::Value> = None;
+    // Steps deselected by branch decisions
+    let mut skipped_steps: std::collections::HashSet<usize> = std::collections::HashSet::new();
 
-        let budget_outcome = governance::enforce_budget(config.token_budget, total_usage);
+    // Steps recorded as incidents while the execution continued, and the steps
+    // skipped because they consume those missing outputs
+    let mut incident_steps: std::collections::HashSet<usize> = std::collections::HashSet::new();
 
-        let (kind, inputs_sha, outputs_sha, semantic_digest) = match budget_outcome {
-            Ok(_) => {
-                let semantic = if config.proof_mode.is_concordant() {
-                    Some(execution.semantic_digest.clone().ok_or_else(|| {
-                        anyhow!("semantic digest missing for concordant checkpoint")
-                    })?)
-                } else {
-                    None
-                };
-                (
-                    "Step",
-                    execution.inputs_sha256.as_deref(),
-                    execution.outputs_sha256.as_deref(),
-                    semantic,
-                )
+    // Documents in validation/test splits, and the documents each executed step derives from
+    let held_out_documents =
+        store::dataset_splits::held_out_documents(conn, &stored_run.project_id)?;
+    let mut document_lineage: std::collections::HashMap<usize, BTreeSet<String>> =
+        std::collections::HashMap::new();
+    // Privacy statuses of the documents each executed step derives from
+    let mut privacy_lineage: std::collections::HashMap<usize, BTreeSet<String>> =
+        std::collections::HashMap::new();
+
+    for (index, config) in stored_run.steps.iter().enumerate() {
+        if config.is_interactive_chat() {
+            continue;
+        }
+
+        if skipped_steps.contains(&(config.order_index as usize)) {
+            if DEBUG_STEP_EXECUTION {
+                eprintln!("⏭️  Step {} skipped by branch decision", config.order_index);
             }
-            Err(incident) => {
-                incident_value = Some(serde_json::to_value(&incident)?);
-                ("Incident", None, None, None)
+            continue;
+        }
+
+        if step_sources(config).iter().any(|source| incident_steps.contains(source)) {
+            if DEBUG_STEP_EXECUTION {
+                eprintln!("⏭️  Step {} skipped: its source step was recorded as an incident", config.order_index);
+            }
+            incident_steps.insert(config.order_index as usize);
+            continue;
+        }
+
+        // Between steps, give the slot to a waiting higher-priority run
+        if index > 0 {
+            let preemption = runtime::park(|| permit.yield_to_higher_priority(config.order_index));
+            if let Some(preemption) = preemption {
+                record_preemption(conn, &execution_record.id, preemption)?;
             }
+        }
+
+        let timestamp = Utc::now().to_rfc3339();
+
+        // Re-read the ledger every step so usage committed by concurrent
+        // executions of the project counts against the cumulative budget
+        let ledger_snapshot = store::project_usage_ledgers::get(
+            conn,
+            &stored_run.project_id,
+            stored_run.policy_version,
+        )?;
+        let ledger_tokens = ledger_snapshot.total_tokens.saturating_sub(committed.tokens);
+        let ledger_usd = (ledger_snapshot.total_usd - committed.usd).max(0.0);
+        let ledger_nature_cost =
+            (ledger_snapshot.total_nature_cost - committed.nature_cost).max(0.0);
+
+        // Model and provider budgets count this execution's usage by model,
+        // and what the project's other executions recorded
+        let mut usage_by_model = UsageByModel {
+            run: run_usage_by_model.clone(),
+            project: load_project_usage_by_model(
+                conn,
+                &policy,
+                &stored_run.project_id,
+                stored_run.policy_version,
+                Some(&execution_record.id),
+            )?,
         };
+        for (model, projected) in project_step_usage_by_model(&stored_run.steps[index..]) {
+            add_model_usage(&mut usage_by_model.run, Some(&model), projected);
+        }
 
-        let checkpoint_insert = CheckpointInsert {
-            run_id,
-            run_execution_id: execution_record.id.as_str(),
-            checkpoint_config_id: Some(config.id.as_str()),
-            parent_checkpoint_id: None,
-            turn_index: None,
-            kind,
-            timestamp: &timestamp,
-            incident: incident_value.as_ref(),
-            inputs_sha256: inputs_sha,
-            outputs_sha256: outputs_sha,
-            prev_chain: prev_chain.as_str(),
-            usage_tokens: total_usage,
-            prompt_tokens,
-            completion_tokens,
+        let projected_costs = estimate_costs_with_policy(
+            &policy,
+            cumulative_usage_tokens,
+            project_step_usage(&stored_run.steps[index..]),
+            run_usage_usd,
+            run_usage_nature_cost,
+            ledger_tokens,
+            ledger_usd,
+            ledger_nature_cost,
+            &usage_by_model,
+            &load_window_usage(conn, &policy, &stored_run.project_id)?,
+        );
+
+        // Check blocking budget violations
+        let issues = projected_costs.blocking_overruns();
+
+        if !issues.is_empty() {
+            let summary = issues.join(", ");
+            let incident = governance::Incident {
+                kind: "budget_projection_exceeded".into(),
+                severity: "error".into(),
+                details: format!(
+                    "Projected costs exceed policy budgets before executing checkpoint {} ({}): {}.",
+                    config.id, config.checkpoint_type, summary
+                ),
+            };
+            let persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &incident,
+            )?;
+            if !respond_to_incident(&persisted.id, &incident)? {
+                break;
+            }
+            prev_chain = persisted.curr_chain;
+        }
+
+        // Handle Nature Cost warning (non-blocking unless the policy says otherwise)
+        if let Some((scope, projection)) = projected_costs.nature_cost_overrun() {
+            let warning = governance::Incident {
+                kind: "nature_cost_warning".into(),
+                severity: "warn".into(),
+                details: format!(
+                    "{} Nature Cost {:.2} exceeds budget {:.2} for checkpoint {} (execution continues)",
+                    scope,
+                    projection.estimated_nature_cost,
+                    projection.budget_nature_cost,
+                    config.id
+                ),
+            };
+            let warning_persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &warning,
+            )?;
+            if !respond_to_incident(&warning_persisted.id, &warning)? {
+                break;
+            }
+            prev_chain = warning_persisted.curr_chain;
+        }
+
+        // Check network policy before executing checkpoints that require network
+        let requires_network = config.model.as_deref().is_some_and(model_requires_network);
+
+        if requires_network {
+            if let Err(network_incident) = governance::enforce_network_policy(&policy) {
+                let persisted = persist_incident_checkpoint(
+                    conn,
+                    signing_key,
+                    execution_record,
+                    Some(config.id.as_str()),
+                    &timestamp,
+                    &prev_chain,
+                    run_inputs_sha256.as_deref(),
+                    &network_incident,
+                )?;
+                if !respond_to_incident(&persisted.id, &network_incident)? {
+                    break;
+                }
+                prev_chain = persisted.curr_chain;
+            }
+        }
+
+        // Check data residency before executing checkpoints that call a model
+        let provider_incident = config
+            .model
+            .as_deref()
+            .and_then(|model_id| governance::enforce_provider_policy(&policy, model_id).err());
+        if let Some(provider_incident) = provider_incident {
+            let persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &provider_incident,
+            )?;
+            if !respond_to_incident(&persisted.id, &provider_incident)? {
+                break;
+            }
+            prev_chain = persisted.curr_chain;
+        }
+
+        // Check the filesystem sandbox before executing checkpoints that read local files
+        let path_incident = step_filesystem_paths(config, invocation).iter().find_map(|path| {
+            governance::enforce_path_policy(&policy, std::path::Path::new(path)).err()
+        });
+        if let Some(path_incident) = path_incident {
+            let persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &path_incident,
+            )?;
+            if !respond_to_incident(&persisted.id, &path_incident)? {
+                break;
+            }
+            prev_chain = persisted.curr_chain;
+        }
+
+        // Keep held-out evaluation documents away from models
+        let documents = if held_out_documents.is_empty() {
+            BTreeSet::new()
+        } else {
+            step_documents(config, &document_lineage)
+        };
+        if step_sends_documents_to_model(config) {
+            if let Err(leak_incident) =
+                governance::enforce_split_isolation(&held_out_documents, &documents)
+            {
+                let persisted = persist_incident_checkpoint(
+                    conn,
+                    signing_key,
+                    execution_record,
+                    Some(config.id.as_str()),
+                    &timestamp,
+                    &prev_chain,
+                    run_inputs_sha256.as_deref(),
+                    &leak_incident,
+                )?;
+                if !respond_to_incident(&persisted.id, &leak_incident)? {
+                    break;
+                }
+                prev_chain = persisted.curr_chain;
+            }
+        }
+
+        // Keep content derived from restricted documents away from network models
+        let privacy_statuses = step_privacy_statuses(config, &privacy_lineage);
+        let privacy_incident = step_pricing_model(config).and_then(|model| {
+            governance::enforce_privacy_policy(
+                &model,
+                model_requires_network(&model),
+                &privacy_statuses,
+            )
+            .err()
+        });
+        if let Some(privacy_incident) = privacy_incident {
+            let persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &privacy_incident,
+            )?;
+            if !respond_to_incident(&persisted.id, &privacy_incident)? {
+                break;
+            }
+            prev_chain = persisted.curr_chain;
+        }
+
+        // Scan what the step sends onward for secrets, PII and injection markers
+        if !policy.content_rules.is_disabled() {
+            let input_findings = content_policy::scan(
+                &policy.content_rules,
+                &step_input_text(config, invocation, &prior_outputs),
+                content_policy::ScanTarget::Input,
+            );
+            if let Some((_, content_incident)) = content_policy::findings_incident(
+                &policy.content_rules,
+                &format!("Step {}", config.order_index),
+                &input_findings,
+            ) {
+                let persisted = persist_incident_checkpoint(
+                    conn,
+                    signing_key,
+                    execution_record,
+                    Some(config.id.as_str()),
+                    &timestamp,
+                    &prev_chain,
+                    run_inputs_sha256.as_deref(),
+                    &content_incident,
+                )?;
+                if !respond_to_incident(&persisted.id, &content_incident)? {
+                    break;
+                }
+                prev_chain = persisted.curr_chain;
+            }
+        }
+
+        // Set when a typed step completes but its result must be recorded as an incident
+        // (e.g. output schema still violated after retries, map input is not an array)
+        let mut step_incident: Option<governance::Incident> = None;
+
+        // Hash of the output schema a prompt step was validated against
+        let mut output_schema_sha256: Option<String> = None;
+
+        // Per-element executions of a map step (or per-turn executions of an agent step),
+        // persisted as children of the step checkpoint
+        let mut child_executions: Vec<NodeExecution> = Vec::new();
+
+        // Vectors of an embed step, stored once its checkpoint is persisted
+        let mut step_embeddings: Option<(String, Vec<embeddings::EmbeddedChunk>)> = None;
+
+        let sampling = step_sampling(config, stored_run.seed);
+
+        // Cached responses are shared only by steps of this project that
+        // validate against the same output schema
+        let caching_client;
+        let llm_client: &dyn LlmClient = if policy.response_cache {
+            let scope = CacheScope {
+                project_id: stored_run.project_id.clone(),
+                output_schema_sha256: config
+                    .config_json
+                    .as_deref()
+                    .and_then(|json| serde_json::from_str::<StepConfig>(json).ok())
+                    .and_then(|step_config| {
+                        step_config
+                            .output_schema()
+                            .map(output_schema::schema_sha256)
+                    }),
+            };
+            caching_client = CachingLlmClient::new(conn, llm_client, scope);
+            &caching_client
+        } else {
+            llm_client
+        };
+
+        // Model calls of the step share its token budget and are stopped
+        // mid-stream once it is spent
+        let step_client = StepBudgetClient::new(llm_client, config.token_budget);
+        let llm_client: &dyn LlmClient = &step_client;
+
+        // Execute the checkpoint - handle typed steps with chaining
+        let execution = if let Some(ref config_json_str) = config.config_json {
+            // Try to parse as typed StepConfig
+            if DEBUG_STEP_EXECUTION {
+                eprintln!("🔍 Attempting to parse config_json: {}", config_json_str);
+            }
+            match serde_json::from_str::<StepConfig>(config_json_str) {
+                Ok(step_config) => {
+                    if DEBUG_STEP_EXECUTION {
+                        eprintln!("✅ Successfully parsed typed step: {:?}", step_config);
+                    }
+                    // Execute based on step type
+                    match step_config {
+                    StepConfig::Ingest { source_path, format, privacy_status } => {
+                        // Build DocumentIngestionConfig JSON for the ingestion function
+                        let ingestion_config = DocumentIngestionConfig {
+                            source_path: invocation.render(&source_path),
+                            format,
+                            privacy_status,
+                            output_storage: "database".to_string(),
+                        };
+                        let ingestion_json = serde_json::to_string(&ingestion_config)?;
+                        execute_document_ingestion_checkpoint(&ingestion_json, &policy)?
+                    }
+                    StepConfig::Summarize {
+                        source_step,
+                        model,
+                        summary_type,
+                        custom_instructions,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        // Resolve source step if specified
+                        if let Some(source_idx) = source_step {
+                            let source = prior_outputs.get(&source_idx).ok_or_else(|| {
+                                anyhow!(
+                                    "Step {} references non-existent source step {}",
+                                    config.order_index,
+                                    source_idx
+                                )
+                            })?;
+
+                            // Build summary prompt
+                            let custom_instructions = custom_instructions.map(|instructions| {
+                                invocation.render(&instructions)
+                            });
+                            let instructions =
+                                summary_instructions(&summary_type, custom_instructions.as_deref());
+                            let source_text = extract_text_from_output(source)?;
+                            let prompt = format!("{}{}", instructions, source_text);
+
+                            // Sources over the model's context window are summarized in
+                            // chunks, each recorded as a child checkpoint
+                            match summary_prompt_limit(&model)
+                                .filter(|limit| estimate_tokens(&prompt) > *limit)
+                            {
+                                Some(prompt_limit) => {
+                                    let summary = execute_chunked_summary(
+                                        &SummaryTask {
+                                            model: &model,
+                                            instructions,
+                                            source_text: &source_text,
+                                            prompt_limit,
+                                        },
+                                        stored_run.seed,
+                                        config.order_index,
+                                        sampling,
+                                        llm_client,
+                                    )?;
+                                    if DEBUG_STEP_EXECUTION {
+                                        eprintln!("📚 Summarize step {} split its source into {} chunk summaries",
+                                            config.order_index, summary.chunks.len());
+                                    }
+                                    child_executions = summary.chunks;
+                                    summary.execution
+                                }
+                                None => execute_model_prompt(
+                                    &model,
+                                    stored_run.seed,
+                                    config.order_index,
+                                    &prompt,
+                                    sampling,
+                                    llm_client,
+                                )?,
+                            }
+                        } else {
+                            return Err(anyhow!(
+                                "Summarize step {} requires a source_step",
+                                config.order_index
+                            ));
+                        }
+                    }
+                    StepConfig::Prompt {
+                        model,
+                        prompt,
+                        use_output_from,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                        output_schema,
+                        schema_retries,
+                        template,
+                    } => {
+                        // Optionally use output from a previous step, or from a
+                        // checkpoint of an earlier run
+                        let referenced;
+                        let source = match &use_output_from {
+                            Some(StepOutputRef::Step(source_idx)) => {
+                                Some(prior_outputs.get(source_idx).ok_or_else(|| {
+                                    anyhow!(
+                                        "Step {} references non-existent source step {}",
+                                        config.order_index,
+                                        source_idx
+                                    )
+                                })?)
+                            }
+                            Some(StepOutputRef::Checkpoint(checkpoint_id)) => {
+                                referenced = load_referenced_checkpoint_output(
+                                    conn,
+                                    &stored_run.project_id,
+                                    checkpoint_id,
+                                )?;
+                                Some(&referenced)
+                            }
+                            None => None,
+                        };
+
+                        let final_prompt = if template {
+                            // The source output is only where the template places it
+                            let partials =
+                                store::prompt_partials::as_map(conn, &stored_run.project_id)?;
+                            let rendered = prompt_template::render(
+                                &prompt,
+                                &prompt_template_context(invocation, source),
+                                &partials,
+                            )
+                            .map_err(|err| {
+                                anyhow!("Step {} prompt template: {}", config.order_index, err)
+                            })?;
+                            if DEBUG_STEP_EXECUTION {
+                                eprintln!("🧩 Prompt step {} rendered from template ({} chars)", config.order_index, rendered.len());
+                            }
+                            rendered
+                        } else if let Some(source) = source {
+                            let prompt = invocation.render(&prompt);
+                            if DEBUG_STEP_EXECUTION {
+                                eprintln!("🔗 Prompt step {} using output from {:?}", config.order_index, use_output_from);
+                                eprintln!("   Source output length: {} chars", source.output_text.len());
+                                eprintln!("   Source output preview: {}",
+                                    if source.output_text.len() > 200 {
+                                        format!("{}...", &source.output_text[..200])
+                                    } else {
+                                        source.output_text.clone()
+                                    });
+                            }
+                            let context_prompt = build_prompt_with_context(&prompt, source);
+                            if DEBUG_STEP_EXECUTION {
+                                eprintln!("   Final prompt length: {} chars", context_prompt.len());
+                            }
+                            context_prompt
+                        } else {
+                            if DEBUG_STEP_EXECUTION {
+                                eprintln!("🔗 Prompt step {} running standalone (no context)", config.order_index);
+                            }
+                            invocation.render(&prompt)
+                        };
+
+                        let run_prompt = |prompt_text: &str| {
+                            execute_model_prompt(
+                                &model,
+                                stored_run.seed,
+                                config.order_index,
+                                prompt_text,
+                                sampling,
+                                llm_client,
+                            )
+                        };
+                        let mut execution = run_prompt(&final_prompt)?;
+
+                        // Validate structured output, retrying with a correction prompt on violation
+                        if let Some(schema) = output_schema.as_ref() {
+                            output_schema_sha256 = Some(output_schema::schema_sha256(schema));
+                            let max_retries = schema_retries.unwrap_or(DEFAULT_SCHEMA_RETRIES);
+                            let mut attempt = 0;
+                            let mut corrections: Vec<String> = Vec::new();
+                            loop {
+                                let response = execution.output_payload.clone().unwrap_or_default();
+                                let violations = output_schema::validate_response(schema, &response);
+                                if violations.is_empty() {
+                                    break;
+                                }
+                                if DEBUG_STEP_EXECUTION {
+                                    eprintln!("⚠️  Prompt step {} output violates schema ({} issue(s), attempt {})",
+                                        config.order_index, violations.len(), attempt + 1);
+                                }
+                                if attempt >= max_retries {
+                                    let issues = violations
+                                        .iter()
+                                        .map(ToString::to_string)
+                                        .collect::<Vec<_>>()
+                                        .join("; ");
+                                    step_incident = Some(governance::Incident {
+                                        kind: "schema_violation".into(),
+                                        severity: "error".into(),
+                                        details: format!(
+                                            "Output of checkpoint {} does not conform to output schema sha256:{} after {} attempt(s): {}",
+                                            config.id,
+                                            output_schema::schema_sha256(schema),
+                                            attempt + 1,
+                                            issues
+                                        ),
+                                    });
+                                    break;
+                                }
+                                attempt += 1;
+                                let correction_prompt = output_schema::build_correction_prompt(
+                                    &final_prompt,
+                                    schema,
+                                    &response,
+                                    &violations,
+                                );
+                                let retry = run_prompt(&correction_prompt)?;
+                                corrections.push(correction_prompt);
+                                // Every attempt is billed against the step
+                                let usage = TokenUsage {
+                                    prompt_tokens: execution.usage.prompt_tokens + retry.usage.prompt_tokens,
+                                    completion_tokens: execution.usage.completion_tokens
+                                        + retry.usage.completion_tokens,
+                                };
+                                // The output answers the original prompt and every correction
+                                let inputs = serde_json::json!({
+                                    "prompt": &final_prompt,
+                                    "corrections": &corrections,
+                                });
+                                execution = NodeExecution {
+                                    inputs_sha256: Some(provenance::sha256_hex(
+                                        &provenance::canonical_json(&inputs),
+                                    )),
+                                    usage,
+                                    ..retry
+                                };
+                            }
+                        }
+
+                        execution
+                    }
+                    StepConfig::Map {
+                        source_step,
+                        model,
+                        prompt_template,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let source = prior_outputs.get(&source_step).ok_or_else(|| {
+                            anyhow!(
+                                "Step {} references non-existent source step {}",
+                                config.order_index,
+                                source_step
+                            )
+                        })?;
+                        let template = invocation.render(&prompt_template);
+
+                        match output_schema::parse_structured_output(&source.output_text) {
+                            Ok(Value::Array(items)) => {
+                                if DEBUG_STEP_EXECUTION {
+                                    eprintln!("🗂️  Map step {} fanning out over {} element(s) from step {}",
+                                        config.order_index, items.len(), source_step);
+                                }
+                                let mut rendered_prompts = Vec::with_capacity(items.len());
+                                let mut element_outputs = Vec::with_capacity(items.len());
+                                let mut usage = TokenUsage {
+                                    prompt_tokens: 0,
+                                    completion_tokens: 0,
+                                };
+                                for (item_index, item) in items.iter().enumerate() {
+                                    let element_prompt = render_map_prompt(&template, item, item_index);
+                                    let child = execute_model_prompt(
+                                        &model,
+                                        stored_run.seed,
+                                        config.order_index,
+                                        &element_prompt,
+                                        sampling,
+                                        llm_client,
+                                    )?;
+                                    usage.prompt_tokens += child.usage.prompt_tokens;
+                                    usage.completion_tokens += child.usage.completion_tokens;
+                                    rendered_prompts.push(element_prompt);
+                                    element_outputs.push(child.output_payload.clone().unwrap_or_default());
+                                    child_executions.push(child);
+
+                                    // Stop fanning out once the shared step budget is spent
+                                    if usage.total() > config.token_budget {
+                                        break;
+                                    }
+                                }
+                                aggregate_map_execution(&prompt_template, &rendered_prompts, &element_outputs, usage)
+                            }
+                            Ok(other) => {
+                                step_incident = Some(map_input_incident(
+                                    config,
+                                    source_step,
+                                    &format!("expected a JSON array, got {}", json_type_name(&other)),
+                                ));
+                                empty_execution()
+                            }
+                            Err(err) => {
+                                step_incident = Some(map_input_incident(config, source_step, &err));
+                                empty_execution()
+                            }
+                        }
+                    }
+                    StepConfig::Branch {
+                        source_step,
+                        predicate,
+                        branches,
+                    } => {
+                        let source = prior_outputs.get(&source_step).ok_or_else(|| {
+                            anyhow!(
+                                "Step {} references non-existent source step {}",
+                                config.order_index,
+                                source_step
+                            )
+                        })?;
+                        // Branches may only gate steps that come after them
+                        if let Some(backward) = branches
+                            .values()
+                            .flatten()
+                            .find(|step| **step as i64 <= config.order_index)
+                        {
+                            return Err(anyhow!(
+                                "Branch step {} cannot select earlier step {}",
+                                config.order_index,
+                                backward
+                            ));
+                        }
+
+                        let (outcome, classifier) = match &predicate {
+                            branching::BranchPredicate::LlmClassification {
+                                model,
+                                labels,
+                                instructions,
+                            } => {
+                                let prompt = branching::build_classification_prompt(
+                                    labels,
+                                    instructions.as_deref(),
+                                    &source.output_text,
+                                );
+                                let classification = execute_model_prompt(
+                                    model,
+                                    stored_run.seed,
+                                    config.order_index,
+                                    &prompt,
+                                    sampling,
+                                    llm_client,
+                                )?;
+                                let response = classification.output_payload.clone().unwrap_or_default();
+                                let outcome = branching::match_label(labels, &response).ok_or_else(|| {
+                                    format!("classifier response matched none of the labels {:?}", labels)
+                                });
+                                (outcome, Some((classification, response)))
+                            }
+                            deterministic => (
+                                branching::evaluate_deterministic(deterministic, &source.output_text),
+                                None,
+                            ),
+                        };
+
+                        match outcome {
+                            Ok(outcome) => {
+                                let (selected_steps, newly_skipped) =
+                                    branching::resolve_groups(&branches, &outcome);
+                                if DEBUG_STEP_EXECUTION {
+                                    eprintln!("🔀 Branch step {} outcome '{}' selects {:?}, skips {:?}",
+                                        config.order_index, outcome, selected_steps, newly_skipped);
+                                }
+                                skipped_steps.extend(newly_skipped.iter().copied());
+                                let decision = branching::BranchDecision {
+                                    predicate: predicate.clone(),
+                                    source_step,
+                                    source_outputs_sha256: source.outputs_sha256.clone(),
+                                    outcome,
+                                    selected_steps,
+                                    skipped_steps: newly_skipped,
+                                    classifier_response: classifier.as_ref().map(|(_, response)| response.clone()),
+                                };
+                                branch_execution(&decision, classifier.map(|(execution, _)| execution))?
+                            }
+                            Err(reason) => {
+                                step_incident = Some(governance::Incident {
+                                    kind: "branch_evaluation_failed".into(),
+                                    severity: "error".into(),
+                                    details: format!(
+                                        "Branch checkpoint {} could not evaluate its {} predicate on step {}: {}",
+                                        config.id,
+                                        predicate.kind(),
+                                        source_step,
+                                        reason
+                                    ),
+                                });
+                                classifier
+                                    .map(|(execution, _)| execution)
+                                    .unwrap_or_else(empty_execution)
+                            }
+                        }
+                    }
+                    StepConfig::Plugin {
+                        plugin,
+                        step,
+                        config: plugin_config,
+                        source_step,
+                        source_path,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let source_text = match source_step {
+                            Some(source_idx) => {
+                                let source = prior_outputs.get(&source_idx).ok_or_else(|| {
+                                    anyhow!(
+                                        "Step {} references non-existent source step {}",
+                                        config.order_index,
+                                        source_idx
+                                    )
+                                })?;
+                                Some(extract_text_from_output(source)?)
+                            }
+                            None => None,
+                        };
+                        execute_plugin_checkpoint(
+                            &plugin,
+                            &step,
+                            &plugin_config,
+                            stored_run.seed,
+                            source_text.as_deref(),
+                            source_path.as_deref(),
+                        )?
+                    }
+                    StepConfig::Agent {
+                        model,
+                        prompt,
+                        tools,
+                        source_step,
+                        file_roots,
+                        max_turns,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let corpus = match source_step {
+                            Some(source_idx) => {
+                                let source = prior_outputs.get(&source_idx).ok_or_else(|| {
+                                    anyhow!(
+                                        "Step {} references non-existent source step {}",
+                                        config.order_index,
+                                        source_idx
+                                    )
+                                })?;
+                                Some(extract_text_from_output(source)?)
+                            }
+                            None => None,
+                        };
+                        let task = invocation.render(&prompt);
+                        let agent_run = execute_agent_checkpoint(
+                            &AgentTask {
+                                model: &model,
+                                task: &task,
+                                tools: &tools,
+                                corpus: corpus.as_deref(),
+                                file_roots: &file_roots,
+                                max_turns: max_turns.unwrap_or(agent::DEFAULT_MAX_TURNS),
+                                token_budget: config.token_budget,
+                            },
+                            stored_run.seed,
+                            config.order_index,
+                            sampling,
+                            llm_client,
+                        )?;
+                        step_incident = agent_run.incident;
+                        child_executions = agent_run.turns;
+                        agent_run.execution
+                    }
+                    StepConfig::Embed {
+                        source_step,
+                        model,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let source = prior_outputs.get(&source_step).ok_or_else(|| {
+                            anyhow!(
+                                "Step {} references non-existent source step {}",
+                                config.order_index,
+                                source_step
+                            )
+                        })?;
+                        let source_text = extract_text_from_output(source)?;
+                        let (execution, chunks) =
+                            execute_embed_checkpoint(&model, &source_text, llm_client)?;
+                        step_embeddings = Some((model, chunks));
+                        execution
+                    }
+                    StepConfig::Evaluate {
+                        candidate_step,
+                        reference_step,
+                        reference_checkpoint_id,
+                        embedding_model,
+                        judge_model,
+                        judge_criteria,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let source_text = |source_step: usize| {
+                            let source = prior_outputs.get(&source_step).ok_or_else(|| {
+                                anyhow!(
+                                    "Step {} references non-existent source step {}",
+                                    config.order_index,
+                                    source_step
+                                )
+                            })?;
+                            extract_text_from_output(source)
+                        };
+                        let candidate = source_text(candidate_step)?;
+                        let reference = match (reference_step, reference_checkpoint_id.as_deref()) {
+                            (Some(reference_step), _) => source_text(reference_step)?,
+                            (None, Some(checkpoint_id)) => {
+                                load_reference_output(conn, &stored_run.project_id, checkpoint_id)?
+                            }
+                            (None, None) => {
+                                return Err(anyhow!(
+                                    "Step {} has no reference to evaluate against",
+                                    config.order_index
+                                ))
+                            }
+                        };
+                        let evaluation_run = execute_evaluate_checkpoint(
+                            &EvaluationTask {
+                                candidate: &candidate,
+                                reference: &reference,
+                                reference_checkpoint_id: reference_checkpoint_id.as_deref(),
+                                embedding_model: embedding_model.as_deref(),
+                                judge_model: judge_model.as_deref(),
+                                judge_criteria: judge_criteria.as_deref(),
+                            },
+                            stored_run.seed,
+                            config.order_index,
+                            sampling,
+                            llm_client,
+                        )?;
+                        child_executions.extend(evaluation_run.judge_call);
+                        evaluation_run.execution
+                    }
+                    StepConfig::FineTune {
+                        base_model,
+                        command,
+                        hyperparameters,
+                        datasets,
+                        output_weights,
+                        loss_log,
+                        register_as,
+                        timeout_secs,
+                        token_budget: _,
+                        proof_mode: _,
+                        epsilon: _,
+                    } => {
+                        let job = finetune::FineTuneJob {
+                            base_model: &base_model,
+                            command: &command,
+                            hyperparameters: &hyperparameters,
+                            datasets: &datasets,
+                            output_weights: &output_weights,
+                            loss_log: loss_log.as_deref(),
+                            timeout: Duration::from_secs(
+                                timeout_secs.unwrap_or(finetune::DEFAULT_TIMEOUT_SECS),
+                            ),
+                        };
+                        let outcome = finetune::authorize(&policy, &job).map(|program| {
+                            finetune::run_job(&job, &program)
+                        });
+                        match outcome {
+                            Err(incident) => {
+                                step_incident = Some(incident);
+                                empty_execution()
+                            }
+                            Ok(Ok(outcome)) => {
+                                if let Some(model_id) = register_as.as_deref() {
+                                    store::model_weights::register(
+                                        conn,
+                                        model_id,
+                                        &outcome.manifest.weights_sha256,
+                                        Some(&outcome.manifest.weights_path),
+                                        None,
+                                    )?;
+                                }
+                                let (execution, artifacts) = finetune_executions(&outcome)?;
+                                child_executions = artifacts;
+                                execution
+                            }
+                            Ok(Err(err)) => {
+                                step_incident = Some(governance::Incident {
+                                    kind: "training_failed".into(),
+                                    severity: "error".into(),
+                                    details: format!(
+                                        "Fine-tune checkpoint {} failed: {:#}",
+                                        config.id, err
+                                    ),
+                                });
+                                empty_execution()
+                            }
+                        }
+                    }
+                    }
+                }
+                Err(parse_err) => {
+                    if DEBUG_STEP_EXECUTION {
+                        eprintln!("❌ Failed to parse as typed step: {}", parse_err);
+                        eprintln!("   Falling back to legacy execution");
+                    }
+                    // Not a typed config, use legacy execution
+                    execute_checkpoint(&parameterized_step(config, invocation), stored_run.seed, llm_client, &policy)?
+                }
+            }
+        } else {
+            // No config_json, use legacy execution
+            execute_checkpoint(&parameterized_step(config, invocation), stored_run.seed, llm_client, &policy)?
+        };
+
+        // A generation stopped for budget keeps its partial output as the
+        // incident's payload
+        if let Some(stop) = step_client.budget_stop() {
+            step_incident.get_or_insert(governance::stream_budget_incident(
+                &format!("Step {}", config.order_index),
+                config.token_budget,
+                stop.usage_tokens,
+                &stop.partial_output_sha256,
+            ));
+        }
+
+        let total_usage = execution.usage.total();
+        cumulative_usage_tokens = cumulative_usage_tokens.saturating_add(total_usage);
+        let pricing = step_pricing_model(config);
+        let step_cost = governance::CheckpointCost::priced(total_usage, pricing.as_deref());
+        run_usage_usd += step_cost.usd;
+        run_usage_nature_cost += step_cost.nature_cost;
+        add_model_usage(
+            &mut run_usage_by_model,
+            pricing.as_deref(),
+            governance::ModelUsage {
+                tokens: total_usage,
+                usd: step_cost.usd,
+                nature_cost: step_cost.nature_cost,
+            },
+        );
+        let prompt_tokens = execution.usage.prompt_tokens;
+        let completion_tokens = execution.usage.completion_tokens;
+        let mut incident_value: Option<serde_json::Value> = None;
+
+        // Incidents recorded for the step, answered once its usage is committed
+        let mut recorded_incidents: Vec<(String, governance::Incident)> = Vec::new();
+
+        // Blocked outputs are recorded as an incident without their payloads
+        let mut withhold_output = false;
+        let mut content_warning: Option<governance::Incident> = None;
+        if !policy.content_rules.is_disabled() {
+            let mut output_findings: Vec<content_policy::Finding> = Vec::new();
+            for text in std::iter::once(&execution)
+                .chain(child_executions.iter())
+                .filter_map(|node| node.output_payload.as_deref())
+            {
+                for finding in content_policy::scan(
+                    &policy.content_rules,
+                    text,
+                    content_policy::ScanTarget::Output,
+                ) {
+                    if !output_findings.iter().any(|seen| seen.pattern == finding.pattern) {
+                        output_findings.push(finding);
+                    }
+                }
+            }
+            match content_policy::findings_incident(
+                &policy.content_rules,
+                &format!("Step {}", config.order_index),
+                &output_findings,
+            ) {
+                Some((content_policy::ContentAction::Block, content_incident)) => {
+                    withhold_output = true;
+                    child_executions.clear();
+                    step_incident.get_or_insert(content_incident);
+                }
+                Some((_, content_incident)) => content_warning = Some(content_incident),
+                None => {}
+            }
+        }
+
+        let budget_outcome = governance::enforce_budget(config.token_budget, total_usage)
+            .and_then(|_| step_incident.map_or(Ok(()), Err));
+
+        let (kind, inputs_sha, outputs_sha, semantic_digest) = match budget_outcome {
+            Ok(_) => {
+                let semantic = if config.proof_mode.is_concordant() {
+                    Some(execution.semantic_digest.clone().ok_or_else(|| {
+                        anyhow!("semantic digest missing for concordant checkpoint")
+                    })?)
+                } else {
+                    None
+                };
+                (
+                    "Step",
+                    execution.inputs_sha256.as_deref(),
+                    execution.outputs_sha256.as_deref(),
+                    semantic,
+                )
+            }
+            Err(ref incident) => {
+                incident_value = Some(serde_json::to_value(incident)?);
+                ("Incident", None, None, None)
+            }
+        };
+
+        let sampling_json = execution.sampling.as_ref().map(serde_json::to_string).transpose()?;
+        let checkpoint_insert = CheckpointInsert {
+            run_id,
+            run_execution_id: execution_record.id.as_str(),
+            checkpoint_config_id: Some(config.id.as_str()),
+            parent_checkpoint_id: None,
+            turn_index: None,
+            kind,
+            timestamp: &timestamp,
+            incident: incident_value.as_ref(),
+            inputs_sha256: inputs_sha,
+            outputs_sha256: outputs_sha,
+            prev_chain: prev_chain.as_str(),
+            usage_tokens: total_usage,
+            prompt_tokens,
+            completion_tokens,
             semantic_digest: semantic_digest.as_deref(),
             prompt_payload: execution.prompt_payload.as_deref(),
-            output_payload: execution.output_payload.as_deref(),
+            output_payload: if withhold_output {
+                None
+            } else {
+                execution.output_payload.as_deref()
+            },
+            sampling_json: sampling_json.as_deref(),
+            cache_hit: execution.cached,
+            response_metadata: execution.response_metadata.as_ref(),
+            run_inputs_sha256: first_checkpoint_inputs(&prev_chain),
+            output_schema_sha256: output_schema_sha256.as_deref(),
+            cost: (kind == "Step").then_some(step_cost),
             message: None,
         };
 
-        let persisted = persist_checkpoint(&tx, &signing_key, &checkpoint_insert)?;
-        prev_chain = persisted.curr_chain;
+        let persisted = persist_checkpoint(conn, signing_key, &checkpoint_insert)?;
+        prev_chain = persisted.curr_chain;
+        if let Err(incident) = budget_outcome {
+            recorded_incidents.push((persisted.id.clone(), incident));
+        }
+
+        if let Some((model, chunks)) = step_embeddings.as_ref().filter(|_| kind == "Step") {
+            store::embeddings::insert_for_checkpoint(
+                conn,
+                &persisted.id,
+                &execution_record.id,
+                &config.id,
+                model,
+                chunks,
+            )?;
+        }
+
+        // Map elements, summary chunks and agent turns are chained after the step
+        // checkpoint and linked to it as children
+        for (item_index, child) in child_executions.iter().enumerate() {
+            let child_timestamp = Utc::now().to_rfc3339();
+            let child_semantic = if config.proof_mode.is_concordant() {
+                child.semantic_digest.as_deref()
+            } else {
+                None
+            };
+            let child_sampling_json = child.sampling.as_ref().map(serde_json::to_string).transpose()?;
+            let child_insert = CheckpointInsert {
+                run_id,
+                run_execution_id: execution_record.id.as_str(),
+                checkpoint_config_id: Some(config.id.as_str()),
+                parent_checkpoint_id: Some(persisted.id.as_str()),
+                turn_index: Some(u32::try_from(item_index)?),
+                kind: "Step",
+                timestamp: &child_timestamp,
+                incident: None,
+                inputs_sha256: child.inputs_sha256.as_deref(),
+                outputs_sha256: child.outputs_sha256.as_deref(),
+                prev_chain: prev_chain.as_str(),
+                usage_tokens: child.usage.total(),
+                prompt_tokens: child.usage.prompt_tokens,
+                completion_tokens: child.usage.completion_tokens,
+                semantic_digest: child_semantic,
+                prompt_payload: child.prompt_payload.as_deref(),
+                output_payload: child.output_payload.as_deref(),
+                sampling_json: child_sampling_json.as_deref(),
+                cache_hit: child.cached,
+                response_metadata: child.response_metadata.as_ref(),
+                run_inputs_sha256: first_checkpoint_inputs(&prev_chain),
+                output_schema_sha256: None,
+                // Already included in the cost of the step
+                cost: None,
+                message: None,
+            };
+            prev_chain = persist_checkpoint(conn, signing_key, &child_insert)?.curr_chain;
+        }
+
+        if let Some(content_warning) = content_warning {
+            let warning_persisted = persist_incident_checkpoint(
+                conn,
+                signing_key,
+                execution_record,
+                Some(config.id.as_str()),
+                &timestamp,
+                &prev_chain,
+                run_inputs_sha256.as_deref(),
+                &content_warning,
+            )?;
+            prev_chain = warning_persisted.curr_chain;
+            recorded_incidents.push((warning_persisted.id, content_warning));
+        }
+
+        store::project_usage_ledgers::increment(
+            conn,
+            &stored_run.project_id,
+            stored_run.policy_version,
+            total_usage,
+            step_cost.usd,
+            step_cost.nature_cost,
+        )?;
+        notifications::check_budget_alerts(
+            conn,
+            &stored_run.project_id,
+            stored_run.policy_version,
+        );
+        committed.tokens = committed.tokens.saturating_add(total_usage);
+        committed.usd += step_cost.usd;
+        committed.nature_cost += step_cost.nature_cost;
+
+        let mut proceed = true;
+        for (checkpoint_id, incident) in &recorded_incidents {
+            if !respond_to_incident(checkpoint_id, incident)? {
+                proceed = false;
+                break;
+            }
+        }
+        if !proceed {
+            break;
+        }
+
+        if kind == "Incident" {
+            incident_steps.insert(config.order_index as usize);
+            continue;
+        }
+
+        // Store step output for chaining (only if execution was successful)
+        if kind == "Step" {
+            let step_output = StepOutput {
+                order_index: config.order_index as usize,
+                step_type: config.step_type.clone(),
+                output_text: execution.output_payload.clone().unwrap_or_default(),
+                output_json: execution.output_payload.as_ref().and_then(|s| serde_json::from_str(s).ok()),
+                outputs_sha256: execution.outputs_sha256.clone().unwrap_or_default(),
+            };
+            // Persisted so later executions can chain from this checkpoint
+            if !step_output.outputs_sha256.is_empty() {
+                crate::attachments::get_global_attachment_store()
+                    .save_step_output(&step_output.outputs_sha256, &step_output.output_text)?;
+            }
+            prior_outputs.insert(config.order_index as usize, step_output);
+            document_lineage.insert(config.order_index as usize, documents);
+            privacy_lineage.insert(config.order_index as usize, privacy_statuses);
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove a failed execution and return the usage it recorded to the ledger
+fn discard_execution(
+    conn: &mut Connection,
+    stored_run: &StoredRun,
+    execution_id: &str,
+    committed: &LedgerDelta,
+) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "DELETE FROM checkpoint_payloads WHERE checkpoint_id IN (SELECT id FROM checkpoints WHERE run_execution_id = ?1)",
+        params![execution_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM checkpoint_messages WHERE checkpoint_id IN (SELECT id FROM checkpoints WHERE run_execution_id = ?1)",
+        params![execution_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM checkpoints WHERE run_execution_id = ?1",
+        params![execution_id],
+    )?;
+
+    tx.execute("DELETE FROM run_executions WHERE id = ?1", params![execution_id])?;
+
+    store::project_usage_ledgers::release(
+        tx.deref(),
+        &stored_run.project_id,
+        stored_run.policy_version,
+        committed.tokens,
+        committed.usd,
+        committed.nature_cost,
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn clone_run(pool: &DbPool, source_run_id: &str) -> anyhow::Result<String> {
+    let source_run = {
+        let conn = pool.get()?;
+        load_stored_run(&conn, source_run_id)?
+    };
+
+    if source_run.steps.is_empty() {
+        return Err(anyhow!(
+            "Cannot clone a run with no checkpoints. Add a checkpoint before cloning."
+        ));
+    }
+
+    let spec_templates = step_templates(&source_run.steps);
+
+    let clone_name = format!("{} (clone)", source_run.name);
+    create_run(
+        pool,
+        &source_run.project_id,
+        &clone_name,
+        source_run.proof_mode.unwrap_or_default(),
+        source_run.epsilon,
+        source_run.seed,
+        source_run.token_budget,
+        &source_run.default_model,
+        spec_templates,
+    )
+}
+
+/// Templates that recreate the given stored steps in a new run
+pub(crate) fn step_templates(steps: &[RunStep]) -> Vec<RunStepTemplate> {
+    steps
+        .iter()
+        .map(|cfg| RunStepTemplate {
+            step_type: cfg.step_type.clone(),
+            model: cfg.model.clone(),
+            prompt: cfg.prompt.clone(),
+            token_budget: cfg.token_budget,
+            proof_mode: cfg.proof_mode,
+            epsilon: cfg.epsilon,
+            config_json: cfg.config_json.clone(),
+            order_index: Some(cfg.order_index),
+            checkpoint_type: cfg.checkpoint_type.clone(),
+        })
+        .collect()
+}
+
+/// Truncate a string to a maximum size for database storage
+fn truncate_payload(content: &str, max_size: usize) -> String {
+    if content.len() <= max_size {
+        return content.to_string();
+    }
+
+    let truncated = &content[..max_size];
+    format!("{}... [TRUNCATED - {} total bytes]", truncated, content.len())
+}
+
+/// Execute a document ingestion checkpoint. The source must pass the
+/// project's filesystem sandbox once symlinks are resolved.
+pub(crate) fn execute_document_ingestion_checkpoint(
+    config_json: &str,
+    policy: &store::policies::Policy,
+) -> anyhow::Result<NodeExecution> {
+    use crate::document_processing;
+
+    // Parse the configuration
+    let ingestion_config: DocumentIngestionConfig = serde_json::from_str(config_json)
+        .context("Failed to parse document ingestion config")?;
+    document_processing::ensure_sandboxed(
+        policy,
+        std::path::Path::new(&ingestion_config.source_path),
+    )?;
+
+    // Process the document based on format
+    let canonical_doc = match ingestion_config.format.to_lowercase().as_str() {
+        "pdf" => {
+            document_processing::process_pdf_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "tex" | "latex" => {
+            document_processing::process_latex_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "txt" => {
+            document_processing::process_txt_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "docx" | "doc" => {
+            document_processing::process_docx_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "html" | "htm" => {
+            document_processing::process_html_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "epub" => {
+            document_processing::process_epub_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "md" | "markdown" => {
+            document_processing::process_markdown_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        "ipynb" => {
+            document_processing::process_ipynb_to_canonical(
+                &ingestion_config.source_path,
+                Some(ingestion_config.privacy_status.clone())
+            )?
+        }
+        unsupported => {
+            return Err(anyhow!(
+                "Unsupported document format: {}. Supported formats: pdf, latex, txt, docx, html, epub, markdown, ipynb",
+                unsupported
+            ));
+        }
+    };
+
+    // Serialize to JSON
+    let canonical_json = serde_json::to_string_pretty(&canonical_doc)
+        .context("Failed to serialize canonical document")?;
+
+    // Create preview for database storage
+    let preview = truncate_payload(&canonical_json, MAX_PAYLOAD_PREVIEW_SIZE);
+
+    // Compute provenance hashes
+    let inputs_sha256 = provenance::sha256_hex(ingestion_config.source_path.as_bytes());
+
+    // For deterministic hashing, create a normalized version without timestamps
+    let mut normalized_doc = canonical_doc.clone();
+    normalized_doc.processing_log.extraction_timestamp_utc = Some("NORMALIZED".to_string());
+    normalized_doc.processing_log.processing_timestamp_utc = "NORMALIZED".to_string();
+    normalized_doc.metadata.date_accessed_utc = Some("NORMALIZED".to_string());
+
+    let normalized_json = serde_json::to_string(&normalized_doc)
+        .context("Failed to serialize normalized document")?;
+    let outputs_sha256 = provenance::sha256_hex(normalized_json.as_bytes());
+
+    // Compute semantic digest from cleaned text content
+    let semantic_digest = provenance::semantic_digest(&normalized_doc.cleaned_text_with_markdown_structure);
+
+    // Create input description
+    let prompt_payload = format!(
+        "Document: {} (format: {}, privacy: {})",
+        ingestion_config.source_path,
+        ingestion_config.format,
+        ingestion_config.privacy_status
+    );
+
+    Ok(NodeExecution {
+        inputs_sha256: Some(inputs_sha256),
+        outputs_sha256: Some(outputs_sha256),
+        semantic_digest: Some(semantic_digest),
+        usage: TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: Some(prompt_payload),
+        output_payload: Some(preview),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    })
+}
+
+/// Extract text content from a step output
+/// For ingest steps: extracts cleaned_text from CanonicalDocument
+/// For LLM steps: uses the output_text directly
+fn extract_text_from_output(output: &StepOutput) -> anyhow::Result<String> {
+    // If output is CanonicalDocument JSON, extract cleaned text
+    if let Some(json) = &output.output_json {
+        if let Some(cleaned_text) = json.get("cleaned_text_with_markdown_structure") {
+            if let Some(text) = cleaned_text.as_str() {
+                return Ok(text.to_string());
+            }
+        }
+    }
+
+    // Otherwise just use the text output
+    Ok(output.output_text.clone())
+}
+
+/// Instructions a summary prompt starts with, based on summary type
+pub(crate) fn summary_instructions<'a>(
+    summary_type: &str,
+    custom_instructions: Option<&'a str>,
+) -> &'a str {
+    match summary_type {
+        "brief" => "Provide a brief 2-3 sentence summary of the following:\n\n",
+        "detailed" => "Provide a comprehensive summary covering all main points of:\n\n",
+        "academic" => "Provide an academic summary including methodology, findings, and conclusions of:\n\n",
+        "custom" => custom_instructions.unwrap_or("Summarize the following:\n\n"),
+        _ => "Summarize the following:\n\n",
+    }
+}
+
+/// Tokens kept free for the answer when the catalog gives no output limit
+const SUMMARY_OUTPUT_RESERVE_TOKENS: u64 = 1_024;
+
+/// Prompt tokens a summary may send to `model`: its catalog context window
+/// less room for the answer. None when the catalog has no window for it.
+pub(crate) fn summary_prompt_limit(model: &str) -> Option<u64> {
+    let model_def = crate::model_catalog::try_get_global_catalog()?.get_model(model)?;
+    let window = u64::from(model_def.context_window?);
+    let reserve = model_def
+        .max_output_tokens
+        .map_or(SUMMARY_OUTPUT_RESERVE_TOKENS, u64::from)
+        .min(window / 2);
+    Some(window - reserve)
+}
+
+/// Split text into consecutive pieces of at most `max_tokens` estimated
+/// tokens, cutting at paragraph, line or word breaks where possible.
+/// Concatenating the pieces gives back the text.
+fn split_for_context(text: &str, max_tokens: u64) -> Vec<&str> {
+    let max_bytes = usize::try_from(max_tokens.saturating_mul(4))
+        .unwrap_or(usize::MAX)
+        .max(4);
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let window = &rest[..end];
+        // Only take a break in the second half, so pieces stay close to the limit
+        let cut = ["\n\n", "\n", " "]
+            .iter()
+            .find_map(|separator| {
+                window
+                    .rfind(separator)
+                    .filter(|at| *at > end / 2)
+                    .map(|at| at + separator.len())
+            })
+            .unwrap_or(end);
+        pieces.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+/// Summarize step whose prompt does not fit the model's context window
+pub(crate) struct SummaryTask<'a> {
+    pub(crate) model: &'a str,
+    pub(crate) instructions: &'a str,
+    pub(crate) source_text: &'a str,
+    /// Prompt tokens the model accepts, from `summary_prompt_limit`
+    pub(crate) prompt_limit: u64,
+}
+
+/// Outcome of a chunked summary: the final combining call as the step-level
+/// execution, carrying the usage of every call, and one execution per chunk
+/// summary in the order they ran
+pub(crate) struct ChunkedSummary {
+    pub(crate) execution: NodeExecution,
+    pub(crate) chunks: Vec<NodeExecution>,
+}
+
+/// Map-reduce summary: each window-sized chunk of the source is summarized on
+/// its own, then the chunk summaries are summarized together. Chunk summaries
+/// that still overflow the window are chunked and summarized again.
+pub(crate) fn execute_chunked_summary(
+    task: &SummaryTask<'_>,
+    run_seed: u64,
+    order_index: i64,
+    sampling: Option<SamplingOptions>,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<ChunkedSummary> {
+    // Room for the instructions and the part header of every chunk prompt
+    let overhead = estimate_tokens(task.instructions) + 32;
+    if overhead > task.prompt_limit / 2 {
+        return Err(anyhow!(
+            "Summarize step {} instructions leave no room for source text in the context window of {}",
+            order_index,
+            task.model
+        ));
+    }
+    let chunk_tokens = task.prompt_limit - overhead;
+
+    let mut chunks = Vec::new();
+    let mut usage = TokenUsage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+    };
+    let mut text = task.source_text.to_string();
+    loop {
+        let pieces = split_for_context(&text, chunk_tokens);
+        let mut summaries = Vec::with_capacity(pieces.len());
+        for (piece_index, piece) in pieces.iter().enumerate() {
+            let prompt = format!(
+                "This is part {} of {} of a longer text.\n{}{}",
+                piece_index + 1,
+                pieces.len(),
+                task.instructions,
+                piece
+            );
+            let chunk = execute_model_prompt(
+                task.model,
+                run_seed,
+                order_index,
+                &prompt,
+                sampling,
+                llm_client,
+            )?;
+            usage.prompt_tokens += chunk.usage.prompt_tokens;
+            usage.completion_tokens += chunk.usage.completion_tokens;
+            summaries.push(chunk.output_payload.clone().unwrap_or_default());
+            chunks.push(chunk);
+        }
+
+        let combined = summaries.join("\n\n");
+        let prompt = format!(
+            "The following are summaries of consecutive parts of one text. Combine them into a single summary.\n{}{}",
+            task.instructions, combined
+        );
+        if estimate_tokens(&prompt) <= task.prompt_limit {
+            let mut execution = execute_model_prompt(
+                task.model,
+                run_seed,
+                order_index,
+                &prompt,
+                sampling,
+                llm_client,
+            )?;
+            usage.prompt_tokens += execution.usage.prompt_tokens;
+            usage.completion_tokens += execution.usage.completion_tokens;
+            execution.usage = usage;
+            return Ok(ChunkedSummary { execution, chunks });
+        }
+        if combined.len() >= text.len() {
+            return Err(anyhow!(
+                "Summarize step {} chunk summaries do not shrink to fit the context window of {}",
+                order_index,
+                task.model
+            ));
+        }
+        text = combined;
+    }
+}
+
+/// Variables a template prompt renders against: the run parameters by name,
+/// the run inputs under `inputs` and the used output under `source`
+fn prompt_template_context(invocation: &RunInvocation, source: Option<&StepOutput>) -> Value {
+    let mut context = invocation.parameters.clone();
+    context.insert("inputs".to_string(), Value::Object(invocation.inputs.clone()));
+    if let Some(source) = source {
+        context.insert(
+            "source".to_string(),
+            serde_json::json!({
+                "step": source.order_index,
+                "output": source.output_text,
+                "json": source.output_json,
+                "sha256": source.outputs_sha256,
+            }),
+        );
+    }
+    Value::Object(context)
+}
+
+/// Build prompt with context from previous step
+fn build_prompt_with_context(prompt: &str, source: &StepOutput) -> String {
+    format!(
+        "{}\n\n--- Context from previous step ---\n{}",
+        prompt,
+        source.output_text
+    )
+}
+
+/// Run a plugin step. The inputs digest commits to the canonical plugin input,
+/// which names the plugin version and module digest alongside the step's data.
+pub(crate) fn execute_plugin_checkpoint(
+    plugin_id: &str,
+    step_type: &str,
+    plugin_config: &Value,
+    run_seed: u64,
+    source_text: Option<&str>,
+    source_path: Option<&str>,
+) -> anyhow::Result<NodeExecution> {
+    let source_file = source_path
+        .map(|path| {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("Failed to read plugin source file {}", path))?;
+            anyhow::Ok(plugins::PluginSourceFile {
+                path: path.to_string(),
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+            })
+        })
+        .transpose()?;
+
+    let execution = plugins::execute_plugin_step(
+        plugin_id,
+        step_type,
+        plugin_config,
+        run_seed,
+        source_text,
+        source_file,
+    )?;
+
+    Ok(NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(execution.input_json.as_bytes())),
+        outputs_sha256: Some(provenance::sha256_hex(execution.output.as_bytes())),
+        semantic_digest: Some(provenance::semantic_digest(&execution.output)),
+        usage: TokenUsage {
+            prompt_tokens: execution.usage.prompt_tokens,
+            completion_tokens: execution.usage.completion_tokens,
+        },
+        prompt_payload: Some(sanitize_payload(&execution.input_json)),
+        output_payload: Some(sanitize_payload(&execution.output)),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    })
+}
+
+/// Text a chained step receives from a recorded step output
+pub(crate) fn chained_input_text(output_payload: &str) -> String {
+    let output = StepOutput {
+        order_index: 0,
+        step_type: String::new(),
+        output_text: output_payload.to_string(),
+        output_json: serde_json::from_str(output_payload).ok(),
+        outputs_sha256: String::new(),
+    };
+    extract_text_from_output(&output).unwrap_or_else(|_| output_payload.to_string())
+}
+
+/// Local filesystem paths a step reads, checked against the project's path sandbox
+fn step_filesystem_paths(config: &RunStep, invocation: &RunInvocation) -> Vec<String> {
+    let Some(ref config_json) = config.config_json else {
+        return Vec::new();
+    };
+
+    match serde_json::from_str::<StepConfig>(config_json) {
+        Ok(StepConfig::Ingest { source_path, .. }) => vec![invocation.render(&source_path)],
+        Ok(StepConfig::Plugin {
+            source_path: Some(source_path),
+            ..
+        }) => vec![source_path],
+        Ok(StepConfig::Agent { file_roots, .. }) => file_roots,
+        Ok(StepConfig::FineTune {
+            datasets,
+            output_weights,
+            loss_log,
+            ..
+        }) => datasets
+            .into_iter()
+            .chain(std::iter::once(output_weights))
+            .chain(loss_log)
+            .collect(),
+        Ok(_) => Vec::new(),
+        // Legacy document ingestion steps store a bare DocumentIngestionConfig
+        Err(_) if config.is_document_ingestion() => {
+            serde_json::from_str::<DocumentIngestionConfig>(config_json)
+                .map(|ingestion| vec![invocation.render(&ingestion.source_path)])
+                .unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Source documents (sha256 of the file contents) a step reads directly or
+/// derives from through the steps it chains from
+fn step_documents(
+    config: &RunStep,
+    lineage: &std::collections::HashMap<usize, BTreeSet<String>>,
+) -> BTreeSet<String> {
+    let Some(ref config_json) = config.config_json else {
+        return BTreeSet::new();
+    };
+
+    let (direct_paths, sources) = match serde_json::from_str::<StepConfig>(config_json) {
+        Ok(StepConfig::Ingest { source_path, .. }) => (vec![source_path], Vec::new()),
+        Ok(StepConfig::FineTune { datasets, .. }) => (datasets, Vec::new()),
+        Ok(step_config) => {
+            let (_, sources, _) = step_config_references(&step_config);
+            let paths = match step_config {
+                StepConfig::Plugin { source_path, .. } => source_path.into_iter().collect(),
+                _ => Vec::new(),
+            };
+            (paths, sources)
+        }
+        Err(_) if config.is_document_ingestion() => (
+            serde_json::from_str::<DocumentIngestionConfig>(config_json)
+                .map(|ingestion| vec![ingestion.source_path])
+                .unwrap_or_default(),
+            Vec::new(),
+        ),
+        Err(_) => (Vec::new(), Vec::new()),
+    };
+
+    // Unreadable files fail in the step itself
+    let mut documents: BTreeSet<String> = direct_paths
+        .iter()
+        .filter_map(|path| provenance::sha256_file(std::path::Path::new(path)).ok())
+        .collect();
+    for source in sources {
+        if let Some(upstream) = lineage.get(&source) {
+            documents.extend(upstream.iter().cloned());
+        }
+    }
+    documents
+}
+
+/// Privacy statuses of the documents a step ingests, or derives from
+/// through the steps it chains from
+fn step_privacy_statuses(
+    config: &RunStep,
+    lineage: &std::collections::HashMap<usize, BTreeSet<String>>,
+) -> BTreeSet<String> {
+    let Some(ref config_json) = config.config_json else {
+        return BTreeSet::new();
+    };
+
+    let (ingested, sources) = match serde_json::from_str::<StepConfig>(config_json) {
+        Ok(StepConfig::Ingest { privacy_status, .. }) => (Some(privacy_status), Vec::new()),
+        Ok(step_config) => (None, step_config_references(&step_config).1),
+        Err(_) if config.is_document_ingestion() => (
+            serde_json::from_str::<DocumentIngestionConfig>(config_json)
+                .ok()
+                .map(|ingestion| ingestion.privacy_status),
+            Vec::new(),
+        ),
+        Err(_) => (None, Vec::new()),
+    };
+
+    let mut statuses: BTreeSet<String> = ingested.into_iter().collect();
+    for source in sources {
+        if let Some(upstream) = lineage.get(&source) {
+            statuses.extend(upstream.iter().cloned());
+        }
+    }
+    statuses
+}
+
+/// Whether a model needs the network: models the catalog does not list are
+/// assumed to, except the stub
+pub(crate) fn model_requires_network(model_id: &str) -> bool {
+    crate::model_catalog::try_get_global_catalog()
+        .and_then(|catalog| catalog.get_model(model_id))
+        .map(|model_def| model_def.requires_network)
+        .unwrap_or(model_id != STUB_MODEL_ID)
+}
+
+/// Text a step sends onward: its prompt and config with run parameters
+/// applied, followed by the outputs of the steps it chains from
+fn step_input_text(
+    config: &RunStep,
+    invocation: &RunInvocation,
+    prior_outputs: &std::collections::HashMap<usize, StepOutput>,
+) -> String {
+    let mut parts: Vec<String> = config
+        .prompt
+        .iter()
+        .chain(config.config_json.iter())
+        .map(|text| invocation.render(text))
+        .collect();
+    if let Some(step_config) = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<StepConfig>(json).ok())
+    {
+        let (_, sources, _) = step_config_references(&step_config);
+        parts.extend(
+            sources
+                .iter()
+                .filter_map(|source| prior_outputs.get(source))
+                .map(|output| output.output_text.clone()),
+        );
+    }
+    parts.join("\n")
+}
+
+/// Whether a model runs on this machine rather than at a hosted provider.
+/// Models missing from the catalog are assumed to be served by Ollama.
+fn is_local_model(model: &str) -> bool {
+    if model == STUB_MODEL_ID || model.starts_with(CLAUDE_MODEL_PREFIX) {
+        return false;
+    }
+    crate::model_catalog::try_get_global_catalog()
+        .and_then(|catalog| catalog.get_model(model))
+        .is_none_or(|model_def| model_def.provider == "ollama")
+}
+
+/// Runs that load local models or train locally, which execution windows defer
+fn run_uses_local_models(stored_run: &StoredRun) -> bool {
+    stored_run.steps.iter().any(|config| {
+        let model = match config
+            .config_json
+            .as_deref()
+            .map(serde_json::from_str::<StepConfig>)
+        {
+            Some(Ok(StepConfig::FineTune { .. })) => return true,
+            Some(Ok(step_config)) => step_config_references(&step_config).0,
+            _ => config.model.clone(),
+        };
+        model.is_some_and(|model| is_local_model(&model))
+    })
+}
+
+/// Steps whose inputs reach a model, by prompt or by training
+fn step_sends_documents_to_model(config: &RunStep) -> bool {
+    match config
+        .config_json
+        .as_deref()
+        .map(serde_json::from_str::<StepConfig>)
+    {
+        Some(Ok(StepConfig::FineTune { .. })) => true,
+        Some(Ok(step_config)) => step_config_references(&step_config).0.is_some(),
+        _ => false,
+    }
+}
+
+/// Exact-mode steps pin the run seed and greedy decoding so replays reproduce
+/// the recorded output; concordant steps keep the provider's sampling
+fn step_sampling(config: &RunStep, run_seed: u64) -> Option<SamplingOptions> {
+    (!config.proof_mode.is_concordant()).then(|| SamplingOptions::deterministic(run_seed))
+}
+
+/// Execute a prompt against a model (stub, mock, or real LLM)
+fn execute_model_prompt(
+    model: &str,
+    run_seed: u64,
+    order_index: i64,
+    prompt: &str,
+    sampling: Option<SamplingOptions>,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<NodeExecution> {
+    if model == STUB_MODEL_ID {
+        Ok(execute_stub_checkpoint(run_seed, order_index, prompt))
+    } else if model.starts_with(CLAUDE_MODEL_PREFIX) {
+        execute_claude_mock_checkpoint(model, prompt)
+    } else {
+        execute_llm_checkpoint(model, prompt, sampling, llm_client)
+    }
+}
+
+/// Agent step settings resolved for one execution
+pub(crate) struct AgentTask<'a> {
+    pub(crate) model: &'a str,
+    pub(crate) task: &'a str,
+    pub(crate) tools: &'a [agent::AgentTool],
+    pub(crate) corpus: Option<&'a str>,
+    pub(crate) file_roots: &'a [String],
+    pub(crate) max_turns: u32,
+    pub(crate) token_budget: u64,
+}
 
-        if kind == "Incident" {
+/// Outcome of an agent step: the step-level execution, one execution per model
+/// turn and per tool invocation in trajectory order, and any incident that
+/// ended the trajectory
+pub(crate) struct AgentRun {
+    pub(crate) execution: NodeExecution,
+    pub(crate) turns: Vec<NodeExecution>,
+    pub(crate) incident: Option<governance::Incident>,
+}
+
+/// Tool invocations commit to the call (tool name and input) and its result
+fn tool_execution(tool: &str, input: &Value, result: &str) -> NodeExecution {
+    let call = serde_json::json!({ "tool": tool, "input": input });
+    NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&call))),
+        outputs_sha256: Some(provenance::sha256_hex(result.as_bytes())),
+        semantic_digest: Some(provenance::semantic_digest(result)),
+        usage: TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: Some(call.to_string()),
+        output_payload: Some(sanitize_payload(result)),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    }
+}
+
+/// Run the agent loop: each turn the model either calls a tool, whose result is
+/// appended to the next turn's prompt, or gives its final answer.
+pub(crate) fn execute_agent_checkpoint(
+    task: &AgentTask<'_>,
+    run_seed: u64,
+    order_index: i64,
+    sampling: Option<SamplingOptions>,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<AgentRun> {
+    let context = agent::ToolContext {
+        corpus: task.corpus,
+        file_roots: task.file_roots,
+    };
+    let mut transcript: Vec<agent::ToolExchange> = Vec::new();
+    let mut turns = Vec::new();
+    let mut usage = TokenUsage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+    };
+    let mut answer = None;
+    let mut incident = None;
+    // Usage of the most expensive turn so far, the estimate for the next one
+    let mut largest_turn: u64 = 0;
+
+    for turn_number in 0..task.max_turns {
+        // Stop before a turn that would take the trajectory past the step budget
+        if turn_number > 0 && usage.total().saturating_add(largest_turn) > task.token_budget {
+            incident = Some(governance::Incident {
+                kind: "budget_exceeded".into(),
+                severity: "error".into(),
+                details: format!(
+                    "Agent stopped after {} turn(s): {} tokens used, another turn (~{} tokens) would exceed the step budget of {}",
+                    turn_number,
+                    usage.total(),
+                    largest_turn,
+                    task.token_budget
+                ),
+            });
             break;
         }
 
-        // Store step output for chaining (only if execution was successful)
-        if kind == "Step" {
-            let step_output = StepOutput {
-                order_index: config.order_index as usize,
-                step_type: config.step_type.clone(),
-                output_text: execution.output_payload.clone().unwrap_or_default(),
-                output_json: execution.output_payload.as_ref().and_then(|s| serde_json::from_str(s).ok()),
-                outputs_sha256: execution.outputs_sha256.clone().unwrap_or_default(),
-            };
-            prior_outputs.insert(config.order_index as usize, step_output);
-        }
+        let prompt = agent::build_agent_prompt(task.task, task.tools, &transcript);
+        let turn = execute_model_prompt(task.model, run_seed, order_index, &prompt, sampling, llm_client)?;
+        usage.prompt_tokens += turn.usage.prompt_tokens;
+        usage.completion_tokens += turn.usage.completion_tokens;
+        largest_turn = largest_turn.max(turn.usage.total());
+        let response = turn.output_payload.clone().unwrap_or_default();
+        let thought = agent::parse_thought(&response);
+        turns.push(turn);
+
+        let (tool, input) = match agent::parse_action(&response) {
+            agent::AgentAction::Final { answer: final_answer } => {
+                answer = Some(final_answer);
+                break;
+            }
+            agent::AgentAction::ToolCall { tool, input } => (tool, input),
+        };
+        let result = match agent::run_tool(task.tools, &tool, &input, &context) {
+            Ok(output) => output,
+            Err(agent::ToolError::Recoverable(message)) => format!("error: {message}"),
+            Err(agent::ToolError::PathDenied(path)) => {
+                let details = format!(
+                    "Agent tool call read_file denied access to {} outside the step's file roots",
+                    path.display()
+                );
+                turns.push(tool_execution(&tool, &input, &details));
+                incident = Some(governance::Incident {
+                    kind: "filesystem_access_denied".into(),
+                    severity: "error".into(),
+                    details,
+                });
+                break;
+            }
+        };
+        turns.push(tool_execution(&tool, &input, &result));
+        transcript.push(agent::ToolExchange {
+            thought,
+            tool,
+            input,
+            result,
+        });
     }
 
-    store::project_usage_ledgers::increment(
-        tx.deref(),
-        &stored_run.project_id,
-        stored_run.policy_version,
-        cumulative_usage_tokens,
-        run_usage_usd,
-        run_usage_nature_cost,
-    )?;
+    if answer.is_none() && incident.is_none() && usage.total() <= task.token_budget {
+        incident = Some(governance::Incident {
+            kind: "agent_turn_limit".into(),
+            severity: "error".into(),
+            details: format!(
+                "Agent did not give a final answer within {} turn(s)",
+                task.max_turns
+            ),
+        });
+    }
 
-    tx.commit()?;
-    Ok(execution_record)
+    let tool_names: Vec<&str> = task.tools.iter().map(agent::AgentTool::name).collect();
+    let inputs = serde_json::json!({ "task": task.task, "tools": tool_names });
+    let answer = answer.unwrap_or_default();
+    let execution = NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&inputs))),
+        outputs_sha256: Some(provenance::sha256_hex(answer.as_bytes())),
+        semantic_digest: Some(provenance::semantic_digest(&answer)),
+        usage,
+        prompt_payload: Some(sanitize_payload(task.task)),
+        output_payload: Some(sanitize_payload(&answer)),
+        sampling: turns.first().and_then(|turn| turn.sampling),
+        cached: false,
+        response_metadata: None,
+    };
+    Ok(AgentRun {
+        execution,
+        turns,
+        incident,
+    })
 }
 
-pub fn clone_run(pool: &DbPool, source_run_id: &str) -> anyhow::Result<String> {
-    let source_run = {
-        let conn = pool.get()?;
-        load_stored_run(&conn, source_run_id)?
-    };
+/// Evidence checkpoint for one part of a training job
+fn artifact_execution(label: &str, artifact: &Value) -> anyhow::Result<NodeExecution> {
+    let artifact_json = serde_json::to_string(artifact)?;
+    Ok(NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(label.as_bytes())),
+        outputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(artifact))),
+        semantic_digest: Some(provenance::semantic_digest(&artifact_json)),
+        usage: TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: Some(label.to_string()),
+        output_payload: Some(artifact_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    })
+}
 
-    if source_run.steps.is_empty() {
-        return Err(anyhow!(
-            "Cannot clone a run with no checkpoints. Add a checkpoint before cloning."
-        ));
-    }
+/// Step-level execution (the manifest) plus one child per piece of evidence:
+/// hyperparameters, dataset versions, loss curve and weights hash
+fn finetune_executions(
+    outcome: &finetune::FineTuneOutcome,
+) -> anyhow::Result<(NodeExecution, Vec<NodeExecution>)> {
+    let manifest = &outcome.manifest;
+    let manifest_value = serde_json::to_value(manifest)?;
+    let manifest_json = serde_json::to_string(manifest)?;
+    let inputs = serde_json::json!({
+        "baseModel": &manifest.base_model,
+        "command": &manifest.command,
+        "hyperparameters": &manifest.hyperparameters,
+        "datasets": &manifest.datasets,
+    });
+
+    let children = vec![
+        artifact_execution("hyperparameters", &Value::Object(manifest.hyperparameters.clone()))?,
+        artifact_execution("datasets", &serde_json::to_value(&manifest.datasets)?)?,
+        artifact_execution("loss_curve", &serde_json::to_value(&outcome.loss_curve)?)?,
+        artifact_execution(
+            "weights",
+            &serde_json::json!({
+                "path": &manifest.weights_path,
+                "sha256": &manifest.weights_sha256,
+            }),
+        )?,
+    ];
+    let execution = NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&inputs))),
+        outputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&manifest_value))),
+        semantic_digest: Some(provenance::semantic_digest(&manifest_json)),
+        usage: TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: Some(sanitize_payload(&serde_json::to_string(&inputs)?)),
+        output_payload: Some(manifest_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    };
+    Ok((execution, children))
+}
 
-    let spec_templates: Vec<RunStepTemplate> = source_run
-        .steps
-        .iter()
-        .map(|cfg| RunStepTemplate {
-            step_type: cfg.step_type.clone(),
-            model: cfg.model.clone(),
-            prompt: cfg.prompt.clone(),
-            token_budget: cfg.token_budget,
-            proof_mode: cfg.proof_mode,
-            epsilon: cfg.epsilon,
-            config_json: cfg.config_json.clone(),
-            order_index: Some(cfg.order_index),
-            checkpoint_type: cfg.checkpoint_type.clone(),
-        })
+/// Chunk the source text and embed every chunk. The execution records the
+/// embedding manifest; the vectors themselves are returned for storage.
+pub(crate) fn execute_embed_checkpoint(
+    model: &str,
+    source_text: &str,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<(NodeExecution, Vec<embeddings::EmbeddedChunk>)> {
+    let texts = chunk::chunk_text(source_text)?;
+    let (vectors, prompt_tokens) = if texts.is_empty() {
+        (Vec::new(), 0)
+    } else {
+        let embedded = llm_client.embed(model, &texts)?;
+        if embedded.vectors.len() != texts.len() {
+            return Err(anyhow!(
+                "{} returned {} embeddings for {} chunks",
+                model,
+                embedded.vectors.len(),
+                texts.len()
+            ));
+        }
+        (embedded.vectors, embedded.prompt_tokens)
+    };
+    let chunks: Vec<embeddings::EmbeddedChunk> = texts
+        .into_iter()
+        .zip(vectors)
+        .enumerate()
+        .map(|(index, (text, vector))| embeddings::EmbeddedChunk::new(index, text, vector))
         .collect();
 
-    let clone_name = format!("{} (clone)", source_run.name);
-    create_run(
-        pool,
-        &source_run.project_id,
-        &clone_name,
-        source_run.proof_mode.unwrap_or_default(),
-        source_run.epsilon,
-        source_run.seed,
-        source_run.token_budget,
-        &source_run.default_model,
-        spec_templates,
-    )
+    let manifest = embeddings::EmbeddingManifest::new(model, &chunks);
+    let manifest_value = serde_json::to_value(&manifest)?;
+    let manifest_json = serde_json::to_string(&manifest)?;
+    let inputs = serde_json::json!({
+        "model": model,
+        "chunks": chunks.iter().map(|chunk| &chunk.text_sha256).collect::<Vec<_>>(),
+    });
+    let execution = NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&inputs))),
+        outputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&manifest_value))),
+        semantic_digest: Some(provenance::semantic_digest(&manifest_json)),
+        usage: TokenUsage {
+            prompt_tokens,
+            completion_tokens: 0,
+        },
+        prompt_payload: Some(sanitize_payload(source_text)),
+        output_payload: Some(manifest_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    };
+    Ok((execution, chunks))
 }
 
-/// Truncate a string to a maximum size for database storage
-fn truncate_payload(content: &str, max_size: usize) -> String {
-    if content.len() <= max_size {
-        return content.to_string();
-    }
-
-    let truncated = &content[..max_size];
-    format!("{}... [TRUNCATED - {} total bytes]", truncated, content.len())
+/// Output of a checkpoint in the project, used as an evaluation reference
+pub(crate) fn load_reference_output(
+    conn: &Connection,
+    project_id: &str,
+    checkpoint_id: &str,
+) -> anyhow::Result<String> {
+    let output = load_referenced_checkpoint_output(conn, project_id, checkpoint_id)?;
+    Ok(chained_input_text(&output.output_text))
 }
 
-/// Execute a document ingestion checkpoint
-pub(crate) fn execute_document_ingestion_checkpoint(
-    config_json: &str,
-) -> anyhow::Result<NodeExecution> {
-    use crate::document_processing;
+/// Step output of a checkpoint recorded by any run of the project; checkpoints
+/// of other projects are rejected so runs cannot read across projects
+pub(crate) fn load_referenced_checkpoint_output(
+    conn: &Connection,
+    project_id: &str,
+    checkpoint_id: &str,
+) -> anyhow::Result<StepOutput> {
+    let source_run_id: Option<String> = conn
+        .query_row(
+            "SELECT c.run_id FROM checkpoints c JOIN runs r ON r.id = c.run_id
+             WHERE c.id = ?1 AND r.project_id = ?2",
+            params![checkpoint_id, project_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(source_run_id) = source_run_id else {
+        return Err(anyhow!("reference checkpoint {checkpoint_id} not found in project"));
+    };
+    crate::cold_storage::ensure_hot(conn, &source_run_id)?;
+    hydrate_step_output(conn, checkpoint_id)
+}
 
-    // Parse the configuration
-    let ingestion_config: DocumentIngestionConfig = serde_json::from_str(config_json)
-        .context("Failed to parse document ingestion config")?;
+/// Rebuild the output a past step checkpoint handed to later steps, so it can
+/// be chained into another execution. Checkpoints recorded before step outputs
+/// were persisted fall back to their stored payload.
+pub fn hydrate_step_output(conn: &Connection, checkpoint_id: &str) -> anyhow::Result<StepOutput> {
+    let row: Option<(String, Option<String>, Option<i64>, Option<String>, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT c.kind, c.outputs_sha256, s.order_index, s.step_type, p.output_payload, p.full_output_hash
+             FROM checkpoints c
+             LEFT JOIN run_steps s ON s.id = c.checkpoint_config_id
+             LEFT JOIN checkpoint_payloads p ON p.checkpoint_id = c.id
+             WHERE c.id = ?1",
+            params![checkpoint_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        )
+        .optional()?;
+    let (kind, outputs_sha256, order_index, step_type, preview, full_output_hash) =
+        row.ok_or_else(|| anyhow!("checkpoint {checkpoint_id} not found"))?;
+    if kind != "Step" {
+        return Err(anyhow!("checkpoint {checkpoint_id} is an incident and has no step output"));
+    }
 
-    // Process the document based on format
-    let canonical_doc = match ingestion_config.format.to_lowercase().as_str() {
-        "pdf" => {
-            document_processing::process_pdf_to_canonical(
-                &ingestion_config.source_path,
-                Some(ingestion_config.privacy_status.clone())
-            )?
-        }
-        "tex" | "latex" => {
-            document_processing::process_latex_to_canonical(
-                &ingestion_config.source_path,
-                Some(ingestion_config.privacy_status.clone())
-            )?
-        }
-        "txt" => {
-            document_processing::process_txt_to_canonical(
-                &ingestion_config.source_path,
-                Some(ingestion_config.privacy_status.clone())
-            )?
-        }
-        "docx" | "doc" => {
-            document_processing::process_docx_to_canonical(
-                &ingestion_config.source_path,
-                Some(ingestion_config.privacy_status.clone())
-            )?
-        }
-        unsupported => {
-            return Err(anyhow!(
-                "Unsupported document format: {}. Supported formats: pdf, latex, txt, docx",
-                unsupported
-            ));
+    let store = crate::attachments::get_global_attachment_store();
+    let persisted = match outputs_sha256.as_deref() {
+        Some(hash) => store.load_step_output(hash)?,
+        None => None,
+    };
+    let output_text = match (persisted, full_output_hash, preview) {
+        (Some(output), _, _) => output,
+        (None, Some(hash), _) => store.load_full_output(&hash)?,
+        (None, None, Some(preview)) => preview,
+        (None, None, None) => {
+            return Err(anyhow!("checkpoint {checkpoint_id} has no recorded output"));
         }
     };
 
-    // Serialize to JSON
-    let canonical_json = serde_json::to_string_pretty(&canonical_doc)
-        .context("Failed to serialize canonical document")?;
-
-    // Create preview for database storage
-    let preview = truncate_payload(&canonical_json, MAX_PAYLOAD_PREVIEW_SIZE);
+    Ok(StepOutput {
+        order_index: order_index.unwrap_or(0).max(0) as usize,
+        step_type: step_type.unwrap_or_default(),
+        output_json: serde_json::from_str(&output_text).ok(),
+        output_text,
+        outputs_sha256: outputs_sha256.unwrap_or_default(),
+    })
+}
 
-    // Compute provenance hashes
-    let inputs_sha256 = provenance::sha256_hex(ingestion_config.source_path.as_bytes());
+/// Inputs of an evaluate step
+pub(crate) struct EvaluationTask<'a> {
+    pub(crate) candidate: &'a str,
+    pub(crate) reference: &'a str,
+    pub(crate) reference_checkpoint_id: Option<&'a str>,
+    pub(crate) embedding_model: Option<&'a str>,
+    pub(crate) judge_model: Option<&'a str>,
+    pub(crate) judge_criteria: Option<&'a str>,
+}
 
-    // For deterministic hashing, create a normalized version without timestamps
-    let mut normalized_doc = canonical_doc.clone();
-    normalized_doc.processing_log.extraction_timestamp_utc = Some("NORMALIZED".to_string());
-    normalized_doc.processing_log.processing_timestamp_utc = "NORMALIZED".to_string();
-    normalized_doc.metadata.date_accessed_utc = Some("NORMALIZED".to_string());
+/// Outcome of an evaluate step: the step-level execution and, when a judge
+/// graded the candidate, its model call, persisted as a child checkpoint
+pub(crate) struct EvaluationRun {
+    pub(crate) execution: NodeExecution,
+    pub(crate) judge_call: Option<NodeExecution>,
+}
 
-    let normalized_json = serde_json::to_string(&normalized_doc)
-        .context("Failed to serialize normalized document")?;
-    let outputs_sha256 = provenance::sha256_hex(normalized_json.as_bytes());
+/// Score the candidate against the reference. The report is the step output,
+/// so the scores are signed along with the hashes of the texts they compare.
+pub(crate) fn execute_evaluate_checkpoint(
+    task: &EvaluationTask<'_>,
+    run_seed: u64,
+    order_index: i64,
+    sampling: Option<SamplingOptions>,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<EvaluationRun> {
+    let mut scores = evaluation::lexical_scores(task.candidate, task.reference);
+    let mut usage = TokenUsage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+    };
 
-    // Compute semantic digest from cleaned text content
-    let semantic_digest = provenance::semantic_digest(&normalized_doc.cleaned_text_with_markdown_structure);
+    if let Some(model) = task.embedding_model {
+        let texts = [task.candidate.to_string(), task.reference.to_string()];
+        let embedded = llm_client.embed(model, &texts)?;
+        let [candidate, reference] = embedded.vectors.as_slice() else {
+            return Err(anyhow!(
+                "{} returned {} embeddings for 2 texts",
+                model,
+                embedded.vectors.len()
+            ));
+        };
+        scores.embedding_cosine = Some(f64::from(embeddings::cosine_similarity(candidate, reference)));
+        usage.prompt_tokens += embedded.prompt_tokens;
+    }
 
-    // Create input description
-    let prompt_payload = format!(
-        "Document: {} (format: {}, privacy: {})",
-        ingestion_config.source_path,
-        ingestion_config.format,
-        ingestion_config.privacy_status
-    );
+    let judge_call = match task.judge_model {
+        Some(model) => {
+            let prompt =
+                evaluation::build_judge_prompt(task.candidate, task.reference, task.judge_criteria);
+            let call = execute_model_prompt(model, run_seed, order_index, &prompt, sampling, llm_client)?;
+            let (score, rationale) =
+                evaluation::parse_judge_reply(call.output_payload.as_deref().unwrap_or_default())
+                    .map_err(|err| anyhow!("judge {model}: {err}"))?;
+            scores.judge = Some(evaluation::JudgeVerdict {
+                model: model.to_string(),
+                score,
+                rationale,
+            });
+            usage.prompt_tokens += call.usage.prompt_tokens;
+            usage.completion_tokens += call.usage.completion_tokens;
+            Some(call)
+        }
+        None => None,
+    };
 
-    Ok(NodeExecution {
-        inputs_sha256: Some(inputs_sha256),
-        outputs_sha256: Some(outputs_sha256),
-        semantic_digest: Some(semantic_digest),
-        usage: TokenUsage {
-            prompt_tokens: 0,
-            completion_tokens: 0,
-        },
-        prompt_payload: Some(prompt_payload),
-        output_payload: Some(preview),
+    let report = evaluation::EvaluationReport {
+        candidate_sha256: provenance::sha256_hex(task.candidate.as_bytes()),
+        reference_sha256: provenance::sha256_hex(task.reference.as_bytes()),
+        reference_checkpoint_id: task.reference_checkpoint_id.map(str::to_string),
+        embedding_model: task.embedding_model.map(str::to_string),
+        scores,
+    };
+    let inputs = serde_json::json!({
+        "candidateSha256": &report.candidate_sha256,
+        "referenceSha256": &report.reference_sha256,
+        "embeddingModel": task.embedding_model,
+        "judgeModel": task.judge_model,
+        "judgeCriteria": task.judge_criteria,
+    });
+    let report_value = serde_json::to_value(&report)?;
+    let report_json = serde_json::to_string(&report)?;
+    let execution = NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&inputs))),
+        outputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&report_value))),
+        semantic_digest: Some(provenance::semantic_digest(&report_json)),
+        usage,
+        prompt_payload: Some(sanitize_payload(&serde_json::to_string(&inputs)?)),
+        output_payload: Some(report_json),
+        sampling: judge_call.as_ref().and_then(|call| call.sampling),
+        cached: false,
+        response_metadata: None,
+    };
+    Ok(EvaluationRun {
+        execution,
+        judge_call,
     })
 }
 
-/// Extract text content from a step output
-/// For ingest steps: extracts cleaned_text from CanonicalDocument
-/// For LLM steps: uses the output_text directly
-fn extract_text_from_output(output: &StepOutput) -> anyhow::Result<String> {
-    // If output is CanonicalDocument JSON, extract cleaned text
-    if let Some(json) = &output.output_json {
-        if let Some(cleaned_text) = json.get("cleaned_text_with_markdown_structure") {
-            if let Some(text) = cleaned_text.as_str() {
-                return Ok(text.to_string());
-            }
-        }
+/// Render a map template for one array element
+fn render_map_prompt(template: &str, item: &Value, item_index: usize) -> String {
+    let mut parameters = serde_json::Map::new();
+    parameters.insert("item".to_string(), item.clone());
+    parameters.insert("index".to_string(), Value::from(item_index));
+    apply_run_parameters(template, &parameters)
+}
+
+/// Summarize a map step's element executions into the step-level execution.
+/// Digests commit to the ordered element prompts and outputs.
+fn aggregate_map_execution(
+    prompt_template: &str,
+    rendered_prompts: &[String],
+    element_outputs: &[String],
+    usage: TokenUsage,
+) -> NodeExecution {
+    let inputs_hex = provenance::sha256_hex(&provenance::canonical_json(&rendered_prompts));
+    let outputs_json = serde_json::to_string(element_outputs).unwrap_or_else(|_| "[]".to_string());
+    let outputs_hex = provenance::sha256_hex(&provenance::canonical_json(&element_outputs));
+
+    NodeExecution {
+        inputs_sha256: Some(inputs_hex),
+        outputs_sha256: Some(outputs_hex),
+        semantic_digest: Some(provenance::semantic_digest(&outputs_json)),
+        usage,
+        prompt_payload: Some(sanitize_payload(prompt_template)),
+        output_payload: Some(outputs_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    }
+}
+
+fn map_input_incident(config: &RunStep, source_step: usize, reason: &str) -> governance::Incident {
+    governance::Incident {
+        kind: "map_input_invalid".into(),
+        severity: "error".into(),
+        details: format!(
+            "Map checkpoint {} cannot fan out over output of step {}: {}",
+            config.id, source_step, reason
+        ),
     }
+}
 
-    // Otherwise just use the text output
-    Ok(output.output_text.clone())
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
 }
 
-/// Build prompt for summarization based on summary type
-fn build_summary_prompt(
-    source: &StepOutput,
-    summary_type: &str,
-    custom_instructions: Option<&str>,
-) -> anyhow::Result<String> {
-    let base_prompt = match summary_type {
-        "brief" => "Provide a brief 2-3 sentence summary of the following:\n\n",
-        "detailed" => "Provide a comprehensive summary covering all main points of:\n\n",
-        "academic" => "Provide an academic summary including methodology, findings, and conclusions of:\n\n",
-        "custom" => custom_instructions.unwrap_or("Summarize the following:\n\n"),
-        _ => "Summarize the following:\n\n",
-    };
+/// Build the step-level execution for a branch decision. The decision is the
+/// checkpoint output; the inputs digest commits to the predicate and the
+/// source output it was evaluated on.
+fn branch_execution(
+    decision: &branching::BranchDecision,
+    classifier: Option<NodeExecution>,
+) -> anyhow::Result<NodeExecution> {
+    let inputs = serde_json::json!({
+        "predicate": &decision.predicate,
+        "sourceOutputsSha256": &decision.source_outputs_sha256,
+    });
+    let decision_json = serde_json::to_string(decision)?;
+    let usage = classifier.as_ref().map(|execution| execution.usage).unwrap_or(TokenUsage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+    });
+    let prompt_payload = classifier
+        .and_then(|execution| execution.prompt_payload)
+        .unwrap_or_else(|| sanitize_payload(&serde_json::to_string(&inputs).unwrap_or_default()));
 
-    let source_text = extract_text_from_output(source)?;
+    Ok(NodeExecution {
+        inputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(&inputs))),
+        outputs_sha256: Some(decision.digest()),
+        semantic_digest: Some(provenance::semantic_digest(&decision_json)),
+        usage,
+        prompt_payload: Some(prompt_payload),
+        output_payload: Some(decision_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    })
+}
 
-    Ok(format!("{}{}", base_prompt, source_text))
+fn empty_execution() -> NodeExecution {
+    NodeExecution {
+        inputs_sha256: None,
+        outputs_sha256: None,
+        semantic_digest: None,
+        usage: TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: None,
+        output_payload: None,
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    }
 }
 
-/// Build prompt with context from previous step
-fn build_prompt_with_context(prompt: &str, source: &StepOutput) -> String {
-    format!(
-        "{}\n\n--- Context from previous step ---\n{}",
-        prompt,
-        source.output_text
-    )
+/// Legacy steps carry their prompt on the step row; render invocation parameters into it
+fn parameterized_step(config: &RunStep, invocation: &RunInvocation) -> RunStep {
+    let mut step = config.clone();
+    if let Some(ref prompt) = config.prompt {
+        step.prompt = Some(invocation.render(prompt));
+    }
+    // Legacy ingestion steps read their source path rendered, as Ingest steps do
+    if config.is_document_ingestion() {
+        if let Some(mut ingestion) = config
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<DocumentIngestionConfig>(json).ok())
+        {
+            ingestion.source_path = invocation.render(&ingestion.source_path);
+            step.config_json = serde_json::to_string(&ingestion).ok();
+        }
+    }
+    step
 }
 
-fn execute_checkpoint(
+pub(crate) fn execute_checkpoint(
     config: &RunStep,
     run_seed: u64,
     llm_client: &dyn LlmClient,
+    policy: &store::policies::Policy,
 ) -> anyhow::Result<NodeExecution> {
     // Check if this is a document ingestion step
     if config.is_document_ingestion() {
         let config_json = config.config_json.as_ref()
             .ok_or_else(|| anyhow!("Document ingestion step missing config_json"))?;
-        return execute_document_ingestion_checkpoint(config_json);
+        return execute_document_ingestion_checkpoint(config_json, policy);
     }
 
     // For LLM steps, model and prompt must be present
@@ -2422,7 +6238,7 @@ fn execute_checkpoint(
     } else if model.starts_with(CLAUDE_MODEL_PREFIX) {
         execute_claude_mock_checkpoint(model, prompt)
     } else {
-        execute_llm_checkpoint(model, prompt, llm_client)
+        execute_llm_checkpoint(model, prompt, step_sampling(config, run_seed), llm_client)
     }
 }
 
@@ -2454,18 +6270,25 @@ fn execute_stub_checkpoint(run_seed: u64, order_index: i64, prompt: &str) -> Nod
         },
         prompt_payload: Some(prompt_payload),
         output_payload: Some(output_payload),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
     }
 }
 
 fn execute_claude_mock_checkpoint(model: &str, prompt: &str) -> anyhow::Result<NodeExecution> {
     // Mock Claude API response - requires network access policy
     // In production, would use actual Claude API with user-configured key
+    let prompt_preview = if prompt.len() > 100 {
+        format!("{}...", &prompt[..100])
+    } else {
+        prompt.to_string()
+    };
     let mock_response = format!(
         "[MOCK CLAUDE RESPONSE - Model: {}]\n\nThis is a simulated response from Claude. \
         In production, this would make a real API call to Anthropic's servers using your configured API key.\n\n\
         Your prompt was: {}",
-        model,
-        if prompt.len() > 100 { &format!("{}...", &prompt[..100]) } else { prompt }
+        model, prompt_preview
     );
 
     let inputs_hex = provenance::sha256_hex(prompt.as_bytes());
@@ -2488,15 +6311,22 @@ fn execute_claude_mock_checkpoint(model: &str, prompt: &str) -> anyhow::Result<N
         },
         prompt_payload: Some(prompt_payload),
         output_payload: Some(output_payload),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
     })
 }
 
 fn execute_llm_checkpoint(
     model: &str,
     prompt: &str,
+    sampling: Option<SamplingOptions>,
     llm_client: &dyn LlmClient,
 ) -> anyhow::Result<NodeExecution> {
-    let generation = llm_client.stream_generate(model, prompt)?;
+    let (generation, sampling) = match sampling {
+        Some(sampling) => llm_client.generate_with_sampling(model, prompt, &sampling)?,
+        None => (llm_client.stream_generate(model, prompt)?, None),
+    };
     let inputs_hex = provenance::sha256_hex(prompt.as_bytes());
     let outputs_hex = provenance::sha256_hex(generation.response.as_bytes());
     let semantic_digest = provenance::semantic_digest(&generation.response);
@@ -2510,12 +6340,39 @@ fn execute_llm_checkpoint(
         usage: generation.usage,
         prompt_payload: Some(prompt_payload),
         output_payload: Some(output_payload),
+        sampling,
+        cached: llm_client.last_generation_cached(),
+        response_metadata: generation.metadata,
     })
 }
 
-fn ensure_project_signing_key(conn: &Connection, project_id: &str) -> anyhow::Result<SigningKey> {
-    match provenance::load_secret_key(project_id) {
-        Ok(signing_key) => Ok(signing_key),
+/// Per-project locks serializing key provisioning, so concurrent executions
+/// never regenerate a missing project key twice
+static SIGNING_KEY_LOCKS: Lazy<Mutex<std::collections::HashMap<String, Arc<Mutex<()>>>>> =
+    Lazy::new(Default::default);
+
+fn project_signing_key_lock(project_id: &str) -> Arc<Mutex<()>> {
+    SIGNING_KEY_LOCKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(project_id.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Load the project's signer, regenerating its key when the secret is missing.
+/// A regeneration is recorded as a signing key event, which is returned so
+/// the caller can also record it as an incident of the execution it signs.
+/// A threshold project has no key on this device and is never regenerated.
+fn ensure_project_signing_key(
+    conn: &Connection,
+    project_id: &str,
+    run_id: Option<&str>,
+) -> anyhow::Result<(ProjectSigner, Option<store::signing_key_events::SigningKeyEvent>)> {
+    let lock = project_signing_key_lock(project_id);
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    match ProjectSigner::load(project_id) {
+        Ok(signer) => Ok((signer, None)),
         Err(err) => {
             let missing_in_keyring = err
                 .downcast_ref::<KeyringError>()
@@ -2528,11 +6385,8 @@ fn ensure_project_signing_key(conn: &Connection, project_id: &str) -> anyhow::Re
                 .unwrap_or(false);
 
             if missing_in_keyring || missing_on_disk {
-                println!(
-                    "[intelexta] WARNING: Secret for project {} missing; generating a new key pair.",
-                    project_id
-                );
-                regenerate_project_signing_key(conn, project_id)
+                regenerate_project_signing_key(conn, project_id, run_id)
+                    .map(|(signer, event)| (signer, Some(event)))
                     .context("failed to regenerate missing project secret")
             } else {
                 Err(err)
@@ -2544,24 +6398,42 @@ fn ensure_project_signing_key(conn: &Connection, project_id: &str) -> anyhow::Re
 fn regenerate_project_signing_key(
     conn: &Connection,
     project_id: &str,
-) -> anyhow::Result<SigningKey> {
+    run_id: Option<&str>,
+) -> anyhow::Result<(ProjectSigner, store::signing_key_events::SigningKeyEvent)> {
+    let previous_pubkey: String = conn
+        .query_row(
+            "SELECT pubkey FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("project {project_id} not found while regenerating secret"))?;
+
     let keypair = provenance::generate_keypair();
 
     provenance::store_secret_key(project_id, &keypair.secret_key_b64)
         .context("failed to persist regenerated project secret")?;
 
-    let updated = conn.execute(
+    conn.execute(
         "UPDATE projects SET pubkey = ?1 WHERE id = ?2",
         params![keypair.public_key_b64, project_id],
     )?;
 
-    if updated == 0 {
-        return Err(anyhow!(
-            "project {project_id} not found while regenerating secret"
-        ));
-    }
+    let event = store::signing_key_events::SigningKeyEvent {
+        id: Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        event: "regenerated".to_string(),
+        previous_pubkey: Some(previous_pubkey).filter(|pubkey| !pubkey.is_empty()),
+        pubkey: keypair.public_key_b64.clone(),
+        reason: "project secret missing from the keychain".to_string(),
+        run_id: run_id.map(str::to_string),
+        created_at: Utc::now().to_rfc3339(),
+    };
+    store::signing_key_events::record(conn, &event)?;
 
-    provenance::load_secret_key(project_id).context("failed to load regenerated project secret")
+    let signer =
+        ProjectSigner::load(project_id).context("failed to load regenerated project secret")?;
+    Ok((signer, event))
 }
 
 pub fn create_run_step(
@@ -2624,6 +6496,13 @@ pub fn create_run_step(
                 StepConfig::Ingest { .. } => "ingest",
                 StepConfig::Summarize { .. } => "summarize",
                 StepConfig::Prompt { .. } => "prompt",
+                StepConfig::Map { .. } => "map",
+                StepConfig::Branch { .. } => "branch",
+                StepConfig::Plugin { .. } => "plugin",
+                StepConfig::Agent { .. } => "agent",
+                StepConfig::FineTune { .. } => "finetune",
+                StepConfig::Embed { .. } => "embed",
+                StepConfig::Evaluate { .. } => "evaluate",
             };
 
             if step_type != expected_type {
@@ -2633,6 +6512,8 @@ pub fn create_run_step(
                     expected_type
                 ));
             }
+
+            step_config.validate().map_err(|err| anyhow!(err))?;
         }
         // If parsing fails, it's okay - might be legacy config or other format
     }
@@ -2640,8 +6521,8 @@ pub fn create_run_step(
     // Validate epsilon for concordant mode (only for LLM steps).
     let validated_epsilon = if proof_mode.is_concordant() {
         let value = epsilon.ok_or_else(|| anyhow!("concordant steps require an epsilon"))?;
-        if !value.is_finite() || value < 0.0 {
-            return Err(anyhow!("epsilon must be a finite, non-negative value"));
+        if !semantic::epsilon_in_bounds(value) {
+            return Err(anyhow!("epsilon must be between 0 and 1"));
         }
         Some(value)
     } else {
@@ -2902,6 +6783,10 @@ mod tests {
             usage_tokens,
             prompt_tokens,
             completion_tokens,
+            cache_hit: false,
+            response_metadata: None,
+            run_inputs_sha256: None,
+            output_schema_sha256: None,
         };
         let body_value = serde_json::to_value(&checkpoint_body)?;
         let canonical = provenance::canonical_json(&body_value);
@@ -3022,7 +6907,749 @@ mod tests {
             Ok(LlmGeneration {
                 response: self.response.clone(),
                 usage: self.usage,
+                metadata: None,
+            })
+        }
+    }
+
+    #[test]
+    fn map_prompt_substitutes_item_and_index() {
+        let template = "Item {{index}}: {{item}}";
+        assert_eq!(
+            render_map_prompt(template, &serde_json::json!("alpha"), 0),
+            "Item 0: alpha"
+        );
+        assert_eq!(
+            render_map_prompt(template, &serde_json::json!({"k": 1}), 2),
+            "Item 2: {\"k\":1}"
+        );
+    }
+
+    #[test]
+    fn run_inputs_render_and_hash_canonically() {
+        let invocation = RunInvocation {
+            inputs: serde_json::json!({"topic": "rust", "limit": 3})
+                .as_object()
+                .cloned()
+                .unwrap(),
+            ..RunInvocation::default()
+        };
+        assert_eq!(
+            invocation.render("Top {{inputs.limit}} {{inputs.topic}} crates"),
+            "Top 3 rust crates"
+        );
+        assert_eq!(
+            referenced_run_inputs("{{inputs.topic}} {{inputs.limit}} {{item}}"),
+            BTreeSet::from(["limit".to_string(), "topic".to_string()])
+        );
+
+        // Key order of the supplied map does not change the hash
+        let reordered = RunInvocation {
+            inputs: serde_json::from_str(r#"{"limit": 3, "topic": "rust"}"#).unwrap(),
+            ..RunInvocation::default()
+        };
+        assert_eq!(invocation.inputs_sha256(), reordered.inputs_sha256());
+        assert_eq!(RunInvocation::default().inputs_sha256(), None);
+    }
+
+    #[test]
+    fn map_aggregate_sums_usage_and_commits_to_outputs() {
+        let usage = TokenUsage {
+            prompt_tokens: 7,
+            completion_tokens: 5,
+        };
+        let outputs = vec!["a".to_string(), "b".to_string()];
+        let prompts = vec!["p0".to_string(), "p1".to_string()];
+        let aggregate = aggregate_map_execution("{{item}}", &prompts, &outputs, usage);
+
+        assert_eq!(aggregate.usage.total(), 12);
+        assert_eq!(aggregate.output_payload.as_deref(), Some("[\"a\",\"b\"]"));
+        assert_eq!(
+            aggregate.outputs_sha256,
+            Some(provenance::sha256_hex(&provenance::canonical_json(&outputs)))
+        );
+    }
+
+    #[test]
+    fn ollama_body_carries_sampling_options_only_when_requested() {
+        let plain: Value = serde_json::from_str(&ollama_generate_body("llama3", "hi", None)).unwrap();
+        assert!(plain.get("options").is_none());
+
+        let sampling = SamplingOptions::deterministic(42);
+        let seeded: Value =
+            serde_json::from_str(&ollama_generate_body("llama3", "hi", Some(&sampling))).unwrap();
+        assert_eq!(seeded["options"]["seed"], 42);
+        assert_eq!(seeded["options"]["temperature"], 0.0);
+    }
+
+    #[test]
+    fn llm_checkpoint_records_applied_sampling() {
+        struct SeededClient;
+        impl LlmClient for SeededClient {
+            fn stream_generate(&self, _model: &str, _prompt: &str) -> anyhow::Result<LlmGeneration> {
+                Ok(LlmGeneration {
+                    response: "unseeded".to_string(),
+                    usage: TokenUsage {
+                        prompt_tokens: 1,
+                        completion_tokens: 1,
+                    },
+                    metadata: None,
+                })
+            }
+
+            fn generate_with_sampling(
+                &self,
+                model: &str,
+                prompt: &str,
+                sampling: &SamplingOptions,
+            ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+                Ok((self.stream_generate(model, prompt)?, Some(*sampling)))
+            }
+        }
+
+        let sampling = SamplingOptions::deterministic(7);
+        let seeded = execute_llm_checkpoint("llama3", "hi", Some(sampling), &SeededClient).unwrap();
+        assert_eq!(seeded.sampling, Some(sampling));
+
+        let unseeded = execute_llm_checkpoint("llama3", "hi", None, &SeededClient).unwrap();
+        assert_eq!(unseeded.sampling, None);
+    }
+
+    #[test]
+    fn step_documents_follow_the_chain_back_to_ingested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paper = dir.path().join("paper.txt");
+        std::fs::write(&paper, b"held-out text").unwrap();
+        let paper_hash = provenance::sha256_file(&paper).unwrap();
+
+        let step = |order_index: i64, config: Value| RunStep {
+            id: format!("step-{order_index}"),
+            run_id: "run".to_string(),
+            order_index,
+            checkpoint_type: "Step".to_string(),
+            step_type: config["stepType"].as_str().unwrap().to_string(),
+            model: None,
+            prompt: None,
+            token_budget: 100,
+            proof_mode: RunProofMode::Exact,
+            epsilon: None,
+            config_json: Some(config.to_string()),
+        };
+        let ingest = step(
+            0,
+            serde_json::json!({"stepType": "ingest", "sourcePath": paper, "format": "txt", "privacyStatus": "public"}),
+        );
+        let prompt = step(
+            1,
+            serde_json::json!({"stepType": "prompt", "model": "stub-model", "prompt": "Q", "useOutputFrom": 0}),
+        );
+
+        let mut lineage = std::collections::HashMap::new();
+        let ingested = step_documents(&ingest, &lineage);
+        assert_eq!(ingested, BTreeSet::from([paper_hash.clone()]));
+        assert!(!step_sends_documents_to_model(&ingest));
+
+        lineage.insert(0, ingested);
+        assert_eq!(step_documents(&prompt, &lineage), BTreeSet::from([paper_hash]));
+        assert!(step_sends_documents_to_model(&prompt));
+    }
+
+    #[test]
+    fn sandbox_checks_see_ingest_paths_rendered_with_run_inputs() {
+        let step = |step_type: &str, config: Value| RunStep {
+            id: "step-0".to_string(),
+            run_id: "run".to_string(),
+            order_index: 0,
+            checkpoint_type: "Step".to_string(),
+            step_type: step_type.to_string(),
+            model: None,
+            prompt: None,
+            token_budget: 100,
+            proof_mode: RunProofMode::Exact,
+            epsilon: None,
+            config_json: Some(config.to_string()),
+        };
+        let typed = step(
+            "ingest",
+            serde_json::json!({"stepType": "ingest", "sourcePath": "{{inputs.dir}}/paper.txt", "format": "txt", "privacyStatus": "public"}),
+        );
+        let legacy = step(
+            "document_ingestion",
+            serde_json::json!({"sourcePath": "{{inputs.dir}}/paper.txt", "format": "txt", "privacyStatus": "public", "outputStorage": "database"}),
+        );
+        let invocation = RunInvocation {
+            inputs: serde_json::json!({"dir": "/data/papers"})
+                .as_object()
+                .cloned()
+                .unwrap(),
+            ..RunInvocation::default()
+        };
+
+        for config in [&typed, &legacy] {
+            assert_eq!(
+                step_filesystem_paths(config, &invocation),
+                vec!["/data/papers/paper.txt".to_string()]
+            );
+        }
+        // The legacy executor reads the same rendered path the check saw
+        let executed = parameterized_step(&legacy, &invocation);
+        let ingestion: DocumentIngestionConfig =
+            serde_json::from_str(executed.config_json.as_deref().unwrap()).unwrap();
+        assert_eq!(ingestion.source_path, "/data/papers/paper.txt");
+    }
+
+    #[test]
+    fn privacy_statuses_follow_the_chain_from_ingestion() {
+        let step = |order_index: i64, config: Value| RunStep {
+            id: format!("step-{order_index}"),
+            run_id: "run".to_string(),
+            order_index,
+            checkpoint_type: "Step".to_string(),
+            step_type: config["stepType"].as_str().unwrap().to_string(),
+            model: None,
+            prompt: None,
+            token_budget: 100,
+            proof_mode: RunProofMode::Exact,
+            epsilon: None,
+            config_json: Some(config.to_string()),
+        };
+        let ingest = step(
+            0,
+            serde_json::json!({"stepType": "ingest", "sourcePath": "interviews.txt", "format": "txt", "privacyStatus": "consent_obtained_anonymized"}),
+        );
+        let summarize = step(
+            1,
+            serde_json::json!({"stepType": "summarize", "sourceStep": 0, "model": "claude-haiku", "summaryType": "brief"}),
+        );
+        let prompt = step(
+            2,
+            serde_json::json!({"stepType": "prompt", "model": "claude-haiku", "prompt": "Q", "useOutputFrom": 1}),
+        );
+
+        let mut lineage = std::collections::HashMap::new();
+        let ingested = step_privacy_statuses(&ingest, &lineage);
+        assert_eq!(ingested, BTreeSet::from(["consent_obtained_anonymized".to_string()]));
+        lineage.insert(0, ingested.clone());
+        lineage.insert(1, step_privacy_statuses(&summarize, &lineage));
+        assert_eq!(step_privacy_statuses(&prompt, &lineage), ingested);
+        assert!(step_privacy_statuses(&prompt, &std::collections::HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn projected_usage_prices_each_step_with_its_model() {
+        let step = |order_index: i64, model: Option<&str>, config: Option<Value>| RunStep {
+            id: format!("step-{order_index}"),
+            run_id: "run".to_string(),
+            order_index,
+            checkpoint_type: "Step".to_string(),
+            step_type: "llm".to_string(),
+            model: model.map(str::to_string),
+            prompt: None,
+            token_budget: 1_000,
+            proof_mode: RunProofMode::Exact,
+            epsilon: None,
+            config_json: config.map(|config| config.to_string()),
+        };
+        let legacy = step(0, Some("llama3"), None);
+        let typed = step(
+            1,
+            None,
+            Some(serde_json::json!({"stepType": "prompt", "model": "claude-3-5-sonnet", "prompt": "Q"})),
+        );
+        assert_eq!(step_pricing_model(&legacy).as_deref(), Some("llama3"));
+        assert_eq!(step_pricing_model(&typed).as_deref(), Some("claude-3-5-sonnet"));
+
+        let steps = [legacy, typed];
+        let projected = project_step_usage(&steps);
+        let expected: Vec<_> = steps
+            .iter()
+            .map(|config| {
+                governance::CheckpointCost::priced(1_000, step_pricing_model(config).as_deref())
+            })
+            .collect();
+        assert_eq!(projected.tokens, 2_000);
+        assert_eq!(projected.usd, expected[0].usd + expected[1].usd);
+        assert_eq!(
+            projected.nature_cost,
+            expected[0].nature_cost + expected[1].nature_cost
+        );
+    }
+
+    #[test]
+    fn signing_key_locks_are_per_project() {
+        let first = project_signing_key_lock("lock-test-a");
+        let again = project_signing_key_lock("lock-test-a");
+        let other = project_signing_key_lock("lock-test-b");
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(!Arc::ptr_eq(&first, &other));
+
+        let _held = first.lock().unwrap();
+        assert!(again.try_lock().is_err());
+        assert!(other.try_lock().is_ok());
+    }
+
+    #[test]
+    fn prompt_context_references_a_step_or_an_earlier_checkpoint() {
+        let parse = |use_output_from: serde_json::Value| {
+            serde_json::from_value::<StepConfig>(serde_json::json!({
+                "stepType": "prompt", "model": "stub-model", "prompt": "Q",
+                "useOutputFrom": use_output_from,
+            }))
+            .unwrap()
+        };
+
+        let by_step = parse(serde_json::json!(2));
+        assert_eq!(step_config_references(&by_step).1, vec![2]);
+        assert_eq!(by_step.referenced_checkpoint(), None);
+
+        let by_checkpoint = parse(serde_json::json!("ck-earlier"));
+        assert!(step_config_references(&by_checkpoint).1.is_empty());
+        assert_eq!(by_checkpoint.referenced_checkpoint(), Some("ck-earlier"));
+        assert_eq!(
+            serde_json::to_value(&by_checkpoint).unwrap()["useOutputFrom"],
+            "ck-earlier"
+        );
+    }
+
+    #[test]
+    fn template_prompts_render_run_inputs_and_the_source_output() {
+        let config: StepConfig = serde_json::from_value(serde_json::json!({
+            "stepType": "prompt", "model": "stub-model", "template": true,
+            "prompt": "{{#each source.json}}- {{this}} ({{inputs.tone}})\n{{/each}}",
+        }))
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        let invocation = RunInvocation {
+            inputs: serde_json::from_value(serde_json::json!({ "tone": "formal" })).unwrap(),
+            ..RunInvocation::default()
+        };
+        let source = StepOutput {
+            order_index: 0,
+            step_type: "prompt".to_string(),
+            output_text: r#"["a","b"]"#.to_string(),
+            output_json: Some(serde_json::json!(["a", "b"])),
+            outputs_sha256: "ab".repeat(32),
+        };
+        let StepConfig::Prompt { prompt, .. } = &config else {
+            unreachable!()
+        };
+        let rendered = prompt_template::render(
+            prompt,
+            &prompt_template_context(&invocation, Some(&source)),
+            &std::collections::BTreeMap::new(),
+        )
+        .unwrap();
+        assert_eq!(rendered, "- a (formal)\n- b (formal)\n");
+
+        let broken: StepConfig = serde_json::from_value(serde_json::json!({
+            "stepType": "prompt", "model": "stub-model", "template": true,
+            "prompt": "{{#each source.json}}",
+        }))
+        .unwrap();
+        assert!(broken.validate().is_err());
+    }
+
+    /// Answers each call with the next scripted response
+    struct ScriptedClient {
+        responses: Mutex<Vec<&'static str>>,
+    }
+
+    impl ScriptedClient {
+        fn new(responses: Vec<&'static str>) -> Self {
+            Self {
+                responses: Mutex::new(responses),
+            }
+        }
+    }
+
+    impl LlmClient for ScriptedClient {
+        fn stream_generate(&self, _model: &str, _prompt: &str) -> anyhow::Result<LlmGeneration> {
+            Ok(LlmGeneration {
+                response: self.responses.lock().unwrap().remove(0).to_string(),
+                usage: TokenUsage {
+                    prompt_tokens: 3,
+                    completion_tokens: 2,
+                },
+                metadata: None,
             })
         }
     }
+
+    #[test]
+    fn agent_records_each_model_turn_and_tool_call() {
+        let client = ScriptedClient::new(vec![
+            r#"{"tool": "calculator", "input": {"expression": "6 * 7"}}"#,
+            r#"{"final": "42"}"#,
+        ]);
+        let tools = [agent::AgentTool::Calculator];
+        let task = AgentTask {
+            model: "llama3",
+            task: "What is six times seven?",
+            tools: &tools,
+            corpus: None,
+            file_roots: &[],
+            max_turns: 4,
+            token_budget: 1_000,
+        };
+        let run = execute_agent_checkpoint(&task, 1, 0, None, &client).unwrap();
+
+        assert!(run.incident.is_none());
+        assert_eq!(run.execution.output_payload.as_deref(), Some("42"));
+        assert_eq!(run.execution.usage.total(), 10);
+        // model turn, tool call, model turn
+        assert_eq!(run.turns.len(), 3);
+        assert_eq!(run.turns[1].output_payload.as_deref(), Some("42"));
+        assert!(run.turns[1]
+            .prompt_payload
+            .as_deref()
+            .is_some_and(|call| call.contains("calculator")));
+    }
+
+    #[test]
+    fn agent_without_final_answer_hits_turn_limit() {
+        let client = ScriptedClient::new(vec![
+            r#"{"tool": "calculator", "input": {"expression": "1 + 1"}}"#,
+            r#"{"tool": "calculator", "input": {"expression": "2 + 2"}}"#,
+        ]);
+        let tools = [agent::AgentTool::Calculator];
+        let task = AgentTask {
+            model: "llama3",
+            task: "Loop forever",
+            tools: &tools,
+            corpus: None,
+            file_roots: &[],
+            max_turns: 2,
+            token_budget: 1_000,
+        };
+        let run = execute_agent_checkpoint(&task, 1, 0, None, &client).unwrap();
+        assert_eq!(run.turns.len(), 4);
+        assert_eq!(
+            run.incident.map(|incident| incident.kind),
+            Some("agent_turn_limit".to_string())
+        );
+    }
+
+    #[test]
+    fn agent_stops_before_a_turn_that_would_overrun_the_budget() {
+        let client = ScriptedClient::new(vec![
+            r#"{"thought": "Start small.", "tool": "calculator", "input": {"expression": "1 + 1"}}"#,
+            r#"{"thought": "Keep going.", "tool": "calculator", "input": {"expression": "2 + 2"}}"#,
+            r#"{"final": "4"}"#,
+        ]);
+        let tools = [agent::AgentTool::Calculator];
+        let task = AgentTask {
+            model: "llama3",
+            task: "Add things",
+            tools: &tools,
+            corpus: None,
+            file_roots: &[],
+            max_turns: 8,
+            token_budget: 12,
+        };
+        let run = execute_agent_checkpoint(&task, 1, 0, None, &client).unwrap();
+
+        // Two turns of 5 tokens fit; a third would reach 15
+        assert_eq!(run.execution.usage.total(), 10);
+        assert_eq!(run.turns.len(), 4);
+        assert!(run.turns[2]
+            .output_payload
+            .as_deref()
+            .is_some_and(|reply| reply.contains("Keep going.")));
+        let incident = run.incident.unwrap();
+        assert_eq!(incident.kind, "budget_exceeded");
+        assert!(incident.details.contains("after 2 turn(s)"));
+    }
+
+    #[test]
+    fn context_split_keeps_the_text_and_cuts_at_breaks() {
+        let text = format!("{}\n\n{}\n\n{}", "a".repeat(30), "b".repeat(30), "c".repeat(30));
+        let pieces = split_for_context(&text, 10);
+        assert_eq!(pieces.concat(), text);
+        assert_eq!(pieces.len(), 3);
+        assert!(pieces[0].ends_with("\n\n"));
+        assert!(pieces.iter().all(|piece| piece.len() <= 40));
+
+        // Multi-byte text without breaks is cut on char boundaries
+        let unbroken = "é".repeat(50);
+        let pieces = split_for_context(&unbroken, 5);
+        assert_eq!(pieces.concat(), unbroken);
+        assert!(pieces.len() > 1);
+    }
+
+    #[test]
+    fn chunked_summary_records_each_chunk_and_bills_every_call() {
+        let client = ScriptedClient::new(vec!["first", "second", "third", "combined"]);
+        let source = ["alpha ", "beta ", "gamma "]
+            .map(|word| word.repeat(20))
+            .join("\n\n");
+        let task = SummaryTask {
+            model: "llama3",
+            instructions: "Summarize the following:\n\n",
+            source_text: &source,
+            prompt_limit: 80,
+        };
+        let summary = execute_chunked_summary(&task, 1, 2, None, &client).unwrap();
+
+        assert_eq!(summary.chunks.len(), 3);
+        assert!(summary.chunks[1]
+            .prompt_payload
+            .as_deref()
+            .is_some_and(|prompt| {
+                prompt.starts_with("This is part 2 of 3") && prompt.contains("beta")
+            }));
+        assert_eq!(summary.execution.output_payload.as_deref(), Some("combined"));
+        assert!(summary
+            .execution
+            .prompt_payload
+            .as_deref()
+            .is_some_and(|prompt| prompt.ends_with("first\n\nsecond\n\nthird")));
+        assert_eq!(summary.execution.usage.total(), 20);
+
+        let cramped = SummaryTask {
+            prompt_limit: 20,
+            ..task
+        };
+        assert!(execute_chunked_summary(&cramped, 1, 2, None, &client).is_err());
+    }
+
+    #[test]
+    fn response_cache_serves_repeated_prompts_without_usage() -> Result<()> {
+        struct CountingClient {
+            calls: Mutex<u32>,
+        }
+        impl LlmClient for CountingClient {
+            fn stream_generate(&self, _model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
+                *self.calls.lock().unwrap() += 1;
+                Ok(LlmGeneration {
+                    response: format!("echo: {prompt}"),
+                    usage: TokenUsage {
+                        prompt_tokens: 4,
+                        completion_tokens: 6,
+                    },
+                    metadata: None,
+                })
+            }
+        }
+
+        let pool: Pool<SqliteConnectionManager> =
+            Pool::builder().max_size(1).build(SqliteConnectionManager::memory())?;
+        let mut conn = pool.get()?;
+        store::migrate_db(&mut conn)?;
+
+        let inner = CountingClient {
+            calls: Mutex::new(0),
+        };
+        let scope = CacheScope {
+            project_id: "project".to_string(),
+            output_schema_sha256: None,
+        };
+        let client = CachingLlmClient::new(&conn, &inner, scope.clone());
+        let fresh = execute_llm_checkpoint("llama3", "hello", None, &client)?;
+        let cached = execute_llm_checkpoint("llama3", "hello", None, &client)?;
+        let other_model = execute_llm_checkpoint("mistral", "hello", None, &client)?;
+
+        assert_eq!(*inner.calls.lock().unwrap(), 2);
+        assert!(!fresh.cached);
+        assert_eq!(fresh.usage.total(), 10);
+        assert!(cached.cached);
+        assert_eq!(cached.usage.total(), 0);
+        assert_eq!(cached.outputs_sha256, fresh.outputs_sha256);
+        assert!(!other_model.cached);
+
+        // Sampling parameters are part of the key
+        assert_ne!(
+            response_cache_key("llama3", "hello", None, None, &scope),
+            response_cache_key(
+                "llama3",
+                "hello",
+                Some(&SamplingOptions::deterministic(1)),
+                None,
+                &scope
+            )
+        );
+
+        // So are the token limit, the output schema and the project
+        let key = response_cache_key("llama3", "hello", None, Some(100), &scope);
+        assert_ne!(
+            key,
+            response_cache_key("llama3", "hello", None, Some(4_000), &scope)
+        );
+        let schema = serde_json::json!({"type": "object"});
+        let validated = CacheScope {
+            output_schema_sha256: Some(output_schema::schema_sha256(&schema)),
+            ..scope.clone()
+        };
+        assert_ne!(
+            key,
+            response_cache_key("llama3", "hello", None, Some(100), &validated)
+        );
+        let other_project = CacheScope {
+            project_id: "other-project".to_string(),
+            ..scope
+        };
+        let elsewhere = CachingLlmClient::new(&conn, &inner, other_project);
+        assert!(!execute_llm_checkpoint("llama3", "hello", None, &elsewhere)?.cached);
+        assert_eq!(*inner.calls.lock().unwrap(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn step_budget_stops_generations_mid_stream() -> Result<()> {
+        // Streams one token per word after a 10-token prompt, stopping once
+        // the limit is exceeded
+        struct StreamingClient {
+            limits: Mutex<Vec<u64>>,
+        }
+        impl LlmClient for StreamingClient {
+            fn stream_generate(&self, model: &str, prompt: &str) -> anyhow::Result<LlmGeneration> {
+                Ok(self.generate_bounded(model, prompt, None, u64::MAX)?.0)
+            }
+
+            fn generate_bounded(
+                &self,
+                _model: &str,
+                _prompt: &str,
+                _sampling: Option<&SamplingOptions>,
+                token_limit: u64,
+            ) -> anyhow::Result<(LlmGeneration, Option<SamplingOptions>)> {
+                self.limits.lock().unwrap().push(token_limit);
+                let mut response = String::new();
+                let mut streamed = 0;
+                let mut finish_reason = crate::model_adapters::FinishReason::Stop;
+                for word in ["one ", "two ", "three ", "four ", "five ", "six ", "seven ", "eight"] {
+                    response.push_str(word);
+                    streamed += 1;
+                    if 10 + streamed > token_limit {
+                        finish_reason = crate::model_adapters::FinishReason::Budget;
+                        break;
+                    }
+                }
+                Ok((
+                    LlmGeneration {
+                        response,
+                        usage: TokenUsage {
+                            prompt_tokens: 10,
+                            completion_tokens: streamed,
+                        },
+                        metadata: Some(ResponseMetadata {
+                            provider: "ollama".to_string(),
+                            finish_reason,
+                            tool_calls: Vec::new(),
+                            provider_request_id: None,
+                            response_headers_sha256: None,
+                        }),
+                    },
+                    None,
+                ))
+            }
+        }
+
+        let inner = StreamingClient {
+            limits: Mutex::new(Vec::new()),
+        };
+        let client = StepBudgetClient::new(&inner, 30);
+
+        let complete = execute_llm_checkpoint("llama3", "hi", None, &client)?;
+        assert_eq!(complete.usage.total(), 18);
+        assert!(client.budget_stop().is_none());
+
+        // Only 12 tokens of the step budget remain for the second generation
+        let partial = execute_llm_checkpoint("llama3", "hi", None, &client)?;
+        assert_eq!(*inner.limits.lock().unwrap(), vec![30, 12]);
+        assert_eq!(partial.output_payload.as_deref(), Some("one two three "));
+
+        let stop = client.budget_stop().expect("second generation ran out of budget");
+        assert_eq!(stop.usage_tokens, 31);
+        assert_eq!(Some(stop.partial_output_sha256), partial.outputs_sha256);
+        Ok(())
+    }
+
+    #[test]
+    fn embed_step_records_vector_hashes_in_its_manifest() -> Result<()> {
+        struct EmbeddingClient;
+        impl LlmClient for EmbeddingClient {
+            fn stream_generate(&self, _model: &str, _prompt: &str) -> anyhow::Result<LlmGeneration> {
+                Err(anyhow!("not a generation model"))
+            }
+
+            fn embed(&self, _model: &str, inputs: &[String]) -> anyhow::Result<EmbeddingResponse> {
+                Ok(EmbeddingResponse {
+                    provider: "ollama".to_string(),
+                    vectors: inputs
+                        .iter()
+                        .map(|input| vec![input.len() as f32, 1.0])
+                        .collect(),
+                    prompt_tokens: 7,
+                })
+            }
+        }
+
+        let client = StepBudgetClient::new(&EmbeddingClient, 100);
+        let (execution, chunks) =
+            execute_embed_checkpoint("nomic-embed-text", "Provenance for embeddings.", &client)?;
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].vector, vec![26.0, 1.0]);
+        assert_eq!(execution.usage.total(), 7);
+        assert_eq!(client.remaining(), 93);
+
+        let manifest: embeddings::EmbeddingManifest =
+            serde_json::from_str(execution.output_payload.as_deref().unwrap())?;
+        assert_eq!(manifest.model, "nomic-embed-text");
+        assert_eq!(manifest.dimensions, 2);
+        assert_eq!(manifest.chunks[0].vector_sha256, chunks[0].vector_sha256);
+
+        // Clients without an embedding endpoint fail the step
+        assert!(execute_embed_checkpoint("llama3", "text", &ScriptedClient::new(vec![])).is_err());
+        Ok(())
+    }
+    #[test]
+    fn evaluate_step_records_lexical_and_judge_scores() -> Result<()> {
+        let client = ScriptedClient::new(vec![
+            r#"{"score": 0.5, "rationale": "Half of the facts match."}"#,
+        ]);
+        let task = EvaluationTask {
+            candidate: "Paris is the capital of France",
+            reference: "The capital of France is Paris",
+            reference_checkpoint_id: None,
+            embedding_model: None,
+            judge_model: Some("llama3"),
+            judge_criteria: Some("Factual agreement"),
+        };
+        let run = execute_evaluate_checkpoint(&task, 1, 2, None, &client)?;
+
+        let report: evaluation::EvaluationReport =
+            serde_json::from_str(run.execution.output_payload.as_deref().unwrap())?;
+        assert!(!report.scores.exact_match);
+        assert_eq!(report.scores.rouge_1, 1.0);
+        assert!(report.scores.rouge_l < 1.0);
+        assert_eq!(report.scores.judge.as_ref().map(|judge| judge.score), Some(0.5));
+        assert_eq!(
+            report.reference_sha256,
+            provenance::sha256_hex(task.reference.as_bytes())
+        );
+
+        // The judge call is kept as its own execution and counts toward usage
+        let judge_call = run.judge_call.expect("judge call recorded");
+        assert!(judge_call
+            .prompt_payload
+            .as_deref()
+            .is_some_and(|prompt| prompt.contains("Factual agreement")));
+        assert_eq!(run.execution.usage.total(), judge_call.usage.total());
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_steps_need_exactly_one_reference() {
+        let config: StepConfig = serde_json::from_value(serde_json::json!({
+            "stepType": "evaluate",
+            "candidateStep": 0,
+            "referenceStep": 1,
+            "referenceCheckpointId": "ckpt-1"
+        }))
+        .unwrap();
+        assert!(config.validate().is_err());
+        assert_eq!(step_config_references(&config).1, vec![0, 1]);
+    }
 }
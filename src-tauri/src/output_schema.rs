@@ -0,0 +1,388 @@
+// src-tauri/src/output_schema.rs
+//!
+//! Structured output validation for prompt steps
+//!
+//! Prompt steps may declare an `outputSchema` (JSON Schema) describing the
+//! shape the LLM response must take. This module implements the subset of
+//! JSON Schema that is useful for structured LLM output:
+//!
+//! - `type` (single type or list of types)
+//! - `enum`, `const`
+//! - `properties`, `required`, `additionalProperties` (bool or schema)
+//! - `items` (single schema), `minItems`, `maxItems`
+//! - `minLength`, `maxLength`, `pattern`
+//! - `minimum`, `maximum`
+//! - `anyOf`, `oneOf`, `allOf`
+//!
+//! Unknown keywords are ignored, matching JSON Schema's open-world semantics.
+//!
+//! The step checkpoint records `output_schema_sha256`, hashed into its chain
+//! link, and a step whose output needed corrections hashes the original
+//! prompt together with every correction prompt into `inputs_sha256`.
+
+use crate::provenance;
+use regex::Regex;
+use serde_json::Value;
+
+/// A single schema violation, located by JSON pointer into the instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// Hash of the canonical (JCS) schema, recorded as a provenance claim.
+pub fn schema_sha256(schema: &Value) -> String {
+    provenance::sha256_hex(&provenance::canonical_json(schema))
+}
+
+/// Reject schemas that cannot be evaluated before they are stored on a step.
+pub fn check_schema(schema: &Value) -> Result<(), String> {
+    match schema {
+        Value::Bool(_) => Ok(()),
+        Value::Object(map) => {
+            if let Some(pattern) = map.get("pattern").and_then(Value::as_str) {
+                Regex::new(pattern)
+                    .map_err(|err| format!("invalid pattern '{}': {}", pattern, err))?;
+            }
+            if let Some(Value::Object(properties)) = map.get("properties") {
+                for (name, sub) in properties {
+                    check_schema(sub).map_err(|err| format!("properties.{}: {}", name, err))?;
+                }
+            }
+            for key in ["items", "additionalProperties"] {
+                if let Some(sub) = map.get(key) {
+                    check_schema(sub).map_err(|err| format!("{}: {}", key, err))?;
+                }
+            }
+            for key in ["anyOf", "oneOf", "allOf"] {
+                if let Some(Value::Array(subs)) = map.get(key) {
+                    for sub in subs {
+                        check_schema(sub).map_err(|err| format!("{}: {}", key, err))?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Err("schema must be a JSON object or boolean".to_string()),
+    }
+}
+
+/// Parse an LLM response as JSON, tolerating a surrounding markdown code fence.
+pub fn parse_structured_output(response: &str) -> Result<Value, String> {
+    let trimmed = response.trim();
+    let body = if let Some(rest) = trimmed.strip_prefix("```") {
+        let rest = rest.split_once('\n').map(|(_, body)| body).unwrap_or("");
+        rest.trim_end().strip_suffix("```").unwrap_or(rest).trim()
+    } else {
+        trimmed
+    };
+
+    serde_json::from_str(body).map_err(|err| format!("response is not valid JSON: {}", err))
+}
+
+/// Validate an instance against a schema, returning every violation found.
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_at(schema, instance, "", &mut violations);
+    violations
+}
+
+/// Parse and validate a raw LLM response in one step.
+pub fn validate_response(schema: &Value, response: &str) -> Vec<SchemaViolation> {
+    match parse_structured_output(response) {
+        Ok(instance) => validate(schema, &instance),
+        Err(message) => vec![SchemaViolation {
+            path: String::new(),
+            message,
+        }],
+    }
+}
+
+/// Build the follow-up prompt asking the model to fix a non-conforming answer.
+pub fn build_correction_prompt(
+    original_prompt: &str,
+    schema: &Value,
+    previous_response: &str,
+    violations: &[SchemaViolation],
+) -> String {
+    let schema_text = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+    let issues = violations
+        .iter()
+        .map(|violation| format!("- {}", violation))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{}\n\n---\n\nYour previous response did not conform to the required JSON Schema.\n\n\
+         Previous response:\n{}\n\nProblems:\n{}\n\nRequired JSON Schema:\n{}\n\n\
+         Respond again with ONLY a JSON value that satisfies the schema. Do not include any explanation.",
+        original_prompt, previous_response, issues, schema_text
+    )
+}
+
+fn push(violations: &mut Vec<SchemaViolation>, path: &str, message: String) {
+    violations.push(SchemaViolation {
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "number" => value.is_number(),
+        "integer" => match value {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().map(|f| f.fract() == 0.0).unwrap_or(false)
+            }
+            _ => false,
+        },
+        other => type_name(value) == other,
+    }
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    let map = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            push(out, path, "no value is allowed here".to_string());
+            return;
+        }
+        Value::Object(map) => map,
+        _ => return,
+    };
+
+    if let Some(expected) = map.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| matches_type(name, instance)) {
+            push(
+                out,
+                path,
+                format!("expected {}, got {}", allowed.join(" or "), type_name(instance)),
+            );
+            // Further keyword checks would only produce noise for the wrong type.
+            return;
+        }
+    }
+
+    if let Some(Value::Array(options)) = map.get("enum") {
+        if !options.iter().any(|option| option == instance) {
+            push(out, path, format!("value must be one of {}", Value::Array(options.clone())));
+        }
+    }
+
+    if let Some(expected) = map.get("const") {
+        if expected != instance {
+            push(out, path, format!("value must equal {}", expected));
+        }
+    }
+
+    match instance {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = map.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        push(out, path, format!("missing required property '{}'", name));
+                    }
+                }
+            }
+
+            let properties = map.get("properties").and_then(Value::as_object);
+            for (name, value) in object {
+                let child_path = format!("{}/{}", path, escape_pointer(name));
+                if let Some(sub) = properties.and_then(|props| props.get(name)) {
+                    validate_at(sub, value, &child_path, out);
+                } else if let Some(additional) = map.get("additionalProperties") {
+                    if additional == &Value::Bool(false) {
+                        push(out, path, format!("unexpected property '{}'", name));
+                    } else {
+                        validate_at(additional, value, &child_path, out);
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = map.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    push(out, path, format!("expected at least {} items, got {}", min, items.len()));
+                }
+            }
+            if let Some(max) = map.get("maxItems").and_then(Value::as_u64) {
+                if (items.len() as u64) > max {
+                    push(out, path, format!("expected at most {} items, got {}", max, items.len()));
+                }
+            }
+            if let Some(item_schema) = map.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}/{}", path, index), out);
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = map.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    push(out, path, format!("string shorter than {} characters", min));
+                }
+            }
+            if let Some(max) = map.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    push(out, path, format!("string longer than {} characters", max));
+                }
+            }
+            if let Some(pattern) = map.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(re) if !re.is_match(text) => {
+                        push(out, path, format!("string does not match pattern '{}'", pattern));
+                    }
+                    Ok(_) => {}
+                    Err(err) => push(out, path, format!("invalid pattern '{}': {}", pattern, err)),
+                }
+            }
+        }
+        Value::Number(number) => {
+            if let Some(value) = number.as_f64() {
+                if let Some(min) = map.get("minimum").and_then(Value::as_f64) {
+                    if value < min {
+                        push(out, path, format!("{} is less than minimum {}", value, min));
+                    }
+                }
+                if let Some(max) = map.get("maximum").and_then(Value::as_f64) {
+                    if value > max {
+                        push(out, path, format!("{} is greater than maximum {}", value, max));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(Value::Array(subs)) = map.get("allOf") {
+        for sub in subs {
+            validate_at(sub, instance, path, out);
+        }
+    }
+
+    if let Some(Value::Array(subs)) = map.get("anyOf") {
+        if !subs.iter().any(|sub| validate(sub, instance).is_empty()) {
+            push(out, path, "value does not match any schema in anyOf".to_string());
+        }
+    }
+
+    if let Some(Value::Array(subs)) = map.get("oneOf") {
+        let matching = subs
+            .iter()
+            .filter(|sub| validate(sub, instance).is_empty())
+            .count();
+        if matching != 1 {
+            push(
+                out,
+                path,
+                format!("value must match exactly one schema in oneOf (matched {})", matching),
+            );
+        }
+    }
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn person_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name", "tags"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn accepts_conforming_instance() {
+        let instance = json!({ "name": "Ada", "age": 36, "tags": ["math"] });
+        assert!(validate(&person_schema(), &instance).is_empty());
+    }
+
+    #[test]
+    fn reports_violations_with_paths() {
+        let instance = json!({ "name": "", "age": -1, "tags": ["a", 2, "c"], "extra": true });
+        let violations = validate(&person_schema(), &instance);
+        let rendered: Vec<String> = violations.iter().map(ToString::to_string).collect();
+
+        assert!(rendered.iter().any(|v| v.starts_with("/name:")));
+        assert!(rendered.iter().any(|v| v.starts_with("/age:")));
+        assert!(rendered.iter().any(|v| v.starts_with("/tags/1:")));
+        assert!(rendered.iter().any(|v| v.contains("at most 2 items")));
+        assert!(rendered.iter().any(|v| v.contains("unexpected property 'extra'")));
+    }
+
+    #[test]
+    fn reports_missing_required_property() {
+        let violations = validate(&person_schema(), &json!({ "name": "Ada" }));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message, "missing required property 'tags'");
+    }
+
+    #[test]
+    fn parses_fenced_json_responses() {
+        let response = "```json\n{\"ok\": true}\n```";
+        assert_eq!(parse_structured_output(response).unwrap(), json!({ "ok": true }));
+        assert!(validate_response(&json!({ "type": "object" }), "not json")[0]
+            .message
+            .starts_with("response is not valid JSON"));
+    }
+
+    #[test]
+    fn combinators_and_enums() {
+        let schema = json!({ "anyOf": [{ "type": "string", "enum": ["yes", "no"] }, { "type": "null" }] });
+        assert!(validate(&schema, &json!("yes")).is_empty());
+        assert!(validate(&schema, &Value::Null).is_empty());
+        assert_eq!(validate(&schema, &json!("maybe")).len(), 1);
+    }
+
+    #[test]
+    fn schema_hash_is_key_order_independent() {
+        let a = json!({ "type": "object", "required": ["x"] });
+        let b: Value = serde_json::from_str(r#"{"required":["x"],"type":"object"}"#).unwrap();
+        assert_eq!(schema_sha256(&a), schema_sha256(&b));
+    }
+
+    #[test]
+    fn check_schema_rejects_bad_patterns() {
+        assert!(check_schema(&json!({ "type": "string", "pattern": "(" })).is_err());
+        assert!(check_schema(&json!("string")).is_err());
+        assert!(check_schema(&json!(true)).is_ok());
+    }
+}
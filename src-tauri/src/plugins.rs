@@ -0,0 +1,675 @@
+// src-tauri/src/plugins.rs
+//!
+//! Plugins: third-party step types running as sandboxed WASM modules
+//!
+//! Each plugin lives in its own directory under `<app data>/plugins/` and is
+//! described by a `plugin.json` manifest:
+//!
+//! - `id`, `name`, `version`
+//! - `module`: file name of the WASM module (default `plugin.wasm`)
+//! - `moduleSha256`: pinned digest; a module that does not match is not loaded
+//! - `stepTypes`: the step types the plugin provides
+//! - `capabilities`: what the host may give the plugin (see `PluginCapability`)
+//! - `maxFuel`: instruction budget for one step execution
+//! - `maxMemoryBytes`: linear memory one step execution may grow to; growing
+//!   past it traps
+//!
+//! Modules export `memory`, `alloc(len: i32) -> i32` and
+//! `run(ptr: i32, len: i32) -> i64`. `run` receives the UTF-8 JSON
+//! `PluginInput` and returns `(ptr << 32) | len` of a JSON `PluginOutput`.
+//! Modules can only import host functions granted by their capabilities, so a
+//! plugin has no filesystem, network or clock access of its own.
+//!
+//! Plugin steps are ordinary checkpoints: the canonical input, which names the
+//! plugin version and module digest, is hashed into `inputs_sha256`, reported
+//! usage counts against budgets, and the step config travels with the CAR.
+
+use crate::provenance;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+use wasmi::{
+    Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TrapCode,
+};
+
+pub const MANIFEST_FILE: &str = "plugin.json";
+pub const DEFAULT_MAX_FUEL: u64 = 5_000_000_000;
+pub const DEFAULT_MAX_MEMORY_BYTES: u64 = 256 * 1024 * 1024;
+const MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+const MAX_LOG_BYTES: usize = 4096;
+
+static PLUGIN_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z0-9][a-z0-9_-]{0,63}$").expect("valid plugin id regex"));
+
+static GLOBAL_REGISTRY: Lazy<RwLock<PluginRegistry>> =
+    Lazy::new(|| RwLock::new(PluginRegistry::new()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    /// Receive the output of the step named by the config's `sourceStep`
+    ReadStepOutput,
+    /// Receive the contents of the file named by the config's `sourcePath`
+    ReadSourceFile,
+    /// Import `intelexta.log(ptr, len)` to write diagnostics to the app log
+    Log,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginStepType {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default = "default_module")]
+    pub module: String,
+    pub module_sha256: String,
+    pub step_types: Vec<PluginStepType>,
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+    #[serde(default = "default_max_fuel")]
+    pub max_fuel: u64,
+    #[serde(default = "default_max_memory_bytes")]
+    pub max_memory_bytes: u64,
+}
+
+fn default_module() -> String {
+    "plugin.wasm".to_string()
+}
+
+fn default_max_fuel() -> u64 {
+    DEFAULT_MAX_FUEL
+}
+
+fn default_max_memory_bytes() -> u64 {
+    DEFAULT_MAX_MEMORY_BYTES
+}
+
+impl PluginManifest {
+    pub fn check(&self) -> Result<()> {
+        if !PLUGIN_ID.is_match(&self.id) {
+            return Err(anyhow!(
+                "plugin id '{}' must be lowercase letters, digits, '-' or '_'",
+                self.id
+            ));
+        }
+        if self.module.is_empty()
+            || self.module.contains(['/', '\\'])
+            || self.module.starts_with('.')
+        {
+            return Err(anyhow!(
+                "plugin module '{}' must be a plain file name",
+                self.module
+            ));
+        }
+        if self.module_sha256.len() != 64 || hex::decode(&self.module_sha256).is_err() {
+            return Err(anyhow!("plugin moduleSha256 must be a hex sha256 digest"));
+        }
+        if self.step_types.is_empty() {
+            return Err(anyhow!("plugin {} declares no step types", self.id));
+        }
+        let mut names = BTreeSet::new();
+        for step_type in &self.step_types {
+            if step_type.name.trim().is_empty() || !names.insert(step_type.name.as_str()) {
+                return Err(anyhow!(
+                    "plugin {} has an empty or duplicate step type '{}'",
+                    self.id,
+                    step_type.name
+                ));
+            }
+        }
+        if self.max_fuel == 0 {
+            return Err(anyhow!("plugin {} has no fuel", self.id));
+        }
+        if self.max_memory_bytes == 0 {
+            return Err(anyhow!("plugin {} has no memory", self.id));
+        }
+        Ok(())
+    }
+
+    pub fn has_capability(&self, capability: PluginCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    pub fn provides(&self, step_type: &str) -> bool {
+        self.step_types.iter().any(|entry| entry.name == step_type)
+    }
+}
+
+/// Identity of the plugin build that produced a checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginIdentity {
+    pub id: String,
+    pub version: String,
+    pub module_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginSourceFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// JSON handed to a plugin's `run` export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInput {
+    pub plugin: PluginIdentity,
+    pub step_type: String,
+    pub config: Value,
+    pub seed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<PluginSourceFile>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUsage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+}
+
+/// JSON returned by a plugin's `run` export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginOutput {
+    #[serde(default)]
+    pub output: String,
+    #[serde(default)]
+    pub usage: PluginUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of one plugin step
+#[derive(Debug, Clone)]
+pub struct PluginExecution {
+    /// Canonical JSON of the `PluginInput`
+    pub input_json: String,
+    pub output: String,
+    pub usage: PluginUsage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginLoadError {
+    pub directory: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginListing {
+    pub plugins: Vec<PluginManifest>,
+    pub errors: Vec<PluginLoadError>,
+}
+
+struct LoadedPlugin {
+    manifest: PluginManifest,
+    module: Module,
+}
+
+pub struct PluginRegistry {
+    engine: Engine,
+    directory: Option<PathBuf>,
+    plugins: BTreeMap<String, LoadedPlugin>,
+    errors: Vec<PluginLoadError>,
+}
+
+impl Default for PluginRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        Self {
+            engine: Engine::new(&config),
+            directory: None,
+            plugins: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Load every plugin directory below `directory`. Broken plugins are
+    /// skipped and reported through `listing`.
+    pub fn load_dir(directory: &Path) -> Self {
+        let mut registry = Self::new();
+        registry.directory = Some(directory.to_path_buf());
+
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return registry,
+            Err(err) => {
+                registry.errors.push(PluginLoadError {
+                    directory: directory.display().to_string(),
+                    message: err.to_string(),
+                });
+                return registry;
+            }
+        };
+        let mut plugin_dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        plugin_dirs.sort();
+
+        for plugin_dir in plugin_dirs {
+            if let Err(err) = registry.load_plugin_dir(&plugin_dir) {
+                registry.errors.push(PluginLoadError {
+                    directory: plugin_dir.display().to_string(),
+                    message: format!("{err:#}"),
+                });
+            }
+        }
+        registry
+    }
+
+    fn load_plugin_dir(&mut self, plugin_dir: &Path) -> Result<()> {
+        let manifest_path = plugin_dir.join(MANIFEST_FILE);
+        let manifest_json = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+        let manifest: PluginManifest =
+            serde_json::from_str(&manifest_json).context("invalid plugin manifest")?;
+        manifest.check()?;
+        let wasm = std::fs::read(plugin_dir.join(&manifest.module))
+            .with_context(|| format!("failed to read module {}", manifest.module))?;
+        self.register(manifest, &wasm)
+    }
+
+    /// Verify and compile a plugin module and make its step types available
+    pub fn register(&mut self, manifest: PluginManifest, wasm: &[u8]) -> Result<()> {
+        manifest.check()?;
+        if self.plugins.contains_key(&manifest.id) {
+            return Err(anyhow!("plugin {} is already loaded", manifest.id));
+        }
+        let digest = provenance::sha256_hex(wasm);
+        if !digest.eq_ignore_ascii_case(&manifest.module_sha256) {
+            return Err(anyhow!(
+                "module digest {} does not match the manifest's moduleSha256",
+                digest
+            ));
+        }
+        let module =
+            Module::new(&self.engine, wasm).map_err(|err| anyhow!("invalid WASM module: {err}"))?;
+        self.plugins
+            .insert(manifest.id.clone(), LoadedPlugin { manifest, module });
+        Ok(())
+    }
+
+    pub fn manifest(&self, plugin_id: &str) -> Option<&PluginManifest> {
+        self.plugins.get(plugin_id).map(|plugin| &plugin.manifest)
+    }
+
+    pub fn listing(&self) -> PluginListing {
+        PluginListing {
+            plugins: self
+                .plugins
+                .values()
+                .map(|plugin| plugin.manifest.clone())
+                .collect(),
+            errors: self.errors.clone(),
+        }
+    }
+
+    /// Build the plugin input for a step and run it
+    pub fn execute(
+        &self,
+        plugin_id: &str,
+        step_type: &str,
+        config: &Value,
+        seed: u64,
+        input: Option<&str>,
+        source_file: Option<PluginSourceFile>,
+    ) -> Result<PluginExecution> {
+        let plugin = self
+            .plugins
+            .get(plugin_id)
+            .ok_or_else(|| anyhow!("plugin '{}' is not installed", plugin_id))?;
+        let manifest = &plugin.manifest;
+        if !manifest.provides(step_type) {
+            return Err(anyhow!(
+                "plugin {} does not provide step type '{}'",
+                plugin_id,
+                step_type
+            ));
+        }
+        if input.is_some() && !manifest.has_capability(PluginCapability::ReadStepOutput) {
+            return Err(anyhow!(
+                "plugin {} lacks the read_step_output capability",
+                plugin_id
+            ));
+        }
+        if source_file.is_some() && !manifest.has_capability(PluginCapability::ReadSourceFile) {
+            return Err(anyhow!(
+                "plugin {} lacks the read_source_file capability",
+                plugin_id
+            ));
+        }
+
+        let plugin_input = PluginInput {
+            plugin: PluginIdentity {
+                id: manifest.id.clone(),
+                version: manifest.version.clone(),
+                module_sha256: manifest.module_sha256.to_ascii_lowercase(),
+            },
+            step_type: step_type.to_string(),
+            config: config.clone(),
+            seed,
+            input: input.map(str::to_string),
+            source_file,
+        };
+        let input_json = String::from_utf8(provenance::canonical_json(&plugin_input))
+            .context("plugin input is not valid UTF-8")?;
+
+        let raw_output = self.invoke(plugin, input_json.as_bytes())?;
+        let output: PluginOutput = serde_json::from_slice(&raw_output)
+            .with_context(|| format!("plugin {} returned invalid JSON", plugin_id))?;
+        if let Some(error) = output.error {
+            return Err(anyhow!("plugin {} failed: {}", plugin_id, error));
+        }
+
+        Ok(PluginExecution {
+            input_json,
+            output: output.output,
+            usage: output.usage,
+        })
+    }
+
+    fn invoke(&self, plugin: &LoadedPlugin, input: &[u8]) -> Result<Vec<u8>> {
+        let manifest = &plugin.manifest;
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(usize::try_from(manifest.max_memory_bytes).unwrap_or(usize::MAX))
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store
+            .set_fuel(manifest.max_fuel)
+            .map_err(|err| anyhow!("failed to set plugin fuel: {err}"))?;
+
+        let mut linker = Linker::<StoreLimits>::new(&self.engine);
+        if manifest.has_capability(PluginCapability::Log) {
+            let plugin_id = manifest.id.clone();
+            linker
+                .func_wrap(
+                    "intelexta",
+                    "log",
+                    move |caller: Caller<'_, StoreLimits>, ptr: i32, len: i32| {
+                        let len = (len.max(0) as usize).min(MAX_LOG_BYTES);
+                        if let Some(bytes) = read_caller_memory(&caller, ptr, len) {
+                            eprintln!("[plugin {}] {}", plugin_id, String::from_utf8_lossy(&bytes));
+                        }
+                    },
+                )
+                .map_err(|err| anyhow!("failed to link plugin host functions: {err}"))?;
+        }
+
+        let trap = |err: wasmi::Error| match err.as_trap_code() {
+            Some(TrapCode::OutOfFuel) => anyhow!(
+                "plugin {} exceeded its fuel limit of {}",
+                manifest.id,
+                manifest.max_fuel
+            ),
+            Some(TrapCode::GrowthOperationLimited) => anyhow!(
+                "plugin {} exceeded its memory limit of {} bytes",
+                manifest.id,
+                manifest.max_memory_bytes
+            ),
+            _ => anyhow!("plugin {} trapped: {}", manifest.id, err),
+        };
+
+        let instance = linker
+            .instantiate_and_start(&mut store, &plugin.module)
+            .map_err(|err| anyhow!("failed to instantiate plugin {}: {}", manifest.id, err))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("plugin {} does not export memory", manifest.id))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|err| anyhow!("plugin {} has no usable alloc export: {}", manifest.id, err))?;
+        let run = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "run")
+            .map_err(|err| anyhow!("plugin {} has no usable run export: {}", manifest.id, err))?;
+
+        let input_len = i32::try_from(input.len()).context("plugin input is too large")?;
+        let input_ptr = alloc.call(&mut store, input_len).map_err(trap)?;
+        memory
+            .write(&mut store, input_ptr as u32 as usize, input)
+            .map_err(|err| anyhow!("plugin {} returned an invalid buffer: {}", manifest.id, err))?;
+
+        let packed = run.call(&mut store, (input_ptr, input_len)).map_err(trap)? as u64;
+        let output_ptr = (packed >> 32) as usize;
+        let output_len = (packed & 0xffff_ffff) as usize;
+        if output_len > MAX_OUTPUT_BYTES {
+            return Err(anyhow!(
+                "plugin {} output exceeds {} bytes",
+                manifest.id,
+                MAX_OUTPUT_BYTES
+            ));
+        }
+        let mut output = vec![0u8; output_len];
+        memory
+            .read(&store, output_ptr, &mut output)
+            .map_err(|err| anyhow!("plugin {} returned an invalid output: {}", manifest.id, err))?;
+        Ok(output)
+    }
+}
+
+fn read_caller_memory(caller: &Caller<'_, StoreLimits>, ptr: i32, len: usize) -> Option<Vec<u8>> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let mut buffer = vec![0u8; len];
+    memory.read(caller, ptr as u32 as usize, &mut buffer).ok()?;
+    Some(buffer)
+}
+
+/// Load the plugins installed under `<app data>/plugins`
+pub fn init_global_registry(app_data_dir: &Path) -> Result<()> {
+    let directory = app_data_dir.join("plugins");
+    std::fs::create_dir_all(&directory)
+        .with_context(|| format!("failed to create {}", directory.display()))?;
+    *GLOBAL_REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = PluginRegistry::load_dir(&directory);
+    Ok(())
+}
+
+/// Re-read the plugin directory, e.g. after installing a plugin
+pub fn reload_global_registry() -> Result<PluginListing> {
+    let mut registry = GLOBAL_REGISTRY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    let directory = registry
+        .directory
+        .clone()
+        .ok_or_else(|| anyhow!("plugin registry has not been initialized"))?;
+    *registry = PluginRegistry::load_dir(&directory);
+    Ok(registry.listing())
+}
+
+pub fn list_plugins() -> PluginListing {
+    GLOBAL_REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .listing()
+}
+
+pub fn plugin_manifest(plugin_id: &str) -> Option<PluginManifest> {
+    GLOBAL_REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .manifest(plugin_id)
+        .cloned()
+}
+
+/// Run a plugin step with the globally installed plugins
+pub fn execute_plugin_step(
+    plugin_id: &str,
+    step_type: &str,
+    config: &Value,
+    seed: u64,
+    input: Option<&str>,
+    source_file: Option<PluginSourceFile>,
+) -> Result<PluginExecution> {
+    GLOBAL_REGISTRY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .execute(plugin_id, step_type, config, seed, input, source_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Echo plugin in WAT text form, which wasmi compiles like a binary module.
+    /// Returns a fixed JSON document, or loops forever when `spin` is set.
+    fn echo_module(body: &str, spin: bool) -> Vec<u8> {
+        let output = format!(r#"{{"output":"{body}","usage":{{"completionTokens":3}}}}"#);
+        let run_body = if spin {
+            "(loop $forever (br $forever)) (i64.const 0)".to_string()
+        } else {
+            format!(
+                "(i64.or (i64.shl (i64.const 1024) (i64.const 32)) (i64.const {}))",
+                output.len()
+            )
+        };
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{}")
+                (func (export "alloc") (param i32) (result i32) (i32.const 4096))
+                (func (export "run") (param i32 i32) (result i64) {run_body}))"#,
+            output.replace('"', "\\\"")
+        );
+        wat.into_bytes()
+    }
+
+    fn manifest_for(wasm: &[u8], capabilities: Vec<PluginCapability>) -> PluginManifest {
+        PluginManifest {
+            id: "echo".to_string(),
+            name: "Echo".to_string(),
+            version: "1.0.0".to_string(),
+            module: default_module(),
+            module_sha256: provenance::sha256_hex(wasm),
+            step_types: vec![PluginStepType {
+                name: "echo".to_string(),
+                description: String::new(),
+            }],
+            capabilities,
+            max_fuel: 1_000_000,
+            max_memory_bytes: DEFAULT_MAX_MEMORY_BYTES,
+        }
+    }
+
+    #[test]
+    fn runs_plugin_and_commits_to_its_identity() {
+        let wasm = echo_module("hello", false);
+        let mut registry = PluginRegistry::new();
+        registry
+            .register(manifest_for(&wasm, Vec::new()), &wasm)
+            .unwrap();
+
+        let execution = registry
+            .execute("echo", "echo", &json!({"mode": "upper"}), 7, None, None)
+            .unwrap();
+        assert_eq!(execution.output, "hello");
+        assert_eq!(execution.usage.completion_tokens, 3);
+        let input: PluginInput = serde_json::from_str(&execution.input_json).unwrap();
+        assert_eq!(input.plugin.module_sha256, provenance::sha256_hex(&wasm));
+        assert_eq!(input.seed, 7);
+
+        assert!(registry
+            .execute("echo", "other", &Value::Null, 7, None, None)
+            .is_err());
+        assert!(registry
+            .execute("echo", "echo", &Value::Null, 7, Some("prior output"), None)
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_modules_that_do_not_match_the_manifest() {
+        let wasm = echo_module("hello", false);
+        let mut manifest = manifest_for(&wasm, Vec::new());
+        manifest.module_sha256 = provenance::sha256_hex(b"something else");
+        assert!(PluginRegistry::new().register(manifest, &wasm).is_err());
+
+        let mut manifest = manifest_for(&wasm, Vec::new());
+        manifest.id = "../escape".to_string();
+        assert!(manifest.check().is_err());
+    }
+
+    #[test]
+    fn runaway_plugins_stop_at_the_fuel_limit() {
+        let wasm = echo_module("never", true);
+        let mut registry = PluginRegistry::new();
+        registry
+            .register(manifest_for(&wasm, Vec::new()), &wasm)
+            .unwrap();
+        let err = registry
+            .execute("echo", "echo", &Value::Null, 0, None, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("fuel"), "{err}");
+    }
+
+    #[test]
+    fn plugins_trap_when_growing_past_the_memory_limit() {
+        let wasm = r#"(module
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                (func (export "run") (param i32 i32) (result i64)
+                    (drop (memory.grow (i32.const 64)))
+                    (i64.const 0)))"#
+            .as_bytes()
+            .to_vec();
+        let mut manifest = manifest_for(&wasm, Vec::new());
+        manifest.max_memory_bytes = 1024 * 1024;
+        let mut registry = PluginRegistry::new();
+        registry.register(manifest, &wasm).unwrap();
+        let err = registry
+            .execute("echo", "echo", &Value::Null, 0, None, None)
+            .unwrap_err();
+        assert!(err.to_string().contains("memory limit"), "{err}");
+    }
+
+    #[test]
+    fn plugins_cannot_import_ungranted_host_functions() {
+        let wasm = r#"(module
+                (import "intelexta" "log" (func $log (param i32 i32)))
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                (func (export "run") (param i32 i32) (result i64) (i64.const 0)))"#
+            .as_bytes()
+            .to_vec();
+        let mut registry = PluginRegistry::new();
+        registry
+            .register(manifest_for(&wasm, Vec::new()), &wasm)
+            .unwrap();
+        assert!(registry
+            .execute("echo", "echo", &Value::Null, 0, None, None)
+            .is_err());
+    }
+}
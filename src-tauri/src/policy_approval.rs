@@ -0,0 +1,219 @@
+// src-tauri/src/policy_approval.rs
+//!
+//! Policy approval: two-step policy changes
+//!
+//! A proposed policy is stored with the version it was based on and does not
+//! apply until it is approved. Reviewers see it diffed, field by field,
+//! against the project's current policy. Approving creates the next policy
+//! version with the proposer as its author and the approver recorded next to
+//! it. Nobody approves their own proposal, and a proposal made against an
+//! older version than the current one has to be proposed again, so an
+//! approval never silently reverts a change made in between.
+//!
+//! A policy with `requireChangeApproval` refuses direct updates: from then on
+//! every change, including turning the requirement off, goes through here.
+
+use crate::car_diff::{self, Change};
+use crate::store::{
+    policies::{self, Policy, PolicyVersion},
+    policy_proposals::{self, PolicyProposal, STATUS_APPROVED, STATUS_PENDING, STATUS_REJECTED},
+};
+use crate::{dashboard, Error};
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// A proposal with what it changes relative to the current policy
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalReview {
+    pub proposal: PolicyProposal,
+    pub current_version: i64,
+    /// Pending, but the policy changed since it was proposed; it cannot be approved
+    pub stale: bool,
+    /// Fields that differ, with the current value on the left
+    pub changes: Vec<Change>,
+}
+
+/// Refuse a direct policy update when the current policy requires approval
+pub fn ensure_direct_update_allowed(conn: &Connection, project_id: &str) -> Result<(), Error> {
+    if policies::get(conn, project_id)?.require_change_approval {
+        return Err(Error::Api(
+            "this project's policy changes need approval: propose the change instead".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// The policy the project has now, as a proposal would replace it
+fn current_policy(conn: &Connection, project_id: &str) -> Result<Policy, Error> {
+    match policies::get_project_layer(conn, project_id)? {
+        Some(policy) => Ok(policy),
+        None => policies::get(conn, project_id),
+    }
+}
+
+fn diff_policies(current: &Policy, proposed: &Policy) -> Result<Vec<Change>, Error> {
+    let to_value = |policy: &Policy| {
+        serde_json::to_value(policy)
+            .map_err(|err| Error::Api(format!("failed to serialize policy: {err}")))
+    };
+    let mut changes = Vec::new();
+    car_diff::diff_values("", &to_value(current)?, &to_value(proposed)?, &mut changes);
+    Ok(changes)
+}
+
+fn review(conn: &Connection, proposal: PolicyProposal) -> Result<ProposalReview, Error> {
+    let current_version = policies::get_current_version(conn, &proposal.project_id)?;
+    let changes = diff_policies(
+        &current_policy(conn, &proposal.project_id)?,
+        &proposal.policy,
+    )?;
+    Ok(ProposalReview {
+        stale: proposal.status == STATUS_PENDING && proposal.base_version != current_version,
+        current_version,
+        changes,
+        proposal,
+    })
+}
+
+fn require_name(name: &str, role: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::Api(format!("a policy change needs its {role}")));
+    }
+    Ok(name.to_string())
+}
+
+/// Store a proposed policy for someone else to approve; it does not apply yet
+pub fn propose_policy_change(
+    conn: &Connection,
+    project_id: &str,
+    policy: &Policy,
+    proposed_by: &str,
+    change_notes: Option<&str>,
+) -> Result<ProposalReview, Error> {
+    dashboard::ensure_project(conn, project_id)?;
+    let proposed_by = require_name(proposed_by, "proposer")?;
+    if let Some(default) = policies::get_default(conn)? {
+        let loosened = policy.loosened_fields(&default);
+        if !loosened.is_empty() {
+            return Err(Error::Api(format!(
+                "policy loosens what the default policy caps: {}",
+                loosened.join(", ")
+            )));
+        }
+    }
+    if diff_policies(&current_policy(conn, project_id)?, policy)?.is_empty() {
+        return Err(Error::Api("the proposed policy changes nothing".into()));
+    }
+
+    let proposal = PolicyProposal {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_id: project_id.to_string(),
+        base_version: policies::get_current_version(conn, project_id)?,
+        policy: policy.clone(),
+        proposed_by,
+        change_notes: change_notes
+            .map(str::trim)
+            .filter(|notes| !notes.is_empty())
+            .map(str::to_string),
+        proposed_at: Utc::now().to_rfc3339(),
+        status: STATUS_PENDING.to_string(),
+        decided_by: None,
+        decided_at: None,
+        approved_version: None,
+    };
+    policy_proposals::insert(conn, &proposal)?;
+    review(conn, proposal)
+}
+
+fn load_pending(conn: &Connection, proposal_id: &str) -> Result<PolicyProposal, Error> {
+    let proposal = policy_proposals::get(conn, proposal_id)?
+        .ok_or_else(|| Error::Api(format!("policy proposal {proposal_id} not found")))?;
+    if proposal.status != STATUS_PENDING {
+        return Err(Error::Api(format!(
+            "policy proposal {proposal_id} was already {} by {}",
+            proposal.status,
+            proposal.decided_by.as_deref().unwrap_or("someone")
+        )));
+    }
+    Ok(proposal)
+}
+
+/// Apply a pending proposal as the project's next policy version
+pub fn approve_policy_change(
+    conn: &mut Connection,
+    proposal_id: &str,
+    approved_by: &str,
+) -> Result<PolicyVersion, Error> {
+    let approved_by = require_name(approved_by, "approver")?;
+    let proposal = load_pending(conn, proposal_id)?;
+    if approved_by.eq_ignore_ascii_case(&proposal.proposed_by) {
+        return Err(Error::Api(
+            "a policy change must be approved by someone other than its proposer".into(),
+        ));
+    }
+    let current_version = policies::get_current_version(conn, &proposal.project_id)?;
+    if proposal.base_version != current_version {
+        return Err(Error::Api(format!(
+            "policy proposal {proposal_id} was made against version {} but the policy is \
+             now at version {current_version}; propose the change again",
+            proposal.base_version
+        )));
+    }
+
+    let tx = conn.transaction()?;
+    let version = policies::upsert_approved(
+        &tx,
+        &proposal.project_id,
+        &proposal.policy,
+        &proposal.proposed_by,
+        proposal.change_notes.as_deref(),
+        &approved_by,
+    )?;
+    policy_proposals::decide(
+        &tx,
+        proposal_id,
+        STATUS_APPROVED,
+        &approved_by,
+        &Utc::now().to_rfc3339(),
+        Some(version),
+    )?;
+    tx.commit()?;
+
+    policies::get_version(conn, &proposal.project_id, version)?
+        .ok_or_else(|| Error::Api(format!("policy version {version} was not recorded")))
+}
+
+/// Decline a pending proposal; the policy stays as it is
+pub fn reject_policy_change(
+    conn: &Connection,
+    proposal_id: &str,
+    rejected_by: &str,
+) -> Result<PolicyProposal, Error> {
+    let rejected_by = require_name(rejected_by, "reviewer")?;
+    load_pending(conn, proposal_id)?;
+    policy_proposals::decide(
+        conn,
+        proposal_id,
+        STATUS_REJECTED,
+        &rejected_by,
+        &Utc::now().to_rfc3339(),
+        None,
+    )?;
+    policy_proposals::get(conn, proposal_id)?
+        .ok_or_else(|| Error::Api(format!("policy proposal {proposal_id} not found")))
+}
+
+/// The project's proposals, newest first, each diffed against the current policy
+pub fn list_policy_proposals(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<Vec<ProposalReview>, Error> {
+    dashboard::ensure_project(conn, project_id)?;
+    policy_proposals::list(conn, project_id)?
+        .into_iter()
+        .map(|proposal| review(conn, proposal))
+        .collect()
+}
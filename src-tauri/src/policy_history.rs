@@ -0,0 +1,329 @@
+// src-tauri/src/policy_history.rs
+//!
+//! Signed policy history: a project's policy lineage as standalone evidence
+//!
+//! The bundle lists every policy version oldest first. Each entry commits to
+//! the canonical policy JSON (`policySha256`) and to the previous entry
+//! (`prevHash`), and `entryHash` hashes the canonical entry without itself, so
+//! dropping, reordering or editing a version breaks the chain. The bundle body
+//! (everything but `signature`) is signed with the project's signing key, the
+//! same key its CARs are signed with.
+//!
+//! `intelexta-verify` recognizes bundles by their `format` and checks them
+//! without the application or its database.
+
+use crate::provenance;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+#[cfg(feature = "app")]
+use chrono::Utc;
+#[cfg(feature = "app")]
+use ed25519_dalek::SigningKey;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+#[cfg(feature = "app")]
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const POLICY_HISTORY_FORMAT: &str = "intelexta.policy_history.v1";
+
+const SIGNATURE_PREFIX: &str = "ed25519-body:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyHistoryEntry {
+    pub version: i64,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_notes: Option<String>,
+    /// The policy as stored for this version
+    pub policy: Value,
+    /// sha256 of the canonical policy JSON
+    pub policy_sha256: String,
+    /// `entryHash` of the previous version; empty for the first
+    pub prev_hash: String,
+    /// sha256 of the canonical entry without this field
+    pub entry_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyHistoryBundle {
+    pub format: String,
+    pub project_id: String,
+    pub exported_at: String,
+    /// Versions oldest first, starting at 1 without gaps
+    pub versions: Vec<PolicyHistoryEntry>,
+    /// `entryHash` of the latest version
+    pub head_hash: String,
+    pub signer_public_key: String,
+    /// `ed25519-body:<base64>` over the canonical bundle without this field
+    #[serde(default)]
+    pub signature: String,
+}
+
+/// What a verified bundle attests to
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyHistoryVerification {
+    pub project_id: String,
+    pub versions_verified: usize,
+    pub current_version: i64,
+    pub head_hash: String,
+    pub signer_public_key: String,
+}
+
+fn entry_hash(entry: &PolicyHistoryEntry) -> Result<String> {
+    let mut value = serde_json::to_value(entry)?;
+    if let Value::Object(ref mut fields) = value {
+        fields.remove("entryHash");
+    }
+    Ok(provenance::sha256_hex(&provenance::canonical_json(&value)))
+}
+
+/// Chain the policy versions of a project and sign the bundle
+#[cfg(feature = "app")]
+pub fn build_bundle(
+    conn: &Connection,
+    project_id: &str,
+    signing_key: &SigningKey,
+) -> Result<PolicyHistoryBundle> {
+    let mut stmt = conn.prepare(
+        "SELECT version, policy_json, created_at, created_by, change_notes
+         FROM policy_versions WHERE project_id = ?1 ORDER BY version ASC",
+    )?;
+    let rows = stmt
+        .query_map(params![project_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if rows.is_empty() {
+        return Err(anyhow!("project {project_id} has no policy versions"));
+    }
+
+    let mut versions = Vec::with_capacity(rows.len());
+    let mut prev_hash = String::new();
+    for (version, policy_json, created_at, created_by, change_notes) in rows {
+        let policy: Value = serde_json::from_str(&policy_json)
+            .with_context(|| format!("policy version {version} is not valid JSON"))?;
+        let mut entry = PolicyHistoryEntry {
+            version,
+            created_at,
+            created_by,
+            change_notes,
+            policy_sha256: provenance::sha256_hex(&provenance::canonical_json(&policy)),
+            policy,
+            prev_hash,
+            entry_hash: String::new(),
+        };
+        entry.entry_hash = entry_hash(&entry)?;
+        prev_hash = entry.entry_hash.clone();
+        versions.push(entry);
+    }
+
+    let mut bundle = PolicyHistoryBundle {
+        format: POLICY_HISTORY_FORMAT.to_string(),
+        project_id: project_id.to_string(),
+        exported_at: Utc::now().to_rfc3339(),
+        versions,
+        head_hash: prev_hash,
+        signer_public_key: provenance::public_key_from_secret(signing_key),
+        signature: String::new(),
+    };
+    let body = body_canonical(&serde_json::to_value(&bundle)?);
+    bundle.signature = format!(
+        "{SIGNATURE_PREFIX}{}",
+        provenance::sign_bytes(signing_key, &body)
+    );
+    Ok(bundle)
+}
+
+fn body_canonical(bundle: &Value) -> Vec<u8> {
+    let mut body = bundle.clone();
+    if let Value::Object(ref mut fields) = body {
+        fields.remove("signature");
+    }
+    provenance::canonical_json(&body)
+}
+
+/// Whether a JSON document is a policy history bundle rather than a CAR
+pub fn is_policy_history(raw_json: &str) -> bool {
+    serde_json::from_str::<Value>(raw_json)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("format")
+                .and_then(Value::as_str)
+                .map(str::to_owned)
+        })
+        .is_some_and(|format| format == POLICY_HISTORY_FORMAT)
+}
+
+/// Check a bundle's signature, version lineage and hash chain. The signature
+/// is checked over the raw JSON so fields this version does not know about
+/// are covered too.
+pub fn verify_bundle(raw_json: &str) -> Result<PolicyHistoryVerification> {
+    let raw: Value = serde_json::from_str(raw_json).context("bundle is not valid JSON")?;
+    let bundle: PolicyHistoryBundle =
+        serde_json::from_value(raw.clone()).context("not a policy history bundle")?;
+    if bundle.format != POLICY_HISTORY_FORMAT {
+        return Err(anyhow!("unsupported bundle format '{}'", bundle.format));
+    }
+
+    let key_bytes: [u8; 32] = STANDARD
+        .decode(&bundle.signer_public_key)
+        .context("invalid signer public key base64")?
+        .try_into()
+        .map_err(|_| anyhow!("signer public key must be 32 bytes"))?;
+    let verifying_key =
+        VerifyingKey::from_bytes(&key_bytes).context("invalid signer public key")?;
+    let signature_b64 = bundle
+        .signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .ok_or_else(|| anyhow!("bundle is not signed"))?;
+    let signature_bytes: [u8; 64] = STANDARD
+        .decode(signature_b64)
+        .context("invalid signature base64")?
+        .try_into()
+        .map_err(|_| anyhow!("signature must be 64 bytes"))?;
+    verifying_key
+        .verify(
+            &body_canonical(&raw),
+            &Signature::from_bytes(&signature_bytes),
+        )
+        .map_err(|_| anyhow!("bundle signature does not match its contents"))?;
+
+    let mut prev_hash = String::new();
+    for (position, entry) in bundle.versions.iter().enumerate() {
+        let expected_version = i64::try_from(position)? + 1;
+        if entry.version != expected_version {
+            return Err(anyhow!(
+                "expected policy version {expected_version}, found {}; versions are missing or out of order",
+                entry.version
+            ));
+        }
+        if entry.policy_sha256 != provenance::sha256_hex(&provenance::canonical_json(&entry.policy))
+        {
+            return Err(anyhow!(
+                "policy of version {} does not match its hash",
+                entry.version
+            ));
+        }
+        if entry.prev_hash != prev_hash {
+            return Err(anyhow!(
+                "version {} does not chain to the previous version",
+                entry.version
+            ));
+        }
+        if entry.entry_hash != entry_hash(entry)? {
+            return Err(anyhow!(
+                "entry hash of version {} does not match its contents",
+                entry.version
+            ));
+        }
+        prev_hash = entry.entry_hash.clone();
+    }
+    if bundle.versions.is_empty() {
+        return Err(anyhow!("bundle contains no policy versions"));
+    }
+    if bundle.head_hash != prev_hash {
+        return Err(anyhow!("head hash does not match the latest version"));
+    }
+
+    Ok(PolicyHistoryVerification {
+        project_id: bundle.project_id,
+        versions_verified: bundle.versions.len(),
+        current_version: bundle.versions.last().map_or(0, |entry| entry.version),
+        head_hash: bundle.head_hash,
+        signer_public_key: bundle.signer_public_key,
+    })
+}
+
+#[cfg(all(test, feature = "app"))]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::store::migrations::runner()
+            .to_latest(&mut conn)
+            .unwrap();
+        conn
+    }
+
+    fn signed_history() -> String {
+        let conn = test_conn();
+        for (budget, notes) in [
+            (1_000, "initial"),
+            (5_000, "raise budget"),
+            (5_000, "allow network"),
+        ] {
+            conn.execute(
+                "INSERT INTO policy_versions (project_id, version, policy_json, created_by, change_notes)
+                 VALUES ('p1', (SELECT COALESCE(MAX(version), 0) + 1 FROM policy_versions WHERE project_id = 'p1'), ?1, 'reviewer', ?2)",
+                params![
+                    serde_json::json!({ "budgetTokens": budget, "allowNetwork": notes == "allow network" })
+                        .to_string(),
+                    notes
+                ],
+            )
+            .unwrap();
+        }
+        let bundle = build_bundle(&conn, "p1", &SigningKey::from_bytes(&[9u8; 32])).unwrap();
+        serde_json::to_string_pretty(&bundle).unwrap()
+    }
+
+    #[test]
+    fn signed_history_verifies_standalone() {
+        let raw = signed_history();
+        assert!(is_policy_history(&raw));
+        let verified = verify_bundle(&raw).unwrap();
+        assert_eq!(verified.versions_verified, 3);
+        assert_eq!(verified.current_version, 3);
+    }
+
+    #[test]
+    fn edited_dropped_or_resigned_versions_are_rejected() {
+        let raw = signed_history();
+        let bundle: Value = serde_json::from_str(&raw).unwrap();
+
+        let mut edited = bundle.clone();
+        edited["versions"][1]["policy"]["budgetTokens"] = serde_json::json!(1);
+        assert!(verify_bundle(&edited.to_string())
+            .unwrap_err()
+            .to_string()
+            .contains("signature"));
+
+        // A re-signed bundle with a version dropped still fails the lineage check
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut dropped = bundle.clone();
+        dropped["versions"].as_array_mut().unwrap().remove(1);
+        dropped["signature"] = serde_json::json!(format!(
+            "{SIGNATURE_PREFIX}{}",
+            provenance::sign_bytes(&signing_key, &body_canonical(&dropped))
+        ));
+        assert!(verify_bundle(&dropped.to_string())
+            .unwrap_err()
+            .to_string()
+            .contains("missing or out of order"));
+
+        let mut rechained = bundle;
+        rechained["versions"][2]["changeNotes"] = serde_json::json!("rewritten");
+        rechained["signature"] = serde_json::json!(format!(
+            "{SIGNATURE_PREFIX}{}",
+            provenance::sign_bytes(&signing_key, &body_canonical(&rechained))
+        ));
+        assert!(verify_bundle(&rechained.to_string())
+            .unwrap_err()
+            .to_string()
+            .contains("entry hash"));
+    }
+}
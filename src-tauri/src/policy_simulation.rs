@@ -0,0 +1,316 @@
+// src-tauri/src/policy_simulation.rs
+//!
+//! Policy simulation: a draft policy replayed against the project's history
+//!
+//! Before a new policy version is committed, the project's executions are
+//! replayed oldest first under the draft, with the usage their top-level
+//! checkpoints recorded (priced from the model catalog where no cost was
+//! stored, as the ledger prices it). Each execution goes through the checks
+//! the orchestrator makes before a step: its own usage against the per-run
+//! budgets, the project's usage up to and including it against the
+//! cumulative and model/provider budgets, the usage of its UTC day, week and
+//! month against the window budgets, and its models against the network
+//! policy and provider allowlist. The cumulative total runs across policy
+//! versions, as if the draft had been in force from the first execution.
+//!
+//! Incidents the draft would have raised get the draft's incident actions;
+//! the draft is tightened to the default policy first, as saving it would.
+//! Executions that recorded no usage are not replayed.
+
+use crate::{
+    dashboard, governance,
+    incident_response::{self, IncidentAction},
+    orchestrator::{self, ProjectedUsage, UsageByModel},
+    store::{
+        self,
+        policies::Policy,
+        project_usage_ledgers::{BudgetWindow, WindowUsage},
+    },
+    Error,
+};
+use chrono::{NaiveDate, NaiveTime};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SimulatedOutcome {
+    /// An incident's action would have stopped the execution, or held it
+    /// until someone approved it
+    Blocked,
+    /// Incidents would have been recorded and the execution carried on
+    Warned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedIncident {
+    pub kind: String,
+    pub severity: String,
+    pub details: String,
+    /// The draft's action for the incident
+    pub action: IncidentAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedExecution {
+    pub execution_id: String,
+    pub run_id: String,
+    pub run_name: String,
+    pub created_at: String,
+    pub usage_tokens: u64,
+    pub usd: f64,
+    pub nature_cost: f64,
+    pub outcome: SimulatedOutcome,
+    pub incidents: Vec<SimulatedIncident>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySimulation {
+    pub project_id: String,
+    pub executions_replayed: u64,
+    pub blocked: u64,
+    pub warned: u64,
+    /// Executions the draft would have blocked or warned on, oldest first
+    pub executions: Vec<SimulatedExecution>,
+}
+
+/// Usage one execution recorded, per pricing model
+struct ExecutionUsage {
+    execution_id: String,
+    run_id: String,
+    run_name: String,
+    created_at: String,
+    by_model: BTreeMap<String, governance::ModelUsage>,
+    total: governance::ModelUsage,
+}
+
+/// Replay the project's executions under `draft` and report those it would
+/// have blocked or warned on
+pub fn simulate_policy_change(
+    conn: &Connection,
+    project_id: &str,
+    draft: &Policy,
+) -> Result<PolicySimulation, Error> {
+    dashboard::ensure_project(conn, project_id)?;
+    let policy = match store::policies::get_default(conn)? {
+        Some(default) => draft.within(&default),
+        None => draft.clone(),
+    };
+
+    let mut cumulative = governance::ModelUsage::default();
+    let mut cumulative_by_model = BTreeMap::new();
+    let mut windows: BTreeMap<(BudgetWindow, NaiveDate), governance::ModelUsage> = BTreeMap::new();
+    let mut simulation = PolicySimulation {
+        project_id: project_id.to_string(),
+        executions_replayed: 0,
+        blocked: 0,
+        warned: 0,
+        executions: Vec::new(),
+    };
+
+    for execution in load_execution_usage(conn, project_id)? {
+        let window_usage = execution_date(&execution.created_at)
+            .map(|date| record_window_usage(&mut windows, date, execution.total))
+            .unwrap_or_default();
+        let estimates = orchestrator::estimate_costs_with_policy(
+            &policy,
+            execution.total.tokens,
+            ProjectedUsage::default(),
+            execution.total.usd,
+            execution.total.nature_cost,
+            cumulative.tokens,
+            cumulative.usd,
+            cumulative.nature_cost,
+            &UsageByModel {
+                run: execution.by_model.clone(),
+                project: cumulative_by_model.clone(),
+            },
+            &window_usage,
+        );
+
+        let mut incidents = Vec::new();
+        let overruns = estimates.blocking_overruns();
+        if !overruns.is_empty() {
+            incidents.push(governance::Incident {
+                kind: "budget_projection_exceeded".into(),
+                severity: "error".into(),
+                details: format!("Costs exceed policy budgets: {}.", overruns.join(", ")),
+            });
+        }
+        if let Some((scope, projection)) = estimates.nature_cost_overrun() {
+            incidents.push(governance::Incident {
+                kind: "nature_cost_warning".into(),
+                severity: "warn".into(),
+                details: format!(
+                    "{} Nature Cost {:.2} exceeds budget {:.2}",
+                    scope, projection.estimated_nature_cost, projection.budget_nature_cost
+                ),
+            });
+        }
+        incidents.extend(model_incidents(&policy, execution.by_model.keys()));
+
+        simulation.executions_replayed += 1;
+        cumulative.add(execution.total);
+        for (model, used) in &execution.by_model {
+            orchestrator::add_model_usage(&mut cumulative_by_model, Some(model), *used);
+        }
+
+        if incidents.is_empty() {
+            continue;
+        }
+        let incidents: Vec<SimulatedIncident> = incidents
+            .into_iter()
+            .map(|incident| SimulatedIncident {
+                action: incident_response::action_for(&policy.incident_actions, &incident),
+                kind: incident.kind,
+                severity: incident.severity,
+                details: incident.details,
+            })
+            .collect();
+        let outcome = if incidents.iter().any(|incident| {
+            matches!(
+                incident.action,
+                IncidentAction::Block | IncidentAction::PauseForApproval
+            )
+        }) {
+            simulation.blocked += 1;
+            SimulatedOutcome::Blocked
+        } else {
+            simulation.warned += 1;
+            SimulatedOutcome::Warned
+        };
+        simulation.executions.push(SimulatedExecution {
+            execution_id: execution.execution_id,
+            run_id: execution.run_id,
+            run_name: execution.run_name,
+            created_at: execution.created_at,
+            usage_tokens: execution.total.tokens,
+            usd: execution.total.usd,
+            nature_cost: execution.total.nature_cost,
+            outcome,
+            incidents,
+        });
+    }
+
+    Ok(simulation)
+}
+
+/// Network and provider allowlist incidents for the models an execution used
+fn model_incidents<'a>(
+    policy: &Policy,
+    models: impl Iterator<Item = &'a String>,
+) -> Vec<governance::Incident> {
+    let mut incidents = Vec::new();
+    let mut network_models = BTreeSet::new();
+    for model in models {
+        if orchestrator::model_requires_network(model) {
+            network_models.insert(model.as_str());
+        }
+        if let Err(incident) = governance::enforce_provider_policy(policy, model) {
+            incidents.push(incident);
+        }
+    }
+    if !network_models.is_empty() {
+        if let Err(mut incident) = governance::enforce_network_policy(policy) {
+            let models: Vec<&str> = network_models.into_iter().collect();
+            incident.details = format!("{} ({})", incident.details, models.join(", "));
+            incidents.insert(0, incident);
+        }
+    }
+    incidents
+}
+
+/// Add an execution's usage to its day, week and month, returning each
+/// window's usage including it
+fn record_window_usage(
+    windows: &mut BTreeMap<(BudgetWindow, NaiveDate), governance::ModelUsage>,
+    date: NaiveDate,
+    used: governance::ModelUsage,
+) -> Vec<WindowUsage> {
+    let at = date.and_time(NaiveTime::MIN).and_utc();
+    BudgetWindow::ALL
+        .into_iter()
+        .map(|window| {
+            let window_start = window.start(at);
+            let total = windows.entry((window, window_start)).or_default();
+            total.add(used);
+            WindowUsage {
+                window,
+                window_start: window_start.to_string(),
+                total_tokens: total.tokens,
+                total_usd: total.usd,
+                total_nature_cost: total.nature_cost,
+            }
+        })
+        .collect()
+}
+
+/// UTC date of an execution; `created_at` is RFC 3339 or SQLite's
+/// `CURRENT_TIMESTAMP`, both of which start with the date
+fn execution_date(created_at: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(created_at.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Usage of the project's executions, oldest first
+fn load_execution_usage(conn: &Connection, project_id: &str) -> Result<Vec<ExecutionUsage>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.run_id, r.name, e.created_at, s.model, s.config_json, SUM(c.usage_tokens),
+                SUM(c.usd_cost), SUM(CASE WHEN c.usd_cost IS NULL THEN c.usage_tokens ELSE 0 END),
+                SUM(c.nature_cost), SUM(CASE WHEN c.nature_cost IS NULL THEN c.usage_tokens ELSE 0 END)
+         FROM run_executions e
+         JOIN runs r ON r.id = e.run_id
+         JOIN checkpoints c ON c.run_execution_id = e.id
+         LEFT JOIN run_steps s ON s.id = c.checkpoint_config_id
+         WHERE r.project_id = ?1 AND c.parent_checkpoint_id IS NULL AND c.usage_tokens > 0
+         GROUP BY e.id, c.checkpoint_config_id
+         ORDER BY datetime(e.created_at), e.id",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        let model: Option<String> = row.get(4)?;
+        let config_json: Option<String> = row.get(5)?;
+        let model = orchestrator::pricing_model(config_json.as_deref(), model.as_deref());
+        let unpriced_usd_tokens = row.get::<_, i64>(8)?.max(0) as u64;
+        let unpriced_nature_tokens = row.get::<_, i64>(10)?.max(0) as u64;
+        let used = governance::ModelUsage {
+            tokens: row.get::<_, i64>(6)?.max(0) as u64,
+            usd: row.get::<_, Option<f64>>(7)?.unwrap_or(0.0)
+                + governance::estimate_usd_cost(unpriced_usd_tokens, model.as_deref()),
+            nature_cost: row.get::<_, Option<f64>>(9)?.unwrap_or(0.0)
+                + governance::estimate_nature_cost(unpriced_nature_tokens, model.as_deref()),
+        };
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            model,
+            used,
+        ))
+    })?;
+
+    let mut executions: Vec<ExecutionUsage> = Vec::new();
+    for row in rows {
+        let (execution_id, run_id, run_name, created_at, model, used) = row?;
+        if executions
+            .last()
+            .is_none_or(|last| last.execution_id != execution_id)
+        {
+            executions.push(ExecutionUsage {
+                execution_id,
+                run_id,
+                run_name,
+                created_at,
+                by_model: BTreeMap::new(),
+                total: governance::ModelUsage::default(),
+            });
+        }
+        let execution = executions.last_mut().expect("execution was just pushed");
+        execution.total.add(used);
+        orchestrator::add_model_usage(&mut execution.by_model, model.as_deref(), used);
+    }
+    Ok(executions)
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
@@ -9,12 +9,13 @@ use chrono::Utc;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use rusqlite::{params, types::Type, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use intelexta_archive::{ArchiveLimits, SafeArchive};
 use zip::write::FileOptions;
 
 use crate::{
-    car, governance, provenance, replay,
+    car, car_cbor, governance, incident_log, provenance, replay,
     store::{self, policies::Policy},
-    DbPool, Error, Project,
+    verify, DbPool, Error, Project,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -86,6 +87,18 @@ struct CheckpointExport {
     prompt_tokens: u64,
     completion_tokens: u64,
     semantic_digest: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cache_hit: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    response_metadata: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_inputs_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output_schema_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    usd_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nature_cost: Option<f64>,
     message: Option<CheckpointMessageExport>,
     payload: Option<CheckpointPayloadExport>,
 }
@@ -99,6 +112,8 @@ struct ReceiptExport {
     epsilon: Option<f64>,
     s_grade: Option<i64>,
     car_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_execution_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -110,6 +125,13 @@ pub(crate) struct PolicyVersionExport {
     created_at: String,
     created_by: Option<String>,
     change_notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    approved_at: Option<String>,
+    /// Default policy the version resolves against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_policy_json: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -129,6 +151,10 @@ struct RunExecutionExport {
     id: String,
     run_id: String,
     created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    environment: Option<crate::run_environment::RunEnvironment>,
     checkpoints: Vec<CheckpointExport>,
 }
 
@@ -243,7 +269,7 @@ fn append_entry(
 
 pub(crate) fn load_project(conn: &Connection, project_id: &str) -> Result<Project, Error> {
     conn.query_row(
-        "SELECT id, name, created_at, pubkey FROM projects WHERE id = ?1",
+        "SELECT id, name, created_at, pubkey, archived_at FROM projects WHERE id = ?1",
         params![project_id],
         |row| {
             Ok(Project {
@@ -251,6 +277,7 @@ pub(crate) fn load_project(conn: &Connection, project_id: &str) -> Result<Projec
                 name: row.get(1)?,
                 created_at: row.get(2)?,
                 pubkey: row.get(3)?,
+                archived_at: row.get(4)?,
             })
         },
     )
@@ -267,7 +294,8 @@ pub(crate) fn load_policy_versions_for_export(
     project_id: &str,
 ) -> Result<Vec<PolicyVersionExport>, Error> {
     let mut stmt = conn.prepare(
-        "SELECT id, project_id, version, policy_json, created_at, created_by, change_notes
+        "SELECT id, project_id, version, policy_json, created_at, created_by, change_notes,
+                approved_by, approved_at, default_policy_json
          FROM policy_versions WHERE project_id = ?1 ORDER BY version ASC",
     )?;
 
@@ -280,6 +308,9 @@ pub(crate) fn load_policy_versions_for_export(
             created_at: row.get(4)?,
             created_by: row.get(5)?,
             change_notes: row.get(6)?,
+            approved_by: row.get(7)?,
+            approved_at: row.get(8)?,
+            default_policy_json: row.get(9)?,
         })
     })?;
 
@@ -362,6 +393,7 @@ pub(crate) fn load_runs_for_export(
 
     while let Some(run) = runs_iter.next() {
         let mut run = run?;
+        crate::cold_storage::ensure_hot(conn, &run.id)?;
 
         // CHECKPOINT-FIRST APPROACH: First get all checkpoints to know which steps are needed
         let checkpoints_preview = {
@@ -439,13 +471,15 @@ pub(crate) fn load_runs_for_export(
         // Get all run_executions for this run
         let executions = {
             let mut stmt = conn.prepare(
-                "SELECT id, run_id, created_at FROM run_executions WHERE run_id = ?1 ORDER BY created_at ASC",
+                "SELECT id, run_id, created_at, input_sha256, environment_json FROM run_executions WHERE run_id = ?1 ORDER BY created_at ASC",
             )?;
             let rows = stmt.query_map(params![&run.id], |row| {
                 Ok((
                     row.get::<_, String>(0)?,
                     row.get::<_, String>(1)?,
                     row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
                 ))
             })?;
             let mut execs = Vec::new();
@@ -457,14 +491,17 @@ pub(crate) fn load_runs_for_export(
 
         // For each execution, get its checkpoints
         let mut execution_exports = Vec::new();
-        for (exec_id, exec_run_id, exec_created_at) in executions {
+        for (exec_id, exec_run_id, exec_created_at, exec_input_sha256, exec_environment_json) in
+            executions
+        {
             let checkpoints = {
             let mut stmt = conn.prepare(
                 "SELECT c.id, c.run_id, c.run_execution_id, c.checkpoint_config_id, c.parent_checkpoint_id, c.turn_index, c.kind,
                         c.incident_json, c.timestamp, c.inputs_sha256, c.outputs_sha256, c.prev_chain, c.curr_chain,
                         c.signature, c.usage_tokens, c.prompt_tokens, c.completion_tokens, c.semantic_digest,
                         m.role, m.body, m.created_at, m.updated_at,
-                        p.prompt_payload, p.output_payload, p.created_at, p.updated_at
+                        p.prompt_payload, p.output_payload, p.created_at, p.updated_at, c.cache_hit,
+                        c.response_metadata_json, c.run_inputs_sha256, c.usd_cost, c.nature_cost, c.output_schema_sha256
                  FROM checkpoints c
                  LEFT JOIN checkpoint_messages m ON m.checkpoint_id = c.id
                  LEFT JOIN checkpoint_payloads p ON p.checkpoint_id = c.id
@@ -494,6 +531,13 @@ pub(crate) fn load_runs_for_export(
                 let payload_output: Option<String> = row.get(23)?;
                 let payload_created: Option<String> = row.get(24)?;
                 let payload_updated: Option<String> = row.get(25)?;
+                let response_metadata = row
+                    .get::<_, Option<String>>(27)?
+                    .map(|payload| serde_json::from_str(&payload))
+                    .transpose()
+                    .map_err(|err| {
+                        rusqlite::Error::FromSqlConversionFailure(27, Type::Text, Box::new(err))
+                    })?;
 
                 Ok(CheckpointExport {
                     id: row.get(0)?,
@@ -514,6 +558,12 @@ pub(crate) fn load_runs_for_export(
                     prompt_tokens: prompt_tokens.max(0) as u64,
                     completion_tokens: completion_tokens.max(0) as u64,
                     semantic_digest: row.get(17)?,
+                    cache_hit: row.get(26)?,
+                    response_metadata,
+                    run_inputs_sha256: row.get(28)?,
+                    output_schema_sha256: row.get(31)?,
+                    usd_cost: row.get(29)?,
+                    nature_cost: row.get(30)?,
                     message: match (message_role, message_body, message_created_at) {
                         (Some(role), Some(body), Some(created_at)) => {
                             Some(CheckpointMessageExport {
@@ -548,13 +598,16 @@ pub(crate) fn load_runs_for_export(
                 id: exec_id,
                 run_id: exec_run_id,
                 created_at: exec_created_at,
+                input_sha256: exec_input_sha256,
+                environment: exec_environment_json
+                    .and_then(|json| serde_json::from_str(&json).ok()),
                 checkpoints,
             });
         }
 
         let (receipts, car_files) = {
             let mut stmt = conn.prepare(
-                "SELECT id, run_id, created_at, file_path, match_kind, epsilon, s_grade
+                "SELECT id, run_id, created_at, file_path, match_kind, epsilon, s_grade, run_execution_id
                  FROM receipts WHERE run_id = ?1",
             )?;
             let rows = stmt.query_map(params![&run.id], |row| {
@@ -566,13 +619,15 @@ pub(crate) fn load_runs_for_export(
                     row.get::<_, Option<String>>(4)?,
                     row.get::<_, Option<f64>>(5)?,
                     row.get::<_, Option<i64>>(6)?,
+                    row.get::<_, Option<String>>(7)?,
                 ))
             })?;
 
             let mut receipts = Vec::new();
             let mut cars = Vec::new();
             for row in rows {
-                let (id, run_id, created_at, file_path, match_kind, epsilon, s_grade) = row?;
+                let (id, run_id, created_at, file_path, match_kind, epsilon, s_grade, run_execution_id) =
+                    row?;
                 let path = PathBuf::from(&file_path);
                 let file_name = path
                     .file_name()
@@ -591,6 +646,7 @@ pub(crate) fn load_runs_for_export(
                     epsilon,
                     s_grade,
                     car_path: Some(zip_path.clone()),
+                    run_execution_id,
                 });
                 cars.push(CarAttachment { zip_path, bytes });
             }
@@ -611,12 +667,15 @@ pub(crate) fn load_runs_for_export(
 }
 
 /// Write project archive directly to the specified path
+#[allow(clippy::too_many_arguments)]
 pub fn write_project_archive_to_path(
     export_path: &Path,
     project: &Project,
     policy: &Policy,
     policy_versions: &[PolicyVersionExport],
     project_usage_ledgers: &[ProjectUsageLedgerExport],
+    replay_usage: &[store::project_usage_ledgers::ReplayUsage],
+    incidents: &[incident_log::IncidentRecord],
     runs: &[RunExport],
     attachments: &[CarAttachment],
 ) -> Result<(), Error> {
@@ -669,6 +728,30 @@ pub fn write_project_archive_to_path(
         );
     }
 
+    if !replay_usage.is_empty() {
+        let replay_usage_json = serde_json::to_vec_pretty(&replay_usage)
+            .map_err(|err| Error::Api(format!("failed to serialize replay usage: {err}")))?;
+        append_entry(
+            &mut pending_entries,
+            &mut manifest_entries,
+            "replay_usage.json".to_string(),
+            "replay_usage",
+            replay_usage_json,
+        );
+    }
+
+    if !incidents.is_empty() {
+        let incidents_json = serde_json::to_vec_pretty(&incidents)
+            .map_err(|err| Error::Api(format!("failed to serialize incident log: {err}")))?;
+        append_entry(
+            &mut pending_entries,
+            &mut manifest_entries,
+            "incidents.json".to_string(),
+            "incidents",
+            incidents_json,
+        );
+    }
+
     for run in runs {
         let run_path = format!("runs/{}.json", run.run.id);
         let run_json = serde_json::to_vec_pretty(run)
@@ -733,6 +816,8 @@ pub fn export_project_archive(
     let policy = store::policies::get(&conn, project_id)?;
     let policy_versions = load_policy_versions_for_export(&conn, project_id)?;
     let project_usage_ledgers = load_project_usage_ledgers_for_export(&conn, project_id)?;
+    let replay_usage = store::project_usage_ledgers::list_replays(&conn, project_id)?;
+    let incidents = incident_log::list_incidents(&conn, project_id, None, None, None)?;
     let (runs, attachments) = load_runs_for_export(&conn, project_id)?;
 
     let exports_dir = base_dir.join(project_id).join("exports");
@@ -801,6 +886,30 @@ pub fn export_project_archive(
         );
     }
 
+    if !replay_usage.is_empty() {
+        let replay_usage_json = serde_json::to_vec_pretty(&replay_usage)
+            .map_err(|err| Error::Api(format!("failed to serialize replay usage: {err}")))?;
+        append_entry(
+            &mut pending_entries,
+            &mut manifest_entries,
+            "replay_usage.json".to_string(),
+            "replay_usage",
+            replay_usage_json,
+        );
+    }
+
+    if !incidents.is_empty() {
+        let incidents_json = serde_json::to_vec_pretty(&incidents)
+            .map_err(|err| Error::Api(format!("failed to serialize incident log: {err}")))?;
+        append_entry(
+            &mut pending_entries,
+            &mut manifest_entries,
+            "incidents.json".to_string(),
+            "incidents",
+            incidents_json,
+        );
+    }
+
     for run in &runs {
         let run_path = format!("runs/{}.json", run.run.id);
         let run_json = serde_json::to_vec_pretty(run)
@@ -855,15 +964,8 @@ pub fn export_project_archive(
     Ok(export_path)
 }
 
-fn decode_verifying_key(pubkey_b64: &str) -> Result<VerifyingKey, Error> {
-    let bytes = STANDARD
-        .decode(pubkey_b64)
-        .map_err(|err| Error::Api(format!("invalid verifying key: {err}")))?;
-    let array: [u8; ed25519_dalek::PUBLIC_KEY_LENGTH] = bytes
-        .try_into()
-        .map_err(|_| Error::Api("verifying key has invalid length".to_string()))?;
-    VerifyingKey::from_bytes(&array)
-        .map_err(|err| Error::Api(format!("invalid verifying key material: {err}")))
+pub(crate) fn decode_verifying_key(pubkey_b64: &str) -> Result<VerifyingKey, Error> {
+    verify::decode_verifying_key(pubkey_b64).map_err(|err| Error::Api(err.to_string()))
 }
 
 fn signature_valid(
@@ -909,39 +1011,45 @@ fn extract_car_data(
     if car_bytes.len() >= 4 && &car_bytes[0..2] == b"PK" {
         // It's a zip file - extract car.json and attachments
         let cursor = std::io::Cursor::new(car_bytes);
-        let mut archive = zip::ZipArchive::new(cursor)
+        let mut archive = SafeArchive::new(cursor, ArchiveLimits::default())
             .map_err(|err| Error::Api(format!("failed to read CAR zip {}: {err}", file_name)))?;
 
-        // Read car.json
-        let mut car_json_bytes = Vec::new();
-        archive
-            .by_name("car.json")
-            .map_err(|err| Error::Api(format!("car.json not found in CAR zip {}: {err}", file_name)))?
-            .read_to_end(&mut car_json_bytes)
-            .map_err(|err| Error::Api(format!("failed to read car.json from {}: {err}", file_name)))?;
+        // Read car.json, or car.cbor for DAG-CBOR bundles
+        let car_json_bytes = if archive.names().iter().any(|name| name == car_cbor::BUNDLE_ENTRY) {
+            let cbor = archive.read_by_name(car_cbor::BUNDLE_ENTRY).map_err(|err| {
+                Error::Api(format!("failed to read car.cbor from {}: {err}", file_name))
+            })?;
+            car_cbor::decode_to_json(&cbor).map_err(|err| {
+                Error::Api(format!("failed to decode car.cbor from {}: {err}", file_name))
+            })?
+        } else {
+            archive
+                .read_by_name("car.json")
+                .map_err(|err| Error::Api(format!("failed to read car.json from {}: {err}", file_name)))?
+        };
 
         let car: car::Car = serde_json::from_slice(&car_json_bytes)
             .map_err(|err| Error::Api(format!("failed to parse car.json from {}: {err}", file_name)))?;
 
         // Extract all attachments from attachments/ directory
         for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|err| Error::Api(format!("failed to read zip entry {}: {err}", i)))?;
-
-            if file.name().starts_with("attachments/") && !file.is_dir() {
-                let attachment_name = file.name().to_string();
-                let mut attachment_bytes = Vec::new();
-                file.read_to_end(&mut attachment_bytes)
-                    .map_err(|err| Error::Api(format!("failed to read attachment {}: {err}", attachment_name)))?;
-
-                // Extract hash from filename (attachments/{hash}.txt)
-                if let Some(hash) = attachment_name
-                    .strip_prefix("attachments/")
-                    .and_then(|name| name.strip_suffix(".txt"))
-                {
-                    attachments.insert(hash.to_string(), attachment_bytes);
-                }
+            let attachment_name = archive.names()[i].clone();
+            if !attachment_name.starts_with("attachments/") {
+                continue;
+            }
+            let Some(attachment_bytes) = archive
+                .read_index(i)
+                .map_err(|err| Error::Api(format!("failed to read attachment {}: {err}", attachment_name)))?
+            else {
+                continue;
+            };
+
+            // Extract hash from filename (attachments/{hash}.txt)
+            if let Some(hash) = attachment_name
+                .strip_prefix("attachments/")
+                .and_then(|name| name.strip_suffix(".txt"))
+            {
+                attachments.insert(hash.to_string(), attachment_bytes);
             }
         }
 
@@ -965,25 +1073,21 @@ pub fn import_project_archive(
             archive_path.display()
         ))
     })?;
-    let mut archive = zip::ZipArchive::new(file)
+    let mut archive = SafeArchive::new(file, ArchiveLimits::default())
         .map_err(|err| Error::Api(format!("failed to read archive: {err}")))?;
 
-    let mut manifest_bytes = Vec::new();
-    archive
-        .by_name("manifest.json")
-        .map_err(|err| Error::Api(format!("manifest not found in archive: {err}")))?
-        .read_to_end(&mut manifest_bytes)
+    let manifest_bytes = archive
+        .read_by_name("manifest.json")
         .map_err(|err| Error::Api(format!("failed to read manifest: {err}")))?;
     let manifest: ExportManifest = serde_json::from_slice(&manifest_bytes)
         .map_err(|err| Error::Api(format!("failed to parse manifest: {err}")))?;
 
     let mut contents: HashMap<String, Vec<u8>> = HashMap::new();
     for entry in &manifest.entries {
-        let mut data = Vec::new();
-        archive
-            .by_name(&entry.path)
-            .map_err(|err| Error::Api(format!("missing archive entry {}: {err}", entry.path)))?
-            .read_to_end(&mut data)
+        intelexta_archive::sanitize_entry_path(&entry.path)
+            .map_err(|err| Error::Api(format!("invalid manifest entry: {err}")))?;
+        let data = archive
+            .read_by_name(&entry.path)
             .map_err(|err| Error::Api(format!("failed to read entry {}: {err}", entry.path)))?;
         let actual = provenance::sha256_hex(&data);
         if actual != entry.sha256 {
@@ -1000,6 +1104,10 @@ pub fn import_project_archive(
         .ok_or_else(|| Error::Api("project.json missing from archive".to_string()))?;
     let project: Project = serde_json::from_slice(&project_bytes)
         .map_err(|err| Error::Api(format!("failed to parse project: {err}")))?;
+    // The project id becomes a directory name under base_dir
+    if project.id.is_empty() || project.id.starts_with('.') || sanitize_for_file(&project.id) != project.id {
+        return Err(Error::Api(format!("invalid project id '{}' in archive", project.id)));
+    }
 
     let policy_bytes = contents
         .remove("policy.json")
@@ -1026,6 +1134,24 @@ pub fn import_project_archive(
         .transpose()?
         .unwrap_or_default();
 
+    let replay_usage: Vec<store::project_usage_ledgers::ReplayUsage> = contents
+        .remove("replay_usage.json")
+        .map(|bytes| {
+            serde_json::from_slice(&bytes)
+                .map_err(|err| Error::Api(format!("failed to parse replay usage: {err}")))
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let incidents: Vec<incident_log::IncidentRecord> = contents
+        .remove("incidents.json")
+        .map(|bytes| {
+            serde_json::from_slice(&bytes)
+                .map_err(|err| Error::Api(format!("failed to parse incident log: {err}")))
+        })
+        .transpose()?
+        .unwrap_or_default();
+
     let verifying_key = decode_verifying_key(&project.pubkey)?;
 
     let mut run_exports = Vec::new();
@@ -1089,8 +1215,8 @@ pub fn import_project_archive(
         // We have version history - import it
         for policy_version in &policy_versions {
             tx.execute(
-                "INSERT INTO policy_versions (id, project_id, version, policy_json, created_at, created_by, change_notes)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO policy_versions (id, project_id, version, policy_json, created_at, created_by, change_notes, approved_by, approved_at, default_policy_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
                 params![
                     &policy_version.id,
                     &policy_version.project_id,
@@ -1099,6 +1225,9 @@ pub fn import_project_archive(
                     &policy_version.created_at,
                     &policy_version.created_by,
                     &policy_version.change_notes,
+                    &policy_version.approved_by,
+                    &policy_version.approved_at,
+                    &policy_version.default_policy_json,
                 ],
             )?;
         }
@@ -1142,6 +1271,16 @@ pub fn import_project_archive(
         )?;
     }
 
+    for replay in &replay_usage {
+        store::project_usage_ledgers::insert_replay(
+            &tx,
+            &store::project_usage_ledgers::ReplayUsage {
+                project_id: project.id.clone(),
+                ..replay.clone()
+            },
+        )?;
+    }
+
     let mut checkpoints_imported = 0usize;
     let mut receipts_imported = 0usize;
     let mut incidents_generated = 0usize;
@@ -1178,11 +1317,20 @@ pub fn import_project_archive(
         // Import all run_executions for this run
         for execution in &run.executions {
             tx.execute(
-                "INSERT INTO run_executions (id, run_id, created_at) VALUES (?1, ?2, ?3)",
+                "INSERT INTO run_executions (id, run_id, created_at, input_sha256, environment_json) VALUES (?1, ?2, ?3, ?4, ?5)",
                 params![
                     &execution.id,
                     &execution.run_id,
                     &execution.created_at,
+                    &execution.input_sha256,
+                    execution
+                        .environment
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()
+                        .map_err(|err| {
+                            Error::Api(format!("failed to serialize run environment: {err}"))
+                        })?,
                 ],
             )?;
         }
@@ -1346,8 +1494,9 @@ pub fn import_project_archive(
             tx.execute(
                 "INSERT INTO checkpoints (id, run_id, run_execution_id, checkpoint_config_id, parent_checkpoint_id, turn_index, kind, incident_json, timestamp,
                                           inputs_sha256, outputs_sha256, prev_chain, curr_chain, signature, usage_tokens, prompt_tokens,
-                                          completion_tokens, semantic_digest)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+                                          completion_tokens, semantic_digest, cache_hit, response_metadata_json, run_inputs_sha256, usd_cost, nature_cost,
+                                          output_schema_sha256)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
                 params![
                     &checkpoint.id,
                     &checkpoint.run_id,
@@ -1370,6 +1519,15 @@ pub fn import_project_archive(
                     checkpoint.prompt_tokens as i64,
                     checkpoint.completion_tokens as i64,
                     &checkpoint.semantic_digest,
+                    checkpoint.cache_hit,
+                    checkpoint
+                        .response_metadata
+                        .as_ref()
+                        .map(|value| serde_json::to_string(value).unwrap()),
+                    &checkpoint.run_inputs_sha256,
+                    checkpoint.usd_cost,
+                    checkpoint.nature_cost,
+                    &checkpoint.output_schema_sha256,
                 ],
             ).map_err(|err| Error::Api(format!(
                 "failed to insert checkpoint {}: config_id={:?}, parent_id={:?}, error={}",
@@ -1464,15 +1622,15 @@ pub fn import_project_archive(
 
             // Save the CAR file (preserve original format or convert to zip if it was json)
             let dest_path = if car_filename.ends_with(".car.zip") {
-                dest_dir.join(format!("{}.car.zip", receipt.id.replace(':', "_")))
+                dest_dir.join(format!("{}.car.zip", sanitize_for_file(&receipt.id)))
             } else {
-                dest_dir.join(format!("{}.car.json", receipt.id))
+                dest_dir.join(format!("{}.car.json", sanitize_for_file(&receipt.id)))
             };
             file_writes.push((dest_path.clone(), car_bytes));
 
             tx.execute(
-                "INSERT INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO receipts (id, run_id, created_at, file_path, match_kind, epsilon, s_grade, run_execution_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
                 params![
                     &receipt.id,
                     &receipt.run_id,
@@ -1481,12 +1639,30 @@ pub fn import_project_archive(
                     &receipt.match_kind,
                     &receipt.epsilon,
                     receipt.s_grade,
+                    &receipt.run_execution_id,
                 ],
             )?;
             receipts_imported += 1;
         }
     }
 
+    // Restore acknowledgements the project key signed; the incidents
+    // themselves came back with their runs' checkpoints
+    let acknowledgements = incidents
+        .iter()
+        .filter_map(|incident| incident.acknowledgement.as_ref());
+    for acknowledgement in acknowledgements {
+        if acknowledgement.project_id != project.id
+            || !incident_log::acknowledgement_signed_by(acknowledgement, &verifying_key)
+        {
+            return Err(Error::Api(format!(
+                "acknowledgement of incident {} is not signed by the project key",
+                acknowledgement.checkpoint_id
+            )));
+        }
+        store::incident_acknowledgements::insert(&tx, acknowledgement)?;
+    }
+
     let mut written_paths: Vec<PathBuf> = Vec::new();
     for (path, bytes) in &file_writes {
         if let Some(dir) = path.parent() {
@@ -0,0 +1,152 @@
+// src-tauri/src/project_archival.rs
+//!
+//! Project archival: deleting a project archives it first
+//!
+//! An archived project keeps its runs, checkpoints, receipts and signing key
+//! but is left out of the project list, and can be restored as it was. Once
+//! it has been archived for longer than the retention period it is purged:
+//! deleted for good, signing key included. Without a retention period,
+//! archived projects are kept until they are purged by hand.
+//!
+//! Archiving, restoring and purging are recorded in `project_archive_events`,
+//! which keeps the project's id and name after the project is gone.
+
+use crate::store::{
+    self,
+    project_archive_events::{
+        self as events, ProjectArchiveEvent, ACTION_ARCHIVED, ACTION_PURGED, ACTION_RESTORED,
+    },
+};
+use crate::{provenance, DbPool, Error, Project};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How often the sweeper looks for archived projects past their retention
+pub const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Longest retention period that can be set: ten years
+pub const MAX_RETENTION_DAYS: u32 = 3_650;
+
+fn load(conn: &Connection, project_id: &str) -> Result<Project, Error> {
+    store::projects::get(conn, project_id)?
+        .ok_or_else(|| Error::Api(format!("project {project_id} not found")))
+}
+
+fn record(
+    conn: &Connection,
+    project: &Project,
+    action: &str,
+    at: DateTime<Utc>,
+) -> Result<(), Error> {
+    events::insert(
+        conn,
+        &ProjectArchiveEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: project.id.clone(),
+            project_name: project.name.clone(),
+            action: action.to_string(),
+            at: at.to_rfc3339(),
+        },
+    )
+}
+
+/// Hide a project from the project list, keeping everything it recorded
+pub fn archive_project(conn: &mut Connection, project_id: &str) -> Result<Project, Error> {
+    let project = load(conn, project_id)?;
+    if project.archived_at.is_some() {
+        return Err(Error::Api(format!(
+            "project {project_id} is already archived"
+        )));
+    }
+    let now = Utc::now();
+    let tx = conn.transaction()?;
+    store::projects::set_archived_at(&tx, project_id, Some(&now))?;
+    record(&tx, &project, ACTION_ARCHIVED, now)?;
+    tx.commit()?;
+    load(conn, project_id)
+}
+
+/// Bring an archived project back to the project list
+pub fn restore_project(conn: &mut Connection, project_id: &str) -> Result<Project, Error> {
+    let project = load(conn, project_id)?;
+    if project.archived_at.is_none() {
+        return Err(Error::Api(format!("project {project_id} is not archived")));
+    }
+    let tx = conn.transaction()?;
+    store::projects::set_archived_at(&tx, project_id, None)?;
+    record(&tx, &project, ACTION_RESTORED, Utc::now())?;
+    tx.commit()?;
+    load(conn, project_id)
+}
+
+fn purge(conn: &mut Connection, project: &Project, at: DateTime<Utc>) -> Result<(), Error> {
+    let tx = conn.transaction()?;
+    store::projects::delete(&tx, &project.id)?;
+    record(&tx, project, ACTION_PURGED, at)?;
+    tx.commit()?;
+    // The key goes only once the deletion is committed
+    if let Err(err) = provenance::delete_secret_key(&project.id) {
+        eprintln!(
+            "[intelexta] WARNING: Failed to delete provenance key for project {}: {}",
+            project.id, err
+        );
+    }
+    Ok(())
+}
+
+/// Delete an archived project for good, without waiting out the retention period
+pub fn purge_project(conn: &mut Connection, project_id: &str) -> Result<(), Error> {
+    let project = load(conn, project_id)?;
+    if project.archived_at.is_none() {
+        return Err(Error::Api(format!(
+            "project {project_id} must be archived before it is purged"
+        )));
+    }
+    purge(conn, &project, Utc::now())
+}
+
+/// Purge the projects archived for longer than the retention period,
+/// returning their ids
+pub fn purge_expired_projects(
+    conn: &mut Connection,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, Error> {
+    let Some(retention_days) = store::project_retention_settings::get_retention_days(conn)? else {
+        return Ok(Vec::new());
+    };
+    let cutoff = now - chrono::Duration::days(i64::from(retention_days));
+    let expired = store::projects::list_archived_before(conn, &cutoff)?;
+    for project in &expired {
+        purge(conn, project, now)?;
+    }
+    Ok(expired.into_iter().map(|project| project.id).collect())
+}
+
+pub fn set_retention_days(conn: &Connection, retention_days: Option<u32>) -> Result<(), Error> {
+    if let Some(days) = retention_days {
+        if !(1..=MAX_RETENTION_DAYS).contains(&days) {
+            return Err(Error::Api(format!(
+                "retention period must be between 1 and {MAX_RETENTION_DAYS} days"
+            )));
+        }
+    }
+    store::project_retention_settings::set_retention_days(conn, retention_days)
+}
+
+/// Purge expired archived projects now and then every `check_interval`
+pub fn spawn_retention_sweeper(pool: DbPool, check_interval: Duration) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        if let Err(err) = sweep(&pool) {
+            eprintln!("⚠️  Warning: Failed to purge expired projects: {}", err);
+        }
+        std::thread::sleep(check_interval);
+    })
+}
+
+fn sweep(pool: &DbPool) -> Result<(), Error> {
+    let mut conn = pool.get()?;
+    purge_expired_projects(&mut conn, Utc::now())?;
+    Ok(())
+}
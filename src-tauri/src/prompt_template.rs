@@ -0,0 +1,521 @@
+// src-tauri/src/prompt_template.rs
+//!
+//! Prompt templates: handlebars-style rendering of step prompts
+//!
+//! Prompt steps marked as templates are rendered against the run parameters
+//! (by name), the run inputs (`inputs.*`) and the output the step uses
+//! (`source.output`, `source.json`, `source.sha256`). Supported tags:
+//!
+//! - `{{path}}` inserts a value: strings verbatim, other JSON values compact
+//! - `{{json path}}` inserts the value as JSON, quoting and escaping strings
+//! - `{{truncate path 500}}` inserts at most about 500 tokens of the value
+//! - `{{#each path}}…{{else}}…{{/each}}` loops over an array (`this`, `@index`)
+//! - `{{#if path}}…{{else}}…{{/if}}` renders on a truthy value
+//! - `{{> name}}` includes a project prompt partial
+//! - `{{! comment}}` is dropped; `\{{` emits a literal `{{`
+//!
+//! Rendering is a single pass: inserted values are never parsed as template
+//! syntax, so step outputs and run inputs cannot inject tags. Unknown
+//! variables and partials are errors rather than empty strings. The rendered
+//! prompt is what the model receives, so the checkpoint's inputs digest
+//! covers it rather than the template.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Partials may include other partials up to this depth
+const MAX_PARTIAL_DEPTH: usize = 8;
+
+/// Appended where `truncate` cut a value short
+pub const TRUNCATION_MARKER: &str = "… [truncated]";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Value(Expr),
+    Each {
+        path: String,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    If {
+        path: String,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Partial(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Path(String),
+    Json(String),
+    Truncate(String, u64),
+}
+
+enum Token<'a> {
+    Text(String),
+    Tag(&'a str),
+}
+
+enum BlockEnd {
+    Else,
+    Close,
+}
+
+/// Check a template's syntax without rendering it
+pub fn validate(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// Check that every partial the template includes, directly or through other
+/// partials, exists and parses
+pub fn check_partials(template: &str, partials: &BTreeMap<String, String>) -> Result<(), String> {
+    fn walk(
+        nodes: &[Node],
+        partials: &BTreeMap<String, String>,
+        depth: usize,
+    ) -> Result<(), String> {
+        for node in nodes {
+            match node {
+                Node::Partial(name) => {
+                    let partial = lookup_partial(partials, name, depth)?;
+                    walk(&parse(partial)?, partials, depth + 1)?;
+                }
+                Node::Each {
+                    body, otherwise, ..
+                }
+                | Node::If {
+                    body, otherwise, ..
+                } => {
+                    walk(body, partials, depth)?;
+                    walk(otherwise, partials, depth)?;
+                }
+                Node::Text(_) | Node::Value(_) => {}
+            }
+        }
+        Ok(())
+    }
+    walk(&parse(template)?, partials, 0)
+}
+
+/// Partial names are identifiers: letters, digits, `_` and `-`
+pub fn validate_partial_name(name: &str) -> Result<(), String> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid partial name '{name}': use letters, digits, '_' and '-'"
+        ))
+    }
+}
+
+/// Render a template against `context`, including `partials` by name
+pub fn render(
+    template: &str,
+    context: &Value,
+    partials: &BTreeMap<String, String>,
+) -> Result<String, String> {
+    let nodes = parse(template)?;
+    let mut scopes = vec![Scope {
+        value: context,
+        index: None,
+    }];
+    let mut out = String::new();
+    render_nodes(&nodes, &mut scopes, partials, 0, &mut out)?;
+    Ok(out)
+}
+
+/// Cut text to about `max_tokens` tokens (four bytes each, as budgets estimate
+/// them), on a character boundary
+pub fn truncate_to_tokens(text: &str, max_tokens: u64) -> String {
+    let max_bytes = usize::try_from(max_tokens.saturating_mul(4)).unwrap_or(usize::MAX);
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{TRUNCATION_MARKER}", &text[..end])
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            text.push_str(&rest[..start - 1]);
+            text.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            format!(
+                "unclosed tag at byte {}",
+                template.len() - rest.len() + start
+            )
+        })?;
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(Token::Tag(after[..end].trim()));
+        rest = &after[end + 2..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+fn parse(template: &str) -> Result<Vec<Node>, String> {
+    let mut tokens = tokenize(template)?.into_iter();
+    let (nodes, _) = parse_nodes(&mut tokens, None)?;
+    Ok(nodes)
+}
+
+/// Parse nodes up to `{{else}}` or the closing tag of the enclosing `block`
+fn parse_nodes<'a>(
+    tokens: &mut impl Iterator<Item = Token<'a>>,
+    block: Option<&str>,
+) -> Result<(Vec<Node>, Option<BlockEnd>), String> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+        if tag.starts_with('!') {
+            continue;
+        }
+        if tag == "else" {
+            return match block {
+                Some(_) => Ok((nodes, Some(BlockEnd::Else))),
+                None => Err("{{else}} outside of a block".to_string()),
+            };
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            return match block {
+                Some(open) if open == name => Ok((nodes, Some(BlockEnd::Close))),
+                _ => Err(format!("unexpected {{{{/{name}}}}}")),
+            };
+        }
+        if let Some(name) = tag.strip_prefix('>') {
+            let name = name.trim();
+            validate_partial_name(name)?;
+            nodes.push(Node::Partial(name.to_string()));
+            continue;
+        }
+        if let Some(open) = tag.strip_prefix('#') {
+            let words: Vec<&str> = open.split_whitespace().collect();
+            let [helper, path] = words.as_slice() else {
+                return Err(format!("block {{{{#{open}}}}} takes a helper and one path"));
+            };
+            if *helper != "each" && *helper != "if" {
+                return Err(format!("unknown block helper '{helper}'"));
+            }
+            let unclosed = || format!("{{{{#{helper}}}}} is not closed");
+            let (body, end) = parse_nodes(tokens, Some(helper))?;
+            let otherwise = match end {
+                Some(BlockEnd::Close) => Vec::new(),
+                Some(BlockEnd::Else) => match parse_nodes(tokens, Some(helper))? {
+                    (otherwise, Some(BlockEnd::Close)) => otherwise,
+                    _ => return Err(unclosed()),
+                },
+                None => return Err(unclosed()),
+            };
+            let path = path.to_string();
+            nodes.push(if *helper == "each" {
+                Node::Each {
+                    path,
+                    body,
+                    otherwise,
+                }
+            } else {
+                Node::If {
+                    path,
+                    body,
+                    otherwise,
+                }
+            });
+            continue;
+        }
+        nodes.push(Node::Value(parse_expr(tag)?));
+    }
+    match block {
+        Some(open) => Err(format!("{{{{#{open}}}}} is not closed")),
+        None => Ok((nodes, None)),
+    }
+}
+
+fn parse_expr(tag: &str) -> Result<Expr, String> {
+    let words: Vec<&str> = tag.split_whitespace().collect();
+    match words.as_slice() {
+        [path] => Ok(Expr::Path(path.to_string())),
+        ["json", path] => Ok(Expr::Json(path.to_string())),
+        ["truncate", path, max_tokens] => max_tokens
+            .parse::<u64>()
+            .map(|max_tokens| Expr::Truncate(path.to_string(), max_tokens))
+            .map_err(|_| format!("truncate needs a token count, got '{max_tokens}'")),
+        _ => Err(format!("unsupported tag {{{{{tag}}}}}")),
+    }
+}
+
+struct Scope<'a> {
+    value: &'a Value,
+    /// Position in the array an `{{#each}}` iterates
+    index: Option<usize>,
+}
+
+enum Resolved<'a> {
+    Value(&'a Value),
+    Index(usize),
+}
+
+impl Resolved<'_> {
+    fn to_json(&self) -> Value {
+        match self {
+            Resolved::Value(value) => (*value).clone(),
+            Resolved::Index(index) => Value::from(*index),
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Resolved::Value(Value::String(text)) => text.clone(),
+            Resolved::Value(Value::Null) => String::new(),
+            Resolved::Value(value) => value.to_string(),
+            Resolved::Index(index) => index.to_string(),
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            Resolved::Value(Value::Null) => false,
+            Resolved::Value(Value::Bool(flag)) => *flag,
+            Resolved::Value(Value::Number(number)) => number.as_f64() != Some(0.0),
+            Resolved::Value(Value::String(text)) => !text.is_empty(),
+            Resolved::Value(Value::Array(items)) => !items.is_empty(),
+            Resolved::Value(Value::Object(_)) => true,
+            Resolved::Index(index) => *index != 0,
+        }
+    }
+}
+
+/// Resolve a dotted path: `this` is the innermost scope, other names are
+/// looked up from the innermost scope outwards
+fn lookup<'a>(scopes: &[Scope<'a>], path: &str) -> Result<Resolved<'a>, String> {
+    let unknown = || format!("unknown variable '{path}'");
+    let scope = scopes.last().ok_or_else(unknown)?;
+    if path == "@index" {
+        return scope
+            .index
+            .map(Resolved::Index)
+            .ok_or_else(|| "@index outside of {{#each}}".to_string());
+    }
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap_or_default();
+    let mut value = if first == "this" {
+        scope.value
+    } else {
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.value.get(first))
+            .ok_or_else(unknown)?
+    };
+    for segment in segments {
+        value = match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            other => other.get(segment),
+        }
+        .ok_or_else(unknown)?;
+    }
+    Ok(Resolved::Value(value))
+}
+
+fn lookup_partial<'p>(
+    partials: &'p BTreeMap<String, String>,
+    name: &str,
+    depth: usize,
+) -> Result<&'p str, String> {
+    if depth >= MAX_PARTIAL_DEPTH {
+        return Err(format!(
+            "partial '{name}' is nested more than {MAX_PARTIAL_DEPTH} levels deep"
+        ));
+    }
+    partials
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| format!("unknown partial '{name}'"))
+}
+
+fn render_nodes<'a>(
+    nodes: &[Node],
+    scopes: &mut Vec<Scope<'a>>,
+    partials: &BTreeMap<String, String>,
+    depth: usize,
+    out: &mut String,
+) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(Expr::Path(path)) => out.push_str(&lookup(scopes, path)?.display()),
+            Node::Value(Expr::Json(path)) => {
+                out.push_str(&lookup(scopes, path)?.to_json().to_string())
+            }
+            Node::Value(Expr::Truncate(path, max_tokens)) => out.push_str(&truncate_to_tokens(
+                &lookup(scopes, path)?.display(),
+                *max_tokens,
+            )),
+            Node::Each {
+                path,
+                body,
+                otherwise,
+            } => {
+                let items = match lookup(scopes, path)? {
+                    Resolved::Value(Value::Array(items)) => items.as_slice(),
+                    Resolved::Value(Value::Null) => &[],
+                    _ => return Err(format!("{{{{#each {path}}}}} needs an array")),
+                };
+                if items.is_empty() {
+                    render_nodes(otherwise, scopes, partials, depth, out)?;
+                }
+                for (index, item) in items.iter().enumerate() {
+                    scopes.push(Scope {
+                        value: item,
+                        index: Some(index),
+                    });
+                    let rendered = render_nodes(body, scopes, partials, depth, out);
+                    scopes.pop();
+                    rendered?;
+                }
+            }
+            Node::If {
+                path,
+                body,
+                otherwise,
+            } => {
+                // Missing values are falsy so templates can test optional fields
+                let truthy = lookup(scopes, path).is_ok_and(|value| value.truthy());
+                let branch = if truthy { body } else { otherwise };
+                render_nodes(branch, scopes, partials, depth, out)?;
+            }
+            Node::Partial(name) => {
+                let partial = lookup_partial(partials, name, depth)?;
+                render_nodes(&parse(partial)?, scopes, partials, depth + 1, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render_plain(template: &str, context: Value) -> Result<String, String> {
+        render(template, &context, &BTreeMap::new())
+    }
+
+    #[test]
+    fn values_are_inserted_once_and_never_parsed_as_tags() {
+        let context = json!({
+            "topic": "{{inputs.secret}}",
+            "inputs": { "secret": "leaked", "limit": 3 },
+            "source": { "output": "say \"hi\"" }
+        });
+        assert_eq!(
+            render_plain(
+                "{{topic}} / {{inputs.limit}} / {{json source.output}} / \\{{literal}}{{! note }}",
+                context
+            )
+            .unwrap(),
+            "{{inputs.secret}} / 3 / \"say \\\"hi\\\"\" / {{literal}}"
+        );
+        assert_eq!(
+            render_plain("{{missing}}", json!({})).unwrap_err(),
+            "unknown variable 'missing'"
+        );
+    }
+
+    #[test]
+    fn loops_and_conditionals_walk_array_outputs() {
+        let context = json!({
+            "source": { "json": [{ "name": "a" }, { "name": "b" }] },
+            "empty": [],
+            "heading": "Items"
+        });
+        let template = "{{#if heading}}{{heading}}:{{/if}}\
+            {{#each source.json}} {{@index}}={{name}}/{{heading}}{{/each}}\
+            {{#each empty}}x{{else}} none{{/each}}\
+            {{#if source.json.5}}!{{else}}.{{/if}}";
+        assert_eq!(
+            render_plain(template, context).unwrap(),
+            "Items: 0=a/Items 1=b/Items none."
+        );
+        assert!(render_plain("{{#each heading}}{{/each}}", json!({ "heading": "x" })).is_err());
+    }
+
+    #[test]
+    fn partials_are_included_and_checked() {
+        let partials = BTreeMap::from([
+            ("header".to_string(), "[{{> role}}] ".to_string()),
+            ("role".to_string(), "You review {{topic}}".to_string()),
+            ("loop".to_string(), "{{> loop}}".to_string()),
+        ]);
+        let context = json!({ "topic": "contracts" });
+        assert_eq!(
+            render("{{> header}}Go.", &context, &partials).unwrap(),
+            "[You review contracts] Go."
+        );
+        assert!(check_partials("{{#if topic}}{{> header}}{{/if}}", &partials).is_ok());
+        assert_eq!(
+            check_partials("{{> footer}}", &partials).unwrap_err(),
+            "unknown partial 'footer'"
+        );
+        assert!(render("{{> loop}}", &context, &partials)
+            .unwrap_err()
+            .contains("nested more than"));
+    }
+
+    #[test]
+    fn truncation_respects_token_estimates_and_char_boundaries() {
+        assert_eq!(truncate_to_tokens("short", 10), "short");
+        assert_eq!(
+            truncate_to_tokens("abcdefghij", 2),
+            format!("abcdefgh{TRUNCATION_MARKER}")
+        );
+        assert_eq!(
+            truncate_to_tokens("ééééé", 1),
+            format!("éé{TRUNCATION_MARKER}")
+        );
+        assert_eq!(
+            render_plain("{{truncate text 1}}", json!({ "text": "abcdef" })).unwrap(),
+            format!("abcd{TRUNCATION_MARKER}")
+        );
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        assert!(validate("{{#each items}}{{this}}").is_err());
+        assert!(validate("{{/if}}").is_err());
+        assert!(validate("{{#with items}}{{/with}}").is_err());
+        assert!(validate("{{truncate text many}}").is_err());
+        assert!(validate("{{unclosed").is_err());
+        assert!(validate("{{#if a}}x{{else}}y{{/if}}").is_ok());
+    }
+}
@@ -1,17 +1,20 @@
-use crate::keychain;
+#[cfg(feature = "app")]
+use crate::{key_protection, keychain};
+#[cfg(feature = "app")]
 use anyhow::anyhow;
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+#[cfg(feature = "app")]
 use rand::rngs::OsRng;
-use serde::Serialize;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "app")]
 pub struct KeypairOut {
     pub public_key_b64: String,
     pub secret_key_b64: String,
 }
 
+#[cfg(feature = "app")]
 pub fn generate_keypair() -> KeypairOut {
     let sk = SigningKey::generate(&mut OsRng);
     let pk = sk.verifying_key();
@@ -21,21 +24,119 @@ pub fn generate_keypair() -> KeypairOut {
     }
 }
 
+#[cfg(feature = "app")]
 pub fn store_secret_key(project_id: &str, secret_key_b64: &str) -> anyhow::Result<()> {
     keychain::store_secret(project_id, secret_key_b64)
 }
 
+#[cfg(feature = "app")]
 pub fn load_secret_key(project_id: &str) -> anyhow::Result<SigningKey> {
+    if let Some(sk) = key_protection::load_sealed(project_id)? {
+        return Ok(sk);
+    }
     let b64 = keychain::load_secret(project_id)?;
     let bytes = STANDARD.decode(b64)?;
     let sk = SigningKey::from_bytes(&bytes.try_into().map_err(|_| anyhow!("bad sk len"))?);
     Ok(sk)
 }
 
+#[cfg(feature = "app")]
 pub fn delete_secret_key(project_id: &str) -> anyhow::Result<()> {
+    key_protection::delete(project_id)?;
     keychain::delete_secret(project_id)
 }
 
+/// Key of a reviewer or organization identity that countersigns CARs, kept
+/// next to the project keys under `cosigner-<signer_id>`; generated the first
+/// time the identity countersigns
+#[cfg(feature = "app")]
+pub fn load_or_create_cosigner_key(signer_id: &str) -> anyhow::Result<SigningKey> {
+    let valid_id = !signer_id.is_empty()
+        && signer_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_id {
+        return Err(anyhow!(
+            "signer id '{signer_id}' may only contain letters, digits, '-' and '_'"
+        ));
+    }
+    let key_id = format!("cosigner-{signer_id}");
+    match load_secret_key(&key_id) {
+        Ok(sk) => Ok(sk),
+        Err(err) => {
+            let missing = err
+                .downcast_ref::<keyring::Error>()
+                .is_some_and(|inner| matches!(inner, keyring::Error::NoEntry))
+                || err
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(|io_err| io_err.kind() == std::io::ErrorKind::NotFound);
+            if !missing {
+                return Err(err);
+            }
+            let keypair = generate_keypair();
+            store_secret_key(&key_id, &keypair.secret_key_b64)?;
+            load_secret_key(&key_id)
+        }
+    }
+}
+
+/// What signs a project's checkpoints and CARs: its key, or the cosigners
+/// sharing a threshold key (see `threshold`)
+#[cfg(feature = "app")]
+pub enum ProjectSigner {
+    Key(SigningKey),
+    Threshold {
+        project_id: String,
+        config: crate::threshold::ThresholdConfig,
+    },
+}
+
+#[cfg(feature = "app")]
+impl ProjectSigner {
+    pub fn load(project_id: &str) -> anyhow::Result<Self> {
+        match crate::threshold::load_config(project_id)? {
+            Some(config) => Ok(Self::Threshold {
+                project_id: project_id.to_string(),
+                config,
+            }),
+            None => Ok(Self::Key(load_secret_key(project_id)?)),
+        }
+    }
+
+    pub fn public_key(&self) -> String {
+        match self {
+            Self::Key(sk) => public_key_from_secret(sk),
+            Self::Threshold { config, .. } => config.group.group_public_key.clone(),
+        }
+    }
+
+    /// Base64 signature over `bytes`; a threshold project waits for its
+    /// cosigners
+    pub fn sign(&self, bytes: &[u8]) -> anyhow::Result<String> {
+        match self {
+            Self::Key(sk) => Ok(sign_bytes(sk, bytes)),
+            Self::Threshold { project_id, config } => {
+                let sig = crate::threshold::sign_with_cosigners(project_id, config, bytes)?;
+                Ok(STANDARD.encode(sig.to_bytes()))
+            }
+        }
+    }
+
+    /// Secret the redaction salts derive from. A threshold project derives
+    /// it from this device's share, so only that device can reproduce them.
+    pub fn redaction_key(&self) -> anyhow::Result<SigningKey> {
+        match self {
+            Self::Key(sk) => Ok(sk.clone()),
+            Self::Threshold { project_id, .. } => {
+                let share = crate::threshold::load_local_share(project_id)?;
+                let bytes = STANDARD.decode(&share.signing_share)?;
+                let bytes = bytes.try_into().map_err(|_| anyhow!("bad share len"))?;
+                Ok(SigningKey::from_bytes(&bytes))
+            }
+        }
+    }
+}
+
 pub fn public_key_from_secret(sk: &SigningKey) -> String {
     let pk: VerifyingKey = sk.verifying_key();
     STANDARD.encode(pk.as_bytes())
@@ -47,7 +148,7 @@ pub fn sign_bytes(sk: &SigningKey, bytes: &[u8]) -> String {
 }
 
 pub fn canonical_json<T: Serialize>(t: &T) -> Vec<u8> {
-    serde_jcs::to_vec(t).expect("canonical json")
+    intelexta_canon::to_vec(t).expect("canonical json")
 }
 
 pub fn sha256_hex(data: &[u8]) -> String {
@@ -55,66 +156,117 @@ pub fn sha256_hex(data: &[u8]) -> String {
     hex::encode(Sha256::digest(data))
 }
 
-pub fn semantic_digest(text: &str) -> String {
-    const BITS: usize = 64;
+/// Hash a file without loading it into memory (model weights can be many GB)
+pub fn sha256_file(path: &std::path::Path) -> anyhow::Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
 
-    if text.trim().is_empty() {
-        return format!("{:016x}", 0_u64);
-    }
+/// Format marker of detached artifact signatures
+pub const ARTIFACT_SIGNATURE_FORMAT: &str = "intelexta-artifact-signature/1";
 
-    let normalized = text.to_lowercase();
-    let mut features: Vec<String> = Vec::new();
+/// The checkpoint an artifact was produced from. An artifact such as a report
+/// rendered from a model's answer rarely hashes to the checkpoint's output, so
+/// the signature states the binding instead.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactBinding {
+    pub run_id: String,
+    pub checkpoint_id: String,
+    pub outputs_sha256: String,
+}
 
-    let chars: Vec<char> = normalized.chars().collect();
-    if chars.len() >= 3 {
-        for window in chars.windows(3) {
-            let gram: String = window.iter().collect();
-            features.push(gram);
-        }
+/// A detached signature over a plain file, kept next to it as
+/// `<file>.sig.json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactSignature {
+    pub format: String,
+    /// Name of the signed file, for display; only its hash is checked
+    pub file_name: String,
+    pub sha256: String,
+    pub checkpoint: ArtifactBinding,
+    pub public_key: String,
+    /// Base64 Ed25519 signature over the canonical JSON of the other fields
+    pub signature: String,
+}
+
+impl ArtifactSignature {
+    /// The statement about the file at `path`, not yet signed
+    pub fn unsigned(
+        path: &std::path::Path,
+        checkpoint: ArtifactBinding,
+        public_key: String,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            format: ARTIFACT_SIGNATURE_FORMAT.to_string(),
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            sha256: sha256_file(path)?,
+            checkpoint,
+            public_key,
+            signature: String::new(),
+        })
     }
 
-    if features.is_empty() {
-        for token in normalized.split_whitespace() {
-            if !token.is_empty() {
-                features.push(token.to_string());
-            }
+    /// Bytes the signature covers
+    pub fn signed_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut value = serde_json::to_value(self)?;
+        if let serde_json::Value::Object(ref mut obj) = value {
+            obj.remove("signature");
         }
+        Ok(canonical_json(&value))
     }
+}
 
-    if features.is_empty() {
-        features.push(normalized);
-    }
+/// Where the detached signature of `path` is written
+pub fn artifact_signature_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".sig.json");
+    name.into()
+}
 
-    let mut weights = [0_i64; BITS];
+/// Sign the file at `path` as an artifact of `checkpoint`
+#[cfg(feature = "app")]
+pub fn sign_file(
+    path: &std::path::Path,
+    checkpoint: ArtifactBinding,
+    signer: &ProjectSigner,
+) -> anyhow::Result<ArtifactSignature> {
+    let mut signature = ArtifactSignature::unsigned(path, checkpoint, signer.public_key())?;
+    signature.signature = signer.sign(&signature.signed_bytes()?)?;
+    Ok(signature)
+}
 
-    for feature in features {
-        let mut hasher = DefaultHasher::new();
-        feature.hash(&mut hasher);
-        let hash = hasher.finish();
-        for bit in 0..BITS {
-            if (hash >> bit) & 1 == 1 {
-                weights[bit] += 1;
-            } else {
-                weights[bit] -= 1;
-            }
-        }
+/// Check that the file at `path` is the one `signature` covers and that the
+/// signature verifies under its public key. Who holds that key, and whether
+/// the checkpoint exists, is for the caller to check.
+pub fn verify_file(path: &std::path::Path, signature: &ArtifactSignature) -> anyhow::Result<()> {
+    if signature.format != ARTIFACT_SIGNATURE_FORMAT {
+        return Err(anyhow::anyhow!(
+            "unsupported artifact signature format '{}'",
+            signature.format
+        ));
     }
-
-    let mut digest: u64 = 0;
-    for bit in 0..BITS {
-        if weights[bit] >= 0 {
-            digest |= 1 << bit;
-        }
+    let sha256 = sha256_file(path)?;
+    if sha256 != signature.sha256 {
+        return Err(anyhow::anyhow!(
+            "file does not match the signature: sha256 is {sha256}, signed {}",
+            signature.sha256
+        ));
     }
-
-    format!("{:016x}", digest)
+    let key = crate::verify::decode_verifying_key(&signature.public_key)?;
+    if !crate::verify::signature_valid(&key, &signature.signed_bytes()?, &signature.signature) {
+        return Err(anyhow::anyhow!("artifact signature does not verify"));
+    }
+    Ok(())
 }
 
-pub fn semantic_distance(a: &str, b: &str) -> Option<u32> {
-    let left = u64::from_str_radix(a, 16).ok()?;
-    let right = u64::from_str_radix(b, 16).ok()?;
-    Some((left ^ right).count_ones())
-}
+// Shared with the verifiers, which recompute digests from bundled outputs
+pub use intelexta_verify_core::semantic::{semantic_digest, semantic_distance};
 
 #[cfg(test)]
 mod tests {
@@ -134,6 +286,42 @@ mod tests {
         assert_eq!(c1, c2, "JCS must produce identical bytes");
     }
 
+    #[test]
+    fn canonical_json_matches_the_shared_vectors() {
+        for vector in intelexta_canon::vectors() {
+            let input: serde_json::Value = serde_json::from_str(&vector.input).unwrap();
+            let canonical = String::from_utf8(canonical_json(&input)).unwrap();
+            assert_eq!(canonical, vector.canonical, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn artifact_signatures_cover_the_file_and_its_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.pdf");
+        std::fs::write(&path, b"%PDF-1.7 report").unwrap();
+
+        let sk = SigningKey::from_bytes(&[5u8; 32]);
+        let binding = ArtifactBinding {
+            run_id: "run-1".into(),
+            checkpoint_id: "ck-1".into(),
+            outputs_sha256: "ab".repeat(32),
+        };
+        let mut signature =
+            ArtifactSignature::unsigned(&path, binding, public_key_from_secret(&sk)).unwrap();
+        signature.signature = sign_bytes(&sk, &signature.signed_bytes().unwrap());
+        assert_eq!(signature.file_name, "report.pdf");
+        assert!(verify_file(&path, &signature).is_ok());
+
+        let mut rebound = signature.clone();
+        rebound.checkpoint.checkpoint_id = "ck-2".into();
+        assert!(verify_file(&path, &rebound).is_err());
+
+        std::fs::write(&path, b"%PDF-1.7 edited").unwrap();
+        let err = verify_file(&path, &signature).unwrap_err();
+        assert!(err.to_string().contains("does not match"), "{err}");
+    }
+
     #[test]
     fn semantic_digest_close_texts_have_small_distance() {
         let original = "Hello world from intelexta";
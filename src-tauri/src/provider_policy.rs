@@ -0,0 +1,139 @@
+// src-tauri/src/provider_policy.rs
+//!
+//! Data residency: which providers and regions a project's models may use
+//!
+//! A project policy can confine steps to a set of catalog providers and to
+//! the regions those providers process data in. Regions come from the model
+//! catalog: `local` for on-device inference, otherwise a jurisdiction such as
+//! `us` or `eu`. `regions: ["local"]` keeps a project local-only and
+//! `deniedRegions: ["us"]` keeps it off US clouds. The orchestrator refuses a
+//! step whose model falls outside the allowlist with a
+//! `provider_policy_violation` incident, and the allowlist in force is
+//! embedded in the run's CAR. Checking steps needs the `app` feature; the
+//! allowlist itself is part of the CAR model.
+
+use serde::{Deserialize, Serialize};
+
+/// Region of on-device inference
+pub const LOCAL_REGION: &str = "local";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAllowlist {
+    /// Catalog providers steps may use; empty allows any provider
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<String>,
+    /// Regions steps' providers may process data in; empty allows any region
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+    /// Regions no step's provider may process data in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_regions: Vec<String>,
+}
+
+impl ProviderAllowlist {
+    pub fn is_unrestricted(&self) -> bool {
+        self.providers.is_empty() && self.regions.is_empty() && self.denied_regions.is_empty()
+    }
+
+    /// This allowlist narrowed to `default`: providers and regions the
+    /// default does not allow are dropped, falling back to the default's
+    /// list when none remain, and the default's denied regions are added
+    pub fn within(&self, default: &ProviderAllowlist) -> ProviderAllowlist {
+        let mut denied_regions = self.denied_regions.clone();
+        for region in &default.denied_regions {
+            if !denied_regions.contains(region) {
+                denied_regions.push(region.clone());
+            }
+        }
+        ProviderAllowlist {
+            providers: narrowed(&self.providers, &default.providers),
+            regions: narrowed(&self.regions, &default.regions),
+            denied_regions,
+        }
+    }
+
+    /// Why a model of `provider`, processing data in `region`, is refused
+    pub fn violation(&self, provider: &str, region: &str) -> Option<String> {
+        if !self.providers.is_empty() && !self.providers.iter().any(|p| p == provider) {
+            return Some(format!(
+                "provider {provider} is not one of the allowed providers ({})",
+                self.providers.join(", ")
+            ));
+        }
+        if self.denied_regions.iter().any(|denied| denied == region) {
+            return Some(format!("provider {provider} processes data in denied region {region}"));
+        }
+        if !self.regions.is_empty() && !self.regions.iter().any(|allowed| allowed == region) {
+            return Some(format!(
+                "provider {provider} processes data in {region}, outside the allowed regions ({})",
+                self.regions.join(", ")
+            ));
+        }
+        None
+    }
+}
+
+/// The entries of `own` that `cap` allows, or `cap` itself when none are;
+/// an empty list allows everything
+fn narrowed(own: &[String], cap: &[String]) -> Vec<String> {
+    if cap.is_empty() {
+        return own.to_vec();
+    }
+    let kept: Vec<String> = own.iter().filter(|entry| cap.contains(entry)).cloned().collect();
+    if kept.is_empty() {
+        cap.to_vec()
+    } else {
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlists_refuse_providers_and_regions_outside_them() {
+        let local_only = ProviderAllowlist {
+            regions: vec![LOCAL_REGION.to_string()],
+            ..ProviderAllowlist::default()
+        };
+        assert_eq!(local_only.violation("ollama", LOCAL_REGION), None);
+        assert!(local_only.violation("anthropic", "us").is_some());
+
+        let no_us_cloud = ProviderAllowlist {
+            denied_regions: vec!["us".to_string()],
+            ..ProviderAllowlist::default()
+        };
+        assert!(no_us_cloud.violation("openai", "us").is_some());
+        assert_eq!(no_us_cloud.violation("mistral", "eu"), None);
+
+        let anthropic = ProviderAllowlist {
+            providers: vec!["anthropic".to_string()],
+            ..ProviderAllowlist::default()
+        };
+        assert!(anthropic.violation("openai", "us").is_some());
+        assert!(ProviderAllowlist::default().is_unrestricted());
+        assert!(!anthropic.is_unrestricted());
+    }
+
+    #[test]
+    fn allowlists_narrow_to_the_default() {
+        let eu_only = ProviderAllowlist {
+            regions: vec!["eu".to_string(), LOCAL_REGION.to_string()],
+            denied_regions: vec!["us".to_string()],
+            ..ProviderAllowlist::default()
+        };
+        let anywhere = ProviderAllowlist {
+            providers: vec!["anthropic".to_string()],
+            regions: vec!["us".to_string(), "eu".to_string()],
+            ..ProviderAllowlist::default()
+        };
+        let narrowed = anywhere.within(&eu_only);
+        assert_eq!(narrowed.providers, vec!["anthropic".to_string()]);
+        assert_eq!(narrowed.regions, vec!["eu".to_string()]);
+        assert_eq!(narrowed.denied_regions, vec!["us".to_string()]);
+        assert_eq!(ProviderAllowlist::default().within(&eu_only), eu_only);
+        assert_eq!(eu_only.within(&eu_only), eu_only);
+    }
+}
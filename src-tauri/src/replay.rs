@@ -1,10 +1,10 @@
 // In src-tauri/src/replay.rs
 use crate::{
-    car,
+    attachments, branching, car, finetune,
+    model_adapters::SamplingOptions,
     orchestrator::{self, RunProofMode},
-    provenance, DbPool,
+    provenance, store, DbPool,
 };
-#[cfg(feature = "interactive")]
 use anyhow::Context;
 use anyhow::{anyhow, Result};
 #[cfg(feature = "interactive")]
@@ -115,6 +115,15 @@ pub struct CheckpointReplayReport {
     /// Nature cost from replay execution
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_nature_cost: Option<f64>,
+    /// Attachment hash of the output recorded by the original execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_output_ref: Option<String>,
+    /// Attachment hash of the output the replay produced, when replay outputs are stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_output_ref: Option<String>,
+    /// Output the replay produced, held until `store_replay_outputs` persists it
+    #[serde(skip)]
+    pub(crate) replay_output: Option<String>,
 }
 
 impl CheckpointReplayReport {
@@ -139,6 +148,9 @@ impl CheckpointReplayReport {
             usage_tokens: None,
             usage_usd: None,
             usage_nature_cost: None,
+            original_output_ref: None,
+            replay_output_ref: None,
+            replay_output: None,
         }
     }
 
@@ -163,6 +175,9 @@ impl CheckpointReplayReport {
             usage_tokens: None,
             usage_usd: None,
             usage_nature_cost: None,
+            original_output_ref: None,
+            replay_output_ref: None,
+            replay_output: None,
         }
     }
 }
@@ -191,6 +206,11 @@ pub struct ReplayReport {
     /// Overall grade (worst grade from all checkpoints)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grade: Option<ReplayGrade>,
+    /// Id of the signed replay CAR, when one was emitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_car_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_car_path: Option<String>,
 }
 
 fn checkpoint_mode_for_step(step: &orchestrator::RunStep) -> CheckpointReplayMode {
@@ -230,6 +250,9 @@ pub fn replay_car(car: &car::Car) -> anyhow::Result<ReplayReport> {
             usage_tokens: None,
             usage_usd: None,
             usage_nature_cost: None,
+            original_output_ref: None,
+            replay_output_ref: None,
+            replay_output: None,
         };
 
         if let Some(process) = car.proof.process.as_ref() {
@@ -267,6 +290,8 @@ pub fn replay_car(car: &car::Car) -> anyhow::Result<ReplayReport> {
             checkpoint_reports,
             similarity_score: None,
             grade: None,
+            replay_car_id: None,
+            replay_car_path: None,
         });
     }
 
@@ -290,6 +315,8 @@ pub fn replay_car(car: &car::Car) -> anyhow::Result<ReplayReport> {
         checkpoint_reports,
         similarity_score: None,
         grade: None,
+        replay_car_id: None,
+        replay_car_path: None,
     })
 }
 
@@ -314,6 +341,8 @@ impl ReplayReport {
                 checkpoint_reports,
                 similarity_score: None,
                 grade: None,
+                replay_car_id: None,
+                replay_car_path: None,
             };
         }
 
@@ -390,6 +419,8 @@ impl ReplayReport {
             checkpoint_reports,
             similarity_score,
             grade,
+            replay_car_id: None,
+            replay_car_path: None,
         }
     }
 }
@@ -414,7 +445,7 @@ fn load_checkpoint_digests(
 ) -> Result<Option<(Option<String>, Option<String>)>> {
     let row = conn
         .query_row(
-            "SELECT outputs_sha256, semantic_digest FROM checkpoints WHERE run_id = ?1 AND checkpoint_config_id = ?2 AND kind = 'Step' ORDER BY timestamp DESC, id DESC LIMIT 1",
+            "SELECT outputs_sha256, semantic_digest FROM checkpoints WHERE run_id = ?1 AND checkpoint_config_id = ?2 AND kind = 'Step' AND parent_checkpoint_id IS NULL ORDER BY timestamp DESC, id DESC LIMIT 1",
             params![run_id, config_id],
             |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<String>>(1)?)),
         )
@@ -422,6 +453,25 @@ fn load_checkpoint_digests(
     Ok(row)
 }
 
+/// Sampling options recorded on the checkpoint's model call, if any
+fn load_checkpoint_sampling(
+    conn: &rusqlite::Connection,
+    run_id: &str,
+    config_id: &str,
+) -> Result<Option<SamplingOptions>> {
+    let sampling_json: Option<String> = conn
+        .query_row(
+            "SELECT sampling_json FROM checkpoints WHERE run_id = ?1 AND checkpoint_config_id = ?2 AND kind = 'Step' AND parent_checkpoint_id IS NULL ORDER BY timestamp DESC, id DESC LIMIT 1",
+            params![run_id, config_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    sampling_json
+        .map(|json| serde_json::from_str(&json).context("invalid recorded sampling options"))
+        .transpose()
+}
+
 pub(crate) fn replay_exact_checkpoint(
     run: &orchestrator::StoredRun,
     conn: &rusqlite::Connection,
@@ -429,6 +479,10 @@ pub(crate) fn replay_exact_checkpoint(
 ) -> Result<CheckpointReplayReport> {
     let mut report = CheckpointReplayReport::new(config, CheckpointReplayMode::Exact);
 
+    if config.step_type == "branch" {
+        return replay_branch_checkpoint(run, conn, config, report);
+    }
+
     let digests = load_checkpoint_digests(conn, &run.id, &config.id)?;
     let Some((original_digest_opt, _semantic_opt)) = digests else {
         report.error_message = Some("no outputs digest recorded for checkpoint".to_string());
@@ -442,10 +496,25 @@ pub(crate) fn replay_exact_checkpoint(
     }
     report.original_digest = original_digest.clone();
 
-    let replay_digest = if config.is_document_ingestion() {
+    let replay_digest = if matches!(config.step_type.as_str(), "plugin" | "agent" | "finetune" | "embed" | "evaluate") {
+        match replay_typed_execution(run, conn, config)? {
+            Ok(node) => {
+                report.usage_tokens = Some(node.usage.total());
+                report.replay_output = node.output_payload;
+                node.outputs_sha256.unwrap_or_default()
+            }
+            Err(reason) => {
+                report.error_message = Some(reason);
+                return Ok(report);
+            }
+        }
+    } else if config.is_document_ingestion() {
         // For document ingestion, re-execute the processing
         if let Some(config_json) = config.config_json.as_ref() {
-            let node = orchestrator::execute_document_ingestion_checkpoint(config_json)?;
+            let policy =
+                store::policies::get_for_policy_version(conn, &run.project_id, run.policy_version)?;
+            let node = orchestrator::execute_document_ingestion_checkpoint(config_json, &policy)?;
+            report.replay_output = node.output_payload;
             node.outputs_sha256.unwrap_or_default()
         } else {
             report.error_message = Some("document ingestion config missing".to_string());
@@ -457,7 +526,9 @@ pub(crate) fn replay_exact_checkpoint(
     } else {
         let model = config.model.as_deref().unwrap_or("");
         let prompt = config.prompt.as_deref().unwrap_or("");
-        let generation = orchestrator::replay_llm_generation(model, prompt)?;
+        let sampling = load_checkpoint_sampling(conn, &run.id, &config.id)?;
+        let generation =
+            orchestrator::replay_llm_generation_with_sampling(model, prompt, sampling.as_ref())?;
 
         // Track usage and costs from replay
         let total_usage = generation.usage.total();
@@ -465,7 +536,9 @@ pub(crate) fn replay_exact_checkpoint(
         report.usage_usd = Some(crate::governance::estimate_usd_cost(total_usage, Some(model)));
         report.usage_nature_cost = Some(crate::governance::estimate_nature_cost(total_usage, Some(model)));
 
-        provenance::sha256_hex(generation.response.as_bytes())
+        let outputs_hex = provenance::sha256_hex(generation.response.as_bytes());
+        report.replay_output = Some(generation.response);
+        outputs_hex
     };
 
     report.replay_digest = replay_digest.clone();
@@ -491,6 +564,10 @@ pub(crate) fn replay_concordant_checkpoint(
     let mut report = CheckpointReplayReport::new(config, CheckpointReplayMode::Concordant);
     report.epsilon = Some(epsilon);
 
+    if config.step_type == "branch" {
+        return replay_branch_checkpoint(run, conn, config, report);
+    }
+
     let digests = load_checkpoint_digests(conn, &run.id, &config.id)?;
     let Some((original_digest_opt, semantic_digest_opt)) = digests else {
         report.error_message = Some("no outputs digest recorded for checkpoint".to_string());
@@ -513,10 +590,28 @@ pub(crate) fn replay_concordant_checkpoint(
     };
     report.semantic_original_digest = Some(original_semantic.clone());
 
-    let (replay_digest, replay_semantic) = if config.is_document_ingestion() {
+    let (replay_digest, replay_semantic) = if matches!(config.step_type.as_str(), "plugin" | "agent" | "finetune" | "embed" | "evaluate") {
+        match replay_typed_execution(run, conn, config)? {
+            Ok(node) => {
+                report.usage_tokens = Some(node.usage.total());
+                report.replay_output = node.output_payload;
+                (
+                    node.outputs_sha256.unwrap_or_default(),
+                    node.semantic_digest.unwrap_or_default(),
+                )
+            }
+            Err(reason) => {
+                report.error_message = Some(reason);
+                return Ok(report);
+            }
+        }
+    } else if config.is_document_ingestion() {
         // For document ingestion, re-execute the processing
         if let Some(config_json) = config.config_json.as_ref() {
-            let node = orchestrator::execute_document_ingestion_checkpoint(config_json)?;
+            let policy =
+                store::policies::get_for_policy_version(conn, &run.project_id, run.policy_version)?;
+            let node = orchestrator::execute_document_ingestion_checkpoint(config_json, &policy)?;
+            report.replay_output = node.output_payload;
             (
                 node.outputs_sha256.unwrap_or_default(),
                 node.semantic_digest.unwrap_or_default(),
@@ -540,6 +635,7 @@ pub(crate) fn replay_concordant_checkpoint(
 
         let outputs_hex = provenance::sha256_hex(generation.response.as_bytes());
         let semantic = provenance::semantic_digest(&generation.response);
+        report.replay_output = Some(generation.response);
         (outputs_hex, semantic)
     };
 
@@ -575,6 +671,387 @@ pub(crate) fn replay_concordant_checkpoint(
     Ok(report)
 }
 
+/// Latest top-level Step checkpoint for a step config: (execution id, outputs digest, output payload)
+fn load_step_output(
+    conn: &rusqlite::Connection,
+    run_id: &str,
+    config_id: &str,
+    run_execution_id: Option<&str>,
+) -> Result<Option<(String, Option<String>, Option<String>)>> {
+    let row = conn
+        .query_row(
+            "SELECT c.run_execution_id, c.outputs_sha256, p.output_payload
+             FROM checkpoints c
+             LEFT JOIN checkpoint_payloads p ON p.checkpoint_id = c.id
+             WHERE c.run_id = ?1 AND c.checkpoint_config_id = ?2 AND c.kind = 'Step'
+               AND c.parent_checkpoint_id IS NULL
+               AND (?3 IS NULL OR c.run_execution_id = ?3)
+             ORDER BY c.timestamp DESC, c.id DESC LIMIT 1",
+            params![run_id, config_id, run_execution_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    Ok(row)
+}
+
+/// Re-execute plugin, agent, fine-tune, embed and evaluate steps, which are replayed as a whole
+/// rather than as a single model call. The inner error explains why the step
+/// could not be replayed.
+fn replay_typed_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    match config.step_type.as_str() {
+        "agent" => replay_agent_execution(run, conn, config),
+        "finetune" => replay_finetune_execution(run, conn, config),
+        "embed" => replay_embed_execution(run, conn, config),
+        "evaluate" => replay_evaluate_execution(run, conn, config),
+        _ => replay_plugin_execution(run, conn, config),
+    }
+}
+
+/// Training is not repeated: the recorded manifest is reproduced only if the
+/// datasets and weights it names still hash to the recorded values
+fn replay_finetune_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    let Some(output_ref) = load_original_output_ref(conn, &run.id, &config.id)? else {
+        return Ok(Err("no training manifest recorded for checkpoint".to_string()));
+    };
+    let manifest_json = attachments::get_global_attachment_store().load_full_output(&output_ref)?;
+    let manifest: finetune::FineTuneManifest =
+        serde_json::from_str(&manifest_json).context("invalid recorded training manifest")?;
+
+    let mismatches = finetune::check_artifacts(&manifest);
+    if !mismatches.is_empty() {
+        return Ok(Err(mismatches.join("; ")));
+    }
+    let manifest_value = serde_json::to_value(&manifest)?;
+    let manifest_json = serde_json::to_string(&manifest)?;
+    Ok(Ok(orchestrator::NodeExecution {
+        inputs_sha256: None,
+        outputs_sha256: Some(provenance::sha256_hex(&provenance::canonical_json(
+            &manifest_value,
+        ))),
+        semantic_digest: Some(provenance::semantic_digest(&manifest_json)),
+        usage: orchestrator::TokenUsage {
+            prompt_tokens: 0,
+            completion_tokens: 0,
+        },
+        prompt_payload: None,
+        output_payload: Some(manifest_json),
+        sampling: None,
+        cached: false,
+        response_metadata: None,
+    }))
+}
+
+/// Source output the given step consumed, as recorded by the same execution
+fn load_chained_source(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+    source_step: usize,
+) -> Result<std::result::Result<String, String>> {
+    let Some((execution_id, _, _)) = load_step_output(conn, &run.id, &config.id, None)? else {
+        return Ok(Err("no output recorded for checkpoint".to_string()));
+    };
+    let Some(source_config) = run
+        .steps
+        .iter()
+        .find(|step| step.order_index == source_step as i64)
+    else {
+        return Ok(Err(format!("source step {source_step} not found")));
+    };
+    let Some((_, _, Some(source_output))) =
+        load_step_output(conn, &run.id, &source_config.id, Some(&execution_id))?
+    else {
+        return Ok(Err("no source output recorded for step".to_string()));
+    };
+    Ok(Ok(orchestrator::chained_input_text(&source_output)))
+}
+
+/// Re-embed the source output recorded by the same execution. The manifest
+/// matches only if the model returns the same vectors.
+fn replay_embed_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    let step_config = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok());
+    let Some(orchestrator::StepConfig::Embed {
+        source_step, model, ..
+    }) = step_config
+    else {
+        return Ok(Err("embed config missing".to_string()));
+    };
+
+    let source_text = match load_chained_source(run, conn, config, source_step)? {
+        Ok(text) => text,
+        Err(reason) => return Ok(Err(reason)),
+    };
+    let client = orchestrator::DispatchingLlmClient::new();
+    Ok(
+        orchestrator::execute_embed_checkpoint(&model, &source_text, &client)
+            .map(|(execution, _)| execution)
+            .map_err(|err| format!("embed replay failed: {err:#}")),
+    )
+}
+
+/// Re-score the outputs the evaluation compared. Lexical scores always
+/// reproduce; embedding and judge scores only if the models answer the same.
+fn replay_evaluate_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    let step_config = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok());
+    let Some(orchestrator::StepConfig::Evaluate {
+        candidate_step,
+        reference_step,
+        reference_checkpoint_id,
+        embedding_model,
+        judge_model,
+        judge_criteria,
+        ..
+    }) = step_config
+    else {
+        return Ok(Err("evaluate config missing".to_string()));
+    };
+
+    let candidate = match load_chained_source(run, conn, config, candidate_step)? {
+        Ok(text) => text,
+        Err(reason) => return Ok(Err(reason)),
+    };
+    let reference = match (reference_step, reference_checkpoint_id.as_deref()) {
+        (Some(reference_step), _) => match load_chained_source(run, conn, config, reference_step)? {
+            Ok(text) => text,
+            Err(reason) => return Ok(Err(reason)),
+        },
+        (None, Some(checkpoint_id)) => {
+            match orchestrator::load_reference_output(conn, &run.project_id, checkpoint_id) {
+                Ok(text) => text,
+                Err(err) => return Ok(Err(format!("{err:#}"))),
+            }
+        }
+        (None, None) => return Ok(Err("evaluate config has no reference".to_string())),
+    };
+    let sampling = load_checkpoint_sampling(conn, &run.id, &config.id)?;
+    let task = orchestrator::EvaluationTask {
+        candidate: &candidate,
+        reference: &reference,
+        reference_checkpoint_id: reference_checkpoint_id.as_deref(),
+        embedding_model: embedding_model.as_deref(),
+        judge_model: judge_model.as_deref(),
+        judge_criteria: judge_criteria.as_deref(),
+    };
+    let client = orchestrator::DispatchingLlmClient::new();
+    Ok(
+        orchestrator::execute_evaluate_checkpoint(&task, run.seed, config.order_index, sampling, &client)
+            .map(|evaluation_run| evaluation_run.execution)
+            .map_err(|err| format!("evaluate replay failed: {err:#}")),
+    )
+}
+
+/// Re-run the agent loop with the recorded sampling options. Tool calls are
+/// executed again, so the trajectory must reproduce for the answer to match.
+fn replay_agent_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    let step_config = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok());
+    let Some(orchestrator::StepConfig::Agent {
+        model,
+        prompt,
+        tools,
+        source_step,
+        file_roots,
+        max_turns,
+        ..
+    }) = step_config
+    else {
+        return Ok(Err("agent config missing".to_string()));
+    };
+
+    let corpus = match source_step {
+        Some(source_step) => match load_chained_source(run, conn, config, source_step)? {
+            Ok(text) => Some(text),
+            Err(reason) => return Ok(Err(reason)),
+        },
+        None => None,
+    };
+    let sampling = load_checkpoint_sampling(conn, &run.id, &config.id)?;
+    let task = orchestrator::AgentTask {
+        model: &model,
+        task: &prompt,
+        tools: &tools,
+        corpus: corpus.as_deref(),
+        file_roots: &file_roots,
+        max_turns: max_turns.unwrap_or(crate::agent::DEFAULT_MAX_TURNS),
+        token_budget: config.token_budget,
+    };
+    let client = orchestrator::DispatchingLlmClient::new();
+    Ok(
+        orchestrator::execute_agent_checkpoint(&task, run.seed, config.order_index, sampling, &client)
+            .map(|agent_run| agent_run.execution)
+            .map_err(|err| format!("agent replay failed: {err:#}")),
+    )
+}
+
+/// Re-run a plugin step on the source output recorded by the same execution.
+fn replay_plugin_execution(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+) -> Result<std::result::Result<orchestrator::NodeExecution, String>> {
+    let step_config = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok());
+    let Some(orchestrator::StepConfig::Plugin {
+        plugin,
+        step,
+        config: plugin_config,
+        source_step,
+        source_path,
+        ..
+    }) = step_config
+    else {
+        return Ok(Err("plugin config missing".to_string()));
+    };
+
+    let source_text = match source_step {
+        Some(source_step) => match load_chained_source(run, conn, config, source_step)? {
+            Ok(text) => Some(text),
+            Err(reason) => return Ok(Err(reason)),
+        },
+        None => None,
+    };
+
+    Ok(orchestrator::execute_plugin_checkpoint(
+        &plugin,
+        &step,
+        &plugin_config,
+        run.seed,
+        source_text.as_deref(),
+        source_path.as_deref(),
+    )
+    .map_err(|err| format!("plugin replay failed: {err:#}")))
+}
+
+/// Branch checkpoints record their decision as output. Replay re-derives the
+/// outcome from the recorded source output (or, for classification, from the
+/// recorded classifier response) and checks the same branch would be taken.
+fn replay_branch_checkpoint(
+    run: &orchestrator::StoredRun,
+    conn: &rusqlite::Connection,
+    config: &orchestrator::RunStep,
+    mut report: CheckpointReplayReport,
+) -> Result<CheckpointReplayReport> {
+    let step_config = config
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str::<orchestrator::StepConfig>(json).ok());
+    let Some(orchestrator::StepConfig::Branch {
+        source_step,
+        predicate,
+        branches,
+    }) = step_config
+    else {
+        report.error_message = Some("branch config missing".to_string());
+        return Ok(report);
+    };
+
+    let Some((execution_id, Some(original_digest), Some(decision_json))) =
+        load_step_output(conn, &run.id, &config.id, None)?
+    else {
+        report.error_message = Some("no branch decision recorded for checkpoint".to_string());
+        return Ok(report);
+    };
+    report.original_digest = original_digest.clone();
+
+    let recorded: branching::BranchDecision = match serde_json::from_str(&decision_json) {
+        Ok(decision) => decision,
+        Err(err) => {
+            report.error_message = Some(format!("recorded branch decision is malformed: {err}"));
+            return Ok(report);
+        }
+    };
+
+    let Some(source_config) = run
+        .steps
+        .iter()
+        .find(|step| step.order_index == source_step as i64)
+    else {
+        report.error_message = Some(format!("branch source step {source_step} not found"));
+        return Ok(report);
+    };
+    let Some((_, source_digest, Some(source_output))) =
+        load_step_output(conn, &run.id, &source_config.id, Some(&execution_id))?
+    else {
+        report.error_message = Some("no source output recorded for branch".to_string());
+        return Ok(report);
+    };
+    if source_digest.as_deref() != Some(recorded.source_outputs_sha256.as_str()) {
+        report.error_message =
+            Some("branch decision references a different source output".to_string());
+        return Ok(report);
+    }
+
+    let outcome = match &predicate {
+        branching::BranchPredicate::LlmClassification { labels, .. } => recorded
+            .classifier_response
+            .as_deref()
+            .and_then(|response| branching::match_label(labels, response))
+            .ok_or_else(|| "recorded classifier response matches no label".to_string()),
+        deterministic => branching::evaluate_deterministic(deterministic, &source_output),
+    };
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(reason) => {
+            report.error_message = Some(format!("branch predicate failed on replay: {reason}"));
+            return Ok(report);
+        }
+    };
+
+    let (selected_steps, skipped_steps) = branching::resolve_groups(&branches, &outcome);
+    let replayed = branching::BranchDecision {
+        predicate,
+        source_step,
+        source_outputs_sha256: recorded.source_outputs_sha256.clone(),
+        outcome,
+        selected_steps,
+        skipped_steps,
+        classifier_response: recorded.classifier_response.clone(),
+    };
+
+    report.replay_digest = replayed.digest();
+    report.replay_output = serde_json::to_string(&replayed).ok();
+    if report.replay_digest == original_digest {
+        report.match_status = true;
+    } else {
+        report.error_message = Some(format!(
+            "branch outcome mismatch: recorded '{}', replay '{}'",
+            recorded.outcome, replayed.outcome
+        ));
+    }
+
+    Ok(report)
+}
+
 pub fn replay_exact_run(run_id: String, pool: &DbPool) -> Result<ReplayReport> {
     let conn = pool.get()?;
     let stored_run = match orchestrator::load_stored_run(&conn, &run_id) {
@@ -593,6 +1070,8 @@ pub fn replay_exact_run(run_id: String, pool: &DbPool) -> Result<ReplayReport> {
                 checkpoint_reports: Vec::new(),
                 similarity_score: None,
                 grade: None,
+                replay_car_id: None,
+                replay_car_path: None,
             });
         }
     };
@@ -617,6 +1096,8 @@ pub fn replay_exact_run(run_id: String, pool: &DbPool) -> Result<ReplayReport> {
             checkpoint_reports: Vec::new(),
             similarity_score: None,
             grade: None,
+            replay_car_id: None,
+            replay_car_path: None,
         });
     }
 
@@ -697,6 +1178,7 @@ mod tests {
             Ok(orchestrator::LlmGeneration {
                 response: self.response.clone(),
                 usage: self.usage,
+                metadata: None,
             })
         }
     }
@@ -819,6 +1301,8 @@ pub fn replay_concordant_run(run_id: String, pool: &DbPool) -> Result<ReplayRepo
                 checkpoint_reports: Vec::new(),
                 similarity_score: None,
                 grade: None,
+                replay_car_id: None,
+                replay_car_path: None,
             });
         }
     };
@@ -843,6 +1327,8 @@ pub fn replay_concordant_run(run_id: String, pool: &DbPool) -> Result<ReplayRepo
             checkpoint_reports: Vec::new(),
             similarity_score: None,
             grade: None,
+            replay_car_id: None,
+            replay_car_path: None,
         });
     }
 
@@ -937,6 +1423,8 @@ pub fn replay_interactive_run(run_id: String, pool: &DbPool) -> Result<ReplayRep
                 checkpoint_reports: Vec::new(),
                 similarity_score: None,
                 grade: None,
+                replay_car_id: None,
+                replay_car_path: None,
             });
         }
     };
@@ -957,6 +1445,8 @@ pub fn replay_interactive_run(run_id: String, pool: &DbPool) -> Result<ReplayRep
                 checkpoint_reports: Vec::new(),
                 similarity_score: None,
                 grade: None,
+                replay_car_id: None,
+                replay_car_path: None,
             });
         }
     };
@@ -1199,6 +1689,9 @@ pub fn replay_interactive_run(run_id: String, pool: &DbPool) -> Result<ReplayRep
                     usage_tokens: None,
                     usage_usd: None,
                     usage_nature_cost: None,
+                    original_output_ref: None,
+                    replay_output_ref: None,
+                    replay_output: None,
                 }
             }
         } else {
@@ -1222,6 +1715,9 @@ pub fn replay_interactive_run(run_id: String, pool: &DbPool) -> Result<ReplayRep
                 usage_tokens: None,
                 usage_usd: None,
                 usage_nature_cost: None,
+                original_output_ref: None,
+                replay_output_ref: None,
+                replay_output: None,
             }
         };
 
@@ -1266,3 +1762,146 @@ pub fn replay_interactive_run(run_id: String, pool: &DbPool) -> Result<ReplayRep
         failure,
     ))
 }
+
+/// What a replay keeps besides its report
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayOptions {
+    /// Store replay outputs content-addressed and reference them from the report
+    #[serde(default)]
+    pub store_outputs: bool,
+    /// Also emit a signed replay CAR bundling the report with both sets of outputs
+    #[serde(default)]
+    pub emit_replay_car: bool,
+}
+
+/// Attachment hash of the latest recorded output for a step config
+fn load_original_output_ref(
+    conn: &rusqlite::Connection,
+    run_id: &str,
+    config_id: &str,
+) -> Result<Option<String>> {
+    let hash: Option<Option<String>> = conn
+        .query_row(
+            "SELECT p.full_output_hash
+             FROM checkpoints c
+             JOIN checkpoint_payloads p ON p.checkpoint_id = c.id
+             WHERE c.run_id = ?1 AND c.checkpoint_config_id = ?2 AND c.kind = 'Step'
+               AND c.parent_checkpoint_id IS NULL
+             ORDER BY c.timestamp DESC, c.id DESC LIMIT 1",
+            params![run_id, config_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(hash.flatten())
+}
+
+/// Persist the outputs a replay produced in the attachment store and link each
+/// checkpoint report to both the original and the replayed output, so
+/// disagreements can be inspected rather than only measured.
+pub fn store_replay_outputs(
+    conn: &rusqlite::Connection,
+    run_id: &str,
+    checkpoint_reports: &mut [CheckpointReplayReport],
+) -> Result<()> {
+    let store = attachments::get_global_attachment_store();
+    for report in checkpoint_reports.iter_mut() {
+        if let Some(config_id) = report.checkpoint_config_id.as_deref() {
+            report.original_output_ref = load_original_output_ref(conn, run_id, config_id)?;
+        }
+        if let Some(output) = report.replay_output.take() {
+            report.replay_output_ref = Some(store.save_full_output(&output)?);
+        }
+    }
+    Ok(())
+}
+
+/// Signed, portable record of a replay: the report plus every output it compared
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReplayCar {
+    pub id: String, // "replay-car:..." - sha256 of the canonical body
+    pub run_id: String,
+    pub created_at: String,
+    pub report: ReplayReport,
+    /// Attachment hashes of the original and replayed outputs
+    pub attachments: Vec<String>,
+    pub signer_public_key: String,
+    pub signatures: Vec<String>,
+}
+
+pub fn build_replay_car(conn: &rusqlite::Connection, report: &ReplayReport) -> Result<ReplayCar> {
+    let (project_id, pubkey): (String, String) = conn
+        .query_row(
+            "SELECT r.project_id, p.pubkey FROM runs r JOIN projects p ON p.id = r.project_id WHERE r.id = ?1",
+            params![&report.run_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|err| anyhow!("failed to load run {}: {err}", report.run_id))?;
+
+    let mut attachments: Vec<String> = report
+        .checkpoint_reports
+        .iter()
+        .flat_map(|entry| [entry.original_output_ref.clone(), entry.replay_output_ref.clone()])
+        .flatten()
+        .collect();
+    attachments.sort();
+    attachments.dedup();
+
+    let mut replay_car = ReplayCar {
+        id: String::new(),
+        run_id: report.run_id.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        report: ReplayReport {
+            replay_car_id: None,
+            replay_car_path: None,
+            ..report.clone()
+        },
+        attachments,
+        signer_public_key: pubkey,
+        signatures: Vec::new(),
+    };
+
+    let mut body_value = serde_json::to_value(&replay_car)?;
+    if let serde_json::Value::Object(ref mut obj) = body_value {
+        obj.remove("id");
+        obj.remove("signatures");
+    }
+    let canonical = provenance::canonical_json(&body_value);
+    replay_car.id = format!("replay-car:{}", provenance::sha256_hex(&canonical));
+
+    let signer = provenance::ProjectSigner::load(&project_id)
+        .with_context(|| format!("failed to load signing key for project {project_id}"))?;
+    let mut signed_body = serde_json::to_value(&replay_car)?;
+    if let serde_json::Value::Object(ref mut obj) = signed_body {
+        obj.remove("signatures");
+    }
+    let body_signature = signer.sign(&provenance::canonical_json(&signed_body))?;
+    replay_car.signatures.push(format!("ed25519-body:{body_signature}"));
+
+    Ok(replay_car)
+}
+
+/// Write a replay CAR and its referenced outputs as a zip bundle
+pub fn write_replay_car_bundle(replay_car: &ReplayCar, output_path: &std::path::Path) -> Result<()> {
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    let file = std::fs::File::create(output_path)
+        .with_context(|| format!("Failed to create zip file at {:?}", output_path))?;
+    let mut zip = zip::ZipWriter::new(file);
+
+    zip.start_file("replay_car.json", FileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(replay_car)?.as_bytes())?;
+
+    let store = attachments::get_global_attachment_store();
+    for hash in &replay_car.attachments {
+        if store.exists(hash) {
+            let content = store.load_full_output(hash)?;
+            zip.start_file(format!("attachments/{hash}.txt"), FileOptions::default())?;
+            zip.write_all(content.as_bytes())?;
+        }
+    }
+
+    zip.finish()?;
+    Ok(())
+}
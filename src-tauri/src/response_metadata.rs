@@ -0,0 +1,46 @@
+// src-tauri/src/response_metadata.rs
+//!
+//! What a checkpoint records about a model response
+//!
+//! Checkpoint bodies hash these fields, so they are part of the CAR model and
+//! build without the model adapters (see the `verify` feature). The adapters
+//! re-export them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Why a provider stopped generating, in one vocabulary across providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    Stop,
+    Length,
+    ToolCalls,
+    ContentFilter,
+    /// Abandoned mid-stream because the step's token budget ran out
+    Budget,
+    Unknown,
+}
+
+/// Tool invocation requested by the model through the provider's native API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// What a checkpoint records about a response besides its text and usage,
+/// which the checkpoint already carries as hashes and token counts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    pub provider: String,
+    pub finish_reason: FinishReason,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_headers_sha256: Option<String>,
+}
@@ -0,0 +1,202 @@
+// src-tauri/src/run_environment.rs
+//!
+//! Run environment: where an execution ran
+//!
+//! Every run execution records the operating system, app version, model
+//! catalog version, Ollama version and a hash of the machine identity when
+//! it starts. The record is stored with the execution and embedded in its
+//! CAR, so a verifier can see the environment a receipt was produced in.
+//! Capturing it needs the `app` feature; the record itself is part of the
+//! CAR model.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunEnvironment {
+    /// e.g. `linux`, `macos`, `windows`
+    pub os: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub app_version: String,
+    pub model_catalog_version: String,
+    /// Only recorded for runs on local models, when Ollama answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ollama_version: Option<String>,
+    /// SHA-256 of the machine id, so runs from one machine can be matched
+    /// without disclosing the id itself
+    pub machine_fingerprint: String,
+}
+
+#[cfg(feature = "app")]
+mod capture {
+    use super::RunEnvironment;
+    use crate::{model_catalog, provenance};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    const OLLAMA_VERSION_URL: &str = "http://127.0.0.1:11434/api/version";
+
+    /// Ollama is local; a missing server should not hold up the execution
+    const OLLAMA_TIMEOUT: Duration = Duration::from_secs(2);
+
+    impl RunEnvironment {
+        /// Capture the current environment; `query_ollama` asks the local Ollama
+        /// server for its version
+        pub fn capture(query_ollama: bool) -> Self {
+            Self {
+                os: std::env::consts::OS.to_string(),
+                os_version: os_version(),
+                arch: std::env::consts::ARCH.to_string(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                model_catalog_version: model_catalog::get_global_catalog().version().to_string(),
+                ollama_version: query_ollama.then(ollama_version).flatten(),
+                machine_fingerprint: machine_fingerprint(machine_id().as_deref()),
+            }
+        }
+    }
+
+    fn ollama_version() -> Option<String> {
+        #[derive(Deserialize)]
+        struct VersionResponse {
+            version: String,
+        }
+
+        ureq::builder()
+            .timeout(OLLAMA_TIMEOUT)
+            .build()
+            .get(OLLAMA_VERSION_URL)
+            .call()
+            .ok()?
+            .into_json::<VersionResponse>()
+            .ok()
+            .map(|response| response.version)
+    }
+
+    fn machine_fingerprint(machine_id: Option<&str>) -> String {
+        let machine_id = machine_id.unwrap_or("unknown");
+        format!(
+            "sha256:{}",
+            provenance::sha256_hex(format!("intelexta-machine:{machine_id}").as_bytes())
+        )
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    fn command_output(program: &str, args: &[&str]) -> Option<String> {
+        let output = std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// `PRETTY_NAME` of an os-release file
+    #[cfg(any(target_os = "linux", test))]
+    fn parse_os_release(contents: &str) -> Option<String> {
+        contents.lines().find_map(|line| {
+            line.strip_prefix("PRETTY_NAME=")
+                .map(|value| value.trim().trim_matches('"').to_string())
+                .filter(|value| !value.is_empty())
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn os_version() -> Option<String> {
+        std::fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|contents| parse_os_release(&contents))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn os_version() -> Option<String> {
+        command_output("sw_vers", &["-productVersion"]).map(|version| version.trim().to_string())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn os_version() -> Option<String> {
+        command_output("cmd", &["/C", "ver"]).map(|version| version.trim().to_string())
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    fn os_version() -> Option<String> {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn machine_id() -> Option<String> {
+        ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .map(|id| id.trim().to_string())
+            .find(|id| !id.is_empty())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn machine_id() -> Option<String> {
+        let output = command_output("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"])?;
+        output.lines().find_map(|line| {
+            let (_, value) = line.split_once("\"IOPlatformUUID\" = ")?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn machine_id() -> Option<String> {
+        let output = command_output(
+            "reg",
+            &[
+                "query",
+                r"HKLM\SOFTWARE\Microsoft\Cryptography",
+                "/v",
+                "MachineGuid",
+            ],
+        )?;
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("MachineGuid"))
+            .and_then(|rest| rest.split_whitespace().last())
+            .map(str::to_string)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    fn machine_id() -> Option<String> {
+        None
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn fingerprints_hide_the_machine_id_but_match_across_runs() {
+            let fingerprint = machine_fingerprint(Some("4c4c4544-0032"));
+            assert!(fingerprint.starts_with("sha256:"));
+            assert!(!fingerprint.contains("4c4c4544"));
+            assert_eq!(fingerprint, machine_fingerprint(Some("4c4c4544-0032")));
+            assert_ne!(fingerprint, machine_fingerprint(Some("4c4c4544-0033")));
+        }
+
+        #[test]
+        fn os_release_pretty_name_is_unquoted() {
+            let contents = "NAME=\"Ubuntu\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\nID=ubuntu\n";
+            assert_eq!(
+                parse_os_release(contents).as_deref(),
+                Some("Ubuntu 24.04.1 LTS")
+            );
+            assert_eq!(parse_os_release("ID=arch\n"), None);
+        }
+
+        #[test]
+        fn environments_without_ollama_omit_its_version() {
+            let environment = RunEnvironment::capture(false);
+            assert_eq!(environment.os, std::env::consts::OS);
+            assert_eq!(environment.app_version, env!("CARGO_PKG_VERSION"));
+            assert!(environment.ollama_version.is_none());
+            let json = serde_json::to_value(&environment).unwrap();
+            assert!(json.get("ollama_version").is_none());
+        }
+    }
+}
@@ -0,0 +1,487 @@
+// src-tauri/src/run_queue.rs
+//!
+//! Run Queue: admission control for concurrent run executions
+//!
+//! Every execution passes through the queue before it touches the database.
+//! A project runs at most `Policy::max_concurrent_runs` executions at once;
+//! further executions wait until a slot frees up, higher-priority runs first
+//! and in arrival order within a priority. Projects do not wait on each other.
+//!
+//! A running execution can be preempted between steps: when a higher-priority
+//! execution of the project is waiting and no slot is free, the running one
+//! goes back to waiting until the queue admits it again (see
+//! `RunPermit::yield_to_higher_priority`). Executions on the worker pool wait
+//! through `runtime::park`, so the runs they yield to get a worker.
+//!
+//! An execution may also be held back before it joins the queue, e.g. while
+//! the project's execution window is closed. Such executions are listed as
+//! deferred with the reason and do not hold up later arrivals.
+//!
+//! Queued and running executions are reported by `list_active_executions`.
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+static GLOBAL_QUEUE: Lazy<RunQueue> = Lazy::new(RunQueue::new);
+
+/// The queue shared by every caller that starts runs
+pub fn global() -> &'static RunQueue {
+    &GLOBAL_QUEUE
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum RunPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl RunPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunPriority::Low => "low",
+            RunPriority::Normal => "normal",
+            RunPriority::High => "high",
+        }
+    }
+}
+
+impl TryFrom<&str> for RunPriority {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "low" => Ok(RunPriority::Low),
+            "normal" => Ok(RunPriority::Normal),
+            "high" => Ok(RunPriority::High),
+            other => Err(format!("unknown run priority '{other}'")),
+        }
+    }
+}
+
+/// A pause of a running execution so a higher-priority one could take its slot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Preemption {
+    /// Step the execution was about to start
+    pub before_step: i64,
+    /// Run whose execution was waiting for the slot
+    pub preempted_by: String,
+    pub paused_at: String,
+    pub resumed_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionState {
+    Deferred,
+    Queued,
+    Running,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveExecution {
+    pub ticket: u64,
+    pub project_id: String,
+    pub run_id: String,
+    pub priority: RunPriority,
+    /// Assigned once the execution has been recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    pub state: ExecutionState,
+    /// Why a deferred execution is waiting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deferred_reason: Option<String>,
+    pub queued_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    next_ticket: u64,
+    /// Queued and running executions in arrival order
+    entries: Vec<ActiveExecution>,
+}
+
+impl QueueState {
+    fn project_entries<'a>(
+        &'a self,
+        project_id: &'a str,
+    ) -> impl Iterator<Item = &'a ActiveExecution> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.project_id == project_id)
+    }
+
+    fn running(&self, project_id: &str) -> usize {
+        self.project_entries(project_id)
+            .filter(|entry| entry.state == ExecutionState::Running)
+            .count()
+    }
+
+    /// The waiting execution the project admits next
+    fn next_waiting<'a>(&'a self, project_id: &'a str) -> Option<&'a ActiveExecution> {
+        self.project_entries(project_id)
+            .filter(|entry| entry.state == ExecutionState::Queued)
+            .max_by_key(|entry| (entry.priority, Reverse(entry.ticket)))
+    }
+
+    fn is_admissible(&self, ticket: u64, project_id: &str, limit: usize) -> bool {
+        self.running(project_id) < limit
+            && self.next_waiting(project_id).map(|entry| entry.ticket) == Some(ticket)
+    }
+
+    fn entry_mut(&mut self, ticket: u64) -> Option<&mut ActiveExecution> {
+        self.entries.iter_mut().find(|entry| entry.ticket == ticket)
+    }
+}
+
+pub struct RunQueue {
+    state: Mutex<QueueState>,
+    slot_freed: Condvar,
+}
+
+impl Default for RunQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunQueue {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            slot_freed: Condvar::new(),
+        }
+    }
+
+    /// Block until the project has a free slot and return a permit that holds
+    /// it. The slot is released when the permit is dropped.
+    pub fn acquire(
+        &self,
+        project_id: &str,
+        run_id: &str,
+        priority: RunPriority,
+        max_concurrent: u32,
+    ) -> RunPermit<'_> {
+        self.acquire_when(
+            project_id,
+            run_id,
+            priority,
+            max_concurrent,
+            Duration::ZERO,
+            || None,
+        )
+    }
+
+    /// Like `acquire`, but hold the execution as deferred for as long as
+    /// `deferral` gives a reason to wait, asking again every `recheck`
+    pub fn acquire_when(
+        &self,
+        project_id: &str,
+        run_id: &str,
+        priority: RunPriority,
+        max_concurrent: u32,
+        recheck: Duration,
+        deferral: impl Fn() -> Option<String>,
+    ) -> RunPermit<'_> {
+        let limit = max_concurrent.max(1) as usize;
+        let mut reason = deferral();
+        let ticket = {
+            let mut state = self.lock();
+            state.next_ticket += 1;
+            let ticket = state.next_ticket;
+            state.entries.push(ActiveExecution {
+                ticket,
+                project_id: project_id.to_string(),
+                run_id: run_id.to_string(),
+                priority,
+                execution_id: None,
+                state: if reason.is_some() {
+                    ExecutionState::Deferred
+                } else {
+                    ExecutionState::Queued
+                },
+                deferred_reason: reason.clone(),
+                queued_at: Utc::now().to_rfc3339(),
+                started_at: None,
+            });
+            ticket
+        };
+        // Removes the entry again should the deferral check panic
+        let permit = RunPermit {
+            queue: self,
+            ticket,
+            project_id: project_id.to_string(),
+            limit,
+        };
+
+        while reason.is_some() {
+            std::thread::sleep(recheck);
+            reason = deferral();
+            let mut state = self.lock();
+            if let Some(entry) = state.entry_mut(ticket) {
+                entry.deferred_reason = reason.clone();
+                if reason.is_none() {
+                    entry.state = ExecutionState::Queued;
+                }
+            }
+        }
+
+        let state = self.lock();
+        let state = self.wait_for_slot(state, ticket, project_id, limit);
+        drop(state);
+
+        permit
+    }
+
+    /// Wait until the queue admits the execution, then mark it running
+    fn wait_for_slot<'a>(
+        &'a self,
+        mut state: MutexGuard<'a, QueueState>,
+        ticket: u64,
+        project_id: &str,
+        limit: usize,
+    ) -> MutexGuard<'a, QueueState> {
+        while !state.is_admissible(ticket, project_id, limit) {
+            state = self
+                .slot_freed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if let Some(entry) = state.entry_mut(ticket) {
+            entry.state = ExecutionState::Running;
+            entry.started_at = Some(Utc::now().to_rfc3339());
+        }
+        // Later executions of the same project may fit in the remaining slots
+        self.slot_freed.notify_all();
+        state
+    }
+
+    /// Snapshot of queued and running executions, optionally for one project
+    pub fn active(&self, project_id: Option<&str>) -> Vec<ActiveExecution> {
+        self.lock()
+            .entries
+            .iter()
+            .filter(|entry| project_id.is_none_or(|id| entry.project_id == id))
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        // A panicking run must not wedge every other project
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A running slot in the queue
+pub struct RunPermit<'a> {
+    queue: &'a RunQueue,
+    ticket: u64,
+    project_id: String,
+    limit: usize,
+}
+
+impl RunPermit<'_> {
+    pub fn set_execution_id(&self, execution_id: &str) {
+        let mut state = self.queue.lock();
+        if let Some(entry) = state.entry_mut(self.ticket) {
+            entry.execution_id = Some(execution_id.to_string());
+        }
+    }
+
+    /// Call at a safe point between steps. If a higher-priority execution of
+    /// the project is waiting and every slot is taken, give up this slot and
+    /// block until the queue admits this execution again.
+    pub fn yield_to_higher_priority(&self, before_step: i64) -> Option<Preemption> {
+        let mut state = self.queue.lock();
+        let own_priority = state
+            .entries
+            .iter()
+            .find(|entry| entry.ticket == self.ticket)?
+            .priority;
+        if state.running(&self.project_id) < self.limit {
+            return None;
+        }
+        let preempted_by = state
+            .next_waiting(&self.project_id)
+            .filter(|waiting| waiting.priority > own_priority)?
+            .run_id
+            .clone();
+
+        let paused_at = Utc::now().to_rfc3339();
+        if let Some(entry) = state.entry_mut(self.ticket) {
+            entry.state = ExecutionState::Queued;
+        }
+        self.queue.slot_freed.notify_all();
+        let state = self
+            .queue
+            .wait_for_slot(state, self.ticket, &self.project_id, self.limit);
+        drop(state);
+
+        Some(Preemption {
+            before_step,
+            preempted_by,
+            paused_at,
+            resumed_at: Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+impl Drop for RunPermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.entries.retain(|entry| entry.ticket != self.ticket);
+        drop(state);
+        self.queue.slot_freed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn permits_are_listed_until_dropped() {
+        let queue = RunQueue::new();
+        let permit = queue.acquire("project-a", "run-1", RunPriority::Normal, 2);
+        permit.set_execution_id("exec-1");
+
+        let active = queue.active(Some("project-a"));
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].state, ExecutionState::Running);
+        assert_eq!(active[0].execution_id.as_deref(), Some("exec-1"));
+        assert!(queue.active(Some("project-b")).is_empty());
+
+        drop(permit);
+        assert!(queue.active(None).is_empty());
+    }
+
+    #[test]
+    fn limits_concurrency_per_project() {
+        let queue = Arc::new(RunQueue::new());
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..6)
+            .map(|index| {
+                let queue = Arc::clone(&queue);
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                std::thread::spawn(move || {
+                    let _permit =
+                        queue.acquire("project-a", &format!("run-{index}"), RunPriority::Normal, 2);
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert!(queue.active(None).is_empty());
+    }
+
+    #[test]
+    fn deferred_executions_wait_without_holding_up_the_queue() {
+        let queue = Arc::new(RunQueue::new());
+        let checks = Arc::new(AtomicUsize::new(0));
+
+        let deferred = {
+            let queue = Arc::clone(&queue);
+            let checks = Arc::clone(&checks);
+            std::thread::spawn(move || {
+                let _permit = queue.acquire_when(
+                    "project-a",
+                    "run-1",
+                    RunPriority::Normal,
+                    1,
+                    Duration::from_millis(50),
+                    || {
+                        (checks.fetch_add(1, Ordering::SeqCst) < 3)
+                            .then(|| "outside execution window 22:00-06:00".to_string())
+                    },
+                );
+            })
+        };
+        while queue.active(None).is_empty() {
+            std::thread::yield_now();
+        }
+        let listed = queue.active(Some("project-a"));
+        assert_eq!(listed[0].state, ExecutionState::Deferred);
+        assert!(listed[0].deferred_reason.is_some());
+
+        // A later arrival of the same project is admitted meanwhile
+        let later = queue.acquire("project-a", "run-2", RunPriority::Normal, 1);
+        drop(later);
+
+        deferred.join().unwrap();
+        assert_eq!(checks.load(Ordering::SeqCst), 4);
+        assert!(queue.active(None).is_empty());
+    }
+
+    #[test]
+    fn high_priority_runs_are_admitted_first_and_preempt_low_priority_steps() {
+        let queue = Arc::new(RunQueue::new());
+        let batch = queue.acquire("project-a", "batch", RunPriority::Low, 1);
+        // Nothing is waiting, so the batch run keeps its slot
+        assert!(batch.yield_to_higher_priority(1).is_none());
+
+        let spawn = |run_id: &'static str, priority: RunPriority| {
+            let queue = Arc::clone(&queue);
+            std::thread::spawn(move || {
+                let _permit = queue.acquire("project-a", run_id, priority, 1);
+                queue
+                    .active(Some("project-a"))
+                    .iter()
+                    .filter(|entry| entry.state == ExecutionState::Queued)
+                    .map(|entry| entry.run_id.clone())
+                    .collect::<Vec<_>>()
+            })
+        };
+        let normal = spawn("normal", RunPriority::Normal);
+        while queue.active(None).len() < 2 {
+            std::thread::yield_now();
+        }
+        let interactive = spawn("interactive", RunPriority::High);
+        while queue.active(None).len() < 3 {
+            std::thread::yield_now();
+        }
+
+        let preemption = batch
+            .yield_to_higher_priority(2)
+            .expect("batch run preempted");
+        assert_eq!(preemption.before_step, 2);
+        assert_eq!(preemption.preempted_by, "interactive");
+
+        // The high-priority run went first although it arrived last; the
+        // normal run then still outranked the preempted batch run
+        assert_eq!(interactive.join().unwrap(), vec!["batch", "normal"]);
+        assert_eq!(normal.join().unwrap(), vec!["batch"]);
+        drop(batch);
+        assert!(queue.active(None).is_empty());
+    }
+
+    #[test]
+    fn full_project_does_not_block_other_projects() {
+        let queue = RunQueue::new();
+        let _held = queue.acquire("project-a", "run-1", RunPriority::Normal, 1);
+        let _other = queue.acquire("project-b", "run-2", RunPriority::Normal, 1);
+        assert_eq!(queue.active(None).len(), 2);
+    }
+}
@@ -0,0 +1,113 @@
+// src-tauri/src/run_spec.rs
+//!
+//! Run specification: the steps and proof mode a run is defined by
+//!
+//! CARs embed the run's steps, so these types are part of the CAR model and
+//! build without the rest of the app (see the `verify` feature). The
+//! orchestrator re-exports them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunProofMode {
+    Exact,
+    Concordant,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunProofModeParseError {
+    mode: String,
+}
+
+impl RunProofModeParseError {
+    fn new(mode: &str) -> Self {
+        Self {
+            mode: mode.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for RunProofModeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported run proof mode: {}", self.mode)
+    }
+}
+
+impl std::error::Error for RunProofModeParseError {}
+
+impl Default for RunProofMode {
+    fn default() -> Self {
+        RunProofMode::Exact
+    }
+}
+
+impl RunProofMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunProofMode::Exact => "exact",
+            RunProofMode::Concordant => "concordant",
+        }
+    }
+
+    pub fn is_concordant(&self) -> bool {
+        matches!(self, RunProofMode::Concordant)
+    }
+}
+
+impl TryFrom<&str> for RunProofMode {
+    type Error = RunProofModeParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "exact" => Ok(RunProofMode::Exact),
+            "concordant" => Ok(RunProofMode::Concordant),
+            "interactive" => Ok(RunProofMode::Exact),
+            other => Err(RunProofModeParseError::new(other)),
+        }
+    }
+}
+
+pub(crate) fn default_step_type() -> String {
+    "llm".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStep {
+    pub id: String,
+    pub run_id: String,
+    pub order_index: i64,
+    pub checkpoint_type: String,
+    #[serde(default = "default_step_type")]
+    pub step_type: String,
+    // LLM step fields (optional for document ingestion steps)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub token_budget: u64,
+    #[serde(default)]
+    pub proof_mode: RunProofMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+    // Document ingestion config (as JSON string)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_json: Option<String>,
+}
+
+impl RunStep {
+    pub fn is_interactive_chat(&self) -> bool {
+        self.checkpoint_type.eq_ignore_ascii_case("InteractiveChat")
+    }
+
+    pub fn is_llm_step(&self) -> bool {
+        self.step_type == "llm"
+    }
+
+    pub fn is_document_ingestion(&self) -> bool {
+        self.step_type == "ingest" || self.step_type == "document_ingestion"
+    }
+}
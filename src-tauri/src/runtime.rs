@@ -1,10 +1,561 @@
-use std::sync::OnceLock;
+// src-tauri/src/runtime.rs
+//!
+//! Runtime: the worker pool heavy commands run on
+//!
+//! Commands that execute or replay runs, call models, or read and write
+//! exports, imports and archives submit their work as a job instead of
+//! blocking the command thread. `WORKER_COUNT` worker threads take jobs in
+//! submission order, so at most that many run at once and the rest wait as
+//! queued. A job may first have to be admitted, e.g. a run execution by the
+//! run queue; it waits for that on a thread of its own and only takes a
+//! worker once admitted, so waiting runs never hold up other work. A job that
+//! has to wait again while running, e.g. a run preempted by a higher-priority
+//! one, does so through `park`, which hands its worker to the queued jobs.
+//!
+//! Every job gets an id. `get_job_status` reports its state and, once it
+//! succeeded, its result; `list_jobs` the jobs that are queued or running
+//! plus the most recently finished ones.
 
+use crate::Error;
 use anyhow::Result;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use tauri::async_runtime::{channel, Receiver, Sender};
+use uuid::Uuid;
 
-static INITIALIZED: OnceLock<()> = OnceLock::new();
+/// Worker threads of the shared pool. Jobs mostly wait on model providers,
+/// the database or the disk, so this is not tied to the CPU count.
+pub const WORKER_COUNT: usize = 8;
 
+/// Finished jobs kept for status queries
+const FINISHED_JOBS_KEPT: usize = 100;
+
+static WORKERS: Lazy<WorkerPool> = Lazy::new(|| WorkerPool::start(WORKER_COUNT));
+
+thread_local! {
+    /// The pool the current thread works for, if it is a worker
+    static CURRENT_POOL: RefCell<Option<Arc<Shared>>> = const { RefCell::new(None) };
+}
+
+/// The pool shared by every command
+pub fn workers() -> &'static WorkerPool {
+    &WORKERS
+}
+
+/// Start the shared worker pool
 pub fn initialize() -> Result<()> {
-    INITIALIZED.get_or_init(|| ());
+    Lazy::force(&WORKERS);
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub id: String,
+    /// What the job does, e.g. `start_run` or `export_project`
+    pub kind: String,
+    pub state: JobState,
+    pub submitted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    /// Why a failed job failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What a succeeded job returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+}
+
+type Work = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct PoolState {
+    pending: VecDeque<(String, Work)>,
+    /// Queued, running and recently finished jobs in submission order
+    jobs: VecDeque<JobStatus>,
+    /// Worker threads alive, parked ones included
+    threads: usize,
+    /// Workers whose job waits in `park`
+    parked: usize,
+}
+
+impl PoolState {
+    fn job_mut(&mut self, id: &str) -> Option<&mut JobStatus> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    /// Drop the oldest finished jobs beyond `FINISHED_JOBS_KEPT`
+    fn prune_finished(&mut self) {
+        let is_finished =
+            |job: &JobStatus| matches!(job.state, JobState::Succeeded | JobState::Failed);
+        let mut excess = self
+            .jobs
+            .iter()
+            .filter(|job| is_finished(job))
+            .count()
+            .saturating_sub(FINISHED_JOBS_KEPT);
+        self.jobs.retain(|job| {
+            if excess > 0 && is_finished(job) {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+struct Shared {
+    state: Mutex<PoolState>,
+    work_available: Condvar,
+    /// Workers taking jobs, not counting parked ones
+    workers: usize,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start a worker thread; the caller has counted it in `threads`
+    fn spawn_worker(self: &Arc<Self>) {
+        let shared = Arc::clone(self);
+        thread::Builder::new()
+            .name("intelexta-worker".to_string())
+            .spawn(move || {
+                CURRENT_POOL.with(|pool| *pool.borrow_mut() = Some(Arc::clone(&shared)));
+                work_loop(&shared);
+            })
+            .expect("failed to spawn worker thread");
+    }
+
+    fn enqueue(&self, id: String, work: Work) {
+        self.lock().pending.push_back((id, work));
+        self.work_available.notify_one();
+    }
+
+    fn finish(&self, id: &str, outcome: Result<Value, String>) {
+        let mut state = self.lock();
+        if let Some(job) = state.job_mut(id) {
+            job.finished_at = Some(Utc::now().to_rfc3339());
+            match outcome {
+                Ok(result) => {
+                    job.state = JobState::Succeeded;
+                    job.result = Some(result);
+                }
+                Err(error) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(error);
+                }
+            }
+        }
+        state.prune_finished();
+    }
+}
+
+/// Run `work` and record how it finished before handing its result to the
+/// job's handle, so a status query after `join` sees the final state
+fn finishing<T, F>(
+    shared: Arc<Shared>,
+    id: String,
+    sender: Sender<Result<T, Error>>,
+    work: F,
+) -> Work
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    Box::new(move || {
+        // A panicking job fails on its own without taking the worker down
+        let result = panic::catch_unwind(AssertUnwindSafe(work))
+            .unwrap_or_else(|_| Err(Error::Api("job panicked".to_string())));
+        let outcome = match &result {
+            Ok(value) => serde_json::to_value(value).map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        shared.finish(&id, outcome);
+        // The job counts as finished even if nobody waits for it anymore
+        let _ = sender.blocking_send(result);
+    })
+}
+
+pub struct WorkerPool {
+    shared: Arc<Shared>,
+}
+
+/// A submitted job; `join` waits for its result
+pub struct JobHandle<T> {
+    pub id: String,
+    receiver: Receiver<Result<T, Error>>,
+}
+
+impl<T> JobHandle<T> {
+    pub async fn join(mut self) -> Result<T, Error> {
+        self.receiver.recv().await.unwrap_or_else(|| {
+            Err(Error::Api(format!(
+                "job {} stopped without a result",
+                self.id
+            )))
+        })
+    }
+}
+
+impl WorkerPool {
+    /// Spawn a pool of `workers` threads (at least one)
+    pub fn start(workers: usize) -> Self {
+        let workers = workers.max(1);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(PoolState {
+                threads: workers,
+                ..PoolState::default()
+            }),
+            work_available: Condvar::new(),
+            workers,
+        });
+        for _ in 0..workers {
+            shared.spawn_worker();
+        }
+        Self { shared }
+    }
+
+    /// Queue `work` as a job of `kind`
+    pub fn submit<T, F>(&self, kind: &str, work: F) -> JobHandle<T>
+    where
+        T: Serialize + Send + 'static,
+        F: FnOnce() -> Result<T, Error> + Send + 'static,
+    {
+        let (sender, handle) = self.register(kind);
+        let id = handle.id.clone();
+        self.shared.enqueue(
+            id.clone(),
+            finishing(Arc::clone(&self.shared), id, sender, work),
+        );
+        handle
+    }
+
+    /// Queue `work` as a job of `kind` once `admit` lets it run. `admit` may
+    /// block for as long as it needs; the job stays queued meanwhile and only
+    /// takes a worker once admitted. A job that is not admitted fails.
+    pub fn submit_admitted<A, T, G, F>(&self, kind: &str, admit: G, work: F) -> JobHandle<T>
+    where
+        A: Send + 'static,
+        T: Serialize + Send + 'static,
+        G: FnOnce() -> Result<A, Error> + Send + 'static,
+        F: FnOnce(A) -> Result<T, Error> + Send + 'static,
+    {
+        let (sender, handle) = self.register(kind);
+        let shared = Arc::clone(&self.shared);
+        let job_id = handle.id.clone();
+        thread::Builder::new()
+            .name("intelexta-admission".to_string())
+            .spawn(move || {
+                let admitted = panic::catch_unwind(AssertUnwindSafe(admit))
+                    .unwrap_or_else(|_| Err(Error::Api("job panicked".to_string())));
+                match admitted {
+                    Ok(admission) => shared.enqueue(
+                        job_id.clone(),
+                        finishing(Arc::clone(&shared), job_id, sender, move || work(admission)),
+                    ),
+                    Err(err) => finishing(shared, job_id, sender, move || -> Result<T, Error> {
+                        Err(err)
+                    })(),
+                }
+            })
+            .expect("failed to spawn admission thread");
+        handle
+    }
+
+    /// List a new job as queued
+    fn register<T>(&self, kind: &str) -> (Sender<Result<T, Error>>, JobHandle<T>) {
+        let id = Uuid::new_v4().to_string();
+        let (sender, receiver) = channel(1);
+        self.shared.lock().jobs.push_back(JobStatus {
+            id: id.clone(),
+            kind: kind.to_string(),
+            state: JobState::Queued,
+            submitted_at: Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+            result: None,
+        });
+        (sender, JobHandle { id, receiver })
+    }
+
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.shared
+            .lock()
+            .jobs
+            .iter()
+            .find(|job| job.id == id)
+            .cloned()
+    }
+
+    /// Queued and running jobs and the most recently finished ones, oldest first
+    pub fn list(&self) -> Vec<JobStatus> {
+        self.shared.lock().jobs.iter().cloned().collect()
+    }
+}
+
+/// Run `wait` without holding a worker: while it blocks, another worker takes
+/// the queued jobs, so a job waiting on jobs queued behind it cannot wedge the
+/// pool. Once `wait` returns the job carries on, and the pool shrinks back as
+/// workers finish their jobs. Outside the pool `wait` simply runs.
+pub fn park<T>(wait: impl FnOnce() -> T) -> T {
+    let Some(shared) = CURRENT_POOL.with(|pool| pool.borrow().clone()) else {
+        return wait();
+    };
+    let spawn = {
+        let mut state = shared.lock();
+        state.parked += 1;
+        let short = state.threads - state.parked < shared.workers;
+        if short {
+            state.threads += 1;
+        }
+        short
+    };
+    if spawn {
+        shared.spawn_worker();
+    }
+
+    // Unparks even if `wait` panics, which the job's own handler catches
+    struct Unpark<'a>(&'a Shared);
+    impl Drop for Unpark<'_> {
+        fn drop(&mut self) {
+            self.0.lock().parked -= 1;
+            // Idle stand-in workers now beyond the pool size can leave
+            self.0.work_available.notify_all();
+        }
+    }
+    let _unpark = Unpark(&shared);
+    wait()
+}
+
+fn work_loop(shared: &Shared) {
+    loop {
+        let (id, work) = {
+            let mut state = shared.lock();
+            loop {
+                // Workers started for parked jobs leave once those resume
+                if state.threads - state.parked > shared.workers {
+                    state.threads -= 1;
+                    // Pass on a wakeup meant for a worker that stays
+                    shared.work_available.notify_one();
+                    return;
+                }
+                if let Some(next) = state.pending.pop_front() {
+                    break next;
+                }
+                state = shared
+                    .work_available
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+        };
+        if let Some(job) = shared.lock().job_mut(&id) {
+            job.state = JobState::Running;
+            job.started_at = Some(Utc::now().to_rfc3339());
+        }
+        work();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn jobs_report_results_and_failures_by_id() {
+        let pool = WorkerPool::start(2);
+        let succeeded = pool.submit("sum", || Ok(2 + 2));
+        let failed = pool.submit("fail", || -> Result<(), Error> {
+            Err(Error::Api("provider unavailable".into()))
+        });
+        let (succeeded_id, failed_id) = (succeeded.id.clone(), failed.id.clone());
+
+        assert_eq!(tauri::async_runtime::block_on(succeeded.join()).unwrap(), 4);
+        assert!(tauri::async_runtime::block_on(failed.join()).is_err());
+
+        let success = pool.status(&succeeded_id).unwrap();
+        assert_eq!(success.state, JobState::Succeeded);
+        assert_eq!(success.result, Some(serde_json::json!(4)));
+        let failure = pool.status(&failed_id).unwrap();
+        assert_eq!(failure.state, JobState::Failed);
+        assert_eq!(failure.kind, "fail");
+        assert!(failure.error.unwrap().contains("provider unavailable"));
+        assert!(pool.status("unknown").is_none());
+    }
+
+    #[test]
+    fn at_most_worker_count_jobs_run_at_once() {
+        let pool = WorkerPool::start(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                let (running, peak) = (Arc::clone(&running), Arc::clone(&peak));
+                pool.submit("sleep", move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(30));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(())
+                })
+            })
+            .collect();
+        assert!(pool.list().iter().any(|job| job.state == JobState::Queued));
+
+        for handle in handles {
+            tauri::async_runtime::block_on(handle.join()).unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn jobs_wait_for_admission_without_taking_a_worker() {
+        let pool = WorkerPool::start(1);
+        let (admit, admission) = std::sync::mpsc::channel::<&str>();
+        let admitted = pool.submit_admitted(
+            "start_run",
+            move || admission.recv().map_err(|err| Error::Api(err.to_string())),
+            |slot| Ok(format!("ran in {slot}")),
+        );
+        let admitted_id = admitted.id.clone();
+
+        // The only worker is free for other jobs while the run waits
+        let other = pool.submit("export_project", || Ok("exported"));
+        assert_eq!(
+            tauri::async_runtime::block_on(other.join()).unwrap(),
+            "exported"
+        );
+        assert_eq!(pool.status(&admitted_id).unwrap().state, JobState::Queued);
+
+        admit.send("slot 1").unwrap();
+        assert_eq!(
+            tauri::async_runtime::block_on(admitted.join()).unwrap(),
+            "ran in slot 1"
+        );
+
+        let rejected = pool.submit_admitted(
+            "start_run",
+            || -> Result<(), Error> { Err(Error::Api("run has no steps".into())) },
+            |()| Ok(()),
+        );
+        let rejected_id = rejected.id.clone();
+        assert!(tauri::async_runtime::block_on(rejected.join()).is_err());
+        let rejection = pool.status(&rejected_id).unwrap();
+        assert_eq!(rejection.state, JobState::Failed);
+        assert!(rejection.started_at.is_none());
+    }
+
+    #[test]
+    fn preempted_runs_hand_their_workers_to_the_runs_they_yield_to() {
+        use crate::run_queue::{RunPriority, RunQueue};
+        use std::sync::mpsc;
+
+        let queue: &'static RunQueue = Box::leak(Box::new(RunQueue::new()));
+        // More runs are preempted than the pool has workers
+        let pool = WorkerPool::start(1);
+        let projects = ["project-a", "project-b", "project-c"];
+        let (done, finished) = mpsc::channel();
+
+        let batches: Vec<_> = projects
+            .iter()
+            .map(|&project| {
+                let done = done.clone();
+                pool.submit_admitted(
+                    "start_run",
+                    move || Ok(queue.acquire(project, "batch", RunPriority::Low, 1)),
+                    move |permit| {
+                        // Yield between steps until an interactive run outranks this one
+                        while park(|| permit.yield_to_higher_priority(1)).is_none() {
+                            thread::sleep(Duration::from_millis(5));
+                        }
+                        done.send(format!("{project} batch")).unwrap();
+                        Ok(())
+                    },
+                )
+            })
+            .collect();
+        while queue.active(None).len() < projects.len() {
+            thread::yield_now();
+        }
+        let interactive: Vec<_> = projects
+            .iter()
+            .map(|&project| {
+                let done = done.clone();
+                pool.submit_admitted(
+                    "start_run",
+                    move || Ok(queue.acquire(project, "interactive", RunPriority::High, 1)),
+                    move |_permit| {
+                        done.send(format!("{project} interactive")).unwrap();
+                        Ok(())
+                    },
+                )
+            })
+            .collect();
+
+        let order: Vec<String> = (0..projects.len() * 2)
+            .map(|_| {
+                finished
+                    .recv_timeout(Duration::from_secs(10))
+                    .expect("pool wedged")
+            })
+            .collect();
+        for handle in batches.into_iter().chain(interactive) {
+            tauri::async_runtime::block_on(handle.join()).unwrap();
+        }
+        // Each interactive run finished before the batch run it preempted
+        for project in projects {
+            let position = |run: &str| {
+                let name = format!("{project} {run}");
+                order.iter().position(|entry| *entry == name).unwrap()
+            };
+            assert!(position("interactive") < position("batch"));
+        }
+        assert!(queue.active(None).is_empty());
+
+        // Stand-in workers leave once the parked runs resume
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while pool.shared.lock().threads > 1 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "stand-in workers stayed"
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(pool.shared.lock().parked, 0);
+    }
+
+    #[test]
+    fn panicking_jobs_fail_without_stopping_the_worker() {
+        let pool = WorkerPool::start(1);
+        let panicked = pool.submit("panic", || -> Result<(), Error> { panic!("boom") });
+        assert!(tauri::async_runtime::block_on(panicked.join()).is_err());
+        let next = pool.submit("after", || Ok("still running"));
+        assert_eq!(
+            tauri::async_runtime::block_on(next.join()).unwrap(),
+            "still running"
+        );
+    }
+}
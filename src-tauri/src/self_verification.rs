@@ -0,0 +1,638 @@
+// src-tauri/src/self_verification.rs
+//!
+//! Self-verification: nightly re-checks of stored receipts and checkpoint chains
+//!
+//! Receipts and checkpoints are verified when they are produced and trusted
+//! from then on. A scheduled pass re-verifies everything created in the last
+//! `lookback_days`, so bit rot or tampering on disk or in the database shows
+//! up long before an external audit:
+//!
+//! - each CAR file recorded in `receipts`: body and receipt signatures,
+//!   checkpoint signatures and chain hashes, provenance claims and the
+//!   content hashes of bundled attachments
+//! - each run execution's checkpoints in the database: chain hashes, links
+//!   and signatures under the project's current or an earlier signing key
+//!
+//! Every result goes to `verification_history`. A target that fails after
+//! passing, or on its first check, is a new failure and stays an alert until
+//! acknowledged; targets that keep failing do not raise another one.
+
+use crate::model_adapters::ResponseMetadata;
+use crate::store::self_verification::{
+    self as history, SelfVerificationSettings, VerificationRecord, STATUS_FAILED, STATUS_PASSED,
+    TARGET_CAR_FILE, TARGET_CHECKPOINT_CHAIN,
+};
+use crate::store::signing_key_events;
+use crate::verification_html::HtmlReport;
+use crate::verify::{self, CheckpointBody};
+use crate::{car, car_cbor, runtime, DbPool, Error};
+use anyhow::{anyhow, bail, Context};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use intelexta_archive::{ArchiveLimits, SafeArchive};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::Path;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the scheduler checks whether the nightly pass is due
+pub const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfVerificationReport {
+    pub pass_id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub car_files_checked: usize,
+    pub chains_checked: usize,
+    pub failed: usize,
+    /// Failures this pass raised as alerts
+    pub new_failures: Vec<VerificationRecord>,
+}
+
+struct Target {
+    kind: &'static str,
+    id: String,
+    project_id: Option<String>,
+    run_id: Option<String>,
+    /// CAR file of a receipt
+    file_path: Option<String>,
+}
+
+/// Whether the nightly pass should start at `now`: enabled, past the
+/// configured hour and not run since that hour today
+pub fn is_due(settings: &SelfVerificationSettings, now: DateTime<Utc>) -> bool {
+    if !settings.enabled {
+        return false;
+    }
+    let Some(scheduled) = now
+        .date_naive()
+        .and_hms_opt(settings.hour_utc, 0, 0)
+        .map(|time| time.and_utc())
+    else {
+        return false;
+    };
+    if now < scheduled {
+        return false;
+    }
+    settings
+        .last_run_at
+        .as_deref()
+        .and_then(|last| DateTime::parse_from_rfc3339(last).ok())
+        .is_none_or(|last| last.with_timezone(&Utc) < scheduled)
+}
+
+/// Re-verify the receipts and checkpoint chains created in the last
+/// `lookback_days` and record the results
+pub fn run_pass(pool: &DbPool, lookback_days: u32) -> Result<SelfVerificationReport, Error> {
+    let conn = pool.get()?;
+    let started_at = Utc::now();
+    let since = (started_at - chrono::Duration::days(i64::from(lookback_days))).to_rfc3339();
+    history::mark_run(&conn, &started_at.to_rfc3339())?;
+
+    let mut report = SelfVerificationReport {
+        pass_id: Uuid::new_v4().to_string(),
+        started_at: started_at.to_rfc3339(),
+        finished_at: String::new(),
+        car_files_checked: 0,
+        chains_checked: 0,
+        failed: 0,
+        new_failures: Vec::new(),
+    };
+
+    for target in recent_receipts(&conn, &since)? {
+        let outcome = match target.file_path.as_deref() {
+            Some(path) => verify_car_file(Path::new(path)).map(|_| ()),
+            None => Err(anyhow!("receipt has no CAR file")),
+        };
+        record(&conn, &target, outcome, &mut report)?;
+        report.car_files_checked += 1;
+    }
+    for target in recent_executions(&conn, &since)? {
+        let outcome =
+            verify_checkpoint_chain(&conn, &target.id, target.project_id.as_deref()).map(|_| ());
+        record(&conn, &target, outcome, &mut report)?;
+        report.chains_checked += 1;
+    }
+
+    report.finished_at = Utc::now().to_rfc3339();
+    Ok(report)
+}
+
+/// Run the nightly pass whenever it is due, for the lifetime of the app
+pub fn spawn_nightly_scheduler(pool: DbPool, check_interval: Duration) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        if let Err(err) = run_if_due(&pool) {
+            eprintln!("⚠️  Warning: nightly self-verification failed: {}", err);
+        }
+        std::thread::sleep(check_interval);
+    })
+}
+
+fn run_if_due(pool: &DbPool) -> Result<(), Error> {
+    let settings = history::get_settings(&*pool.get()?)?;
+    if !is_due(&settings, Utc::now()) {
+        return Ok(());
+    }
+    let job_pool = pool.clone();
+    let job = runtime::workers().submit("self_verification", move || {
+        run_pass(&job_pool, settings.lookback_days)
+    });
+    let report = tauri::async_runtime::block_on(job.join())?;
+    if !report.new_failures.is_empty() {
+        eprintln!(
+            "⚠️  Warning: self-verification found {} new failure(s)",
+            report.new_failures.len()
+        );
+    }
+    Ok(())
+}
+
+fn record(
+    conn: &Connection,
+    target: &Target,
+    outcome: anyhow::Result<()>,
+    report: &mut SelfVerificationReport,
+) -> Result<(), Error> {
+    let details = outcome.err().map(|err| format!("{err:#}"));
+    let failed = details.is_some();
+    let new_failure = failed
+        && history::last_status(conn, target.kind, &target.id)?.as_deref() != Some(STATUS_FAILED);
+    let record = VerificationRecord {
+        id: Uuid::new_v4().to_string(),
+        pass_id: report.pass_id.clone(),
+        target_kind: target.kind.to_string(),
+        target_id: target.id.clone(),
+        project_id: target.project_id.clone(),
+        run_id: target.run_id.clone(),
+        status: if failed { STATUS_FAILED } else { STATUS_PASSED }.to_string(),
+        details,
+        new_failure,
+        acknowledged_at: None,
+        verified_at: Utc::now().to_rfc3339(),
+    };
+    history::insert(conn, &record)?;
+    if failed {
+        report.failed += 1;
+    }
+    if new_failure {
+        report.new_failures.push(record);
+    }
+    Ok(())
+}
+
+fn recent_receipts(conn: &Connection, since: &str) -> Result<Vec<Target>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.run_id, runs.project_id, r.file_path
+         FROM receipts r LEFT JOIN runs ON runs.id = r.run_id
+         WHERE datetime(r.created_at) >= datetime(?1)
+         ORDER BY datetime(r.created_at) ASC",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok(Target {
+            kind: TARGET_CAR_FILE,
+            id: row.get(0)?,
+            run_id: row.get(1)?,
+            project_id: row.get(2)?,
+            file_path: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn recent_executions(conn: &Connection, since: &str) -> Result<Vec<Target>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.run_id, runs.project_id
+         FROM run_executions e JOIN runs ON runs.id = e.run_id
+         WHERE datetime(e.created_at) >= datetime(?1)
+         ORDER BY datetime(e.created_at) ASC",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok(Target {
+            kind: TARGET_CHECKPOINT_CHAIN,
+            id: row.get(0)?,
+            run_id: row.get(1)?,
+            project_id: row.get(2)?,
+            file_path: None,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Check a `.car.json` or `.car.zip` file the way an external verifier would
+pub fn verify_car_file(path: &Path) -> anyhow::Result<verify::CarVerification> {
+    read_car_file(path)?.verify()
+}
+
+/// Verify a CAR file and write the outcome to `output_path` as a
+/// self-contained HTML report; returns whether the CAR verified. Only a CAR
+/// that cannot be read at all is an error.
+pub fn export_verification_report(car_path: &Path, output_path: &Path) -> anyhow::Result<bool> {
+    let file = read_car_file(car_path)?;
+    let car: car::Car = serde_json::from_slice(&file.car_json).context("car.json is not a CAR")?;
+    let outcome = file.verify().map_err(|err| format!("{err:#}"));
+    let generator = format!("Intelexta {}", env!("CARGO_PKG_VERSION"));
+    let report = HtmlReport::from_verification(&car, &outcome, generator);
+    std::fs::write(output_path, report.render())
+        .with_context(|| format!("failed to write {}", output_path.display()))?;
+    Ok(report.verified)
+}
+
+/// The `car.json` of a CAR file and, for a bundle, its attachments
+struct CarFile {
+    car_json: Vec<u8>,
+    attachments: Vec<(String, Vec<u8>)>,
+    is_bundle: bool,
+}
+
+impl CarFile {
+    fn verify(&self) -> anyhow::Result<verify::CarVerification> {
+        // A bare CAR comes without its attachments; a bundle must carry them all
+        if self.is_bundle {
+            verify::verify_car_bundle(&self.car_json, &self.attachments)
+        } else {
+            verify::verify_car(&self.car_json, &self.attachments)
+        }
+    }
+}
+
+fn read_car_file(path: &Path) -> anyhow::Result<CarFile> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut attachments = Vec::new();
+    let is_bundle = bytes.starts_with(b"PK");
+    let car_json = if is_bundle {
+        let limits = ArchiveLimits::default();
+        let max_entry_bytes = limits.max_entry_bytes;
+        let mut archive = SafeArchive::new(Cursor::new(bytes.as_slice()), limits)
+            .context("failed to open CAR archive")?;
+        let car_json = if archive.names().iter().any(|name| name == car_cbor::BUNDLE_ENTRY) {
+            car_cbor::decode_to_json(&archive.read_by_name(car_cbor::BUNDLE_ENTRY)?)?
+        } else {
+            archive
+                .read_by_name("car.json")
+                .context("archive has no car.json")?
+        };
+        // Attachments are hashed decompressed, in the encoding the signed
+        // manifest records
+        let manifest = serde_json::from_slice::<car::Car>(&car_json)
+            .ok()
+            .and_then(|car| car.attachments);
+        for index in 0..archive.len() {
+            let name = archive.names()[index].clone();
+            let Some((hash, compression)) = car::parse_attachment_entry(&name) else {
+                continue;
+            };
+            if let Some(manifest) = &manifest {
+                manifest.check_encoding(hash, compression).map_err(anyhow::Error::msg)?;
+            }
+            let content = archive.read_index(index)?.unwrap_or_default();
+            let content = match compression {
+                Some(compression) => compression
+                    .decompress(&content, max_entry_bytes)
+                    .map_err(anyhow::Error::msg)?,
+                None => content,
+            };
+            attachments.push((hash.to_string(), content));
+        }
+        car_json
+    } else {
+        bytes
+    };
+    Ok(CarFile {
+        car_json,
+        attachments,
+        is_bundle,
+    })
+}
+
+/// A checkpoint row with the columns its chain hash covers
+struct StoredCheckpoint {
+    id: String,
+    run_id: String,
+    kind: String,
+    timestamp: String,
+    inputs_sha256: Option<String>,
+    outputs_sha256: Option<String>,
+    incident_json: Option<String>,
+    usage_tokens: i64,
+    prompt_tokens: i64,
+    completion_tokens: i64,
+    cache_hit: bool,
+    response_metadata_json: Option<String>,
+    run_inputs_sha256: Option<String>,
+    output_schema_sha256: Option<String>,
+    prev_chain: String,
+    curr_chain: String,
+    signature: String,
+}
+
+/// Keys the project signed with: its current key and any it replaced
+fn project_keys(conn: &Connection, project_id: &str) -> anyhow::Result<Vec<VerifyingKey>> {
+    let current: String = conn
+        .query_row(
+            "SELECT pubkey FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .with_context(|| format!("failed to load project {project_id}"))?;
+    let mut encoded = vec![current];
+    for event in signing_key_events::list(conn, project_id)? {
+        encoded.extend(event.previous_pubkey);
+        encoded.push(event.pubkey);
+    }
+    encoded.sort();
+    encoded.dedup();
+    encoded
+        .iter()
+        .map(|key| verify::decode_verifying_key(key).map_err(anyhow::Error::from))
+        .collect()
+}
+
+/// Re-hash and check the signatures of an execution's stored checkpoints;
+/// returns how many were checked
+pub fn verify_checkpoint_chain(
+    conn: &Connection,
+    run_execution_id: &str,
+    project_id: Option<&str>,
+) -> anyhow::Result<usize> {
+    let project_id = project_id.ok_or_else(|| anyhow!("execution has no project"))?;
+    let keys = project_keys(conn, project_id)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, run_id, kind, timestamp, inputs_sha256, outputs_sha256, incident_json, usage_tokens, prompt_tokens, completion_tokens, cache_hit, response_metadata_json, run_inputs_sha256, prev_chain, curr_chain, signature, output_schema_sha256
+         FROM checkpoints WHERE run_execution_id = ?1",
+    )?;
+    let rows = stmt
+        .query_map(params![run_execution_id], |row| {
+            Ok(StoredCheckpoint {
+                id: row.get(0)?,
+                run_id: row.get(1)?,
+                kind: row.get(2)?,
+                timestamp: row.get(3)?,
+                inputs_sha256: row.get(4)?,
+                outputs_sha256: row.get(5)?,
+                incident_json: row.get(6)?,
+                usage_tokens: row.get(7)?,
+                prompt_tokens: row.get(8)?,
+                completion_tokens: row.get(9)?,
+                cache_hit: row.get(10)?,
+                response_metadata_json: row.get(11)?,
+                run_inputs_sha256: row.get(12)?,
+                prev_chain: row.get(13)?,
+                curr_chain: row.get(14)?,
+                signature: row.get(15)?,
+                output_schema_sha256: row.get(16)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let links: HashSet<&str> = rows.iter().map(|row| row.curr_chain.as_str()).collect();
+    for checkpoint in &rows {
+        let id = &checkpoint.id;
+        let incident: Option<Value> = checkpoint
+            .incident_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .with_context(|| format!("checkpoint {id} has an unreadable incident"))?;
+        let response_metadata: Option<ResponseMetadata> = checkpoint
+            .response_metadata_json
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .with_context(|| format!("checkpoint {id} has unreadable response metadata"))?;
+        let body = CheckpointBody {
+            run_id: &checkpoint.run_id,
+            kind: &checkpoint.kind,
+            timestamp: checkpoint.timestamp.clone(),
+            inputs_sha256: checkpoint.inputs_sha256.as_deref(),
+            outputs_sha256: checkpoint.outputs_sha256.as_deref(),
+            incident: incident.as_ref(),
+            usage_tokens: checkpoint.usage_tokens.max(0) as u64,
+            prompt_tokens: checkpoint.prompt_tokens.max(0) as u64,
+            completion_tokens: checkpoint.completion_tokens.max(0) as u64,
+            cache_hit: checkpoint.cache_hit,
+            response_metadata: response_metadata.as_ref(),
+            run_inputs_sha256: checkpoint.run_inputs_sha256.as_deref(),
+            output_schema_sha256: checkpoint.output_schema_sha256.as_deref(),
+        };
+        if body.chain_hash(&checkpoint.prev_chain)? != checkpoint.curr_chain {
+            bail!("checkpoint {id} does not match its chain hash");
+        }
+        if !checkpoint.prev_chain.is_empty() && !links.contains(checkpoint.prev_chain.as_str()) {
+            bail!("checkpoint {id} links to a checkpoint missing from the execution");
+        }
+        if !keys.iter().any(|key| {
+            verify::signature_valid(key, checkpoint.curr_chain.as_bytes(), &checkpoint.signature)
+        }) {
+            bail!("checkpoint {id} signature does not verify under the project's keys");
+        }
+    }
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{provenance, store};
+    use ed25519_dalek::SigningKey;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    fn settings(hour_utc: u32, last_run_at: Option<&str>) -> SelfVerificationSettings {
+        SelfVerificationSettings {
+            enabled: true,
+            hour_utc,
+            lookback_days: 30,
+            last_run_at: last_run_at.map(str::to_string),
+        }
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn nightly_pass_is_due_once_per_day_after_its_hour() {
+        let now = at("2025-03-10T04:30:00Z");
+        assert!(is_due(&settings(3, None), now));
+        assert!(is_due(&settings(3, Some("2025-03-09T03:00:05Z")), now));
+        assert!(!is_due(&settings(3, Some("2025-03-10T03:00:05Z")), now));
+        assert!(!is_due(&settings(5, None), now));
+        let disabled = SelfVerificationSettings {
+            enabled: false,
+            ..settings(3, None)
+        };
+        assert!(!is_due(&disabled, now));
+    }
+
+    fn pool_with_execution(signing_key: &SigningKey) -> DbPool {
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::memory())
+            .unwrap();
+        let mut conn = pool.get().unwrap();
+        store::migrate_db(&mut conn).unwrap();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO projects (id, name, created_at, pubkey) VALUES ('p1', 'Audit', ?1, ?2)",
+            params![&now, provenance::public_key_from_secret(signing_key)],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO runs (id, project_id, name, created_at, sampler_json, seed, epsilon, token_budget, default_model, proof_mode)
+             VALUES ('r1', 'p1', 'nightly', ?1, NULL, 1, NULL, 100, 'stub-model', 'exact')",
+            params![&now],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO run_executions (id, run_id, created_at) VALUES ('e1', 'r1', ?1)",
+            params![&now],
+        )
+        .unwrap();
+
+        let mut prev_chain = String::new();
+        for (id, outputs) in [("c1", "aa"), ("c2", "bb")] {
+            let body = CheckpointBody {
+                run_id: "r1",
+                kind: "Step",
+                timestamp: now.clone(),
+                inputs_sha256: None,
+                outputs_sha256: Some(outputs),
+                incident: None,
+                usage_tokens: 5,
+                prompt_tokens: 3,
+                completion_tokens: 2,
+                cache_hit: false,
+                response_metadata: None,
+                run_inputs_sha256: None,
+                output_schema_sha256: None,
+            };
+            let curr_chain = body.chain_hash(&prev_chain).unwrap();
+            conn.execute(
+                "INSERT INTO checkpoints (id, run_id, run_execution_id, kind, timestamp, outputs_sha256, prev_chain, curr_chain, signature, usage_tokens, prompt_tokens, completion_tokens)
+                 VALUES (?1, 'r1', 'e1', 'Step', ?2, ?3, ?4, ?5, ?6, 5, 3, 2)",
+                params![
+                    id,
+                    &now,
+                    outputs,
+                    &prev_chain,
+                    &curr_chain,
+                    provenance::sign_bytes(signing_key, curr_chain.as_bytes())
+                ],
+            )
+            .unwrap();
+            prev_chain = curr_chain;
+        }
+        drop(conn);
+        pool
+    }
+
+    #[test]
+    fn tampered_checkpoints_raise_one_alert_until_they_pass_again() {
+        let pool = pool_with_execution(&SigningKey::from_bytes(&[4u8; 32]));
+        let clean = run_pass(&pool, 7).unwrap();
+        assert_eq!(clean.chains_checked, 1);
+        assert_eq!(clean.failed, 0);
+
+        pool.get()
+            .unwrap()
+            .execute(
+                "UPDATE checkpoints SET usage_tokens = 500 WHERE id = 'c2'",
+                [],
+            )
+            .unwrap();
+        let tampered = run_pass(&pool, 7).unwrap();
+        assert_eq!(tampered.failed, 1);
+        assert_eq!(tampered.new_failures.len(), 1);
+        let alert = &tampered.new_failures[0];
+        assert_eq!(alert.target_kind, TARGET_CHECKPOINT_CHAIN);
+        assert!(alert.details.as_deref().unwrap().contains("c2"));
+
+        // Still failing: recorded, but not raised again
+        let repeated = run_pass(&pool, 7).unwrap();
+        assert_eq!(repeated.failed, 1);
+        assert!(repeated.new_failures.is_empty());
+
+        let conn = pool.get().unwrap();
+        assert_eq!(history::list(&conn, 10).unwrap().len(), 3);
+        assert_eq!(history::list_alerts(&conn, true).unwrap().len(), 1);
+        history::acknowledge(&conn, &alert.id, &Utc::now().to_rfc3339()).unwrap();
+        assert!(history::list_alerts(&conn, true).unwrap().is_empty());
+        assert!(history::get_settings(&conn).unwrap().last_run_at.is_some());
+    }
+
+    #[test]
+    fn edited_car_files_fail_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipt.car.json");
+        let mut car = verify::tests::signed_car_json(&SigningKey::from_bytes(&[4u8; 32]));
+        std::fs::write(&path, serde_json::to_vec_pretty(&car).unwrap()).unwrap();
+        verify_car_file(&path).unwrap();
+
+        car["budgets"]["tokens"] = serde_json::json!(1);
+        std::fs::write(&path, serde_json::to_vec(&car).unwrap()).unwrap();
+        assert!(verify_car_file(&path)
+            .unwrap_err()
+            .to_string()
+            .contains("ed25519-body"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(verify_car_file(&path).is_err());
+    }
+
+    #[test]
+    fn verification_reports_are_exported_for_failing_cars_too() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipt.car.json");
+        let output = dir.path().join("report.html");
+        let mut car = verify::tests::signed_car_json(&SigningKey::from_bytes(&[4u8; 32]));
+        std::fs::write(&path, serde_json::to_vec(&car).unwrap()).unwrap();
+        assert!(export_verification_report(&path, &output).unwrap());
+        assert!(std::fs::read_to_string(&output).unwrap().contains("VERIFIED"));
+
+        car["budgets"]["tokens"] = serde_json::json!(1);
+        std::fs::write(&path, serde_json::to_vec(&car).unwrap()).unwrap();
+        assert!(!export_verification_report(&path, &output).unwrap());
+        let html = std::fs::read_to_string(&output).unwrap();
+        assert!(html.contains("FAILED") && html.contains("ed25519-body"));
+    }
+
+    #[test]
+    fn checkpoints_signed_with_a_replaced_key_still_verify() {
+        let old_key = SigningKey::from_bytes(&[4u8; 32]);
+        let pool = pool_with_execution(&old_key);
+        let conn = pool.get().unwrap();
+        let new_pubkey = provenance::public_key_from_secret(&SigningKey::from_bytes(&[5u8; 32]));
+        conn.execute(
+            "UPDATE projects SET pubkey = ?1 WHERE id = 'p1'",
+            params![&new_pubkey],
+        )
+        .unwrap();
+        assert!(verify_checkpoint_chain(&conn, "e1", Some("p1")).is_err());
+
+        signing_key_events::record(
+            &conn,
+            &signing_key_events::SigningKeyEvent {
+                id: "k1".to_string(),
+                project_id: "p1".to_string(),
+                event: "regenerated".to_string(),
+                previous_pubkey: Some(provenance::public_key_from_secret(&old_key)),
+                pubkey: new_pubkey,
+                reason: "secret missing".to_string(),
+                run_id: None,
+                created_at: Utc::now().to_rfc3339(),
+            },
+        )
+        .unwrap();
+        assert_eq!(verify_checkpoint_chain(&conn, "e1", Some("p1")).unwrap(), 2);
+    }
+}
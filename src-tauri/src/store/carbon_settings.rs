@@ -0,0 +1,21 @@
+// src-tauri/src/store/carbon_settings.rs
+use crate::Error;
+use rusqlite::{params, Connection};
+
+/// The user's grid region, which on-device inference is priced against;
+/// `None` uses the catalog's default region
+pub fn get_grid_region(conn: &Connection) -> Result<Option<String>, Error> {
+    Ok(conn.query_row(
+        "SELECT grid_region FROM carbon_settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn set_grid_region(conn: &Connection, grid_region: Option<&str>) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE carbon_settings SET grid_region = ?1 WHERE id = 1",
+        params![grid_region],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+// src-tauri/src/store/dataset_splits.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitKind {
+    Train,
+    Validation,
+    Test,
+}
+
+impl SplitKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SplitKind::Train => "train",
+            SplitKind::Validation => "validation",
+            SplitKind::Test => "test",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "train" => Some(SplitKind::Train),
+            "validation" | "val" => Some(SplitKind::Validation),
+            "test" => Some(SplitKind::Test),
+            _ => None,
+        }
+    }
+
+    /// Documents in validation and test splits must not reach a model
+    pub fn is_held_out(self) -> bool {
+        !matches!(self, SplitKind::Train)
+    }
+}
+
+/// Documents assigned to one split of a dataset, as sha256 of the file contents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSplit {
+    pub project_id: String,
+    pub dataset: String,
+    pub split: SplitKind,
+    pub document_hashes: Vec<String>,
+}
+
+/// Split a held-out document was assigned to
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldOutAssignment {
+    pub dataset: String,
+    pub split: SplitKind,
+}
+
+/// Replace the documents of one split. Fails if a document is already
+/// assigned to another split of the same dataset.
+pub fn set_split(
+    conn: &mut Connection,
+    project_id: &str,
+    dataset: &str,
+    split: SplitKind,
+    document_hashes: &[String],
+) -> Result<DatasetSplit, Error> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM dataset_splits WHERE project_id = ?1 AND dataset = ?2 AND split = ?3",
+        params![project_id, dataset, split.as_str()],
+    )?;
+
+    let mut hashes: Vec<String> = document_hashes
+        .iter()
+        .map(|hash| hash.trim().to_ascii_lowercase())
+        .collect();
+    hashes.sort();
+    hashes.dedup();
+    for hash in &hashes {
+        let existing: Option<String> = tx
+            .query_row(
+                "SELECT split FROM dataset_splits WHERE project_id = ?1 AND dataset = ?2 AND document_sha256 = ?3",
+                params![project_id, dataset, hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(existing) = existing {
+            return Err(Error::Api(format!(
+                "document {hash} is already in the {existing} split of dataset {dataset}"
+            )));
+        }
+        tx.execute(
+            "INSERT INTO dataset_splits (project_id, dataset, split, document_sha256) VALUES (?1, ?2, ?3, ?4)",
+            params![project_id, dataset, split.as_str(), hash],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(DatasetSplit {
+        project_id: project_id.to_string(),
+        dataset: dataset.to_string(),
+        split,
+        document_hashes: hashes,
+    })
+}
+
+pub fn list_for_project(conn: &Connection, project_id: &str) -> Result<Vec<DatasetSplit>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT dataset, split, document_sha256 FROM dataset_splits WHERE project_id = ?1
+         ORDER BY dataset ASC, split ASC, document_sha256 ASC",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut splits: BTreeMap<(String, SplitKind), Vec<String>> = BTreeMap::new();
+    for row in rows {
+        let (dataset, split, hash) = row?;
+        let Some(split) = SplitKind::parse(&split) else {
+            continue;
+        };
+        splits.entry((dataset, split)).or_default().push(hash);
+    }
+    Ok(splits
+        .into_iter()
+        .map(|((dataset, split), document_hashes)| DatasetSplit {
+            project_id: project_id.to_string(),
+            dataset,
+            split,
+            document_hashes,
+        })
+        .collect())
+}
+
+/// Held-out documents of the project keyed by their hash
+pub fn held_out_documents(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<HashMap<String, HeldOutAssignment>, Error> {
+    let mut documents = HashMap::new();
+    for split in list_for_project(conn, project_id)? {
+        if !split.split.is_held_out() {
+            continue;
+        }
+        for hash in split.document_hashes {
+            documents.insert(
+                hash,
+                HeldOutAssignment {
+                    dataset: split.dataset.clone(),
+                    split: split.split,
+                },
+            );
+        }
+    }
+    Ok(documents)
+}
+
+pub fn delete_dataset(conn: &Connection, project_id: &str, dataset: &str) -> Result<(), Error> {
+    let affected = conn.execute(
+        "DELETE FROM dataset_splits WHERE project_id = ?1 AND dataset = ?2",
+        params![project_id, dataset],
+    )?;
+    if affected == 0 {
+        return Err(Error::Api(format!("dataset {dataset} not found")));
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// src-tauri/src/store/embeddings.rs
+use crate::embeddings::{self, EmbeddedChunk};
+use crate::Error;
+use rusqlite::{params, Connection};
+
+/// Stores the chunks an embed step checkpoint produced
+pub fn insert_for_checkpoint(
+    conn: &Connection,
+    checkpoint_id: &str,
+    run_execution_id: &str,
+    checkpoint_config_id: &str,
+    model: &str,
+    chunks: &[EmbeddedChunk],
+) -> Result<(), Error> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO embeddings (checkpoint_id, run_execution_id, checkpoint_config_id, chunk_index, model, chunk_text, text_sha256, vector, vector_sha256, dimensions)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )?;
+    for chunk in chunks {
+        stmt.execute(params![
+            checkpoint_id,
+            run_execution_id,
+            checkpoint_config_id,
+            chunk.index as i64,
+            model,
+            &chunk.text,
+            &chunk.text_sha256,
+            embeddings::vector_bytes(&chunk.vector),
+            &chunk.vector_sha256,
+            chunk.vector.len() as i64,
+        ])?;
+    }
+    Ok(())
+}
+
+/// Chunks embedded by a step in one execution, in chunk order
+pub fn list_for_step(
+    conn: &Connection,
+    run_execution_id: &str,
+    checkpoint_config_id: &str,
+) -> Result<Vec<EmbeddedChunk>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT chunk_index, chunk_text, text_sha256, vector, vector_sha256 FROM embeddings
+         WHERE run_execution_id = ?1 AND checkpoint_config_id = ?2 ORDER BY chunk_index ASC",
+    )?;
+    let rows = stmt.query_map(params![run_execution_id, checkpoint_config_id], |row| {
+        let index: i64 = row.get(0)?;
+        let bytes: Vec<u8> = row.get(3)?;
+        Ok((index, row.get(1)?, row.get(2)?, bytes, row.get(4)?))
+    })?;
+
+    let mut chunks = Vec::new();
+    for row in rows {
+        let (index, text, text_sha256, bytes, vector_sha256) = row?;
+        let vector = embeddings::vector_from_bytes(&bytes).ok_or_else(|| {
+            Error::Api(format!(
+                "stored vector for chunk {index} is not a list of f32"
+            ))
+        })?;
+        chunks.push(EmbeddedChunk {
+            index: index.max(0) as usize,
+            text,
+            text_sha256,
+            vector,
+            vector_sha256,
+        });
+    }
+    Ok(chunks)
+}
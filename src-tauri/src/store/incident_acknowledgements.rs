@@ -0,0 +1,80 @@
+// src-tauri/src/store/incident_acknowledgements.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// Who acknowledged an incident checkpoint, when and why, signed with the
+/// project key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentAcknowledgement {
+    pub checkpoint_id: String,
+    pub project_id: String,
+    /// Chain hash of the incident checkpoint the acknowledgement commits to
+    pub checkpoint_curr_chain: String,
+    pub acknowledged_by: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub acknowledged_at: String,
+    pub signer_public_key: String,
+    /// Base64 ed25519 signature over the canonical acknowledgement without
+    /// this field
+    pub signature: String,
+}
+
+const COLUMNS: &str = "checkpoint_id, project_id, checkpoint_curr_chain, acknowledged_by, note, acknowledged_at, signer_public_key, signature";
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<IncidentAcknowledgement> {
+    Ok(IncidentAcknowledgement {
+        checkpoint_id: row.get(0)?,
+        project_id: row.get(1)?,
+        checkpoint_curr_chain: row.get(2)?,
+        acknowledged_by: row.get(3)?,
+        note: row.get(4)?,
+        acknowledged_at: row.get(5)?,
+        signer_public_key: row.get(6)?,
+        signature: row.get(7)?,
+    })
+}
+
+pub fn insert(conn: &Connection, acknowledgement: &IncidentAcknowledgement) -> Result<(), Error> {
+    conn.execute(
+        &format!(
+            "INSERT INTO incident_acknowledgements ({COLUMNS})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        ),
+        params![
+            &acknowledgement.checkpoint_id,
+            &acknowledgement.project_id,
+            &acknowledgement.checkpoint_curr_chain,
+            &acknowledgement.acknowledged_by,
+            &acknowledgement.note,
+            &acknowledgement.acknowledged_at,
+            &acknowledgement.signer_public_key,
+            &acknowledgement.signature,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get(
+    conn: &Connection,
+    checkpoint_id: &str,
+) -> Result<Option<IncidentAcknowledgement>, Error> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {COLUMNS} FROM incident_acknowledgements WHERE checkpoint_id = ?1"),
+            params![checkpoint_id],
+            from_row,
+        )
+        .optional()?)
+}
+
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<IncidentAcknowledgement>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM incident_acknowledgements
+         WHERE project_id = ?1 ORDER BY acknowledged_at ASC"
+    ))?;
+    let rows = stmt.query_map(params![project_id], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,114 @@
+// src-tauri/src/store/incident_responses.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// Notification or approval request raised for an incident checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentResponse {
+    pub id: String,
+    pub project_id: String,
+    pub run_id: String,
+    pub run_execution_id: String,
+    pub checkpoint_id: String,
+    pub incident_kind: String,
+    pub action: String,
+    pub status: String,
+    pub details: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<String>,
+}
+
+const COLUMNS: &str = "id, project_id, run_id, run_execution_id, checkpoint_id, incident_kind, action, status, details, created_at, resolved_at";
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<IncidentResponse> {
+    Ok(IncidentResponse {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        run_id: row.get(2)?,
+        run_execution_id: row.get(3)?,
+        checkpoint_id: row.get(4)?,
+        incident_kind: row.get(5)?,
+        action: row.get(6)?,
+        status: row.get(7)?,
+        details: row.get(8)?,
+        created_at: row.get(9)?,
+        resolved_at: row.get(10)?,
+    })
+}
+
+pub fn insert(conn: &Connection, response: &IncidentResponse) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO incident_responses (id, project_id, run_id, run_execution_id, checkpoint_id, incident_kind, action, status, details, created_at, resolved_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            &response.id,
+            &response.project_id,
+            &response.run_id,
+            &response.run_execution_id,
+            &response.checkpoint_id,
+            &response.incident_kind,
+            &response.action,
+            &response.status,
+            &response.details,
+            &response.created_at,
+            &response.resolved_at,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Option<IncidentResponse>, Error> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {COLUMNS} FROM incident_responses WHERE id = ?1"),
+            params![id],
+            from_row,
+        )
+        .optional()?)
+}
+
+/// Responses of a project, newest first; `pending_only` leaves out resolved ones
+pub fn list(
+    conn: &Connection,
+    project_id: &str,
+    pending_only: bool,
+) -> Result<Vec<IncidentResponse>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM incident_responses
+         WHERE project_id = ?1 AND (?2 = 0 OR status = 'pending')
+         ORDER BY created_at DESC"
+    ))?;
+    let rows = stmt.query_map(params![project_id, pending_only], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Move a pending response to `status`; returns false if it was already resolved
+pub fn resolve(
+    conn: &Connection,
+    id: &str,
+    status: &str,
+    resolved_at: &str,
+) -> Result<bool, Error> {
+    let updated = conn.execute(
+        "UPDATE incident_responses SET status = ?2, resolved_at = ?3
+         WHERE id = ?1 AND status = 'pending'",
+        params![id, status, resolved_at],
+    )?;
+    Ok(updated > 0)
+}
+
+/// Acknowledge the pending notifications raised for an incident checkpoint
+pub fn acknowledge_notifications_for_checkpoint(
+    conn: &Connection,
+    checkpoint_id: &str,
+    resolved_at: &str,
+) -> Result<usize, Error> {
+    Ok(conn.execute(
+        "UPDATE incident_responses SET status = 'acknowledged', resolved_at = ?2
+         WHERE checkpoint_id = ?1 AND action = 'notify' AND status = 'pending'",
+        params![checkpoint_id, resolved_at],
+    )?)
+}
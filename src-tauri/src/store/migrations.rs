@@ -17,6 +17,36 @@ const MIGRATION_SCRIPTS: &[&str] = &[
     include_str!("migrations/V13__add_full_output_hash.sql"),
     include_str!("migrations/V14__policy_versioning.sql"),
     include_str!("migrations/V15__project_usage_ledgers.sql"),
+    include_str!("migrations/V16__run_triggers.sql"),
+    include_str!("migrations/V17__run_templates.sql"),
+    include_str!("migrations/V18__telemetry.sql"),
+    include_str!("migrations/V19__checkpoint_sampling.sql"),
+    include_str!("migrations/V20__model_weights.sql"),
+    include_str!("migrations/V21__dataset_splits.sql"),
+    include_str!("migrations/V22__response_cache.sql"),
+    include_str!("migrations/V23__response_metadata.sql"),
+    include_str!("migrations/V24__embeddings.sql"),
+    include_str!("migrations/V25__run_priority.sql"),
+    include_str!("migrations/V26__run_inputs.sql"),
+    include_str!("migrations/V27__receipt_execution.sql"),
+    include_str!("migrations/V28__checkpoint_costs.sql"),
+    include_str!("migrations/V29__run_archives.sql"),
+    include_str!("migrations/V30__signing_key_events.sql"),
+    include_str!("migrations/V31__incident_responses.sql"),
+    include_str!("migrations/V32__prompt_partials.sql"),
+    include_str!("migrations/V33__self_verification.sql"),
+    include_str!("migrations/V34__run_environment.sql"),
+    include_str!("migrations/V35__receipt_chain.sql"),
+    include_str!("migrations/V36__default_policy.sql"),
+    include_str!("migrations/V37__project_usage_windows.sql"),
+    include_str!("migrations/V38__carbon_accounting.sql"),
+    include_str!("migrations/V39__incident_acknowledgements.sql"),
+    include_str!("migrations/V40__budget_alerts.sql"),
+    include_str!("migrations/V41__replay_usage.sql"),
+    include_str!("migrations/V42__policy_proposals.sql"),
+    include_str!("migrations/V43__project_archival.sql"),
+    include_str!("migrations/V44__policy_version_defaults.sql"),
+    include_str!("migrations/V45__checkpoint_output_schema.sql"),
 ];
 
 pub fn runner() -> Migrations<'static> {
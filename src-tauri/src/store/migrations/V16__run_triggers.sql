@@ -0,0 +1,33 @@
+-- V16__run_triggers.sql
+-- Inbound triggers that start parameterized runs from external events
+
+-- Hash of the external input (e.g. trigger payload) that parameterized an execution
+ALTER TABLE run_executions ADD COLUMN input_sha256 TEXT;
+
+CREATE TABLE IF NOT EXISTS run_triggers (
+    id TEXT PRIMARY KEY,
+    run_id TEXT NOT NULL,
+    kind TEXT NOT NULL,           -- 'file_drop'
+    watch_path TEXT NOT NULL,     -- directory polled for payload files
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_triggers_run ON run_triggers(run_id);
+
+CREATE TABLE IF NOT EXISTS run_trigger_events (
+    id TEXT PRIMARY KEY,
+    trigger_id TEXT NOT NULL,
+    run_execution_id TEXT,
+    source_name TEXT NOT NULL,
+    payload_sha256 TEXT NOT NULL,
+    status TEXT NOT NULL,         -- 'started' | 'failed'
+    error TEXT,
+    received_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (trigger_id) REFERENCES run_triggers(id) ON DELETE CASCADE,
+    FOREIGN KEY (run_execution_id) REFERENCES run_executions(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_trigger_events_trigger
+    ON run_trigger_events(trigger_id, received_at);
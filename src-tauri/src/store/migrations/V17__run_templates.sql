@@ -0,0 +1,16 @@
+-- V17__run_templates.sql
+-- Reusable run configurations with {{variable}} placeholders
+
+CREATE TABLE IF NOT EXISTS run_templates (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    variables_json TEXT NOT NULL,  -- JSON array of declared variable names
+    spec_json TEXT NOT NULL,       -- run settings and step templates
+    source_run_id TEXT,            -- run the template was saved from
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_templates_project ON run_templates(project_id);
@@ -0,0 +1,16 @@
+-- V18__telemetry.sql
+-- Opt-in, locally aggregated usage counts
+
+CREATE TABLE IF NOT EXISTS telemetry_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    enabled INTEGER NOT NULL DEFAULT 0,
+    enabled_at TEXT,
+    last_exported_at TEXT
+);
+
+INSERT OR IGNORE INTO telemetry_settings (id, enabled) VALUES (1, 0);
+
+CREATE TABLE IF NOT EXISTS telemetry_counters (
+    metric TEXT PRIMARY KEY,      -- e.g. 'runs.completed', 'steps.llm', 'verify.replay.passed'
+    count INTEGER NOT NULL DEFAULT 0
+);
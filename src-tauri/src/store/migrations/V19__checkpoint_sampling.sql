@@ -0,0 +1,3 @@
+-- V19__checkpoint_sampling.sql
+-- Record the sampling options (seed, temperature) applied to a checkpoint's model call
+ALTER TABLE checkpoints ADD COLUMN sampling_json TEXT;
@@ -0,0 +1,14 @@
+-- V20__model_weights.sql
+-- Weights registered for local fine-tuned models. Registrations are append-only
+-- so CARs for earlier runs keep attesting the weights in effect at the time.
+
+CREATE TABLE IF NOT EXISTS model_weights (
+    id TEXT PRIMARY KEY,
+    model_id TEXT NOT NULL,
+    weights_sha256 TEXT,          -- NULL marks the model as unregistered from this point on
+    weights_path TEXT,
+    training_car_id TEXT,         -- CAR of the training run that produced the weights
+    registered_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_model_weights_model ON model_weights(model_id, registered_at);
@@ -0,0 +1,16 @@
+-- V21__dataset_splits.sql
+-- Train/validation/test assignment of source documents, by file content hash.
+-- A document belongs to at most one split of a dataset.
+
+CREATE TABLE IF NOT EXISTS dataset_splits (
+    project_id TEXT NOT NULL,
+    dataset TEXT NOT NULL,
+    split TEXT NOT NULL,           -- 'train' | 'validation' | 'test'
+    document_sha256 TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, dataset, document_sha256),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_splits_document
+    ON dataset_splits(project_id, document_sha256);
@@ -0,0 +1,15 @@
+-- V22__response_cache.sql
+-- Content-addressed cache of model generations, consulted when a project's
+-- policy enables it. Checkpoints served from the cache are marked so a
+-- verifier can tell them apart from fresh generations.
+
+CREATE TABLE IF NOT EXISTS llm_response_cache (
+    cache_key TEXT PRIMARY KEY,    -- sha256 of model, sampling parameters and prompt
+    model TEXT NOT NULL,
+    response TEXT NOT NULL,
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE checkpoints ADD COLUMN cache_hit INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- V23__response_metadata.sql
+-- Provider-neutral details of model responses (provider, finish reason, tool
+-- calls, provider request ID), stored as JSON and hashed into the checkpoint.
+
+ALTER TABLE checkpoints ADD COLUMN response_metadata_json TEXT;
@@ -0,0 +1,23 @@
+-- V24__embeddings.sql
+-- Vectors computed by embed steps, one row per chunk of the source output.
+-- Vectors are little-endian f32 bytes; their sha256 is recorded in the step's
+-- provenance manifest.
+
+CREATE TABLE IF NOT EXISTS embeddings (
+    checkpoint_id TEXT NOT NULL,
+    run_execution_id TEXT NOT NULL,
+    checkpoint_config_id TEXT NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    model TEXT NOT NULL,
+    chunk_text TEXT NOT NULL,
+    text_sha256 TEXT NOT NULL,
+    vector BLOB NOT NULL,
+    vector_sha256 TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (checkpoint_id, chunk_index),
+    FOREIGN KEY (checkpoint_id) REFERENCES checkpoints(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_embeddings_step
+    ON embeddings(run_execution_id, checkpoint_config_id);
@@ -0,0 +1,6 @@
+-- V25__run_priority.sql
+-- Scheduling priority of runs, and the preemptions each execution went through
+-- (JSON array of { beforeStep, preemptedBy, pausedAt, resumedAt }).
+
+ALTER TABLE runs ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal';
+ALTER TABLE run_executions ADD COLUMN preemptions_json TEXT;
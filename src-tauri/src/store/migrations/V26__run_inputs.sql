@@ -0,0 +1,5 @@
+-- V26__run_inputs.sql
+-- sha256 of the canonical JSON map of inputs a run was started with, hashed
+-- into the first checkpoint of the execution.
+
+ALTER TABLE checkpoints ADD COLUMN run_inputs_sha256 TEXT;
@@ -0,0 +1,5 @@
+-- V27__receipt_execution.sql
+-- Link receipts to the execution their CAR was built from, so executions
+-- still awaiting a receipt can be listed. Existing receipts stay unlinked.
+
+ALTER TABLE receipts ADD COLUMN run_execution_id TEXT;
@@ -0,0 +1,7 @@
+-- V28__checkpoint_costs.sql
+-- USD and Nature Cost of each checkpoint, priced from the model catalog when
+-- the checkpoint was recorded so later pricing changes do not alter history.
+-- NULL for checkpoints recorded before costs were stored.
+
+ALTER TABLE checkpoints ADD COLUMN usd_cost REAL;
+ALTER TABLE checkpoints ADD COLUMN nature_cost REAL;
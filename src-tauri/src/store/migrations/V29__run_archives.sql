@@ -0,0 +1,19 @@
+-- V29__run_archives.sql
+-- Completed runs whose payloads and attachments were moved to cold storage
+
+CREATE TABLE IF NOT EXISTS cold_storage_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    archive_dir TEXT              -- NULL uses cold_storage/ under the app data dir
+);
+
+INSERT OR IGNORE INTO cold_storage_settings (id, archive_dir) VALUES (1, NULL);
+
+CREATE TABLE IF NOT EXISTS run_archives (
+    run_id TEXT PRIMARY KEY,
+    archive_path TEXT NOT NULL,
+    archive_sha256 TEXT NOT NULL,
+    signature TEXT NOT NULL,      -- Ed25519 signature over the canonical archive manifest
+    archived_at TEXT NOT NULL,
+    rehydrated_at TEXT,           -- set once payloads are restored; NULL while they are cold
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- V30__signing_key_events.sql
+-- Audit trail of project signing key changes
+
+CREATE TABLE IF NOT EXISTS signing_key_events (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    event TEXT NOT NULL,          -- 'regenerated'
+    previous_pubkey TEXT,
+    pubkey TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    run_id TEXT,                  -- run whose execution triggered the change, if any
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_signing_key_events_project
+    ON signing_key_events(project_id, created_at);
@@ -0,0 +1,21 @@
+-- V31__incident_responses.sql
+-- Notifications and approval requests raised by policy incident actions
+
+CREATE TABLE IF NOT EXISTS incident_responses (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    run_execution_id TEXT NOT NULL,
+    checkpoint_id TEXT NOT NULL,  -- incident checkpoint the response belongs to
+    incident_kind TEXT NOT NULL,
+    action TEXT NOT NULL,         -- 'notify' | 'pause_for_approval'
+    status TEXT NOT NULL,         -- 'pending' | 'acknowledged' | 'approved' | 'rejected'
+    details TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    resolved_at TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (checkpoint_id) REFERENCES checkpoints(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_responses_project
+    ON incident_responses(project_id, status, created_at);
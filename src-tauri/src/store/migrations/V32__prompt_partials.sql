@@ -0,0 +1,11 @@
+-- V32__prompt_partials.sql
+-- Reusable prompt fragments included by template prompts as {{> name}}
+
+CREATE TABLE IF NOT EXISTS prompt_partials (
+    project_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    template TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (project_id, name),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,32 @@
+-- V33__self_verification.sql
+-- Nightly re-verification of stored receipts and checkpoint chains
+
+CREATE TABLE IF NOT EXISTS self_verification_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    enabled INTEGER NOT NULL DEFAULT 1,
+    hour_utc INTEGER NOT NULL DEFAULT 3,       -- hour of the day (UTC) the nightly pass starts
+    lookback_days INTEGER NOT NULL DEFAULT 30, -- receipts and executions this recent are re-verified
+    last_run_at TEXT
+);
+
+INSERT OR IGNORE INTO self_verification_settings (id) VALUES (1);
+
+CREATE TABLE IF NOT EXISTS verification_history (
+    id TEXT PRIMARY KEY,
+    pass_id TEXT NOT NULL,            -- all results of one pass share it
+    target_kind TEXT NOT NULL,        -- 'car_file' | 'checkpoint_chain'
+    target_id TEXT NOT NULL,          -- receipt id or run execution id
+    project_id TEXT,
+    run_id TEXT,
+    status TEXT NOT NULL,             -- 'passed' | 'failed'
+    details TEXT,                     -- why it failed
+    new_failure INTEGER NOT NULL DEFAULT 0, -- failed without failing the check before
+    acknowledged_at TEXT,
+    verified_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_history_target
+    ON verification_history(target_kind, target_id, verified_at);
+
+CREATE INDEX IF NOT EXISTS idx_verification_history_alerts
+    ON verification_history(new_failure, acknowledged_at);
@@ -0,0 +1,5 @@
+-- V34__run_environment.sql
+-- Environment an execution started in: OS, app, model catalog and Ollama
+-- versions plus a machine fingerprint, as JSON
+
+ALTER TABLE run_executions ADD COLUMN environment_json TEXT;
@@ -0,0 +1,6 @@
+-- V35__receipt_chain.sql
+-- Each CAR names the receipt its project emitted before it; the link is kept
+-- here too so the project's ledger can be listed. Existing receipts stay
+-- unlinked.
+
+ALTER TABLE receipts ADD COLUMN previous_car_id TEXT;
@@ -0,0 +1,10 @@
+-- V36__default_policy.sql
+-- App-level default policy that projects inherit. Projects may tighten the
+-- fields it caps but not loosen them; without a row, projects keep their own
+-- policies as they are.
+
+CREATE TABLE IF NOT EXISTS default_policy (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    policy_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,15 @@
+-- V37__project_usage_windows.sql
+-- Project usage bucketed by UTC day, week and month, for the window budgets
+-- of a policy. Unlike project_usage_ledgers, windows span policy versions.
+
+CREATE TABLE IF NOT EXISTS project_usage_windows (
+    project_id TEXT NOT NULL,
+    window TEXT NOT NULL CHECK (window IN ('day', 'week', 'month')),
+    window_start TEXT NOT NULL,
+    total_tokens INTEGER NOT NULL DEFAULT 0,
+    total_usd REAL NOT NULL DEFAULT 0,
+    total_nature_cost REAL NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, window, window_start),
+    FOREIGN KEY (project_id) REFERENCES projects(id)
+);
@@ -0,0 +1,15 @@
+-- V38__carbon_accounting.sql
+-- Emissions (gCO2e) of each checkpoint, from the catalog's energy figures and
+-- the carbon intensity of the grid the model ran on; NULL for checkpoints
+-- recorded before emissions were stored or for models without energy data.
+-- The user's grid region prices on-device inference; NULL uses the catalog's
+-- default region.
+
+ALTER TABLE checkpoints ADD COLUMN g_co2e REAL;
+
+CREATE TABLE IF NOT EXISTS carbon_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    grid_region TEXT
+);
+
+INSERT OR IGNORE INTO carbon_settings (id) VALUES (1);
@@ -0,0 +1,20 @@
+-- V39__incident_acknowledgements.sql
+-- Signed acknowledgements of incident checkpoints: who acknowledged the
+-- incident, when and with what note. The project key signs the canonical
+-- acknowledgement, which commits to the incident checkpoint's chain hash.
+
+CREATE TABLE IF NOT EXISTS incident_acknowledgements (
+    checkpoint_id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    checkpoint_curr_chain TEXT NOT NULL,
+    acknowledged_by TEXT NOT NULL,
+    note TEXT,
+    acknowledged_at TEXT NOT NULL,
+    signer_public_key TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (checkpoint_id) REFERENCES checkpoints(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_incident_acknowledgements_project
+    ON incident_acknowledgements(project_id);
@@ -0,0 +1,33 @@
+-- V40__budget_alerts.sql
+-- Budget alerts: which alert thresholds the project's usage has crossed, so
+-- each fires once per budget and period, and where alerts are delivered
+-- besides the app. The SMTP password is kept in the OS keychain.
+
+CREATE TABLE IF NOT EXISTS budget_alerts (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    scope TEXT NOT NULL,          -- 'policy', 'day', 'week' or 'month'
+    period TEXT NOT NULL,         -- policy version, or the window's first day
+    budget TEXT NOT NULL,         -- 'tokens', 'usd' or 'natureCost'
+    threshold INTEGER NOT NULL,   -- percent of the budget
+    used REAL NOT NULL,
+    budget_limit REAL NOT NULL,
+    fired_at TEXT NOT NULL,
+    UNIQUE (project_id, scope, period, budget, threshold),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_alerts_project
+    ON budget_alerts(project_id, fired_at);
+
+CREATE TABLE IF NOT EXISTS notification_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    webhook_url TEXT,
+    email_to TEXT,
+    email_from TEXT,
+    smtp_host TEXT,
+    smtp_port INTEGER,
+    smtp_username TEXT
+);
+
+INSERT OR IGNORE INTO notification_settings (id) VALUES (1);
@@ -0,0 +1,18 @@
+-- V41__replay_usage.sql
+-- Usage of replays. The ledger counts it but replays record no checkpoints,
+-- so ledger reconciliation adds it to the usage recorded in checkpoints.
+
+CREATE TABLE IF NOT EXISTS replay_usage (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    policy_version INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER NOT NULL DEFAULT 0,
+    total_usd REAL NOT NULL DEFAULT 0,
+    total_nature_cost REAL NOT NULL DEFAULT 0,
+    replayed_at TEXT NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_replay_usage_project
+    ON replay_usage(project_id, policy_version);
@@ -0,0 +1,25 @@
+-- V42__policy_proposals.sql
+-- Two-step policy changes: a proposed policy is stored against the version it
+-- was based on and only becomes a policy version once someone other than its
+-- proposer approves it. Versions record who approved them.
+
+CREATE TABLE IF NOT EXISTS policy_proposals (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    base_version INTEGER NOT NULL,  -- current version when proposed; 0 for none
+    policy_json TEXT NOT NULL,
+    proposed_by TEXT NOT NULL,
+    change_notes TEXT,
+    proposed_at TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',  -- 'pending', 'approved' or 'rejected'
+    decided_by TEXT,
+    decided_at TEXT,
+    approved_version INTEGER,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_proposals_project
+    ON policy_proposals(project_id, status);
+
+ALTER TABLE policy_versions ADD COLUMN approved_by TEXT;
+ALTER TABLE policy_versions ADD COLUMN approved_at TEXT;
@@ -0,0 +1,23 @@
+-- V43__project_archival.sql
+-- Deleting a project archives it; archived projects are purged after a retention period
+
+ALTER TABLE projects ADD COLUMN archived_at TEXT; -- NULL while the project is active
+
+CREATE TABLE IF NOT EXISTS project_retention_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    retention_days INTEGER        -- NULL keeps archived projects until they are purged by hand
+);
+
+INSERT OR IGNORE INTO project_retention_settings (id, retention_days) VALUES (1, 30);
+
+-- No foreign key: the trail outlives the projects it records
+CREATE TABLE IF NOT EXISTS project_archive_events (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    project_name TEXT NOT NULL,
+    action TEXT NOT NULL,         -- 'archived', 'restored' or 'purged'
+    at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_archive_events_project
+    ON project_archive_events(project_id, at);
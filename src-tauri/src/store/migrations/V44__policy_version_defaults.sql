@@ -0,0 +1,9 @@
+-- V44__policy_version_defaults.sql
+-- Each policy version keeps the default policy it resolves against, so a
+-- later default does not change the effective policy of past versions
+
+ALTER TABLE policy_versions ADD COLUMN default_policy_json TEXT; -- NULL when no default was set
+
+-- Existing versions are pinned to the default in force at upgrade
+UPDATE policy_versions
+SET default_policy_json = (SELECT policy_json FROM default_policy WHERE id = 1);
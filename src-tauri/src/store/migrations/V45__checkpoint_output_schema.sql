@@ -0,0 +1,5 @@
+-- V45__checkpoint_output_schema.sql
+-- sha256 of the JSON Schema a step's output was validated against, hashed
+-- into the step checkpoint.
+
+ALTER TABLE checkpoints ADD COLUMN output_schema_sha256 TEXT;
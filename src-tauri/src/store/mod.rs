@@ -3,10 +3,28 @@
 // This file makes the `store` directory a Rust module.
 // Now we can declare sub-modules.
 
+pub mod carbon_settings;
+pub mod dataset_splits;
+pub mod embeddings;
+pub mod incident_acknowledgements;
+pub mod incident_responses;
 pub mod migrations;
+pub mod model_weights;
+pub mod notifications;
 pub mod policies;
+pub mod policy_proposals;
+pub mod project_archive_events;
 pub mod project_usage_ledgers;
+pub mod project_retention_settings;
 pub mod projects;
+pub mod prompt_partials;
+pub mod response_cache;
+pub mod run_archives;
+pub mod run_templates;
+pub mod run_triggers;
+pub mod self_verification;
+pub mod signing_key_events;
+pub mod telemetry;
 
 // We'll also put the database migration logic here.
 use crate::Error;
@@ -0,0 +1,117 @@
+// src-tauri/src/store/model_weights.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Weights file registered for a local fine-tuned model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelWeights {
+    pub id: String,
+    pub model_id: String,
+    pub weights_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weights_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub training_car_id: Option<String>,
+    pub registered_at: String,
+}
+
+const WEIGHTS_COLUMNS: &str =
+    "id, model_id, weights_sha256, weights_path, training_car_id, registered_at";
+
+/// Reads a registration row; tombstones (no weights hash) map to `None`
+fn weights_from_row(row: &Row<'_>) -> rusqlite::Result<Option<ModelWeights>> {
+    let Some(weights_sha256) = row.get::<_, Option<String>>(2)? else {
+        return Ok(None);
+    };
+    Ok(Some(ModelWeights {
+        id: row.get(0)?,
+        model_id: row.get(1)?,
+        weights_sha256,
+        weights_path: row.get(3)?,
+        training_car_id: row.get(4)?,
+        registered_at: row.get(5)?,
+    }))
+}
+
+pub fn register(
+    conn: &Connection,
+    model_id: &str,
+    weights_sha256: &str,
+    weights_path: Option<&str>,
+    training_car_id: Option<&str>,
+) -> Result<ModelWeights, Error> {
+    let weights = ModelWeights {
+        id: Uuid::new_v4().to_string(),
+        model_id: model_id.to_string(),
+        weights_sha256: weights_sha256.to_string(),
+        weights_path: weights_path.map(str::to_string),
+        training_car_id: training_car_id.map(str::to_string),
+        registered_at: chrono::Utc::now().to_rfc3339(),
+    };
+    conn.execute(
+        "INSERT INTO model_weights (id, model_id, weights_sha256, weights_path, training_car_id, registered_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            &weights.id,
+            &weights.model_id,
+            &weights.weights_sha256,
+            &weights.weights_path,
+            &weights.training_car_id,
+            &weights.registered_at,
+        ],
+    )?;
+    Ok(weights)
+}
+
+/// Stop attaching weights claims for the model; earlier checkpoints keep theirs
+pub fn unregister(conn: &Connection, model_id: &str) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO model_weights (id, model_id, weights_sha256, registered_at) VALUES (?1, ?2, NULL, ?3)",
+        params![
+            Uuid::new_v4().to_string(),
+            model_id,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Registration in effect at `timestamp` (RFC 3339), if any
+pub fn effective_at(
+    conn: &Connection,
+    model_id: &str,
+    timestamp: &str,
+) -> Result<Option<ModelWeights>, Error> {
+    let sql = format!(
+        "SELECT {WEIGHTS_COLUMNS} FROM model_weights WHERE model_id = ?1 AND registered_at <= ?2
+         ORDER BY registered_at DESC, rowid DESC LIMIT 1"
+    );
+    Ok(conn
+        .query_row(&sql, params![model_id, timestamp], weights_from_row)
+        .optional()?
+        .flatten())
+}
+
+/// Current registration of every model that has one
+pub fn list_current(conn: &Connection) -> Result<Vec<ModelWeights>, Error> {
+    let sql = format!(
+        "SELECT {WEIGHTS_COLUMNS} FROM model_weights AS latest
+         WHERE rowid = (
+             SELECT rowid FROM model_weights WHERE model_id = latest.model_id
+             ORDER BY registered_at DESC, rowid DESC LIMIT 1
+         )
+         ORDER BY model_id ASC"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([], weights_from_row)?;
+    let mut weights = Vec::new();
+    for row in rows {
+        if let Some(registration) = row? {
+            weights.push(registration);
+        }
+    }
+    Ok(weights)
+}
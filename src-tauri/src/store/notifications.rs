@@ -0,0 +1,127 @@
+// src-tauri/src/store/notifications.rs
+use crate::Error;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+/// Where budget alerts are delivered besides the app; absent fields turn
+/// that channel off
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_username: Option<String>,
+}
+
+/// A threshold of one budget the project's usage crossed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlert {
+    pub id: String,
+    pub project_id: String,
+    /// `policy` for the policy version's budgets, or the budget window
+    pub scope: String,
+    /// The policy version, or the first day of the window
+    pub period: String,
+    /// `tokens`, `usd` or `natureCost`
+    pub budget: String,
+    /// Percent of the budget
+    pub threshold: u32,
+    pub used: f64,
+    pub limit: f64,
+    pub fired_at: String,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<NotificationSettings, Error> {
+    Ok(conn.query_row(
+        "SELECT webhook_url, email_to, email_from, smtp_host, smtp_port, smtp_username
+         FROM notification_settings WHERE id = 1",
+        [],
+        |row| {
+            Ok(NotificationSettings {
+                webhook_url: row.get(0)?,
+                email_to: row.get(1)?,
+                email_from: row.get(2)?,
+                smtp_host: row.get(3)?,
+                smtp_port: row.get(4)?,
+                smtp_username: row.get(5)?,
+            })
+        },
+    )?)
+}
+
+pub fn set_settings(conn: &Connection, settings: &NotificationSettings) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE notification_settings SET webhook_url = ?1, email_to = ?2, email_from = ?3,
+             smtp_host = ?4, smtp_port = ?5, smtp_username = ?6
+         WHERE id = 1",
+        params![
+            settings.webhook_url,
+            settings.email_to,
+            settings.email_from,
+            settings.smtp_host,
+            settings.smtp_port,
+            settings.smtp_username,
+        ],
+    )?;
+    Ok(())
+}
+
+const COLUMNS: &str =
+    "id, project_id, scope, period, budget, threshold, used, budget_limit, fired_at";
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<BudgetAlert> {
+    Ok(BudgetAlert {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        scope: row.get(2)?,
+        period: row.get(3)?,
+        budget: row.get(4)?,
+        threshold: row.get(5)?,
+        used: row.get(6)?,
+        limit: row.get(7)?,
+        fired_at: row.get(8)?,
+    })
+}
+
+/// Record the alert unless its threshold already fired for that budget and
+/// period; returns whether it was recorded
+pub fn insert_if_new(conn: &Connection, alert: &BudgetAlert) -> Result<bool, Error> {
+    let inserted = conn.execute(
+        &format!(
+            "INSERT OR IGNORE INTO budget_alerts ({COLUMNS})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+        ),
+        params![
+            &alert.id,
+            &alert.project_id,
+            &alert.scope,
+            &alert.period,
+            &alert.budget,
+            alert.threshold,
+            alert.used,
+            alert.limit,
+            &alert.fired_at,
+        ],
+    )?;
+    Ok(inserted > 0)
+}
+
+/// The project's alerts, newest first
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<BudgetAlert>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM budget_alerts
+         WHERE project_id = ?1 ORDER BY fired_at DESC, budget, threshold DESC"
+    ))?;
+    let rows = stmt.query_map(params![project_id], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
@@ -1,7 +1,14 @@
 // In src-tauri/src/store/policies.rs
+use crate::content_policy::ContentRules;
+use crate::execution_window::ExecutionWindow;
+use crate::incident_response::{self, IncidentAction};
+use crate::provider_policy::ProviderAllowlist;
+use crate::store::project_usage_ledgers::BudgetWindow;
 use crate::Error;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +17,75 @@ pub struct Policy {
     pub budget_tokens: u64,
     pub budget_usd: f64,
     pub budget_nature_cost: f64, // Renamed from budget_g_co2e
+    /// Filesystem sandbox roots; empty means filesystem access is unrestricted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_path_roots: Vec<String>,
+    /// Programs `finetune` steps may run, by name on `PATH` or by path; with
+    /// none, fine-tune steps are refused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_training_programs: Vec<String>,
+    /// Executions of this project's runs allowed at once; more wait in the run queue
+    #[serde(default = "default_max_concurrent_runs")]
+    pub max_concurrent_runs: u32,
+    /// Secret, PII and prompt-injection scanning of step inputs and outputs
+    #[serde(default, skip_serializing_if = "ContentRules::is_default")]
+    pub content_rules: ContentRules,
+    /// Serve repeated model+parameters+prompt requests from the response cache
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub response_cache: bool,
+    /// When runs that use local models may start; outside it they are deferred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_window: Option<ExecutionWindow>,
+    /// Action per incident kind; unlisted kinds block on errors and continue on warnings
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub incident_actions: BTreeMap<String, IncidentAction>,
+    /// Caps on single models' usage, by model id, within the budgets above
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub model_budgets: BTreeMap<String, SubBudget>,
+    /// Caps on providers' usage, by catalog provider ("anthropic", "ollama", ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provider_budgets: BTreeMap<String, SubBudget>,
+    /// Caps on the project's usage per UTC day, week or month, across policy versions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub window_budgets: Vec<WindowBudget>,
+    /// Providers and regions steps' models may use; steps outside them are refused
+    #[serde(default, skip_serializing_if = "ProviderAllowlist::is_unrestricted")]
+    pub provider_allowlist: ProviderAllowlist,
+    /// Percentages of each budget at which a budget alert fires, e.g. `[80, 95]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budget_alert_thresholds: Vec<u32>,
+    /// Changes go through a proposal someone else approves instead of applying at once
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_change_approval: bool,
+}
+
+/// Caps on the usage of one model or provider; an absent cap is unlimited.
+/// Unlike the project's Nature Cost budget, every cap blocks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubBudget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nature_cost: Option<f64>,
+}
+
+/// Caps on the project's usage within each calendar window, e.g. 100k
+/// tokens a day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBudget {
+    pub window: BudgetWindow,
+    #[serde(flatten)]
+    pub caps: SubBudget,
+}
+
+pub const DEFAULT_MAX_CONCURRENT_RUNS: u32 = 2;
+
+fn default_max_concurrent_runs() -> u32 {
+    DEFAULT_MAX_CONCURRENT_RUNS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +98,15 @@ pub struct PolicyVersion {
     pub created_at: String,
     pub created_by: Option<String>,
     pub change_notes: Option<String>,
+    /// Who approved the proposal this version was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<String>,
+    /// The default policy this version resolves against: the one in force
+    /// while it was the project's current version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_policy: Option<Policy>,
 }
 
 impl Default for Policy {
@@ -31,11 +116,194 @@ impl Default for Policy {
             budget_tokens: 1_000,
             budget_usd: 10.0,
             budget_nature_cost: 100.0, // Higher default, more flexible metric
+            allowed_path_roots: Vec::new(),
+            allowed_training_programs: Vec::new(),
+            max_concurrent_runs: DEFAULT_MAX_CONCURRENT_RUNS,
+            content_rules: ContentRules::default(),
+            response_cache: false,
+            execution_window: None,
+            incident_actions: BTreeMap::new(),
+            model_budgets: BTreeMap::new(),
+            provider_budgets: BTreeMap::new(),
+            window_budgets: Vec::new(),
+            provider_allowlist: ProviderAllowlist::default(),
+            budget_alert_thresholds: Vec::new(),
+            require_change_approval: false,
+        }
+    }
+}
+
+/// Fields the default policy caps, as they are named in policy JSON: a
+/// project may tighten them but never loosen them. `responseCache` and
+/// `budgetAlertThresholds` restrict nothing and are left to each project.
+pub const CAPPED_FIELDS: [&str; 15] = [
+    "allowNetwork",
+    "budgetTokens",
+    "budgetUsd",
+    "budgetNatureCost",
+    "allowedPathRoots",
+    "allowedTrainingPrograms",
+    "maxConcurrentRuns",
+    "contentRules",
+    "executionWindow",
+    "incidentActions",
+    "modelBudgets",
+    "providerBudgets",
+    "windowBudgets",
+    "providerAllowlist",
+    "requireChangeApproval",
+];
+
+impl SubBudget {
+    /// The tighter of each cap of this budget and `default`
+    fn within(&self, default: &SubBudget) -> SubBudget {
+        SubBudget {
+            tokens: tighter(self.tokens, default.tokens),
+            usd: tighter(self.usd, default.usd),
+            nature_cost: tighter(self.nature_cost, default.nature_cost),
+        }
+    }
+}
+
+/// The lower of two caps, where an absent cap is unlimited
+fn tighter<T: PartialOrd>(own: Option<T>, cap: Option<T>) -> Option<T> {
+    match (own, cap) {
+        (Some(own), Some(cap)) => Some(if cap < own { cap } else { own }),
+        (own, cap) => own.or(cap),
+    }
+}
+
+/// `budgets` with every budget `default` sets tightened to it
+fn sub_budgets_within(
+    budgets: &BTreeMap<String, SubBudget>,
+    default: &BTreeMap<String, SubBudget>,
+) -> BTreeMap<String, SubBudget> {
+    let mut narrowed = budgets.clone();
+    for (key, cap) in default {
+        narrowed
+            .entry(key.clone())
+            .and_modify(|budget| *budget = budget.within(cap))
+            .or_insert_with(|| cap.clone());
+    }
+    narrowed
+}
+
+fn window_budgets_within(budgets: &[WindowBudget], default: &[WindowBudget]) -> Vec<WindowBudget> {
+    let mut narrowed = budgets.to_vec();
+    for cap in default {
+        match narrowed
+            .iter_mut()
+            .find(|budget| budget.window == cap.window)
+        {
+            Some(budget) => budget.caps = budget.caps.within(&cap.caps),
+            None => narrowed.push(cap.clone()),
         }
     }
+    narrowed
 }
 
+/// The roots of `roots` under one of the default's, or the default's roots
+/// when none are; no roots leaves the filesystem unrestricted
+fn path_roots_within(roots: &[String], default: &[String]) -> Vec<String> {
+    if default.is_empty() {
+        return roots.to_vec();
+    }
+    let kept: Vec<String> = roots
+        .iter()
+        .filter(|root| default.iter().any(|cap| Path::new(root).starts_with(cap)))
+        .cloned()
+        .collect();
+    if kept.is_empty() {
+        default.to_vec()
+    } else {
+        kept
+    }
+}
+
+impl Policy {
+    pub fn has_sub_budgets(&self) -> bool {
+        !self.model_budgets.is_empty() || !self.provider_budgets.is_empty()
+    }
+
+    /// This policy with every field `default` caps tightened to it
+    pub fn within(&self, default: &Policy) -> Policy {
+        Policy {
+            allow_network: self.allow_network && default.allow_network,
+            budget_tokens: self.budget_tokens.min(default.budget_tokens),
+            budget_usd: self.budget_usd.min(default.budget_usd),
+            budget_nature_cost: self.budget_nature_cost.min(default.budget_nature_cost),
+            allowed_path_roots: path_roots_within(
+                &self.allowed_path_roots,
+                &default.allowed_path_roots,
+            ),
+            allowed_training_programs: self
+                .allowed_training_programs
+                .iter()
+                .filter(|program| default.allowed_training_programs.contains(program))
+                .cloned()
+                .collect(),
+            max_concurrent_runs: self.max_concurrent_runs.min(default.max_concurrent_runs),
+            content_rules: self.content_rules.within(&default.content_rules),
+            execution_window: match (&self.execution_window, &default.execution_window) {
+                (Some(own), Some(cap)) => Some(own.within(cap)),
+                (own, cap) => own.clone().or_else(|| cap.clone()),
+            },
+            incident_actions: incident_response::actions_within(
+                &self.incident_actions,
+                &default.incident_actions,
+            ),
+            model_budgets: sub_budgets_within(&self.model_budgets, &default.model_budgets),
+            provider_budgets: sub_budgets_within(&self.provider_budgets, &default.provider_budgets),
+            window_budgets: window_budgets_within(&self.window_budgets, &default.window_budgets),
+            provider_allowlist: self.provider_allowlist.within(&default.provider_allowlist),
+            require_change_approval: self.require_change_approval
+                || default.require_change_approval,
+            ..self.clone()
+        }
+    }
+
+    /// The capped fields this policy loosens relative to `default`: those
+    /// tightening it to the default would change
+    pub fn loosened_fields(&self, default: &Policy) -> Vec<&'static str> {
+        let tightened = self.within(default);
+        let loosened = [
+            self.allow_network != tightened.allow_network,
+            self.budget_tokens != tightened.budget_tokens,
+            self.budget_usd != tightened.budget_usd,
+            self.budget_nature_cost != tightened.budget_nature_cost,
+            self.allowed_path_roots != tightened.allowed_path_roots,
+            self.allowed_training_programs != tightened.allowed_training_programs,
+            self.max_concurrent_runs != tightened.max_concurrent_runs,
+            self.content_rules != tightened.content_rules,
+            self.execution_window != tightened.execution_window,
+            self.incident_actions != tightened.incident_actions,
+            self.model_budgets != tightened.model_budgets,
+            self.provider_budgets != tightened.provider_budgets,
+            self.window_budgets != tightened.window_budgets,
+            self.provider_allowlist != tightened.provider_allowlist,
+            self.require_change_approval != tightened.require_change_approval,
+        ];
+        CAPPED_FIELDS
+            .into_iter()
+            .zip(loosened)
+            .filter_map(|(field, loosened)| loosened.then_some(field))
+            .collect()
+    }
+}
+
+fn parse_policy(json: &str) -> Result<Policy, Error> {
+    serde_json::from_str(json).map_err(|e| Error::Api(format!("failed to parse policy JSON: {e}")))
+}
+
+/// The project's effective policy: its own, tightened to the default policy
+/// where that caps it, or the default policy when it has none of its own
 pub fn get(conn: &Connection, project_id: &str) -> Result<Policy, Error> {
+    let project = get_project_layer(conn, project_id)?;
+    Ok(resolve(get_default(conn)?.as_ref(), project))
+}
+
+/// The policy the project set for itself, before the default applies
+pub fn get_project_layer(conn: &Connection, project_id: &str) -> Result<Option<Policy>, Error> {
     let policy_json: Option<String> = conn
         .query_row(
             "SELECT policy_json FROM policies WHERE project_id = ?1",
@@ -43,14 +311,49 @@ pub fn get(conn: &Connection, project_id: &str) -> Result<Policy, Error> {
             |row| row.get(0),
         )
         .optional()?;
+    policy_json.as_deref().map(parse_policy).transpose()
+}
+
+/// The app-level default policy, if one has been set
+pub fn get_default(conn: &Connection) -> Result<Option<Policy>, Error> {
+    let policy_json: Option<String> = conn
+        .query_row("SELECT policy_json FROM default_policy WHERE id = 1", [], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    policy_json.as_deref().map(parse_policy).transpose()
+}
 
-    match policy_json {
-        Some(json) => serde_json::from_str(&json)
-            .map_err(|e| Error::Api(format!("failed to parse policy JSON: {e}"))),
-        None => Ok(Policy::default()),
+/// Set the default policy. It applies to every project's current policy
+/// version from now on; superseded versions keep the default they had.
+pub fn set_default(conn: &Connection, policy: &Policy) -> Result<(), Error> {
+    let policy_json = serde_json::to_string(policy)
+        .map_err(|e| Error::Api(format!("failed to serialize policy: {e}")))?;
+    conn.execute(
+        "INSERT INTO default_policy (id, policy_json) VALUES (1, ?1)
+         ON CONFLICT(id) DO UPDATE SET
+            policy_json = excluded.policy_json,
+            updated_at = CURRENT_TIMESTAMP",
+        params![policy_json],
+    )?;
+    conn.execute(
+        "UPDATE policy_versions SET default_policy_json = ?1
+         WHERE (project_id, version) IN (SELECT project_id, current_version FROM policies)",
+        params![policy_json],
+    )?;
+    Ok(())
+}
+
+fn resolve(default: Option<&Policy>, project: Option<Policy>) -> Policy {
+    match (default, project) {
+        (Some(default), Some(project)) => project.within(default),
+        (Some(default), None) => default.clone(),
+        (None, project) => project.unwrap_or_default(),
     }
 }
 
+/// The project's effective policy as of `policy_version`, resolved against
+/// the default that version was recorded with rather than today's
 pub fn get_for_policy_version(
     conn: &Connection,
     project_id: &str,
@@ -59,7 +362,10 @@ pub fn get_for_policy_version(
     if let Some(version) = policy_version {
         if version > 0 {
             if let Some(policy_version) = get_version(conn, project_id, version)? {
-                return Ok(policy_version.policy);
+                return Ok(resolve(
+                    policy_version.default_policy.as_ref(),
+                    Some(policy_version.policy),
+                ));
             }
         }
     }
@@ -78,6 +384,54 @@ pub fn upsert_with_notes(
     created_by: Option<&str>,
     change_notes: Option<&str>,
 ) -> Result<(), Error> {
+    insert_version(conn, project_id, policy, created_by, change_notes, None)?;
+    Ok(())
+}
+
+/// Make an approved proposal the project's policy, recording the approver
+/// with the new version; returns the new version number
+pub fn upsert_approved(
+    conn: &Connection,
+    project_id: &str,
+    policy: &Policy,
+    created_by: &str,
+    change_notes: Option<&str>,
+    approved_by: &str,
+) -> Result<i64, Error> {
+    insert_version(
+        conn,
+        project_id,
+        policy,
+        Some(created_by),
+        change_notes,
+        Some(approved_by),
+    )
+}
+
+fn insert_version(
+    conn: &Connection,
+    project_id: &str,
+    policy: &Policy,
+    created_by: Option<&str>,
+    change_notes: Option<&str>,
+    approved_by: Option<&str>,
+) -> Result<i64, Error> {
+    let default = get_default(conn)?;
+    if let Some(default) = &default {
+        let loosened = policy.loosened_fields(default);
+        if !loosened.is_empty() {
+            return Err(Error::Api(format!(
+                "policy loosens what the default policy caps: {}",
+                loosened.join(", ")
+            )));
+        }
+    }
+    let default_policy_json = default
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| Error::Api(format!("failed to serialize policy: {e}")))?;
+
     let policy_json = serde_json::to_string(policy)
         .map_err(|e| Error::Api(format!("failed to serialize policy: {e}")))?;
 
@@ -94,15 +448,21 @@ pub fn upsert_with_notes(
     let new_version = current_version + 1;
 
     // Insert new version into policy_versions table
+    let approved_at = approved_by.map(|_| chrono::Utc::now().to_rfc3339());
     conn.execute(
-        "INSERT INTO policy_versions (project_id, version, policy_json, created_by, change_notes)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO policy_versions
+             (project_id, version, policy_json, created_by, change_notes, approved_by, approved_at,
+              default_policy_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         params![
             project_id,
             new_version,
             policy_json,
             created_by,
-            change_notes
+            change_notes,
+            approved_by,
+            approved_at,
+            default_policy_json
         ],
     )?;
 
@@ -135,39 +495,43 @@ pub fn upsert_with_notes(
         params![new_version, project_id],
     )?;
 
-    Ok(())
+    Ok(new_version)
+}
+
+const VERSION_COLUMNS: &str = "id, project_id, version, policy_json, created_at, created_by,
+     change_notes, approved_by, approved_at, default_policy_json";
+
+fn policy_from_column(index: usize, json: &str) -> rusqlite::Result<Policy> {
+    serde_json::from_str(json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn version_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<PolicyVersion> {
+    let default_policy_json: Option<String> = row.get(9)?;
+    Ok(PolicyVersion {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        version: row.get(2)?,
+        policy: policy_from_column(3, &row.get::<_, String>(3)?)?,
+        created_at: row.get(4)?,
+        created_by: row.get(5)?,
+        change_notes: row.get(6)?,
+        approved_by: row.get(7)?,
+        approved_at: row.get(8)?,
+        default_policy: default_policy_json
+            .map(|json| policy_from_column(9, &json))
+            .transpose()?,
+    })
 }
 
 /// Get all policy versions for a project
 pub fn get_versions(conn: &Connection, project_id: &str) -> Result<Vec<PolicyVersion>, Error> {
-    let mut stmt = conn.prepare(
-        "SELECT id, project_id, version, policy_json, created_at, created_by, change_notes
-         FROM policy_versions
-         WHERE project_id = ?1
-         ORDER BY version DESC",
-    )?;
-
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {VERSION_COLUMNS} FROM policy_versions WHERE project_id = ?1 ORDER BY version DESC"
+    ))?;
     let versions = stmt
-        .query_map(params![project_id], |row| {
-            let policy_json: String = row.get(3)?;
-            let policy: Policy = serde_json::from_str(&policy_json).map_err(|e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    3,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                )
-            })?;
-
-            Ok(PolicyVersion {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                version: row.get(2)?,
-                policy,
-                created_at: row.get(4)?,
-                created_by: row.get(5)?,
-                change_notes: row.get(6)?,
-            })
-        })?
+        .query_map(params![project_id], version_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     Ok(versions)
@@ -181,30 +545,11 @@ pub fn get_version(
 ) -> Result<Option<PolicyVersion>, Error> {
     let row = conn
         .query_row(
-            "SELECT id, project_id, version, policy_json, created_at, created_by, change_notes
-             FROM policy_versions
-             WHERE project_id = ?1 AND version = ?2",
+            &format!(
+                "SELECT {VERSION_COLUMNS} FROM policy_versions WHERE project_id = ?1 AND version = ?2"
+            ),
             params![project_id, version],
-            |row| {
-                let policy_json: String = row.get(3)?;
-                let policy: Policy = serde_json::from_str(&policy_json).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        3,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?;
-
-                Ok(PolicyVersion {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    version: row.get(2)?,
-                    policy,
-                    created_at: row.get(4)?,
-                    created_by: row.get(5)?,
-                    change_notes: row.get(6)?,
-                })
-            },
+            version_from_row,
         )
         .optional()?;
 
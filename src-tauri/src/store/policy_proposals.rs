@@ -0,0 +1,124 @@
+// src-tauri/src/store/policy_proposals.rs
+use crate::store::policies::Policy;
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_APPROVED: &str = "approved";
+pub const STATUS_REJECTED: &str = "rejected";
+
+/// A policy change waiting for, or decided by, a second person
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyProposal {
+    pub id: String,
+    pub project_id: String,
+    /// The project's policy version when the change was proposed; 0 for none
+    pub base_version: i64,
+    pub policy: Policy,
+    pub proposed_by: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_notes: Option<String>,
+    pub proposed_at: String,
+    /// `pending`, `approved` or `rejected`
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<String>,
+    /// The policy version the approved proposal became
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_version: Option<i64>,
+}
+
+const COLUMNS: &str = "id, project_id, base_version, policy_json, proposed_by, change_notes, proposed_at, status, decided_by, decided_at, approved_version";
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<PolicyProposal> {
+    let policy_json: String = row.get(3)?;
+    let policy: Policy = serde_json::from_str(&policy_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(PolicyProposal {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        base_version: row.get(2)?,
+        policy,
+        proposed_by: row.get(4)?,
+        change_notes: row.get(5)?,
+        proposed_at: row.get(6)?,
+        status: row.get(7)?,
+        decided_by: row.get(8)?,
+        decided_at: row.get(9)?,
+        approved_version: row.get(10)?,
+    })
+}
+
+pub fn insert(conn: &Connection, proposal: &PolicyProposal) -> Result<(), Error> {
+    let policy_json = serde_json::to_string(&proposal.policy)
+        .map_err(|e| Error::Api(format!("failed to serialize policy: {e}")))?;
+    conn.execute(
+        &format!(
+            "INSERT INTO policy_proposals ({COLUMNS})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+        ),
+        params![
+            &proposal.id,
+            &proposal.project_id,
+            proposal.base_version,
+            policy_json,
+            &proposal.proposed_by,
+            &proposal.change_notes,
+            &proposal.proposed_at,
+            &proposal.status,
+            &proposal.decided_by,
+            &proposal.decided_at,
+            proposal.approved_version,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get(conn: &Connection, proposal_id: &str) -> Result<Option<PolicyProposal>, Error> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {COLUMNS} FROM policy_proposals WHERE id = ?1"),
+            params![proposal_id],
+            from_row,
+        )
+        .optional()?)
+}
+
+/// The project's proposals, newest first
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<PolicyProposal>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM policy_proposals
+         WHERE project_id = ?1 ORDER BY proposed_at DESC, id"
+    ))?;
+    let rows = stmt.query_map(params![project_id], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Record the decision on a pending proposal; returns whether it was pending
+pub fn decide(
+    conn: &Connection,
+    proposal_id: &str,
+    status: &str,
+    decided_by: &str,
+    decided_at: &str,
+    approved_version: Option<i64>,
+) -> Result<bool, Error> {
+    let updated = conn.execute(
+        "UPDATE policy_proposals
+         SET status = ?2, decided_by = ?3, decided_at = ?4, approved_version = ?5
+         WHERE id = ?1 AND status = 'pending'",
+        params![
+            proposal_id,
+            status,
+            decided_by,
+            decided_at,
+            approved_version
+        ],
+    )?;
+    Ok(updated > 0)
+}
@@ -0,0 +1,60 @@
+// src-tauri/src/store/project_archive_events.rs
+use crate::Error;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+pub const ACTION_ARCHIVED: &str = "archived";
+pub const ACTION_RESTORED: &str = "restored";
+pub const ACTION_PURGED: &str = "purged";
+
+/// A project being archived, restored or purged
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectArchiveEvent {
+    pub id: String,
+    pub project_id: String,
+    /// The project's name at the time, kept once the project is purged
+    pub project_name: String,
+    /// `archived`, `restored` or `purged`
+    pub action: String,
+    pub at: String,
+}
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<ProjectArchiveEvent> {
+    Ok(ProjectArchiveEvent {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_name: row.get(2)?,
+        action: row.get(3)?,
+        at: row.get(4)?,
+    })
+}
+
+pub fn insert(conn: &Connection, event: &ProjectArchiveEvent) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO project_archive_events (id, project_id, project_name, action, at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            &event.id,
+            &event.project_id,
+            &event.project_name,
+            &event.action,
+            &event.at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Events of one project, or of every project, newest first
+pub fn list(
+    conn: &Connection,
+    project_id: Option<&str>,
+) -> Result<Vec<ProjectArchiveEvent>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, project_name, action, at FROM project_archive_events
+         WHERE ?1 IS NULL OR project_id = ?1
+         ORDER BY at DESC, rowid DESC",
+    )?;
+    let rows = stmt.query_map(params![project_id], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,21 @@
+// src-tauri/src/store/project_retention_settings.rs
+use crate::Error;
+use rusqlite::{params, Connection};
+
+/// Days an archived project is kept before it is purged; `None` keeps it
+/// until it is purged by hand
+pub fn get_retention_days(conn: &Connection) -> Result<Option<u32>, Error> {
+    Ok(conn.query_row(
+        "SELECT retention_days FROM project_retention_settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn set_retention_days(conn: &Connection, retention_days: Option<u32>) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE project_retention_settings SET retention_days = ?1 WHERE id = 1",
+        params![retention_days],
+    )?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
 use crate::Error;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +21,52 @@ pub struct ProjectUsageLedger {
     pub updated_at: Option<String>,
 }
 
+/// Calendar windows, in UTC, that usage is also bucketed into for the
+/// window budgets of a policy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetWindow {
+    Day,
+    Week,
+    Month,
+}
+
+impl BudgetWindow {
+    pub const ALL: [BudgetWindow; 3] =
+        [BudgetWindow::Day, BudgetWindow::Week, BudgetWindow::Month];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BudgetWindow::Day => "day",
+            BudgetWindow::Week => "week",
+            BudgetWindow::Month => "month",
+        }
+    }
+
+    /// First day of the window `at` falls in; weeks start on Monday
+    pub fn start(self, at: DateTime<Utc>) -> NaiveDate {
+        let day = at.date_naive();
+        match self {
+            BudgetWindow::Day => day,
+            BudgetWindow::Week => {
+                day - Duration::days(i64::from(day.weekday().num_days_from_monday()))
+            }
+            BudgetWindow::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+}
+
+/// Usage the project recorded in one window, across policy versions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowUsage {
+    pub window: BudgetWindow,
+    pub window_start: String,
+    pub total_tokens: u64,
+    pub total_usd: f64,
+    pub total_nature_cost: f64,
+}
+
 pub fn get(
     conn: &Connection,
     project_id: &str,
@@ -92,5 +139,261 @@ pub fn increment(
         ],
     )?;
 
+    for window in BudgetWindow::ALL {
+        conn.execute(
+            concat!(
+                "INSERT INTO project_usage_windows ",
+                "(project_id, window, window_start, total_tokens, total_usd, total_nature_cost) ",
+                "VALUES (?1, ?2, ?3, ?4, ?5, ?6) ",
+                "ON CONFLICT(project_id, window, window_start) DO UPDATE SET ",
+                "total_tokens = total_tokens + excluded.total_tokens, ",
+                "total_usd = total_usd + excluded.total_usd, ",
+                "total_nature_cost = total_nature_cost + excluded.total_nature_cost, ",
+                "updated_at = CURRENT_TIMESTAMP"
+            ),
+            params![
+                project_id,
+                window.as_str(),
+                window.start(Utc::now()).to_string(),
+                delta_tokens_i64,
+                delta_usd,
+                delta_nature_cost
+            ],
+        )?;
+    }
+
     get(conn, project_id, Some(normalized_version))
 }
+
+/// Usage the project recorded in the window `at` falls in
+pub fn get_window(
+    conn: &Connection,
+    project_id: &str,
+    window: BudgetWindow,
+    at: DateTime<Utc>,
+) -> Result<WindowUsage, Error> {
+    let window_start = window.start(at).to_string();
+    let row: Option<(i64, f64, f64)> = conn
+        .query_row(
+            concat!(
+                "SELECT total_tokens, total_usd, total_nature_cost ",
+                "FROM project_usage_windows ",
+                "WHERE project_id = ?1 AND window = ?2 AND window_start = ?3"
+            ),
+            params![project_id, window.as_str(), window_start],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    let (tokens_raw, total_usd, total_nature_cost) = row.unwrap_or((0, 0.0, 0.0));
+
+    Ok(WindowUsage {
+        window,
+        window_start,
+        total_tokens: tokens_raw.max(0) as u64,
+        total_usd,
+        total_nature_cost,
+    })
+}
+
+/// Return usage recorded by an execution that was later discarded. Window
+/// usage is returned to the current windows, which the execution recorded
+/// into unless it was discarded after they rolled over.
+pub fn release(
+    conn: &Connection,
+    project_id: &str,
+    policy_version: Option<i64>,
+    delta_tokens: u64,
+    delta_usd: f64,
+    delta_nature_cost: f64,
+) -> Result<(), Error> {
+    let normalized_version = normalize_policy_version(policy_version);
+    let delta_tokens_i64 = i64::try_from(delta_tokens)
+        .map_err(|_| Error::Api("token delta exceeds supported range".to_string()))?;
+
+    conn.execute(
+        concat!(
+            "UPDATE project_usage_ledgers SET ",
+            "total_tokens = MAX(total_tokens - ?3, 0), ",
+            "total_usd = MAX(total_usd - ?4, 0.0), ",
+            "total_nature_cost = MAX(total_nature_cost - ?5, 0.0), ",
+            "updated_at = CURRENT_TIMESTAMP ",
+            "WHERE project_id = ?1 AND policy_version = ?2"
+        ),
+        params![
+            project_id,
+            normalized_version,
+            delta_tokens_i64,
+            delta_usd,
+            delta_nature_cost
+        ],
+    )?;
+
+    for window in BudgetWindow::ALL {
+        conn.execute(
+            concat!(
+                "UPDATE project_usage_windows SET ",
+                "total_tokens = MAX(total_tokens - ?4, 0), ",
+                "total_usd = MAX(total_usd - ?5, 0.0), ",
+                "total_nature_cost = MAX(total_nature_cost - ?6, 0.0), ",
+                "updated_at = CURRENT_TIMESTAMP ",
+                "WHERE project_id = ?1 AND window = ?2 AND window_start = ?3"
+            ),
+            params![
+                project_id,
+                window.as_str(),
+                window.start(Utc::now()).to_string(),
+                delta_tokens_i64,
+                delta_usd,
+                delta_nature_cost
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The project's ledgers, one per policy version it recorded usage under
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<ProjectUsageLedger>, Error> {
+    let mut stmt = conn.prepare(concat!(
+        "SELECT policy_version, total_tokens, total_usd, total_nature_cost, updated_at ",
+        "FROM project_usage_ledgers WHERE project_id = ?1 ORDER BY policy_version"
+    ))?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok(ProjectUsageLedger {
+            project_id: project_id.to_string(),
+            policy_version: row.get(0)?,
+            total_tokens: row.get::<_, i64>(1)?.max(0) as u64,
+            total_usd: row.get(2)?,
+            total_nature_cost: row.get(3)?,
+            updated_at: row.get(4)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Overwrite a ledger's totals, as a reconciliation repair does; window
+/// usage is left as it is
+pub fn set_totals(
+    conn: &Connection,
+    project_id: &str,
+    policy_version: Option<i64>,
+    total_tokens: u64,
+    total_usd: f64,
+    total_nature_cost: f64,
+) -> Result<(), Error> {
+    let total_tokens = i64::try_from(total_tokens)
+        .map_err(|_| Error::Api("token total exceeds supported range".to_string()))?;
+    conn.execute(
+        concat!(
+            "INSERT INTO project_usage_ledgers ",
+            "(project_id, policy_version, total_tokens, total_usd, total_nature_cost) ",
+            "VALUES (?1, ?2, ?3, ?4, ?5) ",
+            "ON CONFLICT(project_id, policy_version) DO UPDATE SET ",
+            "total_tokens = excluded.total_tokens, ",
+            "total_usd = excluded.total_usd, ",
+            "total_nature_cost = excluded.total_nature_cost, ",
+            "updated_at = CURRENT_TIMESTAMP"
+        ),
+        params![
+            project_id,
+            normalize_policy_version(policy_version),
+            total_tokens,
+            total_usd,
+            total_nature_cost
+        ],
+    )?;
+    Ok(())
+}
+
+/// Usage one replay added to the ledger
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayUsage {
+    pub id: String,
+    pub project_id: String,
+    pub run_id: String,
+    pub policy_version: i64,
+    pub total_tokens: u64,
+    pub total_usd: f64,
+    pub total_nature_cost: f64,
+    pub replayed_at: String,
+}
+
+/// Add a replay's usage to the ledger and keep a record of it, since
+/// replays record no checkpoints the usage could be recomputed from
+pub fn record_replay(
+    conn: &Connection,
+    project_id: &str,
+    run_id: &str,
+    policy_version: Option<i64>,
+    delta_tokens: u64,
+    delta_usd: f64,
+    delta_nature_cost: f64,
+) -> Result<ProjectUsageLedger, Error> {
+    let ledger = increment(
+        conn,
+        project_id,
+        policy_version,
+        delta_tokens,
+        delta_usd,
+        delta_nature_cost,
+    )?;
+    insert_replay(
+        conn,
+        &ReplayUsage {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            run_id: run_id.to_string(),
+            policy_version: ledger.policy_version,
+            total_tokens: delta_tokens,
+            total_usd: delta_usd,
+            total_nature_cost: delta_nature_cost,
+            replayed_at: Utc::now().to_rfc3339(),
+        },
+    )?;
+    Ok(ledger)
+}
+
+pub fn insert_replay(conn: &Connection, replay: &ReplayUsage) -> Result<(), Error> {
+    let total_tokens = i64::try_from(replay.total_tokens)
+        .map_err(|_| Error::Api("token total exceeds supported range".to_string()))?;
+    conn.execute(
+        concat!(
+            "INSERT INTO replay_usage (id, project_id, run_id, policy_version, ",
+            "total_tokens, total_usd, total_nature_cost, replayed_at) ",
+            "VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        ),
+        params![
+            &replay.id,
+            &replay.project_id,
+            &replay.run_id,
+            replay.policy_version,
+            total_tokens,
+            replay.total_usd,
+            replay.total_nature_cost,
+            &replay.replayed_at
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn list_replays(conn: &Connection, project_id: &str) -> Result<Vec<ReplayUsage>, Error> {
+    let mut stmt = conn.prepare(concat!(
+        "SELECT id, project_id, run_id, policy_version, total_tokens, total_usd, ",
+        "total_nature_cost, replayed_at FROM replay_usage ",
+        "WHERE project_id = ?1 ORDER BY replayed_at, id"
+    ))?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok(ReplayUsage {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            run_id: row.get(2)?,
+            policy_version: row.get(3)?,
+            total_tokens: row.get::<_, i64>(4)?.max(0) as u64,
+            total_usd: row.get(5)?,
+            total_nature_cost: row.get(6)?,
+            replayed_at: row.get(7)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
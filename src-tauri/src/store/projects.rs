@@ -1,7 +1,19 @@
 // In src-tauri/src/store/projects.rs
 use crate::{Error, Project};
-use chrono::Utc;
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+const PROJECT_COLUMNS: &str = "id, name, created_at, pubkey, archived_at";
+
+fn project_from_row(row: &Row<'_>) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        pubkey: row.get(3)?,
+        archived_at: row.get(4)?,
+    })
+}
 
 pub fn create(conn: &Connection, id: &str, name: &str, pubkey: &str) -> Result<Project, Error> {
     let now = Utc::now();
@@ -14,24 +26,57 @@ pub fn create(conn: &Connection, id: &str, name: &str, pubkey: &str) -> Result<P
         name: name.to_string(),
         created_at: now,
         pubkey: pubkey.to_string(),
+        archived_at: None,
     })
 }
 
+/// Active projects; archived ones are listed by `list_archived`
 pub fn list(conn: &Connection) -> Result<Vec<Project>, Error> {
-    let mut stmt =
-        conn.prepare("SELECT id, name, created_at, pubkey FROM projects ORDER BY created_at DESC")?;
-    let project_iter = stmt.query_map([], |row| {
-        Ok(Project {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            created_at: row.get(2)?,
-            pubkey: row.get(3)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS} FROM projects WHERE archived_at IS NULL ORDER BY created_at DESC"
+    ))?;
+    let project_iter = stmt.query_map([], project_from_row)?;
     let projects = project_iter.collect::<Result<Vec<Project>, _>>()?;
     Ok(projects)
 }
 
+/// Archived projects, most recently archived first
+pub fn list_archived(conn: &Connection) -> Result<Vec<Project>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS} FROM projects
+         WHERE archived_at IS NOT NULL ORDER BY archived_at DESC"
+    ))?;
+    let projects = stmt
+        .query_map([], project_from_row)?
+        .collect::<Result<Vec<Project>, _>>()?;
+    Ok(projects)
+}
+
+/// Projects archived before `cutoff`
+pub fn list_archived_before(
+    conn: &Connection,
+    cutoff: &DateTime<Utc>,
+) -> Result<Vec<Project>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {PROJECT_COLUMNS} FROM projects
+         WHERE archived_at IS NOT NULL AND archived_at < ?1 ORDER BY archived_at ASC"
+    ))?;
+    let projects = stmt
+        .query_map(params![cutoff], project_from_row)?
+        .collect::<Result<Vec<Project>, _>>()?;
+    Ok(projects)
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Option<Project>, Error> {
+    Ok(conn
+        .query_row(
+            &format!("SELECT {PROJECT_COLUMNS} FROM projects WHERE id = ?1"),
+            params![id],
+            project_from_row,
+        )
+        .optional()?)
+}
+
 pub fn rename(conn: &Connection, id: &str, name: &str) -> Result<Project, Error> {
     let affected = conn.execute(
         "UPDATE projects SET name = ?1 WHERE id = ?2",
@@ -40,76 +85,93 @@ pub fn rename(conn: &Connection, id: &str, name: &str) -> Result<Project, Error>
     if affected == 0 {
         return Err(Error::Api(format!("Project {id} not found")));
     }
-    let mut stmt =
-        conn.prepare("SELECT id, name, created_at, pubkey FROM projects WHERE id = ?1")?;
-    let project = stmt.query_row(params![id], |row| {
-        Ok(Project {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            created_at: row.get(2)?,
-            pubkey: row.get(3)?,
-        })
-    })?;
-    Ok(project)
+    get(conn, id)?.ok_or_else(|| Error::Api(format!("Project {id} not found")))
 }
 
-pub fn delete(conn: &mut Connection, id: &str) -> Result<(), Error> {
-    let tx = conn.transaction()?;
+/// Archive the project, or restore it with `None`
+pub fn set_archived_at(
+    conn: &Connection,
+    id: &str,
+    archived_at: Option<&DateTime<Utc>>,
+) -> Result<(), Error> {
+    let affected = conn.execute(
+        "UPDATE projects SET archived_at = ?1 WHERE id = ?2",
+        params![archived_at, id],
+    )?;
+    if affected == 0 {
+        return Err(Error::Api(format!("Project {id} not found")));
+    }
+    Ok(())
+}
 
+/// Delete the project and everything it recorded; callers run this in a
+/// transaction with whatever else the deletion entails
+pub fn delete(conn: &Connection, id: &str) -> Result<(), Error> {
     // Delete policy version history first (foreign key to projects)
-    tx.execute(
+    conn.execute(
         "DELETE FROM policy_versions WHERE project_id = ?1",
         params![id],
     )?;
 
     // Delete usage ledger snapshots for all policy versions
-    tx.execute(
+    conn.execute(
         "DELETE FROM project_usage_ledgers WHERE project_id = ?1",
         params![id],
     )?;
 
     // Delete policies
-    tx.execute("DELETE FROM policies WHERE project_id = ?1", params![id])?;
+    conn.execute("DELETE FROM policies WHERE project_id = ?1", params![id])?;
 
-    tx.execute(
+    conn.execute(
         "DELETE FROM checkpoint_payloads WHERE checkpoint_id IN (SELECT id FROM checkpoints WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1))",
         params![id],
     )?;
 
-    tx.execute(
+    conn.execute(
         "DELETE FROM checkpoint_messages WHERE checkpoint_id IN (SELECT id FROM checkpoints WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1))",
         params![id],
     )?;
 
-    tx.execute(
+    conn.execute(
         "DELETE FROM receipts WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1)",
         params![id],
     )?;
 
-    tx.execute(
+    conn.execute(
         "DELETE FROM checkpoints WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1)",
         params![id],
     )?;
 
+    // Delete run triggers and their event history (foreign key to runs)
+    conn.execute(
+        "DELETE FROM run_trigger_events WHERE trigger_id IN (SELECT id FROM run_triggers WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1))",
+        params![id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM run_triggers WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1)",
+        params![id],
+    )?;
+
+    conn.execute("DELETE FROM run_templates WHERE project_id = ?1", params![id])?;
+
     // Delete run steps (foreign key to runs)
-    tx.execute(
+    conn.execute(
         "DELETE FROM run_steps WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1)",
         params![id],
     )?;
 
-    tx.execute(
+    conn.execute(
         "DELETE FROM run_executions WHERE run_id IN (SELECT id FROM runs WHERE project_id = ?1)",
         params![id],
     )?;
 
-    tx.execute("DELETE FROM runs WHERE project_id = ?1", params![id])?;
+    conn.execute("DELETE FROM runs WHERE project_id = ?1", params![id])?;
 
-    let affected = tx.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
+    let affected = conn.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
     if affected == 0 {
         return Err(Error::Api(format!("Project {id} not found")));
     }
 
-    tx.commit()?;
-
     Ok(())
 }
@@ -0,0 +1,63 @@
+// src-tauri/src/store/prompt_partials.rs
+use crate::Error;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Named prompt fragment a project's template prompts include as `{{> name}}`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPartial {
+    pub project_id: String,
+    pub name: String,
+    pub template: String,
+    pub updated_at: String,
+}
+
+/// Partials of a project, by name
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<PromptPartial>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT project_id, name, template, updated_at FROM prompt_partials
+         WHERE project_id = ?1 ORDER BY name",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok(PromptPartial {
+            project_id: row.get(0)?,
+            name: row.get(1)?,
+            template: row.get(2)?,
+            updated_at: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Partial templates of a project keyed by name, as rendering takes them
+pub fn as_map(conn: &Connection, project_id: &str) -> Result<BTreeMap<String, String>, Error> {
+    Ok(list(conn, project_id)?
+        .into_iter()
+        .map(|partial| (partial.name, partial.template))
+        .collect())
+}
+
+pub fn upsert(conn: &Connection, partial: &PromptPartial) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO prompt_partials (project_id, name, template, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(project_id, name) DO UPDATE SET template = excluded.template, updated_at = excluded.updated_at",
+        params![
+            &partial.project_id,
+            &partial.name,
+            &partial.template,
+            &partial.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove a partial; returns false if it did not exist
+pub fn delete(conn: &Connection, project_id: &str, name: &str) -> Result<bool, Error> {
+    let deleted = conn.execute(
+        "DELETE FROM prompt_partials WHERE project_id = ?1 AND name = ?2",
+        params![project_id, name],
+    )?;
+    Ok(deleted > 0)
+}
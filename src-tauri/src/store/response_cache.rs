@@ -0,0 +1,53 @@
+// src-tauri/src/store/response_cache.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Generation stored under a cache key, with the usage it originally cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub response: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+pub fn get(conn: &Connection, cache_key: &str) -> Result<Option<CachedResponse>, Error> {
+    Ok(conn
+        .query_row(
+            "SELECT response, prompt_tokens, completion_tokens FROM llm_response_cache WHERE cache_key = ?1",
+            params![cache_key],
+            |row| {
+                Ok(CachedResponse {
+                    response: row.get(0)?,
+                    prompt_tokens: row.get::<_, i64>(1)?.max(0) as u64,
+                    completion_tokens: row.get::<_, i64>(2)?.max(0) as u64,
+                })
+            },
+        )
+        .optional()?)
+}
+
+pub fn put(
+    conn: &Connection,
+    cache_key: &str,
+    model: &str,
+    cached: &CachedResponse,
+) -> Result<(), Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO llm_response_cache (cache_key, model, response, prompt_tokens, completion_tokens, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            cache_key,
+            model,
+            &cached.response,
+            cached.prompt_tokens as i64,
+            cached.completion_tokens as i64,
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove every cached generation, returning how many were dropped
+pub fn clear(conn: &Connection) -> Result<usize, Error> {
+    Ok(conn.execute("DELETE FROM llm_response_cache", [])?)
+}
@@ -0,0 +1,92 @@
+// src-tauri/src/store/run_archives.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Cold storage archive of a completed run's payloads and attachments
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunArchive {
+    pub run_id: String,
+    pub archive_path: String,
+    pub archive_sha256: String,
+    pub signature: String,
+    pub archived_at: String,
+    /// Set once the payloads were restored from the archive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rehydrated_at: Option<String>,
+}
+
+impl RunArchive {
+    /// Whether the run's payloads currently live only in the archive
+    pub fn is_cold(&self) -> bool {
+        self.rehydrated_at.is_none()
+    }
+}
+
+/// Directory archives are written to; `None` uses the default under the app data dir
+pub fn archive_dir(conn: &Connection) -> Result<Option<String>, Error> {
+    Ok(conn.query_row(
+        "SELECT archive_dir FROM cold_storage_settings WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn set_archive_dir(conn: &Connection, archive_dir: Option<&str>) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE cold_storage_settings SET archive_dir = ?1 WHERE id = 1",
+        params![archive_dir],
+    )?;
+    Ok(())
+}
+
+pub fn get(conn: &Connection, run_id: &str) -> Result<Option<RunArchive>, Error> {
+    Ok(conn
+        .query_row(
+            "SELECT run_id, archive_path, archive_sha256, signature, archived_at, rehydrated_at
+             FROM run_archives WHERE run_id = ?1",
+            params![run_id],
+            |row| {
+                Ok(RunArchive {
+                    run_id: row.get(0)?,
+                    archive_path: row.get(1)?,
+                    archive_sha256: row.get(2)?,
+                    signature: row.get(3)?,
+                    archived_at: row.get(4)?,
+                    rehydrated_at: row.get(5)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Whether the run's payloads were archived and not yet rehydrated
+pub fn is_cold(conn: &Connection, run_id: &str) -> Result<bool, Error> {
+    Ok(get(conn, run_id)?.is_some_and(|archive| archive.is_cold()))
+}
+
+/// Record a new archive of the run, replacing any earlier one
+pub fn record(conn: &Connection, archive: &RunArchive) -> Result<(), Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO run_archives (run_id, archive_path, archive_sha256, signature, archived_at, rehydrated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            &archive.run_id,
+            &archive.archive_path,
+            &archive.archive_sha256,
+            &archive.signature,
+            &archive.archived_at,
+            &archive.rehydrated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn mark_rehydrated(conn: &Connection, run_id: &str, rehydrated_at: &str) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE run_archives SET rehydrated_at = ?2 WHERE run_id = ?1",
+        params![run_id, rehydrated_at],
+    )?;
+    Ok(())
+}
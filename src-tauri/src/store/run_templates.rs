@@ -0,0 +1,121 @@
+// src-tauri/src/store/run_templates.rs
+use crate::orchestrator::{RunProofMode, RunStepTemplate};
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Run settings and steps captured by a template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTemplateSpec {
+    #[serde(default)]
+    pub proof_mode: RunProofMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+    pub seed: u64,
+    pub token_budget: u64,
+    pub default_model: String,
+    pub steps: Vec<RunStepTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTemplate {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub variables: Vec<String>,
+    pub spec: RunTemplateSpec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_run_id: Option<String>,
+    pub created_at: String,
+}
+
+const TEMPLATE_COLUMNS: &str =
+    "id, project_id, name, description, variables_json, spec_json, source_run_id, created_at";
+
+fn template_from_row(row: &Row<'_>) -> rusqlite::Result<RunTemplate> {
+    let variables_json: String = row.get(4)?;
+    let spec_json: String = row.get(5)?;
+    let variables = serde_json::from_str(&variables_json).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(err))
+    })?;
+    let spec = serde_json::from_str(&spec_json).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(err))
+    })?;
+    Ok(RunTemplate {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        variables,
+        spec,
+        source_run_id: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+pub fn create(
+    conn: &Connection,
+    project_id: &str,
+    name: &str,
+    description: Option<&str>,
+    variables: &[String],
+    spec: &RunTemplateSpec,
+    source_run_id: Option<&str>,
+) -> Result<RunTemplate, Error> {
+    let id = Uuid::new_v4().to_string();
+    let variables_json = serde_json::to_string(variables)
+        .map_err(|err| Error::Api(format!("failed to serialize template variables: {err}")))?;
+    let spec_json = serde_json::to_string(spec)
+        .map_err(|err| Error::Api(format!("failed to serialize template spec: {err}")))?;
+    let created_at = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO run_templates (id, project_id, name, description, variables_json, spec_json, source_run_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            &id,
+            project_id,
+            name,
+            description,
+            &variables_json,
+            &spec_json,
+            source_run_id,
+            &created_at
+        ],
+    )?;
+
+    get(conn, &id)?.ok_or_else(|| Error::Api(format!("template {id} not found after insert")))
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Option<RunTemplate>, Error> {
+    let sql = format!("SELECT {TEMPLATE_COLUMNS} FROM run_templates WHERE id = ?1");
+    Ok(conn
+        .query_row(&sql, params![id], template_from_row)
+        .optional()?)
+}
+
+pub fn list_for_project(conn: &Connection, project_id: &str) -> Result<Vec<RunTemplate>, Error> {
+    let sql = format!(
+        "SELECT {TEMPLATE_COLUMNS} FROM run_templates WHERE project_id = ?1 ORDER BY created_at ASC"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![project_id], template_from_row)?;
+    let mut templates = Vec::new();
+    for row in rows {
+        templates.push(row?);
+    }
+    Ok(templates)
+}
+
+pub fn delete(conn: &Connection, id: &str) -> Result<(), Error> {
+    let affected = conn.execute("DELETE FROM run_templates WHERE id = ?1", params![id])?;
+    if affected == 0 {
+        return Err(Error::Api(format!("template {id} not found")));
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+// src-tauri/src/store/run_triggers.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const KIND_FILE_DROP: &str = "file_drop";
+
+pub const EVENT_STARTED: &str = "started";
+pub const EVENT_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTrigger {
+    pub id: String,
+    pub run_id: String,
+    pub kind: String,
+    pub watch_path: String,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTriggerEvent {
+    pub id: String,
+    pub trigger_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_execution_id: Option<String>,
+    pub source_name: String,
+    pub payload_sha256: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub received_at: String,
+}
+
+const TRIGGER_COLUMNS: &str = "id, run_id, kind, watch_path, enabled, created_at";
+
+fn trigger_from_row(row: &Row<'_>) -> rusqlite::Result<RunTrigger> {
+    Ok(RunTrigger {
+        id: row.get(0)?,
+        run_id: row.get(1)?,
+        kind: row.get(2)?,
+        watch_path: row.get(3)?,
+        enabled: row.get::<_, i64>(4)? != 0,
+        created_at: row.get(5)?,
+    })
+}
+
+pub fn create(
+    conn: &Connection,
+    run_id: &str,
+    kind: &str,
+    watch_path: &str,
+) -> Result<RunTrigger, Error> {
+    if kind != KIND_FILE_DROP {
+        return Err(Error::Api(format!("unsupported trigger kind '{kind}'")));
+    }
+
+    let run_exists: Option<()> = conn
+        .query_row("SELECT 1 FROM runs WHERE id = ?1", params![run_id], |_| Ok(()))
+        .optional()?;
+    if run_exists.is_none() {
+        return Err(Error::Api(format!("run {run_id} not found")));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO run_triggers (id, run_id, kind, watch_path) VALUES (?1, ?2, ?3, ?4)",
+        params![&id, run_id, kind, watch_path],
+    )?;
+
+    get(conn, &id)?.ok_or_else(|| Error::Api(format!("trigger {id} not found after insert")))
+}
+
+pub fn get(conn: &Connection, id: &str) -> Result<Option<RunTrigger>, Error> {
+    let sql = format!("SELECT {TRIGGER_COLUMNS} FROM run_triggers WHERE id = ?1");
+    Ok(conn
+        .query_row(&sql, params![id], trigger_from_row)
+        .optional()?)
+}
+
+pub fn list_for_run(conn: &Connection, run_id: &str) -> Result<Vec<RunTrigger>, Error> {
+    let sql = format!(
+        "SELECT {TRIGGER_COLUMNS} FROM run_triggers WHERE run_id = ?1 ORDER BY created_at ASC"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![run_id], trigger_from_row)?;
+    let mut triggers = Vec::new();
+    for row in rows {
+        triggers.push(row?);
+    }
+    Ok(triggers)
+}
+
+/// Enabled triggers of the given kind, leaving out those of archived projects
+pub fn list_enabled(conn: &Connection, kind: &str) -> Result<Vec<RunTrigger>, Error> {
+    let sql = format!(
+        "SELECT {TRIGGER_COLUMNS} FROM run_triggers
+         WHERE kind = ?1 AND enabled = 1
+           AND run_id NOT IN (SELECT r.id FROM runs r JOIN projects p ON p.id = r.project_id
+                              WHERE p.archived_at IS NOT NULL)
+         ORDER BY created_at ASC"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![kind], trigger_from_row)?;
+    let mut triggers = Vec::new();
+    for row in rows {
+        triggers.push(row?);
+    }
+    Ok(triggers)
+}
+
+pub fn set_enabled(conn: &Connection, id: &str, enabled: bool) -> Result<RunTrigger, Error> {
+    let affected = conn.execute(
+        "UPDATE run_triggers SET enabled = ?1 WHERE id = ?2",
+        params![enabled as i64, id],
+    )?;
+    if affected == 0 {
+        return Err(Error::Api(format!("trigger {id} not found")));
+    }
+    get(conn, id)?.ok_or_else(|| Error::Api(format!("trigger {id} not found")))
+}
+
+pub fn delete(conn: &mut Connection, id: &str) -> Result<(), Error> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "DELETE FROM run_trigger_events WHERE trigger_id = ?1",
+        params![id],
+    )?;
+    let affected = tx.execute("DELETE FROM run_triggers WHERE id = ?1", params![id])?;
+    if affected == 0 {
+        return Err(Error::Api(format!("trigger {id} not found")));
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn record_event(
+    conn: &Connection,
+    trigger_id: &str,
+    run_execution_id: Option<&str>,
+    source_name: &str,
+    payload_sha256: &str,
+    error: Option<&str>,
+) -> Result<RunTriggerEvent, Error> {
+    let id = Uuid::new_v4().to_string();
+    let status = if error.is_some() {
+        EVENT_FAILED
+    } else {
+        EVENT_STARTED
+    };
+    let received_at = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO run_trigger_events (id, trigger_id, run_execution_id, source_name, payload_sha256, status, error, received_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            &id,
+            trigger_id,
+            run_execution_id,
+            source_name,
+            payload_sha256,
+            status,
+            error,
+            &received_at
+        ],
+    )?;
+
+    Ok(RunTriggerEvent {
+        id,
+        trigger_id: trigger_id.to_string(),
+        run_execution_id: run_execution_id.map(str::to_string),
+        source_name: source_name.to_string(),
+        payload_sha256: payload_sha256.to_string(),
+        status: status.to_string(),
+        error: error.map(str::to_string),
+        received_at,
+    })
+}
+
+pub fn list_events(conn: &Connection, trigger_id: &str) -> Result<Vec<RunTriggerEvent>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, trigger_id, run_execution_id, source_name, payload_sha256, status, error, received_at
+         FROM run_trigger_events WHERE trigger_id = ?1 ORDER BY received_at DESC",
+    )?;
+    let rows = stmt.query_map(params![trigger_id], |row| {
+        Ok(RunTriggerEvent {
+            id: row.get(0)?,
+            trigger_id: row.get(1)?,
+            run_execution_id: row.get(2)?,
+            source_name: row.get(3)?,
+            payload_sha256: row.get(4)?,
+            status: row.get(5)?,
+            error: row.get(6)?,
+            received_at: row.get(7)?,
+        })
+    })?;
+    let mut events = Vec::new();
+    for row in rows {
+        events.push(row?);
+    }
+    Ok(events)
+}
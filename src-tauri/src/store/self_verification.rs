@@ -0,0 +1,191 @@
+// src-tauri/src/store/self_verification.rs
+use crate::Error;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+pub const TARGET_CAR_FILE: &str = "car_file";
+pub const TARGET_CHECKPOINT_CHAIN: &str = "checkpoint_chain";
+
+pub const STATUS_PASSED: &str = "passed";
+pub const STATUS_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfVerificationSettings {
+    pub enabled: bool,
+    /// Hour of the day (UTC) the nightly pass starts
+    pub hour_utc: u32,
+    /// Receipts and executions created this many days back are re-verified
+    pub lookback_days: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<String>,
+}
+
+/// Outcome of re-verifying one CAR file or checkpoint chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationRecord {
+    pub id: String,
+    pub pass_id: String,
+    pub target_kind: String,
+    /// Receipt id for CAR files, run execution id for checkpoint chains
+    pub target_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// Failed without having failed the previous check of the same target
+    pub new_failure: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acknowledged_at: Option<String>,
+    pub verified_at: String,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<SelfVerificationSettings, Error> {
+    Ok(conn.query_row(
+        "SELECT enabled, hour_utc, lookback_days, last_run_at FROM self_verification_settings WHERE id = 1",
+        [],
+        |row| {
+            Ok(SelfVerificationSettings {
+                enabled: row.get::<_, i64>(0)? != 0,
+                hour_utc: row.get::<_, i64>(1)?.clamp(0, 23) as u32,
+                lookback_days: row.get::<_, i64>(2)?.max(0) as u32,
+                last_run_at: row.get(3)?,
+            })
+        },
+    )?)
+}
+
+pub fn update_settings(
+    conn: &Connection,
+    enabled: bool,
+    hour_utc: u32,
+    lookback_days: u32,
+) -> Result<SelfVerificationSettings, Error> {
+    if hour_utc > 23 {
+        return Err(Error::Api(format!(
+            "hour_utc must be between 0 and 23, got {hour_utc}"
+        )));
+    }
+    if lookback_days == 0 {
+        return Err(Error::Api("lookback_days must be at least 1".to_string()));
+    }
+    conn.execute(
+        "UPDATE self_verification_settings SET enabled = ?1, hour_utc = ?2, lookback_days = ?3 WHERE id = 1",
+        params![enabled, hour_utc, lookback_days],
+    )?;
+    get_settings(conn)
+}
+
+pub fn mark_run(conn: &Connection, run_at: &str) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE self_verification_settings SET last_run_at = ?1 WHERE id = 1",
+        params![run_at],
+    )?;
+    Ok(())
+}
+
+const COLUMNS: &str = "id, pass_id, target_kind, target_id, project_id, run_id, status, details, new_failure, acknowledged_at, verified_at";
+
+fn from_row(row: &Row<'_>) -> rusqlite::Result<VerificationRecord> {
+    Ok(VerificationRecord {
+        id: row.get(0)?,
+        pass_id: row.get(1)?,
+        target_kind: row.get(2)?,
+        target_id: row.get(3)?,
+        project_id: row.get(4)?,
+        run_id: row.get(5)?,
+        status: row.get(6)?,
+        details: row.get(7)?,
+        new_failure: row.get::<_, i64>(8)? != 0,
+        acknowledged_at: row.get(9)?,
+        verified_at: row.get(10)?,
+    })
+}
+
+pub fn insert(conn: &Connection, record: &VerificationRecord) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO verification_history (id, pass_id, target_kind, target_id, project_id, run_id, status, details, new_failure, acknowledged_at, verified_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            &record.id,
+            &record.pass_id,
+            &record.target_kind,
+            &record.target_id,
+            &record.project_id,
+            &record.run_id,
+            &record.status,
+            &record.details,
+            record.new_failure,
+            &record.acknowledged_at,
+            &record.verified_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Status of the most recent check of a target, if it was checked before
+pub fn last_status(
+    conn: &Connection,
+    target_kind: &str,
+    target_id: &str,
+) -> Result<Option<String>, Error> {
+    Ok(conn
+        .query_row(
+            "SELECT status FROM verification_history
+             WHERE target_kind = ?1 AND target_id = ?2
+             ORDER BY verified_at DESC, rowid DESC LIMIT 1",
+            params![target_kind, target_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Most recent results first
+pub fn list(conn: &Connection, limit: u32) -> Result<Vec<VerificationRecord>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM verification_history
+         ORDER BY verified_at DESC, rowid DESC LIMIT ?1"
+    ))?;
+    let rows = stmt.query_map(params![limit], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// New failures, newest first; `pending_only` leaves out acknowledged ones
+pub fn list_alerts(
+    conn: &Connection,
+    pending_only: bool,
+) -> Result<Vec<VerificationRecord>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {COLUMNS} FROM verification_history
+         WHERE new_failure = 1 AND (?1 = 0 OR acknowledged_at IS NULL)
+         ORDER BY verified_at DESC, rowid DESC"
+    ))?;
+    let rows = stmt.query_map(params![pending_only], from_row)?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+pub fn acknowledge(
+    conn: &Connection,
+    id: &str,
+    acknowledged_at: &str,
+) -> Result<VerificationRecord, Error> {
+    let updated = conn.execute(
+        "UPDATE verification_history SET acknowledged_at = ?1
+         WHERE id = ?2 AND new_failure = 1 AND acknowledged_at IS NULL",
+        params![acknowledged_at, id],
+    )?;
+    if updated == 0 {
+        return Err(Error::Api(format!(
+            "verification alert {id} not found or already acknowledged"
+        )));
+    }
+    Ok(conn.query_row(
+        &format!("SELECT {COLUMNS} FROM verification_history WHERE id = ?1"),
+        params![id],
+        from_row,
+    )?)
+}
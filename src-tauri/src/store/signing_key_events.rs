@@ -0,0 +1,61 @@
+// src-tauri/src/store/signing_key_events.rs
+use crate::Error;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Change to a project's signing key, kept so verifiers can tell which key
+/// signed which checkpoints
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningKeyEvent {
+    pub id: String,
+    pub project_id: String,
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_pubkey: Option<String>,
+    pub pubkey: String,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    pub created_at: String,
+}
+
+pub fn record(conn: &Connection, event: &SigningKeyEvent) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO signing_key_events (id, project_id, event, previous_pubkey, pubkey, reason, run_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            &event.id,
+            &event.project_id,
+            &event.event,
+            &event.previous_pubkey,
+            &event.pubkey,
+            &event.reason,
+            &event.run_id,
+            &event.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Key events of a project, oldest first
+pub fn list(conn: &Connection, project_id: &str) -> Result<Vec<SigningKeyEvent>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, event, previous_pubkey, pubkey, reason, run_id, created_at
+         FROM signing_key_events WHERE project_id = ?1
+         ORDER BY created_at ASC",
+    )?;
+    let rows = stmt.query_map(params![project_id], |row| {
+        Ok(SigningKeyEvent {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            event: row.get(2)?,
+            previous_pubkey: row.get(3)?,
+            pubkey: row.get(4)?,
+            reason: row.get(5)?,
+            run_id: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,88 @@
+// src-tauri/src/store/telemetry.rs
+use crate::Error;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_exported_at: Option<String>,
+}
+
+pub fn get_settings(conn: &Connection) -> Result<TelemetrySettings, Error> {
+    Ok(conn.query_row(
+        "SELECT enabled, enabled_at, last_exported_at FROM telemetry_settings WHERE id = 1",
+        [],
+        |row| {
+            Ok(TelemetrySettings {
+                enabled: row.get::<_, i64>(0)? != 0,
+                enabled_at: row.get(1)?,
+                last_exported_at: row.get(2)?,
+            })
+        },
+    )?)
+}
+
+/// Turn collection on or off. Turning it off discards everything collected.
+pub fn set_enabled(conn: &Connection, enabled: bool) -> Result<TelemetrySettings, Error> {
+    if enabled {
+        conn.execute(
+            "UPDATE telemetry_settings SET enabled = 1,
+                 enabled_at = COALESCE(enabled_at, ?1)
+             WHERE id = 1",
+            params![chrono::Utc::now().to_rfc3339()],
+        )?;
+    } else {
+        conn.execute(
+            "UPDATE telemetry_settings SET enabled = 0, enabled_at = NULL, last_exported_at = NULL
+             WHERE id = 1",
+            [],
+        )?;
+        conn.execute("DELETE FROM telemetry_counters", [])?;
+    }
+    get_settings(conn)
+}
+
+/// Add to the given counters, but only while collection is enabled
+pub fn increment_if_enabled(conn: &Connection, metrics: &[&str]) -> Result<(), Error> {
+    if !get_settings(conn)?.enabled {
+        return Ok(());
+    }
+    for metric in metrics {
+        conn.execute(
+            "INSERT INTO telemetry_counters (metric, count) VALUES (?1, 1)
+             ON CONFLICT(metric) DO UPDATE SET count = count + 1",
+            params![metric],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn counters(conn: &Connection) -> Result<BTreeMap<String, u64>, Error> {
+    let mut stmt = conn.prepare("SELECT metric, count FROM telemetry_counters")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+    let mut counters = BTreeMap::new();
+    for row in rows {
+        let (metric, count) = row?;
+        counters.insert(metric, count.max(0) as u64);
+    }
+    Ok(counters)
+}
+
+pub fn mark_exported(conn: &Connection, exported_at: &str) -> Result<(), Error> {
+    conn.execute(
+        "UPDATE telemetry_settings SET last_exported_at = ?1 WHERE id = 1",
+        params![exported_at],
+    )?;
+    Ok(())
+}
+
+pub fn reset_counters(conn: &Connection) -> Result<(), Error> {
+    conn.execute("DELETE FROM telemetry_counters", [])?;
+    Ok(())
+}
@@ -0,0 +1,243 @@
+// src-tauri/src/telemetry.rs
+//!
+//! Telemetry: opt-in, locally aggregated usage counts
+//!
+//! Nothing is collected until the user enables telemetry, and nothing ever
+//! leaves the machine on its own. While enabled, the app only increments a
+//! fixed set of counters:
+//!
+//! - runs completed and failed
+//! - executed steps per step type
+//! - passed and failed verifications per kind (replay, CAR import, challenge)
+//!
+//! No prompts, outputs, hashes, identifiers, paths or keys are recorded. The
+//! report built from the counters is exported as JSON so it can be reviewed
+//! before the user decides to share it. Disabling telemetry deletes all
+//! collected counts.
+
+use crate::store::telemetry::{self, TelemetrySettings};
+use crate::DbPool;
+use anyhow::Context;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Step types reported by name; anything else is counted as `other`
+const KNOWN_STEP_TYPES: &[&str] = &[
+    "llm",
+    "ingest",
+    "document_ingestion",
+    "summarize",
+    "prompt",
+    "map",
+    "branch",
+    "plugin",
+    "agent",
+    "finetune",
+    "embed",
+    "evaluate",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationKind {
+    Replay,
+    CarImport,
+    Challenge,
+}
+
+impl VerificationKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            VerificationKind::Replay => "replay",
+            VerificationKind::CarImport => "car_import",
+            VerificationKind::Challenge => "challenge",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationStats {
+    pub passed: u64,
+    pub failed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pass_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    /// Calendar dates only, to keep the report coarse
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collecting_since: Option<String>,
+    pub generated_on: String,
+    pub app_version: String,
+    pub runs_completed: u64,
+    pub runs_failed: u64,
+    pub step_types: BTreeMap<String, u64>,
+    pub verifications: BTreeMap<String, VerificationStats>,
+}
+
+fn step_type_bucket(step_type: &str) -> &str {
+    KNOWN_STEP_TYPES
+        .iter()
+        .copied()
+        .find(|known| *known == step_type)
+        .unwrap_or("other")
+}
+
+fn record(conn: &Connection, metrics: &[&str]) {
+    // Telemetry must never get in the way of the operation being counted
+    if let Err(err) = telemetry::increment_if_enabled(conn, metrics) {
+        eprintln!("failed to record telemetry: {err}");
+    }
+}
+
+/// Count a finished run execution and the types of the steps it ran
+pub fn record_run<'a>(
+    conn: &Connection,
+    succeeded: bool,
+    step_types: impl IntoIterator<Item = &'a str>,
+) {
+    let step_metrics: Vec<String> = step_types
+        .into_iter()
+        .map(|step_type| format!("steps.{}", step_type_bucket(step_type)))
+        .collect();
+    let mut metrics = vec![if succeeded {
+        "runs.completed"
+    } else {
+        "runs.failed"
+    }];
+    if succeeded {
+        metrics.extend(step_metrics.iter().map(String::as_str));
+    }
+    record(conn, &metrics);
+}
+
+pub fn record_verification(conn: &Connection, kind: VerificationKind, passed: bool) {
+    let outcome = if passed { "passed" } else { "failed" };
+    record(conn, &[&format!("verify.{}.{}", kind.as_str(), outcome)]);
+}
+
+/// Same as `record_verification`, for callers that only hold the pool
+pub fn record_verification_with_pool(pool: &DbPool, kind: VerificationKind, passed: bool) {
+    match pool.get() {
+        Ok(conn) => record_verification(&conn, kind, passed),
+        Err(err) => eprintln!("failed to record telemetry: {err}"),
+    }
+}
+
+pub fn build_report(conn: &Connection) -> anyhow::Result<TelemetryReport> {
+    let settings = telemetry::get_settings(conn)?;
+    let counters = telemetry::counters(conn)?;
+    Ok(report_from_counters(&settings, &counters))
+}
+
+fn report_from_counters(
+    settings: &TelemetrySettings,
+    counters: &BTreeMap<String, u64>,
+) -> TelemetryReport {
+    let count = |metric: &str| counters.get(metric).copied().unwrap_or(0);
+
+    let step_types = counters
+        .iter()
+        .filter_map(|(metric, value)| {
+            metric
+                .strip_prefix("steps.")
+                .map(|step_type| (step_type.to_string(), *value))
+        })
+        .collect();
+
+    let verifications = [
+        VerificationKind::Replay,
+        VerificationKind::CarImport,
+        VerificationKind::Challenge,
+    ]
+    .iter()
+    .map(|kind| {
+        let passed = count(&format!("verify.{}.passed", kind.as_str()));
+        let failed = count(&format!("verify.{}.failed", kind.as_str()));
+        let total = passed + failed;
+        let stats = VerificationStats {
+            passed,
+            failed,
+            pass_rate: (total > 0).then(|| passed as f64 / total as f64),
+        };
+        (kind.as_str().to_string(), stats)
+    })
+    .collect();
+
+    TelemetryReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        collecting_since: settings
+            .enabled_at
+            .as_deref()
+            .map(|timestamp| timestamp.chars().take(10).collect()),
+        generated_on: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        runs_completed: count("runs.completed"),
+        runs_failed: count("runs.failed"),
+        step_types,
+        verifications,
+    }
+}
+
+/// Write the report as pretty JSON for review and remember when it was exported
+pub fn export_report(conn: &Connection, output_path: &Path) -> anyhow::Result<TelemetryReport> {
+    let report = build_report(conn)?;
+    let json = serde_json::to_string_pretty(&report)?;
+    std::fs::write(output_path, json)
+        .with_context(|| format!("failed to write telemetry report to {}", output_path.display()))?;
+    telemetry::mark_exported(conn, &chrono::Utc::now().to_rfc3339())?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::store::migrations::runner().to_latest(&mut conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn nothing_is_counted_until_enabled() {
+        let conn = test_conn();
+        record_run(&conn, true, ["llm"]);
+        assert!(telemetry::counters(&conn).unwrap().is_empty());
+
+        telemetry::set_enabled(&conn, true).unwrap();
+        record_run(&conn, true, ["llm", "branch", "custom"]);
+        record_run(&conn, false, ["llm"]);
+        record_verification(&conn, VerificationKind::Replay, true);
+        record_verification(&conn, VerificationKind::Replay, false);
+        record_verification(&conn, VerificationKind::Replay, true);
+
+        let report = build_report(&conn).unwrap();
+        assert_eq!(report.runs_completed, 1);
+        assert_eq!(report.runs_failed, 1);
+        assert_eq!(report.step_types.get("llm"), Some(&1));
+        assert_eq!(report.step_types.get("other"), Some(&1));
+        let replay = &report.verifications["replay"];
+        assert_eq!((replay.passed, replay.failed), (2, 1));
+        assert!((replay.pass_rate.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.verifications["challenge"].pass_rate, None);
+    }
+
+    #[test]
+    fn disabling_discards_collected_counts() {
+        let conn = test_conn();
+        telemetry::set_enabled(&conn, true).unwrap();
+        record_run(&conn, true, ["llm"]);
+        let settings = telemetry::set_enabled(&conn, false).unwrap();
+        assert!(!settings.enabled);
+        assert!(settings.enabled_at.is_none());
+        assert!(telemetry::counters(&conn).unwrap().is_empty());
+    }
+}
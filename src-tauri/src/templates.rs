@@ -0,0 +1,273 @@
+// src-tauri/src/templates.rs
+//!
+//! Run Templates: save a configured run and instantiate copies of it
+//!
+//! A template captures a run's settings and steps. Declared variables appear
+//! as `{{name}}` placeholders in step prompts and in string values of step
+//! configs (e.g. ingest source paths) and must all be supplied when the
+//! template is instantiated. Placeholders that are not declared are left in
+//! place, so run parameters filled in by triggers keep working.
+
+use crate::orchestrator::{self, RunStepTemplate, StepConfig};
+use crate::store::run_templates::{self, RunTemplate, RunTemplateSpec};
+use crate::DbPool;
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{([A-Za-z_][A-Za-z0-9_]*)\}\}").expect("valid placeholder regex"));
+static VARIABLE_NAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").expect("valid variable name regex"));
+
+/// Placeholder names owned by the map step and unavailable as template variables
+const RESERVED_VARIABLES: &[&str] = &["item", "index"];
+
+/// Names of every `{{name}}` placeholder in a piece of text
+pub fn find_placeholders(text: &str) -> BTreeSet<String> {
+    PLACEHOLDER
+        .captures_iter(text)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Placeholders used anywhere in a template's step prompts and configs
+pub fn spec_placeholders(spec: &RunTemplateSpec) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for step in &spec.steps {
+        if let Some(prompt) = &step.prompt {
+            names.extend(find_placeholders(prompt));
+        }
+        if let Some(config_json) = &step.config_json {
+            names.extend(find_placeholders(config_json));
+        }
+    }
+    names
+}
+
+/// Fill declared variables into a step's prompt and config string values
+pub fn render_step(step: &RunStepTemplate, values: &Map<String, Value>) -> RunStepTemplate {
+    let mut rendered = step.clone();
+    if let Some(prompt) = &step.prompt {
+        rendered.prompt = Some(orchestrator::apply_run_parameters(prompt, values));
+    }
+    if let Some(config_json) = &step.config_json {
+        // Substitute inside parsed string values so inserted text is JSON-escaped
+        rendered.config_json = Some(match serde_json::from_str::<Value>(config_json) {
+            Ok(mut config) => {
+                render_value(&mut config, values);
+                config.to_string()
+            }
+            Err(_) => orchestrator::apply_run_parameters(config_json, values),
+        });
+    }
+    rendered
+}
+
+fn render_value(value: &mut Value, values: &Map<String, Value>) {
+    match value {
+        Value::String(text) => *text = orchestrator::apply_run_parameters(text, values),
+        Value::Array(items) => items.iter_mut().for_each(|item| render_value(item, values)),
+        Value::Object(map) => map.values_mut().for_each(|item| render_value(item, values)),
+        _ => {}
+    }
+}
+
+fn check_variables(variables: &[String], spec: &RunTemplateSpec) -> anyhow::Result<()> {
+    let used = spec_placeholders(spec);
+    let mut seen = BTreeSet::new();
+    for name in variables {
+        if !VARIABLE_NAME.is_match(name) {
+            return Err(anyhow!("invalid template variable name '{}'", name));
+        }
+        if RESERVED_VARIABLES.contains(&name.as_str()) {
+            return Err(anyhow!("'{}' is reserved for map steps", name));
+        }
+        if !seen.insert(name.as_str()) {
+            return Err(anyhow!("template variable '{}' is declared twice", name));
+        }
+        if !used.contains(name) {
+            return Err(anyhow!(
+                "template variable '{}' does not appear as {{{{{}}}}} in any step",
+                name,
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Save a run's current configuration as a template
+pub fn save_run_template(
+    pool: &DbPool,
+    run_id: &str,
+    name: &str,
+    description: Option<&str>,
+    variables: &[String],
+) -> anyhow::Result<RunTemplate> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("template name cannot be empty"));
+    }
+
+    let conn = pool.get()?;
+    let run = orchestrator::load_stored_run(&conn, run_id)?;
+    if run.steps.is_empty() {
+        return Err(anyhow!("Cannot save a run with no steps as a template"));
+    }
+
+    let spec = RunTemplateSpec {
+        proof_mode: run.proof_mode.unwrap_or_default(),
+        epsilon: run.epsilon,
+        seed: run.seed,
+        token_budget: run.token_budget,
+        default_model: run.default_model.clone(),
+        steps: orchestrator::step_templates(&run.steps),
+    };
+    check_variables(variables, &spec)?;
+
+    let description = description.map(str::trim).filter(|text| !text.is_empty());
+    Ok(run_templates::create(
+        &conn,
+        &run.project_id,
+        name,
+        description,
+        variables,
+        &spec,
+        Some(run_id),
+    )?)
+}
+
+/// Create a new run from a template, filling in every declared variable
+pub fn instantiate_run_template(
+    pool: &DbPool,
+    template_id: &str,
+    run_name: Option<&str>,
+    values: &Map<String, Value>,
+) -> anyhow::Result<String> {
+    let template = {
+        let conn = pool.get()?;
+        run_templates::get(&conn, template_id)?
+            .ok_or_else(|| anyhow!("template {} not found", template_id))?
+    };
+
+    let missing: Vec<&str> = template
+        .variables
+        .iter()
+        .filter(|name| !values.contains_key(name.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!("missing template variables: {}", missing.join(", ")));
+    }
+    let unknown: Vec<&str> = values
+        .keys()
+        .filter(|key| !template.variables.contains(key))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(anyhow!("unknown template variables: {}", unknown.join(", ")));
+    }
+
+    let steps: Vec<RunStepTemplate> = template
+        .spec
+        .steps
+        .iter()
+        .map(|step| render_step(step, values))
+        .collect();
+    for step in &steps {
+        if let Some(config_json) = &step.config_json {
+            if let Ok(config) = serde_json::from_str::<StepConfig>(config_json) {
+                config.validate().map_err(|err| {
+                    anyhow!(
+                        "template step {} is invalid: {}",
+                        step.order_index.unwrap_or_default(),
+                        err
+                    )
+                })?;
+            }
+        }
+    }
+
+    let name = run_name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&template.name);
+    orchestrator::create_run(
+        pool,
+        &template.project_id,
+        name,
+        template.spec.proof_mode,
+        template.spec.epsilon,
+        template.spec.seed,
+        template.spec.token_budget,
+        &template.spec.default_model,
+        steps,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::RunProofMode;
+    use serde_json::json;
+
+    fn spec_with(prompt: &str, config_json: Option<&str>) -> RunTemplateSpec {
+        RunTemplateSpec {
+            proof_mode: RunProofMode::Exact,
+            epsilon: None,
+            seed: 7,
+            token_budget: 1_000,
+            default_model: "stub-model".to_string(),
+            steps: vec![RunStepTemplate {
+                step_type: "llm".to_string(),
+                model: Some("stub-model".to_string()),
+                prompt: Some(prompt.to_string()),
+                token_budget: 100,
+                proof_mode: RunProofMode::Exact,
+                epsilon: None,
+                config_json: config_json.map(str::to_string),
+                order_index: Some(0),
+                checkpoint_type: "Step".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn finds_placeholders_in_prompts_and_configs() {
+        let spec = spec_with(
+            "Summarize {{topic}} for {{ audience }}",
+            Some(r#"{"stepType":"ingest","sourcePath":"{{input_dir}}/paper.pdf","format":"pdf"}"#),
+        );
+        let names: Vec<String> = spec_placeholders(&spec).into_iter().collect();
+        assert_eq!(names, vec!["input_dir", "topic"]);
+    }
+
+    #[test]
+    fn render_escapes_values_inside_config_json() {
+        let spec = spec_with(
+            "About {{topic}}, keep {{later}}",
+            Some(r#"{"sourcePath":"{{input_dir}}/a.pdf"}"#),
+        );
+        let mut values = Map::new();
+        values.insert("topic".to_string(), json!("rust"));
+        values.insert("input_dir".to_string(), json!("C:\\data \"q1\""));
+
+        let rendered = render_step(&spec.steps[0], &values);
+        assert_eq!(rendered.prompt.as_deref(), Some("About rust, keep {{later}}"));
+        let config: Value = serde_json::from_str(rendered.config_json.as_deref().unwrap()).unwrap();
+        assert_eq!(config["sourcePath"], json!("C:\\data \"q1\"/a.pdf"));
+    }
+
+    #[test]
+    fn declared_variables_must_be_used_and_unreserved() {
+        let spec = spec_with("Top {{limit}} items", None);
+        assert!(check_variables(&["limit".to_string()], &spec).is_ok());
+        assert!(check_variables(&["missing".to_string()], &spec).is_err());
+        assert!(check_variables(&["item".to_string()], &spec).is_err());
+        assert!(check_variables(&["bad name".to_string()], &spec).is_err());
+        assert!(check_variables(&["limit".to_string(), "limit".to_string()], &spec).is_err());
+    }
+}
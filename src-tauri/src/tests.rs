@@ -58,6 +58,108 @@ fn create_project_stores_secret_for_later_use() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn passphrase_sealed_key_leaves_no_plaintext_and_signs_once_unlocked() -> Result<()> {
+    use crate::key_protection::{self, KeyLocked, KeyProtectionChoice};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Sealed Key".into(), &pool)?;
+    let key_dir = std::path::PathBuf::from(std::env::var("INTELEXTA_KEYCHAIN_DIR")?);
+
+    key_protection::set_protection(&project.id, &KeyProtectionChoice::Passphrase, Some("pw"))?;
+    assert!(!key_dir.join(format!("{}.key", project.id)).exists());
+    let sealed = std::fs::read_to_string(key_dir.join(format!("{}.sealed.json", project.id)))?;
+    let secret = STANDARD.encode(provenance::load_secret_key(&project.id)?.to_bytes());
+    assert!(!sealed.contains(&secret));
+
+    key_protection::lock(&project.id);
+    let err = provenance::load_secret_key(&project.id).unwrap_err();
+    assert!(err.downcast_ref::<KeyLocked>().is_some());
+    assert!(key_protection::unlock(&project.id, Some("wrong")).is_err());
+
+    key_protection::unlock(&project.id, Some("pw"))?;
+    let sk = provenance::load_secret_key(&project.id)?;
+    assert_eq!(provenance::public_key_from_secret(&sk), project.pubkey);
+
+    // Back to the keychain, with the same key
+    key_protection::set_protection(&project.id, &KeyProtectionChoice::Keychain, None)?;
+    key_protection::lock(&project.id);
+    let sk = provenance::load_secret_key(&project.id)?;
+    assert_eq!(provenance::public_key_from_secret(&sk), project.pubkey);
+    Ok(())
+}
+
+#[test]
+fn countersigned_bundles_keep_their_entries_and_verify() -> Result<()> {
+    use std::io::{Read, Write};
+    use zip::write::FileOptions;
+
+    init_keyring_mock();
+    let author = ed25519_dalek::SigningKey::from_bytes(&[4u8; 32]);
+    let car_json = crate::verify::tests::signed_car_json(&author);
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("receipt.car.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&path)?);
+    zip.start_file("car.json", FileOptions::default())?;
+    zip.write_all(&serde_json::to_vec_pretty(&car_json)?)?;
+    zip.start_file("attachments/notes.txt", FileOptions::default())?;
+    zip.write_all(b"reviewer notes")?;
+    zip.finish()?;
+
+    assert!(provenance::load_or_create_cosigner_key("../alice").is_err());
+    let reviewer = provenance::load_or_create_cosigner_key("alice")?;
+    let cosignature = car::countersign_car_file(&path, car::SignerRole::Reviewer, &reviewer)?;
+    let reloaded = provenance::load_or_create_cosigner_key("alice")?;
+    assert_eq!(cosignature.public_key, provenance::public_key_from_secret(&reloaded));
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&path)?)?;
+    let mut notes = String::new();
+    archive.by_name("attachments/notes.txt")?.read_to_string(&mut notes)?;
+    assert_eq!(notes, "reviewer notes");
+    let mut car_bytes = Vec::new();
+    archive.by_name("car.json")?.read_to_end(&mut car_bytes)?;
+    let verification = crate::verify::verify_car(&car_bytes, &[])?;
+    let roles: Vec<_> = verification.signers.iter().map(|signer| signer.role).collect();
+    assert_eq!(roles, vec![car::SignerRole::Author, car::SignerRole::Reviewer]);
+    Ok(())
+}
+
+#[test]
+fn encrypted_bundles_open_only_for_their_recipients() -> Result<()> {
+    use age::secrecy::ExposeSecret;
+    use std::io::Read;
+
+    let auditor = age::x25519::Identity::generate();
+    let encryption = car::Encryption {
+        recipients: vec![auditor.to_public().to_string()],
+    };
+    let invalid = car::Encryption {
+        recipients: vec![auditor.to_string().expose_secret().to_string()],
+    };
+    assert!(invalid.parse_recipients().is_err());
+
+    let dir = tempfile::tempdir()?;
+    let plain_path = dir.path().join("receipt.car.zip");
+    std::fs::write(&plain_path, b"bundle bytes")?;
+    let encrypted_path = car::encrypted_path(&plain_path);
+    assert!(encrypted_path.ends_with("receipt.car.zip.age"));
+    car::encrypt_car_file(&plain_path, &encrypted_path, &encryption.parse_recipients()?)?;
+    assert!(!plain_path.exists());
+
+    let encrypted = std::fs::read(&encrypted_path)?;
+    let other = age::x25519::Identity::generate();
+    assert!(age::Decryptor::new(&encrypted[..])?
+        .decrypt(std::iter::once(&other as &dyn age::Identity))
+        .is_err());
+    let mut plain = Vec::new();
+    age::Decryptor::new(&encrypted[..])?
+        .decrypt(std::iter::once(&auditor as &dyn age::Identity))?
+        .read_to_end(&mut plain)?;
+    assert_eq!(plain, b"bundle bytes");
+    Ok(())
+}
+
 #[test]
 fn start_run_creates_new_execution_without_truncating_history() -> Result<()> {
     init_keyring_mock();
@@ -98,6 +200,7 @@ fn start_run_creates_new_execution_without_truncating_history() -> Result<()> {
                     prompt_tokens: 3,
                     completion_tokens: 5,
                 },
+                metadata: None,
             })
         }
     }
@@ -157,6 +260,250 @@ fn start_run_creates_new_execution_without_truncating_history() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn run_environment_is_recorded_and_embedded_in_the_car() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Run Environment".into(), &pool)?;
+
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "environment-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("hello".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+
+    let execution = orchestrator::start_run(&pool, &run_id)?;
+    let environment = execution
+        .environment
+        .clone()
+        .expect("environment captured at execution start");
+    assert_eq!(environment.os, std::env::consts::OS);
+    assert_eq!(environment.app_version, env!("CARGO_PKG_VERSION"));
+    assert!(environment.machine_fingerprint.starts_with("sha256:"));
+
+    let conn = pool.get()?;
+    let listed = orchestrator::list_run_executions(&conn, &run_id)?;
+    assert_eq!(listed[0].environment.as_ref(), Some(&environment));
+
+    let car = car::build_car(&conn, &run_id, Some(execution.id.as_str()))?;
+    assert_eq!(car.run_environment, Some(environment));
+    let car_json = serde_json::to_value(&car)?;
+    assert!(car_json["run_environment"]["machine_fingerprint"].is_string());
+
+    Ok(())
+}
+
+#[test]
+fn redacted_cars_verify_and_their_commitments_open() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Redaction".into(), &pool)?;
+
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "redaction-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("confidential patient notes".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    let execution = orchestrator::start_run(&pool, &run_id)?;
+
+    let conn = pool.get()?;
+    let step_id = orchestrator::load_stored_run(&conn, &run_id)?.steps[0].id.clone();
+    let request = car::RedactionRequest {
+        step_ids: vec![step_id.clone()],
+        checkpoint_ids: Vec::new(),
+    };
+    let car = car::build_redacted_car(&conn, &run_id, Some(execution.id.as_str()), &request)?;
+
+    let target = format!("steps/{step_id}/prompt");
+    assert_eq!(car.redactions.len(), 1);
+    assert_eq!(car.redactions[0].target, target);
+    assert_eq!(car.run.steps[0].prompt, Some(car.redactions[0].marker()));
+    assert!(!serde_json::to_string(&car)?.contains("confidential patient notes"));
+
+    let verification = crate::verify::verify_car(&serde_json::to_vec(&car)?, &[])?;
+    assert_eq!(verification.redactions, 1);
+
+    let signing_key = provenance::load_secret_key(&project.id)?;
+    let salt = car::redaction_salt(&signing_key, &run_id, &target);
+    assert!(car.redactions[0].opens(&salt, b"confidential patient notes"));
+    assert!(!car.redactions[0].opens(&salt, b"other notes"));
+
+    let unknown = car::RedactionRequest {
+        step_ids: vec!["missing-step".to_string()],
+        checkpoint_ids: Vec::new(),
+    };
+    assert!(car::build_redacted_car(&conn, &run_id, None, &unknown).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn workspace_search_finds_outputs_across_projects() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Search Source".into(), &pool)?;
+    api::create_project_with_pool("Search Other".into(), &pool)?;
+
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "search-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("where did this come from?".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    let execution = orchestrator::start_run(&pool, &run_id)?;
+
+    let conn = pool.get()?;
+    let (checkpoint_id, outputs_sha256): (String, String) = conn.query_row(
+        "SELECT id, outputs_sha256 FROM checkpoints WHERE run_execution_id = ?1 AND kind = 'Step'",
+        params![&execution.id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let found =
+        crate::workspace_search::search_workspace(&conn, &format!("sha256:{outputs_sha256}"))?;
+    assert_eq!(found.checkpoints.len(), 1);
+    assert_eq!(found.checkpoints[0].checkpoint_id, checkpoint_id);
+    assert_eq!(found.checkpoints[0].project_name, "Search Source");
+    assert!(found.checkpoints[0]
+        .roles
+        .contains(&crate::workspace_search::MatchRole::Output));
+    assert_eq!(found.verifications.len(), 1);
+    assert_eq!(found.verifications[0].error, None);
+
+    let nothing = crate::workspace_search::search_workspace(&conn, &"00".repeat(32))?;
+    assert!(nothing.is_empty());
+    assert!(crate::workspace_search::search_workspace(&conn, "not-a-hash").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn usage_timeseries_buckets_checkpoints_by_period_and_model() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Usage Timeseries".into(), &pool)?;
+
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "usage-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("count my tokens".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    let monday = orchestrator::start_run(&pool, &run_id)?;
+    let sunday = orchestrator::start_run(&pool, &run_id)?;
+
+    let conn = pool.get()?;
+    conn.execute("UPDATE checkpoints SET usage_tokens = 0 WHERE kind != 'Step'", [])?;
+    for (execution, timestamp, tokens, usd_cost) in [
+        (&monday.id, "2026-03-02T10:00:00+00:00", 100, Some(0.5)),
+        (&sunday.id, "2026-03-08T23:00:00+00:00", 50, None),
+    ] {
+        conn.execute(
+            "UPDATE checkpoints SET timestamp = ?2, usage_tokens = ?3, usd_cost = ?4, nature_cost = 1.0
+             WHERE run_execution_id = ?1 AND kind = 'Step'",
+            params![execution, timestamp, tokens, usd_cost],
+        )?;
+    }
+
+    let daily = crate::dashboard::get_usage_timeseries(
+        &conn,
+        &project.id,
+        crate::dashboard::UsageGranularity::Day,
+    )?;
+    let days: Vec<(&str, u64)> = daily
+        .buckets
+        .iter()
+        .map(|bucket| (bucket.period_start.as_str(), bucket.tokens))
+        .collect();
+    assert_eq!(days, vec![("2026-03-02", 100), ("2026-03-08", 50)]);
+
+    let weekly = crate::dashboard::get_usage_timeseries(
+        &conn,
+        &project.id,
+        crate::dashboard::UsageGranularity::Week,
+    )?;
+    assert_eq!(weekly.buckets.len(), 1);
+    let week = &weekly.buckets[0];
+    assert_eq!(week.period_start, "2026-03-02");
+    assert_eq!(week.model.as_deref(), Some("stub-model"));
+    assert_eq!((week.checkpoints, week.tokens), (2, 150));
+    // The unpriced checkpoint is priced from the catalog
+    let estimated = crate::governance::estimate_usd_cost(50, Some("stub-model"));
+    assert!((week.usd - (0.5 + estimated)).abs() < 1e-9);
+    assert!((week.nature_cost - 2.0).abs() < 1e-9);
+
+    assert!(crate::dashboard::get_usage_timeseries(
+        &conn,
+        "no-such-project",
+        crate::dashboard::UsageGranularity::Day,
+    )
+    .is_err());
+
+    Ok(())
+}
+
 #[test]
 fn start_run_with_client_replays_concordant_with_epsilon() -> Result<()> {
     init_keyring_mock();
@@ -197,6 +544,7 @@ fn start_run_with_client_replays_concordant_with_epsilon() -> Result<()> {
                     prompt_tokens: 0,
                     completion_tokens: 0,
                 },
+                metadata: None,
             })
         }
     }
@@ -292,6 +640,100 @@ fn reorder_run_steps_swaps_entries() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn start_run_dry_reports_problems_without_writing_checkpoints() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Dry Run".into(), &pool)?;
+
+    let step = |order_index: i64, step_type: &str, config_json: Option<String>| {
+        orchestrator::RunStepTemplate {
+            step_type: step_type.to_string(),
+            model: config_json.is_none().then(|| "stub-model".to_string()),
+            prompt: config_json.is_none().then(|| "hello".to_string()),
+            token_budget: 100,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json,
+            order_index: Some(order_index),
+            checkpoint_type: "Step".to_string(),
+        }
+    };
+    let missing_path = std::env::temp_dir().join(format!("intelexta-dry-run-{}.txt", Uuid::new_v4()));
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "dry-run",
+        orchestrator::RunProofMode::Exact,
+        None,
+        5,
+        1_000,
+        "stub-model",
+        vec![
+            step(0, "llm", None),
+            step(
+                1,
+                "ingest",
+                Some(
+                    serde_json::json!({
+                        "stepType": "ingest",
+                        "sourcePath": missing_path,
+                        "format": "txt",
+                        "privacyStatus": "public"
+                    })
+                    .to_string(),
+                ),
+            ),
+            step(
+                2,
+                "prompt",
+                Some(
+                    serde_json::json!({
+                        "stepType": "prompt",
+                        "model": "stub-model",
+                        "prompt": "Use the earlier output",
+                        "useOutputFrom": 7
+                    })
+                    .to_string(),
+                ),
+            ),
+        ],
+    )?;
+
+    let report = orchestrator::start_run_dry(&pool, &run_id, serde_json::Map::new())?;
+    assert!(!report.valid);
+    assert_eq!(report.steps.len(), 3);
+    assert_eq!(report.steps[2].model.as_deref(), Some("stub-model"));
+    assert_eq!(report.steps[2].provider.as_deref(), Some("stub"));
+    assert_eq!(report.steps[2].depends_on, vec![7]);
+    let errors_at = |order_index: i64| {
+        report
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == orchestrator::DryRunSeverity::Error)
+            .filter(|issue| issue.order_index == Some(order_index))
+            .count()
+    };
+    assert_eq!(errors_at(0), 0);
+    assert_eq!(errors_at(1), 1);
+    assert_eq!(errors_at(2), 1);
+
+    let conn = pool.get()?;
+    let checkpoints: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM checkpoints WHERE run_id = ?1",
+        params![&run_id],
+        |row| row.get(0),
+    )?;
+    let executions: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM run_executions WHERE run_id = ?1",
+        params![&run_id],
+        |row| row.get(0),
+    )?;
+    assert_eq!((checkpoints, executions), (0, 0));
+
+    Ok(())
+}
+
 #[cfg(feature = "interactive")]
 #[test]
 fn interactive_run_emits_process_proof_and_replays() -> Result<()> {
@@ -416,6 +858,13 @@ fn interactive_run_emits_process_proof_and_replays() -> Result<()> {
                     (completion_tokens as i64),
                 ],
             )?;
+            // The first turn carries a chat message, bound by its inputs hash
+            if turn == 0 {
+                conn.execute(
+                    "INSERT INTO checkpoint_messages (checkpoint_id, role, body, created_at, updated_at) VALUES (?1, 'human', 'interactive-input-0', ?2, ?2)",
+                    params![&checkpoint_id, &timestamp],
+                )?;
+            }
         }
 
         expected_metadata.push((
@@ -464,6 +913,13 @@ fn interactive_run_emits_process_proof_and_replays() -> Result<()> {
         assert_eq!(entry.curr_chain, expected.4);
         assert_eq!(entry.signature, expected.5);
     }
+    let message = process.sequential_checkpoints[0].message.as_ref().expect("chat message");
+    assert_eq!(message.role, "human");
+    assert_eq!(
+        provenance::sha256_hex(message.body.as_deref().unwrap_or_default().as_bytes()),
+        process.sequential_checkpoints[0].inputs_sha256.clone().unwrap()
+    );
+    assert!(process.sequential_checkpoints[1].message.is_none());
     assert_eq!(car.checkpoints, inserted_ids);
     assert_eq!(car.run.name, stored_run_snapshot.name);
     assert_eq!(car.run.steps, stored_run_snapshot.steps);
@@ -472,8 +928,32 @@ fn interactive_run_emits_process_proof_and_replays() -> Result<()> {
     let base_dir = std::env::temp_dir().join(format!("intelexta-process-tests-{}", Uuid::new_v4()));
     std::fs::create_dir_all(&base_dir)?;
     let emitted_path =
-        api::emit_car_to_base_dir(&run_id, Some(run_execution_id.as_str()), &pool, &base_dir)?;
+        api::emit_car_to_base_dir(
+            &run_id,
+            &car::ExecutionSelection::Execution {
+                run_execution_id: run_execution_id.clone(),
+            },
+            &pool,
+            &base_dir,
+            car::CarFormat::Json,
+            &car::RedactionRequest::default(),
+            &car::Attestations::default(),
+            &car::Encryption::default(),
+        )?;
     assert!(emitted_path.exists());
+
+    // The project's next CAR chains onto the receipt just recorded
+    let (recorded_id, next_car) = {
+        let conn = pool.get()?;
+        let recorded_id: String = conn.query_row(
+            "SELECT id FROM receipts WHERE run_id = ?1",
+            params![&run_id],
+            |row| row.get(0),
+        )?;
+        (recorded_id, car::build_car(&conn, &run_id, Some(run_execution_id.as_str()))?)
+    };
+    assert_eq!(next_car.previous_car_id.as_deref(), Some(recorded_id.as_str()));
+
     let persisted: car::Car = serde_json::from_str(&std::fs::read_to_string(&emitted_path)?)?;
     assert_eq!(persisted.proof.match_kind, "process");
     assert_eq!(persisted.run.name, stored_run_snapshot.name);
@@ -492,6 +972,58 @@ fn interactive_run_emits_process_proof_and_replays() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn replay_car_is_signed_with_project_key() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Replay CAR".into(), &pool)?;
+
+    let run_id = Uuid::new_v4().to_string();
+    {
+        let conn = pool.get()?;
+        conn.execute(
+            "INSERT INTO runs (id, project_id, name, created_at, sampler_json, seed, epsilon, token_budget, default_model, proof_mode)
+             VALUES (?1, ?2, ?3, ?4, NULL, ?5, NULL, ?6, ?7, ?8)",
+            params![
+                &run_id,
+                &project.id,
+                "replay-car",
+                &Utc::now().to_rfc3339(),
+                5_i64,
+                1_000_i64,
+                "stub-model",
+                orchestrator::RunProofMode::Exact.as_str(),
+            ],
+        )?;
+    }
+
+    let report = replay::ReplayReport::from_checkpoint_reports(run_id.clone(), Vec::new(), None);
+    let conn = pool.get()?;
+    let replay_car = replay::build_replay_car(&conn, &report)?;
+    assert!(replay_car.id.starts_with("replay-car:"));
+    assert_eq!(replay_car.run_id, run_id);
+    assert_eq!(replay_car.signer_public_key, project.pubkey);
+    assert!(replay_car.attachments.is_empty());
+
+    let mut signed_body = serde_json::to_value(&replay_car)?;
+    signed_body
+        .as_object_mut()
+        .expect("replay CAR serializes to an object")
+        .remove("signatures");
+    let signature = replay_car.signatures[0]
+        .strip_prefix("ed25519-body:")
+        .expect("body signature prefix");
+    let signature = Signature::from_slice(&STANDARD.decode(signature)?)?;
+    let public_key: [u8; 32] = STANDARD
+        .decode(&project.pubkey)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("public key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&public_key)?
+        .verify(&provenance::canonical_json(&signed_body), &signature)?;
+
+    Ok(())
+}
+
 #[test]
 fn build_car_filters_checkpoints_by_run_execution() -> Result<()> {
     init_keyring_mock();
@@ -672,6 +1204,44 @@ fn build_car_filters_checkpoints_by_run_execution() -> Result<()> {
 
     assert_eq!(car_latest.checkpoints, second_checkpoint_ids);
 
+    // Combined CARs carry every execution, oldest first, next to the latest's proof
+    let car_combined = {
+        let conn = pool.get()?;
+        car::build_selected_car(
+            &conn,
+            &run_id,
+            &car::ExecutionSelection::All,
+            &car::RedactionRequest::default(),
+        )?
+    };
+    assert_eq!(car_combined.checkpoints, second_checkpoint_ids);
+    let sections: Vec<(&str, Vec<String>)> = car_combined
+        .proof
+        .executions
+        .iter()
+        .map(|execution| {
+            let ids = execution
+                .process
+                .sequential_checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.id.clone())
+                .collect();
+            (execution.run_execution_id.as_str(), ids)
+        })
+        .collect();
+    assert_eq!(
+        sections,
+        vec![
+            (first_execution_id.as_str(), first_checkpoint_ids.clone()),
+            (second_execution_id.as_str(), second_checkpoint_ids.clone()),
+        ]
+    );
+    assert_eq!(
+        car_combined.proof.executions[0].budgets.tokens,
+        car_first.budgets.tokens
+    );
+    assert!(car_latest.proof.executions.is_empty());
+
     Ok(())
 }
 
@@ -689,25 +1259,961 @@ fn get_policy_returns_default_for_new_project() -> Result<()> {
 }
 
 #[test]
-fn update_policy_persists_values() -> Result<()> {
+fn default_policy_caps_what_projects_may_loosen() -> Result<()> {
     init_keyring_mock();
     let pool = setup_pool()?;
-    let project = api::create_project_with_pool("Policy Persist".into(), &pool)?;
+    let inheriting = api::create_project_with_pool("Policy Inherits".into(), &pool)?;
+    let own = api::create_project_with_pool("Policy Own".into(), &pool)?;
+    let conn = pool.get()?;
 
-    let desired = Policy {
+    let generous = Policy {
         allow_network: true,
-        budget_tokens: 512,
-        budget_usd: 4.25,
-        budget_nature_cost: 0.75,
+        budget_tokens: 5_000,
+        ..Policy::default()
+    };
+    policies::upsert(&conn, &own.id, &generous)?;
+    let default = Policy {
+        budget_tokens: 2_000,
+        budget_usd: 1.0,
+        ..Policy::default()
+    };
+    policies::set_default(&conn, &default)?;
+
+    assert_eq!(policies::get(&conn, &inheriting.id)?, default);
+    let effective = policies::get(&conn, &own.id)?;
+    assert!(!effective.allow_network);
+    assert_eq!((effective.budget_tokens, effective.budget_usd), (2_000, 1.0));
+    assert_eq!(policies::get_project_layer(&conn, &own.id)?, Some(generous));
+
+    // Projects may tighten what the default caps, but not loosen it
+    let tighter = Policy {
+        budget_tokens: 500,
+        ..default.clone()
+    };
+    policies::upsert(&conn, &own.id, &tighter)?;
+    assert_eq!(policies::get(&conn, &own.id)?.budget_tokens, 500);
+    let looser = Policy {
+        allow_network: true,
+        ..default
     };
+    assert!(policies::upsert(&conn, &own.id, &looser).is_err());
+    Ok(())
+}
 
-    {
-        let conn = pool.get()?;
-        policies::upsert(&conn, &project.id, &desired)?;
-    }
+#[test]
+fn default_policy_caps_allowlists_and_content_rules() -> Result<()> {
+    use crate::content_policy::{ContentAction, ContentRules};
+    use crate::provider_policy::ProviderAllowlist;
 
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Policy Residency".into(), &pool)?;
     let conn = pool.get()?;
-    let fetched = policies::get(&conn, &project.id)?;
-    assert_eq!(fetched, desired);
+
+    let default = Policy {
+        provider_allowlist: ProviderAllowlist {
+            regions: vec!["eu".into()],
+            ..ProviderAllowlist::default()
+        },
+        content_rules: ContentRules {
+            secrets: ContentAction::Block,
+            ..ContentRules::default()
+        },
+        ..Policy::default()
+    };
+    policies::set_default(&conn, &default)?;
+
+    // Dropping the region or the secrets scan loosens the default
+    let error = policies::upsert(&conn, &project.id, &Policy::default())
+        .expect_err("policy loosening the default was stored")
+        .to_string();
+    assert!(error.contains("contentRules") && error.contains("providerAllowlist"));
+
+    let tighter = Policy {
+        content_rules: ContentRules {
+            pii: ContentAction::Warn,
+            ..default.content_rules.clone()
+        },
+        ..default.clone()
+    };
+    policies::upsert(&conn, &project.id, &tighter)?;
+    assert_eq!(policies::get(&conn, &project.id)?, tighter);
+    Ok(())
+}
+
+#[test]
+fn past_policy_versions_keep_the_default_they_resolved_against() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Policy History Defaults".into(), &pool)?;
+    let conn = pool.get()?;
+
+    let first_default = Policy {
+        budget_tokens: 2_000,
+        ..Policy::default()
+    };
+    policies::set_default(&conn, &first_default)?;
+    let own = Policy {
+        budget_tokens: 1_500,
+        ..Policy::default()
+    };
+    policies::upsert(&conn, &project.id, &own)?;
+    let first = policies::get_current_version(&conn, &project.id)?;
+    policies::upsert(
+        &conn,
+        &project.id,
+        &Policy {
+            budget_tokens: 1_800,
+            ..own
+        },
+    )?;
+    let second = policies::get_current_version(&conn, &project.id)?;
+
+    // A tighter default applies to the current version only
+    policies::set_default(
+        &conn,
+        &Policy {
+            budget_tokens: 1_000,
+            ..Policy::default()
+        },
+    )?;
+    let as_of = |version| policies::get_for_policy_version(&conn, &project.id, Some(version));
+    assert_eq!(as_of(first)?.budget_tokens, 1_500);
+    assert_eq!(as_of(second)?.budget_tokens, 1_000);
+    assert_eq!(policies::get(&conn, &project.id)?.budget_tokens, 1_000);
+    assert_eq!(
+        policies::get_version(&conn, &project.id, first)?
+            .and_then(|version| version.default_policy),
+        Some(first_default)
+    );
+    Ok(())
+}
+
+#[test]
+fn window_budgets_count_usage_within_the_current_window() -> Result<()> {
+    use store::project_usage_ledgers::{self as ledgers, BudgetWindow};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Window Budgets".into(), &pool)?;
+
+    let sunday: chrono::DateTime<Utc> = "2026-03-08T23:00:00Z".parse()?;
+    assert_eq!(BudgetWindow::Day.start(sunday).to_string(), "2026-03-08");
+    assert_eq!(BudgetWindow::Week.start(sunday).to_string(), "2026-03-02");
+    assert_eq!(BudgetWindow::Month.start(sunday).to_string(), "2026-03-01");
+
+    let policy = Policy {
+        budget_tokens: 1_000_000,
+        window_budgets: vec![policies::WindowBudget {
+            window: BudgetWindow::Day,
+            caps: policies::SubBudget {
+                tokens: Some(5_000),
+                ..Default::default()
+            },
+        }],
+        ..Policy::default()
+    };
+    {
+        let conn = pool.get()?;
+        policies::upsert(&conn, &project.id, &policy)?;
+    }
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "window-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("spend today's tokens".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+
+    let conn = pool.get()?;
+    ledgers::increment(&conn, &project.id, None, 4_500, 0.0, 0.0)?;
+    let week = ledgers::get_window(&conn, &project.id, BudgetWindow::Week, Utc::now())?;
+    assert_eq!(week.total_tokens, 4_500);
+
+    // 4,500 of today's 5,000 tokens are spent, so the 1,000-token step does not fit
+    let estimates = orchestrator::estimate_run_cost(&conn, &run_id)?;
+    let today = &estimates.windows[0];
+    assert_eq!(today.window, BudgetWindow::Day);
+    assert_eq!(today.remaining_tokens, Some(500));
+    assert_eq!(today.estimated_tokens, 5_500);
+    assert!(today.exceeds);
+    assert!(!estimates.cumulative.exceeds_tokens);
+
+    ledgers::release(&conn, &project.id, None, 4_500, 0.0, 0.0)?;
+    let estimates = orchestrator::estimate_run_cost(&conn, &run_id)?;
+    assert_eq!(estimates.windows[0].remaining_tokens, Some(5_000));
+    assert!(!estimates.windows[0].exceeds);
+    Ok(())
+}
+
+#[test]
+fn step_cost_estimates_follow_chained_context() -> Result<()> {
+    use crate::cost_estimation::{self, EstimateBasis, TokenRange};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Chained Estimates".into(), &pool)?;
+    let dir = tempfile::tempdir()?;
+    let paper = dir.path().join("paper.txt");
+    std::fs::write(&paper, "word ".repeat(1_600))?;
+
+    let step = |order_index: i64, token_budget: u64, config: serde_json::Value| {
+        orchestrator::RunStepTemplate {
+            step_type: config["stepType"].as_str().unwrap_or("llm").to_string(),
+            model: None,
+            prompt: None,
+            token_budget,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: Some(config.to_string()),
+            order_index: Some(order_index),
+            checkpoint_type: "Step".to_string(),
+        }
+    };
+    let chained_run = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "chained",
+        orchestrator::RunProofMode::Exact,
+        None,
+        3,
+        1_000,
+        "stub-model",
+        vec![
+            step(
+                0,
+                100,
+                serde_json::json!({
+                    "stepType": "ingest",
+                    "sourcePath": paper,
+                    "format": "txt",
+                    "privacyStatus": "public"
+                }),
+            ),
+            step(
+                1,
+                500,
+                serde_json::json!({
+                    "stepType": "prompt",
+                    "model": "stub-model",
+                    "prompt": "Summarize the document",
+                    "useOutputFrom": 0
+                }),
+            ),
+        ],
+    )?;
+
+    // The 8,000-byte document is 2,000 tokens of context the 500-token step inherits
+    let estimates = cost_estimation::estimate_step_costs(&pool.get()?, &chained_run)?;
+    assert_eq!(estimates.static_budget_tokens, 600);
+    assert_eq!(estimates.steps[0].output_tokens, TokenRange::exact(2_000));
+    assert_eq!(estimates.steps[0].usage_tokens, TokenRange::exact(0));
+    let prompt = &estimates.steps[1];
+    assert_eq!(prompt.basis, EstimateBasis::Projection);
+    assert_eq!(prompt.input_tokens, TokenRange::exact(2_006));
+    assert_eq!(prompt.usage_tokens, TokenRange::exact(2_006));
+    assert!(prompt.exceeds_token_budget);
+    assert_eq!(estimates.usage_tokens.expected, 2_006);
+
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "history",
+        orchestrator::RunProofMode::Exact,
+        None,
+        3,
+        1_000,
+        "stub-model",
+        vec![
+            orchestrator::RunStepTemplate {
+                step_type: "llm".to_string(),
+                model: Some("stub-model".to_string()),
+                prompt: Some("hello".to_string()),
+                token_budget: 100,
+                proof_mode: orchestrator::RunProofMode::Exact,
+                epsilon: None,
+                config_json: None,
+                order_index: Some(0),
+                checkpoint_type: "Step".to_string(),
+            },
+            step(
+                1,
+                800,
+                serde_json::json!({
+                    "stepType": "prompt",
+                    "model": "stub-model",
+                    "prompt": "Q",
+                    "useOutputFrom": 0
+                }),
+            ),
+        ],
+    )?;
+    let before = cost_estimation::estimate_step_costs(&pool.get()?, &run_id)?;
+    assert_eq!(before.steps[0].history_samples, 0);
+    assert_eq!(before.steps[1].basis, EstimateBasis::Projection);
+
+    // Once run, the first step's recorded output is what the second inherits
+    orchestrator::start_run(&pool, &run_id)?;
+    let after = cost_estimation::estimate_step_costs(&pool.get()?, &run_id)?;
+    assert_eq!(after.steps[0].basis, EstimateBasis::History);
+    assert_eq!(after.steps[0].history_samples, 1);
+    assert_eq!(after.steps[0].output_tokens, TokenRange::exact(10));
+    assert_eq!(after.steps[1].input_tokens, TokenRange::exact(11));
+    Ok(())
+}
+
+#[test]
+fn deleted_projects_stay_archived_until_their_retention_period_passes() -> Result<()> {
+    use crate::project_archival;
+    use store::project_archive_events::{ACTION_ARCHIVED, ACTION_PURGED, ACTION_RESTORED};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Archived".into(), &pool)?;
+    let mut conn = pool.get()?;
+    let listed = |conn: &rusqlite::Connection| -> Result<bool> {
+        Ok(store::projects::list(conn)?
+            .iter()
+            .any(|listed| listed.id == project.id))
+    };
+
+    let archived = project_archival::archive_project(&mut conn, &project.id)?;
+    assert!(archived.archived_at.is_some());
+    assert!(!listed(&conn)?);
+    assert_eq!(store::projects::list_archived(&conn)?.len(), 1);
+    assert!(project_archival::archive_project(&mut conn, &project.id).is_err());
+
+    let restored = project_archival::restore_project(&mut conn, &project.id)?;
+    assert!(restored.archived_at.is_none());
+    assert!(listed(&conn)?);
+    assert!(project_archival::purge_project(&mut conn, &project.id).is_err());
+
+    // Kept through the default 30 days, and for good without a retention period
+    project_archival::archive_project(&mut conn, &project.id)?;
+    let now = Utc::now();
+    assert!(
+        project_archival::purge_expired_projects(&mut conn, now + Duration::days(29))?.is_empty()
+    );
+    project_archival::set_retention_days(&conn, None)?;
+    assert!(
+        project_archival::purge_expired_projects(&mut conn, now + Duration::days(365))?.is_empty()
+    );
+    assert!(project_archival::set_retention_days(&conn, Some(0)).is_err());
+
+    project_archival::set_retention_days(&conn, Some(7))?;
+    let purged = project_archival::purge_expired_projects(&mut conn, now + Duration::days(8))?;
+    assert_eq!(purged, vec![project.id.clone()]);
+    assert!(store::projects::get(&conn, &project.id)?.is_none());
+
+    let actions: Vec<String> = store::project_archive_events::list(&conn, Some(&project.id))?
+        .into_iter()
+        .map(|event| event.action)
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            ACTION_PURGED,
+            ACTION_ARCHIVED,
+            ACTION_RESTORED,
+            ACTION_ARCHIVED
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reconcile_ledger_reports_and_repairs_drift() -> Result<()> {
+    use crate::ledger;
+    use store::project_usage_ledgers as ledgers;
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Ledger Reconciliation".into(), &pool)?;
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "reconcile-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        100,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("count me".to_string()),
+            token_budget: 100,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    orchestrator::start_run(&pool, &run_id)?;
+
+    let mut conn = pool.get()?;
+    let policy_version: Option<i64> = conn.query_row(
+        "SELECT policy_version FROM runs WHERE id = ?1",
+        params![&run_id],
+        |row| row.get(0),
+    )?;
+    // Replays record no checkpoints, but their usage is kept for reconciliation
+    ledgers::record_replay(&conn, &project.id, &run_id, policy_version, 5, 0.25, 0.5)?;
+
+    let report = ledger::reconcile_ledger(&mut conn, &project.id, false)?;
+    assert!(!report.drifted);
+    let version = report
+        .policy_versions
+        .iter()
+        .find(|drift| drift.recorded.tokens > 0)
+        .ok_or_else(|| anyhow!("no ledger with usage"))?;
+    // The stub step uses 10 tokens
+    assert_eq!(version.recorded.tokens, 15);
+    assert_eq!(version.recomputed, version.recorded);
+
+    // A ledger that lost its usage, as when an archive is imported without one
+    let recorded = ledgers::get(&conn, &project.id, policy_version)?;
+    ledgers::release(
+        &conn,
+        &project.id,
+        policy_version,
+        recorded.total_tokens,
+        recorded.total_usd,
+        recorded.total_nature_cost,
+    )?;
+    let report = ledger::reconcile_ledger(&mut conn, &project.id, false)?;
+    assert!(report.drifted);
+    assert!(!report.repaired);
+    let drift = report
+        .policy_versions
+        .iter()
+        .find(|drift| drift.drifted)
+        .ok_or_else(|| anyhow!("no drifted ledger"))?;
+    assert_eq!(drift.recorded.tokens, 0);
+    assert_eq!(drift.recomputed.tokens, 15);
+    assert_eq!(ledgers::get(&conn, &project.id, policy_version)?.total_tokens, 0);
+
+    let report = ledger::reconcile_ledger(&mut conn, &project.id, true)?;
+    assert!(report.repaired);
+    let repaired = ledgers::get(&conn, &project.id, policy_version)?;
+    assert_eq!(repaired.total_tokens, 15);
+    assert!((repaired.total_usd - recorded.total_usd).abs() < 1e-9);
+    assert!(!ledger::reconcile_ledger(&mut conn, &project.id, true)?.drifted);
+    Ok(())
+}
+
+#[test]
+fn budget_alerts_fire_once_per_threshold_and_period() -> Result<()> {
+    use crate::notifications;
+    use store::project_usage_ledgers::{self as ledgers, BudgetWindow};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Budget Alerts".into(), &pool)?;
+    let policy = Policy {
+        budget_tokens: 1_000,
+        window_budgets: vec![policies::WindowBudget {
+            window: BudgetWindow::Day,
+            caps: policies::SubBudget {
+                tokens: Some(2_000),
+                ..Default::default()
+            },
+        }],
+        budget_alert_thresholds: vec![80, 50],
+        ..Policy::default()
+    };
+    assert!(notifications::validate_thresholds(&[0]).is_err());
+    assert!(notifications::validate_thresholds(&[101]).is_err());
+    assert!(notifications::validate_thresholds(&policy.budget_alert_thresholds).is_ok());
+
+    let conn = pool.get()?;
+    policies::upsert(&conn, &project.id, &policy)?;
+    let version = Some(policies::get_current_version(&conn, &project.id)?);
+    let fired_thresholds = |fired: &[store::notifications::BudgetAlert]| -> Vec<(String, u32)> {
+        fired
+            .iter()
+            .map(|alert| (format!("{}/{}", alert.scope, alert.budget), alert.threshold))
+            .collect()
+    };
+
+    ledgers::increment(&conn, &project.id, version, 600, 0.0, 0.0)?;
+    let fired = notifications::check_budget_alerts(&conn, &project.id, version);
+    assert_eq!(fired_thresholds(&fired), vec![("policy/tokens".to_string(), 50)]);
+    assert_eq!(fired[0].used, 600.0);
+    assert_eq!(fired[0].limit, 1_000.0);
+    assert!(notifications::check_budget_alerts(&conn, &project.id, version).is_empty());
+
+    ledgers::increment(&conn, &project.id, version, 300, 0.0, 0.0)?;
+    let fired = notifications::check_budget_alerts(&conn, &project.id, version);
+    assert_eq!(fired_thresholds(&fired), vec![("policy/tokens".to_string(), 80)]);
+
+    // Today's window crosses both thresholds at once; only the higher is delivered
+    ledgers::increment(&conn, &project.id, version, 1_100, 0.0, 0.0)?;
+    let fired = notifications::check_budget_alerts(&conn, &project.id, version);
+    assert_eq!(fired_thresholds(&fired), vec![("day/tokens".to_string(), 80)]);
+    assert_eq!(fired[0].period, BudgetWindow::Day.start(Utc::now()).to_string());
+
+    let recorded = store::notifications::list(&conn, &project.id)?;
+    assert_eq!(recorded.len(), 4);
+
+    // Delivery settings are validated before they are saved
+    let invalid = store::notifications::NotificationSettings {
+        email_to: Some("alerts@example.com".into()),
+        ..Default::default()
+    };
+    assert!(notifications::save_settings(&conn, &invalid, None).is_err());
+    let settings = store::notifications::NotificationSettings {
+        email_to: Some("alerts@example.com".into()),
+        smtp_host: Some("smtp.example.com".into()),
+        smtp_port: Some(587),
+        ..Default::default()
+    };
+    assert_eq!(notifications::save_settings(&conn, &settings, None)?, settings);
+    Ok(())
+}
+
+#[test]
+fn policy_simulation_replays_past_executions_against_a_draft() -> Result<()> {
+    use crate::incident_response::IncidentAction;
+    use crate::policy_simulation::{self, SimulatedOutcome};
+    use store::project_usage_ledgers::BudgetWindow;
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Policy Simulation".into(), &pool)?;
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "simulation-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("replay me".to_string()),
+            token_budget: 1_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    let executions = [
+        orchestrator::start_run(&pool, &run_id)?,
+        orchestrator::start_run(&pool, &run_id)?,
+        orchestrator::start_run(&pool, &run_id)?,
+    ];
+
+    let conn = pool.get()?;
+    conn.execute("UPDATE checkpoints SET usage_tokens = 0 WHERE kind != 'Step'", [])?;
+    for (execution, created_at) in executions.iter().zip([
+        "2026-03-02T09:00:00+00:00",
+        "2026-03-02 17:00:00",
+        "2026-03-03T09:00:00+00:00",
+    ]) {
+        conn.execute(
+            "UPDATE run_executions SET created_at = ?2 WHERE id = ?1",
+            params![execution.id, created_at],
+        )?;
+        conn.execute(
+            "UPDATE checkpoints SET usage_tokens = 600, usd_cost = 0.0, nature_cost = 1.0
+             WHERE run_execution_id = ?1 AND kind = 'Step'",
+            params![execution.id],
+        )?;
+    }
+
+    // 1,000 tokens a day: the second execution of 2 March runs over
+    let mut draft = Policy {
+        budget_tokens: 5_000,
+        budget_nature_cost: 2.5,
+        window_budgets: vec![policies::WindowBudget {
+            window: BudgetWindow::Day,
+            caps: policies::SubBudget {
+                tokens: Some(1_000),
+                ..Default::default()
+            },
+        }],
+        ..Policy::default()
+    };
+    let simulation = policy_simulation::simulate_policy_change(&conn, &project.id, &draft)?;
+    assert_eq!(simulation.executions_replayed, 3);
+    assert_eq!((simulation.blocked, simulation.warned), (1, 1));
+    let blocked = &simulation.executions[0];
+    assert_eq!(blocked.execution_id, executions[1].id);
+    assert_eq!(blocked.outcome, SimulatedOutcome::Blocked);
+    assert_eq!(blocked.incidents[0].kind, "budget_projection_exceeded");
+    assert!(blocked.incidents[0].details.contains("day tokens 1200 > 1000"));
+    // A new day, but the project's Nature Cost reaches 3.0 of 2.5
+    let warned = &simulation.executions[1];
+    assert_eq!(warned.execution_id, executions[2].id);
+    assert_eq!(warned.outcome, SimulatedOutcome::Warned);
+    assert_eq!(warned.incidents[0].kind, "nature_cost_warning");
+
+    // The draft's incident actions decide what an incident would have done
+    draft.incident_actions = [(
+        "budget_projection_exceeded".to_string(),
+        IncidentAction::WarnContinue,
+    )]
+    .into();
+    let simulation = policy_simulation::simulate_policy_change(&conn, &project.id, &draft)?;
+    assert_eq!((simulation.blocked, simulation.warned), (0, 2));
+    assert_eq!(
+        simulation.executions[0].incidents[0].action,
+        IncidentAction::WarnContinue
+    );
+
+    assert!(policy_simulation::simulate_policy_change(&conn, "no-such-project", &draft).is_err());
+    Ok(())
+}
+
+#[test]
+fn incident_log_lists_acknowledges_and_archives_incidents() -> Result<()> {
+    use crate::incident_log::{self, DateRange};
+    use crate::store::incident_acknowledgements::IncidentAcknowledgement;
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Incident Log".into(), &pool)?;
+    // The step's 5,000 tokens do not fit the default 1,000-token budget
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "incident-test",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        5_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            step_type: "llm".to_string(),
+            model: Some("stub-model".to_string()),
+            prompt: Some("spend too much".to_string()),
+            token_budget: 5_000,
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+            config_json: None,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+        }],
+    )?;
+    let execution = orchestrator::start_run(&pool, &run_id)?;
+
+    let conn = pool.get()?;
+    let incidents = incident_log::list_incidents(&conn, &project.id, None, None, None)?;
+    assert_eq!(incidents.len(), 1);
+    let incident = &incidents[0];
+    assert_eq!(incident.kind, "budget_projection_exceeded");
+    assert_eq!(incident.severity, "error");
+    assert_eq!(incident.run_execution_id.as_deref(), Some(execution.id.as_str()));
+    assert!(incident.acknowledgement.is_none());
+
+    let list = |severity: Option<&str>, kind: Option<&str>, range: Option<&DateRange>| {
+        incident_log::list_incidents(&conn, &project.id, severity, kind, range)
+    };
+    assert!(list(Some("warn"), None, None)?.is_empty());
+    assert!(list(None, Some("network_denied"), None)?.is_empty());
+    assert_eq!(list(Some("error"), Some("budget_projection_exceeded"), None)?.len(), 1);
+    let today = Utc::now().date_naive();
+    let from_tomorrow = DateRange {
+        from: Some(today + Duration::days(1)),
+        to: None,
+    };
+    assert!(list(None, None, Some(&from_tomorrow))?.is_empty());
+    let through_today = DateRange {
+        from: None,
+        to: Some(today),
+    };
+    assert_eq!(list(None, None, Some(&through_today))?.len(), 1);
+
+    let acknowledgement = incident_log::acknowledge_incident(
+        &conn,
+        &incident.checkpoint_id,
+        "auditor",
+        Some("budget raised for the quarter"),
+    )?;
+    assert_eq!(acknowledgement.acknowledged_by, "auditor");
+    let project_key = crate::portability::decode_verifying_key(&project.pubkey)?;
+    assert!(incident_log::acknowledgement_signed_by(&acknowledgement, &project_key));
+    let reworded = IncidentAcknowledgement {
+        note: Some("nothing to see".to_string()),
+        ..acknowledgement.clone()
+    };
+    assert!(!incident_log::acknowledgement_signed_by(&reworded, &project_key));
+    assert!(
+        incident_log::acknowledge_incident(&conn, &incident.checkpoint_id, "auditor", None)
+            .is_err()
+    );
+    let listed = list(None, None, None)?;
+    assert_eq!(listed[0].acknowledgement.as_ref(), Some(&acknowledgement));
+    drop(conn);
+
+    // Archives carry the log, and importing one restores the acknowledgement
+    let dir = tempfile::tempdir()?;
+    let archive = crate::portability::export_project_archive(&pool, &project.id, dir.path())?;
+    let imported = setup_pool()?;
+    let import_dir = tempfile::tempdir()?;
+    crate::portability::import_project_archive(&imported, &archive, import_dir.path())?;
+    let conn = imported.get()?;
+    let restored = incident_log::list_incidents(&conn, &project.id, None, None, None)?;
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].acknowledgement.as_ref(), Some(&acknowledgement));
+    Ok(())
+}
+
+#[test]
+fn policy_changes_apply_only_once_someone_else_approves_them() -> Result<()> {
+    use crate::policy_approval;
+    use store::policy_proposals::{STATUS_APPROVED, STATUS_REJECTED};
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Policy Approval".into(), &pool)?;
+    let mut conn = pool.get()?;
+    let policy = Policy {
+        budget_tokens: 1_000,
+        require_change_approval: true,
+        ..Policy::default()
+    };
+    policies::upsert(&conn, &project.id, &policy)?;
+    let base_version = policies::get_current_version(&conn, &project.id)?;
+    assert!(policy_approval::ensure_direct_update_allowed(&conn, &project.id).is_err());
+
+    let raised = Policy {
+        budget_tokens: 2_000,
+        ..policy.clone()
+    };
+    assert!(
+        policy_approval::propose_policy_change(&conn, &project.id, &policy, "alice", None).is_err()
+    );
+    let review = policy_approval::propose_policy_change(
+        &conn,
+        &project.id,
+        &raised,
+        "alice",
+        Some("more headroom"),
+    )?;
+    assert!(!review.stale);
+    assert_eq!(review.proposal.base_version, base_version);
+    assert_eq!(review.changes.len(), 1);
+    assert_eq!(review.changes[0].path, "budgetTokens");
+    assert_eq!(review.changes[0].left, Some(serde_json::json!(1_000)));
+    assert_eq!(review.changes[0].right, Some(serde_json::json!(2_000)));
+    // Proposing changes nothing yet
+    assert_eq!(policies::get(&conn, &project.id)?.budget_tokens, 1_000);
+    assert_eq!(
+        policies::get_current_version(&conn, &project.id)?,
+        base_version
+    );
+
+    let proposal_id = review.proposal.id;
+    assert!(policy_approval::approve_policy_change(&mut conn, &proposal_id, "Alice").is_err());
+    let version = policy_approval::approve_policy_change(&mut conn, &proposal_id, "bob")?;
+    assert_eq!(version.version, base_version + 1);
+    assert_eq!(version.created_by.as_deref(), Some("alice"));
+    assert_eq!(version.approved_by.as_deref(), Some("bob"));
+    assert!(version.approved_at.is_some());
+    assert_eq!(version.change_notes.as_deref(), Some("more headroom"));
+    assert_eq!(policies::get(&conn, &project.id)?.budget_tokens, 2_000);
+    assert!(policy_approval::approve_policy_change(&mut conn, &proposal_id, "carol").is_err());
+
+    // A proposal overtaken by another approved change cannot be approved
+    let lowered = Policy {
+        budget_tokens: 500,
+        ..raised.clone()
+    };
+    let overtaken =
+        policy_approval::propose_policy_change(&conn, &project.id, &lowered, "carol", None)?;
+    let cached = Policy {
+        response_cache: true,
+        ..raised.clone()
+    };
+    let applied =
+        policy_approval::propose_policy_change(&conn, &project.id, &cached, "carol", None)?;
+    policy_approval::approve_policy_change(&mut conn, &applied.proposal.id, "bob")?;
+    assert!(
+        policy_approval::approve_policy_change(&mut conn, &overtaken.proposal.id, "bob").is_err()
+    );
+
+    let reviews = policy_approval::list_policy_proposals(&conn, &project.id)?;
+    assert_eq!(reviews.len(), 3);
+    let stale = reviews
+        .iter()
+        .find(|review| review.proposal.id == overtaken.proposal.id)
+        .ok_or_else(|| anyhow!("overtaken proposal not listed"))?;
+    assert!(stale.stale);
+    assert_eq!(
+        reviews
+            .iter()
+            .filter(|review| review.proposal.status == STATUS_APPROVED)
+            .count(),
+        2
+    );
+
+    let rejected = policy_approval::reject_policy_change(&conn, &overtaken.proposal.id, "bob")?;
+    assert_eq!(rejected.status, STATUS_REJECTED);
+    assert_eq!(rejected.decided_by.as_deref(), Some("bob"));
+    assert_eq!(policies::get(&conn, &project.id)?.budget_tokens, 2_000);
+    Ok(())
+}
+
+#[test]
+fn update_policy_persists_values() -> Result<()> {
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Policy Persist".into(), &pool)?;
+
+    let desired = Policy {
+        allow_network: true,
+        budget_tokens: 512,
+        budget_usd: 4.25,
+        budget_nature_cost: 0.75,
+        allowed_path_roots: Vec::new(),
+        allowed_training_programs: Vec::new(),
+        max_concurrent_runs: 3,
+        content_rules: Default::default(),
+        response_cache: false,
+        execution_window: None,
+        incident_actions: [(
+            "network_denied".to_string(),
+            crate::incident_response::IncidentAction::PauseForApproval,
+        )]
+        .into(),
+        model_budgets: Default::default(),
+        provider_budgets: [(
+            "anthropic".to_string(),
+            policies::SubBudget {
+                usd: Some(5.0),
+                ..Default::default()
+            },
+        )]
+        .into(),
+        window_budgets: vec![policies::WindowBudget {
+            window: crate::store::project_usage_ledgers::BudgetWindow::Day,
+            caps: policies::SubBudget {
+                tokens: Some(100_000),
+                ..Default::default()
+            },
+        }],
+        provider_allowlist: Default::default(),
+        budget_alert_thresholds: vec![80, 95],
+        require_change_approval: true,
+    };
+
+    {
+        let conn = pool.get()?;
+        policies::upsert(&conn, &project.id, &desired)?;
+    }
+
+    let conn = pool.get()?;
+    let fetched = policies::get(&conn, &project.id)?;
+    assert_eq!(fetched, desired);
+    Ok(())
+}
+
+#[test]
+fn trigger_poll_records_a_bad_payload_and_fires_the_rest() -> Result<()> {
+    use crate::store::run_triggers;
+    use crate::triggers;
+
+    init_keyring_mock();
+    let pool = setup_pool()?;
+    let project = api::create_project_with_pool("Trigger Poll".into(), &pool)?;
+    let run_id = orchestrator::create_run(
+        &pool,
+        &project.id,
+        "trigger-poll",
+        orchestrator::RunProofMode::Exact,
+        None,
+        7,
+        1_000,
+        "stub-model",
+        vec![orchestrator::RunStepTemplate {
+            model: "stub-model".into(),
+            prompt: "{\"prompt\":\"hello\"}".into(),
+            token_budget: 1_000,
+            order_index: Some(0),
+            checkpoint_type: "Step".to_string(),
+            proof_mode: orchestrator::RunProofMode::Exact,
+            epsilon: None,
+        }],
+    )?;
+
+    struct FixedClient;
+
+    impl orchestrator::LlmClient for FixedClient {
+        fn stream_generate(
+            &self,
+            _model: &str,
+            _prompt: &str,
+        ) -> anyhow::Result<orchestrator::LlmGeneration> {
+            Ok(orchestrator::LlmGeneration {
+                response: "stub-response".to_string(),
+                usage: orchestrator::TokenUsage {
+                    prompt_tokens: 3,
+                    completion_tokens: 5,
+                },
+                metadata: None,
+            })
+        }
+    }
+
+    // The bad payload sorts first and cannot be moved: `failed` is a file
+    let watch = tempfile::tempdir()?;
+    std::fs::write(watch.path().join("a-bad.json"), "not json")?;
+    std::fs::write(watch.path().join("b-good.json"), r#"{"topic":"rust"}"#)?;
+    std::fs::write(watch.path().join(triggers::FAILED_DIR), "")?;
+    let other = tempfile::tempdir()?;
+    std::fs::write(other.path().join("c-good.json"), r#"{"topic":"sqlite"}"#)?;
+    {
+        let conn = pool.get()?;
+        for dir in [&watch, &other] {
+            run_triggers::create(
+                &conn,
+                &run_id,
+                run_triggers::KIND_FILE_DROP,
+                &dir.path().to_string_lossy(),
+            )?;
+        }
+    }
+
+    let events = triggers::poll_file_drop_triggers_with_client(&pool, &FixedClient)?;
+    let mut outcomes: Vec<_> = events
+        .iter()
+        .map(|event| (event.source_name.as_str(), event.status.as_str()))
+        .collect();
+    outcomes.sort();
+    assert_eq!(
+        outcomes,
+        vec![
+            ("a-bad.json", run_triggers::EVENT_FAILED),
+            ("b-good.json", run_triggers::EVENT_STARTED),
+            ("c-good.json", run_triggers::EVENT_STARTED),
+        ]
+    );
+    assert!(watch.path().join(triggers::PROCESSED_DIR).join("b-good.json").exists());
+    assert!(other.path().join(triggers::PROCESSED_DIR).join("c-good.json").exists());
+
+    // The unmovable payload stays claimed and does not fire again
+    assert!(!watch.path().join("a-bad.json").exists());
+    assert!(triggers::poll_file_drop_triggers_with_client(&pool, &FixedClient)?.is_empty());
     Ok(())
 }
@@ -0,0 +1,667 @@
+// src-tauri/src/threshold.rs
+//!
+//! Threshold signing: FROST(Ed25519, SHA-512), RFC 9591
+//!
+//! A team project can split its signing key into shares so that any
+//! `min_signers` of them sign together and fewer sign nothing. The
+//! signatures are plain Ed25519 signatures under the project's public key,
+//! so verifiers cannot tell them from those of a single key.
+//!
+//! Signing takes two rounds. Each participant first commits to a pair of
+//! fresh nonces (`commit`); the coordinator sends every participant the
+//! message with the commitments of all (`SigningPackage`), each answers
+//! with a signature share (`sign`), and the coordinator checks the shares
+//! against the participants' verifying shares and adds them up
+//! (`aggregate`). A participant must never reuse its nonces.
+//!
+//! The math builds without the desktop app, so cosigner services can depend
+//! on the crate with `default-features = false`. In the app, a project with
+//! a `ThresholdConfig` signs its checkpoints and CARs through the cosigner
+//! endpoints it lists, which speak JSON over HTTP:
+//!
+//! - `POST <url>/commit` with `{"session", "group_public_key", "message"}`
+//!   answers the participant's `SigningCommitments`
+//! - `POST <url>/sign` with `{"session", "package"}` answers its
+//!   `SignatureShare` for the nonces committed to in that session
+//!
+//! `message` is base64, so a cosigner can decide what it signs.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::IsIdentity;
+use ed25519_dalek::{Signature, SigningKey, Verifier, VerifyingKey};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+/// Domain separation of the ciphersuite's hashes
+const CONTEXT: &[u8] = b"FROST-ED25519-SHA512-v1";
+
+/// A participant's share of a threshold key
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    /// Nonzero and unique among the participants
+    pub identifier: u16,
+    /// Base64 scalar; as secret as a signing key
+    pub signing_share: String,
+    /// Base64 Ed25519 public key the shares sign for
+    pub group_public_key: String,
+    pub min_signers: u16,
+}
+
+impl std::fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("identifier", &self.identifier)
+            .field("group_public_key", &self.group_public_key)
+            .field("min_signers", &self.min_signers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The public side of a threshold key, which the coordinator checks signature
+/// shares against
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GroupKey {
+    pub group_public_key: String,
+    pub min_signers: u16,
+    /// Base64 point of each participant's share, by identifier
+    pub verifying_shares: BTreeMap<u16, String>,
+}
+
+/// A participant's round-one commitments to its nonces
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SigningCommitments {
+    pub identifier: u16,
+    pub hiding: String,
+    pub binding: String,
+}
+
+/// Nonces a participant keeps between the two rounds, and uses once
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+    commitments: SigningCommitments,
+}
+
+/// What the coordinator asks the participants to sign in round two
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SigningPackage {
+    /// Base64 message
+    pub message: String,
+    pub commitments: Vec<SigningCommitments>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SignatureShare {
+    pub identifier: u16,
+    pub share: String,
+}
+
+/// Split a project's signing key into `max_signers` shares, any `min_signers`
+/// of which sign for its public key. The caller hands the shares out and
+/// then deletes the key.
+pub fn split_key(
+    signing_key: &SigningKey,
+    min_signers: u16,
+    max_signers: u16,
+    rng: &mut impl CryptoRngCore,
+) -> anyhow::Result<(Vec<KeyShare>, GroupKey)> {
+    if min_signers < 2 || min_signers > max_signers {
+        bail!("a threshold key needs 2 <= min_signers <= max_signers, got {min_signers} of {max_signers}");
+    }
+    // f(0) is the secret; any min_signers points of f recover it, fewer
+    // reveal nothing
+    let coefficients: Vec<Scalar> = std::iter::once(signing_key.to_scalar())
+        .chain((1..min_signers).map(|_| random_scalar(rng)))
+        .collect();
+    let group_public_key = STANDARD.encode(signing_key.verifying_key().as_bytes());
+
+    let mut shares = Vec::new();
+    let mut verifying_shares = BTreeMap::new();
+    for identifier in 1..=max_signers {
+        let x = Scalar::from(identifier);
+        let share = coefficients
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, coefficient| acc * x + coefficient);
+        verifying_shares.insert(identifier, encode_point(&EdwardsPoint::mul_base(&share)));
+        shares.push(KeyShare {
+            identifier,
+            signing_share: STANDARD.encode(share.to_bytes()),
+            group_public_key: group_public_key.clone(),
+            min_signers,
+        });
+    }
+    let group = GroupKey {
+        group_public_key,
+        min_signers,
+        verifying_shares,
+    };
+    Ok((shares, group))
+}
+
+/// Round one: fresh nonces to keep, and the commitments to send the
+/// coordinator
+pub fn commit(
+    share: &KeyShare,
+    rng: &mut impl CryptoRngCore,
+) -> anyhow::Result<(SigningNonces, SigningCommitments)> {
+    let secret = decode_scalar(&share.signing_share).context("invalid signing share")?;
+    let hiding = generate_nonce(&secret, rng);
+    let binding = generate_nonce(&secret, rng);
+    let commitments = SigningCommitments {
+        identifier: share.identifier,
+        hiding: encode_point(&EdwardsPoint::mul_base(&hiding)),
+        binding: encode_point(&EdwardsPoint::mul_base(&binding)),
+    };
+    let nonces = SigningNonces {
+        hiding,
+        binding,
+        commitments: commitments.clone(),
+    };
+    Ok((nonces, commitments))
+}
+
+/// Round two: this participant's share of the signature over the package
+pub fn sign(
+    share: &KeyShare,
+    nonces: SigningNonces,
+    package: &SigningPackage,
+) -> anyhow::Result<SignatureShare> {
+    if !package.commitments.contains(&nonces.commitments) {
+        bail!("the signing package leaves out this participant's commitments");
+    }
+    if package.commitments.len() < usize::from(share.min_signers) {
+        bail!(
+            "the signing package has {} signers, {} are needed",
+            package.commitments.len(),
+            share.min_signers
+        );
+    }
+    let session = Session::new(&share.group_public_key, package)?;
+    let secret = decode_scalar(&share.signing_share).context("invalid signing share")?;
+    let binding_factor = session.binding_factor(share.identifier);
+    let lambda = session.lagrange(share.identifier);
+    let z = nonces.hiding + nonces.binding * binding_factor + lambda * secret * session.challenge;
+    Ok(SignatureShare {
+        identifier: share.identifier,
+        share: STANDARD.encode(z.to_bytes()),
+    })
+}
+
+/// Check every signer's share and combine them into an Ed25519 signature
+/// under the group key. Fails naming the first participant whose share does
+/// not verify.
+pub fn aggregate(
+    group: &GroupKey,
+    package: &SigningPackage,
+    shares: &[SignatureShare],
+) -> anyhow::Result<Signature> {
+    if package.commitments.len() < usize::from(group.min_signers) {
+        bail!(
+            "the signing package has {} signers, {} are needed",
+            package.commitments.len(),
+            group.min_signers
+        );
+    }
+    let session = Session::new(&group.group_public_key, package)?;
+    let mut z = Scalar::ZERO;
+    for commitment in &session.commitments {
+        let identifier = commitment.identifier;
+        let share = shares
+            .iter()
+            .find(|share| share.identifier == identifier)
+            .ok_or_else(|| anyhow!("participant {identifier} sent no signature share"))?;
+        let z_i = decode_scalar(&share.share)
+            .with_context(|| format!("signature share of participant {identifier}"))?;
+        let verifying_share = group
+            .verifying_shares
+            .get(&identifier)
+            .ok_or_else(|| anyhow!("participant {identifier} is not part of the group"))
+            .and_then(|encoded| decode_point(encoded))?;
+        let expected = commitment.hiding
+            + commitment.binding * session.binding_factor(identifier)
+            + verifying_share * (session.challenge * session.lagrange(identifier));
+        if EdwardsPoint::mul_base(&z_i) != expected {
+            bail!("signature share of participant {identifier} does not verify");
+        }
+        z += z_i;
+    }
+
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(session.group_commitment.compress().as_bytes());
+    bytes[32..].copy_from_slice(&z.to_bytes());
+    let signature = Signature::from_bytes(&bytes);
+    session
+        .group_public_key
+        .verify(&session.message, &signature)
+        .map_err(|_| anyhow!("aggregated signature does not verify under the group key"))?;
+    Ok(signature)
+}
+
+struct Commitment {
+    identifier: u16,
+    hiding: EdwardsPoint,
+    binding: EdwardsPoint,
+}
+
+/// Everything both rounds derive from the signing package
+struct Session {
+    group_public_key: VerifyingKey,
+    message: Vec<u8>,
+    /// Ordered by identifier
+    commitments: Vec<Commitment>,
+    binding_factors: BTreeMap<u16, Scalar>,
+    group_commitment: EdwardsPoint,
+    challenge: Scalar,
+}
+
+impl Session {
+    fn new(group_public_key: &str, package: &SigningPackage) -> anyhow::Result<Self> {
+        let group_public_key = crate::verify::decode_verifying_key(group_public_key)?;
+        let message = STANDARD
+            .decode(&package.message)
+            .context("signing package message is not base64")?;
+        let mut commitments = package
+            .commitments
+            .iter()
+            .map(|commitment| {
+                if commitment.identifier == 0 {
+                    bail!("participant identifiers start at 1");
+                }
+                Ok(Commitment {
+                    identifier: commitment.identifier,
+                    hiding: decode_point(&commitment.hiding)?,
+                    binding: decode_point(&commitment.binding)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        commitments.sort_by_key(|commitment| commitment.identifier);
+        if commitments
+            .windows(2)
+            .any(|pair| pair[0].identifier == pair[1].identifier)
+        {
+            bail!("the signing package lists a participant twice");
+        }
+
+        let mut encoded_commitments = Vec::new();
+        for commitment in &commitments {
+            encoded_commitments.extend(Scalar::from(commitment.identifier).to_bytes());
+            encoded_commitments.extend(commitment.hiding.compress().to_bytes());
+            encoded_commitments.extend(commitment.binding.compress().to_bytes());
+        }
+        let prefix = [
+            group_public_key.as_bytes().as_slice(),
+            &hash(&[CONTEXT, b"msg", &message]),
+            &hash(&[CONTEXT, b"com", &encoded_commitments]),
+        ]
+        .concat();
+        let binding_factors: BTreeMap<u16, Scalar> = commitments
+            .iter()
+            .map(|commitment| {
+                let identifier = Scalar::from(commitment.identifier).to_bytes();
+                let factor = hash_to_scalar(&[CONTEXT, b"rho", &prefix, &identifier]);
+                (commitment.identifier, factor)
+            })
+            .collect();
+        let group_commitment = commitments
+            .iter()
+            .map(|commitment| {
+                commitment.hiding + commitment.binding * binding_factors[&commitment.identifier]
+            })
+            .sum::<EdwardsPoint>();
+        let challenge = hash_to_scalar(&[
+            group_commitment.compress().as_bytes(),
+            group_public_key.as_bytes(),
+            &message,
+        ]);
+        Ok(Self {
+            group_public_key,
+            message,
+            commitments,
+            binding_factors,
+            group_commitment,
+            challenge,
+        })
+    }
+
+    fn binding_factor(&self, identifier: u16) -> Scalar {
+        self.binding_factors[&identifier]
+    }
+
+    /// Lagrange coefficient of `identifier` at 0 over the session's signers
+    fn lagrange(&self, identifier: u16) -> Scalar {
+        let x_i = Scalar::from(identifier);
+        let (numerator, denominator) = self
+            .commitments
+            .iter()
+            .filter(|commitment| commitment.identifier != identifier)
+            .map(|commitment| Scalar::from(commitment.identifier))
+            .fold((Scalar::ONE, Scalar::ONE), |(num, den), x_j| {
+                (num * x_j, den * (x_j - x_i))
+            });
+        numerator * denominator.invert()
+    }
+}
+
+/// Cosigner requests give up after this long
+#[cfg(feature = "app")]
+const COSIGNER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A project whose signing key is split among cosigners, kept as
+/// `<project>.threshold.json` in the key directory
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdConfig {
+    pub group: GroupKey,
+    /// Identifier of the share this device holds in the keychain, if any
+    pub local_identifier: Option<u16>,
+    pub cosigners: Vec<CosignerEndpoint>,
+}
+
+/// A participant reached over HTTP, see the module documentation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CosignerEndpoint {
+    pub identifier: u16,
+    pub url: String,
+}
+
+/// Keychain entry of the share a device holds for a project
+#[cfg(feature = "app")]
+fn local_share_id(project_id: &str) -> String {
+    format!("{project_id}-share")
+}
+
+/// The project's threshold setup; `None` for projects signing with a key
+#[cfg(feature = "app")]
+pub fn load_config(project_id: &str) -> anyhow::Result<Option<ThresholdConfig>> {
+    let path = crate::keychain::threshold_config_path(project_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = std::fs::read_to_string(&path)
+        .with_context(|| format!("unable to read threshold config at {}", path.display()))?;
+    let config = serde_json::from_str(&json)
+        .with_context(|| format!("threshold config at {} is corrupt", path.display()))?;
+    Ok(Some(config))
+}
+
+/// The share this device holds for a project
+#[cfg(feature = "app")]
+pub fn load_local_share(project_id: &str) -> anyhow::Result<KeyShare> {
+    let json = crate::keychain::load_secret(&local_share_id(project_id))
+        .context("this device holds no share of the project's threshold key")?;
+    serde_json::from_str(&json).context("stored key share is corrupt")
+}
+
+/// Split a project's signing key among this device and `cosigner_urls`, any
+/// `min_signers` of which then sign together. The key, and so the project's
+/// public key, stays the same, but the whole key is deleted: this device
+/// keeps share 1, and the returned shares, in the order of `cosigner_urls`,
+/// are for the operators of the cosigner endpoints.
+#[cfg(feature = "app")]
+pub fn enable(
+    project_id: &str,
+    min_signers: u16,
+    cosigner_urls: &[String],
+) -> anyhow::Result<Vec<KeyShare>> {
+    use rand::rngs::OsRng;
+
+    if load_config(project_id)?.is_some() {
+        bail!("project {project_id} already signs with a threshold key");
+    }
+    let max_signers =
+        u16::try_from(cosigner_urls.len() + 1).map_err(|_| anyhow!("too many cosigners"))?;
+    let signing_key = crate::provenance::load_secret_key(project_id)?;
+    let (mut shares, group) = split_key(&signing_key, min_signers, max_signers, &mut OsRng)?;
+    let remote_shares = shares.split_off(1);
+    let config = ThresholdConfig {
+        group,
+        local_identifier: Some(shares[0].identifier),
+        cosigners: remote_shares
+            .iter()
+            .zip(cosigner_urls)
+            .map(|(share, url)| CosignerEndpoint {
+                identifier: share.identifier,
+                url: url.trim_end_matches('/').to_string(),
+            })
+            .collect(),
+    };
+
+    crate::keychain::store_secret(
+        &local_share_id(project_id),
+        &serde_json::to_string(&shares[0])?,
+    )?;
+    let path = crate::keychain::threshold_config_path(project_id)?;
+    crate::keychain::write_private_file(&path, &serde_json::to_string_pretty(&config)?)?;
+    crate::provenance::delete_secret_key(project_id)?;
+    Ok(remote_shares)
+}
+
+/// Sign `message` for a threshold project: this device's share, if it holds
+/// one, and as many cosigners as it takes to reach the threshold, tried in
+/// the configured order
+#[cfg(feature = "app")]
+pub fn sign_with_cosigners(
+    project_id: &str,
+    config: &ThresholdConfig,
+    message: &[u8],
+) -> anyhow::Result<Signature> {
+    use rand::rngs::OsRng;
+
+    let agent = ureq::builder().timeout(COSIGNER_TIMEOUT).build();
+    let session = uuid::Uuid::new_v4().to_string();
+    let needed = usize::from(config.group.min_signers);
+    let encoded_message = STANDARD.encode(message);
+
+    let local = match config.local_identifier {
+        Some(_) => {
+            let share = load_local_share(project_id)?;
+            let (nonces, commitments) = commit(&share, &mut OsRng)?;
+            Some((share, nonces, commitments))
+        }
+        None => None,
+    };
+    let mut commitments: Vec<SigningCommitments> = local
+        .iter()
+        .map(|(_, _, commitments)| commitments.clone())
+        .collect();
+    let mut signers = Vec::new();
+    let mut unavailable = Vec::new();
+    for cosigner in &config.cosigners {
+        if commitments.len() >= needed {
+            break;
+        }
+        let answer = agent
+            .post(&format!("{}/commit", cosigner.url))
+            .send_json(serde_json::json!({
+                "session": session,
+                "group_public_key": config.group.group_public_key,
+                "message": encoded_message,
+            }))
+            .map_err(anyhow::Error::from)
+            .and_then(|response| Ok(response.into_json::<SigningCommitments>()?));
+        match answer {
+            Ok(answer) if answer.identifier == cosigner.identifier => {
+                commitments.push(answer);
+                signers.push(cosigner);
+            }
+            Ok(answer) => unavailable.push(format!(
+                "{} answered as participant {}",
+                cosigner.url, answer.identifier
+            )),
+            Err(err) => unavailable.push(format!("{}: {err}", cosigner.url)),
+        }
+    }
+    if commitments.len() < needed {
+        bail!(
+            "{} of the {needed} signers needed are available; {}",
+            commitments.len(),
+            unavailable.join("; ")
+        );
+    }
+
+    let package = SigningPackage {
+        message: encoded_message,
+        commitments,
+    };
+    let mut shares = Vec::new();
+    if let Some((share, nonces, _)) = local {
+        shares.push(sign(&share, nonces, &package)?);
+    }
+    for cosigner in signers {
+        let share: SignatureShare = agent
+            .post(&format!("{}/sign", cosigner.url))
+            .send_json(serde_json::json!({ "session": session, "package": package }))
+            .with_context(|| format!("cosigner {} did not sign", cosigner.url))?
+            .into_json()?;
+        shares.push(share);
+    }
+    aggregate(&config.group, &package, &shares)
+}
+
+fn hash(parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash(parts))
+}
+
+fn random_scalar(rng: &mut impl CryptoRngCore) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+/// Nonces mix fresh randomness with the secret, so a weak RNG alone does
+/// not leak the share
+fn generate_nonce(secret: &Scalar, rng: &mut impl CryptoRngCore) -> Scalar {
+    let mut random = [0u8; 32];
+    rng.fill_bytes(&mut random);
+    hash_to_scalar(&[CONTEXT, b"nonce", &random, secret.as_bytes()])
+}
+
+fn encode_point(point: &EdwardsPoint) -> String {
+    STANDARD.encode(point.compress().as_bytes())
+}
+
+/// Decode a point of the prime-order subgroup, other than the identity
+fn decode_point(encoded: &str) -> anyhow::Result<EdwardsPoint> {
+    let bytes: [u8; 32] = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("invalid point encoding"))?;
+    let point = CompressedEdwardsY(bytes)
+        .decompress()
+        .filter(|point| point.compress().to_bytes() == bytes)
+        .ok_or_else(|| anyhow!("invalid point encoding"))?;
+    if point.is_identity() || !point.is_torsion_free() {
+        bail!("point is not in the prime-order subgroup");
+    }
+    Ok(point)
+}
+
+fn decode_scalar(encoded: &str) -> anyhow::Result<Scalar> {
+    let bytes: [u8; 32] = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("invalid scalar encoding"))?;
+    Option::from(Scalar::from_canonical_bytes(bytes))
+        .ok_or_else(|| anyhow!("scalar is not reduced"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_core::OsRng;
+
+    /// Run both rounds among `signers` and aggregate
+    fn threshold_sign(
+        shares: &[KeyShare],
+        group: &GroupKey,
+        signers: &[u16],
+        message: &[u8],
+    ) -> anyhow::Result<Signature> {
+        let signing: Vec<&KeyShare> = shares
+            .iter()
+            .filter(|share| signers.contains(&share.identifier))
+            .collect();
+        let mut nonces = Vec::new();
+        let mut commitments = Vec::new();
+        for share in &signing {
+            let (share_nonces, share_commitments) = commit(share, &mut OsRng)?;
+            nonces.push(share_nonces);
+            commitments.push(share_commitments);
+        }
+        let package = SigningPackage {
+            message: STANDARD.encode(message),
+            commitments,
+        };
+        let signature_shares = signing
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| sign(share, nonces, &package))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        aggregate(group, &package, &signature_shares)
+    }
+
+    #[test]
+    fn any_threshold_of_shares_signs_for_the_project_key() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let (shares, group) = split_key(&signing_key, 2, 3, &mut OsRng).unwrap();
+        assert_eq!(
+            group.group_public_key,
+            STANDARD.encode(signing_key.verifying_key().as_bytes())
+        );
+
+        for signers in [[1, 2], [1, 3], [2, 3]] {
+            let signature = threshold_sign(&shares, &group, &signers, b"curr_chain").unwrap();
+            assert!(signing_key
+                .verifying_key()
+                .verify_strict(b"curr_chain", &signature)
+                .is_ok());
+        }
+        let all = threshold_sign(&shares, &group, &[1, 2, 3], b"car:1").unwrap();
+        assert!(signing_key.verifying_key().verify(b"car:1", &all).is_ok());
+
+        // One share alone signs nothing
+        let err = threshold_sign(&shares, &group, &[2], b"curr_chain").unwrap_err();
+        assert!(err.to_string().contains("2 are needed"));
+        assert!(split_key(&signing_key, 1, 3, &mut OsRng).is_err());
+    }
+
+    #[test]
+    fn a_bad_share_is_pinned_on_its_participant() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let (shares, group) = split_key(&signing_key, 2, 3, &mut OsRng).unwrap();
+        let (nonces_1, commitments_1) = commit(&shares[0], &mut OsRng).unwrap();
+        let (nonces_3, commitments_3) = commit(&shares[2], &mut OsRng).unwrap();
+        let package = SigningPackage {
+            message: STANDARD.encode(b"curr_chain"),
+            commitments: vec![commitments_1, commitments_3.clone()],
+        };
+        let share_1 = sign(&shares[0], nonces_1, &package).unwrap();
+        let mut share_3 = sign(&shares[2], nonces_3, &package).unwrap();
+        share_3.share = share_1.share.clone();
+        let err = aggregate(&group, &package, &[share_1, share_3]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "signature share of participant 3 does not verify"
+        );
+
+        // Nonces only sign a package that carries their commitments
+        let (nonces_2, _) = commit(&shares[1], &mut OsRng).unwrap();
+        assert!(sign(&shares[1], nonces_2, &package).is_err());
+    }
+}
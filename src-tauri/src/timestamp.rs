@@ -0,0 +1,507 @@
+// src-tauri/src/timestamp.rs
+//!
+//! RFC 3161 trusted timestamps for CARs
+//!
+//! A CAR's `created_at` is asserted by its own signer. To give auditors a
+//! third party's word on when a receipt existed, a timestamp authority (TSA)
+//! can be asked at emit time to stamp the sha256 of the CAR id. The token it
+//! returns is kept as an `rfc3161:<base64 DER>` entry of `signatures`, which
+//! the CAR id and body signature leave out, so stamping a signed CAR leaves
+//! its signatures intact and verifiers that predate timestamps skip it.
+//!
+//! Verification reads the token's TSTInfo: the stamped hash must be the
+//! CAR's, and the TSA's `genTime` is reported. The TSA's CMS signature over
+//! the TSTInfo is not checked here; auditors check it against the TSA's
+//! certificate chain, e.g. with `openssl ts -verify`.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::car::Car;
+
+/// Prefix of the `signatures` entry carrying a timestamp token
+pub const SIGNATURE_PREFIX: &str = "rfc3161:";
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_EXPLICIT_0: u8 = 0xA0;
+
+/// 2.16.840.1.101.3.4.2.1
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+/// 1.2.840.113549.1.7.2
+const OID_SIGNED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+/// 1.2.840.113549.1.9.16.1.4
+const OID_TST_INFO: &[u8] = &[
+    0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x10, 0x01, 0x04,
+];
+
+/// What a timestamp token says about the CAR it stamps
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TimestampInfo {
+    /// When the TSA saw the CAR id's hash
+    pub gen_time: DateTime<Utc>,
+    /// TSA policy the token was issued under, as a dotted OID
+    pub policy: String,
+    /// Hex serial number the TSA gave the token
+    pub serial_number: String,
+    /// Hex digest the TSA stamped
+    pub message_imprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+/// The digest a CAR's timestamp stamps: sha256 of the CAR id
+pub fn car_digest(car_id: &str) -> [u8; 32] {
+    Sha256::digest(car_id.as_bytes()).into()
+}
+
+/// DER `TimeStampReq` for a sha256 `digest`, asking for the TSA certificate
+pub fn request(digest: &[u8; 32], nonce: u64) -> Vec<u8> {
+    encode(
+        TAG_SEQUENCE,
+        &[
+            encode(TAG_INTEGER, &[1]),
+            message_imprint(digest),
+            encode(TAG_INTEGER, &unsigned_integer(&nonce.to_be_bytes())),
+            encode(TAG_BOOLEAN, &[0xFF]),
+        ]
+        .concat(),
+    )
+}
+
+/// DER timestamp token of a granted `TimeStampResp`
+pub fn token_from_response(response: &[u8]) -> Result<Vec<u8>, String> {
+    let (resp, _) = read(response)?;
+    let mut fields = resp.expect(TAG_SEQUENCE, "TimeStampResp")?;
+    let status_info = fields.next_tlv("PKIStatusInfo")?;
+    let status = status_info
+        .expect(TAG_SEQUENCE, "PKIStatusInfo")?
+        .next_tlv("status")?;
+    // 0 granted, 1 granted with modifications
+    match status.expect_content(TAG_INTEGER, "status")? {
+        [0] | [1] => {}
+        code => {
+            return Err(format!(
+                "timestamp authority refused the request (status {})",
+                code.last().copied().unwrap_or_default()
+            ))
+        }
+    }
+    Ok(fields.next_tlv("timeStampToken")?.raw.to_vec())
+}
+
+/// Read the TSTInfo out of a DER timestamp token (a CMS `ContentInfo`)
+pub fn parse_token(token: &[u8]) -> Result<TimestampInfo, String> {
+    let (content_info, _) = read(token)?;
+    let mut content_info = content_info.expect(TAG_SEQUENCE, "ContentInfo")?;
+    if content_info
+        .next_tlv("contentType")?
+        .expect_content(TAG_OID, "contentType")?
+        != OID_SIGNED_DATA
+    {
+        return Err("timestamp token is not CMS SignedData".to_string());
+    }
+    let signed_data = content_info.next_tlv("content")?;
+    let (signed_data, _) = read(signed_data.expect_content(TAG_EXPLICIT_0, "content")?)?;
+    let mut signed_data = signed_data.expect(TAG_SEQUENCE, "SignedData")?;
+    signed_data.next_tlv("version")?;
+    signed_data.next_tlv("digestAlgorithms")?;
+    let mut encapsulated = signed_data
+        .next_tlv("encapContentInfo")?
+        .expect(TAG_SEQUENCE, "encapContentInfo")?;
+    if encapsulated
+        .next_tlv("eContentType")?
+        .expect_content(TAG_OID, "eContentType")?
+        != OID_TST_INFO
+    {
+        return Err("timestamp token does not carry a TSTInfo".to_string());
+    }
+    let (octets, _) = read(
+        encapsulated
+            .next_tlv("eContent")?
+            .expect_content(TAG_EXPLICIT_0, "eContent")?,
+    )?;
+    let (tst_info, _) = read(octets.expect_content(TAG_OCTET_STRING, "eContent")?)?;
+
+    let mut tst_info = tst_info.expect(TAG_SEQUENCE, "TSTInfo")?;
+    tst_info.next_tlv("version")?;
+    let policy = dotted_oid(
+        tst_info
+            .next_tlv("policy")?
+            .expect_content(TAG_OID, "policy")?,
+    );
+    let mut imprint = tst_info
+        .next_tlv("messageImprint")?
+        .expect(TAG_SEQUENCE, "messageImprint")?;
+    let mut algorithm = imprint
+        .next_tlv("hashAlgorithm")?
+        .expect(TAG_SEQUENCE, "hashAlgorithm")?;
+    if algorithm
+        .next_tlv("algorithm")?
+        .expect_content(TAG_OID, "algorithm")?
+        != OID_SHA256
+    {
+        return Err("timestamp token does not stamp a sha256 digest".to_string());
+    }
+    let hashed = imprint.next_tlv("hashedMessage")?;
+    let message_imprint = hex::encode(hashed.expect_content(TAG_OCTET_STRING, "hashedMessage")?);
+    let serial = tst_info.next_tlv("serialNumber")?;
+    let serial_number = hex::encode(serial.expect_content(TAG_INTEGER, "serialNumber")?);
+    let gen_time = tst_info.next_tlv("genTime")?;
+    let gen_time = generalized_time(gen_time.expect_content(TAG_GENERALIZED_TIME, "genTime")?)?;
+
+    // accuracy and ordering may precede the optional nonce
+    let nonce = tst_info
+        .find(|field| field.tag == TAG_INTEGER)
+        .map(|field| hex::encode(field.content));
+
+    Ok(TimestampInfo {
+        gen_time,
+        policy,
+        serial_number,
+        message_imprint,
+        nonce,
+    })
+}
+
+/// Base64 token of the CAR's `rfc3161:` entry, if it has one
+pub fn car_token(car: &Car) -> Option<&str> {
+    car.signatures
+        .iter()
+        .find_map(|entry| entry.strip_prefix(SIGNATURE_PREFIX))
+}
+
+/// Read the CAR's timestamp token and check that it stamps this CAR;
+/// `Ok(None)` when the CAR was not timestamped
+pub fn verify_car_timestamp(car: &Car) -> Result<Option<TimestampInfo>, String> {
+    let Some(encoded) = car_token(car) else {
+        return Ok(None);
+    };
+    let token = STANDARD
+        .decode(encoded)
+        .map_err(|err| format!("timestamp token is not base64: {err}"))?;
+    let info = parse_token(&token)?;
+    let expected = hex::encode(car_digest(&car.id));
+    if info.message_imprint != expected {
+        return Err(format!(
+            "timestamp token stamps another document\nExpected: {expected}\nStamped: {}",
+            info.message_imprint
+        ));
+    }
+    Ok(Some(info))
+}
+
+/// Have the TSA at `tsa_url` stamp the signed `car`, adding its token to
+/// `signatures`
+#[cfg(feature = "app")]
+pub fn stamp_car(car: &mut Car, tsa_url: &str) -> anyhow::Result<TimestampInfo> {
+    use anyhow::{anyhow, Context};
+    use std::io::Read;
+
+    /// Tokens carry the TSA certificate chain, which stays well below this
+    const MAX_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+    let nonce: u64 = rand::random();
+    let response = ureq::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .post(tsa_url)
+        .set("Content-Type", "application/timestamp-query")
+        .send_bytes(&request(&car_digest(&car.id), nonce))
+        .with_context(|| format!("timestamp authority {tsa_url} did not answer"))?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut body)?;
+
+    let token = token_from_response(&body).map_err(|err| anyhow!(err))?;
+    let info = parse_token(&token).map_err(|err| anyhow!(err))?;
+    if info.message_imprint != hex::encode(car_digest(&car.id)) {
+        return Err(anyhow!("timestamp authority stamped another digest"));
+    }
+    if info.nonce.as_deref() != Some(hex::encode(unsigned_integer(&nonce.to_be_bytes())).as_str()) {
+        return Err(anyhow!("timestamp authority answered with another nonce"));
+    }
+    car.signatures
+        .push(format!("{SIGNATURE_PREFIX}{}", STANDARD.encode(token)));
+    Ok(info)
+}
+
+fn message_imprint(digest: &[u8; 32]) -> Vec<u8> {
+    let algorithm = encode(
+        TAG_SEQUENCE,
+        &[encode(TAG_OID, OID_SHA256), encode(TAG_NULL, &[])].concat(),
+    );
+    encode(
+        TAG_SEQUENCE,
+        &[algorithm, encode(TAG_OCTET_STRING, digest)].concat(),
+    )
+}
+
+/// Minimal two's complement content of a non-negative big-endian integer
+fn unsigned_integer(bytes: &[u8]) -> Vec<u8> {
+    let significant = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len() - 1);
+    let mut content = bytes[significant..].to_vec();
+    if content[0] & 0x80 != 0 {
+        content.insert(0, 0);
+    }
+    content
+}
+
+fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    if content.len() < 0x80 {
+        encoded.push(content.len() as u8);
+    } else {
+        let length = content.len().to_be_bytes();
+        let significant = length.iter().position(|&byte| byte != 0).unwrap_or(0);
+        encoded.push(0x80 | (length.len() - significant) as u8);
+        encoded.extend_from_slice(&length[significant..]);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+/// One DER element: its tag, its content and its whole encoding
+struct Tlv<'a> {
+    tag: u8,
+    content: &'a [u8],
+    raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    fn expect_content(&self, tag: u8, name: &str) -> Result<&'a [u8], String> {
+        if self.tag == tag {
+            Ok(self.content)
+        } else {
+            Err(format!("timestamp token: malformed {name}"))
+        }
+    }
+
+    /// Children of a constructed element
+    fn expect(&self, tag: u8, name: &str) -> Result<Children<'a>, String> {
+        self.expect_content(tag, name)
+            .map(|content| Children { rest: content })
+    }
+}
+
+struct Children<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Children<'a> {
+    fn next_tlv(&mut self, name: &str) -> Result<Tlv<'a>, String> {
+        if self.rest.is_empty() {
+            return Err(format!("timestamp token: missing {name}"));
+        }
+        let (tlv, rest) = read(self.rest)?;
+        self.rest = rest;
+        Ok(tlv)
+    }
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Tlv<'a>;
+
+    fn next(&mut self) -> Option<Tlv<'a>> {
+        self.next_tlv("element").ok()
+    }
+}
+
+/// Split the first DER element off `input`
+fn read(input: &[u8]) -> Result<(Tlv<'_>, &[u8]), String> {
+    let truncated = || "timestamp token: truncated DER".to_string();
+    let (&tag, rest) = input.split_first().ok_or_else(truncated)?;
+    let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+    let (length, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return Err("timestamp token: unsupported DER length".to_string());
+        }
+        let length = rest[..count]
+            .iter()
+            .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+        (length, &rest[count..])
+    };
+    if rest.len() < length {
+        return Err(truncated());
+    }
+    let header = input.len() - rest.len();
+    Ok((
+        Tlv {
+            tag,
+            content: &rest[..length],
+            raw: &input[..header + length],
+        },
+        &rest[length..],
+    ))
+}
+
+fn dotted_oid(content: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut value: u64 = 0;
+    for &byte in content {
+        value = (value << 7) | u64::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first);
+                arcs.push(value - first * 40);
+            } else {
+                arcs.push(value);
+            }
+            value = 0;
+        }
+    }
+    arcs.iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `YYYYMMDDHHMMSS[.fff]Z`, always UTC in timestamp tokens
+fn generalized_time(content: &[u8]) -> Result<DateTime<Utc>, String> {
+    let text = std::str::from_utf8(content)
+        .ok()
+        .and_then(|text| text.strip_suffix('Z'))
+        .ok_or_else(|| "timestamp token: genTime is not UTC".to_string())?;
+    let (seconds, fraction) = text.split_once('.').unwrap_or((text, ""));
+    let time = NaiveDateTime::parse_from_str(seconds, "%Y%m%d%H%M%S")
+        .map_err(|err| format!("timestamp token: invalid genTime: {err}"))?;
+    let nanos = format!("{fraction:0<9}")
+        .get(..9)
+        .and_then(|digits| digits.parse::<u32>().ok())
+        .ok_or_else(|| "timestamp token: invalid genTime fraction".to_string())?;
+    let time = time + chrono::Duration::nanoseconds(i64::from(nanos));
+    Ok(DateTime::from_naive_utc_and_offset(time, Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A token as a TSA would return it, minus certificates and signer infos
+    fn token(digest: &[u8], gen_time: &str, nonce: u64) -> Vec<u8> {
+        let tst_info = encode(
+            TAG_SEQUENCE,
+            &[
+                encode(TAG_INTEGER, &[1]),
+                // 1.3.6.1.4.1.4146.2.2
+                encode(
+                    TAG_OID,
+                    &[0x2B, 0x06, 0x01, 0x04, 0x01, 0xA0, 0x32, 0x02, 0x02],
+                ),
+                message_imprint(digest.try_into().unwrap()),
+                encode(TAG_INTEGER, &[0x01, 0x23]),
+                encode(TAG_GENERALIZED_TIME, gen_time.as_bytes()),
+                encode(TAG_INTEGER, &unsigned_integer(&nonce.to_be_bytes())),
+            ]
+            .concat(),
+        );
+        let encapsulated = encode(
+            TAG_SEQUENCE,
+            &[
+                encode(TAG_OID, OID_TST_INFO),
+                encode(TAG_EXPLICIT_0, &encode(TAG_OCTET_STRING, &tst_info)),
+            ]
+            .concat(),
+        );
+        let signed_data = encode(
+            TAG_SEQUENCE,
+            &[
+                encode(TAG_INTEGER, &[3]),
+                encode(0x31, &[]),
+                encapsulated,
+                encode(0x31, &[]),
+            ]
+            .concat(),
+        );
+        encode(
+            TAG_SEQUENCE,
+            &[
+                encode(TAG_OID, OID_SIGNED_DATA),
+                encode(TAG_EXPLICIT_0, &signed_data),
+            ]
+            .concat(),
+        )
+    }
+
+    #[test]
+    fn requests_are_der_timestamp_queries() {
+        let request = request(&[0xAB; 32], 0x80);
+        // SEQUENCE { version 1, messageImprint, nonce 0x0080, certReq TRUE }
+        assert_eq!(&request[..5], &[0x30, 0x3D, 0x02, 0x01, 0x01]);
+        assert_eq!(
+            &request[request.len() - 7..],
+            &[0x02, 0x02, 0x00, 0x80, 0x01, 0x01, 0xFF]
+        );
+    }
+
+    #[test]
+    fn tokens_are_read_out_of_granted_responses() {
+        let digest = car_digest("car:abc");
+        let token = token(&digest, "20250102030405.25Z", 7);
+        let status = encode(TAG_SEQUENCE, &encode(TAG_INTEGER, &[0]));
+        let response = encode(TAG_SEQUENCE, &[status, token.clone()].concat());
+        assert_eq!(token_from_response(&response).unwrap(), token);
+
+        let info = parse_token(&token).unwrap();
+        assert_eq!(info.message_imprint, hex::encode(digest));
+        assert_eq!(info.policy, "1.3.6.1.4.1.4146.2.2");
+        assert_eq!(info.serial_number, "0123");
+        assert_eq!(info.nonce.as_deref(), Some("07"));
+        assert_eq!(info.gen_time.to_rfc3339(), "2025-01-02T03:04:05.250+00:00");
+
+        let rejection = encode(
+            TAG_SEQUENCE,
+            &encode(TAG_SEQUENCE, &encode(TAG_INTEGER, &[2])),
+        );
+        assert!(token_from_response(&rejection)
+            .unwrap_err()
+            .contains("status 2"));
+    }
+
+    #[test]
+    fn car_timestamps_must_stamp_the_car_id() {
+        let mut car: Car = serde_json::from_value(serde_json::json!({
+            "id": "car:abc",
+            "run_id": "run-1",
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {"kind": "exact", "name": "t", "model": "m", "version": "1", "seed": 1, "steps": []},
+            "proof": {"match_kind": "process"},
+            "policy_ref": {"hash": "h", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 0, "nature_cost": 0.0},
+            "provenance": [],
+            "checkpoints": [],
+            "sgrade": {"score": 0, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": "",
+            "signatures": ["ed25519-body:sig"]
+        }))
+        .unwrap();
+        assert_eq!(verify_car_timestamp(&car), Ok(None));
+
+        let stamped = token(&car_digest(&car.id), "20250101000001Z", 1);
+        car.signatures
+            .push(format!("{SIGNATURE_PREFIX}{}", STANDARD.encode(&stamped)));
+        let info = verify_car_timestamp(&car).unwrap().unwrap();
+        assert_eq!(info.gen_time.to_rfc3339(), "2025-01-01T00:00:01+00:00");
+
+        car.id = "car:other".to_string();
+        assert!(verify_car_timestamp(&car)
+            .unwrap_err()
+            .contains("another document"));
+    }
+}
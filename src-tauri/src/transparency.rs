@@ -0,0 +1,355 @@
+// src-tauri/src/transparency.rs
+//!
+//! Transparency log publishing for CARs
+//!
+//! A signer could quietly issue a second, different receipt for the same run
+//! and show each party the one it prefers. Publishing every CAR to an
+//! append-only public log makes that visible: at emit time the sha256 of the
+//! CAR id, with the checkpoint signature over the id and the signer's key,
+//! is submitted to a Rekor-style log as a `hashedrekord` entry. The log's
+//! answer, with the inclusion proof of the entry, is stored in the bundle as
+//! `transparency-log.json` next to the CAR.
+//!
+//! Verification checks that the logged entry names this CAR and that the
+//! entry's leaf proves into the logged tree root (RFC 9162 Merkle audit
+//! path). That the root is one the log actually published is left to the
+//! auditor, who compares it with the log's signed checkpoint.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::car::Car;
+
+/// Name of the log entry in a CAR bundle
+pub const BUNDLE_ENTRY: &str = "transparency-log.json";
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// A CAR's entry in a transparency log, as the log returned it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntryProof {
+    /// Base URL of the log
+    pub log_url: String,
+    pub uuid: String,
+    pub log_index: u64,
+    /// Unix seconds at which the log integrated the entry
+    pub integrated_time: i64,
+    /// Base64 canonical entry the log hashed into its leaf
+    pub body: String,
+    pub inclusion_proof: InclusionProof,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionProof {
+    /// Index of the leaf in the tree `root_hash` is the root of
+    pub log_index: u64,
+    pub tree_size: u64,
+    /// Hex sha256 tree root
+    pub root_hash: String,
+    /// Hex audit path from the leaf up to the root
+    pub hashes: Vec<String>,
+}
+
+/// The digest a CAR is logged under: sha256 of the CAR id
+pub fn car_digest(car_id: &str) -> String {
+    hex::encode(Sha256::digest(car_id.as_bytes()))
+}
+
+/// RFC 9162 leaf hash of a log entry
+pub fn leaf_hash(entry: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([LEAF_PREFIX])
+        .chain_update(entry)
+        .finalize()
+        .into()
+}
+
+fn node_hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([NODE_PREFIX])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+/// Whether `path` proves `leaf` at `index` into the root of a tree of
+/// `tree_size` leaves (RFC 9162, section 2.1.3.2)
+pub fn verify_inclusion(
+    leaf: &[u8; 32],
+    index: u64,
+    tree_size: u64,
+    path: &[[u8; 32]],
+    root: &[u8; 32],
+) -> bool {
+    if index >= tree_size {
+        return false;
+    }
+    let (mut node, mut last) = (index, tree_size - 1);
+    let mut hash = *leaf;
+    for sibling in path {
+        if last == 0 {
+            return false;
+        }
+        if node & 1 == 1 || node == last {
+            hash = node_hash(sibling, &hash);
+            while node & 1 == 0 && node != 0 {
+                node >>= 1;
+                last >>= 1;
+            }
+        } else {
+            hash = node_hash(&hash, sibling);
+        }
+        node >>= 1;
+        last >>= 1;
+    }
+    last == 0 && hash == *root
+}
+
+/// Check that `entry` logs `car` and that its inclusion proof holds
+pub fn verify_entry(car: &Car, entry: &LogEntryProof) -> Result<(), String> {
+    let body = STANDARD
+        .decode(&entry.body)
+        .map_err(|err| format!("log entry body is not base64: {err}"))?;
+    let logged: Value = serde_json::from_slice(&body)
+        .map_err(|err| format!("log entry body is not JSON: {err}"))?;
+    let hash = &logged["spec"]["data"]["hash"];
+    let expected = car_digest(&car.id);
+    if hash["algorithm"] != "sha256" || hash["value"] != expected.as_str() {
+        return Err(format!(
+            "log entry {} does not log this CAR\nExpected sha256: {expected}",
+            entry.uuid
+        ));
+    }
+
+    let decode = |name: &str, encoded: &str| {
+        hex::decode(encoded)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| format!("log inclusion proof has an invalid {name}"))
+    };
+    let proof = &entry.inclusion_proof;
+    let root = decode("root hash", &proof.root_hash)?;
+    let path = proof
+        .hashes
+        .iter()
+        .map(|hash| decode("audit path hash", hash))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !verify_inclusion(
+        &leaf_hash(&body),
+        proof.log_index,
+        proof.tree_size,
+        &path,
+        &root,
+    ) {
+        return Err(format!(
+            "log entry {} does not prove into root {} of tree size {}",
+            entry.uuid, proof.root_hash, proof.tree_size
+        ));
+    }
+    Ok(())
+}
+
+/// Submit the signed `car` to the log at `log_url` and return the verified
+/// entry the log created
+#[cfg(feature = "app")]
+pub fn publish(car: &Car, log_url: &str) -> anyhow::Result<LogEntryProof> {
+    use anyhow::{anyhow, Context};
+
+    /// DER SubjectPublicKeyInfo prefix of an ed25519 key
+    const ED25519_SPKI_PREFIX: [u8; 12] = [
+        0x30, 0x2A, 0x30, 0x05, 0x06, 0x03, 0x2B, 0x65, 0x70, 0x03, 0x21, 0x00,
+    ];
+
+    let signature = car
+        .signatures
+        .iter()
+        .find_map(|entry| match crate::car::parse_signature(entry) {
+            Some((crate::car::SignatureKind::Checkpoint, encoded)) => Some(encoded),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("CAR has no checkpoint signature to log"))?;
+    let key = STANDARD
+        .decode(&car.signer_public_key)
+        .context("signer public key is not base64")?;
+    let pem = format!(
+        "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+        STANDARD.encode([ED25519_SPKI_PREFIX.as_slice(), &key].concat())
+    );
+    let proposed = serde_json::json!({
+        "apiVersion": "0.0.1",
+        "kind": "hashedrekord",
+        "spec": {
+            "signature": {
+                "content": signature,
+                "publicKey": {"content": STANDARD.encode(pem)},
+            },
+            "data": {"hash": {"algorithm": "sha256", "value": car_digest(&car.id)}},
+        },
+    });
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct LoggedEntry {
+        body: String,
+        integrated_time: i64,
+        log_index: u64,
+        verification: Verification,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Verification {
+        inclusion_proof: InclusionProof,
+    }
+
+    let log_url = log_url.trim_end_matches('/');
+    let created: std::collections::BTreeMap<String, LoggedEntry> = ureq::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .post(&format!("{log_url}/api/v1/log/entries"))
+        .send_json(proposed)
+        .with_context(|| format!("transparency log {log_url} did not accept the entry"))?
+        .into_json()
+        .context("transparency log answered with an unexpected body")?;
+    let (uuid, logged) = created
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("transparency log answered without an entry"))?;
+
+    let entry = LogEntryProof {
+        log_url: log_url.to_string(),
+        uuid,
+        log_index: logged.log_index,
+        integrated_time: logged.integrated_time,
+        body: logged.body,
+        inclusion_proof: logged.verification.inclusion_proof,
+    };
+    verify_entry(car, &entry).map_err(|err| anyhow!(err))?;
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 9162 tree root and audit path, computed top-down as the RFC defines them
+    fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        match leaves.len() {
+            0 => Sha256::digest([]).into(),
+            1 => leaves[0],
+            n => {
+                let split = split(n);
+                node_hash(&root(&leaves[..split]), &root(&leaves[split..]))
+            }
+        }
+    }
+
+    fn path(index: usize, leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        if leaves.len() <= 1 {
+            return Vec::new();
+        }
+        let split = split(leaves.len());
+        if index < split {
+            let mut path = path(index, &leaves[..split]);
+            path.push(root(&leaves[split..]));
+            path
+        } else {
+            let mut path = path(index - split, &leaves[split..]);
+            path.push(root(&leaves[..split]));
+            path
+        }
+    }
+
+    /// Largest power of two below `n`
+    fn split(n: usize) -> usize {
+        let mut split = 1;
+        while split * 2 < n {
+            split *= 2;
+        }
+        split
+    }
+
+    #[test]
+    fn audit_paths_prove_every_leaf_of_every_tree_size() {
+        for size in 1..=9usize {
+            let leaves: Vec<[u8; 32]> = (0..size).map(|i| leaf_hash(&[i as u8])).collect();
+            let root = root(&leaves);
+            for index in 0..size {
+                let path = path(index, &leaves);
+                let (index, size) = (index as u64, size as u64);
+                assert!(verify_inclusion(
+                    &leaves[index as usize],
+                    index,
+                    size,
+                    &path,
+                    &root
+                ));
+                assert!(!verify_inclusion(&leaves[0], index + 1, size, &path, &root));
+                if !path.is_empty() {
+                    assert!(!verify_inclusion(
+                        &leaves[index as usize],
+                        index,
+                        size,
+                        &path[1..],
+                        &root
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn log_entries_must_name_the_car_and_prove_into_the_root() {
+        let mut car: Car = serde_json::from_value(serde_json::json!({
+            "id": "car:abc",
+            "run_id": "run-1",
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {"kind": "exact", "name": "t", "model": "m", "version": "1", "seed": 1, "steps": []},
+            "proof": {"match_kind": "process"},
+            "policy_ref": {"hash": "h", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 0, "nature_cost": 0.0},
+            "provenance": [],
+            "checkpoints": [],
+            "sgrade": {"score": 0, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": "",
+            "signatures": []
+        }))
+        .unwrap();
+        let body = serde_json::to_vec(&serde_json::json!({
+            "kind": "hashedrekord",
+            "spec": {"data": {"hash": {"algorithm": "sha256", "value": car_digest(&car.id)}}}
+        }))
+        .unwrap();
+        let leaves = [leaf_hash(b"earlier"), leaf_hash(&body), leaf_hash(b"later")];
+        let entry = LogEntryProof {
+            log_url: "https://log.example".to_string(),
+            uuid: "entry-1".to_string(),
+            log_index: 1,
+            integrated_time: 1_735_689_600,
+            body: STANDARD.encode(&body),
+            inclusion_proof: InclusionProof {
+                log_index: 1,
+                tree_size: 3,
+                root_hash: hex::encode(root(&leaves)),
+                hashes: path(1, &leaves).iter().map(hex::encode).collect(),
+            },
+        };
+        assert_eq!(verify_entry(&car, &entry), Ok(()));
+
+        let mut moved = entry.clone();
+        moved.inclusion_proof.log_index = 2;
+        assert!(verify_entry(&car, &moved)
+            .unwrap_err()
+            .contains("does not prove"));
+
+        car.id = "car:other".to_string();
+        assert!(verify_entry(&car, &entry)
+            .unwrap_err()
+            .contains("does not log this CAR"));
+    }
+}
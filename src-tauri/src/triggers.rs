@@ -0,0 +1,318 @@
+// src-tauri/src/triggers.rs
+//!
+//! Run Triggers: start parameterized runs from external events
+//!
+//! A file-drop trigger watches a directory for `*.json` payload files. Each
+//! payload starts a new execution of the trigger's run:
+//!
+//! - a JSON object supplies `{{name}}` parameters for the run's prompts
+//! - any other payload is exposed as the single `{{payload}}` parameter
+//! - the sha256 of the raw payload bytes is recorded on the execution and
+//!   claimed as an `input` in the CAR
+//!
+//! A payload is claimed (renamed to a hidden name) before it fires and then
+//! moved to `processed/` (or `failed/`) inside the watch directory, so it is
+//! never fired twice. A payload that cannot be read or fired is recorded as a
+//! failed event without holding up the others. External systems should write
+//! payloads under a temporary name and rename them to `*.json` once complete.
+
+use crate::orchestrator::{self, LlmClient, RunInvocation};
+use crate::store::run_triggers::{self, RunTrigger, RunTriggerEvent};
+use crate::{attachments, provenance, DbPool};
+use anyhow::{anyhow, Context};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub const PROCESSED_DIR: &str = "processed";
+pub const FAILED_DIR: &str = "failed";
+
+/// Payloads larger than this are rejected without being read
+pub const MAX_PAYLOAD_BYTES: u64 = 1024 * 1024;
+
+/// Build the invocation for a raw trigger payload
+pub fn parse_trigger_payload(bytes: &[u8]) -> anyhow::Result<RunInvocation> {
+    let text = std::str::from_utf8(bytes).context("trigger payload is not valid UTF-8")?;
+    let parameters = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Object(map)) => map,
+        Ok(other) => {
+            let mut map = serde_json::Map::new();
+            map.insert("payload".to_string(), other);
+            map
+        }
+        Err(err) => return Err(anyhow!("trigger payload is not valid JSON: {}", err)),
+    };
+
+    Ok(RunInvocation {
+        parameters,
+        input_sha256: Some(provenance::sha256_hex(bytes)),
+        ..RunInvocation::default()
+    })
+}
+
+/// Start one execution of the trigger's run for a payload and record the event
+pub(crate) fn fire_trigger(
+    pool: &DbPool,
+    trigger: &RunTrigger,
+    source_name: &str,
+    payload: &[u8],
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<RunTriggerEvent> {
+    let payload_sha256 = provenance::sha256_hex(payload);
+
+    let outcome = parse_trigger_payload(payload).and_then(|invocation| {
+        // Keep the payload retrievable by its hash for later audit
+        if let (Some(store), Ok(text)) = (
+            attachments::try_get_global_attachment_store(),
+            std::str::from_utf8(payload),
+        ) {
+            store.store_with_hash(&payload_sha256, text)?;
+        }
+        orchestrator::start_run_with_invocation(pool, &trigger.run_id, llm_client, &invocation)
+    });
+
+    let conn = pool.get()?;
+    let event = match outcome {
+        Ok(execution) => run_triggers::record_event(
+            &conn,
+            &trigger.id,
+            Some(execution.id.as_str()),
+            source_name,
+            &payload_sha256,
+            None,
+        )?,
+        Err(err) => run_triggers::record_event(
+            &conn,
+            &trigger.id,
+            None,
+            source_name,
+            &payload_sha256,
+            Some(&err.to_string()),
+        )?,
+    };
+
+    Ok(event)
+}
+
+/// Scan every enabled file-drop trigger once and fire pending payloads
+pub fn poll_file_drop_triggers(pool: &DbPool) -> anyhow::Result<Vec<RunTriggerEvent>> {
+    let client = orchestrator::DispatchingLlmClient::new();
+    poll_file_drop_triggers_with_client(pool, &client)
+}
+
+pub(crate) fn poll_file_drop_triggers_with_client(
+    pool: &DbPool,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<Vec<RunTriggerEvent>> {
+    let triggers = {
+        let conn = pool.get()?;
+        run_triggers::list_enabled(&conn, run_triggers::KIND_FILE_DROP)?
+    };
+
+    let mut events = Vec::new();
+    for trigger in triggers {
+        let watch_dir = PathBuf::from(&trigger.watch_path);
+        let payloads = match pending_payloads(&watch_dir) {
+            Ok(payloads) => payloads,
+            Err(err) => {
+                eprintln!(
+                    "⚠️  Warning: skipping file-drop trigger {}: {:#}",
+                    trigger.id, err
+                );
+                continue;
+            }
+        };
+
+        for payload_path in payloads {
+            let source_name = payload_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("payload.json")
+                .to_string();
+
+            // Later polls skip a claimed payload, even if it cannot be moved afterwards
+            let claimed = match claim_payload(&payload_path) {
+                Ok(claimed) => claimed,
+                Err(err) => {
+                    eprintln!("⚠️  Warning: skipping trigger payload: {:#}", err);
+                    continue;
+                }
+            };
+
+            let event = match fire_payload(pool, &trigger, &source_name, &claimed, llm_client) {
+                Ok(event) => event,
+                Err(err) => {
+                    let conn = pool.get()?;
+                    run_triggers::record_event(
+                        &conn,
+                        &trigger.id,
+                        None,
+                        &source_name,
+                        "",
+                        Some(&format!("{:#}", err)),
+                    )?
+                }
+            };
+
+            let destination = if event.status == run_triggers::EVENT_STARTED {
+                PROCESSED_DIR
+            } else {
+                FAILED_DIR
+            };
+            if let Err(err) = move_payload(&watch_dir, &claimed, &source_name, destination) {
+                eprintln!("⚠️  Warning: {:#}", err);
+            }
+            events.push(event);
+        }
+    }
+
+    Ok(events)
+}
+
+/// Read a claimed payload and fire the trigger for it
+fn fire_payload(
+    pool: &DbPool,
+    trigger: &RunTrigger,
+    source_name: &str,
+    claimed: &Path,
+    llm_client: &dyn LlmClient,
+) -> anyhow::Result<RunTriggerEvent> {
+    let size = fs::metadata(claimed)?.len();
+    if size > MAX_PAYLOAD_BYTES {
+        let conn = pool.get()?;
+        return Ok(run_triggers::record_event(
+            &conn,
+            &trigger.id,
+            None,
+            source_name,
+            "",
+            Some(&format!(
+                "payload is {} bytes, exceeding the {} byte limit",
+                size, MAX_PAYLOAD_BYTES
+            )),
+        )?);
+    }
+
+    let payload = fs::read(claimed)
+        .with_context(|| format!("failed to read trigger payload {}", claimed.display()))?;
+    fire_trigger(pool, trigger, source_name, &payload, llm_client)
+}
+
+/// Poll file-drop triggers in the background for the lifetime of the app
+pub fn spawn_file_drop_watcher(pool: DbPool, interval: Duration) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        if let Err(err) = poll_file_drop_triggers(&pool) {
+            eprintln!("⚠️  Warning: file-drop trigger poll failed: {}", err);
+        }
+        std::thread::sleep(interval);
+    })
+}
+
+fn pending_payloads(watch_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !watch_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut payloads = Vec::new();
+    for entry in fs::read_dir(watch_dir)
+        .with_context(|| format!("failed to read trigger directory {}", watch_dir.display()))?
+    {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || !name.ends_with(".json") {
+            continue;
+        }
+        if entry.file_type()?.is_file() {
+            payloads.push(entry.path());
+        }
+    }
+    // Fire in a stable order so payload sequencing is predictable
+    payloads.sort();
+    Ok(payloads)
+}
+
+/// Rename a payload to a hidden name in the watch directory, which
+/// `pending_payloads` skips
+fn claim_payload(payload_path: &Path) -> anyhow::Result<PathBuf> {
+    let file_name = payload_path
+        .file_name()
+        .ok_or_else(|| anyhow!("payload path has no file name"))?;
+    let claimed = payload_path.with_file_name(format!(".{}.claimed", file_name.to_string_lossy()));
+    fs::rename(payload_path, &claimed)
+        .with_context(|| format!("failed to claim trigger payload {}", payload_path.display()))?;
+    Ok(claimed)
+}
+
+fn move_payload(
+    watch_dir: &Path,
+    payload_path: &Path,
+    file_name: &str,
+    subdir: &str,
+) -> anyhow::Result<()> {
+    let target_dir = watch_dir.join(subdir);
+    fs::create_dir_all(&target_dir)?;
+    let mut target = target_dir.join(file_name);
+    if target.exists() {
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        target = target_dir.join(format!("{}-{}", stamp, file_name));
+    }
+    fs::rename(payload_path, &target).with_context(|| {
+        format!(
+            "failed to move trigger payload {} to {}",
+            payload_path.display(),
+            target.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn object_payload_becomes_parameters() {
+        let invocation = parse_trigger_payload(br#"{"topic":"rust","limit":3}"#).unwrap();
+        assert_eq!(invocation.parameters.get("topic"), Some(&json!("rust")));
+        assert_eq!(
+            invocation.input_sha256.as_deref(),
+            Some(provenance::sha256_hex(br#"{"topic":"rust","limit":3}"#).as_str())
+        );
+        assert_eq!(
+            orchestrator::apply_run_parameters("Top {{limit}} {{topic}} crates", &invocation.parameters),
+            "Top 3 rust crates"
+        );
+    }
+
+    #[test]
+    fn non_object_payload_is_exposed_as_payload() {
+        let invocation = parse_trigger_payload(b"[1,2]").unwrap();
+        assert_eq!(invocation.parameters.get("payload"), Some(&json!([1, 2])));
+        assert!(parse_trigger_payload(b"not json").is_err());
+    }
+
+    #[test]
+    fn pending_payloads_skip_hidden_and_non_json() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("b.json"), "{}").unwrap();
+        fs::write(dir.path().join("a.json"), "{}").unwrap();
+        fs::write(dir.path().join(".partial.json"), "{}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "x").unwrap();
+        fs::create_dir(dir.path().join("processed.json")).unwrap();
+
+        let names: Vec<_> = pending_payloads(dir.path())
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["a.json", "b.json"]);
+
+        let claimed = claim_payload(&dir.path().join("a.json")).unwrap();
+        assert_eq!(pending_payloads(dir.path()).unwrap().len(), 1);
+        move_payload(dir.path(), &claimed, "a.json", PROCESSED_DIR).unwrap();
+        assert!(dir.path().join(PROCESSED_DIR).join("a.json").exists());
+    }
+}
@@ -0,0 +1,363 @@
+// src-tauri/src/verification_html.rs
+//!
+//! Verification report as a self-contained HTML page
+//!
+//! Auditors attach verification results to their documentation, where a
+//! terminal transcript or a JSON report reads poorly. `HtmlReport::render`
+//! lays a verifier's outcome out as one static page: the verdict, each check
+//! that ran, who signed the CAR, which models the run used and its summary
+//! metrics. Styles are inline and nothing is fetched, so the page reads the
+//! same offline and years later in an archive.
+//!
+//! The CLI and the app run different checks, so each lists its own steps;
+//! `HtmlReport::from_verification` lists the app's.
+
+use chrono::Utc;
+
+use crate::car::Car;
+use crate::verify::{CarVerification, SignerCheck};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    Passed,
+    Failed,
+    /// Checked and suspicious, but not a failure
+    Warning,
+    /// Not applicable to this CAR, or not reached
+    Skipped,
+}
+
+impl StepOutcome {
+    fn label(self) -> &'static str {
+        match self {
+            StepOutcome::Passed => "Passed",
+            StepOutcome::Failed => "Failed",
+            StepOutcome::Warning => "Warning",
+            StepOutcome::Skipped => "Skipped",
+        }
+    }
+}
+
+/// One check a verifier ran, as a row of the report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportStep {
+    pub label: String,
+    pub outcome: StepOutcome,
+    pub detail: String,
+}
+
+impl ReportStep {
+    pub fn new(label: impl Into<String>, outcome: StepOutcome, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            outcome,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HtmlReport<'a> {
+    pub car: &'a Car,
+    pub verified: bool,
+    /// Why verification failed, when it did
+    pub error: Option<String>,
+    pub steps: Vec<ReportStep>,
+    /// The author, then each co-signer; empty when verification stopped
+    /// before the signatures were checked
+    pub signers: Vec<SignerCheck>,
+    /// The tool that verified the CAR, e.g. `intelexta-verify 0.1.0`
+    pub generator: String,
+}
+
+impl<'a> HtmlReport<'a> {
+    /// The report of `verify::verify_car` or `verify_car_bundle`, which stop
+    /// at the first check that fails
+    pub fn from_verification(
+        car: &'a Car,
+        outcome: &Result<CarVerification, String>,
+        generator: impl Into<String>,
+    ) -> Self {
+        let generator = generator.into();
+        let verification = match outcome {
+            Ok(verification) => verification,
+            Err(reason) => {
+                return Self {
+                    car,
+                    verified: false,
+                    error: Some(reason.clone()),
+                    steps: vec![ReportStep::new("Verification", StepOutcome::Failed, reason)],
+                    signers: Vec::new(),
+                    generator,
+                };
+            }
+        };
+
+        let claims_total = verification.claims.checks.len();
+        let unvalidated = verification.claims.unvalidated_types();
+        let mut steps = vec![
+            ReportStep::new(
+                "Signatures",
+                StepOutcome::Passed,
+                format!("{} CAR signatures verified", verification.signatures_verified),
+            ),
+            ReportStep::new(
+                "Hash chain",
+                StepOutcome::Passed,
+                format!("{} checkpoints verified", verification.checkpoints_verified),
+            ),
+            ReportStep::new(
+                "Provenance claims",
+                if unvalidated.is_empty() {
+                    StepOutcome::Passed
+                } else {
+                    StepOutcome::Warning
+                },
+                if unvalidated.is_empty() {
+                    format!("{}/{} claims verified", verification.claims.verified(), claims_total)
+                } else {
+                    format!(
+                        "{}/{} claims verified; no validator for {}",
+                        verification.claims.verified(),
+                        claims_total,
+                        unvalidated.join(", ")
+                    )
+                },
+            ),
+            ReportStep::new(
+                "Attachments",
+                StepOutcome::Passed,
+                format!("{} attachments verified", verification.attachments_verified),
+            ),
+        ];
+        if verification.redactions > 0 {
+            steps.push(ReportStep::new(
+                "Redactions",
+                StepOutcome::Passed,
+                format!("{} redactions match their commitments", verification.redactions),
+            ));
+        }
+        steps.push(match &verification.timestamp {
+            Some(info) => ReportStep::new(
+                "Trusted timestamp",
+                StepOutcome::Passed,
+                format!("Stamped {} under TSA policy {}", info.gen_time.to_rfc3339(), info.policy),
+            ),
+            None => ReportStep::new("Trusted timestamp", StepOutcome::Skipped, "None attached"),
+        });
+        let cosigners = verification.signers.len().saturating_sub(1);
+        steps.push(if cosigners > 0 {
+            ReportStep::new(
+                "Co-signatures",
+                StepOutcome::Passed,
+                format!("{cosigners} co-signatures verified"),
+            )
+        } else {
+            ReportStep::new("Co-signatures", StepOutcome::Skipped, "None attached")
+        });
+
+        Self {
+            car,
+            verified: true,
+            error: None,
+            steps,
+            signers: verification.signers.clone(),
+            generator,
+        }
+    }
+
+    /// The complete HTML document
+    pub fn render(&self) -> String {
+        let car = self.car;
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!(
+            "<title>Verification report – {}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+            escape(&car.id)
+        ));
+        html.push_str("<h1>Intelexta Verification Report</h1>\n");
+        let (class, verdict) = if self.verified {
+            ("passed", "VERIFIED")
+        } else {
+            ("failed", "FAILED")
+        };
+        html.push_str(&format!("<p class=\"verdict {class}\">{verdict}"));
+        if let Some(error) = &self.error {
+            html.push_str(&format!("<span>{}</span>", escape(error)));
+        }
+        html.push_str("</p>\n");
+
+        html.push_str("<h2>Receipt</h2>\n<table>\n");
+        let run = format!("{} ({})", car.run.name, car.run.kind);
+        let created = car.created_at.to_rfc3339();
+        let format_version = car.format_version.to_string();
+        for (label, value) in [
+            ("CAR ID", car.id.as_str()),
+            ("Run", run.as_str()),
+            ("Run ID", car.run_id.as_str()),
+            ("Created", created.as_str()),
+            ("Format version", format_version.as_str()),
+            ("Policy hash", car.policy_ref.hash.as_str()),
+        ] {
+            html.push_str(&row(&[label, value]));
+        }
+        html.push_str(&row(&["Models", &models(car).join(", ")]));
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Summary</h2>\n<table>\n");
+        let checkpoints = car
+            .proof
+            .process
+            .iter()
+            .chain(car.proof.executions.iter().map(|execution| &execution.process))
+            .map(|process| process.sequential_checkpoints.len())
+            .sum::<usize>();
+        let attachments = car.attachments.as_ref().map_or(0, |manifest| manifest.hashes.len());
+        for (label, value) in [
+            ("Steps", car.run.steps.len().to_string()),
+            ("Checkpoints", checkpoints.to_string()),
+            ("Tokens", car.budgets.tokens.to_string()),
+            ("Cost (USD)", format!("{:.4}", car.budgets.usd)),
+            ("Nature cost", format!("{:.4}", car.budgets.nature_cost)),
+            ("Provenance claims", car.provenance.len().to_string()),
+            ("Attachments", attachments.to_string()),
+            ("S-grade", format!("{}/100", car.sgrade.score)),
+        ] {
+            html.push_str(&row(&[label, &value]));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Checks</h2>\n<table>\n");
+        html.push_str("<tr><th>Check</th><th>Result</th><th>Details</th></tr>\n");
+        for step in &self.steps {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td></tr>\n",
+                escape(&step.label),
+                step.outcome.label().to_lowercase(),
+                step.outcome.label(),
+                escape(&step.detail)
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Signers</h2>\n<table>\n");
+        html.push_str("<tr><th>Role</th><th>Public key</th><th>Signature</th></tr>\n");
+        if self.signers.is_empty() {
+            html.push_str(&row(&["author", &car.signer_public_key, "Not checked"]));
+        }
+        for signer in &self.signers {
+            let status = if signer.valid { "Valid" } else { "Invalid" };
+            html.push_str(&row(&[signer.role.as_str(), &signer.public_key, status]));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str(&format!(
+            "<footer>Generated {} by {}. The receipt itself, not this page, is the evidence: \
+             verify the CAR again with intelexta-verify to rely on it.</footer>\n",
+            Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            escape(&self.generator)
+        ));
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:56rem;margin:2rem auto;\
+padding:0 1rem;color:#1f2937}h1{font-size:1.5rem}h2{font-size:1.1rem;margin-top:2rem}\
+table{border-collapse:collapse;width:100%}td,th{border:1px solid #e5e7eb;padding:.4rem .6rem;\
+text-align:left;vertical-align:top;word-break:break-all}th{background:#f9fafb}\
+.verdict{font-size:1.25rem;font-weight:700;padding:.75rem 1rem;border-radius:.375rem}\
+.verdict span{display:block;font-size:.9rem;font-weight:400}\
+.verdict.passed{background:#dcfce7;color:#166534}.verdict.failed{background:#fee2e2;\
+color:#991b1b}td.passed{color:#166534}td.failed{color:#991b1b}td.warning{color:#92400e}\
+td.skipped{color:#6b7280}footer{margin-top:2rem;font-size:.8rem;color:#6b7280}";
+
+/// The models the run's steps name, in step order, or the run's own model
+/// when no step names one
+fn models(car: &Car) -> Vec<String> {
+    let mut models: Vec<String> = Vec::new();
+    for model in car.run.steps.iter().filter_map(|step| step.model.as_deref()) {
+        if !models.iter().any(|seen| seen == model) {
+            models.push(model.to_string());
+        }
+    }
+    if models.is_empty() {
+        models.push(car.run.model.clone());
+    }
+    models
+}
+
+fn row(cells: &[&str]) -> String {
+    let mut html = String::from("<tr>");
+    for (index, cell) in cells.iter().enumerate() {
+        let tag = if index == 0 && cells.len() == 2 { "th" } else { "td" };
+        html.push_str(&format!("<{tag}>{}</{tag}>", escape(cell)));
+    }
+    html.push_str("</tr>\n");
+    html
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn car() -> Car {
+        serde_json::from_value(json!({
+            "id": "car:abc",
+            "run_id": "run-1",
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {
+                "kind": "exact", "name": "<nightly>", "model": "workflow:nightly",
+                "version": "1", "seed": 7,
+                "steps": [{
+                    "id": "step-1", "runId": "run-1", "orderIndex": 0,
+                    "checkpointType": "Step", "stepType": "prompt", "model": "llama3",
+                    "tokenBudget": 100, "proofMode": "exact"
+                }]
+            },
+            "proof": {"match_kind": "process", "process": {"sequential_checkpoints": []}},
+            "policy_ref": {"hash": "sha256:p", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 10, "nature_cost": 0.0},
+            "provenance": [],
+            "checkpoints": [],
+            "sgrade": {"score": 80, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": "key==",
+            "signatures": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn failed_verifications_render_their_reason_escaped() {
+        let car = car();
+        let outcome = Err("checkpoint <ck-1> does not match its chain hash".to_string());
+        let report = HtmlReport::from_verification(&car, &outcome, "intelexta 0.1.0");
+        assert!(!report.verified);
+
+        let html = report.render();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("FAILED"));
+        assert!(html.contains("checkpoint &lt;ck-1&gt; does not match"));
+        assert!(html.contains("&lt;nightly&gt; (exact)"));
+        assert!(html.contains("<td>llama3</td>"));
+        assert!(html.contains("<td>key==</td><td>Not checked</td>"));
+        // Self-contained: nothing is fetched
+        assert!(!html.contains("src=") && !html.contains("href="));
+    }
+}
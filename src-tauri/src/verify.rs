@@ -0,0 +1,827 @@
+// src-tauri/src/verify.rs
+//!
+//! Verify: check a CAR the way an external verifier would
+//!
+//! Together with the CAR model this module builds without the desktop app,
+//! so servers and other Rust apps can depend on the crate with
+//! `default-features = false, features = ["verify"]`. A CAR passes when:
+//!
+//! - its `format_version` is one of `car::FORMAT_VERSIONS`; any other fails
+//!   with a `car::UnsupportedFormatVersion` error
+//! - it carries the signatures its format version requires, and its body
+//!   (`ed25519-body:`) and receipt (`ed25519-checkpoint:`) signatures verify
+//!   under `signer_public_key`
+//! - every checkpoint, in the top-level proof and in each execution section
+//!   of a multi-execution CAR, matches its chain hash and signature
+//! - no registered provenance claim fails
+//! - every redacted field carries the commitment of a listed redaction
+//! - every attachment the caller passes in matches its content hash and
+//!   proves into the attachment Merkle root of the signed body
+//! - an `rfc3161:` timestamp token, if present, stamps the CAR id; the TSA's
+//!   own signature is left to the auditor, see `timestamp`
+//! - every `ed25519-cosign:` countersignature of a reviewer or organization
+//!   verifies under the key it names
+//!
+//! `verify_car_bundle` also requires every attachment the manifest lists,
+//! except redacted ones, to be present. `verify_external_attachments` checks
+//! the files a CAR binds without bundling against local copies.
+//! `check_receipt_chain` checks that a project's CARs, each naming the
+//! receipt before it, form one unbroken line.
+//!
+//! A CAR that passes is consistent with the key it carries, which anyone
+//! can generate. `TrustedKeys::signer_trust` tells whether that key is one
+//! the auditor pinned for a known project.
+
+use crate::car::{
+    self, Car, ClaimRegistry, ClaimStatus, ClaimsReport, Cosignature, SignatureKind, SignerRole,
+};
+use crate::response_metadata::ResponseMetadata;
+use crate::timestamp::{self, TimestampInfo};
+use intelexta_verify_core::ChainedCheckpoint;
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use serde_json::Value;
+
+/// The part of a checkpoint its chain hash covers
+pub(crate) type CheckpointBody<'a> = intelexta_verify_core::CheckpointBody<'a, ResponseMetadata>;
+
+pub use intelexta_verify_core::{decode_verifying_key, signature_valid};
+
+impl ChainedCheckpoint for car::ProcessCheckpointProof {
+    type Metadata = ResponseMetadata;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn prev_chain(&self) -> &str {
+        &self.prev_chain
+    }
+
+    fn curr_chain(&self) -> &str {
+        &self.curr_chain
+    }
+
+    fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    fn body(&self) -> CheckpointBody<'_> {
+        CheckpointBody {
+            run_id: &self.run_id,
+            kind: &self.kind,
+            timestamp: self.timestamp.clone(),
+            inputs_sha256: self.inputs_sha256.as_deref(),
+            outputs_sha256: self.outputs_sha256.as_deref(),
+            incident: None,
+            usage_tokens: self.usage_tokens,
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            cache_hit: self.cache_hit,
+            response_metadata: self.response_metadata.as_ref(),
+            run_inputs_sha256: self.run_inputs_sha256.as_deref(),
+            output_schema_sha256: self.output_schema_sha256.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CarVerification {
+    pub car_id: String,
+    pub run_id: String,
+    pub format_version: u32,
+    pub signatures_verified: usize,
+    pub checkpoints_verified: usize,
+    /// Includes claims of extension types no validator is registered for
+    pub claims: ClaimsReport,
+    pub attachments_verified: usize,
+    /// Fields and attachments withheld behind commitments; a CAR with
+    /// redactions verifies, and reports how many it has
+    pub redactions: usize,
+    /// What the CAR's trusted timestamp says, when it has one
+    pub timestamp: Option<TimestampInfo>,
+    /// The author, then every co-signer in the order they signed
+    pub signers: Vec<SignerCheck>,
+}
+
+/// A signer of a CAR and whether their signature holds
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SignerCheck {
+    pub role: SignerRole,
+    pub public_key: String,
+    pub valid: bool,
+}
+
+/// Check the co-signatures of a CAR. A signature that does not verify is
+/// reported as invalid; `Err` names the first entry that is malformed.
+pub fn check_cosignatures(car: &Car) -> std::result::Result<Vec<SignerCheck>, String> {
+    let mut signers = Vec::new();
+    for entry in &car.signatures {
+        let Some(cosignature) = Cosignature::parse(entry) else {
+            continue;
+        };
+        let cosignature = cosignature?;
+        let valid = decode_verifying_key(&cosignature.public_key).is_ok_and(|key| {
+            signature_valid(
+                &key,
+                &Cosignature::message(&car.id, cosignature.role),
+                &cosignature.signature,
+            )
+        });
+        signers.push(SignerCheck {
+            role: cosignature.role,
+            public_key: cosignature.public_key,
+            valid,
+        });
+    }
+    Ok(signers)
+}
+
+/// Verify the `car.json` of a receipt; `attachments` pairs the content
+/// hashes a bundle names its attachments by with their contents
+pub fn verify_car(
+    car_json: &[u8],
+    attachments: &[(String, Vec<u8>)],
+) -> anyhow::Result<CarVerification> {
+    let raw: Value = serde_json::from_slice(car_json).context("car.json is not valid JSON")?;
+    let car: Car = serde_json::from_value(raw.clone()).context("car.json is not a CAR")?;
+    let format = car::negotiate_format(car.format_version)?;
+    let key = decode_verifying_key(&car.signer_public_key)?;
+
+    let body_canonical = intelexta_verify_core::body_signature_message(&raw)?;
+    let mut signatures_verified = 0;
+    for signature in &car.signatures {
+        let (message, encoded) = match car::parse_signature(signature) {
+            Some((SignatureKind::Body, encoded)) => (body_canonical.as_slice(), encoded),
+            Some((SignatureKind::Checkpoint, encoded)) => (car.id.as_bytes(), encoded),
+            None => continue,
+        };
+        if !signature_valid(&key, message, encoded) {
+            bail!("CAR signature '{}' does not verify", signature);
+        }
+        signatures_verified += 1;
+    }
+    if signatures_verified == 0 {
+        bail!("CAR carries no ed25519 signature");
+    }
+    format.check_signatures(&car.signatures).map_err(anyhow::Error::msg)?;
+    let timestamp = timestamp::verify_car_timestamp(&car).map_err(anyhow::Error::msg)?;
+    let mut signers = vec![SignerCheck {
+        role: SignerRole::Author,
+        public_key: car.signer_public_key.clone(),
+        valid: true,
+    }];
+    signers.extend(check_cosignatures(&car).map_err(anyhow::Error::msg)?);
+    if let Some(invalid) = signers.iter().find(|signer| !signer.valid) {
+        bail!(
+            "{} co-signature by {} does not verify",
+            invalid.role.as_str(),
+            invalid.public_key
+        );
+    }
+
+    // Multi-execution CARs carry a proof section per execution besides the
+    // top-level one
+    let processes = car
+        .proof
+        .process
+        .iter()
+        .chain(car.proof.executions.iter().map(|execution| &execution.process));
+    let mut checkpoints_verified = 0;
+    for process in processes {
+        for (index, checkpoint) in process.sequential_checkpoints.iter().enumerate() {
+            intelexta_verify_core::check_chain_link(index, checkpoint).map_err(|_| {
+                anyhow!("checkpoint {} does not match its chain hash", checkpoint.id)
+            })?;
+            intelexta_verify_core::check_checkpoint_signature(index, checkpoint, &key).map_err(
+                |_| anyhow!("checkpoint {} signature does not verify", checkpoint.id),
+            )?;
+            checkpoints_verified += 1;
+        }
+    }
+
+    let claims = ClaimRegistry::builtin().verify(&car);
+    if let Some(failure) = claims.failures().next() {
+        if let ClaimStatus::Failed { reason } = &failure.status {
+            bail!(
+                "{} claim #{} failed: {}",
+                failure.claim_type,
+                failure.index,
+                reason
+            );
+        }
+    }
+
+    let redactions = car::check_redactions(&car)
+        .map_err(|reason| anyhow!("redactions do not match the CAR: {reason}"))?;
+
+    for (hash, content) in attachments {
+        intelexta_verify_core::check_attachment(hash, content)
+            .map_err(|_| anyhow!("attachment {hash} does not match its content hash"))?;
+    }
+    // CARs from before attachment manifests leave the set unchecked
+    if let Some(manifest) = &car.attachments {
+        manifest.check_root().map_err(anyhow::Error::msg)?;
+        for (hash, _) in attachments {
+            manifest.check_inclusion(hash).map_err(anyhow::Error::msg)?;
+        }
+    }
+
+    Ok(CarVerification {
+        car_id: car.id,
+        run_id: car.run_id,
+        format_version: format.version,
+        signatures_verified,
+        checkpoints_verified,
+        claims,
+        attachments_verified: attachments.len(),
+        redactions,
+        timestamp,
+        signers,
+    })
+}
+
+/// Verify a CAR bundle: as `verify_car`, and no attachment the manifest
+/// lists may be missing unless it was redacted
+pub fn verify_car_bundle(
+    car_json: &[u8],
+    attachments: &[(String, Vec<u8>)],
+) -> anyhow::Result<CarVerification> {
+    let verification = verify_car(car_json, attachments)?;
+    let car: Car = serde_json::from_slice(car_json)?;
+    for hash in car.attachments.iter().flat_map(|manifest| &manifest.hashes) {
+        let present = attachments.iter().any(|(name, _)| name == hash);
+        let target = format!("attachments/{hash}");
+        let redacted = car.redactions.iter().any(|redaction| redaction.target == target);
+        if !present && !redacted {
+            bail!("attachment {hash} listed in the manifest is missing from the bundle");
+        }
+    }
+    Ok(verification)
+}
+
+/// What `verify_external_attachments` found for a CAR's external attachments
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ExternalAttachments {
+    /// URIs of the attachments whose local copy matches
+    pub verified: Vec<String>,
+    /// URIs of the attachments with no local copy in the directory
+    pub missing: Vec<String>,
+}
+
+/// Check a CAR's external attachments against local copies in `dir`, named
+/// by content hash or by the file name of their URI. Fails on the first copy
+/// that does not match; attachments without a copy are listed as missing.
+pub fn verify_external_attachments(
+    car: &Car,
+    dir: &std::path::Path,
+) -> anyhow::Result<ExternalAttachments> {
+    let mut report = ExternalAttachments {
+        verified: Vec::new(),
+        missing: Vec::new(),
+    };
+    for attachment in &car.external_attachments {
+        let copy = std::iter::once(attachment.sha256.as_str())
+            .chain(attachment.file_name())
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file());
+        let Some(copy) = copy else {
+            report.missing.push(attachment.uri.clone());
+            continue;
+        };
+        attachment.check_file(&copy).map_err(anyhow::Error::msg)?;
+        report.verified.push(attachment.uri.clone());
+    }
+    Ok(report)
+}
+
+/// Signer keys an auditor trusts, in the format of a `--trusted-keys`
+/// file: `{"keys": [{"project": "...", "public_key": "<base64>"}]}`. A
+/// project that rotated its key lists each key it signed with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TrustedKeys {
+    pub keys: Vec<TrustedKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrustedKey {
+    /// Name of the project the key signs for, as the auditor knows it
+    pub project: String,
+    /// Base64 ed25519 public key
+    pub public_key: String,
+}
+
+/// Whether a CAR's signer is one the trust store knows
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SignerTrust {
+    /// Verified by a key pinned for `project`
+    Trusted { project: String },
+    /// Self-consistent, but signed by a key the trust store does not list
+    Unknown { public_key: String },
+}
+
+impl TrustedKeys {
+    /// Parse a trust store, rejecting keys that are not ed25519 public keys
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let keys: Self = serde_json::from_str(json).context("trusted keys are not valid JSON")?;
+        for key in &keys.keys {
+            decode_verifying_key(&key.public_key)
+                .with_context(|| format!("trusted key of project {}", key.project))?;
+        }
+        Ok(keys)
+    }
+
+    /// Look up the CAR's signer key. Says nothing about whether the CAR's
+    /// signatures hold; check those with `verify_car` first.
+    pub fn signer_trust(&self, car: &Car) -> SignerTrust {
+        let signer = decode_verifying_key(&car.signer_public_key).ok();
+        let pinned = self.keys.iter().find(|key| {
+            signer.is_some() && decode_verifying_key(&key.public_key).ok() == signer
+        });
+        match pinned {
+            Some(key) => SignerTrust::Trusted {
+                project: key.project.clone(),
+            },
+            None => SignerTrust::Unknown {
+                public_key: car.signer_public_key.clone(),
+            },
+        }
+    }
+}
+
+/// A project's receipts in chain order
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReceiptChain {
+    /// CAR IDs from the first receipt of the chain to its head
+    pub receipts: Vec<String>,
+    /// CARs that predate the chain: no previous receipt and no successor
+    pub unchained: Vec<String>,
+}
+
+/// Order a project's CARs along their `previous_car_id` links. Fails when a
+/// linked receipt is missing, when two receipts follow the same one (one
+/// replaced the other) or when the links form more than one chain. A head
+/// removed together with nothing after it cannot be told from a shorter
+/// ledger.
+pub fn check_receipt_chain(cars: &[Car]) -> anyhow::Result<ReceiptChain> {
+    let mut by_id = HashMap::new();
+    for car in cars {
+        if by_id.insert(car.id.as_str(), car).is_some() {
+            bail!("receipt {} appears more than once", car.id);
+        }
+    }
+    let mut successors: HashMap<&str, &str> = HashMap::new();
+    for car in cars {
+        let Some(previous) = car.previous_car_id.as_deref() else {
+            continue;
+        };
+        if !by_id.contains_key(previous) {
+            bail!("receipt {} follows {previous}, which is missing", car.id);
+        }
+        if let Some(other) = successors.insert(previous, &car.id) {
+            bail!(
+                "receipts {other} and {} both follow {previous}; one replaced the other",
+                car.id
+            );
+        }
+    }
+
+    let linked = |car: &Car| {
+        car.previous_car_id.is_some() || successors.contains_key(car.id.as_str())
+    };
+    let heads: Vec<&Car> = cars
+        .iter()
+        .filter(|car| linked(car) && !successors.contains_key(car.id.as_str()))
+        .collect();
+    if heads.len() > 1 {
+        bail!("receipts form {} separate chains", heads.len());
+    }
+
+    let mut receipts = Vec::new();
+    let mut next = heads.first().copied();
+    while let Some(car) = next {
+        receipts.push(car.id.clone());
+        next = car.previous_car_id.as_deref().map(|previous| by_id[previous]);
+    }
+    receipts.reverse();
+    // Without forks, linked receipts the walk missed can only form a loop
+    if receipts.len() != cars.iter().filter(|car| linked(car)).count() {
+        bail!("receipt chain loops back on itself");
+    }
+    let unchained = cars
+        .iter()
+        .filter(|car| !linked(car))
+        .map(|car| car.id.clone())
+        .collect();
+    Ok(ReceiptChain {
+        receipts,
+        unchained,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::provenance;
+    use ed25519_dalek::SigningKey;
+
+    /// A minimal CAR with one step checkpoint, signed like `car::build_car` signs
+    pub(crate) fn signed_car_json(signing_key: &SigningKey) -> Value {
+        let mut car = serde_json::json!({
+            "id": "car:test",
+            "format_version": car::CAR_FORMAT_VERSION,
+            "run_id": "r1",
+            "created_at": "2025-01-01T00:00:00Z",
+            "run": {"kind": "exact", "name": "nightly", "model": "stub-model", "version": "1", "seed": 1, "steps": []},
+            "proof": {"match_kind": "process", "process": {"sequential_checkpoints": []}},
+            "policy_ref": {"hash": "h", "egress": false, "estimator": "e"},
+            "budgets": {"usd": 0.0, "tokens": 5, "nature_cost": 0.0},
+            "provenance": [],
+            "checkpoints": ["c1"],
+            "sgrade": {"score": 0, "components": {"provenance": 0.0, "energy": 0.0, "replay": 0.0, "consent": 0.0, "incidents": 0.0}},
+            "signer_public_key": provenance::public_key_from_secret(signing_key)
+        });
+        car["proof"]["process"]["sequential_checkpoints"] =
+            serde_json::json!([signed_checkpoint(signing_key, 5)]);
+        sign(&mut car, signing_key);
+        car
+    }
+
+    fn signed_checkpoint(signing_key: &SigningKey, usage_tokens: u64) -> Value {
+        let body = CheckpointBody {
+            run_id: "r1",
+            kind: "Step",
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            inputs_sha256: None,
+            outputs_sha256: Some("aa"),
+            incident: None,
+            usage_tokens,
+            prompt_tokens: 3,
+            completion_tokens: 2,
+            cache_hit: false,
+            response_metadata: None,
+            run_inputs_sha256: None,
+            output_schema_sha256: None,
+        };
+        let curr_chain = body.chain_hash("").unwrap();
+        serde_json::json!({
+            "id": "c1", "prev_chain": "", "curr_chain": curr_chain,
+            "signature": provenance::sign_bytes(signing_key, curr_chain.as_bytes()),
+            "run_id": "r1", "kind": "Step", "timestamp": "2025-01-01T00:00:00Z",
+            "outputs_sha256": "aa", "usage_tokens": usage_tokens, "prompt_tokens": 3, "completion_tokens": 2
+        })
+    }
+
+    #[test]
+    fn signers_are_trusted_only_when_pinned() {
+        let signing_key = SigningKey::from_bytes(&[5u8; 32]);
+        let car: Car = serde_json::from_value(signed_car_json(&signing_key)).unwrap();
+        let other = provenance::public_key_from_secret(&SigningKey::from_bytes(&[6u8; 32]));
+
+        let keys = TrustedKeys::from_json(&format!(
+            r#"{{"keys": [{{"project": "other", "public_key": "{other}"}}]}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            keys.signer_trust(&car),
+            SignerTrust::Unknown {
+                public_key: car.signer_public_key.clone()
+            }
+        );
+
+        let keys = TrustedKeys::from_json(&format!(
+            r#"{{"keys": [{{"project": "other", "public_key": "{other}"}},
+                          {{"project": "nightly", "public_key": "{}"}}]}}"#,
+            car.signer_public_key
+        ))
+        .unwrap();
+        assert_eq!(
+            keys.signer_trust(&car),
+            SignerTrust::Trusted {
+                project: "nightly".to_string()
+            }
+        );
+
+        let err = TrustedKeys::from_json(r#"{"keys": [{"project": "bad", "public_key": "AAAA"}]}"#)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("project bad"));
+    }
+
+    #[test]
+    fn receipt_chains_must_be_one_unbroken_line() {
+        let receipt = |id: &str, previous: Option<&str>| {
+            let mut car: Car = serde_json::from_value(signed_car_json(&SigningKey::from_bytes(
+                &[4u8; 32],
+            )))
+            .unwrap();
+            car.id = id.to_string();
+            car.previous_car_id = previous.map(str::to_string);
+            car
+        };
+        let legacy = receipt("car:legacy", None);
+        let first = receipt("car:1", None);
+        let second = receipt("car:2", Some("car:1"));
+        let third = receipt("car:3", Some("car:2"));
+
+        let chain = check_receipt_chain(&[third.clone(), legacy, first.clone(), second.clone()])
+            .unwrap();
+        assert_eq!(chain.receipts, vec!["car:1", "car:2", "car:3"]);
+        assert_eq!(chain.unchained, vec!["car:legacy"]);
+
+        // A removed receipt breaks the link of the one after it
+        let err = check_receipt_chain(&[first.clone(), third.clone()]).unwrap_err();
+        assert!(err.to_string().contains("car:2, which is missing"));
+
+        // A replacement follows the same receipt as the original
+        let replacement = receipt("car:2b", Some("car:1"));
+        let err = check_receipt_chain(&[first, second, replacement, third]).unwrap_err();
+        assert!(err.to_string().contains("both follow car:1"));
+    }
+
+    /// Replace the signatures with fresh ones over the current body
+    fn sign(car: &mut Value, signing_key: &SigningKey) {
+        car.as_object_mut().unwrap().remove("signatures");
+        let body_signature = provenance::sign_bytes(signing_key, &provenance::canonical_json(&car));
+        let id = car["id"].as_str().unwrap().to_string();
+        car["signatures"] = serde_json::json!([
+            format!("ed25519-body:{body_signature}"),
+            format!(
+                "ed25519-checkpoint:{}",
+                provenance::sign_bytes(signing_key, id.as_bytes())
+            ),
+        ]);
+    }
+
+    fn bytes(car: &Value) -> Vec<u8> {
+        serde_json::to_vec(car).unwrap()
+    }
+
+    #[test]
+    fn signed_cars_verify_with_their_attachments() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let car = signed_car_json(&signing_key);
+        let attachment = b"model output".to_vec();
+        let attachments = vec![(provenance::sha256_hex(&attachment), attachment)];
+
+        let verification = verify_car(&bytes(&car), &attachments).unwrap();
+        assert_eq!(verification.car_id, "car:test");
+        assert_eq!(verification.signatures_verified, 2);
+        assert_eq!(verification.checkpoints_verified, 1);
+        assert_eq!(verification.attachments_verified, 1);
+
+        let swapped = vec![(attachments[0].0.clone(), b"other output".to_vec())];
+        assert!(verify_car(&bytes(&car), &swapped)
+            .unwrap_err()
+            .to_string()
+            .contains("attachment"));
+    }
+
+    #[test]
+    fn edited_bodies_and_checkpoints_fail() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let mut car = signed_car_json(&signing_key);
+        car["budgets"]["tokens"] = serde_json::json!(1);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("ed25519-body"));
+
+        // Re-signing the body does not cover up an edited checkpoint
+        let mut car = signed_car_json(&signing_key);
+        car["proof"]["process"]["sequential_checkpoints"][0]["usage_tokens"] =
+            serde_json::json!(50);
+        sign(&mut car, &signing_key);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("chain hash"));
+
+        // So does signing with another key
+        let other_key = SigningKey::from_bytes(&[5u8; 32]);
+        let mut car = signed_car_json(&signing_key);
+        car["proof"]["process"]["sequential_checkpoints"] =
+            serde_json::json!([signed_checkpoint(&other_key, 5)]);
+        sign(&mut car, &signing_key);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("signature does not verify"));
+    }
+
+    #[test]
+    fn redacted_cars_verify_with_their_redaction_count() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let redaction = car::Redaction {
+            target: "steps/s1/prompt".to_string(),
+            commitment: car::redaction_commitment("salt", b"secret prompt"),
+        };
+        let mut car = signed_car_json(&signing_key);
+        car["run"]["steps"] = serde_json::json!([{
+            "id": "s1", "runId": "r1", "orderIndex": 0, "checkpointType": "Step",
+            "prompt": redaction.marker()
+        }]);
+        car["redactions"] = serde_json::json!([redaction]);
+        sign(&mut car, &signing_key);
+
+        let verification = verify_car(&bytes(&car), &[]).unwrap();
+        assert_eq!(verification.redactions, 1);
+        assert!(redaction.opens("salt", b"secret prompt"));
+
+        // A marker without a listed commitment is tampering, not a redaction
+        car["redactions"] = serde_json::json!([]);
+        sign(&mut car, &signing_key);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("without a listed commitment"));
+    }
+
+    #[test]
+    fn bundles_must_match_the_attachment_manifest() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let attachment = |text: &str| {
+            (
+                provenance::sha256_hex(text.as_bytes()),
+                text.as_bytes().to_vec(),
+            )
+        };
+        let (first, second) = (attachment("first output"), attachment("second output"));
+        let mut car = signed_car_json(&signing_key);
+        car["attachments"] =
+            serde_json::json!(car::AttachmentManifest::new([first.0.clone(), second.0.clone()]));
+        sign(&mut car, &signing_key);
+
+        let both = [first.clone(), second.clone()];
+        let verification = verify_car_bundle(&bytes(&car), &both).unwrap();
+        assert_eq!(verification.attachments_verified, 2);
+
+        // A removed attachment is only acceptable outside a bundle
+        assert!(verify_car(&bytes(&car), &both[..1]).is_ok());
+        assert!(verify_car_bundle(&bytes(&car), &both[..1])
+            .unwrap_err()
+            .to_string()
+            .contains("missing from the bundle"));
+
+        // An added one never proves into the root
+        let added = [first, second, attachment("slipped in")];
+        assert!(verify_car(&bytes(&car), &added)
+            .unwrap_err()
+            .to_string()
+            .contains("not in the CAR's attachment manifest"));
+
+        car["attachments"]["hashes"] = serde_json::json!([both[0].0]);
+        sign(&mut car, &signing_key);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("Merkle root mismatch"));
+    }
+
+    #[test]
+    fn format_versions_are_negotiated_not_guessed() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let mut car = signed_car_json(&signing_key);
+        let verification = verify_car(&bytes(&car), &[]).unwrap();
+        assert_eq!(verification.format_version, car::CAR_FORMAT_VERSION);
+
+        // Current CARs must carry both signatures
+        let body_only = car["signatures"][0].clone();
+        car["signatures"] = serde_json::json!([body_only]);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("must carry an ed25519-checkpoint signature"));
+
+        // Legacy CARs predate the field and verify with either signature
+        car.as_object_mut().unwrap().remove("format_version");
+        sign(&mut car, &signing_key);
+        let body_only = car["signatures"][0].clone();
+        car["signatures"] = serde_json::json!([body_only]);
+        let verification = verify_car(&bytes(&car), &[]).unwrap();
+        assert_eq!(verification.format_version, car::LEGACY_FORMAT_VERSION);
+
+        car["format_version"] = serde_json::json!(99);
+        sign(&mut car, &signing_key);
+        let err = verify_car(&bytes(&car), &[]).unwrap_err();
+        let unsupported = err.downcast_ref::<car::UnsupportedFormatVersion>().unwrap();
+        assert_eq!(unsupported.found, 99);
+        assert_eq!(unsupported.supported, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn every_execution_section_is_verified() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let mut car = signed_car_json(&signing_key);
+        let section = |usage_tokens| {
+            let checkpoint = signed_checkpoint(&signing_key, usage_tokens);
+            serde_json::json!({
+                "run_execution_id": "e1",
+                "created_at": "2025-01-01T00:00:00Z",
+                "budgets": {"usd": 0.0, "tokens": usage_tokens, "nature_cost": 0.0},
+                "process": {"sequential_checkpoints": [checkpoint]}
+            })
+        };
+        car["proof"]["executions"] = serde_json::json!([section(7), section(5)]);
+        sign(&mut car, &signing_key);
+        let verification = verify_car(&bytes(&car), &[]).unwrap();
+        assert_eq!(verification.checkpoints_verified, 3);
+
+        // A re-signed body does not cover up an edited earlier execution
+        car["proof"]["executions"][0]["process"]["sequential_checkpoints"][0]["usage_tokens"] =
+            serde_json::json!(70);
+        sign(&mut car, &signing_key);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("chain hash"));
+    }
+
+    #[test]
+    fn external_attachments_are_checked_against_local_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir(&source).unwrap();
+        std::fs::write(source.join("corpus.txt"), b"a large corpus").unwrap();
+        std::fs::write(source.join("notes.txt"), b"notes").unwrap();
+        let attachment = |name: &str| {
+            car::ExternalAttachment::of_file(
+                format!("file:///data/{name}"),
+                &source.join(name),
+            )
+            .unwrap()
+        };
+
+        let mut car: Car =
+            serde_json::from_value(signed_car_json(&SigningKey::from_bytes(&[4u8; 32]))).unwrap();
+        car.external_attachments = vec![attachment("corpus.txt"), attachment("notes.txt")];
+        assert_eq!(car.external_attachments[0].size, 14);
+
+        // Copies go by their URI's file name or by their content hash
+        let copies = dir.path().join("copies");
+        std::fs::create_dir(&copies).unwrap();
+        std::fs::copy(source.join("corpus.txt"), copies.join("corpus.txt")).unwrap();
+        let report = verify_external_attachments(&car, &copies).unwrap();
+        assert_eq!(report.verified, vec!["file:///data/corpus.txt"]);
+        assert_eq!(report.missing, vec!["file:///data/notes.txt"]);
+
+        let notes_hash = car.external_attachments[1].sha256.clone();
+        std::fs::copy(source.join("notes.txt"), copies.join(notes_hash)).unwrap();
+        assert!(verify_external_attachments(&car, &copies).unwrap().missing.is_empty());
+
+        std::fs::write(copies.join("corpus.txt"), b"a large corpvs").unwrap();
+        let err = verify_external_attachments(&car, &copies).unwrap_err();
+        assert!(err.to_string().contains("content mismatch"));
+        std::fs::write(copies.join("corpus.txt"), b"short").unwrap();
+        let err = verify_external_attachments(&car, &copies).unwrap_err();
+        assert!(err.to_string().contains("is 5 bytes, the CAR records 14"));
+    }
+
+    #[test]
+    fn cosignatures_are_reported_with_their_role() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let reviewer_key = SigningKey::from_bytes(&[6u8; 32]);
+        let mut json = signed_car_json(&signing_key);
+        let mut car: Car = serde_json::from_value(json.clone()).unwrap();
+        let cosignature = car::cosign(&mut car, SignerRole::Reviewer, &reviewer_key).unwrap();
+        assert!(car::cosign(&mut car, SignerRole::Reviewer, &reviewer_key).is_err());
+        assert!(car::cosign(&mut car, SignerRole::Author, &reviewer_key).is_err());
+
+        // Countersigning leaves the author's signatures intact
+        json["signatures"] = serde_json::json!(car.signatures);
+        let verification = verify_car(&bytes(&json), &[]).unwrap();
+        assert_eq!(verification.signatures_verified, 2);
+        let roles: Vec<_> = verification.signers.iter().map(|signer| signer.role).collect();
+        assert_eq!(roles, vec![SignerRole::Author, SignerRole::Reviewer]);
+        assert_eq!(verification.signers[1].public_key, cosignature.public_key);
+
+        // The role is signed: a reviewer signature does not pass as an organization's
+        let relabeled = Cosignature {
+            role: SignerRole::Organization,
+            ..cosignature
+        };
+        *car.signatures.last_mut().unwrap() = relabeled.entry();
+        assert!(!check_cosignatures(&car).unwrap()[0].valid);
+        json["signatures"] = serde_json::json!(car.signatures);
+        assert!(verify_car(&bytes(&json), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("organization co-signature"));
+
+        *car.signatures.last_mut().unwrap() = "ed25519-cosign:author:key:sig".to_string();
+        assert!(check_cosignatures(&car).unwrap_err().contains("unknown role"));
+    }
+
+    #[test]
+    fn unsigned_cars_fail() {
+        let signing_key = SigningKey::from_bytes(&[4u8; 32]);
+        let mut car = signed_car_json(&signing_key);
+        car["signatures"] = serde_json::json!([]);
+        assert!(verify_car(&bytes(&car), &[])
+            .unwrap_err()
+            .to_string()
+            .contains("no ed25519 signature"));
+        assert!(verify_car(b"not json", &[]).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+// src-tauri/src/workspace_search.rs
+//!
+//! Workspace-wide artifact search
+//!
+//! Answers "where did this artifact come from?" for a sha256 digest across
+//! every project: the checkpoints that signed it as an output, a prompt or
+//! run inputs, the executions a trigger payload with that digest started,
+//! the dataset splits that list it as a source document, and the receipts
+//! (CARs) emitted for the executions it appears in. Every execution found is
+//! re-verified against its stored hash chain and signatures, so a match
+//! also says whether the evidence behind it still holds.
+
+use crate::{self_verification, Error};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How a checkpoint carries the searched digest
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchRole {
+    /// Output digest the checkpoint signed
+    Output,
+    /// Full output kept in the attachment store
+    Attachment,
+    /// Input digest the checkpoint signed, e.g. the hash of its prompt
+    Input,
+    /// Canonical hash of the inputs the run was started with
+    RunInputs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointMatch {
+    pub project_id: String,
+    pub project_name: String,
+    pub run_id: String,
+    pub run_name: String,
+    pub run_execution_id: String,
+    pub checkpoint_id: String,
+    pub kind: String,
+    pub timestamp: String,
+    pub roles: Vec<MatchRole>,
+}
+
+/// Execution started with an external input (e.g. a trigger payload)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionInputMatch {
+    pub project_id: String,
+    pub project_name: String,
+    pub run_id: String,
+    pub run_name: String,
+    pub run_execution_id: String,
+    pub created_at: String,
+}
+
+/// Dataset split listing the digest as a source document
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentMatch {
+    pub project_id: String,
+    pub project_name: String,
+    pub dataset: String,
+    pub split: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptMatch {
+    pub car_id: String,
+    pub run_id: String,
+    pub run_execution_id: Option<String>,
+    pub file_path: String,
+    pub created_at: String,
+}
+
+/// Result of re-checking an execution's stored checkpoints
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionVerification {
+    pub run_execution_id: String,
+    pub checkpoints_verified: usize,
+    /// Why the chain did not verify; `None` when it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSearch {
+    /// The searched digest, as lowercase hex without a `sha256:` prefix
+    pub sha256: String,
+    pub checkpoints: Vec<CheckpointMatch>,
+    pub execution_inputs: Vec<ExecutionInputMatch>,
+    pub documents: Vec<DocumentMatch>,
+    /// Receipts of the executions the digest appears in, and any receipt
+    /// whose CAR ID is the digest itself
+    pub receipts: Vec<ReceiptMatch>,
+    pub verifications: Vec<ExecutionVerification>,
+}
+
+impl WorkspaceSearch {
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+            && self.execution_inputs.is_empty()
+            && self.documents.is_empty()
+            && self.receipts.is_empty()
+    }
+}
+
+/// Accepts `sha256:<hex>`, `car:<hex>` or bare hex in either case
+pub fn normalize_digest(value: &str) -> Result<String, Error> {
+    let trimmed = value.trim();
+    let hex = trimmed
+        .strip_prefix("sha256:")
+        .or_else(|| trimmed.strip_prefix("car:"))
+        .unwrap_or(trimmed)
+        .to_ascii_lowercase();
+    if hex.len() != 64 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(Error::Api(format!("'{value}' is not a sha256 digest")));
+    }
+    Ok(hex)
+}
+
+/// Search every project for an output, prompt or document digest
+pub fn search_workspace(conn: &Connection, digest: &str) -> Result<WorkspaceSearch, Error> {
+    let sha256 = normalize_digest(digest)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, r.id, r.name, c.run_execution_id, c.id, c.kind, c.timestamp,
+                c.outputs_sha256 = ?1, cp.full_output_hash = ?1, c.inputs_sha256 = ?1, c.run_inputs_sha256 = ?1
+         FROM checkpoints c
+         JOIN runs r ON r.id = c.run_id
+         JOIN projects p ON p.id = r.project_id
+         LEFT JOIN checkpoint_payloads cp ON cp.checkpoint_id = c.id
+         WHERE c.outputs_sha256 = ?1 OR cp.full_output_hash = ?1
+            OR c.inputs_sha256 = ?1 OR c.run_inputs_sha256 = ?1
+         ORDER BY c.timestamp ASC, c.id ASC",
+    )?;
+    let checkpoints = stmt
+        .query_map(params![&sha256], |row| {
+            let roles = [
+                (8, MatchRole::Output),
+                (9, MatchRole::Attachment),
+                (10, MatchRole::Input),
+                (11, MatchRole::RunInputs),
+            ]
+            .into_iter()
+            .filter_map(|(index, role)| match row.get::<_, Option<bool>>(index) {
+                Ok(Some(true)) => Some(Ok(role)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(CheckpointMatch {
+                project_id: row.get(0)?,
+                project_name: row.get(1)?,
+                run_id: row.get(2)?,
+                run_name: row.get(3)?,
+                run_execution_id: row.get(4)?,
+                checkpoint_id: row.get(5)?,
+                kind: row.get(6)?,
+                timestamp: row.get(7)?,
+                roles,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, r.id, r.name, e.id, e.created_at
+         FROM run_executions e
+         JOIN runs r ON r.id = e.run_id
+         JOIN projects p ON p.id = r.project_id
+         WHERE e.input_sha256 = ?1
+         ORDER BY e.created_at ASC, e.id ASC",
+    )?;
+    let execution_inputs = stmt
+        .query_map(params![&sha256], |row| {
+            Ok(ExecutionInputMatch {
+                project_id: row.get(0)?,
+                project_name: row.get(1)?,
+                run_id: row.get(2)?,
+                run_name: row.get(3)?,
+                run_execution_id: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, d.dataset, d.split
+         FROM dataset_splits d
+         JOIN projects p ON p.id = d.project_id
+         WHERE d.document_sha256 = ?1
+         ORDER BY p.name ASC, d.dataset ASC",
+    )?;
+    let documents = stmt
+        .query_map(params![&sha256], |row| {
+            Ok(DocumentMatch {
+                project_id: row.get(0)?,
+                project_name: row.get(1)?,
+                dataset: row.get(2)?,
+                split: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // Executions the digest appears in, with the project whose keys signed them
+    let executions: BTreeMap<&str, &str> = checkpoints
+        .iter()
+        .map(|found| (found.run_execution_id.as_str(), found.project_id.as_str()))
+        .chain(
+            execution_inputs
+                .iter()
+                .map(|found| (found.run_execution_id.as_str(), found.project_id.as_str())),
+        )
+        .collect();
+
+    let mut receipts = Vec::new();
+    let mut seen_receipts = BTreeSet::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, run_id, run_execution_id, file_path, created_at FROM receipts
+         WHERE run_execution_id = ?1 OR id = ?2
+         ORDER BY created_at ASC, id ASC",
+    )?;
+    let car_id = format!("car:{sha256}");
+    let execution_ids: Vec<Option<&str>> = if executions.is_empty() {
+        vec![None]
+    } else {
+        executions.keys().copied().map(Some).collect()
+    };
+    for execution_id in execution_ids {
+        let rows = stmt
+            .query_map(params![execution_id, &car_id], |row| {
+                Ok(ReceiptMatch {
+                    car_id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    run_execution_id: row.get(2)?,
+                    file_path: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for receipt in rows {
+            if seen_receipts.insert(receipt.car_id.clone()) {
+                receipts.push(receipt);
+            }
+        }
+    }
+
+    let verifications = executions
+        .iter()
+        .map(|(execution_id, project_id)| {
+            let outcome =
+                self_verification::verify_checkpoint_chain(conn, execution_id, Some(*project_id));
+            match outcome {
+                Ok(checkpoints_verified) => ExecutionVerification {
+                    run_execution_id: execution_id.to_string(),
+                    checkpoints_verified,
+                    error: None,
+                },
+                Err(err) => ExecutionVerification {
+                    run_execution_id: execution_id.to_string(),
+                    checkpoints_verified: 0,
+                    error: Some(err.to_string()),
+                },
+            }
+        })
+        .collect();
+
+    Ok(WorkspaceSearch {
+        sha256,
+        checkpoints,
+        execution_inputs,
+        documents,
+        receipts,
+        verifications,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests_are_accepted_with_or_without_prefix() {
+        let hex = "AB".repeat(32);
+        let expected = "ab".repeat(32);
+        assert_eq!(normalize_digest(&hex).unwrap(), expected);
+        assert_eq!(normalize_digest(&format!("sha256:{hex}")).unwrap(), expected);
+        assert_eq!(normalize_digest(&format!(" car:{expected} ")).unwrap(), expected);
+        assert!(normalize_digest("sha256:abc").is_err());
+        assert!(normalize_digest(&"zz".repeat(32)).is_err());
+    }
+}